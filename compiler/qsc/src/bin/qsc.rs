@@ -6,10 +6,17 @@ allocator::assign_global!();
 use clap::{crate_version, ArgGroup, Parser, ValueEnum};
 use log::info;
 use miette::{Context, IntoDiagnostic, Report};
+use qsc::codegen::qir::check_for_target;
 use qsc::hir::PackageId;
 use qsc::packages::BuildableProgram;
+use qsc::provenance::Provenance;
 use qsc::{compile::compile, PassContext};
-use qsc_codegen::qir::fir_to_qir;
+use qsc_codegen::qir::{
+    determinism, fir_to_qir_bitcode, fir_to_qir_recording_passes, fir_to_rir,
+    source_metadata::{embed_sources, SourceEmbedding},
+    GateSet, Measurements, Messages, NamingScheme, OutputRecording, PartialEvalConfig,
+    PeepholeConfig, QirOptions, QubitTermination,
+};
 use qsc_data_structures::{language_features::LanguageFeatures, target::TargetCapabilityFlags};
 use qsc_frontend::{
     compile::{PackageStore, SourceContents, SourceMap, SourceName},
@@ -18,7 +25,9 @@ use qsc_frontend::{
 use qsc_hir::hir::Package;
 use qsc_partial_eval::ProgramEntry;
 use qsc_passes::PackageType;
-use qsc_project::{FileSystem, StdFs};
+use qsc_project::{FileSystem, PackageGraphSources, StdFs};
+use qsc_rca::PackageStoreComputeProperties;
+use qsc_rir::rir;
 use std::sync::Arc;
 use std::{
     concat, fs,
@@ -53,6 +62,7 @@ impl From<Profile> for qsc::target::Profile {
 #[derive(Debug, Parser)]
 #[command(version = concat!(crate_version!(), " (", env!("QSHARP_GIT_HASH"), ")"), arg_required_else_help(false))]
 #[clap(group(ArgGroup::new("input").args(["entry", "sources"]).required(false).multiple(true)))]
+#[allow(clippy::struct_excessive_bools)]
 struct Cli {
     /// Disable automatic inclusion of the standard library.
     #[arg(long)]
@@ -89,12 +99,189 @@ struct Cli {
     /// Language features to compile with
     #[arg(short, long)]
     features: Vec<String>,
+
+    /// Verify that QIR generation is deterministic by generating it a second time with the
+    /// iteration order of its hash maps and sets perturbed using the given seed.
+    #[arg(long, value_name = "SEED", num_args = 0..=1, default_missing_value = "0")]
+    audit_determinism: Option<u64>,
+
+    /// Name QIR variables and blocks after the Q# identifiers and constructs they originate from.
+    #[arg(long, conflicts_with = "audit_determinism")]
+    readable_qir: bool,
+
+    /// Number QIR blocks, variables and callables in a canonical order so that the output is stable
+    /// across unrelated changes to the program.
+    #[arg(long, conflicts_with = "audit_determinism")]
+    canonical_ids: bool,
+
+    /// Apply the gate-level peephole optimizations, such as cancelling adjacent self-inverse gates, before
+    /// emitting QIR.
+    #[arg(long)]
+    peephole: bool,
+
+    /// The gates the target supports, as a comma-separated list such as `rz,sx,cz`. Calls to other gates are
+    /// decomposed into these gates in the emitted QIR. Including `exp` emits `Exp` as multi-qubit Pauli rotations.
+    #[arg(long, value_name = "GATES")]
+    gate_set: Option<GateSet>,
+
+    /// How the output recording calls in emitted QIR label the values they record.
+    #[arg(long, value_enum, default_value_t = OutputLabels::Ordinal)]
+    output_labels: OutputLabels,
+
+    /// How calls to `Message` are treated in emitted QIR.
+    #[arg(long, value_enum, default_value_t = MessageOutput::Drop)]
+    messages: MessageOutput,
+
+    /// The measurement intrinsics the target implements, which measurements in emitted QIR are emitted as.
+    #[arg(long, value_enum, default_value_t = MeasurementIntrinsics::MAndMresetz)]
+    measurements: MeasurementIntrinsics,
+
+    /// The number of times a callable can appear in the chain of calls being inlined into emitted QIR before its
+    /// recursion is reported as an error. Recursion isn't limited if not given.
+    #[arg(long)]
+    recursion_limit: Option<usize>,
+
+    /// How qubits that are not measured or reset by the end of the program are terminated in emitted QIR, for
+    /// targets that require it.
+    #[arg(long, value_enum, default_value_t = Termination::None)]
+    terminate_qubits: Termination,
+
+    /// Reuse the IDs of qubits once they are reset in emitted QIR, rescheduling its instructions to reduce the number
+    /// of qubits it requires. Profiles without qubit reset, such as the base profile, are rejected, since a qubit
+    /// can't be returned to a known state for reuse without a reset.
+    #[arg(long)]
+    reuse_qubits: bool,
+
+    /// Describe where each value recorded as output sits within the return value of the entry point, in the
+    /// `qsharp.output_positions` metadata of emitted QIR, so that the output of a run can be parsed back into it.
+    #[arg(long)]
+    output_positions: bool,
+
+    /// How the Q# sources are recorded in the metadata of emitted QIR.
+    #[arg(long, value_enum, default_value_t = SourceMetadata::Full)]
+    embed_sources: SourceMetadata,
+
+    /// Do not record the Q# sources in emitted QIR, for example when the sources are proprietary.
+    #[arg(long, conflicts_with = "embed_sources")]
+    strip_sources: bool,
+
+    /// Write a `provenance.json` manifest describing how the emitted QIR was produced, so that it can be
+    /// reproduced later.
+    #[arg(long, conflicts_with = "audit_determinism")]
+    provenance: bool,
+
+    /// Check the whole program against the target profile, including the checks that run during
+    /// code generation, and report all diagnostics without writing any output. Code generation is
+    /// only checked for executable projects or when an entry expression is given.
+    #[arg(long, conflicts_with_all = ["emit", "audit_determinism", "provenance"])]
+    check: bool,
+
+    /// Print statistics about the program generated for the target profile, such as the number of
+    /// calls to each gate and the estimated circuit depth.
+    #[arg(long, conflicts_with = "check")]
+    stats: bool,
+}
+
+/// Settings that control how QIR is emitted.
+struct QirEmitSettings {
+    audit_seed: Option<u64>,
+    source_embedding: SourceEmbedding,
+    options: QirOptions,
+    /// A manifest to complete with the passes that ran and the generated artifacts, and write alongside them.
+    provenance: Option<Provenance>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum SourceMetadata {
+    /// Record the name, SHA-256 digest and contents of each source.
+    Full,
+    /// Record only the name and SHA-256 digest of each source.
+    Hashes,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum OutputLabels {
+    /// Label only the values named with `OutputName`, leaving the rest identified by their order.
+    Ordinal,
+    /// Label every value, after its position in the output unless it is named with `OutputName`.
+    Labeled,
+    /// Do not record the output.
+    None,
+}
+
+impl From<OutputLabels> for OutputRecording {
+    fn from(labels: OutputLabels) -> Self {
+        match labels {
+            OutputLabels::Ordinal => OutputRecording::Ordinal,
+            OutputLabels::Labeled => OutputRecording::Labeled,
+            OutputLabels::None => OutputRecording::None,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum MessageOutput {
+    /// Leave messages out of the program, with a warning.
+    Drop,
+    /// Record messages with `__quantum__rt__message_record_output`, for targets that support it.
+    Record,
+}
+
+impl From<MessageOutput> for Messages {
+    fn from(messages: MessageOutput) -> Self {
+        match messages {
+            MessageOutput::Drop => Messages::Drop,
+            MessageOutput::Record => Messages::Record,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum MeasurementIntrinsics {
+    /// Both `m` and `mresetz`.
+    MAndMresetz,
+    /// Only `m`, with an explicit reset after measurements that reset the qubit.
+    M,
+    /// Only `mz`, with an explicit reset after measurements that reset the qubit.
+    Mz,
+}
+
+impl From<MeasurementIntrinsics> for Measurements {
+    fn from(measurements: MeasurementIntrinsics) -> Self {
+        match measurements {
+            MeasurementIntrinsics::MAndMresetz => Measurements::MAndMResetZ,
+            MeasurementIntrinsics::M => Measurements::M,
+            MeasurementIntrinsics::Mz => Measurements::Mz,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum Termination {
+    /// Leave qubits as they are.
+    None,
+    /// Reset qubits, or measure them on targets without reset.
+    Reset,
+    /// Measure qubits into result registers that are not otherwise used.
+    Measure,
+}
+
+impl From<Termination> for QubitTermination {
+    fn from(termination: Termination) -> Self {
+        match termination {
+            Termination::None => QubitTermination::None,
+            Termination::Reset => QubitTermination::Reset,
+            Termination::Measure => QubitTermination::Measure,
+        }
+    }
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
 enum Emit {
+    Bitcode,
     Hir,
     Qir,
+    Rir,
 }
 
 #[allow(clippy::too_many_lines)]
@@ -103,43 +290,87 @@ fn main() -> miette::Result<ExitCode> {
     let cli = Cli::parse();
     let profile: qsc::target::Profile = cli.profile.unwrap_or_default().into();
     let capabilities = profile.into();
-    let package_type = if cli.emit.contains(&Emit::Qir) {
+    let package_type = if cli.emit.contains(&Emit::Qir)
+        || cli.emit.contains(&Emit::Bitcode)
+        || cli.emit.contains(&Emit::Rir)
+        || cli.stats
+    {
         PackageType::Exe
     } else {
         PackageType::Lib
     };
     let mut features = LanguageFeatures::from_iter(cli.features);
 
-    let (mut store, dependencies, source_map) = if let Some(qsharp_json) = cli.qsharp_json {
-        if let Some(dir) = qsharp_json.parent() {
-            match load_project(dir, &mut features) {
-                Ok(items) => items,
-                Err(exit_code) => return Ok(exit_code),
+    let (mut store, dependencies, source_map, package_graph_sources) =
+        if let Some(qsharp_json) = cli.qsharp_json {
+            if let Some(dir) = qsharp_json.parent() {
+                match load_project(dir, &mut features) {
+                    Ok(items) => items,
+                    Err(exit_code) => return Ok(exit_code),
+                }
+            } else {
+                eprintln!("{} must have a parent directory", qsharp_json.display());
+                return Ok(ExitCode::FAILURE);
             }
         } else {
-            eprintln!("{} must have a parent directory", qsharp_json.display());
-            return Ok(ExitCode::FAILURE);
-        }
-    } else {
-        let sources = cli
-            .sources
-            .iter()
-            .map(read_source)
-            .collect::<miette::Result<Vec<_>>>()?;
-
-        let mut store = PackageStore::new(qsc::compile::core());
-        let dependencies = if cli.nostdlib {
-            vec![]
-        } else {
-            let std_id = store.insert(qsc::compile::std(&store, TargetCapabilityFlags::all()));
-            vec![(std_id, None)]
+            let sources = cli
+                .sources
+                .iter()
+                .map(read_source)
+                .collect::<miette::Result<Vec<_>>>()?;
+
+            let mut store = PackageStore::new(qsc::compile::core());
+            let dependencies = if cli.nostdlib {
+                vec![]
+            } else {
+                let std_id = store.insert(qsc::compile::std(&store, TargetCapabilityFlags::all()));
+                vec![(std_id, None)]
+            };
+            (
+                store,
+                dependencies,
+                SourceMap::new(sources, cli.entry.clone().map(std::convert::Into::into)),
+                None,
+            )
+        };
+
+    if cli.check {
+        let package_type = match package_graph_sources
+            .as_ref()
+            .and_then(|sources| sources.root.package_type)
+        {
+            Some(qsc_project::PackageType::Exe) => PackageType::Exe,
+            _ => PackageType::Lib,
         };
-        (
+        let errors = check_for_target(
+            source_map,
+            features,
+            capabilities,
+            package_type,
             store,
-            dependencies,
-            SourceMap::new(sources, cli.entry.clone().map(std::convert::Into::into)),
-        )
-    };
+            &dependencies,
+        );
+        if errors.is_empty() {
+            return Ok(ExitCode::SUCCESS);
+        }
+        for error in errors {
+            eprintln!("{:?}", Report::new(error));
+        }
+        return Ok(ExitCode::FAILURE);
+    }
+
+    if cli.reuse_qubits && !capabilities.contains(TargetCapabilityFlags::QubitReset) {
+        eprintln!("qubit reuse is not supported for profiles without qubit reset, such as the base profile");
+        return Ok(ExitCode::FAILURE);
+    }
+
+    let mut provenance = cli.provenance.then(|| {
+        let mut provenance = Provenance::new(profile, capabilities, &source_map);
+        if let Some(package_graph_sources) = &package_graph_sources {
+            provenance.add_dependencies(package_graph_sources);
+        }
+        provenance
+    });
 
     let (unit, errors) = compile(
         &store,
@@ -153,8 +384,52 @@ fn main() -> miette::Result<ExitCode> {
     let unit = store.get(package_id).expect("package should be in store");
 
     let out_dir = cli.out_dir.as_ref().map_or(".".as_ref(), PathBuf::as_path);
+    let qir_options = QirOptions {
+        naming: if cli.readable_qir {
+            NamingScheme::Readable
+        } else {
+            NamingScheme::Numeric
+        },
+        partial_eval: PartialEvalConfig {
+            canonical_ids: cli.canonical_ids,
+            output_recording: cli.output_labels.into(),
+            messages: cli.messages.into(),
+            measurements: cli.measurements.into(),
+            recursion_limit: cli.recursion_limit,
+            ..PartialEvalConfig::default()
+        },
+        peephole: if cli.peephole {
+            PeepholeConfig::ALL
+        } else {
+            PeepholeConfig::NONE
+        },
+        gate_set: cli.gate_set,
+        qubit_termination: cli.terminate_qubits.into(),
+        reuse_qubits: cli.reuse_qubits,
+        output_positions: cli.output_positions,
+    };
     for emit in &cli.emit {
         match emit {
+            Emit::Bitcode => {
+                if package_type != PackageType::Exe {
+                    eprintln!("bitcode generation is only supported for executable packages");
+                    return Ok(ExitCode::FAILURE);
+                }
+                if capabilities == TargetCapabilityFlags::all() {
+                    eprintln!("bitcode generation is not supported for unrestricted profile");
+                    return Ok(ExitCode::FAILURE);
+                }
+                if errors.is_empty() {
+                    if let Err(reports) =
+                        emit_bitcode(out_dir, &store, package_id, capabilities, qir_options)
+                    {
+                        for report in reports {
+                            eprintln!("{report:?}");
+                        }
+                        return Ok(ExitCode::FAILURE);
+                    }
+                }
+            }
             Emit::Hir => emit_hir(&unit.package, out_dir)?,
             Emit::Qir => {
                 if package_type != PackageType::Exe {
@@ -166,7 +441,40 @@ fn main() -> miette::Result<ExitCode> {
                     return Ok(ExitCode::FAILURE);
                 }
                 if errors.is_empty() {
-                    if let Err(reports) = emit_qir(out_dir, &store, package_id, capabilities) {
+                    if let Err(reports) = emit_qir(
+                        out_dir,
+                        &store,
+                        package_id,
+                        capabilities,
+                        QirEmitSettings {
+                            audit_seed: cli.audit_determinism,
+                            source_embedding: match (cli.strip_sources, cli.embed_sources) {
+                                (true, _) => SourceEmbedding::None,
+                                (false, SourceMetadata::Full) => SourceEmbedding::Full,
+                                (false, SourceMetadata::Hashes) => SourceEmbedding::Hashes,
+                            },
+                            options: qir_options,
+                            provenance: provenance.take(),
+                        },
+                    ) {
+                        for report in reports {
+                            eprintln!("{report:?}");
+                        }
+                        return Ok(ExitCode::FAILURE);
+                    }
+                }
+            }
+            Emit::Rir => {
+                if package_type != PackageType::Exe {
+                    eprintln!("RIR generation is only supported for executable packages");
+                    return Ok(ExitCode::FAILURE);
+                }
+                if capabilities == TargetCapabilityFlags::all() {
+                    eprintln!("RIR generation is not supported for unrestricted profile");
+                    return Ok(ExitCode::FAILURE);
+                }
+                if errors.is_empty() {
+                    if let Err(reports) = emit_rir(out_dir, &store, package_id, capabilities) {
                         for report in reports {
                             eprintln!("{report:?}");
                         }
@@ -177,6 +485,24 @@ fn main() -> miette::Result<ExitCode> {
         }
     }
 
+    if cli.stats {
+        if capabilities == TargetCapabilityFlags::all() {
+            eprintln!("program statistics are not supported for unrestricted profile");
+            return Ok(ExitCode::FAILURE);
+        }
+        if errors.is_empty() {
+            match generate_rir(&store, package_id, capabilities) {
+                Ok((_, program)) => println!("{}", program.stats()),
+                Err(reports) => {
+                    for report in reports {
+                        eprintln!("{report:?}");
+                    }
+                    return Ok(ExitCode::FAILURE);
+                }
+            }
+        }
+    }
+
     if errors.is_empty() {
         Ok(ExitCode::SUCCESS)
     } else {
@@ -223,41 +549,91 @@ fn emit_qir(
     store: &PackageStore,
     package_id: PackageId,
     capabilities: TargetCapabilityFlags,
+    settings: QirEmitSettings,
 ) -> Result<(), Vec<Report>> {
-    let (fir_store, fir_package_id) = qsc_passes::lower_hir_to_fir(store, package_id);
-    let package = fir_store.get(fir_package_id);
-    let entry = ProgramEntry {
-        exec_graph: package.entry_exec_graph.clone(),
-        expr: (
-            fir_package_id,
-            package
-                .entry
-                .expect("package must have an entry expression"),
+    let (fir_store, entry, compute_properties) = lower_to_fir(store, package_id, capabilities)?;
+
+    let QirEmitSettings {
+        audit_seed,
+        source_embedding,
+        options,
+        mut provenance,
+    } = settings;
+    let mut passes = Vec::new();
+    let mut warnings = Vec::new();
+    let qir = match audit_seed {
+        Some(seed) => determinism::audit_determinism(
+            &fir_store,
+            capabilities,
+            Some(compute_properties),
+            &entry,
+            options,
+            seed,
+        ),
+        None => fir_to_qir_recording_passes(
+            &fir_store,
+            capabilities,
+            Some(compute_properties),
+            &entry,
+            options,
+            &mut passes,
+            &mut warnings,
         )
-            .into(),
+        .map_err(determinism::Error::PartialEvaluation),
     };
 
-    let results = PassContext::run_fir_passes_on_fir(&fir_store, fir_package_id, capabilities);
-    if results.is_err() {
-        let errors = results.expect_err("should have errors");
-        let errors = errors.into_iter().map(Report::new).collect();
-        return Err(errors);
+    for warning in warnings {
+        let unit = store
+            .get(warning.span().package)
+            .expect("package should be in store");
+        eprintln!(
+            "{:?}",
+            Report::new(WithSource::from_map(&unit.sources, warning))
+        );
     }
-    let compute_properties = results.expect("should have compute properties");
 
-    match fir_to_qir(&fir_store, capabilities, Some(compute_properties), &entry) {
-        Ok(qir) => {
+    match qir {
+        Ok(mut qir) => {
+            let sources = &store
+                .get(package_id)
+                .expect("package should be in store")
+                .sources;
+            embed_sources(
+                &mut qir,
+                sources
+                    .iter()
+                    .map(|source| (source.name.as_ref(), source.contents.as_ref())),
+                source_embedding,
+            );
             let path = out_dir.join("qir.ll");
             info!(
                 "Writing QIR output file to: {}",
                 path.to_str().unwrap_or_default()
             );
-            fs::write(&path, qir)
+            fs::write(&path, &qir)
                 .into_diagnostic()
                 .with_context(|| format!("could not emit QIR file `{}`", path.display()))
-                .map_err(|err| vec![err])
+                .map_err(|err| vec![err])?;
+
+            if let Some(provenance) = &mut provenance {
+                provenance.passes = passes;
+                provenance.add_artifact("qir.ll", &qir);
+                let path = out_dir.join("provenance.json");
+                info!(
+                    "Writing provenance manifest to: {}",
+                    path.to_str().unwrap_or_default()
+                );
+                fs::write(&path, provenance.to_json())
+                    .into_diagnostic()
+                    .with_context(|| {
+                        format!("could not emit provenance manifest `{}`", path.display())
+                    })
+                    .map_err(|err| vec![err])?;
+            }
+            Ok(())
         }
-        Err(error) => {
+        Err(error @ determinism::Error::Nondeterministic { .. }) => Err(vec![Report::new(error)]),
+        Err(determinism::Error::PartialEvaluation(error)) => {
             let source_package = match error.span() {
                 Some(span) => span.package,
                 None => package_id,
@@ -273,6 +649,124 @@ fn emit_qir(
     }
 }
 
+fn emit_bitcode(
+    out_dir: &Path,
+    store: &PackageStore,
+    package_id: PackageId,
+    capabilities: TargetCapabilityFlags,
+    options: QirOptions,
+) -> Result<(), Vec<Report>> {
+    let (fir_store, entry, compute_properties) = lower_to_fir(store, package_id, capabilities)?;
+    let bitcode = fir_to_qir_bitcode(
+        &fir_store,
+        capabilities,
+        Some(compute_properties),
+        &entry,
+        options,
+    )
+    .map_err(|error| {
+        let source_package = match error.span() {
+            Some(span) => span.package,
+            None => package_id,
+        };
+        let unit = store
+            .get(source_package)
+            .expect("package should be in store");
+        vec![Report::new(WithSource::from_map(&unit.sources, error))]
+    })?;
+
+    let path = out_dir.join("qir.bc");
+    info!(
+        "Writing bitcode output file to: {}",
+        path.to_str().unwrap_or_default()
+    );
+    fs::write(&path, bitcode)
+        .into_diagnostic()
+        .with_context(|| format!("could not emit bitcode file `{}`", path.display()))
+        .map_err(|err| vec![err])
+}
+
+/// Lowers the package to FIR and runs the FIR passes needed for code generation, returning the FIR store, the
+/// program entry and the compute properties of the store.
+fn lower_to_fir(
+    store: &PackageStore,
+    package_id: PackageId,
+    capabilities: TargetCapabilityFlags,
+) -> Result<
+    (
+        qsc_fir::fir::PackageStore,
+        ProgramEntry,
+        PackageStoreComputeProperties,
+    ),
+    Vec<Report>,
+> {
+    let (fir_store, fir_package_id) = qsc_passes::lower_hir_to_fir(store, package_id);
+    let package = fir_store.get(fir_package_id);
+    let entry = ProgramEntry {
+        exec_graph: package.entry_exec_graph.clone(),
+        expr: (
+            fir_package_id,
+            package
+                .entry
+                .expect("package must have an entry expression"),
+        )
+            .into(),
+    };
+
+    let compute_properties =
+        PassContext::run_fir_passes_on_fir(&fir_store, fir_package_id, capabilities)
+            .map_err(|errors| errors.into_iter().map(Report::new).collect::<Vec<_>>())?;
+    Ok((fir_store, entry, compute_properties))
+}
+
+fn emit_rir(
+    out_dir: &Path,
+    store: &PackageStore,
+    package_id: PackageId,
+    capabilities: TargetCapabilityFlags,
+) -> Result<(), Vec<Report>> {
+    let (raw, transformed) = generate_rir(store, package_id, capabilities)?;
+    // The binary encoding of the transformed program can be decoded to emit it again without
+    // re-running partial evaluation.
+    let bytes = transformed.to_bytes();
+    let outputs = [
+        ("rir_raw.txt", raw.to_string().into_bytes()),
+        ("rir.txt", transformed.to_string().into_bytes()),
+        ("rir.bin", bytes),
+    ];
+    for (name, contents) in outputs {
+        let path = out_dir.join(name);
+        info!(
+            "Writing RIR output file to: {}",
+            path.to_str().unwrap_or_default()
+        );
+        fs::write(&path, contents)
+            .into_diagnostic()
+            .with_context(|| format!("could not emit RIR file `{}`", path.display()))
+            .map_err(|err| vec![err])?;
+    }
+    Ok(())
+}
+
+/// Generates the RIR program for the package, before and after the RIR passes.
+fn generate_rir(
+    store: &PackageStore,
+    package_id: PackageId,
+    capabilities: TargetCapabilityFlags,
+) -> Result<(rir::Program, rir::Program), Vec<Report>> {
+    let (fir_store, entry, compute_properties) = lower_to_fir(store, package_id, capabilities)?;
+    fir_to_rir(&fir_store, capabilities, Some(compute_properties), &entry).map_err(|error| {
+        let source_package = match error.span() {
+            Some(span) => span.package,
+            None => package_id,
+        };
+        let unit = store
+            .get(source_package)
+            .expect("package should be in store");
+        vec![Report::new(WithSource::from_map(&unit.sources, error))]
+    })
+}
+
 /// Loads a project from the given directory and returns the package store, the list of
 /// dependencies, and the source map.
 /// Pre-populates the package store with all of the compiled dependencies.
@@ -280,7 +774,15 @@ fn emit_qir(
 fn load_project(
     dir: impl AsRef<Path>,
     features: &mut LanguageFeatures,
-) -> Result<(PackageStore, Vec<(PackageId, Option<Arc<str>>)>, SourceMap), ExitCode> {
+) -> Result<
+    (
+        PackageStore,
+        Vec<(PackageId, Option<Arc<str>>)>,
+        SourceMap,
+        Option<PackageGraphSources>,
+    ),
+    ExitCode,
+> {
     let fs = StdFs;
     let project = match fs.load_project(dir.as_ref(), None) {
         Ok(project) => project,
@@ -299,6 +801,8 @@ fn load_project(
         return Err(ExitCode::FAILURE);
     }
 
+    let package_graph_sources = project.package_graph_sources.clone();
+
     // This builds all the dependencies
     let buildable_program =
         BuildableProgram::new(TargetCapabilityFlags::all(), project.package_graph_sources);
@@ -321,5 +825,10 @@ fn load_project(
 
     features.merge(LanguageFeatures::from_iter(user_code.language_features));
 
-    Ok((store, user_code_dependencies, source_map))
+    Ok((
+        store,
+        user_code_dependencies,
+        source_map,
+        Some(package_graph_sources),
+    ))
 }
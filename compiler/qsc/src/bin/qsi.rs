@@ -22,7 +22,10 @@ use qsc_eval::{
 use qsc_frontend::compile::{SourceContents, SourceMap, SourceName};
 use qsc_passes::PackageType;
 use qsc_project::{FileSystem, StdFs};
+#[cfg(feature = "parallel-shots")]
+use std::num::NonZeroUsize;
 use std::{
+    fmt::Display,
     fs,
     io::{self, prelude::BufRead, Write},
     path::{Path, PathBuf},
@@ -62,6 +65,46 @@ struct Cli {
     /// Compile the given files and interactive snippets in debug mode.
     #[arg(long)]
     debug: bool,
+
+    /// Run the entry point this many times and exit, printing the output and result of each shot.
+    #[cfg(feature = "parallel-shots")]
+    #[arg(long)]
+    shots: Option<usize>,
+
+    /// The number of threads to run shots on. Defaults to the available parallelism.
+    #[cfg(feature = "parallel-shots")]
+    #[arg(long, requires = "shots")]
+    threads: Option<NonZeroUsize>,
+
+    /// The seed of the simulator for the first shot. Each later shot uses the next seed.
+    #[cfg(feature = "parallel-shots")]
+    #[arg(long, requires = "shots")]
+    seed: Option<u64>,
+
+    /// The simulator each shot runs on.
+    #[cfg(feature = "parallel-shots")]
+    #[arg(long, value_enum, requires = "shots", default_value_t = Simulator::Sparse)]
+    simulator: Simulator,
+}
+
+#[cfg(feature = "parallel-shots")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ValueEnum)]
+enum Simulator {
+    /// The sparse state-vector simulator.
+    Sparse,
+    /// The stabilizer simulator, which switches to the sparse simulator at the first non-Clifford
+    /// operation.
+    Auto,
+}
+
+#[cfg(feature = "parallel-shots")]
+impl From<Simulator> for interpret::SimulatorKind {
+    fn from(simulator: Simulator) -> Self {
+        match simulator {
+            Simulator::Sparse => interpret::SimulatorKind::Sparse,
+            Simulator::Auto => interpret::SimulatorKind::Auto,
+        }
+    }
 }
 
 struct TerminalReceiver;
@@ -97,42 +140,20 @@ impl Receiver for TerminalReceiver {
     }
 }
 
-#[allow(clippy::too_many_lines)]
 fn main() -> miette::Result<ExitCode> {
     let cli = Cli::parse();
-    let mut features = LanguageFeatures::from_iter(cli.features);
-
-    let (store, dependencies, source_map) = if let Some(qsharp_json) = cli.qsharp_json {
-        if let Some(dir) = qsharp_json.parent() {
-            match load_project(dir, &mut features) {
-                Ok(items) => items,
-                Err(code) => return Ok(code),
-            }
-        } else {
-            eprintln!("{} must have a parent directory", qsharp_json.display());
-            return Ok(ExitCode::FAILURE);
-        }
-    } else {
-        let sources = cli
-            .sources
-            .iter()
-            .map(read_source)
-            .collect::<miette::Result<Vec<_>>>()?;
+    let mut features = LanguageFeatures::from_iter(&cli.features);
 
-        let mut store = PackageStore::new(qsc::compile::core());
-        let dependencies = if cli.nostdlib {
-            vec![]
-        } else {
-            let std_id = store.insert(qsc::compile::std(&store, TargetCapabilityFlags::all()));
-            vec![(std_id, None)]
-        };
-        (
-            store,
-            dependencies,
-            SourceMap::new(sources, cli.entry.clone().map(std::convert::Into::into)),
-        )
+    let (store, dependencies, source_map) = match load_program(&cli, &mut features)? {
+        Ok(program) => program,
+        Err(code) => return Ok(code),
     };
 
+    #[cfg(feature = "parallel-shots")]
+    if let Some(shots) = cli.shots {
+        return Ok(run_shots(&cli, shots));
+    }
+
     if cli.exec {
         let mut interpreter = match (if cli.debug {
             Interpreter::new_with_debug
@@ -253,7 +274,7 @@ fn print_interpret_result(result: InterpretResult) {
     }
 }
 
-fn print_exec_result(result: Result<Value, Vec<interpret::Error>>) -> ExitCode {
+fn print_exec_result(result: Result<impl Display, Vec<interpret::Error>>) -> ExitCode {
     match result {
         Ok(value) => {
             println!("{value}");
@@ -272,6 +293,102 @@ fn print_exec_result(result: Result<Value, Vec<interpret::Error>>) -> ExitCode {
     }
 }
 
+/// Loads the project or the source files given on the command line and returns the package
+/// store, the list of dependencies, and the source map.
+#[allow(clippy::type_complexity)]
+fn load_program(
+    cli: &Cli,
+    features: &mut LanguageFeatures,
+) -> miette::Result<Result<(PackageStore, Vec<(PackageId, Option<Arc<str>>)>, SourceMap), ExitCode>>
+{
+    if let Some(qsharp_json) = &cli.qsharp_json {
+        if let Some(dir) = qsharp_json.parent() {
+            Ok(load_project(dir, features))
+        } else {
+            eprintln!("{} must have a parent directory", qsharp_json.display());
+            Ok(Err(ExitCode::FAILURE))
+        }
+    } else {
+        let sources = cli
+            .sources
+            .iter()
+            .map(read_source)
+            .collect::<miette::Result<Vec<_>>>()?;
+
+        let mut store = PackageStore::new(qsc::compile::core());
+        let dependencies = if cli.nostdlib {
+            vec![]
+        } else {
+            let std_id = store.insert(qsc::compile::std(&store, TargetCapabilityFlags::all()));
+            vec![(std_id, None)]
+        };
+        Ok(Ok((
+            store,
+            dependencies,
+            SourceMap::new(sources, cli.entry.clone().map(std::convert::Into::into)),
+        )))
+    }
+}
+
+/// Runs the entry point of the program the given number of times, with each thread loading
+/// and compiling its own copy of the program, and prints the output and result of each shot.
+#[cfg(feature = "parallel-shots")]
+fn run_shots(cli: &Cli, shots: usize) -> ExitCode {
+    let threads = cli
+        .threads
+        .or_else(|| std::thread::available_parallelism().ok())
+        .unwrap_or(NonZeroUsize::MIN);
+    let make_interpreter = || {
+        let mut features = LanguageFeatures::from_iter(&cli.features);
+        let Ok(Ok((store, dependencies, source_map))) = load_program(cli, &mut features) else {
+            panic!("program should load the same way on every thread");
+        };
+        (if cli.debug {
+            Interpreter::new_with_debug
+        } else {
+            Interpreter::new
+        })(
+            source_map,
+            PackageType::Exe,
+            TargetCapabilityFlags::all(),
+            features,
+            store,
+            &dependencies,
+        )
+    };
+
+    let config = qsc::shots::ShotConfig {
+        simulation: interpret::SimulationConfig {
+            quantum_seed: cli.seed,
+            simulator: cli.simulator.into(),
+            ..interpret::SimulationConfig::default()
+        },
+        ..qsc::shots::ShotConfig::default()
+    };
+
+    match qsc::shots::run_shots(shots, threads, &config, make_interpreter, |value| {
+        value.to_string()
+    }) {
+        Ok(shots) => {
+            let mut code = ExitCode::SUCCESS;
+            for shot in shots {
+                print!("{}", shot.output);
+                if shot.result.is_err() {
+                    code = ExitCode::FAILURE;
+                }
+                print_exec_result(shot.result);
+            }
+            code
+        }
+        Err(errors) => {
+            for error in errors {
+                eprintln!("error: {:?}", Report::new(error));
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
+
 /// Loads a project from the given directory and returns the package store, the list of
 /// dependencies, and the source map.
 /// Pre-populates the package store with all of the compiled dependencies.
@@ -10,15 +10,32 @@ pub mod qsharp {
 }
 
 pub mod qir {
-    use qsc_codegen::qir::{fir_to_qir, fir_to_rir};
+    use qsc_codegen::qir::{
+        count_instructions_from_callable, determinism, fir_to_qir, fir_to_qir_bitcode,
+        fir_to_qir_with_options, fir_to_rir,
+    };
+    pub use qsc_codegen::qir::{
+        entry_point_callables, fir_to_qir_from_entry_points, link_entry_points,
+        source_metadata::{embed_sources, SourceEmbedding},
+        GateSet, Measurements, Messages, NamingScheme, OutputRecording, PartialEvalConfig,
+        PeepholeConfig, ProgramStats, QirOptions, QubitTermination,
+    };
 
     use qsc_data_structures::{language_features::LanguageFeatures, target::TargetCapabilityFlags};
+    use qsc_eval::val::Value;
+    use qsc_fir::{
+        fir::{CallableImpl, ItemKind, PackageLookup, StoreItemId},
+        ty::Ty,
+    };
     use qsc_frontend::{
         compile::{Dependencies, PackageStore, SourceMap},
         error::WithSource,
     };
     use qsc_partial_eval::ProgramEntry;
     use qsc_passes::{PackageType, PassContext};
+    use qsc_rca::{ComputeKind, ItemComputeProperties, RuntimeFeatureFlags};
+
+    use crate::target::Profile;
 
     use crate::interpret::Error;
     pub fn get_qir_from_ast(
@@ -121,11 +138,30 @@ pub mod qir {
     }
 
     pub fn get_qir(
+        sources: SourceMap,
+        language_features: LanguageFeatures,
+        capabilities: TargetCapabilityFlags,
+        package_store: PackageStore,
+        dependencies: &Dependencies,
+    ) -> Result<String, Vec<Error>> {
+        get_qir_with_options(
+            sources,
+            language_features,
+            capabilities,
+            package_store,
+            dependencies,
+            QirOptions::default(),
+        )
+    }
+
+    /// Generates QIR for the given sources using the given options.
+    pub fn get_qir_with_options(
         sources: SourceMap,
         language_features: LanguageFeatures,
         capabilities: TargetCapabilityFlags,
         mut package_store: PackageStore,
         dependencies: &Dependencies,
+        options: QirOptions,
     ) -> Result<String, Vec<Error>> {
         let (package_id, fir_store, entry, compute_properties) = compile_to_fir(
             sources,
@@ -135,7 +171,53 @@ pub mod qir {
             dependencies,
         )?;
 
-        fir_to_qir(&fir_store, capabilities, Some(compute_properties), &entry).map_err(|e| {
+        fir_to_qir_with_options(
+            &fir_store,
+            capabilities,
+            Some(compute_properties),
+            &entry,
+            options,
+        )
+        .map_err(|e| {
+            let source_package_id = match e.span() {
+                Some(span) => span.package,
+                None => package_id,
+            };
+            let source_package = package_store
+                .get(source_package_id)
+                .expect("package should be in store");
+            vec![Error::PartialEvaluation(WithSource::from_map(
+                &source_package.sources,
+                e,
+            ))]
+        })
+    }
+
+    /// Generates LLVM bitcode, the binary form of QIR, for the given sources using the given options.
+    pub fn get_qir_bitcode(
+        sources: SourceMap,
+        language_features: LanguageFeatures,
+        capabilities: TargetCapabilityFlags,
+        mut package_store: PackageStore,
+        dependencies: &Dependencies,
+        options: QirOptions,
+    ) -> Result<Vec<u8>, Vec<Error>> {
+        let (package_id, fir_store, entry, compute_properties) = compile_to_fir(
+            sources,
+            language_features,
+            capabilities,
+            &mut package_store,
+            dependencies,
+        )?;
+
+        fir_to_qir_bitcode(
+            &fir_store,
+            capabilities,
+            Some(compute_properties),
+            &entry,
+            options,
+        )
+        .map_err(|e| {
             let source_package_id = match e.span() {
                 Some(span) => span.package,
                 None => package_id,
@@ -150,6 +232,197 @@ pub mod qir {
         })
     }
 
+    /// Generates QIR for the given sources using the given options twice, the second time with the hash maps and
+    /// sets of code generation seeded with `seed`, and verifies that both runs produce byte-identical output before
+    /// returning it.
+    pub fn get_qir_with_determinism_audit(
+        sources: SourceMap,
+        language_features: LanguageFeatures,
+        capabilities: TargetCapabilityFlags,
+        mut package_store: PackageStore,
+        dependencies: &Dependencies,
+        options: QirOptions,
+        seed: u64,
+    ) -> Result<String, Vec<Error>> {
+        let (package_id, fir_store, entry, compute_properties) = compile_to_fir(
+            sources,
+            language_features,
+            capabilities,
+            &mut package_store,
+            dependencies,
+        )?;
+
+        determinism::audit_determinism(
+            &fir_store,
+            capabilities,
+            Some(compute_properties),
+            &entry,
+            options,
+            seed,
+        )
+        .map_err(|e| match e {
+            determinism::Error::PartialEvaluation(e) => {
+                let source_package_id = match e.span() {
+                    Some(span) => span.package,
+                    None => package_id,
+                };
+                let source_package = package_store
+                    .get(source_package_id)
+                    .expect("package should be in store");
+                vec![Error::PartialEvaluation(WithSource::from_map(
+                    &source_package.sources,
+                    e,
+                ))]
+            }
+            e @ determinism::Error::Nondeterministic { .. } => vec![Error::Codegen(e)],
+        })
+    }
+
+    /// Runs the whole pipeline for the given sources against the target capabilities without generating any
+    /// output, and returns all of the diagnostics that would be reported when generating code for that target.
+    /// Unlike `get_qir`, every callable in the package is checked against the target, not only those reachable
+    /// from the entry point, so libraries can be checked too. Partial evaluation and code generation only run
+    /// if the package has an entry point and the earlier stages reported no errors.
+    pub fn check_for_target(
+        sources: SourceMap,
+        language_features: LanguageFeatures,
+        capabilities: TargetCapabilityFlags,
+        package_type: PackageType,
+        mut package_store: PackageStore,
+        dependencies: &Dependencies,
+    ) -> Vec<Error> {
+        let (unit, errors) = crate::compile::compile(
+            &package_store,
+            dependencies,
+            sources,
+            package_type,
+            capabilities,
+            language_features,
+        );
+        if !errors.is_empty() {
+            return errors.into_iter().map(Error::Compile).collect();
+        }
+
+        let package_id = package_store.insert(unit);
+        let (fir_store, fir_package_id) = qsc_passes::lower_hir_to_fir(&package_store, package_id);
+        let compute_properties =
+            match PassContext::run_fir_passes_on_fir(&fir_store, fir_package_id, capabilities) {
+                Ok(compute_properties) => compute_properties,
+                Err(errors) => {
+                    let source_package = package_store
+                        .get(package_id)
+                        .expect("package should be in store");
+                    return errors
+                        .into_iter()
+                        .map(|e| Error::Pass(WithSource::from_map(&source_package.sources, e)))
+                        .collect();
+                }
+            };
+
+        let package = fir_store.get(fir_package_id);
+        let Some(entry_expr) = package.entry else {
+            return Vec::new();
+        };
+        if capabilities == TargetCapabilityFlags::all() {
+            // There is no code generation for unrestricted targets.
+            return Vec::new();
+        }
+        let entry = ProgramEntry {
+            exec_graph: package.entry_exec_graph.clone(),
+            expr: (fir_package_id, entry_expr).into(),
+        };
+        match fir_to_qir(&fir_store, capabilities, Some(compute_properties), &entry) {
+            Ok(_) => Vec::new(),
+            Err(e) => {
+                let source_package_id = match e.span() {
+                    Some(span) => span.package,
+                    None => package_id,
+                };
+                let source_package = package_store
+                    .get(source_package_id)
+                    .expect("package should be in store");
+                vec![Error::PartialEvaluation(WithSource::from_map(
+                    &source_package.sources,
+                    e,
+                ))]
+            }
+        }
+    }
+
+    /// The runtime features used by an operation and the size of the program generated for it.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct OperationSummary {
+        /// The runtime features used by the operation, including those used by the callables it calls, when its
+        /// input is known at compile time.
+        pub runtime_features: RuntimeFeatureFlags,
+        /// The number of RIR instructions generated when the operation is the entry point of a program targeting
+        /// the most restrictive profile that supports its runtime features. Only available for operations that
+        /// take no input and whose code generation succeeds for such a profile.
+        pub instruction_count: Option<usize>,
+    }
+
+    /// Summarizes the given operations of a package that compiled without errors.
+    /// Items that are not operations with a body are summarized as using no runtime features.
+    #[must_use]
+    pub fn summarize_operations(
+        package_store: &PackageStore,
+        package_id: qsc_hir::hir::PackageId,
+        operations: &[qsc_hir::hir::LocalItemId],
+    ) -> Vec<OperationSummary> {
+        let (fir_store, fir_package_id) = qsc_passes::lower_hir_to_fir(package_store, package_id);
+        let compute_properties = qsc_rca::Analyzer::init(&fir_store).analyze_all();
+        let package = fir_store.get(fir_package_id);
+
+        operations
+            .iter()
+            .map(|item_id| {
+                let item_id = qsc_fir::fir::LocalItemId::from(usize::from(*item_id));
+                let ItemKind::Callable(decl) = &package.get_item(item_id).kind else {
+                    return OperationSummary {
+                        runtime_features: RuntimeFeatureFlags::empty(),
+                        instruction_count: None,
+                    };
+                };
+                let runtime_features = match compute_properties
+                    .get(fir_package_id)
+                    .get_item(item_id)
+                {
+                    ItemComputeProperties::Callable(callable) => match &callable.body.inherent {
+                        ComputeKind::Quantum(properties) => properties.runtime_features,
+                        ComputeKind::Classical => RuntimeFeatureFlags::empty(),
+                    },
+                    ItemComputeProperties::NonCallable => RuntimeFeatureFlags::empty(),
+                };
+
+                let profile = Profile::minimal_for(runtime_features.target_capabilities());
+                let takes_no_input = package.get_pat(decl.input).ty == Ty::UNIT;
+                let instruction_count = if takes_no_input
+                    && matches!(decl.implementation, CallableImpl::Spec(_))
+                    && profile != Profile::Unrestricted
+                {
+                    count_instructions_from_callable(
+                        &fir_store,
+                        profile.into(),
+                        &compute_properties,
+                        StoreItemId {
+                            package: fir_package_id,
+                            item: item_id,
+                        },
+                        Value::unit(),
+                    )
+                    .ok()
+                } else {
+                    None
+                };
+
+                OperationSummary {
+                    runtime_features,
+                    instruction_count,
+                }
+            })
+            .collect()
+    }
+
     fn compile_to_fir(
         sources: SourceMap,
         language_features: LanguageFeatures,
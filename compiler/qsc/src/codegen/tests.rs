@@ -5,7 +5,10 @@ use expect_test::expect;
 use qsc_data_structures::{language_features::LanguageFeatures, target::TargetCapabilityFlags};
 use qsc_frontend::compile::SourceMap;
 
-use crate::codegen::qir::get_qir;
+use miette::Diagnostic;
+use qsc_passes::PackageType;
+
+use crate::codegen::qir::{check_for_target, get_qir, summarize_operations};
 
 fn compile_source_to_qir(source: &str, capabilities: TargetCapabilityFlags) -> String {
     let sources = SourceMap::new([("test.qs".into(), source.into())], None);
@@ -74,6 +77,198 @@ fn code_with_errors_returns_errors() {
     .assert_debug_eq(&get_qir(sources, language_features, capabilities, store, &[(std_id, None)]));
 }
 
+fn check_source_for_target(
+    source: &str,
+    capabilities: TargetCapabilityFlags,
+    package_type: PackageType,
+) -> Vec<String> {
+    let sources = SourceMap::new([("test.qs".into(), source.into())], None);
+    let (std_id, store) = crate::compile::package_store_with_stdlib(capabilities);
+    check_for_target(
+        sources,
+        LanguageFeatures::default(),
+        capabilities,
+        package_type,
+        store,
+        &[(std_id, None)],
+    )
+    .iter()
+    .map(|e| format!("{}: {e}", e.code().expect("error should have a code")))
+    .collect()
+}
+
+#[test]
+fn check_for_target_reports_errors_in_all_callables_of_library() {
+    let source = "namespace Test {
+            operation Foo() : Int {
+                use q = Qubit();
+                if M(q) == One { 1 } else { 0 }
+            }
+            operation Bar() : Double {
+                use q = Qubit();
+                mutable d = 0.0;
+                if M(q) == One { set d = 1.0; }
+                d
+            }
+        }";
+    expect![[r#"
+        [
+            "Qsc.CapabilitiesCk.UseOfDynamicInt: cannot use a dynamic integer value",
+            "Qsc.CapabilitiesCk.UseOfDynamicDouble: cannot use a dynamic double value",
+        ]
+    "#]]
+    .assert_debug_eq(&check_source_for_target(
+        source,
+        TargetCapabilityFlags::Adaptive,
+        PackageType::Lib,
+    ));
+}
+
+#[test]
+fn check_for_target_reports_enum_crossing_dynamic_branch() {
+    let source = "namespace Test {
+            enum Rotation { Quarter, Turns(Int) }
+            @EntryPoint()
+            operation Main() : Result {
+                use (q0, q1) = (Qubit(), Qubit());
+                let rotation = if M(q0) == One { Quarter() } else { Turns(2) };
+                match rotation {
+                    Quarter() => S(q1),
+                    Turns(n) => for _ in 1..n { Z(q1); },
+                }
+                M(q1)
+            }
+        }";
+    expect![[r#"
+        [
+            "Qsc.CapabilitiesCk.UseOfDynamicUdt: cannot use a dynamic user-defined type",
+            "Qsc.CapabilitiesCk.UseOfDynamicUdt: cannot use a dynamic user-defined type",
+            "Qsc.CapabilitiesCk.UseOfDynamicUdt: cannot use a dynamic user-defined type",
+            "Qsc.CapabilitiesCk.UseOfDynamicRange: cannot use a dynamic Range value",
+            "Qsc.CapabilitiesCk.UseOfDynamicUdt: cannot use a dynamic user-defined type",
+            "Qsc.CapabilitiesCk.LoopWithDynamicCondition: cannot have a loop with a dynamic condition",
+            "Qsc.CapabilitiesCk.UseOfDynamicRange: cannot use a dynamic Range value",
+            "Qsc.CapabilitiesCk.UseOfDynamicUdt: cannot use a dynamic user-defined type",
+        ]
+    "#]].assert_debug_eq(&check_source_for_target(
+        source,
+        TargetCapabilityFlags::Adaptive
+            | TargetCapabilityFlags::IntegerComputations
+            | TargetCapabilityFlags::FloatingPointComputations,
+        PackageType::Exe,
+    ));
+}
+
+#[test]
+fn check_for_target_reports_no_errors_for_supported_program() {
+    let source = "namespace Test {
+            @EntryPoint()
+            operation Main() : Result {
+                use q = Qubit();
+                H(q);
+                M(q)
+            }
+        }";
+    expect![[r#"
+        []
+    "#]]
+    .assert_debug_eq(&check_source_for_target(
+        source,
+        TargetCapabilityFlags::empty(),
+        PackageType::Exe,
+    ));
+}
+
+#[test]
+fn check_for_target_reports_compile_errors_before_running_analysis() {
+    let source = "namespace Test {
+            operation Foo() : Int {
+                use q = Qubit();
+                if M(q) == One { 1 } else { 0.0 }
+            }
+        }";
+    expect![[r#"
+        [
+            "Qsc.TypeCk.TyMismatch: type error",
+        ]
+    "#]]
+    .assert_debug_eq(&check_source_for_target(
+        source,
+        TargetCapabilityFlags::empty(),
+        PackageType::Lib,
+    ));
+}
+
+#[test]
+fn summarize_operations_reports_runtime_features_and_instruction_counts() {
+    let source = "namespace Test {
+            operation Static() : Result {
+                use q = Qubit();
+                H(q);
+                M(q)
+            }
+            operation Dynamic() : Int {
+                use q = Qubit();
+                mutable i = 0;
+                if M(q) == One { set i = 1; }
+                i
+            }
+            operation TakesQubit(q : Qubit) : Unit {
+                H(q);
+            }
+        }";
+    let sources = SourceMap::new([("test.qs".into(), source.into())], None);
+    let (std_id, mut store) =
+        crate::compile::package_store_with_stdlib(TargetCapabilityFlags::all());
+    let (unit, errors) = crate::compile::compile(
+        &store,
+        &[(std_id, None)],
+        sources,
+        PackageType::Lib,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::default(),
+    );
+    assert!(errors.is_empty(), "{errors:?}");
+    let operations = unit
+        .package
+        .items
+        .iter()
+        .filter_map(|(item_id, item)| match &item.kind {
+            qsc_hir::hir::ItemKind::Callable(_) => Some(item_id),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let package_id = store.insert(unit);
+
+    expect![[r#"
+        [
+            OperationSummary {
+                runtime_features: RuntimeFeatureFlags(
+                    0x0,
+                ),
+                instruction_count: Some(
+                    4,
+                ),
+            },
+            OperationSummary {
+                runtime_features: RuntimeFeatureFlags(
+                    UseOfDynamicBool | UseOfDynamicInt,
+                ),
+                instruction_count: Some(
+                    7,
+                ),
+            },
+            OperationSummary {
+                runtime_features: RuntimeFeatureFlags(
+                    0x0,
+                ),
+                instruction_count: None,
+            },
+        ]
+    "#]]
+    .assert_debug_eq(&summarize_operations(&store, package_id, &operations));
+}
+
 mod base_profile {
     use expect_test::expect;
     use qsc_data_structures::target::TargetCapabilityFlags;
@@ -654,13 +849,12 @@ mod adaptive_ri_profile {
 
             ; module flags
 
-            !llvm.module.flags = !{!0, !1, !2, !3, !4}
+            !llvm.module.flags = !{!0, !1, !2, !3}
 
             !0 = !{i32 1, !"qir_major_version", i32 1}
             !1 = !{i32 7, !"qir_minor_version", i32 0}
             !2 = !{i32 1, !"dynamic_qubit_management", i1 false}
             !3 = !{i32 1, !"dynamic_result_management", i1 false}
-            !4 = !{i32 1, !"int_computations", !"i64"}
         "#]]
         .assert_eq(&qir);
     }
@@ -700,13 +894,12 @@ mod adaptive_ri_profile {
 
             ; module flags
 
-            !llvm.module.flags = !{!0, !1, !2, !3, !4}
+            !llvm.module.flags = !{!0, !1, !2, !3}
 
             !0 = !{i32 1, !"qir_major_version", i32 1}
             !1 = !{i32 7, !"qir_minor_version", i32 0}
             !2 = !{i32 1, !"dynamic_qubit_management", i1 false}
             !3 = !{i32 1, !"dynamic_result_management", i1 false}
-            !4 = !{i32 1, !"int_computations", !"i64"}
         "#]].assert_eq(&qir);
     }
 
@@ -753,13 +946,12 @@ mod adaptive_ri_profile {
 
             ; module flags
 
-            !llvm.module.flags = !{!0, !1, !2, !3, !4}
+            !llvm.module.flags = !{!0, !1, !2, !3}
 
             !0 = !{i32 1, !"qir_major_version", i32 1}
             !1 = !{i32 7, !"qir_minor_version", i32 0}
             !2 = !{i32 1, !"dynamic_qubit_management", i1 false}
             !3 = !{i32 1, !"dynamic_result_management", i1 false}
-            !4 = !{i32 1, !"int_computations", !"i64"}
         "#]].assert_eq(&qir);
     }
 
@@ -805,13 +997,12 @@ mod adaptive_ri_profile {
 
             ; module flags
 
-            !llvm.module.flags = !{!0, !1, !2, !3, !4}
+            !llvm.module.flags = !{!0, !1, !2, !3}
 
             !0 = !{i32 1, !"qir_major_version", i32 1}
             !1 = !{i32 7, !"qir_minor_version", i32 0}
             !2 = !{i32 1, !"dynamic_qubit_management", i1 false}
             !3 = !{i32 1, !"dynamic_result_management", i1 false}
-            !4 = !{i32 1, !"int_computations", !"i64"}
         "#]].assert_eq(&qir);
     }
 
@@ -866,13 +1057,12 @@ mod adaptive_ri_profile {
 
             ; module flags
 
-            !llvm.module.flags = !{!0, !1, !2, !3, !4}
+            !llvm.module.flags = !{!0, !1, !2, !3}
 
             !0 = !{i32 1, !"qir_major_version", i32 1}
             !1 = !{i32 7, !"qir_minor_version", i32 0}
             !2 = !{i32 1, !"dynamic_qubit_management", i1 false}
             !3 = !{i32 1, !"dynamic_result_management", i1 false}
-            !4 = !{i32 1, !"int_computations", !"i64"}
         "#]].assert_eq(&qir);
     }
 
@@ -926,13 +1116,12 @@ mod adaptive_ri_profile {
 
             ; module flags
 
-            !llvm.module.flags = !{!0, !1, !2, !3, !4}
+            !llvm.module.flags = !{!0, !1, !2, !3}
 
             !0 = !{i32 1, !"qir_major_version", i32 1}
             !1 = !{i32 7, !"qir_minor_version", i32 0}
             !2 = !{i32 1, !"dynamic_qubit_management", i1 false}
             !3 = !{i32 1, !"dynamic_result_management", i1 false}
-            !4 = !{i32 1, !"int_computations", !"i64"}
         "#]].assert_eq(&qir);
     }
 
@@ -963,8 +1152,8 @@ mod adaptive_ri_profile {
             block_2:
               br label %block_3
             block_3:
-              %var_4 = phi i64 [0, %block_1], [1, %block_2]
-              call void @__quantum__rt__int_record_output(i64 %var_4, i8* null)
+              %var_5 = phi i64 [0, %block_1], [1, %block_2]
+              call void @__quantum__rt__int_record_output(i64 %var_5, i8* null)
               ret void
             }
 
@@ -1029,13 +1218,12 @@ mod adaptive_ri_profile {
 
             ; module flags
 
-            !llvm.module.flags = !{!0, !1, !2, !3, !4}
+            !llvm.module.flags = !{!0, !1, !2, !3}
 
             !0 = !{i32 1, !"qir_major_version", i32 1}
             !1 = !{i32 7, !"qir_minor_version", i32 0}
             !2 = !{i32 1, !"dynamic_qubit_management", i1 false}
             !3 = !{i32 1, !"dynamic_result_management", i1 false}
-            !4 = !{i32 1, !"int_computations", !"i64"}
         "#]]
         .assert_eq(&qir);
     }
@@ -1096,18 +1284,77 @@ mod adaptive_rif_profile {
 
             ; module flags
 
-            !llvm.module.flags = !{!0, !1, !2, !3, !4, !5}
+            !llvm.module.flags = !{!0, !1, !2, !3}
 
             !0 = !{i32 1, !"qir_major_version", i32 1}
             !1 = !{i32 7, !"qir_minor_version", i32 0}
             !2 = !{i32 1, !"dynamic_qubit_management", i1 false}
             !3 = !{i32 1, !"dynamic_result_management", i1 false}
-            !4 = !{i32 1, !"int_computations", !"i64"}
-            !5 = !{i32 1, !"float_computations", !"f64"}
         "#]]
         .assert_eq(&qir);
     }
 
+    #[test]
+    fn enum_matched_within_dynamic_branch() {
+        let source = "namespace Test {
+            enum Rotation { Quarter, Turns(Int) }
+            operation Rotate(rotation : Rotation, q : Qubit) : Unit {
+                match rotation {
+                    Quarter() => S(q),
+                    Turns(n) => for _ in 1..n { Z(q); },
+                }
+            }
+            @EntryPoint()
+            operation Main() : Result {
+                use (q0, q1) = (Qubit(), Qubit());
+                let rotation = Turns(3);
+                if M(q0) == One {
+                    Rotate(rotation, q1);
+                }
+                M(q1)
+            }
+        }";
+        let qir = compile_source_to_qir(source, *CAPABILITIES);
+        expect![[r#"
+            %Result = type opaque
+            %Qubit = type opaque
+
+            define void @ENTRYPOINT__main() #0 {
+            block_0:
+              call void @__quantum__qis__m__body(%Qubit* inttoptr (i64 0 to %Qubit*), %Result* inttoptr (i64 0 to %Result*))
+              %var_0 = call i1 @__quantum__qis__read_result__body(%Result* inttoptr (i64 0 to %Result*))
+              br i1 %var_0, label %block_1, label %block_2
+            block_1:
+              call void @__quantum__qis__z__body(%Qubit* inttoptr (i64 1 to %Qubit*))
+              br label %block_2
+            block_2:
+              call void @__quantum__qis__m__body(%Qubit* inttoptr (i64 1 to %Qubit*), %Result* inttoptr (i64 1 to %Result*))
+              call void @__quantum__rt__result_record_output(%Result* inttoptr (i64 1 to %Result*), i8* null)
+              ret void
+            }
+
+            declare void @__quantum__qis__m__body(%Qubit*, %Result*) #1
+
+            declare i1 @__quantum__qis__read_result__body(%Result*)
+
+            declare void @__quantum__qis__z__body(%Qubit*)
+
+            declare void @__quantum__rt__result_record_output(%Result*, i8*)
+
+            attributes #0 = { "entry_point" "output_labeling_schema" "qir_profiles"="adaptive_profile" "required_num_qubits"="2" "required_num_results"="2" }
+            attributes #1 = { "irreversible" }
+
+            ; module flags
+
+            !llvm.module.flags = !{!0, !1, !2, !3}
+
+            !0 = !{i32 1, !"qir_major_version", i32 1}
+            !1 = !{i32 7, !"qir_minor_version", i32 0}
+            !2 = !{i32 1, !"dynamic_qubit_management", i1 false}
+            !3 = !{i32 1, !"dynamic_result_management", i1 false}
+        "#]].assert_eq(&qir);
+    }
+
     #[test]
     fn qubit_reuse_allowed() {
         let source = "namespace Test {
@@ -1143,14 +1390,12 @@ mod adaptive_rif_profile {
 
             ; module flags
 
-            !llvm.module.flags = !{!0, !1, !2, !3, !4, !5}
+            !llvm.module.flags = !{!0, !1, !2, !3}
 
             !0 = !{i32 1, !"qir_major_version", i32 1}
             !1 = !{i32 7, !"qir_minor_version", i32 0}
             !2 = !{i32 1, !"dynamic_qubit_management", i1 false}
             !3 = !{i32 1, !"dynamic_result_management", i1 false}
-            !4 = !{i32 1, !"int_computations", !"i64"}
-            !5 = !{i32 1, !"float_computations", !"f64"}
         "#]].assert_eq(&qir);
     }
 
@@ -1197,14 +1442,12 @@ mod adaptive_rif_profile {
 
             ; module flags
 
-            !llvm.module.flags = !{!0, !1, !2, !3, !4, !5}
+            !llvm.module.flags = !{!0, !1, !2, !3}
 
             !0 = !{i32 1, !"qir_major_version", i32 1}
             !1 = !{i32 7, !"qir_minor_version", i32 0}
             !2 = !{i32 1, !"dynamic_qubit_management", i1 false}
             !3 = !{i32 1, !"dynamic_result_management", i1 false}
-            !4 = !{i32 1, !"int_computations", !"i64"}
-            !5 = !{i32 1, !"float_computations", !"f64"}
         "#]].assert_eq(&qir);
     }
 
@@ -1250,14 +1493,12 @@ mod adaptive_rif_profile {
 
             ; module flags
 
-            !llvm.module.flags = !{!0, !1, !2, !3, !4, !5}
+            !llvm.module.flags = !{!0, !1, !2, !3}
 
             !0 = !{i32 1, !"qir_major_version", i32 1}
             !1 = !{i32 7, !"qir_minor_version", i32 0}
             !2 = !{i32 1, !"dynamic_qubit_management", i1 false}
             !3 = !{i32 1, !"dynamic_result_management", i1 false}
-            !4 = !{i32 1, !"int_computations", !"i64"}
-            !5 = !{i32 1, !"float_computations", !"f64"}
         "#]].assert_eq(&qir);
     }
 
@@ -1312,14 +1553,12 @@ mod adaptive_rif_profile {
 
             ; module flags
 
-            !llvm.module.flags = !{!0, !1, !2, !3, !4, !5}
+            !llvm.module.flags = !{!0, !1, !2, !3}
 
             !0 = !{i32 1, !"qir_major_version", i32 1}
             !1 = !{i32 7, !"qir_minor_version", i32 0}
             !2 = !{i32 1, !"dynamic_qubit_management", i1 false}
             !3 = !{i32 1, !"dynamic_result_management", i1 false}
-            !4 = !{i32 1, !"int_computations", !"i64"}
-            !5 = !{i32 1, !"float_computations", !"f64"}
         "#]].assert_eq(&qir);
     }
 
@@ -1373,14 +1612,12 @@ mod adaptive_rif_profile {
 
             ; module flags
 
-            !llvm.module.flags = !{!0, !1, !2, !3, !4, !5}
+            !llvm.module.flags = !{!0, !1, !2, !3}
 
             !0 = !{i32 1, !"qir_major_version", i32 1}
             !1 = !{i32 7, !"qir_minor_version", i32 0}
             !2 = !{i32 1, !"dynamic_qubit_management", i1 false}
             !3 = !{i32 1, !"dynamic_result_management", i1 false}
-            !4 = !{i32 1, !"int_computations", !"i64"}
-            !5 = !{i32 1, !"float_computations", !"f64"}
         "#]].assert_eq(&qir);
     }
 
@@ -1411,8 +1648,8 @@ mod adaptive_rif_profile {
             block_2:
               br label %block_3
             block_3:
-              %var_4 = phi i64 [0, %block_1], [1, %block_2]
-              call void @__quantum__rt__int_record_output(i64 %var_4, i8* null)
+              %var_5 = phi i64 [0, %block_1], [1, %block_2]
+              call void @__quantum__rt__int_record_output(i64 %var_5, i8* null)
               ret void
             }
 
@@ -1429,14 +1666,13 @@ mod adaptive_rif_profile {
 
             ; module flags
 
-            !llvm.module.flags = !{!0, !1, !2, !3, !4, !5}
+            !llvm.module.flags = !{!0, !1, !2, !3, !4}
 
             !0 = !{i32 1, !"qir_major_version", i32 1}
             !1 = !{i32 7, !"qir_minor_version", i32 0}
             !2 = !{i32 1, !"dynamic_qubit_management", i1 false}
             !3 = !{i32 1, !"dynamic_result_management", i1 false}
             !4 = !{i32 1, !"int_computations", !"i64"}
-            !5 = !{i32 1, !"float_computations", !"f64"}
         "#]].assert_eq(&qir);
     }
 
@@ -1467,8 +1703,8 @@ mod adaptive_rif_profile {
             block_2:
               br label %block_3
             block_3:
-              %var_4 = phi double [0.0, %block_1], [1.0, %block_2]
-              call void @__quantum__rt__double_record_output(double %var_4, i8* null)
+              %var_5 = phi double [0.0, %block_1], [1.0, %block_2]
+              call void @__quantum__rt__double_record_output(double %var_5, i8* null)
               ret void
             }
 
@@ -1485,14 +1721,13 @@ mod adaptive_rif_profile {
 
             ; module flags
 
-            !llvm.module.flags = !{!0, !1, !2, !3, !4, !5}
+            !llvm.module.flags = !{!0, !1, !2, !3, !4}
 
             !0 = !{i32 1, !"qir_major_version", i32 1}
             !1 = !{i32 7, !"qir_minor_version", i32 0}
             !2 = !{i32 1, !"dynamic_qubit_management", i1 false}
             !3 = !{i32 1, !"dynamic_result_management", i1 false}
-            !4 = !{i32 1, !"int_computations", !"i64"}
-            !5 = !{i32 1, !"float_computations", !"f64"}
+            !4 = !{i32 1, !"float_computations", !"f64"}
         "#]].assert_eq(&qir);
     }
 
@@ -1534,14 +1769,12 @@ mod adaptive_rif_profile {
 
             ; module flags
 
-            !llvm.module.flags = !{!0, !1, !2, !3, !4, !5}
+            !llvm.module.flags = !{!0, !1, !2, !3}
 
             !0 = !{i32 1, !"qir_major_version", i32 1}
             !1 = !{i32 7, !"qir_minor_version", i32 0}
             !2 = !{i32 1, !"dynamic_qubit_management", i1 false}
             !3 = !{i32 1, !"dynamic_result_management", i1 false}
-            !4 = !{i32 1, !"int_computations", !"i64"}
-            !5 = !{i32 1, !"float_computations", !"f64"}
         "#]]
         .assert_eq(&qir);
     }
@@ -1581,18 +1814,18 @@ mod adaptive_rif_profile {
             block_2:
               br label %block_3
             block_3:
-              %var_9 = phi double [0.0, %block_1], [1.0, %block_2]
-              %var_4 = fadd double 1.0, %var_9
+              %var_10 = phi double [0.0, %block_1], [1.0, %block_2]
+              %var_4 = fadd double 1.0, %var_10
               call void @OpA(double %var_4, %Qubit* inttoptr (i64 0 to %Qubit*))
-              %var_5 = fmul double 2.0, %var_9
+              %var_5 = fmul double 2.0, %var_10
               call void @__quantum__qis__rx__body(double %var_5, %Qubit* inttoptr (i64 0 to %Qubit*))
-              %var_6 = fdiv double %var_9, 3.0
+              %var_6 = fdiv double %var_10, 3.0
               call void @__quantum__qis__ry__body(double %var_6, %Qubit* inttoptr (i64 0 to %Qubit*))
-              %var_7 = fsub double %var_9, 4.0
+              %var_7 = fsub double %var_10, 4.0
               call void @__quantum__qis__rz__body(double %var_7, %Qubit* inttoptr (i64 0 to %Qubit*))
-              call void @OpA(double %var_9, %Qubit* inttoptr (i64 0 to %Qubit*))
-              call void @__quantum__qis__rx__body(double %var_9, %Qubit* inttoptr (i64 0 to %Qubit*))
-              call void @__quantum__rt__double_record_output(double %var_9, i8* null)
+              call void @OpA(double %var_10, %Qubit* inttoptr (i64 0 to %Qubit*))
+              call void @__quantum__qis__rx__body(double %var_10, %Qubit* inttoptr (i64 0 to %Qubit*))
+              call void @__quantum__rt__double_record_output(double %var_10, i8* null)
               ret void
             }
 
@@ -1617,14 +1850,977 @@ mod adaptive_rif_profile {
 
             ; module flags
 
-            !llvm.module.flags = !{!0, !1, !2, !3, !4, !5}
+            !llvm.module.flags = !{!0, !1, !2, !3, !4}
+
+            !0 = !{i32 1, !"qir_major_version", i32 1}
+            !1 = !{i32 7, !"qir_minor_version", i32 0}
+            !2 = !{i32 1, !"dynamic_qubit_management", i1 false}
+            !3 = !{i32 1, !"dynamic_result_management", i1 false}
+            !4 = !{i32 1, !"float_computations", !"f64"}
+        "#]].assert_eq(&qir);
+    }
+}
+
+mod determinism_audit {
+    use qsc_data_structures::{language_features::LanguageFeatures, target::TargetCapabilityFlags};
+    use qsc_frontend::compile::SourceMap;
+
+    use super::compile_source_to_qir;
+    use crate::codegen::qir::{
+        get_qir_with_determinism_audit, get_qir_with_options, QirOptions, QubitTermination,
+    };
+
+    fn audit_source(
+        source: &str,
+        capabilities: TargetCapabilityFlags,
+        options: QirOptions,
+        seed: u64,
+    ) -> String {
+        let sources = SourceMap::new([("test.qs".into(), source.into())], None);
+        let (std_id, store) = crate::compile::package_store_with_stdlib(capabilities);
+        get_qir_with_determinism_audit(
+            sources,
+            LanguageFeatures::default(),
+            capabilities,
+            store,
+            &[(std_id, None)],
+            options,
+            seed,
+        )
+        .expect("determinism audit should succeed")
+    }
+
+    #[test]
+    fn audit_matches_qir_for_base_profile() {
+        let source = "namespace Test {
+            @EntryPoint()
+            operation Main() : Result[] {
+                use (q0, q1) = (Qubit(), Qubit());
+                H(q0);
+                CNOT(q0, q1);
+                [MResetZ(q0), MResetZ(q1)]
+            }
+        }";
+        let qir = compile_source_to_qir(source, TargetCapabilityFlags::empty());
+        assert_eq!(
+            audit_source(
+                source,
+                TargetCapabilityFlags::empty(),
+                QirOptions::default(),
+                0
+            ),
+            qir
+        );
+    }
+
+    #[test]
+    fn audit_matches_qir_for_branching_program_across_seeds() {
+        let source = "namespace Test {
+            @EntryPoint()
+            operation Main() : Int {
+                use (q0, q1) = (Qubit(), Qubit());
+                H(q0);
+                mutable count = 0;
+                if MResetZ(q0) == One {
+                    set count += 1;
+                    X(q1);
+                }
+                if MResetZ(q1) == One {
+                    set count += 2;
+                } else {
+                    set count -= 1;
+                }
+                count
+            }
+        }";
+        let capabilities = TargetCapabilityFlags::Adaptive
+            | TargetCapabilityFlags::QubitReset
+            | TargetCapabilityFlags::IntegerComputations;
+        let qir = compile_source_to_qir(source, capabilities);
+        for seed in 0..4 {
+            assert_eq!(
+                audit_source(source, capabilities, QirOptions::default(), seed),
+                qir
+            );
+        }
+    }
+
+    #[test]
+    fn audit_uses_the_given_options() {
+        let source = "namespace Test {
+            @EntryPoint()
+            operation Main() : Result {
+                use (q0, q1) = (Qubit(), Qubit());
+                H(q0);
+                CNOT(q0, q1);
+                MResetZ(q0)
+            }
+        }";
+        let capabilities = TargetCapabilityFlags::empty();
+        let options = QirOptions {
+            qubit_termination: QubitTermination::Reset,
+            ..QirOptions::default()
+        };
+        let sources = SourceMap::new([("test.qs".into(), source.into())], None);
+        let (std_id, store) = crate::compile::package_store_with_stdlib(capabilities);
+        let qir = get_qir_with_options(
+            sources,
+            LanguageFeatures::default(),
+            capabilities,
+            store,
+            &[(std_id, None)],
+            options,
+        )
+        .expect("Failed to generate QIR");
+        assert_ne!(qir, compile_source_to_qir(source, capabilities));
+        assert_eq!(audit_source(source, capabilities, options, 0), qir);
+    }
+}
+
+mod readable_names {
+    use expect_test::expect;
+    use qsc_data_structures::{language_features::LanguageFeatures, target::TargetCapabilityFlags};
+    use qsc_frontend::compile::SourceMap;
+
+    use crate::codegen::qir::{get_qir_with_options, NamingScheme, QirOptions};
+
+    #[test]
+    fn variables_and_blocks_are_named_after_their_origin() {
+        let source = "namespace Test {
+            @EntryPoint()
+            operation Main() : Int {
+                use q = Qubit();
+                H(q);
+                mutable count = 0;
+                if MResetZ(q) == One {
+                    set count += 1;
+                }
+                count
+            }
+        }";
+        let capabilities = TargetCapabilityFlags::Adaptive
+            | TargetCapabilityFlags::QubitReset
+            | TargetCapabilityFlags::IntegerComputations;
+        let sources = SourceMap::new([("test.qs".into(), source.into())], None);
+        let (std_id, store) = crate::compile::package_store_with_stdlib(capabilities);
+        let qir = get_qir_with_options(
+            sources,
+            LanguageFeatures::default(),
+            capabilities,
+            store,
+            &[(std_id, None)],
+            QirOptions {
+                naming: NamingScheme::Readable,
+                ..QirOptions::default()
+            },
+        )
+        .expect("Failed to generate QIR");
+        expect![[r#"
+            %Result = type opaque
+            %Qubit = type opaque
+
+            define void @ENTRYPOINT__main() #0 {
+            entry_0:
+              call void @__quantum__qis__h__body(%Qubit* inttoptr (i64 0 to %Qubit*))
+              call void @__quantum__qis__mresetz__body(%Qubit* inttoptr (i64 0 to %Qubit*), %Result* inttoptr (i64 0 to %Result*))
+              %read_result_1 = call i1 @__quantum__qis__read_result__body(%Result* inttoptr (i64 0 to %Result*))
+              br i1 %read_result_1, label %if_true_1, label %if_continue_2
+            if_true_1:
+              br label %if_continue_2
+            if_continue_2:
+              %count_5 = phi i64 [0, %entry_0], [1, %if_true_1]
+              call void @__quantum__rt__int_record_output(i64 %count_5, i8* null)
+              ret void
+            }
+
+            declare void @__quantum__qis__h__body(%Qubit*)
+
+            declare void @__quantum__qis__mresetz__body(%Qubit*, %Result*) #1
+
+            declare i1 @__quantum__qis__read_result__body(%Result*)
+
+            declare void @__quantum__rt__int_record_output(i64, i8*)
+
+            attributes #0 = { "entry_point" "output_labeling_schema" "qir_profiles"="adaptive_profile" "required_num_qubits"="1" "required_num_results"="1" }
+            attributes #1 = { "irreversible" }
+
+            ; module flags
+
+            !llvm.module.flags = !{!0, !1, !2, !3, !4}
 
             !0 = !{i32 1, !"qir_major_version", i32 1}
             !1 = !{i32 7, !"qir_minor_version", i32 0}
             !2 = !{i32 1, !"dynamic_qubit_management", i1 false}
             !3 = !{i32 1, !"dynamic_result_management", i1 false}
             !4 = !{i32 1, !"int_computations", !"i64"}
-            !5 = !{i32 1, !"float_computations", !"f64"}
+        "#]].assert_eq(&qir);
+    }
+}
+
+mod gate_set {
+    use expect_test::expect;
+    use qsc_data_structures::{language_features::LanguageFeatures, target::TargetCapabilityFlags};
+    use qsc_frontend::compile::SourceMap;
+
+    use crate::codegen::qir::{get_qir_with_options, GateSet, QirOptions};
+
+    #[test]
+    fn gates_outside_gate_set_are_decomposed() {
+        let source = "namespace Test {
+            @EntryPoint()
+            operation Main() : (Result, Result) {
+                use (q0, q1) = (Qubit(), Qubit());
+                H(q0);
+                CNOT(q0, q1);
+                (MResetZ(q0), MResetZ(q1))
+            }
+        }";
+        let capabilities = TargetCapabilityFlags::empty();
+        let sources = SourceMap::new([("test.qs".into(), source.into())], None);
+        let (std_id, store) = crate::compile::package_store_with_stdlib(capabilities);
+        let qir = get_qir_with_options(
+            sources,
+            LanguageFeatures::default(),
+            capabilities,
+            store,
+            &[(std_id, None)],
+            QirOptions {
+                gate_set: Some(
+                    "rz, sx, cz"
+                        .parse::<GateSet>()
+                        .expect("gate set should parse"),
+                ),
+                ..QirOptions::default()
+            },
+        )
+        .expect("Failed to generate QIR");
+        expect![[r#"
+            %Result = type opaque
+            %Qubit = type opaque
+
+            define void @ENTRYPOINT__main() #0 {
+            block_0:
+              call void @__quantum__qis__rz__body(double 1.5707963267948966, %Qubit* inttoptr (i64 0 to %Qubit*))
+              call void @__quantum__qis__sx__body(%Qubit* inttoptr (i64 0 to %Qubit*))
+              call void @__quantum__qis__rz__body(double 1.5707963267948966, %Qubit* inttoptr (i64 0 to %Qubit*))
+              call void @__quantum__qis__rz__body(double 1.5707963267948966, %Qubit* inttoptr (i64 1 to %Qubit*))
+              call void @__quantum__qis__sx__body(%Qubit* inttoptr (i64 1 to %Qubit*))
+              call void @__quantum__qis__rz__body(double 1.5707963267948966, %Qubit* inttoptr (i64 1 to %Qubit*))
+              call void @__quantum__qis__cz__body(%Qubit* inttoptr (i64 0 to %Qubit*), %Qubit* inttoptr (i64 1 to %Qubit*))
+              call void @__quantum__qis__rz__body(double 1.5707963267948966, %Qubit* inttoptr (i64 1 to %Qubit*))
+              call void @__quantum__qis__sx__body(%Qubit* inttoptr (i64 1 to %Qubit*))
+              call void @__quantum__qis__rz__body(double 1.5707963267948966, %Qubit* inttoptr (i64 1 to %Qubit*))
+              call void @__quantum__qis__m__body(%Qubit* inttoptr (i64 0 to %Qubit*), %Result* inttoptr (i64 0 to %Result*))
+              call void @__quantum__qis__m__body(%Qubit* inttoptr (i64 1 to %Qubit*), %Result* inttoptr (i64 1 to %Result*))
+              call void @__quantum__rt__tuple_record_output(i64 2, i8* null)
+              call void @__quantum__rt__result_record_output(%Result* inttoptr (i64 0 to %Result*), i8* null)
+              call void @__quantum__rt__result_record_output(%Result* inttoptr (i64 1 to %Result*), i8* null)
+              ret void
+            }
+
+            declare void @__quantum__rt__tuple_record_output(i64, i8*)
+
+            declare void @__quantum__rt__result_record_output(%Result*, i8*)
+
+            declare void @__quantum__qis__sx__body(%Qubit*)
+
+            declare void @__quantum__qis__rz__body(double, %Qubit*)
+
+            declare void @__quantum__qis__cz__body(%Qubit*, %Qubit*)
+
+            declare void @__quantum__qis__m__body(%Qubit*, %Result*) #1
+
+            attributes #0 = { "entry_point" "output_labeling_schema" "qir_profiles"="base_profile" "required_num_qubits"="2" "required_num_results"="2" }
+            attributes #1 = { "irreversible" }
+
+            ; module flags
+
+            !llvm.module.flags = !{!0, !1, !2, !3}
+
+            !0 = !{i32 1, !"qir_major_version", i32 1}
+            !1 = !{i32 7, !"qir_minor_version", i32 0}
+            !2 = !{i32 1, !"dynamic_qubit_management", i1 false}
+            !3 = !{i32 1, !"dynamic_result_management", i1 false}
+        "#]].assert_eq(&qir);
+    }
+
+    fn exp_qir(gate_set: Option<&str>) -> String {
+        let source = "namespace Test {
+            @EntryPoint()
+            operation Main() : Result[] {
+                use qs = Qubit[4];
+                Exp([PauliX, PauliI, PauliZ, PauliY], 0.5, qs);
+                Adjoint Exp([PauliZ, PauliZ], 0.25, qs[0..1]);
+                MResetEachZ(qs)
+            }
+        }";
+        let capabilities = TargetCapabilityFlags::empty();
+        let sources = SourceMap::new([("test.qs".into(), source.into())], None);
+        let (std_id, store) = crate::compile::package_store_with_stdlib(capabilities);
+        get_qir_with_options(
+            sources,
+            LanguageFeatures::default(),
+            capabilities,
+            store,
+            &[(std_id, None)],
+            QirOptions {
+                gate_set: gate_set
+                    .map(|gate_set| gate_set.parse::<GateSet>().expect("gate set should parse")),
+                ..QirOptions::default()
+            },
+        )
+        .expect("Failed to generate QIR")
+    }
+
+    #[test]
+    fn exp_is_emitted_as_pauli_rotation_when_gate_set_includes_it() {
+        let qir = exp_qir(Some("exp, h, s, s_adj, cx, rz"));
+        assert!(qir.contains(
+            "call void @__quantum__qis__exp_xzy__body(double 0.5, %Qubit* inttoptr (i64 0 to %Qubit*), %Qubit* inttoptr (i64 2 to %Qubit*), %Qubit* inttoptr (i64 3 to %Qubit*))"
+        ));
+        assert!(qir.contains(
+            "call void @__quantum__qis__exp_zz__body(double -0.25, %Qubit* inttoptr (i64 0 to %Qubit*), %Qubit* inttoptr (i64 1 to %Qubit*))"
+        ));
+        assert!(qir.contains(
+            "declare void @__quantum__qis__exp_xzy__body(double, %Qubit*, %Qubit*, %Qubit*)"
+        ));
+        assert!(!qir.contains("@__quantum__qis__cx__body"));
+    }
+
+    #[test]
+    fn exp_is_decomposed_when_gate_set_excludes_it() {
+        for gate_set in [None, Some("h, s, s_adj, cx, rz")] {
+            let qir = exp_qir(gate_set);
+            assert!(!qir.contains("__quantum__qis__exp_"));
+            assert!(qir.contains("@__quantum__qis__cx__body"));
+        }
+    }
+}
+
+mod output_names {
+    use expect_test::expect;
+    use qsc_data_structures::{language_features::LanguageFeatures, target::TargetCapabilityFlags};
+    use qsc_frontend::compile::SourceMap;
+
+    use super::compile_source_to_qir;
+    use crate::codegen::qir::{
+        get_qir_with_options, OutputRecording, PartialEvalConfig, QirOptions,
+    };
+
+    #[test]
+    fn output_names_label_recorded_outputs() {
+        let source = r#"namespace Test {
+            @EntryPoint()
+            @OutputName("syndrome", "fl\"ag")
+            operation Main() : (Result[], Result) {
+                use qs = Qubit[3];
+                H(qs[0]);
+                CNOT(qs[0], qs[1]);
+                ([MResetZ(qs[0]), MResetZ(qs[1])], MResetZ(qs[2]))
+            }
+        }"#;
+
+        let qir = compile_source_to_qir(source, TargetCapabilityFlags::empty());
+        expect![[r#"
+            %Result = type opaque
+            %Qubit = type opaque
+
+            @0 = internal constant [9 x i8] c"syndrome\00"
+            @1 = internal constant [12 x i8] c"syndrome[0]\00"
+            @2 = internal constant [12 x i8] c"syndrome[1]\00"
+            @3 = internal constant [6 x i8] c"fl\22ag\00"
+
+            define void @ENTRYPOINT__main() #0 {
+            block_0:
+              call void @__quantum__qis__h__body(%Qubit* inttoptr (i64 0 to %Qubit*))
+              call void @__quantum__qis__cx__body(%Qubit* inttoptr (i64 0 to %Qubit*), %Qubit* inttoptr (i64 1 to %Qubit*))
+              call void @__quantum__qis__m__body(%Qubit* inttoptr (i64 0 to %Qubit*), %Result* inttoptr (i64 0 to %Result*))
+              call void @__quantum__qis__m__body(%Qubit* inttoptr (i64 1 to %Qubit*), %Result* inttoptr (i64 1 to %Result*))
+              call void @__quantum__qis__m__body(%Qubit* inttoptr (i64 2 to %Qubit*), %Result* inttoptr (i64 2 to %Result*))
+              call void @__quantum__rt__tuple_record_output(i64 2, i8* null)
+              call void @__quantum__rt__array_record_output(i64 2, i8* getelementptr inbounds ([9 x i8], [9 x i8]* @0, i64 0, i64 0))
+              call void @__quantum__rt__result_record_output(%Result* inttoptr (i64 0 to %Result*), i8* getelementptr inbounds ([12 x i8], [12 x i8]* @1, i64 0, i64 0))
+              call void @__quantum__rt__result_record_output(%Result* inttoptr (i64 1 to %Result*), i8* getelementptr inbounds ([12 x i8], [12 x i8]* @2, i64 0, i64 0))
+              call void @__quantum__rt__result_record_output(%Result* inttoptr (i64 2 to %Result*), i8* getelementptr inbounds ([6 x i8], [6 x i8]* @3, i64 0, i64 0))
+              ret void
+            }
+
+            declare void @__quantum__qis__h__body(%Qubit*)
+
+            declare void @__quantum__qis__cx__body(%Qubit*, %Qubit*)
+
+            declare void @__quantum__rt__tuple_record_output(i64, i8*)
+
+            declare void @__quantum__rt__array_record_output(i64, i8*)
+
+            declare void @__quantum__rt__result_record_output(%Result*, i8*)
+
+            declare void @__quantum__qis__m__body(%Qubit*, %Result*) #1
+
+            attributes #0 = { "entry_point" "output_labeling_schema" "qir_profiles"="base_profile" "required_num_qubits"="3" "required_num_results"="3" }
+            attributes #1 = { "irreversible" }
+
+            ; module flags
+
+            !llvm.module.flags = !{!0, !1, !2, !3}
+
+            !0 = !{i32 1, !"qir_major_version", i32 1}
+            !1 = !{i32 7, !"qir_minor_version", i32 0}
+            !2 = !{i32 1, !"dynamic_qubit_management", i1 false}
+            !3 = !{i32 1, !"dynamic_result_management", i1 false}
+        "#]]
+        .assert_eq(&qir);
+    }
+
+    #[test]
+    fn labeled_output_recording_labels_unnamed_outputs() {
+        let source = r#"namespace Test {
+            @EntryPoint()
+            operation Main() : (Result, Result) {
+                use qs = Qubit[2];
+                (MResetZ(qs[0]), MResetZ(qs[1]))
+            }
+        }"#;
+
+        let capabilities = TargetCapabilityFlags::empty();
+        let sources = SourceMap::new([("test.qs".into(), source.into())], None);
+        let (std_id, store) = crate::compile::package_store_with_stdlib(capabilities);
+        let qir = get_qir_with_options(
+            sources,
+            LanguageFeatures::default(),
+            capabilities,
+            store,
+            &[(std_id, None)],
+            QirOptions {
+                partial_eval: PartialEvalConfig {
+                    output_recording: OutputRecording::Labeled,
+                    ..PartialEvalConfig::default()
+                },
+                ..QirOptions::default()
+            },
+        )
+        .expect("Failed to generate QIR");
+        expect![[r#"
+            %Result = type opaque
+            %Qubit = type opaque
+
+            @0 = internal constant [7 x i8] c"output\00"
+            @1 = internal constant [9 x i8] c"output.0\00"
+            @2 = internal constant [9 x i8] c"output.1\00"
+
+            define void @ENTRYPOINT__main() #0 {
+            block_0:
+              call void @__quantum__qis__m__body(%Qubit* inttoptr (i64 0 to %Qubit*), %Result* inttoptr (i64 0 to %Result*))
+              call void @__quantum__qis__m__body(%Qubit* inttoptr (i64 1 to %Qubit*), %Result* inttoptr (i64 1 to %Result*))
+              call void @__quantum__rt__tuple_record_output(i64 2, i8* getelementptr inbounds ([7 x i8], [7 x i8]* @0, i64 0, i64 0))
+              call void @__quantum__rt__result_record_output(%Result* inttoptr (i64 0 to %Result*), i8* getelementptr inbounds ([9 x i8], [9 x i8]* @1, i64 0, i64 0))
+              call void @__quantum__rt__result_record_output(%Result* inttoptr (i64 1 to %Result*), i8* getelementptr inbounds ([9 x i8], [9 x i8]* @2, i64 0, i64 0))
+              ret void
+            }
+
+            declare void @__quantum__rt__tuple_record_output(i64, i8*)
+
+            declare void @__quantum__rt__result_record_output(%Result*, i8*)
+
+            declare void @__quantum__qis__m__body(%Qubit*, %Result*) #1
+
+            attributes #0 = { "entry_point" "output_labeling_schema" "qir_profiles"="base_profile" "required_num_qubits"="2" "required_num_results"="2" }
+            attributes #1 = { "irreversible" }
+
+            ; module flags
+
+            !llvm.module.flags = !{!0, !1, !2, !3}
+
+            !0 = !{i32 1, !"qir_major_version", i32 1}
+            !1 = !{i32 7, !"qir_minor_version", i32 0}
+            !2 = !{i32 1, !"dynamic_qubit_management", i1 false}
+            !3 = !{i32 1, !"dynamic_result_management", i1 false}
+        "#]].assert_eq(&qir);
+    }
+}
+
+mod bitcode {
+    use qsc_data_structures::{language_features::LanguageFeatures, target::TargetCapabilityFlags};
+    use qsc_frontend::compile::SourceMap;
+
+    use crate::codegen::qir::{get_qir_bitcode, QirOptions};
+
+    #[test]
+    fn bitcode_starts_with_magic_number_and_is_word_aligned() {
+        let source = "namespace Test {
+            @EntryPoint()
+            operation Main() : Result {
+                use q = Qubit();
+                H(q);
+                MResetZ(q)
+            }
+        }";
+        let capabilities = TargetCapabilityFlags::empty();
+        let sources = SourceMap::new([("test.qs".into(), source.into())], None);
+        let (std_id, store) = crate::compile::package_store_with_stdlib(capabilities);
+        let bitcode = get_qir_bitcode(
+            sources,
+            LanguageFeatures::default(),
+            capabilities,
+            store,
+            &[(std_id, None)],
+            QirOptions::default(),
+        )
+        .expect("Failed to generate bitcode");
+        assert_eq!(&bitcode[..4], b"BC\xC0\xDE");
+        assert_eq!(bitcode.len() % 4, 0);
+    }
+}
+
+mod entry_points {
+    use expect_test::expect;
+    use qsc_data_structures::{language_features::LanguageFeatures, target::TargetCapabilityFlags};
+    use qsc_frontend::compile::SourceMap;
+    use qsc_passes::PackageType;
+
+    use crate::codegen::qir::{entry_point_callables, fir_to_qir_from_entry_points, QirOptions};
+
+    #[test]
+    fn entry_point_callables_are_linked_into_one_module() {
+        let source = "namespace Test {
+            @EntryPoint()
+            operation Bell() : (Result, Result) {
+                use (q0, q1) = (Qubit(), Qubit());
+                H(q0);
+                CNOT(q0, q1);
+                (MResetZ(q0), MResetZ(q1))
+            }
+
+            @EntryPoint()
+            operation Single() : Result {
+                use q = Qubit();
+                X(q);
+                MResetZ(q)
+            }
+        }";
+        let capabilities = TargetCapabilityFlags::empty();
+        // An entry expression is given, so the compilation may have more than one entry point callable.
+        let sources = SourceMap::new([("test.qs".into(), source.into())], Some("()".into()));
+        let (std_id, mut store) = crate::compile::package_store_with_stdlib(capabilities);
+        let (unit, errors) = crate::compile::compile(
+            &store,
+            &[(std_id, None)],
+            sources,
+            PackageType::Exe,
+            capabilities,
+            LanguageFeatures::default(),
+        );
+        assert!(errors.is_empty(), "{errors:?}");
+        let package_id = store.insert(unit);
+        let (fir_store, fir_package_id) = qsc_passes::lower_hir_to_fir(&store, package_id);
+
+        let entry_points = entry_point_callables(&fir_store, fir_package_id);
+        let qir = fir_to_qir_from_entry_points(
+            &fir_store,
+            capabilities,
+            None,
+            &entry_points,
+            QirOptions::default(),
+        )
+        .expect("linking entry points should succeed");
+        expect![[r#"
+            %Result = type opaque
+            %Qubit = type opaque
+
+            define void @ENTRYPOINT__Bell() #0 {
+            block_0:
+              call void @__quantum__qis__h__body(%Qubit* inttoptr (i64 0 to %Qubit*))
+              call void @__quantum__qis__cx__body(%Qubit* inttoptr (i64 0 to %Qubit*), %Qubit* inttoptr (i64 1 to %Qubit*))
+              call void @__quantum__qis__m__body(%Qubit* inttoptr (i64 0 to %Qubit*), %Result* inttoptr (i64 0 to %Result*))
+              call void @__quantum__qis__m__body(%Qubit* inttoptr (i64 1 to %Qubit*), %Result* inttoptr (i64 1 to %Result*))
+              call void @__quantum__rt__tuple_record_output(i64 2, i8* null)
+              call void @__quantum__rt__result_record_output(%Result* inttoptr (i64 0 to %Result*), i8* null)
+              call void @__quantum__rt__result_record_output(%Result* inttoptr (i64 1 to %Result*), i8* null)
+              ret void
+            }
+
+            define void @ENTRYPOINT__Single() #1 {
+            block_0:
+              call void @__quantum__qis__x__body(%Qubit* inttoptr (i64 0 to %Qubit*))
+              call void @__quantum__qis__m__body(%Qubit* inttoptr (i64 0 to %Qubit*), %Result* inttoptr (i64 0 to %Result*))
+              call void @__quantum__rt__result_record_output(%Result* inttoptr (i64 0 to %Result*), i8* null)
+              ret void
+            }
+
+            declare void @__quantum__qis__h__body(%Qubit*)
+
+            declare void @__quantum__qis__cx__body(%Qubit*, %Qubit*)
+
+            declare void @__quantum__rt__tuple_record_output(i64, i8*)
+
+            declare void @__quantum__rt__result_record_output(%Result*, i8*)
+
+            declare void @__quantum__qis__m__body(%Qubit*, %Result*) #2
+
+            declare void @__quantum__qis__x__body(%Qubit*)
+
+            attributes #0 = { "entry_point" "output_labeling_schema" "qir_profiles"="base_profile" "required_num_qubits"="2" "required_num_results"="2" }
+            attributes #1 = { "entry_point" "output_labeling_schema" "qir_profiles"="base_profile" "required_num_qubits"="1" "required_num_results"="1" }
+            attributes #2 = { "irreversible" }
+
+            ; module flags
+
+            !llvm.module.flags = !{!0, !1, !2, !3}
+
+            !0 = !{i32 1, !"qir_major_version", i32 1}
+            !1 = !{i32 7, !"qir_minor_version", i32 0}
+            !2 = !{i32 1, !"dynamic_qubit_management", i1 false}
+            !3 = !{i32 1, !"dynamic_result_management", i1 false}
+        "#]].assert_eq(&qir);
+    }
+
+    #[test]
+    fn output_names_of_linked_entry_points_have_separate_globals() {
+        let source = r#"namespace Test {
+            @EntryPoint()
+            @OutputName("first")
+            operation First() : Result {
+                use q = Qubit();
+                MResetZ(q)
+            }
+
+            @EntryPoint()
+            @OutputName("second")
+            operation Second() : Result {
+                use q = Qubit();
+                X(q);
+                MResetZ(q)
+            }
+        }"#;
+        let capabilities = TargetCapabilityFlags::empty();
+        let sources = SourceMap::new([("test.qs".into(), source.into())], Some("()".into()));
+        let (std_id, mut store) = crate::compile::package_store_with_stdlib(capabilities);
+        let (unit, errors) = crate::compile::compile(
+            &store,
+            &[(std_id, None)],
+            sources,
+            PackageType::Exe,
+            capabilities,
+            LanguageFeatures::default(),
+        );
+        assert!(errors.is_empty(), "{errors:?}");
+        let package_id = store.insert(unit);
+        let (fir_store, fir_package_id) = qsc_passes::lower_hir_to_fir(&store, package_id);
+
+        let entry_points = entry_point_callables(&fir_store, fir_package_id);
+        let qir = fir_to_qir_from_entry_points(
+            &fir_store,
+            capabilities,
+            None,
+            &entry_points,
+            QirOptions::default(),
+        )
+        .expect("linking entry points should succeed");
+        expect![[r#"
+            %Result = type opaque
+            %Qubit = type opaque
+
+            @0 = internal constant [6 x i8] c"first\00"
+            @1 = internal constant [7 x i8] c"second\00"
+
+            define void @ENTRYPOINT__First() #0 {
+            block_0:
+              call void @__quantum__qis__m__body(%Qubit* inttoptr (i64 0 to %Qubit*), %Result* inttoptr (i64 0 to %Result*))
+              call void @__quantum__rt__result_record_output(%Result* inttoptr (i64 0 to %Result*), i8* getelementptr inbounds ([6 x i8], [6 x i8]* @0, i64 0, i64 0))
+              ret void
+            }
+
+            define void @ENTRYPOINT__Second() #1 {
+            block_0:
+              call void @__quantum__qis__x__body(%Qubit* inttoptr (i64 0 to %Qubit*))
+              call void @__quantum__qis__m__body(%Qubit* inttoptr (i64 0 to %Qubit*), %Result* inttoptr (i64 0 to %Result*))
+              call void @__quantum__rt__result_record_output(%Result* inttoptr (i64 0 to %Result*), i8* getelementptr inbounds ([7 x i8], [7 x i8]* @1, i64 0, i64 0))
+              ret void
+            }
+
+            declare void @__quantum__rt__result_record_output(%Result*, i8*)
+
+            declare void @__quantum__qis__m__body(%Qubit*, %Result*) #2
+
+            declare void @__quantum__qis__x__body(%Qubit*)
+
+            attributes #0 = { "entry_point" "output_labeling_schema" "qir_profiles"="base_profile" "required_num_qubits"="1" "required_num_results"="1" }
+            attributes #1 = { "entry_point" "output_labeling_schema" "qir_profiles"="base_profile" "required_num_qubits"="1" "required_num_results"="1" }
+            attributes #2 = { "irreversible" }
+
+            ; module flags
+
+            !llvm.module.flags = !{!0, !1, !2, !3}
+
+            !0 = !{i32 1, !"qir_major_version", i32 1}
+            !1 = !{i32 7, !"qir_minor_version", i32 0}
+            !2 = !{i32 1, !"dynamic_qubit_management", i1 false}
+            !3 = !{i32 1, !"dynamic_result_management", i1 false}
+        "#]]
+        .assert_eq(&qir);
+    }
+}
+
+mod delay {
+    use qsc_data_structures::target::TargetCapabilityFlags;
+
+    use super::compile_source_to_qir;
+
+    const SOURCE: &str = "namespace Test {
+        @EntryPoint()
+        operation Main() : Result {
+            use (q0, q1) = (Qubit(), Qubit());
+            H(q0);
+            let r = MResetZ(q0);
+            DelayUs(2.5);
+            if r == One {
+                X(q1);
+            }
+            MResetZ(q1)
+        }
+    }";
+
+    #[test]
+    fn delay_is_emitted_for_targets_with_delay_capability() {
+        let qir = compile_source_to_qir(
+            SOURCE,
+            TargetCapabilityFlags::Adaptive
+                | TargetCapabilityFlags::QubitReset
+                | TargetCapabilityFlags::Delay,
+        );
+        assert!(
+            qir.contains("call void @__quantum__qis__delay_us__body(double 2.5)"),
+            "{qir}"
+        );
+        assert!(
+            qir.contains("declare void @__quantum__qis__delay_us__body(double)"),
+            "{qir}"
+        );
+    }
+
+    #[test]
+    fn delay_is_left_out_for_targets_without_delay_capability() {
+        let qir = compile_source_to_qir(
+            SOURCE,
+            TargetCapabilityFlags::Adaptive | TargetCapabilityFlags::QubitReset,
+        );
+        assert!(!qir.contains("__quantum__qis__delay_us__body"), "{qir}");
+    }
+}
+
+mod qubit_termination {
+    use expect_test::expect;
+    use qsc_data_structures::{language_features::LanguageFeatures, target::TargetCapabilityFlags};
+    use qsc_frontend::compile::SourceMap;
+
+    use crate::codegen::qir::{get_qir_with_options, QirOptions, QubitTermination};
+
+    #[test]
+    fn qubits_left_in_use_are_measured_on_target_without_reset() {
+        let source = "namespace Test {
+            @EntryPoint()
+            operation Main() : Result {
+                use (q0, q1) = (Qubit(), Qubit());
+                H(q0);
+                CNOT(q0, q1);
+                MResetZ(q0)
+            }
+        }";
+        let capabilities = TargetCapabilityFlags::empty();
+        let sources = SourceMap::new([("test.qs".into(), source.into())], None);
+        let (std_id, store) = crate::compile::package_store_with_stdlib(capabilities);
+        let qir = get_qir_with_options(
+            sources,
+            LanguageFeatures::default(),
+            capabilities,
+            store,
+            &[(std_id, None)],
+            QirOptions {
+                qubit_termination: QubitTermination::Reset,
+                ..QirOptions::default()
+            },
+        )
+        .expect("Failed to generate QIR");
+        expect![[r#"
+            %Result = type opaque
+            %Qubit = type opaque
+
+            ; inserted to terminate qubit: Qubit(1) measured into Result(1)
+            define void @ENTRYPOINT__main() #0 {
+            block_0:
+              call void @__quantum__qis__h__body(%Qubit* inttoptr (i64 0 to %Qubit*))
+              call void @__quantum__qis__cx__body(%Qubit* inttoptr (i64 0 to %Qubit*), %Qubit* inttoptr (i64 1 to %Qubit*))
+              call void @__quantum__qis__m__body(%Qubit* inttoptr (i64 0 to %Qubit*), %Result* inttoptr (i64 0 to %Result*))
+              call void @__quantum__qis__m__body(%Qubit* inttoptr (i64 1 to %Qubit*), %Result* inttoptr (i64 1 to %Result*))
+              call void @__quantum__rt__result_record_output(%Result* inttoptr (i64 0 to %Result*), i8* null)
+              ret void
+            }
+
+            declare void @__quantum__qis__h__body(%Qubit*)
+
+            declare void @__quantum__qis__cx__body(%Qubit*, %Qubit*)
+
+            declare void @__quantum__rt__result_record_output(%Result*, i8*)
+
+            declare void @__quantum__qis__m__body(%Qubit*, %Result*) #1
+
+            attributes #0 = { "entry_point" "output_labeling_schema" "qir_profiles"="base_profile" "required_num_qubits"="2" "required_num_results"="2" }
+            attributes #1 = { "irreversible" }
+
+            ; module flags
+
+            !llvm.module.flags = !{!0, !1, !2, !3}
+
+            !0 = !{i32 1, !"qir_major_version", i32 1}
+            !1 = !{i32 7, !"qir_minor_version", i32 0}
+            !2 = !{i32 1, !"dynamic_qubit_management", i1 false}
+            !3 = !{i32 1, !"dynamic_result_management", i1 false}
+        "#]].assert_eq(&qir);
+    }
+}
+
+mod qubit_reuse {
+    use expect_test::expect;
+    use qsc_data_structures::{language_features::LanguageFeatures, target::TargetCapabilityFlags};
+    use qsc_frontend::compile::SourceMap;
+
+    use crate::codegen::qir::{get_qir_with_options, QirOptions};
+
+    #[test]
+    fn reset_qubits_are_reused_when_enabled() {
+        let source = "namespace Test {
+            @EntryPoint()
+            operation Main() : (Result, Result) {
+                use q0 = Qubit();
+                X(q0);
+                let r0 = MResetZ(q0);
+                use q1 = Qubit();
+                H(q1);
+                (r0, MResetZ(q1))
+            }
+        }";
+        let capabilities = TargetCapabilityFlags::Adaptive | TargetCapabilityFlags::QubitReset;
+        let sources = SourceMap::new([("test.qs".into(), source.into())], None);
+        let (std_id, store) = crate::compile::package_store_with_stdlib(capabilities);
+        let qir = get_qir_with_options(
+            sources,
+            LanguageFeatures::default(),
+            capabilities,
+            store,
+            &[(std_id, None)],
+            QirOptions {
+                reuse_qubits: true,
+                ..QirOptions::default()
+            },
+        )
+        .expect("Failed to generate QIR");
+        expect![[r#"
+            %Result = type opaque
+            %Qubit = type opaque
+
+            define void @ENTRYPOINT__main() #0 {
+            block_0:
+              call void @__quantum__qis__x__body(%Qubit* inttoptr (i64 0 to %Qubit*))
+              call void @__quantum__qis__mresetz__body(%Qubit* inttoptr (i64 0 to %Qubit*), %Result* inttoptr (i64 0 to %Result*))
+              call void @__quantum__qis__h__body(%Qubit* inttoptr (i64 0 to %Qubit*))
+              call void @__quantum__qis__mresetz__body(%Qubit* inttoptr (i64 0 to %Qubit*), %Result* inttoptr (i64 1 to %Result*))
+              call void @__quantum__rt__tuple_record_output(i64 2, i8* null)
+              call void @__quantum__rt__result_record_output(%Result* inttoptr (i64 0 to %Result*), i8* null)
+              call void @__quantum__rt__result_record_output(%Result* inttoptr (i64 1 to %Result*), i8* null)
+              ret void
+            }
+
+            declare void @__quantum__qis__x__body(%Qubit*)
+
+            declare void @__quantum__qis__mresetz__body(%Qubit*, %Result*) #1
+
+            declare void @__quantum__qis__h__body(%Qubit*)
+
+            declare void @__quantum__rt__tuple_record_output(i64, i8*)
+
+            declare void @__quantum__rt__result_record_output(%Result*, i8*)
+
+            attributes #0 = { "entry_point" "output_labeling_schema" "qir_profiles"="adaptive_profile" "required_num_qubits"="1" "required_num_results"="2" }
+            attributes #1 = { "irreversible" }
+
+            ; module flags
+
+            !llvm.module.flags = !{!0, !1, !2, !3}
+
+            !0 = !{i32 1, !"qir_major_version", i32 1}
+            !1 = !{i32 7, !"qir_minor_version", i32 0}
+            !2 = !{i32 1, !"dynamic_qubit_management", i1 false}
+            !3 = !{i32 1, !"dynamic_result_management", i1 false}
+        "#]].assert_eq(&qir);
+    }
+
+    #[test]
+    fn qubits_are_not_reused_for_base_profile() {
+        // Without the reset capability, no used qubit can be returned to a known state, so reuse is skipped and each
+        // measured qubit keeps its own ID.
+        let source = "namespace Test {
+            @EntryPoint()
+            operation Main() : (Result, Result) {
+                use q0 = Qubit();
+                X(q0);
+                let r0 = MResetZ(q0);
+                use q1 = Qubit();
+                H(q1);
+                (r0, MResetZ(q1))
+            }
+        }";
+        let capabilities = TargetCapabilityFlags::empty();
+        let sources = SourceMap::new([("test.qs".into(), source.into())], None);
+        let (std_id, store) = crate::compile::package_store_with_stdlib(capabilities);
+        let qir = get_qir_with_options(
+            sources,
+            LanguageFeatures::default(),
+            capabilities,
+            store,
+            &[(std_id, None)],
+            QirOptions {
+                reuse_qubits: true,
+                ..QirOptions::default()
+            },
+        )
+        .expect("Failed to generate QIR");
+        expect![[r#"
+            %Result = type opaque
+            %Qubit = type opaque
+
+            define void @ENTRYPOINT__main() #0 {
+            block_0:
+              call void @__quantum__qis__x__body(%Qubit* inttoptr (i64 0 to %Qubit*))
+              call void @__quantum__qis__h__body(%Qubit* inttoptr (i64 1 to %Qubit*))
+              call void @__quantum__qis__m__body(%Qubit* inttoptr (i64 0 to %Qubit*), %Result* inttoptr (i64 0 to %Result*))
+              call void @__quantum__qis__m__body(%Qubit* inttoptr (i64 1 to %Qubit*), %Result* inttoptr (i64 1 to %Result*))
+              call void @__quantum__rt__tuple_record_output(i64 2, i8* null)
+              call void @__quantum__rt__result_record_output(%Result* inttoptr (i64 0 to %Result*), i8* null)
+              call void @__quantum__rt__result_record_output(%Result* inttoptr (i64 1 to %Result*), i8* null)
+              ret void
+            }
+
+            declare void @__quantum__qis__x__body(%Qubit*)
+
+            declare void @__quantum__qis__h__body(%Qubit*)
+
+            declare void @__quantum__rt__tuple_record_output(i64, i8*)
+
+            declare void @__quantum__rt__result_record_output(%Result*, i8*)
+
+            declare void @__quantum__qis__m__body(%Qubit*, %Result*) #1
+
+            attributes #0 = { "entry_point" "output_labeling_schema" "qir_profiles"="base_profile" "required_num_qubits"="2" "required_num_results"="2" }
+            attributes #1 = { "irreversible" }
+
+            ; module flags
+
+            !llvm.module.flags = !{!0, !1, !2, !3}
+
+            !0 = !{i32 1, !"qir_major_version", i32 1}
+            !1 = !{i32 7, !"qir_minor_version", i32 0}
+            !2 = !{i32 1, !"dynamic_qubit_management", i1 false}
+            !3 = !{i32 1, !"dynamic_result_management", i1 false}
         "#]].assert_eq(&qir);
     }
 }
@@ -5,14 +5,19 @@ use miette::{Diagnostic, Report};
 use qsc_data_structures::{language_features::LanguageFeatures, target::TargetCapabilityFlags};
 pub use qsc_frontend::compile::Dependencies;
 use qsc_frontend::{
-    compile::{CompileUnit, PackageStore, SourceMap},
+    compile::{CompileKey, CompileUnit, PackageStore, SourceMap},
     error::WithSource,
 };
+pub use qsc_parse::incremental::ParseCache;
 use qsc_passes::{run_core_passes, run_default_passes, PackageType};
 use thiserror::Error;
 
 pub type Error = WithSource<ErrorKind>;
 
+/// A cache of compiled packages along with their errors, which lets a compilation reuse the
+/// packages whose sources and dependencies have not changed since the last one.
+pub type CompileCache = qsc_frontend::compile::CompileCache<Vec<Error>>;
+
 #[derive(Clone, Debug, Diagnostic, Error)]
 #[error(transparent)]
 /// `ErrorKind` represents the different kinds of errors that can occur in the compiler.
@@ -81,6 +86,71 @@ pub fn compile(
     process_compile_unit(store, package_type, unit)
 }
 
+/// Compiles a package from its source representation, reparsing only the parts of each source
+/// that changed since the previous compilation with the same cache.
+#[must_use]
+pub fn compile_with_parse_cache(
+    store: &PackageStore,
+    dependencies: &Dependencies,
+    sources: SourceMap,
+    package_type: PackageType,
+    capabilities: TargetCapabilityFlags,
+    language_features: LanguageFeatures,
+    parse_cache: &mut ParseCache,
+) -> (CompileUnit, Vec<Error>) {
+    let unit = qsc_frontend::compile::compile_with_parse_cache(
+        store,
+        dependencies,
+        sources,
+        capabilities,
+        language_features,
+        parse_cache,
+    );
+    process_compile_unit(store, package_type, unit)
+}
+
+/// Compiles a package from its source representation like [`compile`], reusing the package from
+/// the cache if it was compiled from the same sources against the same dependencies before. The
+/// package is compiled without the cache if any of its dependencies was compiled without one.
+#[must_use]
+pub fn compile_with_cache(
+    store: &PackageStore,
+    dependencies: &Dependencies,
+    sources: SourceMap,
+    package_type: PackageType,
+    capabilities: TargetCapabilityFlags,
+    language_features: LanguageFeatures,
+    compile_cache: &mut CompileCache,
+) -> (CompileUnit, Vec<Error>) {
+    let Some(key) = CompileKey::new(
+        store,
+        dependencies,
+        &sources,
+        capabilities,
+        language_features,
+    ) else {
+        return compile(
+            store,
+            dependencies,
+            sources,
+            package_type,
+            capabilities,
+            language_features,
+        );
+    };
+
+    compile_cache.get_or_compile(key.with(package_type), || {
+        compile(
+            store,
+            dependencies,
+            sources,
+            package_type,
+            capabilities,
+            language_features,
+        )
+    })
+}
+
 #[must_use]
 #[allow(clippy::module_name_repetitions)]
 fn process_compile_unit(
@@ -94,7 +164,7 @@ fn process_compile_unit(
     }
 
     if errors.is_empty() {
-        for error in run_default_passes(store.core(), &mut unit, package_type) {
+        for error in run_default_passes(store, &mut unit, package_type) {
             errors.push(WithSource::from_map(&unit.sources, error.into()));
         }
     }
@@ -111,6 +181,25 @@ pub fn package_store_with_stdlib(
     (std_id, store)
 }
 
+/// Creates a package store with the core and standard libraries like [`package_store_with_stdlib`],
+/// reusing the libraries from the cache if they were compiled for the same capabilities before.
+#[must_use]
+pub fn package_store_with_stdlib_cached(
+    capabilities: TargetCapabilityFlags,
+    compile_cache: &mut CompileCache,
+) -> (qsc_hir::hir::PackageId, PackageStore) {
+    let (core, _) = compile_cache.get_or_compile(
+        CompileKey::library("core", TargetCapabilityFlags::empty()),
+        || (core(), Vec::new()),
+    );
+    let mut store = PackageStore::new(core);
+    let (std, _) = compile_cache.get_or_compile(CompileKey::library("std", capabilities), || {
+        (std(&store, capabilities), Vec::new())
+    });
+    let std_id = store.insert(std);
+    (std_id, store)
+}
+
 /// Compiles the core library.
 ///
 /// # Panics
@@ -140,7 +229,7 @@ pub fn core() -> CompileUnit {
 #[must_use]
 pub fn std(store: &PackageStore, capabilities: TargetCapabilityFlags) -> CompileUnit {
     let mut unit = qsc_frontend::compile::std(store, capabilities);
-    let pass_errors = run_default_passes(store.core(), &mut unit, PackageType::Lib);
+    let pass_errors = run_default_passes(store, &mut unit, PackageType::Lib);
     if pass_errors.is_empty() {
         unit
     } else {
@@ -2,6 +2,7 @@
 // Licensed under the MIT License.
 
 use miette::Diagnostic;
+#[cfg(feature = "interpreter")]
 use qsc_frontend::compile::PackageStore;
 use std::fmt::{self, Debug, Display, Formatter};
 use thiserror::Error;
@@ -15,6 +16,7 @@ pub struct WithStack<E> {
 }
 
 impl<E> WithStack<E> {
+    #[cfg(feature = "interpreter")]
     pub(super) fn new(error: E, stack_trace: Option<String>) -> Self {
         WithStack { error, stack_trace }
     }
@@ -69,6 +71,7 @@ impl<E: Diagnostic> Diagnostic for WithStack<E> {
     }
 }
 
+#[cfg(feature = "interpreter")]
 pub(super) fn from_eval(
     error: qsc_eval::Error,
     store: &PackageStore,
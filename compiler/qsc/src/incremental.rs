@@ -70,13 +70,15 @@ impl Compiler {
             capabilities,
             language_features,
         );
-        let store = store.open();
+        let passes = PassContext::with_store(&store);
+        let mut store = store.open();
+        store.get_open_mut().1.dependencies = dependencies.iter().map(|(id, _)| *id).collect();
 
         Ok(Self {
             store,
             source_package_id,
             frontend,
-            passes: PassContext::default(),
+            passes,
         })
     }
 
@@ -97,13 +99,15 @@ impl Compiler {
             capabilities,
             language_features,
         );
-        let store = store.open();
+        let passes = PassContext::with_store(&store);
+        let mut store = store.open();
+        store.get_open_mut().1.dependencies = dependencies.iter().map(|(id, _)| *id).collect();
 
         Ok(Self {
             store,
             source_package_id,
             frontend,
-            passes: PassContext::default(),
+            passes,
         })
     }
 
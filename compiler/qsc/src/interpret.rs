@@ -1,73 +1,112 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
-#[cfg(test)]
+#[cfg(all(test, feature = "debugger"))]
 mod circuit_tests;
+#[cfg(feature = "interpreter")]
 mod debug;
-#[cfg(test)]
+#[cfg(all(test, feature = "debugger"))]
 mod debugger_tests;
-#[cfg(test)]
+#[cfg(all(test, feature = "interpreter"))]
 mod package_tests;
-#[cfg(test)]
+#[cfg(all(test, feature = "debugger"))]
 mod tests;
 
-use std::rc::Rc;
-
 pub use qsc_eval::{
+    checkpoint::ResumeError,
     debug::Frame,
     noise::PauliNoise,
     output::{self, GenericReceiver},
+    tape::{Outcome, Tape},
+    trace::{AttributionNode, Measurement, MeasurementKind},
     val::Closure,
     val::Range as ValueRange,
     val::Result,
     val::Value,
     StepAction, StepResult,
 };
-use qsc_hir::{global, ty};
-use qsc_linter::{HirLint, Lint, LintKind, LintLevel};
-use qsc_lowerer::{map_fir_package_to_hir, map_hir_package_to_fir};
-use qsc_partial_eval::ProgramEntry;
-use qsc_rca::PackageStoreComputeProperties;
 
+use crate::error::WithStack;
+use miette::Diagnostic;
+use qsc_frontend::error::WithSource;
+use thiserror::Error;
+
+#[cfg(feature = "debugger")]
+use crate::location::Location;
+#[cfg(feature = "interpreter")]
 use crate::{
-    error::{self, WithStack},
+    error,
     incremental::Compiler,
-    location::Location,
+    memory::{self, MemoryUsage},
 };
+#[cfg(feature = "interpreter")]
 use debug::format_call_stack;
-use miette::Diagnostic;
+#[cfg(feature = "interpreter")]
 use num_bigint::BigUint;
+#[cfg(feature = "interpreter")]
 use num_complex::Complex;
+#[cfg(feature = "interpreter")]
 use qsc_circuit::{
     operations::entry_expr_for_qubit_operation, Builder as CircuitBuilder, Circuit,
     Config as CircuitConfig,
 };
-use qsc_codegen::qir::{fir_to_qir, fir_to_qir_from_callable};
+#[cfg(feature = "interpreter")]
+use qsc_codegen::qir::{
+    fir_to_qir, fir_to_qir_bitcode, fir_to_qir_from_callable, fir_to_rir, ProgramStats, QirOptions,
+};
+#[cfg(feature = "interpreter")]
+use qsc_data_structures::{
+    functors::FunctorApp, language_features::LanguageFeatures, target::TargetCapabilityFlags,
+};
+#[cfg(feature = "debugger")]
 use qsc_data_structures::{
-    functors::FunctorApp,
-    language_features::LanguageFeatures,
     line_column::{Encoding, Range},
     span::Span,
-    target::TargetCapabilityFlags,
 };
+#[cfg(feature = "gpu")]
+use qsc_eval::gpu::{GpuContext, GpuSim};
+#[cfg(feature = "interpreter")]
 use qsc_eval::{
-    backend::{Backend, Chain as BackendChain, SparseSim},
+    backend::{Backend, Chain as BackendChain, Noisy, SparseSim},
+    noise::NoiseModel,
     output::Receiver,
-    val, Env, State, VariableInfo,
+    reuse::{ReuseAnalyzer, ReuseReport, ReuseWarning},
+    stabilizer::AutoSim,
+    trace::{TraceSim, TracedRun},
+    val, Env, IntrinsicPolicy, Quota,
 };
-use qsc_fir::fir::{self, ExecGraph, Global, PackageStoreLookup};
+#[cfg(feature = "debugger")]
+use qsc_eval::{State, VariableInfo};
+#[cfg(feature = "interpreter")]
+use qsc_fir::fir::{self, ExecGraph, Package, PackageId};
+#[cfg(feature = "debugger")]
 use qsc_fir::{
-    fir::{Block, BlockId, Expr, ExprId, Package, PackageId, Pat, PatId, Stmt, StmtId},
+    fir::{Block, BlockId, Expr, ExprId, Global, PackageStoreLookup, Pat, PatId, Stmt, StmtId},
     visit::{self, Visitor},
 };
+#[cfg(feature = "debugger")]
+use qsc_frontend::compile::{CompileUnit, Source};
+#[cfg(feature = "interpreter")]
 use qsc_frontend::{
-    compile::{CompileUnit, Dependencies, PackageStore, Source, SourceMap},
-    error::WithSource,
+    compile::{Dependencies, PackageStore, SourceMap},
     incremental::Increment,
 };
+#[cfg(feature = "interpreter")]
+use qsc_hir::{global, hir, ty};
+#[cfg(feature = "interpreter")]
+use qsc_linter::{HirLint, Lint, LintKind, LintLevel};
+#[cfg(feature = "interpreter")]
+use qsc_lowerer::{map_fir_package_to_hir, map_hir_package_to_fir};
+#[cfg(feature = "interpreter")]
+use qsc_partial_eval::ProgramEntry;
+#[cfg(feature = "interpreter")]
 use qsc_passes::{PackageType, PassContext};
+#[cfg(feature = "interpreter")]
+use qsc_rca::PackageStoreComputeProperties;
+#[cfg(feature = "debugger")]
 use rustc_hash::FxHashSet;
-use thiserror::Error;
+#[cfg(feature = "interpreter")]
+use std::rc::Rc;
 
 impl Error {
     #[must_use]
@@ -109,9 +148,58 @@ pub enum Error {
     #[error("partial evaluation error")]
     #[diagnostic(transparent)]
     PartialEvaluation(#[from] WithSource<qsc_partial_eval::Error>),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Codegen(#[from] qsc_codegen::qir::determinism::Error),
+    #[error("program is too large to explain its results")]
+    #[diagnostic(code("Qsc.Interpret.TooLargeToExplain"))]
+    #[diagnostic(help(
+        "results can only be explained for programs that use at most {} qubits at a time and have at most {} possible sequences of measurement outcomes",
+        qsc_eval::trace::MAX_QUBITS,
+        qsc_eval::trace::MAX_RUNS
+    ))]
+    TooLargeToExplain,
+    #[error("{0}")]
+    #[diagnostic(code("Qsc.Interpret.SimulatorUnavailable"))]
+    SimulatorUnavailable(String),
+}
+
+/// The settings of the simulations an interpreter runs, which can be given to another interpreter
+/// of the same program, including one on another thread, so that its runs are simulated the same way.
+#[cfg(feature = "interpreter")]
+#[derive(Clone, Debug, Default)]
+pub struct SimulationConfig {
+    /// The quantum seed, if any.
+    pub quantum_seed: Option<u64>,
+    /// The classical seed, if any.
+    pub classical_seed: Option<u64>,
+    /// The quota bounding each run.
+    pub quota: Quota,
+    /// The noise injected into each run that uses a fresh simulator.
+    pub noise_model: NoiseModel,
+    /// The memory, in bytes, that the quantum state of each simulation can use.
+    pub simulation_memory_budget: Option<usize>,
+    /// The simulator of each run that uses a fresh simulator.
+    pub simulator: SimulatorKind,
+}
+
+/// The simulator a program runs on.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SimulatorKind {
+    /// The sparse state-vector simulator, which is fastest for states with few nonzero amplitudes.
+    #[default]
+    Sparse,
+    /// The dense state-vector simulator on the GPU, which is fastest for large, highly entangled
+    /// states. It's only available in builds with the `gpu` feature.
+    Gpu,
+    /// The stabilizer simulator, which scales to thousands of qubits for programs made only of
+    /// Clifford operations, switching to the sparse simulator the first time a non-Clifford
+    /// operation is applied.
+    Auto,
 }
 
 /// A Q# interpreter.
+#[cfg(feature = "interpreter")]
 pub struct Interpreter {
     /// The incremental Q# compiler.
     compiler: Compiler,
@@ -142,12 +230,30 @@ pub struct Interpreter {
     /// The classical seed, if any. This needs to be passed to the evaluator for use in intrinsic
     /// calls that produce classical random numbers.
     classical_seed: Option<u64>,
+    /// The quota bounding each execution of the program.
+    quota: Quota,
+    /// The policy restricting the intrinsics each execution of the program can call.
+    intrinsic_policy: IntrinsicPolicy,
+    /// The noise injected into runs of the program that use a fresh simulator.
+    noise_model: NoiseModel,
+    /// The memory, in bytes, that the quantum state of each simulation can use.
+    simulation_memory_budget: Option<usize>,
+    /// The simulator that runs of the program use.
+    simulator: SimulatorKind,
+    /// The GPU device that runs of the program simulate on, if the GPU simulator is selected.
+    #[cfg(feature = "gpu")]
+    gpu: Option<Rc<GpuContext>>,
+    /// The issues in how qubits are reused that were found in the runs of the program so far, if
+    /// the analysis is enabled.
+    reuse_report: Option<ReuseReport>,
     /// The evaluator environment.
     env: Env,
 }
 
+#[cfg(feature = "interpreter")]
 pub type InterpretResult = std::result::Result<Value, Vec<Error>>;
 
+#[cfg(feature = "interpreter")]
 impl Interpreter {
     /// Creates a new incremental compiler, compiling the passed in sources.
     /// # Errors
@@ -243,17 +349,29 @@ impl Interpreter {
             })?;
         }
 
+        let lowerer = qsc_lowerer::Lowerer::new()
+            .with_debug(dbg)
+            .with_quantities(&fir_store);
+
         Ok(Self {
             compiler,
             lines: 0,
             capabilities,
             fir_store,
-            lowerer: qsc_lowerer::Lowerer::new().with_debug(dbg),
+            lowerer,
             expr_graph: None,
             env: Env::default(),
             sim: sim_circuit_backend(),
             quantum_seed: None,
             classical_seed: None,
+            quota: Quota::default(),
+            intrinsic_policy: IntrinsicPolicy::default(),
+            noise_model: NoiseModel::default(),
+            simulation_memory_budget: None,
+            simulator: SimulatorKind::default(),
+            #[cfg(feature = "gpu")]
+            gpu: None,
+            reuse_report: None,
             package,
             source_package: map_hir_package_to_fir(source_package_id),
         })
@@ -305,17 +423,27 @@ impl Interpreter {
             })?;
         }
 
+        let lowerer = qsc_lowerer::Lowerer::new().with_quantities(&fir_store);
+
         Ok(Self {
             compiler,
             lines: 0,
             capabilities,
             fir_store,
-            lowerer: qsc_lowerer::Lowerer::new(),
+            lowerer,
             expr_graph: None,
             env: Env::default(),
             sim: sim_circuit_backend(),
             quantum_seed: None,
             classical_seed: None,
+            quota: Quota::default(),
+            intrinsic_policy: IntrinsicPolicy::default(),
+            noise_model: NoiseModel::default(),
+            simulation_memory_budget: None,
+            simulator: SimulatorKind::default(),
+            #[cfg(feature = "gpu")]
+            gpu: None,
+            reuse_report: None,
             package,
             source_package: map_hir_package_to_fir(source_package_id),
         })
@@ -379,7 +507,7 @@ impl Interpreter {
             qsc_hir::hir::ItemKind::Callable(decl) => {
                 Some((decl.input.ty.clone(), decl.output.clone()))
             }
-            qsc_hir::hir::ItemKind::Ty(_, udt) => {
+            qsc_hir::hir::ItemKind::Ty(_, udt) if !udt.is_enum() => {
                 // We don't handle UDTs, so we return an error type that prevents later code from processing this item.
                 Some((udt.get_pure_ty(), ty::Ty::Err))
             }
@@ -396,6 +524,120 @@ impl Interpreter {
         self.classical_seed = seed;
     }
 
+    /// Sets the quota that bounds each subsequent execution of the program, such as the number of
+    /// steps it can take or how long it can run.
+    pub fn set_quota(&mut self, quota: Quota) {
+        self.quota = quota;
+    }
+
+    /// Sets the policy that restricts which intrinsics each subsequent execution of the program can call,
+    /// and how many messages and qubits it can use.
+    pub fn set_intrinsic_policy(&mut self, policy: IntrinsicPolicy) {
+        self.intrinsic_policy = policy;
+    }
+
+    /// Limits the memory, in bytes, that the quantum state of the simulator and of each subsequent
+    /// call to `run` and `invoke_with_noise` can use, or removes the limit if `None`. A gate that grows
+    /// the state beyond the budget stops execution with a `SimulationMemoryExceeded` error.
+    pub fn set_simulation_memory_budget(&mut self, budget: Option<usize>) {
+        self.simulation_memory_budget = budget;
+        self.sim.main.set_memory_budget(budget);
+    }
+
+    /// Selects the simulator of subsequent calls to `run` and `invoke_with_noise`. The GPU simulator
+    /// takes the Pauli noise they are given as the noise of gates that don't have their own noise in
+    /// the noise model, and doesn't support the memory budget of the sparse simulator. The automatic
+    /// simulator applies the memory budget once it switches to sparse simulation.
+    /// # Errors
+    /// Returns an error if the GPU simulator is selected but isn't part of this build, or if the
+    /// machine has no GPU device that supports it.
+    pub fn set_simulator(&mut self, kind: SimulatorKind) -> std::result::Result<(), String> {
+        match kind {
+            SimulatorKind::Sparse | SimulatorKind::Auto => {
+                #[cfg(feature = "gpu")]
+                {
+                    self.gpu = None;
+                }
+            }
+            #[cfg(feature = "gpu")]
+            SimulatorKind::Gpu => {
+                if self.gpu.is_none() {
+                    self.gpu = Some(Rc::new(GpuContext::new()?));
+                }
+            }
+            #[cfg(not(feature = "gpu"))]
+            SimulatorKind::Gpu => {
+                return Err("this build doesn't include the GPU simulator".to_string());
+            }
+        }
+        self.simulator = kind;
+        Ok(())
+    }
+
+    /// Sets the noise injected into the gates and measurements of subsequent calls to `run` and
+    /// `invoke_with_noise`, in addition to any Pauli noise they are given.
+    pub fn set_noise_model(&mut self, model: NoiseModel) {
+        self.noise_model = model;
+    }
+
+    /// The settings of the simulations this interpreter runs.
+    #[must_use]
+    pub fn simulation_config(&self) -> SimulationConfig {
+        SimulationConfig {
+            quantum_seed: self.quantum_seed,
+            classical_seed: self.classical_seed,
+            quota: self.quota,
+            noise_model: self.noise_model.clone(),
+            simulation_memory_budget: self.simulation_memory_budget,
+            simulator: self.simulator,
+        }
+    }
+
+    /// Applies the settings of the simulations of another interpreter, as returned by
+    /// `simulation_config`.
+    /// # Errors
+    /// Returns an error if the settings select a simulator that isn't available, as with
+    /// `set_simulator`.
+    pub fn set_simulation_config(
+        &mut self,
+        config: &SimulationConfig,
+    ) -> std::result::Result<(), Vec<Error>> {
+        self.set_simulator(config.simulator)
+            .map_err(|error| vec![Error::SimulatorUnavailable(error)])?;
+        self.set_quantum_seed(config.quantum_seed);
+        self.set_classical_seed(config.classical_seed);
+        self.set_quota(config.quota);
+        self.set_noise_model(config.noise_model.clone());
+        self.set_simulation_memory_budget(config.simulation_memory_budget);
+        Ok(())
+    }
+
+    /// Enables or disables the analysis of how subsequent calls to `run` and `invoke_with_noise`
+    /// reuse their qubits, which looks for qubits that are used again after a measurement without
+    /// being reset, and for qubits released in a non-zero state. Enabling it again discards the
+    /// issues found so far.
+    pub fn set_reuse_analysis(&mut self, enabled: bool) {
+        self.reuse_report = enabled.then(ReuseReport::default);
+    }
+
+    /// The issues in how qubits were reused, aggregated over the runs since the analysis was
+    /// enabled, or `None` if it isn't enabled.
+    #[must_use]
+    pub fn reuse_warnings(&self) -> Option<Vec<ReuseWarning>> {
+        self.reuse_report.as_ref().map(ReuseReport::warnings)
+    }
+
+    /// Sets the tape that records or replays the outcomes of measurements and random draws in
+    /// subsequent executions of the program, or stops taping outcomes if `None`.
+    pub fn set_tape(&mut self, tape: Option<Tape>) {
+        self.env.set_tape(tape);
+    }
+
+    /// Takes the tape set with `set_tape`, including any outcomes recorded on it so far.
+    pub fn take_tape(&mut self) -> Option<Tape> {
+        self.env.take_tape()
+    }
+
     pub fn check_source_lints(&self) -> Vec<Lint> {
         if let Some(compile_unit) = self
             .compiler
@@ -423,16 +665,21 @@ impl Interpreter {
     pub fn eval_entry(&mut self, receiver: &mut impl Receiver) -> InterpretResult {
         let graph = self.get_entry_exec_graph()?;
         self.expr_graph = Some(graph.clone());
-        eval(
+        let mut env = self.new_env();
+        let result = eval(
             self.source_package,
             self.classical_seed,
+            self.quota,
+            &self.intrinsic_policy,
             graph,
             self.compiler.package_store(),
             &self.fir_store,
-            &mut Env::default(),
+            &mut env,
             &mut self.sim,
             receiver,
-        )
+        );
+        self.env.set_tape(env.take_tape());
+        result
     }
 
     /// Executes the entry expression until the end of execution, using the given simulator backend
@@ -447,16 +694,29 @@ impl Interpreter {
         if self.quantum_seed.is_some() {
             sim.set_seed(self.quantum_seed);
         }
-        eval(
+        let mut env = self.new_env();
+        let result = eval(
             self.source_package,
             self.classical_seed,
+            self.quota,
+            &self.intrinsic_policy,
             graph,
             self.compiler.package_store(),
             &self.fir_store,
-            &mut Env::default(),
+            &mut env,
             sim,
             receiver,
-        )
+        );
+        self.env.set_tape(env.take_tape());
+        result
+    }
+
+    /// Creates a new instance of the environment for a run of the program, which takes over the tape
+    /// until it is handed back with `self.env.set_tape`.
+    fn new_env(&mut self) -> Env {
+        let mut env = Env::default();
+        env.set_tape(self.env.take_tape());
+        env
     }
 
     fn get_entry_exec_graph(&self) -> std::result::Result<ExecGraph, Vec<Error>> {
@@ -489,6 +749,41 @@ impl Interpreter {
         self.eval_increment(receiver, increment)
     }
 
+    /// Compiles the fragments like `eval_fragments`, but only evaluates them if their top-level
+    /// statements bind names, which later fragments and entry expressions can read. This rebuilds
+    /// the state of another interpreter that evaluated the same fragments without repeating the
+    /// side effects of the fragments that only declare items or run statements.
+    /// # Errors
+    /// If the parsing of the fragments fails, an error is returned.
+    /// If the compilation of the fragments fails, an error is returned.
+    /// If there is a runtime error when interpreting the fragments, an error is returned.
+    pub fn replay_fragments(
+        &mut self,
+        receiver: &mut impl Receiver,
+        fragments: &str,
+    ) -> InterpretResult {
+        let label = self.next_line_label();
+
+        let mut increment = self
+            .compiler
+            .compile_fragments_fail_fast(&label, fragments)
+            .map_err(into_errors)?;
+        increment.clear_entry();
+
+        let binds_names = increment.hir.stmts.iter().any(|stmt| {
+            matches!(
+                stmt.kind,
+                hir::StmtKind::Local(..) | hir::StmtKind::Qubit(..)
+            )
+        });
+        if binds_names {
+            return self.eval_increment(receiver, increment);
+        }
+        self.lower(&increment)?;
+        self.compiler.update(increment);
+        Ok(Value::unit())
+    }
+
     /// It is assumed that if there were any parse errors on the fragments, the caller would have
     /// already handled them. This function is intended to be used in cases where the caller wants
     /// to handle the parse errors themselves.
@@ -530,6 +825,8 @@ impl Interpreter {
         eval(
             self.package,
             self.classical_seed,
+            self.quota,
+            &self.intrinsic_policy,
             graph,
             self.compiler.package_store(),
             &self.fir_store,
@@ -549,6 +846,8 @@ impl Interpreter {
         qsc_eval::invoke(
             self.package,
             self.classical_seed,
+            self.quota,
+            &self.intrinsic_policy,
             &self.fir_store,
             &mut self.env,
             &mut self.sim,
@@ -575,16 +874,120 @@ impl Interpreter {
         args: Value,
         noise: Option<PauliNoise>,
     ) -> InterpretResult {
+        #[cfg(feature = "gpu")]
+        if let Some(mut sim) = self.gpu_sim(noise) {
+            return self.invoke_shot_with_sim(&mut sim, receiver, callable, args);
+        }
+        if self.simulator == SimulatorKind::Auto {
+            let mut sim = self.auto_sim(noise);
+            return if self.noise_model.is_noiseless() {
+                self.invoke_shot_with_sim(&mut sim, receiver, callable, args)
+            } else {
+                let mut sim = Noisy::new(sim, self.noise_model.clone());
+                self.invoke_shot_with_sim(&mut sim, receiver, callable, args)
+            };
+        }
         let mut sim = match noise {
             Some(noise) => SparseSim::new_with_noise(&noise),
             None => SparseSim::new(),
         };
+        sim.set_memory_budget(self.simulation_memory_budget);
+        if self.noise_model.is_noiseless() {
+            self.invoke_shot_with_sim(&mut sim, receiver, callable, args)
+        } else {
+            let mut sim = Noisy::new(sim, self.noise_model.clone());
+            self.invoke_shot_with_sim(&mut sim, receiver, callable, args)
+        }
+    }
+
+    /// Invokes the callable on the given simulator, analyzing how it reuses qubits if the
+    /// analysis is enabled.
+    fn invoke_shot_with_sim(
+        &mut self,
+        sim: &mut impl Backend<ResultType = impl Into<val::Result>>,
+        receiver: &mut impl Receiver,
+        callable: Value,
+        args: Value,
+    ) -> InterpretResult {
+        if self.reuse_report.is_none() {
+            return self.invoke_in_env_with_sim(sim, receiver, callable, args);
+        }
+        let mut analyzer = ReuseAnalyzer::new(sim);
+        let result = self.invoke_in_env_with_sim(&mut analyzer, receiver, callable, args);
+        self.add_reuse_findings(analyzer);
+        result
+    }
+
+    fn add_reuse_findings(&mut self, analyzer: ReuseAnalyzer<'_, impl Backend>) {
+        if let Some(report) = &mut self.reuse_report {
+            report.add_shot(analyzer.into_findings());
+        }
+    }
+
+    /// Runs a parameter sweep: invokes the given callable with each of the given argument values
+    /// for the given number of shots, each on a new instance of the environment and simulator.
+    /// The callable is compiled once and shared by every run, so only evaluation is repeated.
+    /// The results are returned in the order of the argument values, each paired with the
+    /// arguments that produced it.
+    pub fn run_sweep(
+        &mut self,
+        receiver: &mut impl Receiver,
+        callable: &Value,
+        args: impl IntoIterator<Item = Value>,
+        shots: usize,
+        noise: Option<PauliNoise>,
+    ) -> Vec<SweepPoint> {
+        args.into_iter()
+            .map(|args| {
+                let results = (0..shots)
+                    .map(|_| {
+                        self.invoke_with_noise(receiver, callable.clone(), args.clone(), noise)
+                    })
+                    .collect();
+                SweepPoint { args, results }
+            })
+            .collect()
+    }
+
+    /// A fresh stabilizer simulator that switches to sparse simulation when needed, with the given
+    /// Pauli noise and the memory budget.
+    fn auto_sim(&self, noise: Option<PauliNoise>) -> AutoSim {
+        let mut sim = match noise {
+            Some(noise) => AutoSim::new_with_noise(&noise),
+            None => AutoSim::new(),
+        };
+        sim.set_memory_budget(self.simulation_memory_budget);
+        sim
+    }
+
+    /// A fresh GPU simulator with the noise model and the given Pauli noise, if the GPU simulator is
+    /// selected.
+    #[cfg(feature = "gpu")]
+    fn gpu_sim(&self, noise: Option<PauliNoise>) -> Option<Noisy<GpuSim>> {
+        let context = self.gpu.clone()?;
+        let mut model = self.noise_model.clone();
+        if let Some(noise) = noise {
+            model.gate = noise;
+        }
+        Some(Noisy::new(GpuSim::new(context), model))
+    }
+
+    /// Invokes the callable on the given simulator in the current environment.
+    fn invoke_in_env_with_sim(
+        &mut self,
+        sim: &mut impl Backend<ResultType = impl Into<val::Result>>,
+        receiver: &mut impl Receiver,
+        callable: Value,
+        args: Value,
+    ) -> InterpretResult {
         qsc_eval::invoke(
             self.package,
             self.classical_seed,
+            self.quota,
+            &self.intrinsic_policy,
             &self.fir_store,
             &mut self.env,
-            &mut sim,
+            sim,
             receiver,
             callable,
             args,
@@ -607,11 +1010,182 @@ impl Interpreter {
         expr: Option<&str>,
         noise: Option<PauliNoise>,
     ) -> InterpretResult {
+        if let Some(expr) = expr {
+            self.set_entry_expr(expr)?;
+        }
+        self.run_shot(receiver, noise, false)
+    }
+
+    /// Runs the given entry expression, or the entry point if none is given, for the given number
+    /// of shots, each on a new instance of the environment and simulator. The shots run one at a
+    /// time as the returned iterator advances, so that callers can show the results as they arrive
+    /// and stop early by dropping the iterator.
+    /// # Errors
+    /// Returns a vector of errors if compiling the entry expression fails or if there is no entry
+    /// point.
+    pub fn run_shots<'a, R: Receiver>(
+        &'a mut self,
+        receiver: &'a mut R,
+        expr: Option<&str>,
+        noise: Option<PauliNoise>,
+        shots: usize,
+    ) -> std::result::Result<Shots<'a, R>, Vec<Error>> {
+        let entry_point = if let Some(expr) = expr {
+            self.set_entry_expr(expr)?;
+            false
+        } else {
+            self.get_entry_exec_graph()?;
+            true
+        };
+        Ok(Shots {
+            interpreter: self,
+            receiver,
+            noise,
+            entry_point,
+            remaining: shots,
+        })
+    }
+
+    /// Runs one shot of the entry expression set with `set_entry_expr`, or of the entry point if
+    /// `entry_point` is true, on a new simulator with the given noise and the noise model, memory
+    /// budget and kind of simulator of `run`. The simulator is seeded with the given seed in place
+    /// of the quantum seed, if one is given.
+    pub fn run_seeded_shot(
+        &mut self,
+        receiver: &mut impl Receiver,
+        entry_point: bool,
+        noise: Option<PauliNoise>,
+        seed: Option<u64>,
+    ) -> InterpretResult {
+        let quantum_seed = self.quantum_seed;
+        if seed.is_some() {
+            self.quantum_seed = seed;
+        }
+        let result = self.run_shot(receiver, noise, entry_point);
+        self.quantum_seed = quantum_seed;
+        result
+    }
+
+    /// Runs one shot of the last entry expression, or of the entry point, on a new simulator.
+    fn run_shot(
+        &mut self,
+        receiver: &mut impl Receiver,
+        noise: Option<PauliNoise>,
+        entry_point: bool,
+    ) -> InterpretResult {
+        #[cfg(feature = "gpu")]
+        if let Some(mut sim) = self.gpu_sim(noise) {
+            return self.run_shot_with_sim(&mut sim, receiver, entry_point);
+        }
+        if self.simulator == SimulatorKind::Auto {
+            let mut sim = self.auto_sim(noise);
+            return if self.noise_model.is_noiseless() {
+                self.run_shot_with_sim(&mut sim, receiver, entry_point)
+            } else {
+                let mut sim = Noisy::new(sim, self.noise_model.clone());
+                self.run_shot_with_sim(&mut sim, receiver, entry_point)
+            };
+        }
         let mut sim = match noise {
             Some(noise) => SparseSim::new_with_noise(&noise),
             None => SparseSim::new(),
         };
-        self.run_with_sim(&mut sim, receiver, expr)
+        sim.set_memory_budget(self.simulation_memory_budget);
+        if self.noise_model.is_noiseless() {
+            self.run_shot_with_sim(&mut sim, receiver, entry_point)
+        } else {
+            let mut sim = Noisy::new(sim, self.noise_model.clone());
+            self.run_shot_with_sim(&mut sim, receiver, entry_point)
+        }
+    }
+
+    /// Runs one shot on the given simulator, analyzing how it reuses qubits if the analysis is
+    /// enabled.
+    fn run_shot_with_sim(
+        &mut self,
+        sim: &mut impl Backend<ResultType = impl Into<val::Result>>,
+        receiver: &mut impl Receiver,
+        entry_point: bool,
+    ) -> InterpretResult {
+        if self.reuse_report.is_none() {
+            return self.run_shot_on(sim, receiver, entry_point);
+        }
+        let mut analyzer = ReuseAnalyzer::new(sim);
+        let result = self.run_shot_on(&mut analyzer, receiver, entry_point);
+        self.add_reuse_findings(analyzer);
+        result
+    }
+
+    fn run_shot_on(
+        &mut self,
+        sim: &mut impl Backend<ResultType = impl Into<val::Result>>,
+        receiver: &mut impl Receiver,
+        entry_point: bool,
+    ) -> InterpretResult {
+        if entry_point {
+            self.eval_entry_with_sim(sim, receiver)
+        } else {
+            self.run_with_sim(sim, receiver, None)
+        }
+    }
+
+    /// Explains how the entry expression, or the entry point if no entry expression is given, comes to
+    /// produce the given result, which is compared against the display form of the returned values.
+    /// The program is run once for every possible sequence of measurement outcomes, and the runs are
+    /// arranged into a tree of branch decisions that shows how much each branch contributes to the
+    /// probability of the result.
+    ///
+    /// Classical random numbers are drawn with the classical seed, or a fixed seed if none is set, so that
+    /// the runs agree with each other.
+    /// # Errors
+    /// Returns a vector of errors if compiling the entry expression fails, if any run fails, or if the
+    /// program is too large to explain.
+    pub fn explain_result(
+        &mut self,
+        entry_expr: Option<&str>,
+        result: &str,
+    ) -> std::result::Result<AttributionNode, Vec<Error>> {
+        let (package_id, graph) = if let Some(entry_expr) = entry_expr {
+            (self.package, self.compile_entry_expr(entry_expr)?.0)
+        } else {
+            (self.source_package, self.get_entry_exec_graph()?)
+        };
+        let classical_seed = Some(self.classical_seed.unwrap_or_default());
+
+        let mut runs = Vec::new();
+        let mut pending = vec![Vec::new()];
+        while let Some(decisions) = pending.pop() {
+            if runs.len() == qsc_eval::trace::MAX_RUNS {
+                return Err(vec![Error::TooLargeToExplain]);
+            }
+
+            let mut sim = TraceSim::new(decisions);
+            let mut sink = std::io::sink();
+            let mut out = GenericReceiver::new(&mut sink);
+            let value = eval(
+                package_id,
+                classical_seed,
+                self.quota,
+                &self.intrinsic_policy,
+                graph.clone(),
+                self.compiler.package_store(),
+                &self.fir_store,
+                &mut Env::default(),
+                &mut sim,
+                &mut out,
+            );
+            if sim.exceeded_qubit_limit() {
+                return Err(vec![Error::TooLargeToExplain]);
+            }
+
+            pending.extend(sim.unexplored_decisions());
+            runs.push(TracedRun {
+                measurements: sim.measurements().to_vec(),
+                result: value?.to_string(),
+            });
+        }
+
+        Ok(AttributionNode::new(&runs, result))
     }
 
     /// Gets the current quantum state of the simulator.
@@ -619,6 +1193,33 @@ impl Interpreter {
         self.sim.capture_quantum_state()
     }
 
+    /// Estimates the memory held by each compiled package and by the simulator.
+    /// The runtime capabilities analysis tables aren't kept between evaluations, so they are
+    /// computed again for the report when the target capabilities aren't unrestricted.
+    pub fn memory_usage(&mut self) -> MemoryUsage {
+        let compute_properties = (self.capabilities != TargetCapabilityFlags::all())
+            .then(|| qsc_rca::Analyzer::init(&self.fir_store).analyze_all());
+        let packages = self
+            .compiler
+            .package_store()
+            .iter()
+            .map(|(id, unit)| {
+                let fir_id = map_hir_package_to_fir(id);
+                let mut usage = memory::unit_memory_usage(id, unit);
+                usage.fir = memory::fir_size(self.fir_store.get(fir_id));
+                if let Some(compute_properties) = &compute_properties {
+                    usage.rca = memory::rca_size(compute_properties.get(fir_id));
+                }
+                usage
+            })
+            .collect();
+        let (state, qubit_count) = self.sim.capture_quantum_state();
+        MemoryUsage {
+            packages,
+            simulator: memory::quantum_state_size(&state, qubit_count),
+        }
+    }
+
     /// Get the current circuit representation of the program.
     pub fn get_circuit(&self) -> Circuit {
         self.sim.chained.snapshot()
@@ -627,30 +1228,7 @@ impl Interpreter {
     /// Performs QIR codegen using the given entry expression on a new instance of the environment
     /// and simulator but using the current compilation.
     pub fn qirgen(&mut self, expr: &str) -> std::result::Result<String, Vec<Error>> {
-        if self.capabilities == TargetCapabilityFlags::all() {
-            return Err(vec![Error::UnsupportedRuntimeCapabilities]);
-        }
-
-        // Compile the expression. This operation will set the expression as
-        // the entry-point in the FIR store.
-        let (graph, compute_properties) = self.compile_entry_expr(expr)?;
-
-        let Some(compute_properties) = compute_properties else {
-            // This can only happen if capability analysis was not run. This would be a bug
-            // and we are in a bad state and can't proceed.
-            panic!("internal error: compute properties not set after lowering entry expression");
-        };
-        let package = self.fir_store.get(self.package);
-        let entry = ProgramEntry {
-            exec_graph: graph,
-            expr: (
-                self.package,
-                package
-                    .entry
-                    .expect("package must have an entry expression"),
-            )
-                .into(),
-        };
+        let (entry, compute_properties) = self.codegen_entry(expr)?;
         // Generate QIR
         fir_to_qir(
             &self.fir_store,
@@ -658,21 +1236,35 @@ impl Interpreter {
             Some(compute_properties),
             &entry,
         )
-        .map_err(|e| {
-            let hir_package_id = match e.span() {
-                Some(span) => span.package,
-                None => map_fir_package_to_hir(self.package),
-            };
-            let source_package = self
-                .compiler
-                .package_store()
-                .get(hir_package_id)
-                .expect("package should exist in the package store");
-            vec![Error::PartialEvaluation(WithSource::from_map(
-                &source_package.sources,
-                e,
-            ))]
-        })
+        .map_err(|e| self.partial_eval_errors(e))
+    }
+
+    /// Performs QIR codegen using the given entry expression on a new instance of the environment
+    /// and simulator but using the current compilation, and writes the QIR as LLVM bitcode.
+    pub fn qirgen_bitcode(&mut self, expr: &str) -> std::result::Result<Vec<u8>, Vec<Error>> {
+        let (entry, compute_properties) = self.codegen_entry(expr)?;
+        fir_to_qir_bitcode(
+            &self.fir_store,
+            self.capabilities,
+            Some(compute_properties),
+            &entry,
+            QirOptions::default(),
+        )
+        .map_err(|e| self.partial_eval_errors(e))
+    }
+
+    /// Computes statistics about the program generated for the target using the given entry expression,
+    /// such as the number of calls to each gate and the estimated circuit depth.
+    pub fn program_stats(&mut self, expr: &str) -> std::result::Result<ProgramStats, Vec<Error>> {
+        let (entry, compute_properties) = self.codegen_entry(expr)?;
+        let (_, program) = fir_to_rir(
+            &self.fir_store,
+            self.capabilities,
+            Some(compute_properties),
+            &entry,
+        )
+        .map_err(|e| self.partial_eval_errors(e))?;
+        Ok(program.stats())
     }
 
     /// Performs QIR codegen using the given callable with the given arguments on a new instance of the environment
@@ -697,21 +1289,55 @@ impl Interpreter {
             *store_item_id,
             args,
         )
-        .map_err(|e| {
-            let hir_package_id = match e.span() {
-                Some(span) => span.package,
-                None => map_fir_package_to_hir(self.package),
-            };
-            let source_package = self
-                .compiler
-                .package_store()
-                .get(hir_package_id)
-                .expect("package should exist in the package store");
-            vec![Error::PartialEvaluation(WithSource::from_map(
-                &source_package.sources,
-                e,
-            ))]
-        })
+        .map_err(|e| self.partial_eval_errors(e))
+    }
+
+    /// Compiles the entry expression for code generation, which is only supported for restricted targets.
+    fn codegen_entry(
+        &mut self,
+        expr: &str,
+    ) -> std::result::Result<(ProgramEntry, PackageStoreComputeProperties), Vec<Error>> {
+        if self.capabilities == TargetCapabilityFlags::all() {
+            return Err(vec![Error::UnsupportedRuntimeCapabilities]);
+        }
+
+        // Compile the expression. This operation will set the expression as
+        // the entry-point in the FIR store.
+        let (graph, compute_properties) = self.compile_entry_expr(expr)?;
+
+        let Some(compute_properties) = compute_properties else {
+            // This can only happen if capability analysis was not run. This would be a bug
+            // and we are in a bad state and can't proceed.
+            panic!("internal error: compute properties not set after lowering entry expression");
+        };
+        let package = self.fir_store.get(self.package);
+        let entry = ProgramEntry {
+            exec_graph: graph,
+            expr: (
+                self.package,
+                package
+                    .entry
+                    .expect("package must have an entry expression"),
+            )
+                .into(),
+        };
+        Ok((entry, compute_properties))
+    }
+
+    fn partial_eval_errors(&self, error: qsc_partial_eval::Error) -> Vec<Error> {
+        let hir_package_id = match error.span() {
+            Some(span) => span.package,
+            None => map_fir_package_to_hir(self.package),
+        };
+        let source_package = self
+            .compiler
+            .package_store()
+            .get(hir_package_id)
+            .expect("package should exist in the package store");
+        vec![Error::PartialEvaluation(WithSource::from_map(
+            &source_package.sources,
+            error,
+        ))]
     }
 
     /// Generates a circuit representation for the program.
@@ -745,6 +1371,7 @@ impl Interpreter {
 
         let circuit = if simulate {
             let mut sim = sim_circuit_backend();
+            sim.main.set_memory_budget(self.simulation_memory_budget);
 
             match invoke_params {
                 Some((callable, args)) => {
@@ -806,16 +1433,21 @@ impl Interpreter {
             sim.set_seed(self.quantum_seed);
         }
 
-        eval(
+        let mut env = self.new_env();
+        let result = eval(
             self.package,
             self.classical_seed,
+            self.quota,
+            &self.intrinsic_policy,
             graph,
             self.compiler.package_store(),
             &self.fir_store,
-            &mut Env::default(),
+            &mut env,
             sim,
             receiver,
-        )
+        );
+        self.env.set_tape(env.take_tape());
+        result
     }
 
     fn run_with_sim_no_output(
@@ -842,6 +1474,8 @@ impl Interpreter {
         eval(
             package_id,
             self.classical_seed,
+            self.quota,
+            &self.intrinsic_policy,
             graph,
             self.compiler.package_store(),
             &self.fir_store,
@@ -860,17 +1494,21 @@ impl Interpreter {
         callable: Value,
         args: Value,
     ) -> InterpretResult {
-        qsc_eval::invoke(
+        let mut env = self.new_env();
+        let result = qsc_eval::invoke(
             self.package,
             self.classical_seed,
+            self.quota,
+            &self.intrinsic_policy,
             &self.fir_store,
-            &mut Env::default(),
+            &mut env,
             sim,
             receiver,
             callable,
             args,
-        )
-        .map_err(|(error, call_stack)| {
+        );
+        self.env.set_tape(env.take_tape());
+        result.map_err(|(error, call_stack)| {
             eval_error(
                 self.compiler.package_store(),
                 &self.fir_store,
@@ -1000,6 +1638,50 @@ impl Interpreter {
     }
 }
 
+/// The results of one parameter set of a sweep.
+#[cfg(feature = "interpreter")]
+#[derive(Debug)]
+pub struct SweepPoint {
+    /// The arguments the callable was invoked with.
+    pub args: Value,
+    /// The result of each shot with these arguments.
+    pub results: Vec<InterpretResult>,
+}
+
+/// The shots of a run, which are evaluated one at a time as the iterator advances.
+#[cfg(feature = "interpreter")]
+pub struct Shots<'a, R: Receiver> {
+    interpreter: &'a mut Interpreter,
+    receiver: &'a mut R,
+    noise: Option<PauliNoise>,
+    entry_point: bool,
+    remaining: usize,
+}
+
+#[cfg(feature = "interpreter")]
+impl<R: Receiver> Iterator for Shots<'_, R> {
+    type Item = InterpretResult;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        Some(
+            self.interpreter
+                .run_shot(self.receiver, self.noise, self.entry_point),
+        )
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+#[cfg(feature = "interpreter")]
+impl<R: Receiver> ExactSizeIterator for Shots<'_, R> {}
+
+#[cfg(feature = "interpreter")]
 fn sim_circuit_backend() -> BackendChain<SparseSim, CircuitBuilder> {
     BackendChain::new(
         SparseSim::new(),
@@ -1018,6 +1700,7 @@ fn sim_circuit_backend() -> BackendChain<SparseSim, CircuitBuilder> {
 }
 
 /// Describes the entry point for circuit generation.
+#[cfg(feature = "interpreter")]
 pub enum CircuitEntryPoint {
     /// An operation. This must be a callable name or a lambda
     /// expression that only takes qubits as arguments.
@@ -1033,6 +1716,7 @@ pub enum CircuitEntryPoint {
 
 /// A debugger that enables step-by-step evaluation of code
 /// and inspecting state in the interpreter.
+#[cfg(feature = "debugger")]
 pub struct Debugger {
     interpreter: Interpreter,
     /// The encoding (utf-8 or utf-16) used for character offsets
@@ -1040,8 +1724,11 @@ pub struct Debugger {
     position_encoding: Encoding,
     /// The current state of the evaluator.
     state: State,
+    /// The quota bounding the execution, which is kept when the state is replaced.
+    quota: Quota,
 }
 
+#[cfg(feature = "debugger")]
 impl Debugger {
     pub fn new(
         sources: SourceMap,
@@ -1066,9 +1753,16 @@ impl Debugger {
             interpreter,
             position_encoding,
             state: State::new(source_package_id, entry_exec_graph, None),
+            quota: Quota::default(),
         })
     }
 
+    /// Sets the quota that bounds the rest of the execution, restarting the count of steps and the clock.
+    pub fn set_quota(&mut self, quota: Quota) {
+        self.quota = quota;
+        self.state.set_quota(quota);
+    }
+
     /// Resumes execution with specified `StepAction`.
     /// # Errors
     /// Returns a vector of errors if evaluating the entry point fails.
@@ -1133,10 +1827,46 @@ impl Debugger {
         self.interpreter.sim.capture_quantum_state()
     }
 
+    /// Encodes a checkpoint of the paused program, including its variables, its position and the
+    /// quantum state, which a debugger for the same program can `resume`, such as one created in
+    /// a new process after a hosted notebook restarts.
+    pub fn checkpoint(&mut self) -> Vec<u8> {
+        self.state
+            .checkpoint(&self.interpreter.env, &mut self.interpreter.sim)
+    }
+
+    /// Continues the program from a checkpoint encoded with `checkpoint`. The debugger must not
+    /// have started running the program yet, since the checkpoint replaces its state.
+    /// # Errors
+    /// Returns an error if the bytes are not a checkpoint or the quantum state can't be restored.
+    pub fn resume(&mut self, checkpoint: &[u8]) -> std::result::Result<(), ResumeError> {
+        self.state = State::resume(
+            checkpoint,
+            &mut self.interpreter.env,
+            &mut self.interpreter.sim,
+        )?;
+        self.state.set_quota(self.quota);
+        Ok(())
+    }
+
     pub fn circuit(&self) -> Circuit {
         self.interpreter.get_circuit()
     }
 
+    pub fn memory_usage(&mut self) -> MemoryUsage {
+        self.interpreter.memory_usage()
+    }
+
+    /// Sets the tape that records or replays the outcomes of measurements and random draws while
+    /// debugging, such as a tape recorded on a failing run of the program.
+    pub fn set_tape(&mut self, tape: Option<Tape>) {
+        self.interpreter.set_tape(tape);
+    }
+
+    pub fn take_tape(&mut self) -> Option<Tape> {
+        self.interpreter.take_tape()
+    }
+
     #[must_use]
     pub fn get_breakpoints(&self, path: &str) -> Vec<BreakpointSpan> {
         let unit = self.source_package();
@@ -1183,10 +1913,13 @@ impl Debugger {
 }
 
 /// Wrapper function for `qsc_eval::eval` that handles error conversion.
+#[cfg(feature = "interpreter")]
 #[allow(clippy::too_many_arguments)]
 fn eval(
     package: PackageId,
     classical_seed: Option<u64>,
+    quota: Quota,
+    policy: &IntrinsicPolicy,
     exec_graph: ExecGraph,
     package_store: &PackageStore,
     fir_store: &fir::PackageStore,
@@ -1197,6 +1930,8 @@ fn eval(
     qsc_eval::eval(
         package,
         classical_seed,
+        quota,
+        policy,
         exec_graph,
         fir_store,
         env,
@@ -1207,6 +1942,7 @@ fn eval(
 }
 
 /// Represents a stack frame for debugging.
+#[cfg(feature = "debugger")]
 pub struct StackFrame {
     /// The name of the callable.
     pub name: String,
@@ -1216,6 +1952,7 @@ pub struct StackFrame {
     pub location: Location,
 }
 
+#[cfg(feature = "debugger")]
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct BreakpointSpan {
     /// The id of the statement representing the breakpoint location.
@@ -1224,6 +1961,7 @@ pub struct BreakpointSpan {
     pub range: Range,
 }
 
+#[cfg(feature = "debugger")]
 struct BreakpointCollector<'a> {
     statements: FxHashSet<BreakpointSpan>,
     sources: &'a SourceMap,
@@ -1232,6 +1970,7 @@ struct BreakpointCollector<'a> {
     position_encoding: Encoding,
 }
 
+#[cfg(feature = "debugger")]
 impl<'a> BreakpointCollector<'a> {
     fn new(
         sources: &'a SourceMap,
@@ -1269,6 +2008,7 @@ impl<'a> BreakpointCollector<'a> {
     }
 }
 
+#[cfg(feature = "debugger")]
 impl<'a> Visitor<'a> for BreakpointCollector<'a> {
     fn visit_stmt(&mut self, stmt: StmtId) {
         let stmt_res = self.get_stmt(stmt);
@@ -1309,6 +2049,7 @@ impl<'a> Visitor<'a> for BreakpointCollector<'a> {
     }
 }
 
+#[cfg(feature = "interpreter")]
 fn eval_error(
     package_store: &PackageStore,
     fir_store: &fir::PackageStore,
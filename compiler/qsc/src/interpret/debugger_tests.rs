@@ -4,7 +4,7 @@
 use crate::interpret::Debugger;
 use crate::line_column::Encoding;
 use qsc_data_structures::language_features::LanguageFeatures;
-use qsc_eval::{output::CursorReceiver, StepAction, StepResult};
+use qsc_eval::{output::CursorReceiver, Quota, StepAction, StepResult};
 use qsc_fir::fir::StmtId;
 use qsc_frontend::compile::SourceMap;
 use std::io::Cursor;
@@ -233,5 +233,88 @@ mod given_debugger {
             expect_return(debugger, expected);
             Ok(())
         }
+
+        #[test]
+        fn resume_from_checkpoint_works() -> Result<(), Vec<crate::interpret::Error>> {
+            let new_debugger = || {
+                let sources = SourceMap::new([("test".into(), STEPPING_SOURCE.into())], None);
+                let (std_id, store) =
+                    crate::compile::package_store_with_stdlib(TargetCapabilityFlags::all());
+                Debugger::new(
+                    sources,
+                    TargetCapabilityFlags::all(),
+                    Encoding::Utf8,
+                    LanguageFeatures::default(),
+                    store,
+                    &[(std_id, None)],
+                )
+            };
+            let mut debugger = new_debugger()?;
+            let ids = get_breakpoint_ids(&debugger, "test");
+            let expected_id = ids[0];
+            expect_bp(&mut debugger, &ids, expected_id);
+            expect_in(&mut debugger);
+            expect_next(&mut debugger);
+            expect_next(&mut debugger);
+            expect_in(&mut debugger);
+            let checkpoint = debugger.checkpoint();
+
+            let mut resumed = new_debugger()?;
+            resumed
+                .resume(&checkpoint)
+                .expect("checkpoint should resume");
+            let locals = resumed
+                .get_locals()
+                .iter()
+                .map(|v| format!("{}: {}", v.name, v.value))
+                .collect::<Vec<_>>();
+            assert_eq!(locals, ["m: 10", "n: 20"]);
+            expect_next(&mut resumed);
+            expect_next(&mut resumed);
+            expect_out(&mut resumed);
+            expect_out(&mut resumed);
+            expect_next(&mut resumed);
+            expect_return(resumed, "42");
+            Ok(())
+        }
+
+        #[test]
+        fn exceeding_call_depth_fails() -> Result<(), Vec<crate::interpret::Error>> {
+            let source = "
+                namespace Test {
+                    @EntryPoint()
+                    operation Main() : Int { Down(10) }
+                    function Down(n : Int) : Int { if n == 0 { 0 } else { 1 + Down(n - 1) } }
+                }";
+            let sources = SourceMap::new([("test".into(), source.into())], None);
+            let (std_id, store) =
+                crate::compile::package_store_with_stdlib(TargetCapabilityFlags::all());
+            let mut debugger = Debugger::new(
+                sources,
+                TargetCapabilityFlags::all(),
+                Encoding::Utf8,
+                LanguageFeatures::default(),
+                store,
+                &[(std_id, None)],
+            )?;
+            debugger.set_quota(Quota {
+                max_call_depth: Some(3),
+                ..Quota::default()
+            });
+            let errors = step_next(&mut debugger, &[])
+                .0
+                .expect_err("debugger should stop at the call depth limit");
+            let crate::interpret::Error::Eval(error) = &errors[0] else {
+                panic!("expected a runtime error, got {:?}", errors[0]);
+            };
+            assert!(
+                matches!(
+                    error.error().error(),
+                    qsc_eval::Error::RecursionLimitExceeded(3, _)
+                ),
+                "{error:?}"
+            );
+            Ok(())
+        }
     }
 }
@@ -64,6 +64,15 @@ mod given_interpreter {
     mod without_sources {
         use expect_test::expect;
         use indoc::indoc;
+        use qsc_eval::{
+            noise::{NoiseModel, PauliNoise},
+            tape::{Outcome, Tape},
+            val, IntrinsicPolicy, Quota,
+        };
+        use std::{
+            sync::atomic::{AtomicU64, Ordering},
+            time::Duration,
+        };
 
         use super::*;
 
@@ -93,8 +102,6 @@ mod given_interpreter {
                     &expect![[r#"
                         name error: `Message` not found
                            [line_0] [Message]
-                        type error: insufficient type information to infer type
-                           [line_0] [Message("_")]
                     "#]],
                 );
             }
@@ -177,8 +184,6 @@ mod given_interpreter {
                 &expect![[r#"
                     name error: `x` not found
                        [line_0] [x]
-                    type error: insufficient type information to infer type
-                       [line_0] [y]
                 "#]],
             );
 
@@ -399,6 +404,369 @@ mod given_interpreter {
             );
         }
 
+        #[test]
+        fn quantities_are_erased_to_their_base_type() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(
+                &mut interpreter,
+                "Std.Units.Radians(1.0) + Std.Units.ScaledAngle(0.25, Std.Units.Radians(2.0))",
+            );
+            is_only_value(&result, &output, &Value::Double(1.5));
+        }
+
+        #[test]
+        fn quantities_of_different_types_cannot_be_mixed() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(
+                &mut interpreter,
+                "Std.Units.Radians(1.0) + Std.Units.Microseconds(2.0)",
+            );
+            is_only_error(
+                &result,
+                &output,
+                &expect![[r#"
+                    type error: expected Radians, found Microseconds
+                       [line_0] [Std.Units.Microseconds(2.0)]
+                "#]],
+            );
+        }
+
+        #[test]
+        fn quantities_cannot_be_multiplied() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(
+                &mut interpreter,
+                "Std.Units.Radians(1.0) * Std.Units.Radians(2.0)",
+            );
+            is_only_error(
+                &result,
+                &output,
+                &expect![[r#"
+                    type error: Type Radians cannot be used in multiplication
+                       [line_0] [Std.Units.Radians(1.0)]
+                "#]],
+            );
+        }
+
+        #[test]
+        fn udt_operators_call_their_functions() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(
+                &mut interpreter,
+                indoc! {"
+                    struct Pair { X : Int, Y : Int }
+                    function PlusPair(a : Pair, b : Pair) : Pair { new Pair { X = a.X + b.X, Y = a.Y + b.Y } }
+                    function NegationPair(a : Pair) : Pair { new Pair { X = -a.X, Y = -a.Y } }
+                    function ComparePair(a : Pair, b : Pair) : Int { (a.X + a.Y) - (b.X + b.Y) }
+                "},
+            );
+            is_only_value(&result, &output, &Value::unit());
+            let (result, output) = line(
+                &mut interpreter,
+                indoc! {"
+                    mutable p = new Pair { X = 1, Y = 2 };
+                    set p += -p + new Pair { X = 3, Y = 4 };
+                    (p.X, p.Y, p < new Pair { X = 0, Y = 0 }, p == new Pair { X = 4, Y = 3 })
+                "},
+            );
+            is_only_value(
+                &result,
+                &output,
+                &Value::Tuple(
+                    vec![
+                        Value::Int(3),
+                        Value::Int(4),
+                        Value::Bool(false),
+                        Value::Bool(true),
+                    ]
+                    .into(),
+                ),
+            );
+        }
+
+        #[test]
+        fn udt_operator_without_function_is_an_error() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(
+                &mut interpreter,
+                indoc! {"
+                    struct Pair { X : Int, Y : Int }
+                    function PlusPair(a : Pair, b : Pair) : Pair { new Pair { X = a.X + b.X, Y = a.Y + b.Y } }
+                "},
+            );
+            is_only_value(&result, &output, &Value::unit());
+            let (result, output) = line(
+                &mut interpreter,
+                "new Pair { X = 1, Y = 2 } * new Pair { X = 3, Y = 4 }",
+            );
+            is_only_error(
+                &result,
+                &output,
+                &expect![[r#"
+                    type error: Type Pair cannot be used in multiplication
+                       [line_1] [new Pair { X = 1, Y = 2 }]
+                "#]],
+            );
+        }
+
+        #[test]
+        fn udt_operator_with_wrong_signature_is_an_error() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(
+                &mut interpreter,
+                indoc! {"
+                    struct Pair { X : Int, Y : Int }
+                    function TimesPair(factor : Int, a : Pair) : Pair { new Pair { X = factor * a.X, Y = factor * a.Y } }
+                "},
+            );
+            is_only_error(
+                &result,
+                &output,
+                &expect![[r#"
+                    type error: function TimesPair is named after an operator of its type but does not have its signature
+                       [line_0] [TimesPair]
+                "#]],
+            );
+        }
+
+        #[test]
+        fn match_expressions_select_arms() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(
+                &mut interpreter,
+                indoc! {"
+                    function Parity(r : Result, b : Bool) : Int {
+                        match r {
+                            Zero => 0,
+                            One => match b {
+                                true => 1,
+                                false => 2,
+                            },
+                        }
+                    }
+                "},
+            );
+            is_only_value(&result, &output, &Value::unit());
+            let (result, output) = line(
+                &mut interpreter,
+                "(Parity(Zero, true), Parity(One, true), Parity(One, false), match 5 { 0 => 0, n => n * 2 })",
+            );
+            is_only_value(
+                &result,
+                &output,
+                &Value::Tuple(
+                    vec![Value::Int(0), Value::Int(1), Value::Int(2), Value::Int(10)].into(),
+                ),
+            );
+        }
+
+        #[test]
+        fn match_on_measurement_result() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(
+                &mut interpreter,
+                indoc! {"
+                    use q = Qubit();
+                    X(q);
+                    match MResetZ(q) {
+                        Zero => 0,
+                        One => 1,
+                    }
+                "},
+            );
+            is_only_value(&result, &output, &Value::Int(1));
+        }
+
+        #[test]
+        fn non_exhaustive_match_is_an_error() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(&mut interpreter, "match One { Zero => 0 }");
+            is_only_error(
+                &result,
+                &output,
+                &expect![[r#"
+                    match expression does not cover `One`
+                       [line_0] [match One { Zero => 0 }]
+                "#]],
+            );
+        }
+
+        #[test]
+        fn enum_variants_carry_payloads() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(
+                &mut interpreter,
+                indoc! {"
+                    enum Shape { Square(Int), Rect(Int, Int), Empty }
+                    function Area(s : Shape) : Int {
+                        match s {
+                            Square(n) => n * n,
+                            Rect(w, h) => w * h,
+                            Empty() => 0,
+                        }
+                    }
+                "},
+            );
+            is_only_value(&result, &output, &Value::unit());
+            let (result, output) = line(
+                &mut interpreter,
+                "(Area(Square(3)), Area(Rect(2, 5)), Area(Empty()))",
+            );
+            is_only_value(
+                &result,
+                &output,
+                &Value::Tuple(vec![Value::Int(9), Value::Int(10), Value::Int(0)].into()),
+            );
+        }
+
+        #[test]
+        fn generic_enum_matches_on_instantiated_payload() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(
+                &mut interpreter,
+                indoc! {"
+                    function OrZero(o : Option<Int>) : Int {
+                        match o {
+                            Some(n) => n,
+                            None() => 0,
+                        }
+                    }
+                "},
+            );
+            is_only_value(&result, &output, &Value::unit());
+            let (result, output) = line(&mut interpreter, "(OrZero(Some(4)), OrZero(None()))");
+            is_only_value(
+                &result,
+                &output,
+                &Value::Tuple(vec![Value::Int(4), Value::Int(0)].into()),
+            );
+        }
+
+        #[test]
+        fn top_level_declarations_shadow_core_option_and_outcome() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(
+                &mut interpreter,
+                indoc! {"
+                    enum Maybe { Some(Int), None }
+                    struct Option { Value : Int }
+                    function Ok(x : Int) : Int { x + 1 }
+                    function Unwrap(m : Maybe) : Int {
+                        match m {
+                            Some(n) => n,
+                            None() => 0,
+                        }
+                    }
+                    function ValueOf(o : Option) : Int { o.Value }
+                "},
+            );
+            is_only_value(&result, &output, &Value::unit());
+            let (result, output) = line(
+                &mut interpreter,
+                "(Unwrap(Some(3)), Unwrap(None()), Ok(1), ValueOf(new Option { Value = 5 }))",
+            );
+            is_only_value(
+                &result,
+                &output,
+                &Value::Tuple(
+                    vec![Value::Int(3), Value::Int(0), Value::Int(2), Value::Int(5)].into(),
+                ),
+            );
+        }
+
+        #[test]
+        fn namespace_declarations_shadow_core_option_and_outcome() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(
+                &mut interpreter,
+                indoc! {r#"
+                    namespace Test {
+                        enum Outcome { Ok, Err(String) }
+                        function Some() : String { "mine" }
+                        function Describe(o : Outcome) : String {
+                            match o {
+                                Ok() => "ok",
+                                Err(e) => e,
+                            }
+                        }
+                        function Main() : (String, String, String) {
+                            (Describe(Ok()), Describe(Err("failed")), Some())
+                        }
+                    }
+                "#},
+            );
+            is_only_value(&result, &output, &Value::unit());
+            let (result, output) = line(&mut interpreter, "Test.Main()");
+            is_only_value(
+                &result,
+                &output,
+                &Value::Tuple(
+                    vec![
+                        Value::String("ok".into()),
+                        Value::String("failed".into()),
+                        Value::String("mine".into()),
+                    ]
+                    .into(),
+                ),
+            );
+        }
+
+        #[test]
+        fn propagate_returns_early_on_failed_variant() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(
+                &mut interpreter,
+                indoc! {"
+                    function Checked(x : Int) : Outcome<Int, String> {
+                        if x < 0 { Err(\"negative\") } else { Ok(x) }
+                    }
+                    function CheckedSum(x : Int, y : Int) : Outcome<Int, String> {
+                        let a = Checked(x)?;
+                        let b = Checked(y)?;
+                        Ok(a + b)
+                    }
+                    function Show(o : Outcome<Int, String>) : String {
+                        match o {
+                            Ok(n) => $\"ok {n}\",
+                            Err(e) => $\"err {e}\",
+                        }
+                    }
+                "},
+            );
+            is_only_value(&result, &output, &Value::unit());
+            let (result, output) = line(
+                &mut interpreter,
+                "(Show(CheckedSum(1, 2)), Show(CheckedSum(1, -2)))",
+            );
+            is_only_value(
+                &result,
+                &output,
+                &Value::Tuple(
+                    vec![
+                        Value::String("ok 3".into()),
+                        Value::String("err negative".into()),
+                    ]
+                    .into(),
+                ),
+            );
+        }
+
+        #[test]
+        fn enum_without_constructor_is_an_error() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(&mut interpreter, "enum Bit { Low, High }");
+            is_only_value(&result, &output, &Value::unit());
+            let (result, output) = line(&mut interpreter, "Bit()");
+            is_only_error(
+                &result,
+                &output,
+                &expect![[r#"
+                    name error: `Bit` not found
+                       [line_1] [Bit]
+                "#]],
+            );
+        }
+
         #[test]
         fn incremental_lambas_work() {
             let mut interpreter = get_interpreter();
@@ -480,8 +848,6 @@ mod given_interpreter {
                       ambiguous name [line_3] [DumpMachine]
                       found in this namespace [line_1] [Other]
                       and also in this namespace [line_2] [Std.Diagnostics]
-                    type error: insufficient type information to infer type
-                       [line_3] [DumpMachine()]
                 "#]],
             );
         }
@@ -647,6 +1013,25 @@ mod given_interpreter {
             .assert_debug_eq(&items[3].1);
         }
 
+        #[test]
+        fn replayed_fragments_only_evaluate_bindings() {
+            let mut interpreter = get_interpreter();
+            let mut cursor = Cursor::new(Vec::<u8>::new());
+            let mut receiver = CursorReceiver::new(&mut cursor);
+            interpreter
+                .replay_fragments(&mut receiver, "function Foo() : Int { 2 }")
+                .expect("declaring an item should succeed");
+            interpreter
+                .replay_fragments(&mut receiver, r#"Message("hi"); fail "boom";"#)
+                .expect("statements without bindings should not be evaluated");
+            interpreter
+                .replay_fragments(&mut receiver, "let x = Foo() + 1;")
+                .expect("bindings should be evaluated");
+            assert_eq!(receiver.dump(), "");
+            let (result, output) = line(&mut interpreter, "x");
+            is_only_value(&result, &output, &Value::Int(3));
+        }
+
         #[test]
         fn invoke_callable_without_args_succeeds() {
             let mut interpreter = get_interpreter();
@@ -914,6 +1299,21 @@ mod given_interpreter {
             "#]].assert_eq(&res);
         }
 
+        #[test]
+        fn base_qirgen_bitcode() {
+            let mut interpreter = get_interpreter_with_capabilities(TargetCapabilityFlags::empty());
+            let (result, output) = line(
+                &mut interpreter,
+                indoc! {"operation Foo() : Result { use q = Qubit(); let r = M(q); Reset(q); return r; } "},
+            );
+            is_only_value(&result, &output, &Value::unit());
+            let res = interpreter
+                .qirgen_bitcode("Foo()")
+                .expect("expected success");
+            assert_eq!(&res[..4], b"BC\xC0\xDE");
+            assert_eq!(res.len() % 4, 0);
+        }
+
         #[test]
         fn adaptive_qirgen() {
             let mut interpreter = get_interpreter_with_capabilities(
@@ -968,13 +1368,12 @@ mod given_interpreter {
 
                 ; module flags
 
-                !llvm.module.flags = !{!0, !1, !2, !3, !4}
+                !llvm.module.flags = !{!0, !1, !2, !3}
 
                 !0 = !{i32 1, !"qir_major_version", i32 1}
                 !1 = !{i32 7, !"qir_minor_version", i32 0}
                 !2 = !{i32 1, !"dynamic_qubit_management", i1 false}
                 !3 = !{i32 1, !"dynamic_result_management", i1 false}
-                !4 = !{i32 1, !"int_computations", !"i64"}
             "#]]
             .assert_eq(&res);
         }
@@ -1008,8 +1407,7 @@ mod given_interpreter {
                 block_0:
                   call void @__quantum__qis__mresetz__body(%Qubit* inttoptr (i64 0 to %Qubit*), %Result* inttoptr (i64 0 to %Result*))
                   %var_0 = call i1 @__quantum__qis__read_result__body(%Result* inttoptr (i64 0 to %Result*))
-                  %var_2 = call i1 @__quantum__qis__read_result__body(%Result* inttoptr (i64 0 to %Result*))
-                  %var_3 = icmp eq i1 %var_2, false
+                  %var_3 = icmp eq i1 %var_0, false
                   call void @__quantum__rt__tuple_record_output(i64 2, i8* null)
                   call void @__quantum__rt__result_record_output(%Result* inttoptr (i64 0 to %Result*), i8* null)
                   call void @__quantum__rt__tuple_record_output(i64 2, i8* null)
@@ -1114,19 +1512,62 @@ mod given_interpreter {
         }
 
         #[test]
-        fn qirgen_entry_expr_defines_operation() {
-            let mut interpreter = get_interpreter_with_capabilities(TargetCapabilityFlags::empty());
-
+        fn program_stats_for_entry_expr() {
+            let mut interpreter = get_interpreter_with_capabilities(
+                TargetCapabilityFlags::Adaptive | TargetCapabilityFlags::QubitReset,
+            );
             let (result, output) = line(
                 &mut interpreter,
-                indoc! {"operation Foo() : Result { use q = Qubit(); let r = M(q); Reset(q); return r; } "},
+                indoc! {"
+                operation Foo() : Result[] {
+                    use qs = Qubit[2];
+                    H(qs[0]);
+                    CNOT(qs[0], qs[1]);
+                    if M(qs[0]) == One { X(qs[1]); }
+                    [MResetZ(qs[0]), MResetZ(qs[1])]
+                }"},
             );
             is_only_value(&result, &output, &Value::unit());
-            let res = interpreter
-                .qirgen("{operation Bar() : Unit {}; Foo()}")
+            let stats = interpreter
+                .program_stats("Foo()")
                 .expect("expected success");
             expect![[r#"
-                %Result = type opaque
+                gates:
+                    cx: 1
+                    h: 1
+                    x: 1
+                two-qubit gates: 1
+                measurements: 3
+                classical instructions: 1
+                depth: 4
+                feedback depth: 1"#]]
+            .assert_eq(&stats.to_string());
+        }
+
+        #[test]
+        fn program_stats_not_supported_for_unrestricted() {
+            let mut interpreter = get_interpreter();
+            let errors = interpreter.program_stats("{}").expect_err("expected error");
+            assert!(matches!(
+                errors.as_slice(),
+                [crate::interpret::Error::UnsupportedRuntimeCapabilities]
+            ));
+        }
+
+        #[test]
+        fn qirgen_entry_expr_defines_operation() {
+            let mut interpreter = get_interpreter_with_capabilities(TargetCapabilityFlags::empty());
+
+            let (result, output) = line(
+                &mut interpreter,
+                indoc! {"operation Foo() : Result { use q = Qubit(); let r = M(q); Reset(q); return r; } "},
+            );
+            is_only_value(&result, &output, &Value::unit());
+            let res = interpreter
+                .qirgen("{operation Bar() : Unit {}; Foo()}")
+                .expect("expected success");
+            expect![[r#"
+                %Result = type opaque
                 %Qubit = type opaque
 
                 define void @ENTRYPOINT__main() #0 {
@@ -1168,8 +1609,6 @@ mod given_interpreter {
                 &expect![[r#"
                     name error: `Bar` not found
                        [line_1] [Bar]
-                    type error: insufficient type information to infer type
-                       [line_1] [Bar()]
                 "#]],
             );
         }
@@ -1253,8 +1692,6 @@ mod given_interpreter {
                 &expect![[r#"
                     name error: `Bar` not found
                        [line_2] [Bar]
-                    type error: insufficient type information to infer type
-                       [line_2] [Bar()]
                 "#]],
             );
         }
@@ -1373,6 +1810,478 @@ mod given_interpreter {
             }
         }
 
+        #[test]
+        fn run_with_noise_model_injects_errors() {
+            let mut interpreter = get_interpreter();
+            interpreter.set_noise_model(NoiseModel {
+                gates: [(
+                    "h".into(),
+                    PauliNoise::phase_flip(1.0).expect("phase flip noise should be valid"),
+                )]
+                .into_iter()
+                .collect(),
+                measurement: 1.0,
+                ..NoiseModel::default()
+            });
+            // The phase flip after the first H turns the second H into an X, and the measurement
+            // error reports the opposite of the outcome, so the result is always `Zero`.
+            let (result, output) = run(
+                &mut interpreter,
+                "{ use q = Qubit(); H(q); H(q); MResetZ(q) }",
+            );
+            is_only_value(&result, &output, &Value::RESULT_ZERO);
+            interpreter.set_noise_model(NoiseModel::default());
+            let (result, output) = run(&mut interpreter, "{ use q = Qubit(); X(q); MResetZ(q) }");
+            is_only_value(&result, &output, &Value::RESULT_ONE);
+        }
+
+        #[test]
+        fn run_with_idle_noise_applies_it_during_delays() {
+            let mut interpreter = get_interpreter();
+            interpreter.set_noise_model(NoiseModel {
+                idle: PauliNoise::bit_flip(1.0).expect("bit flip noise should be valid"),
+                ..NoiseModel::default()
+            });
+            // Each microsecond of delay flips every allocated qubit, so an odd number of
+            // microseconds flips them and an even number restores them.
+            let (result, output) = run(
+                &mut interpreter,
+                "{ use qs = Qubit[2]; DelayUs(3.0); let odd = MResetEachZ(qs); DelayUs(2.0); (odd, MResetEachZ(qs)) }",
+            );
+            is_only_value(
+                &result,
+                &output,
+                &Value::Tuple(
+                    vec![
+                        Value::Array(vec![Value::RESULT_ONE; 2].into()),
+                        Value::Array(vec![Value::RESULT_ZERO; 2].into()),
+                    ]
+                    .into(),
+                ),
+            );
+        }
+
+        #[test]
+        fn run_with_qubit_loss_reports_lost_results() {
+            let mut interpreter = get_interpreter();
+            interpreter.set_noise_model(NoiseModel {
+                loss: 1.0,
+                ..NoiseModel::default()
+            });
+            // A lost result is neither `Zero` nor `One`.
+            let (result, output) = run(
+                &mut interpreter,
+                "{ use q = Qubit(); X(q); let r = MResetZ(q); (r, IsLossResult(r), r == One) }",
+            );
+            is_only_value(
+                &result,
+                &output,
+                &Value::Tuple(
+                    vec![
+                        Value::Result(val::Result::Loss),
+                        Value::Bool(true),
+                        Value::Bool(false),
+                    ]
+                    .into(),
+                ),
+            );
+            interpreter.set_noise_model(NoiseModel::default());
+            let (result, output) = run(
+                &mut interpreter,
+                "{ use q = Qubit(); X(q); IsLossResult(MResetZ(q)) }",
+            );
+            is_only_value(&result, &output, &Value::Bool(false));
+        }
+
+        #[test]
+        fn run_replays_recorded_outcomes() {
+            let mut interpreter = get_interpreter();
+            let expr = "{ use qs = Qubit[4]; ApplyToEach(H, qs); (MResetEachZ(qs), Std.Random.DrawRandomInt(0, 1000)) }";
+            interpreter.set_tape(Some(Tape::record()));
+            let (recorded, _) = run(&mut interpreter, expr);
+            let tape = interpreter.take_tape().expect("tape should be set");
+            assert_eq!(tape.outcomes().len(), 5);
+
+            interpreter.set_tape(Some(tape.rewind()));
+            let (result, output) = run(&mut interpreter, expr);
+            is_only_value(
+                &result,
+                &output,
+                &recorded.expect("recorded run should succeed"),
+            );
+        }
+
+        #[test]
+        fn run_replays_given_outcomes() {
+            let mut interpreter = get_interpreter();
+            // The qubit is flipped to agree with the replayed outcome, so the program resets it.
+            let expr = "{ use q = Qubit(); let r = M(q); if r == One { X(q); } (r, Std.Random.DrawRandomInt(0, 10)) }";
+            interpreter.set_tape(Some(Tape::replay(vec![
+                Outcome::Result(val::Result::Val(true)),
+                Outcome::Int(7),
+            ])));
+            let (result, output) = run(&mut interpreter, expr);
+            is_only_value(
+                &result,
+                &output,
+                &Value::Tuple(vec![Value::RESULT_ONE, Value::Int(7)].into()),
+            );
+
+            interpreter.set_tape(Some(Tape::replay(vec![Outcome::Result(val::Result::Val(
+                true,
+            ))])));
+            let (result, output) = run(&mut interpreter, "Std.Random.DrawRandomInt(0, 10)");
+            is_only_error(
+                &result,
+                &output,
+                &expect![[r#"
+                    runtime error: replayed tape has no outcome for `DrawRandomInt`
+                      no matching outcome on the tape [<entry>] [Std.Random.DrawRandomInt]
+                "#]],
+            );
+        }
+
+        #[test]
+        fn run_exceeding_step_quota_fails_with_call_stack() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(
+                &mut interpreter,
+                indoc! {"
+                    function Spin() : Unit {
+                        mutable i = 0;
+                        while true {
+                            set i += 1;
+                        }
+                    }
+                    function Foo() : Unit { Spin(); }
+                "},
+            );
+            is_only_value(&result, &output, &Value::unit());
+            interpreter.set_quota(Quota {
+                max_steps: Some(1000),
+                ..Quota::default()
+            });
+            let (result, output) = run(&mut interpreter, "Foo()");
+            is_only_error(
+                &result,
+                &output,
+                &expect![[r#"
+                    runtime error: program exceeded the limit of 1000 execution steps
+                      execution stopped here [line_0] [set i += 1]
+                "#]],
+            );
+            let errors = result.expect_err("run should fail");
+            expect![[r#"
+                Error: program exceeded the limit of 1000 execution steps
+                Call stack:
+                    at Spin in line_0
+                    at Foo in line_0
+            "#]]
+            .assert_eq(
+                errors[0]
+                    .stack_trace()
+                    .expect("error should have a stack trace"),
+            );
+        }
+
+        #[test]
+        fn run_exceeding_timeout_fails() {
+            let mut interpreter = get_interpreter();
+            interpreter.set_quota(Quota {
+                timeout: Some(Duration::ZERO),
+                ..Quota::default()
+            });
+            let (result, output) = run(&mut interpreter, "while true {}");
+            is_only_error(
+                &result,
+                &output,
+                &expect![[r#"
+                    runtime error: program timed out after 0ns
+                      execution stopped here [<entry>] [true]
+                "#]],
+            );
+        }
+
+        #[test]
+        fn run_exceeding_timeout_on_given_clock_fails() {
+            // A clock that advances by a second each time it is read, so the timeout expires on the first check.
+            fn ticking_clock() -> Duration {
+                static TICKS: AtomicU64 = AtomicU64::new(0);
+                Duration::from_secs(TICKS.fetch_add(1, Ordering::Relaxed))
+            }
+            let mut interpreter = get_interpreter();
+            interpreter.set_quota(Quota {
+                timeout: Some(Duration::from_secs(1)),
+                clock: Some(ticking_clock),
+                ..Quota::default()
+            });
+            let (result, output) = run(&mut interpreter, "while true {}");
+            is_only_error(
+                &result,
+                &output,
+                &expect![[r#"
+                    runtime error: program timed out after 1s
+                      execution stopped here [<entry>] [true]
+                "#]],
+            );
+        }
+
+        #[test]
+        fn run_within_quota_succeeds() {
+            let mut interpreter = get_interpreter();
+            interpreter.set_quota(Quota {
+                max_steps: Some(1000),
+                timeout: Some(Duration::from_secs(10)),
+                ..Quota::default()
+            });
+            let (result, output) = run(
+                &mut interpreter,
+                "{ mutable sum = 0; for i in 1..10 { set sum += i; } sum }",
+            );
+            is_only_value(&result, &output, &Value::Int(55));
+        }
+
+        #[test]
+        fn run_exceeding_call_depth_fails() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(
+                &mut interpreter,
+                indoc! {"
+                    function Down(n : Int) : Int { if n == 0 { 0 } else { 1 + Down(n - 1) } }
+                    function Foo() : Int { Down(10) }
+                "},
+            );
+            is_only_value(&result, &output, &Value::unit());
+            interpreter.set_quota(Quota {
+                max_call_depth: Some(3),
+                ..Quota::default()
+            });
+            let (result, output) = run(&mut interpreter, "Foo()");
+            is_only_error(
+                &result,
+                &output,
+                &expect![[r#"
+                    runtime error: program exceeded the limit of 3 nested calls
+                      call exceeded the limit [line_0] [Down]
+                "#]],
+            );
+            let errors = result.expect_err("run should fail");
+            expect![[r#"
+                Error: program exceeded the limit of 3 nested calls
+                Call stack:
+                    at Down in line_0
+                    at Down in line_0
+                    at Foo in line_0
+            "#]]
+            .assert_eq(
+                errors[0]
+                    .stack_trace()
+                    .expect("error should have a stack trace"),
+            );
+        }
+
+        #[test]
+        fn run_deep_recursion_without_call_depth_limit_succeeds() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(
+                &mut interpreter,
+                "function Down(n : Int) : Int { if n == 0 { 0 } else { 1 + Down(n - 1) } }",
+            );
+            is_only_value(&result, &output, &Value::unit());
+            let (result, output) = run(&mut interpreter, "Down(20000)");
+            is_only_value(&result, &output, &Value::Int(20000));
+        }
+
+        #[test]
+        fn run_tail_recursion_beyond_call_depth_succeeds() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(
+                &mut interpreter,
+                "function Down(n : Int) : Int { if n == 0 { 0 } else { Down(n - 1) } }",
+            );
+            is_only_value(&result, &output, &Value::unit());
+            interpreter.set_quota(Quota {
+                max_call_depth: Some(3),
+                ..Quota::default()
+            });
+            let (result, output) = run(&mut interpreter, "Down(5000)");
+            is_only_value(&result, &output, &Value::Int(0));
+        }
+
+        #[test]
+        fn const_declared_in_earlier_line_is_usable() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(&mut interpreter, "const Width : Int = 2 * 3;");
+            is_only_value(&result, &output, &Value::unit());
+            let (result, output) = line(&mut interpreter, "Width + 1");
+            is_only_value(&result, &output, &Value::Int(7));
+        }
+
+        #[test]
+        fn failing_compile_time_assert_is_error() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(
+                &mut interpreter,
+                indoc! {r#"
+                    const Width : Int = 2 * 3;
+                    function Check() : Unit { CompileTimeAssert(Width % 4 == 0, "bad width"); }
+                "#},
+            );
+            is_only_error(
+                &result,
+                &output,
+                &expect![[r#"
+                    compile-time assertion failed: bad width
+                       [line_0] [CompileTimeAssert(Width % 4 == 0, "bad width")]
+                "#]],
+            );
+        }
+
+        #[test]
+        fn run_calling_denied_intrinsic_fails() {
+            let mut interpreter = get_interpreter();
+            interpreter.set_intrinsic_policy(IntrinsicPolicy {
+                denied: ["DumpMachine".into()].into_iter().collect(),
+                ..IntrinsicPolicy::default()
+            });
+            let (result, output) = run(&mut interpreter, "Std.Diagnostics.DumpMachine()");
+            is_only_error(
+                &result,
+                &output,
+                &expect![[r#"
+                    runtime error: call to `DumpMachine` is not allowed in this environment
+                      call not allowed [<entry>] [Std.Diagnostics.DumpMachine]
+                "#]],
+            );
+        }
+
+        #[test]
+        fn run_calling_intrinsic_outside_allow_list_fails() {
+            let mut interpreter = get_interpreter();
+            interpreter.set_intrinsic_policy(IntrinsicPolicy {
+                allowed: Some(["Message".into()].into_iter().collect()),
+                ..IntrinsicPolicy::default()
+            });
+            let (result, output) = run(
+                &mut interpreter,
+                r#"{ Message("allowed"); Std.Math.Sqrt(4.0) }"#,
+            );
+            expect!["allowed"].assert_eq(&output);
+            is_error(
+                &result.expect_err("run should fail"),
+                &expect![[r#"
+                    runtime error: call to `Sqrt` is not allowed in this environment
+                      call not allowed [<entry>] [Std.Math.Sqrt]
+                "#]],
+            );
+        }
+
+        #[test]
+        fn run_exceeding_message_limit_fails() {
+            let mut interpreter = get_interpreter();
+            interpreter.set_intrinsic_policy(IntrinsicPolicy {
+                max_messages: Some(2),
+                ..IntrinsicPolicy::default()
+            });
+            let (result, output) = run(
+                &mut interpreter,
+                r#"for i in 1..10 { Message($"message {i}"); }"#,
+            );
+            expect![[r#"
+                message 1
+                message 2"#]]
+            .assert_eq(&output);
+            is_error(
+                &result.expect_err("run should fail"),
+                &expect![[r#"
+                    runtime error: program exceeded the limit of 2 messages
+                      execution stopped here [<entry>] [Message]
+                "#]],
+            );
+        }
+
+        #[test]
+        fn run_exceeding_qubit_limit_fails() {
+            let mut interpreter = get_interpreter();
+            interpreter.set_intrinsic_policy(IntrinsicPolicy {
+                max_qubits: Some(2),
+                ..IntrinsicPolicy::default()
+            });
+            let (result, output) = run(&mut interpreter, "{ use qs = Qubit[3]; }");
+            is_only_error(
+                &result,
+                &output,
+                &expect![[r#"
+                    runtime error: program exceeded the limit of 2 allocated qubits
+                      execution stopped here [qsharp-library-source:core/qir.qs] [__quantum__rt__qubit_allocate]
+                "#]],
+            );
+        }
+
+        #[test]
+        fn run_exceeding_simulation_memory_budget_fails() {
+            let mut interpreter = get_interpreter();
+            // Each basis state of up to 64 qubits is estimated at 48 bytes, so the budget fits the
+            // 16 basis states of four qubits in superposition but not the 32 of five.
+            interpreter.set_simulation_memory_budget(Some(1000));
+            let (result, output) = run(
+                &mut interpreter,
+                "{ use qs = Qubit[8]; for q in qs { H(q); } ResetAll(qs); }",
+            );
+            is_only_error(
+                &result,
+                &output,
+                &expect![[r#"
+                    runtime error: simulation exceeded the memory budget of 1000 bytes
+                      the quantum state grew too large here [qsharp-library-source:Std/Intrinsic.qs] [__quantum__qis__h__body]
+                "#]],
+            );
+        }
+
+        #[test]
+        fn run_within_simulation_memory_budget_succeeds() {
+            let mut interpreter = get_interpreter();
+            interpreter.set_simulation_memory_budget(Some(1000));
+            let (result, output) = run(
+                &mut interpreter,
+                "{ use qs = Qubit[8]; for q in qs { H(q); H(q); } MResetEachZ(qs) }",
+            );
+            is_only_value(
+                &result,
+                &output,
+                &Value::Array(vec![Value::RESULT_ZERO; 8].into()),
+            );
+        }
+
+        #[test]
+        fn run_on_auto_simulator_fits_clifford_program_in_memory_budget() {
+            let mut interpreter = get_interpreter();
+            // The same program exceeds the budget on the sparse simulator, but the stabilizer
+            // tableau of eight qubits is small.
+            interpreter.set_simulation_memory_budget(Some(1000));
+            interpreter
+                .set_simulator(crate::interpret::SimulatorKind::Auto)
+                .expect("auto simulator should be available");
+            let (result, output) = run(
+                &mut interpreter,
+                "{ use qs = Qubit[8]; for q in qs { H(q); } ResetAll(qs); }",
+            );
+            is_only_value(&result, &output, &Value::unit());
+        }
+
+        #[test]
+        fn run_on_auto_simulator_runs_non_clifford_program() {
+            let mut interpreter = get_interpreter();
+            interpreter
+                .set_simulator(crate::interpret::SimulatorKind::Auto)
+                .expect("auto simulator should be available");
+            let (result, output) = run(
+                &mut interpreter,
+                "{ use q = Qubit(); H(q); T(q); Adjoint T(q); H(q); MResetZ(q) }",
+            );
+            is_only_value(&result, &output, &Value::RESULT_ZERO);
+        }
+
         #[test]
         fn run_output_merged() {
             let mut interpreter = get_interpreter();
@@ -1387,6 +2296,85 @@ mod given_interpreter {
             }
         }
 
+        #[test]
+        fn explain_result_of_entangled_measurements() {
+            let mut interpreter = get_interpreter();
+            let tree = interpreter
+                .explain_result(
+                    Some("{ use qs = Qubit[2]; H(qs[0]); CNOT(qs[0], qs[1]); MResetEachZ(qs) }"),
+                    "[One, One]",
+                )
+                .expect("explaining result should succeed");
+            expect![[r#"
+                0.5000
+                  MResetZ(q0) = One (p = 0.5000): 0.5000 of 0.5000
+                    MResetZ(q1) = One (p = 1.0000): 0.5000 of 0.5000 => [One, One]
+                  MResetZ(q0) = Zero (p = 0.5000): 0.0000 of 0.5000"#]]
+            .assert_eq(&tree.to_string());
+        }
+
+        #[test]
+        fn explain_result_attributes_branches() {
+            let mut interpreter = get_interpreter();
+            let (result, output) = line(
+                &mut interpreter,
+                indoc! {"
+                    operation Foo() : Result {
+                        use (q0, q1) = (Qubit(), Qubit());
+                        Ry(Std.Math.PI() / 3.0, q0);
+                        if M(q0) == One {
+                            H(q1);
+                        }
+                        Reset(q0);
+                        MResetZ(q1)
+                    }
+                "},
+            );
+            is_only_value(&result, &output, &Value::unit());
+            let tree = interpreter
+                .explain_result(Some("Foo()"), "One")
+                .expect("explaining result should succeed");
+            expect![[r#"
+                0.1250
+                  M(q0) = One (p = 0.2500): 0.1250 of 0.2500
+                    Reset(q0) = One (p = 1.0000): 0.1250 of 0.2500
+                      MResetZ(q1) = One (p = 0.5000): 0.1250 of 0.1250 => One
+                      MResetZ(q1) = Zero (p = 0.5000): 0.0000 of 0.1250 => Zero
+                  M(q0) = Zero (p = 0.7500): 0.0000 of 0.7500"#]]
+            .assert_eq(&tree.to_string());
+        }
+
+        #[test]
+        fn explain_result_of_large_program_fails() {
+            let mut interpreter = get_interpreter();
+            let result = interpreter.explain_result(
+                Some("{ use qs = Qubit[11]; ApplyToEach(H, qs); MResetEachZ(qs) }"),
+                "[]",
+            );
+            is_error(
+                &result.expect_err("explaining result should fail"),
+                &expect![[r#"
+                    program is too large to explain its results
+                "#]],
+            );
+        }
+
+        #[test]
+        fn explain_result_returns_runtime_errors() {
+            let mut interpreter = get_interpreter();
+            let result = interpreter.explain_result(
+                Some(r#"{ use q = Qubit(); H(q); if M(q) == One { fail "failed" } }"#),
+                "()",
+            );
+            is_error(
+                &result.expect_err("explaining result should fail"),
+                &expect![[r#"
+                    runtime error: program failed: failed
+                      explicit fail [<entry>] [fail "failed"]
+                "#]],
+            );
+        }
+
         #[test]
         fn base_prof_non_result_return() {
             let mut interpreter = get_interpreter_with_capabilities(TargetCapabilityFlags::empty());
@@ -1543,6 +2531,251 @@ mod given_interpreter {
             is_unit_with_output_eval_entry(&result, &output, "hello there...");
         }
 
+        fn shots_interpreter() -> Interpreter {
+            let source = indoc! { r#"
+            namespace Test {
+                @EntryPoint()
+                operation Main() : Result {
+                    Message("shot");
+                    use q = Qubit();
+                    X(q);
+                    MResetZ(q)
+                }
+            }"#};
+
+            let sources = SourceMap::new([("test".into(), source.into())], None);
+            let (std_id, store) =
+                crate::compile::package_store_with_stdlib(TargetCapabilityFlags::all());
+            Interpreter::new(
+                sources,
+                PackageType::Exe,
+                TargetCapabilityFlags::all(),
+                LanguageFeatures::default(),
+                store,
+                &[(std_id, None)],
+            )
+            .expect("interpreter should be created")
+        }
+
+        #[test]
+        fn run_shots_yields_each_shot() {
+            let mut interpreter = shots_interpreter();
+            let mut cursor = Cursor::new(Vec::<u8>::new());
+            let mut receiver = CursorReceiver::new(&mut cursor);
+            let results = interpreter
+                .run_shots(&mut receiver, None, None, 3)
+                .expect("shots should start")
+                .map(|result| result.expect("shot should succeed").to_string())
+                .collect::<Vec<_>>();
+            assert_eq!(results, vec!["One"; 3]);
+            assert_eq!(receiver.dump(), "shot\nshot\nshot");
+        }
+
+        #[test]
+        fn run_shots_stops_when_dropped() {
+            let mut interpreter = shots_interpreter();
+            let mut cursor = Cursor::new(Vec::<u8>::new());
+            let mut receiver = CursorReceiver::new(&mut cursor);
+            let shots = interpreter
+                .run_shots(&mut receiver, Some("Test.Main()"), None, 5)
+                .expect("shots should start");
+            assert_eq!(shots.len(), 5);
+            assert_eq!(shots.take(2).count(), 2);
+            assert_eq!(receiver.dump(), "shot\nshot");
+        }
+
+        #[test]
+        fn reuse_analysis_aggregates_warnings_across_runs() {
+            let source = indoc! { r#"
+            namespace Test {
+                operation Main() : Result {
+                    use q = Qubit();
+                    X(q);
+                    let r = M(q);
+                    H(q);
+                    H(q);
+                    X(q);
+                    r
+                }
+            }"#};
+
+            let sources = SourceMap::new([("test".into(), source.into())], None);
+            let (std_id, store) =
+                crate::compile::package_store_with_stdlib(TargetCapabilityFlags::all());
+            let mut interpreter = Interpreter::new(
+                sources,
+                PackageType::Lib,
+                TargetCapabilityFlags::all(),
+                LanguageFeatures::default(),
+                store,
+                &[(std_id, None)],
+            )
+            .expect("interpreter should be created");
+            assert!(interpreter.reuse_warnings().is_none());
+            interpreter.set_reuse_analysis(true);
+            let mut cursor = Cursor::new(Vec::<u8>::new());
+            let mut receiver = CursorReceiver::new(&mut cursor);
+            for _ in 0..3 {
+                let result = interpreter
+                    .run(&mut receiver, Some("Test.Main()"), None)
+                    .expect("run should succeed");
+                assert_eq!(result.to_string(), "One");
+            }
+            let warnings = interpreter
+                .reuse_warnings()
+                .expect("analysis should be enabled")
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>();
+            assert_eq!(
+                warnings,
+                vec!["qubit used after measurement without reset: Qubit0 (3 times in 3 shots)"]
+            );
+        }
+
+        #[test]
+        fn run_sweep_keys_results_by_args() {
+            let source = indoc! { r#"
+            namespace Test {
+                operation Flip(flip : Bool) : Result {
+                    Message("flip");
+                    use q = Qubit();
+                    if flip {
+                        X(q);
+                    }
+                    MResetZ(q)
+                }
+            }"#};
+
+            let sources = SourceMap::new([("test".into(), source.into())], None);
+            let (std_id, store) =
+                crate::compile::package_store_with_stdlib(TargetCapabilityFlags::all());
+            let mut interpreter = Interpreter::new(
+                sources,
+                PackageType::Lib,
+                TargetCapabilityFlags::all(),
+                LanguageFeatures::default(),
+                store,
+                &[(std_id, None)],
+            )
+            .expect("interpreter should be created");
+            let mut cursor = Cursor::new(Vec::<u8>::new());
+            let mut receiver = CursorReceiver::new(&mut cursor);
+            let callable = interpreter
+                .eval_fragments(&mut receiver, "Test.Flip")
+                .expect("callable should resolve");
+            let points = interpreter.run_sweep(
+                &mut receiver,
+                &callable,
+                [Value::Bool(false), Value::Bool(true)],
+                2,
+                None,
+            );
+            let points = points
+                .into_iter()
+                .map(|point| {
+                    let results = point
+                        .results
+                        .into_iter()
+                        .map(|result| result.expect("shot should succeed").to_string())
+                        .collect::<Vec<_>>();
+                    (point.args.to_string(), results)
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(
+                points,
+                vec![
+                    ("false".to_string(), vec!["Zero".to_string(); 2]),
+                    ("true".to_string(), vec!["One".to_string(); 2]),
+                ]
+            );
+            assert_eq!(receiver.dump(), "flip\nflip\nflip\nflip");
+        }
+
+        #[test]
+        fn non_clifford_gate_fails_on_stabilizer_sim() {
+            let source = indoc! { r#"
+            namespace Test {
+                @EntryPoint()
+                operation Main() : Result {
+                    use q = Qubit();
+                    H(q);
+                    T(q);
+                    MResetZ(q)
+                }
+            }"#};
+
+            let sources = SourceMap::new([("test".into(), source.into())], None);
+            let (std_id, store) =
+                crate::compile::package_store_with_stdlib(TargetCapabilityFlags::all());
+            let mut interpreter = Interpreter::new(
+                sources,
+                PackageType::Exe,
+                TargetCapabilityFlags::all(),
+                LanguageFeatures::default(),
+                store,
+                &[(std_id, None)],
+            )
+            .expect("interpreter should be created");
+
+            let mut cursor = Cursor::new(Vec::<u8>::new());
+            let mut receiver = CursorReceiver::new(&mut cursor);
+            let result =
+                interpreter.eval_entry_with_sim(&mut crate::StabilizerSim::new(), &mut receiver);
+            is_only_error(
+                &result,
+                &receiver.dump(),
+                &expect![[r#"
+                    runtime error: intrinsic callable `__quantum__qis__t__body` failed: the stabilizer simulator can only apply Clifford operations
+                       [qsharp-library-source:QIR/Intrinsic.qs] [operation __quantum__qis__t__body(target : Qubit) : Unit {
+                        body intrinsic;
+                    }]
+                "#]],
+            );
+        }
+
+        #[test]
+        fn auto_sim_runs_non_clifford_program() {
+            let source = indoc! { r#"
+            namespace Test {
+                @EntryPoint()
+                operation Main() : Result[] {
+                    use qs = Qubit[2];
+                    H(qs[0]);
+                    CNOT(qs[0], qs[1]);
+                    T(qs[1]);
+                    Adjoint T(qs[1]);
+                    CNOT(qs[0], qs[1]);
+                    H(qs[0]);
+                    [MResetZ(qs[0]), MResetZ(qs[1])]
+                }
+            }"#};
+
+            let sources = SourceMap::new([("test".into(), source.into())], None);
+            let (std_id, store) =
+                crate::compile::package_store_with_stdlib(TargetCapabilityFlags::all());
+            let mut interpreter = Interpreter::new(
+                sources,
+                PackageType::Exe,
+                TargetCapabilityFlags::all(),
+                LanguageFeatures::default(),
+                store,
+                &[(std_id, None)],
+            )
+            .expect("interpreter should be created");
+
+            let mut cursor = Cursor::new(Vec::<u8>::new());
+            let mut receiver = CursorReceiver::new(&mut cursor);
+            let mut sim = crate::AutoSim::new();
+            let result = interpreter.eval_entry_with_sim(&mut sim, &mut receiver);
+            is_only_value(
+                &result,
+                &receiver.dump(),
+                &Value::Array(vec![Value::RESULT_ZERO, Value::RESULT_ZERO].into()),
+            );
+            assert!(!sim.is_stabilizer());
+        }
+
         #[test]
         fn errors_returned_if_sources_do_not_match_profile() {
             let source = indoc! { r#"
@@ -7,13 +7,23 @@ pub mod error;
 pub mod incremental;
 pub mod interpret;
 pub mod location;
+pub mod memory;
 pub mod packages;
+pub mod provenance;
+#[cfg(feature = "parallel-shots")]
+pub mod shots;
 pub mod target;
 
 pub use qsc_formatter::formatter;
 
 pub use qsc_frontend::compile::{CompileUnit, PackageStore, SourceContents, SourceMap, SourceName};
 
+pub mod library {
+    pub use ::library::{
+        library_source_name, versioned_library_uri, LIBRARY_VERSION, QSHARP_LIBRARY_URI_SCHEME,
+    };
+}
+
 pub mod resolve {
     pub use qsc_frontend::resolve::{path_as_field_accessor, Local, LocalKind, Locals, Res};
 }
@@ -44,17 +54,25 @@ pub use qsc_data_structures::{
 
 pub use qsc_passes::{lower_hir_to_fir, PackageType, PassContext};
 
+pub use qsc_rca::RuntimeFeatureFlags;
+
 pub mod line_column {
-    pub use qsc_data_structures::line_column::{Encoding, Position, Range};
+    pub use qsc_data_structures::line_column::{Encoding, LineIndex, Position, Range};
 }
 
+#[cfg(feature = "interpreter")]
+pub use qsc_eval::backend::SparseSim;
+#[cfg(feature = "interpreter")]
+pub use qsc_eval::stabilizer::AutoSim;
 pub use qsc_eval::{
-    backend::{Backend, SparseSim},
-    noise::PauliNoise,
+    backend::{Backend, Noisy},
+    noise::{NoiseModel, PauliNoise},
+    stabilizer::StabilizerSim,
     state::{
         fmt_basis_state_label, fmt_complex, format_state_id, get_matrix_latex, get_phase,
         get_state_latex,
     },
+    IntrinsicPolicy, Quota,
 };
 
 pub mod linter {
@@ -68,7 +86,7 @@ pub mod circuit {
 }
 
 pub mod parse {
-    pub use qsc_parse::{completion, top_level_nodes};
+    pub use qsc_parse::{completion, lex::highlight, top_level_nodes};
 }
 
 pub mod partial_eval {
@@ -0,0 +1,313 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! Estimates of the memory held by compiled packages and by the simulator, used to report
+//! what is taking up space in a long-running language service or interpreter.
+//!
+//! The estimates count the nodes reachable from each representation of a package and multiply
+//! them by the size of the node types. They don't include allocator overhead or unused capacity,
+//! so they are a lower bound suited to comparing packages against each other.
+
+#[cfg(test)]
+mod tests;
+
+use std::mem::size_of;
+
+use num_bigint::BigUint;
+use num_complex::Complex;
+use qsc_ast::{ast, visit as ast_visit};
+use qsc_fir::fir;
+use qsc_frontend::compile::{CompileUnit, PackageStore};
+use qsc_hir::{hir, visit as hir_visit};
+use qsc_rca::{ApplicationGeneratorSet, ItemComputeProperties, PackageComputeProperties};
+
+/// The estimated memory, in bytes, held by one package. Each representation that isn't kept by
+/// the component reporting the usage is zero.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PackageMemoryUsage {
+    /// The ID of the package in the package store.
+    pub id: usize,
+    /// A human-readable name for the package.
+    pub name: String,
+    /// The source text of the package.
+    pub sources: usize,
+    /// The abstract syntax tree.
+    pub ast: usize,
+    /// The high-level intermediate representation.
+    pub hir: usize,
+    /// The flattened intermediate representation used for evaluation and code generation.
+    pub fir: usize,
+    /// The runtime capabilities analysis tables.
+    pub rca: usize,
+}
+
+impl PackageMemoryUsage {
+    /// The estimated memory held by all representations of the package.
+    #[must_use]
+    pub fn total(&self) -> usize {
+        self.sources + self.ast + self.hir + self.fir + self.rca
+    }
+}
+
+/// The estimated memory, in bytes, held by the packages and the simulator of a language service
+/// or interpreter.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MemoryUsage {
+    /// The usage of each package, in package ID order.
+    pub packages: Vec<PackageMemoryUsage>,
+    /// The quantum state of the simulator.
+    pub simulator: usize,
+}
+
+impl MemoryUsage {
+    /// The estimated memory held by all packages and the simulator.
+    #[must_use]
+    pub fn total(&self) -> usize {
+        self.packages
+            .iter()
+            .map(PackageMemoryUsage::total)
+            .sum::<usize>()
+            + self.simulator
+    }
+}
+
+/// Estimates the sources, AST and HIR of every package in the store.
+#[must_use]
+pub fn store_memory_usage(store: &PackageStore) -> Vec<PackageMemoryUsage> {
+    store
+        .iter()
+        .map(|(id, unit)| unit_memory_usage(id, unit))
+        .collect()
+}
+
+/// Estimates the sources, AST and HIR of a compiled package.
+#[must_use]
+pub fn unit_memory_usage(id: hir::PackageId, unit: &CompileUnit) -> PackageMemoryUsage {
+    let mut ast_sizer = AstSizer::default();
+    ast_visit::Visitor::visit_package(&mut ast_sizer, &unit.ast.package);
+    let mut hir_sizer = HirSizer::default();
+    hir_visit::Visitor::visit_package(&mut hir_sizer, &unit.package);
+
+    PackageMemoryUsage {
+        id: id.into(),
+        name: package_name(id, unit),
+        sources: unit
+            .sources
+            .iter()
+            .map(|source| source.name.len() + source.contents.len())
+            .sum(),
+        ast: ast_sizer.size,
+        hir: hir_sizer.size,
+        fir: 0,
+        rca: 0,
+    }
+}
+
+/// Gets a name for the package: `core` for the core library, otherwise the name of its first source.
+#[must_use]
+pub fn package_name(id: hir::PackageId, unit: &CompileUnit) -> String {
+    if id == hir::PackageId::CORE {
+        "core".to_string()
+    } else if let Some(source) = unit.sources.iter().next() {
+        source.name.to_string()
+    } else {
+        format!("package {id}")
+    }
+}
+
+/// Estimates the memory held by the arenas of a FIR package.
+#[must_use]
+pub fn fir_size(package: &fir::Package) -> usize {
+    package.items.values().count() * size_of::<fir::Item>()
+        + package.blocks.values().count() * size_of::<fir::Block>()
+        + package.exprs.values().count() * size_of::<fir::Expr>()
+        + package.pats.values().count() * size_of::<fir::Pat>()
+        + package.stmts.values().count() * size_of::<fir::Stmt>()
+}
+
+/// Estimates the memory held by the runtime capabilities analysis tables of a package.
+#[must_use]
+pub fn rca_size(properties: &PackageComputeProperties) -> usize {
+    properties.items.values().count() * size_of::<ItemComputeProperties>()
+        + (properties.blocks.values().count()
+            + properties.stmts.values().count()
+            + properties.exprs.values().count())
+            * size_of::<ApplicationGeneratorSet>()
+}
+
+/// Estimates the memory held by a captured quantum state with the given number of qubits.
+#[must_use]
+pub fn quantum_state_size(state: &[(BigUint, Complex<f64>)], qubit_count: usize) -> usize {
+    qsc_eval::backend::quantum_state_size(state.len(), qubit_count)
+}
+
+#[derive(Default)]
+struct AstSizer {
+    size: usize,
+}
+
+impl AstSizer {
+    fn add<T>(&mut self, _: &T) {
+        self.size += size_of::<T>();
+    }
+}
+
+impl<'a> ast_visit::Visitor<'a> for AstSizer {
+    fn visit_item(&mut self, item: &'a ast::Item) {
+        self.add(item);
+        ast_visit::walk_item(self, item);
+    }
+
+    fn visit_attr(&mut self, attr: &'a ast::Attr) {
+        self.add(attr);
+        ast_visit::walk_attr(self, attr);
+    }
+
+    fn visit_ty_def(&mut self, def: &'a ast::TyDef) {
+        self.add(def);
+        ast_visit::walk_ty_def(self, def);
+    }
+
+    fn visit_callable_decl(&mut self, decl: &'a ast::CallableDecl) {
+        self.add(decl);
+        ast_visit::walk_callable_decl(self, decl);
+    }
+
+    fn visit_struct_decl(&mut self, decl: &'a ast::StructDecl) {
+        self.add(decl);
+        ast_visit::walk_struct_decl(self, decl);
+    }
+
+    fn visit_field_def(&mut self, def: &'a ast::FieldDef) {
+        self.add(def);
+        ast_visit::walk_field_def(self, def);
+    }
+
+    fn visit_spec_decl(&mut self, decl: &'a ast::SpecDecl) {
+        self.add(decl);
+        ast_visit::walk_spec_decl(self, decl);
+    }
+
+    fn visit_functor_expr(&mut self, expr: &'a ast::FunctorExpr) {
+        self.add(expr);
+        ast_visit::walk_functor_expr(self, expr);
+    }
+
+    fn visit_ty(&mut self, ty: &'a ast::Ty) {
+        self.add(ty);
+        ast_visit::walk_ty(self, ty);
+    }
+
+    fn visit_block(&mut self, block: &'a ast::Block) {
+        self.add(block);
+        ast_visit::walk_block(self, block);
+    }
+
+    fn visit_stmt(&mut self, stmt: &'a ast::Stmt) {
+        self.add(stmt);
+        ast_visit::walk_stmt(self, stmt);
+    }
+
+    fn visit_expr(&mut self, expr: &'a ast::Expr) {
+        self.add(expr);
+        ast_visit::walk_expr(self, expr);
+    }
+
+    fn visit_field_assign(&mut self, assign: &'a ast::FieldAssign) {
+        self.add(assign);
+        ast_visit::walk_field_assign(self, assign);
+    }
+
+    fn visit_match_arm(&mut self, arm: &'a ast::MatchArm) {
+        self.add(arm);
+        ast_visit::walk_match_arm(self, arm);
+    }
+
+    fn visit_pat(&mut self, pat: &'a ast::Pat) {
+        self.add(pat);
+        ast_visit::walk_pat(self, pat);
+    }
+
+    fn visit_qubit_init(&mut self, init: &'a ast::QubitInit) {
+        self.add(init);
+        ast_visit::walk_qubit_init(self, init);
+    }
+
+    fn visit_path(&mut self, path: &'a ast::Path) {
+        self.add(path);
+        ast_visit::walk_path(self, path);
+    }
+
+    fn visit_ident(&mut self, ident: &'a ast::Ident) {
+        self.add(ident);
+        self.size += ident.name.len();
+    }
+}
+
+#[derive(Default)]
+struct HirSizer {
+    size: usize,
+}
+
+impl HirSizer {
+    fn add<T>(&mut self, _: &T) {
+        self.size += size_of::<T>();
+    }
+}
+
+impl<'a> hir_visit::Visitor<'a> for HirSizer {
+    fn visit_item(&mut self, item: &'a hir::Item) {
+        self.add(item);
+        hir_visit::walk_item(self, item);
+    }
+
+    fn visit_callable_decl(&mut self, decl: &'a hir::CallableDecl) {
+        self.add(decl);
+        hir_visit::walk_callable_decl(self, decl);
+    }
+
+    fn visit_spec_decl(&mut self, decl: &'a hir::SpecDecl) {
+        self.add(decl);
+        hir_visit::walk_spec_decl(self, decl);
+    }
+
+    fn visit_block(&mut self, block: &'a hir::Block) {
+        self.add(block);
+        hir_visit::walk_block(self, block);
+    }
+
+    fn visit_stmt(&mut self, stmt: &'a hir::Stmt) {
+        self.add(stmt);
+        hir_visit::walk_stmt(self, stmt);
+    }
+
+    fn visit_expr(&mut self, expr: &'a hir::Expr) {
+        self.add(expr);
+        hir_visit::walk_expr(self, expr);
+    }
+
+    fn visit_field_assign(&mut self, assign: &'a hir::FieldAssign) {
+        self.add(assign);
+        hir_visit::walk_field_assign(self, assign);
+    }
+
+    fn visit_match_arm(&mut self, arm: &'a hir::MatchArm) {
+        self.add(arm);
+        hir_visit::walk_match_arm(self, arm);
+    }
+
+    fn visit_pat(&mut self, pat: &'a hir::Pat) {
+        self.add(pat);
+        hir_visit::walk_pat(self, pat);
+    }
+
+    fn visit_qubit_init(&mut self, init: &'a hir::QubitInit) {
+        self.add(init);
+        hir_visit::walk_qubit_init(self, init);
+    }
+
+    fn visit_ident(&mut self, ident: &'a hir::Ident) {
+        self.add(ident);
+        self.size += ident.name.len();
+    }
+}
@@ -0,0 +1,91 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::store_memory_usage;
+use crate::compile::package_store_with_stdlib;
+use qsc_data_structures::target::TargetCapabilityFlags;
+
+#[test]
+fn store_usage_reports_ast_and_hir_of_core_and_std() {
+    let (_, store) = package_store_with_stdlib(TargetCapabilityFlags::all());
+    let packages = store_memory_usage(&store);
+
+    assert_eq!(packages.len(), 2);
+    assert_eq!(packages[0].name, "core");
+    for package in &packages {
+        assert!(package.sources > 0, "{} should have sources", package.name);
+        assert!(package.ast > 0, "{} should have an AST", package.name);
+        assert!(package.hir > 0, "{} should have a HIR", package.name);
+        assert_eq!(package.fir, 0);
+        assert_eq!(package.rca, 0);
+    }
+    assert!(
+        packages[1].total() > packages[0].total(),
+        "std should be larger than core"
+    );
+}
+
+#[cfg(feature = "interpreter")]
+mod interpreter {
+    use crate::interpret::Interpreter;
+    use qsc_data_structures::{language_features::LanguageFeatures, target::TargetCapabilityFlags};
+    use qsc_eval::output::CursorReceiver;
+    use qsc_frontend::compile::SourceMap;
+    use qsc_passes::PackageType;
+    use std::io::Cursor;
+
+    fn interpreter(capabilities: TargetCapabilityFlags) -> Interpreter {
+        let (std_id, store) = crate::compile::package_store_with_stdlib(capabilities);
+        Interpreter::new(
+            SourceMap::new([("A.qs".into(), "function A() : Unit {}".into())], None),
+            PackageType::Lib,
+            capabilities,
+            LanguageFeatures::default(),
+            store,
+            &[(std_id, None)],
+        )
+        .expect("interpreter should be created")
+    }
+
+    #[test]
+    fn interpreter_usage_reports_fir_of_every_package() {
+        let usage = interpreter(TargetCapabilityFlags::all()).memory_usage();
+
+        let names = usage
+            .packages
+            .iter()
+            .map(|package| package.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names[0], "core");
+        assert!(names.contains(&"A.qs"), "packages should include A.qs");
+        // The open package used for incremental fragments is empty until a fragment is evaluated.
+        assert!(usage
+            .packages
+            .iter()
+            .filter(|package| package.sources > 0)
+            .all(|package| package.fir > 0));
+        assert!(usage.packages.iter().all(|package| package.rca == 0));
+    }
+
+    #[test]
+    fn interpreter_usage_reports_rca_for_restricted_capabilities() {
+        let usage = interpreter(TargetCapabilityFlags::Adaptive).memory_usage();
+
+        assert!(usage.packages.iter().any(|package| package.rca > 0));
+    }
+
+    #[test]
+    fn interpreter_usage_grows_with_simulator_state() {
+        let mut interpreter = interpreter(TargetCapabilityFlags::all());
+        let before = interpreter.memory_usage().simulator;
+
+        let mut cursor = Cursor::new(Vec::<u8>::new());
+        let mut receiver = CursorReceiver::new(&mut cursor);
+        interpreter
+            .eval_fragments(&mut receiver, "use qs = Qubit[4]; for q in qs { H(q); }")
+            .expect("fragments should evaluate");
+        let after = interpreter.memory_usage().simulator;
+
+        assert!(after > before, "{after} should be larger than {before}");
+    }
+}
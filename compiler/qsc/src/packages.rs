@@ -42,7 +42,32 @@ pub fn prepare_package_store(
     capabilities: TargetCapabilityFlags,
     package_graph_sources: PackageGraphSources,
 ) -> BuildableProgram {
-    let (std_id, mut package_store) = crate::compile::package_store_with_stdlib(capabilities);
+    prepare_package_store_inner(capabilities, package_graph_sources, None)
+}
+
+/// Prepares the package store like [`prepare_package_store`], reusing the standard library and
+/// the dependencies from the cache when their sources and dependencies have not changed since
+/// they were last compiled.
+#[must_use]
+pub fn prepare_package_store_with_cache(
+    capabilities: TargetCapabilityFlags,
+    package_graph_sources: PackageGraphSources,
+    compile_cache: &mut compile::CompileCache,
+) -> BuildableProgram {
+    prepare_package_store_inner(capabilities, package_graph_sources, Some(compile_cache))
+}
+
+fn prepare_package_store_inner(
+    capabilities: TargetCapabilityFlags,
+    package_graph_sources: PackageGraphSources,
+    mut compile_cache: Option<&mut compile::CompileCache>,
+) -> BuildableProgram {
+    let (std_id, mut package_store) = match compile_cache.as_deref_mut() {
+        Some(compile_cache) => {
+            crate::compile::package_store_with_stdlib_cached(capabilities, compile_cache)
+        }
+        None => crate::compile::package_store_with_stdlib(capabilities),
+    };
 
     let mut canonical_package_identifier_to_package_id_mapping = FxHashMap::default();
 
@@ -80,14 +105,26 @@ pub fn prepare_package_store(
             .map(|(alias, b)| (*b, Some(alias.clone())))
             .chain(std::iter::once((std_id, None)))
             .collect::<Vec<_>>();
-        let (compile_unit, mut this_errors) = compile::compile(
-            &package_store,
-            &dependencies[..],
-            source_map,
-            PackageType::Lib,
-            capabilities,
-            LanguageFeatures::from_iter(package_to_compile.language_features),
-        );
+        let language_features = LanguageFeatures::from_iter(package_to_compile.language_features);
+        let (compile_unit, mut this_errors) = match compile_cache.as_deref_mut() {
+            Some(compile_cache) => compile::compile_with_cache(
+                &package_store,
+                &dependencies[..],
+                source_map,
+                PackageType::Lib,
+                capabilities,
+                language_features,
+                compile_cache,
+            ),
+            None => compile::compile(
+                &package_store,
+                &dependencies[..],
+                source_map,
+                PackageType::Lib,
+                capabilities,
+                language_features,
+            ),
+        };
 
         let package_id = package_store.insert(compile_unit);
         if !this_errors.is_empty() {
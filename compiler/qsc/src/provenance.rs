@@ -0,0 +1,122 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! A provenance manifest records everything needed to reproduce a code generation artifact: the compiler that
+//! produced it, the target it was generated for, the passes that ran, and digests of the sources and dependencies
+//! it was compiled from.
+
+#[cfg(test)]
+mod tests;
+
+use qsc_data_structures::{sha256, target::TargetCapabilityFlags};
+use qsc_frontend::compile::SourceMap;
+use qsc_project::{package_ref_from_key, PackageGraphSources, PackageRef};
+use serde::Serialize;
+
+use crate::target::Profile;
+
+/// A description of how a code generation artifact was produced.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Provenance {
+    pub compiler_version: String,
+    pub compiler_commit: String,
+    pub target_profile: String,
+    pub capabilities: Vec<String>,
+    pub passes: Vec<String>,
+    pub sources: Vec<Digest>,
+    pub dependencies: Vec<Dependency>,
+    pub artifacts: Vec<Digest>,
+}
+
+/// The SHA-256 digest of a named piece of content.
+#[derive(Debug, Serialize)]
+pub struct Digest {
+    pub name: String,
+    pub sha256: String,
+}
+
+/// A package the compiled program depends on, along with a digest over all of its sources.
+#[derive(Debug, Serialize)]
+pub struct Dependency {
+    pub package: PackageRef,
+    pub sha256: String,
+}
+
+impl Provenance {
+    /// Creates a manifest for artifacts generated from the given sources for the given target.
+    /// Passes, dependencies and artifacts are added by the caller as they become known.
+    #[must_use]
+    pub fn new(profile: Profile, capabilities: TargetCapabilityFlags, sources: &SourceMap) -> Self {
+        // The commit is empty when the compiler was not built from a git checkout.
+        let commit = env!("QSHARP_GIT_HASH").trim();
+        Self {
+            compiler_version: env!("CARGO_PKG_VERSION").to_string(),
+            compiler_commit: if commit.is_empty() { "unknown" } else { commit }.to_string(),
+            target_profile: profile.to_str().to_string(),
+            capabilities: capabilities
+                .iter_names()
+                .map(|(name, _)| name.to_string())
+                .collect(),
+            passes: Vec::new(),
+            sources: sources
+                .iter()
+                .map(|source| Digest::new(&source.name, &source.contents))
+                .collect(),
+            dependencies: Vec::new(),
+            artifacts: Vec::new(),
+        }
+    }
+
+    /// Records the packages of the given package graph, other than the root package, as dependencies.
+    pub fn add_dependencies(&mut self, package_graph_sources: &PackageGraphSources) {
+        let mut dependencies = package_graph_sources
+            .packages
+            .iter()
+            .map(|(key, info)| (key.clone(), digest_sources(&info.sources)))
+            .collect::<Vec<_>>();
+        // Package keys are unique, so sorting by them makes the manifest independent of hash map ordering.
+        dependencies.sort_by(|(a, _), (b, _)| a.cmp(b));
+        self.dependencies
+            .extend(dependencies.into_iter().map(|(key, sha256)| Dependency {
+                package: package_ref_from_key(&key),
+                sha256,
+            }));
+    }
+
+    /// Records a generated artifact.
+    pub fn add_artifact(&mut self, name: &str, contents: &str) {
+        self.artifacts.push(Digest::new(name, contents));
+    }
+
+    /// Serializes the manifest as pretty-printed JSON.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("provenance should be serializable")
+    }
+}
+
+impl Digest {
+    fn new(name: &str, contents: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            sha256: sha256::hex_digest(contents.as_bytes()),
+        }
+    }
+}
+
+/// Digests a set of sources in a way that does not depend on the order they were loaded in.
+fn digest_sources<N: AsRef<str>, C: AsRef<str>>(sources: &[(N, C)]) -> String {
+    let mut entries = sources
+        .iter()
+        .map(|(name, contents)| {
+            format!(
+                "{} {}\n",
+                sha256::hex_digest(contents.as_ref().as_bytes()),
+                name.as_ref()
+            )
+        })
+        .collect::<Vec<_>>();
+    entries.sort();
+    sha256::hex_digest(entries.concat().as_bytes())
+}
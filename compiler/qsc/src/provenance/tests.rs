@@ -0,0 +1,128 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#![allow(clippy::needless_raw_string_hashes)]
+
+use expect_test::expect;
+use qsc_data_structures::{language_features::LanguageFeatures, target::TargetCapabilityFlags};
+use qsc_frontend::compile::SourceMap;
+use qsc_project::{key_for_package_ref, PackageGraphSources, PackageInfo, PackageRef};
+use rustc_hash::FxHashMap;
+
+use super::Provenance;
+use crate::target::Profile;
+
+fn package_info(sources: &[(&str, &str)]) -> PackageInfo {
+    PackageInfo {
+        sources: sources
+            .iter()
+            .map(|(name, contents)| ((*name).into(), (*contents).into()))
+            .collect(),
+        language_features: LanguageFeatures::default(),
+        dependencies: FxHashMap::default(),
+        package_type: None,
+    }
+}
+
+fn provenance_for(sources: &[(&str, &str)]) -> Provenance {
+    let profile = Profile::AdaptiveRI;
+    let source_map = SourceMap::new(
+        sources
+            .iter()
+            .map(|(name, contents)| ((*name).into(), (*contents).into())),
+        None,
+    );
+    let mut provenance =
+        Provenance::new(profile, TargetCapabilityFlags::from(profile), &source_map);
+    // The compiler version and commit change with every build, so they are not part of the expected output.
+    provenance.compiler_version = "<version>".to_string();
+    provenance.compiler_commit = "<commit>".to_string();
+    provenance
+}
+
+#[test]
+fn manifest_records_target_sources_passes_and_artifacts() {
+    let mut provenance = provenance_for(&[("Main.qs", "namespace Test {}")]);
+    provenance.passes = vec!["simplify_control_flow".to_string()];
+    provenance.add_artifact("qir.ll", "; qir");
+    expect![[r#"
+        {
+          "compilerVersion": "<version>",
+          "compilerCommit": "<commit>",
+          "targetProfile": "Adaptive_RI",
+          "capabilities": [
+            "Adaptive",
+            "IntegerComputations",
+            "QubitReset"
+          ],
+          "passes": [
+            "simplify_control_flow"
+          ],
+          "sources": [
+            {
+              "name": "Main.qs",
+              "sha256": "3a6e6f49a79458275016681c010c37962571294367d3b5d21740b27ad538ae1d"
+            }
+          ],
+          "dependencies": [],
+          "artifacts": [
+            {
+              "name": "qir.ll",
+              "sha256": "75425513fd6619750cad4401318242e574353e22168704a266d45e9bd18f8de5"
+            }
+          ]
+        }"#]]
+    .assert_eq(&provenance.to_json());
+}
+
+#[test]
+fn dependencies_are_sorted_and_digested_independently_of_source_order() {
+    let github = PackageRef::GitHub {
+        github: qsc_project::GitHubRef {
+            owner: "microsoft".to_string(),
+            repo: "qsharp".to_string(),
+            r#ref: "v1.0.0".to_string(),
+            path: Some("library/fixed_point".to_string()),
+        },
+    };
+    let local = PackageRef::Path {
+        path: "/projects/dep".to_string(),
+    };
+    let sources = [("A.qs", "namespace A {}"), ("B.qs", "namespace B {}")];
+    let reversed = [sources[1], sources[0]];
+    let mut packages = FxHashMap::default();
+    packages.insert(key_for_package_ref(&github), package_info(&sources));
+    packages.insert(key_for_package_ref(&local), package_info(&reversed));
+    let package_graph_sources = PackageGraphSources {
+        root: package_info(&[]),
+        packages,
+    };
+
+    let mut provenance = provenance_for(&[]);
+    provenance.add_dependencies(&package_graph_sources);
+    assert_eq!(
+        provenance.dependencies[0].sha256,
+        provenance.dependencies[1].sha256
+    );
+    expect![[r#"
+        [
+          {
+            "package": {
+              "github": {
+                "owner": "microsoft",
+                "repo": "qsharp",
+                "ref": "v1.0.0",
+                "path": "library/fixed_point"
+              }
+            },
+            "sha256": "b4190cbe2145c5ad1771c57ef0345fead54c30161cc1a60c220b96056b2f20ed"
+          },
+          {
+            "package": {
+              "path": "/projects/dep"
+            },
+            "sha256": "b4190cbe2145c5ad1771c57ef0345fead54c30161cc1a60c220b96056b2f20ed"
+          }
+        ]"#]]
+    .assert_eq(&serde_json::to_string_pretty(&provenance.dependencies).expect("should serialize"));
+}
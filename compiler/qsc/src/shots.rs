@@ -0,0 +1,157 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! Runs the shots of a program on several threads at once.
+//!
+//! Compiled packages and runtime values are reference counted with `Rc`, so an [`Interpreter`]
+//! can't be shared between threads. Instead, each worker thread builds its own interpreter
+//! from the given factory and configures it with the settings of the interpreter the shots
+//! were requested from, so that each shot is simulated with the same noise, memory budget and
+//! simulator as a shot of [`Interpreter::run`]. Then it takes shots from a shared counter until
+//! all of them have run. Shot `i` is simulated with the seed `seed + i`, so the results don't
+//! depend on the number of threads. They differ from those of running the shots one after another
+//! with `run`, which seeds the simulator of every shot with the same seed. The output of each shot
+//! is captured in its own buffer and the shots are returned in order.
+
+#[cfg(test)]
+mod tests;
+
+use crate::interpret::{Error, Interpreter, SimulationConfig, Value};
+use qsc_eval::{noise::PauliNoise, output::CursorReceiver};
+use std::{
+    io::Cursor,
+    num::NonZeroUsize,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+};
+
+/// The output and result of a single shot.
+#[derive(Debug)]
+pub struct Shot<T> {
+    /// Everything the shot printed, such as messages and state dumps.
+    pub output: String,
+    /// The value the shot returned, converted on the thread that ran it, or its runtime errors.
+    pub result: Result<T, Vec<Error>>,
+}
+
+/// How the shots of a program are run.
+#[derive(Clone, Debug, Default)]
+pub struct ShotConfig {
+    /// The entry expression each shot runs, or `None` to run the entry point of the program.
+    pub entry_expr: Option<String>,
+    /// The Pauli noise of each shot, like the noise given to [`Interpreter::run`].
+    pub noise: Option<PauliNoise>,
+    /// The settings of the interpreter the shots were requested from, which are given to the
+    /// interpreter of each thread. Its quantum seed is the seed of the first shot.
+    pub simulation: SimulationConfig,
+}
+
+/// Runs `shots` shots of the program of the interpreters built by `make_interpreter`, as given
+/// by `config`, using at most `threads` threads. Each shot's return value is converted with
+/// `map_value` on the thread that ran it, since values can't be sent between threads.
+///
+/// # Errors
+///
+/// Returns the errors of the first interpreter that failed to build or to take the
+/// configuration, in which case no shots are returned.
+pub fn run_shots<T, F, M>(
+    shots: usize,
+    threads: NonZeroUsize,
+    config: &ShotConfig,
+    make_interpreter: F,
+    map_value: M,
+) -> Result<Vec<Shot<T>>, Vec<Error>>
+where
+    T: Send,
+    F: Fn() -> Result<Interpreter, Vec<Error>> + Sync,
+    M: Fn(Value) -> T + Sync,
+{
+    let build = || -> Result<Interpreter, Vec<Error>> {
+        let mut interpreter = make_interpreter()?;
+        interpreter.set_simulation_config(&config.simulation)?;
+        if let Some(entry_expr) = &config.entry_expr {
+            interpreter.set_entry_expr(entry_expr)?;
+        }
+        Ok(interpreter)
+    };
+
+    let next_shot = AtomicUsize::new(0);
+    let completed = Mutex::new(Vec::with_capacity(shots));
+    let build_errors = Mutex::new(None);
+
+    let worker = || {
+        let mut interpreter = match build() {
+            Ok(interpreter) => interpreter,
+            Err(errors) => {
+                build_errors
+                    .lock()
+                    .expect("lock should not be poisoned")
+                    .get_or_insert(errors);
+                // Stop the other workers from starting more shots.
+                next_shot.store(shots, Ordering::Relaxed);
+                return;
+            }
+        };
+
+        loop {
+            let index = next_shot.fetch_add(1, Ordering::Relaxed);
+            if index >= shots {
+                break;
+            }
+            let shot = run_shot(&mut interpreter, index, config, &map_value);
+            completed
+                .lock()
+                .expect("lock should not be poisoned")
+                .push((index, shot));
+        }
+    };
+
+    let threads = threads.get().min(shots);
+    thread::scope(|scope| {
+        for _ in 1..threads {
+            scope.spawn(worker);
+        }
+        if threads > 0 {
+            worker();
+        }
+    });
+
+    if let Some(errors) = build_errors
+        .into_inner()
+        .expect("lock should not be poisoned")
+    {
+        return Err(errors);
+    }
+
+    let mut completed = completed.into_inner().expect("lock should not be poisoned");
+    completed.sort_unstable_by_key(|(index, _)| *index);
+    Ok(completed.into_iter().map(|(_, shot)| shot).collect())
+}
+
+fn run_shot<T>(
+    interpreter: &mut Interpreter,
+    index: usize,
+    config: &ShotConfig,
+    map_value: impl Fn(Value) -> T,
+) -> Shot<T> {
+    let seed = config
+        .simulation
+        .quantum_seed
+        .map(|seed| seed.wrapping_add(index as u64));
+    let mut cursor = Cursor::new(Vec::new());
+    let result = interpreter
+        .run_seeded_shot(
+            &mut CursorReceiver::new(&mut cursor),
+            config.entry_expr.is_none(),
+            config.noise,
+            seed,
+        )
+        .map(map_value);
+    Shot {
+        output: String::from_utf8_lossy(&cursor.into_inner()).into_owned(),
+        result,
+    }
+}
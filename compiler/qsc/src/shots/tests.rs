@@ -0,0 +1,173 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{run_shots, ShotConfig};
+use crate::interpret::{Error, GenericReceiver, Interpreter, PauliNoise, SimulationConfig};
+use qsc_data_structures::{language_features::LanguageFeatures, target::TargetCapabilityFlags};
+use qsc_eval::noise::NoiseModel;
+use qsc_frontend::compile::SourceMap;
+use qsc_passes::PackageType;
+use std::{io, num::NonZeroUsize};
+
+const SOURCE: &str = "
+    namespace Test {
+        @EntryPoint()
+        operation Main() : Result[] {
+            use qs = Qubit[4];
+            ApplyToEach(H, qs);
+            let results = MResetEachZ(qs);
+            Message($\"{results}\");
+            results
+        }
+    }";
+
+fn interpreter(source: &str) -> Result<Interpreter, Vec<Error>> {
+    let (std_id, store) = crate::compile::package_store_with_stdlib(TargetCapabilityFlags::all());
+    Interpreter::new(
+        SourceMap::new([("test.qs".into(), source.into())], None),
+        PackageType::Exe,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::default(),
+        store,
+        &[(std_id, None)],
+    )
+}
+
+fn threads(count: usize) -> NonZeroUsize {
+    NonZeroUsize::new(count).expect("thread count should be nonzero")
+}
+
+fn seeded(seed: u64) -> ShotConfig {
+    ShotConfig {
+        simulation: SimulationConfig {
+            quantum_seed: Some(seed),
+            ..SimulationConfig::default()
+        },
+        ..ShotConfig::default()
+    }
+}
+
+#[test]
+fn results_do_not_depend_on_thread_count() {
+    let run = |count| {
+        run_shots(
+            10,
+            threads(count),
+            &seeded(42),
+            || interpreter(SOURCE),
+            |value| value.to_string(),
+        )
+        .expect("interpreters should build")
+        .into_iter()
+        .map(|shot| (shot.output, shot.result.expect("shot should succeed")))
+        .collect::<Vec<_>>()
+    };
+
+    let serial = run(1);
+    assert_eq!(serial.len(), 10);
+    for (output, result) in &serial {
+        assert_eq!(output.trim(), result);
+    }
+    assert_eq!(serial, run(3));
+    assert_eq!(serial, run(16));
+}
+
+#[test]
+fn noisy_shots_match_shots_run_one_after_another() {
+    let source = "
+        namespace Test {
+            @EntryPoint()
+            operation Main() : Result[] {
+                use qs = Qubit[4];
+                MResetEachZ(qs)
+            }
+        }";
+    let mut config = seeded(7);
+    config.noise = Some(PauliNoise::bit_flip(0.2).expect("bit flip noise should be valid"));
+    config.simulation.noise_model = NoiseModel {
+        measurement: 0.2,
+        ..NoiseModel::default()
+    };
+
+    let parallel = run_shots(
+        20,
+        threads(4),
+        &config,
+        || interpreter(source),
+        |value| value.to_string(),
+    )
+    .expect("interpreters should build")
+    .into_iter()
+    .map(|shot| shot.result.expect("shot should succeed"))
+    .collect::<Vec<_>>();
+
+    let mut interpreter = interpreter(source).expect("interpreter should build");
+    interpreter
+        .set_simulation_config(&config.simulation)
+        .expect("configuration should apply");
+    let mut sink = io::sink();
+    let sequential = (0..20)
+        .map(|index| {
+            interpreter
+                .run_seeded_shot(
+                    &mut GenericReceiver::new(&mut sink),
+                    true,
+                    config.noise,
+                    Some(7 + index),
+                )
+                .expect("shot should succeed")
+                .to_string()
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(parallel, sequential);
+    // Without noise, every qubit would be measured as `Zero`.
+    assert!(parallel.iter().any(|result| result.contains("One")));
+}
+
+#[test]
+fn runtime_errors_are_reported_per_shot() {
+    let shots = run_shots(
+        2,
+        threads(2),
+        &ShotConfig::default(),
+        || {
+            interpreter(
+                "namespace Test { @EntryPoint() operation Main() : Unit { fail \"oops\"; } }",
+            )
+        },
+        |value| value.to_string(),
+    )
+    .expect("interpreters should build");
+
+    assert_eq!(shots.len(), 2);
+    assert!(shots.iter().all(|shot| shot.result.is_err()));
+}
+
+#[test]
+fn build_errors_stop_the_run() {
+    let errors = run_shots(
+        5,
+        threads(2),
+        &ShotConfig::default(),
+        || interpreter("namespace Test { @EntryPoint() operation Main() : Unit { x } }"),
+        |value| value.to_string(),
+    )
+    .expect_err("interpreters should fail to build");
+
+    assert!(!errors.is_empty());
+}
+
+#[test]
+fn zero_shots_builds_no_interpreter() {
+    let shots = run_shots(
+        0,
+        threads(4),
+        &ShotConfig::default(),
+        || panic!("no interpreter should be built"),
+        |value| value.to_string(),
+    )
+    .expect("nothing should fail");
+
+    assert!(shots.is_empty());
+}
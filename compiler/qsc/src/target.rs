@@ -25,6 +25,17 @@ impl Profile {
     }
 }
 
+impl Profile {
+    /// Gets the most restrictive profile that supports all of the given capabilities.
+    #[must_use]
+    pub fn minimal_for(capabilities: TargetCapabilityFlags) -> Self {
+        [Self::Base, Self::AdaptiveRI, Self::AdaptiveRIF]
+            .into_iter()
+            .find(|profile| TargetCapabilityFlags::from(*profile).contains(capabilities))
+            .unwrap_or(Self::Unrestricted)
+    }
+}
+
 impl From<Profile> for TargetCapabilityFlags {
     fn from(value: Profile) -> Self {
         match value {
@@ -3,8 +3,8 @@
 
 use crate::{
     ast::{
-        Attr, Block, CallableDecl, Expr, FieldAssign, FunctorExpr, Ident, Item, Namespace, NodeId,
-        Package, Pat, Path, QubitInit, SpecDecl, Stmt, Ty, TyDef,
+        Attr, Block, CallableDecl, Expr, FieldAssign, FunctorExpr, Ident, Item, MatchArm,
+        Namespace, NodeId, Package, Pat, Path, QubitInit, SpecDecl, Stmt, Ty, TyDef,
     },
     mut_visit::{self, MutVisitor},
 };
@@ -82,6 +82,26 @@ impl MutVisitor for Assigner {
         mut_visit::walk_field_def(self, def);
     }
 
+    fn visit_enum_decl(&mut self, decl: &mut crate::ast::EnumDecl) {
+        self.assign(&mut decl.id);
+        mut_visit::walk_enum_decl(self, decl);
+    }
+
+    fn visit_const_decl(&mut self, decl: &mut crate::ast::ConstDecl) {
+        self.assign(&mut decl.id);
+        mut_visit::walk_const_decl(self, decl);
+    }
+
+    fn visit_class_decl(&mut self, decl: &mut crate::ast::ClassDecl) {
+        self.assign(&mut decl.id);
+        mut_visit::walk_class_decl(self, decl);
+    }
+
+    fn visit_variant_def(&mut self, def: &mut crate::ast::VariantDef) {
+        self.assign(&mut def.id);
+        mut_visit::walk_variant_def(self, def);
+    }
+
     fn visit_spec_decl(&mut self, decl: &mut SpecDecl) {
         self.assign(&mut decl.id);
         mut_visit::walk_spec_decl(self, decl);
@@ -117,6 +137,11 @@ impl MutVisitor for Assigner {
         mut_visit::walk_field_assign(self, assign);
     }
 
+    fn visit_match_arm(&mut self, arm: &mut MatchArm) {
+        self.assign(&mut arm.id);
+        mut_visit::walk_match_arm(self, arm);
+    }
+
     fn visit_pat(&mut self, pat: &mut Pat) {
         self.assign(&mut pat.id);
         mut_visit::walk_pat(self, pat);
@@ -7,7 +7,10 @@
 
 use indenter::{indented, Format, Indented};
 use num_bigint::BigInt;
-use qsc_data_structures::span::{Span, WithSpan};
+use qsc_data_structures::{
+    format_spec::FormatSpec,
+    span::{Span, WithSpan},
+};
 use std::{
     cmp::Ordering,
     fmt::{self, Display, Formatter, Write},
@@ -226,6 +229,8 @@ pub struct Item {
     pub doc: Rc<str>,
     /// The attributes.
     pub attrs: Box<[Box<Attr>]>,
+    /// The visibility modifier, if any.
+    pub visibility: Option<Visibility>,
     /// The item kind.
     pub kind: Box<ItemKind>,
 }
@@ -237,6 +242,7 @@ impl Default for Item {
             span: Span::default(),
             doc: "".into(),
             attrs: Box::default(),
+            visibility: None,
             kind: Box::default(),
         }
     }
@@ -259,11 +265,37 @@ impl Display for Item {
             write!(indent, "\n{attr}")?;
         }
 
+        if let Some(visibility) = &self.visibility {
+            write!(indent, "\n{visibility}")?;
+        }
+
         write!(indent, "\n{}", self.kind)?;
         Ok(())
     }
 }
 
+/// A visibility modifier.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Visibility {
+    /// The span.
+    pub span: Span,
+    /// The visibility kind.
+    pub kind: VisibilityKind,
+}
+
+impl Display for Visibility {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "Visibility {} ({:?})", self.span, self.kind)
+    }
+}
+
+/// A visibility kind.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum VisibilityKind {
+    /// `internal`, which keeps the item from being exported from its package.
+    Internal,
+}
+
 /// An item kind.
 #[derive(Clone, Debug, Default, PartialEq)]
 pub enum ItemKind {
@@ -278,6 +310,12 @@ pub enum ItemKind {
     Ty(Box<Ident>, Box<TyDef>),
     /// A `struct` declaration.
     Struct(Box<StructDecl>),
+    /// An `enum` declaration.
+    Enum(Box<EnumDecl>),
+    /// A `const` declaration.
+    Const(Box<ConstDecl>),
+    /// A `class` declaration, which is an alias for a combination of class constraints.
+    Class(Box<ClassDecl>),
     /// An export declaration
     ImportOrExport(ImportOrExportDecl),
 }
@@ -293,6 +331,9 @@ impl Display for ItemKind {
             },
             ItemKind::Ty(name, t) => write!(f, "New Type ({name}): {t}")?,
             ItemKind::Struct(s) => write!(f, "{s}")?,
+            ItemKind::Enum(e) => write!(f, "{e}")?,
+            ItemKind::Const(c) => write!(f, "{c}")?,
+            ItemKind::Class(c) => write!(f, "{c}")?,
             ItemKind::ImportOrExport(item) if item.is_export => write!(f, "Export ({item})")?,
             ItemKind::ImportOrExport(item) => write!(f, "Import ({item})")?,
         }
@@ -474,6 +515,202 @@ impl WithSpan for FieldDef {
     }
 }
 
+/// An enum definition.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct EnumDecl {
+    /// The node ID.
+    pub id: NodeId,
+    /// The span.
+    pub span: Span,
+    /// The name of the enum.
+    pub name: Box<Ident>,
+    /// The type parameters of the enum.
+    pub generics: Box<[TypeParameter]>,
+    /// The variants of the enum.
+    pub variants: Box<[Box<VariantDef>]>,
+}
+
+impl Display for EnumDecl {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut indent = set_indentation(indented(f), 0);
+        write!(indent, "Enum {} {} ({}):", self.id, self.span, self.name)?;
+        if !self.generics.is_empty() {
+            indent = set_indentation(indent, 1);
+            write!(indent, "\ngenerics:")?;
+            indent = set_indentation(indent, 2);
+            for param in &self.generics {
+                write!(indent, "\n{param}")?;
+            }
+            indent = set_indentation(indent, 0);
+        }
+        if self.variants.is_empty() {
+            write!(indent, " <empty>")?;
+        } else {
+            indent = set_indentation(indent, 1);
+            for variant in &self.variants {
+                write!(indent, "\n{variant}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl WithSpan for EnumDecl {
+    fn with_span(self, span: Span) -> Self {
+        Self { span, ..self }
+    }
+}
+
+/// A constant definition, whose value is computed at compile time.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct ConstDecl {
+    /// The node ID.
+    pub id: NodeId,
+    /// The span.
+    pub span: Span,
+    /// The name of the constant.
+    pub name: Box<Ident>,
+    /// The type of the constant.
+    pub ty: Box<Ty>,
+    /// The expression that computes the value of the constant.
+    pub value: Box<Expr>,
+}
+
+impl Display for ConstDecl {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut indent = set_indentation(indented(f), 0);
+        write!(indent, "Const {} {} ({}):", self.id, self.span, self.name)?;
+        indent = set_indentation(indent, 1);
+        write!(indent, "\ntype: {}", self.ty)?;
+        write!(indent, "\nvalue: {}", self.value)?;
+        Ok(())
+    }
+}
+
+impl WithSpan for ConstDecl {
+    fn with_span(self, span: Span) -> Self {
+        Self { span, ..self }
+    }
+}
+
+/// A class definition. A class combines other class constraints so that type parameters can be
+/// constrained by it, and it may take type parameters of its own and declare methods that every
+/// member type must implement. A type without methods to implement is a member exactly when all of
+/// the class's constraints hold.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct ClassDecl {
+    /// The node ID.
+    pub id: NodeId,
+    /// The span.
+    pub span: Span,
+    /// The name of the class.
+    pub name: Box<Ident>,
+    /// The type parameters of the class.
+    pub generics: Box<[TypeParameter]>,
+    /// The constraints that a type must satisfy to be a member of the class.
+    pub constraints: ClassConstraints,
+    /// The methods that a member of the class must implement. Each is declared without a body.
+    pub methods: Box<[Box<CallableDecl>]>,
+}
+
+impl ClassDecl {
+    /// The name of the type parameter that stands for the member type in the class's methods.
+    pub const SELF_PARAM: &'static str = "'Self";
+
+    /// The type parameters of every method of the class: the class's own type parameters,
+    /// followed by `'Self`, which is constrained by the class itself.
+    #[must_use]
+    pub fn method_generics(&self) -> Vec<TypeParameter> {
+        let parameters = self
+            .generics
+            .iter()
+            .map(|param| ConstraintParameter {
+                ty: Ty {
+                    id: param.ty.id,
+                    span: param.ty.span,
+                    kind: Box::new(TyKind::Param(param.clone())),
+                },
+            })
+            .collect();
+        let bound = ClassConstraint {
+            name: (*self.name).clone(),
+            parameters,
+        };
+        let mut generics = self.generics.to_vec();
+        generics.push(TypeParameter::new(
+            Ident {
+                id: self.id,
+                span: self.name.span,
+                name: Self::SELF_PARAM.into(),
+            },
+            ClassConstraints(Box::new([bound])),
+            self.name.span,
+        ));
+        generics
+    }
+}
+
+impl Display for ClassDecl {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut indent = set_indentation(indented(f), 0);
+        write!(indent, "Class {} {} ({})", self.id, self.span, self.name)?;
+        if !self.constraints.0.is_empty() {
+            write!(indent, ": {}", self.constraints)?;
+        }
+        indent = set_indentation(indent, 1);
+        if !self.generics.is_empty() {
+            write!(indent, "\ngenerics:")?;
+            indent = set_indentation(indent, 2);
+            for param in &self.generics {
+                write!(indent, "\n{param}")?;
+            }
+            indent = set_indentation(indent, 1);
+        }
+        for method in &self.methods {
+            write!(indent, "\n{method}")?;
+        }
+        Ok(())
+    }
+}
+
+impl WithSpan for ClassDecl {
+    fn with_span(self, span: Span) -> Self {
+        Self { span, ..self }
+    }
+}
+
+/// An enum variant definition.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct VariantDef {
+    /// The node ID.
+    pub id: NodeId,
+    /// The span.
+    pub span: Span,
+    /// The name of the variant.
+    pub name: Box<Ident>,
+    /// The type of the payload of the variant, if it has one.
+    pub ty: Option<Box<Ty>>,
+}
+
+impl Display for VariantDef {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.ty {
+            Some(ty) => write!(
+                f,
+                "VariantDef {} {} ({}): {ty}",
+                self.id, self.span, self.name
+            ),
+            None => write!(f, "VariantDef {} {} ({})", self.id, self.span, self.name),
+        }
+    }
+}
+
+impl WithSpan for VariantDef {
+    fn with_span(self, span: Span) -> Self {
+        Self { span, ..self }
+    }
+}
+
 /// A callable declaration header.
 #[derive(Clone, Debug, PartialEq)]
 pub struct CallableDecl {
@@ -669,6 +906,9 @@ impl WithSpan for Ty {
 pub enum TyKind {
     /// An array type.
     Array(Box<Ty>),
+    /// An array type with a length known at compile time, such as `Qubit[3]`. The length is only checked
+    /// at call sites and is erased when the type is converted, so later passes see an ordinary array.
+    SizedArray(Box<Ty>, usize),
     /// An arrow type: `->` for a function or `=>` for an operation.
     Arrow(CallableKind, Box<Ty>, Box<Ty>, Option<Box<FunctorExpr>>),
     /// An unspecified type, `_`, which may be inferred.
@@ -677,6 +917,8 @@ pub enum TyKind {
     Paren(Box<Ty>),
     /// A named type.
     Path(PathKind),
+    /// A named type applied to type arguments, such as `Option<Int>`.
+    App(PathKind, Box<[Ty]>),
     /// A type parameter.
     Param(TypeParameter),
     /// A tuple type.
@@ -691,6 +933,7 @@ impl Display for TyKind {
         let mut indent = set_indentation(indented(f), 0);
         match self {
             TyKind::Array(item) => write!(indent, "Array: {item}")?,
+            TyKind::SizedArray(item, len) => write!(indent, "Sized Array ({len}): {item}")?,
             TyKind::Arrow(ck, param, rtrn, functors) => {
                 write!(indent, "Arrow ({ck:?}):")?;
                 indent = set_indentation(indent, 1);
@@ -703,6 +946,13 @@ impl Display for TyKind {
             TyKind::Hole => write!(indent, "Hole")?,
             TyKind::Paren(t) => write!(indent, "Paren: {t}")?,
             TyKind::Path(p) => write!(indent, "Path: {p}")?,
+            TyKind::App(p, args) => {
+                write!(indent, "App: {p}")?;
+                indent = set_indentation(indent, 1);
+                for arg in args {
+                    write!(indent, "\n{arg}")?;
+                }
+            }
             TyKind::Param(name) => write!(indent, "Type Param: {name}")?,
             TyKind::Tuple(ts) => {
                 if ts.is_empty() {
@@ -895,6 +1145,8 @@ pub enum ExprKind {
     Lambda(CallableKind, Box<Pat>, Box<Expr>),
     /// A literal.
     Lit(Box<Lit>),
+    /// A match expression: `match a { Zero => b, One => c }`.
+    Match(Box<Expr>, Box<[Box<MatchArm>]>),
     /// Parentheses: `(a)`.
     Paren(Box<Expr>),
     /// A path: `a` or `a.b`.
@@ -942,6 +1194,7 @@ impl Display for ExprKind {
             ExprKind::Interpolate(components) => display_interpolate(indent, components)?,
             ExprKind::Lambda(kind, param, expr) => display_lambda(indent, *kind, param, expr)?,
             ExprKind::Lit(lit) => write!(indent, "Lit: {lit}")?,
+            ExprKind::Match(expr, arms) => display_match(indent, expr, arms)?,
             ExprKind::Paren(e) => write!(indent, "Paren: {e}")?,
             ExprKind::Path(p) => write!(indent, "Path: {p}")?,
             ExprKind::Range(start, step, end) => {
@@ -1107,7 +1360,10 @@ fn display_interpolate(
     indent = set_indentation(indent, 1);
     for component in components {
         match component {
-            StringComponent::Expr(expr) => write!(indent, "\nExpr: {expr}")?,
+            StringComponent::Expr(expr, None) => write!(indent, "\nExpr: {expr}")?,
+            StringComponent::Expr(expr, Some(format)) => {
+                write!(indent, "\nExpr (:{format}): {expr}")?;
+            }
             StringComponent::Lit(str) => write!(indent, "\nLit: {str:?}")?,
         }
     }
@@ -1168,6 +1424,20 @@ fn display_repeat(
     Ok(())
 }
 
+fn display_match(
+    mut indent: Indented<Formatter>,
+    expr: &Expr,
+    arms: &[Box<MatchArm>],
+) -> fmt::Result {
+    write!(indent, "Match:")?;
+    indent = set_indentation(indent, 1);
+    write!(indent, "\n{expr}")?;
+    for arm in arms {
+        write!(indent, "\n{arm}")?;
+    }
+    Ok(())
+}
+
 fn display_struct(
     mut indent: Indented<Formatter>,
     name: &PathKind,
@@ -1261,11 +1531,57 @@ impl Display for FieldAssign {
     }
 }
 
+/// An arm of a match expression: `pattern => body`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MatchArm {
+    /// The node ID.
+    pub id: NodeId,
+    /// The span.
+    pub span: Span,
+    /// The pattern that the matched value is compared with.
+    pub pat: Box<MatchPat>,
+    /// The expression that is evaluated if the pattern matches.
+    pub body: Box<Expr>,
+}
+
+impl Display for MatchArm {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut indent = set_indentation(indented(f), 0);
+        write!(indent, "MatchArm {} {}:", self.id, self.span)?;
+        indent = set_indentation(indent, 1);
+        write!(indent, "\n{}", self.pat)?;
+        write!(indent, "\n{}", self.body)?;
+        Ok(())
+    }
+}
+
+/// The pattern of a match arm.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MatchPat {
+    /// A literal, which matches values that are equal to it: `Zero`, `PauliX`, `true` or `3`.
+    Lit(Box<Expr>),
+    /// An enum variant, which matches values of that variant and binds their payload: `Circle(r)`
+    /// or `Empty()`.
+    Variant(Box<Path>, Box<Pat>),
+    /// A pattern that matches any value and binds it: `a`, `_` or `(a, b)`.
+    Bind(Box<Pat>),
+}
+
+impl Display for MatchPat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            MatchPat::Lit(expr) => write!(f, "{expr}"),
+            MatchPat::Variant(path, pat) => write!(f, "Variant ({path}): {pat}"),
+            MatchPat::Bind(pat) => write!(f, "{pat}"),
+        }
+    }
+}
+
 /// An interpolated string component.
 #[derive(Clone, Debug, PartialEq)]
 pub enum StringComponent {
-    /// An expression.
-    Expr(Box<Expr>),
+    /// An expression, with an optional format specifier.
+    Expr(Box<Expr>, Option<FormatSpec>),
     /// A string literal.
     Lit(Rc<str>),
 }
@@ -1807,6 +2123,8 @@ pub enum UnOp {
     Pos,
     /// Unwrap a user-defined type: `!`.
     Unwrap,
+    /// Propagate a failed variant of an enum: `?`.
+    Propagate,
 }
 
 impl Display for UnOp {
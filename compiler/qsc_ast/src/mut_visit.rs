@@ -2,10 +2,11 @@
 // Licensed under the MIT License.
 
 use crate::ast::{
-    Attr, Block, CallableBody, CallableDecl, Expr, ExprKind, FieldAccess, FieldAssign, FieldDef,
-    FunctorExpr, FunctorExprKind, Ident, Item, ItemKind, Namespace, Package, Pat, PatKind, Path,
-    PathKind, QubitInit, QubitInitKind, SpecBody, SpecDecl, Stmt, StmtKind, StringComponent,
-    StructDecl, TopLevelNode, Ty, TyDef, TyDefKind, TyKind, TypeParameter,
+    Attr, Block, CallableBody, CallableDecl, ClassDecl, ConstDecl, EnumDecl, Expr, ExprKind,
+    FieldAccess, FieldAssign, FieldDef, FunctorExpr, FunctorExprKind, Ident, Item, ItemKind,
+    MatchArm, MatchPat, Namespace, Package, Pat, PatKind, Path, PathKind, QubitInit, QubitInitKind,
+    SpecBody, SpecDecl, Stmt, StmtKind, StringComponent, StructDecl, TopLevelNode, Ty, TyDef,
+    TyDefKind, TyKind, TypeParameter, VariantDef,
 };
 use qsc_data_structures::span::Span;
 
@@ -42,6 +43,22 @@ pub trait MutVisitor: Sized {
         walk_field_def(self, def);
     }
 
+    fn visit_enum_decl(&mut self, decl: &mut EnumDecl) {
+        walk_enum_decl(self, decl);
+    }
+
+    fn visit_const_decl(&mut self, decl: &mut ConstDecl) {
+        walk_const_decl(self, decl);
+    }
+
+    fn visit_class_decl(&mut self, decl: &mut ClassDecl) {
+        walk_class_decl(self, decl);
+    }
+
+    fn visit_variant_def(&mut self, def: &mut VariantDef) {
+        walk_variant_def(self, def);
+    }
+
     fn visit_spec_decl(&mut self, decl: &mut SpecDecl) {
         walk_spec_decl(self, decl);
     }
@@ -70,6 +87,10 @@ pub trait MutVisitor: Sized {
         walk_field_assign(self, assign);
     }
 
+    fn visit_match_arm(&mut self, arm: &mut MatchArm) {
+        walk_match_arm(self, arm);
+    }
+
     fn visit_pat(&mut self, pat: &mut Pat) {
         walk_pat(self, pat);
     }
@@ -115,6 +136,9 @@ pub fn walk_namespace(vis: &mut impl MutVisitor, namespace: &mut Namespace) {
 pub fn walk_item(vis: &mut impl MutVisitor, item: &mut Item) {
     vis.visit_span(&mut item.span);
     item.attrs.iter_mut().for_each(|a| vis.visit_attr(a));
+    item.visibility
+        .iter_mut()
+        .for_each(|v| vis.visit_span(&mut v.span));
 
     match &mut *item.kind {
         ItemKind::Callable(decl) => vis.visit_callable_decl(decl),
@@ -128,6 +152,9 @@ pub fn walk_item(vis: &mut impl MutVisitor, item: &mut Item) {
             vis.visit_ty_def(def);
         }
         ItemKind::Struct(decl) => vis.visit_struct_decl(decl),
+        ItemKind::Enum(decl) => vis.visit_enum_decl(decl),
+        ItemKind::Const(decl) => vis.visit_const_decl(decl),
+        ItemKind::Class(decl) => vis.visit_class_decl(decl),
         ItemKind::ImportOrExport(export) => {
             vis.visit_span(&mut export.span);
             for item in &mut *export.items {
@@ -199,6 +226,57 @@ pub fn walk_field_def(vis: &mut impl MutVisitor, def: &mut FieldDef) {
     vis.visit_ty(&mut def.ty);
 }
 
+pub fn walk_const_decl(vis: &mut impl MutVisitor, decl: &mut ConstDecl) {
+    vis.visit_span(&mut decl.span);
+    vis.visit_ident(&mut decl.name);
+    vis.visit_ty(&mut decl.ty);
+    vis.visit_expr(&mut decl.value);
+}
+
+pub fn walk_class_decl(vis: &mut impl MutVisitor, decl: &mut ClassDecl) {
+    vis.visit_span(&mut decl.span);
+    vis.visit_ident(&mut decl.name);
+    decl.generics
+        .iter_mut()
+        .for_each(|p| vis.visit_ident(&mut p.ty));
+    decl.constraints.0.iter_mut().for_each(|b| {
+        vis.visit_ident(&mut b.name);
+        b.parameters
+            .iter_mut()
+            .for_each(|crate::ast::ConstraintParameter { ty, .. }| {
+                vis.visit_ty(ty);
+            });
+    });
+    decl.methods
+        .iter_mut()
+        .for_each(|m| vis.visit_callable_decl(m));
+}
+
+pub fn walk_enum_decl(vis: &mut impl MutVisitor, decl: &mut EnumDecl) {
+    vis.visit_span(&mut decl.span);
+    vis.visit_ident(&mut decl.name);
+    decl.generics.iter_mut().for_each(|p| {
+        vis.visit_ident(&mut p.ty);
+        p.constraints.0.iter_mut().for_each(|b| {
+            vis.visit_ident(&mut b.name);
+            b.parameters
+                .iter_mut()
+                .for_each(|crate::ast::ConstraintParameter { ty, .. }| {
+                    vis.visit_ty(ty);
+                });
+        });
+    });
+    decl.variants
+        .iter_mut()
+        .for_each(|v| vis.visit_variant_def(v));
+}
+
+pub fn walk_variant_def(vis: &mut impl MutVisitor, def: &mut VariantDef) {
+    vis.visit_span(&mut def.span);
+    vis.visit_ident(&mut def.name);
+    def.ty.iter_mut().for_each(|t| vis.visit_ty(t));
+}
+
 pub fn walk_spec_decl(vis: &mut impl MutVisitor, decl: &mut SpecDecl) {
     vis.visit_span(&mut decl.span);
 
@@ -228,7 +306,7 @@ pub fn walk_ty(vis: &mut impl MutVisitor, ty: &mut Ty) {
     vis.visit_span(&mut ty.span);
 
     match &mut *ty.kind {
-        TyKind::Array(item) => vis.visit_ty(item),
+        TyKind::Array(item) | TyKind::SizedArray(item, _) => vis.visit_ty(item),
         TyKind::Arrow(_, lhs, rhs, functors) => {
             vis.visit_ty(lhs);
             vis.visit_ty(rhs);
@@ -252,6 +330,10 @@ pub fn walk_ty(vis: &mut impl MutVisitor, ty: &mut Ty) {
             vis.visit_ident(ty);
         }
         TyKind::Path(path) => vis.visit_path_kind(path),
+        TyKind::App(path, args) => {
+            vis.visit_path_kind(path);
+            args.iter_mut().for_each(|t| vis.visit_ty(t));
+        }
         TyKind::Tuple(tys) => tys.iter_mut().for_each(|t| vis.visit_ty(t)),
     }
 }
@@ -333,7 +415,7 @@ pub fn walk_expr(vis: &mut impl MutVisitor, expr: &mut Expr) {
         ExprKind::Interpolate(components) => {
             for component in components.iter_mut() {
                 match component {
-                    StringComponent::Expr(expr) => vis.visit_expr(expr.as_mut()),
+                    StringComponent::Expr(expr, _) => vis.visit_expr(expr.as_mut()),
                     StringComponent::Lit(_) => {}
                 }
             }
@@ -342,6 +424,10 @@ pub fn walk_expr(vis: &mut impl MutVisitor, expr: &mut Expr) {
             vis.visit_pat(pat);
             vis.visit_expr(expr);
         }
+        ExprKind::Match(expr, arms) => {
+            vis.visit_expr(expr);
+            arms.iter_mut().for_each(|a| vis.visit_match_arm(a));
+        }
         ExprKind::Paren(expr) | ExprKind::Return(expr) | ExprKind::UnOp(_, expr) => {
             vis.visit_expr(expr);
         }
@@ -381,6 +467,19 @@ pub fn walk_field_assign(vis: &mut impl MutVisitor, assign: &mut FieldAssign) {
     vis.visit_expr(&mut assign.value);
 }
 
+pub fn walk_match_arm(vis: &mut impl MutVisitor, arm: &mut MatchArm) {
+    vis.visit_span(&mut arm.span);
+    match &mut *arm.pat {
+        MatchPat::Lit(expr) => vis.visit_expr(expr),
+        MatchPat::Variant(path, pat) => {
+            vis.visit_path(path);
+            vis.visit_pat(pat);
+        }
+        MatchPat::Bind(pat) => vis.visit_pat(pat),
+    }
+    vis.visit_expr(&mut arm.body);
+}
+
 pub fn walk_pat(vis: &mut impl MutVisitor, pat: &mut Pat) {
     vis.visit_span(&mut pat.span);
 
@@ -3,8 +3,8 @@
 
 use crate::{
     ast::{
-        Attr, Block, CallableDecl, Expr, FieldAssign, FunctorExpr, Ident, Item, Namespace, NodeId,
-        Package, Pat, Path, QubitInit, SpecDecl, Stmt, Ty, TyDef,
+        Attr, Block, CallableDecl, Expr, FieldAssign, FunctorExpr, Ident, Item, MatchArm,
+        Namespace, NodeId, Package, Pat, Path, QubitInit, SpecDecl, Stmt, Ty, TyDef,
     },
     visit::{self, Visitor},
 };
@@ -69,6 +69,26 @@ impl Visitor<'_> for Validator {
         visit::walk_field_def(self, def);
     }
 
+    fn visit_enum_decl(&mut self, decl: &'_ crate::ast::EnumDecl) {
+        self.check(decl.id, decl);
+        visit::walk_enum_decl(self, decl);
+    }
+
+    fn visit_const_decl(&mut self, decl: &'_ crate::ast::ConstDecl) {
+        self.check(decl.id, decl);
+        visit::walk_const_decl(self, decl);
+    }
+
+    fn visit_class_decl(&mut self, decl: &'_ crate::ast::ClassDecl) {
+        self.check(decl.id, decl);
+        visit::walk_class_decl(self, decl);
+    }
+
+    fn visit_variant_def(&mut self, def: &'_ crate::ast::VariantDef) {
+        self.check(def.id, def);
+        visit::walk_variant_def(self, def);
+    }
+
     fn visit_spec_decl(&mut self, decl: &SpecDecl) {
         self.check(decl.id, decl);
         visit::walk_spec_decl(self, decl);
@@ -104,6 +124,11 @@ impl Visitor<'_> for Validator {
         visit::walk_field_assign(self, assign);
     }
 
+    fn visit_match_arm(&mut self, arm: &MatchArm) {
+        self.check(arm.id, arm);
+        visit::walk_match_arm(self, arm);
+    }
+
     fn visit_pat(&mut self, pat: &Pat) {
         self.check(pat.id, pat);
         visit::walk_pat(self, pat);
@@ -2,10 +2,11 @@
 // Licensed under the MIT License.
 
 use crate::ast::{
-    Attr, Block, CallableBody, CallableDecl, Expr, ExprKind, FieldAccess, FieldAssign, FieldDef,
-    FunctorExpr, FunctorExprKind, Ident, Item, ItemKind, Namespace, Package, Pat, PatKind, Path,
-    PathKind, QubitInit, QubitInitKind, SpecBody, SpecDecl, Stmt, StmtKind, StringComponent,
-    StructDecl, TopLevelNode, Ty, TyDef, TyDefKind, TyKind, TypeParameter,
+    Attr, Block, CallableBody, CallableDecl, ClassDecl, ConstDecl, EnumDecl, Expr, ExprKind,
+    FieldAccess, FieldAssign, FieldDef, FunctorExpr, FunctorExprKind, Ident, Item, ItemKind,
+    MatchArm, MatchPat, Namespace, Package, Pat, PatKind, Path, PathKind, QubitInit, QubitInitKind,
+    SpecBody, SpecDecl, Stmt, StmtKind, StringComponent, StructDecl, TopLevelNode, Ty, TyDef,
+    TyDefKind, TyKind, TypeParameter, VariantDef,
 };
 
 pub trait Visitor<'a>: Sized {
@@ -41,6 +42,22 @@ pub trait Visitor<'a>: Sized {
         walk_field_def(self, def);
     }
 
+    fn visit_enum_decl(&mut self, decl: &'a EnumDecl) {
+        walk_enum_decl(self, decl);
+    }
+
+    fn visit_const_decl(&mut self, decl: &'a ConstDecl) {
+        walk_const_decl(self, decl);
+    }
+
+    fn visit_class_decl(&mut self, decl: &'a ClassDecl) {
+        walk_class_decl(self, decl);
+    }
+
+    fn visit_variant_def(&mut self, def: &'a VariantDef) {
+        walk_variant_def(self, def);
+    }
+
     fn visit_spec_decl(&mut self, decl: &'a SpecDecl) {
         walk_spec_decl(self, decl);
     }
@@ -69,6 +86,10 @@ pub trait Visitor<'a>: Sized {
         walk_field_assign(self, assign);
     }
 
+    fn visit_match_arm(&mut self, arm: &'a MatchArm) {
+        walk_match_arm(self, arm);
+    }
+
     fn visit_pat(&mut self, pat: &'a Pat) {
         walk_pat(self, pat);
     }
@@ -119,6 +140,9 @@ pub fn walk_item<'a>(vis: &mut impl Visitor<'a>, item: &'a Item) {
             vis.visit_ty_def(def);
         }
         ItemKind::Struct(decl) => vis.visit_struct_decl(decl),
+        ItemKind::Enum(decl) => vis.visit_enum_decl(decl),
+        ItemKind::Const(decl) => vis.visit_const_decl(decl),
+        ItemKind::Class(decl) => vis.visit_class_decl(decl),
         ItemKind::ImportOrExport(decl) => {
             for item in &decl.items {
                 vis.visit_path_kind(&item.path);
@@ -179,6 +203,47 @@ pub fn walk_field_def<'a>(vis: &mut impl Visitor<'a>, def: &'a FieldDef) {
     vis.visit_ty(&def.ty);
 }
 
+pub fn walk_const_decl<'a>(vis: &mut impl Visitor<'a>, decl: &'a ConstDecl) {
+    vis.visit_ident(&decl.name);
+    vis.visit_ty(&decl.ty);
+    vis.visit_expr(&decl.value);
+}
+
+pub fn walk_class_decl<'a>(vis: &mut impl Visitor<'a>, decl: &'a ClassDecl) {
+    vis.visit_ident(&decl.name);
+    decl.generics.iter().for_each(|p| vis.visit_ident(&p.ty));
+    decl.constraints.0.iter().for_each(|b| {
+        vis.visit_ident(&b.name);
+        b.parameters
+            .iter()
+            .for_each(|crate::ast::ConstraintParameter { ty, .. }| {
+                vis.visit_ty(ty);
+            });
+    });
+    decl.methods.iter().for_each(|m| vis.visit_callable_decl(m));
+}
+
+pub fn walk_enum_decl<'a>(vis: &mut impl Visitor<'a>, decl: &'a EnumDecl) {
+    vis.visit_ident(&decl.name);
+    decl.generics.iter().for_each(|p| {
+        vis.visit_ident(&p.ty);
+        p.constraints.0.iter().for_each(|b| {
+            vis.visit_ident(&b.name);
+            b.parameters
+                .iter()
+                .for_each(|crate::ast::ConstraintParameter { ty, .. }| {
+                    vis.visit_ty(ty);
+                });
+        });
+    });
+    decl.variants.iter().for_each(|v| vis.visit_variant_def(v));
+}
+
+pub fn walk_variant_def<'a>(vis: &mut impl Visitor<'a>, def: &'a VariantDef) {
+    vis.visit_ident(&def.name);
+    def.ty.iter().for_each(|t| vis.visit_ty(t));
+}
+
 pub fn walk_spec_decl<'a>(vis: &mut impl Visitor<'a>, decl: &'a SpecDecl) {
     match &decl.body {
         SpecBody::Gen(_) => {}
@@ -202,7 +267,7 @@ pub fn walk_functor_expr<'a>(vis: &mut impl Visitor<'a>, expr: &'a FunctorExpr)
 
 pub fn walk_ty<'a>(vis: &mut impl Visitor<'a>, ty: &'a Ty) {
     match &*ty.kind {
-        TyKind::Array(item) => vis.visit_ty(item),
+        TyKind::Array(item) | TyKind::SizedArray(item, _) => vis.visit_ty(item),
         TyKind::Arrow(_, lhs, rhs, functors) => {
             vis.visit_ty(lhs);
             vis.visit_ty(rhs);
@@ -211,6 +276,10 @@ pub fn walk_ty<'a>(vis: &mut impl Visitor<'a>, ty: &'a Ty) {
         TyKind::Hole | TyKind::Err => {}
         TyKind::Paren(ty) => vis.visit_ty(ty),
         TyKind::Path(path) => vis.visit_path_kind(path),
+        TyKind::App(path, args) => {
+            vis.visit_path_kind(path);
+            args.iter().for_each(|t| vis.visit_ty(t));
+        }
         TyKind::Param(TypeParameter {
             ty,
             constraints: bounds,
@@ -303,7 +372,7 @@ pub fn walk_expr<'a>(vis: &mut impl Visitor<'a>, expr: &'a Expr) {
         ExprKind::Interpolate(components) => {
             for component in components.as_ref() {
                 match component {
-                    StringComponent::Expr(expr) => vis.visit_expr(expr.as_ref()),
+                    StringComponent::Expr(expr, _) => vis.visit_expr(expr.as_ref()),
                     StringComponent::Lit(_) => {}
                 }
             }
@@ -312,6 +381,10 @@ pub fn walk_expr<'a>(vis: &mut impl Visitor<'a>, expr: &'a Expr) {
             vis.visit_pat(pat);
             vis.visit_expr(expr);
         }
+        ExprKind::Match(expr, arms) => {
+            vis.visit_expr(expr);
+            arms.iter().for_each(|a| vis.visit_match_arm(a));
+        }
         ExprKind::Paren(expr) | ExprKind::Return(expr) | ExprKind::UnOp(_, expr) => {
             vis.visit_expr(expr);
         }
@@ -350,6 +423,18 @@ pub fn walk_field_assign<'a>(vis: &mut impl Visitor<'a>, assign: &'a FieldAssign
     vis.visit_expr(&assign.value);
 }
 
+pub fn walk_match_arm<'a>(vis: &mut impl Visitor<'a>, arm: &'a MatchArm) {
+    match &*arm.pat {
+        MatchPat::Lit(expr) => vis.visit_expr(expr),
+        MatchPat::Variant(path, pat) => {
+            vis.visit_path(path);
+            vis.visit_pat(pat);
+        }
+        MatchPat::Bind(pat) => vis.visit_pat(pat),
+    }
+    vis.visit_expr(&arm.body);
+}
+
 pub fn walk_pat<'a>(vis: &mut impl Visitor<'a>, pat: &'a Pat) {
     match &*pat.kind {
         PatKind::Bind(name, ty) => {
@@ -7,15 +7,32 @@ mod instruction_tests;
 #[cfg(test)]
 mod tests;
 
+pub mod bitcode;
+pub mod determinism;
+mod output_metadata;
+pub mod source_metadata;
+
+pub use qsc_partial_eval::{Measurements, Messages, OutputRecording, PartialEvalConfig, Warning};
+pub use qsc_rir::rir::{GateSet, NamingScheme, PeepholeConfig, ProgramStats, QubitTermination};
+
+use qsc_data_structures::fx::FxHashSet;
 use qsc_data_structures::target::TargetCapabilityFlags;
 use qsc_eval::val::Value;
+use qsc_fir::fir::{Global, PackageStoreLookup};
 use qsc_lowerer::map_hir_package_to_fir;
 use qsc_partial_eval::{partially_evaluate, partially_evaluate_call, ProgramEntry};
 use qsc_rca::PackageStoreComputeProperties;
 use qsc_rir::{
-    passes::check_and_transform,
+    passes::{check_and_transform, check_and_transform_with_observer, transform_to_ssa},
     rir::{self, ConditionCode, FcmpConditionCode, Program},
-    utils::get_all_block_successors,
+    utils::{
+        build_predecessors_map, for_each_operand, for_each_operand_mut, get_all_block_successors,
+    },
+};
+use std::{
+    borrow::Cow,
+    fmt::{self, Display, Formatter},
+    rc::Rc,
 };
 
 fn lower_store(package_store: &qsc_frontend::compile::PackageStore) -> qsc_fir::fir::PackageStore {
@@ -45,7 +62,14 @@ pub fn fir_to_rir(
     compute_properties: Option<PackageStoreComputeProperties>,
     entry: &ProgramEntry,
 ) -> Result<(Program, Program), qsc_partial_eval::Error> {
-    let mut program = get_rir_from_compilation(fir_store, compute_properties, entry, capabilities)?;
+    let mut program = get_rir_from_compilation(
+        fir_store,
+        compute_properties,
+        entry,
+        capabilities,
+        PartialEvalConfig::default(),
+        &mut Vec::new(),
+    )?;
     let orig = program.clone();
     check_and_transform(&mut program);
     Ok((orig, program))
@@ -58,11 +82,173 @@ pub fn fir_to_qir(
     compute_properties: Option<PackageStoreComputeProperties>,
     entry: &ProgramEntry,
 ) -> Result<String, qsc_partial_eval::Error> {
-    let mut program = get_rir_from_compilation(fir_store, compute_properties, entry, capabilities)?;
-    check_and_transform(&mut program);
+    fir_to_qir_with_options(
+        fir_store,
+        capabilities,
+        compute_properties,
+        entry,
+        QirOptions::default(),
+    )
+}
+
+/// Options that control how QIR is generated.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct QirOptions {
+    /// How variables and blocks are named in the emitted QIR.
+    pub naming: NamingScheme,
+    /// Options for the partial evaluation that produces the program.
+    pub partial_eval: PartialEvalConfig,
+    /// The gate-level peephole optimizations applied to the program before QIR is generated.
+    pub peephole: PeepholeConfig,
+    /// The gates the target supports, which calls to other gates are decomposed into, or `None` to emit every gate
+    /// as it is.
+    pub gate_set: Option<GateSet>,
+    /// How qubits that are not measured or reset by the end of the program are terminated.
+    pub qubit_termination: QubitTermination,
+    /// Whether the IDs of qubits are reused once the qubits are reset, which reduces the number of qubits the QIR
+    /// requires at the cost of rescheduling its instructions. This is ignored for targets without the `QubitReset`
+    /// capability, such as base profile targets, since only a reset returns a used qubit to a known state.
+    pub reuse_qubits: bool,
+    /// Whether the QIR describes where each recorded output value sits within the return value of the entry point,
+    /// in the `qsharp.output_positions` named metadata. Modules with more than one entry point don't describe them.
+    pub output_positions: bool,
+}
+
+impl QirOptions {
+    /// The configuration of partial evaluation, which emits `Exp` as multi-qubit Pauli rotations when the gate set
+    /// includes them.
+    fn partial_eval_config(&self) -> PartialEvalConfig {
+        PartialEvalConfig {
+            pauli_rotations: self.partial_eval.pauli_rotations
+                || self
+                    .gate_set
+                    .is_some_and(|gate_set| gate_set.contains(GateSet::EXP)),
+            ..self.partial_eval
+        }
+    }
+}
+
+/// converts the given sources to QIR using the given language features and options.
+pub fn fir_to_qir_with_options(
+    fir_store: &qsc_fir::fir::PackageStore,
+    capabilities: TargetCapabilityFlags,
+    compute_properties: Option<PackageStoreComputeProperties>,
+    entry: &ProgramEntry,
+    options: QirOptions,
+) -> Result<String, qsc_partial_eval::Error> {
+    fir_to_qir_recording_passes(
+        fir_store,
+        capabilities,
+        compute_properties,
+        entry,
+        options,
+        &mut Vec::new(),
+        &mut Vec::new(),
+    )
+}
+
+/// converts the given sources to QIR using the given language features and options, appending the names of the
+/// RIR transformation passes that ran, in order, to `passes` and any partial evaluation warnings to `warnings`.
+pub fn fir_to_qir_recording_passes(
+    fir_store: &qsc_fir::fir::PackageStore,
+    capabilities: TargetCapabilityFlags,
+    compute_properties: Option<PackageStoreComputeProperties>,
+    entry: &ProgramEntry,
+    options: QirOptions,
+    passes: &mut Vec<String>,
+    warnings: &mut Vec<Warning>,
+) -> Result<String, qsc_partial_eval::Error> {
+    let program = get_transformed_program(
+        fir_store,
+        capabilities,
+        compute_properties,
+        entry,
+        options,
+        passes,
+        warnings,
+    )?;
     Ok(ToQir::<String>::to_qir(&program, &program))
 }
 
+/// converts the given sources to LLVM bitcode using the given language features and options.
+pub fn fir_to_qir_bitcode(
+    fir_store: &qsc_fir::fir::PackageStore,
+    capabilities: TargetCapabilityFlags,
+    compute_properties: Option<PackageStoreComputeProperties>,
+    entry: &ProgramEntry,
+    options: QirOptions,
+) -> Result<Vec<u8>, qsc_partial_eval::Error> {
+    let program = get_transformed_program(
+        fir_store,
+        capabilities,
+        compute_properties,
+        entry,
+        options,
+        &mut Vec::new(),
+        &mut Vec::new(),
+    )?;
+    Ok(bitcode::write_program(&program))
+}
+
+fn get_transformed_program(
+    fir_store: &qsc_fir::fir::PackageStore,
+    capabilities: TargetCapabilityFlags,
+    compute_properties: Option<PackageStoreComputeProperties>,
+    entry: &ProgramEntry,
+    options: QirOptions,
+    passes: &mut Vec<String>,
+    warnings: &mut Vec<Warning>,
+) -> Result<rir::Program, qsc_partial_eval::Error> {
+    let mut program = get_configured_program(
+        fir_store,
+        capabilities,
+        compute_properties,
+        entry,
+        options,
+        warnings,
+    )?;
+    transform_program(&mut program, options, &mut |name, _| {
+        passes.push(name.to_string());
+    });
+    Ok(program)
+}
+
+/// Partially evaluates the entry point into a program configured with the given options, before any pass has run.
+fn get_configured_program(
+    fir_store: &qsc_fir::fir::PackageStore,
+    capabilities: TargetCapabilityFlags,
+    compute_properties: Option<PackageStoreComputeProperties>,
+    entry: &ProgramEntry,
+    options: QirOptions,
+    warnings: &mut Vec<Warning>,
+) -> Result<rir::Program, qsc_partial_eval::Error> {
+    let mut program = get_rir_from_compilation(
+        fir_store,
+        compute_properties,
+        entry,
+        capabilities,
+        options.partial_eval_config(),
+        warnings,
+    )?;
+    program.config.peephole = options.peephole;
+    program.config.gate_set = options.gate_set;
+    program.config.qubit_termination = options.qubit_termination;
+    program.config.reuse_qubits = options.reuse_qubits;
+    Ok(program)
+}
+
+/// Runs the RIR passes on a program from `get_configured_program`, invoking `observer` after each pass, then applies
+/// the options that only affect how the program is emitted.
+fn transform_program(
+    program: &mut rir::Program,
+    options: QirOptions,
+    observer: &mut dyn FnMut(&str, &rir::Program),
+) {
+    check_and_transform_with_observer(program, observer);
+    program.config.naming = options.naming;
+    program.config.emit_output_positions = options.output_positions;
+}
+
 /// converts the given callable to QIR using the given arguments and language features.
 pub fn fir_to_qir_from_callable(
     fir_store: &qsc_fir::fir::PackageStore,
@@ -76,24 +262,128 @@ pub fn fir_to_qir_from_callable(
         analyzer.analyze_all()
     });
 
-    let mut program =
-        partially_evaluate_call(fir_store, &compute_properties, callable, args, capabilities)?;
+    let mut program = partially_evaluate_call(
+        fir_store,
+        &compute_properties,
+        callable,
+        args,
+        capabilities,
+        PartialEvalConfig::default(),
+        &mut Vec::new(),
+    )?;
     check_and_transform(&mut program);
     Ok(ToQir::<String>::to_qir(&program, &program))
 }
 
+/// Counts the RIR instructions generated for the given callable using the given arguments, after the RIR passes
+/// that run before QIR generation, in the SSA form the QIR is emitted in.
+pub fn count_instructions_from_callable(
+    fir_store: &qsc_fir::fir::PackageStore,
+    capabilities: TargetCapabilityFlags,
+    compute_properties: &PackageStoreComputeProperties,
+    callable: qsc_fir::fir::StoreItemId,
+    args: Value,
+) -> Result<usize, qsc_partial_eval::Error> {
+    let mut program = partially_evaluate_call(
+        fir_store,
+        compute_properties,
+        callable,
+        args,
+        capabilities,
+        PartialEvalConfig::default(),
+        &mut Vec::new(),
+    )?;
+    check_and_transform(&mut program);
+    let program = prepare_for_emission(&program);
+    Ok(program.blocks.values().map(|block| block.0.len()).sum())
+}
+
+/// converts the given callables, which must take no input, to a single QIR module with an entry point for each
+/// callable, named after the callable.
+pub fn fir_to_qir_from_entry_points(
+    fir_store: &qsc_fir::fir::PackageStore,
+    capabilities: TargetCapabilityFlags,
+    compute_properties: Option<PackageStoreComputeProperties>,
+    entry_points: &[qsc_fir::fir::StoreItemId],
+    options: QirOptions,
+) -> Result<String, qsc_partial_eval::Error> {
+    let compute_properties = compute_properties.unwrap_or_else(|| {
+        let analyzer = qsc_rca::Analyzer::init(fir_store);
+        analyzer.analyze_all()
+    });
+
+    let programs = entry_points
+        .iter()
+        .map(|&callable| {
+            let mut program = partially_evaluate_call(
+                fir_store,
+                &compute_properties,
+                callable,
+                Value::unit(),
+                capabilities,
+                options.partial_eval_config(),
+                &mut Vec::new(),
+            )?;
+            program.config.peephole = options.peephole;
+            program.config.gate_set = options.gate_set;
+            program.config.qubit_termination = options.qubit_termination;
+            program.config.reuse_qubits = options.reuse_qubits;
+            check_and_transform(&mut program);
+            program.config.naming = options.naming;
+            let name = match &fir_store.get_global(callable) {
+                Some(Global::Callable(decl)) => decl.name.name.clone(),
+                _ => panic!("entry point should be a callable"),
+            };
+            Ok((name, program))
+        })
+        .collect::<Result<Vec<_>, qsc_partial_eval::Error>>()?;
+
+    let entry_points = programs
+        .iter()
+        .map(|(name, program)| (name.as_ref(), program))
+        .collect::<Vec<_>>();
+    Ok(link_entry_points(&entry_points))
+}
+
+/// Finds the callables of the given package that are marked with the `@EntryPoint()` attribute.
+#[must_use]
+pub fn entry_point_callables(
+    fir_store: &qsc_fir::fir::PackageStore,
+    package: qsc_fir::fir::PackageId,
+) -> Vec<qsc_fir::fir::StoreItemId> {
+    fir_store
+        .get(package)
+        .items
+        .iter()
+        .filter(|(_, item)| {
+            matches!(item.kind, qsc_fir::fir::ItemKind::Callable(_))
+                && item.attrs.contains(&qsc_fir::fir::Attr::EntryPoint)
+        })
+        .map(|(item, _)| qsc_fir::fir::StoreItemId { package, item })
+        .collect()
+}
+
 fn get_rir_from_compilation(
     fir_store: &qsc_fir::fir::PackageStore,
     compute_properties: Option<PackageStoreComputeProperties>,
     entry: &ProgramEntry,
     capabilities: TargetCapabilityFlags,
+    config: PartialEvalConfig,
+    warnings: &mut Vec<Warning>,
 ) -> Result<rir::Program, qsc_partial_eval::Error> {
     let compute_properties = compute_properties.unwrap_or_else(|| {
         let analyzer = qsc_rca::Analyzer::init(fir_store);
         analyzer.analyze_all()
     });
 
-    partially_evaluate(fir_store, &compute_properties, entry, capabilities)
+    partially_evaluate(
+        fir_store,
+        &compute_properties,
+        entry,
+        capabilities,
+        config,
+        warnings,
+    )
 }
 
 /// A trait for converting a type into QIR of type `T`.
@@ -103,7 +393,7 @@ pub trait ToQir<T> {
 }
 
 impl ToQir<String> for rir::Literal {
-    fn to_qir(&self, _program: &rir::Program) -> String {
+    fn to_qir(&self, program: &rir::Program) -> String {
         match self {
             rir::Literal::Bool(b) => format!("i1 {b}"),
             rir::Literal::Double(d) => {
@@ -117,6 +407,10 @@ impl ToQir<String> for rir::Literal {
             }
             rir::Literal::Integer(i) => format!("i64 {i}"),
             rir::Literal::Pointer => "i8* null".to_string(),
+            rir::Literal::Tag(_) => format!(
+                "i8* {}",
+                get_value_as_str(&rir::Operand::Literal(*self), program)
+            ),
             rir::Literal::Qubit(q) => format!("%Qubit* inttoptr (i64 {q} to %Qubit*)"),
             rir::Literal::Result(r) => format!("%Result* inttoptr (i64 {r} to %Result*)"),
         }
@@ -146,8 +440,11 @@ impl ToQir<String> for Option<rir::Ty> {
 }
 
 impl ToQir<String> for rir::VariableId {
-    fn to_qir(&self, _program: &rir::Program) -> String {
-        format!("%var_{}", self.0)
+    fn to_qir(&self, program: &rir::Program) -> String {
+        match get_readable_name(program, program.variable_names.get(*self)) {
+            Some(name) => format!("%{name}_{}", self.0),
+            None => format!("%var_{}", self.0),
+        }
     }
 }
 
@@ -265,6 +562,9 @@ impl ToQir<String> for rir::Instruction {
             rir::Instruction::Fcmp(op, lhs, rhs, variable) => {
                 fcmp_to_qir(*op, lhs, rhs, *variable, program)
             }
+            rir::Instruction::Fptosi(value, variable) => {
+                conversion_to_qir("fptosi", value, *variable, program)
+            }
             rir::Instruction::Icmp(op, lhs, rhs, variable) => {
                 icmp_to_qir(*op, lhs, rhs, *variable, program)
             }
@@ -276,6 +576,9 @@ impl ToQir<String> for rir::Instruction {
             rir::Instruction::Sdiv(lhs, rhs, variable) => {
                 binop_to_qir("sdiv", lhs, rhs, *variable, program)
             }
+            rir::Instruction::Sitofp(value, variable) => {
+                conversion_to_qir("sitofp", value, *variable, program)
+            }
             rir::Instruction::Shl(lhs, rhs, variable) => {
                 binop_to_qir("shl", lhs, rhs, *variable, program)
             }
@@ -358,6 +661,32 @@ fn bitwise_not_to_qir(
     )
 }
 
+fn conversion_to_qir(
+    op: &str,
+    value: &rir::Operand,
+    variable: rir::Variable,
+    program: &rir::Program,
+) -> String {
+    let value_ty = get_value_ty(value);
+    let var_ty = get_variable_ty(variable);
+    let expected_tys = match op {
+        "sitofp" => ("i64", "double"),
+        "fptosi" => ("double", "i64"),
+        _ => panic!("unsupported conversion {op}"),
+    };
+    assert_eq!(
+        (value_ty, var_ty),
+        expected_tys,
+        "unsupported input/output types ({value_ty}, {var_ty}) for {op}"
+    );
+
+    format!(
+        "  {} = {op} {value_ty} {} to {var_ty}",
+        ToQir::<String>::to_qir(&variable.variable_id, program),
+        get_value_as_str(value, program)
+    )
+}
+
 fn call_to_qir(
     args: &[rir::Operand],
     call_id: rir::CallableId,
@@ -568,6 +897,10 @@ fn get_value_as_str(value: &rir::Operand, program: &rir::Program) -> String {
             }
             rir::Literal::Integer(i) => format!("{i}"),
             rir::Literal::Pointer => "null".to_string(),
+            rir::Literal::Tag(id) => {
+                let len = program.get_tag(*id).len() + 1;
+                format!("getelementptr inbounds ([{len} x i8], [{len} x i8]* @{id}, i64 0, i64 0)")
+            }
             rir::Literal::Qubit(q) => format!("{q}"),
             rir::Literal::Result(r) => format!("{r}"),
         },
@@ -583,7 +916,7 @@ fn get_value_ty(lhs: &rir::Operand) -> &str {
             rir::Literal::Double(_) => get_f64_ty(),
             rir::Literal::Qubit(_) => "%Qubit*",
             rir::Literal::Result(_) => "%Result*",
-            rir::Literal::Pointer => "i8*",
+            rir::Literal::Pointer | rir::Literal::Tag(_) => "i8*",
         },
         rir::Operand::Variable(var) => get_variable_ty(*var),
     }
@@ -616,8 +949,72 @@ fn get_f64_ty() -> &'static str {
 }
 
 impl ToQir<String> for rir::BlockId {
-    fn to_qir(&self, _program: &rir::Program) -> String {
-        format!("block_{}", self.0)
+    fn to_qir(&self, program: &rir::Program) -> String {
+        match get_readable_name(program, program.block_names.get(*self)) {
+            Some(name) => format!("{name}_{}", self.0),
+            None => format!("block_{}", self.0),
+        }
+    }
+}
+
+/// Gets the name to use as the prefix of a variable or block label, if the program uses readable names.
+/// Since the numeric ID is always appended to the prefix, names stay unique even when several variables share
+/// the same Q# identifier.
+fn get_readable_name(program: &rir::Program, name: Option<&Rc<str>>) -> Option<String> {
+    if program.config.naming != NamingScheme::Readable {
+        return None;
+    }
+    let name = name?
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    Some(name)
+}
+
+/// Prepares the program for emission. The RIR passes leave the program out of SSA form, but LLVM IR expresses
+/// the values of variables that depend on control flow as phi nodes, so a program that stores to variables is
+/// transformed back into SSA form. Call outputs are also named if the program uses readable names.
+fn prepare_for_emission(program: &rir::Program) -> Cow<'_, rir::Program> {
+    let has_stores = program
+        .blocks
+        .values()
+        .flat_map(|block| &block.0)
+        .any(|instr| matches!(instr, rir::Instruction::Store(..)));
+    let is_readable = program.config.naming == NamingScheme::Readable;
+    if !has_stores && !is_readable {
+        return Cow::Borrowed(program);
+    }
+
+    let mut program = program.clone();
+    if has_stores {
+        let preds = build_predecessors_map(&program);
+        transform_to_ssa(&mut program, &preds);
+    }
+    if is_readable {
+        name_call_outputs(&mut program);
+    }
+    Cow::Owned(program)
+}
+
+/// Names the unnamed variables that hold the output of a call after the callable, for example `read_result`
+/// for the output of `__quantum__rt__read_result`.
+fn name_call_outputs(program: &mut rir::Program) {
+    for (_, block) in program.blocks.iter() {
+        for instr in &block.0 {
+            if let rir::Instruction::Call(callable_id, _, Some(variable)) = instr {
+                if program.variable_names.contains_key(variable.variable_id) {
+                    continue;
+                }
+                let callable_name = &program.get_callable(*callable_id).name;
+                let name = callable_name
+                    .trim_start_matches("__quantum__qis__")
+                    .trim_start_matches("__quantum__rt__")
+                    .trim_end_matches("__body");
+                program
+                    .variable_names
+                    .insert(variable.variable_id, name.into());
+            }
+        }
     }
 }
 
@@ -633,120 +1030,403 @@ impl ToQir<String> for rir::Block {
 
 impl ToQir<String> for rir::Callable {
     fn to_qir(&self, program: &rir::Program) -> String {
-        let input_type = self
-            .input_type
-            .iter()
-            .map(|t| ToQir::<String>::to_qir(t, program))
-            .collect::<Vec<_>>()
-            .join(", ");
-        let output_type = ToQir::<String>::to_qir(&self.output_type, program);
-        let Some(entry_id) = self.body else {
-            return format!(
-                "declare {output_type} @{}({input_type}){}",
-                self.name,
-                if matches!(
-                    self.call_type,
-                    rir::CallableType::Measurement | rir::CallableType::Reset
-                ) {
-                    // These callables are a special case that need the irreversable attribute.
-                    " #1"
-                } else {
-                    ""
-                }
-            );
-        };
-        let mut body = String::new();
-        let mut all_blocks = vec![entry_id];
-        all_blocks.extend(get_all_block_successors(entry_id, program));
-        for block_id in all_blocks {
-            let block = program.get_block(block_id);
-            body.push_str(&format!(
-                "{}:\n{}\n",
-                ToQir::<String>::to_qir(&block_id, program),
-                ToQir::<String>::to_qir(block, program)
-            ));
+        if self.body.is_some() {
+            entry_point_to_qir(self, program, "ENTRYPOINT__main", 0)
+        } else {
+            declaration_to_qir(self, program, 1)
         }
-        assert!(
-            input_type.is_empty(),
-            "entry point should not have an input"
-        );
-        format!("define {output_type} @ENTRYPOINT__main() #0 {{\n{body}}}",)
     }
 }
 
+/// Declares a callable without a body, marking measurements and resets with the given attribute group,
+/// which holds the irreversible attribute.
+fn declaration_to_qir(
+    callable: &rir::Callable,
+    program: &rir::Program,
+    irreversible_attrs: usize,
+) -> String {
+    let input_type = callable
+        .input_type
+        .iter()
+        .map(|t| ToQir::<String>::to_qir(t, program))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let output_type = ToQir::<String>::to_qir(&callable.output_type, program);
+    format!(
+        "declare {output_type} @{}({input_type}){}",
+        callable.name,
+        if matches!(
+            callable.call_type,
+            rir::CallableType::Measurement | rir::CallableType::Reset
+        ) {
+            // These callables are a special case that need the irreversable attribute.
+            format!(" #{irreversible_attrs}")
+        } else {
+            String::new()
+        }
+    )
+}
+
+/// Defines the entry point of a program with the given name and attribute group.
+fn entry_point_to_qir(
+    callable: &rir::Callable,
+    program: &rir::Program,
+    name: &str,
+    attrs: usize,
+) -> String {
+    let entry_id = callable.body.expect("entry point should have a body");
+    assert!(
+        callable.input_type.is_empty(),
+        "entry point should not have an input"
+    );
+    let output_type = ToQir::<String>::to_qir(&callable.output_type, program);
+    let mut body = String::new();
+    let mut all_blocks = vec![entry_id];
+    all_blocks.extend(get_all_block_successors(entry_id, program));
+    for block_id in all_blocks {
+        let block = program.get_block(block_id);
+        body.push_str(&format!(
+            "{}:\n{}\n",
+            ToQir::<String>::to_qir(&block_id, program),
+            ToQir::<String>::to_qir(block, program)
+        ));
+    }
+    // The measurements and resets inserted to terminate qubits are listed, since they are not in the Q# program.
+    let mut terminations = String::new();
+    for termination in &program.inserted_terminations {
+        terminations.push_str(&format!("; inserted to terminate qubit: {termination}\n"));
+    }
+    format!("{terminations}define {output_type} @{name}() #{attrs} {{\n{body}}}",)
+}
+
 impl ToQir<String> for rir::Program {
     fn to_qir(&self, _program: &rir::Program) -> String {
-        let callables = self
-            .callables
-            .iter()
-            .map(|(_, callable)| ToQir::<String>::to_qir(callable, self))
-            .collect::<Vec<_>>()
-            .join("\n\n");
-        let profile = if self.config.is_base() {
+        program_to_qir(&prepare_for_emission(self))
+    }
+}
+
+fn program_to_qir(program: &rir::Program) -> String {
+    let callables = tags_to_qir(&program.tags)
+        .into_iter()
+        .chain(
+            program
+                .callables
+                .iter()
+                .map(|(_, callable)| ToQir::<String>::to_qir(callable, program)),
+        )
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let profile = if program.config.is_base() {
+        "base_profile"
+    } else {
+        "adaptive_profile"
+    };
+    let body = format!(
+        include_str!("./qir/template.ll"),
+        callables, profile, program.num_qubits, program.num_results
+    );
+    let flags = get_module_flags(program);
+    let mut metadata = module_flags_to_qir(&flags);
+    if program.config.emit_output_positions {
+        metadata.push_str(&output_metadata::output_positions_to_qir(
+            &program.output_positions,
+            flags.len(),
+        ));
+    }
+    body + "\n" + &metadata
+}
+
+/// Generates a single QIR module with an entry point for each of the given programs, which must
+/// target the same profile. Each entry point is named after the name given with its program, and
+/// carries the number of qubits and results its own program requires. Callables declared by more
+/// than one program are declared once.
+#[must_use]
+pub fn link_entry_points(entry_points: &[(&str, &rir::Program)]) -> String {
+    let mut tags = Vec::new();
+    let programs = entry_points
+        .iter()
+        .map(|(_, program)| {
+            let mut program = prepare_for_emission(program);
+            if !program.tags.is_empty() {
+                append_tags(program.to_mut(), &mut tags);
+            }
+            program
+        })
+        .collect::<Vec<_>>();
+
+    let irreversible_attrs = programs.len();
+    let mut names = FxHashSet::default();
+    let mut definitions = Vec::new();
+    let mut attributes = Vec::new();
+    let mut declared = FxHashSet::default();
+    let mut declarations = Vec::new();
+    for (attrs, ((name, _), program)) in entry_points.iter().zip(&programs).enumerate() {
+        let name = unique_entry_point_name(name, &mut names);
+        let entry = program.get_callable(program.entry);
+        definitions.push(entry_point_to_qir(entry, program, &name, attrs));
+        let profile = if program.config.is_base() {
             "base_profile"
         } else {
             "adaptive_profile"
         };
-        let body = format!(
-            include_str!("./qir/template.ll"),
-            callables, profile, self.num_qubits, self.num_results
-        );
-        let flags = get_module_metadata(self);
-        body + "\n" + &flags
+        attributes.push(format!(
+            "attributes #{attrs} = {{ \"entry_point\" \"output_labeling_schema\" \"qir_profiles\"=\"{profile}\" \"required_num_qubits\"=\"{}\" \"required_num_results\"=\"{}\" }}",
+            program.num_qubits, program.num_results
+        ));
+        for (_, callable) in program.callables.iter() {
+            if callable.body.is_none() && declared.insert(callable.name.clone()) {
+                declarations.push(declaration_to_qir(callable, program, irreversible_attrs));
+            }
+        }
     }
+
+    let body = format!(
+        include_str!("./qir/linked_template.ll"),
+        tags_to_qir(&tags)
+            .into_iter()
+            .chain(definitions)
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+        declarations.join("\n\n"),
+        attributes.join("\n"),
+        irreversible_attrs
+    );
+    let programs = programs.iter().map(AsRef::as_ref).collect::<Vec<_>>();
+    let flags = module_flags_to_qir(&get_linked_module_flags(&programs));
+    body + "\n" + &flags
 }
 
-/// Create the module metadata for the given program.
-/// creating the `llvm.module.flags` and its associated values.
-fn get_module_metadata(program: &rir::Program) -> String {
-    let mut flags = String::new();
+/// The global string constants that tag literals point to, if there are any.
+fn tags_to_qir(tags: &[Rc<str>]) -> Option<String> {
+    if tags.is_empty() {
+        return None;
+    }
+    let globals = tags.iter().enumerate().map(|(id, tag)| {
+        // Quotes, backslashes and unprintable bytes are written as hex escapes.
+        let escaped: String = tag
+            .bytes()
+            .map(|b| match b {
+                b' '..=b'~' if b != b'"' && b != b'\\' => char::from(b).to_string(),
+                _ => format!("\\{b:02X}"),
+            })
+            .collect();
+        format!(
+            "@{id} = internal constant [{} x i8] c\"{escaped}\\00\"",
+            tag.len() + 1
+        )
+    });
+    Some(globals.collect::<Vec<_>>().join("\n"))
+}
 
-    // push the default attrs, we don't have any config values
-    // for now that would change any of them.
-    flags.push_str(
-        r#"
-!0 = !{i32 1, !"qir_major_version", i32 1}
-!1 = !{i32 7, !"qir_minor_version", i32 0}
-!2 = !{i32 1, !"dynamic_qubit_management", i1 false}
-!3 = !{i32 1, !"dynamic_result_management", i1 false}
-"#,
+/// Renumbers the tags of the program to follow the given tags, and makes them the tags of the program
+/// along with its own, so that programs linked into one module don't share tag globals.
+fn append_tags(program: &mut rir::Program, tags: &mut Vec<Rc<str>>) {
+    let offset = u32::try_from(tags.len()).expect("tag count should fit in u32");
+    for (_, block) in program.blocks.iter_mut() {
+        for instr in &mut block.0 {
+            for_each_operand_mut(instr, |operand| {
+                if let rir::Operand::Literal(rir::Literal::Tag(id)) = operand {
+                    *id += offset;
+                }
+            });
+        }
+    }
+    tags.append(&mut program.tags);
+    program.tags.clone_from(tags);
+}
+
+/// Makes an entry point function name from the given name that is not already in `names`.
+fn unique_entry_point_name(name: &str, names: &mut FxHashSet<String>) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    let base = format!("ENTRYPOINT__{name}");
+    let mut name = base.clone();
+    let mut suffix = 1;
+    while !names.insert(name.clone()) {
+        name = format!("{base}_{suffix}");
+        suffix += 1;
+    }
+    name
+}
+
+/// The `llvm.module.flags` metadata holding the given flags.
+fn module_flags_to_qir(flags: &[ModuleFlag]) -> String {
+    let mut metadata_def = String::new();
+    metadata_def.push_str("!llvm.module.flags = !{");
+    metadata_def.push_str(
+        &(0..flags.len())
+            .map(|i| format!("!{i}"))
+            .collect::<Vec<_>>()
+            .join(", "),
     );
+    metadata_def.push_str("}\n\n");
+    for (i, flag) in flags.iter().enumerate() {
+        metadata_def.push_str(&format!(
+            "!{i} = !{{i32 {}, !\"{}\", {}}}\n",
+            flag.behavior, flag.name, flag.value
+        ));
+    }
+    metadata_def
+}
+
+/// An entry of the `llvm.module.flags` metadata.
+struct ModuleFlag {
+    /// The behavior when modules with the flag are linked, such as 1 for error on mismatch or 7 for max.
+    behavior: u32,
+    name: &'static str,
+    value: ModuleFlagValue,
+}
+
+enum ModuleFlagValue {
+    Int { width: u32, value: u64 },
+    String(&'static str),
+}
+
+impl Display for ModuleFlagValue {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            ModuleFlagValue::Int { width: 1, value } => write!(f, "i1 {}", *value != 0),
+            ModuleFlagValue::Int { width, value } => write!(f, "i{width} {value}"),
+            ModuleFlagValue::String(value) => write!(f, "!\"{value}\""),
+        }
+    }
+}
+
+/// Gets the module flags for the given program.
+fn get_module_flags(program: &rir::Program) -> Vec<ModuleFlag> {
+    get_linked_module_flags(&[program])
+}
 
-    let mut index = 4;
+/// Gets the module flags for a module holding the entry points of all the given programs, which
+/// must target the same profile.
+fn get_linked_module_flags(programs: &[&rir::Program]) -> Vec<ModuleFlag> {
+    let flag = |behavior, name, value| ModuleFlag {
+        behavior,
+        name,
+        value,
+    };
+    let int = |width, value| ModuleFlagValue::Int { width, value };
+
+    // push the default attrs, we don't have any config values
+    // for now that would change any of them.
+    let mut flags = vec![
+        flag(1, "qir_major_version", int(32, 1)),
+        flag(7, "qir_minor_version", int(32, 0)),
+        flag(1, "dynamic_qubit_management", int(1, 0)),
+        flag(1, "dynamic_result_management", int(1, 0)),
+    ];
 
     // If we are not in the base profile, we need to add the capabilities
-    // associated with the adaptive profile.
-    if !program.config.is_base() {
-        // loop through the capabilities and add them to the metadata
-        // for values that we can generate.
-        for cap in program.config.capabilities.iter() {
-            match cap {
-                TargetCapabilityFlags::IntegerComputations => {
-                    let name = "int_computations";
-                    flags.push_str(&format!(
-                        "!{} = !{{i32 {}, !\"{}\", !\"i{}\"}}\n",
-                        index, 1, name, 64
-                    ));
-                    index += 1;
-                }
-                TargetCapabilityFlags::FloatingPointComputations => {
-                    let name = "float_computations";
-                    flags.push_str(&format!(
-                        "!{} = !{{i32 {}, !\"{}\", !\"f{}\"}}\n",
-                        index, 1, name, 64
-                    ));
-                    index += 1;
-                }
-                _ => continue,
+    // associated with the adaptive profile that the program actually uses.
+    if programs
+        .first()
+        .is_some_and(|program| !program.config.is_base())
+    {
+        let computations = programs
+            .iter()
+            .map(|program| RequiredComputations::from_program(program))
+            .fold(RequiredComputations::default(), RequiredComputations::union);
+        if computations.int {
+            flags.push(flag(1, "int_computations", ModuleFlagValue::String("i64")));
+        }
+        if computations.float {
+            flags.push(flag(
+                1,
+                "float_computations",
+                ModuleFlagValue::String("f64"),
+            ));
+        }
+        if computations.backwards_branching {
+            flags.push(flag(7, "backwards_branching", int(2, 3)));
+        }
+    }
+    flags
+}
+
+/// The classical computations performed by the instructions of a program, which determine
+/// the adaptive profile module flags it needs.
+#[derive(Clone, Copy, Debug, Default)]
+struct RequiredComputations {
+    int: bool,
+    float: bool,
+    backwards_branching: bool,
+}
+
+impl RequiredComputations {
+    fn from_program(program: &rir::Program) -> Self {
+        let mut computations = Self::default();
+        let Some(entry_block) = program.get_callable(program.entry).body else {
+            return computations;
+        };
+        let mut blocks = vec![entry_block];
+        blocks.extend(get_all_block_successors(entry_block, program));
+        for &block_id in &blocks {
+            for instr in &program.get_block(block_id).0 {
+                computations.add_instruction(instr);
             }
         }
+        computations.backwards_branching = blocks
+            .iter()
+            .any(|&block_id| get_all_block_successors(block_id, program).contains(&block_id));
+        computations
     }
 
-    let mut metadata_def = String::new();
-    metadata_def.push_str("!llvm.module.flags = !{");
-    for i in 0..index - 1 {
-        metadata_def.push_str(&format!("!{i}, "));
+    fn union(self, other: Self) -> Self {
+        Self {
+            int: self.int || other.int,
+            float: self.float || other.float,
+            backwards_branching: self.backwards_branching || other.backwards_branching,
+        }
+    }
+
+    fn add_instruction(&mut self, instr: &rir::Instruction) {
+        let output = match instr {
+            // The arguments of a call are computed by other instructions, or are constants
+            // passed to the callable, so only the value it returns is a computation.
+            rir::Instruction::Call(_, _, output) => *output,
+            rir::Instruction::Jump(..)
+            | rir::Instruction::Branch(..)
+            | rir::Instruction::Return => None,
+            rir::Instruction::Store(_, output)
+            | rir::Instruction::Add(_, _, output)
+            | rir::Instruction::Sub(_, _, output)
+            | rir::Instruction::Mul(_, _, output)
+            | rir::Instruction::Sdiv(_, _, output)
+            | rir::Instruction::Srem(_, _, output)
+            | rir::Instruction::Shl(_, _, output)
+            | rir::Instruction::Ashr(_, _, output)
+            | rir::Instruction::Fadd(_, _, output)
+            | rir::Instruction::Fsub(_, _, output)
+            | rir::Instruction::Fmul(_, _, output)
+            | rir::Instruction::Fdiv(_, _, output)
+            | rir::Instruction::Fcmp(_, _, _, output)
+            | rir::Instruction::Icmp(_, _, _, output)
+            | rir::Instruction::LogicalNot(_, output)
+            | rir::Instruction::LogicalAnd(_, _, output)
+            | rir::Instruction::LogicalOr(_, _, output)
+            | rir::Instruction::BitwiseNot(_, output)
+            | rir::Instruction::Sitofp(_, output)
+            | rir::Instruction::Fptosi(_, output)
+            | rir::Instruction::BitwiseAnd(_, _, output)
+            | rir::Instruction::BitwiseOr(_, _, output)
+            | rir::Instruction::BitwiseXor(_, _, output)
+            | rir::Instruction::Phi(_, output) => {
+                for_each_operand(instr, |operand| self.add_ty(operand.get_type()));
+                Some(*output)
+            }
+        };
+        if let Some(output) = output {
+            self.add_ty(output.ty);
+        }
+    }
+
+    fn add_ty(&mut self, ty: rir::Ty) {
+        match ty {
+            rir::Ty::Integer => self.int = true,
+            rir::Ty::Double => self.float = true,
+            _ => {}
+        }
     }
-    metadata_def.push_str(&format!("!{}}}\n", index - 1));
-    metadata_def + &flags
 }
@@ -0,0 +1,1075 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! Writes a program as LLVM bitcode, the binary form of the textual QIR, without depending on LLVM.
+//!
+//! The module holds the same tag strings, declarations, attributes, entry point and module flags as the textual QIR, and
+//! uses typed pointers so that it can be read by the LLVM versions that QIR consumers are built on. Only
+//! unabbreviated records are written, and names are kept in value symbol tables rather than a string table,
+//! which is a layout that every LLVM bitcode reader accepts.
+
+#[cfg(test)]
+mod tests;
+
+use super::{get_module_flags, prepare_for_emission, ModuleFlagValue, ToQir};
+use qsc_data_structures::fx::FxHashMap;
+use qsc_rir::{rir, utils::get_all_block_successors};
+use std::rc::Rc;
+
+// Block IDs.
+const MODULE_BLOCK: u64 = 8;
+const PARAMATTR_BLOCK: u64 = 9;
+const PARAMATTR_GROUP_BLOCK: u64 = 10;
+const CONSTANTS_BLOCK: u64 = 11;
+const FUNCTION_BLOCK: u64 = 12;
+const IDENTIFICATION_BLOCK: u64 = 13;
+const VALUE_SYMTAB_BLOCK: u64 = 14;
+const METADATA_BLOCK: u64 = 15;
+const TYPE_BLOCK: u64 = 17;
+
+// Record codes, by block.
+const IDENTIFICATION_STRING: u64 = 1;
+const IDENTIFICATION_EPOCH: u64 = 2;
+const MODULE_VERSION: u64 = 1;
+const MODULE_GLOBALVAR: u64 = 7;
+const MODULE_FUNCTION: u64 = 8;
+const PARAMATTR_ENTRY: u64 = 2;
+const PARAMATTR_GROUP_ENTRY: u64 = 3;
+const TYPE_NUMENTRY: u64 = 1;
+const TYPE_VOID: u64 = 2;
+const TYPE_DOUBLE: u64 = 4;
+const TYPE_OPAQUE: u64 = 6;
+const TYPE_INTEGER: u64 = 7;
+const TYPE_POINTER: u64 = 8;
+const TYPE_ARRAY: u64 = 11;
+const TYPE_STRUCT_NAME: u64 = 19;
+const TYPE_FUNCTION: u64 = 21;
+const CONSTANT_SETTYPE: u64 = 1;
+const CONSTANT_NULL: u64 = 2;
+const CONSTANT_INTEGER: u64 = 4;
+const CONSTANT_FLOAT: u64 = 6;
+const CONSTANT_CSTRING: u64 = 9;
+const CONSTANT_CAST: u64 = 11;
+const CONSTANT_INBOUNDS_GEP: u64 = 20;
+const METADATA_STRING: u64 = 1;
+const METADATA_VALUE: u64 = 2;
+const METADATA_NODE: u64 = 3;
+const METADATA_NAME: u64 = 4;
+const METADATA_NAMED_NODE: u64 = 10;
+const SYMTAB_ENTRY: u64 = 1;
+const SYMTAB_BLOCK_ENTRY: u64 = 2;
+const FUNCTION_DECLAREBLOCKS: u64 = 1;
+const FUNCTION_BINOP: u64 = 2;
+const FUNCTION_CAST: u64 = 3;
+const FUNCTION_RET: u64 = 10;
+const FUNCTION_BR: u64 = 11;
+const FUNCTION_PHI: u64 = 16;
+const FUNCTION_CMP: u64 = 28;
+const FUNCTION_CALL: u64 = 34;
+
+// Operand encodings.
+const LINKAGE_INTERNAL: u64 = 3;
+const CAST_INTTOPTR: u64 = 10;
+const CALL_EXPLICIT_TYPE: u64 = 1 << 15;
+const FUNCTION_ATTRIBUTE_INDEX: u64 = 0xFFFF_FFFF;
+const ATTRIBUTE_STRING: u64 = 3;
+const ATTRIBUTE_STRING_WITH_VALUE: u64 = 4;
+
+/// The attribute list of the entry point, which is attribute `#0` in the textual QIR.
+const ENTRY_POINT_ATTRIBUTES: u64 = 1;
+/// The attribute list of irreversible callables, which is attribute `#1` in the textual QIR.
+const IRREVERSIBLE_ATTRIBUTES: u64 = 2;
+
+/// The width of abbreviation IDs in every block. Only the standard abbreviations are used, so two bits would
+/// do, but LLVM itself never uses fewer than three.
+const ABBREV_WIDTH: u32 = 3;
+const END_BLOCK: u32 = 0;
+const ENTER_SUBBLOCK: u32 = 1;
+const UNABBREV_RECORD: u32 = 3;
+
+/// Writes the program as an LLVM bitcode module.
+#[must_use]
+pub fn write_program(program: &rir::Program) -> Vec<u8> {
+    ModuleWriter::new(&prepare_for_emission(program)).write()
+}
+
+/// Writes the bits of an LLVM bitstream, packed into little-endian 32-bit words.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    current: u64,
+    current_bits: u32,
+    abbrev_width: u32,
+    /// The byte offset of the length of each open block, and the abbreviation width outside of it.
+    blocks: Vec<(usize, u32)>,
+}
+
+impl BitWriter {
+    fn emit(&mut self, value: u64, width: u32) {
+        debug_assert!(width <= 32 && value >> width == 0);
+        self.current |= value << self.current_bits;
+        self.current_bits += width;
+        if self.current_bits >= 32 {
+            let word = u32::try_from(self.current & 0xFFFF_FFFF).expect("word should fit in u32");
+            self.bytes.extend_from_slice(&word.to_le_bytes());
+            self.current >>= 32;
+            self.current_bits -= 32;
+        }
+    }
+
+    fn emit_vbr(&mut self, mut value: u64, width: u32) {
+        let threshold = 1 << (width - 1);
+        while value >= threshold {
+            self.emit((value & (threshold - 1)) | threshold, width);
+            value >>= width - 1;
+        }
+        self.emit(value, width);
+    }
+
+    fn align(&mut self) {
+        if self.current_bits > 0 {
+            self.emit(0, 32 - self.current_bits);
+        }
+    }
+
+    fn enter_block(&mut self, block_id: u64) {
+        self.emit(ENTER_SUBBLOCK.into(), self.abbrev_width);
+        self.emit_vbr(block_id, 8);
+        self.emit_vbr(ABBREV_WIDTH.into(), 4);
+        self.align();
+        // The length of the block in words is filled in when the block is exited.
+        self.blocks.push((self.bytes.len(), self.abbrev_width));
+        self.emit(0, 32);
+        self.abbrev_width = ABBREV_WIDTH;
+    }
+
+    fn exit_block(&mut self) {
+        self.emit(END_BLOCK.into(), self.abbrev_width);
+        self.align();
+        let (offset, abbrev_width) = self.blocks.pop().expect("a block should be open");
+        let words = u32::try_from((self.bytes.len() - offset - 4) / 4)
+            .expect("block length should fit in u32");
+        self.bytes[offset..offset + 4].copy_from_slice(&words.to_le_bytes());
+        self.abbrev_width = abbrev_width;
+    }
+
+    fn record(&mut self, code: u64, operands: &[u64]) {
+        self.emit(UNABBREV_RECORD.into(), self.abbrev_width);
+        self.emit_vbr(code, 6);
+        self.emit_vbr(operands.len() as u64, 6);
+        for &operand in operands {
+            self.emit_vbr(operand, 6);
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        assert!(self.blocks.is_empty(), "all blocks should be exited");
+        self.align();
+        self.bytes
+    }
+}
+
+/// A type in the type table of the module.
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum Type {
+    Void,
+    Int(u32),
+    Double,
+    Opaque(&'static str),
+    Pointer(u64),
+    Array(u64, u64),
+    Function(u64, Vec<u64>),
+}
+
+/// A constant, which is numbered as a value and written in a constants block.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Constant {
+    Int {
+        ty: u64,
+        value: i64,
+    },
+    Double(u64),
+    Null(u64),
+    IntToPtr {
+        ty: u64,
+        value: i64,
+    },
+    /// The null-terminated string of a tag, which is the initializer of its global.
+    CString {
+        ty: u64,
+        tag: u32,
+    },
+    /// A pointer to the first character of the global holding a tag.
+    Tag(u32),
+}
+
+impl Constant {
+    /// The order in which constants are written, so that constants of the same type are written together and
+    /// integers are written before the casts that use them.
+    fn rank(self) -> (u8, u64) {
+        match self {
+            Constant::Int { ty, .. } => (0, ty),
+            Constant::Double(_) => (1, 0),
+            Constant::Null(ty) => (2, ty),
+            Constant::IntToPtr { ty, .. } => (3, ty),
+            Constant::CString { ty, .. } => (4, ty),
+            Constant::Tag(_) => (5, 0),
+        }
+    }
+
+    fn ty(self, types: &Types) -> u64 {
+        match self {
+            Constant::Int { ty, .. }
+            | Constant::Null(ty)
+            | Constant::IntToPtr { ty, .. }
+            | Constant::CString { ty, .. } => ty,
+            Constant::Double(_) => types.double,
+            Constant::Tag(_) => types.pointer,
+        }
+    }
+}
+
+/// The types used by the module.
+struct Types {
+    table: Vec<Type>,
+    ids: FxHashMap<Type, u64>,
+    void: u64,
+    i8: u64,
+    bool: u64,
+    i2: u64,
+    i32: u64,
+    int: u64,
+    double: u64,
+    pointer: u64,
+    qubit: u64,
+    result: u64,
+}
+
+impl Types {
+    fn new() -> Self {
+        let mut types = Self {
+            table: Vec::new(),
+            ids: FxHashMap::default(),
+            void: 0,
+            i8: 0,
+            bool: 0,
+            i2: 0,
+            i32: 0,
+            int: 0,
+            double: 0,
+            pointer: 0,
+            qubit: 0,
+            result: 0,
+        };
+        let result = types.get(Type::Opaque("Result"));
+        types.result = types.get(Type::Pointer(result));
+        let qubit = types.get(Type::Opaque("Qubit"));
+        types.qubit = types.get(Type::Pointer(qubit));
+        types.i8 = types.get(Type::Int(8));
+        types.pointer = types.get(Type::Pointer(types.i8));
+        types.void = types.get(Type::Void);
+        types.bool = types.get(Type::Int(1));
+        types.i2 = types.get(Type::Int(2));
+        types.i32 = types.get(Type::Int(32));
+        types.int = types.get(Type::Int(64));
+        types.double = types.get(Type::Double);
+        types
+    }
+
+    fn get(&mut self, ty: Type) -> u64 {
+        if let Some(&id) = self.ids.get(&ty) {
+            return id;
+        }
+        let id = self.table.len() as u64;
+        self.table.push(ty.clone());
+        self.ids.insert(ty, id);
+        id
+    }
+
+    fn rir(&self, ty: rir::Ty) -> u64 {
+        match ty {
+            rir::Ty::Boolean => self.bool,
+            rir::Ty::Integer => self.int,
+            rir::Ty::Double => self.double,
+            rir::Ty::Pointer => self.pointer,
+            rir::Ty::Qubit => self.qubit,
+            rir::Ty::Result => self.result,
+        }
+    }
+
+    /// The type of the global holding the tag, which is an array of its characters and a null terminator.
+    fn tag(&mut self, tag: &str) -> u64 {
+        self.get(Type::Array(tag.len() as u64 + 1, self.i8))
+    }
+
+    fn function(&mut self, callable: &rir::Callable) -> u64 {
+        let output = callable.output_type.map_or(self.void, |ty| self.rir(ty));
+        let input = callable.input_type.iter().map(|&ty| self.rir(ty)).collect();
+        self.get(Type::Function(output, input))
+    }
+}
+
+/// The numbering of the constants of a module or function, which follow the values numbered before them.
+struct Constants {
+    first_id: u64,
+    ids: FxHashMap<Constant, u64>,
+    values: Vec<Constant>,
+}
+
+impl Constants {
+    /// Numbers the given constants in the order they are written.
+    fn new(first_id: u64, constants: impl IntoIterator<Item = Constant>) -> Self {
+        let mut unique = Vec::new();
+        for constant in constants {
+            if !unique.contains(&constant) {
+                unique.push(constant);
+            }
+        }
+        // The sort is stable, so constants of the same rank stay in the order they are first used.
+        unique.sort_by_key(|constant| constant.rank());
+        let ids = unique
+            .iter()
+            .zip(first_id..)
+            .map(|(&constant, id)| (constant, id))
+            .collect();
+        Self {
+            first_id,
+            ids,
+            values: unique,
+        }
+    }
+
+    fn id(&self, constant: Constant) -> u64 {
+        self.ids[&constant]
+    }
+
+    fn next_id(&self) -> u64 {
+        self.first_id + self.values.len() as u64
+    }
+
+    fn write(&self, writer: &mut BitWriter, types: &Types, tags: &[Rc<str>]) {
+        if self.values.is_empty() {
+            return;
+        }
+        writer.enter_block(CONSTANTS_BLOCK);
+        let mut current_ty = None;
+        for &constant in &self.values {
+            let ty = constant.ty(types);
+            if current_ty != Some(ty) {
+                writer.record(CONSTANT_SETTYPE, &[ty]);
+                current_ty = Some(ty);
+            }
+            match constant {
+                Constant::Int { value, .. } => writer.record(CONSTANT_INTEGER, &[signed(value)]),
+                Constant::Double(bits) => writer.record(CONSTANT_FLOAT, &[bits]),
+                Constant::Null(_) => writer.record(CONSTANT_NULL, &[]),
+                Constant::IntToPtr { value, .. } => {
+                    let int = self.id(Constant::Int {
+                        ty: types.int,
+                        value,
+                    });
+                    writer.record(CONSTANT_CAST, &[CAST_INTTOPTR, types.int, int]);
+                }
+                Constant::CString { tag, .. } => {
+                    writer.record(CONSTANT_CSTRING, &chars(&tags[tag as usize]));
+                }
+                Constant::Tag(tag) => {
+                    // The globals are numbered first, in the order of their tags.
+                    let ty = types.ids[&Type::Array(tags[tag as usize].len() as u64 + 1, types.i8)];
+                    let zero = self.id(Constant::Int {
+                        ty: types.int,
+                        value: 0,
+                    });
+                    let global_ty = types.ids[&Type::Pointer(ty)];
+                    writer.record(
+                        CONSTANT_INBOUNDS_GEP,
+                        &[ty, global_ty, tag.into(), types.int, zero, types.int, zero],
+                    );
+                }
+            }
+        }
+        writer.exit_block();
+    }
+}
+
+struct ModuleWriter<'a> {
+    program: &'a rir::Program,
+    writer: BitWriter,
+    types: Types,
+    /// The callables in the order they are declared, which is also their value ID.
+    callables: Vec<rir::CallableId>,
+}
+
+impl<'a> ModuleWriter<'a> {
+    fn new(program: &'a rir::Program) -> Self {
+        let mut types = Types::new();
+        for tag in &program.tags {
+            let ty = types.tag(tag);
+            types.get(Type::Pointer(ty));
+        }
+        let callables = program
+            .callables
+            .iter()
+            .map(|(id, callable)| {
+                types.function(callable);
+                id
+            })
+            .collect();
+        Self {
+            program,
+            writer: BitWriter {
+                abbrev_width: 2,
+                ..BitWriter::default()
+            },
+            types,
+            callables,
+        }
+    }
+
+    fn write(mut self) -> Vec<u8> {
+        for byte in *b"BC\xC0\xDE" {
+            self.writer.emit(byte.into(), 8);
+        }
+        self.write_identification();
+
+        self.writer.enter_block(MODULE_BLOCK);
+        // Version 1 numbers the operands of instructions relative to the instruction, and keeps the names of
+        // functions in the value symbol table.
+        self.writer.record(MODULE_VERSION, &[1]);
+        self.write_attributes();
+        self.write_types();
+        let constants = self.module_constants();
+        self.write_globals(&constants);
+        self.write_function_declarations();
+        self.write_module_flags(&constants);
+        let functions = self
+            .callables
+            .iter()
+            .zip(self.program.tags.len() as u64..)
+            .map(|(&id, value_id)| (value_id, function_name(self.program.get_callable(id))))
+            .collect::<Vec<_>>();
+        self.write_symbols(functions, []);
+        let program = self.program;
+        for (_, callable) in program.callables.iter() {
+            if let Some(entry_block) = callable.body {
+                self.write_function(entry_block, constants.next_id());
+            }
+        }
+        self.writer.exit_block();
+
+        self.writer.finish()
+    }
+
+    fn write_identification(&mut self) {
+        self.writer.enter_block(IDENTIFICATION_BLOCK);
+        self.writer
+            .record(IDENTIFICATION_STRING, &chars(env!("CARGO_PKG_NAME")));
+        self.writer.record(IDENTIFICATION_EPOCH, &[0]);
+        self.writer.exit_block();
+    }
+
+    fn write_attributes(&mut self) {
+        let profile = if self.program.config.is_base() {
+            "base_profile"
+        } else {
+            "adaptive_profile"
+        };
+        let num_qubits = self.program.num_qubits.to_string();
+        let num_results = self.program.num_results.to_string();
+        let entry_point: &[(&str, Option<&str>)] = &[
+            ("entry_point", None),
+            ("output_labeling_schema", None),
+            ("qir_profiles", Some(profile)),
+            ("required_num_qubits", Some(&num_qubits)),
+            ("required_num_results", Some(&num_results)),
+        ];
+        let irreversible: &[(&str, Option<&str>)] = &[("irreversible", None)];
+        let groups = [
+            (ENTRY_POINT_ATTRIBUTES, entry_point),
+            (IRREVERSIBLE_ATTRIBUTES, irreversible),
+        ];
+
+        self.writer.enter_block(PARAMATTR_GROUP_BLOCK);
+        for (group, attributes) in groups {
+            let mut record = vec![group, FUNCTION_ATTRIBUTE_INDEX];
+            for (key, value) in attributes {
+                record.push(if value.is_some() {
+                    ATTRIBUTE_STRING_WITH_VALUE
+                } else {
+                    ATTRIBUTE_STRING
+                });
+                record.extend(chars(key));
+                record.push(0);
+                if let Some(value) = value {
+                    record.extend(chars(value));
+                    record.push(0);
+                }
+            }
+            self.writer.record(PARAMATTR_GROUP_ENTRY, &record);
+        }
+        self.writer.exit_block();
+
+        // Each attribute list holds a single group, so the lists are numbered like the groups.
+        self.writer.enter_block(PARAMATTR_BLOCK);
+        for (group, _) in groups {
+            self.writer.record(PARAMATTR_ENTRY, &[group]);
+        }
+        self.writer.exit_block();
+    }
+
+    fn write_types(&mut self) {
+        self.writer.enter_block(TYPE_BLOCK);
+        self.writer
+            .record(TYPE_NUMENTRY, &[self.types.table.len() as u64]);
+        for ty in &self.types.table {
+            match ty {
+                Type::Void => self.writer.record(TYPE_VOID, &[]),
+                Type::Int(width) => self.writer.record(TYPE_INTEGER, &[u64::from(*width)]),
+                Type::Double => self.writer.record(TYPE_DOUBLE, &[]),
+                Type::Opaque(name) => {
+                    self.writer.record(TYPE_STRUCT_NAME, &chars(name));
+                    self.writer.record(TYPE_OPAQUE, &[0]);
+                }
+                Type::Pointer(pointee) => self.writer.record(TYPE_POINTER, &[*pointee, 0]),
+                Type::Array(len, element) => self.writer.record(TYPE_ARRAY, &[*len, *element]),
+                Type::Function(output, input) => {
+                    let mut record = vec![0, *output];
+                    record.extend(input);
+                    self.writer.record(TYPE_FUNCTION, &record);
+                }
+            }
+        }
+        self.writer.exit_block();
+    }
+
+    /// Writes a constant global for each tag, initialized with its string.
+    fn write_globals(&mut self, constants: &Constants) {
+        for (tag, id) in self.program.tags.iter().zip(0..) {
+            let ty = self.types.ids[&Type::Array(tag.len() as u64 + 1, self.types.i8)];
+            let init = constants.id(Constant::CString { ty, tag: id });
+            // [pointer type, is constant, initializer plus one, linkage, alignment, section]
+            self.writer.record(
+                MODULE_GLOBALVAR,
+                &[
+                    self.types.ids[&Type::Pointer(ty)],
+                    1,
+                    init + 1,
+                    LINKAGE_INTERNAL,
+                    0,
+                    0,
+                ],
+            );
+        }
+    }
+
+    fn write_function_declarations(&mut self) {
+        for &id in &self.callables {
+            let callable = self.program.get_callable(id);
+            let ty = self.types.function(callable);
+            let attributes = if callable.body.is_some() {
+                ENTRY_POINT_ATTRIBUTES
+            } else if matches!(
+                callable.call_type,
+                rir::CallableType::Measurement | rir::CallableType::Reset
+            ) {
+                IRREVERSIBLE_ATTRIBUTES
+            } else {
+                0
+            };
+            let is_declaration = u64::from(callable.body.is_none());
+            // [type, calling convention, is declaration, linkage, attributes, alignment, section, visibility,
+            // garbage collector, unnamed address]
+            self.writer.record(
+                MODULE_FUNCTION,
+                &[ty, 0, is_declaration, 0, attributes, 0, 0, 0, 0, 0],
+            );
+        }
+    }
+
+    /// The module constants, which are the initializers of the tag globals and the values of the module flags.
+    /// They are numbered after the globals and functions.
+    fn module_constants(&self) -> Constants {
+        let flags = get_module_flags(self.program);
+        let tags = self
+            .program
+            .tags
+            .iter()
+            .zip(0..)
+            .map(|(tag, id)| Constant::CString {
+                ty: self.types.ids[&Type::Array(tag.len() as u64 + 1, self.types.i8)],
+                tag: id,
+            });
+        let flags = flags.iter().flat_map(|flag| {
+            let behavior = Constant::Int {
+                ty: self.types.i32,
+                value: flag.behavior.into(),
+            };
+            let value = match flag.value {
+                ModuleFlagValue::Int { width, value } => Some(Constant::Int {
+                    ty: flag_int_ty(&self.types, width),
+                    value: i64::try_from(value).expect("flag value should fit in i64"),
+                }),
+                ModuleFlagValue::String(_) => None,
+            };
+            [Some(behavior), value].into_iter().flatten()
+        });
+        Constants::new(
+            (self.program.tags.len() + self.callables.len()) as u64,
+            tags.chain(flags).collect::<Vec<_>>(),
+        )
+    }
+
+    /// Writes the module constants and the `llvm.module.flags` metadata that uses them.
+    fn write_module_flags(&mut self, constants: &Constants) {
+        let flags = get_module_flags(self.program);
+        constants.write(&mut self.writer, &self.types, &self.program.tags);
+
+        self.writer.enter_block(METADATA_BLOCK);
+        let mut next_metadata = 0;
+        let mut nodes = Vec::new();
+        for flag in &flags {
+            let mut operands = Vec::new();
+            let behavior = constants.id(Constant::Int {
+                ty: self.types.i32,
+                value: flag.behavior.into(),
+            });
+            self.writer
+                .record(METADATA_VALUE, &[self.types.i32, behavior]);
+            self.writer.record(METADATA_STRING, &chars(flag.name));
+            operands.extend([next_metadata + 1, next_metadata + 2]);
+            next_metadata += 2;
+            match flag.value {
+                ModuleFlagValue::Int { width, value } => {
+                    let ty = flag_int_ty(&self.types, width);
+                    let value = constants.id(Constant::Int {
+                        ty,
+                        value: i64::try_from(value).expect("flag value should fit in i64"),
+                    });
+                    self.writer.record(METADATA_VALUE, &[ty, value]);
+                }
+                ModuleFlagValue::String(value) => {
+                    self.writer.record(METADATA_STRING, &chars(value));
+                }
+            }
+            operands.push(next_metadata + 1);
+            next_metadata += 1;
+            // Node operands are offset by one, so that zero can stand for a null operand.
+            self.writer.record(METADATA_NODE, &operands);
+            nodes.push(next_metadata);
+            next_metadata += 1;
+        }
+        self.writer
+            .record(METADATA_NAME, &chars("llvm.module.flags"));
+        self.writer.record(METADATA_NAMED_NODE, &nodes);
+        self.writer.exit_block();
+    }
+
+    fn write_symbols(
+        &mut self,
+        values: impl IntoIterator<Item = (u64, String)>,
+        blocks: impl IntoIterator<Item = (u64, String)>,
+    ) {
+        self.writer.enter_block(VALUE_SYMTAB_BLOCK);
+        for (code, entries) in [
+            (SYMTAB_ENTRY, values.into_iter().collect::<Vec<_>>()),
+            (SYMTAB_BLOCK_ENTRY, blocks.into_iter().collect()),
+        ] {
+            for (id, name) in entries {
+                let mut record = vec![id];
+                record.extend(chars(&name));
+                self.writer.record(code, &record);
+            }
+        }
+        self.writer.exit_block();
+    }
+
+    fn write_function(&mut self, entry_block: rir::BlockId, first_local_id: u64) {
+        let program = self.program;
+        let mut blocks = vec![entry_block];
+        blocks.extend(
+            get_all_block_successors(entry_block, program)
+                .into_iter()
+                .filter(|&block_id| block_id != entry_block),
+        );
+        let block_indices: FxHashMap<rir::BlockId, u64> = blocks
+            .iter()
+            .zip(0..)
+            .map(|(&id, index)| (id, index))
+            .collect();
+        let instrs = || {
+            blocks
+                .iter()
+                .flat_map(|&block_id| program.get_block(block_id).0.iter())
+        };
+
+        let constants = Constants::new(
+            first_local_id,
+            instrs().flat_map(|instr| self.instruction_constants(instr)),
+        );
+        let mut variables = FxHashMap::default();
+        let mut next_id = constants.next_id();
+        for instr in instrs() {
+            if defines_value(program, instr) {
+                if let Some(variable) = output_variable(instr) {
+                    variables.insert(variable.variable_id, next_id);
+                }
+                next_id += 1;
+            }
+        }
+
+        self.writer.enter_block(FUNCTION_BLOCK);
+        self.writer
+            .record(FUNCTION_DECLAREBLOCKS, &[blocks.len() as u64]);
+        constants.write(&mut self.writer, &self.types, &program.tags);
+        let mut function = FunctionWriter {
+            types: &self.types,
+            constants: &constants,
+            variables: &variables,
+            block_indices: &block_indices,
+            callables: &self.callables,
+            first_callable_id: program.tags.len() as u64,
+            next_id: constants.next_id(),
+        };
+        for instr in instrs() {
+            let (code, record) = function.instruction(program, instr);
+            self.writer.record(code, &record);
+            if defines_value(program, instr) {
+                function.next_id += 1;
+            }
+        }
+
+        let mut values = variables
+            .iter()
+            .map(|(&variable_id, &id)| {
+                let name = ToQir::<String>::to_qir(&variable_id, program);
+                (id, name.trim_start_matches('%').to_string())
+            })
+            .collect::<Vec<_>>();
+        values.sort_unstable();
+        let block_names = blocks
+            .iter()
+            .zip(0..)
+            .map(|(block_id, index)| (index, ToQir::<String>::to_qir(block_id, program)))
+            .collect::<Vec<_>>();
+        self.write_symbols(values, block_names);
+        self.writer.exit_block();
+    }
+
+    /// The constants used by the operands of the instruction.
+    fn instruction_constants(&self, instr: &rir::Instruction) -> Vec<Constant> {
+        let types = &self.types;
+        let mut constants = Vec::new();
+        let mut operand = |operand: &rir::Operand| {
+            if let rir::Operand::Literal(literal) = operand {
+                constants.extend(literal_constants(types, *literal));
+            }
+        };
+        qsc_rir::utils::for_each_operand(instr, &mut operand);
+        match instr {
+            rir::Instruction::LogicalNot(..) => constants.push(Constant::Int {
+                ty: types.bool,
+                value: 1,
+            }),
+            rir::Instruction::BitwiseNot(..) => constants.push(Constant::Int {
+                ty: types.int,
+                value: -1,
+            }),
+            _ => {}
+        }
+        constants
+    }
+}
+
+/// Numbers the operands of the instructions of a function.
+struct FunctionWriter<'a> {
+    types: &'a Types,
+    constants: &'a Constants,
+    variables: &'a FxHashMap<rir::VariableId, u64>,
+    block_indices: &'a FxHashMap<rir::BlockId, u64>,
+    callables: &'a [rir::CallableId],
+    /// The value ID of the first callable, which follows the globals.
+    first_callable_id: u64,
+    /// The value ID of the next instruction, which operands are numbered relative to.
+    next_id: u64,
+}
+
+impl FunctionWriter<'_> {
+    fn value_id(&self, operand: &rir::Operand) -> u64 {
+        match operand {
+            rir::Operand::Literal(literal) => {
+                self.constants.id(*literal_constants(self.types, *literal)
+                    .last()
+                    .expect("literal should have a constant"))
+            }
+            rir::Operand::Variable(variable) => self.variables[&variable.variable_id],
+        }
+    }
+
+    /// The value relative to the instruction, which is negative for values defined after the instruction.
+    #[allow(clippy::cast_possible_wrap)]
+    fn relative_signed(&self, operand: &rir::Operand) -> i64 {
+        self.next_id as i64 - self.value_id(operand) as i64
+    }
+
+    /// The value relative to the instruction, wrapping around to a large 32-bit value for values defined after
+    /// the instruction.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn relative(&self, operand: &rir::Operand) -> u64 {
+        u64::from(self.relative_signed(operand) as u32)
+    }
+
+    /// The value relative to the instruction, followed by its type if it is defined after the instruction.
+    fn relative_with_type(&self, operand: &rir::Operand) -> Vec<u64> {
+        let mut record = vec![self.relative(operand)];
+        if self.value_id(operand) >= self.next_id {
+            record.push(self.types.rir(operand.get_type()));
+        }
+        record
+    }
+
+    fn block(&self, block_id: rir::BlockId) -> u64 {
+        self.block_indices[&block_id]
+    }
+
+    fn binop(&self, lhs: &rir::Operand, rhs: &rir::Operand, opcode: u64) -> (u64, Vec<u64>) {
+        let mut record = self.relative_with_type(lhs);
+        record.extend([self.relative(rhs), opcode]);
+        (FUNCTION_BINOP, record)
+    }
+
+    fn cast(&self, value: &rir::Operand, ty: rir::Ty, opcode: u64) -> (u64, Vec<u64>) {
+        let mut record = self.relative_with_type(value);
+        record.extend([self.types.rir(ty), opcode]);
+        (FUNCTION_CAST, record)
+    }
+
+    fn cmp(&self, lhs: &rir::Operand, rhs: &rir::Operand, predicate: u64) -> (u64, Vec<u64>) {
+        let mut record = self.relative_with_type(lhs);
+        record.extend([self.relative(rhs), predicate]);
+        (FUNCTION_CMP, record)
+    }
+
+    fn instruction(&self, program: &rir::Program, instr: &rir::Instruction) -> (u64, Vec<u64>) {
+        let bool_true = rir::Operand::Literal(rir::Literal::Bool(true));
+        let int_minus_one = rir::Operand::Literal(rir::Literal::Integer(-1));
+        match instr {
+            rir::Instruction::Add(lhs, rhs, _) | rir::Instruction::Fadd(lhs, rhs, _) => {
+                self.binop(lhs, rhs, 0)
+            }
+            rir::Instruction::Sub(lhs, rhs, _) | rir::Instruction::Fsub(lhs, rhs, _) => {
+                self.binop(lhs, rhs, 1)
+            }
+            rir::Instruction::Mul(lhs, rhs, _) | rir::Instruction::Fmul(lhs, rhs, _) => {
+                self.binop(lhs, rhs, 2)
+            }
+            rir::Instruction::Sdiv(lhs, rhs, _) | rir::Instruction::Fdiv(lhs, rhs, _) => {
+                self.binop(lhs, rhs, 4)
+            }
+            rir::Instruction::Srem(lhs, rhs, _) => self.binop(lhs, rhs, 6),
+            rir::Instruction::Shl(lhs, rhs, _) => self.binop(lhs, rhs, 7),
+            rir::Instruction::Ashr(lhs, rhs, _) => self.binop(lhs, rhs, 9),
+            rir::Instruction::BitwiseAnd(lhs, rhs, _)
+            | rir::Instruction::LogicalAnd(lhs, rhs, _) => self.binop(lhs, rhs, 10),
+            rir::Instruction::BitwiseOr(lhs, rhs, _) | rir::Instruction::LogicalOr(lhs, rhs, _) => {
+                self.binop(lhs, rhs, 11)
+            }
+            rir::Instruction::BitwiseXor(lhs, rhs, _) => self.binop(lhs, rhs, 12),
+            rir::Instruction::LogicalNot(value, _) => self.binop(value, &bool_true, 12),
+            rir::Instruction::BitwiseNot(value, _) => self.binop(value, &int_minus_one, 12),
+            rir::Instruction::Fptosi(value, variable) => self.cast(value, variable.ty, 4),
+            rir::Instruction::Sitofp(value, variable) => self.cast(value, variable.ty, 6),
+            rir::Instruction::Fcmp(condition, lhs, rhs, _) => {
+                self.cmp(lhs, rhs, fcmp_predicate(*condition))
+            }
+            rir::Instruction::Icmp(condition, lhs, rhs, _) => {
+                self.cmp(lhs, rhs, icmp_predicate(*condition))
+            }
+            rir::Instruction::Call(callable_id, args, _) => {
+                let callable = program.get_callable(*callable_id);
+                let callee = self.first_callable_id
+                    + self
+                        .callables
+                        .iter()
+                        .position(|id| id == callable_id)
+                        .expect("callable should be declared") as u64;
+                let ty = self.types.ids[&Type::Function(
+                    callable
+                        .output_type
+                        .map_or(self.types.void, |ty| self.types.rir(ty)),
+                    callable
+                        .input_type
+                        .iter()
+                        .map(|&ty| self.types.rir(ty))
+                        .collect(),
+                )];
+                let mut record = vec![0, CALL_EXPLICIT_TYPE, ty, self.next_id - callee];
+                record.extend(args.iter().map(|arg| self.relative(arg)));
+                (FUNCTION_CALL, record)
+            }
+            rir::Instruction::Phi(args, variable) => {
+                let mut record = vec![self.types.rir(variable.ty)];
+                for (arg, block_id) in args {
+                    record.extend([signed(self.relative_signed(arg)), self.block(*block_id)]);
+                }
+                (FUNCTION_PHI, record)
+            }
+            rir::Instruction::Branch(condition, true_id, false_id) => (
+                FUNCTION_BR,
+                vec![
+                    self.block(*true_id),
+                    self.block(*false_id),
+                    self.relative(&rir::Operand::Variable(*condition)),
+                ],
+            ),
+            rir::Instruction::Jump(block_id) => (FUNCTION_BR, vec![self.block(*block_id)]),
+            rir::Instruction::Return => (FUNCTION_RET, Vec::new()),
+            rir::Instruction::Store(..) => unimplemented!("store should be removed by pass"),
+        }
+    }
+}
+
+fn flag_int_ty(types: &Types, width: u32) -> u64 {
+    match width {
+        1 => types.bool,
+        2 => types.i2,
+        32 => types.i32,
+        64 => types.int,
+        _ => panic!("unsupported module flag width {width}"),
+    }
+}
+
+/// The constants for a literal, with the constant for the literal itself last.
+fn literal_constants(types: &Types, literal: rir::Literal) -> Vec<Constant> {
+    let int = |value: u32| Constant::Int {
+        ty: types.int,
+        value: value.into(),
+    };
+    match literal {
+        rir::Literal::Bool(value) => vec![Constant::Int {
+            ty: types.bool,
+            value: value.into(),
+        }],
+        rir::Literal::Integer(value) => vec![Constant::Int {
+            ty: types.int,
+            value,
+        }],
+        rir::Literal::Double(value) => vec![Constant::Double(value.to_bits())],
+        rir::Literal::Pointer => vec![Constant::Null(types.pointer)],
+        rir::Literal::Tag(tag) => vec![int(0), Constant::Tag(tag)],
+        rir::Literal::Qubit(id) => vec![
+            int(id),
+            Constant::IntToPtr {
+                ty: types.qubit,
+                value: id.into(),
+            },
+        ],
+        rir::Literal::Result(id) => vec![
+            int(id),
+            Constant::IntToPtr {
+                ty: types.result,
+                value: id.into(),
+            },
+        ],
+    }
+}
+
+/// Whether the instruction defines a value, which is numbered even when its output is unused.
+fn defines_value(program: &rir::Program, instr: &rir::Instruction) -> bool {
+    match instr {
+        rir::Instruction::Call(callable_id, ..) => {
+            program.get_callable(*callable_id).output_type.is_some()
+        }
+        rir::Instruction::Jump(..) | rir::Instruction::Branch(..) | rir::Instruction::Return => {
+            false
+        }
+        _ => true,
+    }
+}
+
+fn output_variable(instr: &rir::Instruction) -> Option<rir::Variable> {
+    match instr {
+        rir::Instruction::Call(_, _, output) => *output,
+        rir::Instruction::Jump(..) | rir::Instruction::Branch(..) | rir::Instruction::Return => {
+            None
+        }
+        rir::Instruction::Store(_, variable)
+        | rir::Instruction::Add(_, _, variable)
+        | rir::Instruction::Sub(_, _, variable)
+        | rir::Instruction::Mul(_, _, variable)
+        | rir::Instruction::Sdiv(_, _, variable)
+        | rir::Instruction::Srem(_, _, variable)
+        | rir::Instruction::Shl(_, _, variable)
+        | rir::Instruction::Ashr(_, _, variable)
+        | rir::Instruction::Fadd(_, _, variable)
+        | rir::Instruction::Fsub(_, _, variable)
+        | rir::Instruction::Fmul(_, _, variable)
+        | rir::Instruction::Fdiv(_, _, variable)
+        | rir::Instruction::Fcmp(_, _, _, variable)
+        | rir::Instruction::Icmp(_, _, _, variable)
+        | rir::Instruction::LogicalNot(_, variable)
+        | rir::Instruction::LogicalAnd(_, _, variable)
+        | rir::Instruction::LogicalOr(_, _, variable)
+        | rir::Instruction::BitwiseNot(_, variable)
+        | rir::Instruction::Sitofp(_, variable)
+        | rir::Instruction::Fptosi(_, variable)
+        | rir::Instruction::BitwiseAnd(_, _, variable)
+        | rir::Instruction::BitwiseOr(_, _, variable)
+        | rir::Instruction::BitwiseXor(_, _, variable)
+        | rir::Instruction::Phi(_, variable) => Some(*variable),
+    }
+}
+
+fn function_name(callable: &rir::Callable) -> String {
+    if callable.body.is_some() {
+        "ENTRYPOINT__main".to_string()
+    } else {
+        callable.name.clone()
+    }
+}
+
+fn fcmp_predicate(condition: rir::FcmpConditionCode) -> u64 {
+    match condition {
+        rir::FcmpConditionCode::False => 0,
+        rir::FcmpConditionCode::OrderedAndEqual => 1,
+        rir::FcmpConditionCode::OrderedAndGreaterThan => 2,
+        rir::FcmpConditionCode::OrderedAndGreaterThanOrEqual => 3,
+        rir::FcmpConditionCode::OrderedAndLessThan => 4,
+        rir::FcmpConditionCode::OrderedAndLessThanOrEqual => 5,
+        rir::FcmpConditionCode::OrderedAndNotEqual => 6,
+        rir::FcmpConditionCode::Ordered => 7,
+        rir::FcmpConditionCode::Unordered => 8,
+        rir::FcmpConditionCode::UnorderedOrEqual => 9,
+        rir::FcmpConditionCode::UnorderedOrGreaterThan => 10,
+        rir::FcmpConditionCode::UnorderedOrGreaterThanOrEqual => 11,
+        rir::FcmpConditionCode::UnorderedOrLessThan => 12,
+        rir::FcmpConditionCode::UnorderedOrLessThanOrEqual => 13,
+        rir::FcmpConditionCode::UnorderedOrNotEqual => 14,
+        rir::FcmpConditionCode::True => 15,
+    }
+}
+
+fn icmp_predicate(condition: rir::ConditionCode) -> u64 {
+    match condition {
+        rir::ConditionCode::Eq => 32,
+        rir::ConditionCode::Ne => 33,
+        rir::ConditionCode::Sgt => 38,
+        rir::ConditionCode::Sge => 39,
+        rir::ConditionCode::Slt => 40,
+        rir::ConditionCode::Sle => 41,
+    }
+}
+
+/// Encodes a signed value with the sign in the lowest bit, as LLVM does for signed operands.
+#[allow(clippy::cast_sign_loss)]
+fn signed(value: i64) -> u64 {
+    if value >= 0 {
+        (value as u64) << 1
+    } else {
+        // The minimum value has no positive counterpart, and is encoded as negative zero.
+        (value.wrapping_neg() as u64) << 1 | 1
+    }
+}
+
+fn chars(value: &str) -> Vec<u64> {
+    value.bytes().map(u64::from).collect()
+}
@@ -0,0 +1,490 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::write_program;
+use expect_test::{expect, Expect};
+use qsc_rir::{builder, rir};
+use std::fmt::Write;
+
+/// Reads the bits of a bitstream written with only unabbreviated records.
+struct BitReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl BitReader<'_> {
+    fn read(&mut self, width: usize) -> u64 {
+        let mut value = 0;
+        for i in 0..width {
+            let byte = self.bytes[(self.position + i) / 8];
+            value |= u64::from((byte >> ((self.position + i) % 8)) & 1) << i;
+        }
+        self.position += width;
+        value
+    }
+
+    fn read_vbr(&mut self, width: usize) -> u64 {
+        let mut value = 0;
+        let mut shift = 0;
+        loop {
+            let piece = self.read(width);
+            value |= (piece & ((1 << (width - 1)) - 1)) << shift;
+            if piece >> (width - 1) == 0 {
+                return value;
+            }
+            shift += width - 1;
+        }
+    }
+
+    fn align(&mut self) {
+        self.position = self.position.div_ceil(32) * 32;
+    }
+
+    fn done(&self) -> bool {
+        self.position == self.bytes.len() * 8
+    }
+}
+
+/// Dumps the blocks and records of the bitstream, one per line.
+fn dump(bytes: &[u8]) -> String {
+    assert_eq!(
+        &bytes[..4],
+        b"BC\xC0\xDE",
+        "bitcode should start with the magic number"
+    );
+    assert_eq!(
+        bytes.len() % 4,
+        0,
+        "bitcode should be a whole number of words"
+    );
+    let mut reader = BitReader {
+        bytes,
+        position: 32,
+    };
+    let mut output = String::new();
+    let mut widths = vec![2];
+    while !reader.done() {
+        let width = *widths.last().expect("width should be known");
+        let indent = "  ".repeat(widths.len() - 1);
+        match reader.read(width) {
+            0 => {
+                reader.align();
+                widths.pop();
+            }
+            1 => {
+                let block_id = reader.read_vbr(8);
+                let width = reader.read_vbr(4);
+                reader.align();
+                let words = reader.read(32);
+                let end = reader.position + usize::try_from(words).expect("length should fit") * 32;
+                assert!(end <= bytes.len() * 8, "block should fit in the bitcode");
+                writeln!(output, "{indent}block {block_id}").expect("writing should succeed");
+                widths.push(usize::try_from(width).expect("width should fit"));
+            }
+            3 => {
+                let code = reader.read_vbr(6);
+                let len = reader.read_vbr(6);
+                let operands = (0..len).map(|_| reader.read_vbr(6)).collect::<Vec<_>>();
+                // Names are written one character per operand, so show any trailing name as a string.
+                let name_len = operands
+                    .iter()
+                    .rev()
+                    .take_while(|&&c| (0x20..0x7F).contains(&c))
+                    .count();
+                let (operands, name) = if name_len > 2 {
+                    operands.split_at(operands.len() - name_len)
+                } else {
+                    (&operands[..], &[][..])
+                };
+                let mut text = format!("{operands:?}");
+                if !name.is_empty() {
+                    let name = name
+                        .iter()
+                        .map(|&c| char::from(u8::try_from(c).expect("character should fit in u8")))
+                        .collect::<String>();
+                    write!(text, " {name:?}").expect("writing should succeed");
+                }
+                writeln!(output, "{indent}  record {code} {text}").expect("writing should succeed");
+            }
+            id => panic!("unexpected abbreviation id {id}"),
+        }
+    }
+    assert_eq!(widths, [2], "all blocks should be exited");
+    output
+}
+
+fn check(program: &rir::Program, expect: &Expect) {
+    expect.assert_eq(&dump(&write_program(program)));
+}
+
+#[test]
+#[allow(clippy::too_many_lines)]
+fn bell_program() {
+    check(
+        &builder::bell_program(),
+        &expect![[r#"
+        block 13
+            record 1 [] "qsc_codegen"
+            record 2 [0]
+        block 8
+            record 1 [1]
+          block 10
+              record 3 [1, 4294967295, 3, 101, 110, 116, 114, 121, 95, 112, 111, 105, 110, 116, 0, 3, 111, 117, 116, 112, 117, 116, 95, 108, 97, 98, 101, 108, 105, 110, 103, 95, 115, 99, 104, 101, 109, 97, 0, 4, 113, 105, 114, 95, 112, 114, 111, 102, 105, 108, 101, 115, 0, 98, 97, 115, 101, 95, 112, 114, 111, 102, 105, 108, 101, 0, 4, 114, 101, 113, 117, 105, 114, 101, 100, 95, 110, 117, 109, 95, 113, 117, 98, 105, 116, 115, 0, 50, 0, 4, 114, 101, 113, 117, 105, 114, 101, 100, 95, 110, 117, 109, 95, 114, 101, 115, 117, 108, 116, 115, 0, 50, 0]
+              record 3 [2, 4294967295, 3, 105, 114, 114, 101, 118, 101, 114, 115, 105, 98, 108, 101, 0]
+          block 9
+              record 2 [1]
+              record 2 [2]
+          block 17
+              record 1 [18]
+              record 19 [] "Result"
+              record 6 [0]
+              record 8 [0, 0]
+              record 19 [] "Qubit"
+              record 6 [0]
+              record 8 [2, 0]
+              record 7 [8]
+              record 8 [4, 0]
+              record 2 []
+              record 7 [1]
+              record 7 [2]
+              record 7 [32]
+              record 7 [64]
+              record 4 []
+              record 21 [0, 6, 3]
+              record 21 [0, 6, 3, 3]
+              record 21 [0, 6, 3, 1]
+              record 21 [0, 6, 10, 5]
+              record 21 [0, 6, 1, 5]
+              record 21 [0, 6]
+            record 8 [12, 0, 1, 0, 0, 0, 0, 0, 0, 0]
+            record 8 [13, 0, 1, 0, 0, 0, 0, 0, 0, 0]
+            record 8 [14, 0, 1, 0, 2, 0, 0, 0, 0, 0]
+            record 8 [15, 0, 1, 0, 0, 0, 0, 0, 0, 0]
+            record 8 [16, 0, 1, 0, 0, 0, 0, 0, 0, 0]
+            record 8 [17, 0, 0, 0, 1, 0, 0, 0, 0, 0]
+          block 11
+              record 1 [7]
+              record 4 [0]
+              record 1 [9]
+              record 4 [2]
+              record 4 [14]
+              record 4 [0]
+          block 15
+              record 2 [9, 7]
+              record 1 [] "qir_major_version"
+              record 2 [9, 7]
+              record 3 [1, 2, 3]
+              record 2 [9, 8]
+              record 1 [] "qir_minor_version"
+              record 2 [9, 9]
+              record 3 [5, 6, 7]
+              record 2 [9, 7]
+              record 1 [] "dynamic_qubit_management"
+              record 2 [7, 6]
+              record 3 [9, 10, 11]
+              record 2 [9, 7]
+              record 1 [] "dynamic_result_management"
+              record 2 [7, 6]
+              record 3 [13, 14, 15]
+              record 4 [] "llvm.module.flags"
+              record 10 [3, 7, 11, 15]
+          block 14
+              record 1 [0] "__quantum__qis__h__body"
+              record 1 [1] "__quantum__qis__cx__body"
+              record 1 [2] "__quantum__qis__m__body"
+              record 1 [3] "__quantum__rt__array_record_output"
+              record 1 [4] "__quantum__rt__result_record_output"
+              record 1 [5] "ENTRYPOINT__main"
+          block 12
+              record 1 [1]
+            block 11
+                record 1 [10]
+                record 4 [0]
+                record 4 [2]
+                record 4 [4]
+                record 1 [5]
+                record 2 []
+                record 1 [1]
+                record 11 [10, 10, 10]
+                record 11 [10, 10, 11]
+                record 1 [3]
+                record 11 [10, 10, 10]
+                record 11 [10, 10, 11]
+              record 34 [0, 32768, 12, 18, 2]
+              record 34 [0, 32768, 13, 17, 2, 1]
+              record 34 [0, 32768, 14, 16, 2, 4]
+              record 34 [0, 32768, 14, 16, 1, 3]
+              record 34 [0, 32768, 15, 15, 6, 5]
+              record 34 [0, 32768, 16, 14, 4, 5]
+              record 34 [0, 32768, 16, 14, 3, 5]
+              record 10 []
+            block 14
+                record 2 [0] "block_0"
+    "#]],
+    );
+}
+
+#[test]
+#[allow(clippy::too_many_lines)]
+fn teleport_program() {
+    check(
+        &builder::teleport_program(),
+        &expect![[r#"
+        block 13
+            record 1 [] "qsc_codegen"
+            record 2 [0]
+        block 8
+            record 1 [1]
+          block 10
+              record 3 [1, 4294967295, 3, 101, 110, 116, 114, 121, 95, 112, 111, 105, 110, 116, 0, 3, 111, 117, 116, 112, 117, 116, 95, 108, 97, 98, 101, 108, 105, 110, 103, 95, 115, 99, 104, 101, 109, 97, 0, 4, 113, 105, 114, 95, 112, 114, 111, 102, 105, 108, 101, 115, 0, 97, 100, 97, 112, 116, 105, 118, 101, 95, 112, 114, 111, 102, 105, 108, 101, 0, 4, 114, 101, 113, 117, 105, 114, 101, 100, 95, 110, 117, 109, 95, 113, 117, 98, 105, 116, 115, 0, 51, 0, 4, 114, 101, 113, 117, 105, 114, 101, 100, 95, 110, 117, 109, 95, 114, 101, 115, 117, 108, 116, 115, 0, 51, 0]
+              record 3 [2, 4294967295, 3, 105, 114, 114, 101, 118, 101, 114, 115, 105, 98, 108, 101, 0]
+          block 9
+              record 2 [1]
+              record 2 [2]
+          block 17
+              record 1 [18]
+              record 19 [] "Result"
+              record 6 [0]
+              record 8 [0, 0]
+              record 19 [] "Qubit"
+              record 6 [0]
+              record 8 [2, 0]
+              record 7 [8]
+              record 8 [4, 0]
+              record 2 []
+              record 7 [1]
+              record 7 [2]
+              record 7 [32]
+              record 7 [64]
+              record 4 []
+              record 21 [0, 6, 3]
+              record 21 [0, 6, 3, 3]
+              record 21 [0, 6, 3, 1]
+              record 21 [0, 7, 1]
+              record 21 [0, 6, 1, 5]
+              record 21 [0, 6]
+            record 8 [12, 0, 1, 0, 0, 0, 0, 0, 0, 0]
+            record 8 [12, 0, 1, 0, 0, 0, 0, 0, 0, 0]
+            record 8 [12, 0, 1, 0, 0, 0, 0, 0, 0, 0]
+            record 8 [13, 0, 1, 0, 0, 0, 0, 0, 0, 0]
+            record 8 [14, 0, 1, 0, 2, 0, 0, 0, 0, 0]
+            record 8 [15, 0, 1, 0, 0, 0, 0, 0, 0, 0]
+            record 8 [16, 0, 1, 0, 0, 0, 0, 0, 0, 0]
+            record 8 [17, 0, 0, 0, 1, 0, 0, 0, 0, 0]
+          block 11
+              record 1 [7]
+              record 4 [0]
+              record 1 [9]
+              record 4 [2]
+              record 4 [14]
+              record 4 [0]
+          block 15
+              record 2 [9, 9]
+              record 1 [] "qir_major_version"
+              record 2 [9, 9]
+              record 3 [1, 2, 3]
+              record 2 [9, 10]
+              record 1 [] "qir_minor_version"
+              record 2 [9, 11]
+              record 3 [5, 6, 7]
+              record 2 [9, 9]
+              record 1 [] "dynamic_qubit_management"
+              record 2 [7, 8]
+              record 3 [9, 10, 11]
+              record 2 [9, 9]
+              record 1 [] "dynamic_result_management"
+              record 2 [7, 8]
+              record 3 [13, 14, 15]
+              record 4 [] "llvm.module.flags"
+              record 10 [3, 7, 11, 15]
+          block 14
+              record 1 [0] "__quantum__qis__h__body"
+              record 1 [1] "__quantum__qis__z__body"
+              record 1 [2] "__quantum__qis__x__body"
+              record 1 [3] "__quantum__qis__cx__body"
+              record 1 [4] "__quantum__qis__mresetz__body"
+              record 1 [5] "__quantum__qis__read_result__body"
+              record 1 [6] "__quantum__rt__result_record_output"
+              record 1 [7] "ENTRYPOINT__main"
+          block 12
+              record 1 [5]
+            block 11
+                record 1 [10]
+                record 4 [0]
+                record 4 [4]
+                record 4 [2]
+                record 1 [5]
+                record 2 []
+                record 1 [1]
+                record 11 [10, 10, 12]
+                record 11 [10, 10, 14]
+                record 11 [10, 10, 13]
+                record 1 [3]
+                record 11 [10, 10, 12]
+                record 11 [10, 10, 13]
+                record 11 [10, 10, 14]
+              record 34 [0, 32768, 12, 20, 3]
+              record 34 [0, 32768, 12, 22, 2]
+              record 34 [0, 32768, 13, 19, 2, 1]
+              record 34 [0, 32768, 13, 19, 3, 2]
+              record 34 [0, 32768, 12, 22, 3]
+              record 34 [0, 32768, 14, 18, 3, 6]
+              record 34 [0, 32768, 15, 17, 6]
+              record 11 [1, 2, 1]
+              record 34 [0, 32768, 12, 22, 2]
+              record 11 [2]
+              record 34 [0, 32768, 14, 19, 3, 6]
+              record 34 [0, 32768, 15, 18, 6]
+              record 11 [3, 4, 1]
+              record 34 [0, 32768, 12, 22, 3]
+              record 11 [4]
+              record 34 [0, 32768, 14, 20, 3, 6]
+              record 34 [0, 32768, 16, 18, 6, 9]
+              record 10 []
+            block 14
+                record 1 [22] "var_0"
+                record 1 [23] "var_1"
+                record 2 [0] "block_0"
+                record 2 [1] "block_1"
+                record 2 [2] "block_2"
+                record 2 [3] "block_3"
+                record 2 [4] "block_4"
+    "#]],
+    );
+}
+
+#[test]
+#[allow(clippy::too_many_lines)]
+fn bell_program_with_output_labels() {
+    let mut program = builder::bell_program();
+    let mut tags = ["c", "c[0]", "c[1]"]
+        .map(|label| program.add_tag(label))
+        .into_iter();
+    for block in program.blocks.values_mut() {
+        for instr in &mut block.0 {
+            if let rir::Instruction::Call(_, args, _) = instr {
+                for arg in args {
+                    if *arg == rir::Operand::Literal(rir::Literal::Pointer) {
+                        *arg = rir::Operand::Literal(tags.next().expect("tag should be available"));
+                    }
+                }
+            }
+        }
+    }
+    check(
+        &program,
+        &expect![[r#"
+        block 13
+            record 1 [] "qsc_codegen"
+            record 2 [0]
+        block 8
+            record 1 [1]
+          block 10
+              record 3 [1, 4294967295, 3, 101, 110, 116, 114, 121, 95, 112, 111, 105, 110, 116, 0, 3, 111, 117, 116, 112, 117, 116, 95, 108, 97, 98, 101, 108, 105, 110, 103, 95, 115, 99, 104, 101, 109, 97, 0, 4, 113, 105, 114, 95, 112, 114, 111, 102, 105, 108, 101, 115, 0, 98, 97, 115, 101, 95, 112, 114, 111, 102, 105, 108, 101, 0, 4, 114, 101, 113, 117, 105, 114, 101, 100, 95, 110, 117, 109, 95, 113, 117, 98, 105, 116, 115, 0, 50, 0, 4, 114, 101, 113, 117, 105, 114, 101, 100, 95, 110, 117, 109, 95, 114, 101, 115, 117, 108, 116, 115, 0, 50, 0]
+              record 3 [2, 4294967295, 3, 105, 114, 114, 101, 118, 101, 114, 115, 105, 98, 108, 101, 0]
+          block 9
+              record 2 [1]
+              record 2 [2]
+          block 17
+              record 1 [22]
+              record 19 [] "Result"
+              record 6 [0]
+              record 8 [0, 0]
+              record 19 [] "Qubit"
+              record 6 [0]
+              record 8 [2, 0]
+              record 7 [8]
+              record 8 [4, 0]
+              record 2 []
+              record 7 [1]
+              record 7 [2]
+              record 7 [32]
+              record 7 [64]
+              record 4 []
+              record 11 [2, 4]
+              record 8 [12, 0]
+              record 11 [5, 4]
+              record 8 [14, 0]
+              record 21 [0, 6, 3]
+              record 21 [0, 6, 3, 3]
+              record 21 [0, 6, 3, 1]
+              record 21 [0, 6, 10, 5]
+              record 21 [0, 6, 1, 5]
+              record 21 [0, 6]
+            record 7 [13, 1, 14, 3, 0, 0]
+            record 7 [15, 1, 15, 3, 0, 0]
+            record 7 [15, 1, 16, 3, 0, 0]
+            record 8 [16, 0, 1, 0, 0, 0, 0, 0, 0, 0]
+            record 8 [17, 0, 1, 0, 0, 0, 0, 0, 0, 0]
+            record 8 [18, 0, 1, 0, 2, 0, 0, 0, 0, 0]
+            record 8 [19, 0, 1, 0, 0, 0, 0, 0, 0, 0]
+            record 8 [20, 0, 1, 0, 0, 0, 0, 0, 0, 0]
+            record 8 [21, 0, 0, 0, 1, 0, 0, 0, 0, 0]
+          block 11
+              record 1 [7]
+              record 4 [0]
+              record 1 [9]
+              record 4 [2]
+              record 4 [14]
+              record 4 [0]
+              record 1 [12]
+              record 9 [99]
+              record 1 [14]
+              record 9 [] "c[0]"
+              record 9 [] "c[1]"
+          block 15
+              record 2 [9, 10]
+              record 1 [] "qir_major_version"
+              record 2 [9, 10]
+              record 3 [1, 2, 3]
+              record 2 [9, 11]
+              record 1 [] "qir_minor_version"
+              record 2 [9, 12]
+              record 3 [5, 6, 7]
+              record 2 [9, 10]
+              record 1 [] "dynamic_qubit_management"
+              record 2 [7, 9]
+              record 3 [9, 10, 11]
+              record 2 [9, 10]
+              record 1 [] "dynamic_result_management"
+              record 2 [7, 9]
+              record 3 [13, 14, 15]
+              record 4 [] "llvm.module.flags"
+              record 10 [3, 7, 11, 15]
+          block 14
+              record 1 [3] "__quantum__qis__h__body"
+              record 1 [4] "__quantum__qis__cx__body"
+              record 1 [5] "__quantum__qis__m__body"
+              record 1 [6] "__quantum__rt__array_record_output"
+              record 1 [7] "__quantum__rt__result_record_output"
+              record 1 [8] "ENTRYPOINT__main"
+          block 12
+              record 1 [1]
+            block 11
+                record 1 [10]
+                record 4 [0]
+                record 4 [2]
+                record 4 [4]
+                record 1 [1]
+                record 11 [10, 10, 16]
+                record 11 [10, 10, 17]
+                record 1 [3]
+                record 11 [10, 10, 16]
+                record 11 [10, 10, 17]
+                record 1 [5]
+                record 20 [12, 13, 0, 10, 16, 10, 16]
+                record 20 [14, 15, 1, 10, 16, 10, 16]
+                record 20 [14, 15, 2, 10, 16, 10, 16]
+              record 34 [0, 32768, 16, 23, 5]
+              record 34 [0, 32768, 17, 22, 5, 4]
+              record 34 [0, 32768, 18, 21, 5, 7]
+              record 34 [0, 32768, 18, 21, 4, 6]
+              record 34 [0, 32768, 19, 20, 8, 3]
+              record 34 [0, 32768, 20, 19, 7, 2]
+              record 34 [0, 32768, 20, 19, 6, 1]
+              record 10 []
+            block 14
+                record 2 [0] "block_0"
+    "#]],
+    );
+}
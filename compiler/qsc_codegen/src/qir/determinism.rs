@@ -0,0 +1,192 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! Determinism auditing for code generation.
+//!
+//! The audit partially evaluates the same entry point twice and runs the RIR pass pipeline on each result. The
+//! second run happens within `qsc_data_structures::fx::with_seed`, which changes the iteration order of every hash
+//! map and set that capabilities analysis, partial evaluation, the passes and QIR generation create. Every snapshot,
+//! from the first pass onward, as well as the final QIR, is expected to be byte-identical between the two runs. The
+//! first stage whose output differs is reported.
+
+#[cfg(test)]
+mod tests;
+
+use super::{get_configured_program, transform_program, QirOptions, ToQir};
+use miette::Diagnostic;
+use qsc_data_structures::{fx::with_seed, target::TargetCapabilityFlags};
+use qsc_partial_eval::ProgramEntry;
+use qsc_rca::PackageStoreComputeProperties;
+use qsc_rir::rir::Program;
+use thiserror::Error;
+
+/// The stage name used when the partially evaluated programs differ.
+const PARTIAL_EVALUATION_STAGE: &str = "partial_evaluation";
+
+/// The stage name used when the generated QIR differs.
+const QIR_GENERATION_STAGE: &str = "qir_generation";
+
+/// An error produced while auditing code generation for determinism.
+#[derive(Clone, Debug, Diagnostic, Error)]
+pub enum Error {
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    PartialEvaluation(#[from] qsc_partial_eval::Error),
+
+    #[error("code generation is not deterministic: output of `{stage}` differs between runs")]
+    #[diagnostic(code("Qsc.Codegen.Nondeterministic"))]
+    #[diagnostic(help(
+        "the first difference is at line {line}:\n  first run:  {first}\n  second run: {second}"
+    ))]
+    Nondeterministic {
+        stage: String,
+        line: usize,
+        first: String,
+        second: String,
+    },
+}
+
+/// Generates QIR for the given entry point twice, the second time with hash maps and sets seeded with `seed`, and
+/// verifies that both runs produce byte-identical output. Both runs use the given options, so the audited QIR is
+/// the QIR that `fir_to_qir_with_options` emits. On success, the generated QIR is returned.
+pub fn audit_determinism(
+    fir_store: &qsc_fir::fir::PackageStore,
+    capabilities: TargetCapabilityFlags,
+    compute_properties: Option<PackageStoreComputeProperties>,
+    entry: &ProgramEntry,
+    options: QirOptions,
+    seed: u64,
+) -> Result<String, Error> {
+    // Unless the caller supplies them, the compute properties are analyzed within each run, so that the hash maps
+    // and sets of the analysis are seeded along with those of partial evaluation.
+    let generate = || {
+        let compute_properties = compute_properties.clone().unwrap_or_else(|| {
+            let analyzer = qsc_rca::Analyzer::init(fir_store);
+            analyzer.analyze_all()
+        });
+        generate_with_snapshots(fir_store, compute_properties, entry, capabilities, options)
+    };
+
+    let first = generate()?;
+    // The whole second run is seeded at once, so that every map it creates reads the same seed.
+    let second = with_seed(seed, generate)?;
+    compare_stage(PARTIAL_EVALUATION_STAGE, &first.program, &second.program)?;
+    compare_snapshots(&first.snapshots, &second.snapshots)?;
+    compare_stage(QIR_GENERATION_STAGE, &first.qir, &second.qir)?;
+    Ok(first.qir)
+}
+
+/// The output of each stage of one run of code generation.
+struct Snapshots {
+    /// The partially evaluated program, before any pass.
+    program: String,
+    /// The program after every pass.
+    snapshots: Vec<(String, String)>,
+    /// The generated QIR.
+    qir: String,
+}
+
+fn generate_with_snapshots(
+    fir_store: &qsc_fir::fir::PackageStore,
+    compute_properties: PackageStoreComputeProperties,
+    entry: &ProgramEntry,
+    capabilities: TargetCapabilityFlags,
+    options: QirOptions,
+) -> Result<Snapshots, Error> {
+    let mut program = get_configured_program(
+        fir_store,
+        capabilities,
+        Some(compute_properties),
+        entry,
+        options,
+        &mut Vec::new(),
+    )?;
+    let partially_evaluated = program.to_string();
+    let snapshots = run_passes(&mut program, options);
+    let qir = ToQir::<String>::to_qir(&program, &program);
+    Ok(Snapshots {
+        program: partially_evaluated,
+        snapshots,
+        qir,
+    })
+}
+
+/// Runs the RIR passes on the program, returning the snapshot of the program after every pass.
+fn run_passes(program: &mut Program, options: QirOptions) -> Vec<(String, String)> {
+    let mut snapshots = Vec::new();
+    transform_program(program, options, &mut |stage, program| {
+        snapshots.push((stage.to_string(), program.to_string()));
+    });
+    snapshots
+}
+
+/// Compares the snapshots of the two runs pass by pass. If the runs did not run the same passes in the same order,
+/// such as when one of them ran fewer passes, the first pass at which they diverge is reported as a difference.
+fn compare_snapshots(first: &[(String, String)], second: &[(String, String)]) -> Result<(), Error> {
+    for index in 0..first.len().max(second.len()) {
+        let (first_stage, first_snapshot) = stage_at(first, index);
+        let (second_stage, second_snapshot) = stage_at(second, index);
+        let stage = first_stage.or(second_stage).unwrap_or_default();
+        if first_stage != second_stage {
+            return Err(Error::Nondeterministic {
+                stage: stage.to_string(),
+                line: 1,
+                first: describe_stage(first_stage),
+                second: describe_stage(second_stage),
+            });
+        }
+        compare_stage(stage, first_snapshot, second_snapshot)?;
+    }
+    Ok(())
+}
+
+fn stage_at(snapshots: &[(String, String)], index: usize) -> (Option<&str>, &str) {
+    snapshots
+        .get(index)
+        .map_or((None, ""), |(stage, snapshot)| {
+            (Some(stage.as_str()), snapshot.as_str())
+        })
+}
+
+fn describe_stage(stage: Option<&str>) -> String {
+    stage.map_or_else(
+        || "<end of output>".to_string(),
+        |stage| format!("<output of `{stage}`>"),
+    )
+}
+
+fn compare_stage(stage: &str, first: &str, second: &str) -> Result<(), Error> {
+    match first_difference(first, second) {
+        Some((line, first, second)) => Err(Error::Nondeterministic {
+            stage: stage.to_string(),
+            line,
+            first,
+            second,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Finds the first line that differs between the two texts, returning its one-based line number and the
+/// content of that line in each text. A missing line is reported as `<end of output>`.
+fn first_difference(first: &str, second: &str) -> Option<(usize, String, String)> {
+    if first == second {
+        return None;
+    }
+    let mut first_lines = first.lines();
+    let mut second_lines = second.lines();
+    let mut line = 1;
+    loop {
+        match (first_lines.next(), second_lines.next()) {
+            (Some(a), Some(b)) if a == b => line += 1,
+            (None, None) => {
+                // The texts differ only in trailing line endings.
+                return Some((line, "<end of output>".into(), "<end of output>".into()));
+            }
+            (a, b) => {
+                let describe = |l: Option<&str>| l.unwrap_or("<end of output>").to_string();
+                return Some((line, describe(a), describe(b)));
+            }
+        }
+    }
+}
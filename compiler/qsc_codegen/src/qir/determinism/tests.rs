@@ -0,0 +1,143 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{compare_snapshots, compare_stage, first_difference, run_passes, Error};
+use crate::qir::{QirOptions, ToQir};
+use expect_test::expect;
+use qsc_data_structures::{
+    fx::{with_seed, FxHashSet},
+    target::TargetCapabilityFlags,
+};
+use qsc_rir::{builder, rir::Program};
+
+fn adaptive_teleport_program() -> Program {
+    let mut program = builder::teleport_program();
+    program.config.capabilities |= TargetCapabilityFlags::QubitReset;
+    program
+}
+
+#[test]
+fn passes_produce_identical_qir_with_seeded_maps() {
+    let mut first = adaptive_teleport_program();
+    let first_snapshots = run_passes(&mut first, QirOptions::default());
+    let first_qir = ToQir::<String>::to_qir(&first, &first);
+    for seed in 0..8 {
+        let mut second = adaptive_teleport_program();
+        let second_snapshots = with_seed(seed, || run_passes(&mut second, QirOptions::default()));
+        assert_eq!(first_snapshots, second_snapshots);
+        assert_eq!(
+            first_qir,
+            with_seed(seed, || ToQir::<String>::to_qir(&second, &second))
+        );
+    }
+}
+
+/// Renames the callables of the program in the iteration order of a hash set, which depends on the seed.
+fn order_dependent_pass(program: &mut Program) {
+    let names = program
+        .callables
+        .values()
+        .map(|callable| callable.name.clone())
+        .collect::<FxHashSet<_>>();
+    for (index, name) in names.iter().enumerate() {
+        for (_, callable) in program.callables.iter_mut() {
+            if callable.name == *name {
+                callable.name = format!("callable_{index}");
+            }
+        }
+    }
+}
+
+#[test]
+fn order_dependent_pass_is_detected() {
+    let mut first = adaptive_teleport_program();
+    order_dependent_pass(&mut first);
+    let detected = (0..8).any(|seed| {
+        let mut second = adaptive_teleport_program();
+        with_seed(seed, || order_dependent_pass(&mut second));
+        matches!(
+            compare_stage("order_dependent_pass", &first.to_string(), &second.to_string()),
+            Err(Error::Nondeterministic { stage, .. }) if stage == "order_dependent_pass"
+        )
+    });
+    assert!(
+        detected,
+        "the order-dependent pass should be reported as nondeterministic"
+    );
+}
+
+#[test]
+fn snapshots_cover_every_pass() {
+    let mut program = adaptive_teleport_program();
+    let stages = run_passes(&mut program, QirOptions::default())
+        .into_iter()
+        .map(|(stage, _)| stage)
+        .collect::<Vec<_>>();
+    expect![[r#"
+        [
+            "eliminate_dead_blocks",
+            "simplify_control_flow",
+            "remap_block_ids",
+            "transform_to_ssa",
+            "fold_constants",
+            "simplify_control_flow",
+            "remap_block_ids",
+            "eliminate_common_subexpressions",
+            "apply_peephole_optimizations",
+            "decompose_to_gate_set",
+            "transform_from_ssa",
+        ]
+    "#]]
+    .assert_debug_eq(&stages);
+}
+
+#[test]
+fn missing_snapshot_is_reported() {
+    let mut program = adaptive_teleport_program();
+    let first = run_passes(&mut program, QirOptions::default());
+    let second = first[..first.len() - 1].to_vec();
+    let error =
+        compare_snapshots(&first, &second).expect_err("missing snapshot should be reported");
+    expect![[r#"
+        Nondeterministic {
+            stage: "transform_from_ssa",
+            line: 1,
+            first: "<output of `transform_from_ssa`>",
+            second: "<end of output>",
+        }
+    "#]]
+    .assert_debug_eq(&error);
+}
+
+#[test]
+fn first_difference_of_identical_text_is_none() {
+    assert_eq!(first_difference("a\nb\n", "a\nb\n"), None);
+}
+
+#[test]
+fn first_difference_reports_line_and_contents() {
+    expect![[r#"
+        Some(
+            (
+                2,
+                "b",
+                "c",
+            ),
+        )
+    "#]]
+    .assert_debug_eq(&first_difference("a\nb\nd", "a\nc\nd"));
+}
+
+#[test]
+fn first_difference_reports_missing_lines() {
+    expect![[r#"
+        Some(
+            (
+                3,
+                "<end of output>",
+                "extra",
+            ),
+        )
+    "#]]
+    .assert_debug_eq(&first_difference("a\nb", "a\nb\nextra"));
+}
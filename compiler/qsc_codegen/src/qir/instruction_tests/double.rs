@@ -477,3 +477,47 @@ fn fsub_double_variables() {
     );
     expect!["  %var_0 = fsub double %var_1, %var_2"].assert_eq(&inst.to_qir(&Program::default()));
 }
+
+#[test]
+fn fptosi_double_literal() {
+    let inst = Instruction::Fptosi(
+        Operand::Literal(Literal::Double(-PI)),
+        Variable {
+            variable_id: VariableId(0),
+            ty: Ty::Integer,
+        },
+    );
+    expect!["  %var_0 = fptosi double -3.141592653589793 to i64"]
+        .assert_eq(&inst.to_qir(&Program::default()));
+}
+
+#[test]
+fn fptosi_double_variable() {
+    let inst = Instruction::Fptosi(
+        Operand::Variable(Variable {
+            variable_id: VariableId(1),
+            ty: Ty::Double,
+        }),
+        Variable {
+            variable_id: VariableId(0),
+            ty: Ty::Integer,
+        },
+    );
+    expect!["  %var_0 = fptosi double %var_1 to i64"].assert_eq(&inst.to_qir(&Program::default()));
+}
+
+#[test]
+#[should_panic(expected = "unsupported input/output types (double, double) for sitofp")]
+fn sitofp_double_variable() {
+    let inst = Instruction::Sitofp(
+        Operand::Variable(Variable {
+            variable_id: VariableId(1),
+            ty: Ty::Double,
+        }),
+        Variable {
+            variable_id: VariableId(0),
+            ty: Ty::Double,
+        },
+    );
+    let _ = &inst.to_qir(&Program::default());
+}
@@ -557,3 +557,47 @@ fn sub_integer_variables() {
     );
     expect!["  %var_0 = sub i64 %var_1, %var_2"].assert_eq(&inst.to_qir(&rir::Program::default()));
 }
+
+#[test]
+fn sitofp_integer_literal() {
+    let inst = rir::Instruction::Sitofp(
+        rir::Operand::Literal(rir::Literal::Integer(-5)),
+        rir::Variable {
+            variable_id: rir::VariableId(0),
+            ty: rir::Ty::Double,
+        },
+    );
+    expect!["  %var_0 = sitofp i64 -5 to double"].assert_eq(&inst.to_qir(&rir::Program::default()));
+}
+
+#[test]
+fn sitofp_integer_variable() {
+    let inst = rir::Instruction::Sitofp(
+        rir::Operand::Variable(rir::Variable {
+            variable_id: rir::VariableId(1),
+            ty: rir::Ty::Integer,
+        }),
+        rir::Variable {
+            variable_id: rir::VariableId(0),
+            ty: rir::Ty::Double,
+        },
+    );
+    expect!["  %var_0 = sitofp i64 %var_1 to double"]
+        .assert_eq(&inst.to_qir(&rir::Program::default()));
+}
+
+#[test]
+#[should_panic(expected = "unsupported input/output types (i64, i64) for fptosi")]
+fn fptosi_integer_variable() {
+    let inst = rir::Instruction::Fptosi(
+        rir::Operand::Variable(rir::Variable {
+            variable_id: rir::VariableId(1),
+            ty: rir::Ty::Integer,
+        }),
+        rir::Variable {
+            variable_id: rir::VariableId(0),
+            ty: rir::Ty::Integer,
+        },
+    );
+    let _ = &inst.to_qir(&rir::Program::default());
+}
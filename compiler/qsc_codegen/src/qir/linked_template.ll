@@ -0,0 +1,11 @@
+%Result = type opaque
+%Qubit = type opaque
+
+{}
+
+{}
+
+{}
+attributes #{} = {{ "irreversible" }}
+
+; module flags
@@ -0,0 +1,52 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! Records where each value recorded as output sits within the return value of the entry point, as named metadata
+//! in the generated QIR module, so that code that parses the output of a run can rebuild the return value without
+//! knowing the Q# program it came from.
+//!
+//! The output recording calls that end the entry point are described in order, one node for each call. A node holds
+//! the kind of value the call records, which is one of `tuple`, `array`, `result`, `bool`, `int` or `double`,
+//! followed by the indices into the tuples and arrays that lead from the return value to the recorded value. Tuples
+//! and arrays are recorded with their length before their items, so their nodes come before those of their items.
+//! The nodes are listed under the `qsharp.output_positions` named metadata. For a return value of type
+//! `(Result[], Int)` holding an array of two results:
+//!
+//! ```llvm
+//! !qsharp.output_positions = !{!4, !5, !6, !7, !8}
+//! !4 = !{!"tuple"}
+//! !5 = !{!"array", i64 0}
+//! !6 = !{!"result", i64 0, i64 0}
+//! !7 = !{!"result", i64 0, i64 1}
+//! !8 = !{!"int", i64 1}
+//! ```
+//!
+//! Calls that record output before the end of the entry point, such as messages, are not described.
+
+#[cfg(test)]
+mod tests;
+
+use qsc_rir::rir::OutputPosition;
+use std::fmt::Write;
+
+/// The metadata describing the given output positions, with nodes numbered from `first_index`, or an empty string if
+/// there are no positions.
+pub(super) fn output_positions_to_qir(positions: &[OutputPosition], first_index: usize) -> String {
+    if positions.is_empty() {
+        return String::new();
+    }
+
+    let indices = (first_index..first_index + positions.len())
+        .map(|index| format!("!{index}"))
+        .collect::<Vec<_>>();
+    let mut metadata = format!("!qsharp.output_positions = !{{{}}}\n", indices.join(", "));
+    for (index, position) in indices.iter().zip(positions) {
+        write!(metadata, "{index} = !{{!\"{}\"", position.kind.name())
+            .expect("writing to a string should succeed");
+        for item in &position.path {
+            write!(metadata, ", i64 {item}").expect("writing to a string should succeed");
+        }
+        metadata.push_str("}\n");
+    }
+    metadata
+}
@@ -0,0 +1,44 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#![allow(clippy::needless_raw_string_hashes)]
+
+use expect_test::expect;
+use qsc_rir::rir::{OutputKind, OutputPosition};
+
+use super::output_positions_to_qir;
+
+#[test]
+fn no_metadata_without_output_positions() {
+    assert_eq!(output_positions_to_qir(&[], 2), "");
+}
+
+#[test]
+fn output_positions_are_numbered_from_first_index() {
+    let positions = [
+        OutputPosition {
+            kind: OutputKind::Tuple,
+            path: Vec::new(),
+        },
+        OutputPosition {
+            kind: OutputKind::Array,
+            path: vec![0],
+        },
+        OutputPosition {
+            kind: OutputKind::Result,
+            path: vec![0, 0],
+        },
+        OutputPosition {
+            kind: OutputKind::Double,
+            path: vec![1],
+        },
+    ];
+    expect![[r#"
+        !qsharp.output_positions = !{!4, !5, !6, !7}
+        !4 = !{!"tuple"}
+        !5 = !{!"array", i64 0}
+        !6 = !{!"result", i64 0, i64 0}
+        !7 = !{!"double", i64 1}
+    "#]]
+    .assert_eq(&output_positions_to_qir(&positions, 4));
+}
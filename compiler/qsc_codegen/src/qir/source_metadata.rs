@@ -0,0 +1,93 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! Records the Q# sources a program was compiled from as named metadata in the generated QIR module, so that
+//! artifacts submitted to a provider can be traced back to the exact sources that produced them.
+//!
+//! Each source is described by a node holding its name and SHA-256 digest, optionally followed by its full
+//! contents, and the nodes are listed under the `qsharp.sources` named metadata:
+//!
+//! ```llvm
+//! !qsharp.sources = !{!4}
+//! !4 = !{!"Main.qs", !"sha256:…", !"namespace Test { … }"}
+//! ```
+
+#[cfg(test)]
+mod tests;
+
+use qsc_data_structures::sha256;
+use std::fmt::Write;
+
+/// How the sources a program was compiled from are recorded in the generated QIR.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SourceEmbedding {
+    /// The sources are not recorded.
+    #[default]
+    None,
+    /// Only the name and digest of each source is recorded.
+    Hashes,
+    /// The name, digest and full contents of each source are recorded.
+    Full,
+}
+
+/// Appends metadata describing the given sources, as pairs of name and contents, to the QIR module.
+pub fn embed_sources<'a>(
+    qir: &mut String,
+    sources: impl IntoIterator<Item = (&'a str, &'a str)>,
+    embedding: SourceEmbedding,
+) {
+    if embedding == SourceEmbedding::None {
+        return;
+    }
+
+    // Metadata nodes are numbered sequentially across the module, so continue after the module flags.
+    let first_index = qir
+        .lines()
+        .filter(|line| {
+            line.strip_prefix('!')
+                .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+        })
+        .count();
+
+    let mut nodes = String::new();
+    let mut indices = Vec::new();
+    for (index, (name, contents)) in (first_index..).zip(sources) {
+        indices.push(format!("!{index}"));
+        write!(
+            nodes,
+            "!{index} = !{{!\"{}\", !\"sha256:{}\"",
+            escape_metadata_string(name),
+            sha256::hex_digest(contents.as_bytes())
+        )
+        .expect("writing to a string should succeed");
+        if embedding == SourceEmbedding::Full {
+            write!(nodes, ", !\"{}\"", escape_metadata_string(contents))
+                .expect("writing to a string should succeed");
+        }
+        nodes.push_str("}\n");
+    }
+
+    if indices.is_empty() {
+        return;
+    }
+    if !qir.ends_with('\n') {
+        qir.push('\n');
+    }
+    writeln!(qir, "!qsharp.sources = !{{{}}}", indices.join(", "))
+        .expect("writing to a string should succeed");
+    qir.push_str(&nodes);
+}
+
+/// Escapes a string for use in an LLVM metadata string, where quotes, backslashes and any byte that is not
+/// printable ASCII are written as a backslash followed by two hex digits.
+fn escape_metadata_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte == b'"' || byte == b'\\' || !(byte == b' ' || byte.is_ascii_graphic()) {
+            write!(escaped, "\\{byte:02X}").expect("writing to a string should succeed");
+        } else {
+            escaped.push(char::from(byte));
+        }
+    }
+    escaped
+}
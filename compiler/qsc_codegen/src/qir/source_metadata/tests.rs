@@ -0,0 +1,60 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#![allow(clippy::needless_raw_string_hashes)]
+
+use expect_test::expect;
+
+use super::{embed_sources, SourceEmbedding};
+
+const MODULE_FLAGS: &str = r#"!llvm.module.flags = !{!0, !1}
+!0 = !{i32 1, !"qir_major_version", i32 1}
+!1 = !{i32 7, !"qir_minor_version", i32 0}
+"#;
+
+const SOURCES: [(&str, &str); 2] = [
+    ("A.qs", "namespace A {}"),
+    ("dir\\B.qs", "namespace B {\n    // \"quoted\" π\n}"),
+];
+
+#[test]
+fn no_metadata_is_added_when_embedding_is_disabled() {
+    let mut qir = MODULE_FLAGS.to_string();
+    embed_sources(&mut qir, SOURCES, SourceEmbedding::None);
+    assert_eq!(qir, MODULE_FLAGS);
+}
+
+#[test]
+fn hashes_are_numbered_after_module_flags() {
+    let mut qir = MODULE_FLAGS.to_string();
+    embed_sources(&mut qir, SOURCES, SourceEmbedding::Hashes);
+    expect![[r#"
+        !llvm.module.flags = !{!0, !1}
+        !0 = !{i32 1, !"qir_major_version", i32 1}
+        !1 = !{i32 7, !"qir_minor_version", i32 0}
+        !qsharp.sources = !{!2, !3}
+        !2 = !{!"A.qs", !"sha256:2268232869f17fce2b3a51af76edc5dc06c27eb3ad8e95ea16d39c87f3b88aa8"}
+        !3 = !{!"dir\5CB.qs", !"sha256:8f6632b6dc7919b85730b31337d08cc0cec9240ca29a670e68cc02957107a8f7"}
+    "#]].assert_eq(&qir);
+}
+
+#[test]
+fn full_sources_are_escaped() {
+    let mut qir = MODULE_FLAGS.to_string();
+    embed_sources(&mut qir, SOURCES, SourceEmbedding::Full);
+    expect![[r#"
+        !llvm.module.flags = !{!0, !1}
+        !0 = !{i32 1, !"qir_major_version", i32 1}
+        !1 = !{i32 7, !"qir_minor_version", i32 0}
+        !qsharp.sources = !{!2, !3}
+        !2 = !{!"A.qs", !"sha256:2268232869f17fce2b3a51af76edc5dc06c27eb3ad8e95ea16d39c87f3b88aa8", !"namespace A {}"}
+        !3 = !{!"dir\5CB.qs", !"sha256:8f6632b6dc7919b85730b31337d08cc0cec9240ca29a670e68cc02957107a8f7", !"namespace B {\0A    // \22quoted\22 \CF\80\0A}"}
+    "#]].assert_eq(&qir);
+}
+
+#[test]
+fn no_metadata_is_added_without_sources() {
+    let mut qir = MODULE_FLAGS.to_string();
+    embed_sources(&mut qir, [], SourceEmbedding::Full);
+    assert_eq!(qir, MODULE_FLAGS);
+}
@@ -227,3 +227,262 @@ fn teleport_program() {
         !3 = !{i32 1, !"dynamic_result_management", i1 false}
     "#]].assert_eq(&program.to_qir(&program));
 }
+
+fn module_flags(qir: &str) -> &str {
+    qir.split_once("; module flags")
+        .expect("qir should have module flags")
+        .1
+}
+
+#[test]
+fn module_flags_omit_computations_not_used_by_program() {
+    let mut program = builder::teleport_program();
+    program.config.capabilities = qsc_data_structures::target::TargetCapabilityFlags::all();
+    expect![[r#"
+
+
+        !llvm.module.flags = !{!0, !1, !2, !3}
+
+        !0 = !{i32 1, !"qir_major_version", i32 1}
+        !1 = !{i32 7, !"qir_minor_version", i32 0}
+        !2 = !{i32 1, !"dynamic_qubit_management", i1 false}
+        !3 = !{i32 1, !"dynamic_result_management", i1 false}
+    "#]]
+    .assert_eq(module_flags(&program.to_qir(&program)));
+}
+
+#[test]
+fn output_positions_follow_module_flags_when_emitted() {
+    let mut program = builder::bell_program();
+    program.output_positions = vec![
+        rir::OutputPosition {
+            kind: rir::OutputKind::Array,
+            path: Vec::new(),
+        },
+        rir::OutputPosition {
+            kind: rir::OutputKind::Result,
+            path: vec![0],
+        },
+        rir::OutputPosition {
+            kind: rir::OutputKind::Result,
+            path: vec![1],
+        },
+    ];
+    let without_metadata = program.to_qir(&program);
+    assert!(!without_metadata.contains("qsharp.output_positions"));
+
+    program.config.emit_output_positions = true;
+    expect![[r#"
+
+
+        !llvm.module.flags = !{!0, !1, !2, !3}
+
+        !0 = !{i32 1, !"qir_major_version", i32 1}
+        !1 = !{i32 7, !"qir_minor_version", i32 0}
+        !2 = !{i32 1, !"dynamic_qubit_management", i1 false}
+        !3 = !{i32 1, !"dynamic_result_management", i1 false}
+        !qsharp.output_positions = !{!4, !5, !6}
+        !4 = !{!"array"}
+        !5 = !{!"result", i64 0}
+        !6 = !{!"result", i64 1}
+    "#]]
+    .assert_eq(module_flags(&program.to_qir(&program)));
+}
+
+#[test]
+fn module_flags_include_computations_used_by_program() {
+    let mut program = builder::teleport_program();
+    program.config.capabilities = qsc_data_structures::target::TargetCapabilityFlags::all();
+    // Make the conditional X gate loop back to the second measurement, counting the iterations.
+    let block = program.get_block_mut(rir::BlockId(3));
+    block.0.insert(
+        1,
+        rir::Instruction::Add(
+            rir::Operand::Literal(rir::Literal::Integer(1)),
+            rir::Operand::Literal(rir::Literal::Integer(1)),
+            rir::Variable {
+                variable_id: rir::VariableId(2),
+                ty: rir::Ty::Integer,
+            },
+        ),
+    );
+    *block.0.last_mut().expect("block should not be empty") =
+        rir::Instruction::Jump(rir::BlockId(2));
+    expect![[r#"
+
+
+        !llvm.module.flags = !{!0, !1, !2, !3, !4, !5}
+
+        !0 = !{i32 1, !"qir_major_version", i32 1}
+        !1 = !{i32 7, !"qir_minor_version", i32 0}
+        !2 = !{i32 1, !"dynamic_qubit_management", i1 false}
+        !3 = !{i32 1, !"dynamic_result_management", i1 false}
+        !4 = !{i32 1, !"int_computations", !"i64"}
+        !5 = !{i32 7, !"backwards_branching", i2 3}
+    "#]]
+    .assert_eq(module_flags(&program.to_qir(&program)));
+}
+
+#[test]
+fn module_flags_include_float_computations_used_by_program() {
+    let mut program = builder::teleport_program();
+    program.config.capabilities = qsc_data_structures::target::TargetCapabilityFlags::all();
+    program.get_block_mut(rir::BlockId(1)).0.insert(
+        0,
+        rir::Instruction::Fmul(
+            rir::Operand::Literal(rir::Literal::Double(2.0)),
+            rir::Operand::Literal(rir::Literal::Double(3.0)),
+            rir::Variable {
+                variable_id: rir::VariableId(2),
+                ty: rir::Ty::Double,
+            },
+        ),
+    );
+    expect![[r#"
+
+
+        !llvm.module.flags = !{!0, !1, !2, !3, !4}
+
+        !0 = !{i32 1, !"qir_major_version", i32 1}
+        !1 = !{i32 7, !"qir_minor_version", i32 0}
+        !2 = !{i32 1, !"dynamic_qubit_management", i1 false}
+        !3 = !{i32 1, !"dynamic_result_management", i1 false}
+        !4 = !{i32 1, !"float_computations", !"f64"}
+    "#]]
+    .assert_eq(module_flags(&program.to_qir(&program)));
+}
+
+#[test]
+fn linked_entry_points_share_declarations_and_keep_their_own_attributes() {
+    let teleport = builder::teleport_program();
+    let mut bell = builder::bell_program();
+    bell.config.capabilities = teleport.config.capabilities;
+    expect![[r#"
+        %Result = type opaque
+        %Qubit = type opaque
+
+        define void @ENTRYPOINT__Bell() #0 {
+        block_0:
+          call void @__quantum__qis__h__body(%Qubit* inttoptr (i64 0 to %Qubit*))
+          call void @__quantum__qis__cx__body(%Qubit* inttoptr (i64 0 to %Qubit*), %Qubit* inttoptr (i64 1 to %Qubit*))
+          call void @__quantum__qis__m__body(%Qubit* inttoptr (i64 0 to %Qubit*), %Result* inttoptr (i64 0 to %Result*))
+          call void @__quantum__qis__m__body(%Qubit* inttoptr (i64 1 to %Qubit*), %Result* inttoptr (i64 1 to %Result*))
+          call void @__quantum__rt__array_record_output(i64 2, i8* null)
+          call void @__quantum__rt__result_record_output(%Result* inttoptr (i64 0 to %Result*), i8* null)
+          call void @__quantum__rt__result_record_output(%Result* inttoptr (i64 1 to %Result*), i8* null)
+          ret void
+        }
+
+        define void @ENTRYPOINT__Teleport() #1 {
+        block_0:
+          call void @__quantum__qis__x__body(%Qubit* inttoptr (i64 0 to %Qubit*))
+          call void @__quantum__qis__h__body(%Qubit* inttoptr (i64 2 to %Qubit*))
+          call void @__quantum__qis__cx__body(%Qubit* inttoptr (i64 2 to %Qubit*), %Qubit* inttoptr (i64 1 to %Qubit*))
+          call void @__quantum__qis__cx__body(%Qubit* inttoptr (i64 0 to %Qubit*), %Qubit* inttoptr (i64 2 to %Qubit*))
+          call void @__quantum__qis__h__body(%Qubit* inttoptr (i64 0 to %Qubit*))
+          call void @__quantum__qis__mresetz__body(%Qubit* inttoptr (i64 0 to %Qubit*), %Result* inttoptr (i64 0 to %Result*))
+          %var_0 = call i1 @__quantum__qis__read_result__body(%Result* inttoptr (i64 0 to %Result*))
+          br i1 %var_0, label %block_1, label %block_2
+        block_1:
+          call void @__quantum__qis__z__body(%Qubit* inttoptr (i64 1 to %Qubit*))
+          br label %block_2
+        block_2:
+          call void @__quantum__qis__mresetz__body(%Qubit* inttoptr (i64 2 to %Qubit*), %Result* inttoptr (i64 1 to %Result*))
+          %var_1 = call i1 @__quantum__qis__read_result__body(%Result* inttoptr (i64 1 to %Result*))
+          br i1 %var_1, label %block_3, label %block_4
+        block_3:
+          call void @__quantum__qis__x__body(%Qubit* inttoptr (i64 1 to %Qubit*))
+          br label %block_4
+        block_4:
+          call void @__quantum__qis__mresetz__body(%Qubit* inttoptr (i64 1 to %Qubit*), %Result* inttoptr (i64 2 to %Result*))
+          call void @__quantum__rt__result_record_output(%Result* inttoptr (i64 2 to %Result*), i8* null)
+          ret void
+        }
+
+        declare void @__quantum__qis__h__body(%Qubit*)
+
+        declare void @__quantum__qis__cx__body(%Qubit*, %Qubit*)
+
+        declare void @__quantum__qis__m__body(%Qubit*, %Result*) #2
+
+        declare void @__quantum__rt__array_record_output(i64, i8*)
+
+        declare void @__quantum__rt__result_record_output(%Result*, i8*)
+
+        declare void @__quantum__qis__z__body(%Qubit*)
+
+        declare void @__quantum__qis__x__body(%Qubit*)
+
+        declare void @__quantum__qis__mresetz__body(%Qubit*, %Result*) #2
+
+        declare i1 @__quantum__qis__read_result__body(%Result*)
+
+        attributes #0 = { "entry_point" "output_labeling_schema" "qir_profiles"="adaptive_profile" "required_num_qubits"="2" "required_num_results"="2" }
+        attributes #1 = { "entry_point" "output_labeling_schema" "qir_profiles"="adaptive_profile" "required_num_qubits"="3" "required_num_results"="3" }
+        attributes #2 = { "irreversible" }
+
+        ; module flags
+
+        !llvm.module.flags = !{!0, !1, !2, !3}
+
+        !0 = !{i32 1, !"qir_major_version", i32 1}
+        !1 = !{i32 7, !"qir_minor_version", i32 0}
+        !2 = !{i32 1, !"dynamic_qubit_management", i1 false}
+        !3 = !{i32 1, !"dynamic_result_management", i1 false}
+    "#]].assert_eq(&super::link_entry_points(&[
+        ("Bell", &bell),
+        ("Teleport", &teleport),
+    ]));
+}
+
+#[test]
+fn linked_entry_points_with_the_same_name_are_made_unique() {
+    let bell = builder::bell_program();
+    let qir = super::link_entry_points(&[("Main", &bell), ("Main", &bell), ("Test.Main", &bell)]);
+    let names = qir
+        .lines()
+        .filter(|line| line.starts_with("define"))
+        .collect::<Vec<_>>();
+    expect![[r#"
+        [
+            "define void @ENTRYPOINT__Main() #0 {",
+            "define void @ENTRYPOINT__Main_1() #1 {",
+            "define void @ENTRYPOINT__Test_Main() #2 {",
+        ]
+    "#]]
+    .assert_debug_eq(&names);
+}
+
+#[test]
+fn linked_module_flags_include_computations_used_by_any_program() {
+    let mut bell = builder::bell_program();
+    bell.config.capabilities = qsc_data_structures::target::TargetCapabilityFlags::all();
+    let mut teleport = builder::teleport_program();
+    teleport.config.capabilities = qsc_data_structures::target::TargetCapabilityFlags::all();
+    teleport.get_block_mut(rir::BlockId(1)).0.insert(
+        0,
+        rir::Instruction::Fmul(
+            rir::Operand::Literal(rir::Literal::Double(2.0)),
+            rir::Operand::Literal(rir::Literal::Double(3.0)),
+            rir::Variable {
+                variable_id: rir::VariableId(2),
+                ty: rir::Ty::Double,
+            },
+        ),
+    );
+    expect![[r#"
+
+
+        !llvm.module.flags = !{!0, !1, !2, !3, !4}
+
+        !0 = !{i32 1, !"qir_major_version", i32 1}
+        !1 = !{i32 7, !"qir_minor_version", i32 0}
+        !2 = !{i32 1, !"dynamic_qubit_management", i1 false}
+        !3 = !{i32 1, !"dynamic_result_management", i1 false}
+        !4 = !{i32 1, !"float_computations", !"f64"}
+    "#]]
+    .assert_eq(module_flags(&super::link_entry_points(&[
+        ("Bell", &bell),
+        ("Teleport", &teleport),
+    ])));
+}
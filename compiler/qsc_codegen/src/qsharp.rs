@@ -16,9 +16,9 @@ use std::vec;
 use qsc_ast::ast::{
     self, Attr, BinOp, Block, CallableBody, CallableDecl, CallableKind, Expr, ExprKind,
     FieldAccess, Functor, FunctorExpr, FunctorExprKind, Ident, Idents, ImportOrExportItem, Item,
-    ItemKind, Lit, Mutability, Pat, PatKind, Path, PathKind, Pauli, QubitInit, QubitInitKind,
-    QubitSource, SetOp, SpecBody, SpecDecl, SpecGen, Stmt, StmtKind, StringComponent, TernOp,
-    TopLevelNode, Ty, TyDef, TyDefKind, TyKind, UnOp,
+    ItemKind, Lit, MatchArm, MatchPat, Mutability, Pat, PatKind, Path, PathKind, Pauli, QubitInit,
+    QubitInitKind, QubitSource, SetOp, SpecBody, SpecDecl, SpecGen, Stmt, StmtKind,
+    StringComponent, TernOp, TopLevelNode, Ty, TyDef, TyDefKind, TyKind, UnOp, VisibilityKind,
 };
 use qsc_ast::ast::{Namespace, Package};
 use qsc_ast::visit::Visitor;
@@ -103,6 +103,19 @@ where
             self.write(",");
         }
     }
+
+    fn visit_class_constraint(&mut self, constraint: &ast::ClassConstraint) {
+        self.visit_ident(&constraint.name);
+        if let Some((last, most)) = constraint.parameters.split_last() {
+            self.write("[");
+            for i in most {
+                self.visit_ty(&i.ty);
+                self.write(", ");
+            }
+            self.visit_ty(&last.ty);
+            self.write("]");
+        }
+    }
 }
 
 impl<W: Write> Visitor<'_> for QSharpGen<W> {
@@ -128,6 +141,11 @@ impl<W: Write> Visitor<'_> for QSharpGen<W> {
 
     fn visit_item(&mut self, item: &'_ Item) {
         item.attrs.iter().for_each(|a| self.visit_attr(a));
+        if let Some(visibility) = &item.visibility {
+            match visibility.kind {
+                VisibilityKind::Internal => self.write("internal "),
+            }
+        }
         match &*item.kind {
             ItemKind::Err => {
                 unreachable!()
@@ -150,6 +168,9 @@ impl<W: Write> Visitor<'_> for QSharpGen<W> {
                 self.writeln(";");
             }
             ItemKind::Struct(decl) => self.visit_struct_decl(decl),
+            ItemKind::Enum(decl) => self.visit_enum_decl(decl),
+            ItemKind::Const(decl) => self.visit_const_decl(decl),
+            ItemKind::Class(decl) => self.visit_class_decl(decl),
             ItemKind::ImportOrExport(decl) => {
                 if decl.is_export() {
                     self.write("export ");
@@ -280,6 +301,92 @@ impl<W: Write> Visitor<'_> for QSharpGen<W> {
         self.visit_ty(&def.ty);
     }
 
+    fn visit_const_decl(&mut self, decl: &'_ ast::ConstDecl) {
+        self.write("const ");
+        self.visit_ident(&decl.name);
+        self.write(" : ");
+        self.visit_ty(&decl.ty);
+        self.write(" = ");
+        self.visit_expr(&decl.value);
+        self.writeln(";");
+    }
+
+    fn visit_class_decl(&mut self, decl: &'_ ast::ClassDecl) {
+        self.write("class ");
+        self.visit_ident(&decl.name);
+        if let Some((last, most)) = decl.generics.split_last() {
+            self.write("[");
+            for i in most {
+                self.visit_ident(&i.ty);
+                self.write(", ");
+            }
+            self.visit_ident(&last.ty);
+            self.write("]");
+        }
+        if let Some((last, most)) = decl.constraints.0.split_last() {
+            self.write(" : ");
+            for i in most {
+                self.visit_class_constraint(i);
+                self.write(" + ");
+            }
+            self.visit_class_constraint(last);
+        }
+        if decl.methods.is_empty() {
+            self.writeln(";");
+        } else {
+            self.writeln(" {");
+            for method in &decl.methods {
+                match method.kind {
+                    CallableKind::Function => self.write("function "),
+                    CallableKind::Operation => self.write("operation "),
+                }
+                self.visit_ident(&method.name);
+                self.visit_pat(&method.input);
+                self.write(" : ");
+                self.visit_ty(&method.output);
+                if let Some(functors) = method.functors.as_deref() {
+                    self.write(" is ");
+                    self.visit_functor_expr(functors);
+                }
+                self.writeln(";");
+            }
+            self.writeln("}");
+        }
+    }
+
+    fn visit_enum_decl(&mut self, decl: &'_ ast::EnumDecl) {
+        self.write("enum ");
+        self.visit_ident(&decl.name);
+        if !decl.generics.is_empty() {
+            self.write("<");
+            if let Some((last, most)) = decl.generics.split_last() {
+                for i in most {
+                    self.visit_ident(&i.ty);
+                    self.write(", ");
+                }
+                self.visit_ident(&last.ty);
+            }
+
+            self.write(">");
+        }
+        self.writeln(" {");
+        if let Some((last, most)) = decl.variants.split_last() {
+            for i in most {
+                self.visit_variant_def(i);
+                self.write(", ");
+            }
+            self.visit_variant_def(last);
+        }
+        self.writeln("}");
+    }
+
+    fn visit_variant_def(&mut self, def: &'_ ast::VariantDef) {
+        self.visit_ident(&def.name);
+        if let Some(ty) = &def.ty {
+            self.visit_ty(ty);
+        }
+    }
+
     fn visit_spec_decl(&mut self, decl: &'_ SpecDecl) {
         match decl.spec {
             ast::Spec::Body => self.write("body "),
@@ -330,6 +437,10 @@ impl<W: Write> Visitor<'_> for QSharpGen<W> {
                 self.visit_ty(item);
                 self.write("[]");
             }
+            TyKind::SizedArray(item, len) => {
+                self.visit_ty(item);
+                self.write(&format!("[{len}]"));
+            }
             TyKind::Arrow(kind, lhs, rhs, functors) => {
                 self.visit_ty(lhs);
                 match kind {
@@ -349,6 +460,18 @@ impl<W: Write> Visitor<'_> for QSharpGen<W> {
                 self.write(")");
             }
             TyKind::Path(path) => self.visit_path_kind(path),
+            TyKind::App(path, args) => {
+                self.visit_path_kind(path);
+                self.write("<");
+                if let Some((last, most)) = args.split_last() {
+                    for t in most {
+                        self.visit_ty(t);
+                        self.write(", ");
+                    }
+                    self.visit_ty(last);
+                }
+                self.write(">");
+            }
             TyKind::Param(name) => self.visit_ident(&name.ty),
             TyKind::Tuple(tys) => {
                 if tys.is_empty() {
@@ -521,9 +644,12 @@ impl<W: Write> Visitor<'_> for QSharpGen<W> {
                 self.write("$\"");
                 for component in components.as_ref() {
                     match component {
-                        StringComponent::Expr(expr) => {
+                        StringComponent::Expr(expr, format) => {
                             self.write("{");
                             self.visit_expr(expr.as_ref());
+                            if let Some(format) = format {
+                                self.write(&format!(":{format}"));
+                            }
                             self.write("}");
                         }
                         StringComponent::Lit(lit) => {
@@ -541,6 +667,16 @@ impl<W: Write> Visitor<'_> for QSharpGen<W> {
                 }
                 self.visit_expr(expr);
             }
+            ExprKind::Match(expr, arms) => {
+                self.write("match ");
+                self.visit_expr(expr);
+                self.writeln(" {");
+                for arm in arms {
+                    self.visit_match_arm(arm);
+                    self.writeln(",");
+                }
+                self.write("}");
+            }
             ExprKind::Paren(expr) => {
                 self.write("(");
                 self.visit_expr(expr);
@@ -573,7 +709,7 @@ impl<W: Write> Visitor<'_> for QSharpGen<W> {
             }
             ExprKind::UnOp(op, expr) => {
                 let op_str = unop_as_str(op);
-                if op == &UnOp::Unwrap {
+                if matches!(op, UnOp::Unwrap | UnOp::Propagate) {
                     self.visit_expr(expr);
                     self.write(op_str);
                 } else {
@@ -729,6 +865,19 @@ impl<W: Write> Visitor<'_> for QSharpGen<W> {
         self.visit_expr(&assign.value);
     }
 
+    fn visit_match_arm(&mut self, arm: &'_ MatchArm) {
+        match &*arm.pat {
+            MatchPat::Lit(lit) => self.visit_expr(lit),
+            MatchPat::Variant(path, pat) => {
+                self.visit_path(path);
+                self.visit_pat(pat);
+            }
+            MatchPat::Bind(pat) => self.visit_pat(pat),
+        }
+        self.write(" => ");
+        self.visit_expr(&arm.body);
+    }
+
     fn visit_pat(&mut self, pat: &'_ Pat) {
         match &*pat.kind {
             PatKind::Bind(name, ty) => {
@@ -851,5 +1000,6 @@ fn unop_as_str(op: &UnOp) -> &str {
         UnOp::NotL => "not ",
         UnOp::Pos => "+",
         UnOp::Unwrap => "!",
+        UnOp::Propagate => "?",
     }
 }
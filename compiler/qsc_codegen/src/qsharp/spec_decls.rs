@@ -69,7 +69,7 @@ fn comments_are_omitted() {
 }
 
 #[test]
-fn strip_visibility() {
+fn preserve_visibility() {
     check(
         indoc! {r#"
             // NS comment
@@ -83,7 +83,7 @@ fn strip_visibility() {
         None,
         &expect![[r#"
             namespace A {
-                operation B() : Unit {}
+                internal operation B() : Unit {}
             }"#]],
     );
 }
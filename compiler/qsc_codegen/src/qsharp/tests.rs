@@ -119,6 +119,39 @@ fn struct_decl() {
     );
 }
 
+#[test]
+fn enum_decl_and_match() {
+    check(
+        indoc! {r#"
+        namespace Sample {
+            enum Shape { Circle(Double), Rect(Double, Double), Empty }
+            function Area(shape : Shape) : Double {
+                match shape {
+                    Circle(r) => 3.0 * r * r,
+                    Rect(w, h) => w * h,
+                    Empty() => 0.0,
+                }
+            }
+        }"#},
+        None,
+        &expect![[r#"
+            namespace Sample {
+                enum Shape {
+                    Circle(Double),
+                    Rect(Double, Double),
+                    Empty
+                }
+                function Area(shape : Shape) : Double {
+                    match shape {
+                        Circle(r) => 3. * r * r,
+                        Rect(w, h) => w * h,
+                        Empty() => 0.,
+                    }
+                }
+            }"#]],
+    );
+}
+
 #[test]
 fn struct_cons() {
     check(
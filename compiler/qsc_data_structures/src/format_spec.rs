@@ -0,0 +1,270 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! Format specifiers for interpolated string expressions, as in `$"{x:0.3f}"`. A specifier has the form
+//! `[[fill]align][+][0][width][.precision][type]`, where `align` is one of `<`, `>` or `^` and `type` is one
+//! of `b`, `o`, `x`, `X`, `e`, `E` or `f`.
+
+#[cfg(test)]
+mod tests;
+
+use std::{
+    fmt::{self, Display, Formatter, Write},
+    iter::{self, Peekable},
+    str::Chars,
+};
+
+/// A parsed format specifier.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct FormatSpec {
+    /// The character used to pad the value up to the width.
+    pub fill: char,
+    /// The alignment of the value within the width, if given explicitly.
+    pub align: Option<Align>,
+    /// Whether non-negative numbers are prefixed with a `+` sign.
+    pub sign: bool,
+    /// Whether numbers are padded with zeros after the sign instead of with the fill character.
+    pub zero: bool,
+    /// The minimum number of characters in the formatted value.
+    pub width: Option<usize>,
+    /// The number of digits after the decimal point for floating-point formats.
+    pub precision: Option<usize>,
+    /// The presentation type of the value.
+    pub ty: Option<FormatType>,
+}
+
+/// The alignment of a formatted value within its width.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Align {
+    /// `<`
+    Left,
+    /// `>`
+    Right,
+    /// `^`
+    Center,
+}
+
+/// The presentation type of a formatted value.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum FormatType {
+    /// `b`
+    Binary,
+    /// `o`
+    Octal,
+    /// `x`
+    LowerHex,
+    /// `X`
+    UpperHex,
+    /// `e`
+    LowerExp,
+    /// `E`
+    UpperExp,
+    /// `f`
+    Fixed,
+}
+
+impl FormatType {
+    /// The radix of an integer presentation type, if this is one.
+    #[must_use]
+    pub fn radix(self) -> Option<u32> {
+        match self {
+            FormatType::Binary => Some(2),
+            FormatType::Octal => Some(8),
+            FormatType::LowerHex | FormatType::UpperHex => Some(16),
+            FormatType::LowerExp | FormatType::UpperExp | FormatType::Fixed => None,
+        }
+    }
+}
+
+impl FormatSpec {
+    /// Parses the text of a format specifier, without the leading `:`.
+    /// Returns `None` if the text is not a valid specifier.
+    #[must_use]
+    pub fn parse(spec: &str) -> Option<Self> {
+        let mut chars = spec.chars().peekable();
+        let mut format = FormatSpec {
+            fill: ' ',
+            align: None,
+            sign: false,
+            zero: false,
+            width: None,
+            precision: None,
+            ty: None,
+        };
+
+        // The fill character can only be recognized by the alignment that follows it.
+        let mut lookahead = spec.chars();
+        match (lookahead.next(), lookahead.next().and_then(align)) {
+            (Some(fill), Some(align)) if fill != '{' && fill != '}' => {
+                format.fill = fill;
+                format.align = Some(align);
+                chars.next();
+                chars.next();
+            }
+            (Some(c), _) => {
+                if let Some(align) = align(c) {
+                    format.align = Some(align);
+                    chars.next();
+                }
+            }
+            (None, _) => {}
+        }
+
+        format.sign = chars.next_if_eq(&'+').is_some();
+        format.zero = chars.next_if_eq(&'0').is_some();
+        format.width = digits(&mut chars).ok()?;
+        if chars.next_if_eq(&'.').is_some() {
+            format.precision = Some(digits(&mut chars).ok()??);
+        }
+        format.ty = match chars.next() {
+            Some('b') => Some(FormatType::Binary),
+            Some('o') => Some(FormatType::Octal),
+            Some('x') => Some(FormatType::LowerHex),
+            Some('X') => Some(FormatType::UpperHex),
+            Some('e') => Some(FormatType::LowerExp),
+            Some('E') => Some(FormatType::UpperExp),
+            Some('f') => Some(FormatType::Fixed),
+            Some(_) => return None,
+            None => None,
+        };
+
+        if chars.next().is_some()
+            || format.precision.is_some() && format.ty.and_then(FormatType::radix).is_some()
+        {
+            None
+        } else {
+            Some(format)
+        }
+    }
+
+    /// Whether the specifier only applies to numbers, because it has a sign, zero padding, a precision or a
+    /// presentation type.
+    #[must_use]
+    pub fn is_numeric(&self) -> bool {
+        self.sign || self.zero || self.precision.is_some() || self.ty.is_some()
+    }
+
+    /// Formats the magnitude of a floating-point number according to the precision and presentation type.
+    /// Returns `None` if neither is given, in which case the default representation should be used.
+    #[must_use]
+    pub fn float_digits(&self, magnitude: f64) -> Option<String> {
+        let precision = self.precision;
+        match self.ty {
+            Some(FormatType::LowerExp) => Some(match precision {
+                Some(p) => format!("{magnitude:.p$e}"),
+                None => format!("{magnitude:e}"),
+            }),
+            Some(FormatType::UpperExp) => Some(match precision {
+                Some(p) => format!("{magnitude:.p$E}"),
+                None => format!("{magnitude:E}"),
+            }),
+            Some(FormatType::Fixed) => Some(format!("{magnitude:.*}", precision.unwrap_or(6))),
+            _ => precision.map(|p| format!("{magnitude:.p$}")),
+        }
+    }
+
+    /// Formats a number given its sign and the digits of its magnitude, applying the sign, zero padding and
+    /// alignment. Numbers are right-aligned by default.
+    #[must_use]
+    pub fn format_number(&self, negative: bool, digits: &str) -> String {
+        let sign = if negative {
+            "-"
+        } else if self.sign {
+            "+"
+        } else {
+            ""
+        };
+
+        match self.width {
+            Some(width) if self.zero && self.align.is_none() => {
+                let zeros = width.saturating_sub(sign.len() + digits.chars().count());
+                format!("{sign}{}{digits}", "0".repeat(zeros))
+            }
+            _ => self.pad(&format!("{sign}{digits}"), Align::Right),
+        }
+    }
+
+    /// Formats text by applying the alignment. Text is left-aligned by default.
+    #[must_use]
+    pub fn format_text(&self, text: &str) -> String {
+        self.pad(text, Align::Left)
+    }
+
+    fn pad(&self, text: &str, default_align: Align) -> String {
+        let len = text.chars().count();
+        let Some(padding) = self.width.and_then(|width| width.checked_sub(len)) else {
+            return text.to_string();
+        };
+
+        let (before, after) = match self.align.unwrap_or(default_align) {
+            Align::Left => (0, padding),
+            Align::Right => (padding, 0),
+            Align::Center => (padding / 2, padding - padding / 2),
+        };
+        let fill = |count| iter::repeat(self.fill).take(count).collect::<String>();
+        format!("{}{text}{}", fill(before), fill(after))
+    }
+}
+
+impl Display for FormatSpec {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if let Some(align) = self.align {
+            if self.fill != ' ' {
+                f.write_char(self.fill)?;
+            }
+            f.write_char(match align {
+                Align::Left => '<',
+                Align::Right => '>',
+                Align::Center => '^',
+            })?;
+        }
+        if self.sign {
+            f.write_char('+')?;
+        }
+        if self.zero {
+            f.write_char('0')?;
+        }
+        if let Some(width) = self.width {
+            write!(f, "{width}")?;
+        }
+        if let Some(precision) = self.precision {
+            write!(f, ".{precision}")?;
+        }
+        if let Some(ty) = self.ty {
+            f.write_char(match ty {
+                FormatType::Binary => 'b',
+                FormatType::Octal => 'o',
+                FormatType::LowerHex => 'x',
+                FormatType::UpperHex => 'X',
+                FormatType::LowerExp => 'e',
+                FormatType::UpperExp => 'E',
+                FormatType::Fixed => 'f',
+            })?;
+        }
+        Ok(())
+    }
+}
+
+fn align(c: char) -> Option<Align> {
+    match c {
+        '<' => Some(Align::Left),
+        '>' => Some(Align::Right),
+        '^' => Some(Align::Center),
+        _ => None,
+    }
+}
+
+fn digits(chars: &mut Peekable<Chars>) -> Result<Option<usize>, ()> {
+    let mut value: Option<usize> = None;
+    while let Some(digit) = chars.peek().and_then(|c| c.to_digit(10)) {
+        chars.next();
+        value = value
+            .unwrap_or(0)
+            .checked_mul(10)
+            .and_then(|value| value.checked_add(digit.try_into().ok()?));
+        if value.is_none() {
+            return Err(());
+        }
+    }
+    Ok(value)
+}
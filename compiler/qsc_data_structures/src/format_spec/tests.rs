@@ -0,0 +1,88 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{Align, FormatSpec, FormatType};
+
+fn parse(spec: &str) -> FormatSpec {
+    FormatSpec::parse(spec).expect("format specifier should be valid")
+}
+
+#[test]
+fn parse_empty() {
+    let spec = parse("");
+    assert!(!spec.is_numeric());
+    assert_eq!(spec.to_string(), "");
+}
+
+#[test]
+fn parse_all_parts() {
+    let spec = parse("*^+010.3f");
+    assert_eq!(spec.fill, '*');
+    assert_eq!(spec.align, Some(Align::Center));
+    assert!(spec.sign);
+    assert!(spec.zero);
+    assert_eq!(spec.width, Some(10));
+    assert_eq!(spec.precision, Some(3));
+    assert_eq!(spec.ty, Some(FormatType::Fixed));
+    assert_eq!(spec.to_string(), "*^+010.3f");
+}
+
+#[test]
+fn parse_align_without_fill() {
+    let spec = parse(">8");
+    assert_eq!(spec.fill, ' ');
+    assert_eq!(spec.align, Some(Align::Right));
+    assert_eq!(spec.width, Some(8));
+    assert_eq!(spec.to_string(), ">8");
+}
+
+#[test]
+fn parse_alignment_character_as_fill() {
+    let spec = parse("<<4");
+    assert_eq!(spec.fill, '<');
+    assert_eq!(spec.align, Some(Align::Left));
+}
+
+#[test]
+fn parse_invalid() {
+    for spec in [
+        "q",
+        "8.",
+        ".f",
+        "3fx",
+        "{<4",
+        ".2x",
+        "99999999999999999999999",
+    ] {
+        assert_eq!(FormatSpec::parse(spec), None, "{spec:?} should be invalid");
+    }
+}
+
+#[test]
+fn format_number_with_width_and_zero_padding() {
+    assert_eq!(parse("5").format_number(true, "42"), "  -42");
+    assert_eq!(parse("05").format_number(true, "42"), "-0042");
+    assert_eq!(parse("+05").format_number(false, "42"), "+0042");
+    assert_eq!(parse("<5").format_number(false, "42"), "42   ");
+    assert_eq!(parse("_^6").format_number(false, "42"), "__42__");
+}
+
+#[test]
+fn format_text_is_left_aligned_by_default() {
+    assert_eq!(parse("6").format_text("abc"), "abc   ");
+    assert_eq!(parse(">6").format_text("abc"), "   abc");
+    assert_eq!(parse("-^7").format_text("abc"), "--abc--");
+    assert_eq!(parse("2").format_text("abc"), "abc");
+}
+
+#[test]
+fn float_digits() {
+    assert_eq!(parse("").float_digits(1.5), None);
+    assert_eq!(parse(".3").float_digits(1.5), Some("1.500".to_string()));
+    assert_eq!(parse("f").float_digits(1.5), Some("1.500000".to_string()));
+    assert_eq!(
+        parse(".2e").float_digits(1234.5),
+        Some("1.23e3".to_string())
+    );
+    assert_eq!(parse("E").float_digits(0.25), Some("2.5E-1".to_string()));
+}
@@ -0,0 +1,88 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! Drop-in replacements for the `FxHashMap` and `FxHashSet` of `rustc_hash` whose iteration order can be perturbed
+//! with a seed. Outside of `with_seed` they hash exactly as the `rustc_hash` types do; within it, each map or set
+//! created on the thread hashes the seed before every key, which reorders its buckets. This lets the determinism
+//! audit of code generation check that passes do not depend on the order in which they visit map entries.
+//!
+//! Creating a map only reads the thread's seed while some thread is within `with_seed`, so that maps created by an
+//! ordinary compilation cost no more than those of `rustc_hash`.
+
+#[cfg(test)]
+mod tests;
+
+use rustc_hash::FxHasher;
+use std::{
+    cell::Cell,
+    hash::{BuildHasher, Hasher},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+thread_local! {
+    static SEED: Cell<Option<u64>> = const { Cell::new(None) };
+}
+
+/// The number of calls to `with_seed` that are running on any thread.
+static SEEDED_RUNS: AtomicUsize = AtomicUsize::new(0);
+
+#[allow(clippy::disallowed_types)]
+pub type FxHashMap<K, V> = std::collections::HashMap<K, V, FxBuildHasher>;
+#[allow(clippy::disallowed_types)]
+pub type FxHashSet<T> = std::collections::HashSet<T, FxBuildHasher>;
+
+/// Builds the hashers of a map or set, using the seed of the thread at the time the map or set was created.
+#[derive(Clone, Copy, Debug)]
+pub struct FxBuildHasher {
+    seed: Option<u64>,
+}
+
+impl Default for FxBuildHasher {
+    fn default() -> Self {
+        if SEEDED_RUNS.load(Ordering::Relaxed) == 0 {
+            Self { seed: None }
+        } else {
+            Self { seed: SEED.get() }
+        }
+    }
+}
+
+impl BuildHasher for FxBuildHasher {
+    type Hasher = FxHasher;
+
+    fn build_hasher(&self) -> FxHasher {
+        let mut hasher = FxHasher::default();
+        if let Some(seed) = self.seed {
+            hasher.write_u64(seed);
+        }
+        hasher
+    }
+}
+
+/// Runs `f` with the maps and sets it creates on this thread seeded with `seed`, restoring the previous seed after,
+/// even if `f` panics.
+pub fn with_seed<T>(seed: u64, f: impl FnOnce() -> T) -> T {
+    let _guard = SeedGuard::set(seed);
+    f()
+}
+
+/// Restores the seed of the thread that was replaced when the guard is dropped.
+struct SeedGuard {
+    previous: Option<u64>,
+}
+
+impl SeedGuard {
+    fn set(seed: u64) -> Self {
+        SEEDED_RUNS.fetch_add(1, Ordering::Relaxed);
+        Self {
+            previous: SEED.replace(Some(seed)),
+        }
+    }
+}
+
+impl Drop for SeedGuard {
+    fn drop(&mut self) {
+        SEED.set(self.previous);
+        SEEDED_RUNS.fetch_sub(1, Ordering::Relaxed);
+    }
+}
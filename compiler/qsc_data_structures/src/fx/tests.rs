@@ -0,0 +1,52 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{with_seed, FxHashSet};
+
+fn iteration_order() -> Vec<u32> {
+    (0..64).collect::<FxHashSet<u32>>().into_iter().collect()
+}
+
+#[test]
+fn unseeded_set_iterates_as_rustc_hash_does() {
+    let expected = (0..64)
+        .collect::<rustc_hash::FxHashSet<u32>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+    assert_eq!(iteration_order(), expected);
+}
+
+#[test]
+fn same_seed_gives_same_order() {
+    assert_eq!(with_seed(7, iteration_order), with_seed(7, iteration_order));
+}
+
+#[test]
+fn seed_changes_order() {
+    assert_ne!(with_seed(7, iteration_order), iteration_order());
+}
+
+#[test]
+fn seed_is_restored_after_seeded_run() {
+    let unseeded = iteration_order();
+    with_seed(7, || with_seed(8, iteration_order));
+    assert_eq!(iteration_order(), unseeded);
+}
+
+#[test]
+fn map_keeps_seed_it_was_created_with() {
+    let mut set = with_seed(7, FxHashSet::<u32>::default);
+    set.extend(0..64);
+    assert_eq!(
+        set.into_iter().collect::<Vec<_>>(),
+        with_seed(7, iteration_order)
+    );
+}
+
+#[test]
+fn seed_is_restored_after_panic() {
+    let unseeded = iteration_order();
+    let result = std::panic::catch_unwind(|| with_seed(7, || panic!("seeded run failed")));
+    assert!(result.is_err());
+    assert_eq!(iteration_order(), unseeded);
+}
@@ -10,6 +10,9 @@ pub struct LanguageFeatures(u8);
 bitflags! {
     impl LanguageFeatures: u8 {
         const V2PreviewSyntax = 0b1;
+        /// Reports identifiers that are not in Unicode normalization form C, or that can be
+        /// confused with another identifier, instead of silently normalizing them.
+        const StrictUnicodeIdentifiers = 0b10;
     }
 }
 
@@ -33,6 +36,7 @@ where
         iter.into_iter().fold(LanguageFeatures::empty(), |acc, x| {
             acc | match x.as_ref() {
                 "v2-preview-syntax" => LanguageFeatures::V2PreviewSyntax,
+                "strict-unicode-identifiers" => LanguageFeatures::StrictUnicodeIdentifiers,
                 _ => LanguageFeatures::empty(),
             }
         })
@@ -45,6 +49,9 @@ impl From<LanguageFeatures> for Vec<String> {
         if features.contains(LanguageFeatures::V2PreviewSyntax) {
             result.push("v2-preview-syntax".to_string());
         }
+        if features.contains(LanguageFeatures::StrictUnicodeIdentifiers) {
+            result.push("strict-unicode-identifiers".to_string());
+        }
         result
     }
 }
@@ -2,10 +2,13 @@
 // Licensed under the MIT License.
 
 pub mod display;
+pub mod format_spec;
 pub mod functors;
+pub mod fx;
 pub mod index_map;
 pub mod language_features;
 pub mod line_column;
 pub mod namespaces;
+pub mod sha256;
 pub mod span;
 pub mod target;
@@ -5,6 +5,7 @@
 mod tests;
 
 use crate::span::Span;
+use std::sync::Arc;
 
 /// A line and column pair that describes a position in a string.
 #[derive(Clone, Copy, PartialEq, Debug, Eq, Hash)]
@@ -120,12 +121,181 @@ impl Range {
         Self { start, end }
     }
 
+    /// Returns the [`Range`] of each of the given spans, as [`Range::from_span`] does, iterating over
+    /// the string only once. The spans *MUST* be sorted and must not overlap.
+    #[must_use]
+    pub fn from_sorted_spans(encoding: Encoding, contents: &str, spans: &[Span]) -> Vec<Self> {
+        let offsets = spans
+            .iter()
+            .flat_map(|span| [span.lo, span.hi])
+            .collect::<Vec<_>>();
+        let mut positions = vec![Position { line: 0, column: 0 }; offsets.len()];
+        fill_positions(encoding, contents, &offsets, &mut positions);
+        positions
+            .chunks_exact(2)
+            .map(|pair| Self {
+                start: pair[0],
+                end: pair[1],
+            })
+            .collect()
+    }
+
     #[must_use]
     pub fn empty(&self) -> bool {
         self.start == self.end
     }
 }
 
+/// An index of the lines of a string, for converting between utf-8 byte offsets into it, utf-16 code unit
+/// offsets into it and [`Position`]s in either [`Encoding`].
+///
+/// Conversions agree with [`Position::from_utf8_byte_offset`], [`Position::to_utf8_byte_offset`] and
+/// [`Range::from_span`], but only scan the line that the offset or position is on, so the index suits
+/// converting many offsets into the same string.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LineIndex {
+    contents: Arc<str>,
+    /// The utf-8 byte offset of the start of each line.
+    line_starts: Vec<u32>,
+    /// The utf-16 code unit offset of the start of each line.
+    utf16_line_starts: Vec<u32>,
+}
+
+impl LineIndex {
+    #[must_use]
+    pub fn new(contents: impl Into<Arc<str>>) -> Self {
+        let contents = contents.into();
+        let mut line_starts = vec![0];
+        let mut utf16_line_starts = vec![0];
+        let mut utf16_offset = 0;
+        for (byte_offset, c) in contents.char_indices() {
+            utf16_offset += num_code_units(Encoding::Utf16, c);
+            if c == '\n' {
+                line_starts.push(u32(byte_offset + 1));
+                utf16_line_starts.push(utf16_offset);
+            }
+        }
+
+        Self {
+            contents,
+            line_starts,
+            utf16_line_starts,
+        }
+    }
+
+    /// The indexed string.
+    #[must_use]
+    pub fn contents(&self) -> &str {
+        &self.contents
+    }
+
+    /// The number of lines in the string, which is one more than the number of line breaks.
+    #[must_use]
+    pub fn line_count(&self) -> u32 {
+        u32(self.line_starts.len())
+    }
+
+    /// Returns the [`Position`] of the given utf-8 byte offset, like [`Position::from_utf8_byte_offset`].
+    #[must_use]
+    pub fn position(&self, encoding: Encoding, utf8_byte_offset: u32) -> Position {
+        let offset = self.char_boundary(utf8_byte_offset);
+        let line = self.line_of(offset);
+        let line_start = self.line_starts[line];
+        let column = self.contents[line_start as usize..offset as usize]
+            .chars()
+            .map(|c| num_code_units(encoding, c))
+            .sum();
+        Position {
+            line: u32(line),
+            column,
+        }
+    }
+
+    /// Returns the utf-8 byte offset of the given [`Position`], like [`Position::to_utf8_byte_offset`]. A
+    /// column in the middle of a character maps to the start of the character, and a column past the end of
+    /// its line maps to the end of the line.
+    #[must_use]
+    pub fn utf8_byte_offset(&self, encoding: Encoding, position: Position) -> u32 {
+        let Some(&line_start) = self.line_starts.get(position.line as usize) else {
+            return u32(self.contents.len());
+        };
+
+        let mut column = 0;
+        for (index, c) in self.contents[line_start as usize..].char_indices() {
+            column += num_code_units(encoding, c);
+            if c == '\n' || column > position.column {
+                return line_start + u32(index);
+            }
+        }
+        u32(self.contents.len())
+    }
+
+    /// Returns the [`Range`] of the given span of utf-8 byte offsets, like [`Range::from_span`].
+    #[must_use]
+    pub fn range(&self, encoding: Encoding, span: &Span) -> Range {
+        Range {
+            start: self.position(encoding, span.lo),
+            end: self.position(encoding, span.hi),
+        }
+    }
+
+    /// Returns the span of utf-8 byte offsets of the given [`Range`].
+    #[must_use]
+    pub fn span(&self, encoding: Encoding, range: &Range) -> Span {
+        Span {
+            lo: self.utf8_byte_offset(encoding, range.start),
+            hi: self.utf8_byte_offset(encoding, range.end),
+        }
+    }
+
+    /// Returns the utf-16 code unit offset of the given utf-8 byte offset, which is how JavaScript indexes into
+    /// strings. Offsets are mapped to character boundaries like in [`LineIndex::position`].
+    #[must_use]
+    pub fn utf16_offset(&self, utf8_byte_offset: u32) -> u32 {
+        let offset = self.char_boundary(utf8_byte_offset);
+        let line = self.line_of(offset);
+        let line_start = self.line_starts[line];
+        let column = self.contents[line_start as usize..offset as usize]
+            .chars()
+            .map(|c| num_code_units(Encoding::Utf16, c))
+            .sum::<u32>();
+        self.utf16_line_starts[line] + column
+    }
+
+    /// Returns the utf-8 byte offset of the given utf-16 code unit offset. An offset in the middle of a
+    /// surrogate pair maps to the start of the pair.
+    #[must_use]
+    pub fn utf8_byte_offset_from_utf16(&self, utf16_offset: u32) -> u32 {
+        let line = self
+            .utf16_line_starts
+            .partition_point(|&start| start <= utf16_offset)
+            - 1;
+        self.utf8_byte_offset(
+            Encoding::Utf16,
+            Position {
+                line: u32(line),
+                column: utf16_offset - self.utf16_line_starts[line],
+            },
+        )
+    }
+
+    /// The line that the given offset, which must be a character boundary, is on.
+    fn line_of(&self, utf8_byte_offset: u32) -> usize {
+        self.line_starts
+            .partition_point(|&start| start <= utf8_byte_offset)
+            - 1
+    }
+
+    /// The first character boundary at or after the given offset, or the end of the string.
+    fn char_boundary(&self, utf8_byte_offset: u32) -> u32 {
+        let mut offset = (utf8_byte_offset as usize).min(self.contents.len());
+        while !self.contents.is_char_boundary(offset) {
+            offset += 1;
+        }
+        u32(offset)
+    }
+}
+
 /// For a given string and array of utf-8 byte offsets, returns the [`Position`]s
 /// corresponding to the byte offsets.
 ///
@@ -137,6 +307,23 @@ fn positions_from_utf8_byte_offsets<const N: usize>(
     contents: &str,
     sorted_utf8_byte_offsets: [u32; N],
 ) -> [Position; N] {
+    let mut positions = [Position { line: 0, column: 0 }; N];
+    fill_positions(
+        encoding,
+        contents,
+        &sorted_utf8_byte_offsets,
+        &mut positions,
+    );
+    positions
+}
+
+/// Fills in the [`Position`] of each of the given sorted utf-8 byte offsets.
+fn fill_positions(
+    encoding: Encoding,
+    contents: &str,
+    sorted_utf8_byte_offsets: &[u32],
+    positions: &mut [Position],
+) {
     // The below example contains characters that are encoded
     // with different numbers of code units in utf-8 and utf-16,
     // to demonstrate how code unit offset will differ depending
@@ -153,13 +340,12 @@ fn positions_from_utf8_byte_offsets<const N: usize>(
     // utf-8 byte offset        | 0                 4        5                 9        12     13    15
     // utf-16 code unit offset  | 0                 2        3                 5        6      7     8
 
-    let mut positions = [Position { line: 0, column: 0 }; N];
     let mut i = 0;
     let mut column: u32 = 0;
     let mut line: u32 = 0;
 
     for (char_index, c) in contents.char_indices() {
-        if i == N {
+        if i == positions.len() {
             // We've run out of offsets to look for
             break;
         }
@@ -169,7 +355,7 @@ fn positions_from_utf8_byte_offsets<const N: usize>(
             positions[i] = Position { line, column };
             i += 1;
 
-            if i == N {
+            if i == positions.len() {
                 // We've run out of offsets to look for
                 break;
             }
@@ -187,12 +373,10 @@ fn positions_from_utf8_byte_offsets<const N: usize>(
     }
 
     // If any offsets couldn't be mapped, map them to <eof>
-    while i < N {
+    while i < positions.len() {
         positions[i] = Position { line, column };
         i += 1;
     }
-
-    positions
 }
 
 fn num_code_units(encoding: Encoding, c: char) -> u32 {
@@ -3,7 +3,7 @@
 
 use crate::span::Span;
 
-use super::{Encoding, Position, Range};
+use super::{Encoding, LineIndex, Position, Range};
 use expect_test::expect;
 use std::fmt::Write;
 
@@ -34,6 +34,29 @@ fn offset_out_of_bounds() {
     .assert_debug_eq(&pos);
 }
 
+#[test]
+fn sorted_spans_match_individual_ranges() {
+    let contents = "a𝑓\r\nbc\n\nΣ d";
+    let spans = [
+        Span { lo: 0, hi: 1 },
+        Span { lo: 1, hi: 5 },
+        Span { lo: 5, hi: 9 },
+        Span { lo: 9, hi: 9 },
+        Span { lo: 11, hi: 14 },
+        Span { lo: 15, hi: 20 },
+    ];
+    for encoding in [Encoding::Utf8, Encoding::Utf16] {
+        let expected = spans
+            .iter()
+            .map(|span| Range::from_span(encoding, contents, span))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            Range::from_sorted_spans(encoding, contents, &spans),
+            expected
+        );
+    }
+}
+
 #[allow(clippy::cast_possible_truncation)]
 #[test]
 fn position_out_of_bounds() {
@@ -339,6 +362,105 @@ fn range_out_of_bounds() {
     .assert_debug_eq(&range);
 }
 
+const LINE_INDEX_SAMPLES: [&str; 6] = [
+    "",
+    "hello",
+    "a\nb\n",
+    "a𝑓\r\nbc\n\nΣ d",
+    "𝑓(𝑥⃗)Σ",
+    "\n🎉 é\r\n\r\n👩‍👩‍👧x",
+];
+
+#[test]
+fn line_index_positions_match_scanning_conversions() {
+    for contents in LINE_INDEX_SAMPLES {
+        let index = LineIndex::new(contents);
+        let len = u32::try_from(contents.len()).expect("length should fit in u32");
+        for encoding in [Encoding::Utf8, Encoding::Utf16] {
+            for offset in 0..=len + 2 {
+                let position = index.position(encoding, offset);
+                assert_eq!(
+                    position,
+                    Position::from_utf8_byte_offset(encoding, contents, offset),
+                    "{contents:?} {encoding:?} {offset}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn line_index_offsets_match_scanning_conversions() {
+    for contents in LINE_INDEX_SAMPLES {
+        let index = LineIndex::new(contents);
+        for encoding in [Encoding::Utf8, Encoding::Utf16] {
+            for line in 0..=index.line_count() {
+                for column in 0..12 {
+                    let position = Position { line, column };
+                    assert_eq!(
+                        index.utf8_byte_offset(encoding, position),
+                        position.to_utf8_byte_offset(encoding, contents),
+                        "{contents:?} {encoding:?} {position:?}"
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn line_index_utf16_offsets_match_utf16_encoding() {
+    for contents in LINE_INDEX_SAMPLES {
+        let index = LineIndex::new(contents);
+        let mut utf16_offset = 0;
+        for (offset, c) in contents.char_indices() {
+            let offset = u32::try_from(offset).expect("offset should fit in u32");
+            assert_eq!(
+                index.utf16_offset(offset),
+                utf16_offset,
+                "{contents:?} {offset}"
+            );
+            assert_eq!(
+                index.utf8_byte_offset_from_utf16(utf16_offset),
+                offset,
+                "{contents:?} {utf16_offset}"
+            );
+            if c.len_utf16() == 2 {
+                // An offset between the halves of a surrogate pair maps to the start of the pair.
+                assert_eq!(index.utf8_byte_offset_from_utf16(utf16_offset + 1), offset);
+            }
+            utf16_offset += u32::try_from(c.len_utf16()).expect("length should fit in u32");
+        }
+        let len = u32::try_from(contents.len()).expect("length should fit in u32");
+        assert_eq!(index.utf16_offset(len), utf16_offset);
+        assert_eq!(index.utf8_byte_offset_from_utf16(utf16_offset), len);
+        assert_eq!(index.utf8_byte_offset_from_utf16(utf16_offset + 5), len);
+    }
+}
+
+#[test]
+fn line_index_ranges_round_trip() {
+    let contents = "a𝑓\r\nbc\n\nΣ d";
+    let index = LineIndex::new(contents);
+    let span = Span { lo: 1, hi: 13 };
+    let range = index.range(Encoding::Utf16, &span);
+    expect![[r#"
+        Range {
+            start: Position {
+                line: 0,
+                column: 1,
+            },
+            end: Position {
+                line: 3,
+                column: 1,
+            },
+        }
+    "#]]
+    .assert_debug_eq(&range);
+    assert_eq!(range, Range::from_span(Encoding::Utf16, contents, &span));
+    assert_eq!(index.span(Encoding::Utf16, &range), span);
+}
+
 #[allow(clippy::cast_possible_truncation)]
 fn check_all_offsets(contents: &str, expected: &expect_test::Expect) {
     let byte_offsets = 0..=contents.len();
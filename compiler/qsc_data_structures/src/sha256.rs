@@ -0,0 +1,57 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! SHA-256 digests, used to fingerprint sources and artifacts so they can be traced back to their exact inputs,
+//! and to key compiled packages by their inputs.
+
+#[cfg(test)]
+mod tests;
+
+use sha2::{Digest, Sha256};
+use std::fmt::Write;
+
+/// Computes the SHA-256 digest of the given data.
+#[must_use]
+pub fn digest(data: &[u8]) -> [u8; 32] {
+    Sha256::digest(data).into()
+}
+
+/// Computes the SHA-256 digest of the given data as a lowercase hexadecimal string.
+#[must_use]
+pub fn hex_digest(data: &[u8]) -> String {
+    digest(data)
+        .iter()
+        .fold(String::with_capacity(64), |mut hex, byte| {
+            write!(hex, "{byte:02x}").expect("writing to a string should succeed");
+            hex
+        })
+}
+
+/// Computes the SHA-256 digest of the bytes written to it, such as by values that are hashed with it, without
+/// buffering them.
+#[derive(Clone, Default)]
+pub struct Digester(Sha256);
+
+impl Digester {
+    /// The digest of the bytes written so far.
+    #[must_use]
+    pub fn digest(self) -> [u8; 32] {
+        self.0.finalize().into()
+    }
+}
+
+impl std::hash::Hasher for Digester {
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.update(bytes);
+    }
+
+    /// The first eight bytes of the digest of the bytes written so far.
+    fn finish(&self) -> u64 {
+        let digest = self.clone().digest();
+        u64::from_be_bytes(
+            digest[..8]
+                .try_into()
+                .expect("digest should have at least eight bytes"),
+        )
+    }
+}
@@ -0,0 +1,46 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{digest, hex_digest, Digester};
+use std::hash::Hasher;
+
+#[test]
+fn empty_input() {
+    assert_eq!(
+        hex_digest(b""),
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    );
+}
+
+#[test]
+fn short_input() {
+    assert_eq!(
+        hex_digest(b"abc"),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+}
+
+#[test]
+fn input_spanning_two_blocks() {
+    assert_eq!(
+        hex_digest(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"),
+        "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+    );
+}
+
+#[test]
+fn input_of_exactly_one_block() {
+    assert_eq!(
+        hex_digest(&[b'a'; 64]),
+        "ffe054fe7ae0cb6dc65c3af9b61d5209f439851db43d0ba5997337df154668eb"
+    );
+}
+
+#[test]
+fn digester_matches_digest_of_written_bytes() {
+    let mut digester = Digester::default();
+    digester.write(b"ab");
+    digester.write(b"c");
+    assert_eq!(digester.finish(), 0xba78_16bf_8f01_cfea);
+    assert_eq!(digester.digest(), digest(b"abc"));
+}
@@ -432,7 +432,15 @@ impl<'a> HirUdt<'a> {
 
 impl Display for HirUdt<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        if self.is_struct {
+        if self.udt.is_enum() {
+            write!(
+                f,
+                "enum {}{} ",
+                self.udt.name,
+                display_type_params(&self.udt.generics)
+            )?;
+            fmt_brace_seq(f, &self.udt.variants, |variant| HirUdtVariant { variant })
+        } else if self.is_struct {
             match &self.udt.definition.kind {
                 ty::UdtDefKind::Tuple(fields) => {
                     write!(f, "struct {} ", self.udt.name)?;
@@ -501,6 +509,23 @@ impl Display for HirUdtField<'_> {
     }
 }
 
+struct HirUdtVariant<'a> {
+    variant: &'a ty::UdtVariant,
+}
+
+impl Display for HirUdtVariant<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}", self.variant.name)?;
+        if self.variant.ty != ty::Ty::UNIT {
+            match &self.variant.ty {
+                ty::Ty::Tuple(_) => write!(f, "{}", self.variant.ty.display())?,
+                ty => write!(f, "({})", ty.display())?,
+            }
+        }
+        Ok(())
+    }
+}
+
 struct FunctorSetValue {
     functors: ty::FunctorSetValue,
 }
@@ -538,6 +563,7 @@ impl Display for AstTy<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         match self.ty.kind.as_ref() {
             ast::TyKind::Array(ty) => write!(f, "{}[]", AstTy { ty }),
+            ast::TyKind::SizedArray(ty, len) => write!(f, "{}[{len}]", AstTy { ty }),
             ast::TyKind::Arrow(kind, input, output, functors) => {
                 let arrow = match kind {
                     ast::CallableKind::Function => "->",
@@ -555,6 +581,16 @@ impl Display for AstTy<'_> {
             ast::TyKind::Hole => write!(f, "_"),
             ast::TyKind::Paren(ty) => write!(f, "{}", AstTy { ty }),
             ast::TyKind::Path(path) => write!(f, "{}", AstPathKind { path }),
+            ast::TyKind::App(path, args) => {
+                write!(f, "{}<", AstPathKind { path })?;
+                if let Some((first, rest)) = args.split_first() {
+                    write!(f, "{}", AstTy { ty: first })?;
+                    for ty in rest {
+                        write!(f, ", {}", AstTy { ty })?;
+                    }
+                }
+                write!(f, ">")
+            }
             ast::TyKind::Param(AstTypeParameter { ty, .. }) => write!(f, "{}", ty.name),
             ast::TyKind::Tuple(tys) => fmt_tuple(f, tys, |ty| AstTy { ty }),
             ast::TyKind::Err => write!(f, "?"),
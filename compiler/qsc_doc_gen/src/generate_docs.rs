@@ -71,6 +71,7 @@ ms.topic: {}
             MetadataKind::Function => "function",
             MetadataKind::Operation => "operation",
             MetadataKind::Udt => "udt",
+            MetadataKind::Class => "class",
             MetadataKind::Export => "export",
             MetadataKind::TableOfContents => "table of contents",
         };
@@ -116,6 +117,7 @@ enum MetadataKind {
     Function,
     Operation,
     Udt,
+    Class,
     Export,
     TableOfContents,
 }
@@ -126,6 +128,7 @@ impl Display for MetadataKind {
             MetadataKind::Function => "function",
             MetadataKind::Operation => "operation",
             MetadataKind::Udt => "user defined type",
+            MetadataKind::Class => "class",
             MetadataKind::Export => "exported item",
             MetadataKind::TableOfContents => "table of contents",
         };
@@ -687,6 +690,11 @@ fn get_metadata(
             display.hir_udt(udt).to_string(),
             MetadataKind::Udt,
         )),
+        ItemKind::Class(ident, def) => Some((
+            ident.name.clone(),
+            class_signature(&ident.name, def),
+            MetadataKind::Class,
+        )),
         ItemKind::Namespace(_, _) => None,
         ItemKind::Export(name, _) => Some((
             name.name.clone(),
@@ -712,3 +720,25 @@ fn get_metadata(
         signature,
     })
 }
+
+/// The declaration of a class as it is written in source, without its methods, which are
+/// documented as callables of their own.
+fn class_signature(name: &str, def: &ty::ClassDef) -> String {
+    let mut signature = format!("class {name}");
+    if !def.generics.is_empty() {
+        let params = def
+            .generics
+            .iter()
+            .filter_map(|param| match param {
+                ty::TypeParameter::Ty { name, .. } => Some(name.to_string()),
+                ty::TypeParameter::Functor(_) => None,
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        signature.push_str(&format!("[{params}]"));
+    }
+    if !def.constraints.is_empty() {
+        signature.push_str(&format!(" : {}", def.constraints));
+    }
+    signature
+}
@@ -163,8 +163,15 @@ fn index_file_generation() {
 
         | Name | Description |
         |------|-------------|
+        | [CompileTimeAssert](xref:Qdk.Std.Core.CompileTimeAssert) | Checks at compile time that a condition holds, and fails compilation with the given message if it doesn't. |
+        | [Err](xref:Qdk.Std.Core.Err) |  |
         | [Length](xref:Qdk.Std.Core.Length) | Returns the number of elements in the input array `a`. |
+        | [None](xref:Qdk.Std.Core.None) |  |
+        | [Ok](xref:Qdk.Std.Core.Ok) |  |
+        | [Option](xref:Qdk.Std.Core.Option) | An optional value, which is either `Some` value or `None`. |
+        | [Outcome](xref:Qdk.Std.Core.Outcome) | The outcome of a computation that can fail, which is either `Ok` with a value or `Err` with an error. |
         | [Repeated](xref:Qdk.Std.Core.Repeated) | Creates an array of given `length` with all elements equal to given `value`. `length` must be a non-negative integer. |
+        | [Some](xref:Qdk.Std.Core.Some) |  |
     "#]]
     .assert_eq(full_contents.as_str());
 }
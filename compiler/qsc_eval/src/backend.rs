@@ -1,15 +1,25 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
-use crate::val::Value;
-use crate::{noise::PauliNoise, val::unwrap_tuple};
+#[cfg(feature = "sparse-sim")]
+use crate::val::unwrap_tuple;
+use crate::{
+    noise::{NoiseModel, PauliNoise},
+    val::{self, Value},
+};
+#[cfg(feature = "sparse-sim")]
 use ndarray::Array2;
 use num_bigint::BigUint;
 use num_complex::Complex;
+use qsc_fir::fir::Pauli;
+#[cfg(feature = "sparse-sim")]
 use quantum_sparse_sim::QuantumSim;
-use rand::{rngs::StdRng, Rng, RngCore, SeedableRng};
+#[cfg(feature = "sparse-sim")]
+use rand::RngCore;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rustc_hash::FxHashMap;
 
-#[cfg(test)]
+#[cfg(all(test, feature = "sparse-sim"))]
 mod noise_tests;
 
 /// The trait that must be implemented by a quantum backend, whose functions will be invoked when
@@ -99,9 +109,27 @@ pub trait Backend {
     fn capture_quantum_state(&mut self) -> (Vec<(BigUint, Complex<f64>)>, usize) {
         unimplemented!("capture_quantum_state operation");
     }
+    /// Prepares the state, given in the order of `capture_quantum_state`, on the qubits, which are
+    /// all of the allocated qubits in ascending order of id and are in the |0⟩ state. This lets a
+    /// checkpoint of an evaluation be resumed. Backends that can't prepare an arbitrary state
+    /// return an error.
+    fn set_quantum_state(
+        &mut self,
+        _state: &[(BigUint, Complex<f64>)],
+        _qubits: &[usize],
+    ) -> Result<(), String> {
+        Err("the simulator can't prepare an arbitrary quantum state".to_string())
+    }
     fn qubit_is_zero(&mut self, _q: usize) -> bool {
         unimplemented!("qubit_is_zero operation");
     }
+    /// Computes the expectation value ⟨ψ|P|ψ⟩ of the Pauli observable that applies each Pauli to
+    /// the qubit paired with it, without changing the state. By default, it is computed from the
+    /// captured quantum state.
+    fn expectation_value(&mut self, paulis: &[(Pauli, usize)]) -> f64 {
+        let (state, qubit_count) = self.capture_quantum_state();
+        pauli_expectation(paulis, &state, qubit_count)
+    }
     /// Executes custom intrinsic specified by `_name`.
     /// Returns None if this intrinsic is unknown.
     /// Otherwise returns Some(Result), with the Result from intrinsic.
@@ -109,9 +137,84 @@ pub trait Backend {
         None
     }
     fn set_seed(&mut self, _seed: Option<u64>) {}
+    /// Waits for `duration_us` microseconds while the given qubits idle. Backends that model noise
+    /// apply idle noise in proportion to the duration; it doesn't change the state otherwise.
+    fn delay(&mut self, _duration_us: f64, _qubits: &[usize]) {}
+    /// Returns the reason the last operation couldn't be applied and clears it, or `None` if it
+    /// was applied. Backends that can only simulate some operations use this to fail the
+    /// intrinsic that called them.
+    fn take_failure(&mut self) -> Option<String> {
+        None
+    }
+    /// Returns how the quantum state grew beyond the memory budget during the last operation and
+    /// clears it, or `None` if it stayed within the budget.
+    fn take_memory_exceeded(&mut self) -> Option<MemoryExceeded> {
+        None
+    }
+}
+
+/// The memory budget of a simulator and the size of the quantum state that exceeded it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MemoryExceeded {
+    /// The memory budget, in bytes.
+    pub budget: usize,
+    /// The estimated size of the quantum state, in bytes.
+    pub size: usize,
+}
+
+/// Estimates the memory, in bytes, used by a quantum state with the given number of basis states
+/// with a nonzero amplitude over the given number of qubits.
+#[must_use]
+pub fn quantum_state_size(entries: usize, qubit_count: usize) -> usize {
+    let label_bytes = qubit_count.div_ceil(u64::BITS as usize) * std::mem::size_of::<u64>();
+    entries.saturating_mul(std::mem::size_of::<(BigUint, Complex<f64>)>() + label_bytes)
+}
+
+/// Computes ⟨ψ|P|ψ⟩ for a state whose labels have the first qubit as the most significant bit.
+/// Each Pauli maps a basis state to another basis state with a phase, so the sum only needs the
+/// amplitude of the basis state that each basis state is mapped to.
+fn pauli_expectation(
+    paulis: &[(Pauli, usize)],
+    state: &[(BigUint, Complex<f64>)],
+    qubit_count: usize,
+) -> f64 {
+    let amplitudes = state.iter().cloned().collect::<FxHashMap<_, _>>();
+    let mut expectation = Complex::new(0.0, 0.0);
+    for (label, amplitude) in state {
+        let mut mapped = label.clone();
+        let mut phase = Complex::new(1.0, 0.0);
+        for (pauli, q) in paulis {
+            let bit = (qubit_count - q - 1) as u64;
+            let is_one = label.bit(bit);
+            match pauli {
+                Pauli::I => {}
+                Pauli::X => mapped.set_bit(bit, !is_one),
+                Pauli::Y => {
+                    // Y|0⟩ = i|1⟩ and Y|1⟩ = -i|0⟩.
+                    phase *= if is_one {
+                        Complex::new(0.0, -1.0)
+                    } else {
+                        Complex::new(0.0, 1.0)
+                    };
+                    mapped.set_bit(bit, !is_one);
+                }
+                Pauli::Z => {
+                    if is_one {
+                        phase = -phase;
+                    }
+                }
+            }
+        }
+        if let Some(mapped_amplitude) = amplitudes.get(&mapped) {
+            expectation += mapped_amplitude.conj() * phase * amplitude;
+        }
+    }
+    // The observable is Hermitian, so the expectation value is real.
+    expectation.re
 }
 
 /// Default backend used when targeting sparse simulation.
+#[cfg(feature = "sparse-sim")]
 pub struct SparseSim {
     /// Noiseless Sparse simulator to be used by this instance.
     pub sim: QuantumSim,
@@ -121,14 +224,24 @@ pub struct SparseSim {
     /// Random number generator to sample Pauli noise.
     /// Noise is not applied when rng is None.
     pub rng: Option<StdRng>,
+    /// The memory, in bytes, that the quantum state can use, or `None` if it isn't limited.
+    memory_budget: Option<usize>,
+    /// An upper bound on the number of basis states with a nonzero amplitude, so that the budget
+    /// can be checked without capturing the state after every gate.
+    state_len_bound: usize,
+    /// The number of allocated qubits.
+    qubit_count: usize,
+    memory_exceeded: Option<MemoryExceeded>,
 }
 
+#[cfg(feature = "sparse-sim")]
 impl Default for SparseSim {
     fn default() -> Self {
         Self::new()
     }
 }
 
+#[cfg(feature = "sparse-sim")]
 impl SparseSim {
     #[must_use]
     pub fn new() -> Self {
@@ -136,9 +249,20 @@ impl SparseSim {
             sim: QuantumSim::new(None),
             noise: PauliNoise::default(),
             rng: None,
+            memory_budget: None,
+            state_len_bound: 1,
+            qubit_count: 0,
+            memory_exceeded: None,
         }
     }
 
+    /// Limits the memory, in bytes, that the quantum state can use. When a gate grows the state
+    /// beyond the budget, the intrinsic that applied it fails with
+    /// [`Backend::take_memory_exceeded`] instead of the simulation running out of memory.
+    pub fn set_memory_budget(&mut self, budget: Option<usize>) {
+        self.memory_budget = budget;
+    }
+
     #[must_use]
     pub fn new_with_noise(noise: &PauliNoise) -> Self {
         let mut sim = SparseSim::new();
@@ -175,8 +299,92 @@ impl SparseSim {
         }
         // No noise applied if rng is None.
     }
+
+    /// Prepares the basis states of the sorted `state` that all start with the bits of `prefix`,
+    /// where the bit of each qubit in `qubits` is the next most significant bit of the indices.
+    /// Each qubit is rotated, controlled on the prefix, so that its branches get the weights of
+    /// the basis states in them, and each basis state then gets the phase of its amplitude. This
+    /// only visits branches with basis states, so it takes a number of gates that is linear in the
+    /// number of basis states rather than exponential in the number of qubits.
+    fn prepare_branch(
+        &mut self,
+        state: &[(BigUint, Complex<f64>)],
+        qubits: &[usize],
+        prefix: &mut Vec<(usize, bool)>,
+    ) {
+        let Some((&q, rest)) = qubits.split_first() else {
+            if let [(_, amplitude)] = state {
+                let (_, phase) = amplitude.to_polar();
+                if phase != 0.0 {
+                    // As with `GlobalPhase`, an Rz on a fresh qubit in |0⟩ applies the phase to
+                    // the basis states that satisfy the controls.
+                    let ancilla = self.sim.allocate();
+                    self.with_prefix(prefix, |sim, ctls| sim.mcrz(ctls, -2.0 * phase, ancilla));
+                    self.sim.release(ancilla);
+                }
+            }
+            return;
+        };
+        let bit = rest.len() as u64;
+        let split = state.partition_point(|(index, _)| !index.bit(bit));
+        let (zero, one) = state.split_at(split);
+        let weight = |branch: &[(BigUint, Complex<f64>)]| {
+            branch
+                .iter()
+                .map(|(_, amplitude)| amplitude.norm_sqr())
+                .sum::<f64>()
+                .sqrt()
+        };
+        if !one.is_empty() {
+            let theta = 2.0 * weight(one).atan2(weight(zero));
+            self.with_prefix(prefix, |sim, ctls| sim.mcry(ctls, theta, q));
+        }
+        for (branch, value) in [(zero, false), (one, true)] {
+            if !branch.is_empty() {
+                prefix.push((q, value));
+                self.prepare_branch(branch, rest, prefix);
+                prefix.pop();
+            }
+        }
+    }
+
+    /// Calls `f` with the qubits of the prefix as controls, flipping the qubits that are
+    /// controlled on |0⟩ around it.
+    fn with_prefix(&mut self, prefix: &[(usize, bool)], f: impl FnOnce(&mut QuantumSim, &[usize])) {
+        let flipped = prefix
+            .iter()
+            .filter_map(|&(q, value)| (!value).then_some(q))
+            .collect::<Vec<_>>();
+        let ctls = prefix.iter().map(|&(q, _)| q).collect::<Vec<_>>();
+        for &q in &flipped {
+            self.sim.x(q);
+        }
+        f(&mut self.sim, &ctls);
+        for &q in &flipped {
+            self.sim.x(q);
+        }
+    }
+
+    /// Records that a gate may have multiplied the number of basis states by `growth`, and checks
+    /// the memory budget. The state is only captured when the bound exceeds the budget, which
+    /// also tightens the bound to the actual number of basis states.
+    fn check_memory(&mut self, growth: usize) {
+        let Some(budget) = self.memory_budget else {
+            return;
+        };
+        self.state_len_bound = self.state_len_bound.saturating_mul(growth);
+        if quantum_state_size(self.state_len_bound, self.qubit_count) <= budget {
+            return;
+        }
+        self.state_len_bound = self.sim.get_state().0.len();
+        let size = quantum_state_size(self.state_len_bound, self.qubit_count);
+        if size > budget {
+            self.memory_exceeded = Some(MemoryExceeded { budget, size });
+        }
+    }
 }
 
+#[cfg(feature = "sparse-sim")]
 impl Backend for SparseSim {
     type ResultType = bool;
 
@@ -207,6 +415,7 @@ impl Backend for SparseSim {
 
     fn h(&mut self, q: usize) {
         self.sim.h(q);
+        self.check_memory(2);
         self.apply_noise(q);
     }
 
@@ -232,6 +441,7 @@ impl Backend for SparseSim {
 
     fn rx(&mut self, theta: f64, q: usize) {
         self.sim.rx(theta, q);
+        self.check_memory(2);
         self.apply_noise(q);
     }
 
@@ -243,12 +453,14 @@ impl Backend for SparseSim {
         self.sim.mcx(&[q1], q0);
         self.sim.h(q1);
         self.sim.h(q0);
+        self.check_memory(2);
         self.apply_noise(q0);
         self.apply_noise(q1);
     }
 
     fn ry(&mut self, theta: f64, q: usize) {
         self.sim.ry(theta, q);
+        self.check_memory(2);
         self.apply_noise(q);
     }
 
@@ -268,6 +480,7 @@ impl Backend for SparseSim {
         self.sim.h(q0);
         self.sim.sadj(q0);
         self.sim.h(q0);
+        self.check_memory(2);
         self.apply_noise(q0);
         self.apply_noise(q1);
     }
@@ -328,10 +541,12 @@ impl Backend for SparseSim {
 
     fn qubit_allocate(&mut self) -> usize {
         // Fresh qubit start in ground state even with noise.
+        self.qubit_count += 1;
         self.sim.allocate()
     }
 
     fn qubit_release(&mut self, q: usize) -> bool {
+        self.qubit_count = self.qubit_count.saturating_sub(1);
         if self.is_noiseless() {
             let was_zero = self.sim.qubit_is_zero(q);
             self.sim.release(q);
@@ -367,6 +582,28 @@ impl Backend for SparseSim {
         (new_state, count)
     }
 
+    fn set_quantum_state(
+        &mut self,
+        state: &[(BigUint, Complex<f64>)],
+        qubits: &[usize],
+    ) -> Result<(), String> {
+        // This is a service function rather than a sequence of gates so it doesn't incur noise.
+        let norm = state
+            .iter()
+            .map(|(_, amplitude)| amplitude.norm_sqr())
+            .sum::<f64>();
+        if (norm - 1.0).abs() > 1e-6 {
+            return Err(format!("the state has norm {norm} instead of 1"));
+        }
+        let bits = qubits.len() as u64;
+        if state.iter().any(|(index, _)| index.bits() > bits) {
+            return Err(format!("the state has more than {bits} qubits"));
+        }
+        self.prepare_branch(state, qubits, &mut Vec::new());
+        self.check_memory(state.len());
+        Ok(())
+    }
+
     fn qubit_is_zero(&mut self, q: usize) -> bool {
         // This is a service function rather than a measurement so it doesn't incur noise.
         self.sim.qubit_is_zero(q)
@@ -440,6 +677,7 @@ impl Backend for SparseSim {
                 }
 
                 self.sim.apply(&matrix, &qubits, None);
+                self.check_memory(1 << qubits.len());
 
                 Some(Ok(Value::unit()))
             }
@@ -460,8 +698,13 @@ impl Backend for SparseSim {
             self.sim.set_rng_seed(rand::thread_rng().next_u64());
         }
     }
+
+    fn take_memory_exceeded(&mut self) -> Option<MemoryExceeded> {
+        self.memory_exceeded.take()
+    }
 }
 
+#[cfg(feature = "sparse-sim")]
 fn unwrap_matrix_as_array2(matrix: Value, qubits: &[usize]) -> Array2<Complex<f64>> {
     let matrix: Vec<Vec<Complex<f64>>> = matrix
         .unwrap_array()
@@ -650,11 +893,25 @@ where
         self.main.capture_quantum_state()
     }
 
+    fn set_quantum_state(
+        &mut self,
+        state: &[(BigUint, Complex<f64>)],
+        qubits: &[usize],
+    ) -> Result<(), String> {
+        // Only the main backend holds a quantum state, so the chained one is left as it is.
+        self.main.set_quantum_state(state, qubits)
+    }
+
     fn qubit_is_zero(&mut self, q: usize) -> bool {
         let _ = self.chained.qubit_is_zero(q);
         self.main.qubit_is_zero(q)
     }
 
+    fn expectation_value(&mut self, paulis: &[(Pauli, usize)]) -> f64 {
+        let _ = self.chained.expectation_value(paulis);
+        self.main.expectation_value(paulis)
+    }
+
     fn custom_intrinsic(&mut self, name: &str, arg: Value) -> Option<Result<Value, String>> {
         let _ = self.chained.custom_intrinsic(name, arg.clone());
         self.main.custom_intrinsic(name, arg)
@@ -664,4 +921,268 @@ where
         self.chained.set_seed(seed);
         self.main.set_seed(seed);
     }
+
+    fn delay(&mut self, duration_us: f64, qubits: &[usize]) {
+        self.chained.delay(duration_us, qubits);
+        self.main.delay(duration_us, qubits);
+    }
+
+    fn take_failure(&mut self) -> Option<String> {
+        let chained = self.chained.take_failure();
+        self.main.take_failure().or(chained)
+    }
+
+    fn take_memory_exceeded(&mut self) -> Option<MemoryExceeded> {
+        let chained = self.chained.take_memory_exceeded();
+        self.main.take_memory_exceeded().or(chained)
+    }
+}
+
+/// Backend that injects the errors of a [`NoiseModel`] into the operations it passes on to the
+/// wrapped backend. Gate errors are applied with the X, Y and Z gates of the wrapped backend after
+/// each gate, measurement errors flip the reported outcome, and measurements of lost qubits report
+/// [`val::Result::Loss`].
+pub struct Noisy<B> {
+    pub sim: B,
+    model: NoiseModel,
+    /// Random number generator to sample the errors.
+    rng: StdRng,
+}
+
+impl<B> Noisy<B>
+where
+    B: Backend<ResultType = bool>,
+{
+    #[must_use]
+    pub fn new(sim: B, model: NoiseModel) -> Self {
+        Self {
+            sim,
+            model,
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    fn apply_noise(&mut self, gate: &str, qubits: &[usize]) {
+        let noise = *self.model.gate_noise(gate);
+        self.apply_pauli_noise(&noise, qubits);
+    }
+
+    fn apply_pauli_noise(&mut self, noise: &PauliNoise, qubits: &[usize]) {
+        if noise.is_noiseless() {
+            return;
+        }
+        for &q in qubits {
+            let p = self.rng.gen_range(0.0..1.0);
+            if p >= noise.distribution[2] {
+                // In the most common case we don't apply noise
+            } else if p < noise.distribution[0] {
+                self.sim.x(q);
+            } else if p < noise.distribution[1] {
+                self.sim.y(q);
+            } else {
+                self.sim.z(q);
+            }
+        }
+    }
+
+    fn measurement_error(&mut self, outcome: bool) -> bool {
+        if self.model.measurement > 0.0 && self.rng.gen_range(0.0..1.0) < self.model.measurement {
+            !outcome
+        } else {
+            outcome
+        }
+    }
+
+    /// Samples whether the qubit was lost before it was measured. A lost qubit is replaced with a fresh
+    /// one, which is what resetting the qubit in the wrapped backend amounts to.
+    fn qubit_loss(&mut self, q: usize) -> bool {
+        if self.model.loss > 0.0 && self.rng.gen_range(0.0..1.0) < self.model.loss {
+            self.sim.reset(q);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl<B> Backend for Noisy<B>
+where
+    B: Backend<ResultType = bool>,
+{
+    type ResultType = val::Result;
+
+    fn ccx(&mut self, ctl0: usize, ctl1: usize, q: usize) {
+        self.sim.ccx(ctl0, ctl1, q);
+        self.apply_noise("ccx", &[ctl0, ctl1, q]);
+    }
+
+    fn cx(&mut self, ctl: usize, q: usize) {
+        self.sim.cx(ctl, q);
+        self.apply_noise("cx", &[ctl, q]);
+    }
+
+    fn cy(&mut self, ctl: usize, q: usize) {
+        self.sim.cy(ctl, q);
+        self.apply_noise("cy", &[ctl, q]);
+    }
+
+    fn cz(&mut self, ctl: usize, q: usize) {
+        self.sim.cz(ctl, q);
+        self.apply_noise("cz", &[ctl, q]);
+    }
+
+    fn h(&mut self, q: usize) {
+        self.sim.h(q);
+        self.apply_noise("h", &[q]);
+    }
+
+    fn m(&mut self, q: usize) -> Self::ResultType {
+        if self.qubit_loss(q) {
+            return val::Result::Loss;
+        }
+        let outcome = self.sim.m(q);
+        self.measurement_error(outcome).into()
+    }
+
+    fn mresetz(&mut self, q: usize) -> Self::ResultType {
+        if self.qubit_loss(q) {
+            return val::Result::Loss;
+        }
+        let outcome = self.sim.mresetz(q);
+        self.measurement_error(outcome).into()
+    }
+
+    fn reset(&mut self, q: usize) {
+        self.sim.reset(q);
+        self.apply_noise("reset", &[q]);
+    }
+
+    fn rx(&mut self, theta: f64, q: usize) {
+        self.sim.rx(theta, q);
+        self.apply_noise("rx", &[q]);
+    }
+
+    fn rxx(&mut self, theta: f64, q0: usize, q1: usize) {
+        self.sim.rxx(theta, q0, q1);
+        self.apply_noise("rxx", &[q0, q1]);
+    }
+
+    fn ry(&mut self, theta: f64, q: usize) {
+        self.sim.ry(theta, q);
+        self.apply_noise("ry", &[q]);
+    }
+
+    fn ryy(&mut self, theta: f64, q0: usize, q1: usize) {
+        self.sim.ryy(theta, q0, q1);
+        self.apply_noise("ryy", &[q0, q1]);
+    }
+
+    fn rz(&mut self, theta: f64, q: usize) {
+        self.sim.rz(theta, q);
+        self.apply_noise("rz", &[q]);
+    }
+
+    fn rzz(&mut self, theta: f64, q0: usize, q1: usize) {
+        self.sim.rzz(theta, q0, q1);
+        self.apply_noise("rzz", &[q0, q1]);
+    }
+
+    fn sadj(&mut self, q: usize) {
+        self.sim.sadj(q);
+        self.apply_noise("sadj", &[q]);
+    }
+
+    fn s(&mut self, q: usize) {
+        self.sim.s(q);
+        self.apply_noise("s", &[q]);
+    }
+
+    fn swap(&mut self, q0: usize, q1: usize) {
+        self.sim.swap(q0, q1);
+        self.apply_noise("swap", &[q0, q1]);
+    }
+
+    fn tadj(&mut self, q: usize) {
+        self.sim.tadj(q);
+        self.apply_noise("tadj", &[q]);
+    }
+
+    fn t(&mut self, q: usize) {
+        self.sim.t(q);
+        self.apply_noise("t", &[q]);
+    }
+
+    fn x(&mut self, q: usize) {
+        self.sim.x(q);
+        self.apply_noise("x", &[q]);
+    }
+
+    fn y(&mut self, q: usize) {
+        self.sim.y(q);
+        self.apply_noise("y", &[q]);
+    }
+
+    fn z(&mut self, q: usize) {
+        self.sim.z(q);
+        self.apply_noise("z", &[q]);
+    }
+
+    fn qubit_allocate(&mut self) -> usize {
+        self.sim.qubit_allocate()
+    }
+
+    fn qubit_release(&mut self, q: usize) -> bool {
+        // With noise, qubits can be released in a non-zero state, which is allowed.
+        self.sim.qubit_release(q) || !self.model.is_noiseless()
+    }
+
+    fn qubit_swap_id(&mut self, q0: usize, q1: usize) {
+        self.sim.qubit_swap_id(q0, q1);
+    }
+
+    fn capture_quantum_state(&mut self) -> (Vec<(BigUint, Complex<f64>)>, usize) {
+        self.sim.capture_quantum_state()
+    }
+
+    fn set_quantum_state(
+        &mut self,
+        state: &[(BigUint, Complex<f64>)],
+        qubits: &[usize],
+    ) -> Result<(), String> {
+        self.sim.set_quantum_state(state, qubits)
+    }
+
+    fn qubit_is_zero(&mut self, q: usize) -> bool {
+        self.sim.qubit_is_zero(q)
+    }
+
+    fn expectation_value(&mut self, paulis: &[(Pauli, usize)]) -> f64 {
+        self.sim.expectation_value(paulis)
+    }
+
+    fn custom_intrinsic(&mut self, name: &str, arg: Value) -> Option<Result<Value, String>> {
+        self.sim.custom_intrinsic(name, arg)
+    }
+
+    fn set_seed(&mut self, seed: Option<u64>) {
+        self.rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        self.sim.set_seed(seed);
+    }
+
+    fn delay(&mut self, duration_us: f64, qubits: &[usize]) {
+        self.sim.delay(duration_us, qubits);
+        let noise = self.model.idle.over_duration(duration_us);
+        self.apply_pauli_noise(&noise, qubits);
+    }
+
+    fn take_failure(&mut self) -> Option<String> {
+        self.sim.take_failure()
+    }
+
+    fn take_memory_exceeded(&mut self) -> Option<MemoryExceeded> {
+        self.sim.take_memory_exceeded()
+    }
 }
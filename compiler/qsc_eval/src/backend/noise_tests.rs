@@ -2,9 +2,10 @@
 // Licensed under the MIT License.
 
 use crate::{
-    backend::{Backend, SparseSim},
-    noise::PauliNoise,
+    backend::{Backend, Noisy, SparseSim},
+    noise::{NoiseModel, PauliNoise},
     state::{fmt_complex, format_state_id},
+    val,
 };
 use expect_test::{expect, Expect};
 use num_bigint::BigUint;
@@ -202,3 +203,205 @@ fn noisy_via_z() {
     sim.z(q); // Followed by Z. So, no op.
     check_state(&mut sim, &expect!["|0⟩: 0.0000+1.0000𝑖 "]);
 }
+
+#[test]
+fn noise_model_constructors() {
+    let same = |noise: PauliNoise, distribution: [f64; 3]| {
+        noise
+            .distribution
+            .iter()
+            .zip(distribution)
+            .all(|(actual, expected)| (actual - expected).abs() < 1e-12)
+    };
+    let noise = PauliNoise::depolarizing(0.3).expect("depolarizing noise should be constructable.");
+    assert!(
+        same(noise, [0.1, 0.2, 0.3]),
+        "Expected X, Y and Z to be equally likely."
+    );
+    let noise = PauliNoise::bit_flip(0.2).expect("bit flip noise should be constructable.");
+    assert!(same(noise, [0.2, 0.2, 0.2]), "Expected only X.");
+    let noise = PauliNoise::phase_flip(0.2).expect("phase flip noise should be constructable.");
+    assert!(same(noise, [0.0, 0.0, 0.2]), "Expected only Z.");
+    let _ = PauliNoise::depolarizing(1.5).expect_err("depolarizing noise above 1 should fail.");
+    assert!(NoiseModel::default().is_noiseless());
+}
+
+#[test]
+fn noise_model_applies_gate_noise_by_name() {
+    let model = NoiseModel {
+        gates: [(
+            "x".into(),
+            PauliNoise::bit_flip(1.0).expect("bit flip noise should be constructable."),
+        )]
+        .into_iter()
+        .collect(),
+        ..NoiseModel::default()
+    };
+    let mut sim = Noisy::new(SparseSim::new(), model);
+    let q = sim.qubit_allocate();
+    for _ in 0..10 {
+        // Each X is followed by another X from the noise, which undoes it.
+        sim.x(q);
+        assert!(sim.qubit_is_zero(q), "Expected X to be undone by noise.");
+        // Gates without noise of their own use the default gate noise, which is noiseless.
+        sim.y(q);
+        assert_eq!(
+            sim.m(q),
+            val::Result::Val(true),
+            "Expected Y to be noiseless."
+        );
+        sim.y(q);
+    }
+    assert!(sim.qubit_release(q));
+}
+
+#[test]
+fn idle_noise_grows_with_duration() {
+    let noise = PauliNoise::bit_flip(0.1).expect("bit flip noise should be constructable.");
+    let twice = noise.over_duration(2.0);
+    // Flipping exactly once in two steps has probability 2 * 0.1 * 0.9.
+    assert!(
+        (twice.distribution[0] - 0.18).abs() < 1e-12,
+        "Expected X with probability 0.18, got {:?}.",
+        twice.distribution
+    );
+    assert!(
+        (twice.distribution[2] - 0.18).abs() < 1e-12,
+        "Expected only X, got {:?}.",
+        twice.distribution
+    );
+    assert!(noise.over_duration(0.0).is_noiseless());
+}
+
+#[test]
+fn noise_model_applies_idle_noise_during_delay() {
+    let model = NoiseModel {
+        idle: PauliNoise::bit_flip(1.0).expect("bit flip noise should be constructable."),
+        ..NoiseModel::default()
+    };
+    let mut sim = Noisy::new(SparseSim::new(), model);
+    let q0 = sim.qubit_allocate();
+    let q1 = sim.qubit_allocate();
+    // A certain flip for one microsecond flips the qubits once, and twice over two microseconds.
+    sim.delay(1.0, &[q0]);
+    assert!(!sim.qubit_is_zero(q0), "Expected delay to flip the qubit.");
+    assert!(
+        sim.qubit_is_zero(q1),
+        "Expected qubit outside delay to be unchanged."
+    );
+    sim.delay(2.0, &[q0, q1]);
+    assert!(
+        !sim.qubit_is_zero(q0),
+        "Expected delay to flip the qubit twice."
+    );
+    assert!(
+        sim.qubit_is_zero(q1),
+        "Expected delay to flip the qubit twice."
+    );
+    sim.x(q0);
+    assert!(sim.qubit_release(q0));
+    assert!(sim.qubit_release(q1));
+}
+
+#[test]
+fn noise_model_flips_measurement_outcomes() {
+    let model = NoiseModel {
+        measurement: 1.0,
+        ..NoiseModel::default()
+    };
+    let mut sim = Noisy::new(SparseSim::new(), model);
+    let q = sim.qubit_allocate();
+    for _ in 0..10 {
+        assert_eq!(
+            sim.m(q),
+            val::Result::Val(true),
+            "Expected measurement error to flip the outcome."
+        );
+        assert!(
+            sim.qubit_is_zero(q),
+            "Expected measurement error to leave the state unchanged."
+        );
+        assert_eq!(
+            sim.mresetz(q),
+            val::Result::Val(true),
+            "Expected measurement error to flip the outcome."
+        );
+    }
+    assert!(sim.qubit_release(q));
+}
+
+#[test]
+fn noise_model_is_seeded() {
+    let run = |seed| {
+        let model = NoiseModel {
+            gate: PauliNoise::depolarizing(0.5)
+                .expect("depolarizing noise should be constructable."),
+            measurement: 0.1,
+            ..NoiseModel::default()
+        };
+        let mut sim = Noisy::new(SparseSim::new(), model);
+        sim.set_seed(Some(seed));
+        let q = sim.qubit_allocate();
+        (0..64)
+            .map(|_| {
+                sim.x(q);
+                sim.m(q)
+            })
+            .collect::<Vec<_>>()
+    };
+    let outcomes = run(3);
+    assert!(
+        outcomes.contains(&val::Result::Val(true)) && outcomes.contains(&val::Result::Val(false))
+    );
+    assert_eq!(outcomes, run(3));
+}
+
+#[test]
+fn noise_model_loses_measured_qubits() {
+    let model = NoiseModel {
+        loss: 1.0,
+        ..NoiseModel::default()
+    };
+    let mut sim = Noisy::new(SparseSim::new(), model);
+    let q = sim.qubit_allocate();
+    for _ in 0..10 {
+        sim.x(q);
+        assert_eq!(
+            sim.m(q),
+            val::Result::Loss,
+            "Expected the qubit to be lost."
+        );
+        assert!(
+            sim.qubit_is_zero(q),
+            "Expected the lost qubit to be replaced in the |0⟩ state."
+        );
+        sim.x(q);
+        assert_eq!(
+            sim.mresetz(q),
+            val::Result::Loss,
+            "Expected the qubit to be lost."
+        );
+    }
+    assert!(sim.qubit_release(q));
+}
+
+#[test]
+fn noise_model_loses_some_measured_qubits() {
+    let model = NoiseModel {
+        loss: 0.5,
+        ..NoiseModel::default()
+    };
+    let mut sim = Noisy::new(SparseSim::new(), model);
+    sim.set_seed(Some(7));
+    let q = sim.qubit_allocate();
+    let outcomes = (0..64)
+        .map(|_| {
+            sim.x(q);
+            sim.mresetz(q)
+        })
+        .collect::<Vec<_>>();
+    // Qubits that aren't lost are measured as usual.
+    assert!(outcomes.contains(&val::Result::Loss));
+    assert!(outcomes.contains(&val::Result::Val(true)));
+    assert!(!outcomes.contains(&val::Result::Val(false)));
+}
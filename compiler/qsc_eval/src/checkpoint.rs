@@ -0,0 +1,728 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! Checkpoints of a paused evaluation.
+//! A checkpoint captures everything needed to continue the evaluation from where it was paused:
+//! the variables in scope, the position in the execution graphs of the call stack, the values
+//! computed so far and the quantum state of the allocated qubits. It is encoded in a compact
+//! binary format, so that a long-running simulation can be saved, for instance when a hosted
+//! notebook shuts down, and resumed later in a new process.
+//!
+//! A checkpoint refers to callables, statements and expressions by their ids, so it can only be
+//! resumed with the same compiled program that it was taken from.
+
+#[cfg(all(test, feature = "sparse-sim"))]
+mod tests;
+
+use crate::{
+    backend::Backend,
+    debug::{CallStack, Frame},
+    val::{Closure, Qubit, QubitRef, Range, Result as ResultVal, Value, Var, VarTy},
+    CallableCountKey, Env, QubitCounter, Scope, State, Variable,
+};
+use num_bigint::{BigInt, BigUint};
+use num_complex::Complex;
+use qsc_data_structures::{functors::FunctorApp, index_map::IndexMap, span::Span};
+use qsc_fir::fir::{ExecGraph, ExecGraphNode, LocalItemId, PackageId, Pauli, StoreItemId};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::rc::Rc;
+use thiserror::Error;
+
+/// The bytes every checkpoint starts with.
+const MAGIC: &[u8; 4] = b"QCKP";
+
+/// The version of the checkpoint format, which changes whenever the encoding of a checkpoint
+/// changes. Checkpoints encoded with a different version are rejected rather than misread.
+pub const FORMAT_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum ResumeError {
+    #[error("data is not an evaluation checkpoint")]
+    NotACheckpoint,
+    #[error("checkpoint is encoded with format version {0}, expected version {FORMAT_VERSION}")]
+    UnsupportedVersion(u32),
+    #[error("checkpoint ends unexpectedly at byte {0}")]
+    UnexpectedEnd(usize),
+    #[error("invalid {what} tag {tag} at byte {offset}")]
+    InvalidTag {
+        what: &'static str,
+        tag: u8,
+        offset: usize,
+    },
+    #[error("invalid UTF-8 string at byte {0}")]
+    InvalidString(usize),
+    #[error("{0} extra bytes after the checkpoint")]
+    TrailingBytes(usize),
+    #[error("a checkpoint can't be resumed while {0} qubits are allocated")]
+    QubitsInUse(usize),
+    #[error("the simulator didn't allocate qubit {0}")]
+    QubitUnavailable(usize),
+    #[error("the quantum state can't be restored: {0}")]
+    QuantumState(String),
+}
+
+impl State {
+    /// Encodes a checkpoint of the evaluation, which [`State::resume`] continues from the same
+    /// point. The checkpoint includes the variables of the environment and the quantum state of
+    /// the simulator, but not the tape, quota or intrinsic policy, which are set again when
+    /// resuming.
+    ///
+    /// The random number generator for random draws is reseeded with a seed that is stored in
+    /// the checkpoint, so that this evaluation and a resumed one make the same draws. Measurements
+    /// after resuming are sampled by the simulator of the resumed evaluation, so they only agree
+    /// if both simulators are given the same seed.
+    #[must_use]
+    pub fn checkpoint(&self, env: &Env, sim: &mut impl Backend) -> Vec<u8> {
+        let mut writer = Writer(Vec::new());
+        writer.0.extend_from_slice(MAGIC);
+        writer.u32(FORMAT_VERSION);
+
+        let mut qubits = env.qubits.iter().map(|q| q.0).collect::<Vec<_>>();
+        qubits.sort_unstable();
+        writer.len(qubits.len());
+        for &q in &qubits {
+            writer.len(q);
+        }
+        let (state, _) = sim.capture_quantum_state();
+        writer.len(state.len());
+        for (index, amplitude) in &state {
+            writer.bytes(&index.to_bytes_le());
+            writer.f64(amplitude.re);
+            writer.f64(amplitude.im);
+        }
+
+        writer.len(env.scopes.len());
+        for scope in &env.scopes {
+            writer.len(scope.frame_id);
+            writer.len(scope.bindings.iter().count());
+            for (id, var) in scope.bindings.iter() {
+                writer.u32(id.into());
+                writer.str(&var.name);
+                writer.value(&var.value);
+                writer.span(var.span);
+            }
+        }
+
+        writer.len(self.source_package.into());
+        writer.len(self.package.into());
+        writer.len(self.exec_graph_stack.len());
+        for graph in &self.exec_graph_stack {
+            writer.len(graph.len());
+            for node in graph.iter() {
+                writer.node(node);
+            }
+        }
+        writer.u32(self.idx);
+        writer.len(self.idx_stack.len());
+        for &idx in &self.idx_stack {
+            writer.u32(idx);
+        }
+        writer.option(self.val_register.as_ref(), Writer::value);
+        writer.len(self.val_stack.len());
+        for vals in &self.val_stack {
+            writer.values(vals);
+        }
+        let frames = self.call_stack.clone().into_frames();
+        writer.len(frames.len());
+        for frame in &frames {
+            writer.span(frame.span);
+            writer.item(frame.id);
+            writer.len(frame.caller.into());
+            writer.functor(frame.functor);
+        }
+        writer.span(self.current_span);
+
+        let rng_seed = self.rng.borrow_mut().gen::<u64>();
+        *self.rng.borrow_mut() = StdRng::seed_from_u64(rng_seed);
+        writer.u64(rng_seed);
+
+        let mut call_counts = self.call_counts.iter().collect::<Vec<_>>();
+        call_counts.sort_unstable_by_key(|((id, adjoint, controlled), _)| {
+            (
+                usize::from(id.package),
+                usize::from(id.item),
+                *adjoint,
+                *controlled,
+            )
+        });
+        writer.len(call_counts.len());
+        for ((id, adjoint, controlled), count) in call_counts {
+            writer.item(*id);
+            writer.bool(*adjoint);
+            writer.bool(*controlled);
+            writer.i64(*count);
+        }
+        writer.option(self.qubit_counter.as_ref(), |writer, counter| {
+            let mut seen = counter.seen.iter().copied().collect::<Vec<_>>();
+            seen.sort_unstable();
+            writer.len(seen.len());
+            for q in seen {
+                writer.len(q);
+            }
+            writer.i64(counter.count);
+        });
+        writer.0
+    }
+
+    /// Resumes the evaluation from a checkpoint encoded with [`State::checkpoint`], restoring
+    /// the variables into the environment and the quantum state into the simulator. The
+    /// environment and simulator must not have any qubits allocated. The tape of the environment
+    /// is kept as it is.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bytes are not a checkpoint encoded with the current format version,
+    /// if qubits are already allocated, or if the simulator can't restore the quantum state.
+    #[allow(clippy::too_many_lines)]
+    pub fn resume(
+        bytes: &[u8],
+        env: &mut Env,
+        sim: &mut impl Backend,
+    ) -> Result<Self, ResumeError> {
+        let mut reader = Reader {
+            bytes,
+            offset: 0,
+            qubits: FxHashMap::default(),
+        };
+        if reader.take(MAGIC.len()).ok() != Some(MAGIC.as_slice()) {
+            return Err(ResumeError::NotACheckpoint);
+        }
+        let version = reader.u32()?;
+        if version != FORMAT_VERSION {
+            return Err(ResumeError::UnsupportedVersion(version));
+        }
+
+        let mut qubits = Vec::new();
+        for _ in 0..reader.len()? {
+            let q = reader.len()?;
+            reader.qubits.insert(q, Rc::new(Qubit(q)));
+            qubits.push(q);
+        }
+        let mut quantum_state = Vec::new();
+        for _ in 0..reader.len()? {
+            let index = BigUint::from_bytes_le(reader.bytes()?);
+            let amplitude = Complex::new(reader.f64()?, reader.f64()?);
+            quantum_state.push((index, amplitude));
+        }
+
+        let mut scopes = Vec::new();
+        for _ in 0..reader.len()? {
+            let frame_id = reader.len()?;
+            let mut bindings = IndexMap::new();
+            for _ in 0..reader.len()? {
+                let id = reader.u32()?.into();
+                let var = Variable {
+                    name: reader.str()?,
+                    value: reader.value()?,
+                    span: reader.span()?,
+                };
+                bindings.insert(id, var);
+            }
+            scopes.push(Scope { bindings, frame_id });
+        }
+
+        let source_package = PackageId::from(reader.len()?);
+        let package = PackageId::from(reader.len()?);
+        let mut exec_graph_stack = Vec::new();
+        for _ in 0..reader.len()? {
+            let mut nodes = Vec::new();
+            for _ in 0..reader.len()? {
+                nodes.push(reader.node()?);
+            }
+            exec_graph_stack.push(ExecGraph::from(nodes));
+        }
+        let idx = reader.u32()?;
+        let mut idx_stack = Vec::new();
+        for _ in 0..reader.len()? {
+            idx_stack.push(reader.u32()?);
+        }
+        let val_register = reader.option(Reader::value)?;
+        let mut val_stack = Vec::new();
+        for _ in 0..reader.len()? {
+            val_stack.push(reader.values()?);
+        }
+        let mut call_stack = CallStack::default();
+        for _ in 0..reader.len()? {
+            call_stack.push_frame(Frame {
+                span: reader.span()?,
+                id: reader.item()?,
+                caller: PackageId::from(reader.len()?),
+                functor: reader.functor()?,
+            });
+        }
+        let current_span = reader.span()?;
+        let rng_seed = reader.u64()?;
+        let mut call_counts = FxHashMap::default();
+        for _ in 0..reader.len()? {
+            let key: CallableCountKey = (reader.item()?, reader.bool()?, reader.bool()?);
+            call_counts.insert(key, reader.i64()?);
+        }
+        let qubit_counter = reader.option(|reader| {
+            let mut seen = FxHashSet::default();
+            for _ in 0..reader.len()? {
+                seen.insert(reader.len()?);
+            }
+            Ok(QubitCounter {
+                seen,
+                count: reader.i64()?,
+            })
+        })?;
+
+        let extra = bytes.len() - reader.offset;
+        if extra > 0 {
+            return Err(ResumeError::TrailingBytes(extra));
+        }
+
+        if !env.qubits.is_empty() {
+            return Err(ResumeError::QubitsInUse(env.qubits.len()));
+        }
+        allocate_qubits(sim, &qubits)?;
+        sim.set_quantum_state(&quantum_state, &qubits)
+            .map_err(ResumeError::QuantumState)?;
+        env.scopes = scopes;
+        env.qubits = reader.qubits.into_values().collect();
+
+        let mut state = State::new(package, ExecGraph::from(Vec::new()), Some(rng_seed));
+        state.exec_graph_stack = exec_graph_stack;
+        state.idx = idx;
+        state.idx_stack = idx_stack;
+        state.val_register = val_register;
+        state.val_stack = val_stack;
+        state.source_package = source_package;
+        state.call_stack = call_stack;
+        state.current_span = current_span;
+        state.call_counts = call_counts;
+        state.qubit_counter = qubit_counter;
+        Ok(state)
+    }
+}
+
+/// Allocates the qubits with the given sorted ids in the simulator. Simulators hand out the lowest
+/// free id, so allocating up to the largest id and releasing the ones that aren't needed leaves
+/// exactly the given qubits allocated.
+fn allocate_qubits(sim: &mut impl Backend, qubits: &[usize]) -> Result<(), ResumeError> {
+    let Some(&largest) = qubits.last() else {
+        return Ok(());
+    };
+    let mut missing = qubits.iter().copied().collect::<FxHashSet<_>>();
+    let mut extra = Vec::new();
+    for _ in 0..=largest {
+        let q = sim.qubit_allocate();
+        if !missing.remove(&q) {
+            extra.push(q);
+        }
+        if missing.is_empty() {
+            break;
+        }
+    }
+    for q in extra {
+        sim.qubit_release(q);
+    }
+    match missing.into_iter().min() {
+        Some(q) => Err(ResumeError::QubitUnavailable(q)),
+        None => Ok(()),
+    }
+}
+
+struct Writer(Vec<u8>);
+
+impl Writer {
+    fn u8(&mut self, value: u8) {
+        self.0.push(value);
+    }
+
+    fn bool(&mut self, value: bool) {
+        self.u8(value.into());
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.0.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn u64(&mut self, value: u64) {
+        self.0.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn i64(&mut self, value: i64) {
+        self.0.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn f64(&mut self, value: f64) {
+        self.u64(value.to_bits());
+    }
+
+    fn len(&mut self, len: usize) {
+        self.u32(u32::try_from(len).expect("length should fit in u32"));
+    }
+
+    fn bytes(&mut self, value: &[u8]) {
+        self.len(value.len());
+        self.0.extend_from_slice(value);
+    }
+
+    fn str(&mut self, value: &str) {
+        self.bytes(value.as_bytes());
+    }
+
+    fn option<T>(&mut self, value: Option<T>, some: impl FnOnce(&mut Self, T)) {
+        self.bool(value.is_some());
+        if let Some(value) = value {
+            some(self, value);
+        }
+    }
+
+    fn span(&mut self, span: Span) {
+        self.u32(span.lo);
+        self.u32(span.hi);
+    }
+
+    fn item(&mut self, id: StoreItemId) {
+        self.len(id.package.into());
+        self.len(id.item.into());
+    }
+
+    fn functor(&mut self, functor: FunctorApp) {
+        self.bool(functor.adjoint);
+        self.u8(functor.controlled);
+    }
+
+    fn node(&mut self, node: ExecGraphNode) {
+        match node {
+            ExecGraphNode::Bind(pat) => {
+                self.u8(0);
+                self.u32(pat.into());
+            }
+            ExecGraphNode::Expr(expr) => {
+                self.u8(1);
+                self.u32(expr.into());
+            }
+            ExecGraphNode::Jump(idx) => {
+                self.u8(2);
+                self.u32(idx);
+            }
+            ExecGraphNode::JumpIf(idx) => {
+                self.u8(3);
+                self.u32(idx);
+            }
+            ExecGraphNode::JumpIfNot(idx) => {
+                self.u8(4);
+                self.u32(idx);
+            }
+            ExecGraphNode::Store => self.u8(5),
+            ExecGraphNode::Unit => self.u8(6),
+            ExecGraphNode::Ret => self.u8(7),
+            ExecGraphNode::RetFrame => self.u8(8),
+            ExecGraphNode::Stmt(stmt) => {
+                self.u8(9);
+                self.u32(stmt.into());
+            }
+            ExecGraphNode::PushScope => self.u8(10),
+            ExecGraphNode::PopScope => self.u8(11),
+            ExecGraphNode::TailCall(expr) => {
+                self.u8(12);
+                self.u32(expr.into());
+            }
+        }
+    }
+
+    fn values(&mut self, values: &[Value]) {
+        self.len(values.len());
+        for value in values {
+            self.value(value);
+        }
+    }
+
+    fn value(&mut self, value: &Value) {
+        match value {
+            Value::Array(items) => {
+                self.u8(0);
+                self.values(items);
+            }
+            Value::BigInt(value) => {
+                self.u8(1);
+                self.bytes(&value.to_signed_bytes_le());
+            }
+            Value::Bool(value) => {
+                self.u8(2);
+                self.bool(*value);
+            }
+            Value::Closure(closure) => {
+                self.u8(3);
+                self.values(&closure.fixed_args);
+                self.item(closure.id);
+                self.functor(closure.functor);
+            }
+            Value::Double(value) => {
+                self.u8(4);
+                self.f64(*value);
+            }
+            Value::Global(id, functor) => {
+                self.u8(5);
+                self.item(*id);
+                self.functor(*functor);
+            }
+            Value::Int(value) => {
+                self.u8(6);
+                self.i64(*value);
+            }
+            Value::Pauli(pauli) => {
+                self.u8(7);
+                self.u8(match pauli {
+                    Pauli::I => 0,
+                    Pauli::X => 1,
+                    Pauli::Y => 2,
+                    Pauli::Z => 3,
+                });
+            }
+            Value::Qubit(qubit) => {
+                self.u8(8);
+                // References to released qubits stay released when resuming.
+                self.option(qubit.try_deref(), |writer, qubit| writer.len(qubit.0));
+            }
+            Value::Range(range) => {
+                self.u8(9);
+                self.option(range.start, Writer::i64);
+                self.i64(range.step);
+                self.option(range.end, Writer::i64);
+            }
+            Value::Result(result) => {
+                self.u8(10);
+                match result {
+                    ResultVal::Val(value) => {
+                        self.u8(0);
+                        self.bool(*value);
+                    }
+                    ResultVal::Id(id) => {
+                        self.u8(1);
+                        self.len(*id);
+                    }
+                    ResultVal::Loss => self.u8(2),
+                }
+            }
+            Value::String(value) => {
+                self.u8(11);
+                self.str(value);
+            }
+            Value::Tuple(items) => {
+                self.u8(12);
+                self.values(items);
+            }
+            Value::Var(var) => {
+                self.u8(13);
+                self.len(var.id);
+                self.u8(match var.ty {
+                    VarTy::Boolean => 0,
+                    VarTy::Integer => 1,
+                    VarTy::Double => 2,
+                });
+            }
+        }
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+    /// The qubits that were allocated when the checkpoint was taken, by id.
+    qubits: FxHashMap<usize, Rc<Qubit>>,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], ResumeError> {
+        let bytes = self
+            .offset
+            .checked_add(len)
+            .and_then(|end| self.bytes.get(self.offset..end))
+            .ok_or(ResumeError::UnexpectedEnd(self.bytes.len()))?;
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], ResumeError> {
+        Ok(self
+            .take(N)?
+            .try_into()
+            .expect("slice should have length N"))
+    }
+
+    fn u8(&mut self) -> Result<u8, ResumeError> {
+        Ok(self.take(1)?[0])
+    }
+
+    /// Reads a tag, mapping it to a value with the given function, which returns `None` for unknown tags.
+    fn tag<T>(
+        &mut self,
+        what: &'static str,
+        value: impl FnOnce(u8) -> Option<T>,
+    ) -> Result<T, ResumeError> {
+        let offset = self.offset;
+        let tag = self.u8()?;
+        value(tag).ok_or(ResumeError::InvalidTag { what, tag, offset })
+    }
+
+    fn bool(&mut self) -> Result<bool, ResumeError> {
+        self.tag("boolean", |tag| match tag {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        })
+    }
+
+    fn u32(&mut self) -> Result<u32, ResumeError> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    fn u64(&mut self) -> Result<u64, ResumeError> {
+        Ok(u64::from_le_bytes(self.array()?))
+    }
+
+    fn i64(&mut self) -> Result<i64, ResumeError> {
+        Ok(i64::from_le_bytes(self.array()?))
+    }
+
+    fn f64(&mut self) -> Result<f64, ResumeError> {
+        Ok(f64::from_bits(self.u64()?))
+    }
+
+    fn len(&mut self) -> Result<usize, ResumeError> {
+        Ok(self.u32()? as usize)
+    }
+
+    fn bytes(&mut self) -> Result<&'a [u8], ResumeError> {
+        let len = self.len()?;
+        self.take(len)
+    }
+
+    fn str(&mut self) -> Result<Rc<str>, ResumeError> {
+        let offset = self.offset;
+        std::str::from_utf8(self.bytes()?)
+            .map(Into::into)
+            .map_err(|_| ResumeError::InvalidString(offset))
+    }
+
+    fn option<T>(
+        &mut self,
+        some: impl FnOnce(&mut Self) -> Result<T, ResumeError>,
+    ) -> Result<Option<T>, ResumeError> {
+        if self.bool()? {
+            some(self).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn span(&mut self) -> Result<Span, ResumeError> {
+        Ok(Span {
+            lo: self.u32()?,
+            hi: self.u32()?,
+        })
+    }
+
+    fn item(&mut self) -> Result<StoreItemId, ResumeError> {
+        Ok(StoreItemId {
+            package: PackageId::from(self.len()?),
+            item: LocalItemId::from(self.len()?),
+        })
+    }
+
+    fn functor(&mut self) -> Result<FunctorApp, ResumeError> {
+        Ok(FunctorApp {
+            adjoint: self.bool()?,
+            controlled: self.u8()?,
+        })
+    }
+
+    fn node(&mut self) -> Result<ExecGraphNode, ResumeError> {
+        let offset = self.offset;
+        Ok(match self.u8()? {
+            0 => ExecGraphNode::Bind(self.u32()?.into()),
+            1 => ExecGraphNode::Expr(self.u32()?.into()),
+            2 => ExecGraphNode::Jump(self.u32()?),
+            3 => ExecGraphNode::JumpIf(self.u32()?),
+            4 => ExecGraphNode::JumpIfNot(self.u32()?),
+            5 => ExecGraphNode::Store,
+            6 => ExecGraphNode::Unit,
+            7 => ExecGraphNode::Ret,
+            8 => ExecGraphNode::RetFrame,
+            9 => ExecGraphNode::Stmt(self.u32()?.into()),
+            10 => ExecGraphNode::PushScope,
+            11 => ExecGraphNode::PopScope,
+            12 => ExecGraphNode::TailCall(self.u32()?.into()),
+            tag => {
+                return Err(ResumeError::InvalidTag {
+                    what: "execution graph node",
+                    tag,
+                    offset,
+                })
+            }
+        })
+    }
+
+    fn values(&mut self) -> Result<Vec<Value>, ResumeError> {
+        let mut values = Vec::new();
+        for _ in 0..self.len()? {
+            values.push(self.value()?);
+        }
+        Ok(values)
+    }
+
+    fn value(&mut self) -> Result<Value, ResumeError> {
+        let offset = self.offset;
+        Ok(match self.u8()? {
+            0 => Value::Array(self.values()?.into()),
+            1 => Value::BigInt(BigInt::from_signed_bytes_le(self.bytes()?)),
+            2 => Value::Bool(self.bool()?),
+            3 => Value::Closure(Box::new(Closure {
+                fixed_args: self.values()?.into(),
+                id: self.item()?,
+                functor: self.functor()?,
+            })),
+            4 => Value::Double(self.f64()?),
+            5 => Value::Global(self.item()?, self.functor()?),
+            6 => Value::Int(self.i64()?),
+            7 => Value::Pauli(self.tag("Pauli", |tag| match tag {
+                0 => Some(Pauli::I),
+                1 => Some(Pauli::X),
+                2 => Some(Pauli::Y),
+                3 => Some(Pauli::Z),
+                _ => None,
+            })?),
+            8 => Value::Qubit(match self.option(Reader::len)? {
+                Some(q) => self
+                    .qubits
+                    .get(&q)
+                    .map_or_else(QubitRef::released, QubitRef::from),
+                None => QubitRef::released(),
+            }),
+            9 => Value::Range(Box::new(Range {
+                start: self.option(Reader::i64)?,
+                step: self.i64()?,
+                end: self.option(Reader::i64)?,
+            })),
+            10 => Value::Result(match self.tag("result", |tag| (tag <= 2).then_some(tag))? {
+                0 => ResultVal::Val(self.bool()?),
+                1 => ResultVal::Id(self.len()?),
+                _ => ResultVal::Loss,
+            }),
+            11 => Value::String(self.str()?),
+            12 => Value::Tuple(self.values()?.into()),
+            13 => Value::Var(Var {
+                id: self.len()?,
+                ty: self.tag("variable type", |tag| match tag {
+                    0 => Some(VarTy::Boolean),
+                    1 => Some(VarTy::Integer),
+                    2 => Some(VarTy::Double),
+                    _ => None,
+                })?,
+            }),
+            tag => {
+                return Err(ResumeError::InvalidTag {
+                    what: "value",
+                    tag,
+                    offset,
+                })
+            }
+        })
+    }
+}
@@ -0,0 +1,228 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{ResumeError, FORMAT_VERSION};
+use crate::{
+    backend::{Backend, SparseSim},
+    output::GenericReceiver,
+    Env, State, StepAction, StepResult, Value,
+};
+use expect_test::expect;
+use indoc::indoc;
+use qsc_data_structures::{language_features::LanguageFeatures, target::TargetCapabilityFlags};
+use qsc_fir::fir::{self, ExecGraph, PackageId};
+use qsc_frontend::compile::{self, compile, PackageStore, SourceMap};
+use qsc_lowerer::map_hir_package_to_fir;
+use qsc_passes::{run_core_passes, run_default_passes, PackageType};
+
+const PROGRAM: &str = indoc! {"
+    namespace Test {
+        open Microsoft.Quantum.Measurement;
+        open Microsoft.Quantum.Random;
+
+        operation Main() : (Int, Int, Result[]) {
+            use qs = Qubit[2];
+            H(qs[0]);
+            CNOT(qs[0], qs[1]);
+            mutable total = 0;
+            for i in 1..4 {
+                set total += i;
+            }
+            X(qs[1]);
+            let draw = DrawRandomInt(0, 1000000);
+            CNOT(qs[0], qs[1]);
+            H(qs[0]);
+            (total, draw, MResetEachZ(qs))
+        }
+    }
+"};
+
+/// Compiles the program, lowering it with debug information so that evaluation can be paused at
+/// each statement.
+fn compile_program() -> (fir::PackageStore, PackageId, ExecGraph) {
+    let mut core = compile::core();
+    run_core_passes(&mut core);
+    let fir_store = fir::PackageStore::new();
+    let core_fir = qsc_lowerer::Lowerer::new().lower_package(&core.package, &fir_store);
+    let mut store = PackageStore::new(core);
+
+    let mut std = compile::std(&store, TargetCapabilityFlags::all());
+    assert!(std.errors.is_empty());
+    assert!(run_default_passes(&store, &mut std, PackageType::Lib).is_empty());
+    let std_fir = qsc_lowerer::Lowerer::new().lower_package(&std.package, &fir_store);
+    let std_id = store.insert(std);
+
+    let sources = SourceMap::new(
+        [("test".into(), PROGRAM.into())],
+        Some("Test.Main()".into()),
+    );
+    let mut unit = compile(
+        &store,
+        &[(std_id, None)],
+        sources,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::default(),
+    );
+    assert!(unit.errors.is_empty(), "{:?}", unit.errors);
+    assert!(run_default_passes(&store, &mut unit, PackageType::Lib).is_empty());
+    let unit_fir = qsc_lowerer::Lowerer::new()
+        .with_debug(true)
+        .lower_package(&unit.package, &fir_store);
+    let entry = unit_fir.entry_exec_graph.clone();
+    let id = store.insert(unit);
+
+    let mut fir_store = fir::PackageStore::new();
+    fir_store.insert(
+        map_hir_package_to_fir(qsc_hir::hir::PackageId::CORE),
+        core_fir,
+    );
+    fir_store.insert(map_hir_package_to_fir(std_id), std_fir);
+    fir_store.insert(map_hir_package_to_fir(id), unit_fir);
+    (fir_store, map_hir_package_to_fir(id), entry)
+}
+
+/// Steps into the given number of statements, which has to stop before the end of the program.
+fn step(
+    state: &mut State,
+    globals: &fir::PackageStore,
+    env: &mut Env,
+    sim: &mut SparseSim,
+    steps: usize,
+) {
+    let mut out = Vec::new();
+    for _ in 0..steps {
+        let result = state
+            .eval(
+                globals,
+                env,
+                sim,
+                &mut GenericReceiver::new(&mut out),
+                &[],
+                StepAction::In,
+            )
+            .expect("step should succeed");
+        assert!(
+            !matches!(result, StepResult::Return(_)),
+            "program should not finish while stepping"
+        );
+    }
+}
+
+fn finish(
+    state: &mut State,
+    globals: &fir::PackageStore,
+    env: &mut Env,
+    sim: &mut SparseSim,
+) -> Value {
+    let mut out = Vec::new();
+    let result = state
+        .eval(
+            globals,
+            env,
+            sim,
+            &mut GenericReceiver::new(&mut out),
+            &[],
+            StepAction::Continue,
+        )
+        .expect("evaluation should succeed");
+    let StepResult::Return(value) = result else {
+        panic!("evaluation should run to the end");
+    };
+    value
+}
+
+#[test]
+fn resumed_evaluation_finishes_like_original() {
+    let (globals, package, entry) = compile_program();
+    let mut state = State::new(package, entry, None);
+    let mut env = Env::default();
+    let mut sim = SparseSim::new();
+    step(&mut state, &globals, &mut env, &mut sim, 8);
+
+    let checkpoint = state.checkpoint(&env, &mut sim);
+    let mut resumed_env = Env::default();
+    let mut resumed_sim = SparseSim::new();
+    let mut resumed = State::resume(&checkpoint, &mut resumed_env, &mut resumed_sim)
+        .expect("checkpoint should resume");
+
+    let original = finish(&mut state, &globals, &mut env, &mut sim);
+    let resumed = finish(&mut resumed, &globals, &mut resumed_env, &mut resumed_sim);
+    // The random draw after the checkpoint agrees, since both evaluations continue with the seed
+    // stored in the checkpoint.
+    assert_eq!(original.to_string(), resumed.to_string());
+    let [total, _, results] = &*resumed.unwrap_tuple() else {
+        panic!("result should be a tuple of three items");
+    };
+    expect!["10"].assert_eq(&total.to_string());
+    expect!["[Zero, One]"].assert_eq(&results.to_string());
+}
+
+#[test]
+fn resume_restores_quantum_state() {
+    let (globals, package, entry) = compile_program();
+    let mut state = State::new(package, entry, None);
+    let mut env = Env::default();
+    let mut sim = SparseSim::new();
+    step(&mut state, &globals, &mut env, &mut sim, 4);
+
+    let checkpoint = state.checkpoint(&env, &mut sim);
+    let mut resumed_env = Env::default();
+    let mut resumed_sim = SparseSim::new();
+    State::resume(&checkpoint, &mut resumed_env, &mut resumed_sim)
+        .expect("checkpoint should resume");
+
+    let (original, original_count) = sim.capture_quantum_state();
+    let (resumed, resumed_count) = resumed_sim.capture_quantum_state();
+    assert_eq!(original_count, resumed_count);
+    assert_eq!(original.len(), resumed.len());
+    for ((original_index, original_amplitude), (resumed_index, resumed_amplitude)) in
+        original.iter().zip(&resumed)
+    {
+        assert_eq!(original_index, resumed_index);
+        assert!((original_amplitude - resumed_amplitude).norm() < 1e-9);
+    }
+}
+
+#[test]
+fn resume_fails_with_qubits_in_use() {
+    let (globals, package, entry) = compile_program();
+    let mut state = State::new(package, entry, None);
+    let mut env = Env::default();
+    let mut sim = SparseSim::new();
+    step(&mut state, &globals, &mut env, &mut sim, 4);
+    let checkpoint = state.checkpoint(&env, &mut sim);
+
+    let error = State::resume(&checkpoint, &mut env, &mut sim).err();
+    assert_eq!(error, Some(ResumeError::QubitsInUse(2)));
+}
+
+#[test]
+fn resume_rejects_invalid_checkpoints() {
+    let (globals, package, entry) = compile_program();
+    let mut state = State::new(package, entry, None);
+    let mut env = Env::default();
+    let mut sim = SparseSim::new();
+    step(&mut state, &globals, &mut env, &mut sim, 4);
+    let checkpoint = state.checkpoint(&env, &mut sim);
+
+    let resume =
+        |bytes: &[u8]| State::resume(bytes, &mut Env::default(), &mut SparseSim::new()).err();
+    assert_eq!(resume(b"QRIR"), Some(ResumeError::NotACheckpoint));
+
+    let mut other_version = checkpoint.clone();
+    other_version[4..8].copy_from_slice(&(FORMAT_VERSION + 1).to_le_bytes());
+    assert_eq!(
+        resume(&other_version),
+        Some(ResumeError::UnsupportedVersion(FORMAT_VERSION + 1))
+    );
+
+    let truncated = &checkpoint[..checkpoint.len() - 1];
+    assert_eq!(
+        resume(truncated),
+        Some(ResumeError::UnexpectedEnd(truncated.len()))
+    );
+
+    let mut trailing = checkpoint.clone();
+    trailing.push(0);
+    assert_eq!(resume(&trailing), Some(ResumeError::TrailingBytes(1)));
+}
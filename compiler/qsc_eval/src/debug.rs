@@ -30,6 +30,11 @@ impl CallStack {
         self.frames.len()
     }
 
+    #[must_use]
+    pub fn last(&self) -> Option<&Frame> {
+        self.frames.last()
+    }
+
     #[must_use]
     pub fn into_frames(self) -> Vec<Frame> {
         self.frames
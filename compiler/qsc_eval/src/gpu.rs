@@ -0,0 +1,868 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! Dense state-vector simulation on the GPU.
+//! The [`GpuSim`] backend keeps the amplitude of every basis state in a GPU buffer and applies
+//! gates with compute shaders, which makes it faster than sparse simulation for highly entangled
+//! states of 20 or more qubits. Amplitudes are double precision on devices whose shaders support
+//! 64-bit floats, so results agree with the sparse simulator up to rounding. Other devices use
+//! single precision, where results agree to about six digits and amplitudes smaller than about
+//! 1e-4 are left out of captured states, since they can't be told apart from rounding. The GPU
+//! device is held by a [`GpuContext`], which is shared by the simulators of subsequent runs since
+//! setting up the device is slow.
+
+#[cfg(all(test, feature = "sparse-sim"))]
+mod tests;
+
+use crate::{
+    backend::Backend,
+    val::{unwrap_tuple, Value},
+};
+use num_bigint::BigUint;
+use num_complex::{Complex, Complex64};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{borrow::Cow, f64::consts::FRAC_1_SQRT_2, rc::Rc};
+use wgpu::util::DeviceExt;
+
+/// The number of invocations in each workgroup, which has to match the shaders.
+const WORKGROUP_SIZE: u32 = 64;
+
+/// The maximum number of workgroups in one dimension of a dispatch.
+const MAX_WORKGROUPS_PER_DIMENSION: u32 = 65535;
+
+/// A 2x2 matrix of a single-qubit gate, by rows.
+type Matrix = [[Complex64; 2]; 2];
+
+const ONE: Complex64 = Complex64::new(1.0, 0.0);
+const ZERO: Complex64 = Complex64::new(0.0, 0.0);
+
+/// The precision of the floats that the shaders use.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Precision {
+    Single,
+    Double,
+}
+
+impl Precision {
+    /// The size of a float in bytes.
+    fn float_size(self) -> u64 {
+        match self {
+            Self::Single => 4,
+            Self::Double => 8,
+        }
+    }
+
+    /// The size of `Params` in the shaders, which is rounded up to the alignment of its rows.
+    fn params_size(self) -> u64 {
+        16 * self.float_size()
+    }
+
+    /// Probabilities below this are treated as zero, to allow for rounding.
+    fn probability_threshold(self) -> f64 {
+        match self {
+            Self::Single => 1e-6,
+            Self::Double => 1e-10,
+        }
+    }
+
+    /// Squared magnitudes below this are left out of captured states. In single precision, rounding
+    /// leaves amplitudes of up to about 1e-5 on basis states that should have none.
+    fn amplitude_threshold(self) -> f64 {
+        match self {
+            Self::Single => 1e-8,
+            Self::Double => 1e-20,
+        }
+    }
+
+    fn shader(self) -> Cow<'static, str> {
+        let source = include_str!("gpu/shader.wgsl");
+        match self {
+            Self::Single => source.into(),
+            Self::Double => source
+                .replacen("alias Float = f32;", "alias Float = f64;", 1)
+                .into(),
+        }
+    }
+
+    /// Encodes the floats as the shaders read them.
+    fn encode(self, floats: &[f64]) -> Vec<u8> {
+        match self {
+            Self::Single => {
+                #[allow(clippy::cast_possible_truncation)]
+                let floats = floats.iter().map(|&x| x as f32).collect::<Vec<_>>();
+                bytemuck::cast_slice(&floats).to_vec()
+            }
+            Self::Double => bytemuck::cast_slice(floats).to_vec(),
+        }
+    }
+
+    /// Decodes the floats that the shaders wrote.
+    fn decode(self, bytes: &[u8]) -> Vec<f64> {
+        match self {
+            Self::Single => bytes
+                .chunks_exact(4)
+                .map(|float| f64::from(bytemuck::pod_read_unaligned::<f32>(float)))
+                .collect(),
+            Self::Double => bytes
+                .chunks_exact(8)
+                .map(bytemuck::pod_read_unaligned)
+                .collect(),
+        }
+    }
+}
+
+/// The parameters of a shader.
+#[derive(Clone, Copy, Default)]
+struct Params {
+    matrix: Matrix,
+    qubit_bit: u32,
+    controls: u32,
+    work: u32,
+    second: u32,
+    scale: f64,
+}
+
+impl Params {
+    /// Encodes the parameters with the layout of `Params` in the shaders: the rows of the matrix,
+    /// then the integers, then the scale, padded to the size of the struct.
+    fn encode(&self, precision: Precision) -> Vec<u8> {
+        let [[m00, m01], [m10, m11]] = self.matrix;
+        let mut bytes = precision.encode(&[
+            m00.re, m00.im, m01.re, m01.im, m10.re, m10.im, m11.re, m11.im,
+        ]);
+        for int in [self.qubit_bit, self.controls, self.work, self.second] {
+            bytes.extend_from_slice(&int.to_ne_bytes());
+        }
+        bytes.extend(precision.encode(&[self.scale]));
+        #[allow(clippy::cast_possible_truncation)]
+        bytes.resize(precision.params_size() as usize, 0);
+        bytes
+    }
+}
+
+/// A GPU device with the shaders of the simulator.
+pub struct GpuContext {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    layout: wgpu::BindGroupLayout,
+    apply_gate: wgpu::ComputePipeline,
+    swap_bits: wgpu::ComputePipeline,
+    collapse: wgpu::ComputePipeline,
+    probability_one: wgpu::ComputePipeline,
+    precision: Precision,
+    /// The largest number of qubits whose state fits in a buffer of the device.
+    max_qubits: usize,
+}
+
+impl GpuContext {
+    /// Sets up the most capable GPU device of the machine, with double precision if its shaders
+    /// support 64-bit floats.
+    /// # Errors
+    /// Returns an error if the machine has no GPU device that supports compute shaders.
+    pub fn new() -> Result<Self, String> {
+        pollster::block_on(Self::request())
+    }
+
+    async fn request() -> Result<Self, String> {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::HighPerformance,
+                compatible_surface: None,
+                force_fallback_adapter: false,
+            })
+            .await
+            .ok_or("no GPU device is available")?;
+        let limits = adapter.limits();
+        let (precision, required_features) =
+            if adapter.features().contains(wgpu::Features::SHADER_F64) {
+                (Precision::Double, wgpu::Features::SHADER_F64)
+            } else {
+                (Precision::Single, wgpu::Features::empty())
+            };
+        let (device, queue) = adapter
+            .request_device(
+                &wgpu::DeviceDescriptor {
+                    label: Some("qsharp state vector"),
+                    required_features,
+                    required_limits: limits.clone(),
+                    memory_hints: wgpu::MemoryHints::Performance,
+                },
+                None,
+            )
+            .await
+            .map_err(|error| format!("the GPU device couldn't be set up: {error}"))?;
+
+        let storage = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only: false },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("qsharp state vector"),
+            entries: &[
+                storage(0),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                storage(2),
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("qsharp state vector"),
+            bind_group_layouts: &[&layout],
+            push_constant_ranges: &[],
+        });
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("qsharp state vector"),
+            source: wgpu::ShaderSource::Wgsl(precision.shader()),
+        });
+        let pipeline = |entry_point| {
+            device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some(entry_point),
+                layout: Some(&pipeline_layout),
+                module: &module,
+                entry_point: Some(entry_point),
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+                cache: None,
+            })
+        };
+
+        // The shaders index basis states with 32-bit integers, which limits the state to 31 qubits.
+        let max_bytes =
+            u64::from(limits.max_storage_buffer_binding_size).min(limits.max_buffer_size);
+        let max_amplitudes = max_bytes / (2 * precision.float_size());
+        let max_qubits = (max_amplitudes.ilog2() as usize).min(31);
+
+        Ok(Self {
+            apply_gate: pipeline("apply_gate"),
+            swap_bits: pipeline("swap_bits"),
+            collapse: pipeline("collapse"),
+            probability_one: pipeline("probability_one"),
+            device,
+            queue,
+            layout,
+            precision,
+            max_qubits,
+        })
+    }
+
+    /// The largest number of qubits the simulator can allocate on this device.
+    #[must_use]
+    pub fn max_qubits(&self) -> usize {
+        self.max_qubits
+    }
+}
+
+/// Backend that simulates the dense state vector on a GPU device. Qubits are allocated by doubling
+/// the state vector and released by halving it, so the state always holds exactly the qubits in use.
+pub struct GpuSim {
+    context: Rc<GpuContext>,
+    amplitudes: wgpu::Buffer,
+    params: wgpu::Buffer,
+    sums: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    /// The bit of the basis state index that holds each qubit, by qubit id, or `None` for the ids
+    /// of released qubits.
+    bits: Vec<Option<u32>>,
+    /// The id of the qubit that each bit of the basis state index holds.
+    ids: Vec<usize>,
+    rng: StdRng,
+    failure: Option<String>,
+}
+
+impl GpuSim {
+    #[must_use]
+    pub fn new(context: Rc<GpuContext>) -> Self {
+        let amplitudes = context
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("amplitudes"),
+                contents: &context.precision.encode(&[ONE.re, ONE.im]),
+                usage: amplitudes_usage(),
+            });
+        let params = context.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("params"),
+            size: context.precision.params_size(),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let sums = sums_buffer(&context, 1);
+        let bind_group = bind_group(&context, &amplitudes, &params, &sums);
+        Self {
+            context,
+            amplitudes,
+            params,
+            sums,
+            bind_group,
+            bits: Vec::new(),
+            ids: Vec::new(),
+            rng: StdRng::from_entropy(),
+            failure: None,
+        }
+    }
+
+    /// The number of amplitudes in the state vector.
+    fn len(&self) -> u32 {
+        1 << self.ids.len()
+    }
+
+    /// The bit that holds the qubit, or `None` if it couldn't be allocated, in which case the
+    /// operation on it fails.
+    fn bit(&mut self, q: usize) -> Option<u32> {
+        let bit = self.bits.get(q).copied().flatten();
+        if bit.is_none() && self.failure.is_none() {
+            self.failure = Some(format!("qubit {q} isn't held by the GPU simulator"));
+        }
+        bit
+    }
+
+    /// Replaces the state vector with one of the given number of qubits, copying the amplitudes of
+    /// the basis states that both have in common. The amplitudes of new basis states are zero.
+    fn resize(&mut self, qubits: usize) {
+        let old_len = u64::from(self.len());
+        let new_len = 1_u64 << qubits;
+        let amplitude_size = 2 * self.context.precision.float_size();
+        let amplitudes = self.context.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("amplitudes"),
+            size: new_len * amplitude_size,
+            usage: amplitudes_usage(),
+            mapped_at_creation: false,
+        });
+        let mut encoder = self
+            .context
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        encoder.copy_buffer_to_buffer(
+            &self.amplitudes,
+            0,
+            &amplitudes,
+            0,
+            old_len.min(new_len) * amplitude_size,
+        );
+        self.context.queue.submit(Some(encoder.finish()));
+        self.amplitudes = amplitudes;
+        self.sums = sums_buffer(&self.context, new_len);
+        self.bind_group = bind_group(&self.context, &self.amplitudes, &self.params, &self.sums);
+    }
+
+    /// Runs the shader with one work item for each of `work` items and waits for it to finish.
+    fn dispatch(&self, pipeline: &wgpu::ComputePipeline, params: Params) {
+        self.context
+            .queue
+            .write_buffer(&self.params, 0, &params.encode(self.context.precision));
+        let (x, y) = workgroups(params.work);
+        let mut encoder = self
+            .context
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
+            pass.set_pipeline(pipeline);
+            pass.set_bind_group(0, &self.bind_group, &[]);
+            pass.dispatch_workgroups(x, y, 1);
+        }
+        self.context.queue.submit(Some(encoder.finish()));
+    }
+
+    /// Copies the floats of the buffer back from the GPU device.
+    fn read(&self, buffer: &wgpu::Buffer, floats: u64) -> Vec<f64> {
+        let size = floats * self.context.precision.float_size();
+        let staging = self.context.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("readback"),
+            size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let mut encoder = self
+            .context
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        encoder.copy_buffer_to_buffer(buffer, 0, &staging, 0, size);
+        self.context.queue.submit(Some(encoder.finish()));
+        let slice = staging.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |_| {});
+        let _ = self.context.device.poll(wgpu::Maintain::Wait);
+        let values = self.context.precision.decode(&slice.get_mapped_range());
+        staging.unmap();
+        values
+    }
+
+    fn apply(&mut self, matrix: Matrix, q: usize, ctls: &[usize]) {
+        let Some(bit) = self.bit(q) else {
+            return;
+        };
+        let mut controls = 0;
+        for &ctl in ctls {
+            let Some(ctl_bit) = self.bit(ctl) else {
+                return;
+            };
+            controls |= 1 << ctl_bit;
+        }
+        self.dispatch(
+            &self.context.apply_gate,
+            Params {
+                matrix,
+                qubit_bit: bit,
+                controls,
+                work: self.len() / 2,
+                ..Params::default()
+            },
+        );
+    }
+
+    /// The probability that measuring the qubit held by the bit gives |1⟩.
+    fn probability_one(&self, bit: u32) -> f64 {
+        let work = self.len();
+        self.dispatch(
+            &self.context.probability_one,
+            Params {
+                qubit_bit: bit,
+                work,
+                ..Params::default()
+            },
+        );
+        let (x, y) = workgroups(work);
+        self.read(&self.sums, u64::from(x * y))
+            .iter()
+            .sum::<f64>()
+            .clamp(0.0, 1.0)
+    }
+
+    /// Measures the qubit held by the bit, collapsing the state onto the outcome.
+    fn measure(&mut self, bit: u32) -> bool {
+        let p1 = self.probability_one(bit);
+        let outcome = self.rng.gen_range(0.0..1.0) < p1;
+        let p = if outcome { p1 } else { 1.0 - p1 };
+        self.dispatch(
+            &self.context.collapse,
+            Params {
+                qubit_bit: bit,
+                work: self.len(),
+                second: u32::from(outcome),
+                scale: 1.0 / p.sqrt(),
+                ..Params::default()
+            },
+        );
+        outcome
+    }
+
+    fn swap_bits(&mut self, bit0: u32, bit1: u32) {
+        self.dispatch(
+            &self.context.swap_bits,
+            Params {
+                qubit_bit: bit0,
+                second: bit1,
+                work: self.len(),
+                ..Params::default()
+            },
+        );
+    }
+}
+
+fn amplitudes_usage() -> wgpu::BufferUsages {
+    wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC | wgpu::BufferUsages::COPY_DST
+}
+
+/// The buffer that holds the sum of each workgroup of a reduction over the given number of items.
+fn sums_buffer(context: &GpuContext, items: u64) -> wgpu::Buffer {
+    #[allow(clippy::cast_possible_truncation)]
+    let (x, y) = workgroups(items as u32);
+    context.device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("sums"),
+        size: u64::from(x * y) * context.precision.float_size(),
+        usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+        mapped_at_creation: false,
+    })
+}
+
+fn bind_group(
+    context: &GpuContext,
+    amplitudes: &wgpu::Buffer,
+    params: &wgpu::Buffer,
+    sums: &wgpu::Buffer,
+) -> wgpu::BindGroup {
+    context
+        .device
+        .create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("qsharp state vector"),
+            layout: &context.layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: amplitudes.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: params.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: sums.as_entire_binding(),
+                },
+            ],
+        })
+}
+
+/// The grid of workgroups that covers the given number of work items.
+fn workgroups(work: u32) -> (u32, u32) {
+    let groups = work.div_ceil(WORKGROUP_SIZE).max(1);
+    let x = groups.min(MAX_WORKGROUPS_PER_DIMENSION);
+    (x, groups.div_ceil(x))
+}
+
+fn phase(theta: f64) -> Complex64 {
+    Complex64::new(theta.cos(), theta.sin())
+}
+
+fn diagonal(d0: Complex64, d1: Complex64) -> Matrix {
+    [[d0, ZERO], [ZERO, d1]]
+}
+
+const H: Matrix = [
+    [
+        Complex64::new(FRAC_1_SQRT_2, 0.0),
+        Complex64::new(FRAC_1_SQRT_2, 0.0),
+    ],
+    [
+        Complex64::new(FRAC_1_SQRT_2, 0.0),
+        Complex64::new(-FRAC_1_SQRT_2, 0.0),
+    ],
+];
+const X: Matrix = [[ZERO, ONE], [ONE, ZERO]];
+const Y: Matrix = [
+    [ZERO, Complex64::new(0.0, -1.0)],
+    [Complex64::new(0.0, 1.0), ZERO],
+];
+const Z: Matrix = [[ONE, ZERO], [ZERO, Complex64::new(-1.0, 0.0)]];
+
+fn rx(theta: f64) -> Matrix {
+    let (c, s) = ((theta / 2.0).cos(), (theta / 2.0).sin());
+    [
+        [Complex64::new(c, 0.0), Complex64::new(0.0, -s)],
+        [Complex64::new(0.0, -s), Complex64::new(c, 0.0)],
+    ]
+}
+
+fn ry(theta: f64) -> Matrix {
+    let (c, s) = ((theta / 2.0).cos(), (theta / 2.0).sin());
+    [
+        [Complex64::new(c, 0.0), Complex64::new(-s, 0.0)],
+        [Complex64::new(s, 0.0), Complex64::new(c, 0.0)],
+    ]
+}
+
+fn rz(theta: f64) -> Matrix {
+    diagonal(phase(-theta / 2.0), phase(theta / 2.0))
+}
+
+impl Backend for GpuSim {
+    type ResultType = bool;
+
+    fn ccx(&mut self, ctl0: usize, ctl1: usize, q: usize) {
+        self.apply(X, q, &[ctl0, ctl1]);
+    }
+
+    fn cx(&mut self, ctl: usize, q: usize) {
+        self.apply(X, q, &[ctl]);
+    }
+
+    fn cy(&mut self, ctl: usize, q: usize) {
+        self.apply(Y, q, &[ctl]);
+    }
+
+    fn cz(&mut self, ctl: usize, q: usize) {
+        self.apply(Z, q, &[ctl]);
+    }
+
+    fn h(&mut self, q: usize) {
+        self.apply(H, q, &[]);
+    }
+
+    fn m(&mut self, q: usize) -> Self::ResultType {
+        self.bit(q).is_some_and(|bit| self.measure(bit))
+    }
+
+    fn mresetz(&mut self, q: usize) -> Self::ResultType {
+        let outcome = self.m(q);
+        if outcome {
+            self.x(q);
+        }
+        outcome
+    }
+
+    fn reset(&mut self, q: usize) {
+        self.mresetz(q);
+    }
+
+    fn rx(&mut self, theta: f64, q: usize) {
+        self.apply(rx(theta), q, &[]);
+    }
+
+    fn rxx(&mut self, theta: f64, q0: usize, q1: usize) {
+        self.h(q0);
+        self.h(q1);
+        self.rzz(theta, q0, q1);
+        self.h(q1);
+        self.h(q0);
+    }
+
+    fn ry(&mut self, theta: f64, q: usize) {
+        self.apply(ry(theta), q, &[]);
+    }
+
+    fn ryy(&mut self, theta: f64, q0: usize, q1: usize) {
+        self.h(q0);
+        self.s(q0);
+        self.h(q0);
+        self.h(q1);
+        self.s(q1);
+        self.h(q1);
+        self.rzz(theta, q0, q1);
+        self.h(q1);
+        self.sadj(q1);
+        self.h(q1);
+        self.h(q0);
+        self.sadj(q0);
+        self.h(q0);
+    }
+
+    fn rz(&mut self, theta: f64, q: usize) {
+        self.apply(rz(theta), q, &[]);
+    }
+
+    fn rzz(&mut self, theta: f64, q0: usize, q1: usize) {
+        self.cx(q1, q0);
+        self.rz(theta, q0);
+        self.cx(q1, q0);
+    }
+
+    fn sadj(&mut self, q: usize) {
+        self.apply(diagonal(ONE, Complex64::new(0.0, -1.0)), q, &[]);
+    }
+
+    fn s(&mut self, q: usize) {
+        self.apply(diagonal(ONE, Complex64::new(0.0, 1.0)), q, &[]);
+    }
+
+    fn swap(&mut self, q0: usize, q1: usize) {
+        self.qubit_swap_id(q0, q1);
+    }
+
+    fn tadj(&mut self, q: usize) {
+        self.apply(diagonal(ONE, phase(-std::f64::consts::FRAC_PI_4)), q, &[]);
+    }
+
+    fn t(&mut self, q: usize) {
+        self.apply(diagonal(ONE, phase(std::f64::consts::FRAC_PI_4)), q, &[]);
+    }
+
+    fn x(&mut self, q: usize) {
+        self.apply(X, q, &[]);
+    }
+
+    fn y(&mut self, q: usize) {
+        self.apply(Y, q, &[]);
+    }
+
+    fn z(&mut self, q: usize) {
+        self.apply(Z, q, &[]);
+    }
+
+    fn qubit_allocate(&mut self) -> usize {
+        let q = self
+            .bits
+            .iter()
+            .position(Option::is_none)
+            .unwrap_or_else(|| {
+                self.bits.push(None);
+                self.bits.len() - 1
+            });
+        if self.ids.len() == self.context.max_qubits {
+            self.failure = Some(format!(
+                "the GPU simulator can't allocate more than {} qubits on this device",
+                self.context.max_qubits
+            ));
+            return q;
+        }
+        // The new qubit is held by a new most significant bit, whose basis states are all zero.
+        #[allow(clippy::cast_possible_truncation)]
+        let bit = self.ids.len() as u32;
+        self.resize(self.ids.len() + 1);
+        self.bits[q] = Some(bit);
+        self.ids.push(q);
+        q
+    }
+
+    fn qubit_release(&mut self, q: usize) -> bool {
+        let Some(bit) = self.bits.get(q).copied().flatten() else {
+            return true;
+        };
+        // Released qubits are returned to |0⟩ so that the state can drop their bit.
+        let was_zero = self.probability_one(bit) < self.context.precision.probability_threshold();
+        if !was_zero && self.measure(bit) {
+            self.x(q);
+        }
+        // The bit is moved to the most significant one, which halving the state drops.
+        #[allow(clippy::cast_possible_truncation)]
+        let top = (self.ids.len() - 1) as u32;
+        if bit != top {
+            self.swap_bits(bit, top);
+            let moved = self.ids[top as usize];
+            self.ids[bit as usize] = moved;
+            self.bits[moved] = Some(bit);
+        }
+        self.ids.pop();
+        self.bits[q] = None;
+        self.resize(self.ids.len());
+        was_zero
+    }
+
+    fn qubit_swap_id(&mut self, q0: usize, q1: usize) {
+        // Swapping the qubits only exchanges which bits hold them.
+        let (Some(bit0), Some(bit1)) = (self.bit(q0), self.bit(q1)) else {
+            return;
+        };
+        self.bits[q0] = Some(bit1);
+        self.bits[q1] = Some(bit0);
+        self.ids[bit0 as usize] = q1;
+        self.ids[bit1 as usize] = q0;
+    }
+
+    fn capture_quantum_state(&mut self) -> (Vec<(BigUint, Complex<f64>)>, usize) {
+        let values = self.read(&self.amplitudes, 2 * u64::from(self.len()));
+        let in_use = self.bits.iter().filter_map(|bit| *bit).collect::<Vec<_>>();
+        let count = in_use.len();
+        let state = values
+            .chunks_exact(2)
+            .enumerate()
+            .filter_map(|(i, amplitude)| {
+                let amplitude = Complex::new(amplitude[0], amplitude[1]);
+                if amplitude.norm_sqr() <= self.context.precision.amplitude_threshold() {
+                    return None;
+                }
+                // The qubit with the lowest id is the most significant bit of the index.
+                let mut index = BigUint::default();
+                for (k, bit) in in_use.iter().enumerate() {
+                    if (i >> bit) & 1 == 1 {
+                        index.set_bit((count - 1 - k) as u64, true);
+                    }
+                }
+                Some((index, amplitude))
+            });
+        let mut state = state.collect::<Vec<_>>();
+        state.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        (state, count)
+    }
+
+    fn set_quantum_state(
+        &mut self,
+        state: &[(BigUint, Complex<f64>)],
+        qubits: &[usize],
+    ) -> Result<(), String> {
+        if qubits.len() != self.ids.len() {
+            return Err("the state has to cover every qubit held by the GPU simulator".to_string());
+        }
+        let bits = qubits
+            .iter()
+            .map(|&q| {
+                self.bits
+                    .get(q)
+                    .copied()
+                    .flatten()
+                    .ok_or_else(|| format!("qubit {q} isn't held by the GPU simulator"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let count = bits.len();
+        let mut amplitudes = vec![0.0; 2 * self.len() as usize];
+        for (index, amplitude) in state {
+            // The qubit with the lowest id is the most significant bit of the index.
+            let mut i = 0_usize;
+            for (k, bit) in bits.iter().enumerate() {
+                if index.bit((count - 1 - k) as u64) {
+                    i |= 1 << bit;
+                }
+            }
+            amplitudes[2 * i] = amplitude.re;
+            amplitudes[2 * i + 1] = amplitude.im;
+        }
+        self.context.queue.write_buffer(
+            &self.amplitudes,
+            0,
+            &self.context.precision.encode(&amplitudes),
+        );
+        Ok(())
+    }
+
+    fn qubit_is_zero(&mut self, q: usize) -> bool {
+        self.bit(q).is_none_or(|bit| {
+            self.probability_one(bit) < self.context.precision.probability_threshold()
+        })
+    }
+
+    fn custom_intrinsic(&mut self, name: &str, arg: Value) -> Option<Result<Value, String>> {
+        match name {
+            "GlobalPhase" => {
+                let [ctls, theta] = unwrap_tuple(arg);
+                let ctls = ctls
+                    .unwrap_array()
+                    .iter()
+                    .map(|q| q.clone().unwrap_qubit().deref().0)
+                    .collect::<Vec<_>>();
+                let phase = phase(theta.unwrap_double());
+                match ctls.split_first() {
+                    // The phase applies when all of the controls are |1⟩.
+                    Some((first, rest)) => self.apply(diagonal(ONE, phase), *first, rest),
+                    None => {
+                        // A phase on the state of no qubits can't be observed.
+                        if let Some(&q) = self.ids.first() {
+                            self.apply(diagonal(phase, phase), q, &[]);
+                        }
+                    }
+                }
+                Some(Ok(Value::unit()))
+            }
+            "BeginEstimateCaching" => Some(Ok(Value::Bool(true))),
+            "EndEstimateCaching"
+            | "AccountForEstimatesInternal"
+            | "BeginRepeatEstimatesInternal"
+            | "EndRepeatEstimatesInternal"
+            | "ApplyIdleNoise" => Some(Ok(Value::unit())),
+            "ConfigurePauliNoise" => {
+                let [px, py, pz] = unwrap_tuple(arg);
+                if px.get_double() + py.get_double() + pz.get_double() > 0.0 {
+                    Some(Err(
+                        "the GPU simulator applies the noise of the noise model instead of configured Pauli noise"
+                            .to_string(),
+                    ))
+                } else {
+                    Some(Ok(Value::unit()))
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn set_seed(&mut self, seed: Option<u64>) {
+        self.rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+    }
+
+    fn take_failure(&mut self) -> Option<String> {
+        self.failure.take()
+    }
+}
@@ -0,0 +1,136 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+// Compute shaders of the GPU state-vector simulator. The state holds the complex amplitude of every
+// basis state as a pair of floats, and each invocation handles one work item. Work items are spread
+// over a two-dimensional grid of workgroups, since one dimension can't address larger states.
+
+// The type of the floats, which the simulator replaces with `f64` on devices that support it.
+alias Float = f32;
+
+struct Params {
+    // The rows of the 2x2 matrix of a single-qubit gate, with each entry as (re, im).
+    row0: vec4<Float>,
+    row1: vec4<Float>,
+    // The bit of the basis state index that holds the target qubit.
+    qubit_bit: u32,
+    // The bits of the control qubits, which must all be set for a gate to apply.
+    controls: u32,
+    // The number of work items.
+    work: u32,
+    // The bit of the second qubit of a swap, or the outcome of a collapse.
+    second: u32,
+    // The factor that the amplitudes of the outcome of a collapse are scaled by.
+    scale: Float,
+    pad0: Float,
+    pad1: Float,
+    pad2: Float,
+}
+
+@group(0) @binding(0) var<storage, read_write> amplitudes: array<vec2<Float>>;
+@group(0) @binding(1) var<uniform> params: Params;
+@group(0) @binding(2) var<storage, read_write> sums: array<Float>;
+
+const WORKGROUP_SIZE: u32 = 64u;
+
+var<workgroup> partial_sums: array<Float, WORKGROUP_SIZE>;
+
+fn work_item(id: vec3<u32>, groups: vec3<u32>) -> u32 {
+    return id.x + id.y * groups.x * WORKGROUP_SIZE;
+}
+
+fn cmul(a: vec2<Float>, b: vec2<Float>) -> vec2<Float> {
+    return vec2<Float>(a.x * b.x - a.y * b.y, a.x * b.y + a.y * b.x);
+}
+
+// Inserts a zero at the given bit, so that the work items cover the basis states where it's zero.
+fn insert_zero(i: u32, bit: u32) -> u32 {
+    let low = i & ((1u << bit) - 1u);
+    return ((i >> bit) << (bit + 1u)) | low;
+}
+
+// Applies the matrix to each pair of basis states that differ only in the target qubit.
+@compute @workgroup_size(WORKGROUP_SIZE)
+fn apply_gate(
+    @builtin(global_invocation_id) id: vec3<u32>,
+    @builtin(num_workgroups) groups: vec3<u32>,
+) {
+    let i = work_item(id, groups);
+    if i >= params.work {
+        return;
+    }
+    let i0 = insert_zero(i, params.qubit_bit);
+    if (i0 & params.controls) != params.controls {
+        return;
+    }
+    let i1 = i0 | (1u << params.qubit_bit);
+    let a0 = amplitudes[i0];
+    let a1 = amplitudes[i1];
+    amplitudes[i0] = cmul(params.row0.xy, a0) + cmul(params.row0.zw, a1);
+    amplitudes[i1] = cmul(params.row1.xy, a0) + cmul(params.row1.zw, a1);
+}
+
+// Exchanges the values of two bits of the basis state indices.
+@compute @workgroup_size(WORKGROUP_SIZE)
+fn swap_bits(
+    @builtin(global_invocation_id) id: vec3<u32>,
+    @builtin(num_workgroups) groups: vec3<u32>,
+) {
+    let i = work_item(id, groups);
+    if i >= params.work {
+        return;
+    }
+    let a = 1u << params.qubit_bit;
+    let b = 1u << params.second;
+    if (i & a) != 0u && (i & b) == 0u {
+        let j = i ^ (a | b);
+        let amplitude = amplitudes[i];
+        amplitudes[i] = amplitudes[j];
+        amplitudes[j] = amplitude;
+    }
+}
+
+// Projects the state onto the outcome of measuring the target qubit and renormalizes it.
+@compute @workgroup_size(WORKGROUP_SIZE)
+fn collapse(
+    @builtin(global_invocation_id) id: vec3<u32>,
+    @builtin(num_workgroups) groups: vec3<u32>,
+) {
+    let i = work_item(id, groups);
+    if i >= params.work {
+        return;
+    }
+    if ((i >> params.qubit_bit) & 1u) == params.second {
+        amplitudes[i] = amplitudes[i] * params.scale;
+    } else {
+        amplitudes[i] = vec2<Float>(0.0, 0.0);
+    }
+}
+
+// Sums the probabilities of the basis states where the target qubit is |1⟩, with one sum for each
+// workgroup.
+@compute @workgroup_size(WORKGROUP_SIZE)
+fn probability_one(
+    @builtin(global_invocation_id) id: vec3<u32>,
+    @builtin(local_invocation_index) local: u32,
+    @builtin(workgroup_id) group: vec3<u32>,
+    @builtin(num_workgroups) groups: vec3<u32>,
+) {
+    let i = work_item(id, groups);
+    var probability = Float(0.0);
+    if i < params.work && ((i >> params.qubit_bit) & 1u) == 1u {
+        let amplitude = amplitudes[i];
+        probability = dot(amplitude, amplitude);
+    }
+    partial_sums[local] = probability;
+    workgroupBarrier();
+    for (var stride = WORKGROUP_SIZE / 2u; stride > 0u; stride = stride / 2u) {
+        if local < stride {
+            partial_sums[local] += partial_sums[local + stride];
+        }
+        workgroupBarrier();
+    }
+    if local == 0u {
+        sums[group.x + group.y * groups.x] = partial_sums[0];
+    }
+}
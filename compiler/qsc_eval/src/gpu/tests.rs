@@ -0,0 +1,257 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{
+    diagonal, phase, rx, ry, rz, workgroups, GpuContext, GpuSim, Matrix, Params, Precision, H,
+    MAX_WORKGROUPS_PER_DIMENSION, ONE, WORKGROUP_SIZE, X, Y, Z,
+};
+use crate::{
+    backend::{Backend, SparseSim},
+    val::Value,
+};
+use expect_test::{expect, Expect};
+use num_complex::Complex64;
+use std::{
+    f64::consts::{FRAC_PI_4, PI},
+    rc::Rc,
+};
+
+/// Creates a simulator on the GPU device of the machine. Not every machine that builds with the `gpu` feature has
+/// one, so the tests that use it are ignored by default and run with `cargo test --features gpu -- --ignored`.
+fn gpu_sim() -> GpuSim {
+    let context = GpuContext::new().unwrap_or_else(|e| panic!("GPU tests need a GPU device: {e}"));
+    GpuSim::new(Rc::new(context))
+}
+
+fn check_state(sim: &mut impl Backend, expect: &Expect) {
+    let (state, qubit_count) = sim.capture_quantum_state();
+    let actual = state
+        .iter()
+        .map(|(index, amplitude)| {
+            format!(
+                "|{:0>qubit_count$}⟩: {:.4} {:+.4}i",
+                index.to_str_radix(2),
+                amplitude.re,
+                amplitude.im
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    expect.assert_eq(&actual);
+}
+
+fn assert_unitary(matrix: Matrix) {
+    let [[m00, m01], [m10, m11]] = matrix;
+    assert!((m00.norm_sqr() + m01.norm_sqr() - 1.0).abs() < 1e-12);
+    assert!((m10.norm_sqr() + m11.norm_sqr() - 1.0).abs() < 1e-12);
+    assert!((m00 * m10.conj() + m01 * m11.conj()).norm() < 1e-12);
+}
+
+/// Applies a fixed sequence of gates that entangles the qubits.
+fn entangle(sim: &mut impl Backend, qs: &[usize]) {
+    sim.h(qs[0]);
+    sim.cx(qs[0], qs[1]);
+    sim.t(qs[1]);
+    sim.ry(PI / 3.0, qs[2]);
+    sim.cz(qs[1], qs[2]);
+    sim.cy(qs[2], qs[3]);
+    sim.swap(qs[0], qs[3]);
+    sim.rxx(PI / 5.0, qs[0], qs[2]);
+    sim.ccx(qs[0], qs[1], qs[3]);
+    sim.ryy(PI / 7.0, qs[1], qs[3]);
+}
+
+#[test]
+fn gate_matrices_are_unitary() {
+    for matrix in [
+        H,
+        X,
+        Y,
+        Z,
+        rx(PI / 3.0),
+        ry(PI / 5.0),
+        rz(PI / 7.0),
+        diagonal(ONE, phase(FRAC_PI_4)),
+    ] {
+        assert_unitary(matrix);
+    }
+}
+
+#[test]
+fn rotations_by_pi_are_paulis_up_to_global_phase() {
+    let minus_i = Complex64::new(0.0, -1.0);
+    for (rotation, pauli) in [(rx(PI), X), (ry(PI), Y), (rz(PI), Z)] {
+        for (row, pauli_row) in rotation.iter().zip(pauli) {
+            for (entry, pauli_entry) in row.iter().zip(pauli_row) {
+                assert!((entry - minus_i * pauli_entry).norm() < 1e-12);
+            }
+        }
+    }
+}
+
+#[test]
+fn workgroups_cover_every_work_item_without_idle_rows() {
+    for work in [0, 1, 63, 64, 65, 64 * 65535, 64 * 65535 + 1, 1 << 31] {
+        let (x, y) = workgroups(work);
+        assert!(x <= MAX_WORKGROUPS_PER_DIMENSION && y <= MAX_WORKGROUPS_PER_DIMENSION);
+        let row = u64::from(x) * u64::from(WORKGROUP_SIZE);
+        assert!(
+            row * u64::from(y) >= u64::from(work),
+            "{work} isn't covered"
+        );
+        assert!(
+            row * u64::from(y - 1) < u64::from(work.max(1)),
+            "{work} has an idle row"
+        );
+    }
+}
+
+#[test]
+fn params_are_encoded_with_the_layout_of_the_shaders() {
+    let params = Params {
+        matrix: X,
+        qubit_bit: 1,
+        controls: 2,
+        work: 3,
+        second: 4,
+        scale: 0.5,
+    };
+    // The offsets of the integers and the scale in `Params` of each version of the shaders.
+    for (precision, ints, scale) in [(Precision::Single, 32, 48), (Precision::Double, 64, 80)] {
+        let bytes = params.encode(precision);
+        assert_eq!(bytes.len() as u64, precision.params_size());
+        assert_eq!(
+            precision.decode(&bytes[..ints]),
+            [0.0, 0.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0]
+        );
+        assert_eq!(
+            bytes[ints..ints + 16]
+                .chunks_exact(4)
+                .map(bytemuck::pod_read_unaligned::<u32>)
+                .collect::<Vec<_>>(),
+            [1, 2, 3, 4]
+        );
+        assert_eq!(precision.decode(&bytes[scale..])[..1], [0.5]);
+    }
+}
+
+#[test]
+fn double_precision_keeps_amplitudes_that_single_precision_rounds() {
+    let amplitude = 1.0 + 1e-9;
+    let single = Precision::Single.decode(&Precision::Single.encode(&[amplitude]));
+    let double = Precision::Double.decode(&Precision::Double.encode(&[amplitude]));
+    assert_eq!(single, [1.0]);
+    assert_eq!(double, [amplitude]);
+}
+
+#[test]
+fn double_precision_shader_declares_f64() {
+    let shader = Precision::Double.shader();
+    assert!(shader.contains("alias Float = f64;"));
+    assert!(!shader.contains("f32"));
+}
+
+#[test]
+#[ignore = "needs a GPU device"]
+fn bell_pair_state() {
+    let mut sim = gpu_sim();
+    let q0 = sim.qubit_allocate();
+    let q1 = sim.qubit_allocate();
+    sim.h(q0);
+    sim.cx(q0, q1);
+    check_state(
+        &mut sim,
+        &expect![[r#"
+            |00⟩: 0.7071 +0.0000i
+            |11⟩: 0.7071 +0.0000i"#]],
+    );
+}
+
+#[test]
+#[ignore = "needs a GPU device"]
+fn bell_pair_measurements_agree() {
+    let mut sim = gpu_sim();
+    for seed in 0..10 {
+        sim.set_seed(Some(seed));
+        let q0 = sim.qubit_allocate();
+        let q1 = sim.qubit_allocate();
+        sim.h(q0);
+        sim.cx(q0, q1);
+        assert_eq!(sim.m(q0), sim.m(q1));
+        sim.qubit_release(q0);
+        sim.qubit_release(q1);
+    }
+}
+
+#[test]
+#[ignore = "needs a GPU device"]
+fn release_keeps_state_of_remaining_qubits() {
+    let mut sim = gpu_sim();
+    let q0 = sim.qubit_allocate();
+    let q1 = sim.qubit_allocate();
+    let q2 = sim.qubit_allocate();
+    sim.x(q0);
+    sim.h(q2);
+    assert!(sim.qubit_release(q1));
+    check_state(
+        &mut sim,
+        &expect![[r#"
+            |10⟩: 0.7071 +0.0000i
+            |11⟩: 0.7071 +0.0000i"#]],
+    );
+    // The released id is reused by the next allocation.
+    assert_eq!(sim.qubit_allocate(), q1);
+    assert!(sim.qubit_is_zero(q1));
+}
+
+#[test]
+#[ignore = "needs a GPU device"]
+fn release_reports_qubit_not_in_zero() {
+    let mut sim = gpu_sim();
+    let q0 = sim.qubit_allocate();
+    let q1 = sim.qubit_allocate();
+    sim.x(q0);
+    assert!(!sim.qubit_release(q0));
+    assert!(sim.qubit_is_zero(q1));
+    check_state(&mut sim, &expect!["|0⟩: 1.0000 +0.0000i"]);
+}
+
+#[test]
+#[ignore = "needs a GPU device"]
+fn global_phase_applies_to_state() {
+    let mut sim = gpu_sim();
+    let q = sim.qubit_allocate();
+    sim.h(q);
+    let arg = Value::Tuple(vec![Value::Array(Vec::new().into()), Value::Double(PI / 2.0)].into());
+    assert!(matches!(
+        sim.custom_intrinsic("GlobalPhase", arg),
+        Some(Ok(_))
+    ));
+    check_state(
+        &mut sim,
+        &expect![[r#"
+            |0⟩: 0.0000 +0.7071i
+            |1⟩: 0.0000 +0.7071i"#]],
+    );
+}
+
+#[test]
+#[ignore = "needs a GPU device"]
+fn state_matches_sparse_simulator() {
+    let mut gpu = gpu_sim();
+    let mut sparse = SparseSim::new();
+    let gpu_qs = (0..4).map(|_| gpu.qubit_allocate()).collect::<Vec<_>>();
+    let sparse_qs = (0..4).map(|_| sparse.qubit_allocate()).collect::<Vec<_>>();
+    entangle(&mut gpu, &gpu_qs);
+    entangle(&mut sparse, &sparse_qs);
+    let (gpu_state, gpu_count) = gpu.capture_quantum_state();
+    let (sparse_state, sparse_count) = sparse.capture_quantum_state();
+    assert_eq!(gpu_count, sparse_count);
+    assert_eq!(gpu_state.len(), sparse_state.len());
+    for ((gpu_index, gpu_amplitude), (sparse_index, sparse_amplitude)) in
+        gpu_state.iter().zip(&sparse_state)
+    {
+        assert_eq!(gpu_index, sparse_index);
+        assert!((gpu_amplitude - sparse_amplitude).norm() < 1e-5);
+    }
+}
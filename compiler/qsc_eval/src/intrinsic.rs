@@ -3,23 +3,177 @@
 
 mod utils;
 
-#[cfg(test)]
+#[cfg(all(test, feature = "sparse-sim"))]
 mod tests;
 
 use crate::{
     backend::Backend,
     error::PackageSpan,
-    output::Receiver,
+    output::{Receiver, StateSnapshot},
+    tape::{Outcome, Tape},
     val::{self, unwrap_tuple, Value},
     Error, Rc,
 };
 use num_bigint::BigInt;
+use qsc_fir::ty::{Prim, Ty};
 use rand::{rngs::StdRng, Rng};
 use rustc_hash::{FxHashMap, FxHashSet};
 use std::convert::TryFrom;
 
-#[allow(clippy::too_many_lines)]
 pub(crate) fn call(
+    name: &str,
+    name_span: PackageSpan,
+    arg: Value,
+    arg_span: PackageSpan,
+    sim: &mut dyn Backend<ResultType = impl Into<val::Result>>,
+    rng: &mut StdRng,
+    tape: Option<&mut Tape>,
+    out: &mut dyn Receiver,
+) -> Result<Value, Error> {
+    let result = match tape {
+        Some(tape) if is_taped(name) => {
+            call_taped(name, name_span, arg, arg_span, sim, rng, tape, out)
+        }
+        _ => call_intrinsic(name, name_span, arg, arg_span, sim, rng, out),
+    };
+    match sim.take_failure() {
+        Some(message) => Err(Error::IntrinsicFail(name.to_string(), message, name_span)),
+        None => result,
+    }
+}
+
+/// Calls the host function that implements a function with the `@HostCallable()` attribute, and checks that the
+/// value it returns has the output type of the function.
+pub(crate) fn call_host(
+    name: &str,
+    name_span: PackageSpan,
+    arg: Value,
+    output: &Ty,
+    out: &mut dyn Receiver,
+) -> Result<Value, Error> {
+    match out.host_call(name, arg) {
+        None => Err(Error::HostFunctionNotRegistered(
+            name.to_string(),
+            name_span,
+        )),
+        Some(Err(message)) => Err(Error::IntrinsicFail(name.to_string(), message, name_span)),
+        Some(Ok(value)) => host_value_with_ty(value, output).ok_or_else(|| {
+            Error::InvalidHostValue(name.to_string(), output.to_string(), name_span)
+        }),
+    }
+}
+
+/// Converts a value returned by a host function to the given type, if it has that type. Numbers are converted
+/// between `Int` and `Double`, and arrays to tuples, since some hosts, like JavaScript, don't distinguish between
+/// them.
+fn host_value_with_ty(value: Value, ty: &Ty) -> Option<Value> {
+    match (value, ty) {
+        (value @ Value::Int(_), Ty::Prim(Prim::Int))
+        | (value @ Value::Double(_), Ty::Prim(Prim::Double))
+        | (value @ Value::Bool(_), Ty::Prim(Prim::Bool))
+        | (value @ Value::String(_), Ty::Prim(Prim::String)) => Some(value),
+        #[allow(clippy::cast_precision_loss)]
+        (Value::Int(value), Ty::Prim(Prim::Double)) => Some(Value::Double(value as f64)),
+        // Only integers that a double represents exactly are converted.
+        #[allow(clippy::cast_possible_truncation, clippy::float_cmp)]
+        (Value::Double(value), Ty::Prim(Prim::Int))
+            if value.fract() == 0.0 && value.abs() <= 2f64.powi(53) =>
+        {
+            Some(Value::Int(value as i64))
+        }
+        (Value::Array(items), Ty::Array(item_ty)) => items
+            .iter()
+            .map(|item| host_value_with_ty(item.clone(), item_ty))
+            .collect::<Option<Vec<_>>>()
+            .map(|items| Value::Array(items.into())),
+        (Value::Tuple(items), Ty::Tuple(item_tys)) => host_tuple_with_tys(&items, item_tys),
+        (Value::Array(items), Ty::Tuple(item_tys)) => host_tuple_with_tys(&items, item_tys),
+        _ => None,
+    }
+}
+
+fn host_tuple_with_tys(items: &[Value], item_tys: &[Ty]) -> Option<Value> {
+    if items.len() != item_tys.len() {
+        return None;
+    }
+    items
+        .iter()
+        .zip(item_tys)
+        .map(|(item, item_ty)| host_value_with_ty(item.clone(), item_ty))
+        .collect::<Option<Vec<_>>>()
+        .map(|items| Value::Tuple(items.into()))
+}
+
+/// Whether the outcome of the intrinsic is captured on a tape.
+fn is_taped(name: &str) -> bool {
+    matches!(
+        name,
+        "DrawRandomInt"
+            | "DrawRandomDouble"
+            | "DrawRandomBool"
+            | "__quantum__qis__m__body"
+            | "__quantum__qis__mresetz__body"
+    )
+}
+
+/// Calls an intrinsic whose outcome is captured on the tape, recording the outcome or replaying the
+/// next outcome on the tape in its place.
+#[allow(clippy::too_many_arguments)]
+fn call_taped(
+    name: &str,
+    name_span: PackageSpan,
+    arg: Value,
+    arg_span: PackageSpan,
+    sim: &mut dyn Backend<ResultType = impl Into<val::Result>>,
+    rng: &mut StdRng,
+    tape: &mut Tape,
+    out: &mut dyn Receiver,
+) -> Result<Value, Error> {
+    if !tape.is_replaying() {
+        let value = call_intrinsic(name, name_span, arg, arg_span, sim, rng, out)?;
+        tape.push(match &value {
+            Value::Result(result) => Outcome::Result(*result),
+            Value::Int(value) => Outcome::Int(*value),
+            Value::Double(value) => Outcome::Double(*value),
+            Value::Bool(value) => Outcome::Bool(*value),
+            _ => panic!("taped intrinsic should return a result or a number"),
+        });
+        return Ok(value);
+    }
+
+    match (name, tape.next()) {
+        ("DrawRandomInt", Some(Outcome::Int(value))) => Ok(Value::Int(value)),
+        ("DrawRandomDouble", Some(Outcome::Double(value))) => Ok(Value::Double(value)),
+        ("DrawRandomBool", Some(Outcome::Bool(value))) => Ok(Value::Bool(value)),
+        (
+            "__quantum__qis__m__body" | "__quantum__qis__mresetz__body",
+            Some(Outcome::Result(result)),
+        ) => {
+            // The simulator still measures the qubit so that it collapses, and `MResetZ` resets it.
+            let q = arg
+                .clone()
+                .unwrap_qubit()
+                .try_deref()
+                .ok_or(Error::QubitUsedAfterRelease(arg_span))?
+                .0;
+            let Value::Result(measured) =
+                call_intrinsic(name, name_span, arg, arg_span, sim, rng, out)?
+            else {
+                panic!("measurement should return a result");
+            };
+            // After `M`, the qubit is in the state of the outcome, or in |0⟩ if it was lost.
+            let is_one = |result: val::Result| matches!(result, val::Result::Val(true));
+            if name == "__quantum__qis__m__body" && is_one(measured) != is_one(result) {
+                sim.x(q);
+            }
+            Ok(Value::Result(result))
+        }
+        _ => Err(Error::TapeMismatch(name.to_string(), name_span)),
+    }
+}
+
+#[allow(clippy::too_many_lines)]
+fn call_intrinsic(
     name: &str,
     name_span: PackageSpan,
     arg: Value,
@@ -36,6 +190,30 @@ pub(crate) fn call(
         #[allow(clippy::cast_precision_loss)]
         "IntAsDouble" => Ok(Value::Double(arg.unwrap_int() as f64)),
         "IntAsBigInt" => Ok(Value::BigInt(BigInt::from(arg.unwrap_int()))),
+        "TryIntAsDouble" => {
+            let number = arg.unwrap_int();
+            #[allow(clippy::cast_precision_loss)]
+            let value = number as f64;
+            // `i64::MAX` rounds up to 2^63, which saturates back to `i64::MAX` when cast, so it is excluded by the bound.
+            #[allow(clippy::cast_possible_truncation)]
+            let exact = value < TWO_POW_63 && value as i64 == number;
+            Ok(checked_conversion(
+                exact.then_some(Value::Double(value)),
+                Value::Double(0.0),
+            ))
+        }
+        "TryDoubleAsInt" => {
+            let input = arg.unwrap_double();
+            // NaN and infinite values have a NaN fractional part, so they are never whole.
+            let is_whole = input.fract() == 0.0 && (-TWO_POW_63..TWO_POW_63).contains(&input);
+            #[allow(clippy::cast_possible_truncation)]
+            let converted = is_whole.then(|| Value::Int(input as i64));
+            Ok(checked_conversion(converted, Value::Int(0)))
+        }
+        "TryBigIntAsInt" => Ok(checked_conversion(
+            i64::try_from(&arg.unwrap_big_int()).ok().map(Value::Int),
+            Value::Int(0),
+        )),
         "DoubleAsStringWithPrecision" => {
             let [input, prec_val] = unwrap_tuple(arg);
             let prec_int = prec_val.unwrap_int();
@@ -53,8 +231,12 @@ pub(crate) fn call(
             }
         }
         "DumpMachine" => {
-            let (state, qubit_count) = sim.capture_quantum_state();
-            match out.state(state, qubit_count) {
+            let (amplitudes, qubit_count) = sim.capture_quantum_state();
+            let snapshot = StateSnapshot {
+                qubits: (0..qubit_count).collect(),
+                amplitudes,
+            };
+            match out.state_snapshot(snapshot) {
                 Ok(()) => Ok(Value::unit()),
                 Err(_) => Err(Error::OutputFail(name_span)),
             }
@@ -73,9 +255,9 @@ pub(crate) fn call(
                 return Err(Error::QubitUniqueness(arg_span));
             }
             let (state, qubit_count) = sim.capture_quantum_state();
-            let state = utils::split_state(&qubits, &state, qubit_count)
+            let amplitudes = utils::split_state(&qubits, &state, qubit_count)
                 .map_err(|()| Error::QubitsNotSeparable(arg_span))?;
-            match out.state(state, qubits.len()) {
+            match out.state_snapshot(StateSnapshot { qubits, amplitudes }) {
                 Ok(()) => Ok(Value::unit()),
                 Err(_) => Err(Error::OutputFail(name_span)),
             }
@@ -107,6 +289,66 @@ pub(crate) fn call(
             Ok(()) => Ok(Value::unit()),
             Err(_) => Err(Error::OutputFail(name_span)),
         },
+        "ReducedDensityMatrix" => {
+            let qubits = arg.unwrap_array();
+            let qubits_len = qubits.len();
+            let qubits = qubits
+                .iter()
+                .filter_map(|q| q.clone().unwrap_qubit().try_deref().map(|q| q.0))
+                .collect::<Vec<_>>();
+            if qubits.len() != qubits_len {
+                return Err(Error::QubitUsedAfterRelease(arg_span));
+            }
+            if qubits.len() != qubits.iter().collect::<FxHashSet<_>>().len() {
+                return Err(Error::QubitUniqueness(arg_span));
+            }
+            let (state, qubit_count) = sim.capture_quantum_state();
+            let matrix = utils::reduced_density_matrix(&qubits, &state, qubit_count);
+            // Each entry is a `Complex` struct, which is a tuple of its real and imaginary parts.
+            let rows = matrix
+                .into_iter()
+                .map(|row| {
+                    Value::Array(
+                        row.into_iter()
+                            .map(|entry| {
+                                Value::Tuple(
+                                    vec![Value::Double(entry.re), Value::Double(entry.im)].into(),
+                                )
+                            })
+                            .collect::<Vec<_>>()
+                            .into(),
+                    )
+                })
+                .collect::<Vec<_>>();
+            Ok(Value::Array(rows.into()))
+        }
+        "ExpectationValue" => {
+            let [paulis, qubits] = unwrap_tuple(arg);
+            let (paulis, qubits) = (paulis.unwrap_array(), qubits.unwrap_array());
+            if paulis.len() != qubits.len() {
+                return Err(Error::IntrinsicFail(
+                    name.to_string(),
+                    "arrays 'paulis' and 'qubits' must be of the same length".to_string(),
+                    name_span,
+                ));
+            }
+            let qubits = qubits
+                .iter()
+                .filter_map(|q| q.clone().unwrap_qubit().try_deref().map(|q| q.0))
+                .collect::<Vec<_>>();
+            if qubits.len() != paulis.len() {
+                return Err(Error::QubitUsedAfterRelease(arg_span));
+            }
+            if qubits.len() != qubits.iter().collect::<FxHashSet<_>>().len() {
+                return Err(Error::QubitUniqueness(arg_span));
+            }
+            let paulis = paulis
+                .iter()
+                .map(|pauli| pauli.clone().unwrap_pauli())
+                .zip(qubits)
+                .collect::<Vec<_>>();
+            Ok(Value::Double(sim.expectation_value(&paulis)))
+        }
         "CheckZero" => Ok(Value::Bool(
             sim.qubit_is_zero(
                 arg.unwrap_qubit()
@@ -115,6 +357,7 @@ pub(crate) fn call(
                     .0,
             ),
         )),
+        "IsLossResult" => Ok(Value::Bool(matches!(arg, Value::Result(val::Result::Loss)))),
         "ArcCos" => Ok(Value::Double(arg.unwrap_double().acos())),
         "ArcSin" => Ok(Value::Double(arg.unwrap_double().asin())),
         "ArcTan" => Ok(Value::Double(arg.unwrap_double().atan())),
@@ -229,6 +472,19 @@ pub(crate) fn call(
     }
 }
 
+/// The bound of the range of `i64`, exclusive above and inclusive below.
+const TWO_POW_63: f64 = 9_223_372_036_854_775_808.0;
+
+/// Builds the `(Bool, value)` tuple returned by the checked conversions, where a conversion that fails is paired
+/// with the default value of its output type.
+fn checked_conversion(converted: Option<Value>, default: Value) -> Value {
+    let (succeeded, value) = match converted {
+        Some(value) => (true, value),
+        None => (false, default),
+    };
+    Value::Tuple(vec![Value::Bool(succeeded), value].into())
+}
+
 fn one_qubit_gate(
     mut gate: impl FnMut(usize),
     arg: Value,
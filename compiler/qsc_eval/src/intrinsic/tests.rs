@@ -7,13 +7,14 @@ use crate::backend::{Backend, SparseSim};
 use crate::tests::eval_graph;
 use crate::Env;
 use crate::{
-    output::{GenericReceiver, Receiver},
-    val::Value,
+    output::{self, GenericReceiver, Receiver, StateSnapshot},
+    val::{unwrap_tuple, Value},
     Error,
 };
 use expect_test::{expect, Expect};
 use indoc::indoc;
-use num_bigint::BigInt;
+use num_bigint::{BigInt, BigUint};
+use num_complex::Complex64;
 use qsc_data_structures::language_features::LanguageFeatures;
 use qsc_data_structures::target::TargetCapabilityFlags;
 use qsc_fir::fir;
@@ -157,7 +158,7 @@ fn check_intrinsic(file: &str, expr: &str, out: &mut impl Receiver) -> Result<Va
 
     let mut std = compile::std(&store, TargetCapabilityFlags::all());
     assert!(std.errors.is_empty());
-    assert!(run_default_passes(store.core(), &mut std, PackageType::Lib).is_empty());
+    assert!(run_default_passes(&store, &mut std, PackageType::Lib).is_empty());
     let std_fir = qsc_lowerer::Lowerer::new().lower_package(&std.package, &fir_store);
     let std_id = store.insert(std);
 
@@ -170,7 +171,7 @@ fn check_intrinsic(file: &str, expr: &str, out: &mut impl Receiver) -> Result<Va
         LanguageFeatures::default(),
     );
     assert!(unit.errors.is_empty());
-    assert!(run_default_passes(store.core(), &mut unit, PackageType::Lib).is_empty());
+    assert!(run_default_passes(&store, &mut unit, PackageType::Lib).is_empty());
     let unit_fir = qsc_lowerer::Lowerer::new().lower_package(&unit.package, &fir_store);
     let entry = unit_fir.entry_exec_graph.clone();
 
@@ -365,6 +366,303 @@ fn dump_register_subset_qubits() {
     );
 }
 
+#[test]
+fn reduced_density_matrix_of_product_state() {
+    check_intrinsic_result(
+        "",
+        indoc! {"{
+            use qs = Qubit[2];
+            X(qs[1]);
+            let rho = Microsoft.Quantum.Diagnostics.ReducedDensityMatrix([qs[1]]);
+            ResetAll(qs);
+            rho
+        }"},
+        &expect!["[[(0.0, 0.0), (0.0, 0.0)], [(0.0, 0.0), (1.0, 0.0)]]"],
+    );
+}
+
+#[test]
+fn reduced_density_matrix_of_entangled_qubit_is_mixed() {
+    check_intrinsic_result(
+        "",
+        indoc! {"{
+            use (left, right) = (Qubit(), Qubit());
+            H(left);
+            CNOT(left, right);
+            let rho = Microsoft.Quantum.Diagnostics.ReducedDensityMatrix([left]);
+            ResetAll([left, right]);
+            Std.Arrays.Mapped(row -> Std.Arrays.Mapped(entry -> Std.Math.Round(entry.Real * 100.0), row), rho)
+        }"},
+        &expect!["[[50, 0], [0, 50]]"],
+    );
+}
+
+#[test]
+fn reduced_density_matrix_follows_register_order() {
+    check_intrinsic_result(
+        "",
+        indoc! {"{
+            use qs = Qubit[3];
+            X(qs[0]);
+            let rho = Microsoft.Quantum.Diagnostics.ReducedDensityMatrix([qs[2], qs[0]]);
+            ResetAll(qs);
+            Std.Arrays.Mapped(row -> Std.Arrays.Mapped(entry -> Std.Math.Round(entry.Real), row), rho)
+        }"},
+        &expect!["[[0, 0, 0, 0], [0, 1, 0, 0], [0, 0, 0, 0], [0, 0, 0, 0]]"],
+    );
+}
+
+#[test]
+fn expectation_value_of_bell_state() {
+    check_intrinsic_result(
+        "",
+        indoc! {"{
+            use (left, right) = (Qubit(), Qubit());
+            H(left);
+            CNOT(left, right);
+            let values = [
+                Microsoft.Quantum.Diagnostics.ExpectationValue([PauliX, PauliX], [left, right]),
+                Microsoft.Quantum.Diagnostics.ExpectationValue([PauliY, PauliY], [left, right]),
+                Microsoft.Quantum.Diagnostics.ExpectationValue([PauliZ, PauliZ], [left, right]),
+                Microsoft.Quantum.Diagnostics.ExpectationValue([PauliZ, PauliI], [left, right])
+            ];
+            ResetAll([left, right]);
+            Std.Arrays.Mapped(value -> Std.Math.Round(value * 100.0), values)
+        }"},
+        &expect!["[100, -100, 100, 0]"],
+    );
+}
+
+#[test]
+fn expectation_value_of_y_eigenstate() {
+    check_intrinsic_result(
+        "",
+        indoc! {"{
+            use q = Qubit();
+            H(q);
+            S(q);
+            let value = Microsoft.Quantum.Diagnostics.ExpectationValue([PauliY], [q]);
+            Reset(q);
+            Std.Math.Round(value * 100.0)
+        }"},
+        &expect!["100"],
+    );
+}
+
+#[test]
+fn expectation_value_fails_with_mismatched_lengths() {
+    check_intrinsic_result(
+        "",
+        indoc! {"{
+            use q = Qubit();
+            Microsoft.Quantum.Diagnostics.ExpectationValue([PauliZ, PauliZ], [q])
+        }"},
+        &expect!["intrinsic callable `ExpectationValue` failed: arrays 'paulis' and 'qubits' must be of the same length"],
+    );
+}
+
+/// Keeps the state snapshots it receives.
+#[derive(Default)]
+struct SnapshotReceiver {
+    snapshots: Vec<StateSnapshot>,
+}
+
+impl Receiver for SnapshotReceiver {
+    fn state(
+        &mut self,
+        _state: Vec<(BigUint, Complex64)>,
+        _qubit_count: usize,
+    ) -> Result<(), output::Error> {
+        panic!("state should be received as a snapshot");
+    }
+
+    fn state_snapshot(&mut self, snapshot: StateSnapshot) -> Result<(), output::Error> {
+        self.snapshots.push(snapshot);
+        Ok(())
+    }
+
+    fn matrix(&mut self, _matrix: Vec<Vec<Complex64>>) -> Result<(), output::Error> {
+        Ok(())
+    }
+
+    fn message(&mut self, _msg: &str) -> Result<(), output::Error> {
+        Ok(())
+    }
+}
+
+#[test]
+fn dump_register_snapshot_has_qubit_ids() {
+    let mut out = SnapshotReceiver::default();
+    check_intrinsic(
+        "",
+        indoc! {"{
+            use qs = Qubit[4];
+            X(qs[2]);
+            Microsoft.Quantum.Diagnostics.DumpMachine();
+            Microsoft.Quantum.Diagnostics.DumpRegister([qs[3], qs[2]]);
+            X(qs[2]);
+        }"},
+        &mut out,
+    )
+    .expect("evaluation should succeed");
+    let snapshots = out
+        .snapshots
+        .iter()
+        .map(|snapshot| (snapshot.qubits.clone(), snapshot.labeled_amplitudes()))
+        .collect::<Vec<_>>();
+    expect![[r#"
+        [
+            (
+                [
+                    0,
+                    1,
+                    2,
+                    3,
+                ],
+                [
+                    (
+                        "|0010⟩",
+                        Complex {
+                            re: 1.0,
+                            im: 0.0,
+                        },
+                    ),
+                ],
+            ),
+            (
+                [
+                    3,
+                    2,
+                ],
+                [
+                    (
+                        "|01⟩",
+                        Complex {
+                            re: 1.0,
+                            im: 0.0,
+                        },
+                    ),
+                ],
+            ),
+        ]
+    "#]]
+    .assert_debug_eq(&snapshots);
+}
+
+/// Implements the host functions called by the tests.
+#[derive(Default)]
+struct HostReceiver {
+    calls: Vec<String>,
+}
+
+impl Receiver for HostReceiver {
+    fn state(
+        &mut self,
+        _state: Vec<(BigUint, Complex64)>,
+        _qubit_count: usize,
+    ) -> Result<(), output::Error> {
+        Ok(())
+    }
+
+    fn matrix(&mut self, _matrix: Vec<Vec<Complex64>>) -> Result<(), output::Error> {
+        Ok(())
+    }
+
+    fn message(&mut self, _msg: &str) -> Result<(), output::Error> {
+        Ok(())
+    }
+
+    fn host_call(&mut self, name: &str, arg: Value) -> Option<Result<Value, String>> {
+        self.calls.push(format!("{name}{arg}"));
+        match name {
+            // Scales each item and returns the sum as an `Int` and the scaled items as `Double`s, which are
+            // converted to the declared output types.
+            #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+            "Scale" => {
+                let [xs, scale] = unwrap_tuple(arg);
+                let scale = scale.unwrap_int() as f64;
+                let scaled = xs
+                    .unwrap_array()
+                    .iter()
+                    .map(|x| x.clone().unwrap_double() * scale)
+                    .collect::<Vec<_>>();
+                let sum = scaled.iter().sum::<f64>();
+                Some(Ok(Value::Tuple(
+                    vec![
+                        Value::Int(sum as i64),
+                        Value::Array(
+                            scaled
+                                .into_iter()
+                                .map(Value::Double)
+                                .collect::<Vec<_>>()
+                                .into(),
+                        ),
+                    ]
+                    .into(),
+                )))
+            }
+            "Fails" => Some(Err("model diverged".to_string())),
+            "WrongType" => Some(Ok(Value::String("one".into()))),
+            _ => None,
+        }
+    }
+}
+
+fn check_host_call(expr: &str, expect: &Expect) -> Vec<String> {
+    let mut out = HostReceiver::default();
+    let result = check_intrinsic(
+        indoc! {"
+            namespace Test {
+                @HostCallable()
+                function Scale(xs : Double[], scale : Int) : (Double, Int[]) { body intrinsic; }
+                @HostCallable()
+                function Fails() : Int { body intrinsic; }
+                @HostCallable()
+                function WrongType() : Int { body intrinsic; }
+                @HostCallable()
+                function Unregistered() : Int { body intrinsic; }
+            }
+        "},
+        expr,
+        &mut out,
+    );
+    match result {
+        Ok(result) => expect.assert_eq(&result.to_string()),
+        Err(e) => expect.assert_eq(&e.to_string()),
+    }
+    out.calls
+}
+
+#[test]
+fn host_callable_function_calls_host() {
+    let calls = check_host_call("Test.Scale([1.5, 2.5], 2)", &expect!["(8.0, [3, 5])"]);
+    assert_eq!(calls, vec!["Scale([1.5, 2.5], 2)".to_string()]);
+}
+
+#[test]
+fn host_callable_function_fails_with_host_error() {
+    check_host_call(
+        "Test.Fails()",
+        &expect!["intrinsic callable `Fails` failed: model diverged"],
+    );
+}
+
+#[test]
+fn host_callable_function_fails_with_wrong_type() {
+    check_host_call(
+        "Test.WrongType()",
+        &expect!["host function `WrongType` returned a value that is not of type `Int`"],
+    );
+}
+
+#[test]
+fn host_callable_function_fails_when_not_registered() {
+    check_host_call(
+        "Test.Unregistered()",
+        &expect!["host function `Unregistered` is not registered"],
+    );
+}
+
 #[test]
 fn dump_register_subset_entangled_within_subset_is_separable() {
     check_intrinsic_output(
@@ -1884,3 +2182,81 @@ fn applyidlenoise_qubit_already_released_fails() {
         &expect!["qubit used after release"],
     );
 }
+
+const TASKS: &str = indoc! {r#"
+    namespace Test {
+        function Task(label : String, value : Int) : Int {
+            Message(label);
+            value
+        }
+        function FailingTask(label : String) : Int {
+            Message(label);
+            fail label
+        }
+    }
+"#};
+
+#[test]
+fn run_all_forwards_task_output_when_tasks_are_joined() {
+    check_intrinsic_output(
+        TASKS,
+        indoc! {r#"{
+            let results = Std.Tasks.RunAll([() -> Test.Task("first", 1), () -> Test.Task("second", 2)]);
+            Message($"joined {results}");
+        }"#},
+        &expect![[r#"
+            first
+            second
+            joined [1, 2]
+        "#]],
+    );
+}
+
+#[test]
+fn run_all_joins_tasks_in_order_until_a_task_fails() {
+    let mut stdout = vec![];
+    let mut out = GenericReceiver::new(&mut stdout);
+    let error = check_intrinsic(
+        TASKS,
+        indoc! {r#"
+            Std.Tasks.RunAll([
+                () -> Test.Task("first", 1),
+                () -> Test.FailingTask("second"),
+                () -> Test.Task("third", 3),
+                () -> Test.FailingTask("fourth")
+            ])
+        "#},
+        &mut out,
+    )
+    .expect_err("joining the tasks should fail");
+    expect!["program failed: second"].assert_eq(&error.to_string());
+    expect![[r#"
+        first
+        second
+    "#]]
+    .assert_eq(&String::from_utf8(stdout).expect("content should be convertable to string"));
+}
+
+#[test]
+fn run_both_joins_results_of_different_types() {
+    check_intrinsic_result(
+        "",
+        "Std.Tasks.RunBoth(() -> 3 * 7, () -> true)",
+        &expect!["(21, true)"],
+    );
+}
+
+#[test]
+fn run_all_declared_outside_tasks_namespace_is_unknown_intrinsic() {
+    check_intrinsic_result(
+        indoc! {"
+            namespace Test {
+                function RunAll(tasks : (Unit -> Int)[]) : Int[] {
+                    body intrinsic;
+                }
+            }
+        "},
+        "Test.RunAll([() -> 1])",
+        &expect!["unknown intrinsic `RunAll`"],
+    );
+}
@@ -1,7 +1,7 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
-use std::collections::hash_map::Entry;
+use std::collections::{hash_map::Entry, BTreeMap};
 
 use num_bigint::BigUint;
 use num_complex::{Complex, Complex64};
@@ -41,6 +41,45 @@ pub fn split_state(
     Ok(dump_state)
 }
 
+/// Computes the reduced density matrix of the given qubits by tracing out the remaining qubits from the state.
+/// The rows and columns are indexed by the basis states of the given qubits, in the order they are provided.
+pub fn reduced_density_matrix(
+    qubits: &[usize],
+    state: &[(BigUint, Complex64)],
+    qubit_count: usize,
+) -> Vec<Vec<Complex64>> {
+    let (_, other_mask) = compute_mask(qubit_count, qubits);
+
+    // Group the amplitudes by the basis state of the traced out qubits, in a consistent order so that the
+    // sums below are deterministic.
+    let mut groups: BTreeMap<BigUint, Vec<(usize, Complex64)>> = BTreeMap::new();
+    for (label, amplitude) in state {
+        let mut index = 0;
+        for (i, q) in qubits.iter().enumerate() {
+            // Note that the qubit order is reversed to match the order of the qubits in the state.
+            if label.bit((qubit_count - *q - 1) as u64) {
+                index |= 1 << (qubits.len() - i - 1);
+            }
+        }
+        groups
+            .entry(label & &other_mask)
+            .or_default()
+            .push((index, *amplitude));
+    }
+
+    // Each basis state of the traced out qubits contributes the outer product of the amplitudes that go with it.
+    let size = 1 << qubits.len();
+    let mut matrix = vec![vec![Complex64::zero(); size]; size];
+    for amplitudes in groups.values() {
+        for (row, a) in amplitudes {
+            for (col, b) in amplitudes {
+                matrix[*row][*col] += a * b.conj();
+            }
+        }
+    }
+    matrix
+}
+
 /// From the qubit identifiers provided, compute the bit masks for the qubits to dump and the remaining qubits.
 /// These masks can be applied to the state labels to separate the label into the two parts needed.
 fn compute_mask(qubit_count: usize, qubits: &[usize]) -> (BigUint, BigUint) {
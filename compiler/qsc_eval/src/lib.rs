@@ -14,16 +14,23 @@
 //! effectively stateless execution (like running shots of a program) stateful execution scenarios
 //! (like debugging or notebooks).
 
-#[cfg(test)]
+#[cfg(all(test, feature = "sparse-sim"))]
 mod tests;
 
 pub mod backend;
+pub mod checkpoint;
 pub mod debug;
 mod error;
+#[cfg(feature = "gpu")]
+pub mod gpu;
 pub mod intrinsic;
 pub mod noise;
 pub mod output;
+pub mod reuse;
+pub mod stabilizer;
 pub mod state;
+pub mod tape;
+pub mod trace;
 pub mod val;
 
 use crate::val::{
@@ -34,16 +41,16 @@ use debug::{CallStack, Frame};
 pub use error::PackageSpan;
 use miette::Diagnostic;
 use num_bigint::BigInt;
-use output::Receiver;
+use output::{BufferedOutput, BufferedReceiver, Receiver};
 use qsc_data_structures::{functors::FunctorApp, index_map::IndexMap, span::Span};
 use qsc_fir::fir::{
     self, BinOp, CallableImpl, ExecGraph, ExecGraphNode, Expr, ExprId, ExprKind, Field,
     FieldAssign, Global, Lit, LocalItemId, LocalVarId, PackageId, PackageStoreLookup, PatId,
-    PatKind, PrimField, Res, StmtId, StoreItemId, StringComponent, UnOp,
+    PatKind, PrimField, Res, SpecImpl, StmtId, StoreItemId, StringComponent, UnOp,
 };
 use qsc_fir::ty::Ty;
 use qsc_lowerer::map_fir_package_to_hir;
-use rand::{rngs::StdRng, SeedableRng};
+use rand::{rngs::StdRng, Rng, SeedableRng};
 use rustc_hash::{FxHashMap, FxHashSet};
 use std::ops;
 use std::{
@@ -52,7 +59,10 @@ use std::{
     iter,
     ops::Neg,
     rc::Rc,
+    sync::OnceLock,
+    time::{Duration, Instant},
 };
+use tape::Tape;
 use thiserror::Error;
 use val::{update_functor_app, Qubit};
 
@@ -90,6 +100,10 @@ pub enum Error {
     #[diagnostic(code("Qsc.Eval.InvalidIndex"))]
     InvalidIndex(i64, #[label("invalid index")] PackageSpan),
 
+    #[error("format specifier `{0}` cannot be used with a value of type {1}")]
+    #[diagnostic(code("Qsc.Eval.InvalidFormatSpec"))]
+    InvalidFormatSpec(String, &'static str, #[label] PackageSpan),
+
     #[error("integer too large for operation")]
     #[diagnostic(code("Qsc.Eval.IntTooLarge"))]
     IntTooLarge(i64, #[label("this value is too large")] PackageSpan),
@@ -142,6 +156,68 @@ pub enum Error {
     #[diagnostic(code("Qsc.Eval.QubitsNotSeparable"))]
     QubitsNotSeparable(#[label] PackageSpan),
 
+    #[error("program exceeded the limit of {0} execution steps")]
+    #[diagnostic(help(
+        "the program may contain an infinite loop or may need a larger step quota"
+    ))]
+    #[diagnostic(code("Qsc.Eval.StepQuotaExceeded"))]
+    StepQuotaExceeded(u64, #[label("execution stopped here")] PackageSpan),
+
+    #[error("program exceeded the limit of {0} nested calls")]
+    #[diagnostic(help(
+        "the program may contain unbounded recursion or may need a larger call depth limit"
+    ))]
+    #[diagnostic(code("Qsc.Eval.RecursionLimitExceeded"))]
+    RecursionLimitExceeded(usize, #[label("call exceeded the limit")] PackageSpan),
+
+    #[error("program timed out after {0:?}")]
+    #[diagnostic(help("the program may contain an infinite loop or may need a longer timeout"))]
+    #[diagnostic(code("Qsc.Eval.Timeout"))]
+    Timeout(Duration, #[label("execution stopped here")] PackageSpan),
+
+    #[error("call to `{0}` is not allowed in this environment")]
+    #[diagnostic(help(
+        "the environment running the program restricts which intrinsic callables it can call"
+    ))]
+    #[diagnostic(code("Qsc.Eval.IntrinsicNotAllowed"))]
+    IntrinsicNotAllowed(String, #[label("call not allowed")] PackageSpan),
+
+    #[error("host function `{0}` is not registered")]
+    #[diagnostic(help(
+        "a function with the `@HostCallable()` attribute is implemented by the host running the program, which should register a function with the same name"
+    ))]
+    #[diagnostic(code("Qsc.Eval.HostFunctionNotRegistered"))]
+    HostFunctionNotRegistered(String, #[label] PackageSpan),
+
+    #[error("host function `{0}` returned a value that is not of type `{1}`")]
+    #[diagnostic(code("Qsc.Eval.InvalidHostValue"))]
+    InvalidHostValue(String, String, #[label] PackageSpan),
+
+    #[error("program exceeded the limit of {0} messages")]
+    #[diagnostic(help(
+        "the environment running the program limits how many messages it can output"
+    ))]
+    #[diagnostic(code("Qsc.Eval.MessageLimitExceeded"))]
+    MessageLimitExceeded(u64, #[label("execution stopped here")] PackageSpan),
+
+    #[error("program exceeded the limit of {0} allocated qubits")]
+    #[diagnostic(help(
+        "the environment running the program limits how many qubits it can allocate at once"
+    ))]
+    #[diagnostic(code("Qsc.Eval.QubitLimitExceeded"))]
+    QubitLimitExceeded(usize, #[label("execution stopped here")] PackageSpan),
+
+    #[error("simulation exceeded the memory budget of {0} bytes")]
+    #[diagnostic(help(
+        "the quantum state grew to an estimated {1} bytes; entangling fewer qubits at once keeps the state smaller"
+    ))]
+    #[diagnostic(code("Qsc.Eval.SimulationMemoryExceeded"))]
+    SimulationMemoryExceeded(
+        usize,
+        usize,
+        #[label("the quantum state grew too large here")] PackageSpan,
+    ),
+
     #[error("range with step size of zero")]
     #[diagnostic(code("Qsc.Eval.RangeStepZero"))]
     RangeStepZero(#[label("invalid range")] PackageSpan),
@@ -161,6 +237,16 @@ pub enum Error {
     #[diagnostic(help("comparing measurement results is not supported when performing circuit synthesis or base profile QIR generation"))]
     ResultComparisonUnsupported(#[label("cannot compare to result")] PackageSpan),
 
+    #[error("replayed tape has no outcome for `{0}`")]
+    #[diagnostic(help(
+        "the program should make the same measurements and random draws, in the same order, as the run that recorded the tape"
+    ))]
+    #[diagnostic(code("Qsc.Eval.TapeMismatch"))]
+    TapeMismatch(
+        String,
+        #[label("no matching outcome on the tape")] PackageSpan,
+    ),
+
     #[error("name is not bound")]
     #[diagnostic(code("Qsc.Eval.UnboundName"))]
     UnboundName(#[label] PackageSpan),
@@ -193,6 +279,7 @@ impl Error {
             | Error::EmptyRange(span)
             | Error::IndexOutOfRange(_, span)
             | Error::InvalidIndex(_, span)
+            | Error::InvalidFormatSpec(_, _, span)
             | Error::IntrinsicFail(_, _, span)
             | Error::IntTooLarge(_, span)
             | Error::InvalidRotationAngle(_, span)
@@ -205,9 +292,19 @@ impl Error {
             | Error::QubitsNotCounted(span)
             | Error::QubitsNotSeparable(span)
             | Error::RangeStepZero(span)
+            | Error::StepQuotaExceeded(_, span)
+            | Error::RecursionLimitExceeded(_, span)
+            | Error::Timeout(_, span)
+            | Error::IntrinsicNotAllowed(_, span)
+            | Error::HostFunctionNotRegistered(_, span)
+            | Error::InvalidHostValue(_, _, span)
+            | Error::MessageLimitExceeded(_, span)
+            | Error::QubitLimitExceeded(_, span)
+            | Error::SimulationMemoryExceeded(_, _, span)
             | Error::RelabelingMismatch(span)
             | Error::ReleasedQubitNotZero(_, span)
             | Error::ResultComparisonUnsupported(span)
+            | Error::TapeMismatch(_, span)
             | Error::UnboundName(span)
             | Error::UnknownIntrinsic(_, span)
             | Error::UnsupportedIntrinsicType(_, span)
@@ -241,33 +338,23 @@ impl Display for Spec {
 }
 
 /// Utility function to identify a subset of a control flow graph corresponding to a given
-/// range.
+/// range. The section shares the nodes of the graph, so it is cheap to take.
 #[must_use]
 pub fn exec_graph_section(graph: &ExecGraph, range: ops::Range<usize>) -> ExecGraph {
-    let start: u32 = range
-        .start
-        .try_into()
-        .expect("exec graph ranges should fit into u32");
-    graph[range]
-        .iter()
-        .map(|node| match node {
-            ExecGraphNode::Jump(idx) => ExecGraphNode::Jump(idx - start),
-            ExecGraphNode::JumpIf(idx) => ExecGraphNode::JumpIf(idx - start),
-            ExecGraphNode::JumpIfNot(idx) => ExecGraphNode::JumpIfNot(idx - start),
-            _ => *node,
-        })
-        .collect::<Vec<_>>()
-        .into()
+    graph.section(range)
 }
 
-/// Evaluates the given code with the given context.
+/// Evaluates the given code with the given context, within the given quota and intrinsic policy.
 /// # Errors
 /// Returns the first error encountered during execution.
 /// # Panics
 /// On internal error where no result is returned.
+#[allow(clippy::too_many_arguments)]
 pub fn eval(
     package: PackageId,
     seed: Option<u64>,
+    quota: Quota,
+    policy: &IntrinsicPolicy,
     exec_graph: ExecGraph,
     globals: &impl PackageStoreLookup,
     env: &mut Env,
@@ -275,6 +362,8 @@ pub fn eval(
     receiver: &mut impl Receiver,
 ) -> Result<Value, (Error, Vec<Frame>)> {
     let mut state = State::new(package, exec_graph, seed);
+    state.set_quota(quota);
+    state.set_intrinsic_policy(policy.clone());
     let res = state.eval(globals, env, sim, receiver, &[], StepAction::Continue)?;
     let StepResult::Return(value) = res else {
         panic!("eval should always return a value");
@@ -282,7 +371,7 @@ pub fn eval(
     Ok(value)
 }
 
-/// Evaluates the given callable with the given context.
+/// Evaluates the given callable with the given context, within the given quota and intrinsic policy.
 /// # Errors
 /// Returns the first error encountered during execution.
 /// # Panics
@@ -291,6 +380,8 @@ pub fn eval(
 pub fn invoke(
     package: PackageId,
     seed: Option<u64>,
+    quota: Quota,
+    policy: &IntrinsicPolicy,
     globals: &impl PackageStoreLookup,
     env: &mut Env,
     sim: &mut impl Backend<ResultType = impl Into<val::Result>>,
@@ -299,6 +390,8 @@ pub fn invoke(
     args: Value,
 ) -> Result<Value, (Error, Vec<Frame>)> {
     let mut state = State::new(package, Vec::new().into(), seed);
+    state.set_quota(quota);
+    state.set_intrinsic_policy(policy.clone());
     // Push the callable value into the state stack and then the args value so they are ready for evaluation.
     state.set_val_register(callable);
     state.push_val();
@@ -411,6 +504,7 @@ impl Range {
 pub struct Env {
     scopes: Vec<Scope>,
     qubits: FxHashSet<Rc<Qubit>>,
+    tape: Option<Tape>,
 }
 
 impl Default for Env {
@@ -420,6 +514,7 @@ impl Default for Env {
         Self {
             scopes: vec![Scope::default()],
             qubits: FxHashSet::default(),
+            tape: None,
         }
     }
 }
@@ -534,6 +629,17 @@ impl Env {
     pub fn release_qubit(&mut self, qubit: &Rc<Qubit>) {
         self.qubits.remove(qubit);
     }
+
+    /// Sets the tape that records or replays the outcomes of measurements and random draws in this
+    /// environment, or stops taping outcomes if `None`.
+    pub fn set_tape(&mut self, tape: Option<Tape>) {
+        self.tape = tape;
+    }
+
+    /// Takes the tape from the environment, so that later outcomes are no longer taped.
+    pub fn take_tape(&mut self) -> Option<Tape> {
+        self.tape.take()
+    }
 }
 
 #[derive(Default)]
@@ -544,6 +650,63 @@ struct Scope {
 
 type CallableCountKey = (StoreItemId, bool, bool);
 
+/// Limits on the execution of a program, so that embedders can bound the execution of untrusted programs.
+/// Exceeding a limit stops execution with an error that carries the call stack at the point it stopped.
+/// No limit is set by default.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Quota {
+    /// The maximum number of execution graph steps to take.
+    pub max_steps: Option<u64>,
+    /// The maximum wall-clock time to run for, measured with `clock` from when the quota is set on the state.
+    pub timeout: Option<Duration>,
+    /// The source of the current time that `timeout` is measured with, as the time elapsed since a fixed point
+    /// in the past, or `None` for the system clock. Targets without a system clock, such as
+    /// `wasm32-unknown-unknown`, must provide one to set a timeout.
+    pub clock: Option<fn() -> Duration>,
+    /// The maximum number of nested calls to callables with an implementation, so that unbounded
+    /// recursion stops with an error instead of growing the call stack until memory runs out.
+    /// A function's calls to itself in tail position reuse its frame, so they don't count towards it.
+    pub max_call_depth: Option<usize>,
+}
+
+impl Quota {
+    /// The number of steps between checks of the wall-clock time, to avoid reading the clock on every step.
+    const STEPS_PER_CLOCK_CHECK: u64 = 1024;
+
+    fn now(&self) -> Duration {
+        static START: OnceLock<Instant> = OnceLock::new();
+        match self.clock {
+            Some(clock) => clock(),
+            None => START.get_or_init(Instant::now).elapsed(),
+        }
+    }
+}
+
+/// Restrictions on the intrinsic callables a program can call, so that embedders can safely run untrusted
+/// programs. Calling a restricted intrinsic stops execution with an error that carries the call stack.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IntrinsicPolicy {
+    /// The names of the intrinsics the program can call, or `None` to allow all of them.
+    /// Qubit allocation and release are governed by `max_qubits` instead.
+    pub allowed: Option<FxHashSet<Rc<str>>>,
+    /// The names of the intrinsics the program cannot call, such as `DumpMachine`, even if they are allowed.
+    pub denied: FxHashSet<Rc<str>>,
+    /// The maximum number of messages the program can output.
+    pub max_messages: Option<u64>,
+    /// The maximum number of qubits the program can have allocated at once.
+    pub max_qubits: Option<usize>,
+}
+
+impl IntrinsicPolicy {
+    fn is_allowed(&self, name: &str) -> bool {
+        !self.denied.contains(name)
+            && self
+                .allowed
+                .as_ref()
+                .is_none_or(|allowed| allowed.contains(name))
+    }
+}
+
 pub struct State {
     exec_graph_stack: Vec<ExecGraph>,
     idx: u32,
@@ -557,6 +720,12 @@ pub struct State {
     rng: RefCell<StdRng>,
     call_counts: FxHashMap<CallableCountKey, i64>,
     qubit_counter: Option<QubitCounter>,
+    quota: Quota,
+    steps: u64,
+    /// The time on the clock of the quota at which its timeout expires.
+    deadline: Option<Duration>,
+    policy: IntrinsicPolicy,
+    messages: u64,
 }
 
 impl State {
@@ -579,7 +748,91 @@ impl State {
             rng,
             call_counts: FxHashMap::default(),
             qubit_counter: None,
+            quota: Quota::default(),
+            steps: 0,
+            deadline: None,
+            policy: IntrinsicPolicy::default(),
+            messages: 0,
+        }
+    }
+
+    /// Sets the quota for the execution of the program, restarting the count of steps and the clock.
+    pub fn set_quota(&mut self, quota: Quota) {
+        self.quota = quota;
+        self.steps = 0;
+        self.deadline = quota
+            .timeout
+            .and_then(|timeout| quota.now().checked_add(timeout));
+    }
+
+    /// Sets the policy that restricts the intrinsics the program can call, restarting the count of messages.
+    pub fn set_intrinsic_policy(&mut self, policy: IntrinsicPolicy) {
+        self.policy = policy;
+        self.messages = 0;
+    }
+
+    fn check_intrinsic_policy(
+        &mut self,
+        name: &str,
+        env: &Env,
+        span: PackageSpan,
+    ) -> Result<(), Error> {
+        match name {
+            "__quantum__rt__qubit_allocate" => {
+                if let Some(max_qubits) = self.policy.max_qubits {
+                    if env.qubits.len() >= max_qubits {
+                        return Err(Error::QubitLimitExceeded(max_qubits, span));
+                    }
+                }
+            }
+            "__quantum__rt__qubit_release" => {}
+            _ if !self.policy.is_allowed(name) => {
+                return Err(Error::IntrinsicNotAllowed(name.to_string(), span));
+            }
+            "Message" => {
+                self.messages += 1;
+                if let Some(max_messages) = self.policy.max_messages {
+                    if self.messages > max_messages {
+                        return Err(Error::MessageLimitExceeded(max_messages, span));
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn check_quota(&mut self) -> Result<(), Error> {
+        self.steps += 1;
+        if let Some(max_steps) = self.quota.max_steps {
+            if self.steps > max_steps {
+                return Err(Error::StepQuotaExceeded(
+                    max_steps,
+                    self.to_global_span(self.current_span),
+                ));
+            }
+        }
+        if let (Some(deadline), Some(timeout)) = (self.deadline, self.quota.timeout) {
+            if self.steps % Quota::STEPS_PER_CLOCK_CHECK == 0 && self.quota.now() >= deadline {
+                return Err(Error::Timeout(
+                    timeout,
+                    self.to_global_span(self.current_span),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn check_call_depth(&self, call_span: Span) -> Result<(), Error> {
+        if let Some(max_call_depth) = self.quota.max_call_depth {
+            if self.call_stack.len() >= max_call_depth {
+                return Err(Error::RecursionLimitExceeded(
+                    max_call_depth,
+                    self.to_global_span(call_span),
+                ));
+            }
         }
+        Ok(())
     }
 
     fn push_frame(&mut self, exec_graph: ExecGraph, id: StoreItemId, functor: FunctorApp) {
@@ -667,6 +920,8 @@ impl State {
     ) -> Result<StepResult, (Error, Vec<Frame>)> {
         let current_frame = self.call_stack.len();
         while !self.exec_graph_stack.is_empty() {
+            self.check_quota()
+                .map_err(|e| (e, self.get_stack_frames()))?;
             let exec_graph = self
                 .exec_graph_stack
                 .last()
@@ -674,32 +929,38 @@ impl State {
             let res = match exec_graph.get(self.idx as usize) {
                 Some(ExecGraphNode::Bind(pat)) => {
                     self.idx += 1;
-                    self.eval_bind(env, globals, *pat);
+                    self.eval_bind(env, globals, pat);
                     continue;
                 }
                 Some(ExecGraphNode::Expr(expr)) => {
                     self.idx += 1;
-                    self.eval_expr(env, sim, globals, out, *expr)
+                    self.eval_expr(env, sim, globals, out, expr)
+                        .map_err(|e| (e, self.get_stack_frames()))?;
+                    continue;
+                }
+                Some(ExecGraphNode::TailCall(expr)) => {
+                    self.idx += 1;
+                    self.eval_tail_call(env, sim, globals, out, expr)
                         .map_err(|e| (e, self.get_stack_frames()))?;
                     continue;
                 }
                 Some(ExecGraphNode::Stmt(stmt)) => {
                     self.idx += 1;
-                    self.current_span = globals.get_stmt((self.package, *stmt).into()).span;
+                    self.current_span = globals.get_stmt((self.package, stmt).into()).span;
 
-                    match self.check_for_break(breakpoints, *stmt, step, current_frame) {
+                    match self.check_for_break(breakpoints, stmt, step, current_frame) {
                         Some(value) => value,
                         None => continue,
                     }
                 }
                 Some(ExecGraphNode::Jump(idx)) => {
-                    self.idx = *idx;
+                    self.idx = idx;
                     continue;
                 }
                 Some(ExecGraphNode::JumpIf(idx)) => {
                     let cond = self.val_register == Some(Value::Bool(true));
                     if cond {
-                        self.idx = *idx;
+                        self.idx = idx;
                     } else {
                         self.idx += 1;
                     }
@@ -710,7 +971,7 @@ impl State {
                     if cond {
                         self.idx += 1;
                     } else {
-                        self.idx = *idx;
+                        self.idx = idx;
                     }
                     continue;
                 }
@@ -891,7 +1152,7 @@ impl State {
             }
             ExprKind::Return(..) => panic!("return expr should be handled by control flow"),
             ExprKind::Struct(_, copy, fields) => self.eval_struct(*copy, fields),
-            ExprKind::String(components) => self.collect_string(components),
+            ExprKind::String(components) => self.collect_string(components, globals)?,
             ExprKind::UpdateIndex(_, mid, _) => {
                 let mid_span = globals.get_expr((self.package, *mid).into()).span;
                 self.eval_update_index(mid_span)?;
@@ -912,25 +1173,42 @@ impl State {
         Ok(())
     }
 
-    fn collect_string(&mut self, components: &[StringComponent]) {
+    fn collect_string(
+        &mut self,
+        components: &[StringComponent],
+        globals: &impl PackageStoreLookup,
+    ) -> Result<(), Error> {
         if let [StringComponent::Lit(str)] = components {
             self.set_val_register(Value::String(Rc::clone(str)));
-            return;
+            return Ok(());
         }
 
         let mut string = String::new();
         for component in components.iter().rev() {
             match component {
-                StringComponent::Expr(..) => {
+                StringComponent::Expr(_, None) => {
                     let expr_str = format!("{}", self.pop_val());
                     string.insert_str(0, &expr_str);
                 }
+                StringComponent::Expr(expr, Some(format)) => {
+                    let value = self.pop_val();
+                    let Some(expr_str) = value.format_with(format) else {
+                        let span = globals.get_expr((self.package, *expr).into()).span;
+                        return Err(Error::InvalidFormatSpec(
+                            format.to_string(),
+                            value.type_name(),
+                            self.to_global_span(span),
+                        ));
+                    };
+                    string.insert_str(0, &expr_str);
+                }
                 StringComponent::Lit(lit) => {
                     string.insert_str(0, lit);
                 }
             }
         }
         self.set_val_register(Value::String(Rc::from(string)));
+        Ok(())
     }
 
     fn eval_arr(&mut self, len: usize) {
@@ -1073,6 +1351,14 @@ impl State {
 
         let callee_span = self.to_global_span(callee.span);
 
+        if matches!(callee.implementation, CallableImpl::Intrinsic) {
+            self.check_intrinsic_policy(
+                &callee.name.name,
+                env,
+                self.to_global_span(callable_span),
+            )?;
+        }
+
         let spec = spec_from_functor_app(functor);
         match &callee.implementation {
             CallableImpl::Intrinsic if is_counting_call(&callee.name.name) => {
@@ -1084,17 +1370,30 @@ impl State {
                 self.leave_frame();
                 Ok(())
             }
-            CallableImpl::Intrinsic => self.eval_intrinsic(
-                env,
-                callee_id,
-                functor,
-                callee,
-                sim,
-                callee_span,
-                arg,
-                arg_span,
-                out,
-            ),
+            CallableImpl::Intrinsic => {
+                self.eval_intrinsic(
+                    env,
+                    globals,
+                    callee_id,
+                    functor,
+                    callee,
+                    sim,
+                    callee_span,
+                    arg,
+                    arg_span,
+                    out,
+                )?;
+                // The simulator only reports that the state grew beyond its memory budget after
+                // the gate is applied, so the call that grew it is where execution stops.
+                match sim.take_memory_exceeded() {
+                    Some(exceeded) => Err(Error::SimulationMemoryExceeded(
+                        exceeded.budget,
+                        exceeded.size,
+                        self.to_global_span(callable_span),
+                    )),
+                    None => Ok(()),
+                }
+            }
             CallableImpl::Spec(specialized_implementation) => {
                 let spec_decl = match spec {
                     Spec::Body => Some(&specialized_implementation.body),
@@ -1103,6 +1402,7 @@ impl State {
                     Spec::CtlAdj => specialized_implementation.ctl_adj.as_ref(),
                 }
                 .expect("missing specialization should be a compilation error");
+                self.check_call_depth(callable_span)?;
                 self.push_frame(spec_decl.exec_graph.clone(), callee_id, functor);
                 self.push_scope(env);
                 self.increment_call_count(callee_id, functor);
@@ -1120,6 +1420,7 @@ impl State {
                 Ok(())
             }
             CallableImpl::SimulatableIntrinsic(spec_decl) => {
+                self.check_call_depth(callable_span)?;
                 self.push_frame(spec_decl.exec_graph.clone(), callee_id, functor);
                 self.push_scope(env);
 
@@ -1138,10 +1439,70 @@ impl State {
         }
     }
 
+    /// Evaluates a call of a function to itself in tail position by binding the arguments in the
+    /// frame of the current call and restarting its exec graph, so that deep recursion runs in
+    /// constant stack. Any other call is evaluated as usual.
+    fn eval_tail_call(
+        &mut self,
+        env: &mut Env,
+        sim: &mut impl Backend<ResultType = impl Into<val::Result>>,
+        globals: &impl PackageStoreLookup,
+        out: &mut impl Receiver,
+        expr: ExprId,
+    ) -> Result<(), Error> {
+        let frame = self
+            .call_stack
+            .last()
+            .expect("tail call should be in a frame");
+        let (callee_id, functor) = (frame.id, frame.functor);
+        let is_self_call = matches!(
+            self.val_stack.last().and_then(|vals| vals.last()),
+            Some(Value::Global(id, app)) if *id == callee_id && *app == functor
+        );
+        if !is_self_call {
+            return self.eval_expr(env, sim, globals, out, expr);
+        }
+        let Some(Global::Callable(callee)) = globals.get_global(callee_id) else {
+            panic!("frame should be for a callable");
+        };
+        let CallableImpl::Spec(SpecImpl { body, .. }) = &callee.implementation else {
+            panic!("tail calls should only be in functions with a body");
+        };
+
+        let ExprKind::Call(_, args_expr) = globals.get_expr((self.package, expr).into()).kind
+        else {
+            panic!("tail call should be a call expression");
+        };
+        let arg_span = globals.get_expr((self.package, args_expr).into()).span;
+        let arg_span = self.to_global_span(arg_span);
+        let arg = self.take_val_register();
+        self.pop_val();
+
+        env.leave_current_frame();
+        self.val_stack
+            .last_mut()
+            .expect("should have at least one value frame")
+            .clear();
+        self.idx = 0;
+        self.push_scope(env);
+        self.increment_call_count(callee_id, functor);
+        self.bind_args_for_spec(
+            env,
+            globals,
+            callee.input,
+            body.input,
+            arg,
+            arg_span,
+            functor.controlled,
+            None,
+        )
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn eval_intrinsic(
         &mut self,
         env: &mut Env,
+        globals: &impl PackageStoreLookup,
         callee_id: StoreItemId,
         functor: FunctorApp,
         callee: &fir::CallableDecl,
@@ -1155,6 +1516,9 @@ impl State {
         self.increment_call_count(callee_id, functor);
         let name = &callee.name.name;
         let val = match name.as_ref() {
+            _ if callee.attrs.contains(&fir::Attr::HostCallable) => {
+                intrinsic::call_host(name, callee_span, arg, &callee.output, out)?
+            }
             "__quantum__rt__qubit_allocate" => {
                 let q = Rc::new(Qubit(sim.qubit_allocate()));
                 env.track_qubit(Rc::clone(&q));
@@ -1175,6 +1539,17 @@ impl State {
                     return Err(Error::ReleasedQubitNotZero(qubit.0, arg_span));
                 }
             }
+            "__quantum__qis__delay_us__body" => {
+                // Every allocated qubit idles during the delay. They are sorted so that seeded
+                // simulations apply idle noise in the same order each time.
+                let mut qubits = env.qubits.iter().map(|q| q.0).collect::<Vec<_>>();
+                qubits.sort_unstable();
+                sim.delay(arg.unwrap_double(), &qubits);
+                Value::unit()
+            }
+            "RunAll" | "RunBoth" if is_tasks_item(globals, callee_id) => {
+                self.run_tasks(globals, sim, name, callee_span, arg, out)?
+            }
             _ => {
                 let val = intrinsic::call(
                     name,
@@ -1183,6 +1558,7 @@ impl State {
                     arg_span,
                     sim,
                     &mut self.rng.borrow_mut(),
+                    env.tape.as_mut(),
                     out,
                 )?;
                 if val == Value::unit() && callee.output != Ty::UNIT {
@@ -1199,6 +1575,115 @@ impl State {
         Ok(())
     }
 
+    /// Runs the independent classical tasks passed to `RunAll` or `RunBoth`, as an array or a tuple of callables
+    /// that take no input, and joins their results into an array or a tuple.
+    ///
+    /// The tasks run one after another, in order, and every task runs to completion before any of them is joined,
+    /// each in its own state and environment with its output buffered, so the tasks can't observe each other. The tasks are then joined in
+    /// order: the output of each task is forwarded and its result collected, until a task that failed is joined and
+    /// its error is returned. The output of the tasks after it is dropped.
+    fn run_tasks(
+        &mut self,
+        globals: &impl PackageStoreLookup,
+        sim: &mut impl Backend<ResultType = impl Into<val::Result>>,
+        name: &str,
+        callee_span: PackageSpan,
+        tasks: Value,
+        out: &mut impl Receiver,
+    ) -> Result<Value, Error> {
+        let (tasks, is_array) = match tasks {
+            Value::Array(tasks) => (tasks.to_vec(), true),
+            Value::Tuple(tasks) => (tasks.to_vec(), false),
+            value => {
+                return Err(Error::IntrinsicFail(
+                    name.to_string(),
+                    format!(
+                        "expected an array or a tuple of tasks, found {}",
+                        value.type_name()
+                    ),
+                    callee_span,
+                ))
+            }
+        };
+        let runs = tasks
+            .into_iter()
+            .map(|task| self.run_task(globals, sim, task, out))
+            .collect::<Vec<_>>();
+
+        let mut results = Vec::with_capacity(runs.len());
+        for (result, output) in runs {
+            for output in output {
+                output
+                    .replay(out)
+                    .map_err(|_| Error::OutputFail(callee_span))?;
+            }
+            results.push(result?);
+        }
+        Ok(if is_array {
+            Value::Array(results.into())
+        } else {
+            Value::Tuple(results.into())
+        })
+    }
+
+    /// Runs a task to completion in a state of its own, which shares the quota, intrinsic policy and remaining
+    /// call depth of this state and draws its seed from this state, so that seeded runs stay deterministic.
+    /// Returns the result of the task along with the output it produced.
+    fn run_task(
+        &mut self,
+        globals: &impl PackageStoreLookup,
+        sim: &mut impl Backend<ResultType = impl Into<val::Result>>,
+        task: Value,
+        out: &mut impl Receiver,
+    ) -> (Result<Value, Error>, Vec<BufferedOutput>) {
+        let seed = self.rng.get_mut().gen();
+        let mut state = Self::new(self.package, Vec::new().into(), Some(seed));
+        state.quota = Quota {
+            max_call_depth: self
+                .quota
+                .max_call_depth
+                .map(|depth| depth.saturating_sub(self.call_stack.len())),
+            ..self.quota
+        };
+        state.steps = self.steps;
+        state.deadline = self.deadline;
+        state.policy = self.policy.clone();
+        state.messages = self.messages;
+
+        let mut env = Env::default();
+        let mut receiver = BufferedReceiver::new(out);
+        state.set_val_register(task);
+        state.push_val();
+        state.set_val_register(Value::unit());
+        let result = state
+            .eval_call(
+                &mut env,
+                sim,
+                globals,
+                Span::default(),
+                Span::default(),
+                &mut receiver,
+            )
+            .and_then(|()| {
+                match state.eval(
+                    globals,
+                    &mut env,
+                    sim,
+                    &mut receiver,
+                    &[],
+                    StepAction::Continue,
+                ) {
+                    Ok(StepResult::Return(value)) => Ok(value),
+                    Ok(_) => panic!("task should run to completion"),
+                    Err((error, _)) => Err(error),
+                }
+            });
+
+        self.steps = state.steps;
+        self.messages = state.messages;
+        (result, receiver.into_output())
+    }
+
     fn eval_field(&mut self, field: Field) {
         let record = self.take_val_register();
         let val = match (record, field) {
@@ -2168,6 +2653,18 @@ fn is_counting_call(name: &str) -> bool {
     )
 }
 
+/// Whether an item is declared in the `Std.Tasks` namespace, so that its task intrinsics aren't confused with
+/// intrinsics of the same name declared elsewhere.
+fn is_tasks_item(globals: &impl PackageStoreLookup, id: StoreItemId) -> bool {
+    globals.get_item(id).parent.is_some_and(|parent| {
+        let parent = globals.get_item(StoreItemId {
+            package: id.package,
+            item: parent,
+        });
+        matches!(&parent.kind, fir::ItemKind::Namespace(name, _) if name.name.as_ref() == "Std.Tasks")
+    })
+}
+
 fn make_counting_key(id: StoreItemId, functor: FunctorApp) -> CallableCountKey {
     (id, functor.adjoint, functor.controlled > 0)
 }
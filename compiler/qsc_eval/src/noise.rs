@@ -1,6 +1,9 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
+use rustc_hash::FxHashMap;
+use std::sync::Arc;
+
 #[derive(Copy, Clone, Debug)]
 pub struct PauliNoise {
     /// Pauli noise distribution for sampling.
@@ -33,8 +36,90 @@ impl PauliNoise {
         }
     }
 
+    /// Noise that replaces the state of a qubit with the maximally mixed state with probability `p`,
+    /// by applying X, Y or Z with probability `p / 3` each.
+    pub fn depolarizing(p: f64) -> Result<Self, String> {
+        Self::from_probabilities(p / 3.0, p / 3.0, p / 3.0)
+    }
+
+    /// Noise that applies X with probability `p`.
+    pub fn bit_flip(p: f64) -> Result<Self, String> {
+        Self::from_probabilities(p, 0.0, 0.0)
+    }
+
+    /// Noise that applies Z with probability `p`.
+    pub fn phase_flip(p: f64) -> Result<Self, String> {
+        Self::from_probabilities(0.0, 0.0, p)
+    }
+
     #[must_use]
     pub fn is_noiseless(&self) -> bool {
         self.distribution[2] <= f64::EPSILON
     }
+
+    /// The noise of applying this noise once for each unit of time over the given duration, where
+    /// fractional durations grow the noise smoothly between whole units.
+    #[must_use]
+    pub fn over_duration(&self, duration: f64) -> Self {
+        let [d0, d1, d2] = self.distribution;
+        let (px, py, pz) = (d0, d1 - d0, d2 - d1);
+        // Applying Pauli noise repeatedly multiplies the factors by which it scales the X, Y and Z
+        // components of the state, so the factors of the duration are powers of the factors of a unit.
+        // Noise that is more likely than not to flip a component has a negative factor, which has no
+        // fractional powers, so a fractional duration of it mixes that component completely.
+        let scale = |factor: f64| {
+            let scaled = factor.powf(duration);
+            if scaled.is_nan() {
+                0.0
+            } else {
+                scaled
+            }
+        };
+        let fx = scale(1.0 - 2.0 * (py + pz));
+        let fy = scale(1.0 - 2.0 * (px + pz));
+        let fz = scale(1.0 - 2.0 * (px + py));
+        let px = ((1.0 + fx - fy - fz) / 4.0).max(0.0);
+        let py = ((1.0 - fx + fy - fz) / 4.0).max(0.0);
+        let pz = ((1.0 - fx - fy + fz) / 4.0).max(0.0);
+        Self {
+            distribution: [px, px + py, px + py + pz],
+        }
+    }
+}
+
+/// Errors injected into a simulation as the program's gates and measurements are dispatched,
+/// so that a program can be run with noise without changing its source.
+#[derive(Clone, Debug, Default)]
+pub struct NoiseModel {
+    /// Pauli noise applied to each qubit a gate acts on, after the gate, for gates that don't have
+    /// noise of their own in `gates`.
+    pub gate: PauliNoise,
+    /// Pauli noise for specific gates, by the name of the gate, such as `"h"`, `"cx"` or `"reset"`.
+    pub gates: FxHashMap<Arc<str>, PauliNoise>,
+    /// Probability that a measurement reports the opposite of its actual outcome.
+    /// The qubit is still left in the state of the actual outcome.
+    pub measurement: f64,
+    /// Probability that a measured qubit turns out to have been lost, in which case the measurement
+    /// reports `Loss` and the qubit is replaced with a fresh one in the |0⟩ state.
+    pub loss: f64,
+    /// Pauli noise applied to each allocated qubit for every microsecond of a delay, such as
+    /// the delays of `DelayUs`.
+    pub idle: PauliNoise,
+}
+
+impl NoiseModel {
+    /// Noise applied after the gate with the given name.
+    #[must_use]
+    pub fn gate_noise(&self, name: &str) -> &PauliNoise {
+        self.gates.get(name).unwrap_or(&self.gate)
+    }
+
+    #[must_use]
+    pub fn is_noiseless(&self) -> bool {
+        self.gate.is_noiseless()
+            && self.gates.values().all(PauliNoise::is_noiseless)
+            && self.measurement <= f64::EPSILON
+            && self.loss <= f64::EPSILON
+            && self.idle.is_noiseless()
+    }
 }
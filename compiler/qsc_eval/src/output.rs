@@ -3,19 +3,57 @@
 
 use std::io::{Cursor, Write};
 
-use crate::state::{fmt_complex, format_state_id};
+use crate::{
+    state::{fmt_complex, format_state_id},
+    val::Value,
+};
 use num_bigint::BigUint;
 use num_complex::Complex64;
 
 #[derive(Copy, Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct Error;
 
+/// A snapshot of the quantum state, as output by `DumpMachine` or `DumpRegister`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StateSnapshot {
+    /// The ids of the qubits the state is over, in the order of the bits of each basis state label from left
+    /// to right.
+    pub qubits: Vec<usize>,
+    /// The amplitudes of the basis states that have a nonzero amplitude.
+    pub amplitudes: Vec<(BigUint, Complex64)>,
+}
+
+impl StateSnapshot {
+    #[must_use]
+    pub fn qubit_count(&self) -> usize {
+        self.qubits.len()
+    }
+
+    /// The amplitudes of the state, each with the label of its basis state, such as `|01⟩`.
+    #[must_use]
+    pub fn labeled_amplitudes(&self) -> Vec<(String, Complex64)> {
+        self.amplitudes
+            .iter()
+            .map(|(id, amplitude)| (format_state_id(id, self.qubit_count()), *amplitude))
+            .collect()
+    }
+}
+
 pub trait Receiver {
     /// Receive state output
     /// # Errors
     /// This will return an error if handling the output fails.
     fn state(&mut self, state: Vec<(BigUint, Complex64)>, qubit_count: usize) -> Result<(), Error>;
 
+    /// Receive state output along with the ids of the qubits it is over.
+    /// By default, the state is received as [`Receiver::state`] output.
+    /// # Errors
+    /// This will return an error if handling the output fails.
+    fn state_snapshot(&mut self, snapshot: StateSnapshot) -> Result<(), Error> {
+        let qubit_count = snapshot.qubit_count();
+        self.state(snapshot.amplitudes, qubit_count)
+    }
+
     /// Receive matrix output
     /// # Errors
     /// This will return an error if handling the output fails.
@@ -25,6 +63,83 @@ pub trait Receiver {
     /// # Errors
     /// This will return an error if handling the output fails.
     fn message(&mut self, msg: &str) -> Result<(), Error>;
+
+    /// Call the host function registered with `name`, which implements a function with the `@HostCallable()`
+    /// attribute. By default, no host functions are registered.
+    /// Returns None if no host function is registered with `name`.
+    /// Otherwise returns Some(Result), with the value returned by the host function or a message describing
+    /// why it failed.
+    fn host_call(&mut self, _name: &str, _arg: Value) -> Option<Result<Value, String>> {
+        None
+    }
+}
+
+/// Output held by a [`BufferedReceiver`] until it is replayed.
+pub(crate) enum BufferedOutput {
+    State(Vec<(BigUint, Complex64)>, usize),
+    StateSnapshot(StateSnapshot),
+    Matrix(Vec<Vec<Complex64>>),
+    Message(String),
+}
+
+impl BufferedOutput {
+    /// Replays the output to the given receiver, as if it had been received there in the first place.
+    pub(crate) fn replay(self, receiver: &mut impl Receiver) -> Result<(), Error> {
+        match self {
+            Self::State(state, qubit_count) => receiver.state(state, qubit_count),
+            Self::StateSnapshot(snapshot) => receiver.state_snapshot(snapshot),
+            Self::Matrix(matrix) => receiver.matrix(matrix),
+            Self::Message(msg) => receiver.message(&msg),
+        }
+    }
+}
+
+/// A receiver that holds on to the output it receives so that it can be replayed later, such as the output of a
+/// task, which is forwarded when the task is joined. Host functions are still called through the inner receiver
+/// right away, since their results are needed to continue. The inner receiver is a trait object so that a task
+/// that runs tasks of its own does not instantiate the evaluator with an ever deeper nesting of receivers.
+pub(crate) struct BufferedReceiver<'a> {
+    inner: &'a mut dyn Receiver,
+    output: Vec<BufferedOutput>,
+}
+
+impl<'a> BufferedReceiver<'a> {
+    pub(crate) fn new(inner: &'a mut dyn Receiver) -> Self {
+        Self {
+            inner,
+            output: Vec::new(),
+        }
+    }
+
+    pub(crate) fn into_output(self) -> Vec<BufferedOutput> {
+        self.output
+    }
+}
+
+impl Receiver for BufferedReceiver<'_> {
+    fn state(&mut self, state: Vec<(BigUint, Complex64)>, qubit_count: usize) -> Result<(), Error> {
+        self.output.push(BufferedOutput::State(state, qubit_count));
+        Ok(())
+    }
+
+    fn state_snapshot(&mut self, snapshot: StateSnapshot) -> Result<(), Error> {
+        self.output.push(BufferedOutput::StateSnapshot(snapshot));
+        Ok(())
+    }
+
+    fn matrix(&mut self, matrix: Vec<Vec<Complex64>>) -> Result<(), Error> {
+        self.output.push(BufferedOutput::Matrix(matrix));
+        Ok(())
+    }
+
+    fn message(&mut self, msg: &str) -> Result<(), Error> {
+        self.output.push(BufferedOutput::Message(msg.to_string()));
+        Ok(())
+    }
+
+    fn host_call(&mut self, name: &str, arg: Value) -> Option<Result<Value, String>> {
+        self.inner.host_call(name, arg)
+    }
 }
 
 pub struct GenericReceiver<'a> {
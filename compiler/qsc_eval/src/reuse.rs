@@ -0,0 +1,341 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! Analysis of how a program reuses its qubits, to help find logical bugs.
+//! The [`ReuseAnalyzer`] backend wraps the backend that simulates a shot and watches for two
+//! patterns that are usually mistakes: a qubit that is measured without being reset and then used
+//! in another gate, and a qubit that is released while it isn't in the |0⟩ state. The findings of
+//! each shot are gathered into a [`ReuseReport`], which aggregates them across shots into a list of
+//! [`ReuseWarning`]s.
+
+#[cfg(all(test, feature = "sparse-sim"))]
+mod tests;
+
+use crate::{
+    backend::{Backend, MemoryExceeded},
+    val::Value,
+};
+use num_bigint::BigUint;
+use num_complex::Complex;
+use qsc_fir::fir::Pauli;
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::fmt::{self, Display, Formatter};
+
+/// A pattern of qubit reuse that is usually a mistake.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ReuseIssue {
+    /// A qubit was measured with `M`, which leaves it in the measured state, and then used in a
+    /// gate while it was not in the |0⟩ state, without being reset first. Applying `X` is not
+    /// reported, since a measurement followed by a conditional `X` is how a qubit is reset by hand.
+    MeasuredWithoutReset,
+    /// A qubit was released while it was not in the |0⟩ state.
+    ReleasedNotZero,
+}
+
+impl Display for ReuseIssue {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            ReuseIssue::MeasuredWithoutReset => {
+                write!(f, "qubit used after measurement without reset")
+            }
+            ReuseIssue::ReleasedNotZero => write!(f, "qubit released in a non-zero state"),
+        }
+    }
+}
+
+/// An issue found on a qubit, aggregated across the shots it was found in.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReuseWarning {
+    pub issue: ReuseIssue,
+    /// The ID of the qubit the issue was found on.
+    pub qubit: usize,
+    /// The number of times the issue was found, over all shots.
+    pub occurrences: usize,
+    /// The number of shots the issue was found in.
+    pub shots: usize,
+}
+
+impl Display for ReuseWarning {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}: Qubit{} ({} times in {} shots)",
+            self.issue, self.qubit, self.occurrences, self.shots
+        )
+    }
+}
+
+/// The issues found in one shot, with the number of times each was found on each qubit.
+#[derive(Debug, Default)]
+pub struct ShotFindings {
+    occurrences: FxHashMap<(ReuseIssue, usize), usize>,
+}
+
+/// The issues found over a series of shots.
+#[derive(Debug, Default)]
+pub struct ReuseReport {
+    /// The number of occurrences and of shots of each issue on each qubit.
+    issues: FxHashMap<(ReuseIssue, usize), (usize, usize)>,
+    shots: usize,
+}
+
+impl ReuseReport {
+    /// Adds the findings of a shot to the report.
+    pub fn add_shot(&mut self, findings: ShotFindings) {
+        self.shots += 1;
+        for (key, occurrences) in findings.occurrences {
+            let entry = self.issues.entry(key).or_default();
+            entry.0 += occurrences;
+            entry.1 += 1;
+        }
+    }
+
+    /// The number of shots in the report.
+    #[must_use]
+    pub fn shots(&self) -> usize {
+        self.shots
+    }
+
+    /// The issues found, ordered by issue and then by qubit.
+    #[must_use]
+    pub fn warnings(&self) -> Vec<ReuseWarning> {
+        let mut warnings = self
+            .issues
+            .iter()
+            .map(|(&(issue, qubit), &(occurrences, shots))| ReuseWarning {
+                issue,
+                qubit,
+                occurrences,
+                shots,
+            })
+            .collect::<Vec<_>>();
+        warnings.sort_by_key(|warning| (warning.issue, warning.qubit));
+        warnings
+    }
+
+    pub fn clear(&mut self) {
+        self.issues.clear();
+        self.shots = 0;
+    }
+}
+
+/// A backend that passes every operation on to the backend it wraps while looking for issues in
+/// how the program reuses its qubits.
+pub struct ReuseAnalyzer<'a, B> {
+    backend: &'a mut B,
+    /// The qubits that were measured with `M` and haven't been reset since.
+    measured: FxHashSet<usize>,
+    findings: ShotFindings,
+}
+
+impl<'a, B: Backend> ReuseAnalyzer<'a, B> {
+    #[must_use]
+    pub fn new(backend: &'a mut B) -> Self {
+        Self {
+            backend,
+            measured: FxHashSet::default(),
+            findings: ShotFindings::default(),
+        }
+    }
+
+    /// The issues found so far.
+    #[must_use]
+    pub fn into_findings(self) -> ShotFindings {
+        self.findings
+    }
+
+    fn found(&mut self, issue: ReuseIssue, qubit: usize) {
+        *self.findings.occurrences.entry((issue, qubit)).or_default() += 1;
+    }
+
+    /// Checks the qubits a gate is applied to for measured qubits that weren't brought back to the
+    /// |0⟩ state, which the gate reuses.
+    fn used(&mut self, qubits: &[usize]) {
+        for &q in qubits {
+            if self.measured.remove(&q) && !self.backend.qubit_is_zero(q) {
+                self.found(ReuseIssue::MeasuredWithoutReset, q);
+            }
+        }
+    }
+}
+
+impl<B: Backend> Backend for ReuseAnalyzer<'_, B> {
+    type ResultType = B::ResultType;
+
+    fn ccx(&mut self, ctl0: usize, ctl1: usize, q: usize) {
+        self.used(&[ctl0, ctl1, q]);
+        self.backend.ccx(ctl0, ctl1, q);
+    }
+
+    fn cx(&mut self, ctl: usize, q: usize) {
+        self.used(&[ctl, q]);
+        self.backend.cx(ctl, q);
+    }
+
+    fn cy(&mut self, ctl: usize, q: usize) {
+        self.used(&[ctl, q]);
+        self.backend.cy(ctl, q);
+    }
+
+    fn cz(&mut self, ctl: usize, q: usize) {
+        self.used(&[ctl, q]);
+        self.backend.cz(ctl, q);
+    }
+
+    fn h(&mut self, q: usize) {
+        self.used(&[q]);
+        self.backend.h(q);
+    }
+
+    fn m(&mut self, q: usize) -> Self::ResultType {
+        self.measured.insert(q);
+        self.backend.m(q)
+    }
+
+    fn mresetz(&mut self, q: usize) -> Self::ResultType {
+        self.measured.remove(&q);
+        self.backend.mresetz(q)
+    }
+
+    fn reset(&mut self, q: usize) {
+        self.measured.remove(&q);
+        self.backend.reset(q);
+    }
+
+    fn rx(&mut self, theta: f64, q: usize) {
+        self.used(&[q]);
+        self.backend.rx(theta, q);
+    }
+
+    fn rxx(&mut self, theta: f64, q0: usize, q1: usize) {
+        self.used(&[q0, q1]);
+        self.backend.rxx(theta, q0, q1);
+    }
+
+    fn ry(&mut self, theta: f64, q: usize) {
+        self.used(&[q]);
+        self.backend.ry(theta, q);
+    }
+
+    fn ryy(&mut self, theta: f64, q0: usize, q1: usize) {
+        self.used(&[q0, q1]);
+        self.backend.ryy(theta, q0, q1);
+    }
+
+    fn rz(&mut self, theta: f64, q: usize) {
+        self.used(&[q]);
+        self.backend.rz(theta, q);
+    }
+
+    fn rzz(&mut self, theta: f64, q0: usize, q1: usize) {
+        self.used(&[q0, q1]);
+        self.backend.rzz(theta, q0, q1);
+    }
+
+    fn sadj(&mut self, q: usize) {
+        self.used(&[q]);
+        self.backend.sadj(q);
+    }
+
+    fn s(&mut self, q: usize) {
+        self.used(&[q]);
+        self.backend.s(q);
+    }
+
+    fn swap(&mut self, q0: usize, q1: usize) {
+        self.used(&[q0, q1]);
+        self.backend.swap(q0, q1);
+    }
+
+    fn tadj(&mut self, q: usize) {
+        self.used(&[q]);
+        self.backend.tadj(q);
+    }
+
+    fn t(&mut self, q: usize) {
+        self.used(&[q]);
+        self.backend.t(q);
+    }
+
+    fn x(&mut self, q: usize) {
+        // A measured qubit stays measured, since `X` after a measurement is the usual way to
+        // reset a qubit by hand. The next gate finds out whether it was reset.
+        self.backend.x(q);
+    }
+
+    fn y(&mut self, q: usize) {
+        self.used(&[q]);
+        self.backend.y(q);
+    }
+
+    fn z(&mut self, q: usize) {
+        self.used(&[q]);
+        self.backend.z(q);
+    }
+
+    fn qubit_allocate(&mut self) -> usize {
+        let q = self.backend.qubit_allocate();
+        self.measured.remove(&q);
+        q
+    }
+
+    fn qubit_release(&mut self, q: usize) -> bool {
+        self.measured.remove(&q);
+        if !self.backend.qubit_is_zero(q) {
+            self.found(ReuseIssue::ReleasedNotZero, q);
+        }
+        self.backend.qubit_release(q)
+    }
+
+    fn qubit_swap_id(&mut self, q0: usize, q1: usize) {
+        let measured0 = self.measured.remove(&q0);
+        let measured1 = self.measured.remove(&q1);
+        if measured0 {
+            self.measured.insert(q1);
+        }
+        if measured1 {
+            self.measured.insert(q0);
+        }
+        self.backend.qubit_swap_id(q0, q1);
+    }
+
+    fn capture_quantum_state(&mut self) -> (Vec<(BigUint, Complex<f64>)>, usize) {
+        self.backend.capture_quantum_state()
+    }
+
+    fn set_quantum_state(
+        &mut self,
+        state: &[(BigUint, Complex<f64>)],
+        qubits: &[usize],
+    ) -> Result<(), String> {
+        self.backend.set_quantum_state(state, qubits)
+    }
+
+    fn qubit_is_zero(&mut self, q: usize) -> bool {
+        self.backend.qubit_is_zero(q)
+    }
+
+    fn expectation_value(&mut self, paulis: &[(Pauli, usize)]) -> f64 {
+        self.backend.expectation_value(paulis)
+    }
+
+    fn custom_intrinsic(&mut self, name: &str, arg: Value) -> Option<Result<Value, String>> {
+        self.backend.custom_intrinsic(name, arg)
+    }
+
+    fn set_seed(&mut self, seed: Option<u64>) {
+        self.backend.set_seed(seed);
+    }
+
+    fn delay(&mut self, duration_us: f64, qubits: &[usize]) {
+        self.backend.delay(duration_us, qubits);
+    }
+
+    fn take_failure(&mut self) -> Option<String> {
+        self.backend.take_failure()
+    }
+
+    fn take_memory_exceeded(&mut self) -> Option<MemoryExceeded> {
+        self.backend.take_memory_exceeded()
+    }
+}
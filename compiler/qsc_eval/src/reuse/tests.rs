@@ -0,0 +1,83 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{ReuseAnalyzer, ReuseIssue, ReuseReport, ReuseWarning};
+use crate::backend::{Backend, SparseSim};
+
+#[test]
+fn gate_after_measurement_of_one_is_reported() {
+    let mut sim = SparseSim::new();
+    let mut analyzer = ReuseAnalyzer::new(&mut sim);
+    let q = analyzer.qubit_allocate();
+    analyzer.x(q);
+    assert!(analyzer.m(q));
+    analyzer.h(q);
+    analyzer.h(q);
+    analyzer.x(q);
+    assert!(analyzer.qubit_release(q));
+
+    let mut report = ReuseReport::default();
+    report.add_shot(analyzer.into_findings());
+    assert_eq!(
+        report.warnings(),
+        vec![ReuseWarning {
+            issue: ReuseIssue::MeasuredWithoutReset,
+            qubit: q,
+            occurrences: 1,
+            shots: 1,
+        }]
+    );
+}
+
+#[test]
+fn manual_and_explicit_resets_are_not_reported() {
+    let mut sim = SparseSim::new();
+    let mut analyzer = ReuseAnalyzer::new(&mut sim);
+    let q0 = analyzer.qubit_allocate();
+    let q1 = analyzer.qubit_allocate();
+    analyzer.x(q0);
+    analyzer.x(q1);
+    if analyzer.m(q0) {
+        analyzer.x(q0);
+    }
+    analyzer.h(q0);
+    analyzer.m(q1);
+    analyzer.reset(q1);
+    analyzer.h(q1);
+    analyzer.mresetz(q0);
+    analyzer.mresetz(q1);
+    analyzer.qubit_release(q0);
+    analyzer.qubit_release(q1);
+
+    let mut report = ReuseReport::default();
+    report.add_shot(analyzer.into_findings());
+    assert!(report.warnings().is_empty());
+    assert_eq!(report.shots(), 1);
+}
+
+#[test]
+fn releases_in_non_zero_state_are_aggregated_across_shots() {
+    let mut report = ReuseReport::default();
+    for flip in [true, false, true] {
+        let mut sim = SparseSim::new();
+        let mut analyzer = ReuseAnalyzer::new(&mut sim);
+        let q = analyzer.qubit_allocate();
+        if flip {
+            analyzer.x(q);
+        }
+        assert_eq!(analyzer.qubit_release(q), !flip);
+        report.add_shot(analyzer.into_findings());
+    }
+    assert_eq!(
+        report
+            .warnings()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+        vec!["qubit released in a non-zero state: Qubit0 (2 times in 2 shots)"]
+    );
+    assert_eq!(report.shots(), 3);
+
+    report.clear();
+    assert!(report.warnings().is_empty());
+}
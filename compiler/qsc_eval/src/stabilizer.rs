@@ -0,0 +1,1050 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! Stabilizer simulation of Clifford circuits.
+//! The [`StabilizerSim`] backend tracks the state as a CHP tableau (Aaronson and Gottesman,
+//! "Improved simulation of stabilizer circuits", 2004), which takes memory quadratic in the number
+//! of qubits rather than exponential, so programs made only of Clifford operations can use
+//! thousands of qubits. Applying a non-Clifford operation fails the intrinsic that called it.
+//! The [`AutoSim`] backend starts in stabilizer mode as well, but instead of failing on a
+//! non-Clifford operation it prepares the current state in a [`SparseSim`] and continues there.
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "sparse-sim")]
+use crate::backend::MemoryExceeded;
+#[cfg(feature = "sparse-sim")]
+use crate::backend::SparseSim;
+use crate::{backend::Backend, noise::PauliNoise, val::Value};
+use num_bigint::BigUint;
+use num_complex::Complex;
+#[cfg(feature = "sparse-sim")]
+use qsc_fir::fir::Pauli;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rustc_hash::FxHashMap;
+use std::{
+    collections::BTreeSet,
+    f64::consts::{FRAC_1_SQRT_2, FRAC_PI_2},
+};
+
+/// Rotation angles within this many quarter turns of a multiple of π/2 are treated as Clifford.
+const ANGLE_TOLERANCE: f64 = 1e-9;
+
+/// Amplitudes with a squared norm of at most this value are dropped from a captured state.
+const AMPLITUDE_THRESHOLD: f64 = 1e-20;
+
+const NON_CLIFFORD_GATE: &str = "the stabilizer simulator can only apply Clifford operations";
+const NON_CLIFFORD_ANGLE: &str =
+    "the stabilizer simulator can only apply rotations by multiples of π/2";
+
+/// A Pauli operator on every qubit of the tableau, with a sign, stored as bit vectors.
+#[derive(Clone, Debug)]
+struct PauliRow {
+    x: Vec<u64>,
+    z: Vec<u64>,
+    negative: bool,
+}
+
+impl PauliRow {
+    fn identity(words: usize) -> Self {
+        Self {
+            x: vec![0; words],
+            z: vec![0; words],
+            negative: false,
+        }
+    }
+
+    fn x(&self, q: usize) -> bool {
+        self.x[q / 64] >> (q % 64) & 1 == 1
+    }
+
+    fn z(&self, q: usize) -> bool {
+        self.z[q / 64] >> (q % 64) & 1 == 1
+    }
+
+    fn set_x(&mut self, q: usize, value: bool) {
+        set_bit(&mut self.x, q, value);
+    }
+
+    fn set_z(&mut self, q: usize, value: bool) {
+        set_bit(&mut self.z, q, value);
+    }
+
+    fn is_z_on(&self, q: usize) -> bool {
+        self.x.iter().all(|word| *word == 0)
+            && self
+                .z
+                .iter()
+                .enumerate()
+                .all(|(i, word)| *word == if i == q / 64 { 1 << (q % 64) } else { 0 })
+    }
+
+    /// Replaces this operator with `other` times this operator. The operators must commute, so
+    /// the product is Hermitian and its phase is a sign.
+    fn multiply_by(&mut self, other: &PauliRow) {
+        // Count the factors of i and -i that come from multiplying the Paulis on each qubit.
+        let mut phase = 0i64;
+        for word in 0..self.x.len() {
+            let (x1, z1, x2, z2) = (other.x[word], other.z[word], self.x[word], self.z[word]);
+            let (px1, py1, pz1) = (x1 & !z1, x1 & z1, !x1 & z1);
+            let (px2, py2, pz2) = (x2 & !z2, x2 & z2, !x2 & z2);
+            let plus = (px1 & py2) | (py1 & pz2) | (pz1 & px2);
+            let minus = (px1 & pz2) | (py1 & px2) | (pz1 & py2);
+            phase += i64::from(plus.count_ones()) - i64::from(minus.count_ones());
+            self.x[word] ^= x1;
+            self.z[word] ^= z1;
+        }
+        phase += 2 * i64::from(self.negative) + 2 * i64::from(other.negative);
+        debug_assert!(
+            phase % 2 == 0,
+            "product of commuting Paulis should be Hermitian"
+        );
+        self.negative = phase.rem_euclid(4) == 2;
+    }
+
+    fn apply(&mut self, gate: Gate) {
+        match gate {
+            Gate::H(q) => {
+                let (x, z) = (self.x(q), self.z(q));
+                self.negative ^= x && z;
+                self.set_x(q, z);
+                self.set_z(q, x);
+            }
+            Gate::S(q) => {
+                let (x, z) = (self.x(q), self.z(q));
+                self.negative ^= x && z;
+                self.set_z(q, x ^ z);
+            }
+            Gate::Sadj(q) => {
+                let (x, z) = (self.x(q), self.z(q));
+                self.negative ^= x && !z;
+                self.set_z(q, x ^ z);
+            }
+            Gate::X(q) => self.negative ^= self.z(q),
+            Gate::Y(q) => self.negative ^= self.x(q) ^ self.z(q),
+            Gate::Z(q) => self.negative ^= self.x(q),
+            Gate::Cx(ctl, q) => {
+                let (xc, zc, xt, zt) = (self.x(ctl), self.z(ctl), self.x(q), self.z(q));
+                self.negative ^= xc && zt && !(xt ^ zc);
+                self.set_x(q, xt ^ xc);
+                self.set_z(ctl, zc ^ zt);
+            }
+            Gate::Cz(ctl, q) => {
+                self.apply(Gate::H(q));
+                self.apply(Gate::Cx(ctl, q));
+                self.apply(Gate::H(q));
+            }
+            Gate::Swap(q0, q1) => {
+                let (x0, z0, x1, z1) = (self.x(q0), self.z(q0), self.x(q1), self.z(q1));
+                self.set_x(q0, x1);
+                self.set_z(q0, z1);
+                self.set_x(q1, x0);
+                self.set_z(q1, z0);
+            }
+        }
+    }
+}
+
+fn set_bit(words: &mut [u64], q: usize, value: bool) {
+    if value {
+        words[q / 64] |= 1 << (q % 64);
+    } else {
+        words[q / 64] &= !(1 << (q % 64));
+    }
+}
+
+/// A Clifford gate that the tableau can apply directly.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Gate {
+    H(usize),
+    S(usize),
+    Sadj(usize),
+    X(usize),
+    Y(usize),
+    Z(usize),
+    Cx(usize, usize),
+    Cz(usize, usize),
+    Swap(usize, usize),
+}
+
+impl Gate {
+    fn adjoint(self) -> Self {
+        match self {
+            Gate::S(q) => Gate::Sadj(q),
+            Gate::Sadj(q) => Gate::S(q),
+            gate => gate,
+        }
+    }
+}
+
+/// The stabilizer tableau of a state: the stabilizer generators, which fix the state, and the
+/// destabilizers, which pair up with them and make measurement cheaper.
+#[derive(Clone, Debug, Default)]
+struct Tableau {
+    qubits: usize,
+    destabilizers: Vec<PauliRow>,
+    stabilizers: Vec<PauliRow>,
+}
+
+impl Tableau {
+    /// Adds a qubit in the |0⟩ state and returns its index.
+    fn add_qubit(&mut self) -> usize {
+        let q = self.qubits;
+        self.qubits += 1;
+        let words = self.qubits.div_ceil(64);
+        if self.stabilizers.first().map_or(0, |row| row.x.len()) < words {
+            for row in self.rows_mut() {
+                row.x.push(0);
+                row.z.push(0);
+            }
+        }
+        let mut destabilizer = PauliRow::identity(words);
+        destabilizer.set_x(q, true);
+        self.destabilizers.push(destabilizer);
+        let mut stabilizer = PauliRow::identity(words);
+        stabilizer.set_z(q, true);
+        self.stabilizers.push(stabilizer);
+        q
+    }
+
+    fn rows_mut(&mut self) -> impl Iterator<Item = &mut PauliRow> {
+        self.destabilizers
+            .iter_mut()
+            .chain(self.stabilizers.iter_mut())
+    }
+
+    fn apply(&mut self, gate: Gate) {
+        for row in self.rows_mut() {
+            row.apply(gate);
+        }
+    }
+
+    /// Measures the qubit in the Z basis, using `random_outcome` if the outcome isn't determined.
+    fn measure(&mut self, q: usize, random_outcome: impl FnOnce() -> bool) -> bool {
+        let Some(pivot) = self.stabilizers.iter().position(|row| row.x(q)) else {
+            return self.deterministic_outcome(q);
+        };
+
+        // The outcome is random. Every other generator that anticommutes with Z on the qubit is
+        // multiplied by the pivot, which leaves the pivot as the only one to replace with ±Z.
+        let pivot_row = self.stabilizers[pivot].clone();
+        for (i, row) in self.destabilizers.iter_mut().enumerate() {
+            if i != pivot && row.x(q) {
+                row.multiply_by(&pivot_row);
+            }
+        }
+        for (i, row) in self.stabilizers.iter_mut().enumerate() {
+            if i != pivot && row.x(q) {
+                row.multiply_by(&pivot_row);
+            }
+        }
+        let outcome = random_outcome();
+        let mut measured = PauliRow::identity(pivot_row.x.len());
+        measured.set_z(q, true);
+        measured.negative = outcome;
+        self.destabilizers[pivot] = pivot_row;
+        self.stabilizers[pivot] = measured;
+        outcome
+    }
+
+    /// The outcome of measuring the qubit in the Z basis, which must be determined by the
+    /// stabilizers. The Z operator on the qubit is the product of the stabilizers whose
+    /// destabilizers anticommute with it, and the sign of that product is the outcome.
+    fn deterministic_outcome(&self, q: usize) -> bool {
+        let mut product = PauliRow::identity(self.stabilizers.first().map_or(0, |row| row.x.len()));
+        for (destabilizer, stabilizer) in self.destabilizers.iter().zip(&self.stabilizers) {
+            if destabilizer.x(q) {
+                product.multiply_by(stabilizer);
+            }
+        }
+        product.negative
+    }
+
+    /// Whether measuring the qubit in the Z basis certainly gives zero.
+    fn is_zero(&self, q: usize) -> bool {
+        !self.stabilizers.iter().any(|row| row.x(q)) && !self.deterministic_outcome(q)
+    }
+
+    /// Finds Clifford gates that prepare the state from |0...0⟩. The stabilizers are reduced to
+    /// ±Z on each qubit one at a time, so the gates that undo the reduction, applied after X on
+    /// the qubits with a negative sign, prepare the state up to a global phase.
+    fn preparation(&self) -> Vec<Gate> {
+        let n = self.qubits;
+        let mut rows = self.stabilizers.clone();
+        let mut reduction = Vec::new();
+        let mut apply = |rows: &mut [PauliRow], gate: Gate| {
+            for row in rows.iter_mut() {
+                row.apply(gate);
+            }
+            reduction.push(gate);
+        };
+
+        for i in 0..n {
+            // The generators before `i` are ±Z on the qubits before `i`, and no other generator
+            // acts on those qubits, so the search only looks at the remaining block.
+            let pivot = |has_bit: fn(&PauliRow, usize) -> bool| {
+                (i..n).find_map(|r| (i..n).find(|&q| has_bit(&rows[r], q)).map(|q| (r, q)))
+            };
+            if let Some((r, q)) = pivot(PauliRow::x) {
+                rows.swap(i, r);
+                if q != i {
+                    apply(&mut rows, Gate::Swap(i, q));
+                }
+                for q in i + 1..n {
+                    if rows[i].x(q) {
+                        apply(&mut rows, Gate::Cx(i, q));
+                    }
+                }
+                if rows[i].z(i) {
+                    apply(&mut rows, Gate::S(i));
+                }
+                for q in i + 1..n {
+                    if rows[i].z(q) {
+                        apply(&mut rows, Gate::Cz(i, q));
+                    }
+                }
+                apply(&mut rows, Gate::H(i));
+            } else if let Some((r, q)) = pivot(PauliRow::z) {
+                rows.swap(i, r);
+                if q != i {
+                    apply(&mut rows, Gate::Swap(i, q));
+                }
+                for q in i + 1..n {
+                    if rows[i].z(q) {
+                        apply(&mut rows, Gate::Cx(q, i));
+                    }
+                }
+            } else {
+                unreachable!("stabilizer generators should be independent");
+            }
+
+            debug_assert!(rows[i].is_z_on(i), "generator should be reduced to ±Z");
+            let reduced = rows[i].clone();
+            for (r, row) in rows.iter_mut().enumerate() {
+                if r != i && row.z(i) {
+                    row.multiply_by(&reduced);
+                }
+            }
+        }
+
+        let mut preparation = (0..n)
+            .filter(|&q| rows[q].negative)
+            .map(Gate::X)
+            .collect::<Vec<_>>();
+        preparation.extend(reduction.into_iter().rev().map(Gate::adjoint));
+        preparation
+    }
+}
+
+/// The number of quarter turns, from 0 to 3, that a rotation by `theta` makes, or `None` if it
+/// isn't a multiple of π/2.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn quarter_turns(theta: f64) -> Option<u8> {
+    let turns = theta / FRAC_PI_2;
+    let rounded = turns.round();
+    ((turns - rounded).abs() <= ANGLE_TOLERANCE).then(|| rounded.rem_euclid(4.0) as u8)
+}
+
+/// The control qubits and angle of a `GlobalPhase` intrinsic call.
+fn global_phase_arg(arg: &Value) -> (Vec<usize>, f64) {
+    let [ctls, theta] = &*arg.clone().unwrap_tuple() else {
+        panic!("tuple arity for GlobalPhase intrinsic should be 2");
+    };
+    let ctls = ctls
+        .clone()
+        .unwrap_array()
+        .iter()
+        .map(|q| q.clone().unwrap_qubit().deref().0)
+        .collect();
+    (ctls, theta.get_double())
+}
+
+/// The gates that apply a phase of `theta` when all of the controls are one, or `None` if that
+/// isn't a Clifford operation.
+fn global_phase_gates(ctls: &[usize], theta: f64) -> Option<Vec<Gate>> {
+    match (ctls, quarter_turns(theta)?) {
+        (_, 0) | ([], _) => Some(Vec::new()),
+        ([q], 1) => Some(vec![Gate::S(*q)]),
+        ([q], 2) => Some(vec![Gate::Z(*q)]),
+        ([q], _) => Some(vec![Gate::Sadj(*q)]),
+        ([q0, q1], 2) => Some(vec![Gate::Cz(*q0, *q1)]),
+        _ => None,
+    }
+}
+
+/// Backend that simulates Clifford operations on a stabilizer tableau. Non-Clifford operations,
+/// such as T gates, rotations by angles that aren't multiples of π/2 and custom unitaries, aren't
+/// applied and fail the intrinsic that called them.
+pub struct StabilizerSim {
+    tableau: Tableau,
+    free_qubits: BTreeSet<usize>,
+    /// Pauli noise that is applied after a gate or before a measurement is executed.
+    noise: PauliNoise,
+    /// Random number generator for measurement outcomes and noise.
+    rng: StdRng,
+    failure: Option<String>,
+}
+
+impl Default for StabilizerSim {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StabilizerSim {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            tableau: Tableau::default(),
+            free_qubits: BTreeSet::new(),
+            noise: PauliNoise::default(),
+            rng: StdRng::from_entropy(),
+            failure: None,
+        }
+    }
+
+    #[must_use]
+    pub fn new_with_noise(noise: &PauliNoise) -> Self {
+        let mut sim = Self::new();
+        sim.noise = *noise;
+        sim
+    }
+
+    fn gate(&mut self, gate: Gate) {
+        self.tableau.apply(gate);
+        match gate {
+            Gate::Cx(q0, q1) | Gate::Cz(q0, q1) | Gate::Swap(q0, q1) => {
+                self.apply_noise(q0);
+                self.apply_noise(q1);
+            }
+            Gate::H(q) | Gate::S(q) | Gate::Sadj(q) | Gate::X(q) | Gate::Y(q) | Gate::Z(q) => {
+                self.apply_noise(q);
+            }
+        }
+    }
+
+    fn gates(&mut self, gates: &[Gate]) {
+        for gate in gates {
+            self.tableau.apply(*gate);
+        }
+    }
+
+    /// Applies the rotation `exp(-iθ/2 Z)`, without noise, or records a failure if it isn't
+    /// Clifford.
+    fn rotate_z(&mut self, theta: f64, q: usize) {
+        match quarter_turns(theta) {
+            Some(0) => {}
+            Some(1) => self.tableau.apply(Gate::S(q)),
+            Some(2) => self.tableau.apply(Gate::Z(q)),
+            Some(_) => self.tableau.apply(Gate::Sadj(q)),
+            None => self.fail(NON_CLIFFORD_ANGLE),
+        }
+    }
+
+    /// Applies the rotation `exp(-iθ/2 Z⊗Z)`, without noise, or records a failure if it isn't
+    /// Clifford.
+    fn rotate_zz(&mut self, theta: f64, q0: usize, q1: usize) {
+        if quarter_turns(theta).is_none() {
+            self.fail(NON_CLIFFORD_ANGLE);
+            return;
+        }
+        self.tableau.apply(Gate::Cx(q1, q0));
+        self.rotate_z(theta, q0);
+        self.tableau.apply(Gate::Cx(q1, q0));
+    }
+
+    fn fail(&mut self, message: &str) {
+        self.failure = Some(message.to_string());
+    }
+
+    fn measure(&mut self, q: usize) -> bool {
+        let rng = &mut self.rng;
+        self.tableau.measure(q, || rng.gen())
+    }
+
+    fn apply_noise(&mut self, q: usize) {
+        if self.noise.is_noiseless() {
+            return;
+        }
+        let p = self.rng.gen_range(0.0..1.0);
+        if p >= self.noise.distribution[2] {
+            // In the most common case we don't apply noise
+        } else if p < self.noise.distribution[0] {
+            self.tableau.apply(Gate::X(q));
+        } else if p < self.noise.distribution[1] {
+            self.tableau.apply(Gate::Y(q));
+        } else {
+            self.tableau.apply(Gate::Z(q));
+        }
+    }
+}
+
+impl Backend for StabilizerSim {
+    type ResultType = bool;
+
+    fn ccx(&mut self, _ctl0: usize, _ctl1: usize, _q: usize) {
+        self.fail(NON_CLIFFORD_GATE);
+    }
+
+    fn cx(&mut self, ctl: usize, q: usize) {
+        self.gate(Gate::Cx(ctl, q));
+    }
+
+    fn cy(&mut self, ctl: usize, q: usize) {
+        self.gates(&[Gate::Sadj(q), Gate::Cx(ctl, q), Gate::S(q)]);
+        self.apply_noise(ctl);
+        self.apply_noise(q);
+    }
+
+    fn cz(&mut self, ctl: usize, q: usize) {
+        self.gate(Gate::Cz(ctl, q));
+    }
+
+    fn h(&mut self, q: usize) {
+        self.gate(Gate::H(q));
+    }
+
+    fn m(&mut self, q: usize) -> Self::ResultType {
+        self.apply_noise(q);
+        self.measure(q)
+    }
+
+    fn mresetz(&mut self, q: usize) -> Self::ResultType {
+        self.apply_noise(q); // Applying noise before measurement
+        let res = self.measure(q);
+        if res {
+            self.tableau.apply(Gate::X(q));
+        }
+        self.apply_noise(q); // Applying noise after reset
+        res
+    }
+
+    fn reset(&mut self, q: usize) {
+        self.mresetz(q);
+        // Noise applied in mresetz.
+    }
+
+    fn rx(&mut self, theta: f64, q: usize) {
+        if quarter_turns(theta).is_none() {
+            self.fail(NON_CLIFFORD_ANGLE);
+            return;
+        }
+        self.tableau.apply(Gate::H(q));
+        self.rotate_z(theta, q);
+        self.tableau.apply(Gate::H(q));
+        self.apply_noise(q);
+    }
+
+    fn rxx(&mut self, theta: f64, q0: usize, q1: usize) {
+        if quarter_turns(theta).is_none() {
+            self.fail(NON_CLIFFORD_ANGLE);
+            return;
+        }
+        self.gates(&[Gate::H(q0), Gate::H(q1)]);
+        self.rotate_zz(theta, q0, q1);
+        self.gates(&[Gate::H(q1), Gate::H(q0)]);
+        self.apply_noise(q0);
+        self.apply_noise(q1);
+    }
+
+    fn ry(&mut self, theta: f64, q: usize) {
+        if quarter_turns(theta).is_none() {
+            self.fail(NON_CLIFFORD_ANGLE);
+            return;
+        }
+        self.gates(&[Gate::Sadj(q), Gate::H(q)]);
+        self.rotate_z(theta, q);
+        self.gates(&[Gate::H(q), Gate::S(q)]);
+        self.apply_noise(q);
+    }
+
+    fn ryy(&mut self, theta: f64, q0: usize, q1: usize) {
+        if quarter_turns(theta).is_none() {
+            self.fail(NON_CLIFFORD_ANGLE);
+            return;
+        }
+        self.gates(&[Gate::Sadj(q0), Gate::H(q0), Gate::Sadj(q1), Gate::H(q1)]);
+        self.rotate_zz(theta, q0, q1);
+        self.gates(&[Gate::H(q1), Gate::S(q1), Gate::H(q0), Gate::S(q0)]);
+        self.apply_noise(q0);
+        self.apply_noise(q1);
+    }
+
+    fn rz(&mut self, theta: f64, q: usize) {
+        if quarter_turns(theta).is_none() {
+            self.fail(NON_CLIFFORD_ANGLE);
+            return;
+        }
+        self.rotate_z(theta, q);
+        self.apply_noise(q);
+    }
+
+    fn rzz(&mut self, theta: f64, q0: usize, q1: usize) {
+        if quarter_turns(theta).is_none() {
+            self.fail(NON_CLIFFORD_ANGLE);
+            return;
+        }
+        self.rotate_zz(theta, q0, q1);
+        self.apply_noise(q0);
+        self.apply_noise(q1);
+    }
+
+    fn sadj(&mut self, q: usize) {
+        self.gate(Gate::Sadj(q));
+    }
+
+    fn s(&mut self, q: usize) {
+        self.gate(Gate::S(q));
+    }
+
+    fn swap(&mut self, q0: usize, q1: usize) {
+        self.gate(Gate::Swap(q0, q1));
+    }
+
+    fn tadj(&mut self, _q: usize) {
+        self.fail(NON_CLIFFORD_GATE);
+    }
+
+    fn t(&mut self, _q: usize) {
+        self.fail(NON_CLIFFORD_GATE);
+    }
+
+    fn x(&mut self, q: usize) {
+        self.gate(Gate::X(q));
+    }
+
+    fn y(&mut self, q: usize) {
+        self.gate(Gate::Y(q));
+    }
+
+    fn z(&mut self, q: usize) {
+        self.gate(Gate::Z(q));
+    }
+
+    fn qubit_allocate(&mut self) -> usize {
+        // Fresh qubit start in ground state even with noise.
+        self.free_qubits
+            .pop_first()
+            .unwrap_or_else(|| self.tableau.add_qubit())
+    }
+
+    fn qubit_release(&mut self, q: usize) -> bool {
+        let was_zero = self.noise.is_noiseless() && self.tableau.is_zero(q);
+        // Released qubits are returned to |0⟩ so they can be handed out again as fresh qubits.
+        if self.measure(q) {
+            self.tableau.apply(Gate::X(q));
+        }
+        self.free_qubits.insert(q);
+        was_zero || !self.noise.is_noiseless()
+    }
+
+    fn qubit_swap_id(&mut self, q0: usize, q1: usize) {
+        // This is a service function rather than a gate so it doesn't incur noise.
+        self.tableau.apply(Gate::Swap(q0, q1));
+    }
+
+    /// Computes the state vector of the qubits in use. The stabilizers don't fix the global
+    /// phase, so the state is reported with its first amplitude real and positive.
+    fn capture_quantum_state(&mut self) -> (Vec<(BigUint, Complex<f64>)>, usize) {
+        let mut state = FxHashMap::default();
+        state.insert(BigUint::default(), Complex::new(1.0, 0.0));
+        for gate in self.tableau.preparation() {
+            state = apply_to_state(state, gate);
+        }
+
+        let in_use = (0..self.tableau.qubits)
+            .filter(|q| !self.free_qubits.contains(q))
+            .collect::<Vec<_>>();
+        let count = in_use.len();
+        let mut state = state
+            .into_iter()
+            .filter(|(_, amplitude)| amplitude.norm_sqr() > AMPLITUDE_THRESHOLD)
+            .map(|(slots, amplitude)| {
+                // The first qubit in use is the most significant bit of the index.
+                let mut index = BigUint::default();
+                for (i, q) in in_use.iter().enumerate() {
+                    if slots.bit(*q as u64) {
+                        index.set_bit((count - 1 - i) as u64, true);
+                    }
+                }
+                (index, amplitude)
+            })
+            .collect::<Vec<_>>();
+        state.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        if let Some((_, first)) = state.first() {
+            let phase = first.conj() / first.norm();
+            for (_, amplitude) in &mut state {
+                *amplitude *= phase;
+            }
+        }
+        (state, count)
+    }
+
+    fn qubit_is_zero(&mut self, q: usize) -> bool {
+        // This is a service function rather than a measurement so it doesn't incur noise.
+        self.tableau.is_zero(q)
+    }
+
+    fn custom_intrinsic(&mut self, name: &str, arg: Value) -> Option<Result<Value, String>> {
+        // These intrinsics aren't subject to noise.
+        match name {
+            "GlobalPhase" => {
+                let (ctls, theta) = global_phase_arg(&arg);
+                match global_phase_gates(&ctls, theta) {
+                    Some(gates) => {
+                        self.gates(&gates);
+                        Some(Ok(Value::unit()))
+                    }
+                    None => Some(Err(NON_CLIFFORD_GATE.to_string())),
+                }
+            }
+            "BeginEstimateCaching" => Some(Ok(Value::Bool(true))),
+            "EndEstimateCaching"
+            | "AccountForEstimatesInternal"
+            | "BeginRepeatEstimatesInternal"
+            | "EndRepeatEstimatesInternal" => Some(Ok(Value::unit())),
+            "ConfigurePauliNoise" => {
+                let [xv, yv, zv] = &*arg.unwrap_tuple() else {
+                    panic!("tuple arity for ConfigurePauliNoise intrinsic should be 3");
+                };
+                match PauliNoise::from_probabilities(
+                    xv.get_double(),
+                    yv.get_double(),
+                    zv.get_double(),
+                ) {
+                    Ok(noise) => {
+                        self.noise = noise;
+                        Some(Ok(Value::unit()))
+                    }
+                    Err(message) => Some(Err(message)),
+                }
+            }
+            "ApplyIdleNoise" => {
+                let q = arg.unwrap_qubit().deref().0;
+                self.apply_noise(q);
+                Some(Ok(Value::unit()))
+            }
+            "Apply" => Some(Err(NON_CLIFFORD_GATE.to_string())),
+            _ => None,
+        }
+    }
+
+    fn set_seed(&mut self, seed: Option<u64>) {
+        self.rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+    }
+
+    fn take_failure(&mut self) -> Option<String> {
+        self.failure.take()
+    }
+}
+
+/// Applies a Clifford gate to a sparse state vector indexed by qubit.
+fn apply_to_state(
+    state: FxHashMap<BigUint, Complex<f64>>,
+    gate: Gate,
+) -> FxHashMap<BigUint, Complex<f64>> {
+    let bit = |index: &BigUint, q: usize| index.bit(q as u64);
+    let flip = |index: &mut BigUint, q: usize| index.set_bit(q as u64, !index.bit(q as u64));
+    let mut new_state: FxHashMap<BigUint, Complex<f64>> = FxHashMap::default();
+    for (mut index, amplitude) in state {
+        let amplitude = match gate {
+            Gate::H(q) => {
+                // H maps |b⟩ to (|0⟩ + (-1)^b |1⟩)/√2.
+                let sign = if bit(&index, q) { -1.0 } else { 1.0 };
+                let mut other = index.clone();
+                index.set_bit(q as u64, false);
+                other.set_bit(q as u64, true);
+                *new_state.entry(other).or_default() += amplitude * sign * FRAC_1_SQRT_2;
+                amplitude * FRAC_1_SQRT_2
+            }
+            Gate::S(q) if bit(&index, q) => amplitude * Complex::i(),
+            Gate::Sadj(q) if bit(&index, q) => -amplitude * Complex::i(),
+            Gate::Z(q) if bit(&index, q) => -amplitude,
+            Gate::Cz(q0, q1) if bit(&index, q0) && bit(&index, q1) => -amplitude,
+            Gate::X(q) => {
+                flip(&mut index, q);
+                amplitude
+            }
+            Gate::Y(q) => {
+                let factor = if bit(&index, q) {
+                    -Complex::i()
+                } else {
+                    Complex::i()
+                };
+                flip(&mut index, q);
+                amplitude * factor
+            }
+            Gate::Cx(ctl, q) => {
+                if bit(&index, ctl) {
+                    flip(&mut index, q);
+                }
+                amplitude
+            }
+            Gate::Swap(q0, q1) => {
+                let (b0, b1) = (bit(&index, q0), bit(&index, q1));
+                index.set_bit(q0 as u64, b1);
+                index.set_bit(q1 as u64, b0);
+                amplitude
+            }
+            Gate::S(_) | Gate::Sadj(_) | Gate::Z(_) | Gate::Cz(..) => amplitude,
+        };
+        *new_state.entry(index).or_default() += amplitude;
+    }
+    new_state.retain(|_, amplitude| amplitude.norm_sqr() > AMPLITUDE_THRESHOLD);
+    new_state
+}
+
+/// Backend that starts out simulating with a [`StabilizerSim`] and switches to a [`SparseSim`]
+/// the first time a non-Clifford operation is applied. Programs made only of Clifford
+/// operations get the scale of stabilizer simulation, while other programs still run, limited
+/// by the size of their state from the point they switch.
+#[cfg(feature = "sparse-sim")]
+pub struct AutoSim {
+    stabilizer: StabilizerSim,
+    sparse: Option<SparseSim>,
+    memory_budget: Option<usize>,
+}
+
+#[cfg(feature = "sparse-sim")]
+impl Default for AutoSim {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "sparse-sim")]
+impl AutoSim {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            stabilizer: StabilizerSim::new(),
+            sparse: None,
+            memory_budget: None,
+        }
+    }
+
+    #[must_use]
+    pub fn new_with_noise(noise: &PauliNoise) -> Self {
+        Self {
+            stabilizer: StabilizerSim::new_with_noise(noise),
+            sparse: None,
+            memory_budget: None,
+        }
+    }
+
+    /// Limits the memory, in bytes, that the quantum state can use once the simulation has
+    /// switched to sparse simulation, or removes the limit if `None`. The tableau of stabilizer
+    /// mode isn't limited.
+    pub fn set_memory_budget(&mut self, budget: Option<usize>) {
+        self.memory_budget = budget;
+        if let Some(sparse) = &mut self.sparse {
+            sparse.set_memory_budget(budget);
+        }
+    }
+
+    /// Whether the simulation is still in stabilizer mode.
+    #[must_use]
+    pub fn is_stabilizer(&self) -> bool {
+        self.sparse.is_none()
+    }
+
+    fn backend(&mut self) -> &mut dyn Backend<ResultType = bool> {
+        match &mut self.sparse {
+            Some(sparse) => sparse,
+            None => &mut self.stabilizer,
+        }
+    }
+
+    /// Switches to sparse simulation, preparing the current stabilizer state in a new
+    /// [`SparseSim`] with the same qubit IDs.
+    fn sparse(&mut self) -> &mut SparseSim {
+        self.sparse.get_or_insert_with(|| {
+            let stabilizer = &mut self.stabilizer;
+            let mut sparse = SparseSim::new_with_noise(&stabilizer.noise);
+            sparse.set_memory_budget(self.memory_budget);
+            // Seeding from the stabilizer's generator keeps seeded runs reproducible.
+            sparse.set_seed(Some(stabilizer.rng.gen()));
+            for _ in 0..stabilizer.tableau.qubits {
+                sparse.qubit_allocate();
+            }
+            // Preparing the state is a service operation, so it's done without noise.
+            for gate in stabilizer.tableau.preparation() {
+                match gate {
+                    Gate::H(q) => sparse.sim.h(q),
+                    Gate::S(q) => sparse.sim.s(q),
+                    Gate::Sadj(q) => sparse.sim.sadj(q),
+                    Gate::X(q) => sparse.sim.x(q),
+                    Gate::Y(q) => sparse.sim.y(q),
+                    Gate::Z(q) => sparse.sim.z(q),
+                    Gate::Cx(ctl, q) => sparse.sim.mcx(&[ctl], q),
+                    Gate::Cz(ctl, q) => sparse.sim.mcz(&[ctl], q),
+                    Gate::Swap(q0, q1) => sparse.sim.swap_qubit_ids(q0, q1),
+                }
+            }
+            for q in &stabilizer.free_qubits {
+                sparse.qubit_release(*q);
+            }
+            sparse
+        })
+    }
+
+    /// The backend for a rotation by `theta`, switching to sparse simulation if it isn't Clifford.
+    fn rotation_backend(&mut self, theta: f64) -> &mut dyn Backend<ResultType = bool> {
+        if quarter_turns(theta).is_none() {
+            self.sparse();
+        }
+        self.backend()
+    }
+}
+
+#[cfg(feature = "sparse-sim")]
+impl Backend for AutoSim {
+    type ResultType = bool;
+
+    fn ccx(&mut self, ctl0: usize, ctl1: usize, q: usize) {
+        self.sparse().ccx(ctl0, ctl1, q);
+    }
+
+    fn cx(&mut self, ctl: usize, q: usize) {
+        self.backend().cx(ctl, q);
+    }
+
+    fn cy(&mut self, ctl: usize, q: usize) {
+        self.backend().cy(ctl, q);
+    }
+
+    fn cz(&mut self, ctl: usize, q: usize) {
+        self.backend().cz(ctl, q);
+    }
+
+    fn h(&mut self, q: usize) {
+        self.backend().h(q);
+    }
+
+    fn m(&mut self, q: usize) -> Self::ResultType {
+        self.backend().m(q)
+    }
+
+    fn mresetz(&mut self, q: usize) -> Self::ResultType {
+        self.backend().mresetz(q)
+    }
+
+    fn reset(&mut self, q: usize) {
+        self.backend().reset(q);
+    }
+
+    fn rx(&mut self, theta: f64, q: usize) {
+        self.rotation_backend(theta).rx(theta, q);
+    }
+
+    fn rxx(&mut self, theta: f64, q0: usize, q1: usize) {
+        self.rotation_backend(theta).rxx(theta, q0, q1);
+    }
+
+    fn ry(&mut self, theta: f64, q: usize) {
+        self.rotation_backend(theta).ry(theta, q);
+    }
+
+    fn ryy(&mut self, theta: f64, q0: usize, q1: usize) {
+        self.rotation_backend(theta).ryy(theta, q0, q1);
+    }
+
+    fn rz(&mut self, theta: f64, q: usize) {
+        self.rotation_backend(theta).rz(theta, q);
+    }
+
+    fn rzz(&mut self, theta: f64, q0: usize, q1: usize) {
+        self.rotation_backend(theta).rzz(theta, q0, q1);
+    }
+
+    fn sadj(&mut self, q: usize) {
+        self.backend().sadj(q);
+    }
+
+    fn s(&mut self, q: usize) {
+        self.backend().s(q);
+    }
+
+    fn swap(&mut self, q0: usize, q1: usize) {
+        self.backend().swap(q0, q1);
+    }
+
+    fn tadj(&mut self, q: usize) {
+        self.sparse().tadj(q);
+    }
+
+    fn t(&mut self, q: usize) {
+        self.sparse().t(q);
+    }
+
+    fn x(&mut self, q: usize) {
+        self.backend().x(q);
+    }
+
+    fn y(&mut self, q: usize) {
+        self.backend().y(q);
+    }
+
+    fn z(&mut self, q: usize) {
+        self.backend().z(q);
+    }
+
+    fn qubit_allocate(&mut self) -> usize {
+        self.backend().qubit_allocate()
+    }
+
+    fn qubit_release(&mut self, q: usize) -> bool {
+        self.backend().qubit_release(q)
+    }
+
+    fn qubit_swap_id(&mut self, q0: usize, q1: usize) {
+        self.backend().qubit_swap_id(q0, q1);
+    }
+
+    fn capture_quantum_state(&mut self) -> (Vec<(BigUint, Complex<f64>)>, usize) {
+        self.backend().capture_quantum_state()
+    }
+
+    fn qubit_is_zero(&mut self, q: usize) -> bool {
+        self.backend().qubit_is_zero(q)
+    }
+
+    fn expectation_value(&mut self, paulis: &[(Pauli, usize)]) -> f64 {
+        self.backend().expectation_value(paulis)
+    }
+
+    fn custom_intrinsic(&mut self, name: &str, arg: Value) -> Option<Result<Value, String>> {
+        let clifford = match name {
+            "GlobalPhase" => {
+                let (ctls, theta) = global_phase_arg(&arg);
+                global_phase_gates(&ctls, theta).is_some()
+            }
+            "Apply" => false,
+            _ => true,
+        };
+        if !clifford {
+            self.sparse();
+        }
+        self.backend().custom_intrinsic(name, arg)
+    }
+
+    fn set_seed(&mut self, seed: Option<u64>) {
+        self.backend().set_seed(seed);
+    }
+
+    fn delay(&mut self, duration_us: f64, qubits: &[usize]) {
+        self.backend().delay(duration_us, qubits);
+    }
+
+    fn take_failure(&mut self) -> Option<String> {
+        self.backend().take_failure()
+    }
+
+    fn take_memory_exceeded(&mut self) -> Option<MemoryExceeded> {
+        self.backend().take_memory_exceeded()
+    }
+}
@@ -0,0 +1,251 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(feature = "sparse-sim")]
+use super::AutoSim;
+use super::StabilizerSim;
+use crate::backend::Backend;
+use expect_test::{expect, Expect};
+use std::f64::consts::PI;
+
+fn check_state(sim: &mut impl Backend, expect: &Expect) {
+    let (state, qubit_count) = sim.capture_quantum_state();
+    let actual = state
+        .iter()
+        .map(|(index, amplitude)| {
+            format!(
+                "|{:0>qubit_count$}⟩: {:.4} {:+.4}i",
+                index.to_str_radix(2),
+                amplitude.re,
+                amplitude.im
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    expect.assert_eq(&actual);
+}
+
+/// Applies a fixed sequence of Clifford gates that entangles the qubits.
+#[cfg(feature = "sparse-sim")]
+fn entangle(sim: &mut impl Backend, qs: &[usize]) {
+    sim.h(qs[0]);
+    sim.cx(qs[0], qs[1]);
+    sim.s(qs[1]);
+    sim.h(qs[2]);
+    sim.cz(qs[1], qs[2]);
+    sim.cy(qs[2], qs[3]);
+    sim.swap(qs[0], qs[3]);
+    sim.sadj(qs[0]);
+    sim.h(qs[3]);
+    sim.rx(PI / 2.0, qs[1]);
+}
+
+/// Undoes [`entangle`].
+#[cfg(feature = "sparse-sim")]
+fn disentangle(sim: &mut impl Backend, qs: &[usize]) {
+    sim.rx(-PI / 2.0, qs[1]);
+    sim.h(qs[3]);
+    sim.s(qs[0]);
+    sim.swap(qs[0], qs[3]);
+    sim.cy(qs[2], qs[3]);
+    sim.cz(qs[1], qs[2]);
+    sim.h(qs[2]);
+    sim.sadj(qs[1]);
+    sim.cx(qs[0], qs[1]);
+    sim.h(qs[0]);
+}
+
+#[test]
+fn bell_pair_measurements_agree() {
+    for seed in 0..20 {
+        let mut sim = StabilizerSim::new();
+        sim.set_seed(Some(seed));
+        let q0 = sim.qubit_allocate();
+        let q1 = sim.qubit_allocate();
+        sim.h(q0);
+        sim.cx(q0, q1);
+        assert_eq!(sim.m(q0), sim.m(q1));
+    }
+}
+
+#[test]
+fn measurement_outcomes_are_random_and_seeded() {
+    let run = |seed| {
+        let mut sim = StabilizerSim::new();
+        sim.set_seed(Some(seed));
+        let q = sim.qubit_allocate();
+        (0..64)
+            .map(|_| {
+                sim.h(q);
+                sim.mresetz(q)
+            })
+            .collect::<Vec<_>>()
+    };
+    let outcomes = run(7);
+    assert!(outcomes.contains(&true) && outcomes.contains(&false));
+    assert_eq!(outcomes, run(7));
+}
+
+#[test]
+fn ghz_state_on_thousands_of_qubits() {
+    let mut sim = StabilizerSim::new();
+    sim.set_seed(Some(42));
+    let qs = (0..2000).map(|_| sim.qubit_allocate()).collect::<Vec<_>>();
+    sim.h(qs[0]);
+    for pair in qs.windows(2) {
+        sim.cx(pair[0], pair[1]);
+    }
+    let first = sim.mresetz(qs[0]);
+    for q in &qs[1..] {
+        assert_eq!(sim.mresetz(*q), first);
+    }
+    for q in qs {
+        assert!(sim.qubit_release(q));
+    }
+}
+
+#[test]
+fn deterministic_measurements_leave_state_unchanged() {
+    let mut sim = StabilizerSim::new();
+    let q0 = sim.qubit_allocate();
+    let q1 = sim.qubit_allocate();
+    sim.x(q0);
+    sim.cx(q0, q1);
+    assert!(!sim.qubit_is_zero(q0));
+    assert!(sim.m(q0));
+    assert!(sim.m(q1));
+    sim.reset(q0);
+    assert!(sim.qubit_is_zero(q0));
+    assert!(!sim.qubit_release(q1));
+    assert!(sim.qubit_release(q0));
+}
+
+#[test]
+fn released_qubits_are_reused_in_zero_state() {
+    let mut sim = StabilizerSim::new();
+    let q0 = sim.qubit_allocate();
+    let q1 = sim.qubit_allocate();
+    sim.h(q0);
+    assert!(sim.qubit_release(q1));
+    let q2 = sim.qubit_allocate();
+    assert_eq!(q2, q1);
+    assert!(sim.qubit_is_zero(q2));
+}
+
+#[test]
+fn clifford_rotations_are_applied() {
+    let mut sim = StabilizerSim::new();
+    let q0 = sim.qubit_allocate();
+    let q1 = sim.qubit_allocate();
+    sim.rx(PI, q0);
+    sim.ry(-PI, q1);
+    sim.rzz(PI / 2.0, q0, q1);
+    sim.ryy(3.0 * PI, q0, q1);
+    assert!(sim.take_failure().is_none());
+    assert!(sim.qubit_is_zero(q0));
+    assert!(sim.qubit_is_zero(q1));
+}
+
+#[test]
+fn state_is_captured_with_positive_first_amplitude() {
+    let mut sim = StabilizerSim::new();
+    let q0 = sim.qubit_allocate();
+    let q1 = sim.qubit_allocate();
+    let q2 = sim.qubit_allocate();
+    sim.x(q2);
+    sim.h(q0);
+    sim.cx(q0, q1);
+    sim.s(q1);
+    sim.y(q0);
+    check_state(
+        &mut sim,
+        &expect![[r#"
+            |011⟩: 0.7071 +0.0000i
+            |101⟩: 0.0000 +0.7071i"#]],
+    );
+    sim.mresetz(q0);
+    sim.mresetz(q1);
+    assert!(sim.qubit_release(q0));
+    assert!(sim.qubit_release(q1));
+    check_state(
+        &mut sim,
+        &expect![[r#"
+        |1⟩: 1.0000 +0.0000i"#]],
+    );
+}
+
+#[test]
+fn non_clifford_operations_fail() {
+    let mut sim = StabilizerSim::new();
+    let q0 = sim.qubit_allocate();
+    let q1 = sim.qubit_allocate();
+    sim.t(q0);
+    expect!["the stabilizer simulator can only apply Clifford operations"]
+        .assert_eq(&sim.take_failure().expect("t should fail"));
+    sim.rz(0.1, q0);
+    expect!["the stabilizer simulator can only apply rotations by multiples of π/2"]
+        .assert_eq(&sim.take_failure().expect("rz should fail"));
+    sim.ccx(q0, q1, q0);
+    assert!(sim.take_failure().is_some());
+    sim.h(q1);
+    assert!(sim.take_failure().is_none());
+}
+
+#[cfg(feature = "sparse-sim")]
+#[test]
+fn auto_sim_stays_in_stabilizer_mode_for_clifford_operations() {
+    let mut sim = AutoSim::new();
+    let qs = (0..1000).map(|_| sim.qubit_allocate()).collect::<Vec<_>>();
+    sim.h(qs[0]);
+    for pair in qs.windows(2) {
+        sim.cx(pair[0], pair[1]);
+    }
+    sim.rz(PI / 2.0, qs[0]);
+    let first = sim.m(qs[0]);
+    assert!(qs.iter().all(|q| sim.m(*q) == first));
+    assert!(sim.is_stabilizer());
+}
+
+#[cfg(feature = "sparse-sim")]
+#[test]
+fn auto_sim_falls_back_with_the_same_state() {
+    let mut sim = AutoSim::new();
+    let qs = (0..6).map(|_| sim.qubit_allocate()).collect::<Vec<_>>();
+    // Leave a free qubit in the middle to check that IDs are kept when switching.
+    assert!(sim.qubit_release(qs[4]));
+    let qs = [qs[0], qs[1], qs[2], qs[3], qs[5]];
+    entangle(&mut sim, &qs);
+    sim.x(qs[4]);
+    assert!(sim.is_stabilizer());
+
+    sim.t(qs[1]);
+    assert!(!sim.is_stabilizer());
+    sim.tadj(qs[1]);
+    disentangle(&mut sim, &qs);
+    sim.x(qs[4]);
+    for q in qs {
+        assert!(
+            sim.qubit_is_zero(q),
+            "qubit {q} should be in the zero state"
+        );
+    }
+    assert_eq!(sim.qubit_allocate(), 4);
+}
+
+#[cfg(feature = "sparse-sim")]
+#[test]
+fn auto_sim_falls_back_on_non_clifford_rotation() {
+    let mut sim = AutoSim::new();
+    let q0 = sim.qubit_allocate();
+    let q1 = sim.qubit_allocate();
+    sim.h(q0);
+    sim.cx(q0, q1);
+    sim.rzz(0.3, q0, q1);
+    assert!(!sim.is_stabilizer());
+    sim.rzz(-0.3, q0, q1);
+    sim.cx(q0, q1);
+    sim.h(q0);
+    assert!(sim.qubit_is_zero(q0));
+    assert!(sim.qubit_is_zero(q1));
+    assert!(sim.take_failure().is_none());
+}
@@ -0,0 +1,81 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! Recording and replay of the outcomes of measurements and random draws.
+//! A [`Tape`] that is recording captures every outcome as the program runs, and a tape that is
+//! replaying feeds the captured outcomes back to the program in the same order, so that a specific
+//! run, such as a failing shot, can be reproduced exactly in the debugger or in tests.
+
+use crate::val;
+
+/// The outcome of a measurement or random draw, as captured on a [`Tape`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Outcome {
+    /// The result of a measurement.
+    Result(val::Result),
+    /// An integer drawn with `DrawRandomInt`.
+    Int(i64),
+    /// A double drawn with `DrawRandomDouble`.
+    Double(f64),
+    /// A Boolean drawn with `DrawRandomBool`.
+    Bool(bool),
+}
+
+/// The outcomes of the measurements and random draws of a run of a program.
+///
+/// When replaying, the simulator still performs each measurement so that the measured qubit
+/// collapses, and a qubit measured with `M` is then flipped if needed to agree with the replayed
+/// outcome. Replaying fails if the program asks for an outcome of a different kind than the next
+/// one on the tape, or for more outcomes than the tape holds.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Tape {
+    outcomes: Vec<Outcome>,
+    /// The position of the next outcome to replay, or `None` if the tape is recording.
+    position: Option<usize>,
+}
+
+impl Tape {
+    /// Creates an empty tape that records outcomes.
+    #[must_use]
+    pub fn record() -> Self {
+        Self::default()
+    }
+
+    /// Creates a tape that replays the given outcomes.
+    #[must_use]
+    pub fn replay(outcomes: Vec<Outcome>) -> Self {
+        Self {
+            outcomes,
+            position: Some(0),
+        }
+    }
+
+    /// Creates a tape that replays the outcomes on this tape from the start.
+    #[must_use]
+    pub fn rewind(self) -> Self {
+        Self::replay(self.outcomes)
+    }
+
+    #[must_use]
+    pub fn is_replaying(&self) -> bool {
+        self.position.is_some()
+    }
+
+    /// The outcomes on the tape, whether recorded or to be replayed.
+    #[must_use]
+    pub fn outcomes(&self) -> &[Outcome] {
+        &self.outcomes
+    }
+
+    pub(crate) fn push(&mut self, outcome: Outcome) {
+        self.outcomes.push(outcome);
+    }
+
+    /// Takes the next outcome to replay, if there is one.
+    pub(crate) fn next(&mut self) -> Option<Outcome> {
+        let position = self.position.as_mut()?;
+        let outcome = self.outcomes.get(*position).copied()?;
+        *position += 1;
+        Some(outcome)
+    }
+}
@@ -6,12 +6,12 @@ use crate::{
     debug::Frame,
     exec_graph_section,
     output::{GenericReceiver, Receiver},
-    val, Env, Error, State, StepAction, StepResult, Value,
+    val, Env, Error, Quota, State, StepAction, StepResult, Value,
 };
 use expect_test::{expect, Expect};
 use indoc::indoc;
 use qsc_data_structures::{language_features::LanguageFeatures, target::TargetCapabilityFlags};
-use qsc_fir::fir::{self, ExecGraph, StmtId};
+use qsc_fir::fir::{self, ExecGraph, ExecGraphNode, StmtId};
 use qsc_fir::fir::{PackageId, PackageStoreLookup};
 use qsc_frontend::compile::{self, compile, PackageStore, SourceMap};
 use qsc_lowerer::map_hir_package_to_fir;
@@ -39,6 +39,10 @@ pub(super) fn eval_graph(
 }
 
 fn check_expr(file: &str, expr: &str, expect: &Expect) {
+    check_expr_with_quota(file, expr, Quota::default(), expect);
+}
+
+fn check_expr_with_quota(file: &str, expr: &str, quota: Quota, expect: &Expect) {
     let mut fir_lowerer = qsc_lowerer::Lowerer::new();
     let mut core = compile::core();
     run_core_passes(&mut core);
@@ -49,7 +53,7 @@ fn check_expr(file: &str, expr: &str, expect: &Expect) {
 
     let mut std = compile::std(&store, TargetCapabilityFlags::all());
     assert!(std.errors.is_empty());
-    assert!(run_default_passes(store.core(), &mut std, PackageType::Lib).is_empty());
+    assert!(run_default_passes(&store, &mut std, PackageType::Lib).is_empty());
     let std_fir = fir_lowerer.lower_package(&std.package, &fir_store);
     let std_id = store.insert(std);
 
@@ -62,7 +66,7 @@ fn check_expr(file: &str, expr: &str, expect: &Expect) {
         LanguageFeatures::default(),
     );
     assert!(unit.errors.is_empty(), "{:?}", unit.errors);
-    let pass_errors = run_default_passes(store.core(), &mut unit, PackageType::Lib);
+    let pass_errors = run_default_passes(&store, &mut unit, PackageType::Lib);
     assert!(pass_errors.is_empty(), "{pass_errors:?}");
     let unit_fir = fir_lowerer.lower_package(&unit.package, &fir_store);
     let entry = unit_fir.entry_exec_graph.clone();
@@ -77,15 +81,18 @@ fn check_expr(file: &str, expr: &str, expect: &Expect) {
     fir_store.insert(map_hir_package_to_fir(id), unit_fir);
 
     let mut out = Vec::new();
-    match eval_graph(
-        entry,
-        &mut SparseSim::new(),
+    let mut state = State::new(map_hir_package_to_fir(id), entry, None);
+    state.set_quota(quota);
+    match state.eval(
         &fir_store,
-        map_hir_package_to_fir(id),
         &mut Env::default(),
+        &mut SparseSim::new(),
         &mut GenericReceiver::new(&mut out),
+        &[],
+        StepAction::Continue,
     ) {
-        Ok(value) => expect.assert_eq(&value.to_string()),
+        Ok(StepResult::Return(value)) => expect.assert_eq(&value.to_string()),
+        Ok(_) => unreachable!("eval should always return a value"),
         Err((err, _)) => expect.assert_debug_eq(&err),
     }
 }
@@ -105,7 +112,7 @@ fn check_partial_eval_stmt(
 
     let mut std = compile::std(&store, TargetCapabilityFlags::all());
     assert!(std.errors.is_empty());
-    assert!(run_default_passes(store.core(), &mut std, PackageType::Lib).is_empty());
+    assert!(run_default_passes(&store, &mut std, PackageType::Lib).is_empty());
     let std_fir = qsc_lowerer::Lowerer::new().lower_package(&std.package, &fir_store);
     let std_id = store.insert(std);
 
@@ -118,7 +125,7 @@ fn check_partial_eval_stmt(
         LanguageFeatures::default(),
     );
     assert!(unit.errors.is_empty(), "{:?}", unit.errors);
-    let pass_errors = run_default_passes(store.core(), &mut unit, PackageType::Lib);
+    let pass_errors = run_default_passes(&store, &mut unit, PackageType::Lib);
     assert!(pass_errors.is_empty(), "{pass_errors:?}");
     let unit_fir = qsc_lowerer::Lowerer::new().lower_package(&unit.package, &fir_store);
     fir_expect.assert_eq(&unit_fir.to_string());
@@ -167,6 +174,72 @@ fn check_partial_eval_stmt(
     }
 }
 
+fn lower_unit(file: &str, expr: &str) -> fir::Package {
+    let mut core = compile::core();
+    run_core_passes(&mut core);
+    let mut store = PackageStore::new(core);
+
+    let mut std = compile::std(&store, TargetCapabilityFlags::all());
+    assert!(std.errors.is_empty());
+    assert!(run_default_passes(&store, &mut std, PackageType::Lib).is_empty());
+    let std_id = store.insert(std);
+
+    let sources = SourceMap::new([("test".into(), file.into())], Some(expr.into()));
+    let mut unit = compile(
+        &store,
+        &[(std_id, None)],
+        sources,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::default(),
+    );
+    assert!(unit.errors.is_empty(), "{:?}", unit.errors);
+    let pass_errors = run_default_passes(&store, &mut unit, PackageType::Lib);
+    assert!(pass_errors.is_empty(), "{pass_errors:?}");
+    qsc_lowerer::Lowerer::new().lower_package(&unit.package, &fir::PackageStore::new())
+}
+
+#[test]
+fn exec_graph_section_rebases_jumps_once() {
+    let graph = ExecGraph::from(vec![
+        ExecGraphNode::Unit,
+        ExecGraphNode::Store,
+        ExecGraphNode::JumpIfNot(4),
+        ExecGraphNode::Unit,
+        ExecGraphNode::Jump(2),
+        ExecGraphNode::Ret,
+    ]);
+    let section = exec_graph_section(&graph, 2..5);
+    assert!(!section.shares_nodes(&graph));
+    assert_eq!(
+        section.iter().collect::<Vec<_>>(),
+        vec![
+            ExecGraphNode::JumpIfNot(2),
+            ExecGraphNode::Unit,
+            ExecGraphNode::Jump(0),
+        ]
+    );
+    let nested = exec_graph_section(&section, 1..2);
+    assert!(nested.shares_nodes(&section));
+    assert_eq!(nested.iter().collect::<Vec<_>>(), vec![ExecGraphNode::Unit]);
+    assert!(nested.get(1).is_none());
+}
+
+#[test]
+fn exec_graph_section_without_jumps_shares_nodes() {
+    let graph = ExecGraph::from(vec![
+        ExecGraphNode::Unit,
+        ExecGraphNode::Store,
+        ExecGraphNode::Unit,
+        ExecGraphNode::Ret,
+    ]);
+    let section = exec_graph_section(&graph, 1..3);
+    assert!(section.shares_nodes(&graph));
+    assert_eq!(
+        section.iter().collect::<Vec<_>>(),
+        vec![ExecGraphNode::Store, ExecGraphNode::Unit]
+    );
+}
+
 #[test]
 fn array_expr() {
     check_expr("", "[1, 2, 3]", &expect!["[1, 2, 3]"]);
@@ -415,8 +488,8 @@ fn block_qubit_use_array_invalid_count_expr() {
                         0,
                     ),
                     span: Span {
-                        lo: 2050,
-                        hi: 2107,
+                        lo: 4589,
+                        hi: 4646,
                     },
                 },
             )
@@ -2070,6 +2143,79 @@ fn struct_copy_cons() {
     );
 }
 
+#[test]
+fn class_method_calls_member_implementation() {
+    check_expr(
+        indoc! {"
+            namespace A {
+                class Sized {
+                    function Size(x : 'Self) : Int;
+                }
+                struct Rect { Width : Int, Height : Int }
+                function SizeRect(r : Rect) : Int { r.Width * r.Height }
+            }
+        "},
+        indoc! {"{
+            open A;
+            Size(new Rect { Width = 2, Height = 3 })
+        }"},
+        &expect!["6"],
+    );
+}
+
+#[test]
+fn class_method_in_generic_callable_calls_member_implementation() {
+    check_expr(
+        indoc! {"
+            namespace A {
+                class Sized {
+                    function Size(x : 'Self) : Int;
+                }
+                struct Rect { Width : Int, Height : Int }
+                struct Line { Length : Int }
+                function SizeRect(r : Rect) : Int { r.Width * r.Height }
+                function SizeLine(l : Line) : Int { l.Length }
+                function TotalSize<'T : Sized>(xs : 'T[]) : Int {
+                    mutable total = 0;
+                    for x in xs {
+                        set total += Size(x);
+                    }
+                    total
+                }
+            }
+        "},
+        indoc! {"{
+            open A;
+            TotalSize([new Rect { Width = 2, Height = 3 }])
+                + TotalSize([new Line { Length = 4 }, new Line { Length = 5 }])
+        }"},
+        &expect!["15"],
+    );
+}
+
+#[test]
+fn parameterized_class_method_in_generic_callable() {
+    check_expr(
+        indoc! {"
+            namespace A {
+                class Iterable['U] {
+                    function Items(c : 'Self) : 'U[];
+                }
+                struct Bag { Values : Int[] }
+                function ItemsBag(bag : Bag) : Int[] { bag.Values }
+                function First<'T : Iterable['U], 'U>(c : 'T) : 'U {
+                    Items(c)[0]
+                }
+            }
+        "},
+        indoc! {"{
+            open A;
+            First(new Bag { Values = [3, 4] })
+        }"},
+        &expect!["3"],
+    );
+}
+
 #[test]
 fn update_udt_known_field_name() {
     check_expr(
@@ -2929,6 +3075,78 @@ fn call_call_expr() {
     );
 }
 
+#[test]
+fn call_self_in_tail_position_beyond_call_depth_expr() {
+    check_expr_with_quota(
+        indoc! {"
+            namespace Test {
+                function Gcd(a : Int, b : Int) : Int {
+                    if b == 0 { a } else { Gcd(b, a % b) }
+                }
+                function CountDown(n : Int, acc : Int) : Int {
+                    if n == 0 {
+                        return acc;
+                    }
+                    return CountDown(n - 1, acc + 1);
+                }
+            }
+        "},
+        "(Test.Gcd(1071, 462), Test.CountDown(20000, 0))",
+        Quota {
+            max_call_depth: Some(10_000),
+            ..Quota::default()
+        },
+        &expect!["(21, 20000)"],
+    );
+}
+
+#[test]
+fn call_self_not_in_tail_position_has_no_call_depth_limit_by_default_expr() {
+    check_expr(
+        indoc! {"
+            namespace Test {
+                function Sum(n : Int) : Int {
+                    if n == 0 { 0 } else { n + Sum(n - 1) }
+                }
+            }
+        "},
+        "Test.Sum(20000)",
+        &expect!["200010000"],
+    );
+}
+
+#[test]
+fn call_self_not_in_tail_position_exceeds_call_depth_expr() {
+    check_expr_with_quota(
+        indoc! {"
+            namespace Test {
+                function Sum(n : Int) : Int {
+                    if n == 0 { 0 } else { n + Sum(n - 1) }
+                }
+            }
+        "},
+        "Test.Sum(20000)",
+        Quota {
+            max_call_depth: Some(10_000),
+            ..Quota::default()
+        },
+        &expect![[r#"
+            RecursionLimitExceeded(
+                10000,
+                PackageSpan {
+                    package: PackageId(
+                        2,
+                    ),
+                    span: Span {
+                        lo: 102,
+                        hi: 105,
+                    },
+                },
+            )
+        "#]],
+    );
+}
+
 #[test]
 fn call_adjoint_expr() {
     check_expr(
@@ -3117,6 +3335,84 @@ fn check_generated_ctl_expr() {
     );
 }
 
+#[test]
+fn check_generated_ctl_deep_chain_expr() {
+    check_expr(
+        indoc! {r#"
+            namespace Test {
+                operation A() : Unit is Ctl {
+                    body ... {}
+                    controlled (ctls, ...) {
+                        if Length(ctls) != 6 {
+                            fail "Incorrect ctls count!";
+                        }
+                    }
+                }
+                operation B() : Unit is Ctl {
+                    A();
+                }
+            }
+        "#},
+        indoc! {"
+            {
+                use qs = Qubit[6];
+                Controlled Controlled Controlled Controlled Controlled Controlled Test.B(
+                    [qs[0]], ([qs[1]], ([qs[2]], ([qs[3]], ([qs[4]], ([qs[5]], ()))))))
+            }
+        "},
+        &expect!["()"],
+    );
+}
+
+#[test]
+fn generated_ctl_is_not_respecialized_for_deep_chains() {
+    let file = indoc! {"
+        namespace Test {
+            operation A() : Unit is Ctl {}
+            operation B() : Unit is Ctl {
+                A();
+            }
+        }
+    "};
+    let chain = |depth: usize| {
+        let functors = "Controlled ".repeat(depth);
+        let args = (0..depth).fold("()".to_string(), |args, _| format!("(qs, {args})"));
+        lower_unit(
+            file,
+            &format!("{{use qs = Qubit[1]; {functors}Test.B{args}}}"),
+        )
+    };
+    let sizes = [5, 6, 7].map(|depth| {
+        let package = chain(depth);
+        let specs = package
+            .items
+            .values()
+            .filter_map(|item| match &item.kind {
+                fir::ItemKind::Callable(decl) => match &decl.implementation {
+                    fir::CallableImpl::Spec(spec) => {
+                        Some(1 + usize::from(spec.ctl.is_some()) + usize::from(spec.adj.is_some()))
+                    }
+                    _ => None,
+                },
+                _ => None,
+            })
+            .sum::<usize>();
+        (
+            specs,
+            package.blocks.iter().count(),
+            package.exprs.iter().count(),
+        )
+    });
+
+    // Each callable keeps one controlled specialization whatever the depth, so only the
+    // functors and arguments of the call grow the package, by the same amount per level.
+    assert_eq!(sizes[0].0, 4);
+    assert!(sizes
+        .iter()
+        .all(|&(specs, blocks, _)| (specs, blocks) == (sizes[0].0, sizes[0].1)));
+    assert_eq!(sizes[2].2 - sizes[1].2, sizes[1].2 - sizes[0].2);
+}
+
 #[test]
 fn check_generated_ctladj_distrib_expr() {
     check_expr(
@@ -3246,6 +3542,90 @@ fn nested_interpolated_string_with_exprs() {
     );
 }
 
+#[test]
+fn interpolated_string_format_spec_int() {
+    check_expr(
+        "",
+        indoc! {r#"{
+            let n = -42;
+            $"[{n:5}] [{n:<5}] [{n:05}] [{42:+}] [{10:b}] [{255:x}] [{255:#^6X}] [{n:o}]"
+        }"#},
+        &expect!["[  -42] [-42  ] [-0042] [+42] [1010] [ff] [##FF##] [-52]"],
+    );
+}
+
+#[test]
+fn interpolated_string_format_spec_double() {
+    check_expr(
+        "",
+        indoc! {r#"{
+            let x = -3.14159;
+            $"[{x:0.3f}] [{x:10.2}] [{x:f}] [{1234.5:.2e}] [{2.0:>6}] [{7:.1f}]"
+        }"#},
+        &expect!["[-3.142] [     -3.14] [-3.141590] [1.23e3] [   2.0] [7.0]"],
+    );
+}
+
+#[test]
+fn interpolated_string_format_spec_big_int() {
+    check_expr("", r#"$"{-255L:X} {12L:06}""#, &expect!["-FF 000012"]);
+}
+
+#[test]
+fn interpolated_string_format_spec_text() {
+    check_expr(
+        "",
+        r#"$"[{"ab":4}] [{"ab":>4}] [{true:*^6}] [{Zero:3}]""#,
+        &expect!["[ab  ] [  ab] [*true*] [Zero]"],
+    );
+}
+
+#[test]
+fn interpolated_string_format_spec_invalid_for_type() {
+    check_expr(
+        "",
+        r#"$"{"ab":x}""#,
+        &expect![[r#"
+        InvalidFormatSpec(
+            "x",
+            "String",
+            PackageSpan {
+                package: PackageId(
+                    2,
+                ),
+                span: Span {
+                    lo: 3,
+                    hi: 7,
+                },
+            },
+        )
+    "#]],
+    );
+}
+
+#[test]
+fn interpolated_string_format_spec_radix_for_double() {
+    check_expr(
+        "",
+        r#"$"{1.5:b}""#,
+        &expect![[r#"
+        InvalidFormatSpec(
+            "b",
+            "Double",
+            PackageSpan {
+                package: PackageId(
+                    2,
+                ),
+                span: Span {
+                    lo: 3,
+                    hi: 6,
+                },
+            },
+        )
+    "#]],
+    );
+}
+
 #[test]
 fn udt_unwrap() {
     check_expr(
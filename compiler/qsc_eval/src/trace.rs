@@ -0,0 +1,638 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! Trace capture for explaining how likely a program is to produce a given result.
+//! The [`TraceSim`] backend simulates the program while following a prescribed sequence of
+//! measurement outcomes, recording each measurement as a branch decision along with its
+//! probability. Running a program once for every possible sequence of outcomes gives the
+//! probability of each result, and [`AttributionNode`] arranges these runs into a tree that shows
+//! which branch decisions the probability of a result comes from.
+
+#[cfg(test)]
+mod tests;
+
+use crate::{backend::Backend, val::Value};
+use num_bigint::BigUint;
+use num_complex::Complex;
+use rustc_hash::FxHashMap;
+use std::{
+    collections::BTreeSet,
+    f64::consts::FRAC_1_SQRT_2,
+    fmt::{self, Display, Formatter},
+};
+
+/// The largest number of qubits that can be in use at the same time in a traced program.
+pub const MAX_QUBITS: usize = 128;
+
+/// The largest number of runs, one for each possible sequence of measurement outcomes, that
+/// tracing a program may take.
+pub const MAX_RUNS: usize = 1024;
+
+/// Outcomes with a probability of at most this value are considered impossible.
+const PROBABILITY_THRESHOLD: f64 = 1e-10;
+
+/// Amplitudes with a squared norm of at most this value are dropped from the state.
+const AMPLITUDE_THRESHOLD: f64 = 1e-20;
+
+/// The operation that made a branch decision.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MeasurementKind {
+    M,
+    MResetZ,
+    Reset,
+}
+
+impl Display for MeasurementKind {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            MeasurementKind::M => write!(f, "M"),
+            MeasurementKind::MResetZ => write!(f, "MResetZ"),
+            MeasurementKind::Reset => write!(f, "Reset"),
+        }
+    }
+}
+
+/// A measurement made while tracing a program, which is a point where the execution branches.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Measurement {
+    pub kind: MeasurementKind,
+    /// The ID of the measured qubit.
+    pub qubit: usize,
+    /// The outcome of the measurement, `true` for `One`.
+    pub result: bool,
+    /// The probability of the outcome, given the outcomes of the measurements before it.
+    pub probability: f64,
+}
+
+impl Display for Measurement {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}(q{}) = {}",
+            self.kind,
+            self.qubit,
+            if self.result { "One" } else { "Zero" }
+        )
+    }
+}
+
+/// A noiseless backend that follows a prescribed sequence of measurement outcomes and records
+/// every measurement it makes. Measurements beyond the prescribed ones take their most likely
+/// outcome.
+///
+/// The state is kept as a sparse map from basis states to amplitudes, which limits the number of
+/// qubits in use to [`MAX_QUBITS`]. Once a program exceeds it, the backend stops simulating and
+/// [`TraceSim::exceeded_qubit_limit`] returns `true`.
+pub struct TraceSim {
+    state: FxHashMap<u128, Complex<f64>>,
+    decisions: Vec<bool>,
+    measurements: Vec<Measurement>,
+    free_qubits: BTreeSet<usize>,
+    next_qubit: usize,
+    exceeded_qubit_limit: bool,
+}
+
+impl TraceSim {
+    /// Creates a backend whose measurements take the given outcomes, in order.
+    #[must_use]
+    pub fn new(decisions: Vec<bool>) -> Self {
+        let mut state = FxHashMap::default();
+        state.insert(0, Complex::new(1.0, 0.0));
+        Self {
+            state,
+            decisions,
+            measurements: Vec::new(),
+            free_qubits: BTreeSet::new(),
+            next_qubit: 0,
+            exceeded_qubit_limit: false,
+        }
+    }
+
+    /// The measurements made so far, in order.
+    #[must_use]
+    pub fn measurements(&self) -> &[Measurement] {
+        &self.measurements
+    }
+
+    /// Whether the program used more qubits at the same time than can be traced.
+    #[must_use]
+    pub fn exceeded_qubit_limit(&self) -> bool {
+        self.exceeded_qubit_limit
+    }
+
+    /// Gets the sequences of measurement outcomes that lead to the branches not taken by this run.
+    /// Each sequence agrees with the outcomes of this run up to one of the measurements that were
+    /// not prescribed, and takes the other outcome of that measurement if it is possible.
+    #[must_use]
+    pub fn unexplored_decisions(&self) -> Vec<Vec<bool>> {
+        self.measurements
+            .iter()
+            .enumerate()
+            .skip(self.decisions.len())
+            .filter(|(_, measurement)| 1.0 - measurement.probability > PROBABILITY_THRESHOLD)
+            .map(|(idx, measurement)| {
+                let mut decisions = self.measurements[..idx]
+                    .iter()
+                    .map(|measurement| measurement.result)
+                    .collect::<Vec<_>>();
+                decisions.push(!measurement.result);
+                decisions
+            })
+            .collect()
+    }
+
+    fn is_active(&self, qubits: &[usize]) -> bool {
+        !self.exceeded_qubit_limit && qubits.iter().all(|q| *q < MAX_QUBITS)
+    }
+
+    /// Replaces each basis state and its amplitude with the ones given by `f`, adding up the
+    /// amplitudes that end up on the same basis state.
+    fn transform(&mut self, f: impl Fn(u128, Complex<f64>) -> [(u128, Complex<f64>); 2]) {
+        let mut state = FxHashMap::default();
+        for (basis, amplitude) in self.state.drain() {
+            for (new_basis, new_amplitude) in f(basis, amplitude) {
+                *state.entry(new_basis).or_insert(Complex::new(0.0, 0.0)) += new_amplitude;
+            }
+        }
+        state.retain(|_, amplitude| amplitude.norm_sqr() > AMPLITUDE_THRESHOLD);
+        self.state = state;
+    }
+
+    /// Applies the single-qubit gate with the matrix `[[m00, m01], [m10, m11]]` to `q`, if all
+    /// of the control qubits are in the one state.
+    fn apply(&mut self, ctls: &[usize], q: usize, matrix: [[Complex<f64>; 2]; 2]) {
+        if !self.is_active(ctls) || !self.is_active(&[q]) {
+            return;
+        }
+        let ctl_mask = ctls.iter().fold(0_u128, |mask, ctl| mask | (1 << ctl));
+        let mask = 1_u128 << q;
+        let zero = Complex::new(0.0, 0.0);
+        self.transform(|basis, amplitude| {
+            if basis & ctl_mask == ctl_mask {
+                let col = usize::from(basis & mask != 0);
+                [
+                    (basis & !mask, matrix[0][col] * amplitude),
+                    (basis | mask, matrix[1][col] * amplitude),
+                ]
+            } else {
+                [(basis, amplitude), (basis, zero)]
+            }
+        });
+    }
+
+    /// Multiplies the amplitude of each basis state by the phase given for it.
+    fn apply_phase(&mut self, phase: impl Fn(u128) -> Complex<f64>) {
+        for (basis, amplitude) in &mut self.state {
+            *amplitude *= phase(*basis);
+        }
+    }
+
+    fn apply_rotation(&mut self, angle: f64, q: usize, axis: char) {
+        let (sin, cos) = (angle / 2.0).sin_cos();
+        let matrix = match axis {
+            'x' => [
+                [Complex::new(cos, 0.0), Complex::new(0.0, -sin)],
+                [Complex::new(0.0, -sin), Complex::new(cos, 0.0)],
+            ],
+            'y' => [
+                [Complex::new(cos, 0.0), Complex::new(-sin, 0.0)],
+                [Complex::new(sin, 0.0), Complex::new(cos, 0.0)],
+            ],
+            _ => [
+                [Complex::new(cos, -sin), Complex::new(0.0, 0.0)],
+                [Complex::new(0.0, 0.0), Complex::new(cos, sin)],
+            ],
+        };
+        self.apply(&[], q, matrix);
+    }
+
+    /// Applies `exp(-iθ/2 P⊗P)` for the Pauli `P` given by `axis`.
+    fn apply_two_qubit_rotation(&mut self, theta: f64, q0: usize, q1: usize, axis: char) {
+        if !self.is_active(&[q0, q1]) {
+            return;
+        }
+        let (sin, cos) = (theta / 2.0).sin_cos();
+        let (mask0, mask1) = (1_u128 << q0, 1_u128 << q1);
+        let same_parity = move |basis: u128| (basis & mask0 != 0) == (basis & mask1 != 0);
+        match axis {
+            'z' => self.apply_phase(|basis| {
+                if same_parity(basis) {
+                    Complex::new(cos, -sin)
+                } else {
+                    Complex::new(cos, sin)
+                }
+            }),
+            _ => self.transform(|basis, amplitude| {
+                // Y⊗Y flips both qubits like X⊗X, with a factor of -1 when they are equal.
+                let flipped = if axis == 'y' && same_parity(basis) {
+                    Complex::new(0.0, sin)
+                } else {
+                    Complex::new(0.0, -sin)
+                };
+                [
+                    (basis, amplitude * cos),
+                    (basis ^ mask0 ^ mask1, amplitude * flipped),
+                ]
+            }),
+        }
+    }
+
+    fn probability_of_one(&self, q: usize) -> f64 {
+        let mask = 1_u128 << q;
+        self.state
+            .iter()
+            .filter(|(basis, _)| *basis & mask != 0)
+            .map(|(_, amplitude)| amplitude.norm_sqr())
+            .sum()
+    }
+
+    /// Measures `q`, taking the prescribed outcome if there is one, and records the measurement.
+    fn measure(&mut self, kind: MeasurementKind, q: usize) -> bool {
+        if !self.is_active(&[q]) {
+            return false;
+        }
+        let probability_of_one = self.probability_of_one(q);
+        let result = match self.decisions.get(self.measurements.len()) {
+            Some(result) => *result,
+            None => probability_of_one > 0.5,
+        };
+        let probability = if result {
+            probability_of_one
+        } else {
+            1.0 - probability_of_one
+        };
+        self.measurements.push(Measurement {
+            kind,
+            qubit: q,
+            result,
+            probability,
+        });
+
+        if probability > 0.0 {
+            let mask = 1_u128 << q;
+            let scale = 1.0 / probability.sqrt();
+            self.state.retain(|basis, _| (*basis & mask != 0) == result);
+            for amplitude in self.state.values_mut() {
+                *amplitude *= scale;
+            }
+        }
+        result
+    }
+}
+
+impl Backend for TraceSim {
+    type ResultType = bool;
+
+    fn ccx(&mut self, ctl0: usize, ctl1: usize, q: usize) {
+        self.apply(&[ctl0, ctl1], q, X);
+    }
+
+    fn cx(&mut self, ctl: usize, q: usize) {
+        self.apply(&[ctl], q, X);
+    }
+
+    fn cy(&mut self, ctl: usize, q: usize) {
+        self.apply(&[ctl], q, Y);
+    }
+
+    fn cz(&mut self, ctl: usize, q: usize) {
+        self.apply(&[ctl], q, Z);
+    }
+
+    fn h(&mut self, q: usize) {
+        self.apply(&[], q, H);
+    }
+
+    fn m(&mut self, q: usize) -> Self::ResultType {
+        self.measure(MeasurementKind::M, q)
+    }
+
+    fn mresetz(&mut self, q: usize) -> Self::ResultType {
+        let result = self.measure(MeasurementKind::MResetZ, q);
+        if result {
+            self.apply(&[], q, X);
+        }
+        result
+    }
+
+    fn reset(&mut self, q: usize) {
+        if self.measure(MeasurementKind::Reset, q) {
+            self.apply(&[], q, X);
+        }
+    }
+
+    fn rx(&mut self, theta: f64, q: usize) {
+        self.apply_rotation(theta, q, 'x');
+    }
+
+    fn rxx(&mut self, theta: f64, q0: usize, q1: usize) {
+        self.apply_two_qubit_rotation(theta, q0, q1, 'x');
+    }
+
+    fn ry(&mut self, theta: f64, q: usize) {
+        self.apply_rotation(theta, q, 'y');
+    }
+
+    fn ryy(&mut self, theta: f64, q0: usize, q1: usize) {
+        self.apply_two_qubit_rotation(theta, q0, q1, 'y');
+    }
+
+    fn rz(&mut self, theta: f64, q: usize) {
+        self.apply_rotation(theta, q, 'z');
+    }
+
+    fn rzz(&mut self, theta: f64, q0: usize, q1: usize) {
+        self.apply_two_qubit_rotation(theta, q0, q1, 'z');
+    }
+
+    fn sadj(&mut self, q: usize) {
+        self.apply(&[], q, phase_matrix(Complex::new(0.0, -1.0)));
+    }
+
+    fn s(&mut self, q: usize) {
+        self.apply(&[], q, phase_matrix(Complex::new(0.0, 1.0)));
+    }
+
+    fn swap(&mut self, q0: usize, q1: usize) {
+        if !self.is_active(&[q0, q1]) {
+            return;
+        }
+        let (mask0, mask1) = (1_u128 << q0, 1_u128 << q1);
+        let zero = Complex::new(0.0, 0.0);
+        self.transform(|basis, amplitude| {
+            let swapped = if (basis & mask0 != 0) == (basis & mask1 != 0) {
+                basis
+            } else {
+                basis ^ mask0 ^ mask1
+            };
+            [(swapped, amplitude), (swapped, zero)]
+        });
+    }
+
+    fn tadj(&mut self, q: usize) {
+        self.apply(
+            &[],
+            q,
+            phase_matrix(Complex::new(FRAC_1_SQRT_2, -FRAC_1_SQRT_2)),
+        );
+    }
+
+    fn t(&mut self, q: usize) {
+        self.apply(
+            &[],
+            q,
+            phase_matrix(Complex::new(FRAC_1_SQRT_2, FRAC_1_SQRT_2)),
+        );
+    }
+
+    fn x(&mut self, q: usize) {
+        self.apply(&[], q, X);
+    }
+
+    fn y(&mut self, q: usize) {
+        self.apply(&[], q, Y);
+    }
+
+    fn z(&mut self, q: usize) {
+        self.apply(&[], q, Z);
+    }
+
+    fn qubit_allocate(&mut self) -> usize {
+        let q = self.free_qubits.pop_first().unwrap_or_else(|| {
+            self.next_qubit += 1;
+            self.next_qubit - 1
+        });
+        if q >= MAX_QUBITS {
+            self.exceeded_qubit_limit = true;
+        }
+        q
+    }
+
+    fn qubit_release(&mut self, q: usize) -> bool {
+        let was_zero = self.qubit_is_zero(q);
+        self.free_qubits.insert(q);
+        was_zero
+    }
+
+    fn qubit_swap_id(&mut self, q0: usize, q1: usize) {
+        // Swapping the states of the qubits has the same effect as swapping their IDs.
+        self.swap(q0, q1);
+    }
+
+    fn capture_quantum_state(&mut self) -> (Vec<(BigUint, Complex<f64>)>, usize) {
+        let count = self.next_qubit.min(MAX_QUBITS);
+        let mut state = self
+            .state
+            .iter()
+            .map(|(basis, amplitude)| {
+                // The state indices list the qubits with the first one as the most significant bit.
+                let mut idx = BigUint::default();
+                for q in 0..count {
+                    if basis & (1 << q) != 0 {
+                        idx.set_bit((count - 1 - q) as u64, true);
+                    }
+                }
+                (idx, *amplitude)
+            })
+            .collect::<Vec<_>>();
+        state.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        (state, count)
+    }
+
+    fn qubit_is_zero(&mut self, q: usize) -> bool {
+        !self.is_active(&[q]) || self.probability_of_one(q) <= PROBABILITY_THRESHOLD
+    }
+
+    fn custom_intrinsic(&mut self, name: &str, arg: Value) -> Option<Result<Value, String>> {
+        match name {
+            "GlobalPhase" => {
+                let [ctls_val, theta] = &*arg.unwrap_tuple() else {
+                    panic!("tuple arity for GlobalPhase intrinsic should be 2");
+                };
+                let ctls = ctls_val
+                    .clone()
+                    .unwrap_array()
+                    .iter()
+                    .map(|q| q.clone().unwrap_qubit().deref().0)
+                    .collect::<Vec<_>>();
+                if self.is_active(&ctls) {
+                    let ctl_mask = ctls.iter().fold(0_u128, |mask, ctl| mask | (1 << ctl));
+                    let phase = Complex::from_polar(1.0, theta.clone().unwrap_double());
+                    self.apply_phase(|basis| {
+                        if basis & ctl_mask == ctl_mask {
+                            phase
+                        } else {
+                            Complex::new(1.0, 0.0)
+                        }
+                    });
+                }
+                Some(Ok(Value::unit()))
+            }
+            "BeginEstimateCaching" => Some(Ok(Value::Bool(true))),
+            // The trace is noiseless, so noise configuration has no effect.
+            "EndEstimateCaching"
+            | "AccountForEstimatesInternal"
+            | "BeginRepeatEstimatesInternal"
+            | "EndRepeatEstimatesInternal"
+            | "ConfigurePauliNoise"
+            | "ApplyIdleNoise" => Some(Ok(Value::unit())),
+            "Apply" => Some(Err(
+                "applying a unitary matrix is not supported when tracing".to_string(),
+            )),
+            _ => None,
+        }
+    }
+}
+
+const X: [[Complex<f64>; 2]; 2] = [
+    [Complex::new(0.0, 0.0), Complex::new(1.0, 0.0)],
+    [Complex::new(1.0, 0.0), Complex::new(0.0, 0.0)],
+];
+
+const Y: [[Complex<f64>; 2]; 2] = [
+    [Complex::new(0.0, 0.0), Complex::new(0.0, -1.0)],
+    [Complex::new(0.0, 1.0), Complex::new(0.0, 0.0)],
+];
+
+const Z: [[Complex<f64>; 2]; 2] = phase_matrix(Complex::new(-1.0, 0.0));
+
+const H: [[Complex<f64>; 2]; 2] = [
+    [
+        Complex::new(FRAC_1_SQRT_2, 0.0),
+        Complex::new(FRAC_1_SQRT_2, 0.0),
+    ],
+    [
+        Complex::new(FRAC_1_SQRT_2, 0.0),
+        Complex::new(-FRAC_1_SQRT_2, 0.0),
+    ],
+];
+
+const fn phase_matrix(phase: Complex<f64>) -> [[Complex<f64>; 2]; 2] {
+    [
+        [Complex::new(1.0, 0.0), Complex::new(0.0, 0.0)],
+        [Complex::new(0.0, 0.0), phase],
+    ]
+}
+
+/// A run of a traced program: the measurements it made and the result it produced.
+#[derive(Clone, Debug)]
+pub struct TracedRun {
+    pub measurements: Vec<Measurement>,
+    pub result: String,
+}
+
+/// A node in the tree of branch decisions made by a program, annotated with how much each branch
+/// contributes to the probability of a selected result.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AttributionNode {
+    /// The branch decision that leads to this node, or `None` for the root.
+    pub measurement: Option<Measurement>,
+    /// The probability that execution reaches this node.
+    pub probability: f64,
+    /// The probability that execution reaches this node and goes on to produce the selected result.
+    pub contribution: f64,
+    /// The result of the program, if it ends after the branch decision of this node.
+    pub result: Option<String>,
+    /// The branch decisions that can follow this one, with the largest contribution first.
+    /// Branches that do not contribute to the selected result are not expanded.
+    pub children: Vec<AttributionNode>,
+}
+
+impl AttributionNode {
+    /// Builds the tree of branch decisions made by the given runs of a program, which should
+    /// cover every possible sequence of measurement outcomes, attributing the probability of the
+    /// given result to the branches that produce it.
+    #[must_use]
+    pub fn new(runs: &[TracedRun], result: &str) -> Self {
+        let mut root = Self::branch(None, 1.0);
+        for run in runs {
+            let probability = run
+                .measurements
+                .iter()
+                .map(|measurement| measurement.probability)
+                .product::<f64>();
+            let contribution = if run.result == result {
+                probability
+            } else {
+                0.0
+            };
+
+            root.contribution += contribution;
+            let mut node = &mut root;
+            let mut reached = 1.0;
+            for measurement in &run.measurements {
+                reached *= measurement.probability;
+                let idx = node
+                    .children
+                    .iter()
+                    .position(|child| {
+                        child.measurement.is_some_and(|m| {
+                            m.qubit == measurement.qubit && m.result == measurement.result
+                        })
+                    })
+                    .unwrap_or_else(|| {
+                        node.children
+                            .push(Self::branch(Some(*measurement), reached));
+                        node.children.len() - 1
+                    });
+                node = &mut node.children[idx];
+                node.contribution += contribution;
+            }
+            node.result = Some(run.result.clone());
+        }
+        root.prune();
+        root
+    }
+
+    fn branch(measurement: Option<Measurement>, probability: f64) -> Self {
+        Self {
+            measurement,
+            probability,
+            contribution: 0.0,
+            result: None,
+            children: Vec::new(),
+        }
+    }
+
+    fn prune(&mut self) {
+        if self.contribution <= PROBABILITY_THRESHOLD {
+            self.contribution = 0.0;
+            self.children.clear();
+            return;
+        }
+        for child in &mut self.children {
+            child.prune();
+        }
+        self.children
+            .sort_by(|a, b| b.contribution.total_cmp(&a.contribution));
+    }
+
+    fn fmt_indented(&self, f: &mut Formatter, depth: usize) -> fmt::Result {
+        match &self.measurement {
+            Some(measurement) => write!(
+                f,
+                "{:indent$}{measurement} (p = {:.4}): {:.4} of {:.4}",
+                "",
+                measurement.probability,
+                self.contribution,
+                self.probability,
+                indent = depth * 2
+            )?,
+            None => write!(f, "{:.4}", self.contribution)?,
+        }
+        if let Some(result) = &self.result {
+            write!(f, " => {result}")?;
+        }
+        for child in &self.children {
+            writeln!(f)?;
+            child.fmt_indented(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for AttributionNode {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.fmt_indented(f, 0)
+    }
+}
@@ -0,0 +1,192 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{AttributionNode, MeasurementKind, TraceSim, TracedRun};
+use crate::backend::Backend;
+use expect_test::{expect, Expect};
+use std::f64::consts::PI;
+
+fn check_measurements(sim: &TraceSim, expect: &Expect) {
+    let actual = sim
+        .measurements()
+        .iter()
+        .map(|measurement| format!("{measurement} (p = {:.4})", measurement.probability))
+        .collect::<Vec<_>>()
+        .join("\n");
+    expect.assert_eq(&actual);
+}
+
+#[test]
+fn measurements_take_most_likely_outcome_without_decisions() {
+    let mut sim = TraceSim::new(Vec::new());
+    let q0 = sim.qubit_allocate();
+    let q1 = sim.qubit_allocate();
+    sim.ry(PI / 3.0, q0);
+    sim.x(q1);
+    sim.m(q0);
+    sim.m(q1);
+    check_measurements(
+        &sim,
+        &expect![[r#"
+            M(q0) = Zero (p = 0.7500)
+            M(q1) = One (p = 1.0000)"#]],
+    );
+    assert_eq!(sim.unexplored_decisions(), vec![vec![true]]);
+}
+
+#[test]
+fn measurements_follow_decisions() {
+    let mut sim = TraceSim::new(vec![true]);
+    let q0 = sim.qubit_allocate();
+    let q1 = sim.qubit_allocate();
+    sim.h(q0);
+    sim.cx(q0, q1);
+    sim.m(q0);
+    sim.mresetz(q1);
+    check_measurements(
+        &sim,
+        &expect![[r#"
+            M(q0) = One (p = 0.5000)
+            MResetZ(q1) = One (p = 1.0000)"#]],
+    );
+    assert!(sim.unexplored_decisions().is_empty());
+    assert!(!sim.qubit_release(q0));
+    assert!(sim.qubit_release(q1));
+}
+
+fn assert_same_state(actual: &mut TraceSim, expected: &mut TraceSim) {
+    let (actual, _) = actual.capture_quantum_state();
+    let (expected, _) = expected.capture_quantum_state();
+    assert_eq!(
+        actual.len(),
+        expected.len(),
+        "states should have the same size"
+    );
+    for ((actual_idx, actual_amplitude), (expected_idx, expected_amplitude)) in
+        actual.iter().zip(&expected)
+    {
+        assert_eq!(actual_idx, expected_idx);
+        assert!(
+            (actual_amplitude - expected_amplitude).norm() < 1e-9,
+            "amplitude {actual_amplitude} should be {expected_amplitude}"
+        );
+    }
+}
+
+fn check_two_qubit_rotation(
+    rotate: fn(&mut TraceSim, f64, usize, usize),
+    to_z_basis: fn(&mut TraceSim, usize),
+    from_z_basis: fn(&mut TraceSim, usize),
+) {
+    let mut rotated = TraceSim::new(Vec::new());
+    let mut decomposed = TraceSim::new(Vec::new());
+    for sim in [&mut rotated, &mut decomposed] {
+        let q0 = sim.qubit_allocate();
+        let q1 = sim.qubit_allocate();
+        sim.h(q0);
+        sim.t(q0);
+        sim.ry(0.3, q1);
+    }
+
+    rotate(&mut rotated, 0.7, 0, 1);
+
+    to_z_basis(&mut decomposed, 0);
+    to_z_basis(&mut decomposed, 1);
+    decomposed.cx(1, 0);
+    decomposed.rz(0.7, 0);
+    decomposed.cx(1, 0);
+    from_z_basis(&mut decomposed, 1);
+    from_z_basis(&mut decomposed, 0);
+
+    assert_same_state(&mut rotated, &mut decomposed);
+}
+
+#[test]
+fn rxx_matches_decomposition() {
+    check_two_qubit_rotation(TraceSim::rxx, TraceSim::h, TraceSim::h);
+}
+
+#[test]
+fn ryy_matches_decomposition() {
+    check_two_qubit_rotation(
+        TraceSim::ryy,
+        |sim, q| {
+            sim.h(q);
+            sim.s(q);
+            sim.h(q);
+        },
+        |sim, q| {
+            sim.h(q);
+            sim.sadj(q);
+            sim.h(q);
+        },
+    );
+}
+
+#[test]
+fn rzz_matches_decomposition() {
+    check_two_qubit_rotation(TraceSim::rzz, |_, _| {}, |_, _| {});
+}
+
+#[test]
+fn exceeding_qubit_limit_is_reported() {
+    let mut sim = TraceSim::new(Vec::new());
+    for _ in 0..super::MAX_QUBITS {
+        sim.qubit_allocate();
+    }
+    assert!(!sim.exceeded_qubit_limit());
+    let q = sim.qubit_allocate();
+    sim.x(q);
+    assert!(sim.exceeded_qubit_limit());
+    assert!(!sim.m(q));
+    assert!(sim.measurements().is_empty());
+}
+
+fn run(decisions: &[(bool, f64)], result: &str) -> TracedRun {
+    TracedRun {
+        measurements: decisions
+            .iter()
+            .enumerate()
+            .map(|(qubit, (result, probability))| super::Measurement {
+                kind: MeasurementKind::M,
+                qubit,
+                result: *result,
+                probability: *probability,
+            })
+            .collect(),
+        result: result.to_string(),
+    }
+}
+
+#[test]
+fn attribution_tree_orders_branches_by_contribution() {
+    let runs = [
+        run(&[(false, 0.25), (false, 0.5)], "A"),
+        run(&[(false, 0.25), (true, 0.5)], "B"),
+        run(&[(true, 0.75), (false, 0.1)], "B"),
+        run(&[(true, 0.75), (true, 0.9)], "A"),
+    ];
+    expect![[r#"
+        0.8000
+          M(q0) = One (p = 0.7500): 0.6750 of 0.7500
+            M(q1) = One (p = 0.9000): 0.6750 of 0.6750 => A
+            M(q1) = Zero (p = 0.1000): 0.0000 of 0.0750 => B
+          M(q0) = Zero (p = 0.2500): 0.1250 of 0.2500
+            M(q1) = Zero (p = 0.5000): 0.1250 of 0.1250 => A
+            M(q1) = One (p = 0.5000): 0.0000 of 0.1250 => B"#]]
+    .assert_eq(&AttributionNode::new(&runs, "A").to_string());
+}
+
+#[test]
+fn attribution_tree_does_not_expand_branches_without_contribution() {
+    let runs = [
+        run(&[(false, 0.5)], "A"),
+        run(&[(true, 0.5), (false, 0.5)], "B"),
+        run(&[(true, 0.5), (true, 0.5)], "C"),
+    ];
+    expect![[r#"
+        0.5000
+          M(q0) = Zero (p = 0.5000): 0.5000 of 0.5000 => A
+          M(q0) = One (p = 0.5000): 0.0000 of 0.5000"#]]
+    .assert_eq(&AttributionNode::new(&runs, "A").to_string());
+}
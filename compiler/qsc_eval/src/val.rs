@@ -1,8 +1,12 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
-use num_bigint::BigInt;
-use qsc_data_structures::{display::join, functors::FunctorApp};
+use num_bigint::{BigInt, Sign};
+use qsc_data_structures::{
+    display::join,
+    format_spec::{FormatSpec, FormatType},
+    functors::FunctorApp,
+};
 use qsc_fir::fir::{Functor, Pauli, StoreItemId};
 use std::{
     array,
@@ -50,6 +54,8 @@ pub struct Range {
 pub enum Result {
     Val(bool),
     Id(usize),
+    /// The measured qubit was lost, so the measurement has no outcome.
+    Loss,
 }
 
 impl Result {
@@ -61,6 +67,7 @@ impl Result {
         match self {
             Self::Val(v) => v,
             Self::Id(_) => panic!("cannot unwrap Result::Id as bool"),
+            Self::Loss => panic!("cannot unwrap Result::Loss as bool"),
         }
     }
 
@@ -72,6 +79,7 @@ impl Result {
         match self {
             Self::Val(_) => panic!("cannot unwrap Result::Val as id"),
             Self::Id(v) => v,
+            Self::Loss => panic!("cannot unwrap Result::Loss as id"),
         }
     }
 }
@@ -138,6 +146,11 @@ impl QubitRef {
     pub fn deref(&self) -> Rc<Qubit> {
         self.try_deref().expect("qubit should still be alive")
     }
+
+    /// Creates a reference to a qubit that has already been released.
+    pub(crate) fn released() -> Self {
+        Self { inner: Weak::new() }
+    }
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
@@ -203,6 +216,7 @@ impl Display for Value {
                         write!(f, "Zero")
                     }
                 }
+                Result::Loss => write!(f, "Loss"),
             },
             Value::String(v) => write!(f, "{v}"),
             Value::Tuple(tup) => {
@@ -428,6 +442,55 @@ impl Value {
         v
     }
 
+    /// Formats the value according to a format specifier from an interpolated string.
+    /// Returns `None` if the specifier cannot be applied to a value of this type.
+    #[must_use]
+    pub fn format_with(&self, format: &FormatSpec) -> Option<String> {
+        let radix = format.ty.and_then(FormatType::radix);
+        match self {
+            Value::Int(v) => match radix {
+                Some(radix) => {
+                    let magnitude = v.unsigned_abs();
+                    let digits = match (radix, format.ty) {
+                        (2, _) => format!("{magnitude:b}"),
+                        (8, _) => format!("{magnitude:o}"),
+                        (_, Some(FormatType::UpperHex)) => format!("{magnitude:X}"),
+                        _ => format!("{magnitude:x}"),
+                    };
+                    Some(format.format_number(*v < 0, &digits))
+                }
+                None if format.ty.is_some() || format.precision.is_some() =>
+                {
+                    #[allow(clippy::cast_precision_loss)]
+                    Value::Double(*v as f64).format_with(format)
+                }
+                None => Some(format.format_number(*v < 0, &v.unsigned_abs().to_string())),
+            },
+            Value::BigInt(v) => {
+                if format.precision.is_some() || radix.is_none() && format.ty.is_some() {
+                    return None;
+                }
+                let mut digits = v.magnitude().to_str_radix(radix.unwrap_or(10));
+                if format.ty == Some(FormatType::UpperHex) {
+                    digits.make_ascii_uppercase();
+                }
+                Some(format.format_number(v.sign() == Sign::Minus, &digits))
+            }
+            Value::Double(v) => {
+                if radix.is_some() {
+                    return None;
+                }
+                let magnitude = Value::Double(v.abs());
+                let digits = format
+                    .float_digits(v.abs())
+                    .unwrap_or_else(|| magnitude.to_string());
+                Some(format.format_number(v.is_sign_negative() && !v.is_nan(), &digits))
+            }
+            _ if format.is_numeric() => None,
+            _ => Some(format.format_text(&self.to_string())),
+        }
+    }
+
     #[must_use]
     pub fn type_name(&self) -> &'static str {
         match self {
@@ -12,6 +12,7 @@ use crate::ty::{Arrow, FunctorSet, FunctorSetValue, GenericArg, Scheme, Ty, Type
 use indenter::{indented, Indented};
 use num_bigint::BigInt;
 use qsc_data_structures::{
+    format_spec::FormatSpec,
     index_map::{IndexMap, Iter},
     span::Span,
 };
@@ -633,7 +634,7 @@ impl PackageLookup for Package {
             ItemKind::Callable(callable) => Some(Global::Callable(callable)),
             ItemKind::Namespace(..) => None,
             ItemKind::Ty(..) => Some(Global::Udt),
-            ItemKind::Export(_name, _id) => None,
+            ItemKind::Export(..) | ItemKind::Class(_) => None,
         }
     }
 
@@ -710,6 +711,8 @@ pub enum ItemKind {
     Ty(Ident, Udt),
     /// An export referring to another item
     Export(Ident, ItemId),
+    /// A `class` declaration, whose constraints are only needed during type checking.
+    Class(Ident),
 }
 
 impl Display for ItemKind {
@@ -731,6 +734,7 @@ impl Display for ItemKind {
             }
             ItemKind::Ty(name, udt) => write!(f, "Type ({name}): {udt}"),
             ItemKind::Export(name, item) => write!(f, "Export ({name}): {item}"),
+            ItemKind::Class(name) => write!(f, "Class ({name})"),
         }
     }
 }
@@ -896,10 +900,111 @@ impl Display for SpecDecl {
     }
 }
 
-/// An execution graph represented by a reference counted vector of nodes.
-pub type ExecGraph = Rc<[ExecGraphNode]>;
+/// An execution graph, which is a view of a range of reference counted nodes. The targets of the
+/// jumps in the range are relative to its start, so nodes are read without converting them. A
+/// section of a graph shares the nodes of the graph when its jumps don't need to be rebased, and
+/// otherwise copies its nodes with the jumps rebased once.
+#[derive(Clone, Debug)]
+pub struct ExecGraph {
+    nodes: Rc<[ExecGraphNode]>,
+    range: ops::Range<usize>,
+}
+
+impl ExecGraph {
+    /// The node at the given index, with the targets of jumps relative to the start of the view.
+    #[must_use]
+    pub fn get(&self, idx: usize) -> Option<ExecGraphNode> {
+        if idx < self.len() {
+            Some(self.nodes[self.range.start + idx])
+        } else {
+            None
+        }
+    }
+
+    /// The number of nodes in the graph.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.range.len()
+    }
+
+    /// Whether the graph has no nodes.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.range.is_empty()
+    }
+
+    /// The nodes of the graph, with the targets of jumps relative to the start of the view.
+    pub fn iter(&self) -> impl Iterator<Item = ExecGraphNode> + '_ {
+        self.nodes[self.range.clone()].iter().copied()
+    }
+
+    /// The section of this graph in the given range. Jumps in the section are relative to its
+    /// start, so the range must cover the targets of its jumps. The section shares the nodes of
+    /// this graph if it starts where this graph starts or has no jumps, and otherwise copies them.
+    #[must_use]
+    pub fn section(&self, range: ops::Range<usize>) -> Self {
+        assert!(
+            range.start <= range.end && range.end <= self.len(),
+            "exec graph section should be within the graph"
+        );
+        let nodes = &self.nodes[self.range.start + range.start..self.range.start + range.end];
+        let is_jump = |node: &ExecGraphNode| {
+            matches!(
+                node,
+                ExecGraphNode::Jump(_) | ExecGraphNode::JumpIf(_) | ExecGraphNode::JumpIfNot(_)
+            )
+        };
+        if range.start != 0 && nodes.iter().any(is_jump) {
+            let offset = u32::try_from(range.start).expect("exec graph ranges should fit into u32");
+            return nodes
+                .iter()
+                .map(|node| match *node {
+                    ExecGraphNode::Jump(idx) => ExecGraphNode::Jump(idx - offset),
+                    ExecGraphNode::JumpIf(idx) => ExecGraphNode::JumpIf(idx - offset),
+                    ExecGraphNode::JumpIfNot(idx) => ExecGraphNode::JumpIfNot(idx - offset),
+                    node => node,
+                })
+                .collect();
+        }
+        Self {
+            nodes: Rc::clone(&self.nodes),
+            range: self.range.start + range.start..self.range.start + range.end,
+        }
+    }
 
-#[derive(Copy, Clone, Debug, PartialEq)]
+    /// Whether this graph and the other one are views of the same nodes.
+    #[must_use]
+    pub fn shares_nodes(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.nodes, &other.nodes)
+    }
+}
+
+impl PartialEq for ExecGraph {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl From<Rc<[ExecGraphNode]>> for ExecGraph {
+    fn from(nodes: Rc<[ExecGraphNode]>) -> Self {
+        let range = 0..nodes.len();
+        Self { nodes, range }
+    }
+}
+
+impl From<Vec<ExecGraphNode>> for ExecGraph {
+    fn from(nodes: Vec<ExecGraphNode>) -> Self {
+        Rc::<[ExecGraphNode]>::from(nodes).into()
+    }
+}
+
+impl FromIterator<ExecGraphNode> for ExecGraph {
+    fn from_iter<I: IntoIterator<Item = ExecGraphNode>>(iter: I) -> Self {
+        iter.into_iter().collect::<Rc<[_]>>().into()
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
 /// A node within the control flow graph.
 pub enum ExecGraphNode {
     /// A binding of a value to a variable.
@@ -929,6 +1034,9 @@ pub enum ExecGraphNode {
     PushScope,
     /// A pop of the current scope, used when tracking variables for debugging.
     PopScope,
+    /// A call expression of a function to itself that is immediately followed by the end of the
+    /// control flow graph. Its frame can be reused for the call instead of pushing a new one.
+    TailCall(ExprId),
 }
 
 /// A sequenced block of statements.
@@ -1329,7 +1437,10 @@ fn display_string(mut indent: Indented<Formatter>, components: &[StringComponent
     indent = set_indentation(indent, 1);
     for component in components {
         match component {
-            StringComponent::Expr(expr) => write!(indent, "\nExpr: {expr}")?,
+            StringComponent::Expr(expr, None) => write!(indent, "\nExpr: {expr}")?,
+            StringComponent::Expr(expr, Some(format)) => {
+                write!(indent, "\nExpr (:{format}): {expr}")?;
+            }
             StringComponent::Lit(str) => write!(indent, "\nLit: {str:?}")?,
         }
     }
@@ -1435,8 +1546,8 @@ impl Display for FieldAssign {
 /// A string component.
 #[derive(Clone, Debug, PartialEq)]
 pub enum StringComponent {
-    /// An expression.
-    Expr(ExprId),
+    /// An expression, with an optional format specifier.
+    Expr(ExprId, Option<FormatSpec>),
     /// A string literal.
     Lit(Rc<str>),
 }
@@ -1525,6 +1636,14 @@ pub enum Attr {
     Measurement,
     /// Indicates that a callable is a reset.
     Reset,
+    /// Indicates that a function has been verified to have no side effects.
+    Pure,
+    /// Indicates that an intrinsic function is implemented by the host running the program.
+    HostCallable,
+    /// Indicates that a type is a quantity, which is erased to its base type when lowered.
+    Quantity,
+    /// Names the values output by an entry point, either as a whole or, when it returns a tuple, by item.
+    OutputName(Vec<Rc<str>>),
 }
 
 /// A field.
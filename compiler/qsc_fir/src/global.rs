@@ -108,15 +108,18 @@ impl PackageIter<'_> {
                 }),
             }),
             (ItemKind::Ty(name, def), Some(ItemKind::Namespace(namespace, _))) => {
-                self.next = Some(Global {
-                    namespace: Rc::clone(&namespace.name),
-                    name: Rc::clone(&name.name),
-                    visibility: item.visibility,
-                    kind: Kind::Term(Term {
-                        id,
-                        scheme: def.cons_scheme(id),
-                    }),
-                });
+                // An enum has no constructor of its own, its values are built by its variants.
+                if !def.is_enum() {
+                    self.next = Some(Global {
+                        namespace: Rc::clone(&namespace.name),
+                        name: Rc::clone(&name.name),
+                        visibility: item.visibility,
+                        kind: Kind::Term(Term {
+                            id,
+                            scheme: def.cons_scheme(id),
+                        }),
+                    });
+                }
 
                 Some(Global {
                     namespace: Rc::clone(&namespace.name),
@@ -64,7 +64,10 @@ pub fn walk_package<'a>(vis: &mut impl MutVisitor<'a>, package: &'a mut Package)
 pub fn walk_item<'a>(vis: &mut impl MutVisitor<'a>, item: &'a mut Item) {
     match &mut item.kind {
         ItemKind::Callable(decl) => vis.visit_callable_decl(decl),
-        ItemKind::Namespace(name, _) | ItemKind::Ty(name, _) | ItemKind::Export(name, _) => {
+        ItemKind::Namespace(name, _)
+        | ItemKind::Ty(name, _)
+        | ItemKind::Export(name, _)
+        | ItemKind::Class(name) => {
             vis.visit_ident(name);
         }
     };
@@ -184,7 +187,7 @@ pub fn walk_expr<'a>(vis: &mut impl MutVisitor<'a>, expr: ExprId) {
         ExprKind::String(components) => {
             for component in components {
                 match component {
-                    StringComponent::Expr(expr) => vis.visit_expr(*expr),
+                    StringComponent::Expr(expr, _) => vis.visit_expr(*expr),
                     StringComponent::Lit(_) => {}
                 }
             }
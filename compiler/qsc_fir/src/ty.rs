@@ -5,7 +5,7 @@ use indenter::{indented, Indented};
 use qsc_data_structures::span::Span;
 use rustc_hash::FxHashMap;
 
-use crate::fir::{CallableKind, FieldPath, Functor, ItemId, Res};
+use crate::fir::{CallableKind, FieldPath, Functor, ItemId, LocalItemId, Res};
 use std::{
     fmt::{self, Debug, Display, Formatter, Write},
     rc::Rc,
@@ -485,11 +485,34 @@ pub struct Udt {
     pub name: Rc<str>,
     // The definition.
     pub definition: UdtDef,
+    /// The variants of the type, if it is an enum. The values of an enum are tuples of the index of
+    /// their variant and its payload, so an enum has no constructor and no pure type.
+    pub variants: Vec<UdtVariant>,
+}
+
+/// A variant of an enum.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UdtVariant {
+    /// The span.
+    pub span: Span,
+    /// The name.
+    pub name: Rc<str>,
+    /// The type of the payload, which is `Unit` for a variant without one.
+    pub ty: Ty,
+    /// The function that constructs values of this variant, which is declared in the same package as
+    /// the enum.
+    pub item: LocalItemId,
 }
 
 impl Udt {
+    /// The type that this user-defined type wraps.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this type is an enum, since the payload of its values depends on their variant.
     #[must_use]
     pub fn get_pure_ty(&self) -> Ty {
+        assert!(!self.is_enum(), "an enum has no pure type");
         fn get_pure_ty(def: &UdtDef) -> Ty {
             match &def.kind {
                 UdtDefKind::Field(field) => field.ty.clone(),
@@ -517,6 +540,12 @@ impl Udt {
         }
     }
 
+    /// Whether this type is an enum.
+    #[must_use]
+    pub fn is_enum(&self) -> bool {
+        !self.variants.is_empty()
+    }
+
     /// The path to the field with the given name. Returns [None] if this user-defined type does not
     /// have a field with the given name.
     #[must_use]
@@ -578,6 +607,13 @@ impl Display for Udt {
         write!(indent, "UDT {}:", self.span)?;
         indent = set_indentation(indent, 1);
         write!(indent, "\n{}", self.definition)?;
+        for variant in &self.variants {
+            write!(
+                indent,
+                "\nVariant {} ({}): {} = Item {}",
+                variant.span, variant.name, variant.ty, variant.item
+            )?;
+        }
         Ok(())
     }
 }
@@ -64,7 +64,9 @@ pub fn walk_package<'a>(vis: &mut impl Visitor<'a>, package: &'a Package) {
 pub fn walk_item<'a>(vis: &mut impl Visitor<'a>, item: &'a Item) {
     match &item.kind {
         ItemKind::Callable(decl) => vis.visit_callable_decl(decl),
-        ItemKind::Namespace(name, _) | ItemKind::Ty(name, _) => vis.visit_ident(name),
+        ItemKind::Namespace(name, _) | ItemKind::Ty(name, _) | ItemKind::Class(name) => {
+            vis.visit_ident(name);
+        }
         ItemKind::Export(name, _) => {
             vis.visit_ident(name);
         }
@@ -185,7 +187,7 @@ pub fn walk_expr<'a>(vis: &mut impl Visitor<'a>, expr: ExprId) {
         ExprKind::String(components) => {
             for component in components {
                 match component {
-                    StringComponent::Expr(expr) => vis.visit_expr(*expr),
+                    StringComponent::Expr(expr, _) => vis.visit_expr(*expr),
                     StringComponent::Lit(_) => {}
                 }
             }
@@ -434,7 +434,8 @@ impl Formatter<'_> {
                     );
                 }
                 (String(StringToken::Interpolated(_, InterpolatedEnding::LBrace)), _)
-                | (_, String(StringToken::Interpolated(InterpolatedStart::RBrace, _))) => {
+                | (_, String(StringToken::Interpolated(InterpolatedStart::RBrace, _)))
+                | (_, FormatSpec) => {
                     effect_no_space(left, whitespace, right, &mut edits);
                 }
                 (DotDotDot, _) if matches!(self.spec_decl_state, SpecDeclState::OnEllipse) => {
@@ -753,6 +754,9 @@ fn is_newline_keyword_or_ampersat(cooked: &TokenKind) -> bool {
                     | Function
                     | Newtype
                     | Struct
+                    | Enum
+                    | Const
+                    | Class
                     | Namespace
                     | Open
                     | Body
@@ -774,7 +778,7 @@ fn is_starter_keyword(keyword: &Keyword) -> bool {
     use Keyword::*;
     matches!(
         keyword,
-        For | While | Repeat | If | Within | New | Return | Fail
+        For | While | Repeat | If | Match | Within | New | Return | Fail
     )
 }
 
@@ -53,6 +53,49 @@ World\""
     assert!(super::calculate_format_edits(input.as_str()).is_empty());
 }
 
+#[test]
+fn multibyte_chars_in_strings_and_comments() {
+    let extra_spaces = "    ";
+    let input = format!(
+        "/// Doc Comment with émoji 🎉{extra_spaces}
+        operation Foo() : Unit {{   let s = \"héllo 🎉\";{extra_spaces}
+            // Comment with €{extra_spaces}
+            let 𝑥 = $\"🎉{{s}}🎉\" ;
+        }}
+"
+    );
+
+    check(
+        input.as_str(),
+        &expect![[r#"
+        /// Doc Comment with émoji 🎉
+        operation Foo() : Unit {
+            let s = "héllo 🎉";
+            // Comment with €
+            let 𝑥 = $"🎉{s}🎉";
+        }
+    "#]],
+    );
+}
+
+#[test]
+fn multibyte_chars_edit_spans() {
+    check_edits(
+        "let s = \"🎉\" ;",
+        &expect![[r#"
+        [
+            TextEdit {
+                new_text: "",
+                span: Span {
+                    lo: 14,
+                    hi: 15,
+                },
+            },
+        ]
+    "#]],
+    );
+}
+
 // Namespace items begin on their own lines
 
 #[test]
@@ -334,6 +377,14 @@ fn no_space_for_string_interpolation_argument_braces() {
     );
 }
 
+#[test]
+fn no_space_before_string_interpolation_format_spec() {
+    check(
+        r#"let x = $"Value { x :0.3f} and {y:>8}";"#,
+        &expect![[r#"let x = $"Value {x:0.3f} and {y:>8}";"#]],
+    );
+}
+
 // No space before commas or semicolons
 
 #[test]
@@ -4,8 +4,11 @@
 #[cfg(test)]
 mod tests;
 
+mod cache;
 pub mod preprocess;
 
+pub use cache::{CompileCache, CompileKey};
+
 use crate::{
     error::WithSource,
     lower::{self, Lowerer},
@@ -35,10 +38,12 @@ use qsc_hir::{
     validate::Validator as HirValidator,
     visit::Visitor as _,
 };
+use qsc_parse::incremental::ParseCache;
+use rustc_hash::FxHashSet;
 use std::{fmt::Debug, sync::Arc};
 use thiserror::Error;
 
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub struct CompileUnit {
     pub package: hir::Package,
     pub ast: AstPackage,
@@ -46,17 +51,27 @@ pub struct CompileUnit {
     pub sources: SourceMap,
     pub errors: Vec<Error>,
     pub dropped_names: Vec<TrackedName>,
+    /// The spans of items excluded from compilation because their `@Config` attributes
+    /// do not match the target capabilities.
+    pub excluded_spans: Vec<Span>,
+    /// The packages this package was compiled against, which must stay in the store as long
+    /// as this package does.
+    pub dependencies: Vec<PackageId>,
+    /// The fingerprint of the compilation that produced this package, if it was compiled through
+    /// a [`CompileCache`]. Packages without one can't be reused, and neither can their dependents.
+    pub fingerprint: Option<CompileKey>,
 }
 
 impl CompileUnit {
     pub fn expose(&mut self) {
+        self.fingerprint = None;
         for (_item_id, item) in self.package.items.iter_mut() {
             item.visibility = hir::Visibility::Public;
         }
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub struct AstPackage {
     pub package: ast::Package,
     pub tys: Table,
@@ -154,7 +169,7 @@ impl SourceMap {
 
     /// Returns the sources as an iter, but with the project root directory subtracted
     /// from the individual source names.
-    pub(crate) fn relative_sources(&self) -> impl Iterator<Item = Source> + '_ {
+    pub fn relative_sources(&self) -> impl Iterator<Item = Source> + '_ {
         self.sources.iter().map(move |source| {
             let name = source.name.as_ref();
             let relative_name = self.relative_name(name);
@@ -275,6 +290,33 @@ impl PackageStore {
     pub fn is_empty(&self) -> bool {
         self.units.is_empty()
     }
+
+    /// Removes the packages that can't be reached from the given roots through the
+    /// dependencies of each package, and returns their IDs. The core library is always kept.
+    /// IDs of removed packages are never reused.
+    pub fn collect(&mut self, roots: impl IntoIterator<Item = PackageId>) -> Vec<PackageId> {
+        let mut reachable = FxHashSet::default();
+        let mut stack = roots.into_iter().collect::<Vec<_>>();
+        stack.push(PackageId::CORE);
+        while let Some(id) = stack.pop() {
+            if reachable.insert(id) {
+                if let Some(unit) = self.units.get(id) {
+                    stack.extend(unit.dependencies.iter().copied());
+                }
+            }
+        }
+
+        let unreachable = self
+            .units
+            .iter()
+            .map(|(id, _)| id)
+            .filter(|id| !reachable.contains(id))
+            .collect::<Vec<_>>();
+        for &id in &unreachable {
+            self.units.remove(id);
+        }
+        unreachable
+    }
 }
 
 impl<'a> IntoIterator for &'a PackageStore {
@@ -311,14 +353,15 @@ impl OpenPackageStore {
     #[must_use]
     pub fn get_open_mut(&mut self) -> (&global::Table, &mut CompileUnit) {
         let id = self.open;
+        let unit = self
+            .store
+            .units
+            .get_mut(id)
+            .expect("open package id should exist in store");
+        // The open package is about to change, so it no longer matches its fingerprint.
+        unit.fingerprint = None;
 
-        (
-            &self.store.core,
-            self.store
-                .units
-                .get_mut(id)
-                .expect("open package id should exist in store"),
-        )
+        (&self.store.core, unit)
     }
 
     /// Consumes the `OpenPackageStore` and returns a `PackageStore`
@@ -362,7 +405,34 @@ pub fn compile(
     capabilities: TargetCapabilityFlags,
     language_features: LanguageFeatures,
 ) -> CompileUnit {
-    let (ast_package, parse_errors) = parse_all(&sources, language_features);
+    let (ast_package, parse_errors) = parse_all(&sources, language_features, |source| {
+        qsc_parse::namespaces(&source.contents, Some(&source.name), language_features)
+    });
+
+    compile_ast(
+        store,
+        dependencies,
+        ast_package,
+        sources,
+        capabilities,
+        parse_errors,
+    )
+}
+
+/// Compiles a package from its sources like [`compile`], reparsing only the parts of each source
+/// that changed since it was last parsed with the same cache.
+#[must_use]
+pub fn compile_with_parse_cache(
+    store: &PackageStore,
+    dependencies: &Dependencies,
+    sources: SourceMap,
+    capabilities: TargetCapabilityFlags,
+    language_features: LanguageFeatures,
+    parse_cache: &mut ParseCache,
+) -> CompileUnit {
+    let (ast_package, parse_errors) = parse_all(&sources, language_features, |source| {
+        parse_cache.namespaces(&source.contents, &source.name, language_features)
+    });
 
     compile_ast(
         store,
@@ -385,6 +455,7 @@ pub fn compile_ast(
 ) -> CompileUnit {
     let mut cond_compile = preprocess::Conditional::new(capabilities);
     cond_compile.visit_package(&mut ast_package);
+    let excluded_spans = cond_compile.take_excluded_spans();
     let dropped_names = cond_compile.into_names();
 
     let mut ast_assigner = AstAssigner::new();
@@ -432,6 +503,9 @@ pub fn compile_ast(
         sources,
         errors,
         dropped_names,
+        excluded_spans,
+        dependencies: dependencies.iter().map(|(id, _)| *id).collect(),
+        fingerprint: None,
     }
 }
 
@@ -492,12 +566,12 @@ pub fn std(store: &PackageStore, capabilities: TargetCapabilityFlags) -> Compile
 fn parse_all(
     sources: &SourceMap,
     features: LanguageFeatures,
+    mut parse_namespaces: impl FnMut(&Source) -> (Vec<ast::Namespace>, Vec<qsc_parse::Error>),
 ) -> (ast::Package, Vec<qsc_parse::Error>) {
     let mut namespaces = Vec::new();
     let mut errors = Vec::new();
     for source in sources.relative_sources() {
-        let (source_namespaces, source_errors) =
-            qsc_parse::namespaces(&source.contents, Some(&source.name), features);
+        let (source_namespaces, source_errors) = parse_namespaces(&source);
         for mut namespace in source_namespaces {
             Offsetter(source.offset).visit_namespace(&mut namespace);
             namespaces.push(TopLevelNode::Namespace(namespace));
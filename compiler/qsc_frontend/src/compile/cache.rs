@@ -0,0 +1,180 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! Reuse of compiled packages across compilations. A [`CompileKey`] fingerprints everything that
+//! goes into compiling a package: its sources, the target capabilities, the language features,
+//! and the fingerprints of the packages it depends on, along with the IDs they have in the store.
+//! A [`CompileCache`] keeps the most recently used packages by key, so a package whose sources and
+//! dependencies have not changed since it was last compiled is cloned from the cache instead of
+//! being compiled again. Since each key includes the keys of the dependencies, a change to one
+//! package invalidates every package that depends on it, directly or not.
+//!
+//! Reuse is per package: a package whose key has changed is resolved and type-checked again in
+//! full, and only its parsing is reused per source file, through a
+//! [`ParseCache`](qsc_parse::incremental::ParseCache). Name resolution and type checking are not
+//! reused for the unchanged files of a package. A declaration in one file can change how names
+//! in every other file resolve, and their results are keyed by node and item IDs that are
+//! numbered across the whole package, so an edit to one file renumbers the files after it.
+//!
+//! Keys are SHA-256 digests of the inputs, so two different compilations can't share a key in
+//! practice.
+
+use super::{CompileUnit, Dependencies, PackageStore, SourceMap};
+use qsc_data_structures::{
+    language_features::LanguageFeatures, sha256::Digester, target::TargetCapabilityFlags,
+};
+use qsc_hir::hir::PackageId;
+use rustc_hash::FxHashMap;
+use std::hash::Hash;
+
+/// A fingerprint of the inputs to the compilation of a package, as the SHA-256 digest of the
+/// inputs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CompileKey([u8; 32]);
+
+impl CompileKey {
+    /// Fingerprints the compilation of a package from its sources against the given dependencies
+    /// in the store. Returns `None` if the core library or any of the dependencies was not compiled
+    /// through a cache, since its contents can't be fingerprinted.
+    #[must_use]
+    pub fn new(
+        store: &PackageStore,
+        dependencies: &Dependencies,
+        sources: &SourceMap,
+        capabilities: TargetCapabilityFlags,
+        language_features: LanguageFeatures,
+    ) -> Option<Self> {
+        let mut hasher = Digester::default();
+        store.get(PackageId::CORE)?.fingerprint?.hash(&mut hasher);
+        for (id, alias) in dependencies {
+            id.hash(&mut hasher);
+            alias.hash(&mut hasher);
+            store.get(*id)?.fingerprint?.hash(&mut hasher);
+        }
+        for source in sources.entry.iter().chain(sources.iter()) {
+            source.name.hash(&mut hasher);
+            source.contents.hash(&mut hasher);
+        }
+        capabilities.bits().hash(&mut hasher);
+        language_features.bits().hash(&mut hasher);
+        Some(CompileKey(hasher.digest()))
+    }
+
+    /// Fingerprints the compilation of a library that is built into the compiler, such as the
+    /// core and standard libraries, whose sources never change.
+    #[must_use]
+    pub fn library(name: &str, capabilities: TargetCapabilityFlags) -> Self {
+        let mut hasher = Digester::default();
+        name.hash(&mut hasher);
+        capabilities.bits().hash(&mut hasher);
+        CompileKey(hasher.digest())
+    }
+
+    /// Extends the fingerprint with another input to the compilation, such as an option for the
+    /// passes that run after the frontend.
+    #[must_use]
+    pub fn with(self, value: impl Hash) -> Self {
+        let mut hasher = Digester::default();
+        self.0.hash(&mut hasher);
+        value.hash(&mut hasher);
+        CompileKey(hasher.digest())
+    }
+}
+
+/// The most recently used compiled packages, keyed by the fingerprints of their compilations. Each
+/// package is stored along with the data that was produced with it, such as its errors.
+pub struct CompileCache<T> {
+    entries: FxHashMap<CompileKey, CachedUnit<T>>,
+    capacity: usize,
+    clock: u64,
+}
+
+struct CachedUnit<T> {
+    unit: CompileUnit,
+    data: T,
+    last_used: u64,
+}
+
+impl<T> Default for CompileCache<T> {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CAPACITY)
+    }
+}
+
+impl<T> CompileCache<T> {
+    /// The number of packages a default cache keeps, which covers the core and standard libraries
+    /// and the dependencies of a typical project for a few different target capabilities.
+    pub const DEFAULT_CAPACITY: usize = 32;
+
+    /// Creates a cache that keeps at most `capacity` packages.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: FxHashMap::default(),
+            capacity,
+            clock: 0,
+        }
+    }
+
+    fn evict_least_recently_used(&mut self) {
+        if let Some(key) = self
+            .entries
+            .iter()
+            .min_by_key(|(_, cached)| cached.last_used)
+            .map(|(key, _)| *key)
+        {
+            self.entries.remove(&key);
+        }
+    }
+
+    /// Forgets every cached package.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// The number of cached packages.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl<T: Clone> CompileCache<T> {
+    /// Returns a copy of the package compiled with the given key, calling `compile` to compile it
+    /// if it isn't in the cache. The returned package is fingerprinted with the key, so packages
+    /// that depend on it can be cached as well. When the cache is full, the least recently used
+    /// package is evicted to make room for a new one.
+    pub fn get_or_compile(
+        &mut self,
+        key: CompileKey,
+        compile: impl FnOnce() -> (CompileUnit, T),
+    ) -> (CompileUnit, T) {
+        self.clock += 1;
+        if let Some(cached) = self.entries.get_mut(&key) {
+            cached.last_used = self.clock;
+            return (cached.unit.clone(), cached.data.clone());
+        }
+
+        let (mut unit, data) = compile();
+        unit.fingerprint = Some(key);
+        if self.capacity > 0 {
+            if self.entries.len() >= self.capacity {
+                self.evict_least_recently_used();
+            }
+            self.entries.insert(
+                key,
+                CachedUnit {
+                    unit: unit.clone(),
+                    data: data.clone(),
+                    last_used: self.clock,
+                },
+            );
+        }
+        (unit, data)
+    }
+}
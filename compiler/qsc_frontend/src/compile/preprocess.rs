@@ -4,10 +4,11 @@
 use core::str::FromStr;
 use qsc_ast::{
     ast::{Attr, ExprKind, Idents, ItemKind, Namespace, PathKind, Stmt, StmtKind, UnOp},
-    mut_visit::MutVisitor,
+    mut_visit::{walk_namespace, walk_stmt, MutVisitor},
 };
+use qsc_data_structures::span::Span;
 use qsc_hir::hir;
-use std::rc::Rc;
+use std::{mem::take, rc::Rc};
 
 use super::TargetCapabilityFlags;
 
@@ -24,6 +25,7 @@ pub(crate) struct Conditional {
     capabilities: TargetCapabilityFlags,
     dropped_names: Vec<TrackedName>,
     included_names: Vec<TrackedName>,
+    excluded_spans: Vec<Span>,
 }
 
 impl Conditional {
@@ -32,9 +34,15 @@ impl Conditional {
             capabilities,
             dropped_names: Vec::new(),
             included_names: Vec::new(),
+            excluded_spans: Vec::new(),
         }
     }
 
+    /// Takes the spans of the items that were excluded by their `@Config` attributes.
+    pub(crate) fn take_excluded_spans(&mut self) -> Vec<Span> {
+        take(&mut self.excluded_spans)
+    }
+
     pub(crate) fn into_names(self) -> Vec<TrackedName> {
         self.dropped_names
             .into_iter()
@@ -78,11 +86,15 @@ impl MutVisitor for Conditional {
                         }),
                         _ => {}
                     }
+                    self.excluded_spans.push(item.span);
                     None
                 }
             })
             .collect::<Vec<_>>()
             .into_boxed_slice();
+
+        // Items declared in the bodies of the remaining callables can have their own `@Config` attributes.
+        walk_namespace(self, namespace);
     }
 
     fn visit_stmt(&mut self, stmt: &mut Stmt) {
@@ -115,9 +127,12 @@ impl MutVisitor for Conditional {
                     }),
                     _ => {}
                 }
+                self.excluded_spans.push(item.span);
                 stmt.kind = Box::new(StmtKind::Empty);
+                return;
             }
         }
+        walk_stmt(self, stmt);
     }
 }
 
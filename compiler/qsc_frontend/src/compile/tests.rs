@@ -5,7 +5,10 @@ mod multiple_packages;
 
 use std::sync::Arc;
 
-use super::{compile, longest_common_prefix, CompileUnit, Error, PackageStore, SourceMap};
+use super::{
+    compile, compile_with_parse_cache, longest_common_prefix, CompileCache, CompileKey,
+    CompileUnit, Dependencies, Error, PackageStore, ParseCache, SourceMap,
+};
 use crate::compile::TargetCapabilityFlags;
 
 use expect_test::expect;
@@ -15,8 +18,8 @@ use qsc_data_structures::{language_features::LanguageFeatures, span::Span};
 use qsc_hir::{
     global,
     hir::{
-        Block, Expr, ExprKind, ItemId, ItemKind, Lit, LocalItemId, NodeId, Res, SpecBody, Stmt,
-        StmtKind,
+        Block, Expr, ExprKind, ItemId, ItemKind, Lit, LocalItemId, NodeId, PackageId, Res,
+        SpecBody, Stmt, StmtKind,
     },
     mut_visit::MutVisitor,
     ty::{Prim, Ty},
@@ -114,6 +117,22 @@ fn one_file_error() {
     assert_eq!(vec![("test", Span { lo: 50, hi: 51 })], errors);
 }
 
+#[test]
+fn equivalent_identifier_spellings_resolve_to_same_item() {
+    let sources = SourceMap::new(
+        [(
+            "test".into(),
+            // The callable is declared with a precomposed `é` and called with `e` and a combining accent.
+            "namespace Foo { function Caf\u{e9}() : Unit {} function B() : Unit { Cafe\u{301}(); } }"
+                .into(),
+        )],
+        None,
+    );
+
+    let unit = default_compile(sources);
+    assert!(unit.errors.is_empty(), "{:#?}", unit.errors);
+}
+
 #[test]
 fn two_files_dependency() {
     let sources = SourceMap::new(
@@ -215,13 +234,50 @@ fn two_files_error() {
         .map(|error| source_span(&unit.sources, error))
         .collect();
 
-    assert_eq!(
-        vec![
-            ("test2", Span { lo: 50, hi: 51 }),
-            ("test2", Span { lo: 50, hi: 53 }),
-        ],
-        errors
-    );
+    assert_eq!(vec![("test2", Span { lo: 50, hi: 51 })], errors);
+}
+
+#[test]
+fn parse_cache_edit_matches_full_compile() {
+    let source = |body: &str| {
+        SourceMap::new(
+            [(
+                "test".into(),
+                format!(
+                    "namespace Foo {{\n    function A() : Unit {{ {body} }}\n    function B() : Unit {{ C(); }}\n}}\n"
+                )
+                .into(),
+            )],
+            None,
+        )
+    };
+
+    let store = PackageStore::new(super::core());
+    let mut parse_cache = ParseCache::default();
+    let compile_cached = |sources, parse_cache: &mut ParseCache| {
+        compile_with_parse_cache(
+            &store,
+            &[],
+            sources,
+            TargetCapabilityFlags::all(),
+            LanguageFeatures::default(),
+            parse_cache,
+        )
+    };
+
+    let _ = compile_cached(source(""), &mut parse_cache);
+    let cached = compile_cached(source("let x = 1;"), &mut parse_cache);
+    let full = default_compile(source("let x = 1;"));
+
+    let spans = |unit: &CompileUnit| {
+        unit.errors
+            .iter()
+            .map(|error| source_span(&unit.sources, error))
+            .map(|(name, span)| (name.to_string(), span))
+            .collect::<Vec<_>>()
+    };
+    assert!(!full.errors.is_empty());
+    assert_eq!(spans(&full), spans(&cached));
 }
 
 #[test]
@@ -416,7 +472,7 @@ fn insert_core_call() {
                     body: SpecDecl 3 [18-41]: Impl:
                         Block 4 [39-41] [Type Unit]:
                             Stmt 6 [0-0]: Semi: Expr 7 [0-0] [Type Qubit]: Call:
-                                Expr 8 [0-0] [Type (Unit => Qubit)]: Var: Item 4 (Package 0)
+                                Expr 8 [0-0] [Type (Unit => Qubit)]: Var: Item 11 (Package 0)
                                 Expr 9 [0-0] [Type Unit]: Unit
                     adj: <none>
                     ctl: <none>
@@ -654,6 +710,95 @@ fn package_dependency_udt() {
     .assert_eq(&unit2.package.to_string());
 }
 
+#[test]
+fn package_dependency_class() {
+    let mut store = PackageStore::new(super::core());
+
+    let sources1 = SourceMap::new(
+        [(
+            "test".into(),
+            indoc! {"
+                namespace Package1 {
+                    class Numeric : Add + Mul;
+                    function Square<'T: Numeric>(a : 'T) : 'T {
+                        a * a
+                    }
+                    export Numeric, Square;
+                }
+            "}
+            .into(),
+        )],
+        None,
+    );
+    let unit1 = compile(
+        &store,
+        &[],
+        sources1,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::default(),
+    );
+    assert!(unit1.errors.is_empty(), "{:#?}", unit1.errors);
+    let package1 = store.insert(unit1);
+
+    let sources2 = SourceMap::new(
+        [(
+            "test".into(),
+            indoc! {"
+                namespace Package2 {
+                    import PackageAlias.Package1.*;
+                    function Cube<'T: Numeric>(a : 'T) : 'T {
+                        a * Square(a)
+                    }
+                    function Main() : (Int, Double, Bool) {
+                        (Cube(2), Square(1.5), Square(true))
+                    }
+                }
+            "}
+            .into(),
+        )],
+        None,
+    );
+    let unit2 = compile(
+        &store,
+        &[(package1, Some(Arc::from("PackageAlias")))],
+        sources2,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::default(),
+    );
+
+    expect![[r#"
+        [
+            Error(
+                Type(
+                    Error(
+                        MissingClassAdd(
+                            "Bool",
+                            Span {
+                                lo: 206,
+                                hi: 218,
+                            },
+                        ),
+                    ),
+                ),
+            ),
+            Error(
+                Type(
+                    Error(
+                        MissingClassMul(
+                            "Bool",
+                            Span {
+                                lo: 206,
+                                hi: 218,
+                            },
+                        ),
+                    ),
+                ),
+            ),
+        ]
+    "#]]
+    .assert_debug_eq(&unit2.errors);
+}
+
 #[test]
 fn package_dependency_nested_udt() {
     let mut store = PackageStore::new(super::core());
@@ -1489,3 +1634,405 @@ fn export_namespace_with_same_name_as_newtype_does_not_cause_panic() {
     let unit = default_compile(sources);
     expect!["[]"].assert_eq(&format!("{:?}", unit.errors));
 }
+
+#[test]
+fn items_excluded_by_config_are_reported() {
+    let source = indoc! {"
+        namespace Foo {
+            @Config(Adaptive)
+            operation A() : Unit {}
+            @Config(Base)
+            operation B() : Unit {}
+            @Config(Adaptive)
+            newtype C = Int;
+        }
+    "};
+    let sources = SourceMap::new([("test".into(), source.into())], None);
+
+    let unit = compile(
+        &PackageStore::new(super::core()),
+        &[],
+        sources,
+        TargetCapabilityFlags::empty(),
+        LanguageFeatures::default(),
+    );
+    assert!(unit.errors.is_empty(), "{:#?}", unit.errors);
+
+    let excluded = unit
+        .excluded_spans
+        .iter()
+        .map(|span| &source[span.lo as usize..span.hi as usize])
+        .collect::<Vec<_>>();
+    expect![[r#"
+        [
+            "@Config(Adaptive)\n    operation A() : Unit {}",
+            "@Config(Adaptive)\n    newtype C = Int;",
+        ]
+    "#]]
+    .assert_debug_eq(&excluded);
+}
+
+#[test]
+fn collect_removes_packages_unreachable_from_roots() {
+    let mut store = PackageStore::new(super::core());
+    let insert = |store: &mut PackageStore, name: &str, dependencies: &[PackageId]| {
+        let dependencies = dependencies
+            .iter()
+            .map(|id| (*id, None))
+            .collect::<Vec<_>>();
+        let unit = compile(
+            store,
+            &dependencies,
+            SourceMap::new(
+                [(name.into(), format!("namespace {name} {{}}").into())],
+                None,
+            ),
+            TargetCapabilityFlags::all(),
+            LanguageFeatures::default(),
+        );
+        assert!(unit.errors.is_empty(), "{:?}", unit.errors);
+        store.insert(unit)
+    };
+
+    let a = insert(&mut store, "A", &[]);
+    let b = insert(&mut store, "B", &[a]);
+    let c = insert(&mut store, "C", &[a]);
+    let d = insert(&mut store, "D", &[b]);
+
+    assert_eq!(store.collect([d]), vec![c]);
+    assert!(store.get(c).is_none());
+    assert_eq!(store.collect([b]), vec![d]);
+    assert_eq!(store.collect([]), vec![a, b]);
+    assert_eq!(
+        store.iter().map(|(id, _)| id).collect::<Vec<_>>(),
+        vec![PackageId::CORE]
+    );
+    assert!(insert(&mut store, "E", &[]) > d, "IDs should not be reused");
+}
+
+#[test]
+fn local_items_excluded_by_config_are_reported() {
+    let source = indoc! {"
+        namespace Foo {
+            operation Main() : Unit {
+                @Config(Adaptive)
+                operation A() : Unit {}
+                @Config(Base)
+                operation B() : Unit {
+                    @Config(Adaptive)
+                    function C() : Unit {}
+                }
+                B();
+            }
+        }
+    "};
+    let sources = SourceMap::new([("test".into(), source.into())], None);
+
+    let unit = compile(
+        &PackageStore::new(super::core()),
+        &[],
+        sources,
+        TargetCapabilityFlags::empty(),
+        LanguageFeatures::default(),
+    );
+    assert!(unit.errors.is_empty(), "{:#?}", unit.errors);
+
+    let excluded = unit
+        .excluded_spans
+        .iter()
+        .map(|span| &source[span.lo as usize..span.hi as usize])
+        .collect::<Vec<_>>();
+    expect![[r#"
+        [
+            "@Config(Adaptive)\n        operation A() : Unit {}",
+            "@Config(Adaptive)\n            function C() : Unit {}",
+        ]
+    "#]]
+    .assert_debug_eq(&excluded);
+}
+
+#[test]
+fn local_item_excluded_by_config_is_not_available() {
+    let source = indoc! {"
+        namespace Foo {
+            operation Main() : Unit {
+                @Config(Adaptive)
+                operation A() : Unit {}
+                A();
+            }
+        }
+    "};
+    let sources = SourceMap::new([("test".into(), source.into())], None);
+
+    let unit = compile(
+        &PackageStore::new(super::core()),
+        &[],
+        sources,
+        TargetCapabilityFlags::empty(),
+        LanguageFeatures::default(),
+    );
+    expect![[r#"
+        [
+            Error(
+                Resolve(
+                    NotAvailable(
+                        "A",
+                        "A",
+                        Span {
+                            lo: 112,
+                            hi: 113,
+                        },
+                    ),
+                ),
+            ),
+        ]
+    "#]]
+    .assert_debug_eq(&unit.errors);
+}
+
+#[test]
+fn generic_bounded_by_parameterized_class_with_method() {
+    let sources = SourceMap::new(
+        [(
+            "test".into(),
+            indoc! {"
+                namespace Foo {
+                    class Iterable['U] {
+                        function Items(c : 'Self) : 'U[];
+                    }
+                    struct Bag { Values : Int[] }
+                    function ItemsBag(bag : Bag) : Int[] { bag.Values }
+                    function First<'T : Iterable['U], 'U>(c : 'T) : 'U { Items(c)[0] }
+                    function Main() : Int { First(new Bag { Values = [1, 2] }) }
+                }
+            "}
+            .into(),
+        )],
+        None,
+    );
+
+    let unit = default_compile(sources);
+    assert!(unit.errors.is_empty(), "{:#?}", unit.errors);
+}
+
+#[test]
+fn type_without_class_methods_is_not_member() {
+    let sources = SourceMap::new(
+        [(
+            "test".into(),
+            indoc! {"
+                namespace Foo {
+                    class Sized {
+                        function Size(x : 'Self) : Int;
+                    }
+                    struct Line { Length : Int }
+                    function Total<'T : Sized>(xs : 'T[]) : Int { 0 }
+                    function Main() : Int { Total([new Line { Length = 1 }]) }
+                }
+            "}
+            .into(),
+        )],
+        None,
+    );
+
+    let unit = default_compile(sources);
+    assert_eq!(unit.errors.len(), 1, "{:#?}", unit.errors);
+    assert_eq!(
+        unit.errors[0]
+            .code()
+            .expect("expected error code")
+            .to_string(),
+        "Qsc.TypeCk.MissingClassMember"
+    );
+}
+
+#[test]
+fn class_method_implementation_with_wrong_signature() {
+    let sources = SourceMap::new(
+        [(
+            "test".into(),
+            indoc! {"
+                namespace Foo {
+                    class Sized {
+                        function Size(x : 'Self) : Int;
+                    }
+                    struct Line { Length : Int }
+                    function SizeLine(line : Line) : Double { 1.0 }
+                }
+            "}
+            .into(),
+        )],
+        None,
+    );
+
+    let unit = default_compile(sources);
+    assert_eq!(unit.errors.len(), 1, "{:#?}", unit.errors);
+    assert_eq!(
+        unit.errors[0]
+            .code()
+            .expect("expected error code")
+            .to_string(),
+        "Qsc.TypeCk.InvalidMethodSignature"
+    );
+}
+
+/// Creates a store whose core library comes from the cache, so that the packages compiled
+/// against it can be cached as well.
+fn store_with_cached_core(cache: &mut CompileCache<()>) -> PackageStore {
+    let key = CompileKey::library("core", TargetCapabilityFlags::empty());
+    let (core, ()) = cache.get_or_compile(key, || (super::core(), ()));
+    PackageStore::new(core)
+}
+
+/// Compiles a package with a single source through the cache, counting the compilations that
+/// were not served from the cache.
+fn compile_with_cache(
+    store: &PackageStore,
+    cache: &mut CompileCache<()>,
+    dependencies: &Dependencies,
+    contents: &str,
+    compilations: &mut usize,
+) -> CompileUnit {
+    let sources = SourceMap::new([("test.qs".into(), contents.into())], None);
+    let key = CompileKey::new(
+        store,
+        dependencies,
+        &sources,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::default(),
+    )
+    .expect("dependencies should have fingerprints");
+    let (unit, ()) = cache.get_or_compile(key, || {
+        *compilations += 1;
+        let unit = compile(
+            store,
+            dependencies,
+            sources,
+            TargetCapabilityFlags::all(),
+            LanguageFeatures::default(),
+        );
+        (unit, ())
+    });
+    assert!(unit.errors.is_empty(), "{:?}", unit.errors);
+    unit
+}
+
+#[test]
+fn compile_cache_reuses_unchanged_package() {
+    let mut cache = CompileCache::default();
+    let mut compilations = 0;
+    let source = "namespace A { function F() : Unit {} }";
+
+    let store = store_with_cached_core(&mut cache);
+    let first = compile_with_cache(&store, &mut cache, &[], source, &mut compilations);
+    let store = store_with_cached_core(&mut cache);
+    let second = compile_with_cache(&store, &mut cache, &[], source, &mut compilations);
+
+    assert_eq!(compilations, 1);
+    assert!(second.fingerprint.is_some());
+    assert_eq!(first.fingerprint, second.fingerprint);
+    assert_eq!(
+        first.package.items.iter().count(),
+        second.package.items.iter().count()
+    );
+}
+
+#[test]
+fn compile_cache_recompiles_changed_package() {
+    let mut cache = CompileCache::default();
+    let mut compilations = 0;
+
+    let store = store_with_cached_core(&mut cache);
+    let first = compile_with_cache(
+        &store,
+        &mut cache,
+        &[],
+        "namespace A { function F() : Unit {} }",
+        &mut compilations,
+    );
+    let second = compile_with_cache(
+        &store,
+        &mut cache,
+        &[],
+        "namespace A { function F() : Unit {} function G() : Unit {} }",
+        &mut compilations,
+    );
+
+    assert_eq!(compilations, 2);
+    assert_ne!(first.fingerprint, second.fingerprint);
+}
+
+#[test]
+fn compile_cache_recompiles_dependents_of_changed_package() {
+    let mut cache = CompileCache::default();
+    let mut compilations = 0;
+    let dependent = "namespace B { function G() : Unit { Dep.A.F(); } }";
+    let mut compile_both = |dependency: &str, compilations: &mut usize| {
+        let mut store = store_with_cached_core(&mut cache);
+        let a = compile_with_cache(&store, &mut cache, &[], dependency, compilations);
+        let a = store.insert(a);
+        let dependencies = [(a, Some("Dep".into()))];
+        compile_with_cache(&store, &mut cache, &dependencies, dependent, compilations);
+    };
+
+    compile_both(
+        "namespace A { function F() : Unit {} export F; }",
+        &mut compilations,
+    );
+    assert_eq!(compilations, 2);
+    compile_both(
+        "namespace A { function F() : Unit {} export F; }",
+        &mut compilations,
+    );
+    assert_eq!(compilations, 2, "unchanged packages should be reused");
+    compile_both(
+        "namespace A { function F() : Unit {} function H() : Unit {} export F; }",
+        &mut compilations,
+    );
+    assert_eq!(
+        compilations, 4,
+        "the dependent should be recompiled along with its dependency"
+    );
+}
+
+#[test]
+fn compile_key_requires_fingerprinted_dependencies() {
+    let store = PackageStore::new(super::core());
+    let sources = SourceMap::new([("test.qs".into(), "namespace A {}".into())], None);
+
+    let key = CompileKey::new(
+        &store,
+        &[],
+        &sources,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::default(),
+    );
+
+    assert!(key.is_none());
+}
+
+#[test]
+fn compile_cache_evicts_least_recently_used_package() {
+    let mut cache = CompileCache::new(2);
+    let keys = ["A", "B", "C"].map(|name| CompileKey::library(name, TargetCapabilityFlags::all()));
+    let mut compilations = 0;
+    let mut get = |cache: &mut CompileCache<()>, key| {
+        cache.get_or_compile(key, || {
+            compilations += 1;
+            (CompileUnit::default(), ())
+        });
+    };
+
+    get(&mut cache, keys[0]);
+    get(&mut cache, keys[1]);
+    get(&mut cache, keys[0]);
+    get(&mut cache, keys[2]);
+    assert_eq!(cache.len(), 2);
+    get(&mut cache, keys[0]);
+    get(&mut cache, keys[1]);
+
+    assert_eq!(
+        compilations, 4,
+        "only the least recently used package should be evicted"
+    );
+}
@@ -155,18 +155,6 @@ fn namespace_named_main_doesnt_create_main_namespace() {
                         ),
                     ),
                 ),
-                Error(
-                    Type(
-                        Error(
-                            AmbiguousTy(
-                                Span {
-                                    lo: 205,
-                                    hi: 218,
-                                },
-                            ),
-                        ),
-                    ),
-                ),
             ]"#]),
     );
 }
@@ -275,18 +263,6 @@ fn multiple_packages_disallow_unexported_imports() {
                         ),
                     ),
                 ),
-                Error(
-                    Type(
-                        Error(
-                            AmbiguousTy(
-                                Span {
-                                    lo: 78,
-                                    hi: 89,
-                                },
-                            ),
-                        ),
-                    ),
-                ),
             ]"#]],
     );
 }
@@ -602,3 +578,91 @@ fn callable_reexport() {
         ),
     ]);
 }
+
+#[test]
+fn export_from_other_file_of_namespace() {
+    multiple_package_multiple_source_check(
+        vec![
+            (
+                "A",
+                vec![
+                    ("FileOne", "namespace Foo { function Bar() : Int { 1 } }"),
+                    ("FileTwo", "namespace Foo { export Bar; }"),
+                ],
+            ),
+            (
+                "B",
+                vec![(
+                    "FileThree",
+                    "function Main() : Int {
+                A.Foo.Bar()
+            }",
+                )],
+            ),
+        ],
+        Some(&expect!["[]"]),
+    );
+}
+
+#[test]
+fn export_of_item_from_other_namespace_in_package() {
+    multiple_package_multiple_source_check(
+        vec![
+            (
+                "A",
+                vec![
+                    ("FileOne", "namespace Foo { function Bar() : Int { 1 } }"),
+                    ("FileTwo", "namespace Baz { export Foo.Bar; }"),
+                ],
+            ),
+            (
+                "B",
+                vec![(
+                    "FileThree",
+                    "function Main() : Int {
+                A.Baz.Bar()
+            }",
+                )],
+            ),
+        ],
+        Some(&expect!["[]"]),
+    );
+}
+
+#[test]
+fn item_exported_from_other_namespace_is_not_exported_from_its_own() {
+    multiple_package_multiple_source_check(
+        vec![
+            (
+                "A",
+                vec![
+                    ("FileOne", "namespace Foo { function Bar() : Int { 1 } }"),
+                    ("FileTwo", "namespace Baz { export Foo.Bar; }"),
+                ],
+            ),
+            (
+                "B",
+                vec![(
+                    "FileThree",
+                    "function Main() : Int {
+                A.Foo.Bar()
+            }",
+                )],
+            ),
+        ],
+        Some(&expect![[r#"
+            [
+                Error(
+                    Resolve(
+                        NotFound(
+                            "A.Foo.Bar",
+                            Span {
+                                lo: 40,
+                                hi: 49,
+                            },
+                        ),
+                    ),
+                ),
+            ]"#]]),
+    );
+}
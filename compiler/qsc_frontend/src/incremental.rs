@@ -265,6 +265,8 @@ impl Compiler {
     ) -> (hir::Package, Vec<Error>) {
         let mut cond_compile = preprocess::Conditional::new(self.capabilities);
         cond_compile.visit_package(ast);
+        unit.excluded_spans
+            .extend(cond_compile.take_excluded_spans());
 
         self.ast_assigner.visit_package(ast);
 
@@ -333,9 +333,6 @@ fn errors_across_multiple_lines() {
             Some(
                 "line_3",
             ),
-            Some(
-                "line_4",
-            ),
         ]
     "#]]
     .assert_debug_eq(&labels);
@@ -468,6 +465,64 @@ fn continue_after_lower_error() {
         ]
     "#]].assert_debug_eq(&errors);
 }
+#[test]
+fn sized_array_lengths_are_checked_across_fragments() {
+    let store = PackageStore::new(compile::core());
+    let mut compiler = Compiler::new(
+        &store,
+        &[],
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::default(),
+    );
+    let mut unit = CompileUnit::default();
+    compiler
+        .compile_fragments(
+            &mut unit,
+            "line_1",
+            "operation ApplyToThree(qs : Qubit[3]) : Unit {}",
+            fail_on_error,
+        )
+        .expect("should succeed");
+
+    let errors = compiler
+        .compile_fragments(
+            &mut unit,
+            "line_2",
+            "use qs = Qubit[2]; ApplyToThree(qs);",
+            fail_on_error,
+        )
+        .expect_err("should fail");
+
+    expect![[r#"
+        [
+            WithSource {
+                sources: [
+                    Source {
+                        name: "line_2",
+                        contents: "use qs = Qubit[2]; ApplyToThree(qs);",
+                        offset: 48,
+                    },
+                ],
+                error: Error(
+                    Type(
+                        Error(
+                            ArrayLengthMismatch(
+                                3,
+                                2,
+                                Span {
+                                    lo: 80,
+                                    hi: 82,
+                                },
+                            ),
+                        ),
+                    ),
+                ),
+            },
+        ]
+    "#]]
+    .assert_debug_eq(&errors);
+}
+
 #[test]
 fn import_foo() {
     multi_package_test(
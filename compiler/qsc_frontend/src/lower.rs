@@ -19,8 +19,12 @@ use qsc_hir::{
     assigner::Assigner,
     hir::{self, LocalItemId, Visibility},
     mut_visit::MutVisitor,
-    ty::{Arrow, FunctorSetValue, GenericArg, ParamId, Ty, TypeParameter},
+    ty::{
+        Arrow, FunctorSet, FunctorSetValue, GenericArg, ParamId, Prim, Ty, TypeParameter,
+        UdtOperator,
+    },
 };
+use rustc_hash::{FxHashMap, FxHashSet};
 use std::{clone::Clone, rc::Rc, str::FromStr, vec};
 use thiserror::Error;
 
@@ -30,7 +34,7 @@ use self::convert::TyConversionError;
 pub(super) enum Error {
     #[error("unknown attribute {0}")]
     #[diagnostic(help(
-        "supported attributes are: EntryPoint, Config, SimulatableIntrinsic, Measurement, Reset"
+        "supported attributes are: EntryPoint, Config, SimulatableIntrinsic, Measurement, Reset, Test, OutputName"
     ))]
     #[diagnostic(code("Qsc.LowerAst.UnknownAttr"))]
     UnknownAttr(String, #[label] Span),
@@ -41,6 +45,12 @@ pub(super) enum Error {
     #[diagnostic(help("try declaring the callable as an operation"))]
     #[diagnostic(code("Qsc.LowerAst.InvalidAttrOnFunction"))]
     InvalidAttrOnFunction(String, #[label] Span),
+    #[error("invalid use of the Quantity attribute")]
+    #[diagnostic(help(
+        "a quantity must be declared with `newtype` and wrap a single `Int` or `Double`, such as `newtype Radians = Double;`"
+    ))]
+    #[diagnostic(code("Qsc.LowerAst.InvalidQuantity"))]
+    InvalidQuantity(#[label] Span),
     #[error("missing callable body")]
     #[diagnostic(code("Qsc.LowerAst.MissingBody"))]
     MissingBody(#[label] Span),
@@ -152,6 +162,14 @@ impl Lowerer {
     }
 }
 
+/// The items declared in a namespace and the items it exports, with the alias each is exported under,
+/// if any.
+#[derive(Default)]
+struct NamespaceExports {
+    declared: FxHashSet<hir::ItemId>,
+    exported: Vec<(hir::ItemId, Option<ast::Ident>)>,
+}
+
 pub(super) struct With<'a> {
     lowerer: &'a mut Lowerer,
     assigner: &'a mut Assigner,
@@ -165,10 +183,16 @@ impl With<'_> {
         package: &ast::Package,
         namespaces: qsc_data_structures::namespaces::NamespaceTreeRoot,
     ) -> hir::Package {
+        let exports = self.namespace_exports(package);
         let mut stmts = Vec::new();
         for node in &package.nodes {
             match node {
-                ast::TopLevelNode::Namespace(namespace) => self.lower_namespace(namespace),
+                ast::TopLevelNode::Namespace(namespace) => {
+                    let exports = exports
+                        .get(&namespace.name.full_name())
+                        .expect("namespace should have exports");
+                    self.lower_namespace(namespace, exports);
+                }
                 ast::TopLevelNode::Stmt(stmt) => {
                     if let Some(stmt) = self.lower_stmt(stmt) {
                         stmts.push(stmt);
@@ -187,7 +211,56 @@ impl With<'_> {
         }
     }
 
-    pub(super) fn lower_namespace(&mut self, namespace: &ast::Namespace) {
+    /// Gathers the declarations and exports of each namespace from all of its blocks, since a namespace
+    /// can be split across the files of a package.
+    fn namespace_exports(&self, package: &ast::Package) -> FxHashMap<Rc<str>, NamespaceExports> {
+        let mut exports = FxHashMap::<_, NamespaceExports>::default();
+        for node in &package.nodes {
+            let ast::TopLevelNode::Namespace(namespace) = node else {
+                continue;
+            };
+            let namespace_exports = exports.entry(namespace.name.full_name()).or_default();
+
+            namespace_exports
+                .declared
+                .extend(namespace.items.iter().filter_map(|item| {
+                    let name = match &*item.kind {
+                        ast::ItemKind::Callable(decl) => &decl.name,
+                        ast::ItemKind::Ty(name, _) => name,
+                        ast::ItemKind::Struct(decl) => &decl.name,
+                        ast::ItemKind::Enum(decl) => &decl.name,
+                        ast::ItemKind::Const(decl) => &decl.name,
+                        ast::ItemKind::Class(decl) => &decl.name,
+                        ast::ItemKind::Err
+                        | ast::ItemKind::Open(..)
+                        | ast::ItemKind::ImportOrExport(..) => return None,
+                    };
+                    match self.names.get(name.id) {
+                        Some(&resolve::Res::Item(id, _)) => Some(id),
+                        _ => None,
+                    }
+                }));
+
+            // Exports are `Res` items, which contain `hir::ItemId`s.
+            namespace_exports
+                .exported
+                .extend(namespace.exports().filter_map(|item| {
+                    let PathKind::Ok(path) = &item.path else {
+                        return None;
+                    };
+                    match self.names.get(path.id)? {
+                        resolve::Res::ExportedItem(id, hir_alias) => {
+                            Some((*id, hir_alias.clone().or_else(|| item.alias.clone())))
+                        }
+                        resolve::Res::Item(id, _) => Some((*id, item.alias.clone())),
+                        _ => None,
+                    }
+                }));
+        }
+        exports
+    }
+
+    fn lower_namespace(&mut self, namespace: &ast::Namespace, exports: &NamespaceExports) {
         let Some(&resolve::Res::Item(hir::ItemId { item: id, .. }, _)) =
             self.names.get(namespace.id)
         else {
@@ -196,34 +269,25 @@ impl With<'_> {
 
         self.lowerer.parent = Some(id);
 
-        // Exports are `Res` items, which contain `hir::ItemId`s.
-        // The second element in the tuple is the optional alias
-        let exports: Vec<(_, Option<ast::Ident>)> = namespace
-            .exports()
-            .filter_map(|item| {
-                let PathKind::Ok(path) = &item.path else {
-                    return None;
-                };
-                self.names.get(path.id).map(|x| (x, item.alias.clone()))
-            })
-            .collect::<Vec<_>>();
-
-        let exported_hir_ids = exports
-            .iter()
-            .filter_map(|(res, alias)| match res {
-                resolve::Res::ExportedItem(id, hir_alias) => {
-                    Some((*id, hir_alias.as_ref().or(alias.as_ref())))
-                }
-                resolve::Res::Item(id, _) => Some((*id, alias.as_ref())),
-                _ => None,
-            })
-            .collect::<Vec<_>>();
-
-        let items = namespace
-            .items
-            .iter()
-            .filter_map(|i| self.lower_item(i, &exported_hir_ids[..]))
-            .collect::<Vec<_>>();
+        let mut items = Vec::new();
+        for item in &namespace.items {
+            items.extend(self.lower_item(item, exports));
+            // The functions that construct the variants of an enum and the methods of a class are
+            // items of the namespace too.
+            let generated: Vec<&Ident> = match &*item.kind {
+                ast::ItemKind::Enum(decl) => decl.variants.iter().map(|v| &*v.name).collect(),
+                ast::ItemKind::Class(decl) => decl.methods.iter().map(|m| &*m.name).collect(),
+                _ => Vec::new(),
+            };
+            items.extend(
+                generated
+                    .into_iter()
+                    .filter_map(|name| match self.names.get(name.id) {
+                        Some(&resolve::Res::Item(id, _)) => Some(id.item),
+                        _ => None,
+                    }),
+            );
+        }
 
         let name = self.lower_idents(&namespace.name);
 
@@ -241,16 +305,11 @@ impl With<'_> {
     }
 
     #[allow(clippy::too_many_lines)]
-    fn lower_item(
-        &mut self,
-        item: &ast::Item,
-        // the optional ident is the export alias, if any
-        exported_ids: &[(hir::ItemId, Option<&ast::Ident>)],
-    ) -> Option<LocalItemId> {
-        let attrs: Vec<_> = item
+    fn lower_item(&mut self, item: &ast::Item, exports: &NamespaceExports) -> Option<LocalItemId> {
+        let mut attrs: Vec<_> = item
             .attrs
             .iter()
-            .filter_map(|a| self.lower_attr(a))
+            .filter_map(|a| self.lower_attr(a, item))
             .collect();
 
         let resolve_id = |id| match self.names.get(id) {
@@ -261,6 +320,26 @@ impl With<'_> {
             _otherwise => None,
         };
 
+        // The type checker only treats a type as a quantity if its definition allows it, so the
+        // attribute is dropped from anything else.
+        if attrs.contains(&hir::Attr::Quantity) {
+            let is_quantity = match &*item.kind {
+                ast::ItemKind::Ty(name, _) => resolve_id(name.id)
+                    .and_then(|(id, _)| self.tys.udts.get(&id))
+                    .is_some_and(|udt| udt.quantity),
+                _ => false,
+            };
+            if !is_quantity {
+                let span = item
+                    .attrs
+                    .iter()
+                    .find(|attr| attr.name.name.as_ref() == "Quantity")
+                    .map_or(item.span, |attr| attr.span);
+                self.lowerer.errors.push(Error::InvalidQuantity(span));
+                attrs.retain(|attr| *attr != hir::Attr::Quantity);
+            }
+        }
+
         let (id, kind) =
             match &*item.kind {
                 ast::ItemKind::Err | ast::ItemKind::Open(..) => return None,
@@ -276,9 +355,14 @@ impl With<'_> {
                         let Some((id, alias)) = resolve_id(item_name.id) else {
                             continue;
                         };
-                        let is_reexport = id.package.is_some() || alias.is_some();
-                        // if the package is Some, then this is a re-export and we
-                        // need to preserve the reference to the original `ItemId`
+                        // Exports of namespaces are not among the exported items, which are found from
+                        // the items their paths resolve to.
+                        let is_other_namespace_item = !exports.declared.contains(&id)
+                            && exports.exported.iter().any(|(exported, _)| *exported == id);
+                        let is_reexport =
+                            id.package.is_some() || alias.is_some() || is_other_namespace_item;
+                        // if the package is Some or the item is declared in another namespace, then this
+                        // is a re-export and we need to preserve the reference to the original `ItemId`
                         if is_reexport {
                             let mut name = self.lower_ident(item_name);
                             name.id = self.assigner.next_node();
@@ -308,6 +392,15 @@ impl With<'_> {
                     self.lowerer.parent = grandparent;
                     (id, hir::ItemKind::Callable(callable))
                 }
+                ast::ItemKind::Const(decl) => {
+                    let (id, _) = resolve_id(decl.name.id)?;
+                    attrs.push(hir::Attr::Const);
+                    let grandparent = self.lowerer.parent;
+                    self.lowerer.parent = Some(id.item);
+                    let callable = self.lower_const_decl(decl, &attrs);
+                    self.lowerer.parent = grandparent;
+                    (id, hir::ItemKind::Callable(callable))
+                }
                 ast::ItemKind::Ty(name, _) => {
                     let (id, _) = resolve_id(name.id)?;
                     let udt = self
@@ -331,9 +424,35 @@ impl With<'_> {
                         hir::ItemKind::Ty(self.lower_ident(&decl.name), strct.clone()),
                     )
                 }
+                ast::ItemKind::Enum(decl) => {
+                    let (id, _) = resolve_id(decl.name.id)?;
+                    let enm = self
+                        .tys
+                        .udts
+                        .get(&id)
+                        .expect("type item should have lowered enum");
+
+                    (
+                        id,
+                        hir::ItemKind::Ty(self.lower_ident(&decl.name), enm.clone()),
+                    )
+                }
+                ast::ItemKind::Class(decl) => {
+                    let (id, _) = resolve_id(decl.name.id)?;
+                    let class = self
+                        .tys
+                        .classes
+                        .get(&id)
+                        .expect("class item should have lowered definition");
+
+                    (
+                        id,
+                        hir::ItemKind::Class(self.lower_ident(&decl.name), class.clone()),
+                    )
+                }
             };
 
-        let export_info = exported_ids.iter().find(|(hir_id, _)| hir_id == &id);
+        let export_info = exports.exported.iter().find(|(hir_id, _)| hir_id == &id);
         let visibility = match export_info {
             Some((id, Some(alias))) => {
                 // this is the special case where this item _is_ exported,
@@ -367,94 +486,159 @@ impl With<'_> {
             kind,
         });
 
+        match &*item.kind {
+            ast::ItemKind::Enum(decl) => self.lower_enum_variants(decl, id, visibility),
+            ast::ItemKind::Class(decl) => self.lower_class_methods(decl, visibility),
+            _ => {}
+        }
+
         Some(id.item)
     }
 
-    fn lower_attr(&mut self, attr: &ast::Attr) -> Option<hir::Attr> {
+    /// Lowers the methods of a class to callables without bodies, which are visible wherever the
+    /// class is. Their type parameters are those of the class followed by `'Self`, and a call to one
+    /// is replaced by a call to the callable that implements it once the type of `'Self` is known.
+    fn lower_class_methods(&mut self, decl: &ast::ClassDecl, visibility: Visibility) {
+        let generics = decl.method_generics().into_boxed_slice();
+        for method in &decl.methods {
+            let Some(&resolve::Res::Item(id, _)) = self.names.get(method.name.id) else {
+                continue;
+            };
+            let method = ast::CallableDecl {
+                generics: generics.clone(),
+                ..(**method).clone()
+            };
+            let (callable, errs) = self.lower_callable_decl(&method, &[]);
+            self.lowerer.errors.extend(
+                errs.into_iter()
+                    .map(|err| Into::<Error>::into(Into::<convert::TyConversionError>::into(err))),
+            );
+            self.lowerer.items.push(hir::Item {
+                id: id.item,
+                span: method.span,
+                parent: self.lowerer.parent,
+                doc: "".into(),
+                attrs: Vec::new(),
+                visibility,
+                kind: hir::ItemKind::Callable(callable),
+            });
+        }
+    }
+
+    /// Generates the functions that construct the variants of an enum, which are visible wherever
+    /// the enum is. A variant is constructed as the tuple of its index and its payload.
+    fn lower_enum_variants(
+        &mut self,
+        decl: &ast::EnumDecl,
+        id: hir::ItemId,
+        visibility: Visibility,
+    ) {
+        let udt = self
+            .tys
+            .udts
+            .get(&id)
+            .expect("type item should have lowered enum");
+        let output = udt.generic_ty(id);
+        for (index, (variant, def)) in decl.variants.iter().zip(&udt.variants).enumerate() {
+            let span = variant.span;
+            let payload = self.assigner.next_node();
+            let input = hir::Pat {
+                id: self.assigner.next_node(),
+                span,
+                ty: def.ty.clone(),
+                kind: hir::PatKind::Bind(hir::Ident {
+                    id: payload,
+                    span,
+                    name: "payload".into(),
+                }),
+            };
+            let tag = hir::Expr {
+                id: self.assigner.next_node(),
+                span,
+                ty: Ty::Prim(Prim::Int),
+                kind: hir::ExprKind::Lit(hir::Lit::Int(
+                    index
+                        .try_into()
+                        .expect("variant index should fit into an integer"),
+                )),
+            };
+            let value = hir::Expr {
+                id: self.assigner.next_node(),
+                span,
+                ty: def.ty.clone(),
+                kind: hir::ExprKind::Var(hir::Res::Local(payload), Vec::new()),
+            };
+            let body = hir::Expr {
+                id: self.assigner.next_node(),
+                span,
+                ty: Ty::Tuple(vec![Ty::Prim(Prim::Int), def.ty.clone()]),
+                kind: hir::ExprKind::Tuple(vec![tag, value]),
+            };
+            let callable = hir::CallableDecl {
+                id: self.assigner.next_node(),
+                span,
+                kind: hir::CallableKind::Function,
+                name: self.lower_ident(&variant.name),
+                generics: udt.generics.clone(),
+                input,
+                output: output.clone(),
+                functors: FunctorSetValue::Empty,
+                body: hir::SpecDecl {
+                    id: self.assigner.next_node(),
+                    span,
+                    body: hir::SpecBody::Impl(
+                        None,
+                        hir::Block {
+                            id: self.assigner.next_node(),
+                            span,
+                            ty: output.clone(),
+                            stmts: vec![hir::Stmt {
+                                id: self.assigner.next_node(),
+                                span,
+                                kind: hir::StmtKind::Expr(body),
+                            }],
+                        },
+                    ),
+                },
+                adj: None,
+                ctl: None,
+                ctl_adj: None,
+                attrs: Vec::new(),
+            };
+            self.lowerer.items.push(hir::Item {
+                id: def.item,
+                span,
+                parent: self.lowerer.parent,
+                doc: "".into(),
+                attrs: Vec::new(),
+                visibility,
+                kind: hir::ItemKind::Callable(callable),
+            });
+        }
+    }
+
+    fn lower_attr(&mut self, attr: &ast::Attr, item: &ast::Item) -> Option<hir::Attr> {
         match hir::Attr::from_str(attr.name.name.as_ref()) {
-            Ok(hir::Attr::EntryPoint) => match &*attr.arg.kind {
-                ast::ExprKind::Tuple(args) if args.is_empty() => Some(hir::Attr::EntryPoint),
-                _ => {
-                    self.lowerer
-                        .errors
-                        .push(Error::InvalidAttrArgs("()".to_string(), attr.arg.span));
-                    None
-                }
-            },
-            Ok(hir::Attr::Unimplemented) => match &*attr.arg.kind {
-                ast::ExprKind::Tuple(args) if args.is_empty() => Some(hir::Attr::Unimplemented),
-                _ => {
-                    self.lowerer
-                        .errors
-                        .push(Error::InvalidAttrArgs("()".to_string(), attr.arg.span));
-                    None
-                }
-            },
             Ok(hir::Attr::Config) => {
-                match &*attr.arg.kind {
-                    // @Config(Capability)
-                    ast::ExprKind::Paren(inner)
-                        if matches!(inner.kind.as_ref(), ast::ExprKind::Path(PathKind::Ok(path))
-                    if TargetCapabilityFlags::from_str(path.name.name.as_ref()).is_ok()) => {}
-
-                    // @Config(not Capability)
-                    ast::ExprKind::Paren(inner)
-                        if matches!(inner.kind.as_ref(), ast::ExprKind::UnOp(ast::UnOp::NotL, inner)
-                        if matches!(inner.kind.as_ref(), ast::ExprKind::Path(PathKind::Ok(path))
-                    if TargetCapabilityFlags::from_str(path.as_ref().name.name.as_ref()).is_ok())) =>
-                        {}
-
-                    // Any other form is not valid so generates an error.
-                    _ => {
-                        self.lowerer.errors.push(Error::InvalidAttrArgs(
-                            "runtime capability".to_string(),
-                            attr.arg.span,
-                        ));
-                    }
-                }
+                self.check_config_attr_args(attr);
                 None
             }
-            Ok(hir::Attr::SimulatableIntrinsic) => match &*attr.arg.kind {
-                ast::ExprKind::Tuple(args) if args.is_empty() => {
-                    Some(hir::Attr::SimulatableIntrinsic)
-                }
-                _ => {
-                    self.lowerer
-                        .errors
-                        .push(Error::InvalidAttrArgs("()".to_string(), attr.arg.span));
-                    None
-                }
-            },
-            Ok(hir::Attr::Measurement) => match &*attr.arg.kind {
-                ast::ExprKind::Tuple(args) if args.is_empty() => Some(hir::Attr::Measurement),
-                _ => {
-                    self.lowerer
-                        .errors
-                        .push(Error::InvalidAttrArgs("()".to_string(), attr.arg.span));
-                    None
-                }
-            },
-            Ok(hir::Attr::Reset) => match &*attr.arg.kind {
-                ast::ExprKind::Tuple(args) if args.is_empty() => Some(hir::Attr::Reset),
-                _ => {
-                    self.lowerer
-                        .errors
-                        .push(Error::InvalidAttrArgs("()".to_string(), attr.arg.span));
-                    None
-                }
-            },
             Ok(hir::Attr::Test) => {
-                // verify that no args are passed to the attribute
-                match &*attr.arg.kind {
-                    ast::ExprKind::Tuple(args) if args.is_empty() => {}
-                    _ => {
-                        self.lowerer
-                            .errors
-                            .push(Error::InvalidAttrArgs("()".to_string(), attr.arg.span));
-                    }
-                }
+                self.check_empty_attr_args(attr);
                 Some(hir::Attr::Test)
             }
+            Ok(hir::Attr::OutputName(_)) => self.lower_output_name_attr(attr, item),
+            Ok(hir::Attr::Const) => unreachable!("constants are not declared with an attribute"),
+            Ok(
+                lowered @ (hir::Attr::EntryPoint
+                | hir::Attr::Unimplemented
+                | hir::Attr::SimulatableIntrinsic
+                | hir::Attr::Measurement
+                | hir::Attr::Reset
+                | hir::Attr::Pure
+                | hir::Attr::HostCallable
+                | hir::Attr::Quantity),
+            ) => self.check_empty_attr_args(attr).then_some(lowered),
             Err(()) => {
                 self.lowerer.errors.push(Error::UnknownAttr(
                     attr.name.name.to_string(),
@@ -465,6 +649,58 @@ impl With<'_> {
         }
     }
 
+    /// Checks that an attribute that takes no arguments is given an empty tuple, reporting an error
+    /// otherwise.
+    fn check_empty_attr_args(&mut self, attr: &ast::Attr) -> bool {
+        match &*attr.arg.kind {
+            ast::ExprKind::Tuple(args) if args.is_empty() => true,
+            _ => {
+                self.lowerer
+                    .errors
+                    .push(Error::InvalidAttrArgs("()".to_string(), attr.arg.span));
+                false
+            }
+        }
+    }
+
+    /// Checks that the argument of a `@Config` attribute is a runtime capability, or its negation.
+    fn check_config_attr_args(&mut self, attr: &ast::Attr) {
+        match &*attr.arg.kind {
+            // @Config(Capability)
+            ast::ExprKind::Paren(inner)
+                if matches!(inner.kind.as_ref(), ast::ExprKind::Path(PathKind::Ok(path))
+            if TargetCapabilityFlags::from_str(path.name.name.as_ref()).is_ok()) => {}
+
+            // @Config(not Capability)
+            ast::ExprKind::Paren(inner)
+                if matches!(inner.kind.as_ref(), ast::ExprKind::UnOp(ast::UnOp::NotL, inner)
+                if matches!(inner.kind.as_ref(), ast::ExprKind::Path(PathKind::Ok(path))
+            if TargetCapabilityFlags::from_str(path.as_ref().name.name.as_ref()).is_ok())) => {}
+
+            // Any other form is not valid so generates an error.
+            _ => {
+                self.lowerer.errors.push(Error::InvalidAttrArgs(
+                    "runtime capability".to_string(),
+                    attr.arg.span,
+                ));
+            }
+        }
+    }
+
+    /// Lowers an `@OutputName` attribute, whose argument names the output of the item: a string, or
+    /// a tuple with a string for each item of the output tuple.
+    fn lower_output_name_attr(&mut self, attr: &ast::Attr, item: &ast::Item) -> Option<hir::Attr> {
+        if let Some(names) = output_names(&attr.arg, item) {
+            Some(hir::Attr::OutputName(names))
+        } else {
+            self.lowerer.errors.push(Error::InvalidAttrArgs(
+                "a string, or a string for each item of the output tuple".to_string(),
+                attr.arg.span,
+            ));
+            None
+        }
+    }
+
     /// Generates generic parameters for the functors, if there were generics on the original callable.
     /// Basically just creates new generic params for the purpose of being used in functor callable
     /// decls.
@@ -561,6 +797,77 @@ impl With<'_> {
         )
     }
 
+    /// Lowers a constant to a function without inputs that returns its value.
+    fn lower_const_decl(
+        &mut self,
+        decl: &ast::ConstDecl,
+        attrs: &[hir::Attr],
+    ) -> hir::CallableDecl {
+        let span = decl.span;
+        let output = convert::ty_from_ast(self.names, &decl.ty, &mut Default::default()).0;
+        let value = self.lower_expr(&decl.value);
+        let input = hir::Pat {
+            id: self.assigner.next_node(),
+            span,
+            ty: Ty::UNIT,
+            kind: hir::PatKind::Tuple(Vec::new()),
+        };
+
+        hir::CallableDecl {
+            id: self.lower_id(decl.id),
+            span,
+            kind: hir::CallableKind::Function,
+            name: self.lower_ident(&decl.name),
+            generics: Vec::new(),
+            input,
+            output: output.clone(),
+            functors: FunctorSetValue::Empty,
+            body: hir::SpecDecl {
+                id: self.assigner.next_node(),
+                span,
+                body: hir::SpecBody::Impl(
+                    None,
+                    hir::Block {
+                        id: self.assigner.next_node(),
+                        span,
+                        ty: output,
+                        stmts: vec![hir::Stmt {
+                            id: self.assigner.next_node(),
+                            span: value.span,
+                            kind: hir::StmtKind::Expr(value),
+                        }],
+                    },
+                ),
+            },
+            adj: None,
+            ctl: None,
+            ctl_adj: None,
+            attrs: attrs.to_vec(),
+        }
+    }
+
+    /// A reference to a constant calls the function that the constant is lowered to.
+    fn lower_const_ref(&mut self, item: hir::ItemId, span: Span, ty: &Ty) -> hir::ExprKind {
+        let callee = hir::Expr {
+            id: self.assigner.next_node(),
+            span,
+            ty: Ty::Arrow(Box::new(Arrow {
+                kind: hir::CallableKind::Function,
+                input: Box::new(Ty::UNIT),
+                output: Box::new(ty.clone()),
+                functors: FunctorSet::Value(FunctorSetValue::Empty),
+            })),
+            kind: hir::ExprKind::Var(hir::Res::Item(item), Vec::new()),
+        };
+        let input = hir::Expr {
+            id: self.assigner.next_node(),
+            span,
+            ty: Ty::UNIT,
+            kind: hir::ExprKind::Tuple(Vec::new()),
+        };
+        hir::ExprKind::Call(Box::new(callee), Box::new(input))
+    }
+
     fn check_invalid_attrs_on_function(&mut self, attrs: &[hir::Attr], span: Span) {
         const INVALID_ATTRS: [hir::Attr; 2] = [hir::Attr::Measurement, hir::Attr::Reset];
 
@@ -666,7 +973,9 @@ impl With<'_> {
         let kind = match &*stmt.kind {
             ast::StmtKind::Empty | ast::StmtKind::Err => return None,
             ast::StmtKind::Expr(expr) => hir::StmtKind::Expr(self.lower_expr(expr)),
-            ast::StmtKind::Item(item) => hir::StmtKind::Item(self.lower_item(item, &[])?),
+            ast::StmtKind::Item(item) => {
+                hir::StmtKind::Item(self.lower_item(item, &NamespaceExports::default())?)
+            }
             ast::StmtKind::Local(mutability, lhs, rhs) => hir::StmtKind::Local(
                 lower_mutability(*mutability),
                 self.lower_pat(lhs),
@@ -712,11 +1021,28 @@ impl With<'_> {
                 Box::new(self.lower_expr(lhs)),
                 Box::new(self.lower_expr(rhs)),
             ),
-            ast::ExprKind::AssignOp(op, lhs, rhs) => hir::ExprKind::AssignOp(
-                lower_binop(*op),
-                Box::new(self.lower_expr(lhs)),
-                Box::new(self.lower_expr(rhs)),
-            ),
+            ast::ExprKind::AssignOp(op, lhs, rhs) => {
+                let lhs = self.lower_expr(lhs);
+                let rhs = self.lower_expr(rhs);
+                match udt_binop(*op).and_then(|udt_op| self.udt_operator(&lhs.ty, udt_op)) {
+                    Some(item) => {
+                        // The variable is both the target of the assignment and the first input.
+                        let value = hir::Expr {
+                            id: self.assigner.next_node(),
+                            ..lhs.clone()
+                        };
+                        let ty = lhs.ty.clone();
+                        let call = hir::Expr {
+                            id: self.assigner.next_node(),
+                            span: expr.span,
+                            kind: self.lower_operator_call(item, vec![value, rhs], &ty, expr.span),
+                            ty,
+                        };
+                        hir::ExprKind::Assign(Box::new(lhs), Box::new(call))
+                    }
+                    None => hir::ExprKind::AssignOp(lower_binop(*op), Box::new(lhs), Box::new(rhs)),
+                }
+            }
             ast::ExprKind::AssignUpdate(container, index, replace) => {
                 if let Some(field) = resolve::extract_field_name(self.names, index) {
                     let container = self.lower_expr(container);
@@ -731,11 +1057,11 @@ impl With<'_> {
                     )
                 }
             }
-            ast::ExprKind::BinOp(op, lhs, rhs) => hir::ExprKind::BinOp(
-                lower_binop(*op),
-                Box::new(self.lower_expr(lhs)),
-                Box::new(self.lower_expr(rhs)),
-            ),
+            ast::ExprKind::BinOp(op, lhs, rhs) => {
+                let lhs = self.lower_expr(lhs);
+                let rhs = self.lower_expr(rhs);
+                self.lower_binop_expr(*op, lhs, rhs, expr.span)
+            }
             ast::ExprKind::Block(block) => hir::ExprKind::Block(self.lower_block(block)),
             ast::ExprKind::Call(callee, arg) => match &ty {
                 Ty::Arrow(arrow) if is_partial_app(arg) => hir::ExprKind::Block(
@@ -792,6 +1118,14 @@ impl With<'_> {
                 self.lower_lambda(lambda, expr.span)
             }
             ast::ExprKind::Lit(lit) => lower_lit(lit),
+            ast::ExprKind::Match(expr, arms) => {
+                let expr = self.lower_expr(expr);
+                let arms = arms
+                    .iter()
+                    .map(|arm| self.lower_match_arm(arm, &expr.ty))
+                    .collect();
+                hir::ExprKind::Match(Box::new(expr), arms)
+            }
             ast::ExprKind::Paren(_) => unreachable!("parentheses should be removed earlier"),
             ast::ExprKind::Path(PathKind::Ok(path)) => {
                 let args = self
@@ -799,7 +1133,14 @@ impl With<'_> {
                     .generics
                     .get(expr.id)
                     .map_or(Vec::new(), Clone::clone);
-                self.lower_path(path, args)
+                match self.lower_path(path, args) {
+                    hir::ExprKind::Var(hir::Res::Item(item), _)
+                        if self.tys.consts.contains(&item) =>
+                    {
+                        self.lower_const_ref(item, expr.span, &ty)
+                    }
+                    kind => kind,
+                }
             }
             ast::ExprKind::Range(start, step, end) => hir::ExprKind::Range(
                 start.as_ref().map(|s| Box::new(self.lower_expr(s))),
@@ -848,8 +1189,20 @@ impl With<'_> {
             ast::ExprKind::Tuple(items) => {
                 hir::ExprKind::Tuple(items.iter().map(|i| self.lower_expr(i)).collect())
             }
+            ast::ExprKind::UnOp(ast::UnOp::Propagate, operand) => {
+                let operand = self.lower_expr(operand);
+                self.lower_propagate(expr.id, operand, &ty, expr.span)
+            }
             ast::ExprKind::UnOp(op, operand) => {
-                hir::ExprKind::UnOp(lower_unop(*op), Box::new(self.lower_expr(operand)))
+                let operand = self.lower_expr(operand);
+                match (op, self.udt_operator(&operand.ty, UdtOperator::Negation)) {
+                    (ast::UnOp::Neg, Some(item)) => {
+                        self.lower_operator_call(item, vec![operand], &ty, expr.span)
+                    }
+                    // A unary plus has no effect on a type that can be negated.
+                    (ast::UnOp::Pos, Some(_)) => operand.kind,
+                    _ => hir::ExprKind::UnOp(lower_unop(*op), Box::new(operand)),
+                }
             }
             ast::ExprKind::While(cond, body) => {
                 hir::ExprKind::While(Box::new(self.lower_expr(cond)), self.lower_block(body))
@@ -877,6 +1230,28 @@ impl With<'_> {
         }
     }
 
+    fn lower_match_arm(&mut self, arm: &ast::MatchArm, ty: &Ty) -> hir::MatchArm {
+        let pat = match &*arm.pat {
+            ast::MatchPat::Lit(lit) => hir::MatchPat::Lit(self.lower_expr(lit)),
+            ast::MatchPat::Variant(path, pat) => {
+                let pat = self.lower_pat(pat);
+                match self.lower_variant(ty, path) {
+                    Some((names, index)) => hir::MatchPat::Variant(names, index, pat),
+                    // The type checker has already reported the path, so the arm only needs to
+                    // bind its pattern.
+                    None => hir::MatchPat::Bind(pat),
+                }
+            }
+            ast::MatchPat::Bind(pat) => hir::MatchPat::Bind(self.lower_pat(pat)),
+        };
+        hir::MatchArm {
+            id: self.lower_id(arm.id),
+            span: arm.span,
+            pat,
+            body: self.lower_expr(&arm.body),
+        }
+    }
+
     fn lower_partial_app(
         &mut self,
         callee: &ast::Expr,
@@ -934,8 +1309,30 @@ impl With<'_> {
         hir::ExprKind::Closure(args, id)
     }
 
+    /// The names of the variants of an enum type and the index of the variant that a path refers to.
+    fn lower_variant(&self, ty: &Ty, path: &ast::Path) -> Option<(Rc<[Rc<str>]>, usize)> {
+        let Ty::Udt(_, hir::Res::Item(id), _) = ty else {
+            return None;
+        };
+        let (resolve::Res::Item(item, _) | resolve::Res::ExportedItem(item, _)) =
+            self.names.get(path.id)?
+        else {
+            return None;
+        };
+        let udt = self.tys.udts.get(id)?;
+        let index = udt
+            .variant_index(item.item)
+            .filter(|_| item.package == id.package)?;
+        let names = udt
+            .variants
+            .iter()
+            .map(|variant| Rc::clone(&variant.name))
+            .collect();
+        Some((names, index))
+    }
+
     fn lower_field(&mut self, record_ty: &Ty, name: &str) -> hir::Field {
-        if let Ty::Udt(_, hir::Res::Item(id)) = record_ty {
+        if let Ty::Udt(_, hir::Res::Item(id), _) = record_ty {
             self.tys
                 .udts
                 .get(id)
@@ -950,7 +1347,9 @@ impl With<'_> {
 
     fn lower_string_component(&mut self, component: &ast::StringComponent) -> hir::StringComponent {
         match component {
-            ast::StringComponent::Expr(expr) => hir::StringComponent::Expr(self.lower_expr(expr)),
+            ast::StringComponent::Expr(expr, format) => {
+                hir::StringComponent::Expr(Box::new(self.lower_expr(expr)), *format)
+            }
             ast::StringComponent::Lit(str) => hir::StringComponent::Lit(Rc::clone(str)),
         }
     }
@@ -1083,6 +1482,189 @@ impl With<'_> {
         }
     }
 
+    /// Lowers a binary operator. If the operands are of a user-defined type that implements the
+    /// operator, the operator is lowered to a call to the function that implements it, and a comparison
+    /// is lowered to a comparison of the result of the type's `Compare` function with zero.
+    fn lower_binop_expr(
+        &mut self,
+        op: ast::BinOp,
+        lhs: hir::Expr,
+        rhs: hir::Expr,
+        span: Span,
+    ) -> hir::ExprKind {
+        let udt_op = udt_binop(op);
+        let Some(item) = udt_op.and_then(|udt_op| self.udt_operator(&lhs.ty, udt_op)) else {
+            return hir::ExprKind::BinOp(lower_binop(op), Box::new(lhs), Box::new(rhs));
+        };
+
+        if udt_op == Some(UdtOperator::Compare) {
+            let int = Ty::Prim(Prim::Int);
+            let order = hir::Expr {
+                id: self.assigner.next_node(),
+                span,
+                kind: self.lower_operator_call(item, vec![lhs, rhs], &int, span),
+                ty: int.clone(),
+            };
+            let zero = hir::Expr {
+                id: self.assigner.next_node(),
+                span,
+                ty: int,
+                kind: hir::ExprKind::Lit(hir::Lit::Int(0)),
+            };
+            hir::ExprKind::BinOp(lower_binop(op), Box::new(order), Box::new(zero))
+        } else {
+            let output = lhs.ty.clone();
+            self.lower_operator_call(item, vec![lhs, rhs], &output, span)
+        }
+    }
+
+    /// The function that implements the operator for the type, if it is a user-defined type that
+    /// implements the operator.
+    fn udt_operator(&self, ty: &Ty, op: UdtOperator) -> Option<hir::ItemId> {
+        let Ty::Udt(_, hir::Res::Item(id), _) = ty else {
+            return None;
+        };
+        let item = self.tys.udts.get(id)?.operator(op)?;
+        Some(hir::ItemId {
+            package: id.package,
+            item,
+        })
+    }
+
+    /// Lowers `operand?` to a match on the variants of the operand's enum. The payload of the first
+    /// variant is the value of the expression, and each other variant is returned from the callable
+    /// as the same variant of the enum that the callable returns.
+    fn lower_propagate(
+        &mut self,
+        id: ast::NodeId,
+        operand: hir::Expr,
+        ty: &Ty,
+        span: Span,
+    ) -> hir::ExprKind {
+        let (Ty::Udt(_, hir::Res::Item(enum_id), args), Some([GenericArg::Ty(ret)])) =
+            (&operand.ty, self.tys.generics.get(id).map(Vec::as_slice))
+        else {
+            return hir::ExprKind::Err;
+        };
+        let Ty::Udt(_, _, ret_args) = ret else {
+            return hir::ExprKind::Err;
+        };
+        // The type checker has already reported an operand that can't be propagated.
+        let Some(udt) = self
+            .tys
+            .udts
+            .get(enum_id)
+            .filter(|udt| udt.variants.len() > 1 && udt.generics.len() == args.len())
+        else {
+            return hir::ExprKind::Err;
+        };
+
+        let names: Rc<[Rc<str>]> = udt
+            .variants
+            .iter()
+            .map(|variant| Rc::clone(&variant.name))
+            .collect();
+        let mut arms = Vec::new();
+        for (index, variant) in udt.variants.iter().enumerate() {
+            let payload_ty = udt.variant_ty(index, enum_id.package, args);
+            let payload = hir::Ident {
+                id: self.assigner.next_node(),
+                span,
+                name: "payload".into(),
+            };
+            self.lowerer
+                .locals
+                .insert(payload.id, (payload.clone(), payload_ty.clone()));
+            let pat = hir::Pat {
+                id: self.assigner.next_node(),
+                span,
+                ty: payload_ty.clone(),
+                kind: hir::PatKind::Bind(payload.clone()),
+            };
+            let value = hir::Expr {
+                id: self.assigner.next_node(),
+                span,
+                ty: payload_ty.clone(),
+                kind: hir::ExprKind::Var(hir::Res::Local(payload.id), Vec::new()),
+            };
+            let body = if index == 0 {
+                value
+            } else {
+                let constructor = hir::Expr {
+                    id: self.assigner.next_node(),
+                    span,
+                    ty: Ty::Arrow(Box::new(Arrow {
+                        kind: hir::CallableKind::Function,
+                        input: Box::new(payload_ty),
+                        output: Box::new(ret.clone()),
+                        functors: FunctorSet::Value(FunctorSetValue::Empty),
+                    })),
+                    kind: hir::ExprKind::Var(
+                        hir::Res::Item(hir::ItemId {
+                            package: enum_id.package,
+                            item: variant.item,
+                        }),
+                        ret_args.iter().cloned().map(GenericArg::Ty).collect(),
+                    ),
+                };
+                let call = hir::Expr {
+                    id: self.assigner.next_node(),
+                    span,
+                    ty: ret.clone(),
+                    kind: hir::ExprKind::Call(Box::new(constructor), Box::new(value)),
+                };
+                hir::Expr {
+                    id: self.assigner.next_node(),
+                    span,
+                    ty: ty.clone(),
+                    kind: hir::ExprKind::Return(Box::new(call)),
+                }
+            };
+            arms.push(hir::MatchArm {
+                id: self.assigner.next_node(),
+                span,
+                pat: hir::MatchPat::Variant(Rc::clone(&names), index, pat),
+                body,
+            });
+        }
+        hir::ExprKind::Match(Box::new(operand), arms)
+    }
+
+    fn lower_operator_call(
+        &mut self,
+        item: hir::ItemId,
+        args: Vec<hir::Expr>,
+        output: &Ty,
+        span: Span,
+    ) -> hir::ExprKind {
+        let input = match args.as_slice() {
+            [arg] => arg.ty.clone(),
+            _ => Ty::Tuple(args.iter().map(|arg| arg.ty.clone()).collect()),
+        };
+        let callee = hir::Expr {
+            id: self.assigner.next_node(),
+            span,
+            ty: Ty::Arrow(Box::new(Arrow {
+                kind: hir::CallableKind::Function,
+                input: Box::new(input.clone()),
+                output: Box::new(output.clone()),
+                functors: FunctorSet::Value(FunctorSetValue::Empty),
+            })),
+            kind: hir::ExprKind::Var(hir::Res::Item(item), Vec::new()),
+        };
+        let arg = if args.len() == 1 {
+            args.into_iter().next().expect("argument should exist")
+        } else {
+            hir::Expr {
+                id: self.assigner.next_node(),
+                span,
+                ty: input,
+                kind: hir::ExprKind::Tuple(args),
+            }
+        };
+        hir::ExprKind::Call(Box::new(callee), Box::new(arg))
+    }
+
     fn lower_id(&mut self, id: ast::NodeId) -> hir::NodeId {
         self.lowerer.nodes.get(id).copied().unwrap_or_else(|| {
             let new_id = self.assigner.next_node();
@@ -1111,6 +1693,24 @@ fn lower_unop(op: ast::UnOp) -> hir::UnOp {
         ast::UnOp::NotL => hir::UnOp::NotL,
         ast::UnOp::Pos => hir::UnOp::Pos,
         ast::UnOp::Unwrap => hir::UnOp::Unwrap,
+        ast::UnOp::Propagate => unreachable!("propagation should be lowered to a match"),
+    }
+}
+
+/// The operator a user-defined type implements for a binary operator, if it can implement one.
+fn udt_binop(op: ast::BinOp) -> Option<UdtOperator> {
+    match op {
+        ast::BinOp::Add => Some(UdtOperator::Plus),
+        ast::BinOp::Sub => Some(UdtOperator::Minus),
+        ast::BinOp::Mul => Some(UdtOperator::Times),
+        ast::BinOp::Div => Some(UdtOperator::DividedBy),
+        ast::BinOp::Eq
+        | ast::BinOp::Neq
+        | ast::BinOp::Gt
+        | ast::BinOp::Gte
+        | ast::BinOp::Lt
+        | ast::BinOp::Lte => Some(UdtOperator::Compare),
+        _ => None,
     }
 }
 
@@ -1175,3 +1775,30 @@ fn is_partial_app(arg: &ast::Expr) -> bool {
         _ => false,
     }
 }
+
+/// The names given by the argument of an `OutputName` attribute, which is a string literal or a tuple of them.
+fn output_names(arg: &ast::Expr, item: &ast::Item) -> Option<Vec<Rc<str>>> {
+    let name = |expr: &ast::Expr| match expr.kind.as_ref() {
+        ast::ExprKind::Lit(lit) => match lit.as_ref() {
+            ast::Lit::String(name) => Some(Rc::clone(name)),
+            _ => None,
+        },
+        _ => None,
+    };
+    let names = match arg.kind.as_ref() {
+        ast::ExprKind::Paren(inner) => vec![name(inner)?],
+        ast::ExprKind::Tuple(items) if !items.is_empty() => {
+            items.iter().map(|i| name(i)).collect::<Option<_>>()?
+        }
+        _ => return None,
+    };
+    // A tuple of names must name each item of the output tuple.
+    let output_arity = match &*item.kind {
+        ast::ItemKind::Callable(decl) => match &*decl.output.kind {
+            ast::TyKind::Tuple(tys) => Some(tys.len()),
+            _ => None,
+        },
+        _ => None,
+    };
+    (names.len() == 1 || Some(names.len()) == output_arity).then_some(names)
+}
@@ -129,6 +129,57 @@ fn test_target_profile_attr_wrong_args() {
     );
 }
 
+#[test]
+fn test_output_name_attr_allowed() {
+    check_errors(
+        indoc! {r#"
+            namespace input {
+                @OutputName("syndrome", "flag")
+                operation Foo() : (Result[], Bool) {
+                    ([], false)
+                }
+            }
+        "#},
+        &expect![[r#"
+            []
+        "#]],
+    );
+}
+
+#[test]
+fn test_output_name_attr_wrong_args() {
+    check_errors(
+        indoc! {r#"
+            namespace input {
+                @OutputName("syndrome", "flag")
+                operation Foo() : Result[] {
+                    []
+                }
+                @OutputName(Bar)
+                operation Baz() : Unit {}
+            }
+        "#},
+        &expect![[r#"
+            [
+                InvalidAttrArgs(
+                    "a string, or a string for each item of the output tuple",
+                    Span {
+                        lo: 33,
+                        hi: 53,
+                    },
+                ),
+                InvalidAttrArgs(
+                    "a string, or a string for each item of the output tuple",
+                    Span {
+                        lo: 119,
+                        hi: 124,
+                    },
+                ),
+            ]
+        "#]],
+    );
+}
+
 #[test]
 fn test_unknown_attr() {
     check_errors(
@@ -154,6 +205,61 @@ fn test_unknown_attr() {
     );
 }
 
+#[test]
+fn test_quantity_attr_allowed() {
+    check_errors(
+        indoc! {"
+            namespace input {
+                @Quantity()
+                newtype Radians = Double;
+                @Quantity()
+                newtype Steps = Int;
+            }
+        "},
+        &expect![[r#"
+            []
+        "#]],
+    );
+}
+
+#[test]
+fn test_quantity_attr_on_invalid_item() {
+    check_errors(
+        indoc! {"
+            namespace input {
+                @Quantity()
+                newtype Flag = Bool;
+                @Quantity()
+                struct Angle { Value : Double }
+                @Quantity()
+                function Foo() : Unit {}
+            }
+        "},
+        &expect![[r#"
+            [
+                InvalidQuantity(
+                    Span {
+                        lo: 22,
+                        hi: 33,
+                    },
+                ),
+                InvalidQuantity(
+                    Span {
+                        lo: 63,
+                        hi: 74,
+                    },
+                ),
+                InvalidQuantity(
+                    Span {
+                        lo: 115,
+                        hi: 126,
+                    },
+                ),
+            ]
+        "#]],
+    );
+}
+
 #[test]
 fn lift_local_function() {
     check_hir(
@@ -1975,13 +2081,13 @@ fn partial_app_unknown_callable() {
                         body: SpecDecl 3 [18-70]: Impl:
                             Block 4 [38-70] [Type Unit]:
                                 Stmt 5 [40-68]: Local (Immutable):
-                                    Pat 6 [44-45] [Type ?3]: Bind: Ident 7 [44-45] "f"
-                                    Expr 8 [48-67] [Type ?3]: Call:
+                                    Pat 6 [44-45] [Type ?]: Bind: Ident 7 [44-45] "f"
+                                    Expr 8 [48-67] [Type ?]: Call:
                                         Expr 9 [48-55] [Type ?]: Var: Err
-                                        Expr 10 [55-67] [Type (Bool, ?1, ?2)]: Tuple:
+                                        Expr 10 [55-67] [Type (Bool, ?, ?)]: Tuple:
                                             Expr 11 [56-60] [Type Bool]: Lit: Bool(true)
-                                            Expr 12 [62-63] [Type ?1]: Hole
-                                            Expr 13 [65-66] [Type ?2]: Hole
+                                            Expr 12 [62-63] [Type ?]: Hole
+                                            Expr 13 [65-66] [Type ?]: Hole
                         adj: <none>
                         ctl: <none>
                         ctl-adj: <none>"#]],
@@ -2027,10 +2133,10 @@ fn partial_app_too_many_args() {
                                     Pat 14 [82-83] [Type Int]: Bind: Ident 15 [82-83] "f"
                                     Expr 16 [86-98] [Type Int]: Call:
                                         Expr 17 [86-89] [Type (Int -> Int)]: Var: Item 1
-                                        Expr 18 [89-98] [Type (Int, ?1, ?2)]: Tuple:
+                                        Expr 18 [89-98] [Type (Int, ?, ?)]: Tuple:
                                             Expr 19 [90-91] [Type Int]: Lit: Int(1)
-                                            Expr 20 [93-94] [Type ?1]: Hole
-                                            Expr 21 [96-97] [Type ?2]: Hole
+                                            Expr 20 [93-94] [Type ?]: Hole
+                                            Expr 21 [96-97] [Type ?]: Hole
                         adj: <none>
                         ctl: <none>
                         ctl-adj: <none>"#]],
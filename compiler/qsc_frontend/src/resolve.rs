@@ -7,8 +7,8 @@ mod tests;
 use miette::Diagnostic;
 use qsc_ast::{
     ast::{
-        self, CallableBody, CallableDecl, ClassConstraints, Ident, Idents, NodeId, PathKind,
-        SpecBody, SpecGen, TopLevelNode, TypeParameter,
+        self, CallableBody, CallableDecl, ClassConstraints, ClassDecl, ConstDecl, EnumDecl, Ident,
+        Idents, NodeId, PathKind, SpecBody, SpecGen, TopLevelNode, TypeParameter,
     },
     visit::{self as ast_visit, walk_attr, Visitor as AstVisitor},
 };
@@ -132,6 +132,18 @@ pub(super) enum Error {
     #[diagnostic(code("Qsc.Resolve.DuplicateExport"))]
     DuplicateExport(String, #[label] Span),
 
+    #[error("export of `{0}` conflicts with another item named `{0}` in namespace `{1}`")]
+    #[diagnostic(help(
+        "a namespace can span several files, so the other item may be declared or exported in another file"
+    ))]
+    #[diagnostic(code("Qsc.Resolve.ConflictingExport"))]
+    ConflictingExport(String, String, #[label] Span),
+
+    #[error("`{0}` is internal and cannot be exported")]
+    #[diagnostic(help("remove the `internal` modifier from the declaration to export it"))]
+    #[diagnostic(code("Qsc.Resolve.ExportedInternal"))]
+    ExportedInternal(String, #[label] Span),
+
     #[error("`{0}` not found")]
     #[diagnostic(code("Qsc.Resolve.NotFound"))]
     NotFound(String, #[label] Span),
@@ -372,6 +384,8 @@ pub struct GlobalScope {
     terms: IndexMap<NamespaceId, FxHashMap<Rc<str>, Res>>,
     namespaces: NamespaceTreeRoot,
     intrinsics: FxHashSet<Rc<str>>,
+    /// Items of the local package declared `internal`, which cannot be exported.
+    internal_items: FxHashSet<ItemId>,
 }
 
 impl GlobalScope {
@@ -659,6 +673,23 @@ impl Resolver {
         }
     }
 
+    /// Resolves the names of the classes declared with `class` items that are used in the given
+    /// constraints. Names that do not resolve are left for the type checker to report, since they
+    /// may refer to built-in classes.
+    fn resolve_class_constraints(&mut self, constraints: &ast::ClassConstraints) {
+        for constraint in &constraints.0 {
+            if let Ok(res @ Res::Item(..)) = resolve(
+                NameKind::Ty,
+                &self.globals,
+                self.locals.get_scopes(&self.curr_scope_chain),
+                &constraint.name,
+                None,
+            ) {
+                self.names.insert(constraint.name.id, res);
+            }
+        }
+    }
+
     fn resolve_path_kind(&mut self, kind: NameKind, path: &ast::PathKind) -> Result<(), Error> {
         match path {
             PathKind::Ok(path) => self.resolve_path(kind, path).map(|_| ()),
@@ -738,11 +769,13 @@ impl Resolver {
                     if let Some(dropped_name) =
                         self.dropped_names.iter().find(|n| n.name.as_ref() == name)
                     {
-                        Err(Error::NotAvailable(
-                            name,
-                            format!("{}.{}", dropped_name.namespace, dropped_name.name),
-                            span,
-                        ))
+                        // Items declared in callable bodies are tracked without a namespace.
+                        let full_name = if dropped_name.namespace.is_empty() {
+                            dropped_name.name.to_string()
+                        } else {
+                            format!("{}.{}", dropped_name.namespace, dropped_name.name)
+                        };
+                        Err(Error::NotAvailable(name, full_name, span))
                     } else {
                         Err(Error::NotFound(name, span))
                     }
@@ -900,10 +933,73 @@ impl Resolver {
                     ScopeItemEntry::new(id, ItemSource::Declared),
                 );
             }
+            ast::ItemKind::Enum(decl) => self.bind_local_enum(assigner, item, decl),
+            ast::ItemKind::Const(decl) => {
+                let id = self.bind_local_name(assigner, item, &decl.name);
+                self.current_scope_mut().terms.insert(
+                    Rc::clone(&decl.name.name),
+                    ScopeItemEntry::new(id, ItemSource::Declared),
+                );
+            }
+            ast::ItemKind::Class(decl) => self.bind_local_class(assigner, item, decl),
             ast::ItemKind::Err | ast::ItemKind::ImportOrExport(..) => (),
         }
     }
 
+    /// Binds the name of a local item to a new item ID, which is returned so that the caller can add
+    /// the name to the scope as a type, a term, or both.
+    fn bind_local_name(
+        &mut self,
+        assigner: &mut Assigner,
+        item: &ast::Item,
+        name: &ast::Ident,
+    ) -> ItemId {
+        let id = intrapackage(assigner.next_item());
+        self.names.insert(
+            name.id,
+            Res::Item(
+                id,
+                ItemStatus::from_attrs(&ast_attrs_as_hir_attrs(&item.attrs)),
+            ),
+        );
+        id
+    }
+
+    /// Binds a local enum as a type and each of its variants as a term.
+    fn bind_local_enum(&mut self, assigner: &mut Assigner, item: &ast::Item, decl: &ast::EnumDecl) {
+        let status = ItemStatus::from_attrs(&ast_attrs_as_hir_attrs(&item.attrs));
+        let id = intrapackage(assigner.next_item());
+        self.names.insert(decl.name.id, Res::Item(id, status));
+        self.current_scope_mut().tys.insert(
+            Rc::clone(&decl.name.name),
+            ScopeItemEntry::new(id, ItemSource::Declared),
+        );
+        for variant in &decl.variants {
+            let id = intrapackage(assigner.next_item());
+            self.names.insert(variant.name.id, Res::Item(id, status));
+            self.current_scope_mut().terms.insert(
+                Rc::clone(&variant.name.name),
+                ScopeItemEntry::new(id, ItemSource::Declared),
+            );
+        }
+    }
+
+    /// Binds a local class as a type and each of its methods as a term.
+    fn bind_local_class(&mut self, assigner: &mut Assigner, item: &ast::Item, decl: &ClassDecl) {
+        let id = self.bind_local_name(assigner, item, &decl.name);
+        self.current_scope_mut().tys.insert(
+            Rc::clone(&decl.name.name),
+            ScopeItemEntry::new(id, ItemSource::Declared),
+        );
+        for method in &decl.methods {
+            let id = self.bind_local_name(assigner, item, &method.name);
+            self.current_scope_mut().terms.insert(
+                Rc::clone(&method.name.name),
+                ScopeItemEntry::new(id, ItemSource::Declared),
+            );
+        }
+    }
+
     #[allow(clippy::too_many_lines)]
     fn bind_import_or_export(
         &mut self,
@@ -1041,6 +1137,18 @@ impl Resolver {
                 ItemSource::Imported(decl_item.alias.clone())
             };
 
+            if is_export {
+                if let Err(err) = self.check_export(
+                    current_namespace,
+                    decl_item_name,
+                    &local_name,
+                    (&term_result, &ty_result),
+                ) {
+                    self.errors.push(err);
+                    continue;
+                }
+            }
+
             if let Ok(Res::Item(id, _) | Res::ExportedItem(id, _)) = term_result {
                 if is_export {
                     if let Some(namespace) = current_namespace {
@@ -1139,6 +1247,59 @@ impl Resolver {
     /// Very similar to [`bind_import`], but for glob imports.
     /// Globs can only be attached to namespaces, and
     /// they import all items from the namespace into the current scope.
+    /// Checks that an export neither exports an `internal` item nor gives an item a name that another item
+    /// already has in the namespace, which may be declared or exported in another file of the namespace.
+    fn check_export(
+        &self,
+        namespace: Option<NamespaceId>,
+        item_name: &Ident,
+        local_name: &Rc<str>,
+        (term_result, ty_result): (&Result<Res, Error>, &Result<Res, Error>),
+    ) -> Result<(), Error> {
+        let item_id = |result: &Result<Res, Error>| match result {
+            Ok(Res::Item(id, _) | Res::ExportedItem(id, _)) => Some(*id),
+            _ => None,
+        };
+        let (term_id, ty_id) = (item_id(term_result), item_id(ty_result));
+
+        if term_id
+            .iter()
+            .chain(&ty_id)
+            .any(|id| self.globals.internal_items.contains(id))
+        {
+            return Err(Error::ExportedInternal(
+                item_name.name.to_string(),
+                item_name.span,
+            ));
+        }
+
+        let Some(namespace) = namespace else {
+            return Ok(());
+        };
+        let conflicts = |kind, id: Option<ItemId>| {
+            id.is_some_and(|id| {
+                matches!(
+                    self.globals.get(kind, namespace, local_name),
+                    Some(Res::Item(existing, _)) if *existing != id
+                )
+            })
+        };
+        if conflicts(NameKind::Term, term_id) || conflicts(NameKind::Ty, ty_id) {
+            let namespace_name = self
+                .globals
+                .namespaces
+                .find_namespace_by_id(&namespace)
+                .0
+                .join(".");
+            return Err(Error::ConflictingExport(
+                local_name.to_string(),
+                namespace_name,
+                item_name.span,
+            ));
+        }
+        Ok(())
+    }
+
     fn bind_glob_import_or_export(&mut self, item: &ImportOrExportItem, is_export: bool) {
         let PathKind::Ok(path) = &item.path else {
             return;
@@ -1172,11 +1333,11 @@ impl Resolver {
         }
     }
 
-    /// For a given callable declaration, bind the names of the type parameters
+    /// For the type parameters of a callable or enum declaration, bind their names
     /// into the current scope. Tracks the constraints defined on the type parameters
     /// as well, for later use in type checking.
-    fn bind_type_parameters(&mut self, decl: &CallableDecl) {
-        decl.generics
+    fn bind_type_parameters(&mut self, generics: &[TypeParameter]) {
+        generics
             .iter()
             .enumerate()
             .for_each(|(ix, type_parameter)| {
@@ -1392,7 +1553,12 @@ impl AstVisitor<'_> for With<'_> {
         collect_param_names(&decl.input, &mut param_names);
         let prev_param_names = self.resolver.curr_params.replace(param_names);
         self.with_scope(decl.span, ScopeKind::Callable, |visitor| {
-            visitor.resolver.bind_type_parameters(decl);
+            visitor.resolver.bind_type_parameters(&decl.generics);
+            for param in &decl.generics {
+                visitor
+                    .resolver
+                    .resolve_class_constraints(&param.constraints);
+            }
             // The parameter bindings are valid after the end of the input pattern.
             // (More accurately, in the callable body, but we don't have a start offset for that).
             visitor.resolver.bind_pat(&decl.input, decl.input.span.hi);
@@ -1401,6 +1567,50 @@ impl AstVisitor<'_> for With<'_> {
         self.resolver.curr_params = prev_param_names;
     }
 
+    fn visit_enum_decl(&mut self, decl: &EnumDecl) {
+        self.with_scope(decl.span, ScopeKind::Callable, |visitor| {
+            visitor.resolver.bind_type_parameters(&decl.generics);
+            for param in &decl.generics {
+                visitor
+                    .resolver
+                    .resolve_class_constraints(&param.constraints);
+            }
+            ast_visit::walk_enum_decl(visitor, decl);
+        });
+    }
+
+    fn visit_class_decl(&mut self, decl: &ClassDecl) {
+        // The type parameters of the class and `'Self` are in scope in its constraints and in the
+        // signatures of its methods.
+        self.with_scope(decl.span, ScopeKind::Callable, |visitor| {
+            visitor
+                .resolver
+                .bind_type_parameters(&decl.method_generics());
+            visitor
+                .resolver
+                .resolve_class_constraints(&decl.constraints);
+            for constraint in &decl.constraints.0 {
+                for param in &constraint.parameters {
+                    visitor.visit_ty(&param.ty);
+                }
+            }
+            // The methods are not visited as callables, since a callable hides the type parameters
+            // of the scopes around it.
+            for method in &decl.methods {
+                visitor.visit_pat(&method.input);
+                visitor.visit_ty(&method.output);
+            }
+        });
+    }
+
+    fn visit_const_decl(&mut self, decl: &ConstDecl) {
+        // The value of a constant is computed apart from any callable, so it can only refer to
+        // items and to the locals it binds itself.
+        self.with_scope(decl.span, ScopeKind::Callable, |visitor| {
+            ast_visit::walk_const_decl(visitor, decl);
+        });
+    }
+
     fn visit_spec_decl(&mut self, decl: &ast::SpecDecl) {
         if let SpecBody::Impl(input, block) = &decl.body {
             self.with_spec_pat(block.span, ScopeKind::Block, input, |visitor| {
@@ -1418,6 +1628,14 @@ impl AstVisitor<'_> for With<'_> {
                     self.resolver.errors.push(e);
                 }
             }
+            ast::TyKind::App(path, args) => {
+                if let PathKind::Ok(path) = path {
+                    if let Err(e) = self.resolver.resolve_path(NameKind::Ty, path) {
+                        self.resolver.errors.push(e);
+                    }
+                }
+                args.iter().for_each(|arg| self.visit_ty(arg));
+            }
             ast::TyKind::Param(TypeParameter { ty, .. }) => {
                 self.resolver.resolve_ident(NameKind::Ty, ty);
             }
@@ -1509,6 +1727,28 @@ impl AstVisitor<'_> for With<'_> {
             _ => ast_visit::walk_expr(self, expr),
         }
     }
+
+    fn visit_match_arm(&mut self, arm: &ast::MatchArm) {
+        match &*arm.pat {
+            ast::MatchPat::Lit(lit) => {
+                self.visit_expr(lit);
+                self.visit_expr(&arm.body);
+            }
+            ast::MatchPat::Variant(path, pat) => {
+                if let Err(e) = self.resolver.resolve_path(NameKind::Term, path) {
+                    self.resolver.errors.push(e);
+                }
+                self.with_pat(arm.body.span, ScopeKind::Block, pat, |visitor| {
+                    visitor.visit_expr(&arm.body);
+                });
+            }
+            ast::MatchPat::Bind(pat) => {
+                self.with_pat(arm.body.span, ScopeKind::Block, pat, |visitor| {
+                    visitor.visit_expr(&arm.body);
+                });
+            }
+        }
+    }
 }
 
 pub(super) struct GlobalTable {
@@ -1548,6 +1788,7 @@ impl GlobalTable {
                 terms: IndexMap::default(),
                 namespaces: scope.namespaces,
                 intrinsics: FxHashSet::default(),
+                internal_items: FxHashSet::default(),
             },
         }
     }
@@ -1675,7 +1916,7 @@ impl GlobalTable {
                             self.scope
                                 .insert_or_find_namespace(ns.iter().map(|s| s.name.clone()));
                         }
-                        hir::ItemKind::Ty(..) => {
+                        hir::ItemKind::Ty(..) | hir::ItemKind::Class(..) => {
                             self.scope.tys.get_mut_or_default(namespace).insert(
                                 global.name.clone(),
                                 Res::Item(item_id, ItemStatus::Available),
@@ -1702,9 +1943,10 @@ fn find_item(
     let package = store.get(package_id)?;
     let item = package.package.items.get(item.item)?;
     Some(match &item.kind {
-        hir::ItemKind::Callable(_) | hir::ItemKind::Namespace(_, _) | hir::ItemKind::Ty(_, _) => {
-            item.clone()
-        }
+        hir::ItemKind::Callable(_)
+        | hir::ItemKind::Namespace(_, _)
+        | hir::ItemKind::Ty(_, _)
+        | hir::ItemKind::Class(_, _) => item.clone(),
         hir::ItemKind::Export(_alias, item) => return find_item(store, *item, package_id),
     })
 }
@@ -1725,15 +1967,23 @@ fn bind_global_items(
     let namespace_id = scope.insert_or_find_namespace(namespace.name.rc_str_iter().cloned());
 
     for item in &namespace.items {
-        match bind_global_item(
+        let mut item_ids = Vec::new();
+        let result = bind_global_item(
             names,
             scope,
             namespace_id,
-            || intrapackage(assigner.next_item()),
+            || {
+                let id = intrapackage(assigner.next_item());
+                item_ids.push(id);
+                id
+            },
             item,
-        ) {
-            Ok(()) => {}
-            Err(mut e) => errors.append(&mut e),
+        );
+        if item.visibility.is_some() {
+            scope.internal_items.extend(item_ids);
+        }
+        if let Err(mut e) = result {
+            errors.append(&mut e);
         }
     }
 }
@@ -1793,6 +2043,9 @@ fn bind_global_item(
         }
         ast::ItemKind::Ty(name, _) => bind_ty(name, namespace, next_id, item, names, scope),
         ast::ItemKind::Struct(decl) => bind_ty(&decl.name, namespace, next_id, item, names, scope),
+        ast::ItemKind::Enum(decl) => bind_enum(decl, namespace, next_id, item, names, scope),
+        ast::ItemKind::Const(decl) => bind_const(decl, namespace, next_id, item, names, scope),
+        ast::ItemKind::Class(decl) => bind_class(decl, namespace, next_id, item, names, scope),
         ast::ItemKind::ImportOrExport(decl) => {
             if decl.is_import() {
                 Ok(())
@@ -1950,6 +2203,147 @@ fn bind_ty(
     }
 }
 
+/// Binds the name of an enum as a type and the names of its variants as terms, since the variants are
+/// the functions that construct values of the enum.
+fn bind_enum(
+    decl: &EnumDecl,
+    namespace: NamespaceId,
+    mut next_id: impl FnMut() -> ItemId,
+    item: &ast::Item,
+    names: &mut IndexMap<NodeId, Res>,
+    scope: &mut GlobalScope,
+) -> Result<(), Vec<Error>> {
+    let status = ItemStatus::from_attrs(&ast_attrs_as_hir_attrs(item.attrs.as_ref()));
+    let mut errors = Vec::new();
+    let mut bind = |name: &Ident, kind: NameKind| {
+        let res = Res::Item(next_id(), status);
+        names.insert(name.id, res.clone());
+        let entries = match kind {
+            NameKind::Ty => &mut scope.tys,
+            NameKind::Term => &mut scope.terms,
+        };
+        match entries
+            .get_mut_or_default(namespace)
+            .entry(Rc::clone(&name.name))
+        {
+            Entry::Occupied(_) => {
+                let namespace_name = scope
+                    .namespaces
+                    .find_namespace_by_id(&namespace)
+                    .0
+                    .join(".");
+                errors.push(Error::Duplicate(
+                    name.name.to_string(),
+                    namespace_name,
+                    name.span,
+                ));
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(res);
+            }
+        }
+    };
+
+    bind(&decl.name, NameKind::Ty);
+    for variant in &decl.variants {
+        bind(&variant.name, NameKind::Term);
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+fn bind_const(
+    decl: &ConstDecl,
+    namespace: NamespaceId,
+    next_id: impl FnOnce() -> ItemId,
+    item: &ast::Item,
+    names: &mut IndexMap<NodeId, Res>,
+    scope: &mut GlobalScope,
+) -> Result<(), Vec<Error>> {
+    let status = ItemStatus::from_attrs(&ast_attrs_as_hir_attrs(item.attrs.as_ref()));
+    let res = Res::Item(next_id(), status);
+    names.insert(decl.name.id, res.clone());
+    match scope
+        .terms
+        .get_mut_or_default(namespace)
+        .entry(Rc::clone(&decl.name.name))
+    {
+        Entry::Occupied(_) => {
+            let namespace_name = scope
+                .namespaces
+                .find_namespace_by_id(&namespace)
+                .0
+                .join(".");
+            Err(vec![Error::Duplicate(
+                decl.name.name.to_string(),
+                namespace_name,
+                decl.name.span,
+            )])
+        }
+        Entry::Vacant(entry) => {
+            entry.insert(res);
+            Ok(())
+        }
+    }
+}
+
+/// Binds the name of a class as a type, so that it can be referred to from class constraints, and the
+/// names of its methods as terms, since they are called like any other callable.
+fn bind_class(
+    decl: &ClassDecl,
+    namespace: NamespaceId,
+    mut next_id: impl FnMut() -> ItemId,
+    item: &ast::Item,
+    names: &mut IndexMap<NodeId, Res>,
+    scope: &mut GlobalScope,
+) -> Result<(), Vec<Error>> {
+    let status = ItemStatus::from_attrs(&ast_attrs_as_hir_attrs(item.attrs.as_ref()));
+    let mut errors = Vec::new();
+    let mut bind = |name: &Ident, kind: NameKind| {
+        let res = Res::Item(next_id(), status);
+        names.insert(name.id, res.clone());
+        let entries = match kind {
+            NameKind::Ty => &mut scope.tys,
+            NameKind::Term => &mut scope.terms,
+        };
+        match entries
+            .get_mut_or_default(namespace)
+            .entry(Rc::clone(&name.name))
+        {
+            Entry::Occupied(_) => {
+                let namespace_name = scope
+                    .namespaces
+                    .find_namespace_by_id(&namespace)
+                    .0
+                    .join(".");
+                errors.push(Error::Duplicate(
+                    name.name.to_string(),
+                    namespace_name,
+                    name.span,
+                ));
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(res);
+            }
+        }
+    };
+
+    bind(&decl.name, NameKind::Ty);
+    for method in &decl.methods {
+        bind(&method.name, NameKind::Term);
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
 fn decl_is_intrinsic(decl: &CallableDecl, attrs: &[hir::Attr]) -> bool {
     if attrs
         .iter()
@@ -4181,6 +4181,106 @@ fn disallow_repeated_exports_inline() {
     );
 }
 
+#[test]
+fn export_from_other_block_of_namespace() {
+    check(
+        indoc! {"
+            namespace Foo {
+                operation ApplyX() : Unit {}
+            }
+            namespace Foo {
+                export ApplyX;
+            }
+        "},
+        &expect![[r#"
+            namespace namespace3 {
+                operation item1() : Unit {}
+            }
+            namespace namespace3 {
+                export item1;
+            }
+        "#]],
+    );
+}
+
+#[test]
+fn disallow_conflicting_exports_in_blocks_of_namespace() {
+    check(
+        indoc! {"
+            namespace Foo {
+                operation ApplyX() : Unit {}
+                export ApplyX as Apply;
+            }
+            namespace Foo {
+                operation ApplyY() : Unit {}
+                export ApplyY as Apply;
+            }
+        "},
+        &expect![[r#"
+            namespace namespace3 {
+                operation item1() : Unit {}
+                export item1;
+            }
+            namespace namespace3 {
+                operation item3() : Unit {}
+                export item3;
+            }
+
+            // ConflictingExport("Apply", "Foo", Span { lo: 149, hi: 154 })
+        "#]],
+    );
+}
+
+#[test]
+fn disallow_export_conflicting_with_declaration_in_other_block_of_namespace() {
+    check(
+        indoc! {"
+            namespace Foo {
+                operation Apply() : Unit {}
+            }
+            namespace Foo {
+                operation ApplyX() : Unit {}
+                export ApplyX as Apply;
+            }
+        "},
+        &expect![[r#"
+            namespace namespace3 {
+                operation item1() : Unit {}
+            }
+            namespace namespace3 {
+                operation item3() : Unit {}
+                export item3;
+            }
+
+            // ConflictingExport("Apply", "Foo", Span { lo: 120, hi: 125 })
+        "#]],
+    );
+}
+
+#[test]
+fn disallow_export_of_internal_item() {
+    check(
+        indoc! {"
+            namespace Foo {
+                internal operation ApplyX() : Unit {}
+            }
+            namespace Bar {
+                export Foo.ApplyX;
+            }
+        "},
+        &expect![[r#"
+            namespace namespace3 {
+                internal operation item1() : Unit {}
+            }
+            namespace namespace4 {
+                export item1;
+            }
+
+            // ExportedInternal("ApplyX", Span { lo: 91, hi: 97 })
+        "#]],
+    );
+}
+
 #[test]
 fn order_of_exports_does_not_matter() {
     check(
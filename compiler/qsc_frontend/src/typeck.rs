@@ -10,6 +10,7 @@
 mod check;
 pub(super) mod convert;
 mod infer;
+mod lengths;
 mod rules;
 #[cfg(test)]
 mod tests;
@@ -20,9 +21,9 @@ use qsc_ast::ast::NodeId;
 use qsc_data_structures::{index_map::IndexMap, span::Span};
 use qsc_hir::{
     hir::{CallableKind, ItemId},
-    ty::{FunctorSet, GenericArg, Ty, Udt},
+    ty::{ClassDef, FunctorSet, GenericArg, Ty, Udt},
 };
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 use std::fmt::Debug;
 use thiserror::Error;
 
@@ -34,6 +35,13 @@ pub(super) use check::{Checker, GlobalTable};
 pub struct Table {
     pub udts: FxHashMap<ItemId, Udt>,
 
+    /// The classes declared with `class` items.
+    pub classes: FxHashMap<ItemId, ClassDef>,
+
+    /// The items that are constants. A path to a constant has the type of its value rather than
+    /// the type of a callable.
+    pub consts: FxHashSet<ItemId>,
+
     // AST nodes that get mapped to types are Expr, Block, Pat, and QubitInit nodes
     // AST Ident nodes under Paths that are field accessors are also mapped to types, as they will become expressions in the HIR
     pub terms: IndexMap<NodeId, Ty>,
@@ -50,6 +58,31 @@ enum ErrorKind {
     #[error("expected {0}, found {1}")]
     #[diagnostic(code("Qsc.TypeCk.TyMismatch"))]
     TyMismatch(String, String, #[label] Span),
+    #[error("expected an array of length {0}, found an array of length {1}")]
+    #[diagnostic(code("Qsc.TypeCk.ArrayLengthMismatch"))]
+    ArrayLengthMismatch(usize, usize, #[label] Span),
+    #[error("sized array types are only supported for the parameters of callables")]
+    #[diagnostic(help("use an array type without a length, like `Qubit[]`"))]
+    #[diagnostic(code("Qsc.TypeCk.MisplacedSizedArray"))]
+    MisplacedSizedArray(#[label] Span),
+    #[error("function {0} is named after an operator of its type but does not have its signature")]
+    #[diagnostic(help("a function that implements this operator must have the signature `{1}`"))]
+    #[diagnostic(code("Qsc.TypeCk.InvalidOperatorSignature"))]
+    InvalidOperatorSignature(String, String, #[label] Span),
+    #[error("callable {0} is named after a method of class {1} but does not have its signature")]
+    #[diagnostic(help("a callable that implements this method must have the signature `{2}`"))]
+    #[diagnostic(code("Qsc.TypeCk.InvalidMethodSignature"))]
+    InvalidMethodSignature(String, String, String, #[label] Span),
+    #[error("type {0} implements some methods of class {1} but not {2}")]
+    #[diagnostic(help("a member of a class must implement every method of the class"))]
+    #[diagnostic(code("Qsc.TypeCk.MissingClassMethod"))]
+    MissingClassMethod(String, String, String, #[label] Span),
+    #[error("{0} is not a variant of an enum")]
+    #[diagnostic(help(
+        "only the variants of an enum can be matched with a payload, like `Circle(r)`"
+    ))]
+    #[diagnostic(code("Qsc.TypeCk.NotAVariant"))]
+    NotAVariant(String, #[label] Span),
     #[error("expected {0}, found {1}")]
     #[diagnostic(code("Qsc.TypeCk.CallableMismatch"))]
     CallableMismatch(CallableKind, CallableKind, #[label] Span),
@@ -128,10 +161,29 @@ enum ErrorKind {
     #[error("type {0} cannot be converted into a string")]
     #[diagnostic(code("Qsc.TypeCk.MissingClassShow"))]
     MissingClassShow(String, #[label] Span),
+    #[error("type {0} is not a member of class {1}")]
+    #[diagnostic(help(
+        "a type is a member of a class with methods when it implements each method with a function named after the method and the type"
+    ))]
+    #[diagnostic(code("Qsc.TypeCk.MissingClassMember"))]
+    MissingClassMember(String, String, #[label] Span),
     #[error("type {0} cannot be unwrapped")]
     #[diagnostic(help("only newtypes support unwrap"))]
     #[diagnostic(code("Qsc.TypeCk.MissingClassUnwrap"))]
     MissingClassUnwrap(String, #[label] Span),
+    #[error("type {0} cannot be propagated with `?`")]
+    #[diagnostic(help(
+        "only enums with more than one variant support `?`, like `Option<'T>` and `Outcome<'T, 'E>`"
+    ))]
+    #[diagnostic(code("Qsc.TypeCk.MissingClassPropagate"))]
+    MissingClassPropagate(String, #[label] Span),
+    #[error("`?` on type {0} requires the callable to return the same enum, but it returns {1}")]
+    #[diagnostic(code("Qsc.TypeCk.PropagateMismatch"))]
+    PropagateMismatch(String, String, #[label] Span),
+    #[error("`?` can only be used in a callable")]
+    #[diagnostic(help("`?` returns from the callable when its operand is not the first variant"))]
+    #[diagnostic(code("Qsc.TypeCk.PropagateOutsideCallable"))]
+    PropagateOutsideCallable(#[label] Span),
     #[error("expected superset of {0}, found {1}")]
     #[diagnostic(code("Qsc.TypeCk.MissingFunctor"))]
     MissingFunctor(FunctorSet, FunctorSet, #[label] Span),
@@ -3,6 +3,7 @@
 
 use super::{
     infer::Inferrer,
+    lengths::{LengthChecker, Lengths},
     rules::{self, SpecImpl},
     Error, ErrorKind, Table,
 };
@@ -15,16 +16,22 @@ use qsc_ast::{
     visit::{self, Visitor},
 };
 use qsc_data_structures::index_map::IndexMap;
+use qsc_data_structures::span::Span;
 use qsc_hir::{
     hir::{self, ItemId, PackageId},
-    ty::{FunctorSetValue, Scheme, Ty, Udt},
+    ty::{
+        Arrow, ClassConstraints, ClassDef, FunctorSet, FunctorSetValue, GenericArg, ParamId, Prim,
+        Scheme, Ty, TypeParameter, Udt, UdtDef, UdtDefKind, UdtInstance, UdtOperator,
+    },
 };
-use rustc_hash::FxHashMap;
-use std::vec;
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::{iter::once, rc::Rc, vec};
 
 pub(crate) struct GlobalTable {
     udts: FxHashMap<ItemId, Udt>,
+    classes: FxHashMap<ItemId, ClassDef>,
     terms: FxHashMap<ItemId, Scheme>,
+    consts: FxHashSet<ItemId>,
     errors: Vec<Error>,
 }
 
@@ -32,7 +39,9 @@ impl GlobalTable {
     pub(crate) fn new() -> Self {
         Self {
             udts: FxHashMap::default(),
+            classes: FxHashMap::default(),
             terms: FxHashMap::default(),
+            consts: FxHashSet::default(),
             errors: Vec::new(),
         }
     }
@@ -62,12 +71,20 @@ impl GlobalTable {
             hir::ItemKind::Callable(decl) => {
                 self.terms
                     .insert(item_id, decl.scheme().with_package(package_id));
+                if item.attrs.contains(&hir::Attr::Const) {
+                    self.consts.insert(item_id);
+                }
             }
             hir::ItemKind::Namespace(..) => (),
+            hir::ItemKind::Class(_, def) => {
+                self.classes.insert(item_id, def.with_package(package_id));
+            }
             hir::ItemKind::Ty(_, udt) => {
                 self.udts.insert(item_id, udt.clone());
-                self.terms
-                    .insert(item_id, udt.cons_scheme(item_id).with_package(package_id));
+                if !udt.is_enum() {
+                    self.terms
+                        .insert(item_id, udt.cons_scheme(item_id).with_package(package_id));
+                }
             }
             hir::ItemKind::Export(
                 _,
@@ -101,6 +118,7 @@ pub(crate) struct Checker {
     globals: FxHashMap<ItemId, Scheme>,
     table: Table,
     inferrer: Inferrer,
+    lengths: Lengths,
     new: Vec<NodeId>,
     errors: Vec<Error>,
 }
@@ -111,10 +129,13 @@ impl Checker {
             globals: globals.terms,
             table: Table {
                 udts: globals.udts,
+                classes: globals.classes,
+                consts: globals.consts,
                 terms: IndexMap::new(),
                 generics: IndexMap::new(),
             },
             inferrer: Inferrer::new(),
+            lengths: Lengths::default(),
             new: Vec::new(),
             errors: globals.errors,
         }
@@ -133,7 +154,10 @@ impl Checker {
     }
 
     pub(crate) fn check_package(&mut self, names: &Names, package: &ast::Package) {
-        ItemCollector::new(self, names).visit_package(package);
+        let mut collector = ItemCollector::new(self, names);
+        collector.visit_package(package);
+        collector.add_udt_instances();
+        collector.add_udt_operators();
         ItemChecker::new(self, names).visit_package(package);
 
         if let Some(entry) = &package.entry {
@@ -156,6 +180,8 @@ impl Checker {
                 ));
             }
         }
+
+        LengthChecker::new(names, &mut self.lengths, &mut self.errors).visit_package(package);
     }
 
     fn check_callable_decl(&mut self, names: &Names, decl: &ast::CallableDecl) {
@@ -235,11 +261,303 @@ impl Checker {
 struct ItemCollector<'a> {
     checker: &'a mut Checker,
     names: &'a Names,
+    callables: Vec<(Rc<str>, ItemId, Span)>,
 }
 
 impl<'a> ItemCollector<'a> {
     fn new(checker: &'a mut Checker, names: &'a Names) -> Self {
-        Self { checker, names }
+        Self {
+            checker,
+            names,
+            callables: Vec::new(),
+        }
+    }
+
+    /// Attaches the collected callables that implement operators to the types declared in this
+    /// package. A callable implements an operator if its name is the prefix of the operator followed
+    /// by the name of the type, like `PlusComplex`. Quantities already support their operators, so
+    /// they are skipped.
+    fn add_udt_operators(&mut self) {
+        for (name, item, span) in std::mem::take(&mut self.callables) {
+            for op in UdtOperator::ALL {
+                let Some(ty_name) = name.strip_prefix(op.prefix()) else {
+                    continue;
+                };
+                let scheme = self
+                    .checker
+                    .globals
+                    .get(&item)
+                    .expect("callable should have scheme");
+                let mut udts = self
+                    .checker
+                    .table
+                    .udts
+                    .iter_mut()
+                    .filter(|(id, udt)| {
+                        id.package.is_none() && !udt.quantity && udt.name.as_ref() == ty_name
+                    })
+                    .peekable();
+                if udts.peek().is_none() {
+                    continue;
+                }
+
+                match udts.find(|(id, _)| implements_operator(op, **id, scheme)) {
+                    Some((_, udt)) => udt.operators.push((op, item.item)),
+                    None => self
+                        .checker
+                        .errors
+                        .push(Error(ErrorKind::InvalidOperatorSignature(
+                            name.to_string(),
+                            operator_signature(op, ty_name),
+                            span,
+                        ))),
+                }
+            }
+        }
+    }
+
+    /// Makes the types declared in this package members of the classes whose methods they implement.
+    /// A callable implements a method for a type if its name is the name of the method followed by
+    /// the name of the type, like `ItemsRange` for the method `Items` on a type named `Range`, and
+    /// its signature is that of the method with the type in place of `'Self`. A member must implement
+    /// every method of the class. Generic types and quantities are skipped.
+    fn add_udt_instances(&mut self) {
+        let classes: Vec<_> = self
+            .checker
+            .table
+            .classes
+            .iter()
+            .filter(|(_, class)| !class.methods.is_empty())
+            .map(|(id, class)| (*id, class.clone()))
+            .collect();
+        if classes.is_empty() {
+            return;
+        }
+
+        let udts: Vec<_> = self
+            .checker
+            .table
+            .udts
+            .iter()
+            .filter(|(id, udt)| id.package.is_none() && !udt.quantity && udt.generics.is_empty())
+            .map(|(id, udt)| (*id, udt.name.clone()))
+            .collect();
+        for (udt_id, udt_name) in udts {
+            for (class_id, class) in &classes {
+                if let Some(instance) = self.udt_instance(udt_id, &udt_name, *class_id, class) {
+                    self.checker
+                        .table
+                        .udts
+                        .get_mut(&udt_id)
+                        .expect("type should be in table")
+                        .instances
+                        .push(instance);
+                }
+            }
+        }
+    }
+
+    /// The membership of the type in the class, if the collected callables implement the methods of
+    /// the class for the type. Reports an error if they implement only some of the methods or do not
+    /// have their signatures.
+    fn udt_instance(
+        &mut self,
+        udt_id: ItemId,
+        udt_name: &Rc<str>,
+        class_id: ItemId,
+        class: &ClassDef,
+    ) -> Option<UdtInstance> {
+        let implementers: Vec<_> = class
+            .methods
+            .iter()
+            .map(|(method, _)| {
+                let name = format!("{method}{udt_name}");
+                self.callables
+                    .iter()
+                    .find(|(callable, ..)| callable.as_ref() == name)
+                    .map(|&(_, item, span)| (item, span))
+            })
+            .collect();
+        let (_, first_span) = implementers.iter().flatten().next().copied()?;
+        if let Some(index) = implementers.iter().position(Option::is_none) {
+            self.checker
+                .errors
+                .push(Error(ErrorKind::MissingClassMethod(
+                    udt_name.to_string(),
+                    class.name.to_string(),
+                    class.methods[index].0.to_string(),
+                    first_span,
+                )));
+            return None;
+        }
+
+        // The method signatures are instantiated with the type in place of `'Self` and with the
+        // class parameters that are not yet bound to a type left in place, for the matcher to bind.
+        let udt_ty = Ty::Udt(udt_name.clone(), hir::Res::Item(udt_id), Vec::new());
+        let method_tys = |args: &[Option<Ty>]| -> Vec<Ty> {
+            class
+                .generics
+                .iter()
+                .zip(args)
+                .enumerate()
+                .map(|(index, (param, arg))| match (arg, param) {
+                    (Some(arg), _) => arg.clone(),
+                    (None, TypeParameter::Ty { name, .. }) => Ty::Param {
+                        name: name.clone(),
+                        id: ParamId::from(index),
+                        bounds: ClassConstraints::default(),
+                    },
+                    (None, TypeParameter::Functor(_)) => Ty::Err,
+                })
+                .chain(once(udt_ty.clone()))
+                .collect()
+        };
+
+        let mut args = vec![None; class.generics.len()];
+        let mut methods = Vec::new();
+        for ((method, method_item), (item, span)) in
+            class.methods.iter().zip(implementers.into_iter().flatten())
+        {
+            let method_scheme = self
+                .checker
+                .globals
+                .get(&ItemId {
+                    package: class_id.package,
+                    item: *method_item,
+                })
+                .expect("method should have scheme");
+            let method_arrow = instantiate_with_min_functors(method_scheme, &method_tys(&args))
+                .expect("method should instantiate with class arguments");
+            let scheme = self
+                .checker
+                .globals
+                .get(&item)
+                .expect("callable should have scheme");
+            let implements = instantiate_with_min_functors(scheme, &[]).is_some_and(|arrow| {
+                MethodMatcher { args: &mut args }.matches(
+                    &Ty::Arrow(Box::new(method_arrow.clone())),
+                    &Ty::Arrow(Box::new(arrow)),
+                )
+            });
+            if implements {
+                methods.push(item.item);
+            } else {
+                self.checker
+                    .errors
+                    .push(Error(ErrorKind::InvalidMethodSignature(
+                        format!("{method}{udt_name}"),
+                        class.name.to_string(),
+                        Ty::Arrow(Box::new(method_arrow)).display(),
+                        span,
+                    )));
+            }
+        }
+
+        (methods.len() == class.methods.len()).then(|| UdtInstance {
+            class: class_id,
+            args: args.into_iter().map(|arg| arg.unwrap_or(Ty::Err)).collect(),
+            methods,
+        })
+    }
+
+    /// Adds an enum declaration to the table of types, along with the schemes of its variants, which
+    /// construct values of the enum.
+    fn collect_enum(&mut self, span: Span, decl: &ast::EnumDecl) {
+        let Some(&Res::Item(item, _)) = self.names.get(decl.name.id) else {
+            panic!("type should have item ID");
+        };
+
+        let (generics, generic_errors) =
+            convert::type_parameters_for_ast_callable(self.names, &decl.generics);
+        let (variants, errors) = convert::ast_enum_variants(self.names, decl);
+        self.checker
+            .errors
+            .extend(generic_errors.into_iter().chain(errors).map(Into::into));
+
+        let udt = Udt {
+            name: decl.name.name.clone(),
+            span,
+            definition: UdtDef {
+                span: decl.span,
+                kind: UdtDefKind::Tuple(Vec::new()),
+            },
+            quantity: false,
+            operators: Vec::new(),
+            instances: Vec::new(),
+            variants,
+            generics,
+        };
+        for variant in &udt.variants {
+            let variant_item = ItemId {
+                package: item.package,
+                item: variant.item,
+            };
+            self.checker
+                .globals
+                .insert(variant_item, udt.variant_scheme(item, variant));
+        }
+        self.checker.table.udts.insert(item, udt);
+    }
+
+    fn collect_const(&mut self, decl: &ast::ConstDecl) {
+        let Some(&Res::Item(item, _)) = self.names.get(decl.name.id) else {
+            panic!("constant should have item ID");
+        };
+
+        let (ty, errors) = convert::ty_from_ast(self.names, &decl.ty, &mut Default::default());
+        self.checker
+            .errors
+            .extend(errors.into_iter().map(Into::into));
+
+        self.checker.globals.insert(item, convert::const_scheme(ty));
+        self.checker.table.consts.insert(item);
+    }
+
+    /// Adds a class declaration to the table of classes, along with the schemes of its methods, which
+    /// are generic over the type parameters of the class and `'Self`.
+    fn collect_class(&mut self, decl: &ast::ClassDecl) {
+        let Some(&Res::Item(item, _)) = self.names.get(decl.name.id) else {
+            panic!("class should have item ID");
+        };
+
+        let (generics, generic_errors) =
+            convert::type_parameters_for_ast_callable(self.names, &decl.generics);
+        let (constraints, errors) = convert::class_constraints_from_ast(
+            self.names,
+            &decl.constraints,
+            &mut Default::default(),
+        );
+        self.checker
+            .errors
+            .extend(generic_errors.into_iter().chain(errors).map(Into::into));
+
+        let method_generics = decl.method_generics().into_boxed_slice();
+        let mut methods = Vec::new();
+        for method in &decl.methods {
+            let Some(&Res::Item(method_item, _)) = self.names.get(method.name.id) else {
+                panic!("method should have item ID");
+            };
+            let method = ast::CallableDecl {
+                generics: method_generics.clone(),
+                ..(**method).clone()
+            };
+            let (scheme, errors) = convert::scheme_for_ast_callable(self.names, &method);
+            self.checker
+                .errors
+                .extend(errors.into_iter().map(Into::into));
+            self.checker.globals.insert(method_item, scheme);
+            methods.push((method.name.name.clone(), method_item.item));
+        }
+
+        self.checker.table.classes.insert(
+            item,
+            ClassDef {
+                name: decl.name.name.clone(),
+                generics,
+                constraints,
+                methods,
+            },
+        );
     }
 }
 
@@ -257,9 +575,13 @@ impl Visitor<'_> for ItemCollector<'_> {
                 }
 
                 self.checker.globals.insert(item, scheme);
+                self.checker.lengths.add_callable(item, decl);
+                self.callables
+                    .push((decl.name.name.clone(), item, decl.name.span));
             }
             ast::ItemKind::Ty(name, def) => {
                 let span = item.span;
+                let attrs = &item.attrs;
                 let Some(&Res::Item(item, _)) = self.names.get(name.id) else {
                     panic!("type should have item ID");
                 };
@@ -271,12 +593,18 @@ impl Visitor<'_> for ItemCollector<'_> {
                     .errors
                     .extend(cons_errors.into_iter().chain(def_errors).map(Into::into));
 
+                let quantity = is_quantity(attrs, &udt_def);
                 self.checker.table.udts.insert(
                     item,
                     Udt {
                         name: name.name.clone(),
                         span,
                         definition: udt_def,
+                        quantity,
+                        operators: Vec::new(),
+                        instances: Vec::new(),
+                        variants: Vec::new(),
+                        generics: Vec::new(),
                     },
                 );
                 self.checker.globals.insert(item, cons);
@@ -302,10 +630,18 @@ impl Visitor<'_> for ItemCollector<'_> {
                         name: decl.name.name.clone(),
                         span,
                         definition: udt_def,
+                        quantity: false,
+                        operators: Vec::new(),
+                        instances: Vec::new(),
+                        variants: Vec::new(),
+                        generics: Vec::new(),
                     },
                 );
                 self.checker.globals.insert(item, cons);
             }
+            ast::ItemKind::Enum(decl) => self.collect_enum(item.span, decl),
+            ast::ItemKind::Const(decl) => self.collect_const(decl),
+            ast::ItemKind::Class(decl) => self.collect_class(decl),
             _ => {}
         }
 
@@ -316,6 +652,117 @@ impl Visitor<'_> for ItemCollector<'_> {
     fn visit_attr(&mut self, _: &ast::Attr) {}
 }
 
+/// Whether a type declared with the given attributes and definition is a quantity, which requires the
+/// `@Quantity()` attribute and a definition that wraps a single `Int` or `Double`.
+fn is_quantity(attrs: &[Box<ast::Attr>], def: &UdtDef) -> bool {
+    let is_numeric = match &def.kind {
+        UdtDefKind::Field(field) => matches!(field.ty, Ty::Prim(Prim::Int | Prim::Double)),
+        UdtDefKind::Tuple(_) => false,
+    };
+    is_numeric
+        && attrs
+            .iter()
+            .any(|attr| attr.name.name.as_ref() == "Quantity")
+}
+
+/// Whether a callable with the given scheme has the signature of the operator for the type with the
+/// given ID.
+fn implements_operator(op: UdtOperator, udt: ItemId, scheme: &Scheme) -> bool {
+    let is_udt = |ty: &Ty| matches!(ty, Ty::Udt(_, hir::Res::Item(id), _) if *id == udt);
+    // Only a callable without generic parameters can implement an operator, and instantiating its
+    // scheme without arguments fails otherwise.
+    let Ok(arrow) = scheme.instantiate(&[]) else {
+        return false;
+    };
+    let input_matches = match &*arrow.input {
+        Ty::Tuple(items) if op != UdtOperator::Negation => {
+            items.len() == 2 && items.iter().all(is_udt)
+        }
+        input => op == UdtOperator::Negation && is_udt(input),
+    };
+    let output_matches = if op == UdtOperator::Compare {
+        *arrow.output == Ty::Prim(Prim::Int)
+    } else {
+        is_udt(&arrow.output)
+    };
+    arrow.kind == hir::CallableKind::Function && input_matches && output_matches
+}
+
+/// Matches the signature of a class method against the signature of a callable that implements it
+/// for a type, binding the type parameters of the class that the method leaves in place to the
+/// types they stand for in the callable.
+struct MethodMatcher<'a> {
+    /// The types bound to the type parameters of the class so far, by index.
+    args: &'a mut Vec<Option<Ty>>,
+}
+
+impl MethodMatcher<'_> {
+    fn matches(&mut self, method: &Ty, actual: &Ty) -> bool {
+        match (method, actual) {
+            (Ty::Param { id, .. }, actual) => match self.args.get_mut(usize::from(*id)) {
+                Some(Some(bound)) => bound == actual,
+                Some(arg) => {
+                    *arg = Some(actual.clone());
+                    true
+                }
+                None => false,
+            },
+            (Ty::Array(method), Ty::Array(actual)) => self.matches(method, actual),
+            (Ty::Arrow(method), Ty::Arrow(actual)) => {
+                method.kind == actual.kind
+                    && method.functors == actual.functors
+                    && self.matches(&method.input, &actual.input)
+                    && self.matches(&method.output, &actual.output)
+            }
+            (Ty::Tuple(method), Ty::Tuple(actual)) => {
+                method.len() == actual.len()
+                    && method
+                        .iter()
+                        .zip(actual)
+                        .all(|(method, actual)| self.matches(method, actual))
+            }
+            (Ty::Udt(_, method_res, method), Ty::Udt(_, actual_res, actual)) => {
+                method_res == actual_res
+                    && method.len() == actual.len()
+                    && method
+                        .iter()
+                        .zip(actual)
+                        .all(|(method, actual)| self.matches(method, actual))
+            }
+            (method, actual) => method == actual,
+        }
+    }
+}
+
+/// Instantiates the scheme with the given arguments for its type parameters and with the minimal
+/// functors for its functor parameters. Returns [None] if the number of type arguments is wrong.
+fn instantiate_with_min_functors(scheme: &Scheme, tys: &[Ty]) -> Option<Arrow> {
+    let mut tys = tys.iter();
+    let args = scheme
+        .params()
+        .iter()
+        .map(|param| match param {
+            TypeParameter::Ty { .. } => tys.next().cloned().map(GenericArg::Ty),
+            TypeParameter::Functor(min) => Some(GenericArg::Functor(FunctorSet::Value(*min))),
+        })
+        .collect::<Option<Vec<_>>>()?;
+    if tys.next().is_some() {
+        return None;
+    }
+    scheme.instantiate(&args).ok()
+}
+
+/// The signature of the function that implements the operator for the type with the given name.
+fn operator_signature(op: UdtOperator, ty_name: &str) -> String {
+    match op {
+        UdtOperator::Negation => format!("{ty_name} -> {ty_name}"),
+        UdtOperator::Compare => format!("({ty_name}, {ty_name}) -> Int"),
+        UdtOperator::Plus | UdtOperator::Minus | UdtOperator::Times | UdtOperator::DividedBy => {
+            format!("({ty_name}, {ty_name}) -> {ty_name}")
+        }
+    }
+}
+
 struct ItemChecker<'a> {
     checker: &'a mut Checker,
     names: &'a Names,
@@ -325,14 +772,63 @@ impl<'a> ItemChecker<'a> {
     fn new(checker: &'a mut Checker, names: &'a Names) -> Self {
         Self { checker, names }
     }
+
+    /// Reports the constraints that name a declared class with a different number of arguments than
+    /// the class has type parameters. Built-in classes are checked when their constraints are
+    /// converted.
+    fn check_class_arity(&mut self, constraints: &ast::ClassConstraints) {
+        for constraint in &constraints.0 {
+            let Some(Res::Item(id, _)) = self.names.get(constraint.name.id) else {
+                continue;
+            };
+            let Some(class) = self.checker.table.classes.get(id) else {
+                continue;
+            };
+            if class.generics.len() != constraint.parameters.len() {
+                self.checker
+                    .errors
+                    .push(Error(ErrorKind::IncorrectNumberOfConstraintParameters {
+                        expected: class.generics.len(),
+                        found: constraint.parameters.len(),
+                        span: constraint.span(),
+                    }));
+            }
+        }
+    }
 }
 
 impl Visitor<'_> for ItemChecker<'_> {
     fn visit_callable_decl(&mut self, decl: &ast::CallableDecl) {
+        for param in &decl.generics {
+            self.check_class_arity(&param.constraints);
+        }
         self.checker.check_callable_decl(self.names, decl);
         visit::walk_callable_decl(self, decl);
     }
 
+    fn visit_enum_decl(&mut self, decl: &ast::EnumDecl) {
+        for param in &decl.generics {
+            self.check_class_arity(&param.constraints);
+        }
+        visit::walk_enum_decl(self, decl);
+    }
+
+    // Methods have no bodies, and their signatures are checked when the class is collected.
+    fn visit_class_decl(&mut self, decl: &ast::ClassDecl) {
+        self.check_class_arity(&decl.constraints);
+    }
+
+    fn visit_const_decl(&mut self, decl: &ast::ConstDecl) {
+        let ty = convert::ty_from_ast(self.names, &decl.ty, &mut Default::default()).0;
+        self.checker.errors.append(&mut rules::const_decl(
+            self.names,
+            &self.checker.globals,
+            &mut self.checker.table,
+            ty,
+            &decl.value,
+        ));
+    }
+
     // We do not typecheck attributes, as they are verified during lowering.
     fn visit_attr(&mut self, _: &ast::Attr) {}
 }
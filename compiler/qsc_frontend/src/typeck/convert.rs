@@ -7,8 +7,8 @@ use std::rc::Rc;
 use crate::resolve::{self, Names};
 
 use qsc_ast::ast::{
-    self, CallableBody, CallableDecl, CallableKind, FunctorExpr, FunctorExprKind, Pat, PatKind,
-    Path, PathKind, SetOp, Spec, StructDecl, TyDef, TyDefKind, TyKind,
+    self, CallableBody, CallableDecl, CallableKind, EnumDecl, FunctorExpr, FunctorExprKind, Pat,
+    PatKind, Path, PathKind, SetOp, Spec, StructDecl, TyDef, TyDefKind, TyKind,
     TypeParameter as AstTypeParameter,
 };
 use qsc_data_structures::span::Span;
@@ -16,7 +16,7 @@ use qsc_hir::{
     hir::{self},
     ty::{
         Arrow, FunctorSet, FunctorSetValue, ParamId, Scheme, Ty, TypeParameter as HirTypeParameter,
-        UdtDef, UdtDefKind, UdtField,
+        UdtDef, UdtDefKind, UdtField, UdtVariant,
     },
 };
 use rustc_hash::FxHashSet;
@@ -48,7 +48,7 @@ pub(crate) fn ty_from_ast(
     stack: &mut FxHashSet<qsc_ast::ast::ClassConstraint>,
 ) -> (Ty, Vec<TyConversionError>) {
     match &*ty.kind {
-        TyKind::Array(item) => {
+        TyKind::Array(item) | TyKind::SizedArray(item, _) => {
             let (item, errors) = ty_from_ast(names, item, stack);
             (Ty::Array(Box::new(item)), errors)
         }
@@ -76,7 +76,13 @@ pub(crate) fn ty_from_ast(
         TyKind::Paren(inner) => ty_from_ast(names, inner, stack),
         TyKind::Param(AstTypeParameter { ty, .. }) => match names.get(ty.id) {
             Some(resolve::Res::Param { id, bounds }) => {
-                let (bounds, errors) = class_constraints_from_ast(names, bounds, stack);
+                let is_nested_in_constraint = !stack.is_empty();
+                let (bounds, mut errors) = class_constraints_from_ast(names, bounds, stack);
+                // Outside of a constraint, the bounds are converted just as they are where the parameter is
+                // declared, which already reports their errors.
+                if !is_nested_in_constraint {
+                    errors.clear();
+                }
                 (
                     Ty::Param {
                         name: ty.name.clone(),
@@ -92,6 +98,16 @@ pub(crate) fn ty_from_ast(
             ),
         },
         TyKind::Path(PathKind::Ok(path)) => (ty_from_path(names, path), Vec::new()),
+        TyKind::App(PathKind::Ok(path), args) => {
+            let mut tys = Vec::new();
+            let mut errors = Vec::new();
+            for arg in args {
+                let (arg_ty, arg_errors) = ty_from_ast(names, arg, stack);
+                tys.push(arg_ty);
+                errors.extend(arg_errors);
+            }
+            (ty_from_app(names, path, tys), errors)
+        }
         TyKind::Tuple(items) => {
             let mut tys = Vec::new();
             let mut errors = Vec::new();
@@ -102,13 +118,22 @@ pub(crate) fn ty_from_ast(
             }
             (Ty::Tuple(tys), errors)
         }
-        TyKind::Err | TyKind::Path(PathKind::Err { .. }) => (Ty::Err, Vec::new()),
+        TyKind::Err | TyKind::Path(PathKind::Err { .. }) | TyKind::App(PathKind::Err { .. }, _) => {
+            (Ty::Err, Vec::new())
+        }
     }
 }
 
 pub(super) fn ty_from_path(names: &Names, path: &Path) -> Ty {
+    ty_from_app(names, path, Vec::new())
+}
+
+/// The type that a path applies to the given type arguments, which only a generic enum can take.
+pub(super) fn ty_from_app(names: &Names, path: &Path, args: Vec<Ty>) -> Ty {
     match names.get(path.id) {
-        Some(&resolve::Res::Item(item, _)) => Ty::Udt(path.name.name.clone(), hir::Res::Item(item)),
+        Some(&resolve::Res::Item(item, _)) => {
+            Ty::Udt(path.name.name.clone(), hir::Res::Item(item), args)
+        }
         Some(&resolve::Res::PrimTy(prim)) => Ty::Prim(prim),
         Some(resolve::Res::UnitTy) => Ty::Tuple(Vec::new()),
         // a path should never resolve to a parameter,
@@ -158,7 +183,7 @@ pub(super) fn ast_ty_def_cons(
     let ty = Arrow {
         kind: hir::CallableKind::Function,
         input: Box::new(input),
-        output: Box::new(Ty::Udt(ty_name.clone(), hir::Res::Item(id))),
+        output: Box::new(Ty::Udt(ty_name.clone(), hir::Res::Item(id), Vec::new())),
         functors: FunctorSet::Value(FunctorSetValue::Empty),
     };
     let scheme = Scheme::new(Vec::new(), Box::new(ty));
@@ -231,6 +256,40 @@ pub(super) fn ast_ty_def(names: &Names, def: &TyDef) -> (UdtDef, Vec<TyConversio
     (def, errors)
 }
 
+/// Converts the variants of an enum declaration into HIR variants, each of which is constructed by the
+/// item that its name is bound to.
+pub(super) fn ast_enum_variants(
+    names: &Names,
+    decl: &EnumDecl,
+) -> (Vec<UdtVariant>, Vec<TyConversionError>) {
+    let mut errors = Vec::new();
+    let variants = decl
+        .variants
+        .iter()
+        .map(|variant| {
+            let Some(&resolve::Res::Item(item, _)) = names.get(variant.name.id) else {
+                panic!("variant should have item ID");
+            };
+            let ty = match &variant.ty {
+                Some(ty) => {
+                    let (ty, ty_errors) = ty_from_ast(names, ty, &mut Default::default());
+                    errors.extend(ty_errors);
+                    ty
+                }
+                None => Ty::UNIT,
+            };
+            UdtVariant {
+                span: variant.span,
+                name: variant.name.name.clone(),
+                ty,
+                item: item.item,
+            }
+        })
+        .collect();
+
+    (variants, errors)
+}
+
 /// Given a list of ast type parameters, convert them to HIR type parameters and generate errors if
 /// there are any type errors resulting from this.
 pub(crate) fn type_parameters_for_ast_callable(
@@ -285,6 +344,18 @@ pub(super) fn scheme_for_ast_callable(
     (Scheme::new(type_parameters, Box::new(ty)), errors)
 }
 
+/// The scheme of a constant with the given type, which is that of a function without inputs that
+/// returns the value of the constant.
+pub(super) fn const_scheme(ty: Ty) -> Scheme {
+    let ty = Arrow {
+        kind: hir::CallableKind::Function,
+        input: Box::new(Ty::UNIT),
+        output: Box::new(ty),
+        functors: FunctorSet::Value(FunctorSetValue::Empty),
+    };
+    Scheme::new(Vec::new(), Box::new(ty))
+}
+
 /// Given a [`Ty`], find all arrow types and create type parameters, if necessary, for them.
 /// Recurses into container types to find all arrow types contained within the type.
 pub(crate) fn synthesize_functor_params(
@@ -306,7 +377,7 @@ pub(crate) fn synthesize_functor_params(
             .iter_mut()
             .flat_map(|item| synthesize_functor_params(next_param, item))
             .collect(),
-        Ty::Infer(_) | Ty::Param { .. } | Ty::Prim(_) | Ty::Udt(_, _) | Ty::Err => Vec::new(),
+        Ty::Infer(_) | Ty::Param { .. } | Ty::Prim(_) | Ty::Udt(..) | Ty::Err => Vec::new(),
     }
 }
 
@@ -397,6 +468,22 @@ pub(crate) fn class_constraints_from_ast(
             continue;
         }
         stack.insert(ast_bound.clone());
+        // A declared class shadows a built-in class of the same name. The number of its arguments
+        // is checked against its declaration once all classes have been collected.
+        if let Some(resolve::Res::Item(id, _)) = names.get(ast_bound.name.id) {
+            let mut args = Vec::with_capacity(ast_bound.parameters.len());
+            for param in &ast_bound.parameters {
+                let (arg, arg_errors) = ty_from_ast(names, &param.ty, stack);
+                errors.extend(arg_errors);
+                args.push(arg);
+            }
+            bounds_buf.push(qsc_hir::ty::ClassConstraint::NonNativeClass {
+                id: *id,
+                name: ast_bound.name.name.clone(),
+                args,
+            });
+            continue;
+        }
         if check_param_length(ast_bound, &mut errors) {
             continue;
         };
@@ -419,6 +506,7 @@ pub(crate) fn class_constraints_from_ast(
             "Mod" => Ok(qsc_hir::ty::ClassConstraint::Mod),
             "Div" => Ok(qsc_hir::ty::ClassConstraint::Div),
             "Signed" => Ok(qsc_hir::ty::ClassConstraint::Signed),
+            "Ord" => Ok(qsc_hir::ty::ClassConstraint::Ord),
             "Show" => Ok(qsc_hir::ty::ClassConstraint::Show),
             otherwise => Err(TyConversionError::UnrecognizedClass {
                 span: ast_bound.span(),
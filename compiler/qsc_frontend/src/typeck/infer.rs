@@ -6,8 +6,8 @@ use qsc_data_structures::{index_map::IndexMap, span::Span};
 use qsc_hir::{
     hir::{ItemId, PrimField, Res},
     ty::{
-        Arrow, ClassConstraint, FunctorSet, FunctorSetValue, GenericArg, InferFunctorId, InferTyId,
-        Prim, Scheme, Ty, TypeParameter, Udt,
+        Arrow, ClassConstraint, ClassConstraints, ClassDef, FunctorSet, FunctorSetValue,
+        GenericArg, InferFunctorId, InferTyId, Prim, Scheme, Ty, TypeParameter, Udt, UdtOperator,
     },
 };
 use rustc_hash::{FxHashMap, FxHashSet};
@@ -75,10 +75,21 @@ pub(super) enum Class {
         wrapper: Ty,
         base: Ty,
     },
-    // A user-defined class
-    // When we actually support this, and don't just use it to generate an error,
-    // it should have an ID here instead of a name
-    NonPrimitive(Rc<str>),
+    /// The operand of `?` is an enum whose first variant holds the value, and whose other variants
+    /// are returned as the same variants of the enum that the callable returns.
+    Propagate {
+        wrapper: Ty,
+        value: Ty,
+        ret: Ty,
+    },
+    /// A class declared with a `class` item, with the arguments for its own type parameters. It
+    /// holds when all of its constraints hold and the type implements the methods it declares.
+    User {
+        id: ItemId,
+        name: Rc<str>,
+        ty: Ty,
+        args: Vec<Ty>,
+    },
 }
 
 impl Class {
@@ -95,7 +106,8 @@ impl Class {
             | Self::Ord(ty)
             | Self::Signed(ty)
             | Self::Show(ty)
-            | Self::Struct(ty) => {
+            | Self::Struct(ty)
+            | Self::User { ty, .. } => {
                 vec![ty]
             }
             Self::Call { callee, .. } => vec![callee],
@@ -107,7 +119,7 @@ impl Class {
             } => vec![container, index],
             Self::Iterable { container, .. } => vec![container],
             Self::Unwrap { wrapper, .. } => vec![wrapper],
-            Self::NonPrimitive(_) => Vec::new(),
+            Self::Propagate { wrapper, ret, .. } => vec![wrapper, ret],
         }
     }
 
@@ -174,13 +186,42 @@ impl Class {
                 wrapper: f(wrapper),
                 base: f(base),
             },
-            Self::NonPrimitive(name) => Self::NonPrimitive(name),
+            Self::Propagate {
+                wrapper,
+                value,
+                ret,
+            } => Self::Propagate {
+                wrapper: f(wrapper),
+                value: f(value),
+                ret: f(ret),
+            },
+            Self::User { id, name, ty, args } => Self::User {
+                id,
+                name,
+                ty: f(ty),
+                args: args.into_iter().map(&mut f).collect(),
+            },
         }
     }
 
-    fn check(self, udts: &FxHashMap<ItemId, Udt>, span: Span) -> (Vec<Constraint>, Vec<Error>) {
-        match self {
-            Class::Add(ty) if check_add(&ty) => (Vec::new(), Vec::new()),
+    #[allow(clippy::too_many_lines)]
+    fn check(
+        self,
+        udts: &FxHashMap<ItemId, Udt>,
+        classes: &FxHashMap<ItemId, ClassDef>,
+        span: Span,
+    ) -> (Vec<Constraint>, Vec<Error>) {
+        // The bounds of type parameters are checked in terms of the built-in classes, so any
+        // user-defined classes among them are replaced by the classes they stand for.
+        let class = self.map(|ty| expand_param_bounds(classes, ty));
+        match class {
+            Class::Add(ty)
+                if check_add(&ty)
+                    || is_quantity(udts, &ty)
+                    || has_operator(udts, &ty, UdtOperator::Plus) =>
+            {
+                (Vec::new(), Vec::new())
+            }
             Class::Add(ty) => (
                 Vec::new(),
                 vec![Error(ErrorKind::MissingClassAdd(ty.display(), span))],
@@ -192,6 +233,11 @@ impl Class {
                 output,
             } => check_call(callee, &input, output, span),
             Class::Ctl { op, with_ctls } => check_ctl(op, with_ctls, span),
+            Class::Eq(ty)
+                if is_quantity(udts, &ty) || has_operator(udts, &ty, UdtOperator::Compare) =>
+            {
+                (Vec::new(), Vec::new())
+            }
             Class::Eq(ty) => check_eq(ty, span),
             Class::Exp { base, power } => check_exp(base, power, span),
             Class::HasField { record, name, item } => {
@@ -214,27 +260,49 @@ impl Class {
                 vec![Error(ErrorKind::MissingClassInteger(ty.display(), span))],
             ),
             Class::Iterable { container, item } => check_iterable(container, item, span),
-            Class::Sub(ty) if check_sub(&ty) => (Vec::new(), Vec::new()),
+            Class::Sub(ty)
+                if check_sub(&ty)
+                    || is_quantity(udts, &ty)
+                    || has_operator(udts, &ty, UdtOperator::Minus) =>
+            {
+                (Vec::new(), Vec::new())
+            }
             Class::Sub(ty) => (
                 Vec::new(),
                 vec![Error(ErrorKind::MissingClassSub(ty.display(), span))],
             ),
-            Class::Mul(ty) if check_mul(&ty) => (Vec::new(), Vec::new()),
+            Class::Mul(ty) if check_mul(&ty) || has_operator(udts, &ty, UdtOperator::Times) => {
+                (Vec::new(), Vec::new())
+            }
             Class::Mul(ty) => (
                 Vec::new(),
                 vec![Error(ErrorKind::MissingClassMul(ty.display(), span))],
             ),
-            Class::Div(ty) if check_div(&ty) => (Vec::new(), Vec::new()),
+            Class::Div(ty) if check_div(&ty) || has_operator(udts, &ty, UdtOperator::DividedBy) => {
+                (Vec::new(), Vec::new())
+            }
             Class::Div(ty) => (
                 Vec::new(),
                 vec![Error(ErrorKind::MissingClassDiv(ty.display(), span))],
             ),
-            Class::Ord(ty) if check_ord(&ty) => (Vec::new(), Vec::new()),
+            Class::Ord(ty)
+                if check_ord(&ty)
+                    || is_quantity(udts, &ty)
+                    || has_operator(udts, &ty, UdtOperator::Compare) =>
+            {
+                (Vec::new(), Vec::new())
+            }
             Class::Ord(ty) => (
                 Vec::new(),
                 vec![Error(ErrorKind::MissingClassOrd(ty.display(), span))],
             ),
-            Class::Signed(ty) if check_signed(&ty) => (Vec::new(), Vec::new()),
+            Class::Signed(ty)
+                if check_signed(&ty)
+                    || is_quantity(udts, &ty)
+                    || has_operator(udts, &ty, UdtOperator::Negation) =>
+            {
+                (Vec::new(), Vec::new())
+            }
             Class::Signed(ty) => (
                 Vec::new(),
                 vec![Error(ErrorKind::MissingClassSigned(ty.display(), span))],
@@ -244,13 +312,57 @@ impl Class {
                 Vec::new(),
                 vec![Error(ErrorKind::MissingClassMod(ty.display(), span))],
             ),
+            Class::Show(ty) if is_quantity(udts, &ty) => (Vec::new(), Vec::new()),
             Class::Show(ty) => check_show(ty, span),
             Class::Unwrap { wrapper, base } => check_unwrap(udts, &wrapper, base, span),
-            Class::NonPrimitive(_) => (vec![], vec![]),
+            Class::Propagate {
+                wrapper,
+                value,
+                ret,
+            } => check_propagate(udts, &wrapper, value, &ret, span),
+            Class::User { id, name, ty, args } => {
+                let bound = ClassConstraint::NonNativeClass { id, name, args };
+                let mut constraints = Vec::new();
+                let mut errors = Vec::new();
+                for bound in user_class_constraints(classes, &bound) {
+                    match bound {
+                        ClassConstraint::NonNativeClass { id, name, args } => {
+                            match check_member(udts, &ty, id, &args, span) {
+                                Some(member_constraints) => constraints.extend(member_constraints),
+                                None => errors.push(Error(ErrorKind::MissingClassMember(
+                                    ty.display(),
+                                    name.to_string(),
+                                    span,
+                                ))),
+                            }
+                        }
+                        bound => constraints.push(into_constraint(ty.clone(), &bound, span)),
+                    }
+                }
+                (constraints, errors)
+            }
         }
     }
 }
 
+/// Whether the type is a quantity, which supports the addition, subtraction, negation, comparison and
+/// display of its base type but not its multiplication or division, so that a quantity can only be
+/// scaled or converted explicitly.
+fn is_quantity(udts: &FxHashMap<ItemId, Udt>, ty: &Ty) -> bool {
+    match ty {
+        Ty::Udt(_, Res::Item(id), _) => udts.get(id).is_some_and(|udt| udt.quantity),
+        _ => false,
+    }
+}
+
+/// Whether the type is a user-defined type with a function that implements the given operator.
+fn has_operator(udts: &FxHashMap<ItemId, Udt>, ty: &Ty, op: UdtOperator) -> bool {
+    match ty {
+        Ty::Udt(_, Res::Item(id), _) => udts.get(id).is_some_and(|udt| udt.operator(op).is_some()),
+        _ => false,
+    }
+}
+
 fn check_mod(ty: &Ty) -> bool {
     check_num_constraint(&ClassConstraint::Mod, ty)
 }
@@ -476,20 +588,6 @@ impl Inferrer {
         self.constraints.push_back(Constraint::Class(class, span));
     }
 
-    /// Returns a unique type variable with specified constraints.
-    fn constrained_ty(
-        &mut self,
-        meta: TySource,
-        with_constraints: impl Fn(Ty) -> Box<[Constraint]>,
-    ) -> Ty {
-        let fresh = self.next_ty;
-        self.next_ty = fresh.successor();
-        self.ty_metadata.insert(fresh, meta);
-        let constraints = with_constraints(Ty::Infer(fresh));
-        self.constraints.extend(constraints);
-        Ty::Infer(fresh)
-    }
-
     /// Returns a unique unconstrained type variable.
     pub(super) fn fresh_ty(&mut self, meta: TySource) -> Ty {
         let fresh = self.next_ty;
@@ -507,30 +605,40 @@ impl Inferrer {
 
     /// Instantiates the type scheme.
     pub(super) fn instantiate(&mut self, scheme: &Scheme, span: Span) -> (Arrow, Vec<GenericArg>) {
+        // The bounds of a type parameter may refer to other type parameters, like `'U` in
+        // `'T: Iterable['U]`, so every argument is created before any bound is constrained.
         let args: Vec<_> = scheme
             .params()
             .iter()
             .map(|param| match param {
-                TypeParameter::Ty { bounds, .. } => {
-                    GenericArg::Ty(self.constrained_ty(TySource::not_divergent(span), |ty| {
+                TypeParameter::Ty { .. } => {
+                    GenericArg::Ty(self.fresh_ty(TySource::not_divergent(span)))
+                }
+                TypeParameter::Functor(_) => GenericArg::Functor(self.fresh_functor()),
+            })
+            .collect();
+
+        for (param, arg) in scheme.params().iter().zip(&args) {
+            match (param, arg) {
+                (TypeParameter::Ty { bounds, .. }, GenericArg::Ty(ty)) => {
+                    let bounds = bounds.instantiate(&args);
+                    self.constraints.extend(
                         bounds
                             .0
                             .iter()
-                            .map(|x| into_constraint(ty.clone(), x, span))
-                            .collect()
-                    }))
+                            .map(|bound| into_constraint(ty.clone(), bound, span)),
+                    );
                 }
-                TypeParameter::Functor(expected) => {
-                    let actual = self.fresh_functor();
+                (TypeParameter::Functor(expected), GenericArg::Functor(actual)) => {
                     self.constraints.push_back(Constraint::Superset {
                         expected: *expected,
-                        actual,
+                        actual: *actual,
                         span,
                     });
-                    GenericArg::Functor(actual)
                 }
-            })
-            .collect();
+                _ => unreachable!("generic argument should match the kind of its parameter"),
+            }
+        }
 
         let ty = scheme
             .instantiate(&args)
@@ -540,11 +648,30 @@ impl Inferrer {
     }
 
     /// Solves for all variables given the accumulated constraints.
-    pub(super) fn solve(&mut self, udts: &FxHashMap<ItemId, Udt>) -> Vec<Error> {
-        while let Some(constraint) = self.constraints.pop_front() {
-            for constraint in self.solver.constrain(udts, constraint).into_iter().rev() {
-                self.constraints.push_front(constraint);
+    pub(super) fn solve(
+        &mut self,
+        udts: &FxHashMap<ItemId, Udt>,
+        classes: &FxHashMap<ItemId, ClassDef>,
+    ) -> Vec<Error> {
+        loop {
+            while let Some(constraint) = self.constraints.pop_front() {
+                for constraint in self
+                    .solver
+                    .constrain(udts, classes, constraint)
+                    .into_iter()
+                    .rev()
+                {
+                    self.constraints.push_front(constraint);
+                }
+            }
+
+            // Binding variables to the error type can release pending class constraints, which
+            // may in turn have more types to recover, so keep going until nothing changes.
+            let recovered = self.solver.recover();
+            if recovered.is_empty() {
+                break;
             }
+            self.constraints.extend(recovered);
         }
         let unresolved_ty_errs = self.find_unresolved_types();
         self.solver.default_functors(self.next_functor);
@@ -595,6 +722,10 @@ struct Solver {
     solution: Solution,
     pending_tys: FxHashMap<InferTyId, Vec<Class>>,
     pending_functors: FxHashMap<InferFunctorId, FunctorSetValue>,
+    /// Types involved in an error. Any inference variables in these types that are still unsolved
+    /// once all constraints are solved become the error type, instead of being reported as
+    /// ambiguous.
+    recovery: Vec<(Ty, Span)>,
     errors: Vec<Error>,
 }
 
@@ -604,6 +735,7 @@ impl Solver {
             solution: Solution::default(),
             pending_tys: FxHashMap::default(),
             pending_functors: FxHashMap::default(),
+            recovery: Vec::new(),
             errors: Vec::new(),
         }
     }
@@ -614,10 +746,11 @@ impl Solver {
     fn constrain(
         &mut self,
         udts: &FxHashMap<ItemId, Udt>,
+        classes: &FxHashMap<ItemId, ClassDef>,
         constraint: Constraint,
     ) -> Vec<Constraint> {
         match constraint {
-            Constraint::Class(class, span) => self.class(udts, class, span),
+            Constraint::Class(class, span) => self.class(udts, classes, class, span),
             Constraint::Eq {
                 expected,
                 actual,
@@ -638,17 +771,27 @@ impl Solver {
     fn class(
         &mut self,
         udts: &FxHashMap<ItemId, Udt>,
+        classes: &FxHashMap<ItemId, ClassDef>,
         class: Class,
         span: Span,
     ) -> Vec<Constraint> {
+        // A class can't be checked against an error type, and the error has already been
+        // reported, so whatever the class would have determined is an error too.
+        if class
+            .dependencies()
+            .into_iter()
+            .any(|ty| is_err_ty(&self.solution.tys, ty))
+        {
+            self.defer_recovery(class, span);
+            return Vec::new();
+        }
+
         // true if a dependency of this class constraint is currently unknown, meaning we
         // have to come back to it later.
         // false if we know everything we need to know and this is solved
         let unknown_dependency = class.dependencies().into_iter().any(|ty| {
-            if ty == &Ty::Err {
-                true
-                // if this needs to be inferred further, `unknown_ty` returns `Some(ty_id)`
-            } else if let Some(infer) = unknown_ty(&self.solution.tys, ty) {
+            // if this needs to be inferred further, `unknown_ty` returns `Some(ty_id)`
+            if let Some(infer) = unknown_ty(&self.solution.tys, ty) {
                 self.pending_tys
                     .entry(infer)
                     .or_default()
@@ -662,14 +805,41 @@ impl Solver {
         if unknown_dependency {
             Vec::new()
         } else {
-            let (constraints, mut errors) = class
-                .map(|ty| substituted_ty(&self.solution, ty))
-                .check(udts, span);
+            let class = class.map(|ty| substituted_ty(&self.solution, ty));
+            let (constraints, mut errors) = class.clone().check(udts, classes, span);
+            if !errors.is_empty() {
+                self.defer_recovery(class, span);
+            }
             self.errors.append(&mut errors);
             constraints
         }
     }
 
+    /// Records every type in the class for recovery.
+    fn defer_recovery(&mut self, class: Class, span: Span) {
+        class.map(|ty| {
+            self.recovery.push((ty, span));
+            Ty::Err
+        });
+    }
+
+    /// Binds the unsolved inference variables in the types recorded for recovery to the error
+    /// type, returning the constraints implied by the new bindings.
+    fn recover(&mut self) -> Vec<Constraint> {
+        let mut constraints = Vec::new();
+        for (mut ty, span) in std::mem::take(&mut self.recovery) {
+            substitute_ty(&self.solution, &mut ty);
+            let mut infers = Vec::new();
+            collect_infer_tys(&ty, &mut infers);
+            for infer in infers {
+                if !self.solution.tys.contains_key(infer) {
+                    constraints.append(&mut self.bind_ty(infer, Ty::Err, span));
+                }
+            }
+        }
+        constraints
+    }
+
     fn eq(&mut self, mut expected: Ty, mut actual: Ty, span: Span) -> Vec<Constraint> {
         // Only attempt to unify the types if they are fully substituted. If they are not,
         // this usually indicates an infinite recursion in the type inference, so further
@@ -709,10 +879,13 @@ impl Solver {
 
     fn unify(&mut self, ty1: &Ty, ty2: &Ty, span: Span) -> Vec<Constraint> {
         match (ty1, ty2) {
-            (Ty::Err, _)
-            | (_, Ty::Err)
-            | (Ty::Udt(_, Res::Err), Ty::Udt(_, _))
-            | (Ty::Udt(_, _), Ty::Udt(_, Res::Err)) => Vec::new(),
+            (Ty::Err, ty) | (ty, Ty::Err) => {
+                self.recovery.push((ty.clone(), span));
+                Vec::new()
+            }
+            (Ty::Udt(_, Res::Err, _), Ty::Udt(..)) | (Ty::Udt(..), Ty::Udt(_, Res::Err, _)) => {
+                Vec::new()
+            }
             (Ty::Array(item1), Ty::Array(item2)) => self.unify(item1, item2, span),
             (Ty::Arrow(arrow1), Ty::Arrow(arrow2)) => {
                 if arrow1.kind != arrow2.kind {
@@ -786,6 +959,8 @@ impl Solver {
                         ty2.display(),
                         span,
                     )));
+                    self.recovery.push((ty1.clone(), span));
+                    self.recovery.push((ty2.clone(), span));
                 }
 
                 items1
@@ -794,13 +969,23 @@ impl Solver {
                     .flat_map(|(item1, item2)| self.unify(item1, item2, span))
                     .collect()
             }
-            (Ty::Udt(_, res1), Ty::Udt(_, res2)) if res1 == res2 => Vec::new(),
+            (Ty::Udt(_, res1, args1), Ty::Udt(_, res2, args2))
+                if res1 == res2 && args1.len() == args2.len() =>
+            {
+                args1
+                    .iter()
+                    .zip(args2)
+                    .flat_map(|(arg1, arg2)| self.unify(arg1, arg2, span))
+                    .collect()
+            }
             _ => {
                 self.errors.push(Error(ErrorKind::TyMismatch(
                     ty1.display(),
                     ty2.display(),
                     span,
                 )));
+                self.recovery.push((ty1.clone(), span));
+                self.recovery.push((ty2.clone(), span));
                 Vec::new()
             }
         }
@@ -872,7 +1057,7 @@ fn substitute_ty(solution: &Solution, ty: &mut Ty) -> bool {
             return false;
         }
         match ty {
-            Ty::Err | Ty::Param { .. } | Ty::Prim(_) | Ty::Udt(_, _) => true,
+            Ty::Err | Ty::Param { .. } | Ty::Prim(_) => true,
             Ty::Array(item) => substitute_ty_recursive(solution, item, limit - 1),
             Ty::Arrow(arrow) => {
                 let a = substitute_ty_recursive(solution, &mut arrow.input, limit - 1);
@@ -880,7 +1065,7 @@ fn substitute_ty(solution: &Solution, ty: &mut Ty) -> bool {
                 substitute_functor(solution, &mut arrow.functors);
                 a && b
             }
-            Ty::Tuple(items) => {
+            Ty::Tuple(items) | Ty::Udt(_, _, items) => {
                 let mut all_known = true;
                 for item in items {
                     all_known = substitute_ty_recursive(solution, item, limit - 1) && all_known;
@@ -931,15 +1116,41 @@ fn unknown_ty(solved_types: &IndexMap<InferTyId, Ty>, given_type: &Ty) -> Option
     }
 }
 
+// `true` if `given_type` is the error type or has been solved to the error type
+fn is_err_ty(solved_types: &IndexMap<InferTyId, Ty>, given_type: &Ty) -> bool {
+    match given_type {
+        Ty::Err => true,
+        &Ty::Infer(infer) => solved_types
+            .get(infer)
+            .is_some_and(|solved_type| is_err_ty(solved_types, solved_type)),
+        _ => false,
+    }
+}
+
 fn contains_infer_ty(id: InferTyId, ty: &Ty) -> bool {
     match ty {
-        Ty::Err | Ty::Param { .. } | Ty::Prim(_) | Ty::Udt(_, _) => false,
+        Ty::Err | Ty::Param { .. } | Ty::Prim(_) => false,
         Ty::Array(item) => contains_infer_ty(id, item),
         Ty::Arrow(arrow) => {
             contains_infer_ty(id, &arrow.input) || contains_infer_ty(id, &arrow.output)
         }
         Ty::Infer(other_id) => id == *other_id,
-        Ty::Tuple(items) => items.iter().any(|ty| contains_infer_ty(id, ty)),
+        Ty::Tuple(items) | Ty::Udt(_, _, items) => items.iter().any(|ty| contains_infer_ty(id, ty)),
+    }
+}
+
+fn collect_infer_tys(ty: &Ty, infers: &mut Vec<InferTyId>) {
+    match ty {
+        Ty::Err | Ty::Param { .. } | Ty::Prim(_) => {}
+        Ty::Array(item) => collect_infer_tys(item, infers),
+        Ty::Arrow(arrow) => {
+            collect_infer_tys(&arrow.input, infers);
+            collect_infer_tys(&arrow.output, infers);
+        }
+        &Ty::Infer(infer) => infers.push(infer),
+        Ty::Tuple(items) | Ty::Udt(_, _, items) => {
+            items.iter().for_each(|ty| collect_infer_tys(ty, infers));
+        }
     }
 }
 
@@ -1158,7 +1369,7 @@ fn check_has_field(
             }],
             Vec::new(),
         ),
-        (_, Ty::Udt(_, Res::Item(id))) => {
+        (_, Ty::Udt(_, Res::Item(id), _)) => {
             match udts.get(id).and_then(|udt| udt.field_ty_by_name(&name)) {
                 Some(ty) => (
                     vec![Constraint::Eq {
@@ -1195,7 +1406,7 @@ fn check_has_field(
 
 fn ty_to_udt<'a>(udts: &'a FxHashMap<ItemId, Udt>, record: &Ty) -> Option<&'a Udt> {
     match record {
-        Ty::Udt(_, Res::Item(id)) => udts.get(id),
+        Ty::Udt(_, Res::Item(id), _) => udts.get(id),
         _ => None,
     }
 }
@@ -1398,8 +1609,8 @@ fn check_unwrap(
     base: Ty,
     span: Span,
 ) -> (Vec<Constraint>, Vec<Error>) {
-    if let Ty::Udt(_, Res::Item(id)) = wrapper {
-        if let Some(udt) = udts.get(id) {
+    if let Ty::Udt(_, Res::Item(id), _) = wrapper {
+        if let Some(udt) = udts.get(id).filter(|udt| !udt.is_enum()) {
             return (
                 vec![Constraint::Eq {
                     expected: base,
@@ -1423,6 +1634,170 @@ fn check_unwrap(
     )
 }
 
+fn check_propagate(
+    udts: &FxHashMap<ItemId, Udt>,
+    wrapper: &Ty,
+    value: Ty,
+    ret: &Ty,
+    span: Span,
+) -> (Vec<Constraint>, Vec<Error>) {
+    let Ty::Udt(_, Res::Item(id), args) = wrapper else {
+        return (
+            Vec::new(),
+            vec![Error(ErrorKind::MissingClassPropagate(
+                wrapper.display(),
+                span,
+            ))],
+        );
+    };
+    let Some(udt) = udts
+        .get(id)
+        .filter(|udt| udt.variants.len() > 1 && udt.generics.len() == args.len())
+    else {
+        return (
+            Vec::new(),
+            vec![Error(ErrorKind::MissingClassPropagate(
+                wrapper.display(),
+                span,
+            ))],
+        );
+    };
+    let ret_args = match ret {
+        Ty::Udt(_, Res::Item(ret_id), ret_args) if ret_id == id && ret_args.len() == args.len() => {
+            ret_args
+        }
+        _ => {
+            return (
+                Vec::new(),
+                vec![Error(ErrorKind::PropagateMismatch(
+                    wrapper.display(),
+                    ret.display(),
+                    span,
+                ))],
+            )
+        }
+    };
+
+    let mut constraints = vec![Constraint::Eq {
+        expected: value,
+        actual: udt.variant_ty(0, id.package, args),
+        span,
+    }];
+    constraints.extend((1..udt.variants.len()).map(|index| Constraint::Eq {
+        expected: udt.variant_ty(index, id.package, ret_args),
+        actual: udt.variant_ty(index, id.package, args),
+        span,
+    }));
+    (constraints, Vec::new())
+}
+
+/// The constraints that a user-defined class stands for, following the classes it is declared in
+/// terms of and substituting the arguments of each class for its type parameters. A class with
+/// methods is kept along with its constraints, since a member must also implement the methods. A
+/// class that is reached again with the same arguments through a cycle adds nothing further.
+fn user_class_constraints(
+    classes: &FxHashMap<ItemId, ClassDef>,
+    bound: &ClassConstraint,
+) -> Vec<ClassConstraint> {
+    fn collect(
+        classes: &FxHashMap<ItemId, ClassDef>,
+        bound: &ClassConstraint,
+        visited: &mut Vec<ClassConstraint>,
+        constraints: &mut Vec<ClassConstraint>,
+    ) {
+        // A class can name itself with ever larger arguments, so the expansion is cut off.
+        const MAX_DEPTH: usize = 100;
+        let ClassConstraint::NonNativeClass { id, args, .. } = bound else {
+            if !constraints.contains(bound) {
+                constraints.push(bound.clone());
+            }
+            return;
+        };
+        if visited.contains(bound) || visited.len() >= MAX_DEPTH {
+            return;
+        }
+        visited.push(bound.clone());
+        let Some(class) = classes.get(id) else {
+            return;
+        };
+        if !class.methods.is_empty() && !constraints.contains(bound) {
+            constraints.push(bound.clone());
+        }
+        let args: Vec<_> = args.iter().cloned().map(GenericArg::Ty).collect();
+        for constraint in class.constraints.0.iter() {
+            collect(
+                classes,
+                &constraint.instantiate(&args),
+                visited,
+                constraints,
+            );
+        }
+    }
+
+    let mut constraints = Vec::new();
+    collect(classes, bound, &mut Vec::new(), &mut constraints);
+    constraints
+}
+
+/// The constraints on the class arguments under which the type is a member of the class with
+/// methods with the given ID, or [None] if it is not a member. A type parameter is a member of the
+/// classes it is bounded by, and a user-defined type of the classes whose methods it implements.
+fn check_member(
+    udts: &FxHashMap<ItemId, Udt>,
+    ty: &Ty,
+    class: ItemId,
+    args: &[Ty],
+    span: Span,
+) -> Option<Vec<Constraint>> {
+    let member_args = match ty {
+        Ty::Err => return Some(Vec::new()),
+        Ty::Param { bounds, .. } => bounds.0.iter().find_map(|bound| match bound {
+            ClassConstraint::NonNativeClass { id, args, .. } if *id == class => Some(args.clone()),
+            _ => None,
+        })?,
+        Ty::Udt(_, Res::Item(id), _) => udts.get(id)?.instance(id.package, class)?.args,
+        _ => return None,
+    };
+    Some(
+        args.iter()
+            .zip(member_args)
+            .map(|(expected, actual)| Constraint::Eq {
+                expected: expected.clone(),
+                actual,
+                span,
+            })
+            .collect(),
+    )
+}
+
+/// Replaces the user-defined classes in the bounds of a type parameter by the constraints they
+/// stand for.
+fn expand_param_bounds(classes: &FxHashMap<ItemId, ClassDef>, ty: Ty) -> Ty {
+    match ty {
+        Ty::Param { name, id, bounds }
+            if bounds
+                .0
+                .iter()
+                .any(|bound| matches!(bound, ClassConstraint::NonNativeClass { .. })) =>
+        {
+            let mut expanded = Vec::new();
+            for bound in &bounds.0 {
+                for constraint in user_class_constraints(classes, bound) {
+                    if !expanded.contains(&constraint) {
+                        expanded.push(constraint);
+                    }
+                }
+            }
+            Ty::Param {
+                name,
+                id,
+                bounds: ClassConstraints(expanded.into_boxed_slice()),
+            }
+        }
+        ty => ty,
+    }
+}
+
 /// Given an HIR class constraint, produce an actual type system constraint.
 fn into_constraint(ty: Ty, bound: &ClassConstraint, span: Span) -> Constraint {
     match bound {
@@ -1446,9 +1821,15 @@ fn into_constraint(ty: Ty, bound: &ClassConstraint, span: Span) -> Constraint {
             },
             span,
         ),
-        ClassConstraint::NonNativeClass(name) => {
-            Constraint::Class(Class::NonPrimitive(name.clone()), span)
-        }
+        ClassConstraint::NonNativeClass { id, name, args } => Constraint::Class(
+            Class::User {
+                id: *id,
+                name: name.clone(),
+                ty,
+                args: args.clone(),
+            },
+            span,
+        ),
         ClassConstraint::Show => Constraint::Class(Class::Show(ty), span),
         ClassConstraint::Integral => Constraint::Class(Class::Integral(ty), span),
         ClassConstraint::Ord => Constraint::Class(Class::Ord(ty), span),
@@ -0,0 +1,228 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! Checks the lengths of the arrays passed to parameters with a sized array type, like `qs : Qubit[3]`.
+//!
+//! This is a check on the AST at call sites, not part of the type system: the length is erased when the
+//! type is converted, so a sized array is an ordinary array in HIR, and later passes such as RCA and
+//! partial evaluation do not see it. As a result, the check is limited to what is visible here:
+//! - Only arguments whose length is known at compile time are checked: array literals, array repeats
+//!   and qubit arrays with a literal size, and the immutable variables and sized parameters bound to
+//!   them. Any other argument, such as a mutable variable or the result of a call, is not checked.
+//! - Only calls to callables of the package being compiled, including earlier fragments of an
+//!   incremental compilation, are checked, since the lengths of the parameters of callables from other
+//!   packages are not kept.
+//! - Since the length is not carried by the type, a sized array type anywhere other than the type of a
+//!   parameter, such as in a return type or in the type of a callable parameter, is reported as an error.
+
+use super::{Error, ErrorKind};
+use crate::resolve::{Names, Res};
+use qsc_ast::{
+    ast::{
+        self, ExprKind, Lit, Mutability, NodeId, PatKind, PathKind, QubitInitKind, StmtKind, TyKind,
+    },
+    visit::{self, Visitor},
+};
+use qsc_data_structures::fx::{FxHashMap, FxHashSet};
+use qsc_hir::hir::ItemId;
+
+/// The array lengths known to the type checker, kept across the fragments of an incremental compilation.
+#[derive(Debug, Default)]
+pub(super) struct Lengths {
+    /// The lengths of the parameters of each callable with at least one sized array parameter.
+    params: FxHashMap<ItemId, Vec<Option<usize>>>,
+    /// The lengths of the local variables bound to arrays of a known length.
+    locals: FxHashMap<NodeId, usize>,
+}
+
+impl Lengths {
+    pub(super) fn add_callable(&mut self, item: ItemId, decl: &ast::CallableDecl) {
+        let params = top_level_pats(&decl.input)
+            .into_iter()
+            .map(pat_len)
+            .collect::<Vec<_>>();
+        if params.iter().any(Option::is_some) {
+            self.params.insert(item, params);
+        }
+    }
+}
+
+pub(super) struct LengthChecker<'a> {
+    names: &'a Names,
+    lengths: &'a mut Lengths,
+    errors: &'a mut Vec<Error>,
+    /// The sized array types that declare the types of parameters, which are the only ones allowed.
+    params: FxHashSet<NodeId>,
+}
+
+impl<'a> LengthChecker<'a> {
+    pub(super) fn new(
+        names: &'a Names,
+        lengths: &'a mut Lengths,
+        errors: &'a mut Vec<Error>,
+    ) -> Self {
+        Self {
+            names,
+            lengths,
+            errors,
+            params: FxHashSet::default(),
+        }
+    }
+
+    fn bind(&mut self, pat: &ast::Pat, len: usize) {
+        match &*pat.kind {
+            PatKind::Bind(name, _) => {
+                self.lengths.locals.insert(name.id, len);
+            }
+            PatKind::Paren(pat) => self.bind(pat, len),
+            _ => {}
+        }
+    }
+
+    fn check_call(&mut self, callee: &ast::Expr, input: &ast::Expr) {
+        let ExprKind::Path(PathKind::Ok(path)) = &*callee.kind else {
+            return;
+        };
+        let Some(Res::Item(item, _)) = self.names.get(path.id) else {
+            return;
+        };
+        let Some(params) = self.lengths.params.get(item) else {
+            return;
+        };
+
+        let args = top_level_exprs(input);
+        if args.len() != params.len() {
+            // The arity mismatch is reported as a type error.
+            return;
+        }
+        for (arg, &expected) in args.into_iter().zip(params) {
+            if let (Some(expected), Some(found)) = (expected, self.expr_len(arg)) {
+                if expected != found {
+                    self.errors.push(Error(ErrorKind::ArrayLengthMismatch(
+                        expected, found, arg.span,
+                    )));
+                }
+            }
+        }
+    }
+
+    /// The length of the array an expression evaluates to, if it is known at compile time.
+    fn expr_len(&self, expr: &ast::Expr) -> Option<usize> {
+        match &*expr.kind {
+            ExprKind::Array(items) => Some(items.len()),
+            ExprKind::ArrayRepeat(_, size) => lit_len(size),
+            ExprKind::Paren(expr) => self.expr_len(expr),
+            ExprKind::Path(PathKind::Ok(path)) if path.segments.is_none() => {
+                match self.names.get(path.id) {
+                    Some(Res::Local(node)) => self.lengths.locals.get(node).copied(),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Visitor<'_> for LengthChecker<'_> {
+    fn visit_callable_decl(&mut self, decl: &ast::CallableDecl) {
+        for pat in top_level_pats(&decl.input) {
+            if let (PatKind::Bind(name, _), Some(len)) = (&*pat.kind, pat_len(pat)) {
+                self.lengths.locals.insert(name.id, len);
+            }
+            if let Some(ty) = pat_sized_ty(pat) {
+                self.params.insert(ty.id);
+            }
+        }
+        visit::walk_callable_decl(self, decl);
+    }
+
+    fn visit_ty(&mut self, ty: &ast::Ty) {
+        if matches!(&*ty.kind, TyKind::SizedArray(..)) && !self.params.contains(&ty.id) {
+            self.errors
+                .push(Error(ErrorKind::MisplacedSizedArray(ty.span)));
+        }
+        visit::walk_ty(self, ty);
+    }
+
+    fn visit_stmt(&mut self, stmt: &ast::Stmt) {
+        match &*stmt.kind {
+            StmtKind::Local(Mutability::Immutable, pat, expr) => {
+                if let Some(len) = self.expr_len(expr) {
+                    self.bind(pat, len);
+                }
+            }
+            StmtKind::Qubit(_, pat, init, _) => {
+                if let QubitInitKind::Array(size) = &*init.kind {
+                    if let Some(len) = lit_len(size) {
+                        self.bind(pat, len);
+                    }
+                }
+            }
+            _ => {}
+        }
+        visit::walk_stmt(self, stmt);
+    }
+
+    fn visit_expr(&mut self, expr: &ast::Expr) {
+        if let ExprKind::Call(callee, input) = &*expr.kind {
+            self.check_call(callee, input);
+        }
+        visit::walk_expr(self, expr);
+    }
+
+    fn visit_attr(&mut self, _: &ast::Attr) {}
+}
+
+/// The parameters of a callable, which are the items of its input tuple.
+fn top_level_pats(input: &ast::Pat) -> Vec<&ast::Pat> {
+    match &*input.kind {
+        PatKind::Tuple(pats) => pats.iter().map(AsRef::as_ref).collect(),
+        PatKind::Paren(pat) => vec![pat.as_ref()],
+        _ => vec![input],
+    }
+}
+
+/// The arguments of a call, which are the items of its input tuple.
+fn top_level_exprs(input: &ast::Expr) -> Vec<&ast::Expr> {
+    match &*input.kind {
+        ExprKind::Tuple(exprs) => exprs.iter().map(AsRef::as_ref).collect(),
+        ExprKind::Paren(expr) => vec![expr.as_ref()],
+        _ => vec![input],
+    }
+}
+
+/// The length declared by the type annotation of a parameter, if it is a sized array.
+fn pat_len(pat: &ast::Pat) -> Option<usize> {
+    pat_sized_ty(pat).and_then(|ty| match &*ty.kind {
+        TyKind::SizedArray(_, len) => Some(*len),
+        _ => None,
+    })
+}
+
+/// The type annotation of a parameter, if it is a sized array.
+fn pat_sized_ty(pat: &ast::Pat) -> Option<&ast::Ty> {
+    match &*pat.kind {
+        PatKind::Bind(_, Some(ty)) => sized_ty(ty),
+        PatKind::Paren(pat) => pat_sized_ty(pat),
+        _ => None,
+    }
+}
+
+fn sized_ty(ty: &ast::Ty) -> Option<&ast::Ty> {
+    match &*ty.kind {
+        TyKind::SizedArray(..) => Some(ty),
+        TyKind::Paren(ty) => sized_ty(ty),
+        _ => None,
+    }
+}
+
+fn lit_len(expr: &ast::Expr) -> Option<usize> {
+    match &*expr.kind {
+        ExprKind::Lit(lit) => match lit.as_ref() {
+            Lit::Int(n) => usize::try_from(*n).ok(),
+            _ => None,
+        },
+        ExprKind::Paren(expr) => lit_len(expr),
+        _ => None,
+    }
+}
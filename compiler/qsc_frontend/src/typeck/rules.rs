@@ -11,8 +11,8 @@ use super::{
 };
 use crate::resolve::{self, Names, Res};
 use qsc_ast::ast::{
-    self, BinOp, Block, Expr, ExprKind, FieldAccess, Functor, Ident, Idents, Lit, NodeId, Pat,
-    PatKind, Path, PathKind, QubitInit, QubitInitKind, Spec, Stmt, StmtKind, StringComponent,
+    self, BinOp, Block, Expr, ExprKind, FieldAccess, Functor, Ident, Idents, Lit, MatchPat, NodeId,
+    Pat, PatKind, Path, PathKind, QubitInit, QubitInitKind, Spec, Stmt, StmtKind, StringComponent,
     TernOp, TyKind, TypeParameter, UnOp,
 };
 use qsc_data_structures::span::Span;
@@ -100,7 +100,10 @@ impl<'a> Context<'a> {
 
     fn infer_ty(&mut self, ty: &ast::Ty) -> Ty {
         match &*ty.kind {
-            TyKind::Array(item) => Ty::Array(Box::new(self.infer_ty(item))),
+            // The length of a sized array is checked separately, since it isn't part of the type.
+            TyKind::Array(item) | TyKind::SizedArray(item, _) => {
+                Ty::Array(Box::new(self.infer_ty(item)))
+            }
             TyKind::Arrow(kind, input, output, functors) => Ty::Arrow(Box::new(Arrow {
                 kind: convert::callable_kind_from_ast(*kind),
                 input: Box::new(self.infer_ty(input)),
@@ -114,7 +117,9 @@ impl<'a> Context<'a> {
             TyKind::Hole => self.inferrer.fresh_ty(TySource::not_divergent(ty.span)),
             TyKind::Paren(inner) => self.infer_ty(inner),
             TyKind::Path(PathKind::Ok(path)) => match self.names.get(path.id) {
-                Some(&Res::Item(item, _)) => Ty::Udt(path.name.name.clone(), hir::Res::Item(item)),
+                Some(&Res::Item(item, _)) => {
+                    Ty::Udt(path.name.name.clone(), hir::Res::Item(item), Vec::new())
+                }
                 Some(&Res::PrimTy(prim)) => Ty::Prim(prim),
                 Some(Res::UnitTy) => Ty::Tuple(Vec::new()),
                 None => Ty::Err,
@@ -137,14 +142,12 @@ impl<'a> Context<'a> {
                 ty, constraints: _, ..
             }) => match self.names.get(ty.id) {
                 Some(Res::Param { id, bounds }) => {
-                    let (bounds, errs) = convert::class_constraints_from_ast(
+                    // Errors in the bounds are reported where the parameter is declared.
+                    let (bounds, _) = convert::class_constraints_from_ast(
                         self.names,
                         bounds,
                         &mut Default::default(),
                     );
-                    for err in errs {
-                        self.inferrer.report_error(err);
-                    }
                     Ty::Param {
                         name: ty.name.clone(),
                         id: *id,
@@ -157,10 +160,16 @@ impl<'a> Context<'a> {
                     is syntactic differentiation"
                 ),
             },
+            TyKind::App(PathKind::Ok(path), args) => {
+                let args = args.iter().map(|arg| self.infer_ty(arg)).collect();
+                convert::ty_from_app(self.names, path, args)
+            }
             TyKind::Tuple(items) => {
                 Ty::Tuple(items.iter().map(|item| self.infer_ty(item)).collect())
             }
-            TyKind::Err | TyKind::Path(PathKind::Err { .. }) => Ty::Err,
+            TyKind::Err
+            | TyKind::Path(PathKind::Err { .. })
+            | TyKind::App(PathKind::Err { .. }, _) => Ty::Err,
         }
     }
 
@@ -376,7 +385,7 @@ impl<'a> Context<'a> {
                 let mut diverges = false;
                 for component in components {
                     match component {
-                        StringComponent::Expr(expr) => {
+                        StringComponent::Expr(expr, _) => {
                             let span = expr.span;
                             let expr = self.infer_expr(expr.as_ref());
                             self.inferrer.class(span, Class::Show(expr.ty));
@@ -421,6 +430,56 @@ impl<'a> Context<'a> {
                 Lit::Result(_) => converge(Ty::Prim(Prim::Result)),
                 Lit::String(_) => converge(Ty::Prim(Prim::String)),
             },
+            ExprKind::Match(expr, arms) => {
+                let expr = self.infer_expr(expr);
+                let mut result: Option<Partial<Ty>> = None;
+                for arm in arms {
+                    match &*arm.pat {
+                        MatchPat::Lit(lit) => {
+                            let lit_span = lit.span;
+                            let lit = self.infer_expr(lit);
+                            self.inferrer.eq(lit_span, expr.ty.clone(), lit.ty);
+                        }
+                        MatchPat::Variant(path, pat) => {
+                            let pat_ty = self.infer_pat(pat);
+                            match self.variant_scheme(path) {
+                                Some(scheme) => {
+                                    let (arrow, _) = self.inferrer.instantiate(&scheme, path.span);
+                                    self.inferrer.eq(path.span, expr.ty.clone(), *arrow.output);
+                                    self.inferrer.eq(pat.span, *arrow.input, pat_ty);
+                                }
+                                // A path that doesn't resolve is already reported by the resolver.
+                                None if self.names.get(path.id).is_some() => {
+                                    self.inferrer.report_error(Error(
+                                        super::ErrorKind::NotAVariant(
+                                            path.name.name.to_string(),
+                                            path.span,
+                                        ),
+                                    ));
+                                }
+                                None => {}
+                            }
+                        }
+                        MatchPat::Bind(pat) => {
+                            let pat_ty = self.infer_pat(pat);
+                            self.inferrer.eq(pat.span, expr.ty.clone(), pat_ty);
+                        }
+                    }
+                    let body_span = arm.body.span;
+                    let body = self.infer_expr(&arm.body);
+                    result = Some(match result {
+                        None => body,
+                        Some(result) => {
+                            self.inferrer.eq(body_span, result.ty.clone(), body.ty);
+                            Partial {
+                                diverges: result.diverges && body.diverges,
+                                ..result
+                            }
+                        }
+                    });
+                }
+                self.diverge_if(expr.diverges, result.unwrap_or(converge(Ty::UNIT)))
+            }
             ExprKind::Paren(expr) => self.infer_expr(expr),
             ExprKind::Path(path) => self.infer_path_kind(expr, path),
             ExprKind::Range(start, step, end) => {
@@ -480,7 +539,7 @@ impl<'a> Context<'a> {
 
                 // If the container is not a struct type, assign type Err and don't continue to process the fields.
                 match &container {
-                    Ty::Udt(_, hir::Res::Item(item_id)) => match self.table.udts.get(item_id) {
+                    Ty::Udt(_, hir::Res::Item(item_id), _) => match self.table.udts.get(item_id) {
                         Some(udt) if udt.is_struct() => {}
                         _ => return converge(Ty::Err),
                     },
@@ -546,7 +605,7 @@ impl<'a> Context<'a> {
                 }
                 self.diverge_if(diverges, converge(Ty::Tuple(tys)))
             }
-            ExprKind::UnOp(op, expr) => self.infer_unop(*op, expr),
+            ExprKind::UnOp(op, operand) => self.infer_unop(expr.id, *op, operand),
             ExprKind::While(cond, body) => {
                 let cond_span = cond.span;
                 let cond = self.infer_expr(cond);
@@ -622,7 +681,7 @@ impl<'a> Context<'a> {
                     };
                     let (ty, args) = self.inferrer.instantiate(scheme, expr.span);
                     self.table.generics.insert(expr.id, args);
-                    converge(Ty::Arrow(Box::new(ty)))
+                    converge(self.item_ty(*item, ty))
                 }
                 Some(&Res::Local(node)) => converge(
                     self.table
@@ -636,7 +695,7 @@ impl<'a> Context<'a> {
                     let item_scheme = self.globals.get(item).expect("item should have scheme");
                     let (ty, args) = self.inferrer.instantiate(item_scheme, expr.span);
                     self.table.generics.insert(expr.id, args);
-                    converge(Ty::Arrow(Box::new(ty)))
+                    converge(self.item_ty(*item, ty))
                 }
                 Some(Res::PrimTy(_) | Res::UnitTy | Res::Param { .. }) => {
                     panic!("expression should not resolve to type reference")
@@ -645,6 +704,32 @@ impl<'a> Context<'a> {
         }
     }
 
+    /// The type of a path to an item with the given instantiated signature. A path to a constant is
+    /// its value, while a path to any other item is a callable.
+    fn item_ty(&self, item: ItemId, ty: Arrow) -> Ty {
+        if self.table.consts.contains(&item) {
+            *ty.output
+        } else {
+            Ty::Arrow(Box::new(ty))
+        }
+    }
+
+    /// The type scheme of the enum variant that a path in a match arm refers to, if it refers to one.
+    fn variant_scheme(&self, path: &Path) -> Option<Scheme> {
+        let (Res::Item(item, _) | Res::ExportedItem(item, _)) = self.names.get(path.id)? else {
+            return None;
+        };
+        let is_variant =
+            self.table.udts.iter().any(|(id, udt)| {
+                id.package == item.package && udt.variant_index(item.item).is_some()
+            });
+        if is_variant {
+            self.globals.get(item).cloned()
+        } else {
+            None
+        }
+    }
+
     fn infer_path_as_field_access(
         &mut self,
         path: &impl Idents,
@@ -704,7 +789,7 @@ impl<'a> Context<'a> {
         }
     }
 
-    fn infer_unop(&mut self, op: UnOp, operand: &Expr) -> Partial<Ty> {
+    fn infer_unop(&mut self, id: NodeId, op: UnOp, operand: &Expr) -> Partial<Ty> {
         let span = operand.span;
         let operand = self.infer_expr(operand);
         let diverges = operand.diverges;
@@ -749,6 +834,28 @@ impl<'a> Context<'a> {
                 );
                 converge(base)
             }
+            UnOp::Propagate => {
+                let Some(return_ty) = self.return_ty.clone() else {
+                    self.inferrer
+                        .report_error(Error(super::ErrorKind::PropagateOutsideCallable(span)));
+                    return self.diverge_if(diverges, converge(Ty::Err));
+                };
+                let value = self.inferrer.fresh_ty(TySource::not_divergent(span));
+                self.inferrer.class(
+                    span,
+                    Class::Propagate {
+                        wrapper: operand.ty,
+                        value: value.clone(),
+                        ret: return_ty.clone(),
+                    },
+                );
+                // The lowerer constructs the returned variants with the arguments of the enum that
+                // the callable returns.
+                self.table
+                    .generics
+                    .insert(id, vec![GenericArg::Ty(return_ty)]);
+                converge(value)
+            }
         };
 
         self.diverge_if(diverges, ty)
@@ -972,7 +1079,7 @@ impl<'a> Context<'a> {
     }
 
     pub(crate) fn solve(self) -> Vec<Error> {
-        let mut errs = self.inferrer.solve(&self.table.udts);
+        let mut errs = self.inferrer.solve(&self.table.udts, &self.table.classes);
 
         for id in self.new {
             let ty = self.table.terms.get_mut(id).expect("node should have type");
@@ -1033,6 +1140,20 @@ pub(super) fn expr(
     context.solve()
 }
 
+pub(super) fn const_decl(
+    names: &Names,
+    globals: &FxHashMap<ItemId, Scheme>,
+    table: &mut Table,
+    ty: Ty,
+    value: &Expr,
+) -> Vec<Error> {
+    let mut inferrer = Inferrer::new();
+    let mut context = Context::new(names, globals, table, &mut inferrer, Vec::new());
+    let actual = context.infer_expr(value).ty;
+    context.inferrer.eq(value.span, ty, actual);
+    context.solve()
+}
+
 pub(super) fn stmt(
     names: &Names,
     globals: &FxHashMap<ItemId, Scheme>,
@@ -451,14 +451,13 @@ fn length_type_error() {
             #8 44-55 "(xs : 'T[])" : ?
             #9 45-54 "xs : 'T[]" : ?
             #18 84-101 "Length((1, 2, 3))" : Int
-            #19 84-90 "Length" : (?0[] -> Int)
+            #19 84-90 "Length" : (?[] -> Int)
             #22 90-101 "((1, 2, 3))" : (Int, Int, Int)
             #23 91-100 "(1, 2, 3)" : (Int, Int, Int)
             #24 92-93 "1" : Int
             #25 95-96 "2" : Int
             #26 98-99 "3" : Int
             Error(Type(Error(TyMismatch("?[]", "(Int, Int, Int)", Span { lo: 84, hi: 101 }))))
-            Error(Type(Error(AmbiguousTy(Span { lo: 84, hi: 90 }))))
         "##]],
     );
 }
@@ -499,14 +498,13 @@ fn array_index_error() {
         "",
         "[1, 2, 3][false]",
         &expect![[r#"
-            #1 0-16 "[1, 2, 3][false]" : ?0
+            #1 0-16 "[1, 2, 3][false]" : ?
             #2 0-9 "[1, 2, 3]" : Int[]
             #3 1-2 "1" : Int
             #4 4-5 "2" : Int
             #5 7-8 "3" : Int
             #6 10-15 "false" : Bool
             Error(Type(Error(MissingClassHasIndex("Int[]", "Bool", Span { lo: 0, hi: 16 }))))
-            Error(Type(Error(AmbiguousTy(Span { lo: 0, hi: 16 }))))
         "#]],
     );
 }
@@ -807,15 +805,14 @@ fn let_tuple_arity_error() {
         &expect![[r#"
             #1 0-27 "{ let (x, y, z) = (0, 1); }" : Unit
             #2 0-27 "{ let (x, y, z) = (0, 1); }" : Unit
-            #4 6-15 "(x, y, z)" : (Int, Int, ?2)
+            #4 6-15 "(x, y, z)" : (Int, Int, ?)
             #5 7-8 "x" : Int
             #7 10-11 "y" : Int
-            #9 13-14 "z" : ?2
+            #9 13-14 "z" : ?
             #11 18-24 "(0, 1)" : (Int, Int)
             #12 19-20 "0" : Int
             #13 22-23 "1" : Int
             Error(Type(Error(TyMismatch("(?, ?, ?)", "(Int, Int)", Span { lo: 18, hi: 24 }))))
-            Error(Type(Error(AmbiguousTy(Span { lo: 13, hi: 14 }))))
         "#]],
     );
 }
@@ -898,14 +895,13 @@ fn for_loop_not_iterable() {
         "for i in (1, true, One) {}",
         &expect![[r#"
             #1 0-26 "for i in (1, true, One) {}" : Unit
-            #2 4-5 "i" : ?0
+            #2 4-5 "i" : ?
             #4 9-23 "(1, true, One)" : (Int, Bool, Result)
             #5 10-11 "1" : Int
             #6 13-17 "true" : Bool
             #7 19-22 "One" : Result
             #8 24-26 "{}" : Unit
             Error(Type(Error(MissingClassIterable("(Int, Bool, Result)", Span { lo: 9, hi: 23 }))))
-            Error(Type(Error(AmbiguousTy(Span { lo: 4, hi: 5 }))))
         "#]],
     );
 }
@@ -2032,13 +2028,12 @@ fn range_to_field_start() {
         "",
         "(...2..8)::Start",
         &expect![[r#"
-            #1 0-16 "(...2..8)::Start" : ?0
+            #1 0-16 "(...2..8)::Start" : ?
             #2 0-9 "(...2..8)" : RangeTo
             #3 1-8 "...2..8" : RangeTo
             #4 4-5 "2" : Int
             #5 7-8 "8" : Int
             Error(Type(Error(MissingClassHasField("RangeTo", "Start", Span { lo: 0, hi: 16 }))))
-            Error(Type(Error(AmbiguousTy(Span { lo: 0, hi: 16 }))))
         "#]],
     );
 }
@@ -2109,13 +2104,12 @@ fn range_from_field_end() {
         "",
         "(0..2...)::End",
         &expect![[r#"
-            #1 0-14 "(0..2...)::End" : ?0
+            #1 0-14 "(0..2...)::End" : ?
             #2 0-9 "(0..2...)" : RangeFrom
             #3 1-8 "0..2..." : RangeFrom
             #4 1-2 "0" : Int
             #5 4-5 "2" : Int
             Error(Type(Error(MissingClassHasField("RangeFrom", "End", Span { lo: 0, hi: 14 }))))
-            Error(Type(Error(AmbiguousTy(Span { lo: 0, hi: 14 }))))
         "#]],
     );
 }
@@ -2126,10 +2120,9 @@ fn range_full_field_start() {
         "",
         "...::Start",
         &expect![[r#"
-            #1 0-10 "...::Start" : ?0
+            #1 0-10 "...::Start" : ?
             #2 0-3 "..." : RangeFull
             Error(Type(Error(MissingClassHasField("RangeFull", "Start", Span { lo: 0, hi: 10 }))))
-            Error(Type(Error(AmbiguousTy(Span { lo: 0, hi: 10 }))))
         "#]],
     );
 }
@@ -2166,10 +2159,9 @@ fn range_full_field_end() {
         "",
         "...::End",
         &expect![[r#"
-            #1 0-8 "...::End" : ?0
+            #1 0-8 "...::End" : ?
             #2 0-3 "..." : RangeFull
             Error(Type(Error(MissingClassHasField("RangeFull", "End", Span { lo: 0, hi: 8 }))))
-            Error(Type(Error(AmbiguousTy(Span { lo: 0, hi: 8 }))))
         "#]],
     );
 }
@@ -2799,11 +2791,10 @@ fn newtype_field_invalid() {
             #13 59-68 "(x : Foo)" : UDT<"Foo": Item 1>
             #14 60-67 "x : Foo" : UDT<"Foo": Item 1>
             #20 74-106 "{\n        let y = x::Nope;\n    }" : Unit
-            #22 88-89 "y" : ?1
-            #24 92-99 "x::Nope" : ?1
+            #22 88-89 "y" : ?
+            #24 92-99 "x::Nope" : ?
             #25 92-93 "x" : UDT<"Foo": Item 1>
             Error(Type(Error(MissingClassHasField("Foo", "Nope", Span { lo: 92, hi: 99 }))))
-            Error(Type(Error(AmbiguousTy(Span { lo: 92, hi: 99 }))))
         "#]],
     );
 }
@@ -2854,15 +2845,13 @@ fn struct_field_path_invalid() {
             #30 102-109 "(x : A)" : UDT<"A": Item 1>
             #31 103-108 "x : A" : UDT<"A": Item 1>
             #39 117-152 "{\n        let y = x.b.Nope.i;\n    }" : Unit
-            #41 131-132 "y" : ?3
-            #43 135-145 "x.b.Nope.i" : ?3
+            #41 131-132 "y" : ?
+            #43 135-145 "x.b.Nope.i" : ?
             #45 135-136 "x" : UDT<"A": Item 1>
             #46 137-138 "b" : UDT<"B": Item 2>
-            #47 139-143 "Nope" : ?2
-            #48 144-145 "i" : ?3
+            #47 139-143 "Nope" : ?
+            #48 144-145 "i" : ?
             Error(Type(Error(MissingClassHasField("B", "Nope", Span { lo: 135, hi: 143 }))))
-            Error(Type(Error(AmbiguousTy(Span { lo: 135, hi: 143 }))))
-            Error(Type(Error(AmbiguousTy(Span { lo: 135, hi: 145 }))))
         "#]],
     );
 }
@@ -2915,16 +2904,14 @@ fn struct_field_path_with_expr_invalid() {
             #30 102-109 "(x : A)" : UDT<"A": Item 1>
             #31 103-108 "x : A" : UDT<"A": Item 1>
             #39 117-156 "{\n        let y = { x }.b.Nope.i;\n    }" : Unit
-            #41 131-132 "y" : ?3
-            #43 135-149 "{ x }.b.Nope.i" : ?3
-            #44 135-147 "{ x }.b.Nope" : ?2
+            #41 131-132 "y" : ?
+            #43 135-149 "{ x }.b.Nope.i" : ?
+            #44 135-147 "{ x }.b.Nope" : ?
             #45 135-142 "{ x }.b" : UDT<"B": Item 2>
             #46 135-140 "{ x }" : UDT<"A": Item 1>
             #47 135-140 "{ x }" : UDT<"A": Item 1>
             #49 137-138 "x" : UDT<"A": Item 1>
             Error(Type(Error(MissingClassHasField("B", "Nope", Span { lo: 135, hi: 147 }))))
-            Error(Type(Error(AmbiguousTy(Span { lo: 135, hi: 147 }))))
-            Error(Type(Error(AmbiguousTy(Span { lo: 135, hi: 149 }))))
         "#]],
     );
 }
@@ -2955,7 +2942,7 @@ fn unknown_name_has_any_class() {
         &expect![[r#"
             #1 0-18 "{ foo(); foo + 1 }" : ?
             #2 0-18 "{ foo(); foo + 1 }" : ?
-            #4 2-7 "foo()" : ?0
+            #4 2-7 "foo()" : ?
             #5 2-5 "foo" : ?
             #8 5-7 "()" : Unit
             #10 9-16 "foo + 1" : ?
@@ -2963,7 +2950,6 @@ fn unknown_name_has_any_class() {
             #14 15-16 "1" : Int
             Error(Resolve(NotFound("foo", Span { lo: 2, hi: 5 })))
             Error(Resolve(NotFound("foo", Span { lo: 9, hi: 12 })))
-            Error(Type(Error(AmbiguousTy(Span { lo: 2, hi: 7 }))))
         "#]],
     );
 }
@@ -3115,6 +3101,77 @@ fn local_open() {
     );
 }
 
+#[test]
+fn failed_binding_is_error_type_downstream() {
+    check(
+        indoc! {"
+            namespace A {
+                function Foo() : Unit {
+                    let x = (1, 2).Bar;
+                    let xs = [x, x];
+                    let n = 3 + 4;
+                }
+            }
+        "},
+        "",
+        &expect![[r##"
+            #6 30-32 "()" : Unit
+            #10 40-123 "{\n        let x = (1, 2).Bar;\n        let xs = [x, x];\n        let n = 3 + 4;\n    }" : Unit
+            #12 54-55 "x" : ?
+            #14 58-68 "(1, 2).Bar" : ?
+            #15 58-64 "(1, 2)" : (Int, Int)
+            #16 59-60 "1" : Int
+            #17 62-63 "2" : Int
+            #20 82-84 "xs" : ?[]
+            #22 87-93 "[x, x]" : ?[]
+            #23 88-89 "x" : ?
+            #26 91-92 "x" : ?
+            #30 107-108 "n" : Int
+            #32 111-116 "3 + 4" : Int
+            #33 111-112 "3" : Int
+            #34 115-116 "4" : Int
+            Error(Type(Error(MissingClassHasField("(Int, Int)", "Bar", Span { lo: 58, hi: 68 }))))
+        "##]],
+    );
+}
+
+#[test]
+fn unknown_callable_result_is_error_type_downstream() {
+    check(
+        indoc! {"
+            namespace A {
+                function Foo() : Unit {
+                    let f = invalid;
+                    let r = f(1);
+                    let s = r.Bar;
+                    let t = (s, true);
+                }
+            }
+        "},
+        "",
+        &expect![[r##"
+            #6 30-32 "()" : Unit
+            #10 40-144 "{\n        let f = invalid;\n        let r = f(1);\n        let s = r.Bar;\n        let t = (s, true);\n    }" : Unit
+            #12 54-55 "f" : ?
+            #14 58-65 "invalid" : ?
+            #18 79-80 "r" : ?
+            #20 83-87 "f(1)" : ?
+            #21 83-84 "f" : ?
+            #24 84-87 "(1)" : Int
+            #25 85-86 "1" : Int
+            #27 101-102 "s" : ?
+            #29 105-110 "r.Bar" : ?
+            #31 105-106 "r" : ?
+            #32 107-110 "Bar" : ?
+            #34 124-125 "t" : (?, Bool)
+            #36 128-137 "(s, true)" : (?, Bool)
+            #37 129-130 "s" : ?
+            #40 132-136 "true" : Bool
+            Error(Resolve(NotFound("invalid", Span { lo: 58, hi: 65 })))
+        "##]],
+    );
+}
+
 #[test]
 fn infinite() {
     check(
@@ -3130,16 +3187,15 @@ fn infinite() {
         &expect![[r#"
             #6 30-32 "()" : Unit
             #8 38-97 "{\n        let x = invalid;\n        let xs = [x, [x]];\n    }" : Unit
-            #10 52-53 "x" : ?0
+            #10 52-53 "x" : ?
             #12 56-63 "invalid" : ?
-            #16 77-79 "xs" : ?0[]
-            #18 82-90 "[x, [x]]" : ?0[]
-            #19 83-84 "x" : ?0
-            #22 86-89 "[x]" : ?0[]
-            #23 87-88 "x" : ?0
+            #16 77-79 "xs" : ?[]
+            #18 82-90 "[x, [x]]" : ?[]
+            #19 83-84 "x" : ?
+            #22 86-89 "[x]" : ?[]
+            #23 87-88 "x" : ?
             Error(Resolve(NotFound("invalid", Span { lo: 56, hi: 63 })))
             Error(Type(Error(TyMismatch("?", "?[]", Span { lo: 86, hi: 89 }))))
-            Error(Type(Error(AmbiguousTy(Span { lo: 52, hi: 53 }))))
         "#]],
     );
 }
@@ -3629,13 +3685,11 @@ fn partial_app_too_many_args() {
             #22 48-49 "f" : Int
             #24 52-64 "Foo(1, _, _)" : Int
             #25 52-55 "Foo" : (Int -> Int)
-            #28 55-64 "(1, _, _)" : (Int, ?1, ?2)
+            #28 55-64 "(1, _, _)" : (Int, ?, ?)
             #29 56-57 "1" : Int
-            #30 59-60 "_" : ?1
-            #31 62-63 "_" : ?2
+            #30 59-60 "_" : ?
+            #31 62-63 "_" : ?
             Error(Type(Error(TyMismatch("Int", "(Int, ?, ?)", Span { lo: 52, hi: 64 }))))
-            Error(Type(Error(AmbiguousTy(Span { lo: 59, hi: 60 }))))
-            Error(Type(Error(AmbiguousTy(Span { lo: 62, hi: 63 }))))
         "#]],
     );
 }
@@ -4695,3 +4749,394 @@ fn field_access_not_ident() {
         "##]],
     );
 }
+
+#[test]
+fn const_ref_has_value_ty() {
+    check(
+        indoc! {"
+            namespace A {
+                const Width : Int = 2 + 2;
+                function Foo() : Int {
+                    Width * 2
+                }
+            }
+        "},
+        "",
+        &expect![[r##"
+            #9 38-43 "2 + 2" : Int
+            #10 38-39 "2" : Int
+            #11 42-43 "2" : Int
+            #15 61-63 "()" : Unit
+            #19 70-95 "{\n        Width * 2\n    }" : Int
+            #21 80-89 "Width * 2" : Int
+            #22 80-85 "Width" : Int
+            #25 88-89 "2" : Int
+        "##]],
+    );
+}
+
+#[test]
+fn const_value_ty_mismatch() {
+    check(
+        indoc! {"
+            namespace A {
+                const Width : Int = 2.0;
+            }
+        "},
+        "",
+        &expect![[r##"
+            #9 38-41 "2.0" : Double
+            Error(Type(Error(TyMismatch("Int", "Double", Span { lo: 38, hi: 41 }))))
+        "##]],
+    );
+}
+
+#[test]
+fn sized_array_param_with_matching_length() {
+    check(
+        indoc! {"
+            namespace A {
+                function SumPair(xs : Int[2], offset : Int) : Int { xs[0] + xs[1] + offset }
+                function Foo() : Int {
+                    let xs = [1, 2];
+                    SumPair(xs, 3)
+                }
+            }
+        "},
+        "",
+        &expect![[r##"
+            #6 34-61 "(xs : Int[2], offset : Int)" : (Int[], Int)
+            #7 35-46 "xs : Int[2]" : Int[]
+            #13 48-60 "offset : Int" : Int
+            #21 68-94 "{ xs[0] + xs[1] + offset }" : Int
+            #23 70-92 "xs[0] + xs[1] + offset" : Int
+            #24 70-83 "xs[0] + xs[1]" : Int
+            #25 70-75 "xs[0]" : Int
+            #26 70-72 "xs" : Int[]
+            #29 73-74 "0" : Int
+            #30 78-83 "xs[1]" : Int
+            #31 78-80 "xs" : Int[]
+            #34 81-82 "1" : Int
+            #35 86-92 "offset" : Int
+            #41 111-113 "()" : Unit
+            #45 120-175 "{\n        let xs = [1, 2];\n        SumPair(xs, 3)\n    }" : Int
+            #47 134-136 "xs" : Int[]
+            #49 139-145 "[1, 2]" : Int[]
+            #50 140-141 "1" : Int
+            #51 143-144 "2" : Int
+            #53 155-169 "SumPair(xs, 3)" : Int
+            #54 155-162 "SumPair" : ((Int[], Int) -> Int)
+            #57 162-169 "(xs, 3)" : (Int[], Int)
+            #58 163-165 "xs" : Int[]
+            #61 167-168 "3" : Int
+        "##]],
+    );
+}
+
+#[test]
+fn sized_array_length_mismatch_with_literal() {
+    check(
+        indoc! {"
+            namespace A {
+                function SumPair(xs : Int[2], offset : Int) : Int { xs[0] + xs[1] + offset }
+                function Foo() : Int {
+                    SumPair([1, 2, 3], 0)
+                }
+            }
+        "},
+        "",
+        &expect![[r##"
+            #6 34-61 "(xs : Int[2], offset : Int)" : (Int[], Int)
+            #7 35-46 "xs : Int[2]" : Int[]
+            #13 48-60 "offset : Int" : Int
+            #21 68-94 "{ xs[0] + xs[1] + offset }" : Int
+            #23 70-92 "xs[0] + xs[1] + offset" : Int
+            #24 70-83 "xs[0] + xs[1]" : Int
+            #25 70-75 "xs[0]" : Int
+            #26 70-72 "xs" : Int[]
+            #29 73-74 "0" : Int
+            #30 78-83 "xs[1]" : Int
+            #31 78-80 "xs" : Int[]
+            #34 81-82 "1" : Int
+            #35 86-92 "offset" : Int
+            #41 111-113 "()" : Unit
+            #45 120-157 "{\n        SumPair([1, 2, 3], 0)\n    }" : Int
+            #47 130-151 "SumPair([1, 2, 3], 0)" : Int
+            #48 130-137 "SumPair" : ((Int[], Int) -> Int)
+            #51 137-151 "([1, 2, 3], 0)" : (Int[], Int)
+            #52 138-147 "[1, 2, 3]" : Int[]
+            #53 139-140 "1" : Int
+            #54 142-143 "2" : Int
+            #55 145-146 "3" : Int
+            #56 149-150 "0" : Int
+            Error(Type(Error(ArrayLengthMismatch(2, 3, Span { lo: 138, hi: 147 }))))
+        "##]],
+    );
+}
+
+#[test]
+fn sized_array_length_mismatch_with_qubit_array() {
+    check(
+        indoc! {"
+            namespace A {
+                operation ApplyToThree(qs : Qubit[3]) : Unit {}
+                operation Foo() : Unit {
+                    use qs = Qubit[2];
+                    ApplyToThree(qs);
+                }
+            }
+        "},
+        "",
+        &expect![[r##"
+            #6 40-55 "(qs : Qubit[3])" : Qubit[]
+            #7 41-54 "qs : Qubit[3]" : Qubit[]
+            #16 63-65 "{}" : Unit
+            #20 83-85 "()" : Unit
+            #24 93-153 "{\n        use qs = Qubit[2];\n        ApplyToThree(qs);\n    }" : Unit
+            #26 107-109 "qs" : Qubit[]
+            #28 112-120 "Qubit[2]" : Qubit[]
+            #29 118-119 "2" : Int
+            #31 130-146 "ApplyToThree(qs)" : Unit
+            #32 130-142 "ApplyToThree" : (Qubit[] => Unit)
+            #35 142-146 "(qs)" : Qubit[]
+            #36 143-145 "qs" : Qubit[]
+            Error(Type(Error(ArrayLengthMismatch(3, 2, Span { lo: 143, hi: 145 }))))
+        "##]],
+    );
+}
+
+#[test]
+fn sized_array_return_ty_is_rejected() {
+    check(
+        indoc! {"
+            namespace A {
+                function Foo() : Int[2] { [1, 2] }
+            }
+        "},
+        "",
+        &expect![[r##"
+            #6 30-32 "()" : Unit
+            #11 42-52 "{ [1, 2] }" : Int[]
+            #13 44-50 "[1, 2]" : Int[]
+            #14 45-46 "1" : Int
+            #15 48-49 "2" : Int
+            Error(Type(Error(MisplacedSizedArray(Span { lo: 35, hi: 41 }))))
+        "##]],
+    );
+}
+
+#[test]
+fn sized_array_in_callable_param_ty_is_rejected() {
+    check(
+        indoc! {"
+            namespace A {
+                operation Foo(op : Qubit[3] => Unit) : Unit {}
+            }
+        "},
+        "",
+        &expect![[r##"
+            #6 31-54 "(op : Qubit[3] => Unit)" : (Qubit[] => Unit)
+            #7 32-53 "op : Qubit[3] => Unit" : (Qubit[] => Unit)
+            #20 62-64 "{}" : Unit
+            Error(Type(Error(MisplacedSizedArray(Span { lo: 37, hi: 45 }))))
+        "##]],
+    );
+}
+
+#[test]
+fn sized_array_item_ty_is_rejected() {
+    check(
+        indoc! {"
+            namespace A {
+                function Foo(xs : Int[2][3]) : Unit {}
+            }
+        "},
+        "",
+        &expect![[r##"
+            #6 30-46 "(xs : Int[2][3])" : Int[][]
+            #7 31-45 "xs : Int[2][3]" : Int[][]
+            #17 54-56 "{}" : Unit
+            Error(Type(Error(MisplacedSizedArray(Span { lo: 36, hi: 42 }))))
+        "##]],
+    );
+}
+
+#[test]
+fn generic_enum_variant_infers_args() {
+    check(
+        indoc! {"
+            namespace A {
+                enum Opt<'T> { Some('T), None }
+                function Foo(x : Opt<Int>) : Int {
+                    match x {
+                        Some(n) => n,
+                        None() => 0,
+                    }
+                }
+                function Bar() : Opt<Int> { Some(1) }
+            }
+        "},
+        "",
+        &expect![[r##"
+            #17 66-80 "(x : Opt<Int>)" : UDT<"Opt": Item 1><Int>
+            #18 67-79 "x : Opt<Int>" : UDT<"Opt": Item 1><Int>
+            #29 87-173 "{\n        match x {\n            Some(n) => n,\n            None() => 0,\n        }\n    }" : Int
+            #31 97-167 "match x {\n            Some(n) => n,\n            None() => 0,\n        }" : Int
+            #32 103-104 "x" : UDT<"Opt": Item 1><Int>
+            #38 123-126 "(n)" : Int
+            #39 124-125 "n" : Int
+            #41 130-131 "n" : Int
+            #47 149-151 "()" : Unit
+            #48 155-156 "0" : Int
+            #52 190-192 "()" : Unit
+            #59 204-215 "{ Some(1) }" : UDT<"Opt": Item 1><Int>
+            #61 206-213 "Some(1)" : UDT<"Opt": Item 1><Int>
+            #62 206-210 "Some" : (Int -> UDT<"Opt": Item 1><Int>)
+            #65 210-213 "(1)" : Int
+            #66 211-212 "1" : Int
+        "##]],
+    );
+}
+
+#[test]
+fn generic_enum_arg_mismatch() {
+    check(
+        indoc! {"
+            namespace A {
+                enum Opt<'T> { Some('T), None }
+                function Foo() : Opt<Int> { Some(true) }
+            }
+        "},
+        "",
+        &expect![[r##"
+            #17 66-68 "()" : Unit
+            #24 80-94 "{ Some(true) }" : UDT<"Opt": Item 1><Bool>
+            #26 82-92 "Some(true)" : UDT<"Opt": Item 1><Bool>
+            #27 82-86 "Some" : (Bool -> UDT<"Opt": Item 1><Bool>)
+            #30 86-92 "(true)" : Bool
+            #31 87-91 "true" : Bool
+            Error(Type(Error(TyMismatch("Int", "Bool", Span { lo: 82, hi: 92 }))))
+        "##]],
+    );
+}
+
+#[test]
+fn propagate_returns_failed_variant() {
+    check(
+        indoc! {"
+            namespace A {
+                enum Outcome<'T, 'E> { Ok('T), Err('E) }
+                function Foo(x : Outcome<Int, String>) : Outcome<Bool, String> {
+                    let n = x?;
+                    Ok(n > 0)
+                }
+            }
+        "},
+        "",
+        &expect![[r##"
+            #21 75-101 "(x : Outcome<Int, String>)" : UDT<"Outcome": Item 1><Int, String>
+            #22 76-100 "x : Outcome<Int, String>" : UDT<"Outcome": Item 1><Int, String>
+            #42 126-171 "{\n        let n = x?;\n        Ok(n > 0)\n    }" : UDT<"Outcome": Item 1><Bool, String>
+            #44 140-141 "n" : Int
+            #46 144-146 "x?" : Int
+            #47 144-145 "x" : UDT<"Outcome": Item 1><Int, String>
+            #51 156-165 "Ok(n > 0)" : UDT<"Outcome": Item 1><Bool, String>
+            #52 156-158 "Ok" : (Bool -> UDT<"Outcome": Item 1><Bool, String>)
+            #55 158-165 "(n > 0)" : Bool
+            #56 159-164 "n > 0" : Bool
+            #57 159-160 "n" : Int
+            #60 163-164 "0" : Int
+        "##]],
+    );
+}
+
+#[test]
+fn propagate_failed_payload_mismatch() {
+    check(
+        indoc! {"
+            namespace A {
+                enum Outcome<'T, 'E> { Ok('T), Err('E) }
+                function Foo(x : Outcome<Int, String>) : Outcome<Int, Bool> {
+                    Ok(x?)
+                }
+            }
+        "},
+        "",
+        &expect![[r##"
+            #21 75-101 "(x : Outcome<Int, String>)" : UDT<"Outcome": Item 1><Int, String>
+            #22 76-100 "x : Outcome<Int, String>" : UDT<"Outcome": Item 1><Int, String>
+            #42 123-145 "{\n        Ok(x?)\n    }" : UDT<"Outcome": Item 1><Int, Bool>
+            #44 133-139 "Ok(x?)" : UDT<"Outcome": Item 1><Int, Bool>
+            #45 133-135 "Ok" : (Int -> UDT<"Outcome": Item 1><Int, Bool>)
+            #48 135-139 "(x?)" : Int
+            #49 136-138 "x?" : Int
+            #50 136-137 "x" : UDT<"Outcome": Item 1><Int, String>
+            Error(Type(Error(TyMismatch("Bool", "String", Span { lo: 136, hi: 137 }))))
+        "##]],
+    );
+}
+
+#[test]
+fn propagate_return_ty_mismatch() {
+    check(
+        indoc! {"
+            namespace A {
+                enum Opt<'T> { Some('T), None }
+                function Foo(x : Opt<Int>) : Int {
+                    x?
+                }
+            }
+        "},
+        "",
+        &expect![[r##"
+            #17 66-80 "(x : Opt<Int>)" : UDT<"Opt": Item 1><Int>
+            #18 67-79 "x : Opt<Int>" : UDT<"Opt": Item 1><Int>
+            #29 87-105 "{\n        x?\n    }" : Int
+            #31 97-99 "x?" : Int
+            #32 97-98 "x" : UDT<"Opt": Item 1><Int>
+            Error(Type(Error(PropagateMismatch("Opt<Int>", "Int", Span { lo: 97, hi: 98 }))))
+        "##]],
+    );
+}
+
+#[test]
+fn propagate_non_enum_is_rejected() {
+    check(
+        indoc! {"
+            namespace A {
+                function Foo(x : Int) : Int {
+                    x?
+                }
+            }
+        "},
+        "",
+        &expect![[r##"
+            #6 30-39 "(x : Int)" : Int
+            #7 31-38 "x : Int" : Int
+            #15 46-64 "{\n        x?\n    }" : Int
+            #17 56-58 "x?" : Int
+            #18 56-57 "x" : Int
+            Error(Type(Error(MissingClassPropagate("Int", Span { lo: 56, hi: 57 }))))
+        "##]],
+    );
+}
+
+#[test]
+fn propagate_outside_callable_is_rejected() {
+    check(
+        indoc! {"
+            namespace A {
+                enum Opt<'T> { Some('T), None }
+            }
+        "},
+        "A.Some(1)?",
+        &expect![[r##"
+            #14 52-62 "A.Some(1)?" : ?
+            #15 52-61 "A.Some(1)" : UDT<"Opt": Item 1><Int>
+            #16 52-58 "A.Some" : (Int -> UDT<"Opt": Item 1><Int>)
+            #20 58-61 "(1)" : Int
+            #21 59-60 "1" : Int
+            Error(Type(Error(PropagateOutsideCallable(Span { lo: 52, hi: 61 }))))
+        "##]],
+    );
+}
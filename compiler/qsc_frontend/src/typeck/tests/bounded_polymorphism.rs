@@ -104,8 +104,6 @@ fn extra_arg_to_exp() {
             #56 205-206 "a" : Param<"'E": 0>
             #59 209-210 "b" : Int
             Error(Type(Error(IncorrectNumberOfConstraintParameters { expected: 1, found: 2, span: Span { lo: 56, hi: 59 } })))
-            Error(Type(Error(IncorrectNumberOfConstraintParameters { expected: 1, found: 2, span: Span { lo: 56, hi: 59 } })))
-            Error(Type(Error(IncorrectNumberOfConstraintParameters { expected: 1, found: 0, span: Span { lo: 162, hi: 165 } })))
             Error(Type(Error(IncorrectNumberOfConstraintParameters { expected: 1, found: 0, span: Span { lo: 162, hi: 165 } })))
             Error(Type(Error(MissingClassExp("'E", Span { lo: 108, hi: 113 }))))
             Error(Type(Error(TyMismatch("'T", "'E", Span { lo: 108, hi: 113 }))))
@@ -769,9 +767,6 @@ fn unknown_class() {
             #35 172-175 "(1)" : Int
             #36 173-174 "1" : Int
             Error(Type(Error(UnrecognizedClass { span: Span { lo: 52, hi: 59 }, name: "Unknown" })))
-            Error(Type(Error(UnrecognizedClass { span: Span { lo: 52, hi: 59 }, name: "Unknown" })))
-            Error(Type(Error(UnrecognizedClass { span: Span { lo: 52, hi: 59 }, name: "Unknown" })))
-            Error(Type(Error(UnrecognizedClass { span: Span { lo: 52, hi: 59 }, name: "Unknown" })))
         "##]],
     );
 }
@@ -822,3 +817,277 @@ fn test_harness_use_case() {
         "##]],
     );
 }
+
+#[test]
+fn user_defined_class() {
+    check(
+        r#"
+        namespace A {
+            class Numeric : Add + Mul + Eq;
+
+            function Square<'T: Numeric>(a: 'T) : 'T {
+                a * a
+            }
+
+            function Main() : Unit {
+                let x = Square(3);
+                let y = Square(1.5);
+            }
+        }
+        "#,
+        "",
+        &expect![[r##"
+            #14 108-115 "(a: 'T)" : Param<"'T": 0>
+            #15 109-114 "a: 'T" : Param<"'T": 0>
+            #21 121-158 "{\n                a * a\n            }" : Param<"'T": 0>
+            #23 139-144 "a * a" : Param<"'T": 0>
+            #24 139-140 "a" : Param<"'T": 0>
+            #27 143-144 "a" : Param<"'T": 0>
+            #33 185-187 "()" : Unit
+            #37 195-282 "{\n                let x = Square(3);\n                let y = Square(1.5);\n            }" : Unit
+            #39 217-218 "x" : Int
+            #41 221-230 "Square(3)" : Int
+            #42 221-227 "Square" : (Int -> Int)
+            #45 227-230 "(3)" : Int
+            #46 228-229 "3" : Int
+            #48 252-253 "y" : Double
+            #50 256-267 "Square(1.5)" : Double
+            #51 256-262 "Square" : (Double -> Double)
+            #54 262-267 "(1.5)" : Double
+            #55 263-266 "1.5" : Double
+        "##]],
+    );
+}
+
+#[test]
+fn user_defined_class_fail() {
+    check(
+        r#"
+        namespace A {
+            class Numeric : Add + Mul + Eq;
+
+            function Square<'T: Numeric>(a: 'T) : 'T {
+                a * a
+            }
+
+            function Main() : Unit {
+                let x = Square(true);
+            }
+        }
+        "#,
+        "",
+        &expect![[r##"
+            #14 108-115 "(a: 'T)" : Param<"'T": 0>
+            #15 109-114 "a: 'T" : Param<"'T": 0>
+            #21 121-158 "{\n                a * a\n            }" : Param<"'T": 0>
+            #23 139-144 "a * a" : Param<"'T": 0>
+            #24 139-140 "a" : Param<"'T": 0>
+            #27 143-144 "a" : Param<"'T": 0>
+            #33 185-187 "()" : Unit
+            #37 195-248 "{\n                let x = Square(true);\n            }" : Unit
+            #39 217-218 "x" : Bool
+            #41 221-233 "Square(true)" : Bool
+            #42 221-227 "Square" : (Bool -> Bool)
+            #45 227-233 "(true)" : Bool
+            #46 228-232 "true" : Bool
+            Error(Type(Error(MissingClassAdd("Bool", Span { lo: 221, hi: 233 }))))
+            Error(Type(Error(MissingClassMul("Bool", Span { lo: 221, hi: 233 }))))
+        "##]],
+    );
+}
+
+#[test]
+fn user_defined_class_missing_from_bounds() {
+    check(
+        r#"
+        namespace A {
+            class Summable : Add;
+
+            function Square<'T: Summable>(a: 'T) : 'T {
+                a * a
+            }
+        }
+        "#,
+        "",
+        &expect![[r##"
+            #12 99-106 "(a: 'T)" : Param<"'T": 0>
+            #13 100-105 "a: 'T" : Param<"'T": 0>
+            #19 112-149 "{\n                a * a\n            }" : Param<"'T": 0>
+            #21 130-135 "a * a" : Param<"'T": 0>
+            #22 130-131 "a" : Param<"'T": 0>
+            #25 134-135 "a" : Param<"'T": 0>
+            Error(Type(Error(MissingClassMul("'T", Span { lo: 130, hi: 131 }))))
+        "##]],
+    );
+}
+
+#[test]
+fn user_defined_class_of_classes() {
+    check(
+        r#"
+        namespace A {
+            class Numeric : Add + Mul;
+            class Printable : Show;
+            class Reportable : Numeric + Printable;
+
+            function Report<'T: Reportable>(a: 'T) : String {
+                $"{a + a * a}"
+            }
+
+            function CallReport<'T: Reportable>(a: 'T) : String {
+                Report(a)
+            }
+
+            function Main() : Unit {
+                let x = Report(2);
+            }
+        }
+        "#,
+        "",
+        &expect![[r##"
+            #22 194-201 "(a: 'T)" : Param<"'T": 0>
+            #23 195-200 "a: 'T" : Param<"'T": 0>
+            #30 211-257 "{\n                $\"{a + a * a}\"\n            }" : String
+            #32 229-243 "$\"{a + a * a}\"" : String
+            #33 232-241 "a + a * a" : Param<"'T": 0>
+            #34 232-233 "a" : Param<"'T": 0>
+            #37 236-241 "a * a" : Param<"'T": 0>
+            #38 236-237 "a" : Param<"'T": 0>
+            #41 240-241 "a" : Param<"'T": 0>
+            #49 306-313 "(a: 'T)" : Param<"'T": 0>
+            #50 307-312 "a: 'T" : Param<"'T": 0>
+            #57 323-364 "{\n                Report(a)\n            }" : String
+            #59 341-350 "Report(a)" : String
+            #60 341-347 "Report" : (Param<"'T": 0> -> String)
+            #63 347-350 "(a)" : Param<"'T": 0>
+            #64 348-349 "a" : Param<"'T": 0>
+            #70 391-393 "()" : Unit
+            #74 401-451 "{\n                let x = Report(2);\n            }" : Unit
+            #76 423-424 "x" : String
+            #78 427-436 "Report(2)" : String
+            #79 427-433 "Report" : (Int -> String)
+            #82 433-436 "(2)" : Int
+            #83 434-435 "2" : Int
+        "##]],
+    );
+}
+
+#[test]
+fn cyclic_user_defined_classes() {
+    check(
+        r#"
+        namespace A {
+            class First : Eq + Second;
+            class Second : First;
+
+            function Equal<'T: Second>(a: 'T, b: 'T) : Bool {
+                a == b
+            }
+
+            function Main() : Unit {
+                let x = Equal(1, 2);
+            }
+        }
+        "#,
+        "",
+        &expect![[r##"
+            #17 135-149 "(a: 'T, b: 'T)" : (Param<"'T": 0>, Param<"'T": 0>)
+            #18 136-141 "a: 'T" : Param<"'T": 0>
+            #22 143-148 "b: 'T" : Param<"'T": 0>
+            #29 157-195 "{\n                a == b\n            }" : Bool
+            #31 175-181 "a == b" : Bool
+            #32 175-176 "a" : Param<"'T": 0>
+            #35 180-181 "b" : Param<"'T": 0>
+            #41 222-224 "()" : Unit
+            #45 232-284 "{\n                let x = Equal(1, 2);\n            }" : Unit
+            #47 254-255 "x" : Bool
+            #49 258-269 "Equal(1, 2)" : Bool
+            #50 258-263 "Equal" : ((Int, Int) -> Bool)
+            #53 263-269 "(1, 2)" : (Int, Int)
+            #54 264-265 "1" : Int
+            #55 267-268 "2" : Int
+        "##]],
+    );
+}
+
+#[test]
+fn user_defined_class_from_other_namespace() {
+    check(
+        r#"
+        namespace A {
+            class Numeric : Add + Mul;
+        }
+        namespace B {
+            open A;
+            function Square<'T: Numeric>(a: 'T) : 'T {
+                a * a
+            }
+
+            function Main() : Unit {
+                let x = Square(2.0);
+            }
+        }
+        "#,
+        "",
+        &expect![[r##"
+            #18 154-161 "(a: 'T)" : Param<"'T": 0>
+            #19 155-160 "a: 'T" : Param<"'T": 0>
+            #25 167-204 "{\n                a * a\n            }" : Param<"'T": 0>
+            #27 185-190 "a * a" : Param<"'T": 0>
+            #28 185-186 "a" : Param<"'T": 0>
+            #31 189-190 "a" : Param<"'T": 0>
+            #37 231-233 "()" : Unit
+            #41 241-293 "{\n                let x = Square(2.0);\n            }" : Unit
+            #43 263-264 "x" : Double
+            #45 267-278 "Square(2.0)" : Double
+            #46 267-273 "Square" : (Double -> Double)
+            #49 273-278 "(2.0)" : Double
+            #50 274-277 "2.0" : Double
+        "##]],
+    );
+}
+
+#[test]
+fn user_defined_class_takes_no_parameters() {
+    check(
+        r#"
+        namespace A {
+            class Container : Eq;
+
+            function First<'T: Container['U], 'U>(a: 'T) : 'T {
+                a
+            }
+        }
+        "#,
+        "",
+        &expect![[r##"
+            #15 107-114 "(a: 'T)" : Param<"'T": 0>
+            #16 108-113 "a: 'T" : Param<"'T": 0>
+            #22 120-153 "{\n                a\n            }" : Param<"'T": 0>
+            #24 138-139 "a" : Param<"'T": 0>
+            Error(Type(Error(IncorrectNumberOfConstraintParameters { expected: 0, found: 1, span: Span { lo: 89, hi: 98 } })))
+        "##]],
+    );
+}
+
+#[test]
+fn recursive_class_constraint_is_reported_once() {
+    check(
+        r#"
+        namespace A {
+            function Head<'T: Iterable['T]>(a: 'T) : 'T {
+                a
+            }
+        }
+        "#,
+        "",
+        &expect![[r##"
+            #10 66-73 "(a: 'T)" : Param<"'T": 0>
+            #11 67-72 "a: 'T" : Param<"'T": 0>
+            #17 79-112 "{\n                a\n            }" : Param<"'T": 0>
+            #19 97-98 "a" : Param<"'T": 0>
+            Error(Type(Error(RecursiveClassConstraint { span: Span { lo: 53, hi: 61 }, name: "Iterable" })))
+            Error(Type(Error(UnrecognizedClass { span: Span { lo: 97, hi: 98 }, name: "Iterable" })))
+        "##]],
+    );
+}
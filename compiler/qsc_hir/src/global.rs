@@ -169,25 +169,26 @@ impl PackageIter<'_> {
                 }),
             }),
             (ItemKind::Ty(name, def), Some(ItemKind::Namespace(namespace, _))) => {
-                self.next = Some(Global {
-                    namespace: namespace.into(),
-                    name: alias.map_or_else(|| Rc::clone(&name.name), |alias| alias.name.clone()),
-                    visibility,
-                    status,
-                    kind: Kind::Term(Term {
-                        id,
-                        scheme: def.cons_scheme(id),
-                        intrinsic: false,
-                    }),
-                });
-
-                Some(Global {
-                    namespace: namespace.into(),
-                    name: Rc::clone(&name.name),
-                    visibility,
-                    status,
-                    kind: Kind::Ty(Ty { id }),
-                })
+                // An enum has no constructor of its own, its values are built by its variants.
+                if !def.is_enum() {
+                    self.next = Some(Global {
+                        namespace: namespace.into(),
+                        name: alias
+                            .map_or_else(|| Rc::clone(&name.name), |alias| alias.name.clone()),
+                        visibility,
+                        status,
+                        kind: Kind::Term(Term {
+                            id,
+                            scheme: def.cons_scheme(id),
+                            intrinsic: false,
+                        }),
+                    });
+                }
+
+                Some(ty_global(namespace, name, visibility, status, id))
+            }
+            (ItemKind::Class(name, _), Some(ItemKind::Namespace(namespace, _))) => {
+                Some(ty_global(namespace, name, visibility, status, id))
             }
             (ItemKind::Namespace(ident, _), None) => Some(Global {
                 namespace: ident.into(),
@@ -219,6 +220,23 @@ impl PackageIter<'_> {
     }
 }
 
+/// The global for a type, or a class, declared in the given namespace.
+fn ty_global(
+    namespace: &hir::Idents,
+    name: &hir::Ident,
+    visibility: Visibility,
+    status: ItemStatus,
+    id: ItemId,
+) -> Global {
+    Global {
+        namespace: namespace.into(),
+        name: Rc::clone(&name.name),
+        visibility,
+        status,
+        kind: Kind::Ty(Ty { id }),
+    }
+}
+
 impl Iterator for PackageIter<'_> {
     type Item = Global;
 
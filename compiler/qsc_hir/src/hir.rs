@@ -4,10 +4,12 @@
 //! The high-level intermediate representation for Q#. HIR is lowered from the AST.
 
 #![warn(missing_docs)]
-use crate::ty::{Arrow, FunctorSet, FunctorSetValue, GenericArg, Scheme, Ty, TypeParameter, Udt};
+use crate::ty::{
+    Arrow, ClassDef, FunctorSet, FunctorSetValue, GenericArg, Scheme, Ty, TypeParameter, Udt,
+};
 use indenter::{indented, Indented};
 use num_bigint::BigInt;
-use qsc_data_structures::{index_map::IndexMap, span::Span};
+use qsc_data_structures::{format_spec::FormatSpec, index_map::IndexMap, span::Span};
 use std::{
     cmp::Ordering,
     fmt::{self, Debug, Display, Formatter, Write},
@@ -391,6 +393,8 @@ pub enum ItemKind {
     Ty(Ident, Udt),
     /// An export of an item.
     Export(Ident, ItemId),
+    /// A `class` declaration, which combines class constraints and may declare methods.
+    Class(Ident, ClassDef),
 }
 
 impl Display for ItemKind {
@@ -412,6 +416,7 @@ impl Display for ItemKind {
             }
             ItemKind::Ty(name, udt) => write!(f, "Type ({name}): {udt}"),
             ItemKind::Export(name, export) => write!(f, "Export ({name}): {export}"),
+            ItemKind::Class(name, def) => write!(f, "Class ({name}): {def}"),
         }
     }
 }
@@ -703,6 +708,8 @@ pub enum ExprKind {
     Index(Box<Expr>, Box<Expr>),
     /// A literal.
     Lit(Lit),
+    /// A match expression: `match a { Zero => b, One => c }`.
+    Match(Box<Expr>, Vec<MatchArm>),
     /// A range: `start..step..end`, `start..end`, `start...`, `...end`, or `...`.
     Range(Option<Box<Expr>>, Option<Box<Expr>>, Option<Box<Expr>>),
     /// A repeat-until loop with an optional fixup: `repeat { ... } until a fixup { ... }`.
@@ -757,6 +764,7 @@ impl Display for ExprKind {
             ExprKind::If(cond, body, els) => display_if(indent, cond, body, els.as_deref())?,
             ExprKind::Index(array, index) => display_index(indent, array, index)?,
             ExprKind::Lit(lit) => write!(indent, "Lit: {lit}")?,
+            ExprKind::Match(expr, arms) => display_match(indent, expr, arms)?,
             ExprKind::Range(start, step, end) => {
                 display_range(indent, start.as_deref(), step.as_deref(), end.as_deref())?;
             }
@@ -944,6 +952,16 @@ fn display_index(mut indent: Indented<Formatter>, array: &Expr, index: &Expr) ->
     Ok(())
 }
 
+fn display_match(mut indent: Indented<Formatter>, expr: &Expr, arms: &[MatchArm]) -> fmt::Result {
+    write!(indent, "Match:")?;
+    indent = set_indentation(indent, 1);
+    write!(indent, "\n{expr}")?;
+    for arm in arms {
+        write!(indent, "\n{arm}")?;
+    }
+    Ok(())
+}
+
 fn display_range(
     mut indent: Indented<Formatter>,
     start: Option<&Expr>,
@@ -1010,7 +1028,10 @@ fn display_string(mut indent: Indented<Formatter>, components: &[StringComponent
     indent = set_indentation(indent, 1);
     for component in components {
         match component {
-            StringComponent::Expr(expr) => write!(indent, "\nExpr: {expr}")?,
+            StringComponent::Expr(expr, None) => write!(indent, "\nExpr: {expr}")?,
+            StringComponent::Expr(expr, Some(format)) => {
+                write!(indent, "\nExpr (:{format}): {expr}")?;
+            }
             StringComponent::Lit(str) => write!(indent, "\nLit: {str:?}")?,
         }
     }
@@ -1113,11 +1134,59 @@ impl Display for FieldAssign {
     }
 }
 
+/// An arm of a match expression: `pattern => body`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MatchArm {
+    /// The node ID.
+    pub id: NodeId,
+    /// The span.
+    pub span: Span,
+    /// The pattern that the matched value is compared with.
+    pub pat: MatchPat,
+    /// The expression that is evaluated if the pattern matches.
+    pub body: Expr,
+}
+
+impl Display for MatchArm {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut indent = set_indentation(indented(f), 0);
+        write!(indent, "MatchArm {} {}:", self.id, self.span)?;
+        indent = set_indentation(indent, 1);
+        write!(indent, "\n{}", self.pat)?;
+        write!(indent, "\n{}", self.body)?;
+        Ok(())
+    }
+}
+
+/// The pattern of a match arm.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MatchPat {
+    /// A literal, which matches values that are equal to it.
+    Lit(Expr),
+    /// An enum variant, which matches values of that variant and binds their payload. The names are
+    /// those of all of the variants of the enum, followed by the index of the matched variant.
+    Variant(Rc<[Rc<str>]>, usize, Pat),
+    /// A pattern that matches any value and binds it.
+    Bind(Pat),
+}
+
+impl Display for MatchPat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            MatchPat::Lit(expr) => write!(f, "{expr}"),
+            MatchPat::Variant(names, index, pat) => {
+                write!(f, "Variant {index} ({}): {pat}", names[*index])
+            }
+            MatchPat::Bind(pat) => write!(f, "{pat}"),
+        }
+    }
+}
+
 /// A string component.
 #[derive(Clone, Debug, PartialEq)]
 pub enum StringComponent {
-    /// An expression.
-    Expr(Expr),
+    /// An expression, with an optional format specifier.
+    Expr(Box<Expr>, Option<FormatSpec>),
     /// A string literal.
     Lit(Rc<str>),
 }
@@ -1419,6 +1488,22 @@ pub enum Attr {
     Reset,
     /// Indicates that a callable is a test case.
     Test,
+    /// Indicates that a function has no side effects, so that it can be evaluated classically whenever its
+    /// arguments are known. The compiler verifies that the function does not call operations or impure functions.
+    Pure,
+    /// Indicates that an intrinsic function is implemented by the host running the program, such as a Python
+    /// callback or a JavaScript function, which is called with the function's arguments at run time.
+    HostCallable,
+    /// Indicates that a type is a quantity, such as an angle or a duration, which can only be added to,
+    /// subtracted from and compared with values of the same type. Quantities are erased to their base type
+    /// before code generation.
+    Quantity,
+    /// Names the values output by an entry point, either as a whole or, when it returns a tuple, by item.
+    /// The names are empty when the attribute is parsed from its name alone.
+    OutputName(Vec<Rc<str>>),
+    /// Marks the function that a `const` declaration is lowered to, which takes no input and returns the
+    /// value of the constant. It can't be written in source code.
+    Const,
 }
 
 impl Attr {
@@ -1428,7 +1513,7 @@ impl Attr {
         match self {
             Attr::Config => "Provides pre-processing information about when an item should be included in compilation.
 
-Valid arguments are `Base`, `Adaptive`, `IntegerComputations`, `FloatingPointComputations`, `BackwardsBranching`, `HigherLevelConstructs`, `QubitReset`, and `Unrestricted`.
+Valid arguments are `Base`, `Adaptive`, `IntegerComputations`, `FloatingPointComputations`, `BackwardsBranching`, `HigherLevelConstructs`, `QubitReset`, `Delay`, and `Unrestricted`.
 
 The `not` operator is also supported to negate the attribute, e.g. `not Adaptive`.",
             Attr::EntryPoint => "Indicates that the callable is the entry point to a program.",
@@ -1437,6 +1522,17 @@ The `not` operator is also supported to negate the attribute, e.g. `not Adaptive
             Attr::Measurement => "Indicates that an intrinsic callable is a measurement. This means that the operation will be marked as \"irreversible\" in the generated QIR, and output Result types will be moved to the arguments.",
             Attr::Reset => "Indicates that an intrinsic callable is a reset. This means that the operation will be marked as \"irreversible\" in the generated QIR.",
             Attr::Test =>  "Indicates that a callable is a test case.",
+            Attr::Pure => "Indicates that a function has no side effects, so that it can be evaluated classically whenever its arguments are known. The compiler verifies that the function does not call operations or impure functions.",
+            Attr::HostCallable => "Indicates that an intrinsic function is implemented by the host running the program, such as a Python callback or a JavaScript function, which is called with the function's arguments at run time.
+
+The function's input and output can only contain the types `Int`, `Double`, `Bool` and `String`, and arrays and tuples of them.",
+            Attr::Quantity => "Indicates that a type is a quantity, such as an angle or a duration, which can only be added to, subtracted from and compared with values of the same type. Quantities are erased to their base type before code generation.
+
+The type must be declared with `newtype` and wrap a single `Int` or `Double`, as in `newtype Radians = Double;`.",
+            Attr::OutputName(_) => "Names the values output by an entry point, so that they are labeled with the name in the generated QIR.
+
+Valid arguments are a single string, which names the whole output, or one string per item of the output tuple.",
+            Attr::Const => "Marks the function that a `const` declaration is lowered to, which returns the value of the constant.",
         }
     }
 }
@@ -1453,6 +1549,10 @@ impl FromStr for Attr {
             "Measurement" => Ok(Self::Measurement),
             "Reset" => Ok(Self::Reset),
             "Test" => Ok(Self::Test),
+            "Pure" => Ok(Self::Pure),
+            "HostCallable" => Ok(Self::HostCallable),
+            "Quantity" => Ok(Self::Quantity),
+            "OutputName" => Ok(Self::OutputName(Vec::new())),
             _ => Err(()),
         }
     }
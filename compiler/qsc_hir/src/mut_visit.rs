@@ -2,8 +2,9 @@
 // Licensed under the MIT License.
 
 use crate::hir::{
-    Block, CallableDecl, Expr, ExprKind, FieldAssign, Ident, Item, ItemKind, Package, Pat, PatKind,
-    QubitInit, QubitInitKind, SpecBody, SpecDecl, Stmt, StmtKind, StringComponent,
+    Block, CallableDecl, Expr, ExprKind, FieldAssign, Ident, Item, ItemKind, MatchArm, MatchPat,
+    Package, Pat, PatKind, QubitInit, QubitInitKind, SpecBody, SpecDecl, Stmt, StmtKind,
+    StringComponent,
 };
 use qsc_data_structures::span::Span;
 
@@ -40,6 +41,10 @@ pub trait MutVisitor: Sized {
         walk_field_assign(self, assign);
     }
 
+    fn visit_match_arm(&mut self, arm: &mut MatchArm) {
+        walk_match_arm(self, arm);
+    }
+
     fn visit_pat(&mut self, pat: &mut Pat) {
         walk_pat(self, pat);
     }
@@ -71,7 +76,9 @@ pub fn walk_item(vis: &mut impl MutVisitor, item: &mut Item) {
     match &mut item.kind {
         ItemKind::Callable(decl) => vis.visit_callable_decl(decl),
         ItemKind::Namespace(name, _) => vis.visit_idents(name),
-        ItemKind::Ty(name, _) | ItemKind::Export(name, _) => vis.visit_ident(name),
+        ItemKind::Ty(name, _) | ItemKind::Export(name, _) | ItemKind::Class(name, _) => {
+            vis.visit_ident(name);
+        }
     }
 }
 
@@ -178,6 +185,10 @@ pub fn walk_expr(vis: &mut impl MutVisitor, expr: &mut Expr) {
         ExprKind::Return(expr) | ExprKind::UnOp(_, expr) => {
             vis.visit_expr(expr);
         }
+        ExprKind::Match(expr, arms) => {
+            vis.visit_expr(expr);
+            arms.iter_mut().for_each(|a| vis.visit_match_arm(a));
+        }
         ExprKind::Range(start, step, end) => {
             start.iter_mut().for_each(|s| vis.visit_expr(s));
             step.iter_mut().for_each(|s| vis.visit_expr(s));
@@ -195,7 +206,7 @@ pub fn walk_expr(vis: &mut impl MutVisitor, expr: &mut Expr) {
         ExprKind::String(components) => {
             for component in components {
                 match component {
-                    StringComponent::Expr(expr) => vis.visit_expr(expr),
+                    StringComponent::Expr(expr, _) => vis.visit_expr(expr),
                     StringComponent::Lit(_) => {}
                 }
             }
@@ -223,6 +234,15 @@ pub fn walk_field_assign(vis: &mut impl MutVisitor, assign: &mut FieldAssign) {
     vis.visit_expr(&mut assign.value);
 }
 
+pub fn walk_match_arm(vis: &mut impl MutVisitor, arm: &mut MatchArm) {
+    vis.visit_span(&mut arm.span);
+    match &mut arm.pat {
+        MatchPat::Lit(lit) => vis.visit_expr(lit),
+        MatchPat::Variant(_, _, pat) | MatchPat::Bind(pat) => vis.visit_pat(pat),
+    }
+    vis.visit_expr(&mut arm.body);
+}
+
 pub fn walk_pat(vis: &mut impl MutVisitor, pat: &mut Pat) {
     vis.visit_span(&mut pat.span);
 
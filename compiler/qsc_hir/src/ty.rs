@@ -5,7 +5,7 @@ use indenter::{indented, Indented};
 use qsc_data_structures::span::Span;
 use rustc_hash::FxHashMap;
 
-use crate::hir::{CallableKind, FieldPath, Functor, ItemId, PackageId, Res};
+use crate::hir::{CallableKind, FieldPath, Functor, ItemId, LocalItemId, PackageId, Res};
 use std::{
     fmt::{self, Debug, Display, Formatter, Write},
     rc::Rc,
@@ -42,8 +42,8 @@ pub enum Ty {
     Prim(Prim),
     /// A tuple type.
     Tuple(Vec<Ty>),
-    /// A user-defined type.
-    Udt(Rc<str>, Res),
+    /// A user-defined type, with the type arguments of a generic enum.
+    Udt(Rc<str>, Res, Vec<Ty>),
     /// An invalid type.
     #[default]
     Err,
@@ -65,6 +65,22 @@ impl ClassConstraints {
     pub fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
+
+    #[must_use]
+    pub fn with_package(&self, package: PackageId) -> Self {
+        Self(
+            self.0
+                .iter()
+                .map(|bound| bound.with_package(package))
+                .collect(),
+        )
+    }
+
+    /// Substitutes the given generic arguments for the type parameters in these constraints.
+    #[must_use]
+    pub fn instantiate(&self, args: &[GenericArg]) -> Self {
+        Self(self.0.iter().map(|bound| bound.instantiate(args)).collect())
+    }
 }
 
 impl std::fmt::Display for ClassConstraints {
@@ -111,15 +127,78 @@ pub enum ClassConstraint {
     Integral,
     /// Whether or not 'T can be displayed as a string (converted to a string).
     Show,
-    /// A class that is not built-in to the compiler.
-    NonNativeClass(Rc<str>),
+    /// A class that is not built-in to the compiler, declared with a `class` item, with the type
+    /// arguments for the class's own type parameters.
+    NonNativeClass {
+        id: ItemId,
+        name: Rc<str>,
+        args: Vec<Ty>,
+    },
+}
+
+impl ClassConstraint {
+    #[must_use]
+    pub fn with_package(&self, package: PackageId) -> Self {
+        match self {
+            ClassConstraint::Exp { power } => ClassConstraint::Exp {
+                power: power.with_package(package),
+            },
+            ClassConstraint::Iterable { item } => ClassConstraint::Iterable {
+                item: item.with_package(package),
+            },
+            ClassConstraint::NonNativeClass { id, name, args } => ClassConstraint::NonNativeClass {
+                id: ItemId {
+                    package: id.package.or(Some(package)),
+                    item: id.item,
+                },
+                name: name.clone(),
+                args: args.iter().map(|arg| arg.with_package(package)).collect(),
+            },
+            _ => self.clone(),
+        }
+    }
+
+    /// Substitutes the given generic arguments, indexed by parameter ID, for the type parameters
+    /// that appear in the types of this constraint.
+    #[must_use]
+    pub fn instantiate(&self, args: &[GenericArg]) -> Self {
+        let instantiate = |ty: &Ty| ty.instantiate(args);
+        match self {
+            ClassConstraint::Exp { power } => ClassConstraint::Exp {
+                power: instantiate(power),
+            },
+            ClassConstraint::Iterable { item } => ClassConstraint::Iterable {
+                item: instantiate(item),
+            },
+            ClassConstraint::NonNativeClass {
+                id,
+                name,
+                args: class_args,
+            } => ClassConstraint::NonNativeClass {
+                id: *id,
+                name: name.clone(),
+                args: class_args.iter().map(instantiate).collect(),
+            },
+            _ => self.clone(),
+        }
+    }
 }
 
 impl std::fmt::Display for ClassConstraint {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             ClassConstraint::Eq => write!(f, "Eq"),
-            ClassConstraint::NonNativeClass(name) => write!(f, "{name}"),
+            ClassConstraint::NonNativeClass { name, args, .. } if args.is_empty() => {
+                write!(f, "{name}")
+            }
+            ClassConstraint::NonNativeClass { name, args, .. } => {
+                let args = args
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                write!(f, "{name}[{args}]")
+            }
             ClassConstraint::Add => write!(f, "Add"),
             ClassConstraint::Exp { power } => write!(f, "Exp[{power}]"),
             ClassConstraint::Iterable { item } => write!(f, "Iterable<{item}>"),
@@ -142,7 +221,12 @@ impl Ty {
     #[must_use]
     pub fn with_package(&self, package: PackageId) -> Self {
         match self {
-            Ty::Infer(_) | Ty::Param { .. } | Ty::Prim(_) | Ty::Err => self.clone(),
+            Ty::Infer(_) | Ty::Prim(_) | Ty::Err => self.clone(),
+            Ty::Param { name, id, bounds } => Ty::Param {
+                name: name.clone(),
+                id: *id,
+                bounds: bounds.with_package(package),
+            },
             Ty::Array(item) => Ty::Array(Box::new(item.with_package(package))),
             Ty::Arrow(arrow) => Ty::Arrow(Box::new(arrow.with_package(package))),
             Ty::Tuple(items) => Ty::Tuple(
@@ -151,10 +235,21 @@ impl Ty {
                     .map(|item| item.with_package(package))
                     .collect(),
             ),
-            Ty::Udt(name, res) => Ty::Udt(name.clone(), res.with_package(package)),
+            Ty::Udt(name, res, args) => Ty::Udt(
+                name.clone(),
+                res.with_package(package),
+                args.iter().map(|arg| arg.with_package(package)).collect(),
+            ),
         }
     }
 
+    /// Substitutes the given generic arguments, indexed by parameter ID, for the type parameters in
+    /// this type. A parameter without a matching type argument is kept.
+    #[must_use]
+    pub fn instantiate(&self, args: &[GenericArg]) -> Self {
+        instantiate_ty(|id| args.get(usize::from(*id)), self).unwrap_or_else(|_| self.clone())
+    }
+
     pub fn display(&self) -> String {
         match self {
             Ty::Array(item) => {
@@ -183,7 +278,12 @@ impl Ty {
                 )
             }
             Ty::Infer(_) | Ty::Err => "?".to_string(),
-            Ty::Param { name, .. } | Ty::Udt(name, _) => name.to_string(),
+            Ty::Param { name, .. } => name.to_string(),
+            Ty::Udt(name, _, args) if args.is_empty() => name.to_string(),
+            Ty::Udt(name, _, args) => {
+                let args = args.iter().map(Ty::display).collect::<Vec<_>>().join(", ");
+                format!("{name}<{args}>")
+            }
             Ty::Prim(prim) => format!("{prim:?}"),
             Ty::Tuple(items) => {
                 if items.is_empty() {
@@ -229,15 +329,23 @@ impl Display for Ty {
                     f.write_str(")")
                 }
             }
-            Ty::Udt(name, res) => {
-                write!(f, "UDT<\"{name}\": {res}>")
+            Ty::Udt(name, res, args) => {
+                write!(f, "UDT<\"{name}\": {res}>")?;
+                if let Some((first, rest)) = args.split_first() {
+                    write!(f, "<{first}")?;
+                    for arg in rest {
+                        write!(f, ", {arg}")?;
+                    }
+                    f.write_char('>')?;
+                }
+                Ok(())
             }
             Ty::Err => f.write_char('?'),
         }
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 /// A type scheme.
 pub struct Scheme {
     params: Vec<TypeParameter>,
@@ -254,7 +362,17 @@ impl Scheme {
     #[must_use]
     pub fn with_package(&self, package: PackageId) -> Self {
         Self {
-            params: self.params.clone(),
+            params: self
+                .params
+                .iter()
+                .map(|param| match param {
+                    TypeParameter::Ty { name, bounds } => TypeParameter::Ty {
+                        name: name.clone(),
+                        bounds: bounds.with_package(package),
+                    },
+                    TypeParameter::Functor(_) => param.clone(),
+                })
+                .collect(),
             ty: Box::new(Arrow {
                 kind: self.ty.kind,
                 input: Box::new(self.ty.input.with_package(package)),
@@ -307,7 +425,7 @@ fn instantiate_ty<'a>(
     ty: &Ty,
 ) -> Result<Ty, InstantiationError> {
     match ty {
-        Ty::Err | Ty::Infer(_) | Ty::Prim(_) | Ty::Udt(_, _) => Ok(ty.clone()),
+        Ty::Err | Ty::Infer(_) | Ty::Prim(_) => Ok(ty.clone()),
         Ty::Array(item) => Ok(Ty::Array(Box::new(instantiate_ty(arg, item)?))),
         Ty::Arrow(arrow) => Ok(Ty::Arrow(Box::new(instantiate_arrow_ty(arg, arrow)?))),
         Ty::Param { id, .. } => match arg(id) {
@@ -321,6 +439,13 @@ fn instantiate_ty<'a>(
                 .map(|item| instantiate_ty(arg, item))
                 .collect::<Result<_, _>>()?,
         )),
+        Ty::Udt(name, res, args) => Ok(Ty::Udt(
+            name.clone(),
+            res.clone(),
+            args.iter()
+                .map(|item| instantiate_ty(arg, item))
+                .collect::<Result<_, _>>()?,
+        )),
     }
 }
 
@@ -539,6 +664,19 @@ impl FunctorSet {
             Self::Param(_, _) | Self::Infer(_) => panic!("{msg}"),
         }
     }
+
+    /// Substitutes the given generic argument, indexed by parameter ID, if this set is a functor
+    /// parameter with a matching functor argument.
+    #[must_use]
+    pub fn instantiate(self, args: &[GenericArg]) -> Self {
+        match self {
+            Self::Param(id, _) => match args.get(usize::from(id)) {
+                Some(GenericArg::Functor(functors)) => *functors,
+                _ => self,
+            },
+            Self::Value(_) | Self::Infer(_) => self,
+        }
+    }
 }
 
 impl Display for FunctorSet {
@@ -628,6 +766,50 @@ impl Display for FunctorSetValue {
     }
 }
 
+/// An operator that a user-defined type implements with a function named after the operator and the
+/// type, such as `PlusComplex` for `+` on a type named `Complex`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum UdtOperator {
+    /// Binary `+`, implemented by a function with the signature `(T, T) -> T`.
+    Plus,
+    /// Binary `-`, implemented by a function with the signature `(T, T) -> T`.
+    Minus,
+    /// Binary `*`, implemented by a function with the signature `(T, T) -> T`.
+    Times,
+    /// Binary `/`, implemented by a function with the signature `(T, T) -> T`.
+    DividedBy,
+    /// Unary `-`, implemented by a function with the signature `T -> T`.
+    Negation,
+    /// The comparison operators `==`, `!=`, `<`, `<=`, `>` and `>=`, implemented by a function with the
+    /// signature `(T, T) -> Int` that returns a negative number, zero or a positive number when its
+    /// first input is less than, equal to or greater than its second input.
+    Compare,
+}
+
+impl UdtOperator {
+    pub const ALL: [Self; 6] = [
+        Self::Plus,
+        Self::Minus,
+        Self::Times,
+        Self::DividedBy,
+        Self::Negation,
+        Self::Compare,
+    ];
+
+    /// The prefix of the name of the function that implements the operator.
+    #[must_use]
+    pub fn prefix(self) -> &'static str {
+        match self {
+            Self::Plus => "Plus",
+            Self::Minus => "Minus",
+            Self::Times => "Times",
+            Self::DividedBy => "DividedBy",
+            Self::Negation => "Negation",
+            Self::Compare => "Compare",
+        }
+    }
+}
+
 /// The item for a user-defined type.
 #[derive(Clone, Debug, PartialEq)]
 pub struct Udt {
@@ -637,11 +819,111 @@ pub struct Udt {
     pub name: Rc<str>,
     // The definition.
     pub definition: UdtDef,
+    /// Whether the type is a quantity, declared with the `@Quantity()` attribute. A quantity wraps a
+    /// single `Int` or `Double` and supports the addition, subtraction and comparison of its base type.
+    pub quantity: bool,
+    /// The functions that implement operators for this type, which are declared in the same package.
+    pub operators: Vec<(UdtOperator, LocalItemId)>,
+    /// The classes with methods that this type is a member of, by implementing each method with a
+    /// function declared in the same package.
+    pub instances: Vec<UdtInstance>,
+    /// The type parameters of the type, which only an enum can have. The payloads of its variants
+    /// refer to them by their index.
+    pub generics: Vec<TypeParameter>,
+    /// The variants of the type, if it is an enum. The values of an enum are tuples of the index of
+    /// their variant and its payload, so an enum has no constructor and no pure type, and its
+    /// definition is the empty tuple.
+    pub variants: Vec<UdtVariant>,
+}
+
+/// The definition of a class declared with a `class` item.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ClassDef {
+    /// The name.
+    pub name: Rc<str>,
+    /// The type parameters of the class. The arguments for them are given by each constraint that
+    /// names the class.
+    pub generics: Vec<TypeParameter>,
+    /// The constraints that a type must satisfy to be a member of the class, which refer to the
+    /// type parameters of the class by their index.
+    pub constraints: ClassConstraints,
+    /// The methods that a member of the class must implement. Each is a callable declared in the
+    /// same package as the class, whose type parameters are those of the class followed by `'Self`.
+    pub methods: Vec<(Rc<str>, LocalItemId)>,
+}
+
+impl ClassDef {
+    #[must_use]
+    pub fn with_package(&self, package: PackageId) -> Self {
+        Self {
+            name: self.name.clone(),
+            generics: self
+                .generics
+                .iter()
+                .map(|param| match param {
+                    TypeParameter::Ty { name, bounds } => TypeParameter::Ty {
+                        name: name.clone(),
+                        bounds: bounds.with_package(package),
+                    },
+                    TypeParameter::Functor(_) => param.clone(),
+                })
+                .collect(),
+            constraints: self.constraints.with_package(package),
+            methods: self.methods.clone(),
+        }
+    }
+}
+
+impl Display for ClassDef {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let mut indent = set_indentation(indented(f), 0);
+        write!(indent, "{}", self.constraints)?;
+        indent = set_indentation(indent, 1);
+        for param in &self.generics {
+            write!(indent, "\n{param}")?;
+        }
+        for (name, item) in &self.methods {
+            write!(indent, "\nMethod ({name}): Item {item}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The membership of a user-defined type in a class with methods.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UdtInstance {
+    /// The class. Its package is [None] if it is in the same package as the type.
+    pub class: ItemId,
+    /// The type arguments for the class's own type parameters.
+    pub args: Vec<Ty>,
+    /// The functions that implement the methods of the class, in the order that the class declares
+    /// them. They are declared in the same package as the type.
+    pub methods: Vec<LocalItemId>,
+}
+
+/// A variant of an enum.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UdtVariant {
+    /// The span.
+    pub span: Span,
+    /// The name.
+    pub name: Rc<str>,
+    /// The type of the payload, which is `Unit` for a variant without one.
+    pub ty: Ty,
+    /// The function that constructs values of this variant, which is declared in the same package as
+    /// the enum.
+    pub item: LocalItemId,
 }
 
 impl Udt {
+    /// The type that this user-defined type wraps.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this type is an enum, since the payload of its values depends on their variant.
     #[must_use]
     pub fn get_pure_ty(&self) -> Ty {
+        assert!(!self.is_enum(), "an enum has no pure type");
         fn get_pure_ty(def: &UdtDef) -> Ty {
             match &def.kind {
                 UdtDefKind::Field(field) => field.ty.clone(),
@@ -663,12 +945,117 @@ impl Udt {
             ty: Box::new(Arrow {
                 kind: CallableKind::Function,
                 input: Box::new(self.get_pure_ty()),
-                output: Box::new(Ty::Udt(self.name.clone(), Res::Item(id))),
+                output: Box::new(Ty::Udt(self.name.clone(), Res::Item(id), Vec::new())),
                 functors: FunctorSet::Value(FunctorSetValue::Empty),
             }),
         }
     }
 
+    /// The type scheme of the function that constructs values of the given variant of this enum,
+    /// which is generic over the type parameters of the enum.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the enum.
+    /// * `variant` - The variant.
+    #[must_use]
+    pub fn variant_scheme(&self, id: ItemId, variant: &UdtVariant) -> Scheme {
+        Scheme {
+            params: self.generics.clone(),
+            ty: Box::new(Arrow {
+                kind: CallableKind::Function,
+                input: Box::new(variant.ty.clone()),
+                output: Box::new(self.generic_ty(id)),
+                functors: FunctorSet::Value(FunctorSetValue::Empty),
+            }),
+        }
+    }
+
+    /// The type with the given ID applied to its own type parameters, which is the type that the
+    /// variants of an enum construct.
+    #[must_use]
+    pub fn generic_ty(&self, id: ItemId) -> Ty {
+        let args = self
+            .generics
+            .iter()
+            .enumerate()
+            .filter_map(|(index, param)| match param {
+                TypeParameter::Ty { name, bounds } => Some(Ty::Param {
+                    name: name.clone(),
+                    id: ParamId::from(index),
+                    bounds: bounds.clone(),
+                }),
+                TypeParameter::Functor(_) => None,
+            })
+            .collect();
+        Ty::Udt(self.name.clone(), Res::Item(id), args)
+    }
+
+    /// The type of the payload of the variant with the given index, for the given type arguments of
+    /// this enum. The package is that of the enum, if it is not the local package.
+    #[must_use]
+    pub fn variant_ty(&self, index: usize, package: Option<PackageId>, args: &[Ty]) -> Ty {
+        let payload = &self.variants[index].ty;
+        let payload =
+            package.map_or_else(|| payload.clone(), |package| payload.with_package(package));
+        let args: FxHashMap<_, _> = args
+            .iter()
+            .enumerate()
+            .map(|(index, arg)| (ParamId::from(index), GenericArg::Ty(arg.clone())))
+            .collect();
+        instantiate_ty(|id| args.get(id), &payload)
+            .expect("variant payload should instantiate with type arguments")
+    }
+
+    /// The function that implements the given operator for this type, if any. The function is in the
+    /// same package as the type.
+    #[must_use]
+    pub fn operator(&self, op: UdtOperator) -> Option<LocalItemId> {
+        self.operators
+            .iter()
+            .find_map(|&(other, item)| (other == op).then_some(item))
+    }
+
+    /// The membership of this type in the given class, if it is a member. The package is that of the
+    /// type, if it is not the local package, and class IDs are compared after applying it.
+    #[must_use]
+    pub fn instance(&self, package: Option<PackageId>, class: ItemId) -> Option<UdtInstance> {
+        self.instances.iter().find_map(|instance| {
+            let instance_class = ItemId {
+                package: instance.class.package.or(package),
+                item: instance.class.item,
+            };
+            (instance_class == class).then(|| UdtInstance {
+                class: instance_class,
+                args: package.map_or_else(
+                    || instance.args.clone(),
+                    |package| {
+                        instance
+                            .args
+                            .iter()
+                            .map(|arg| arg.with_package(package))
+                            .collect()
+                    },
+                ),
+                methods: instance.methods.clone(),
+            })
+        })
+    }
+
+    /// Whether this type is an enum.
+    #[must_use]
+    pub fn is_enum(&self) -> bool {
+        !self.variants.is_empty()
+    }
+
+    /// The index of the variant constructed by the given function, if it is a variant of this type.
+    #[must_use]
+    pub fn variant_index(&self, item: LocalItemId) -> Option<usize> {
+        self.variants
+            .iter()
+            .position(|variant| variant.item == item)
+    }
+
     /// The path to the field with the given name. Returns [None] if this user-defined type does not
     /// have a field with the given name.
     #[must_use]
@@ -752,6 +1139,9 @@ impl Udt {
     /// Otherwise, returns false.
     #[must_use]
     pub fn is_struct(&self) -> bool {
+        if self.is_enum() {
+            return false;
+        }
         match &self.definition.kind {
             UdtDefKind::Field(_) => false,
             UdtDefKind::Tuple(fields) => fields.iter().all(|field| match &field.kind {
@@ -774,6 +1164,13 @@ impl Display for Udt {
         write!(indent, "UDT {}:", self.span)?;
         indent = set_indentation(indent, 1);
         write!(indent, "\n{}", self.definition)?;
+        for variant in &self.variants {
+            write!(
+                indent,
+                "\nVariant {} ({}): {} = Item {}",
+                variant.span, variant.name, variant.ty, variant.item
+            )?;
+        }
         Ok(())
     }
 }
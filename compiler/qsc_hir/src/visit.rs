@@ -2,8 +2,9 @@
 // Licensed under the MIT License.
 
 use crate::hir::{
-    Block, CallableDecl, Expr, ExprKind, FieldAssign, Ident, Idents, Item, ItemKind, Package, Pat,
-    PatKind, QubitInit, QubitInitKind, SpecBody, SpecDecl, Stmt, StmtKind, StringComponent,
+    Block, CallableDecl, Expr, ExprKind, FieldAssign, Ident, Idents, Item, ItemKind, MatchArm,
+    MatchPat, Package, Pat, PatKind, QubitInit, QubitInitKind, SpecBody, SpecDecl, Stmt, StmtKind,
+    StringComponent,
 };
 
 pub trait Visitor<'a>: Sized {
@@ -39,6 +40,10 @@ pub trait Visitor<'a>: Sized {
         walk_field_assign(self, assign);
     }
 
+    fn visit_match_arm(&mut self, arm: &'a MatchArm) {
+        walk_match_arm(self, arm);
+    }
+
     fn visit_pat(&mut self, pat: &'a Pat) {
         walk_pat(self, pat);
     }
@@ -64,7 +69,9 @@ pub fn walk_item<'a>(vis: &mut impl Visitor<'a>, item: &'a Item) {
     match &item.kind {
         ItemKind::Callable(decl) => vis.visit_callable_decl(decl),
         ItemKind::Namespace(name, _) => vis.visit_idents(name),
-        ItemKind::Ty(name, _) | ItemKind::Export(name, _) => vis.visit_ident(name),
+        ItemKind::Ty(name, _) | ItemKind::Export(name, _) | ItemKind::Class(name, _) => {
+            vis.visit_ident(name);
+        }
     }
 }
 
@@ -159,6 +166,10 @@ pub fn walk_expr<'a>(vis: &mut impl Visitor<'a>, expr: &'a Expr) {
         ExprKind::Return(expr) | ExprKind::UnOp(_, expr) => {
             vis.visit_expr(expr);
         }
+        ExprKind::Match(expr, arms) => {
+            vis.visit_expr(expr);
+            arms.iter().for_each(|a| vis.visit_match_arm(a));
+        }
         ExprKind::Range(start, step, end) => {
             start.iter().for_each(|s| vis.visit_expr(s));
             step.iter().for_each(|s| vis.visit_expr(s));
@@ -176,7 +187,7 @@ pub fn walk_expr<'a>(vis: &mut impl Visitor<'a>, expr: &'a Expr) {
         ExprKind::String(components) => {
             for component in components {
                 match component {
-                    StringComponent::Expr(expr) => vis.visit_expr(expr),
+                    StringComponent::Expr(expr, _) => vis.visit_expr(expr),
                     StringComponent::Lit(_) => {}
                 }
             }
@@ -203,6 +214,14 @@ pub fn walk_field_assign<'a>(vis: &mut impl Visitor<'a>, assign: &'a FieldAssign
     vis.visit_expr(&assign.value);
 }
 
+pub fn walk_match_arm<'a>(vis: &mut impl Visitor<'a>, arm: &'a MatchArm) {
+    match &arm.pat {
+        MatchPat::Lit(lit) => vis.visit_expr(lit),
+        MatchPat::Variant(_, _, pat) | MatchPat::Bind(pat) => vis.visit_pat(pat),
+    }
+    vis.visit_expr(&arm.body);
+}
+
 pub fn walk_pat<'a>(vis: &mut impl Visitor<'a>, pat: &'a Pat) {
     match &pat.kind {
         PatKind::Bind(name) => vis.visit_ident(name),
@@ -211,7 +211,7 @@ impl HirLintPass for DeprecatedWithOperator {
         match &expr.kind {
             ExprKind::UpdateField(container, field, value)
             | ExprKind::AssignField(container, field, value) => {
-                if let Ty::Udt(ty_name, Res::Item(item_id)) = &container.ty {
+                if let Ty::Udt(ty_name, Res::Item(item_id), _) = &container.ty {
                     let item = compilation.resolve_item_id(item_id);
                     if let ItemKind::Ty(_, udt) = &item.kind {
                         if udt.is_struct() {
@@ -3,12 +3,13 @@
 
 use qsc_data_structures::index_map::IndexMap;
 use qsc_fir::assigner::Assigner;
-use qsc_fir::fir::{Block, CallableImpl, ExecGraphNode, Expr, Pat, SpecImpl, Stmt};
+use qsc_fir::fir::{Block, CallableImpl, ExecGraph, ExecGraphNode, Expr, Pat, SpecImpl, Stmt};
 use qsc_fir::{
     fir::{self, BlockId, ExprId, LocalItemId, PatId, StmtId},
     ty::{Arrow, InferFunctorId, ParamId, Ty},
 };
 use qsc_hir::hir::{self, SpecBody, SpecGen};
+use rustc_hash::{FxHashMap, FxHashSet};
 use std::iter::once;
 use std::{clone::Clone, rc::Rc};
 
@@ -40,9 +41,17 @@ pub struct Lowerer {
     blocks: IndexMap<BlockId, Block>,
     assigner: Assigner,
     exec_graph: Vec<ExecGraphNode>,
+    /// The nodes of the exec graphs lowered so far, so that identical graphs share their nodes.
+    exec_graphs: FxHashSet<Rc<[ExecGraphNode]>>,
     enable_debug: bool,
     ret_node: ExecGraphNode,
     fir_increment: FirIncrement,
+    /// The base types of the quantities that are erased when lowered.
+    quantities: FxHashMap<hir::ItemId, Ty>,
+    /// The function being lowered, whose calls to itself in tail position reuse its frame.
+    tail_call_item: Option<hir::LocalItemId>,
+    /// The calls of the function being lowered to itself.
+    self_calls: FxHashSet<ExprId>,
 }
 
 impl Default for Lowerer {
@@ -63,9 +72,13 @@ impl Lowerer {
             blocks: IndexMap::new(),
             assigner: Assigner::new(),
             exec_graph: Vec::new(),
+            exec_graphs: FxHashSet::default(),
             enable_debug: false,
             ret_node: ExecGraphNode::Ret,
             fir_increment: FirIncrement::default(),
+            quantities: FxHashMap::default(),
+            tail_call_item: None,
+            self_calls: FxHashSet::default(),
         }
     }
 
@@ -80,6 +93,14 @@ impl Lowerer {
         self
     }
 
+    /// Registers the quantities declared in the packages of the store, so that they are erased from
+    /// the packages lowered later that use them.
+    #[must_use]
+    pub fn with_quantities(mut self, store: &fir::PackageStore) -> Self {
+        self.collect_store_quantities(store);
+        self
+    }
+
     pub fn take_exec_graph(&mut self) -> Vec<ExecGraphNode> {
         self.exec_graph
             .drain(..)
@@ -92,8 +113,10 @@ impl Lowerer {
         package: &hir::Package,
         store: &fir::PackageStore,
     ) -> fir::Package {
+        self.collect_store_quantities(store);
+        self.collect_quantities(package);
         let entry = package.entry.as_ref().map(|e| self.lower_expr(e));
-        let entry_exec_graph = self.exec_graph.drain(..).collect();
+        let entry_exec_graph = self.take_interned_exec_graph(false);
         let items: IndexMap<LocalItemId, fir::Item> = package
             .items
             .values()
@@ -135,6 +158,7 @@ impl Lowerer {
     ) {
         // Clear the previous increment since we are about to take a new one.
         self.fir_increment = FirIncrement::default();
+        self.collect_quantities(hir_package);
 
         let items: IndexMap<LocalItemId, fir::Item> = hir_package
             .items
@@ -199,6 +223,74 @@ impl Lowerer {
         }
     }
 
+    fn collect_store_quantities(&mut self, store: &fir::PackageStore) {
+        for (package_id, package) in store {
+            for item in package.items.values() {
+                if let fir::ItemKind::Ty(_, udt) = &item.kind {
+                    if let qsc_fir::ty::UdtDefKind::Field(field) = &udt.definition.kind {
+                        if item.attrs.contains(&fir::Attr::Quantity) {
+                            let id = hir::ItemId {
+                                package: Some(map_fir_package_to_hir(package_id)),
+                                item: hir::LocalItemId::from(usize::from(item.id)),
+                            };
+                            self.quantities.insert(id, field.ty.clone());
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn collect_quantities(&mut self, package: &hir::Package) {
+        for item in package.items.values() {
+            if let hir::ItemKind::Ty(_, udt) = &item.kind {
+                if let qsc_hir::ty::UdtDefKind::Field(field) = &udt.definition.kind {
+                    if udt.quantity {
+                        let id = hir::ItemId {
+                            package: None,
+                            item: item.id,
+                        };
+                        let ty = self.lower_ty(&field.ty);
+                        self.quantities.insert(id, ty);
+                    }
+                }
+            }
+        }
+    }
+
+    fn quantity_base_ty(&self, ty: &qsc_hir::ty::Ty) -> Option<&Ty> {
+        match ty {
+            qsc_hir::ty::Ty::Udt(_, hir::Res::Item(id), _) => self.quantities.get(id),
+            _ => None,
+        }
+    }
+
+    /// The operand of an expression that only wraps or unwraps a quantity. Since quantities are
+    /// erased to their base type, the operand is lowered in place of the expression.
+    fn erased_quantity_operand<'a>(&self, expr: &'a hir::Expr) -> Option<&'a hir::Expr> {
+        match &expr.kind {
+            hir::ExprKind::Call(callee, arg) => match &callee.kind {
+                hir::ExprKind::Var(hir::Res::Item(id), _) if self.quantities.contains_key(id) => {
+                    Some(arg)
+                }
+                _ => None,
+            },
+            hir::ExprKind::UnOp(hir::UnOp::Unwrap, operand)
+            | hir::ExprKind::Field(operand, hir::Field::Path(_))
+                if self.quantity_base_ty(&operand.ty).is_some() =>
+            {
+                Some(operand)
+            }
+            // Updating the only field of a quantity replaces the whole value.
+            hir::ExprKind::UpdateField(record, hir::Field::Path(_), replace)
+                if self.quantity_base_ty(&record.ty).is_some() =>
+            {
+                Some(replace)
+            }
+            _ => None,
+        }
+    }
+
     fn lower_item(&mut self, item: &hir::Item) -> fir::Item {
         let kind = match &item.kind {
             hir::ItemKind::Namespace(name, items) => {
@@ -216,7 +308,12 @@ impl Lowerer {
                 fir::ItemKind::Namespace(name, items)
             }
             hir::ItemKind::Callable(callable) => {
+                // Frames are kept when debugging so that the call stack shows every call.
+                self.tail_call_item = (callable.kind == hir::CallableKind::Function
+                    && !self.enable_debug)
+                    .then_some(item.id);
                 let callable = self.lower_callable_decl(callable, &item.attrs);
+                self.tail_call_item = None;
 
                 fir::ItemKind::Callable(callable)
             }
@@ -232,6 +329,7 @@ impl Lowerer {
 
                 fir::ItemKind::Export(name, item)
             }
+            hir::ItemKind::Class(name, _) => fir::ItemKind::Class(self.lower_ident(name)),
         };
         let attrs = lower_attrs(&item.attrs);
         fir::Item {
@@ -310,17 +408,51 @@ impl Lowerer {
         };
         let input = pat.as_ref().map(|p| self.lower_spec_decl_pat(p));
         let block = self.lower_block(block);
+        self.mark_tail_calls();
         fir::SpecDecl {
             id: self.lower_id(decl.id),
             span: decl.span,
             block,
             input,
-            exec_graph: self
-                .exec_graph
-                .drain(..)
-                .chain(once(self.ret_node))
-                .collect(),
+            exec_graph: self.take_interned_exec_graph(true),
+        }
+    }
+
+    /// Replaces the calls of the function to itself that are only followed by jumps to the end of
+    /// the exec graph, or by a return, with tail calls.
+    fn mark_tail_calls(&mut self) {
+        for idx in 0..self.exec_graph.len() {
+            if let ExecGraphNode::Expr(expr) = self.exec_graph[idx] {
+                if self.self_calls.contains(&expr) && self.returns_from(idx + 1) {
+                    self.exec_graph[idx] = ExecGraphNode::TailCall(expr);
+                }
+            }
         }
+        self.self_calls.clear();
+    }
+
+    fn returns_from(&self, mut idx: usize) -> bool {
+        loop {
+            match self.exec_graph.get(idx) {
+                None | Some(ExecGraphNode::Ret | ExecGraphNode::RetFrame) => return true,
+                Some(ExecGraphNode::Jump(target)) => idx = *target as usize,
+                Some(_) => return false,
+            }
+        }
+    }
+
+    /// Takes the exec graph built so far, ending it with a return if requested. Identical graphs,
+    /// like those of specializations with the same implementation, are interned so that they share
+    /// their nodes.
+    fn take_interned_exec_graph(&mut self, ret: bool) -> ExecGraph {
+        let ret_node = ret.then_some(self.ret_node);
+        let nodes: Vec<_> = self.exec_graph.drain(..).chain(ret_node).collect();
+        if let Some(nodes) = self.exec_graphs.get(nodes.as_slice()) {
+            return Rc::clone(nodes).into();
+        }
+        let nodes: Rc<[ExecGraphNode]> = nodes.into();
+        self.exec_graphs.insert(Rc::clone(&nodes));
+        nodes.into()
     }
 
     fn lower_spec_decl_pat(&mut self, pat: &hir::Pat) -> PatId {
@@ -412,6 +544,10 @@ impl Lowerer {
 
     #[allow(clippy::too_many_lines)]
     fn lower_expr(&mut self, expr: &hir::Expr) -> ExprId {
+        if let Some(operand) = self.erased_quantity_operand(expr) {
+            return self.lower_expr(operand);
+        }
+
         let id = self.assigner.next_expr();
         let graph_start_idx = self.exec_graph.len();
         let ty = self.lower_ty(&expr.ty);
@@ -497,6 +633,15 @@ impl Lowerer {
                 }
                 fir::ExprKind::AssignOp(lower_binop(*op), lhs, rhs)
             }
+            hir::ExprKind::AssignField(container, hir::Field::Path(_), replace)
+                if self.quantity_base_ty(&container.ty).is_some() =>
+            {
+                // Assigning the only field of a quantity assigns the whole value.
+                let idx = self.exec_graph.len();
+                let container = self.lower_expr(container);
+                self.exec_graph.drain(idx..);
+                fir::ExprKind::Assign(container, self.lower_expr(replace))
+            }
             hir::ExprKind::AssignField(container, field, replace) => {
                 let field = lower_field(field);
                 let replace = self.lower_expr(replace);
@@ -552,6 +697,11 @@ impl Lowerer {
             }
             hir::ExprKind::Block(block) => fir::ExprKind::Block(self.lower_block(block)),
             hir::ExprKind::Call(callee, arg) => {
+                if let hir::ExprKind::Var(hir::Res::Item(item), _) = &callee.kind {
+                    if item.package.is_none() && Some(item.item) == self.tail_call_item {
+                        self.self_calls.insert(id);
+                    }
+                }
                 let call = self.lower_expr(callee);
                 self.exec_graph.push(ExecGraphNode::Store);
                 let arg = self.lower_expr(arg);
@@ -705,6 +855,7 @@ impl Lowerer {
             hir::ExprKind::Err => panic!("error expr should not be present"),
             hir::ExprKind::For(..) => panic!("for-loop should be eliminated by passes"),
             hir::ExprKind::Hole => fir::ExprKind::Hole, // allowed for discards
+            hir::ExprKind::Match(..) => panic!("match should be eliminated by passes"),
             hir::ExprKind::Repeat(..) => panic!("repeat-loop should be eliminated by passes"),
         };
 
@@ -742,10 +893,10 @@ impl Lowerer {
 
     fn lower_string_component(&mut self, component: &hir::StringComponent) -> fir::StringComponent {
         match component {
-            hir::StringComponent::Expr(expr) => {
+            hir::StringComponent::Expr(expr, format) => {
                 let expr = self.lower_expr(expr);
                 self.exec_graph.push(ExecGraphNode::Store);
-                fir::StringComponent::Expr(expr)
+                fir::StringComponent::Expr(expr, *format)
             }
             hir::StringComponent::Lit(str) => fir::StringComponent::Lit(Rc::clone(str)),
         }
@@ -812,10 +963,21 @@ impl Lowerer {
         let span = udt.span;
         let name = udt.name.clone();
         let definition = self.lower_udt_defn(&udt.definition);
+        let variants = udt
+            .variants
+            .iter()
+            .map(|variant| qsc_fir::ty::UdtVariant {
+                span: variant.span,
+                name: variant.name.clone(),
+                ty: self.lower_ty(&variant.ty),
+                item: lower_local_item_id(variant.item),
+            })
+            .collect();
         qsc_fir::ty::Udt {
             span,
             name,
             definition,
+            variants,
         }
     }
 
@@ -866,7 +1028,10 @@ impl Lowerer {
             qsc_hir::ty::Ty::Tuple(tys) => {
                 qsc_fir::ty::Ty::Tuple(tys.iter().map(|ty| self.lower_ty(ty)).collect())
             }
-            qsc_hir::ty::Ty::Udt(_, res) => qsc_fir::ty::Ty::Udt(self.lower_res(res)),
+            qsc_hir::ty::Ty::Udt(_, res, _) => match self.quantity_base_ty(ty) {
+                Some(base) => base.clone(),
+                None => qsc_fir::ty::Ty::Udt(self.lower_res(res)),
+            },
             qsc_hir::ty::Ty::Err => qsc_fir::ty::Ty::Err,
         }
     }
@@ -920,7 +1085,7 @@ impl Lowerer {
                 power: self.lower_ty(power),
             },
             HirClass::Add => FirClass::Add,
-            HirClass::NonNativeClass(name) => FirClass::NonNativeClass(name.clone()),
+            HirClass::NonNativeClass { name, .. } => FirClass::NonNativeClass(name.clone()),
             HirClass::Iterable { item } => FirClass::Iterable {
                 item: self.lower_ty(item),
             },
@@ -943,10 +1108,15 @@ fn lower_attrs(attrs: &[hir::Attr]) -> Vec<fir::Attr> {
             hir::Attr::EntryPoint => Some(fir::Attr::EntryPoint),
             hir::Attr::Measurement => Some(fir::Attr::Measurement),
             hir::Attr::Reset => Some(fir::Attr::Reset),
+            hir::Attr::Pure => Some(fir::Attr::Pure),
+            hir::Attr::HostCallable => Some(fir::Attr::HostCallable),
+            hir::Attr::Quantity => Some(fir::Attr::Quantity),
+            hir::Attr::OutputName(names) => Some(fir::Attr::OutputName(names.clone())),
             hir::Attr::SimulatableIntrinsic
             | hir::Attr::Unimplemented
             | hir::Attr::Config
-            | hir::Attr::Test => None,
+            | hir::Attr::Test
+            | hir::Attr::Const => None,
         })
         .collect()
 }
@@ -158,9 +158,17 @@ impl ValidWordCollector {
 /// `operation Foo(x: Int , y: String) : Unit {}|`
 ///  - at `EOF`
 ///
+/// Inside a string, or the text around the holes of an interpolated string:
+/// `let s = $"Hello, {name}! W|elcome.";`
+///  - not at any token
+///
 fn cursor_at_token(cursor_offset: u32, next_token: Token, scanner_offset: u32) -> bool {
     match next_token.kind {
         // Order matters here as the cases overlap.
+        TokenKind::String(_) => {
+            // next token is string text, so only count if cursor is before the token
+            scanner_offset <= cursor_offset && cursor_offset <= next_token.span.lo
+        }
         TokenKind::Ident
         | TokenKind::Keyword(_)
         | TokenKind::ClosedBinOp(ClosedBinOp::And | ClosedBinOp::Or)
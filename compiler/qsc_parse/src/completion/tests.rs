@@ -42,7 +42,7 @@ fn end_of_keyword() {
         "namespace Foo { open| ",
         &expect![[r"
             WordKinds(
-                Export | Function | Import | Internal | Newtype | Open | Operation | Struct,
+                Class | Const | Enum | Export | Function | Import | Internal | Newtype | Open | Operation | Struct,
             )
         "]],
     );
@@ -115,7 +115,7 @@ fn in_whitespace() {
         "namespace MyQuantumApp { open Microsoft.Quantum.Diagnostics; |     }",
         &expect![[r"
             WordKinds(
-                Export | Function | Import | Internal | Newtype | Open | Operation | Struct,
+                Class | Const | Enum | Export | Function | Import | Internal | Newtype | Open | Operation | Struct,
             )
         "]],
     );
@@ -127,7 +127,7 @@ fn after_semicolon() {
         "namespace MyQuantumApp { open Microsoft.Quantum.Diagnostics;|      }",
         &expect![[r"
             WordKinds(
-                Export | Function | Import | Internal | Newtype | Open | Operation | Struct,
+                Class | Const | Enum | Export | Function | Import | Internal | Newtype | Open | Operation | Struct,
             )
         "]],
     );
@@ -212,7 +212,7 @@ fn implicit_namespace_items_empty() {
         "|",
         &expect![[r"
             WordKinds(
-                Export | Function | Import | Internal | Namespace | Newtype | Open | Operation | Struct,
+                Class | Const | Enum | Export | Function | Import | Internal | Namespace | Newtype | Open | Operation | Struct,
             )
         "]],
     );
@@ -229,7 +229,7 @@ fn implicit_namespace_items_beginning() {
         "| operation Foo() : Unit {}",
         &expect![[r"
             WordKinds(
-                Export | Function | Import | Internal | Namespace | Newtype | Open | Operation | Struct,
+                Class | Const | Enum | Export | Function | Import | Internal | Namespace | Newtype | Open | Operation | Struct,
             )
         "]],
     );
@@ -241,7 +241,7 @@ fn implicit_namespace_items_end() {
         "operation Foo() : Unit {} |",
         &expect![[r"
             WordKinds(
-                Export | Function | Import | Internal | Newtype | Open | Operation | Struct,
+                Class | Const | Enum | Export | Function | Import | Internal | Newtype | Open | Operation | Struct,
             )
         "]],
     );
@@ -378,3 +378,63 @@ fn keyword_after_incomplete_path() {
         "]],
     );
 }
+
+#[test]
+fn interpolated_string_hole() {
+    check_valid_words(
+        r#"namespace Foo { operation Bar() : Unit { let x = $"a {|} b"; } }"#,
+        &expect![[r#"
+            WordKinds(
+                PathExpr | AdjointUpper | ControlledUpper | Fail | False | For | If | Match | New | Not | One | PauliI | PauliX | PauliY | PauliZ | Repeat | Return | Set | True | Underscore | While | Within | Zero,
+            )
+        "#]],
+    );
+}
+
+#[test]
+fn interpolated_string_hole_after_text() {
+    check_valid_words(
+        r#"namespace Foo { operation Bar() : Unit { let x = $"a {x} b {y.|}"; } }"#,
+        &expect![[r#"
+            WordKinds(
+                PathSegment,
+            )
+        "#]],
+    );
+}
+
+#[test]
+fn interpolated_string_text() {
+    check_valid_words(
+        r#"namespace Foo { operation Bar() : Unit { let x = $"a|b {x}"; } }"#,
+        &expect![[r#"
+            WordKinds(
+                0x0,
+            )
+        "#]],
+    );
+}
+
+#[test]
+fn interpolated_string_text_after_hole() {
+    check_valid_words(
+        r#"namespace Foo { operation Bar() : Unit { let x = $"ab {x} c|d"; } }"#,
+        &expect![[r#"
+            WordKinds(
+                0x0,
+            )
+        "#]],
+    );
+}
+
+#[test]
+fn string_text() {
+    check_valid_words(
+        r#"namespace Foo { operation Bar() : Unit { let x = "a|b"; } }"#,
+        &expect![[r#"
+            WordKinds(
+                0x0,
+            )
+        "#]],
+    );
+}
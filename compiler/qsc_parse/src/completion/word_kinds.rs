@@ -86,12 +86,15 @@ bitflags! {
         const Auto = keyword_bit(Keyword::Auto);
         const Body = keyword_bit(Keyword::Body);
         const Borrow = keyword_bit(Keyword::Borrow);
+        const Class = keyword_bit(Keyword::Class);
+        const Const = keyword_bit(Keyword::Const);
         const Controlled = keyword_bit(Keyword::Controlled);
         const ControlledUpper = keyword_bit(Keyword::ControlledUpper);
         const Ctl = keyword_bit(Keyword::Ctl);
         const Distribute = keyword_bit(Keyword::Distribute);
         const Elif = keyword_bit(Keyword::Elif);
         const Else = keyword_bit(Keyword::Else);
+        const Enum = keyword_bit(Keyword::Enum);
         const Export = keyword_bit(Keyword::Export);
         const Fail = keyword_bit(Keyword::Fail);
         const False = keyword_bit(Keyword::False);
@@ -106,6 +109,7 @@ bitflags! {
         const Invert = keyword_bit(Keyword::Invert);
         const Is = keyword_bit(Keyword::Is);
         const Let = keyword_bit(Keyword::Let);
+        const Match = keyword_bit(Keyword::Match);
         const Mutable = keyword_bit(Keyword::Mutable);
         const Namespace = keyword_bit(Keyword::Namespace);
         const Newtype = keyword_bit(Keyword::Newtype);
@@ -24,10 +24,12 @@ use crate::{
 use num_bigint::BigInt;
 use num_traits::Num;
 use qsc_ast::ast::{
-    self, BinOp, CallableKind, Expr, ExprKind, FieldAccess, FieldAssign, Functor, Lit, NodeId, Pat,
-    PatKind, PathKind, Pauli, StringComponent, TernOp, UnOp,
+    self, BinOp, CallableKind, Expr, ExprKind, FieldAccess, FieldAssign, Functor, Lit, MatchArm,
+    MatchPat, NodeId, Pat, PatKind, Path, PathKind, Pauli, StringComponent, TernOp, UnOp,
+};
+use qsc_data_structures::{
+    format_spec::FormatSpec, language_features::LanguageFeatures, span::Span,
 };
-use qsc_data_structures::{language_features::LanguageFeatures, span::Span};
 use std::{result, str::FromStr};
 
 struct PrefixOp {
@@ -47,6 +49,7 @@ enum OpKind {
     AssignUpdate,
     AssignBinary(BinOp),
     Ternary(TernOp, TokenKind, Assoc),
+    Question,
     Rich(fn(&mut ParserContext, Box<Expr>) -> Result<Box<ExprKind>>),
 }
 
@@ -68,6 +71,8 @@ enum Assoc {
     Right,
 }
 
+const COND_PRECEDENCE: u8 = 1;
+
 const LAMBDA_PRECEDENCE: u8 = 1;
 
 const RANGE_PRECEDENCE: u8 = 1;
@@ -98,6 +103,7 @@ pub(super) fn is_stmt_final(kind: &ExprKind) -> bool {
             | ExprKind::Conjugate(..)
             | ExprKind::For(..)
             | ExprKind::If(..)
+            | ExprKind::Match(..)
             | ExprKind::Repeat(..)
             | ExprKind::While(..)
     )
@@ -105,28 +111,55 @@ pub(super) fn is_stmt_final(kind: &ExprKind) -> bool {
 
 fn expr_op(s: &mut ParserContext, context: OpContext) -> Result<Box<Expr>> {
     let lo = s.peek().span.lo;
+    let (lhs, is_cond) = expr_op_or_cond(s, context)?;
+    if is_cond {
+        cond_op(s, lo, lhs)
+    } else {
+        Ok(lhs)
+    }
+}
+
+/// Parses an expression whose operators bind at least as tightly as the context allows. A `?`
+/// followed by the start of an expression begins a conditional, which binds more loosely than any
+/// operator that can appear before it. If the context binds too tightly for the conditional, the
+/// expression before the `?` is returned along with `true`, so that an enclosing context can parse
+/// the rest of the conditional.
+fn expr_op_or_cond(s: &mut ParserContext, context: OpContext) -> Result<(Box<Expr>, bool)> {
+    let lo = s.peek().span.lo;
 
     s.expect(WordKinds::AdjointUpper | WordKinds::ControlledUpper | WordKinds::Not);
-    let mut lhs = if let Some(op) = prefix_op(op_name(s)) {
+    let (mut lhs, mut is_cond) = if let Some(op) = prefix_op(op_name(s)) {
         s.advance();
-        let rhs = expr_op(s, OpContext::Precedence(op.precedence))?;
-        Box::new(Expr {
+        let (rhs, is_cond) = expr_op_or_cond(s, OpContext::Precedence(op.precedence))?;
+        let lhs = Box::new(Expr {
             id: NodeId::default(),
             span: s.span(lo),
             kind: Box::new(ExprKind::UnOp(op.kind, rhs)),
-        })
+        });
+        (lhs, is_cond)
     } else {
-        expr_base(s)?
+        (expr_base(s)?, false)
     };
 
     let min_precedence = match context {
         OpContext::Precedence(p) => p,
-        OpContext::Stmt if is_stmt_final(&lhs.kind) => return Ok(lhs),
+        OpContext::Stmt if !is_cond && is_stmt_final(&lhs.kind) => return Ok((lhs, false)),
         OpContext::Stmt => 0,
     };
 
     s.expect(WordKinds::And | WordKinds::Or);
-    while let Some(op) = mixfix_op(op_name(s)) {
+    loop {
+        if is_cond {
+            if COND_PRECEDENCE < min_precedence {
+                return Ok((lhs, true));
+            }
+            lhs = cond_op(s, lo, lhs)?;
+            is_cond = false;
+        }
+
+        let Some(op) = mixfix_op(op_name(s)) else {
+            break;
+        };
         if op.precedence < min_precedence {
             break;
         }
@@ -134,6 +167,11 @@ fn expr_op(s: &mut ParserContext, context: OpContext) -> Result<Box<Expr>> {
         s.advance();
         let kind = match op.kind {
             OpKind::Postfix(kind) => Box::new(ExprKind::UnOp(kind, lhs)),
+            OpKind::Question if starts_expr(s.peek().kind) => {
+                is_cond = true;
+                continue;
+            }
+            OpKind::Question => Box::new(ExprKind::UnOp(UnOp::Propagate, lhs)),
             OpKind::Assign => {
                 let rhs = expr_op(s, OpContext::Precedence(op.precedence))?;
                 Box::new(ExprKind::Assign(lhs, rhs))
@@ -150,14 +188,16 @@ fn expr_op(s: &mut ParserContext, context: OpContext) -> Result<Box<Expr>> {
             }
             OpKind::Binary(kind, assoc) => {
                 let precedence = next_precedence(op.precedence, assoc);
-                let rhs = expr_op(s, OpContext::Precedence(precedence))?;
+                let rhs;
+                (rhs, is_cond) = expr_op_or_cond(s, OpContext::Precedence(precedence))?;
                 Box::new(ExprKind::BinOp(kind, lhs, rhs))
             }
             OpKind::Ternary(kind, delim, assoc) => {
                 let mid = expr(s)?;
                 token(s, delim)?;
                 let precedence = next_precedence(op.precedence, assoc);
-                let rhs = expr_op(s, OpContext::Precedence(precedence))?;
+                let rhs;
+                (rhs, is_cond) = expr_op_or_cond(s, OpContext::Precedence(precedence))?;
                 Box::new(ExprKind::TernOp(kind, lhs, mid, rhs))
             }
             OpKind::Rich(f) => f(s, lhs)?,
@@ -170,7 +210,81 @@ fn expr_op(s: &mut ParserContext, context: OpContext) -> Result<Box<Expr>> {
         });
     }
 
-    Ok(lhs)
+    Ok((lhs, false))
+}
+
+/// Parses the rest of a conditional after its condition and the `?`:
+/// `cond ? a | b`
+///        ^^^^^^ rest
+fn cond_op(s: &mut ParserContext, lo: u32, cond: Box<Expr>) -> Result<Box<Expr>> {
+    let continues_operand = continues_operand(s.peek().kind);
+    let mid = expr(s)?;
+    if continues_operand && s.peek().kind != TokenKind::Bar {
+        return Err(Error::new(ErrorKind::MissingCondBar(
+            s.peek().kind,
+            s.peek().span,
+        )));
+    }
+    token(s, TokenKind::Bar)?;
+    let rhs = expr_op(s, OpContext::Precedence(COND_PRECEDENCE))?;
+    Ok(Box::new(Expr {
+        id: NodeId::default(),
+        span: s.span(lo),
+        kind: Box::new(ExprKind::TernOp(TernOp::Cond, cond, mid, rhs)),
+    }))
+}
+
+/// Whether a token that begins the first branch of a conditional could instead continue the
+/// operand of a postfix `?`, like the `-` in `opt? - 1` or the `[` in `opt?[0]`. If the branch is
+/// not followed by a `|`, the `?` was most likely meant to propagate.
+fn continues_operand(kind: TokenKind) -> bool {
+    matches!(
+        kind,
+        TokenKind::ClosedBinOp(ClosedBinOp::Minus | ClosedBinOp::Plus)
+            | TokenKind::Open(Delim::Bracket | Delim::Paren)
+    )
+}
+
+/// Whether a token can begin an expression, which decides if a `?` begins a conditional or is the
+/// postfix operator that propagates a failed variant of an enum.
+fn starts_expr(kind: TokenKind) -> bool {
+    match kind {
+        TokenKind::BigInt(_)
+        | TokenKind::DotDotDot
+        | TokenKind::Float
+        | TokenKind::Ident
+        | TokenKind::Int(_)
+        | TokenKind::Open(_)
+        | TokenKind::String(_)
+        | TokenKind::TildeTildeTilde
+        | TokenKind::ClosedBinOp(ClosedBinOp::Minus | ClosedBinOp::Plus) => true,
+        TokenKind::Keyword(keyword) => matches!(
+            keyword,
+            Keyword::AdjointUpper
+                | Keyword::ControlledUpper
+                | Keyword::Fail
+                | Keyword::False
+                | Keyword::For
+                | Keyword::If
+                | Keyword::Match
+                | Keyword::New
+                | Keyword::Not
+                | Keyword::One
+                | Keyword::PauliI
+                | Keyword::PauliX
+                | Keyword::PauliY
+                | Keyword::PauliZ
+                | Keyword::Repeat
+                | Keyword::Return
+                | Keyword::Set
+                | Keyword::True
+                | Keyword::Underscore
+                | Keyword::While
+                | Keyword::Within
+                | Keyword::Zero
+        ),
+        _ => false,
+    }
 }
 
 fn expr_base(s: &mut ParserContext) -> Result<Box<Expr>> {
@@ -212,6 +326,8 @@ fn expr_base(s: &mut ParserContext) -> Result<Box<Expr>> {
         Ok(Box::new(ExprKind::For(vars, iter, body)))
     } else if token(s, TokenKind::Keyword(Keyword::If)).is_ok() {
         expr_if(s)
+    } else if token(s, TokenKind::Keyword(Keyword::Match)).is_ok() {
+        expr_match(s)
     } else if let Some(components) = opt(s, expr_interpolate)? {
         Ok(Box::new(ExprKind::Interpolate(
             components.into_boxed_slice(),
@@ -345,6 +461,64 @@ fn expr_if(s: &mut ParserContext) -> Result<Box<ExprKind>> {
     Ok(Box::new(ExprKind::If(cond, body, otherwise)))
 }
 
+/// A match expression excluding the `match` keyword, e.g. `a { Zero => b, One => c }`. Arms are
+/// separated by commas, which can be left out after an arm whose body is a block.
+fn expr_match(s: &mut ParserContext) -> Result<Box<ExprKind>> {
+    let expr = expr(s)?;
+    token(s, TokenKind::Open(Delim::Brace))?;
+    let mut arms = Vec::new();
+    while token(s, TokenKind::Close(Delim::Brace)).is_err() {
+        let arm = match_arm(s)?;
+        let needs_comma = !matches!(*arm.body.kind, ExprKind::Block(..));
+        arms.push(arm);
+        if token(s, TokenKind::Comma).is_err() && needs_comma {
+            token(s, TokenKind::Close(Delim::Brace))?;
+            break;
+        }
+    }
+    Ok(Box::new(ExprKind::Match(expr, arms.into_boxed_slice())))
+}
+
+fn match_arm(s: &mut ParserContext) -> Result<Box<MatchArm>> {
+    let lo = s.peek().span.lo;
+    let pat = if let Some(lit) = lit(s)? {
+        MatchPat::Lit(Box::new(Expr {
+            id: NodeId::default(),
+            span: s.span(lo),
+            kind: Box::new(ExprKind::Lit(Box::new(lit))),
+        }))
+    } else {
+        let binding = pat(s)?;
+        // A name followed by a pattern in parentheses is an enum variant and its payload.
+        let variant = match &*binding.kind {
+            PatKind::Bind(name, None) if s.peek().kind == TokenKind::Open(Delim::Paren) => {
+                Some(name.clone())
+            }
+            _ => None,
+        };
+        match variant {
+            Some(name) => {
+                let path = Box::new(Path {
+                    id: NodeId::default(),
+                    span: name.span,
+                    segments: None,
+                    name,
+                });
+                MatchPat::Variant(path, pat(s)?)
+            }
+            None => MatchPat::Bind(binding),
+        }
+    };
+    token(s, TokenKind::FatArrow)?;
+    let body = expr(s)?;
+    Ok(Box::new(MatchArm {
+        id: NodeId::default(),
+        span: s.span(lo),
+        pat: Box::new(pat),
+        body,
+    }))
+}
+
 fn expr_array(s: &mut ParserContext) -> Result<Box<ExprKind>> {
     token(s, TokenKind::Open(Delim::Bracket))?;
     let kind = expr_array_core(s)?;
@@ -424,7 +598,22 @@ fn expr_interpolate(s: &mut ParserContext) -> Result<Vec<StringComponent>> {
 
     s.advance();
     while end == InterpolatedEnding::LBrace {
-        components.push(StringComponent::Expr(expr(s)?));
+        let expr = expr(s)?;
+        let format = if s.peek().kind == TokenKind::FormatSpec {
+            let spec = &s.read()[1..];
+            let format = FormatSpec::parse(spec);
+            if format.is_none() {
+                s.push_error(Error::new(ErrorKind::FormatSpec(
+                    spec.to_string(),
+                    s.peek().span,
+                )));
+            }
+            s.advance();
+            format
+        } else {
+            None
+        };
+        components.push(StringComponent::Expr(expr, format));
 
         let token = s.peek();
         let TokenKind::String(StringToken::Interpolated(InterpolatedStart::RBrace, next_end)) =
@@ -509,8 +698,12 @@ fn lit_token(lexeme: &str, token: Token) -> Result<Option<Lit>> {
                     .next()
                     .expect("character should be found at index");
                 let index: u32 = index.try_into().expect("index should fit into u32");
+                let len: u32 = ch
+                    .len_utf8()
+                    .try_into()
+                    .expect("length should fit into u32");
                 let lo = token.span.lo + index + 2;
-                let span = Span { lo, hi: lo + 1 };
+                let span = Span { lo, hi: lo + len };
                 Error::new(ErrorKind::Escape(ch, span))
             })?;
             Ok(Some(Lit::String(string.into())))
@@ -528,31 +721,23 @@ fn lit_token(lexeme: &str, token: Token) -> Result<Option<Lit>> {
 }
 
 fn lit_int(lexeme: &str, radix: u32) -> Option<i64> {
-    let multiplier = i64::from(radix);
-    lexeme
-        .chars()
-        .filter(|&c| c != '_')
-        .try_rfold((0i64, 1i64, false), |(value, place, mut overflow), c| {
-            let (increment, over) = i64::from(c.to_digit(radix)?).overflowing_mul(place);
-            overflow |= over;
-
-            let (new_value, over) = value.overflowing_add(increment);
-            overflow |= over;
-
-            // Only treat as overflow if the value is not i64::MIN, since we need to allow once special
-            // case of overflow to allow for minimum value literals.
-            if overflow && new_value != i64::MIN {
-                return None;
-            }
+    let mut digits = lexeme.chars().filter(|&c| c != '_').peekable();
 
-            let (new_place, over) = place.overflowing_mul(multiplier);
-            overflow |= over;
+    // A radix prefix, as in `0x`, must be followed by at least one digit.
+    digits.peek()?;
 
-            // If the place overflows, we can still accept the value as long as it's the last digit.
-            // Pass the overflow forward so that it fails if there are more digits.
-            Some((new_value, new_place, overflow))
-        })
-        .map(|(value, _, _)| value)
+    // Only the value itself can overflow, so any number of leading zeros is accepted.
+    let value = digits.try_fold(0u64, |value, c| {
+        value
+            .checked_mul(radix.into())?
+            .checked_add(c.to_digit(radix)?.into())
+    })?;
+
+    // Since we need to support literals of value i64::MIN while also parsing the negative sign as a unary
+    // operator, the absolute value of i64::MIN is accepted and wraps to i64::MIN.
+    i64::try_from(value)
+        .ok()
+        .or_else(|| (value == i64::MIN.unsigned_abs()).then_some(i64::MIN))
 }
 
 fn prefix_op(name: OpName) -> Option<PrefixOp> {
@@ -620,10 +805,6 @@ fn mixfix_op(name: OpName) -> Option<MixfixOp> {
             kind: OpKind::Ternary(TernOp::Update, TokenKind::LArrow, Assoc::Left),
             precedence: 1,
         }),
-        OpName::Token(TokenKind::Question) => Some(MixfixOp {
-            kind: OpKind::Ternary(TernOp::Cond, TokenKind::Bar, Assoc::Right),
-            precedence: 1,
-        }),
         OpName::Token(TokenKind::ClosedBinOp(ClosedBinOp::Or)) => Some(MixfixOp {
             kind: OpKind::Binary(closed_bin_op(ClosedBinOp::Or), Assoc::Left),
             precedence: 2,
@@ -708,6 +889,10 @@ fn mixfix_op(name: OpName) -> Option<MixfixOp> {
             kind: OpKind::Postfix(UnOp::Unwrap),
             precedence: 15,
         }),
+        OpName::Token(TokenKind::Question) => Some(MixfixOp {
+            kind: OpKind::Question,
+            precedence: 15,
+        }),
         OpName::Token(TokenKind::ColonColon | TokenKind::Dot) => Some(MixfixOp {
             kind: OpKind::Rich(recovering_field_op),
             precedence: 15,
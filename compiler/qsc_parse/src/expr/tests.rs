@@ -160,6 +160,204 @@ fn lit_int_binary() {
     check(expr, "0b10110", &expect!["Expr _id_ [0-7]: Lit: Int(22)"]);
 }
 
+#[test]
+fn lit_int_prefix_without_digits() {
+    check(
+        expr,
+        "0x",
+        &expect![[r#"
+            Error(
+                Lit(
+                    "integer",
+                    Span {
+                        lo: 0,
+                        hi: 2,
+                    },
+                ),
+            )
+        "#]],
+    );
+}
+
+#[test]
+fn lit_bigint_prefix_without_digits() {
+    check(
+        expr,
+        "0b_L",
+        &expect![[r#"
+            Error(
+                Lit(
+                    "big-integer",
+                    Span {
+                        lo: 0,
+                        hi: 4,
+                    },
+                ),
+            )
+        "#]],
+    );
+}
+
+#[test]
+fn lit_int_prefix_with_only_underscores() {
+    check(
+        expr,
+        "0x__",
+        &expect![[r#"
+        Error(
+            Lit(
+                "integer",
+                Span {
+                    lo: 0,
+                    hi: 4,
+                },
+            ),
+        )
+    "#]],
+    );
+}
+
+#[test]
+fn lit_int_prefix_without_digits_of_radix() {
+    check(
+        expr,
+        "0o8",
+        &expect![[r#"
+        Error(
+            Lit(
+                "integer",
+                Span {
+                    lo: 0,
+                    hi: 2,
+                },
+            ),
+        )
+    "#]],
+    );
+}
+
+#[test]
+fn lit_bigint_prefix_without_digits_or_underscores() {
+    check(
+        expr,
+        "0xL",
+        &expect![[r#"
+        Error(
+            Lit(
+                "big-integer",
+                Span {
+                    lo: 0,
+                    hi: 3,
+                },
+            ),
+        )
+    "#]],
+    );
+}
+
+#[test]
+fn lit_int_leading_zeros_beyond_max_digits() {
+    check(
+        expr,
+        "0000000000000000000000042",
+        &expect!["Expr _id_ [0-25]: Lit: Int(42)"],
+    );
+}
+
+#[test]
+fn lit_int_leading_zeros_beyond_max_digits_binary() {
+    check(
+        expr,
+        "0b00000000000000000000000000000000000000000000000000000000000000001",
+        &expect!["Expr _id_ [0-67]: Lit: Int(1)"],
+    );
+}
+
+#[test]
+fn lit_int_overflow_min_leading_zeros_hexadecimal() {
+    check(
+        expr,
+        "0x0000_8000_0000_0000_0000",
+        &expect!["Expr _id_ [0-26]: Lit: Int(-9223372036854775808)"],
+    );
+}
+
+#[test]
+fn lit_int_too_big_by_many_digits() {
+    check(
+        expr,
+        "99999999999999999999999999999999999999",
+        &expect![[r#"
+        Error(
+            Lit(
+                "integer",
+                Span {
+                    lo: 0,
+                    hi: 38,
+                },
+            ),
+        )
+    "#]],
+    );
+}
+
+#[test]
+fn lit_int_too_big_by_many_digits_hexadecimal() {
+    check(
+        expr,
+        "0xffff_ffff_ffff_ffff_ffff",
+        &expect![[r#"
+        Error(
+            Lit(
+                "integer",
+                Span {
+                    lo: 0,
+                    hi: 26,
+                },
+            ),
+        )
+    "#]],
+    );
+}
+
+#[test]
+fn lit_double_exponent_sign_without_digits() {
+    check(
+        expr,
+        "1e+",
+        &expect![[r#"
+        Error(
+            Lit(
+                "floating-point",
+                Span {
+                    lo: 0,
+                    hi: 3,
+                },
+            ),
+        )
+    "#]],
+    );
+}
+
+#[test]
+fn lit_int_prefix_followed_by_dot() {
+    check(
+        expr,
+        "0x.",
+        &expect![[r#"
+        Error(
+            Lit(
+                "integer",
+                Span {
+                    lo: 0,
+                    hi: 2,
+                },
+            ),
+        )
+    "#]],
+    );
+}
+
 #[test]
 fn lit_bigint() {
     check(expr, "123L", &expect!["Expr _id_ [0-4]: Lit: BigInt(123)"]);
@@ -440,6 +638,34 @@ fn lit_string_unknown_escape() {
     );
 }
 
+#[test]
+fn lit_string_unknown_escape_after_multibyte_chars() {
+    check(
+        expr,
+        r#""🎉é\é""#,
+        &expect![[r#"
+        Error(
+            Escape(
+                'é',
+                Span {
+                    lo: 8,
+                    hi: 10,
+                },
+            ),
+        )
+    "#]],
+    );
+}
+
+#[test]
+fn lit_string_multibyte_chars() {
+    check(
+        expr,
+        r#""🎉 héllo \"€\"""#,
+        &expect![[r#"Expr _id_ [0-21]: Lit: String("🎉 héllo \"€\"")"#]],
+    );
+}
+
 #[test]
 fn lit_string_unmatched_quote() {
     check(
@@ -649,6 +875,115 @@ fn if_elif_else() {
     );
 }
 
+#[test]
+fn match_expr() {
+    check(
+        expr,
+        "match r { Zero => x, _ => { y } }",
+        &expect![[r#"
+            Expr _id_ [0-33]: Match:
+                Expr _id_ [6-7]: Path: Path _id_ [6-7] (Ident _id_ [6-7] "r")
+                MatchArm _id_ [10-19]:
+                    Expr _id_ [10-14]: Lit: Result(Zero)
+                    Expr _id_ [18-19]: Path: Path _id_ [18-19] (Ident _id_ [18-19] "x")
+                MatchArm _id_ [21-31]:
+                    Pat _id_ [21-22]: Discard
+                    Expr _id_ [26-31]: Expr Block: Block _id_ [26-31]:
+                        Stmt _id_ [28-29]: Expr: Expr _id_ [28-29]: Path: Path _id_ [28-29] (Ident _id_ [28-29] "y")"#]],
+    );
+}
+
+#[test]
+fn match_block_arms_without_commas() {
+    check(
+        expr,
+        "match p { PauliX => { a } q => { b } }",
+        &expect![[r#"
+            Expr _id_ [0-38]: Match:
+                Expr _id_ [6-7]: Path: Path _id_ [6-7] (Ident _id_ [6-7] "p")
+                MatchArm _id_ [10-25]:
+                    Expr _id_ [10-16]: Lit: Pauli(X)
+                    Expr _id_ [20-25]: Expr Block: Block _id_ [20-25]:
+                        Stmt _id_ [22-23]: Expr: Expr _id_ [22-23]: Path: Path _id_ [22-23] (Ident _id_ [22-23] "a")
+                MatchArm _id_ [26-36]:
+                    Pat _id_ [26-27]: Bind:
+                        Ident _id_ [26-27] "q"
+                    Expr _id_ [31-36]: Expr Block: Block _id_ [31-36]:
+                        Stmt _id_ [33-34]: Expr: Expr _id_ [33-34]: Path: Path _id_ [33-34] (Ident _id_ [33-34] "b")"#]],
+    );
+}
+
+#[test]
+fn match_variant_arms() {
+    check(
+        expr,
+        "match s { Circle(r) => r, Empty() => 0.0 }",
+        &expect![[r#"
+            Expr _id_ [0-42]: Match:
+                Expr _id_ [6-7]: Path: Path _id_ [6-7] (Ident _id_ [6-7] "s")
+                MatchArm _id_ [10-24]:
+                    Variant (Path _id_ [10-16] (Ident _id_ [10-16] "Circle")): Pat _id_ [16-19]: Paren:
+                        Pat _id_ [17-18]: Bind:
+                            Ident _id_ [17-18] "r"
+                    Expr _id_ [23-24]: Path: Path _id_ [23-24] (Ident _id_ [23-24] "r")
+                MatchArm _id_ [26-40]:
+                    Variant (Path _id_ [26-31] (Ident _id_ [26-31] "Empty")): Pat _id_ [31-33]: Unit
+                    Expr _id_ [37-40]: Lit: Double(0)"#]],
+    );
+}
+
+#[test]
+fn match_paren_scrutinee() {
+    check(
+        expr,
+        "match (r) { _ => x }",
+        &expect![[r#"
+            Expr _id_ [0-20]: Match:
+                Expr _id_ [6-9]: Paren: Expr _id_ [7-8]: Path: Path _id_ [7-8] (Ident _id_ [7-8] "r")
+                MatchArm _id_ [12-18]:
+                    Pat _id_ [12-13]: Discard
+                    Expr _id_ [17-18]: Path: Path _id_ [17-18] (Ident _id_ [17-18] "x")"#]],
+    );
+}
+
+#[test]
+fn contextual_keywords_as_names() {
+    check(
+        expr,
+        "match(x) + enum",
+        &expect![[r#"
+            Expr _id_ [0-15]: BinOp (Add):
+                Expr _id_ [0-8]: Call:
+                    Expr _id_ [0-5]: Path: Path _id_ [0-5] (Ident _id_ [0-5] "match")
+                    Expr _id_ [5-8]: Paren: Expr _id_ [6-7]: Path: Path _id_ [6-7] (Ident _id_ [6-7] "x")
+                Expr _id_ [11-15]: Path: Path _id_ [11-15] (Ident _id_ [11-15] "enum")"#]],
+    );
+}
+
+#[test]
+fn match_arms_without_commas_error() {
+    check(
+        expr,
+        "match b { true => 1 false => 0 }",
+        &expect![[r#"
+            Error(
+                Token(
+                    Close(
+                        Brace,
+                    ),
+                    Keyword(
+                        False,
+                    ),
+                    Span {
+                        lo: 20,
+                        hi: 25,
+                    },
+                ),
+            )
+        "#]],
+    );
+}
+
 #[test]
 fn repeat_until() {
     check(
@@ -1841,6 +2176,272 @@ fn cond_op_right_assoc() {
     );
 }
 
+#[test]
+fn propagate_op() {
+    check(
+        expr,
+        "x?",
+        &expect![[r#"
+            Expr _id_ [0-2]: UnOp (Propagate):
+                Expr _id_ [0-1]: Path: Path _id_ [0-1] (Ident _id_ [0-1] "x")"#]],
+    );
+}
+
+#[test]
+fn propagate_op_binds_tighter_than_binary_op() {
+    check(
+        expr,
+        "x? == y?",
+        &expect![[r#"
+            Expr _id_ [0-8]: BinOp (Eq):
+                Expr _id_ [0-2]: UnOp (Propagate):
+                    Expr _id_ [0-1]: Path: Path _id_ [0-1] (Ident _id_ [0-1] "x")
+                Expr _id_ [6-8]: UnOp (Propagate):
+                    Expr _id_ [6-7]: Path: Path _id_ [6-7] (Ident _id_ [6-7] "y")"#]],
+    );
+}
+
+#[test]
+fn propagate_op_in_call_args() {
+    check(
+        expr,
+        "f(x?, y.z?)",
+        &expect![[r#"
+            Expr _id_ [0-11]: Call:
+                Expr _id_ [0-1]: Path: Path _id_ [0-1] (Ident _id_ [0-1] "f")
+                Expr _id_ [1-11]: Tuple:
+                    Expr _id_ [2-4]: UnOp (Propagate):
+                        Expr _id_ [2-3]: Path: Path _id_ [2-3] (Ident _id_ [2-3] "x")
+                    Expr _id_ [6-10]: UnOp (Propagate):
+                        Expr _id_ [6-9]: Path: Path _id_ [6-9]:
+                            Ident _id_ [6-7] "y"
+                            Ident _id_ [8-9] "z""#]],
+    );
+}
+
+#[test]
+fn propagate_op_before_cond_op() {
+    check(
+        expr,
+        "c?? a | b",
+        &expect![[r#"
+            Expr _id_ [0-9]: TernOp (Cond):
+                Expr _id_ [0-2]: UnOp (Propagate):
+                    Expr _id_ [0-1]: Path: Path _id_ [0-1] (Ident _id_ [0-1] "c")
+                Expr _id_ [4-5]: Path: Path _id_ [4-5] (Ident _id_ [4-5] "a")
+                Expr _id_ [8-9]: Path: Path _id_ [8-9] (Ident _id_ [8-9] "b")"#]],
+    );
+}
+
+#[test]
+fn cond_op_after_binary_op() {
+    check(
+        expr,
+        "a + b ? c | d",
+        &expect![[r#"
+            Expr _id_ [0-13]: TernOp (Cond):
+                Expr _id_ [0-7]: BinOp (Add):
+                    Expr _id_ [0-1]: Path: Path _id_ [0-1] (Ident _id_ [0-1] "a")
+                    Expr _id_ [4-5]: Path: Path _id_ [4-5] (Ident _id_ [4-5] "b")
+                Expr _id_ [8-9]: Path: Path _id_ [8-9] (Ident _id_ [8-9] "c")
+                Expr _id_ [12-13]: Path: Path _id_ [12-13] (Ident _id_ [12-13] "d")"#]],
+    );
+}
+
+#[test]
+fn cond_op_with_unary_mid() {
+    check(
+        expr,
+        "c ? -1 | 1",
+        &expect![[r#"
+            Expr _id_ [0-10]: TernOp (Cond):
+                Expr _id_ [0-1]: Path: Path _id_ [0-1] (Ident _id_ [0-1] "c")
+                Expr _id_ [4-6]: UnOp (Neg):
+                    Expr _id_ [5-6]: Lit: Int(1)
+                Expr _id_ [9-10]: Lit: Int(1)"#]],
+    );
+}
+
+#[test]
+fn propagate_op_before_minus() {
+    check(
+        expr,
+        "(opt?) - 1",
+        &expect![[r#"
+            Expr _id_ [0-10]: BinOp (Sub):
+                Expr _id_ [0-6]: Paren: Expr _id_ [1-5]: UnOp (Propagate):
+                    Expr _id_ [1-4]: Path: Path _id_ [1-4] (Ident _id_ [1-4] "opt")
+                Expr _id_ [9-10]: Lit: Int(1)"#]],
+    );
+}
+
+#[test]
+fn propagate_op_before_plus() {
+    check(
+        expr,
+        "(opt?) + 1",
+        &expect![[r#"
+            Expr _id_ [0-10]: BinOp (Add):
+                Expr _id_ [0-6]: Paren: Expr _id_ [1-5]: UnOp (Propagate):
+                    Expr _id_ [1-4]: Path: Path _id_ [1-4] (Ident _id_ [1-4] "opt")
+                Expr _id_ [9-10]: Lit: Int(1)"#]],
+    );
+}
+
+#[test]
+fn propagate_op_before_index_op() {
+    check(
+        expr,
+        "(opt?)[0]",
+        &expect![[r#"
+            Expr _id_ [0-9]: Index:
+                Expr _id_ [0-6]: Paren: Expr _id_ [1-5]: UnOp (Propagate):
+                    Expr _id_ [1-4]: Path: Path _id_ [1-4] (Ident _id_ [1-4] "opt")
+                Expr _id_ [7-8]: Lit: Int(0)"#]],
+    );
+}
+
+#[test]
+fn propagate_op_before_call_op() {
+    check(
+        expr,
+        "(op?)(q)",
+        &expect![[r#"
+            Expr _id_ [0-8]: Call:
+                Expr _id_ [0-5]: Paren: Expr _id_ [1-4]: UnOp (Propagate):
+                    Expr _id_ [1-3]: Path: Path _id_ [1-3] (Ident _id_ [1-3] "op")
+                Expr _id_ [5-8]: Paren: Expr _id_ [6-7]: Path: Path _id_ [6-7] (Ident _id_ [6-7] "q")"#]],
+    );
+}
+
+#[test]
+fn cond_op_with_unspaced_unary_mid() {
+    check(
+        expr,
+        "c?-1|1",
+        &expect![[r#"
+            Expr _id_ [0-6]: TernOp (Cond):
+                Expr _id_ [0-1]: Path: Path _id_ [0-1] (Ident _id_ [0-1] "c")
+                Expr _id_ [2-4]: UnOp (Neg):
+                    Expr _id_ [3-4]: Lit: Int(1)
+                Expr _id_ [5-6]: Lit: Int(1)"#]],
+    );
+}
+
+#[test]
+fn cond_op_with_unspaced_paren_mid() {
+    check(
+        expr,
+        "c?(a)|b",
+        &expect![[r#"
+            Expr _id_ [0-7]: TernOp (Cond):
+                Expr _id_ [0-1]: Path: Path _id_ [0-1] (Ident _id_ [0-1] "c")
+                Expr _id_ [2-5]: Paren: Expr _id_ [3-4]: Path: Path _id_ [3-4] (Ident _id_ [3-4] "a")
+                Expr _id_ [6-7]: Path: Path _id_ [6-7] (Ident _id_ [6-7] "b")"#]],
+    );
+}
+
+#[test]
+fn cond_op_with_unspaced_array_mid() {
+    check(
+        expr,
+        "c?[x]|[y]",
+        &expect![[r#"
+            Expr _id_ [0-9]: TernOp (Cond):
+                Expr _id_ [0-1]: Path: Path _id_ [0-1] (Ident _id_ [0-1] "c")
+                Expr _id_ [2-5]: Array:
+                    Expr _id_ [3-4]: Path: Path _id_ [3-4] (Ident _id_ [3-4] "x")
+                Expr _id_ [6-9]: Array:
+                    Expr _id_ [7-8]: Path: Path _id_ [7-8] (Ident _id_ [7-8] "y")"#]],
+    );
+}
+
+#[test]
+fn cond_op_without_bar_after_minus() {
+    check(
+        expr,
+        "opt? - 1",
+        &expect![[r#"
+            Error(
+                MissingCondBar(
+                    Eof,
+                    Span {
+                        lo: 8,
+                        hi: 8,
+                    },
+                ),
+            )
+        "#]],
+    );
+}
+
+#[test]
+fn cond_op_without_bar_after_index() {
+    check(
+        expr,
+        "opt?[0]",
+        &expect![[r#"
+            Error(
+                MissingCondBar(
+                    Eof,
+                    Span {
+                        lo: 7,
+                        hi: 7,
+                    },
+                ),
+            )
+        "#]],
+    );
+}
+
+#[test]
+fn cond_op_without_bar_after_call() {
+    check(
+        expr,
+        "op?(q)",
+        &expect![[r#"
+            Error(
+                MissingCondBar(
+                    Eof,
+                    Span {
+                        lo: 6,
+                        hi: 6,
+                    },
+                ),
+            )
+        "#]],
+    );
+}
+
+#[test]
+fn cond_op_with_spaced_unary_mid() {
+    check(
+        expr,
+        "c ? - 1 | + 1",
+        &expect![[r#"
+            Expr _id_ [0-13]: TernOp (Cond):
+                Expr _id_ [0-1]: Path: Path _id_ [0-1] (Ident _id_ [0-1] "c")
+                Expr _id_ [4-7]: UnOp (Neg):
+                    Expr _id_ [6-7]: Lit: Int(1)
+                Expr _id_ [10-13]: UnOp (Pos):
+                    Expr _id_ [12-13]: Lit: Int(1)"#]],
+    );
+}
+
+#[test]
+fn cond_op_with_array_mid() {
+    check(
+        expr,
+        "c ? [1] | []",
+        &expect![[r#"
+            Expr _id_ [0-12]: TernOp (Cond):
+                Expr _id_ [0-1]: Path: Path _id_ [0-1] (Ident _id_ [0-1] "c")
+                Expr _id_ [4-7]: Array:
+                    Expr _id_ [5-6]: Lit: Int(1)
+                Expr _id_ [10-12]: Array:"#]],
+    );
+}
+
 #[test]
 fn field_op() {
     check(
@@ -2456,6 +3057,55 @@ fn interpolated_string_two_braced() {
     );
 }
 
+#[test]
+fn interpolated_string_format_spec() {
+    check(
+        expr,
+        r#"$"{x:>8.3f} and {y:08b}""#,
+        &expect![[r#"
+            Expr _id_ [0-24]: Interpolate:
+                Expr (:>8.3f): Expr _id_ [3-4]: Path: Path _id_ [3-4] (Ident _id_ [3-4] "x")
+                Lit: " and "
+                Expr (:08b): Expr _id_ [17-18]: Path: Path _id_ [17-18] (Ident _id_ [17-18] "y")"#]],
+    );
+}
+
+#[test]
+fn interpolated_string_format_spec_after_field_access() {
+    check(
+        expr,
+        r#"$"{z::Re:.2}""#,
+        &expect![[r#"
+        Expr _id_ [0-13]: Interpolate:
+            Expr (:.2): Expr _id_ [3-8]: Field:
+                Expr _id_ [3-4]: Path: Path _id_ [3-4] (Ident _id_ [3-4] "z")
+                Ident _id_ [6-8] "Re""#]],
+    );
+}
+
+#[test]
+fn interpolated_string_invalid_format_spec() {
+    check(
+        expr,
+        r#"$"{x:q}""#,
+        &expect![[r#"
+        Expr _id_ [0-8]: Interpolate:
+            Expr: Expr _id_ [3-4]: Path: Path _id_ [3-4] (Ident _id_ [3-4] "x")
+
+        [
+            Error(
+                FormatSpec(
+                    "q",
+                    Span {
+                        lo: 4,
+                        hi: 6,
+                    },
+                ),
+            ),
+        ]"#]],
+    );
+}
+
 #[test]
 fn interpolated_string_braced_normal_string() {
     check(
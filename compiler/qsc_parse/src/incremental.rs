@@ -0,0 +1,282 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! Incremental reparsing of edited sources. A [`ParseCache`] remembers the last parse of each
+//! source, and when that source changes it only reparses the top-level items around the edited
+//! range. The items before and after that window are reused from the previous parse, with the
+//! spans of the later items shifted by the change in length. Whenever the edit can't be confined
+//! to the items of a single namespace, or either parse has errors, the whole source is reparsed.
+
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    item,
+    lex::{Delim, Lexer, TokenKind},
+    scan::ParserContext,
+    Error,
+};
+use qsc_ast::{
+    ast::{Ident, Item, Namespace},
+    mut_visit::MutVisitor,
+};
+use qsc_data_structures::{language_features::LanguageFeatures, span::Span};
+use rustc_hash::FxHashMap;
+use std::{ops::Range, sync::Arc};
+
+/// The most recent parse of each source, used to reparse only what an edit touched.
+#[derive(Default)]
+pub struct ParseCache {
+    sources: FxHashMap<Arc<str>, CachedParse>,
+}
+
+struct CachedParse {
+    contents: Arc<str>,
+    language_features: LanguageFeatures,
+    namespaces: Vec<Namespace>,
+    errors: Vec<Error>,
+}
+
+impl ParseCache {
+    /// Parses the namespaces in a source the same way as [`crate::namespaces`], reusing the items
+    /// of the previous parse of the same source that are outside the edited range.
+    #[must_use]
+    pub fn namespaces(
+        &mut self,
+        input: &Arc<str>,
+        source_name: &Arc<str>,
+        language_features: LanguageFeatures,
+    ) -> (Vec<Namespace>, Vec<Error>) {
+        let cached = self
+            .sources
+            .get(source_name)
+            .filter(|cached| cached.language_features == language_features);
+
+        let (namespaces, errors) = match cached {
+            Some(cached) if cached.contents == *input => {
+                (cached.namespaces.clone(), cached.errors.clone())
+            }
+            _ => cached
+                .and_then(|cached| reparse(cached, input))
+                .map_or_else(
+                    || crate::namespaces(input, Some(source_name), language_features),
+                    |namespaces| (namespaces, Vec::new()),
+                ),
+        };
+
+        self.sources.insert(
+            source_name.clone(),
+            CachedParse {
+                contents: input.clone(),
+                language_features,
+                namespaces: namespaces.clone(),
+                errors: errors.clone(),
+            },
+        );
+        (namespaces, errors)
+    }
+
+    /// Forgets the parses of the sources for which `keep` returns false, such as the sources of
+    /// documents that have been closed.
+    pub fn retain(&mut self, mut keep: impl FnMut(&str) -> bool) {
+        self.sources.retain(|source_name, _| keep(source_name));
+    }
+
+    /// The number of sources with a remembered parse.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.sources.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+}
+
+/// The range that differs between two versions of a source, found by trimming their common
+/// prefix and suffix.
+struct Edit {
+    start: u32,
+    old_end: u32,
+    new_end: u32,
+}
+
+impl Edit {
+    fn new(old: &str, new: &str) -> Option<Self> {
+        let (old_bytes, new_bytes) = (old.as_bytes(), new.as_bytes());
+        let prefix = common_len(old_bytes.iter(), new_bytes.iter());
+        let suffix = common_len(
+            old_bytes[prefix..].iter().rev(),
+            new_bytes[prefix..].iter().rev(),
+        );
+        Some(Self {
+            start: prefix.try_into().ok()?,
+            old_end: (old.len() - suffix).try_into().ok()?,
+            new_end: (new.len() - suffix).try_into().ok()?,
+        })
+    }
+
+    /// Maps an offset at or after the end of the edit in the old source to the new source.
+    fn shift(&self, offset: u32) -> u32 {
+        offset - self.old_end + self.new_end
+    }
+}
+
+fn common_len<'a>(a: impl Iterator<Item = &'a u8>, b: impl Iterator<Item = &'a u8>) -> usize {
+    a.zip(b).take_while(|(a, b)| a == b).count()
+}
+
+/// Reparses the items around the edit that turns the cached source into `input`, or returns
+/// `None` if the edit isn't confined to a single namespace's items and a full parse is needed.
+fn reparse(cached: &CachedParse, input: &str) -> Option<Vec<Namespace>> {
+    if !cached.errors.is_empty() {
+        return None;
+    }
+
+    let old = &*cached.contents;
+    let edit = Edit::new(old, input)?;
+    let (index, block) = cached
+        .namespaces
+        .iter()
+        .enumerate()
+        .find_map(|(index, namespace)| {
+            (namespace.span.lo <= edit.start && edit.old_end <= namespace.span.hi)
+                .then(|| block_range(old, namespace))
+                .flatten()
+                .filter(|block| block.start <= edit.start && edit.old_end <= block.end)
+                .map(|block| (index, block))
+        })?;
+
+    let namespace = &cached.namespaces[index];
+    let implicit = is_implicit(namespace);
+    let items = &namespace.items;
+
+    // Items that end before the edit are kept as they are. The first item of an implicit
+    // namespace also takes the doc comment at the top of the file, so it is never reparsed alone.
+    let kept = items
+        .iter()
+        .take_while(|item| item.span.hi < edit.start)
+        .count();
+    if implicit && kept == 0 {
+        return None;
+    }
+
+    // The window always extends over the first item after the edit, since doc comments and
+    // attributes in the edited text attach to the item that follows them.
+    let next = kept
+        + items[kept..]
+            .iter()
+            .take_while(|item| item.span.lo <= edit.old_end)
+            .count();
+    let (window_end, reused) = match items.get(next) {
+        Some(item) => (item.span.hi, next + 1),
+        None => (block.end, next),
+    };
+    let window_start = match kept.checked_sub(1) {
+        Some(prev) => items[prev].span.hi,
+        None => block.start,
+    };
+
+    let window = window_start as usize..edit.shift(window_end) as usize;
+    let mut window_items = parse_window(input.get(window)?, cached.language_features)?;
+    let mut offsetter = Relocate(0, window_start);
+    window_items
+        .iter_mut()
+        .for_each(|item| offsetter.visit_item(item));
+
+    let mut shifter = Relocate(edit.old_end, edit.new_end);
+    let items: Box<[Box<Item>]> = items[..kept]
+        .iter()
+        .cloned()
+        .chain(window_items)
+        .chain(items[reused..].iter().map(|item| {
+            let mut item = item.clone();
+            shifter.visit_item(&mut item);
+            item
+        }))
+        .collect();
+
+    let (span, name) = if implicit {
+        let span = Span {
+            lo: namespace.span.lo,
+            hi: items.last()?.span.hi,
+        };
+        let name = namespace
+            .name
+            .iter()
+            .map(|ident| Ident {
+                span,
+                ..ident.clone()
+            })
+            .collect();
+        (span, name)
+    } else {
+        let span = Span {
+            lo: namespace.span.lo,
+            hi: edit.shift(namespace.span.hi),
+        };
+        (span, namespace.name.clone())
+    };
+
+    let edited = Namespace {
+        id: namespace.id,
+        span,
+        doc: namespace.doc.clone(),
+        name,
+        items,
+    };
+
+    let namespaces = cached.namespaces[..index]
+        .iter()
+        .cloned()
+        .chain(std::iter::once(edited))
+        .chain(cached.namespaces[index + 1..].iter().map(|namespace| {
+            let mut namespace = namespace.clone();
+            shifter.visit_namespace(&mut namespace);
+            namespace
+        }))
+        .collect();
+
+    Some(namespaces)
+}
+
+/// An implicit namespace is named after its source, and its name has the namespace's own span.
+fn is_implicit(namespace: &Namespace) -> bool {
+    namespace
+        .name
+        .first()
+        .is_some_and(|ident| ident.span == namespace.span)
+}
+
+/// The range between the braces of an explicit namespace, or the whole source for an implicit
+/// namespace.
+fn block_range(source: &str, namespace: &Namespace) -> Option<Range<u32>> {
+    if is_implicit(namespace) {
+        return Some(0..source.len().try_into().ok()?);
+    }
+
+    let name_end = namespace.name.last()?.span.hi;
+    let open = Lexer::new(source.get(name_end as usize..)?)
+        .find_map(Result::ok)
+        .filter(|token| token.kind == TokenKind::Open(Delim::Brace))?;
+    Some(name_end + open.span.hi..namespace.span.hi - 1)
+}
+
+/// Parses the items in a window of the source, which must contain nothing else.
+#[allow(clippy::vec_box)]
+fn parse_window(input: &str, language_features: LanguageFeatures) -> Option<Vec<Box<Item>>> {
+    let mut scanner = ParserContext::new(input, language_features);
+    let items = item::parse_namespace_block_contents(&mut scanner).ok()?;
+    (scanner.peek().kind == TokenKind::Eof && scanner.into_errors().is_empty()).then_some(items)
+}
+
+/// Moves every span from an offset in one text to the corresponding offset in another.
+struct Relocate(u32, u32);
+
+impl MutVisitor for Relocate {
+    fn visit_span(&mut self, span: &mut Span) {
+        span.lo = span.lo - self.0 + self.1;
+        span.hi = span.hi - self.0 + self.1;
+    }
+}
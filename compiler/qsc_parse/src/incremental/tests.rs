@@ -0,0 +1,177 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{reparse, CachedParse, ParseCache};
+use crate::Error;
+use indoc::indoc;
+use qsc_ast::ast::Namespace;
+use qsc_data_structures::language_features::LanguageFeatures;
+use std::sync::Arc;
+
+const EXPLICIT: &str = indoc! {"
+    /// The first namespace.
+    namespace A {
+        open Microsoft.Quantum.Math;
+
+        /// Says hello.
+        @EntryPoint()
+        operation Main() : Unit {
+            let x = 1 + 2;
+            Message($\"Hello {x}\");
+        }
+
+        newtype Pair = (First : Int, Second : Int);
+
+        // A comment between items.
+        function Add(a : Int, b : Int) : Int { a + b }
+    }
+
+    namespace B.C {
+        struct Point { X : Double, Y : Double }
+        internal function Origin() : Point { new Point { X = 0.0, Y = 0.0 } }
+    }
+"};
+
+const IMPLICIT: &str = indoc! {"
+    /// The file doc comment.
+    import Std.Math.*;
+
+    operation Main() : Unit {
+        use q = Qubit();
+        H(q);
+    }
+
+    function Twice(x : Int) : Int { 2 * x }
+    export Twice;
+"};
+
+const INSERTIONS: &[&str] = &[
+    " ",
+    "x",
+    ";",
+    "}",
+    "{",
+    "\"",
+    "/// doc\n",
+    "@Attr()",
+    "function F() : Unit {}",
+];
+
+fn cached(source: &str, source_name: &str) -> CachedParse {
+    let (namespaces, errors) =
+        crate::namespaces(source, Some(source_name), LanguageFeatures::default());
+    CachedParse {
+        contents: source.into(),
+        language_features: LanguageFeatures::default(),
+        namespaces,
+        errors,
+    }
+}
+
+fn check_matches_full_parse(cached: &CachedParse, new: &str, source_name: &str) {
+    if let Some(namespaces) = reparse(cached, new) {
+        let full = crate::namespaces(new, Some(source_name), LanguageFeatures::default());
+        assert_eq!(
+            render(&(namespaces, Vec::new())),
+            render(&full),
+            "edit from {:?} to {new:?}",
+            cached.contents
+        );
+    }
+}
+
+fn render((namespaces, errors): &(Vec<Namespace>, Vec<Error>)) -> String {
+    let mut rendered = String::new();
+    for namespace in namespaces {
+        rendered += &format!("{namespace}\n");
+    }
+    rendered + &format!("{errors:#?}")
+}
+
+fn check_all_edits(source: &str, source_name: &str) {
+    let cached = cached(source, source_name);
+    assert!(cached.errors.is_empty());
+    let boundaries = (0..=source.len()).filter(|&i| source.is_char_boundary(i));
+    for i in boundaries {
+        for insertion in INSERTIONS {
+            let new = format!("{}{insertion}{}", &source[..i], &source[i..]);
+            check_matches_full_parse(&cached, &new, source_name);
+        }
+
+        if let Some(c) = source[i..].chars().next() {
+            let new = format!("{}{}", &source[..i], &source[i + c.len_utf8()..]);
+            check_matches_full_parse(&cached, &new, source_name);
+        }
+    }
+}
+
+#[test]
+fn every_edit_to_explicit_namespaces_matches_full_parse() {
+    check_all_edits(EXPLICIT, "test.qs");
+}
+
+#[test]
+fn every_edit_to_implicit_namespace_matches_full_parse() {
+    check_all_edits(IMPLICIT, "test.qs");
+}
+
+#[test]
+fn edit_inside_callable_body_is_reparsed_incrementally() {
+    let new = EXPLICIT.replace("1 + 2", "1 + 2 + 3");
+    assert!(reparse(&cached(EXPLICIT, "test.qs"), &new).is_some());
+}
+
+#[test]
+fn edit_to_last_item_of_namespace_is_reparsed_incrementally() {
+    let new = EXPLICIT.replace("0.0 } }", "1.0 } }");
+    assert!(reparse(&cached(EXPLICIT, "test.qs"), &new).is_some());
+}
+
+#[test]
+fn new_item_in_implicit_namespace_is_reparsed_incrementally() {
+    let new = IMPLICIT.replace(
+        "export Twice;",
+        "function Thrice(x : Int) : Int { 3 * x }\nexport Twice;",
+    );
+    assert!(reparse(&cached(IMPLICIT, "test.qs"), &new).is_some());
+}
+
+#[test]
+fn edit_to_namespace_name_is_fully_reparsed() {
+    let new = EXPLICIT.replace("namespace B.C", "namespace B.D");
+    assert!(reparse(&cached(EXPLICIT, "test.qs"), &new).is_none());
+}
+
+#[test]
+fn edit_introducing_error_is_fully_reparsed() {
+    let new = EXPLICIT.replace("1 + 2;", "1 + ;");
+    assert!(reparse(&cached(EXPLICIT, "test.qs"), &new).is_none());
+}
+
+#[test]
+fn edit_after_previous_errors_is_fully_reparsed() {
+    let old = EXPLICIT.replace("1 + 2;", "1 + ;");
+    assert!(reparse(&cached(&old, "test.qs"), EXPLICIT).is_none());
+}
+
+#[test]
+fn unchanged_source_reuses_previous_parse() {
+    let mut cache = ParseCache::default();
+    let source: Arc<str> = EXPLICIT.into();
+    let source_name: Arc<str> = "test.qs".into();
+    let first = cache.namespaces(&source, &source_name, LanguageFeatures::default());
+    let second = cache.namespaces(&source, &source_name, LanguageFeatures::default());
+    assert_eq!(render(&first), render(&second));
+}
+
+#[test]
+fn retain_forgets_other_sources() {
+    let mut cache = ParseCache::default();
+    let source: Arc<str> = EXPLICIT.into();
+    for name in ["a.qs", "b.qs"] {
+        let _ = cache.namespaces(&source, &name.into(), LanguageFeatures::default());
+    }
+    assert_eq!(cache.len(), 2);
+    cache.retain(|name| name == "b.qs");
+    assert_eq!(cache.len(), 1);
+}
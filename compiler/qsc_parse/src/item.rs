@@ -12,7 +12,7 @@ use std::rc::Rc;
 use super::{
     expr::expr,
     keyword::Keyword,
-    prim::{ident, many, opt, pat, seq, token},
+    prim::{apos_ident, ident, many, opt, pat, seq, token},
     scan::ParserContext,
     stmt,
     ty::{self, recovering_ty, ty},
@@ -31,10 +31,11 @@ use crate::{
     ErrorKind,
 };
 use qsc_ast::ast::{
-    Attr, Block, CallableBody, CallableDecl, CallableKind, FieldDef, FunctorExpr, Ident, Idents,
-    ImportOrExportDecl, ImportOrExportItem, Item, ItemKind, Namespace, NodeId, Pat, PatKind, Path,
-    PathKind, Spec, SpecBody, SpecDecl, SpecGen, Stmt, StmtKind, StructDecl, TopLevelNode, Ty,
-    TyDef, TyDefKind, TyKind,
+    Attr, Block, CallableBody, CallableDecl, CallableKind, ClassConstraints, ClassDecl, ConstDecl,
+    EnumDecl, FieldDef, FunctorExpr, Ident, Idents, ImportOrExportDecl, ImportOrExportItem, Item,
+    ItemKind, Namespace, NodeId, Pat, PatKind, Path, PathKind, Spec, SpecBody, SpecDecl, SpecGen,
+    Stmt, StmtKind, StructDecl, TopLevelNode, Ty, TyDef, TyDefKind, TyKind, TypeParameter,
+    VariantDef, Visibility, VisibilityKind,
 };
 use qsc_data_structures::language_features::LanguageFeatures;
 use qsc_data_structures::span::Span;
@@ -50,6 +51,12 @@ pub(super) fn parse(s: &mut ParserContext) -> Result<Box<Item>> {
         ty
     } else if let Some(strct) = opt(s, parse_struct)? {
         strct
+    } else if let Some(enm) = opt(s, parse_enum)? {
+        enm
+    } else if let Some(cnst) = opt(s, parse_const)? {
+        cnst
+    } else if let Some(class) = opt(s, parse_class)? {
+        class
     } else if let Some(callable) = opt(s, parse_callable_decl)? {
         Box::new(ItemKind::Callable(callable))
     } else if let Some(decl) = opt(s, parse_import_or_export)? {
@@ -76,6 +83,7 @@ pub(super) fn parse(s: &mut ParserContext) -> Result<Box<Item>> {
         span: s.span(lo),
         doc: doc.unwrap_or_default().into(),
         attrs: attrs.into_boxed_slice(),
+        visibility,
         kind,
     }))
 }
@@ -88,6 +96,9 @@ fn parse_many(s: &mut ParserContext) -> Result<Vec<Box<Item>>> {
         TokenKind::Keyword(Keyword::Open),
         TokenKind::Keyword(Keyword::Newtype),
         TokenKind::Keyword(Keyword::Struct),
+        TokenKind::Keyword(Keyword::Enum),
+        TokenKind::Keyword(Keyword::Const),
+        TokenKind::Keyword(Keyword::Class),
         TokenKind::Keyword(Keyword::Operation),
         TokenKind::Keyword(Keyword::Function),
         TokenKind::Keyword(Keyword::Import),
@@ -108,6 +119,7 @@ fn default(span: Span) -> Box<Item> {
         span,
         doc: "".into(),
         attrs: Vec::new().into_boxed_slice(),
+        visibility: None,
         kind: Box::new(ItemKind::Err),
     })
 }
@@ -299,7 +311,7 @@ fn parse_namespace(s: &mut ParserContext) -> Result<Namespace> {
 /// Parses the contents of a namespace block, what is in between the open and close braces in an
 /// explicit namespace, and any top level items in an implicit namespace.
 #[allow(clippy::vec_box)]
-fn parse_namespace_block_contents(s: &mut ParserContext) -> Result<Vec<Box<Item>>> {
+pub(super) fn parse_namespace_block_contents(s: &mut ParserContext) -> Result<Vec<Box<Item>>> {
     let items = barrier(s, &[TokenKind::Close(Delim::Brace)], parse_many)?;
     Ok(items)
 }
@@ -346,9 +358,13 @@ fn parse_attr(s: &mut ParserContext) -> Result<Box<Attr>> {
     }))
 }
 
-fn parse_visibility(s: &mut ParserContext) -> Result<()> {
+fn parse_visibility(s: &mut ParserContext) -> Result<Visibility> {
+    let lo = s.peek().span.lo;
     token(s, TokenKind::Keyword(Keyword::Internal))?;
-    Ok(())
+    Ok(Visibility {
+        span: s.span(lo),
+        kind: VisibilityKind::Internal,
+    })
 }
 
 fn parse_open(s: &mut ParserContext) -> Result<Box<ItemKind>> {
@@ -418,6 +434,154 @@ fn parse_struct(s: &mut ParserContext) -> Result<Box<ItemKind>> {
     Ok(Box::new(ItemKind::Struct(Box::new(decl))))
 }
 
+fn parse_enum(s: &mut ParserContext) -> Result<Box<ItemKind>> {
+    let lo = s.peek().span.lo;
+    token(s, TokenKind::Keyword(Keyword::Enum))?;
+    let name = ident(s)?;
+    let generics = if token(s, TokenKind::Lt).is_ok() {
+        throw_away_doc(s);
+        let params = seq(s, ty::param)?.0;
+        token(s, TokenKind::Gt)?;
+        params
+    } else {
+        Vec::new()
+    };
+    token(s, TokenKind::Open(Delim::Brace))?;
+    let (variants, _) = seq(s, |s| {
+        let lo = s.peek().span.lo;
+        let name = ident(s)?;
+        let payload_ty = if s.peek().kind == TokenKind::Open(Delim::Paren) {
+            Some(Box::new(ty(s)?))
+        } else {
+            None
+        };
+        Ok(Box::new(VariantDef {
+            id: NodeId::default(),
+            span: s.span(lo),
+            name,
+            ty: payload_ty,
+        }))
+    })?;
+    recovering_token(s, TokenKind::Close(Delim::Brace));
+    let decl = EnumDecl {
+        id: NodeId::default(),
+        span: s.span(lo),
+        name,
+        generics: generics.into_boxed_slice(),
+        variants: variants.into_boxed_slice(),
+    };
+
+    Ok(Box::new(ItemKind::Enum(Box::new(decl))))
+}
+
+fn parse_const(s: &mut ParserContext) -> Result<Box<ItemKind>> {
+    let lo = s.peek().span.lo;
+    token(s, TokenKind::Keyword(Keyword::Const))?;
+    let name = ident(s)?;
+    token(s, TokenKind::Colon)?;
+    let ty = ty(s)?;
+    token(s, TokenKind::Eq)?;
+    let value = expr(s)?;
+    recovering_semi(s);
+    let decl = ConstDecl {
+        id: NodeId::default(),
+        span: s.span(lo),
+        name,
+        ty: Box::new(ty),
+        value,
+    };
+
+    Ok(Box::new(ItemKind::Const(Box::new(decl))))
+}
+
+fn parse_class(s: &mut ParserContext) -> Result<Box<ItemKind>> {
+    let lo = s.peek().span.lo;
+    token(s, TokenKind::Keyword(Keyword::Class))?;
+    let name = ident(s)?;
+    let generics = if token(s, TokenKind::Open(Delim::Bracket)).is_ok() {
+        let params = seq(s, |s| {
+            let param = apos_ident(s)?;
+            let span = param.span;
+            Ok(TypeParameter::new(
+                *param,
+                ClassConstraints::default(),
+                span,
+            ))
+        })?
+        .0;
+        token(s, TokenKind::Close(Delim::Bracket))?;
+        params
+    } else {
+        Vec::new()
+    };
+    let constraints = if s.peek().kind == TokenKind::Open(Delim::Brace) {
+        ClassConstraints::default()
+    } else {
+        token(s, TokenKind::Colon)?;
+        ty::class_constraints(s)?
+    };
+    let methods = if token(s, TokenKind::Open(Delim::Brace)).is_ok() {
+        let methods = many(s, parse_class_method)?;
+        recovering_token(s, TokenKind::Close(Delim::Brace));
+        methods
+    } else {
+        recovering_semi(s);
+        Vec::new()
+    };
+    let decl = ClassDecl {
+        id: NodeId::default(),
+        span: s.span(lo),
+        name,
+        generics: generics.into_boxed_slice(),
+        constraints,
+        methods: methods.into_boxed_slice(),
+    };
+
+    Ok(Box::new(ItemKind::Class(Box::new(decl))))
+}
+
+/// A method of a class, which is a callable signature without a body, e.g.
+/// `function Items(container : 'Self) : 'U[];`
+fn parse_class_method(s: &mut ParserContext) -> Result<Box<CallableDecl>> {
+    let lo = s.peek().span.lo;
+    throw_away_doc(s);
+    let kind = if token(s, TokenKind::Keyword(Keyword::Function)).is_ok() {
+        CallableKind::Function
+    } else if token(s, TokenKind::Keyword(Keyword::Operation)).is_ok() {
+        CallableKind::Operation
+    } else {
+        let token = s.peek();
+        return Err(Error::new(ErrorKind::Rule(
+            "class method",
+            token.kind,
+            token.span,
+        )));
+    };
+    let name = ident(s)?;
+    let input = pat(s)?;
+    check_input_parens(&input)?;
+    let (output, functors) = parse_callable_output_and_functors(s)?;
+    recovering_semi(s);
+    let span = s.span(lo);
+
+    Ok(Box::new(CallableDecl {
+        id: NodeId::default(),
+        span,
+        kind,
+        name,
+        generics: Box::default(),
+        input,
+        output,
+        functors,
+        body: Box::new(CallableBody::Specs(Box::new([Box::new(SpecDecl {
+            id: NodeId::default(),
+            span,
+            spec: Spec::Body,
+            body: SpecBody::Gen(SpecGen::Intrinsic),
+        })]))),
+    }))
+}
+
 fn try_tydef_as_ty(tydef: &TyDef) -> Option<Ty> {
     match tydef.kind.as_ref() {
         TyDefKind::Field(Some(_), _) | TyDefKind::Err => None,
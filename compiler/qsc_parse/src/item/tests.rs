@@ -189,6 +189,146 @@ fn struct_decl_multiple_fields() {
     );
 }
 
+#[test]
+fn enum_decl() {
+    check(
+        parse,
+        "enum Shape { Circle(Double), Empty }",
+        &expect![[r#"
+            Item _id_ [0-36]:
+                Enum _id_ [0-36] (Ident _id_ [5-10] "Shape"):
+                    VariantDef _id_ [13-27] (Ident _id_ [13-19] "Circle"): Type _id_ [19-27]: Paren: Type _id_ [20-26]: Path: Path _id_ [20-26] (Ident _id_ [20-26] "Double")
+                    VariantDef _id_ [29-34] (Ident _id_ [29-34] "Empty")"#]],
+    );
+}
+
+#[test]
+fn enum_decl_generic() {
+    check(
+        parse,
+        "enum Option<'T> { Some('T), None }",
+        &expect![[r#"
+            Item _id_ [0-34]:
+                Enum _id_ [0-34] (Ident _id_ [5-11] "Option"):
+                    generics:
+                        'T
+                    VariantDef _id_ [18-26] (Ident _id_ [18-22] "Some"): Type _id_ [22-26]: Paren: Type _id_ [23-25]: Type Param: 'T
+                    VariantDef _id_ [28-32] (Ident _id_ [28-32] "None")"#]],
+    );
+}
+
+#[test]
+fn enum_decl_empty() {
+    check(
+        parse,
+        "enum Foo { }",
+        &expect![[r#"
+            Item _id_ [0-12]:
+                Enum _id_ [0-12] (Ident _id_ [5-8] "Foo"): <empty>"#]],
+    );
+}
+
+#[test]
+fn const_decl() {
+    check(
+        parse,
+        "const Two : Int = 1 + 1;",
+        &expect![[r#"
+            Item _id_ [0-24]:
+                Const _id_ [0-24] (Ident _id_ [6-9] "Two"):
+                    type: Type _id_ [12-15]: Path: Path _id_ [12-15] (Ident _id_ [12-15] "Int")
+                    value: Expr _id_ [18-23]: BinOp (Add):
+                        Expr _id_ [18-19]: Lit: Int(1)
+                        Expr _id_ [22-23]: Lit: Int(1)"#]],
+    );
+}
+
+#[test]
+fn const_decl_missing_type() {
+    check(
+        parse,
+        "const Two = 2;",
+        &expect![[r#"
+            Error(
+                Token(
+                    Colon,
+                    Eq,
+                    Span {
+                        lo: 10,
+                        hi: 11,
+                    },
+                ),
+            )
+        "#]],
+    );
+}
+
+#[test]
+fn class_decl() {
+    check(
+        parse,
+        "class Numeric : Add + Sub + Mul;",
+        &expect![[r#"
+            Item _id_ [0-32]:
+                Class _id_ [0-32] (Ident _id_ [6-13] "Numeric"): Add + Sub + Mul"#]],
+    );
+}
+
+#[test]
+fn class_decl_with_params() {
+    check(
+        parse,
+        "class Container['U] : Iterable['U];",
+        &expect![[r#"
+            Item _id_ [0-35]:
+                Class _id_ [0-35] (Ident _id_ [6-15] "Container"): Iterable
+                    generics:
+                        'U"#]],
+    );
+}
+
+#[test]
+fn class_decl_with_methods() {
+    check(
+        parse,
+        "class Iterable['U] { function Items(c : 'Self) : 'U[]; }",
+        &expect![[r#"
+            Item _id_ [0-56]:
+                Class _id_ [0-56] (Ident _id_ [6-14] "Iterable")
+                    generics:
+                        'U
+                    Callable _id_ [21-54] (Function):
+                        name: Ident _id_ [30-35] "Items"
+                        input: Pat _id_ [35-46]: Paren:
+                            Pat _id_ [36-45]: Bind:
+                                Ident _id_ [36-37] "c"
+                                Type _id_ [40-45]: Type Param: 'Self
+                        output: Type _id_ [49-53]: Array: Type _id_ [49-51]: Type Param: 'U
+                        body: Specializations:
+                            SpecDecl _id_ [21-54] (Body): Gen: Intrinsic"#]],
+    );
+}
+
+#[test]
+fn class_decl_missing_constraints() {
+    check(
+        parse,
+        "class Numeric;",
+        &expect![[r#"
+            Error(
+                Token(
+                    Colon,
+                    Semi,
+                    Span {
+                        lo: 13,
+                        hi: 14,
+                    },
+                ),
+            )
+    "#]],
+    );
+}
+
 #[test]
 fn ty_decl() {
     check(
@@ -959,6 +1099,7 @@ fn internal_ty() {
         "internal newtype Foo = Unit;",
         &expect![[r#"
             Item _id_ [0-28]:
+                Visibility [0-8] (Internal)
                 New Type (Ident _id_ [17-20] "Foo"): TyDef _id_ [23-27]: Field:
                     Type _id_ [23-27]: Path: Path _id_ [23-27] (Ident _id_ [23-27] "Unit")"#]],
     );
@@ -971,6 +1112,7 @@ fn internal_function() {
         "internal function Foo() : Unit {}",
         &expect![[r#"
             Item _id_ [0-33]:
+                Visibility [0-8] (Internal)
                 Callable _id_ [9-33] (Function):
                     name: Ident _id_ [18-21] "Foo"
                     input: Pat _id_ [21-23]: Unit
@@ -991,6 +1133,7 @@ fn internal_function_doc() {
                 doc:
                     This is a
                     doc comment.
+                Visibility [47-55] (Internal)
                 Callable _id_ [56-78] (Function):
                     name: Ident _id_ [65-68] "Foo"
                     input: Pat _id_ [68-70]: Unit
@@ -1006,6 +1149,7 @@ fn internal_operation() {
         "internal operation Foo() : Unit {}",
         &expect![[r#"
             Item _id_ [0-34]:
+                Visibility [0-8] (Internal)
                 Callable _id_ [9-34] (Operation):
                     name: Ident _id_ [19-22] "Foo"
                     input: Pat _id_ [22-24]: Unit
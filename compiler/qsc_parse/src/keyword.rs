@@ -18,12 +18,15 @@ pub enum Keyword {
     Auto,
     Body,
     Borrow,
+    Class,
+    Const,
     Controlled,
     ControlledUpper,
     Ctl,
     Distribute,
     Elif,
     Else,
+    Enum,
     Export,
     Fail,
     False,
@@ -38,6 +41,7 @@ pub enum Keyword {
     Invert,
     Is,
     Let,
+    Match,
     Mutable,
     Namespace,
     Newtype,
@@ -66,6 +70,12 @@ pub enum Keyword {
 }
 
 impl Keyword {
+    /// Whether the keyword is reserved only where it starts the construct it names, and is an
+    /// ordinary identifier everywhere else.
+    pub(super) fn is_contextual(self) -> bool {
+        matches!(self, Self::Class | Self::Const | Self::Enum | Self::Match)
+    }
+
     pub(super) fn as_str(self) -> &'static str {
         match self {
             Self::Adj => "Adj",
@@ -77,12 +87,15 @@ impl Keyword {
             Self::Auto => "auto",
             Self::Body => "body",
             Self::Borrow => "borrow",
+            Self::Class => "class",
+            Self::Const => "const",
             Self::Controlled => "controlled",
             Self::ControlledUpper => "Controlled",
             Self::Ctl => "Ctl",
             Self::Distribute => "distribute",
             Self::Elif => "elif",
             Self::Else => "else",
+            Self::Enum => "enum",
             Self::Export => "export",
             Self::Fail => "fail",
             Self::False => "false",
@@ -97,6 +110,7 @@ impl Keyword {
             Self::Invert => "invert",
             Self::Is => "is",
             Self::Let => "let",
+            Self::Match => "match",
             Self::Mutable => "mutable",
             Self::Namespace => "namespace",
             Self::Newtype => "newtype",
@@ -189,10 +203,14 @@ impl FromStr for Keyword {
             "until" => Ok(Self::Until),
             "repeat" => Ok(Self::Repeat),
             "fixup" => Ok(Self::Fixup),
-            // The next two are new keywords and their
+            // The next six are new keywords and their
             // usage has yet to be measured.
             "new" => Ok(Self::New),
             "struct" => Ok(Self::Struct),
+            "match" => Ok(Self::Match),
+            "enum" => Ok(Self::Enum),
+            "const" => Ok(Self::Const),
+            "class" => Ok(Self::Class),
             // The next three were not found or measured
             // in the standard library for priority order.
             "PauliY" => Ok(Self::PauliY),
@@ -3,9 +3,13 @@
 
 pub mod concrete;
 pub mod cooked;
+pub mod highlight;
 pub mod raw;
 
+use crate::keyword::Keyword;
 use enum_iterator::Sequence;
+use raw::Single;
+use std::iter::Peekable;
 
 pub(super) use cooked::{ClosedBinOp, Error, Lexer, StringToken, Token, TokenKind};
 
@@ -50,3 +54,60 @@ pub enum InterpolatedEnding {
     Quote,
     LBrace,
 }
+
+/// Whether a word that spells the keyword is lexed as that keyword, given the raw tokens that
+/// follow it. The contextual keywords `class`, `const`, `enum` and `match` are keywords only where
+/// they start the construct they name, so that code which uses them as names keeps compiling:
+///
+/// - `class`, `const` and `enum` must be followed by the name they declare.
+/// - `match` must be followed by the start of its scrutinee. A `(`, `[`, `+` or `-` only starts the
+///   scrutinee after whitespace, so that `match(x)` and `match[0]` stay a call and an index.
+fn is_keyword_in_context(keyword: Keyword, input: &str, mut tokens: Peekable<raw::Lexer>) -> bool {
+    if !keyword.is_contextual() {
+        return true;
+    }
+
+    let mut spaced = false;
+    let next = loop {
+        match tokens.next() {
+            Some(raw::Token {
+                kind: raw::TokenKind::Whitespace | raw::TokenKind::Comment(_),
+                ..
+            }) => spaced = true,
+            next => break next,
+        }
+    };
+
+    let is_match = keyword == Keyword::Match;
+    match next.map(|t| (t.kind, t.offset)) {
+        Some((raw::TokenKind::Ident, offset)) => {
+            let hi = tokens.peek().map_or(input.len(), |t| t.offset as usize);
+            match input[offset as usize..hi].parse() {
+                Ok(
+                    Keyword::AdjointUpper
+                    | Keyword::ControlledUpper
+                    | Keyword::False
+                    | Keyword::New
+                    | Keyword::Not
+                    | Keyword::One
+                    | Keyword::PauliI
+                    | Keyword::PauliX
+                    | Keyword::PauliY
+                    | Keyword::PauliZ
+                    | Keyword::True
+                    | Keyword::Zero,
+                ) => is_match,
+                Ok(word) => word.is_contextual(),
+                Err(()) => true,
+            }
+        }
+        Some((raw::TokenKind::Number(_) | raw::TokenKind::String(_), _)) => is_match,
+        Some((
+            raw::TokenKind::Single(
+                Single::Open(Delim::Paren | Delim::Bracket) | Single::Minus | Single::Plus,
+            ),
+            _,
+        )) => is_match && spaced,
+        _ => false,
+    }
+}
@@ -4,6 +4,8 @@
 //! The second lexing phase "cooks" a raw token stream, transforming them into tokens that directly
 //! correspond to components in the Q# grammar. Keywords are treated as identifiers, except `and`
 //! and `or`, which are cooked into [`ClosedBinOp`] so that `and=` and `or=` are lexed correctly.
+//! Contextual keywords such as `match` are cooked into identifiers where they don't start the
+//! construct they name.
 //!
 //! Whitespace and comment tokens are discarded; this means that cooked tokens are not necessarily
 //! contiguous, so they include both a starting and ending byte offset.
@@ -15,6 +17,7 @@
 mod tests;
 
 use super::{
+    is_keyword_in_context,
     raw::{self, Number, Single},
     Delim, InterpolatedEnding, InterpolatedStart, Radix,
 };
@@ -121,6 +124,8 @@ pub enum TokenKind {
     FatArrow,
     /// A floating-point literal.
     Float,
+    /// A format specifier in an interpolated string argument, including the leading `:`.
+    FormatSpec,
     /// `>`
     Gt,
     /// `>=`
@@ -182,6 +187,7 @@ impl Display for TokenKind {
             TokenKind::EqEq => f.write_str("`==`"),
             TokenKind::FatArrow => f.write_str("`=>`"),
             TokenKind::Float => f.write_str("float"),
+            TokenKind::FormatSpec => f.write_str("format specifier"),
             TokenKind::Gt => f.write_str("`>`"),
             TokenKind::Gte => f.write_str("`>=`"),
             TokenKind::Ident => f.write_str("identifier"),
@@ -306,6 +312,13 @@ impl<'a> Lexer<'a> {
         self.tokens.next_if(|t| t.kind == tok).is_some()
     }
 
+    /// Whether the next two raw tokens are `>`, which follow a `>` to make `>>>`.
+    fn is_gt_gt_gt(&self) -> bool {
+        let mut tokens = self.tokens.clone();
+        let gt = raw::TokenKind::Single(Single::Gt);
+        tokens.next().is_some_and(|t| t.kind == gt) && tokens.next().is_some_and(|t| t.kind == gt)
+    }
+
     fn expect_single(&mut self, single: Single, complete: TokenKind) -> Result<(), Error> {
         self.expect(raw::TokenKind::Single(single), complete)
     }
@@ -331,6 +344,7 @@ impl<'a> Lexer<'a> {
                 Ok(None)
             }
             raw::TokenKind::Comment(raw::CommentKind::Doc) => Ok(Some(TokenKind::DocComment)),
+            raw::TokenKind::FormatSpec => Ok(Some(TokenKind::FormatSpec)),
             raw::TokenKind::Ident => {
                 let ident = &self.input[(token.offset as usize)..(self.offset() as usize)];
                 Ok(Some(self.ident(ident)))
@@ -443,11 +457,13 @@ impl<'a> Lexer<'a> {
             Single::Gt => {
                 if self.next_if_eq_single(Single::Eq) {
                     Ok(TokenKind::Gte)
-                } else if self.next_if_eq_single(Single::Gt) {
-                    let op = ClosedBinOp::GtGtGt;
-                    self.expect_single(Single::Gt, TokenKind::ClosedBinOp(op))?;
-                    Ok(self.closed_bin_op(op))
+                } else if self.is_gt_gt_gt() {
+                    self.tokens.next();
+                    self.tokens.next();
+                    Ok(self.closed_bin_op(ClosedBinOp::GtGtGt))
                 } else {
+                    // Without a third `>`, each `>` is its own token, which closes nested type
+                    // arguments like `Option<Option<Int>>`.
                     Ok(TokenKind::Gt)
                 }
             }
@@ -506,10 +522,12 @@ impl<'a> Lexer<'a> {
                     TokenKind::WSlash
                 }
             }
-            ident => ident
-                .parse()
-                .map(TokenKind::Keyword)
-                .unwrap_or(TokenKind::Ident),
+            ident => match ident.parse() {
+                Ok(keyword) if is_keyword_in_context(keyword, self.input, self.tokens.clone()) => {
+                    TokenKind::Keyword(keyword)
+                }
+                _ => TokenKind::Ident,
+            },
         }
     }
 }
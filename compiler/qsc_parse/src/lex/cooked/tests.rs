@@ -50,6 +50,7 @@ fn op_string(kind: TokenKind) -> Option<String> {
         | TokenKind::DocComment
         | TokenKind::Eof
         | TokenKind::Float
+        | TokenKind::FormatSpec
         | TokenKind::Ident
         | TokenKind::Int(_)
         | TokenKind::Keyword(_)
@@ -138,6 +139,57 @@ fn amp_amp() {
     );
 }
 
+#[test]
+fn gt_gt() {
+    check(
+        ">>",
+        &expect![[r#"
+            [
+                Ok(
+                    Token {
+                        kind: Gt,
+                        span: Span {
+                            lo: 0,
+                            hi: 1,
+                        },
+                    },
+                ),
+                Ok(
+                    Token {
+                        kind: Gt,
+                        span: Span {
+                            lo: 1,
+                            hi: 2,
+                        },
+                    },
+                ),
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn gt_gt_gt() {
+    check(
+        ">>>",
+        &expect![[r#"
+            [
+                Ok(
+                    Token {
+                        kind: ClosedBinOp(
+                            GtGtGt,
+                        ),
+                        span: Span {
+                            lo: 0,
+                            hi: 3,
+                        },
+                    },
+                ),
+            ]
+        "#]],
+    );
+}
+
 #[test]
 fn amp_plus() {
     check(
@@ -1076,6 +1128,86 @@ fn interpolated_string_braced() {
     );
 }
 
+#[test]
+fn interpolated_string_format_spec() {
+    check(
+        r#"$"{x:08b} and {y}""#,
+        &expect![[r#"
+        [
+            Ok(
+                Token {
+                    kind: String(
+                        Interpolated(
+                            DollarQuote,
+                            LBrace,
+                        ),
+                    ),
+                    span: Span {
+                        lo: 0,
+                        hi: 3,
+                    },
+                },
+            ),
+            Ok(
+                Token {
+                    kind: Ident,
+                    span: Span {
+                        lo: 3,
+                        hi: 4,
+                    },
+                },
+            ),
+            Ok(
+                Token {
+                    kind: FormatSpec,
+                    span: Span {
+                        lo: 4,
+                        hi: 8,
+                    },
+                },
+            ),
+            Ok(
+                Token {
+                    kind: String(
+                        Interpolated(
+                            RBrace,
+                            LBrace,
+                        ),
+                    ),
+                    span: Span {
+                        lo: 8,
+                        hi: 15,
+                    },
+                },
+            ),
+            Ok(
+                Token {
+                    kind: Ident,
+                    span: Span {
+                        lo: 15,
+                        hi: 16,
+                    },
+                },
+            ),
+            Ok(
+                Token {
+                    kind: String(
+                        Interpolated(
+                            RBrace,
+                            Quote,
+                        ),
+                    ),
+                    span: Span {
+                        lo: 16,
+                        hi: 18,
+                    },
+                },
+            ),
+        ]
+    "#]],
+    );
+}
+
 #[test]
 fn interpolated_string_escape_brace() {
     check(
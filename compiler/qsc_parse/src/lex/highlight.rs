@@ -0,0 +1,128 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! Classifies the text of a source for syntax highlighting from its raw tokens alone, so that it
+//! works on partial or malformed input that doesn't parse. Like the raw lexer it is built on,
+//! tokenizing never fails and never panics: text that isn't part of a valid token is classified as
+//! an error instead.
+
+#[cfg(test)]
+mod tests;
+
+use super::{
+    is_keyword_in_context,
+    raw::{self, CommentKind, Single},
+};
+use qsc_data_structures::span::Span;
+use std::iter::Peekable;
+
+/// A highlighted token, whose span is relative to the start of the tokenized input.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct HighlightToken {
+    pub kind: HighlightKind,
+    pub span: Span,
+}
+
+/// How a token is highlighted.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum HighlightKind {
+    /// A comment.
+    Comment,
+    /// A doc comment.
+    DocComment,
+    /// A keyword, such as `operation` or `true`.
+    Keyword,
+    /// An identifier that isn't a keyword.
+    Ident,
+    /// A type parameter, such as `'T`.
+    TypeParam,
+    /// A numeric literal.
+    Number,
+    /// A string literal, or a part of an interpolated string outside of its expressions. Strings that
+    /// are not terminated yet are still highlighted as strings.
+    String,
+    /// A character of an operator, such as the `<` and `-` of `<-`.
+    Operator,
+    /// A delimiter or separator, such as `(`, `,` or `;`.
+    Punctuation,
+    /// Text that doesn't start any token.
+    Error,
+}
+
+/// Tokenizes the input for highlighting. The tokens are in order and don't overlap, and only
+/// whitespace is left out.
+#[must_use]
+pub fn tokenize(input: &str) -> Vec<HighlightToken> {
+    let len = u32::try_from(input.len()).unwrap_or(u32::MAX);
+    let mut tokens = raw::Lexer::new(input).peekable();
+    let mut highlighted = Vec::new();
+    while let Some(token) = tokens.next() {
+        let kind = match token.kind {
+            raw::TokenKind::Whitespace => continue,
+            raw::TokenKind::Comment(CommentKind::Normal) => HighlightKind::Comment,
+            raw::TokenKind::Comment(CommentKind::Doc) => HighlightKind::DocComment,
+            raw::TokenKind::Ident => {
+                let hi = next_offset(&mut tokens, len);
+                match input
+                    .get(token.offset as usize..hi as usize)
+                    .and_then(|ident| ident.parse().ok())
+                {
+                    Some(keyword) if is_keyword_in_context(keyword, input, tokens.clone()) => {
+                        HighlightKind::Keyword
+                    }
+                    _ => HighlightKind::Ident,
+                }
+            }
+            raw::TokenKind::Number(_) => HighlightKind::Number,
+            raw::TokenKind::FormatSpec | raw::TokenKind::String(_) => HighlightKind::String,
+            raw::TokenKind::Single(Single::Apos)
+                if tokens
+                    .next_if(|next| next.kind == raw::TokenKind::Ident)
+                    .is_some() =>
+            {
+                HighlightKind::TypeParam
+            }
+            raw::TokenKind::Single(single) => single_kind(single),
+            raw::TokenKind::Unknown => HighlightKind::Error,
+        };
+        let span = Span {
+            lo: token.offset,
+            hi: next_offset(&mut tokens, len),
+        };
+        highlighted.push(HighlightToken { kind, span });
+    }
+    highlighted
+}
+
+/// Raw tokens are contiguous, so each one ends where the next one starts.
+fn next_offset(tokens: &mut Peekable<raw::Lexer>, len: u32) -> u32 {
+    tokens.peek().map_or(len, |next| next.offset)
+}
+
+fn single_kind(single: Single) -> HighlightKind {
+    match single {
+        Single::Open(_)
+        | Single::Close(_)
+        | Single::Colon
+        | Single::Comma
+        | Single::Dot
+        | Single::Semi => HighlightKind::Punctuation,
+        Single::Amp
+        | Single::At
+        | Single::Bang
+        | Single::Bar
+        | Single::Caret
+        | Single::Eq
+        | Single::Gt
+        | Single::Lt
+        | Single::Minus
+        | Single::Percent
+        | Single::Plus
+        | Single::Question
+        | Single::Slash
+        | Single::Star
+        | Single::Tilde => HighlightKind::Operator,
+        // An apostrophe that doesn't start a type parameter.
+        Single::Apos => HighlightKind::Error,
+    }
+}
@@ -0,0 +1,157 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::tokenize;
+use expect_test::{expect, Expect};
+use indoc::indoc;
+use std::fmt::Write;
+
+fn check(input: &str, expect: &Expect) {
+    let mut actual = String::new();
+    for token in tokenize(input) {
+        let text = &input[token.span.lo as usize..token.span.hi as usize];
+        let _ = writeln!(actual, "{:?} {text:?}", token.kind);
+    }
+    expect.assert_eq(&actual);
+}
+
+/// Checks that every prefix of the input, as typed so far in an editor, tokenizes into ordered
+/// tokens that don't overlap and start and end on character boundaries.
+fn check_prefixes_are_well_formed(input: &str) {
+    for end in (0..=input.len()).filter(|&end| input.is_char_boundary(end)) {
+        let prefix = &input[..end];
+        let mut last_hi = 0;
+        for token in tokenize(prefix) {
+            let (lo, hi) = (token.span.lo as usize, token.span.hi as usize);
+            assert!(last_hi <= lo && lo < hi && hi <= prefix.len(), "{prefix:?}");
+            assert!(prefix.is_char_boundary(lo) && prefix.is_char_boundary(hi));
+            last_hi = hi;
+        }
+    }
+}
+
+#[test]
+fn callable() {
+    check(
+        indoc! {"
+            /// Doc.
+            operation Foo<'T>(x : 'T) : Int { // Comment.
+                let n = 0x1F + 2.5e3;
+                n <- 1;
+            }
+        "},
+        &expect![[r#"
+            DocComment "/// Doc."
+            Keyword "operation"
+            Ident "Foo"
+            Operator "<"
+            TypeParam "'T"
+            Operator ">"
+            Punctuation "("
+            Ident "x"
+            Punctuation ":"
+            TypeParam "'T"
+            Punctuation ")"
+            Punctuation ":"
+            Ident "Int"
+            Punctuation "{"
+            Comment "// Comment."
+            Keyword "let"
+            Ident "n"
+            Operator "="
+            Number "0x1F"
+            Operator "+"
+            Number "2.5e3"
+            Punctuation ";"
+            Ident "n"
+            Operator "<"
+            Operator "-"
+            Number "1"
+            Punctuation ";"
+            Punctuation "}"
+        "#]],
+    );
+}
+
+#[test]
+fn contextual_keywords() {
+    check(
+        "enum E {} const c = match x {}; let enum = match(class) + match [1];",
+        &expect![[r#"
+            Keyword "enum"
+            Ident "E"
+            Punctuation "{"
+            Punctuation "}"
+            Keyword "const"
+            Ident "c"
+            Operator "="
+            Keyword "match"
+            Ident "x"
+            Punctuation "{"
+            Punctuation "}"
+            Punctuation ";"
+            Keyword "let"
+            Ident "enum"
+            Operator "="
+            Ident "match"
+            Punctuation "("
+            Ident "class"
+            Punctuation ")"
+            Operator "+"
+            Keyword "match"
+            Punctuation "["
+            Number "1"
+            Punctuation "]"
+            Punctuation ";"
+        "#]],
+    );
+}
+
+#[test]
+fn interpolated_string() {
+    check(
+        r#"$"a {b + 1} c" "unterminated"#,
+        &expect![[r#"
+        String "$\"a {"
+        Ident "b"
+        Operator "+"
+        Number "1"
+        String "} c\""
+        String "\"unterminated"
+    "#]],
+    );
+}
+
+#[test]
+fn malformed_input() {
+    check(
+        "0x 0bL 1e+ ' # é",
+        &expect![[r##"
+        Number "0x"
+        Number "0bL"
+        Number "1e+"
+        Error "'"
+        Error "#"
+        Ident "é"
+    "##]],
+    );
+}
+
+#[test]
+fn partial_input_is_well_formed() {
+    check_prefixes_are_well_formed(indoc! {r#"
+        namespace Test {
+            /// Doc comment with unicode: é 😀
+            operation Foo<'T>(x : 'T) : Unit {
+                let s = $"interpolated {x} and {"nested {$"deep {1}"}"}";
+                let big = 0x_FFL + 0b1_0 + 0o7 + 1.5e-3;
+                x w/= 0 <- ~~~y;
+            }
+        }
+    "#});
+}
+
+#[test]
+fn deeply_nested_interpolation_is_well_formed() {
+    check_prefixes_are_well_formed(&"$\"{".repeat(300));
+}
@@ -22,6 +22,7 @@ use std::{
     iter::Peekable,
     str::CharIndices,
 };
+use unicode_normalization::char::is_combining_mark;
 
 /// A raw token.
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -35,6 +36,7 @@ pub struct Token {
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Sequence)]
 pub enum TokenKind {
     Comment(CommentKind),
+    FormatSpec,
     Ident,
     Number(Number),
     Single(Single),
@@ -48,6 +50,7 @@ impl Display for TokenKind {
         match self {
             TokenKind::Comment(CommentKind::Normal) => f.write_str("comment"),
             TokenKind::Comment(CommentKind::Doc) => f.write_str("doc comment"),
+            TokenKind::FormatSpec => f.write_str("format specifier"),
             TokenKind::Ident => f.write_str("identifier"),
             TokenKind::Number(Number::BigInt(_)) => f.write_str("big integer"),
             TokenKind::Number(Number::Float) => f.write_str("float"),
@@ -171,6 +174,7 @@ pub enum CommentKind {
 pub struct Lexer<'a> {
     chars: Peekable<CharIndices<'a>>,
     interpolation: u8,
+    in_colon_colon: bool,
     starting_offset: u32,
 }
 
@@ -180,6 +184,7 @@ impl<'a> Lexer<'a> {
         Self {
             chars: input.char_indices().peekable(),
             interpolation: 0,
+            in_colon_colon: false,
             starting_offset: 0,
         }
     }
@@ -189,6 +194,7 @@ impl<'a> Lexer<'a> {
         Self {
             chars: input.char_indices().peekable(),
             interpolation: 0,
+            in_colon_colon: false,
             starting_offset,
         }
     }
@@ -242,13 +248,31 @@ impl<'a> Lexer<'a> {
 
     fn ident(&mut self, c: char) -> bool {
         if c == '_' || c.is_alphabetic() {
-            self.eat_while(|c| c == '_' || c.is_alphanumeric());
+            // Combining marks continue an identifier, so that a name written in a decomposed
+            // normalization form is still a single identifier.
+            self.eat_while(|c| c == '_' || c.is_alphanumeric() || is_combining_mark(c));
             true
         } else {
             false
         }
     }
 
+    /// A single colon inside an interpolated string argument starts a format specifier, which runs until the
+    /// closing brace of the argument. Colons can't otherwise appear in expressions, except as part of `::`.
+    fn format_spec(&mut self, c: char) -> bool {
+        if self.interpolation == 0 || c != ':' {
+            return false;
+        }
+
+        if self.in_colon_colon || self.first() == Some(':') {
+            self.in_colon_colon = !self.in_colon_colon;
+            return false;
+        }
+
+        self.eat_while(|c| !matches!(c, '{' | '}' | '"' | '\n' | '\r'));
+        true
+    }
+
     fn number(&mut self, c: char) -> Option<Number> {
         self.leading_zero(c).or_else(|| self.decimal(c))
     }
@@ -361,11 +385,10 @@ impl<'a> Lexer<'a> {
                     InterpolatedStart::RBrace
                 };
 
-                let end = if self.next_if_eq('{') {
-                    self.interpolation = self
-                        .interpolation
-                        .checked_add(1)
-                        .expect("interpolation should not exceed maximum depth");
+                // Past the maximum nesting depth, the brace is left to be lexed on its own and the string
+                // is unterminated, so that arbitrarily deep nesting is an error rather than a panic.
+                let end = if self.interpolation < u8::MAX && self.next_if_eq('{') {
+                    self.interpolation += 1;
                     Some(InterpolatedEnding::LBrace)
                 } else if self.next_if_eq('"') {
                     Some(InterpolatedEnding::Quote)
@@ -390,6 +413,8 @@ impl Iterator for Lexer<'_> {
             TokenKind::Whitespace
         } else if self.ident(c) {
             TokenKind::Ident
+        } else if self.format_spec(c) {
+            TokenKind::FormatSpec
         } else {
             self.number(c)
                 .map(TokenKind::Number)
@@ -2,7 +2,10 @@
 // Licensed under the MIT License.
 
 use super::Lexer;
-use crate::lex::raw::{Single, Token, TokenKind};
+use crate::lex::{
+    raw::{Single, StringToken, Token, TokenKind},
+    Delim, InterpolatedEnding, InterpolatedStart,
+};
 use expect_test::{expect, Expect};
 
 fn check(input: &str, expect: &Expect) {
@@ -303,6 +306,125 @@ fn interpolated_string_braced() {
     );
 }
 
+#[test]
+fn interpolated_string_format_spec() {
+    check(
+        r#"$"{x:>8.3f}""#,
+        &expect![[r#"
+        [
+            Token {
+                kind: String(
+                    Interpolated(
+                        DollarQuote,
+                        Some(
+                            LBrace,
+                        ),
+                    ),
+                ),
+                offset: 0,
+            },
+            Token {
+                kind: Ident,
+                offset: 3,
+            },
+            Token {
+                kind: FormatSpec,
+                offset: 4,
+            },
+            Token {
+                kind: String(
+                    Interpolated(
+                        RBrace,
+                        Some(
+                            Quote,
+                        ),
+                    ),
+                ),
+                offset: 10,
+            },
+        ]
+    "#]],
+    );
+}
+
+#[test]
+fn interpolated_string_field_access_is_not_format_spec() {
+    check(
+        r#"$"{x::Re}""#,
+        &expect![[r#"
+        [
+            Token {
+                kind: String(
+                    Interpolated(
+                        DollarQuote,
+                        Some(
+                            LBrace,
+                        ),
+                    ),
+                ),
+                offset: 0,
+            },
+            Token {
+                kind: Ident,
+                offset: 3,
+            },
+            Token {
+                kind: Single(
+                    Colon,
+                ),
+                offset: 4,
+            },
+            Token {
+                kind: Single(
+                    Colon,
+                ),
+                offset: 5,
+            },
+            Token {
+                kind: Ident,
+                offset: 6,
+            },
+            Token {
+                kind: String(
+                    Interpolated(
+                        RBrace,
+                        Some(
+                            Quote,
+                        ),
+                    ),
+                ),
+                offset: 8,
+            },
+        ]
+    "#]],
+    );
+}
+
+#[test]
+fn colon_outside_interpolated_string_is_not_format_spec() {
+    check(
+        "x:Int",
+        &expect![[r#"
+        [
+            Token {
+                kind: Ident,
+                offset: 0,
+            },
+            Token {
+                kind: Single(
+                    Colon,
+                ),
+                offset: 1,
+            },
+            Token {
+                kind: Ident,
+                offset: 2,
+            },
+        ]
+    "#]],
+    );
+}
+
 #[test]
 fn interpolated_string_escape_brace() {
     check(
@@ -1369,6 +1491,111 @@ fn unknown() {
     );
 }
 
+#[test]
+fn unknown_multibyte() {
+    check(
+        "€🎉x",
+        &expect![[r#"
+        [
+            Token {
+                kind: Unknown,
+                offset: 0,
+            },
+            Token {
+                kind: Unknown,
+                offset: 3,
+            },
+            Token {
+                kind: Ident,
+                offset: 7,
+            },
+        ]
+    "#]],
+    );
+}
+
+#[test]
+fn ident_with_combining_mark() {
+    check(
+        "cafe\u{301} x",
+        &expect![[r#"
+        [
+            Token {
+                kind: Ident,
+                offset: 0,
+            },
+            Token {
+                kind: Whitespace,
+                offset: 6,
+            },
+            Token {
+                kind: Ident,
+                offset: 7,
+            },
+        ]
+    "#]],
+    );
+}
+
+#[test]
+fn comment_and_string_with_multibyte_chars() {
+    check(
+        "// 🎉 é\n\"€🎉\" $\"{x}🎉\"",
+        &expect![[r#"
+        [
+            Token {
+                kind: Comment(
+                    Normal,
+                ),
+                offset: 0,
+            },
+            Token {
+                kind: Whitespace,
+                offset: 10,
+            },
+            Token {
+                kind: String(
+                    Normal {
+                        terminated: true,
+                    },
+                ),
+                offset: 11,
+            },
+            Token {
+                kind: Whitespace,
+                offset: 20,
+            },
+            Token {
+                kind: String(
+                    Interpolated(
+                        DollarQuote,
+                        Some(
+                            LBrace,
+                        ),
+                    ),
+                ),
+                offset: 21,
+            },
+            Token {
+                kind: Ident,
+                offset: 24,
+            },
+            Token {
+                kind: String(
+                    Interpolated(
+                        RBrace,
+                        Some(
+                            Quote,
+                        ),
+                    ),
+                ),
+                offset: 25,
+            },
+        ]
+    "#]],
+    );
+}
+
 #[test]
 fn float_hexadecimal() {
     check(
@@ -1401,3 +1628,24 @@ fn float_hexadecimal() {
         "#]],
     );
 }
+
+#[test]
+fn interpolation_nested_past_maximum_depth_is_unterminated() {
+    let input = "$\"{".repeat(300);
+    let kinds: Vec<_> = Lexer::new(&input).map(|token| token.kind).collect();
+    let nested = TokenKind::String(StringToken::Interpolated(
+        InterpolatedStart::DollarQuote,
+        Some(InterpolatedEnding::LBrace),
+    ));
+    assert!(kinds[..255].iter().all(|&kind| kind == nested));
+    assert_eq!(
+        kinds[255..257],
+        [
+            TokenKind::String(StringToken::Interpolated(
+                InterpolatedStart::DollarQuote,
+                None
+            )),
+            TokenKind::Single(Single::Open(Delim::Brace)),
+        ]
+    );
+}
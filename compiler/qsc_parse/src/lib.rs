@@ -7,6 +7,7 @@
 
 pub mod completion;
 mod expr;
+pub mod incremental;
 mod item;
 pub mod keyword;
 pub mod lex;
@@ -107,6 +108,12 @@ enum ErrorKind {
     #[error("invalid {0} literal")]
     #[diagnostic(code("Qsc.Parse.Literal"))]
     Lit(&'static str, #[label] Span),
+    #[error("invalid format specifier `{0}`")]
+    #[diagnostic(help(
+        "format specifiers have the form `[[fill]align][+][0][width][.precision][type]`, where `type` is one of `b`, `o`, `x`, `X`, `e`, `E` or `f`"
+    ))]
+    #[diagnostic(code("Qsc.Parse.FormatSpec"))]
+    FormatSpec(String, #[label] Span),
     #[error("unknown escape sequence: `{0}`")]
     #[diagnostic(code("Qsc.Parse.Escape"))]
     Escape(char, #[label] Span),
@@ -146,6 +153,22 @@ enum ErrorKind {
     #[error("expected an item or closing brace, found {0}")]
     #[diagnostic(code("Qsc.Parse.ExpectedItem"))]
     ExpectedItem(TokenKind, #[label] Span),
+    #[error("identifier is not in Unicode normalization form C")]
+    #[diagnostic(help("the identifier is equivalent to `{0}`, which should be written instead"))]
+    #[diagnostic(code("Qsc.Parse.NonNormalizedIdent"))]
+    NonNormalizedIdent(String, #[label] Span),
+    #[error("identifier can be confused with `{0}`")]
+    #[diagnostic(help(
+        "the identifier uses compatibility characters, which look like the ones in `{0}`"
+    ))]
+    #[diagnostic(code("Qsc.Parse.ConfusableIdent"))]
+    ConfusableIdent(String, #[label] Span),
+    #[error("expected `|` to separate the branches of a conditional, found {0}")]
+    #[diagnostic(help(
+        "a `?` followed by an expression begins a conditional, so a `?` that propagates a failed variant has to be parenthesized before `-`, `+`, `(` or `[`, as in `(opt?) - 1`"
+    ))]
+    #[diagnostic(code("Qsc.Parse.MissingCondBar"))]
+    MissingCondBar(TokenKind, #[label] Span),
 }
 
 impl ErrorKind {
@@ -153,6 +176,7 @@ impl ErrorKind {
         match self {
             Self::Lex(error) => Self::Lex(error.with_offset(offset)),
             Self::Lit(name, span) => Self::Lit(name, span + offset),
+            Self::FormatSpec(spec, span) => Self::FormatSpec(spec, span + offset),
             Self::Escape(ch, span) => Self::Escape(ch, span + offset),
             Self::Token(expected, actual, span) => Self::Token(expected, actual, span + offset),
             Self::Rule(name, token, span) => Self::Rule(name, token, span + offset),
@@ -166,6 +190,9 @@ impl ErrorKind {
             Self::DotIdentAlias(span) => Self::DotIdentAlias(span + offset),
             Self::InvalidFileName(span, name) => Self::InvalidFileName(span + offset, name),
             Self::ExpectedItem(token, span) => Self::ExpectedItem(token, span + offset),
+            Self::NonNormalizedIdent(name, span) => Self::NonNormalizedIdent(name, span + offset),
+            Self::ConfusableIdent(name, span) => Self::ConfusableIdent(name, span + offset),
+            Self::MissingCondBar(token, span) => Self::MissingCondBar(token, span + offset),
         }
     }
 }
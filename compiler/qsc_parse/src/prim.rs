@@ -12,7 +12,12 @@ use crate::{
     ErrorKind,
 };
 use qsc_ast::ast::{Ident, IncompletePath, NodeId, Pat, PatKind, Path, PathKind};
-use qsc_data_structures::span::{Span, WithSpan};
+use qsc_data_structures::{
+    language_features::LanguageFeatures,
+    span::{Span, WithSpan},
+};
+use std::{borrow::Cow, rc::Rc};
+use unicode_normalization::{is_nfc_quick, is_nfkc_quick, IsNormalized, UnicodeNormalization};
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub(super) enum FinalSep {
@@ -56,7 +61,7 @@ pub(super) fn apos_ident(s: &mut ParserContext) -> Result<Box<Ident>> {
     s.expect(WordKinds::TyParam);
     let peek = s.peek();
     if peek.kind == TokenKind::AposIdent {
-        let name = s.read().into();
+        let name = ident_name(s, peek.span);
         s.advance();
         Ok(Box::new(Ident {
             id: NodeId::default(),
@@ -75,7 +80,7 @@ pub(super) fn apos_ident(s: &mut ParserContext) -> Result<Box<Ident>> {
 pub(super) fn ident(s: &mut ParserContext) -> Result<Box<Ident>> {
     let peek = s.peek();
     if peek.kind == TokenKind::Ident {
-        let name = s.read().into();
+        let name = ident_name(s, peek.span);
         s.advance();
         Ok(Box::new(Ident {
             id: NodeId::default(),
@@ -91,6 +96,31 @@ pub(super) fn ident(s: &mut ParserContext) -> Result<Box<Ident>> {
     }
 }
 
+/// Reads the name of the identifier at the current token, normalized to Unicode normalization form C
+/// so that canonically equivalent spellings of a name are the same identifier.
+fn ident_name(s: &mut ParserContext, span: Span) -> Rc<str> {
+    let name = s.read();
+    let normalized = if is_nfc_quick(name.chars()) == IsNormalized::Yes {
+        Cow::Borrowed(name)
+    } else {
+        Cow::Owned(name.nfc().collect::<String>())
+    };
+
+    if s.contains_language_feature(LanguageFeatures::StrictUnicodeIdentifiers) {
+        if normalized != name {
+            let error = ErrorKind::NonNormalizedIdent(normalized.to_string(), span);
+            s.push_error(Error::new(error));
+        } else if is_nfkc_quick(name.chars()) != IsNormalized::Yes {
+            let compatible = name.nfkc().collect::<String>();
+            if compatible != name {
+                s.push_error(Error::new(ErrorKind::ConfusableIdent(compatible, span)));
+            }
+        }
+    }
+
+    normalized.as_ref().into()
+}
+
 /// A `path` is a dot-separated list of idents like "Foo.Bar.Baz"
 /// this can be a namespace name (in an open statement or namespace declaration),
 /// a reference to an item, like `Microsoft.Quantum.Diagnostics.DumpMachine`,
@@ -8,7 +8,7 @@ use crate::{
     keyword::Keyword,
     lex::{ClosedBinOp, TokenKind},
     scan::ParserContext,
-    tests::{check, check_opt, check_seq},
+    tests::{check, check_opt, check_seq, check_with_features},
     Error, ErrorKind,
 };
 use expect_test::expect;
@@ -61,6 +61,11 @@ fn ident_keyword() {
     for keyword in enum_iterator::all::<Keyword>() {
         let mut scanner = ParserContext::new(keyword.as_str(), LanguageFeatures::default());
         let actual = ident(&mut scanner);
+        if keyword.is_contextual() {
+            assert!(actual.is_ok(), "{keyword}");
+            continue;
+        }
+
         let span = Span {
             lo: 0,
             hi: keyword
@@ -84,6 +89,103 @@ fn ident_keyword() {
     }
 }
 
+#[test]
+fn ident_contextual_keyword_before_name_is_error() {
+    check(
+        ident,
+        "enum Foo",
+        &expect![[r#"
+            Error(
+                Rule(
+                    "identifier",
+                    Keyword(
+                        Enum,
+                    ),
+                    Span {
+                        lo: 0,
+                        hi: 4,
+                    },
+                ),
+            )
+        "#]],
+    );
+}
+
+#[test]
+fn ident_non_ascii() {
+    check(ident, "θ₀", &expect![[r#"Ident _id_ [0-5] "θ₀""#]]);
+}
+
+#[test]
+fn ident_decomposed_is_normalized() {
+    check(
+        ident,
+        "cafe\u{301}",
+        &expect![[r#"Ident _id_ [0-6] "café""#]],
+    );
+}
+
+#[test]
+fn ident_decomposed_is_error_with_strict_unicode_identifiers() {
+    check_with_features(
+        ident,
+        "cafe\u{301}",
+        LanguageFeatures::StrictUnicodeIdentifiers,
+        &expect![[r#"
+            Ident _id_ [0-6] "café"
+
+            [
+                Error(
+                    NonNormalizedIdent(
+                        "café",
+                        Span {
+                            lo: 0,
+                            hi: 6,
+                        },
+                    ),
+                ),
+            ]"#]],
+    );
+}
+
+#[test]
+fn ident_compatibility_characters_are_kept() {
+    check(ident, "\u{fb01}le", &expect![[r#"Ident _id_ [0-5] "ﬁle""#]]);
+}
+
+#[test]
+fn ident_compatibility_characters_are_error_with_strict_unicode_identifiers() {
+    check_with_features(
+        ident,
+        "\u{fb01}le",
+        LanguageFeatures::StrictUnicodeIdentifiers,
+        &expect![[r#"
+            Ident _id_ [0-5] "ﬁle"
+
+            [
+                Error(
+                    ConfusableIdent(
+                        "file",
+                        Span {
+                            lo: 0,
+                            hi: 5,
+                        },
+                    ),
+                ),
+            ]"#]],
+    );
+}
+
+#[test]
+fn ident_normalized_is_allowed_with_strict_unicode_identifiers() {
+    check_with_features(
+        ident,
+        "caf\u{e9}",
+        LanguageFeatures::StrictUnicodeIdentifiers,
+        &expect![[r#"Ident _id_ [0-5] "café""#]],
+    );
+}
+
 #[test]
 fn path_single() {
     check(
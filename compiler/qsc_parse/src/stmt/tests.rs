@@ -23,6 +23,19 @@ fn let_stmt() {
     );
 }
 
+#[test]
+fn let_contextual_keyword_names() {
+    check(
+        parse,
+        "let const = enum;",
+        &expect![[r#"
+            Stmt _id_ [0-17]: Local (Immutable):
+                Pat _id_ [4-9]: Bind:
+                    Ident _id_ [4-9] "const"
+                Expr _id_ [12-16]: Path: Path _id_ [12-16] (Ident _id_ [12-16] "enum")"#]],
+    );
+}
+
 #[test]
 fn let_pat_match() {
     check(
@@ -42,6 +55,22 @@ fn let_pat_match() {
     );
 }
 
+#[test]
+fn let_propagate() {
+    check(
+        parse,
+        "let x = f()?;",
+        &expect![[r#"
+            Stmt _id_ [0-13]: Local (Immutable):
+                Pat _id_ [4-5]: Bind:
+                    Ident _id_ [4-5] "x"
+                Expr _id_ [8-12]: UnOp (Propagate):
+                    Expr _id_ [8-11]: Call:
+                        Expr _id_ [8-9]: Path: Path _id_ [8-9] (Ident _id_ [8-9] "f")
+                        Expr _id_ [9-11]: Unit"#]],
+    );
+}
+
 #[test]
 fn mutable_stmt() {
     check(
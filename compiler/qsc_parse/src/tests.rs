@@ -64,29 +64,47 @@ pub(super) fn check_seq<T: Display>(
 
 /// This function is the same as `check_map`, but it uses the v2 preview syntax language feature.
 fn check_map_v2_preview<T>(
-    mut parser: impl Parser<T>,
+    parser: impl Parser<T>,
     input: &str,
     expect: &Expect,
     f: impl FnOnce(&T) -> String,
 ) {
-    let mut scanner = ParserContext::new(input, LanguageFeatures::V2PreviewSyntax);
-    let result = parser(&mut scanner);
-    let errors = scanner.into_errors();
-    match result {
-        Ok(value) if errors.is_empty() => expect.assert_eq(&f(&value)),
-        Ok(value) => expect.assert_eq(&format!("{}\n\n{errors:#?}", f(&value))),
-        Err(error) if errors.is_empty() => expect.assert_debug_eq(&error),
-        Err(error) => expect.assert_eq(&format!("{error:#?}\n\n{errors:#?}")),
-    }
+    check_map_with_features(parser, input, LanguageFeatures::V2PreviewSyntax, expect, f);
+}
+
+/// This function is the same as `check`, but it uses the given language features.
+pub(super) fn check_with_features<T: Display>(
+    parser: impl Parser<T>,
+    input: &str,
+    language_features: LanguageFeatures,
+    expect: &Expect,
+) {
+    check_map_with_features(
+        parser,
+        input,
+        language_features,
+        expect,
+        ToString::to_string,
+    );
 }
 
 fn check_map<T>(
+    parser: impl Parser<T>,
+    input: &str,
+    expect: &Expect,
+    f: impl FnOnce(&T) -> String,
+) {
+    check_map_with_features(parser, input, LanguageFeatures::default(), expect, f);
+}
+
+fn check_map_with_features<T>(
     mut parser: impl Parser<T>,
     input: &str,
+    language_features: LanguageFeatures,
     expect: &Expect,
     f: impl FnOnce(&T) -> String,
 ) {
-    let mut scanner = ParserContext::new(input, LanguageFeatures::default());
+    let mut scanner = ParserContext::new(input, language_features);
     let result = parser(&mut scanner);
     let errors = scanner.into_errors();
     match result {
@@ -113,6 +113,7 @@ fn fixup_bad_namespace_name_with_dash() {
                             },
                             doc: "",
                             attrs: [],
+                            visibility: None,
                             kind: Callable(
                                 CallableDecl {
                                     id: NodeId(
@@ -298,6 +299,7 @@ oper",
                             },
                             doc: "",
                             attrs: [],
+                            visibility: None,
                             kind: Callable(
                                 CallableDecl {
                                     id: NodeId(
@@ -13,7 +13,7 @@ use super::{
 use crate::{
     completion::WordKinds,
     item::throw_away_doc,
-    lex::{ClosedBinOp, Delim, TokenKind},
+    lex::{ClosedBinOp, Delim, Radix, TokenKind},
     prim::{ident, parse_or_else, recovering_path},
     ErrorKind,
 };
@@ -43,11 +43,15 @@ pub(super) fn recovering_ty(s: &mut ParserContext) -> Result<Ty> {
 
 pub(super) fn array_or_arrow(s: &mut ParserContext<'_>, mut lhs: Ty, lo: u32) -> Result<Ty> {
     loop {
-        if let Some(()) = opt(s, array)? {
+        if let Some(len) = opt(s, array)? {
+            let item = Box::new(lhs);
             lhs = Ty {
                 id: NodeId::default(),
                 span: s.span(lo),
-                kind: Box::new(TyKind::Array(Box::new(lhs))),
+                kind: Box::new(match len {
+                    Some(len) => TyKind::SizedArray(item, len),
+                    None => TyKind::Array(item),
+                }),
             }
         } else if let Some(kind) = opt(s, arrow)? {
             let output = recovering_ty(s)?;
@@ -94,7 +98,7 @@ pub(super) fn param(s: &mut ParserContext) -> Result<TypeParameter> {
 /// This occurs after a `:` in a generic type:
 /// `T: Eq + Iterator[Bool] + Class3`
 ///     ^^^^^^^^^^^^^^^^^^^^^^^^^^^^ bounds
-fn class_constraints(s: &mut ParserContext) -> Result<ClassConstraints> {
+pub(super) fn class_constraints(s: &mut ParserContext) -> Result<ClassConstraints> {
     let mut bounds: Vec<ClassConstraint> = Vec::new();
     loop {
         s.expect(WordKinds::PrimitiveClass);
@@ -122,10 +126,32 @@ fn class_constraints(s: &mut ParserContext) -> Result<ClassConstraints> {
     Ok(ClassConstraints(bounds.into_boxed_slice()))
 }
 
-fn array(s: &mut ParserContext) -> Result<()> {
+fn array(s: &mut ParserContext) -> Result<Option<usize>> {
     token(s, TokenKind::Open(Delim::Bracket))?;
+    let len = opt(s, array_len)?;
     token(s, TokenKind::Close(Delim::Bracket))?;
-    Ok(())
+    Ok(len)
+}
+
+/// Parses the length of a sized array type, which is a decimal integer literal:
+/// `Qubit[3]`
+///        ^ length
+fn array_len(s: &mut ParserContext) -> Result<usize> {
+    let peek = s.peek();
+    if peek.kind != TokenKind::Int(Radix::Decimal) {
+        return Err(Error::new(ErrorKind::Rule(
+            "array length",
+            peek.kind,
+            peek.span,
+        )));
+    }
+    let len = s
+        .read()
+        .replace('_', "")
+        .parse()
+        .map_err(|_| Error::new(ErrorKind::Lit("integer", peek.span)))?;
+    s.advance();
+    Ok(len)
 }
 
 fn arrow(s: &mut ParserContext) -> Result<CallableKind> {
@@ -142,8 +168,8 @@ fn arrow(s: &mut ParserContext) -> Result<CallableKind> {
     }
 }
 
-/// the base type of a type, which can be a hole, a type parameter, a path, or a parenthesized type
-/// (or a tuple)
+/// the base type of a type, which can be a hole, a type parameter, a path (possibly applied to type
+/// arguments), or a parenthesized type (or a tuple)
 /// This parses the part before the arrow or array in a type, if an arrow or array is present.
 fn base(s: &mut ParserContext) -> Result<Ty> {
     throw_away_doc(s);
@@ -153,7 +179,13 @@ fn base(s: &mut ParserContext) -> Result<Ty> {
     } else if let Some(name) = opt(s, param)? {
         Ok(TyKind::Param(name))
     } else if let Some(path) = opt(s, |s| recovering_path(s, WordKinds::PathTy))? {
-        Ok(TyKind::Path(path))
+        if token(s, TokenKind::Lt).is_ok() {
+            let (args, _) = seq(s, ty)?;
+            token(s, TokenKind::Gt)?;
+            Ok(TyKind::App(path, args.into_boxed_slice()))
+        } else {
+            Ok(TyKind::Path(path))
+        }
     } else if token(s, TokenKind::Open(Delim::Paren)).is_ok() {
         let (tys, final_sep) = seq(s, ty)?;
         token(s, TokenKind::Close(Delim::Paren))?;
@@ -126,6 +126,30 @@ fn ty_path2() {
     );
 }
 
+#[test]
+fn ty_app() {
+    check(
+        ty,
+        "Outcome<Int, 'E>",
+        &expect![[r#"
+            Type _id_ [0-16]: App: Path _id_ [0-7] (Ident _id_ [0-7] "Outcome")
+                Type _id_ [8-11]: Path: Path _id_ [8-11] (Ident _id_ [8-11] "Int")
+                Type _id_ [13-15]: Type Param: 'E"#]],
+    );
+}
+
+#[test]
+fn ty_app_nested() {
+    check(
+        ty,
+        "Option<Option<Int>>[]",
+        &expect![[r#"
+            Type _id_ [0-21]: Array: Type _id_ [0-19]: App: Path _id_ [0-6] (Ident _id_ [0-6] "Option")
+                Type _id_ [7-18]: App: Path _id_ [7-13] (Ident _id_ [7-13] "Option")
+                    Type _id_ [14-17]: Path: Path _id_ [14-17] (Ident _id_ [14-17] "Int")"#]],
+    );
+}
+
 #[test]
 fn ty_paren() {
     check(
@@ -196,6 +220,28 @@ fn ty_array2() {
     );
 }
 
+#[test]
+fn ty_sized_array() {
+    check(
+        ty,
+        "Qubit[3]",
+        &expect![[
+            r#"Type _id_ [0-8]: Sized Array (3): Type _id_ [0-5]: Path: Path _id_ [0-5] (Ident _id_ [0-5] "Qubit")"#
+        ]],
+    );
+}
+
+#[test]
+fn ty_array_of_sized_arrays() {
+    check(
+        ty,
+        "Int[2][]",
+        &expect![[
+            r#"Type _id_ [0-8]: Array: Type _id_ [0-6]: Sized Array (2): Type _id_ [0-3]: Path: Path _id_ [0-3] (Ident _id_ [0-3] "Int")"#
+        ]],
+    );
+}
+
 #[test]
 fn ty_tuple_array() {
     check(
@@ -0,0 +1,207 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! Support for residualizing conjugations (`within ... apply ...`).
+//!
+//! By the time a program reaches partial evaluation, conjugate expressions have been replaced by a block with a fixed
+//! shape: the within-block, a binding of the apply-block result, the adjoint-inverted within-block and finally the
+//! apply result. Rather than re-evaluating the adjoint within-block, which may happen after the apply-block has
+//! branched on dynamic values, the partial evaluator records the instructions generated by the within-block and
+//! emits their adjoints in reverse order.
+
+use qsc_data_structures::fx::FxHashSet;
+use qsc_fir::{
+    fir::{
+        Block, BlockId, Expr, ExprId, ExprKind, LocalVarId, Package, PackageLookup, Pat, PatId,
+        PatKind, Res, Stmt, StmtId, StmtKind,
+    },
+    visit::{walk_expr, walk_pat, Visitor},
+};
+use qsc_rir::{
+    builder,
+    rir::{Literal, Operand},
+};
+
+/// The name given to the binding of the apply-block result when conjugate expressions are eliminated.
+const APPLY_RESULT_NAME: &str = "@apply_res";
+
+/// The statements that make up a conjugate block.
+pub struct Conjugate {
+    pub within_block: BlockId,
+    pub within_stmt: StmtId,
+    pub apply_stmt: StmtId,
+    pub adj_within_stmt: StmtId,
+    pub result_stmt: StmtId,
+}
+
+/// Identifies a block generated from a conjugate expression.
+pub fn try_get_conjugate(package: &Package, block: &Block) -> Option<Conjugate> {
+    let [within_stmt, apply_stmt, adj_within_stmt, result_stmt] = block.stmts[..] else {
+        return None;
+    };
+
+    let within_block = get_block_stmt(package, within_stmt)?;
+    get_block_stmt(package, adj_within_stmt)?;
+
+    let StmtKind::Local(_, pat_id, apply_expr_id) = package.get_stmt(apply_stmt).kind else {
+        return None;
+    };
+    let PatKind::Bind(ident) = &package.get_pat(pat_id).kind else {
+        return None;
+    };
+    if ident.name.as_ref() != APPLY_RESULT_NAME
+        || !matches!(package.get_expr(apply_expr_id).kind, ExprKind::Block(_))
+    {
+        return None;
+    }
+
+    let StmtKind::Expr(result_expr_id) = package.get_stmt(result_stmt).kind else {
+        return None;
+    };
+    let ExprKind::Var(Res::Local(local_var_id), _) = package.get_expr(result_expr_id).kind else {
+        return None;
+    };
+    if local_var_id != ident.id {
+        return None;
+    }
+
+    Some(Conjugate {
+        within_block,
+        within_stmt,
+        apply_stmt,
+        adj_within_stmt,
+        result_stmt,
+    })
+}
+
+fn get_block_stmt(package: &Package, stmt_id: StmtId) -> Option<BlockId> {
+    let StmtKind::Expr(expr_id) = package.get_stmt(stmt_id).kind else {
+        return None;
+    };
+    match package.get_expr(expr_id).kind {
+        ExprKind::Block(block_id) => Some(block_id),
+        _ => None,
+    }
+}
+
+/// Whether the effects of a within-block are fully captured by the instructions it generates.
+/// This is not the case when the block assigns to variables declared outside of it, since the adjoint within-block
+/// would perform those assignments again.
+pub fn is_within_block_replayable(package: &Package, block_id: BlockId) -> bool {
+    let mut checker = OuterAssignmentChecker {
+        package,
+        declared: FxHashSet::default(),
+        assigns_outer: false,
+    };
+    checker.visit_block(block_id);
+    !checker.assigns_outer
+}
+
+struct OuterAssignmentChecker<'a> {
+    package: &'a Package,
+    declared: FxHashSet<LocalVarId>,
+    assigns_outer: bool,
+}
+
+impl OuterAssignmentChecker<'_> {
+    fn check_assignee(&mut self, expr_id: ExprId) {
+        match &self.package.get_expr(expr_id).kind {
+            ExprKind::Var(Res::Local(local_var_id), _) => {
+                if !self.declared.contains(local_var_id) {
+                    self.assigns_outer = true;
+                }
+            }
+            ExprKind::Tuple(exprs) => {
+                for expr_id in exprs {
+                    self.check_assignee(*expr_id);
+                }
+            }
+            ExprKind::Hole => {}
+            _ => self.assigns_outer = true,
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for OuterAssignmentChecker<'a> {
+    fn get_block(&self, id: BlockId) -> &'a Block {
+        self.package.get_block(id)
+    }
+
+    fn get_expr(&self, id: ExprId) -> &'a Expr {
+        self.package.get_expr(id)
+    }
+
+    fn get_pat(&self, id: PatId) -> &'a Pat {
+        self.package.get_pat(id)
+    }
+
+    fn get_stmt(&self, id: StmtId) -> &'a Stmt {
+        self.package.get_stmt(id)
+    }
+
+    fn visit_expr(&mut self, expr_id: ExprId) {
+        match self.get_expr(expr_id).kind {
+            ExprKind::Assign(lhs, _)
+            | ExprKind::AssignField(lhs, _, _)
+            | ExprKind::AssignIndex(lhs, _, _)
+            | ExprKind::AssignOp(_, lhs, _) => self.check_assignee(lhs),
+            _ => {}
+        }
+        walk_expr(self, expr_id);
+    }
+
+    fn visit_pat(&mut self, pat_id: PatId) {
+        if let PatKind::Bind(ident) = &self.get_pat(pat_id).kind {
+            self.declared.insert(ident.id);
+        }
+        walk_pat(self, pat_id);
+    }
+}
+
+/// How the adjoint of an intrinsic quantum instruction is obtained.
+pub enum Inversion {
+    /// The instruction is its own adjoint.
+    SelfAdjoint,
+    /// The adjoint is a call to a different intrinsic with the same arguments.
+    Callable(&'static str),
+    /// The adjoint is a call to the same intrinsic with its rotation angle negated.
+    NegatedAngle,
+}
+
+/// Gets how to invert a call to the QIS intrinsic with the given name, if it is known.
+pub fn get_inversion(callable_name: &str) -> Option<Inversion> {
+    match callable_name {
+        "__quantum__qis__ccx__body"
+        | "__quantum__qis__cx__body"
+        | "__quantum__qis__cy__body"
+        | "__quantum__qis__cz__body"
+        | "__quantum__qis__h__body"
+        | "__quantum__qis__swap__body"
+        | "__quantum__qis__x__body"
+        | "__quantum__qis__y__body"
+        | "__quantum__qis__z__body" => Some(Inversion::SelfAdjoint),
+        "__quantum__qis__s__body" => Some(Inversion::Callable("__quantum__qis__s__adj")),
+        "__quantum__qis__s__adj" => Some(Inversion::Callable("__quantum__qis__s__body")),
+        "__quantum__qis__t__body" => Some(Inversion::Callable("__quantum__qis__t__adj")),
+        "__quantum__qis__t__adj" => Some(Inversion::Callable("__quantum__qis__t__body")),
+        "__quantum__qis__rx__body"
+        | "__quantum__qis__rxx__body"
+        | "__quantum__qis__ry__body"
+        | "__quantum__qis__ryy__body"
+        | "__quantum__qis__rz__body"
+        | "__quantum__qis__rzz__body" => Some(Inversion::NegatedAngle),
+        name if name.starts_with(builder::EXP_PREFIX) => Some(Inversion::NegatedAngle),
+        _ => None,
+    }
+}
+
+/// Negates the angle operand of a rotation, which is always its first operand.
+/// Only literal angles can be negated without generating additional instructions.
+pub fn negate_angle(args: &[Operand]) -> Option<Vec<Operand>> {
+    let (Operand::Literal(Literal::Double(angle)), qubits) = args.split_first()? else {
+        return None;
+    };
+    let mut negated = vec![Operand::Literal(Literal::Double(-angle))];
+    negated.extend_from_slice(qubits);
+    Some(negated)
+}
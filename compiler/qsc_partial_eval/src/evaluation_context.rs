@@ -1,15 +1,14 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
-use qsc_data_structures::functors::FunctorApp;
+use qsc_data_structures::{functors::FunctorApp, fx::FxHashMap};
 use qsc_eval::{
     val::{Result, Value},
     Env, Variable,
 };
-use qsc_fir::fir::{LocalItemId, LocalVarId, PackageId};
+use qsc_fir::fir::{LocalItemId, LocalVarId, PackageId, StoreItemId};
 use qsc_rca::{RuntimeKind, ValueKind};
 use qsc_rir::rir::{BlockId, Literal, VariableId};
-use rustc_hash::FxHashMap;
 use std::collections::hash_map::Entry;
 
 /// Struct that keeps track of the active RIR blocks (where RIR instructions are added) and the active scopes (which
@@ -51,6 +50,16 @@ impl EvaluationContext {
             .expect("the evaluation context does not have a current scope")
     }
 
+    /// Gets the callables of the active (call) scopes, from the outermost to the innermost.
+    pub fn get_scope_callables(&self) -> impl Iterator<Item = StoreItemId> + '_ {
+        self.scopes.iter().filter_map(|scope| {
+            scope.callable.map(|(item, _)| StoreItemId {
+                package: scope.package_id,
+                item,
+            })
+        })
+    }
+
     /// Pops the currently active block.
     pub fn pop_block_node(&mut self) -> BlockNode {
         self.get_current_scope_mut().active_block_count -= 1;
@@ -327,7 +336,7 @@ fn map_eval_value_to_value_kind(value: &Value) -> ValueKind {
         | Value::Pauli(_)
         | Value::Qubit(_)
         | Value::Range(_)
-        | Value::Result(Result::Val(_))
+        | Value::Result(Result::Val(_) | Result::Loss)
         | Value::String(_) => ValueKind::Element(RuntimeKind::Static),
     }
 }
@@ -8,33 +8,40 @@
 #[cfg(test)]
 mod tests;
 
+mod conjugation;
 mod evaluation_context;
+mod loops;
 mod management;
 
+use conjugation::{Conjugate, Inversion};
 use core::panic;
 use evaluation_context::{
     Arg, BlockNode, BranchControlFlow, EvalControlFlow, EvaluationContext, Scope,
 };
-use management::{QuantumIntrinsicsChecker, ResourceManager};
+use management::{MessageReceiver, QuantumIntrinsicsChecker, ResourceManager};
 use miette::Diagnostic;
-use qsc_data_structures::{functors::FunctorApp, span::Span, target::TargetCapabilityFlags};
+use qsc_data_structures::{
+    functors::FunctorApp,
+    fx::{FxHashMap, FxHashSet},
+    span::Span,
+    target::TargetCapabilityFlags,
+};
 use qsc_eval::{
     self, are_ctls_unique, exec_graph_section,
     intrinsic::qubit_relabel,
-    output::GenericReceiver,
-    resolve_closure,
     val::{
         self, index_array, slice_array, update_functor_app, update_index_range,
         update_index_single, Value, Var, VarTy,
     },
-    Error as EvalError, PackageSpan, State, StepAction, StepResult, Variable,
+    Env, Error as EvalError, IntrinsicPolicy, PackageSpan, Quota, State, StepAction, StepResult,
+    Variable,
 };
 use qsc_fir::{
     fir::{
         self, BinOp, Block, BlockId, CallableDecl, CallableImpl, ExecGraph, Expr, ExprId, ExprKind,
-        Global, Ident, LocalVarId, Mutability, PackageId, PackageStore, PackageStoreLookup, Pat,
-        PatId, PatKind, Res, SpecDecl, SpecImpl, Stmt, StmtId, StmtKind, StoreBlockId, StoreExprId,
-        StoreItemId, StorePatId, StoreStmtId, UnOp,
+        Global, Ident, LocalVarId, Mutability, PackageId, PackageLookup, PackageStore,
+        PackageStoreLookup, Pat, PatId, PatKind, Res, SpecDecl, SpecImpl, Stmt, StmtId, StmtKind,
+        StoreBlockId, StoreExprId, StoreItemId, StorePatId, StoreStmtId, UnOp,
     },
     ty::{Prim, Ty},
 };
@@ -49,42 +56,133 @@ use qsc_rca::{
 };
 use qsc_rir::{
     builder,
+    passes::renumber_ids,
     rir::{
         self, Callable, CallableId, CallableType, ConditionCode, FcmpConditionCode, Instruction,
-        Literal, Operand, Program, VariableId,
+        Literal, Operand, OutputKind, OutputPosition, Program, VariableId,
     },
 };
-use rustc_hash::FxHashMap;
 use std::{collections::hash_map::Entry, rc::Rc, result::Result};
 use thiserror::Error;
 
-/// Partially evaluates a program with the specified entry expression.
+/// Options that control the program produced by partial evaluation.
+#[derive(Clone, Copy, Debug)]
+pub struct PartialEvalConfig {
+    /// Whether block, callable and variable IDs are renumbered in a canonical order once evaluation completes.
+    /// Otherwise, IDs are handed out in evaluation order, which can shift when unrelated code changes.
+    pub canonical_ids: bool,
+    /// How the output of the program is recorded.
+    pub output_recording: OutputRecording,
+    /// Whether calls to `Exp` whose Pauli operators are known act on at least two qubits are emitted as a single call
+    /// to a multi-qubit Pauli rotation intrinsic, such as `__quantum__qis__exp_xz__body`, instead of being decomposed
+    /// into two-qubit gates. Only targets that declare support for these intrinsics accept them.
+    pub pauli_rotations: bool,
+    /// What happens to calls to `Message`.
+    pub messages: Messages,
+    /// The measurement intrinsics the target implements, which the measurements of the program are emitted as.
+    pub measurements: Measurements,
+    /// The number of times a callable can appear in the chain of calls being evaluated before its recursion is
+    /// reported as an error, if any. Recursive calls are inlined, so with a limit, recursion that doesn't end at
+    /// compile time is reported instead of never finishing. There is no limit by default, so that recursion bounded
+    /// by compile-time values can be as deep as it needs to be.
+    pub recursion_limit: Option<usize>,
+}
+
+impl Default for PartialEvalConfig {
+    fn default() -> Self {
+        Self {
+            canonical_ids: false,
+            output_recording: OutputRecording::default(),
+            pauli_rotations: false,
+            messages: Messages::default(),
+            measurements: Measurements::default(),
+            recursion_limit: None,
+        }
+    }
+}
+
+/// The measurement intrinsics a target implements natively.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Measurements {
+    /// Both `__quantum__qis__m__body`, which leaves the qubit in its measured state, and
+    /// `__quantum__qis__mresetz__body`, which resets the qubit after measuring it.
+    #[default]
+    MAndMResetZ,
+    /// Only `__quantum__qis__m__body`. Measurements that reset the qubit are emitted as a call to it followed by a
+    /// call to `__quantum__qis__reset__body`.
+    M,
+    /// Only `__quantum__qis__mz__body`, which leaves the qubit in its measured state. Measurements that reset the
+    /// qubit are emitted as a call to it followed by a call to `__quantum__qis__reset__body`.
+    Mz,
+}
+
+/// What happens to calls to `Message`, whose text can only be displayed during simulation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Messages {
+    /// The calls are dropped from the program, and a warning is reported for each of them.
+    #[default]
+    Drop,
+    /// Each call is lowered to a call to `__quantum__rt__message_record_output` with a pointer to the text of the
+    /// message in the string table of the program, for targets that support annotation records in their output.
+    Record,
+}
+
+/// How the calls that record the output of a program label the values they record, since backends expect
+/// different labeling schemes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputRecording {
+    /// Values are identified by the order in which they are recorded, so their labels are null pointers. Values
+    /// named with an `OutputName` attribute are still labeled with their names.
+    #[default]
+    Ordinal,
+    /// Every value is labeled with a string. Values that aren't named with an `OutputName` attribute are labeled
+    /// after their position in the output, such as `output.0` or `output[2]`.
+    Labeled,
+    /// The output is not recorded, as for simulators that read the output some other way.
+    None,
+}
+
+/// Partially evaluates a program with the specified entry expression, appending any warnings to `warnings`.
 pub fn partially_evaluate(
     package_store: &PackageStore,
     compute_properties: &PackageStoreComputeProperties,
     entry: &ProgramEntry,
     capabilities: TargetCapabilityFlags,
+    config: PartialEvalConfig,
+    warnings: &mut Vec<Warning>,
 ) -> Result<Program, Error> {
-    let partial_evaluator =
-        PartialEvaluator::new(package_store, compute_properties, entry, capabilities);
-    partial_evaluator.eval()
+    let partial_evaluator = PartialEvaluator::new(
+        package_store,
+        compute_properties,
+        entry,
+        capabilities,
+        config,
+    );
+    let (program, evaluation_warnings) = partial_evaluator.eval()?;
+    warnings.extend(evaluation_warnings);
+    Ok(program)
 }
 
-/// Partially evaluates a callable with the specified arguments.
+/// Partially evaluates a callable with the specified arguments, appending any warnings to `warnings`.
 pub fn partially_evaluate_call(
     package_store: &PackageStore,
     compute_properties: &PackageStoreComputeProperties,
     callable: StoreItemId,
     args: Value,
     capabilities: TargetCapabilityFlags,
+    config: PartialEvalConfig,
+    warnings: &mut Vec<Warning>,
 ) -> Result<Program, Error> {
     let partial_evaluator = PartialEvaluator::new_from_package_id(
         package_store,
         compute_properties,
         callable.package,
         capabilities,
+        config,
     );
-    partial_evaluator.invoke(callable, args)
+    let (program, evaluation_warnings) = partial_evaluator.invoke(callable, args)?;
+    warnings.extend(evaluation_warnings);
+    Ok(program)
 }
 
 /// A partial evaluation error.
@@ -120,11 +218,45 @@ pub enum Error {
     #[error("failed to evaluate: {0} is not supported")]
     #[diagnostic(code("Qsc.PartialEval.Unimplemented"))]
     Unimplemented(String, #[label] PackageSpan),
+
+    #[error("cannot check for qubit loss")]
+    #[diagnostic(help(
+        "checking whether a measurement result indicates qubit loss is not supported by the configured target profile"
+    ))]
+    #[diagnostic(code("Qsc.PartialEval.UnsupportedLossCheck"))]
+    UnsupportedLossCheck(#[label] PackageSpan),
+
+    #[error("cannot call host function `{0}`")]
+    #[diagnostic(help(
+        "functions with the `@HostCallable()` attribute are implemented by the host running the program, so they can only be called during simulation"
+    ))]
+    #[diagnostic(code("Qsc.PartialEval.UnsupportedHostCall"))]
+    UnsupportedHostCall(String, #[label] PackageSpan),
+
+    #[error("cannot convert a dynamic value with `{0}`")]
+    #[diagnostic(help(
+        "checked conversions from `BigInt` can only be applied to values known at compile time, since the generated code has no big integers"
+    ))]
+    #[diagnostic(code("Qsc.PartialEval.UnsupportedDynamicConversion"))]
+    UnsupportedDynamicConversion(String, #[label] PackageSpan),
+
+    #[error("recursion limit of {1} exceeded by `{0}`")]
+    #[diagnostic(help(
+        "recursive calls are inlined when the program is generated, so recursion has to end based on values known at compile time"
+    ))]
+    #[diagnostic(code("Qsc.PartialEval.RecursionLimit"))]
+    RecursionLimit(String, usize, #[label] PackageSpan),
 }
 
 impl From<EvalError> for Error {
     fn from(e: EvalError) -> Self {
-        Error::EvaluationFailed(e.to_string(), *e.span())
+        match e {
+            // Host functions are only registered during simulation, so the classical evaluator never finds them.
+            EvalError::HostFunctionNotRegistered(name, span) => {
+                Error::UnsupportedHostCall(name, span)
+            }
+            _ => Error::EvaluationFailed(e.to_string(), *e.span()),
+        }
     }
 }
 
@@ -137,7 +269,32 @@ impl Error {
             | Self::EvaluationFailed(_, span)
             | Self::OutputResultLiteral(span)
             | Self::Unexpected(_, span)
-            | Self::Unimplemented(_, span) => Some(*span),
+            | Self::Unimplemented(_, span)
+            | Self::UnsupportedLossCheck(span)
+            | Self::UnsupportedHostCall(_, span)
+            | Self::UnsupportedDynamicConversion(_, span)
+            | Self::RecursionLimit(_, _, span) => Some(*span),
+        }
+    }
+}
+
+/// A partial evaluation warning, for a program that can be generated but behaves differently than in simulation.
+#[derive(Clone, Debug, Diagnostic, Error)]
+pub enum Warning {
+    #[error("call to `Message` is dropped from the generated program")]
+    #[diagnostic(severity(Warning))]
+    #[diagnostic(help(
+        "messages are only displayed during simulation, unless the generated program is configured to record them in its output"
+    ))]
+    #[diagnostic(code("Qsc.PartialEval.MessageDropped"))]
+    MessageDropped(#[label] PackageSpan),
+}
+
+impl Warning {
+    #[must_use]
+    pub fn span(&self) -> PackageSpan {
+        match self {
+            Self::MessageDropped(span) => *span,
         }
     }
 }
@@ -159,6 +316,8 @@ struct PartialEvaluator<'a> {
     eval_context: EvaluationContext,
     program: Program,
     entry: Option<&'a ProgramEntry>,
+    config: PartialEvalConfig,
+    warnings: Vec<Warning>,
 }
 
 impl<'a> PartialEvaluator<'a> {
@@ -167,11 +326,13 @@ impl<'a> PartialEvaluator<'a> {
         compute_properties: &'a PackageStoreComputeProperties,
         entry: &'a ProgramEntry,
         capabilities: TargetCapabilityFlags,
+        config: PartialEvalConfig,
     ) -> Self {
         Self::new_internal(
             package_store,
             compute_properties,
             capabilities,
+            config,
             Some(entry),
             None,
         )
@@ -182,11 +343,13 @@ impl<'a> PartialEvaluator<'a> {
         compute_properties: &'a PackageStoreComputeProperties,
         package_id: PackageId,
         capabilities: TargetCapabilityFlags,
+        config: PartialEvalConfig,
     ) -> Self {
         Self::new_internal(
             package_store,
             compute_properties,
             capabilities,
+            config,
             None,
             Some(package_id),
         )
@@ -196,6 +359,7 @@ impl<'a> PartialEvaluator<'a> {
         package_store: &'a PackageStore,
         compute_properties: &'a PackageStoreComputeProperties,
         capabilities: TargetCapabilityFlags,
+        config: PartialEvalConfig,
         entry: Option<&'a ProgramEntry>,
         package_id: Option<PackageId>,
     ) -> Self {
@@ -205,6 +369,7 @@ impl<'a> PartialEvaluator<'a> {
         program.config.capabilities = capabilities;
         let entry_block_id = resource_manager.next_block();
         program.blocks.insert(entry_block_id, rir::Block::default());
+        program.block_names.insert(entry_block_id, "entry".into());
         let entry_point_id = resource_manager.next_callable();
         let entry_point = rir::Callable {
             name: "main".into(),
@@ -235,6 +400,8 @@ impl<'a> PartialEvaluator<'a> {
             callables_map: FxHashMap::default(),
             program,
             entry,
+            config,
+            warnings: Vec::new(),
         }
     }
 
@@ -294,6 +461,13 @@ impl<'a> PartialEvaluator<'a> {
 
         // Always bind the value to the hybrid map but do it differently depending of the value type.
         if let Some((var_id, literal)) = self.try_create_mutable_variable(ident.id, &value) {
+            // Keep track of the identifier the variable was created for, unless it was generated by the compiler.
+            if !ident.name.starts_with('@') {
+                self.program
+                    .variable_names
+                    .insert(var_id, ident.name.clone());
+            }
+
             // If the variable maps to a know static literal, track that mapping.
             if let Some(literal) = literal {
                 self.eval_context
@@ -356,9 +530,10 @@ impl<'a> PartialEvaluator<'a> {
         }
     }
 
-    fn create_program_block(&mut self) -> rir::BlockId {
+    fn create_program_block(&mut self, name: &str) -> rir::BlockId {
         let block_id = self.resource_manager.next_block();
         self.program.blocks.insert(block_id, rir::Block::default());
+        self.program.block_names.insert(block_id, name.into());
         block_id
     }
 
@@ -389,14 +564,65 @@ impl<'a> PartialEvaluator<'a> {
         }
     }
 
+    /// The names given to the output of the callable with its `OutputName` attribute, if any.
+    fn output_names(&self, callable: StoreItemId) -> &'a [Rc<str>] {
+        self.package_store
+            .get_item(callable)
+            .attrs
+            .iter()
+            .find_map(|attr| match attr {
+                fir::Attr::OutputName(names) => Some(names.as_slice()),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    /// The names given to the output of the entry expression, when it is a call to a callable with the
+    /// `OutputName` attribute.
+    fn entry_expr_output_names(&self) -> &'a [Rc<str>] {
+        let entry = self
+            .entry
+            .expect("should have entry when getting entry expr output names");
+        let expr = self.package_store.get_expr(entry.expr);
+        if let ExprKind::Call(callee, _) = &expr.kind {
+            let callee = self
+                .package_store
+                .get_expr(StoreExprId::from((entry.expr.package, *callee)));
+            if let ExprKind::Var(Res::Item(item), _) = &callee.kind {
+                return self.output_names(StoreItemId {
+                    package: item.package.unwrap_or(entry.expr.package),
+                    item: item.item,
+                });
+            }
+        }
+        &[]
+    }
+
     fn extract_program(
         mut self,
         ret_val: Value,
         output_ty: &Ty,
         output_span: PackageSpan,
-    ) -> Result<Program, Error> {
-        let output_recording: Vec<Instruction> = self
-            .generate_output_recording_instructions(ret_val, output_ty)
+        output_names: &[Rc<str>],
+    ) -> Result<(Program, Vec<Warning>), Error> {
+        // A single name labels the whole output, while several name the items of the output tuple.
+        // Otherwise, the output is only labeled when every value must be.
+        let label = (self.config.output_recording == OutputRecording::Labeled).then_some("output");
+        let output_recording: Vec<Instruction> =
+            match (self.config.output_recording, output_names, ret_val) {
+                (OutputRecording::None, _, _) => Ok(Vec::new()),
+                (_, [name], ret_val) => {
+                    self.generate_output_recording_instructions(ret_val, output_ty, Some(name), &[])
+                }
+                (_, names @ [_, _, ..], Value::Tuple(vals)) => {
+                    let mut instrs = Vec::new();
+                    self.record_tuple(output_ty, &mut instrs, &vals, label, names, &[])
+                        .map(|()| instrs)
+                }
+                (_, _, ret_val) => {
+                    self.generate_output_recording_instructions(ret_val, output_ty, label, &[])
+                }
+            }
             .map_err(|()| Error::OutputResultLiteral(output_span))?;
 
         // Insert the return expression and return the generated program.
@@ -416,10 +642,14 @@ impl<'a> PartialEvaluator<'a> {
             .try_into()
             .expect("results count should fit into a u32");
 
-        Ok(self.program)
+        if self.config.canonical_ids {
+            renumber_ids(&mut self.program);
+        }
+
+        Ok((self.program, self.warnings))
     }
 
-    fn eval(mut self) -> Result<Program, Error> {
+    fn eval(mut self) -> Result<(Program, Vec<Warning>), Error> {
         // Evaluate the entry-point expression.
         let ret_val = self
             .try_eval_expr(
@@ -438,10 +668,15 @@ impl<'a> PartialEvaluator<'a> {
             )
             .ty;
         let output_span = self.entry_expr_output_span();
-        self.extract_program(ret_val, output_ty, output_span)
+        let output_names = self.entry_expr_output_names();
+        self.extract_program(ret_val, output_ty, output_span, output_names)
     }
 
-    fn invoke(mut self, callable: StoreItemId, args: Value) -> Result<Program, Error> {
+    fn invoke(
+        mut self,
+        callable: StoreItemId,
+        args: Value,
+    ) -> Result<(Program, Vec<Warning>), Error> {
         // Evaluate the callalbe.
         let ret_val = self.eval_global_call(callable, args)?.into_value();
         let global = self
@@ -453,6 +688,7 @@ impl<'a> PartialEvaluator<'a> {
             panic!("global is not a callable");
         };
         let output_ty = &callable_decl.output;
+        let output_names = self.output_names(callable);
         self.extract_program(
             ret_val,
             output_ty,
@@ -460,6 +696,7 @@ impl<'a> PartialEvaluator<'a> {
                 package: map_fir_package_to_hir(callable.package),
                 span: callable_decl.span,
             },
+            output_names,
         )
     }
 
@@ -862,7 +1099,7 @@ impl<'a> PartialEvaluator<'a> {
 
         // Pop the current block and insert the continuation block.
         let current_block_node = self.eval_context.pop_block_node();
-        let continuation_block_id = self.create_program_block();
+        let continuation_block_id = self.create_program_block("logical_continue");
         let continuation_block_node = BlockNode {
             id: continuation_block_id,
             successor: current_block_node.successor,
@@ -870,7 +1107,7 @@ impl<'a> PartialEvaluator<'a> {
         self.eval_context.push_block_node(continuation_block_node);
 
         // Now insert the conditional block.
-        let rhs_eval_block_id = self.create_program_block();
+        let rhs_eval_block_id = self.create_program_block("logical_rhs");
         let rhs_eval_block_node = BlockNode {
             id: rhs_eval_block_id,
             successor: Some(continuation_block_id),
@@ -1069,14 +1306,19 @@ impl<'a> PartialEvaluator<'a> {
         let scope = self.eval_context.get_current_scope_mut();
         let exec_graph = exec_graph_section(&scope_exec_graph, expr.exec_graph_range.clone());
         let mut state = State::new(current_package_id, exec_graph, None);
+        let mut receiver = MessageReceiver::default();
         let classical_result = state.eval(
             self.package_store,
             &mut scope.env,
             &mut self.backend,
-            &mut GenericReceiver::new(&mut std::io::sink()),
+            &mut receiver,
             &[],
             StepAction::Continue,
         );
+        let expr_package_span = self.get_expr_package_span(expr_id);
+        for message in receiver.messages {
+            self.handle_message(&message, expr_package_span);
+        }
         let eval_result = match classical_result {
             Ok(step_result) => {
                 let StepResult::Return(value) = step_result else {
@@ -1145,14 +1387,7 @@ impl<'a> PartialEvaluator<'a> {
                 self.eval_expr_call(expr_id, *callee_expr_id, *args_expr_id)
             }
             ExprKind::Closure(args, callable) => {
-                let closure = resolve_closure(
-                    &self.eval_context.get_current_scope().env,
-                    self.get_current_package_id(),
-                    expr.span,
-                    args,
-                    *callable,
-                )
-                .map_err(Error::from)?;
+                let closure = self.eval_expr_closure(args, *callable);
                 Ok(EvalControlFlow::Continue(closure))
             }
             ExprKind::Fail(_) => Err(Error::Unexpected(
@@ -1357,9 +1592,53 @@ impl<'a> PartialEvaluator<'a> {
         let args_span = self.get_expr_package_span(args_expr_id);
         let (callee_control_flow, args_control_flow) =
             self.try_eval_callee_and_args(callee_expr_id, args_expr_id)?;
+        let callee_value = callee_control_flow.into_value();
+        let args_value = args_control_flow.into_value();
+        if let Value::Global(store_item_id, functor_app) = callee_value {
+            if let Some(result) = self.try_eval_pure_call(store_item_id, &args_value) {
+                return result.map(EvalControlFlow::Continue);
+            }
+            if let Some(value) =
+                self.try_eval_exp_as_pauli_rotation(store_item_id, functor_app, &args_value)
+            {
+                return Ok(EvalControlFlow::Continue(value));
+            }
+            if let Some(result) = self.try_eval_std_higher_order_call(
+                call_expr_id,
+                callee_expr_id,
+                store_item_id,
+                &args_value,
+                args_span,
+            ) {
+                return result.map(EvalControlFlow::Continue);
+            }
+        }
 
+        let call_was_unresolved = self.is_unresolved_callee_expr(callee_expr_id);
+        let value = self.eval_call_to_value(
+            call_expr_id,
+            callee_expr_id,
+            callee_value,
+            args_value,
+            args_span,
+            call_was_unresolved,
+        )?;
+        Ok(EvalControlFlow::Continue(value))
+    }
+
+    /// Calls a callable value with the given arguments. The call, callee and arguments expressions are only used to
+    /// report errors and to check the compute kind of the call when RCA could not resolve the callee statically.
+    fn eval_call_to_value(
+        &mut self,
+        call_expr_id: ExprId,
+        callee_expr_id: ExprId,
+        callee_value: Value,
+        args_value: Value,
+        args_span: PackageSpan,
+        call_was_unresolved: bool,
+    ) -> Result<Value, Error> {
         // Get the callable.
-        let (store_item_id, functor_app, fixed_args) = match callee_control_flow.into_value() {
+        let (store_item_id, functor_app, fixed_args) = match callee_value {
             Value::Closure(inner) => (inner.id, inner.functor, Some(inner.fixed_args)),
             Value::Global(id, functor) => (id, functor, None),
             _ => panic!("value is not callable"),
@@ -1381,7 +1660,6 @@ impl<'a> PartialEvaluator<'a> {
             None
         };
 
-        let args_value = args_control_flow.into_value();
         let ctls = if let Some(Some(ctls_pat_id)) = spec_decl.map(|spec_decl| spec_decl.input) {
             assert!(
                 functor_app.controlled > 0,
@@ -1416,7 +1694,6 @@ impl<'a> PartialEvaluator<'a> {
         // Now that we are in evaluation, we have a distinct callable resolved and can perform runtime capability check
         // ahead of performing the actual call and return the appropriate capabilities error if this call is not supported
         // by the target.
-        let call_was_unresolved = self.is_unresolved_callee_expr(callee_expr_id);
         if call_was_unresolved {
             let call_compute_kind = self.get_call_compute_kind(&call_scope);
             if let ComputeKind::Quantum(QuantumProperties {
@@ -1440,10 +1717,10 @@ impl<'a> PartialEvaluator<'a> {
                     }
                 }
 
-                // If the call produces a dynamic value, we treat it as an error because we know that later
-                // analysis has not taken that dynamism into account and further partial evaluation may fail
-                // when it encounters that value.
-                if value_kind.is_dynamic() {
+                // If the call produces a dynamic value that RCA did not expect, we treat it as an error because we
+                // know that later analysis has not taken that dynamism into account and further partial evaluation
+                // may fail when it encounters that value.
+                if value_kind.is_dynamic() && !self.is_dynamic_expr(call_expr_id) {
                     return Err(Error::UnexpectedDynamicValue(
                         self.get_expr_package_span(call_expr_id),
                     ));
@@ -1465,10 +1742,182 @@ impl<'a> PartialEvaluator<'a> {
                 )?
             }
             Some(spec_decl) => {
+                self.check_recursion_limit(store_item_id, call_expr_id)?;
                 self.eval_expr_call_to_spec(call_scope, store_item_id, functor_app, spec_decl)?
             }
         };
-        Ok(EvalControlFlow::Continue(value))
+        Ok(value)
+    }
+
+    /// Evaluates a call to a function with the pure attribute classically when all of its arguments are known. Such a
+    /// function has no side effects and its result only depends on its arguments, so the call is folded into its
+    /// result even when RCA could not tell that the arguments are static, such as when the callee is only resolved
+    /// during partial evaluation. Returns `None` when the call has to be evaluated through the body of the callee
+    /// instead.
+    fn try_eval_pure_call(
+        &mut self,
+        store_item_id: StoreItemId,
+        args_value: &Value,
+    ) -> Option<Result<Value, Error>> {
+        let Global::Callable(callable_decl) = self.package_store.get_global(store_item_id)? else {
+            return None;
+        };
+        if callable_decl.kind != fir::CallableKind::Function
+            || !callable_decl.attrs.contains(&fir::Attr::Pure)
+            || callable_decl.attrs.contains(&fir::Attr::HostCallable)
+            || !is_static_value(args_value)
+        {
+            return None;
+        }
+
+        let mut env = Env::default();
+        let mut receiver = MessageReceiver::default();
+        let result = qsc_eval::invoke(
+            store_item_id.package,
+            None,
+            Quota::default(),
+            &IntrinsicPolicy::default(),
+            self.package_store,
+            &mut env,
+            &mut self.backend,
+            &mut receiver,
+            Value::Global(store_item_id, FunctorApp::default()),
+            args_value.clone(),
+        );
+        Some(result.map_err(|(error, _)| Error::from(error)))
+    }
+
+    /// Evaluates a call to `Mapped`, `Fold` or `ForEach` from `Std.Arrays` by calling its callable argument on each
+    /// element of the array. Since the length of the array is known, the iteration count is static, and the callable
+    /// is resolved for every call even if RCA could not resolve it within the body of the higher-order function.
+    /// Returns `None` when the call has to be evaluated through the body of the callee instead.
+    fn try_eval_std_higher_order_call(
+        &mut self,
+        call_expr_id: ExprId,
+        callee_expr_id: ExprId,
+        store_item_id: StoreItemId,
+        args_value: &Value,
+        args_span: PackageSpan,
+    ) -> Option<Result<Value, Error>> {
+        let name = ["Fold", "ForEach", "Mapped"]
+            .into_iter()
+            .find(|name| self.is_std_callable(store_item_id, "Std.Arrays", name))?;
+        let Value::Tuple(args) = args_value else {
+            return None;
+        };
+        let mut call = |callable: &Value, args: Value| {
+            self.eval_call_to_value(
+                call_expr_id,
+                callee_expr_id,
+                callable.clone(),
+                args,
+                args_span,
+                true,
+            )
+        };
+        let result = match (name, &args[..]) {
+            ("Mapped" | "ForEach", [mapper, Value::Array(array)]) => array
+                .iter()
+                .map(|element| call(mapper, element.clone()))
+                .collect::<Result<Vec<_>, _>>()
+                .map(|values| Value::Array(values.into())),
+            ("Fold", [folder, state, Value::Array(array)]) => {
+                array.iter().try_fold(state.clone(), |state, element| {
+                    call(folder, Value::Tuple(vec![state, element.clone()].into()))
+                })
+            }
+            _ => return None,
+        };
+        Some(result)
+    }
+
+    /// Emits a call to `Exp` as a single call to the multi-qubit Pauli rotation intrinsic for its Pauli operators,
+    /// if the configuration allows it. Returns `None` when the call has to be evaluated through the body of `Exp`
+    /// instead, which is the case for controlled calls, calls whose Pauli operators aren't known, calls that act on
+    /// fewer than two qubits once identities are removed, adjoint calls with a dynamic angle, and calls with invalid
+    /// arguments, whose errors the body reports.
+    fn try_eval_exp_as_pauli_rotation(
+        &mut self,
+        store_item_id: StoreItemId,
+        functor_app: FunctorApp,
+        args_value: &Value,
+    ) -> Option<Value> {
+        if !self.config.pauli_rotations
+            || functor_app.controlled > 0
+            || !self.is_std_intrinsic(store_item_id, "Exp")
+        {
+            return None;
+        }
+        let Value::Tuple(args) = args_value else {
+            return None;
+        };
+        let [Value::Array(paulis), theta, Value::Array(qubits)] = &args[..] else {
+            return None;
+        };
+        if paulis.len() != qubits.len() {
+            return None;
+        }
+
+        let mut pauli_string = String::new();
+        let mut qubit_ids = Vec::new();
+        let mut qubit_values = Vec::new();
+        for (pauli, qubit) in paulis.iter().zip(qubits.iter()) {
+            let (Value::Pauli(pauli), Value::Qubit(qubit_ref)) = (pauli, qubit) else {
+                return None;
+            };
+            let letter = match pauli {
+                fir::Pauli::I => continue,
+                fir::Pauli::X => 'x',
+                fir::Pauli::Y => 'y',
+                fir::Pauli::Z => 'z',
+            };
+            pauli_string.push(letter);
+            qubit_ids.push(qubit_ref.try_deref()?.0);
+            qubit_values.push(qubit);
+        }
+        let unique_qubits = qubit_ids.iter().collect::<FxHashSet<_>>();
+        if qubit_ids.len() < 2 || unique_qubits.len() != qubit_ids.len() {
+            return None;
+        }
+        let theta = match (theta, functor_app.adjoint) {
+            (theta, false) => theta.clone(),
+            (Value::Double(theta), true) => Value::Double(-theta),
+            _ => return None,
+        };
+
+        let callable_id = self.get_or_insert_callable(builder::exp_decl(&pauli_string));
+        let args_operands = std::iter::once(&theta)
+            .chain(qubit_values)
+            .map(|value| self.map_eval_value_to_rir_operand(value))
+            .collect();
+        let instruction = Instruction::Call(callable_id, args_operands, None);
+        self.get_current_rir_block_mut().0.push(instruction);
+        Some(Value::unit())
+    }
+
+    /// Whether the given item is the callable with the given name in the `Std.Intrinsic` namespace.
+    fn is_std_intrinsic(&self, store_item_id: StoreItemId, name: &str) -> bool {
+        self.is_std_callable(store_item_id, "Std.Intrinsic", name)
+    }
+
+    /// Whether the given item is the callable with the given name in the given namespace.
+    fn is_std_callable(
+        &self,
+        store_item_id: StoreItemId,
+        namespace_name: &str,
+        name: &str,
+    ) -> bool {
+        let package = self.package_store.get(store_item_id.package);
+        let item = package.get_item(store_item_id.item);
+        let fir::ItemKind::Callable(callable_decl) = &item.kind else {
+            return false;
+        };
+        callable_decl.name.name.as_ref() == name && item.parent.is_some_and(|parent| {
+            matches!(
+                &package.get_item(parent).kind,
+                fir::ItemKind::Namespace(namespace, _) if namespace.name.as_ref() == namespace_name
+            )
+        })
     }
 
     fn eval_global_call(
@@ -1565,6 +2014,12 @@ impl<'a> PartialEvaluator<'a> {
             }
         }
 
+        if callable_decl.attrs.contains(&fir::Attr::HostCallable) {
+            return Err(Error::UnsupportedHostCall(
+                callable_decl.name.name.to_string(),
+                callee_expr_span,
+            ));
+        }
         if callable_decl.attrs.contains(&fir::Attr::Measurement) {
             return Ok(self.measure_qubits(callable_decl, args_value));
         }
@@ -1587,10 +2042,12 @@ impl<'a> PartialEvaluator<'a> {
                 self.resource_manager.swap_qubit_ids(q0, q1);
             })
             .map_err(std::convert::Into::into),
-            "__quantum__qis__m__body" => Ok(self.measure_qubit(builder::m_decl(), args_value)),
-            "__quantum__qis__mresetz__body" => {
-                Ok(self.measure_qubit(builder::mresetz_decl(), args_value))
+            "Message" => {
+                self.handle_message(&args_value.unwrap_string(), callee_expr_span);
+                Ok(Value::unit())
             }
+            "__quantum__qis__m__body" => Ok(self.measure_qubit(self.measure_decl(), args_value)),
+            "__quantum__qis__mresetz__body" => Ok(self.measure_and_reset_qubit(args_value)),
             // The following intrinsic operations and functions are no-ops.
             "BeginEstimateCaching" => Ok(Value::Bool(true)),
             "DumpRegister"
@@ -1602,15 +2059,30 @@ impl<'a> PartialEvaluator<'a> {
             | "GlobalPhase" => Ok(Value::unit()),
             // The following intrinsic functions and operations should never make it past conditional compilation and
             // the capabilities check pass.
-            "CheckZero" | "DrawRandomInt" | "DrawRandomDouble" | "DrawRandomBool" | "Length" => {
-                Err(Error::Unexpected(
-                    format!(
-                        "`{}` is not a supported by partial evaluation",
-                        callable_decl.name.name
-                    ),
-                    callee_expr_span,
-                ))
-            }
+            // Measurements in generated code always have an outcome, so whether a qubit was lost can't be checked.
+            "IsLossResult" => Err(Error::UnsupportedLossCheck(callee_expr_span)),
+            // Checked conversions of values known at compile time are evaluated classically, so only conversions of
+            // dynamic values get here.
+            "TryIntAsDouble" => Ok(self.generate_checked_conversion(&args_value, rir::Ty::Double)),
+            "TryDoubleAsInt" => Ok(self.generate_checked_conversion(&args_value, rir::Ty::Integer)),
+            // Generated code has no big integers, so they are never dynamic.
+            "TryBigIntAsInt" => Err(Error::UnsupportedDynamicConversion(
+                callable_decl.name.name.to_string(),
+                callee_expr_span,
+            )),
+            "CheckZero"
+            | "ReducedDensityMatrix"
+            | "ExpectationValue"
+            | "DrawRandomInt"
+            | "DrawRandomDouble"
+            | "DrawRandomBool"
+            | "Length" => Err(Error::Unexpected(
+                format!(
+                    "`{}` is not a supported by partial evaluation",
+                    callable_decl.name.name
+                ),
+                callee_expr_span,
+            )),
             _ => self.eval_expr_call_to_intrinsic_qis(
                 store_item_id,
                 callable_decl,
@@ -1621,6 +2093,160 @@ impl<'a> PartialEvaluator<'a> {
         }
     }
 
+    /// Generates the instructions for a checked conversion of a dynamic value between `Int` and `Double`, returning
+    /// the `(Bool, value)` tuple of the conversion. A conversion that would lose information reports `false` paired
+    /// with zero, like it does in simulation. The value is only converted back and compared to the input once it is
+    /// known to be in the range of `Int`, since converting a double outside of it has no defined result.
+    #[allow(clippy::too_many_lines)]
+    fn generate_checked_conversion(&mut self, input: &Value, output_ty: rir::Ty) -> Value {
+        const TWO_POW_63: f64 = 9_223_372_036_854_775_808.0;
+        let input_operand = self.map_eval_value_to_rir_operand(input);
+        let mut new_var = |ty| rir::Variable {
+            variable_id: self.resource_manager.next_var(),
+            ty,
+        };
+        let succeeded_var = new_var(rir::Ty::Boolean);
+        let output_var = new_var(output_ty);
+        let in_range_var = new_var(rir::Ty::Boolean);
+        let exact_var = new_var(rir::Ty::Boolean);
+        let zero = match output_ty {
+            rir::Ty::Double => Literal::Double(0.0),
+            rir::Ty::Integer => Literal::Integer(0),
+            _ => panic!("unsupported output type {output_ty} for checked conversion"),
+        };
+
+        // Both outputs start out as the result of a failed conversion, and are only replaced once the conversion is
+        // known to be exact.
+        let mut range_check = vec![
+            Instruction::Store(Operand::Literal(Literal::Bool(false)), succeeded_var),
+            Instruction::Store(Operand::Literal(zero), output_var),
+        ];
+        let mut exact_check = Vec::new();
+        let converted = if output_ty == rir::Ty::Double {
+            // Every `Int` converts to the nearest `Double`, but the ones closest to `i64::MAX` round up to 2^63, which
+            // is out of range when converted back.
+            let converted_var = new_var(rir::Ty::Double);
+            let round_trip_var = new_var(rir::Ty::Integer);
+            range_check.extend([
+                Instruction::Sitofp(input_operand, converted_var),
+                Instruction::Fcmp(
+                    FcmpConditionCode::OrderedAndLessThan,
+                    Operand::Variable(converted_var),
+                    Operand::Literal(Literal::Double(TWO_POW_63)),
+                    in_range_var,
+                ),
+            ]);
+            exact_check.extend([
+                Instruction::Fptosi(Operand::Variable(converted_var), round_trip_var),
+                Instruction::Icmp(
+                    ConditionCode::Eq,
+                    Operand::Variable(round_trip_var),
+                    input_operand,
+                    exact_var,
+                ),
+            ]);
+            converted_var
+        } else {
+            // Comparisons with NaN are false, so NaN is out of range.
+            let above_min_var = new_var(rir::Ty::Boolean);
+            let below_max_var = new_var(rir::Ty::Boolean);
+            let converted_var = new_var(rir::Ty::Integer);
+            let round_trip_var = new_var(rir::Ty::Double);
+            range_check.extend([
+                Instruction::Fcmp(
+                    FcmpConditionCode::OrderedAndGreaterThanOrEqual,
+                    input_operand,
+                    Operand::Literal(Literal::Double(-TWO_POW_63)),
+                    above_min_var,
+                ),
+                Instruction::Fcmp(
+                    FcmpConditionCode::OrderedAndLessThan,
+                    input_operand,
+                    Operand::Literal(Literal::Double(TWO_POW_63)),
+                    below_max_var,
+                ),
+                Instruction::LogicalAnd(
+                    Operand::Variable(above_min_var),
+                    Operand::Variable(below_max_var),
+                    in_range_var,
+                ),
+            ]);
+            exact_check.extend([
+                Instruction::Fptosi(input_operand, converted_var),
+                Instruction::Sitofp(Operand::Variable(converted_var), round_trip_var),
+                Instruction::Fcmp(
+                    FcmpConditionCode::OrderedAndEqual,
+                    Operand::Variable(round_trip_var),
+                    input_operand,
+                    exact_var,
+                ),
+            ]);
+            converted_var
+        };
+
+        // Pop the current block and insert the continuation block, which both checks branch to when they fail.
+        let current_block_node = self.eval_context.pop_block_node();
+        let continuation_block_id = self.create_program_block("conversion_continue");
+        self.eval_context.push_block_node(BlockNode {
+            id: continuation_block_id,
+            successor: current_block_node.successor,
+        });
+        let exact_check_block_id = self.create_program_block("conversion_in_range");
+        let succeeded_block_id = self.create_program_block("conversion_exact");
+
+        range_check.push(Instruction::Branch(
+            in_range_var,
+            exact_check_block_id,
+            continuation_block_id,
+        ));
+        self.get_program_block_mut(current_block_node.id)
+            .0
+            .extend(range_check);
+        exact_check.push(Instruction::Branch(
+            exact_var,
+            succeeded_block_id,
+            continuation_block_id,
+        ));
+        self.get_program_block_mut(exact_check_block_id)
+            .0
+            .extend(exact_check);
+        self.get_program_block_mut(succeeded_block_id).0.extend([
+            Instruction::Store(Operand::Literal(Literal::Bool(true)), succeeded_var),
+            Instruction::Store(Operand::Variable(converted), output_var),
+            Instruction::Jump(continuation_block_id),
+        ]);
+
+        let to_value = |var| {
+            Value::Var(
+                map_rir_var_to_eval_var(var).expect("conversion outputs should be primitive"),
+            )
+        };
+        Value::Tuple(vec![to_value(succeeded_var), to_value(output_var)].into())
+    }
+
+    /// Handles a message from a call to `Message`, either by recording it in the output of the program or by dropping
+    /// it with a warning, depending on the configuration. A call that is evaluated more than once, such as in a loop,
+    /// only gets one warning.
+    fn handle_message(&mut self, message: &str, span: PackageSpan) {
+        match self.config.messages {
+            Messages::Drop => {
+                let is_reported = self.warnings.iter().any(|warning| {
+                    let warning_span = warning.span();
+                    warning_span.package == span.package && warning_span.span == span.span
+                });
+                if !is_reported {
+                    self.warnings.push(Warning::MessageDropped(span));
+                }
+            }
+            Messages::Record => {
+                let callable_id = self.get_or_insert_callable(builder::message_record_decl());
+                let tag = self.program.add_tag(message);
+                let instruction = Instruction::Call(callable_id, vec![Operand::Literal(tag)], None);
+                self.get_current_rir_block_mut().0.push(instruction);
+            }
+        }
+    }
+
     fn eval_expr_call_to_intrinsic_qis(
         &mut self,
         store_item_id: StoreItemId,
@@ -1629,11 +2255,21 @@ impl<'a> PartialEvaluator<'a> {
         callee_expr_span: PackageSpan,
         call_type: CallableType,
     ) -> Result<Value, Error> {
-        // Intrinsic callables that make it to this point are expected to be unitary.
-        if callable_decl.output != Ty::UNIT {
+        // Intrinsic callables that make it to this point are expected to be unitary, unless they are operations that
+        // output a value the generated code can represent. A result is written to a result register passed as the last
+        // argument, like the output of a measurement, while Booleans, integers and doubles are stored in a variable.
+        let output = &callable_decl.output;
+        let is_result_output = *output == Ty::Prim(Prim::Result);
+        if *output != Ty::UNIT
+            && !(callable_decl.kind == fir::CallableKind::Operation
+                && matches!(
+                    output,
+                    Ty::Prim(Prim::Result | Prim::Bool | Prim::Int | Prim::Double)
+                ))
+        {
             return Err(Error::Unexpected(
                 format!(
-                    "non-classical call to non-Unit intrinsic `{}`",
+                    "non-classical call to intrinsic `{}` with unsupported output type `{output}`",
                     callable_decl.name.name
                 ),
                 callee_expr_span,
@@ -1641,7 +2277,12 @@ impl<'a> PartialEvaluator<'a> {
         }
 
         // Check if the callable is already in the program, and if not add it.
-        let callable = self.create_intrinsic_callable(store_item_id, callable_decl, call_type);
+        let mut callable = self.create_intrinsic_callable(store_item_id, callable_decl, call_type);
+        if is_result_output {
+            callable.input_type.push(rir::Ty::Result);
+            callable.output_type = None;
+            callable.call_type = CallableType::Measurement;
+        }
         let callable_id = self.get_or_insert_callable(callable);
 
         // Resove the call arguments, create the call instruction and insert it to the current block.
@@ -1658,15 +2299,72 @@ impl<'a> PartialEvaluator<'a> {
             ctls_arg.is_none(),
             "intrinsic operations cannot have controls"
         );
-        let args_operands = args
+        let mut args_operands: Vec<_> = args
             .into_iter()
             .map(|arg| self.map_eval_value_to_rir_operand(&arg.into_value()))
             .collect();
 
-        let instruction = Instruction::Call(callable_id, args_operands, None);
+        let (output_var, output_value) = if is_result_output {
+            let result_value = Value::Result(self.resource_manager.next_result_register());
+            args_operands.push(self.map_eval_value_to_rir_operand(&result_value));
+            (None, result_value)
+        } else if *output == Ty::UNIT {
+            (None, Value::unit())
+        } else {
+            let rir_var = rir::Variable {
+                variable_id: self.resource_manager.next_var(),
+                ty: map_fir_type_to_rir_type(output),
+            };
+            let eval_var =
+                map_rir_var_to_eval_var(rir_var).expect("output type should be a variable type");
+            (Some(rir_var), Value::Var(eval_var))
+        };
+
+        let instruction = Instruction::Call(callable_id, args_operands, output_var);
         let current_block = self.get_current_rir_block_mut();
         current_block.0.push(instruction);
-        Ok(Value::unit())
+        Ok(output_value)
+    }
+
+    /// Checks that calling the callable doesn't nest it more deeply than the recursion limit, if any, allows. The error names
+    /// the cycle of calls from the innermost call to the callable back to itself.
+    fn check_recursion_limit(
+        &self,
+        store_item_id: StoreItemId,
+        call_expr_id: ExprId,
+    ) -> Result<(), Error> {
+        let Some(limit) = self.config.recursion_limit else {
+            return Ok(());
+        };
+        let callables: Vec<_> = self.eval_context.get_scope_callables().collect();
+        let depth = callables.iter().filter(|id| **id == store_item_id).count();
+        if depth < limit {
+            return Ok(());
+        }
+
+        let cycle_start = callables
+            .iter()
+            .rposition(|id| *id == store_item_id)
+            .unwrap_or(callables.len());
+        let cycle = callables[cycle_start..]
+            .iter()
+            .chain(std::iter::once(&store_item_id))
+            .map(|id| self.get_callable_name(*id))
+            .collect::<Vec<_>>()
+            .join(" -> ");
+        Err(Error::RecursionLimit(
+            cycle,
+            limit,
+            self.get_expr_package_span(call_expr_id),
+        ))
+    }
+
+    fn get_callable_name(&self, store_item_id: StoreItemId) -> Rc<str> {
+        let Some(Global::Callable(callable_decl)) = self.package_store.get_global(store_item_id)
+        else {
+            panic!("global is not a callable");
+        };
+        callable_decl.name.name.clone()
     }
 
     fn eval_expr_call_to_spec(
@@ -1694,6 +2392,26 @@ impl<'a> PartialEvaluator<'a> {
         Ok(block_value)
     }
 
+    fn eval_expr_closure(&mut self, args: &[LocalVarId], callable: fir::LocalItemId) -> Value {
+        // Captured locals are resolved from the hybrid map rather than the classical environment because they can be
+        // bound to RIR variables, which get carried in the closure's fixed arguments and rebound when it is invoked.
+        let fixed_args: Vec<_> = args
+            .iter()
+            .map(|arg| self.eval_expr_var(&Res::Local(*arg)))
+            .collect();
+        Value::Closure(
+            val::Closure {
+                fixed_args: fixed_args.into(),
+                id: StoreItemId {
+                    package: self.get_current_package_id(),
+                    item: callable,
+                },
+                functor: FunctorApp::default(),
+            }
+            .into(),
+        )
+    }
+
     fn eval_expr_if(
         &mut self,
         if_expr_id: ExprId,
@@ -1721,11 +2439,24 @@ impl<'a> PartialEvaluator<'a> {
             );
         }
 
-        // At this point the condition value is not classical, so we need to generate a branching instruction.
-        // First, we pop the current block node and generate a new one which the new branches will jump to when their
-        // instructions end.
+        // At this point the condition value is not classical, so the value of the if expression is dynamic. Only values
+        // of primitive types can be stored in a variable, so values of aggregate types such as tuples, arrays and
+        // user-defined types cannot depend on the condition.
+        let if_expr = self.get_expr(if_expr_id);
+        if if_expr.ty != Ty::UNIT && !matches!(if_expr.ty, Ty::Prim(_)) {
+            return Err(Error::Unimplemented(
+                format!(
+                    "dynamic value of type {} in conditional expression",
+                    if_expr.ty
+                ),
+                self.get_expr_package_span(if_expr_id),
+            ));
+        }
+
+        // Generate a branching instruction. First, we pop the current block node and generate a new one which the new
+        // branches will jump to when their instructions end.
         let current_block_node = self.eval_context.pop_block_node();
-        let continuation_block_node_id = self.create_program_block();
+        let continuation_block_node_id = self.create_program_block("if_continue");
         let continuation_block_node = BlockNode {
             id: continuation_block_node_id,
             successor: current_block_node.successor,
@@ -1749,8 +2480,12 @@ impl<'a> PartialEvaluator<'a> {
         // Evaluate the body expression.
         // First, we cache the current static variable mappings so that we can restore them later.
         let cached_mappings = self.clone_current_static_var_map();
-        let if_true_branch_control_flow =
-            self.eval_expr_if_branch(body_expr_id, continuation_block_node_id, maybe_if_expr_var)?;
+        let if_true_branch_control_flow = self.eval_expr_if_branch(
+            "if_true",
+            body_expr_id,
+            continuation_block_node_id,
+            maybe_if_expr_var,
+        )?;
         let if_true_block_id = match if_true_branch_control_flow {
             BranchControlFlow::Block(block_id) => block_id,
             BranchControlFlow::Return(value) => return Ok(EvalControlFlow::Return(value)),
@@ -1763,6 +2498,7 @@ impl<'a> PartialEvaluator<'a> {
             // Restore the cached mappings from before evaluating the true block.
             self.overwrite_current_static_var_map(cached_mappings);
             let if_false_branch_control_flow = self.eval_expr_if_branch(
+                "if_false",
                 otherwise_expr_id,
                 continuation_block_node_id,
                 maybe_if_expr_var,
@@ -1811,12 +2547,13 @@ impl<'a> PartialEvaluator<'a> {
 
     fn eval_expr_if_branch(
         &mut self,
+        block_name: &str,
         branch_body_expr_id: ExprId,
         continuation_block_id: rir::BlockId,
         if_expr_var: Option<rir::Variable>,
     ) -> Result<BranchControlFlow, Error> {
         // Create the block node that corresponds to the branch body and push it as the active one.
-        let block_node_id = self.create_program_block();
+        let block_node_id = self.create_program_block(block_name);
         let block_node = BlockNode {
             id: block_node_id,
             successor: Some(continuation_block_id),
@@ -2086,10 +2823,6 @@ impl<'a> PartialEvaluator<'a> {
         body_block_id: BlockId,
     ) -> Result<EvalControlFlow, Error> {
         // Verify assumptions.
-        assert!(
-            self.is_classical_expr(condition_expr_id),
-            "loop conditions must be purely classical"
-        );
         let body_block = self.get_block(body_block_id);
         assert_eq!(
             body_block.ty,
@@ -2097,8 +2830,24 @@ impl<'a> PartialEvaluator<'a> {
             "the type of a loop block is expected to be Unit"
         );
 
-        // Evaluate the block until the loop condition is false.
+        // A loop whose condition is not purely classical cannot be unrolled, so it is emitted as an actual loop.
         let condition_expr_span = self.get_expr_package_span(condition_expr_id);
+        if !self.is_classical_expr(condition_expr_id) {
+            if !self
+                .program
+                .config
+                .capabilities
+                .contains(TargetCapabilityFlags::BackwardsBranching)
+            {
+                return Err(Error::Unexpected(
+                    "dynamic loop condition on a target without backwards branching".to_string(),
+                    condition_expr_span,
+                ));
+            }
+            return self.eval_expr_while_with_dynamic_condition(condition_expr_id, body_block_id);
+        }
+
+        // Evaluate the block until the loop condition is false.
         let mut condition_control_flow = self.try_eval_expr(condition_expr_id)?;
         if condition_control_flow.is_return() {
             return Err(Error::Unexpected(
@@ -2129,6 +2878,85 @@ impl<'a> PartialEvaluator<'a> {
         Ok(EvalControlFlow::Continue(Value::unit()))
     }
 
+    fn eval_expr_while_with_dynamic_condition(
+        &mut self,
+        condition_expr_id: ExprId,
+        body_block_id: BlockId,
+    ) -> Result<EvalControlFlow, Error> {
+        // The locals assigned by the loop can have a different value on each iteration, so they can only be read
+        // through their backing variables from here on.
+        let package = self.package_store.get(self.get_current_package_id());
+        let loop_carried_locals =
+            loops::get_loop_carried_locals(package, condition_expr_id, body_block_id);
+        let current_scope = self.eval_context.get_current_scope_mut();
+        for local_var_id in loop_carried_locals {
+            if let Value::Var(var) = current_scope.get_hybrid_local_value(local_var_id) {
+                let var_id = var.id.into();
+                current_scope.remove_static_value(var_id);
+            }
+        }
+        let cached_mappings = self.clone_current_static_var_map();
+
+        // Pop the current block node and replace it with the block that follows the loop, then jump from the current
+        // block to the loop header, which evaluates the condition on every iteration.
+        let current_block_node = self.eval_context.pop_block_node();
+        let header_block_id = self.create_program_block("while_condition");
+        let continuation_block_id = self.create_program_block("while_continue");
+        self.eval_context.push_block_node(BlockNode {
+            id: continuation_block_id,
+            successor: current_block_node.successor,
+        });
+        self.get_program_block_mut(current_block_node.id)
+            .0
+            .push(Instruction::Jump(header_block_id));
+
+        // Evaluate the condition in the header and branch on its value. The condition may itself introduce blocks, so
+        // the branch goes at the end of whichever block is current once it has been evaluated.
+        self.eval_context.push_block_node(BlockNode {
+            id: header_block_id,
+            successor: None,
+        });
+        let condition_control_flow = self.try_eval_expr(condition_expr_id)?;
+        if condition_control_flow.is_return() {
+            return Err(Error::Unexpected(
+                "embedded return in loop condition".to_string(),
+                self.get_expr_package_span(condition_expr_id),
+            ));
+        }
+        let body_rir_block_id = self.create_program_block("while_body");
+        let branch_ins = match condition_control_flow.into_value() {
+            Value::Bool(true) => Instruction::Jump(body_rir_block_id),
+            Value::Bool(false) => Instruction::Jump(continuation_block_id),
+            condition_value => {
+                let condition_rir_var = map_eval_var_to_rir_var(condition_value.unwrap_var());
+                Instruction::Branch(condition_rir_var, body_rir_block_id, continuation_block_id)
+            }
+        };
+        self.get_current_rir_block_mut().0.push(branch_ins);
+        let _ = self.eval_context.pop_block_node();
+
+        // Evaluate the body, which jumps back to the header once its instructions end.
+        self.eval_context.push_block_node(BlockNode {
+            id: body_rir_block_id,
+            successor: Some(header_block_id),
+        });
+        let body_control_flow = self.try_eval_block(body_block_id)?;
+        if body_control_flow.is_return() {
+            return Err(Error::Unimplemented(
+                "return within a loop with a dynamic condition".to_string(),
+                self.get_expr_package_span(condition_expr_id),
+            ));
+        }
+        self.get_current_rir_block_mut()
+            .0
+            .push(Instruction::Jump(header_block_id));
+        let _ = self.eval_context.pop_block_node();
+
+        // Mappings added while evaluating the loop only hold within a single iteration.
+        self.overwrite_current_static_var_map(cached_mappings);
+        Ok(EvalControlFlow::Continue(Value::unit()))
+    }
+
     fn eval_result_as_bool_operand(&mut self, result: val::Result) -> Operand {
         match result {
             val::Result::Id(id) => {
@@ -2154,6 +2982,7 @@ impl<'a> PartialEvaluator<'a> {
                 Operand::Variable(variable)
             }
             val::Result::Val(bool) => Operand::Literal(Literal::Bool(bool)),
+            val::Result::Loss => panic!("lost results should only be produced by simulation"),
         }
     }
 
@@ -2614,6 +3443,11 @@ impl<'a> PartialEvaluator<'a> {
         matches!(compute_kind, ComputeKind::Classical)
     }
 
+    fn is_dynamic_expr(&self, expr_id: ExprId) -> bool {
+        let compute_kind = self.get_expr_compute_kind(expr_id);
+        matches!(compute_kind, ComputeKind::Quantum(QuantumProperties { value_kind, .. }) if value_kind.is_dynamic())
+    }
+
     fn allocate_qubit(&mut self) -> Value {
         let qubit = self.resource_manager.allocate_qubit();
         Value::Qubit(qubit)
@@ -2710,6 +3544,29 @@ impl<'a> PartialEvaluator<'a> {
         result_value
     }
 
+    /// The intrinsic that measures a qubit without resetting it on the target.
+    fn measure_decl(&self) -> Callable {
+        match self.config.measurements {
+            Measurements::MAndMResetZ | Measurements::M => builder::m_decl(),
+            Measurements::Mz => builder::mz_decl(),
+        }
+    }
+
+    /// Measures a qubit and resets it, with an explicit reset after the measurement on targets that do not implement
+    /// `__quantum__qis__mresetz__body`.
+    fn measure_and_reset_qubit(&mut self, args_value: Value) -> Value {
+        if self.config.measurements == Measurements::MAndMResetZ {
+            return self.measure_qubit(builder::mresetz_decl(), args_value);
+        }
+
+        let qubit_operand = self.map_eval_value_to_rir_operand(&args_value);
+        let result_value = self.measure_qubit(self.measure_decl(), args_value);
+        let reset_callable_id = self.get_or_insert_callable(builder::reset_decl());
+        let instruction = Instruction::Call(reset_callable_id, vec![qubit_operand], None);
+        self.get_current_rir_block_mut().0.push(instruction);
+        result_value
+    }
+
     fn release_qubit(&mut self, args_value: Value) -> Value {
         let qubit = args_value.unwrap_qubit();
         self.resource_manager.release_qubit(&qubit);
@@ -2811,6 +3668,11 @@ impl<'a> PartialEvaluator<'a> {
 
     fn try_eval_block(&mut self, block_id: BlockId) -> Result<EvalControlFlow, Error> {
         let block = self.get_block(block_id);
+        let package = self.package_store.get(self.get_current_package_id());
+        if let Some(conjugate) = conjugation::try_get_conjugate(package, block) {
+            return self.try_eval_conjugate(&conjugate);
+        }
+
         let mut return_stmt_id = None;
         let mut last_control_flow = EvalControlFlow::Continue(Value::unit());
 
@@ -2845,6 +3707,96 @@ impl<'a> PartialEvaluator<'a> {
         }
     }
 
+    fn try_eval_conjugate(&mut self, conjugate: &Conjugate) -> Result<EvalControlFlow, Error> {
+        // Evaluate the within-block once, keeping track of the instructions it generates. These can only be replayed
+        // if they were all inserted in the same block.
+        let within_block_span = PackageSpan {
+            package: map_fir_package_to_hir(self.get_current_package_id()),
+            span: self.get_block(conjugate.within_block).span,
+        };
+        let start_block_id = self.eval_context.get_current_block_id();
+        let start_instruction_count = self.get_current_rir_block_mut().0.len();
+        if self.try_eval_stmt(conjugate.within_stmt)?.is_return() {
+            return Err(Error::Unexpected(
+                "embedded return in within-block".to_string(),
+                within_block_span,
+            ));
+        }
+        let package = self.package_store.get(self.get_current_package_id());
+        let within_instructions = if self.eval_context.get_current_block_id() == start_block_id
+            && conjugation::is_within_block_replayable(package, conjugate.within_block)
+        {
+            Some(self.get_current_rir_block_mut().0[start_instruction_count..].to_vec())
+        } else {
+            None
+        };
+
+        // The apply-block is evaluated normally and can branch on dynamic values.
+        if self.try_eval_stmt(conjugate.apply_stmt)?.is_return() {
+            return Err(Error::Unexpected(
+                "embedded return in apply-block".to_string(),
+                within_block_span,
+            ));
+        }
+
+        // Undo the within-block by inverting the instructions it generated, falling back to evaluating the adjoint
+        // within-block when any of them cannot be inverted.
+        if let Some(adjoint_instructions) =
+            within_instructions.and_then(|instructions| self.try_invert_instructions(&instructions))
+        {
+            self.get_current_rir_block_mut()
+                .0
+                .extend(adjoint_instructions);
+        } else if self.try_eval_stmt(conjugate.adj_within_stmt)?.is_return() {
+            return Err(Error::Unexpected(
+                "embedded return in within-block".to_string(),
+                within_block_span,
+            ));
+        }
+
+        self.try_eval_stmt(conjugate.result_stmt)
+    }
+
+    fn try_invert_instructions(
+        &mut self,
+        instructions: &[Instruction],
+    ) -> Option<Vec<Instruction>> {
+        // Determine the adjoint of every instruction before updating the program so that no callables are added when
+        // the instructions cannot be inverted.
+        let mut adjoints = Vec::with_capacity(instructions.len());
+        for instruction in instructions.iter().rev() {
+            let Instruction::Call(callable_id, args, None) = instruction else {
+                return None;
+            };
+            let callable = self.program.get_callable(*callable_id);
+            if callable.call_type != CallableType::Regular {
+                return None;
+            }
+            let adjoint = match conjugation::get_inversion(&callable.name)? {
+                Inversion::SelfAdjoint => (Ok(*callable_id), args.clone()),
+                Inversion::Callable(adjoint_name) => (
+                    Err(Callable {
+                        name: adjoint_name.to_string(),
+                        ..callable.clone()
+                    }),
+                    args.clone(),
+                ),
+                Inversion::NegatedAngle => (Ok(*callable_id), conjugation::negate_angle(args)?),
+            };
+            adjoints.push(adjoint);
+        }
+
+        let adjoint_instructions = adjoints
+            .into_iter()
+            .map(|(callable, args)| {
+                let callable_id =
+                    callable.unwrap_or_else(|callable| self.get_or_insert_callable(callable));
+                Instruction::Call(callable_id, args, None)
+            })
+            .collect();
+        Some(adjoint_instructions)
+    }
+
     fn try_eval_expr(&mut self, expr_id: ExprId) -> Result<EvalControlFlow, Error> {
         // An expression is evaluated differently depending on whether it is purely classical or hybrid.
         if self.is_classical_expr(expr_id) {
@@ -3014,23 +3966,29 @@ impl<'a> PartialEvaluator<'a> {
         Ok(())
     }
 
+    /// Generates the instructions that record the value as output, labeled with the given label and labels
+    /// derived from it for the items of tuples and arrays. Without a label, the outputs are not labeled.
+    /// The position of each recorded value within the return value, starting from the given path to the value, is
+    /// added to the output positions of the program in the order of the instructions.
     fn generate_output_recording_instructions(
         &mut self,
         ret_val: Value,
         ty: &Ty,
+        label: Option<&str>,
+        path: &[u32],
     ) -> Result<Vec<Instruction>, ()> {
         let mut instrs = Vec::new();
 
         match ret_val {
-            Value::Result(val::Result::Val(_)) => return Err(()),
+            Value::Result(val::Result::Val(_) | val::Result::Loss) => return Err(()),
 
-            Value::Array(vals) => self.record_array(ty, &mut instrs, &vals)?,
-            Value::Tuple(vals) => self.record_tuple(ty, &mut instrs, &vals)?,
-            Value::Result(res) => self.record_result(&mut instrs, res),
-            Value::Var(var) => self.record_variable(ty, &mut instrs, var),
-            Value::Bool(val) => self.record_bool(&mut instrs, val),
-            Value::Int(val) => self.record_int(&mut instrs, val),
-            Value::Double(val) => self.record_double(&mut instrs, val),
+            Value::Array(vals) => self.record_array(ty, &mut instrs, &vals, label, path)?,
+            Value::Tuple(vals) => self.record_tuple(ty, &mut instrs, &vals, label, &[], path)?,
+            Value::Result(res) => self.record_result(&mut instrs, res, label, path),
+            Value::Var(var) => self.record_variable(ty, &mut instrs, var, label, path),
+            Value::Bool(val) => self.record_bool(&mut instrs, val, label, path),
+            Value::Int(val) => self.record_int(&mut instrs, val, label, path),
+            Value::Double(val) => self.record_double(&mut instrs, val, label, path),
 
             Value::BigInt(_)
             | Value::Closure(_)
@@ -3044,60 +4002,96 @@ impl<'a> PartialEvaluator<'a> {
         Ok(instrs)
     }
 
-    fn record_int(&mut self, instrs: &mut Vec<Instruction>, val: i64) {
+    fn record_int(
+        &mut self,
+        instrs: &mut Vec<Instruction>,
+        val: i64,
+        label: Option<&str>,
+        path: &[u32],
+    ) {
+        self.add_output_position(OutputKind::Int, path);
         let int_record_callable_id = self.get_int_record_callable();
         instrs.push(Instruction::Call(
             int_record_callable_id,
             vec![
                 Operand::Literal(Literal::Integer(val)),
-                Operand::Literal(Literal::Pointer),
+                self.output_label(label),
             ],
             None,
         ));
     }
 
-    fn record_double(&mut self, instrs: &mut Vec<Instruction>, val: f64) {
+    fn record_double(
+        &mut self,
+        instrs: &mut Vec<Instruction>,
+        val: f64,
+        label: Option<&str>,
+        path: &[u32],
+    ) {
+        self.add_output_position(OutputKind::Double, path);
         let double_record_callable_id = self.get_double_record_callable();
         instrs.push(Instruction::Call(
             double_record_callable_id,
             vec![
                 Operand::Literal(Literal::Double(val)),
-                Operand::Literal(Literal::Pointer),
+                self.output_label(label),
             ],
             None,
         ));
     }
 
-    fn record_bool(&mut self, instrs: &mut Vec<Instruction>, val: bool) {
+    fn record_bool(
+        &mut self,
+        instrs: &mut Vec<Instruction>,
+        val: bool,
+        label: Option<&str>,
+        path: &[u32],
+    ) {
+        self.add_output_position(OutputKind::Bool, path);
         let bool_record_callable_id = self.get_bool_record_callable();
         instrs.push(Instruction::Call(
             bool_record_callable_id,
             vec![
                 Operand::Literal(Literal::Bool(val)),
-                Operand::Literal(Literal::Pointer),
+                self.output_label(label),
             ],
             None,
         ));
     }
 
-    fn record_variable(&mut self, ty: &Ty, instrs: &mut Vec<Instruction>, var: Var) {
-        let record_callable_id = match ty {
-            Ty::Prim(Prim::Bool) => self.get_bool_record_callable(),
-            Ty::Prim(Prim::Int) => self.get_int_record_callable(),
-            Ty::Prim(Prim::Double) => self.get_double_record_callable(),
+    fn record_variable(
+        &mut self,
+        ty: &Ty,
+        instrs: &mut Vec<Instruction>,
+        var: Var,
+        label: Option<&str>,
+        path: &[u32],
+    ) {
+        let (record_callable_id, kind) = match ty {
+            Ty::Prim(Prim::Bool) => (self.get_bool_record_callable(), OutputKind::Bool),
+            Ty::Prim(Prim::Int) => (self.get_int_record_callable(), OutputKind::Int),
+            Ty::Prim(Prim::Double) => (self.get_double_record_callable(), OutputKind::Double),
             _ => panic!("unsupported variable type in output recording"),
         };
+        self.add_output_position(kind, path);
         instrs.push(Instruction::Call(
             record_callable_id,
             vec![
                 Operand::Variable(map_eval_var_to_rir_var(var)),
-                Operand::Literal(Literal::Pointer),
+                self.output_label(label),
             ],
             None,
         ));
     }
 
-    fn record_result(&mut self, instrs: &mut Vec<Instruction>, res: val::Result) {
+    fn record_result(
+        &mut self,
+        instrs: &mut Vec<Instruction>,
+        res: val::Result,
+        label: Option<&str>,
+        path: &[u32],
+    ) {
+        self.add_output_position(OutputKind::Result, path);
         let result_record_callable_id = self.get_result_record_callable();
         instrs.push(Instruction::Call(
             result_record_callable_id,
@@ -3107,21 +4101,27 @@ impl<'a> PartialEvaluator<'a> {
                         .try_into()
                         .expect("result id should fit into u32"),
                 )),
-                Operand::Literal(Literal::Pointer),
+                self.output_label(label),
             ],
             None,
         ));
     }
 
+    /// Records a tuple, with its items labeled by the given item names or, past those, by their index after
+    /// the label of the tuple.
     fn record_tuple(
         &mut self,
         ty: &Ty,
         instrs: &mut Vec<Instruction>,
         vals: &Rc<[Value]>,
+        label: Option<&str>,
+        item_names: &[Rc<str>],
+        path: &[u32],
     ) -> Result<(), ()> {
         let Ty::Tuple(elem_tys) = ty else {
             panic!("expected tuple type for tuple value");
         };
+        self.add_output_position(OutputKind::Tuple, path);
         let tuple_record_callable_id = self.get_tuple_record_callable();
         instrs.push(Instruction::Call(
             tuple_record_callable_id,
@@ -3131,12 +4131,21 @@ impl<'a> PartialEvaluator<'a> {
                         .try_into()
                         .expect("tuple length should fit into u32"),
                 )),
-                Operand::Literal(Literal::Pointer),
+                self.output_label(label),
             ],
             None,
         ));
-        for (val, elem_ty) in vals.iter().zip(elem_tys.iter()) {
-            instrs.extend(self.generate_output_recording_instructions(val.clone(), elem_ty)?);
+        for (idx, (val, elem_ty)) in vals.iter().zip(elem_tys.iter()).enumerate() {
+            let item_label = match item_names.get(idx) {
+                Some(name) => Some(name.to_string()),
+                None => label.map(|label| format!("{label}.{idx}")),
+            };
+            instrs.extend(self.generate_output_recording_instructions(
+                val.clone(),
+                elem_ty,
+                item_label.as_deref(),
+                &item_path(path, idx),
+            )?);
         }
 
         Ok(())
@@ -3147,10 +4156,13 @@ impl<'a> PartialEvaluator<'a> {
         ty: &Ty,
         instrs: &mut Vec<Instruction>,
         vals: &Rc<Vec<Value>>,
+        label: Option<&str>,
+        path: &[u32],
     ) -> Result<(), ()> {
         let Ty::Array(elem_ty) = ty else {
             panic!("expected array type for array value");
         };
+        self.add_output_position(OutputKind::Array, path);
         let array_record_callable_id = self.get_array_record_callable();
         instrs.push(Instruction::Call(
             array_record_callable_id,
@@ -3160,17 +4172,35 @@ impl<'a> PartialEvaluator<'a> {
                         .try_into()
                         .expect("array length should fit into u32"),
                 )),
-                Operand::Literal(Literal::Pointer),
+                self.output_label(label),
             ],
             None,
         ));
-        for val in vals.iter() {
-            instrs.extend(self.generate_output_recording_instructions(val.clone(), elem_ty)?);
+        for (idx, val) in vals.iter().enumerate() {
+            let item_label = label.map(|label| format!("{label}[{idx}]"));
+            instrs.extend(self.generate_output_recording_instructions(
+                val.clone(),
+                elem_ty,
+                item_label.as_deref(),
+                &item_path(path, idx),
+            )?);
         }
 
         Ok(())
     }
 
+    fn add_output_position(&mut self, kind: OutputKind, path: &[u32]) {
+        self.program.output_positions.push(OutputPosition {
+            kind,
+            path: path.to_vec(),
+        });
+    }
+
+    /// The label operand of an output recording call, which points to a tag holding the label if there is one.
+    fn output_label(&mut self, label: Option<&str>) -> Operand {
+        Operand::Literal(label.map_or(Literal::Pointer, |label| self.program.add_tag(label)))
+    }
+
     fn get_array_record_callable(&mut self) -> CallableId {
         if let Some(id) = self.callables_map.get("__quantum__rt__array_record_output") {
             return *id;
@@ -3273,6 +4303,7 @@ impl<'a> PartialEvaluator<'a> {
                         .expect("could not convert result ID to u32"),
                 )),
                 val::Result::Val(bool) => Operand::Literal(Literal::Bool(*bool)),
+                val::Result::Loss => panic!("lost results should only be produced by simulation"),
             },
             Value::Var(var) => Operand::Variable(map_eval_var_to_rir_var(*var)),
             _ => panic!("{value} cannot be mapped to a RIR operand"),
@@ -3454,6 +4485,13 @@ fn eval_bin_op_with_integer_literals(
     }
 }
 
+/// The path to the item of a tuple or array at the given index, from the path to the tuple or array.
+fn item_path(path: &[u32], idx: usize) -> Vec<u32> {
+    let mut item_path = path.to_vec();
+    item_path.push(idx.try_into().expect("item index should fit into u32"));
+    item_path
+}
+
 fn get_spec_decl(spec_impl: &SpecImpl, functor_app: FunctorApp) -> &SpecDecl {
     if !functor_app.adjoint && functor_app.controlled == 0 {
         &spec_impl.body
@@ -3536,6 +4574,25 @@ fn map_rir_type_to_eval_var_type(ty: rir::Ty) -> Result<VarTy, ()> {
     }
 }
 
+/// Whether a value is fully known during partial evaluation, with no variables, qubits or measurement results in it.
+fn is_static_value(value: &Value) -> bool {
+    match value {
+        Value::Array(values) => values.iter().all(is_static_value),
+        Value::Tuple(values) => values.iter().all(is_static_value),
+        Value::Closure(closure) => closure.fixed_args.iter().all(is_static_value),
+        Value::Var(_) | Value::Qubit(_) | Value::Result(val::Result::Id(_)) => false,
+        Value::BigInt(_)
+        | Value::Bool(_)
+        | Value::Double(_)
+        | Value::Global(..)
+        | Value::Int(_)
+        | Value::Pauli(_)
+        | Value::Range(_)
+        | Value::Result(_)
+        | Value::String(_) => true,
+    }
+}
+
 fn try_get_eval_var_type(value: &Value) -> Option<VarTy> {
     match value {
         Value::Bool(_) => Some(VarTy::Boolean),
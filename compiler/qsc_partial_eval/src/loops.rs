@@ -0,0 +1,98 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! Support for residualizing loops whose condition is not known until runtime.
+//!
+//! When the target supports backwards branching, a loop with a dynamic condition is emitted as a header block that
+//! evaluates the condition and branches either to the body, which jumps back to the header, or past the loop. The
+//! locals assigned by the loop are carried across iterations through their backing RIR variables, so any static
+//! values known for them before the loop no longer hold once the loop is entered.
+
+use qsc_data_structures::fx::FxHashSet;
+use qsc_fir::{
+    fir::{
+        Block, BlockId, Expr, ExprId, ExprKind, LocalVarId, Package, PackageLookup, Pat, PatId,
+        PatKind, Res, Stmt, StmtId,
+    },
+    visit::{walk_expr, walk_pat, Visitor},
+};
+
+/// Gets the locals declared outside of a loop that are assigned by its condition or body.
+pub fn get_loop_carried_locals(
+    package: &Package,
+    condition_expr_id: ExprId,
+    body_block_id: BlockId,
+) -> Vec<LocalVarId> {
+    let mut collector = AssignedLocalsCollector {
+        package,
+        declared: FxHashSet::default(),
+        assigned: Vec::new(),
+    };
+    collector.visit_expr(condition_expr_id);
+    collector.visit_block(body_block_id);
+    collector
+        .assigned
+        .into_iter()
+        .filter(|local_var_id| !collector.declared.contains(local_var_id))
+        .collect()
+}
+
+struct AssignedLocalsCollector<'a> {
+    package: &'a Package,
+    declared: FxHashSet<LocalVarId>,
+    assigned: Vec<LocalVarId>,
+}
+
+impl AssignedLocalsCollector<'_> {
+    fn collect_assignee(&mut self, expr_id: ExprId) {
+        match &self.package.get_expr(expr_id).kind {
+            ExprKind::Var(Res::Local(local_var_id), _) => {
+                if !self.assigned.contains(local_var_id) {
+                    self.assigned.push(*local_var_id);
+                }
+            }
+            ExprKind::Tuple(exprs) => {
+                for expr_id in exprs {
+                    self.collect_assignee(*expr_id);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for AssignedLocalsCollector<'a> {
+    fn get_block(&self, id: BlockId) -> &'a Block {
+        self.package.get_block(id)
+    }
+
+    fn get_expr(&self, id: ExprId) -> &'a Expr {
+        self.package.get_expr(id)
+    }
+
+    fn get_pat(&self, id: PatId) -> &'a Pat {
+        self.package.get_pat(id)
+    }
+
+    fn get_stmt(&self, id: StmtId) -> &'a Stmt {
+        self.package.get_stmt(id)
+    }
+
+    fn visit_expr(&mut self, expr_id: ExprId) {
+        match self.get_expr(expr_id).kind {
+            ExprKind::Assign(lhs, _)
+            | ExprKind::AssignField(lhs, _, _)
+            | ExprKind::AssignIndex(lhs, _, _)
+            | ExprKind::AssignOp(_, lhs, _) => self.collect_assignee(lhs),
+            _ => {}
+        }
+        walk_expr(self, expr_id);
+    }
+
+    fn visit_pat(&mut self, pat_id: PatId) {
+        if let PatKind::Bind(ident) = &self.get_pat(pat_id).kind {
+            self.declared.insert(ident.id);
+        }
+        walk_pat(self, pat_id);
+    }
+}
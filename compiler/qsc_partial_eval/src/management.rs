@@ -5,13 +5,13 @@ use std::rc::Rc;
 
 use num_bigint::BigUint;
 use num_complex::Complex;
-use qsc_data_structures::index_map::IndexMap;
+use qsc_data_structures::{fx::FxHashSet, index_map::IndexMap};
 use qsc_eval::{
     backend::Backend,
+    output::{self, Receiver},
     val::{Qubit, QubitRef, Result, Value},
 };
 use qsc_rir::rir::{BlockId, CallableId, VariableId};
-use rustc_hash::FxHashSet;
 
 /// Manages IDs for resources needed while performing partial evaluation.
 #[derive(Default)]
@@ -156,3 +156,32 @@ impl Backend for QuantumIntrinsicsChecker {
         }
     }
 }
+
+/// Receiver for the output of classical evaluation, which only keeps the text of messages so that the partial
+/// evaluator can handle them according to its configuration. Other output is discarded.
+#[derive(Default)]
+pub struct MessageReceiver {
+    pub messages: Vec<String>,
+}
+
+impl Receiver for MessageReceiver {
+    fn state(
+        &mut self,
+        _state: Vec<(BigUint, Complex<f64>)>,
+        _qubit_count: usize,
+    ) -> std::result::Result<(), output::Error> {
+        Ok(())
+    }
+
+    fn matrix(
+        &mut self,
+        _matrix: Vec<Vec<Complex<f64>>>,
+    ) -> std::result::Result<(), output::Error> {
+        Ok(())
+    }
+
+    fn message(&mut self, msg: &str) -> std::result::Result<(), output::Error> {
+        self.messages.push(msg.to_string());
+        Ok(())
+    }
+}
@@ -7,17 +7,21 @@ mod bindings;
 mod branching;
 mod calls;
 mod classical_args;
+mod conjugates;
 mod dynamic_vars;
 mod intrinsics;
 mod loops;
+mod measurements;
+mod messages;
 mod misc;
 mod operators;
 mod output_recording;
 mod qubits;
+mod recursion;
 mod results;
 mod returns;
 
-use crate::{partially_evaluate, Error, ProgramEntry};
+use crate::{partially_evaluate, Error, PartialEvalConfig, ProgramEntry, Warning};
 use expect_test::Expect;
 use qsc::{incremental::Compiler, PackageType};
 use qsc_data_structures::{language_features::LanguageFeatures, target::TargetCapabilityFlags};
@@ -75,6 +79,20 @@ pub fn get_partial_evaluation_error_with_capabilities(
     }
 }
 
+#[must_use]
+pub fn get_partial_evaluation_error_with_config(source: &str, config: PartialEvalConfig) -> Error {
+    let maybe_program = compile_and_partially_evaluate_with_config(
+        source,
+        TargetCapabilityFlags::all(),
+        config,
+        &mut Vec::new(),
+    );
+    match maybe_program {
+        Ok(_) => panic!("partial evaluation succeeded"),
+        Err(error) => error,
+    }
+}
+
 #[must_use]
 pub fn get_rir_program(source: &str) -> Program {
     let maybe_program = compile_and_partially_evaluate(source, TargetCapabilityFlags::all());
@@ -100,9 +118,46 @@ pub fn get_rir_program_with_capabilities(
     }
 }
 
+#[must_use]
+pub fn get_rir_program_with_config(source: &str, config: PartialEvalConfig) -> Program {
+    get_rir_program_and_warnings_with_config(source, config).0
+}
+
+#[must_use]
+pub fn get_rir_program_and_warnings_with_config(
+    source: &str,
+    config: PartialEvalConfig,
+) -> (Program, Vec<Warning>) {
+    let mut warnings = Vec::new();
+    let maybe_program = compile_and_partially_evaluate_with_config(
+        source,
+        TargetCapabilityFlags::all(),
+        config,
+        &mut warnings,
+    );
+    match maybe_program {
+        Ok(program) => (program, warnings),
+        Err(error) => panic!("partial evaluation failed: {error:?}"),
+    }
+}
+
 fn compile_and_partially_evaluate(
     source: &str,
     capabilities: TargetCapabilityFlags,
+) -> Result<Program, Error> {
+    compile_and_partially_evaluate_with_config(
+        source,
+        capabilities,
+        PartialEvalConfig::default(),
+        &mut Vec::new(),
+    )
+}
+
+fn compile_and_partially_evaluate_with_config(
+    source: &str,
+    capabilities: TargetCapabilityFlags,
+    config: PartialEvalConfig,
+    warnings: &mut Vec<Warning>,
 ) -> Result<Program, Error> {
     let compilation_context = CompilationContext::new(source, capabilities);
     partially_evaluate(
@@ -110,6 +165,8 @@ fn compile_and_partially_evaluate(
         &compilation_context.compute_properties,
         &compilation_context.entry,
         capabilities,
+        config,
+        warnings,
     )
 }
 
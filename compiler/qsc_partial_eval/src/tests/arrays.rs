@@ -8,8 +8,8 @@
 )]
 
 use super::{
-    assert_block_instructions, assert_callable, assert_error, get_partial_evaluation_error,
-    get_rir_program,
+    assert_block_instructions, assert_blocks, assert_callable, assert_error,
+    get_partial_evaluation_error, get_rir_program,
 };
 use expect_test::expect;
 use indoc::indoc;
@@ -1034,3 +1034,201 @@ fn result_array_copy_and_update_with_out_of_bounds_range_raises_error() {
         ]],
     );
 }
+
+#[test]
+fn mapped_with_lambda_over_array_of_dynamic_results() {
+    let program = get_rir_program(indoc! {"
+        namespace Test {
+            import Std.Arrays.*;
+            @EntryPoint()
+            operation Main() : Bool[] {
+                use qs = Qubit[2];
+                let rs = MeasureEachZ(qs);
+                Mapped(r -> r == One, rs)
+            }
+        }
+    "});
+    assert_blocks(
+        &program,
+        &expect![[r#"
+        Blocks:
+        Block 0:Block:
+            Variable(0, Integer) = Store Integer(0)
+            Variable(0, Integer) = Store Integer(1)
+            Variable(0, Integer) = Store Integer(2)
+            Variable(1, Integer) = Store Integer(0)
+            Call id(1), args( Qubit(0), Result(0), )
+            Variable(1, Integer) = Store Integer(1)
+            Call id(1), args( Qubit(1), Result(1), )
+            Variable(1, Integer) = Store Integer(2)
+            Variable(2, Boolean) = Call id(2), args( Result(0), )
+            Variable(3, Boolean) = Store Variable(2, Boolean)
+            Variable(4, Boolean) = Call id(2), args( Result(1), )
+            Variable(5, Boolean) = Store Variable(4, Boolean)
+            Variable(6, Integer) = Store Integer(0)
+            Variable(6, Integer) = Store Integer(1)
+            Variable(6, Integer) = Store Integer(2)
+            Call id(3), args( Integer(2), Pointer, )
+            Call id(4), args( Variable(3, Boolean), Pointer, )
+            Call id(4), args( Variable(5, Boolean), Pointer, )
+            Return"#]],
+    );
+}
+
+#[test]
+fn fold_with_lambda_over_array_of_dynamic_results() {
+    let program = get_rir_program(indoc! {"
+        namespace Test {
+            import Std.Arrays.*;
+            @EntryPoint()
+            operation Main() : Int {
+                use qs = Qubit[2];
+                let rs = MeasureEachZ(qs);
+                Fold((count, r) -> r == One ? count + 1 | count, 0, rs)
+            }
+        }
+    "});
+    assert_blocks(
+        &program,
+        &expect![[r#"
+        Blocks:
+        Block 0:Block:
+            Variable(0, Integer) = Store Integer(0)
+            Variable(0, Integer) = Store Integer(1)
+            Variable(0, Integer) = Store Integer(2)
+            Variable(1, Integer) = Store Integer(0)
+            Call id(1), args( Qubit(0), Result(0), )
+            Variable(1, Integer) = Store Integer(1)
+            Call id(1), args( Qubit(1), Result(1), )
+            Variable(1, Integer) = Store Integer(2)
+            Variable(2, Boolean) = Call id(2), args( Result(0), )
+            Variable(3, Boolean) = Store Variable(2, Boolean)
+            Branch Variable(3, Boolean), 2, 3
+        Block 1:Block:
+            Variable(5, Boolean) = Call id(2), args( Result(1), )
+            Variable(6, Boolean) = Store Variable(5, Boolean)
+            Branch Variable(6, Boolean), 5, 6
+        Block 2:Block:
+            Variable(4, Integer) = Store Integer(1)
+            Jump(1)
+        Block 3:Block:
+            Variable(4, Integer) = Store Integer(0)
+            Jump(1)
+        Block 4:Block:
+            Variable(9, Integer) = Store Variable(7, Integer)
+            Variable(10, Integer) = Store Integer(0)
+            Variable(10, Integer) = Store Integer(1)
+            Variable(10, Integer) = Store Integer(2)
+            Call id(3), args( Variable(9, Integer), Pointer, )
+            Return
+        Block 5:Block:
+            Variable(8, Integer) = Add Variable(4, Integer), Integer(1)
+            Variable(7, Integer) = Store Variable(8, Integer)
+            Jump(4)
+        Block 6:Block:
+            Variable(7, Integer) = Store Variable(4, Integer)
+            Jump(4)"#]],
+    );
+}
+
+#[test]
+fn for_each_with_lambda_over_array_of_dynamic_results() {
+    let program = get_rir_program(indoc! {"
+        namespace Test {
+            import Std.Arrays.*;
+            @EntryPoint()
+            operation Main() : Result[] {
+                use qs = Qubit[2];
+                let rs = MeasureEachZ(qs);
+                ForEach(r => {
+                    if r == One {
+                        X(qs[0]);
+                    }
+                    M(qs[0])
+                }, rs)
+            }
+        }
+    "});
+    assert_blocks(
+        &program,
+        &expect![[r#"
+        Blocks:
+        Block 0:Block:
+            Variable(0, Integer) = Store Integer(0)
+            Variable(0, Integer) = Store Integer(1)
+            Variable(0, Integer) = Store Integer(2)
+            Variable(1, Integer) = Store Integer(0)
+            Call id(1), args( Qubit(0), Result(0), )
+            Variable(1, Integer) = Store Integer(1)
+            Call id(1), args( Qubit(1), Result(1), )
+            Variable(1, Integer) = Store Integer(2)
+            Variable(2, Boolean) = Call id(2), args( Result(0), )
+            Variable(3, Boolean) = Store Variable(2, Boolean)
+            Branch Variable(3, Boolean), 2, 1
+        Block 1:Block:
+            Call id(1), args( Qubit(0), Result(2), )
+            Variable(4, Boolean) = Call id(2), args( Result(1), )
+            Variable(5, Boolean) = Store Variable(4, Boolean)
+            Branch Variable(5, Boolean), 4, 3
+        Block 2:Block:
+            Call id(3), args( Qubit(0), )
+            Jump(1)
+        Block 3:Block:
+            Call id(1), args( Qubit(0), Result(3), )
+            Variable(6, Integer) = Store Integer(0)
+            Variable(6, Integer) = Store Integer(1)
+            Variable(6, Integer) = Store Integer(2)
+            Call id(4), args( Integer(2), Pointer, )
+            Call id(5), args( Result(2), Pointer, )
+            Call id(5), args( Result(3), Pointer, )
+            Return
+        Block 4:Block:
+            Call id(3), args( Qubit(0), )
+            Jump(3)"#]],
+    );
+}
+
+#[test]
+fn branch_on_element_of_array_mapped_from_dynamic_results() {
+    let program = get_rir_program(indoc! {"
+        namespace Test {
+            import Std.Arrays.*;
+            @EntryPoint()
+            operation Main() : Unit {
+                use qs = Qubit[2];
+                let bs = Mapped(r -> r == One, MeasureEachZ(qs));
+                if bs[0] {
+                    X(qs[1]);
+                }
+            }
+        }
+    "});
+    assert_blocks(
+        &program,
+        &expect![[r#"
+        Blocks:
+        Block 0:Block:
+            Variable(0, Integer) = Store Integer(0)
+            Variable(0, Integer) = Store Integer(1)
+            Variable(0, Integer) = Store Integer(2)
+            Variable(1, Integer) = Store Integer(0)
+            Call id(1), args( Qubit(0), Result(0), )
+            Variable(1, Integer) = Store Integer(1)
+            Call id(1), args( Qubit(1), Result(1), )
+            Variable(1, Integer) = Store Integer(2)
+            Variable(2, Boolean) = Call id(2), args( Result(0), )
+            Variable(3, Boolean) = Store Variable(2, Boolean)
+            Variable(4, Boolean) = Call id(2), args( Result(1), )
+            Variable(5, Boolean) = Store Variable(4, Boolean)
+            Branch Variable(3, Boolean), 2, 1
+        Block 1:Block:
+            Variable(6, Integer) = Store Integer(0)
+            Variable(6, Integer) = Store Integer(1)
+            Variable(6, Integer) = Store Integer(2)
+            Call id(4), args( Integer(0), Pointer, )
+            Return
+        Block 2:Block:
+            Call id(3), args( Qubit(1), )
+            Jump(1)"#]],
+    );
+}
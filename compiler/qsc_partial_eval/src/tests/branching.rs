@@ -2311,3 +2311,109 @@ fn if_expression_with_dynamic_operand_from_hybrid_doubles_array() {
                 Jump(6)"#]],
     );
 }
+
+#[test]
+fn enum_matched_within_dynamic_branch() {
+    let program = get_rir_program(indoc! {
+        r#"
+        namespace Test {
+            enum Rotation { Quarter, Turns(Int) }
+            operation Rotate(rotation : Rotation, q : Qubit) : Unit {
+                match rotation {
+                    Quarter() => S(q),
+                    Turns(n) => for _ in 1..n { Z(q); },
+                }
+            }
+            @EntryPoint()
+            operation Main() : Result {
+                use (q0, q1) = (Qubit(), Qubit());
+                let rotation = Turns(3);
+                if M(q0) == One {
+                    Rotate(rotation, q1);
+                }
+                M(q1)
+            }
+        }
+        "#,
+    });
+    assert_blocks(
+        &program,
+        &expect![[r#"
+            Blocks:
+            Block 0:Block:
+                Call id(1), args( Qubit(0), Result(0), )
+                Variable(0, Boolean) = Call id(2), args( Result(0), )
+                Variable(1, Boolean) = Store Variable(0, Boolean)
+                Branch Variable(1, Boolean), 2, 1
+            Block 1:Block:
+                Call id(1), args( Qubit(1), Result(1), )
+                Call id(4), args( Result(1), Pointer, )
+                Return
+            Block 2:Block:
+                Variable(2, Integer) = Store Integer(1)
+                Call id(3), args( Qubit(1), )
+                Variable(2, Integer) = Store Integer(2)
+                Call id(3), args( Qubit(1), )
+                Variable(2, Integer) = Store Integer(3)
+                Call id(3), args( Qubit(1), )
+                Variable(2, Integer) = Store Integer(4)
+                Jump(1)"#]],
+    );
+}
+
+#[test]
+fn enum_value_of_if_expression_with_dynamic_condition_fails() {
+    let error = get_partial_evaluation_error(indoc! {
+        r#"
+        namespace Test {
+            enum Rotation { Quarter, Turns(Int) }
+            @EntryPoint()
+            operation Main() : Result {
+                use (q0, q1) = (Qubit(), Qubit());
+                let rotation = if M(q0) == One { Quarter() } else { Turns(2) };
+                match rotation {
+                    Quarter() => S(q1),
+                    Turns(n) => for _ in 1..n { Z(q1); },
+                }
+                M(q1)
+            }
+        }
+        "#,
+    });
+    assert_error(
+        &error,
+        &expect![[
+            r#"Unimplemented("dynamic value of type UDT<Item 1> in conditional expression", PackageSpan { package: PackageId(2), span: Span { lo: 176, hi: 223 } })"#
+        ]],
+    );
+}
+
+#[test]
+fn enum_reassigned_within_dynamic_branch_fails() {
+    let error = get_partial_evaluation_error(indoc! {
+        r#"
+        namespace Test {
+            enum Rotation { Quarter, Turns(Int) }
+            @EntryPoint()
+            operation Main() : Result {
+                use (q0, q1) = (Qubit(), Qubit());
+                mutable rotation = Quarter();
+                if M(q0) == One {
+                    set rotation = Turns(2);
+                }
+                match rotation {
+                    Quarter() => S(q1),
+                    Turns(n) => for _ in 1..n { Z(q1); },
+                }
+                M(q1)
+            }
+        }
+        "#,
+    });
+    assert_error(
+        &error,
+        &expect![[
+            r#"Unexpected("re-assignment within a dynamic branch is unsupported for type UDT<Item 1>", PackageSpan { package: PackageId(2), span: Span { lo: 233, hi: 241 } })"#
+        ]],
+    );
+}
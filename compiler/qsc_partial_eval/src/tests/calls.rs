@@ -1267,24 +1267,62 @@ fn call_to_unresolved_callee_producing_dynamic_value_fails() {
         indoc! {"
         namespace Test {
             import Std.Convert.*;
+            operation Op(q : Qubit) : Int {
+                if MResetZ(q) == One { 1 } else { 0 }
+            }
+            @EntryPoint()
+            operation Main() : Unit {
+                use q = Qubit();
+                let f = [Op][0];
+                let _ = f(q);
+            }
+        }"},
+        TargetCapabilityFlags::Adaptive | TargetCapabilityFlags::IntegerComputations,
+    );
+
+    assert_error(&error, &expect!["UnexpectedDynamicValue(PackageSpan { package: PackageId(2), span: Span { lo: 246, hi: 250 } })"]);
+}
+
+#[test]
+fn call_to_unresolved_callee_with_dynamic_arg_producing_dynamic_value() {
+    let program = get_rir_program_with_capabilities(
+        indoc! {"
+        namespace Test {
             operation Op(i : Int, q : Qubit) : Int {
                 X(q);
                 i
             }
             @EntryPoint()
-            operation Main() : Unit {
+            operation Main() : Int {
                 use q = Qubit();
                 let i = if MResetZ(q) == One { 1 } else { 0 };
                 let f = [Op][0];
-                let _ = f(i, q);
+                f(i, q)
             }
         }"},
         TargetCapabilityFlags::Adaptive | TargetCapabilityFlags::IntegerComputations,
     );
-
-    assert_error(
-        &error,
-        &expect!["UnexpectedDynamicValue(PackageSpan { package: PackageId(2), span: Span { lo: 288, hi: 295 } })"],
+    assert_blocks(
+        &program,
+        &expect![[r#"
+        Blocks:
+        Block 0:Block:
+            Call id(1), args( Qubit(0), Result(0), )
+            Variable(0, Boolean) = Call id(2), args( Result(0), )
+            Variable(1, Boolean) = Store Variable(0, Boolean)
+            Branch Variable(1, Boolean), 2, 3
+        Block 1:Block:
+            Variable(3, Integer) = Store Variable(2, Integer)
+            Call id(3), args( Qubit(0), )
+            Variable(4, Integer) = Store Variable(3, Integer)
+            Call id(4), args( Variable(4, Integer), Pointer, )
+            Return
+        Block 2:Block:
+            Variable(2, Integer) = Store Integer(1)
+            Jump(1)
+        Block 3:Block:
+            Variable(2, Integer) = Store Integer(0)
+            Jump(1)"#]],
     );
 }
 
@@ -1345,3 +1383,288 @@ fn call_to_unresolved_callee_with_static_arg_and_entry_return_value_succeeds() {
                 Return"#]],
     );
 }
+
+#[test]
+fn call_to_host_callable_function_raises_error() {
+    let error = get_partial_evaluation_error_with_capabilities(
+        indoc! {"
+        namespace Test {
+            @HostCallable()
+            function Threshold(x : Int) : Int { body intrinsic; }
+            @EntryPoint()
+            operation Main() : Result {
+                use q = Qubit();
+                if Threshold(3) > 2 {
+                    X(q);
+                }
+                MResetZ(q)
+            }
+        }"},
+        TargetCapabilityFlags::all(),
+    );
+    assert_error(
+        &error,
+        &expect![[
+            r#"UnsupportedHostCall("Threshold", PackageSpan { package: PackageId(2), span: Span { lo: 42, hi: 95 } })"#
+        ]],
+    );
+}
+
+#[test]
+fn checked_conversion_of_dynamic_int_to_double_checks_round_trip() {
+    let program = get_rir_program_with_capabilities(
+        indoc! {"
+        namespace Test {
+            import Std.Convert.*;
+            @EntryPoint()
+            operation Main() : (Bool, Double) {
+                use q = Qubit();
+                let i = MResetZ(q) == One ? 1 | 0;
+                TryIntAsDouble(i)
+            }
+        }"},
+        TargetCapabilityFlags::all(),
+    );
+    assert_blocks(
+        &program,
+        &expect![[r#"
+        Blocks:
+        Block 0:Block:
+            Call id(1), args( Qubit(0), Result(0), )
+            Variable(0, Boolean) = Call id(2), args( Result(0), )
+            Variable(1, Boolean) = Store Variable(0, Boolean)
+            Branch Variable(1, Boolean), 2, 3
+        Block 1:Block:
+            Variable(3, Integer) = Store Variable(2, Integer)
+            Variable(4, Boolean) = Store Bool(false)
+            Variable(5, Double) = Store Double(0)
+            Variable(8, Double) = Sitofp Variable(3, Integer)
+            Variable(6, Boolean) = Fcmp Olt, Variable(8, Double), Double(9223372036854775808)
+            Branch Variable(6, Boolean), 5, 4
+        Block 2:Block:
+            Variable(2, Integer) = Store Integer(1)
+            Jump(1)
+        Block 3:Block:
+            Variable(2, Integer) = Store Integer(0)
+            Jump(1)
+        Block 4:Block:
+            Call id(3), args( Integer(2), Pointer, )
+            Call id(4), args( Variable(4, Boolean), Pointer, )
+            Call id(5), args( Variable(5, Double), Pointer, )
+            Return
+        Block 5:Block:
+            Variable(9, Integer) = Fptosi Variable(8, Double)
+            Variable(7, Boolean) = Icmp Eq, Variable(9, Integer), Variable(3, Integer)
+            Branch Variable(7, Boolean), 6, 4
+        Block 6:Block:
+            Variable(4, Boolean) = Store Bool(true)
+            Variable(5, Double) = Store Variable(8, Double)
+            Jump(4)"#]],
+    );
+}
+
+#[test]
+fn checked_conversion_of_dynamic_double_to_int_checks_range_before_converting() {
+    let program = get_rir_program_with_capabilities(
+        indoc! {"
+        namespace Test {
+            import Std.Convert.*;
+            @EntryPoint()
+            operation Main() : (Bool, Int) {
+                use q = Qubit();
+                let d = MResetZ(q) == One ? 1.5 | 0.0;
+                TryDoubleAsInt(d)
+            }
+        }"},
+        TargetCapabilityFlags::all(),
+    );
+    assert_blocks(
+        &program,
+        &expect![[r#"
+        Blocks:
+        Block 0:Block:
+            Call id(1), args( Qubit(0), Result(0), )
+            Variable(0, Boolean) = Call id(2), args( Result(0), )
+            Variable(1, Boolean) = Store Variable(0, Boolean)
+            Branch Variable(1, Boolean), 2, 3
+        Block 1:Block:
+            Variable(3, Double) = Store Variable(2, Double)
+            Variable(4, Boolean) = Store Bool(false)
+            Variable(5, Integer) = Store Integer(0)
+            Variable(8, Boolean) = Fcmp Oge, Variable(3, Double), Double(-9223372036854775808)
+            Variable(9, Boolean) = Fcmp Olt, Variable(3, Double), Double(9223372036854775808)
+            Variable(6, Boolean) = LogicalAnd Variable(8, Boolean), Variable(9, Boolean)
+            Branch Variable(6, Boolean), 5, 4
+        Block 2:Block:
+            Variable(2, Double) = Store Double(1.5)
+            Jump(1)
+        Block 3:Block:
+            Variable(2, Double) = Store Double(0)
+            Jump(1)
+        Block 4:Block:
+            Call id(3), args( Integer(2), Pointer, )
+            Call id(4), args( Variable(4, Boolean), Pointer, )
+            Call id(5), args( Variable(5, Integer), Pointer, )
+            Return
+        Block 5:Block:
+            Variable(10, Integer) = Fptosi Variable(3, Double)
+            Variable(11, Double) = Sitofp Variable(10, Integer)
+            Variable(7, Boolean) = Fcmp Oeq, Variable(11, Double), Variable(3, Double)
+            Branch Variable(7, Boolean), 6, 4
+        Block 6:Block:
+            Variable(4, Boolean) = Store Bool(true)
+            Variable(5, Integer) = Store Variable(10, Integer)
+            Jump(4)"#]],
+    );
+}
+
+#[test]
+fn call_to_closure_capturing_dynamic_bool() {
+    let program = get_rir_program(indoc! {"
+        namespace Test {
+            @EntryPoint()
+            operation Main() : Unit {
+                use (q0, q1) = (Qubit(), Qubit());
+                let b = MResetZ(q0) == One;
+                let flip = q => if b { X(q); };
+                flip(q1);
+            }
+        }
+    "});
+    assert_blocks(
+        &program,
+        &expect![[r#"
+        Blocks:
+        Block 0:Block:
+            Call id(1), args( Qubit(0), Result(0), )
+            Variable(0, Boolean) = Call id(2), args( Result(0), )
+            Variable(1, Boolean) = Store Variable(0, Boolean)
+            Variable(2, Boolean) = Store Variable(1, Boolean)
+            Branch Variable(2, Boolean), 2, 1
+        Block 1:Block:
+            Call id(4), args( Integer(0), Pointer, )
+            Return
+        Block 2:Block:
+            Call id(3), args( Qubit(1), )
+            Jump(1)"#]],
+    );
+}
+
+#[test]
+fn call_to_closure_capturing_dynamic_int() {
+    let program = get_rir_program_with_capabilities(
+        indoc! {"
+        namespace Test {
+            @EntryPoint()
+            operation Main() : Int {
+                use q = Qubit();
+                let i = MResetZ(q) == One ? 1 | 0;
+                let add = n -> n + i;
+                add(2)
+            }
+        }"},
+        TargetCapabilityFlags::Adaptive | TargetCapabilityFlags::IntegerComputations,
+    );
+    assert_blocks(
+        &program,
+        &expect![[r#"
+        Blocks:
+        Block 0:Block:
+            Call id(1), args( Qubit(0), Result(0), )
+            Variable(0, Boolean) = Call id(2), args( Result(0), )
+            Variable(1, Boolean) = Store Variable(0, Boolean)
+            Branch Variable(1, Boolean), 2, 3
+        Block 1:Block:
+            Variable(3, Integer) = Store Variable(2, Integer)
+            Variable(4, Integer) = Add Integer(2), Variable(3, Integer)
+            Variable(5, Integer) = Store Variable(4, Integer)
+            Call id(3), args( Variable(5, Integer), Pointer, )
+            Return
+        Block 2:Block:
+            Variable(2, Integer) = Store Integer(1)
+            Jump(1)
+        Block 3:Block:
+            Variable(2, Integer) = Store Integer(0)
+            Jump(1)"#]],
+    );
+}
+
+#[test]
+fn call_to_closure_capturing_dynamic_bool_returned_from_operation() {
+    let program = get_rir_program(indoc! {"
+        namespace Test {
+            operation MakeFlip(b : Bool) : (Qubit => Unit) {
+                q => if b { X(q); }
+            }
+            @EntryPoint()
+            operation Main() : Unit {
+                use (q0, q1) = (Qubit(), Qubit());
+                let flip = MakeFlip(MResetZ(q0) == One);
+                flip(q1);
+            }
+        }
+    "});
+    assert_blocks(
+        &program,
+        &expect![[r#"
+        Blocks:
+        Block 0:Block:
+            Call id(1), args( Qubit(0), Result(0), )
+            Variable(0, Boolean) = Call id(2), args( Result(0), )
+            Variable(1, Boolean) = Store Variable(0, Boolean)
+            Branch Variable(1, Boolean), 2, 1
+        Block 1:Block:
+            Call id(4), args( Integer(0), Pointer, )
+            Return
+        Block 2:Block:
+            Call id(3), args( Qubit(1), )
+            Jump(1)"#]],
+    );
+}
+
+#[test]
+fn call_to_pure_function_through_unresolved_callee_is_folded() {
+    let program = get_rir_program_with_capabilities(
+        indoc! {"
+        namespace Test {
+            @Pure()
+            function Triangle(n : Int) : Int {
+                mutable sum = 0;
+                for i in 1..n {
+                    set sum += i;
+                }
+                sum
+            }
+            @EntryPoint()
+            operation Main() : Int {
+                use q = Qubit();
+                let f = [Triangle][0];
+                let r = MResetZ(q);
+                mutable x = 0;
+                if r == One {
+                    set x = f(4);
+                }
+                x
+            }
+        }"},
+        TargetCapabilityFlags::Adaptive | TargetCapabilityFlags::IntegerComputations,
+    );
+    assert_blocks(
+        &program,
+        &expect![[r#"
+        Blocks:
+        Block 0:Block:
+            Call id(1), args( Qubit(0), Result(0), )
+            Variable(0, Integer) = Store Integer(0)
+            Variable(1, Boolean) = Call id(2), args( Result(0), )
+            Variable(2, Boolean) = Store Variable(1, Boolean)
+            Branch Variable(2, Boolean), 2, 1
+        Block 1:Block:
+            Variable(3, Integer) = Store Variable(0, Integer)
+            Call id(3), args( Variable(3, Integer), Pointer, )
+            Return
+        Block 2:Block:
+            Variable(0, Integer) = Store Integer(10)
+            Jump(1)"#]],
+    );
+}
@@ -0,0 +1,313 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#![allow(clippy::needless_raw_string_hashes)]
+
+use super::{assert_blocks, get_rir_program};
+use expect_test::expect;
+use indoc::indoc;
+
+#[test]
+fn conjugate_with_static_apply_block() {
+    let program = get_rir_program(indoc! {
+        r#"
+        namespace Test {
+            @EntryPoint()
+            operation Main() : Result {
+                use q = Qubit();
+                within {
+                    H(q);
+                    S(q);
+                } apply {
+                    X(q);
+                }
+                MResetZ(q)
+            }
+        }
+        "#,
+    });
+    assert_blocks(
+        &program,
+        &expect![[r#"
+        Blocks:
+        Block 0:Block:
+            Call id(1), args( Qubit(0), )
+            Call id(2), args( Qubit(0), )
+            Call id(3), args( Qubit(0), )
+            Call id(4), args( Qubit(0), )
+            Call id(1), args( Qubit(0), )
+            Call id(5), args( Qubit(0), Result(0), )
+            Call id(6), args( Result(0), Pointer, )
+            Return"#]],
+    );
+}
+
+#[test]
+fn conjugate_with_measurement_conditioned_apply_block() {
+    let program = get_rir_program(indoc! {
+        r#"
+        namespace Test {
+            @EntryPoint()
+            operation Main() : Result {
+                use (q0, q1) = (Qubit(), Qubit());
+                within {
+                    H(q0);
+                    T(q0);
+                    Rx(1.0, q0);
+                } apply {
+                    if M(q1) == One {
+                        X(q0);
+                    }
+                }
+                MResetZ(q0)
+            }
+        }
+        "#,
+    });
+    assert_blocks(
+        &program,
+        &expect![[r#"
+        Blocks:
+        Block 0:Block:
+            Call id(1), args( Qubit(0), )
+            Call id(2), args( Qubit(0), )
+            Call id(3), args( Double(1), Qubit(0), )
+            Call id(4), args( Qubit(1), Result(0), )
+            Variable(0, Boolean) = Call id(5), args( Result(0), )
+            Variable(1, Boolean) = Store Variable(0, Boolean)
+            Branch Variable(1, Boolean), 2, 1
+        Block 1:Block:
+            Call id(3), args( Double(-1), Qubit(0), )
+            Call id(7), args( Qubit(0), )
+            Call id(1), args( Qubit(0), )
+            Call id(8), args( Qubit(0), Result(1), )
+            Call id(9), args( Result(1), Pointer, )
+            Return
+        Block 2:Block:
+            Call id(6), args( Qubit(0), )
+            Jump(1)"#]],
+    );
+}
+
+#[test]
+fn conjugate_with_apply_block_returning_measurement() {
+    let program = get_rir_program(indoc! {
+        r#"
+        namespace Test {
+            @EntryPoint()
+            operation Main() : Result {
+                use q = Qubit();
+                within {
+                    H(q);
+                } apply {
+                    M(q)
+                }
+            }
+        }
+        "#,
+    });
+    assert_blocks(
+        &program,
+        &expect![[r#"
+        Blocks:
+        Block 0:Block:
+            Call id(1), args( Qubit(0), )
+            Call id(2), args( Qubit(0), Result(0), )
+            Call id(1), args( Qubit(0), )
+            Call id(3), args( Result(0), Pointer, )
+            Return"#]],
+    );
+}
+
+#[test]
+fn conjugate_with_apply_block_updating_dynamic_variable() {
+    let program = get_rir_program(indoc! {
+        r#"
+        namespace Test {
+            @EntryPoint()
+            operation Main() : Int {
+                use (q0, q1) = (Qubit(), Qubit());
+                mutable count = 0;
+                within {
+                    H(q0);
+                } apply {
+                    if M(q1) == One {
+                        set count += 1;
+                    }
+                }
+                count
+            }
+        }
+        "#,
+    });
+    assert_blocks(
+        &program,
+        &expect![[r#"
+        Blocks:
+        Block 0:Block:
+            Variable(0, Integer) = Store Integer(0)
+            Call id(1), args( Qubit(0), )
+            Call id(2), args( Qubit(1), Result(0), )
+            Variable(1, Boolean) = Call id(3), args( Result(0), )
+            Variable(2, Boolean) = Store Variable(1, Boolean)
+            Branch Variable(2, Boolean), 2, 1
+        Block 1:Block:
+            Call id(1), args( Qubit(0), )
+            Variable(3, Integer) = Store Variable(0, Integer)
+            Call id(4), args( Variable(3, Integer), Pointer, )
+            Return
+        Block 2:Block:
+            Variable(0, Integer) = Store Integer(1)
+            Jump(1)"#]],
+    );
+}
+
+#[test]
+fn nested_conjugates_with_measurement_conditioned_apply_blocks() {
+    let program = get_rir_program(indoc! {
+        r#"
+        namespace Test {
+            @EntryPoint()
+            operation Main() : Result {
+                use (q0, q1) = (Qubit(), Qubit());
+                within {
+                    H(q0);
+                } apply {
+                    if M(q1) == One {
+                        within {
+                            X(q1);
+                        } apply {
+                            CNOT(q1, q0);
+                        }
+                    }
+                }
+                MResetZ(q0)
+            }
+        }
+        "#,
+    });
+    assert_blocks(
+        &program,
+        &expect![[r#"
+        Blocks:
+        Block 0:Block:
+            Call id(1), args( Qubit(0), )
+            Call id(2), args( Qubit(1), Result(0), )
+            Variable(0, Boolean) = Call id(3), args( Result(0), )
+            Variable(1, Boolean) = Store Variable(0, Boolean)
+            Branch Variable(1, Boolean), 2, 1
+        Block 1:Block:
+            Call id(1), args( Qubit(0), )
+            Call id(6), args( Qubit(0), Result(1), )
+            Call id(7), args( Result(1), Pointer, )
+            Return
+        Block 2:Block:
+            Call id(4), args( Qubit(1), )
+            Call id(5), args( Qubit(1), Qubit(0), )
+            Call id(4), args( Qubit(1), )
+            Jump(1)"#]],
+    );
+}
+
+#[test]
+fn conjugate_with_dynamic_within_block_is_evaluated_again() {
+    let program = get_rir_program(indoc! {
+        r#"
+        namespace Test {
+            @EntryPoint()
+            operation Main() : Result {
+                use (q0, q1) = (Qubit(), Qubit());
+                let r = M(q1);
+                within {
+                    if r == One {
+                        S(q0);
+                    }
+                } apply {
+                    if M(q1) == One {
+                        X(q0);
+                    }
+                }
+                MResetZ(q0)
+            }
+        }
+        "#,
+    });
+    assert_blocks(
+        &program,
+        &expect![[r#"
+        Blocks:
+        Block 0:Block:
+            Call id(1), args( Qubit(1), Result(0), )
+            Variable(0, Boolean) = Call id(2), args( Result(0), )
+            Variable(1, Boolean) = Store Variable(0, Boolean)
+            Branch Variable(1, Boolean), 2, 1
+        Block 1:Block:
+            Call id(1), args( Qubit(1), Result(1), )
+            Variable(2, Boolean) = Call id(2), args( Result(1), )
+            Variable(3, Boolean) = Store Variable(2, Boolean)
+            Branch Variable(3, Boolean), 4, 3
+        Block 2:Block:
+            Call id(3), args( Qubit(0), )
+            Jump(1)
+        Block 3:Block:
+            Variable(4, Boolean) = Call id(2), args( Result(0), )
+            Variable(5, Boolean) = Store Variable(4, Boolean)
+            Branch Variable(5, Boolean), 6, 5
+        Block 4:Block:
+            Call id(4), args( Qubit(0), )
+            Jump(3)
+        Block 5:Block:
+            Call id(6), args( Qubit(0), Result(2), )
+            Call id(7), args( Result(2), Pointer, )
+            Return
+        Block 6:Block:
+            Call id(5), args( Qubit(0), )
+            Jump(5)"#]],
+    );
+}
+
+#[test]
+fn conjugate_with_dynamic_rotation_angle_is_evaluated_again() {
+    let program = get_rir_program(indoc! {
+        r#"
+        namespace Test {
+            @EntryPoint()
+            operation Main() : Result {
+                use (q0, q1) = (Qubit(), Qubit());
+                mutable theta = 0.0;
+                if M(q1) == One {
+                    set theta = 1.0;
+                }
+                within {
+                    Rx(theta, q0);
+                } apply {
+                    X(q0);
+                }
+                MResetZ(q0)
+            }
+        }
+        "#,
+    });
+    assert_blocks(
+        &program,
+        &expect![[r#"
+        Blocks:
+        Block 0:Block:
+            Variable(0, Double) = Store Double(0)
+            Call id(1), args( Qubit(1), Result(0), )
+            Variable(1, Boolean) = Call id(2), args( Result(0), )
+            Variable(2, Boolean) = Store Variable(1, Boolean)
+            Branch Variable(2, Boolean), 2, 1
+        Block 1:Block:
+            Call id(3), args( Variable(0, Double), Qubit(0), )
+            Call id(4), args( Qubit(0), )
+            Variable(3, Double) = Fmul Double(-1), Variable(0, Double)
+            Call id(3), args( Variable(3, Double), Qubit(0), )
+            Call id(5), args( Qubit(0), Result(1), )
+            Call id(6), args( Result(1), Pointer, )
+            Return
+        Block 2:Block:
+            Variable(0, Double) = Store Double(1)
+            Jump(1)"#]],
+    );
+}
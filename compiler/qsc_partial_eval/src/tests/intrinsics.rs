@@ -1152,3 +1152,84 @@ fn call_to_operation_with_codegen_intrinsic_override_should_skip_impl() {
             Return"#]],
     );
 }
+
+#[test]
+fn call_to_custom_intrinsic_with_int_output_stores_output_in_variable() {
+    let program = get_rir_program(indoc! {"
+        namespace Test {
+            operation ReadCounter(q : Qubit) : Int {
+                body intrinsic;
+            }
+            @EntryPoint()
+            operation Main() : Int {
+                use q = Qubit();
+                ReadCounter(q)
+            }
+        }
+    "});
+
+    let op_callable_id = CallableId(1);
+    assert_callable(
+        &program,
+        op_callable_id,
+        &expect![[r#"
+            Callable:
+                name: ReadCounter
+                call_type: Regular
+                input_type:
+                    [0]: Qubit
+                output_type: Integer
+                body: <NONE>"#]],
+    );
+    assert_block_instructions(
+        &program,
+        BlockId(0),
+        &expect![[r#"
+            Block:
+                Variable(0, Integer) = Call id(1), args( Qubit(0), )
+                Variable(1, Integer) = Store Variable(0, Integer)
+                Call id(2), args( Variable(1, Integer), Pointer, )
+                Return"#]],
+    );
+}
+
+#[test]
+fn call_to_custom_intrinsic_with_result_output_writes_output_to_result_argument() {
+    let program = get_rir_program(indoc! {"
+        namespace Test {
+            operation Detect(q : Qubit, threshold : Double) : Result {
+                body intrinsic;
+            }
+            @EntryPoint()
+            operation Main() : Result {
+                use q = Qubit();
+                Detect(q, 0.5)
+            }
+        }
+    "});
+
+    let op_callable_id = CallableId(1);
+    assert_callable(
+        &program,
+        op_callable_id,
+        &expect![[r#"
+            Callable:
+                name: Detect
+                call_type: Measurement
+                input_type:
+                    [0]: Qubit
+                    [1]: Double
+                    [2]: Result
+                output_type: <VOID>
+                body: <NONE>"#]],
+    );
+    assert_block_instructions(
+        &program,
+        BlockId(0),
+        &expect![[r#"
+            Block:
+                Call id(1), args( Qubit(0), Double(0.5), Result(0), )
+                Call id(2), args( Result(0), Pointer, )
+                Return"#]],
+    );
+}
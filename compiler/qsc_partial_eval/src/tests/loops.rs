@@ -452,3 +452,90 @@ fn mutable_double_updated_in_loop() {
                 Jump(9)"#]],
     );
 }
+
+#[test]
+fn while_loop_with_dynamic_condition_is_emitted_as_loop() {
+    let program = get_rir_program(indoc! {
+        r#"
+        namespace Test {
+            @EntryPoint()
+            operation Main() : Unit {
+                use q = Qubit();
+                mutable done = false;
+                while not done {
+                    H(q);
+                    set done = MResetZ(q) == Zero;
+                }
+            }
+        }
+        "#,
+    });
+
+    assert_blocks(
+        &program,
+        &expect![[r#"
+        Blocks:
+        Block 0:Block:
+            Variable(0, Boolean) = Store Bool(false)
+            Jump(1)
+        Block 1:Block:
+            Variable(1, Boolean) = LogicalNot Variable(0, Boolean)
+            Branch Variable(1, Boolean), 3, 2
+        Block 2:Block:
+            Call id(4), args( Integer(0), Pointer, )
+            Return
+        Block 3:Block:
+            Call id(1), args( Qubit(0), )
+            Call id(2), args( Qubit(0), Result(0), )
+            Variable(2, Boolean) = Call id(3), args( Result(0), )
+            Variable(3, Boolean) = Icmp Eq, Variable(2, Boolean), Bool(false)
+            Variable(0, Boolean) = Store Variable(3, Boolean)
+            Jump(1)"#]],
+    );
+}
+
+#[test]
+fn repeat_until_loop_with_dynamic_condition_carries_counter_across_iterations() {
+    let program = get_rir_program(indoc! {
+        r#"
+        namespace Test {
+            @EntryPoint()
+            operation Main() : Int {
+                use q = Qubit();
+                mutable attempts = 0;
+                repeat {
+                    H(q);
+                    set attempts += 1;
+                } until MResetZ(q) == One;
+                attempts
+            }
+        }
+        "#,
+    });
+
+    assert_blocks(
+        &program,
+        &expect![[r#"
+        Blocks:
+        Block 0:Block:
+            Variable(0, Integer) = Store Integer(0)
+            Variable(1, Boolean) = Store Bool(true)
+            Jump(1)
+        Block 1:Block:
+            Branch Variable(1, Boolean), 3, 2
+        Block 2:Block:
+            Variable(6, Integer) = Store Variable(0, Integer)
+            Call id(4), args( Variable(6, Integer), Pointer, )
+            Return
+        Block 3:Block:
+            Call id(1), args( Qubit(0), )
+            Variable(2, Integer) = Add Variable(0, Integer), Integer(1)
+            Variable(0, Integer) = Store Variable(2, Integer)
+            Call id(2), args( Qubit(0), Result(0), )
+            Variable(3, Boolean) = Call id(3), args( Result(0), )
+            Variable(4, Boolean) = Store Variable(3, Boolean)
+            Variable(5, Boolean) = LogicalNot Variable(4, Boolean)
+            Variable(1, Boolean) = Store Variable(5, Boolean)
+            Jump(1)"#]],
+    );
+}
@@ -0,0 +1,106 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#![allow(clippy::needless_raw_string_hashes)]
+
+use super::{assert_blocks, get_rir_program_with_config};
+use crate::{Measurements, PartialEvalConfig};
+use expect_test::{expect, Expect};
+use indoc::indoc;
+
+const MEASUREMENTS: &str = indoc! {r#"
+    namespace Test {
+        @EntryPoint()
+        operation Main() : (Result, Result) {
+            use (q0, q1) = (Qubit(), Qubit());
+            (M(q0), MResetZ(q1))
+        }
+    }
+"#};
+
+fn check(measurements: Measurements, expected_callables: &Expect, expected_blocks: &Expect) {
+    let program = get_rir_program_with_config(
+        MEASUREMENTS,
+        PartialEvalConfig {
+            measurements,
+            ..PartialEvalConfig::default()
+        },
+    );
+    expected_callables.assert_eq(
+        &program
+            .callables
+            .iter()
+            .map(|(id, callable)| format!("{}: {}", id.0, callable.name))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    );
+    assert_blocks(&program, expected_blocks);
+}
+
+#[test]
+fn measurements_are_emitted_as_written_by_default() {
+    check(
+        Measurements::MAndMResetZ,
+        &expect![[r#"
+            0: main
+            1: __quantum__qis__m__body
+            2: __quantum__qis__mresetz__body
+            3: __quantum__rt__tuple_record_output
+            4: __quantum__rt__result_record_output"#]],
+        &expect![[r#"
+            Blocks:
+            Block 0:Block:
+                Call id(1), args( Qubit(0), Result(0), )
+                Call id(2), args( Qubit(1), Result(1), )
+                Call id(3), args( Integer(2), Pointer, )
+                Call id(4), args( Result(0), Pointer, )
+                Call id(4), args( Result(1), Pointer, )
+                Return"#]],
+    );
+}
+
+#[test]
+fn measurement_with_reset_is_followed_by_reset_on_target_with_only_m() {
+    check(
+        Measurements::M,
+        &expect![[r#"
+            0: main
+            1: __quantum__qis__m__body
+            2: __quantum__qis__reset__body
+            3: __quantum__rt__tuple_record_output
+            4: __quantum__rt__result_record_output"#]],
+        &expect![[r#"
+            Blocks:
+            Block 0:Block:
+                Call id(1), args( Qubit(0), Result(0), )
+                Call id(1), args( Qubit(1), Result(1), )
+                Call id(2), args( Qubit(1), )
+                Call id(3), args( Integer(2), Pointer, )
+                Call id(4), args( Result(0), Pointer, )
+                Call id(4), args( Result(1), Pointer, )
+                Return"#]],
+    );
+}
+
+#[test]
+fn measurements_are_emitted_as_mz_on_target_with_only_mz() {
+    check(
+        Measurements::Mz,
+        &expect![[r#"
+            0: main
+            1: __quantum__qis__mz__body
+            2: __quantum__qis__reset__body
+            3: __quantum__rt__tuple_record_output
+            4: __quantum__rt__result_record_output"#]],
+        &expect![[r#"
+            Blocks:
+            Block 0:Block:
+                Call id(1), args( Qubit(0), Result(0), )
+                Call id(1), args( Qubit(1), Result(1), )
+                Call id(2), args( Qubit(1), )
+                Call id(3), args( Integer(2), Pointer, )
+                Call id(4), args( Result(0), Pointer, )
+                Call id(4), args( Result(1), Pointer, )
+                Return"#]],
+    );
+}
@@ -0,0 +1,203 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#![allow(clippy::needless_raw_string_hashes)]
+
+use super::{assert_blocks, get_rir_program_and_warnings_with_config};
+use crate::{Messages, PartialEvalConfig};
+use expect_test::expect;
+use indoc::indoc;
+
+#[test]
+fn messages_are_dropped_with_a_warning_by_default() {
+    let (program, warnings) = get_rir_program_and_warnings_with_config(
+        indoc! {r#"
+            namespace Test {
+                @EntryPoint()
+                operation Main() : Result {
+                    use q = Qubit();
+                    Message("hello");
+                    MResetZ(q)
+                }
+            }
+        "#},
+        PartialEvalConfig::default(),
+    );
+    assert_blocks(
+        &program,
+        &expect![[r#"
+        Blocks:
+        Block 0:Block:
+            Call id(1), args( Qubit(0), Result(0), )
+            Call id(2), args( Result(0), Pointer, )
+            Return"#]],
+    );
+    expect![[r#"
+        [
+            MessageDropped(
+                PackageSpan {
+                    package: PackageId(
+                        2,
+                    ),
+                    span: Span {
+                        lo: 101,
+                        hi: 117,
+                    },
+                },
+            ),
+        ]
+    "#]]
+    .assert_debug_eq(&warnings);
+}
+
+#[test]
+fn message_in_loop_is_dropped_with_a_single_warning() {
+    let (_, warnings) = get_rir_program_and_warnings_with_config(
+        indoc! {r#"
+            namespace Test {
+                @EntryPoint()
+                operation Main() : Unit {
+                    use q = Qubit();
+                    for i in 0..2 {
+                        Message($"iteration {i}");
+                        X(q);
+                    }
+                }
+            }
+        "#},
+        PartialEvalConfig::default(),
+    );
+    expect![[r#"
+        [
+            MessageDropped(
+                PackageSpan {
+                    package: PackageId(
+                        2,
+                    ),
+                    span: Span {
+                        lo: 127,
+                        hi: 152,
+                    },
+                },
+            ),
+        ]
+    "#]]
+    .assert_debug_eq(&warnings);
+}
+
+#[test]
+fn messages_are_recorded_when_configured() {
+    let (program, warnings) = get_rir_program_and_warnings_with_config(
+        indoc! {r#"
+            namespace Test {
+                @EntryPoint()
+                operation Main() : Result {
+                    use q = Qubit();
+                    Message("before");
+                    H(q);
+                    Message($"after {1 + 1} gates");
+                    MResetZ(q)
+                }
+            }
+        "#},
+        PartialEvalConfig {
+            messages: Messages::Record,
+            ..PartialEvalConfig::default()
+        },
+    );
+    assert_blocks(
+        &program,
+        &expect![[r#"
+        Blocks:
+        Block 0:Block:
+            Call id(1), args( Tag(0), )
+            Call id(2), args( Qubit(0), )
+            Call id(1), args( Tag(1), )
+            Call id(3), args( Qubit(0), Result(0), )
+            Call id(4), args( Result(0), Pointer, )
+            Return"#]],
+    );
+    expect!["before, after 2 gates"].assert_eq(&program.tags.join(", "));
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn message_in_dynamic_branch_is_recorded_in_branch_block() {
+    let (program, _) = get_rir_program_and_warnings_with_config(
+        indoc! {r#"
+            namespace Test {
+                @EntryPoint()
+                operation Main() : Result {
+                    use q = Qubit();
+                    let r = MResetZ(q);
+                    if r == One {
+                        Message("one");
+                    }
+                    r
+                }
+            }
+        "#},
+        PartialEvalConfig {
+            messages: Messages::Record,
+            ..PartialEvalConfig::default()
+        },
+    );
+    assert_blocks(
+        &program,
+        &expect![[r#"
+        Blocks:
+        Block 0:Block:
+            Call id(1), args( Qubit(0), Result(0), )
+            Variable(0, Boolean) = Call id(2), args( Result(0), )
+            Variable(1, Boolean) = Store Variable(0, Boolean)
+            Branch Variable(1, Boolean), 2, 1
+        Block 1:Block:
+            Call id(4), args( Result(0), Pointer, )
+            Return
+        Block 2:Block:
+            Call id(3), args( Tag(0), )
+            Jump(1)"#]],
+    );
+}
+
+#[test]
+fn message_from_operation_called_with_dynamic_argument_is_recorded() {
+    let (program, _) = get_rir_program_and_warnings_with_config(
+        indoc! {r#"
+            namespace Test {
+                operation Report(r : Result) : Unit {
+                    Message("measured");
+                    if r == One {
+                        Message("one");
+                    }
+                }
+                @EntryPoint()
+                operation Main() : Unit {
+                    use q = Qubit();
+                    Report(MResetZ(q));
+                }
+            }
+        "#},
+        PartialEvalConfig {
+            messages: Messages::Record,
+            ..PartialEvalConfig::default()
+        },
+    );
+    assert_blocks(
+        &program,
+        &expect![[r#"
+        Blocks:
+        Block 0:Block:
+            Call id(1), args( Qubit(0), Result(0), )
+            Call id(2), args( Tag(0), )
+            Variable(0, Boolean) = Call id(3), args( Result(0), )
+            Variable(1, Boolean) = Store Variable(0, Boolean)
+            Branch Variable(1, Boolean), 2, 1
+        Block 1:Block:
+            Call id(4), args( Integer(0), Pointer, )
+            Return
+        Block 2:Block:
+            Call id(2), args( Tag(1), )
+            Jump(1)"#]],
+    );
+}
@@ -600,11 +600,11 @@ fn evaluation_error_within_stdlib_yield_correct_package_span() {
             @EntryPoint()
             operation Main() : Result[] {
                 use qs = Qubit[1];
-                let rs = ForEach(MResetZ, qs);
+                let rs = DrawMany(MResetZ, 1, qs[0]);
                 return rs;
             }
         }
         "#,
     });
-    assert_error(&error, &expect!["UnexpectedDynamicValue(PackageSpan { package: PackageId(1), span: Span { lo: 13910, hi: 13925 } })"]);
+    assert_error(&error, &expect!["UnexpectedDynamicValue(PackageSpan { package: PackageId(1), span: Span { lo: 7726, hi: 7735 } })"]);
 }
@@ -1,7 +1,11 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
-use super::{assert_error, get_partial_evaluation_error, get_rir_program};
+use super::{
+    assert_blocks, assert_error, get_partial_evaluation_error, get_rir_program,
+    get_rir_program_with_config,
+};
+use crate::{OutputRecording, PartialEvalConfig};
 use expect_test::expect;
 use indoc::indoc;
 
@@ -79,9 +83,13 @@ fn output_recording_for_tuple_of_different_types() {
                     Call id(5), args( Variable(1, Boolean), Pointer, )
                     Return
             config: Config:
-                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset)
+                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset | Delay)
             num_qubits: 1
-            num_results: 1"#]]
+            num_results: 1
+            output_positions:
+                [0]: tuple at []
+                [1]: result at [0]
+                [2]: bool at [1]"#]]
     .assert_eq(&program.to_string());
 }
 
@@ -165,9 +173,17 @@ fn output_recording_for_nested_tuples() {
                     Call id(5), args( Variable(3, Boolean), Pointer, )
                     Return
             config: Config:
-                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset)
+                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset | Delay)
             num_qubits: 1
-            num_results: 1"#]]
+            num_results: 1
+            output_positions:
+                [0]: tuple at []
+                [1]: result at [0]
+                [2]: tuple at [1]
+                [3]: bool at [1, 0]
+                [4]: result at [1, 1]
+                [5]: tuple at [2]
+                [6]: bool at [2, 0]"#]]
     .assert_eq(&program.to_string());
 }
 
@@ -259,9 +275,15 @@ fn output_recording_for_tuple_of_arrays() {
                     Call id(6), args( Variable(3, Boolean), Pointer, )
                     Return
             config: Config:
-                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset)
+                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset | Delay)
             num_qubits: 1
-            num_results: 1"#]]
+            num_results: 1
+            output_positions:
+                [0]: tuple at []
+                [1]: result at [0]
+                [2]: array at [1]
+                [3]: bool at [1, 0]
+                [4]: bool at [1, 1]"#]]
     .assert_eq(&program.to_string());
 }
 
@@ -353,9 +375,17 @@ fn output_recording_for_array_of_tuples() {
                     Call id(6), args( Variable(3, Boolean), Pointer, )
                     Return
             config: Config:
-                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset)
+                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset | Delay)
             num_qubits: 1
-            num_results: 1"#]]
+            num_results: 1
+            output_positions:
+                [0]: array at []
+                [1]: tuple at [0]
+                [2]: result at [0, 0]
+                [3]: bool at [0, 1]
+                [4]: tuple at [1]
+                [5]: result at [1, 0]
+                [6]: bool at [1, 1]"#]]
     .assert_eq(&program.to_string());
 }
 
@@ -395,9 +425,11 @@ fn output_recording_for_literal_bool() {
                     Call id(1), args( Bool(true), Pointer, )
                     Return
             config: Config:
-                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset)
+                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset | Delay)
             num_qubits: 0
-            num_results: 0"#]]
+            num_results: 0
+            output_positions:
+                [0]: bool at []"#]]
     .assert_eq(&program.to_string());
 }
 
@@ -437,9 +469,11 @@ fn output_recording_for_literal_double() {
                     Call id(1), args( Double(42.1), Pointer, )
                     Return
             config: Config:
-                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset)
+                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset | Delay)
             num_qubits: 0
-            num_results: 0"#]]
+            num_results: 0
+            output_positions:
+                [0]: double at []"#]]
     .assert_eq(&program.to_string());
 }
 
@@ -479,9 +513,11 @@ fn output_recording_for_literal_int() {
                     Call id(1), args( Integer(42), Pointer, )
                     Return
             config: Config:
-                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset)
+                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset | Delay)
             num_qubits: 0
-            num_results: 0"#]]
+            num_results: 0
+            output_positions:
+                [0]: int at []"#]]
     .assert_eq(&program.to_string());
 }
 
@@ -550,9 +586,13 @@ fn output_recording_for_mix_of_literal_and_variable() {
                     Call id(4), args( Bool(true), Pointer, )
                     Return
             config: Config:
-                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset)
+                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset | Delay)
             num_qubits: 1
-            num_results: 1"#]]
+            num_results: 1
+            output_positions:
+                [0]: tuple at []
+                [1]: result at [0]
+                [2]: bool at [1]"#]]
     .assert_eq(&program.to_string());
 }
 
@@ -633,3 +673,400 @@ fn output_recording_fails_with_result_literal_in_tuple() {
         &expect!["OutputResultLiteral(PackageSpan { package: PackageId(2), span: Span { lo: 50, hi: 54 } })"],
     );
 }
+
+#[test]
+fn output_recording_labels_items_after_output_name() {
+    let program = get_rir_program(indoc! {
+        r#"
+        namespace Test {
+            @EntryPoint()
+            @OutputName("syndrome")
+            operation Main() : (Result, Result)[] {
+                use qs = Qubit[2];
+                let r0 = QIR.Intrinsic.__quantum__qis__mresetz__body(qs[0]);
+                let r1 = QIR.Intrinsic.__quantum__qis__mresetz__body(qs[1]);
+                [(r0, r1)]
+            }
+        }
+        "#,
+    });
+
+    expect![[r#"
+        Program:
+            entry: 0
+            callables:
+                Callable 0: Callable:
+                    name: main
+                    call_type: Regular
+                    input_type: <VOID>
+                    output_type: <VOID>
+                    body: 0
+                Callable 1: Callable:
+                    name: __quantum__qis__mresetz__body
+                    call_type: Measurement
+                    input_type:
+                        [0]: Qubit
+                        [1]: Result
+                    output_type: <VOID>
+                    body: <NONE>
+                Callable 2: Callable:
+                    name: __quantum__rt__array_record_output
+                    call_type: OutputRecording
+                    input_type:
+                        [0]: Integer
+                        [1]: Pointer
+                    output_type: <VOID>
+                    body: <NONE>
+                Callable 3: Callable:
+                    name: __quantum__rt__tuple_record_output
+                    call_type: OutputRecording
+                    input_type:
+                        [0]: Integer
+                        [1]: Pointer
+                    output_type: <VOID>
+                    body: <NONE>
+                Callable 4: Callable:
+                    name: __quantum__rt__result_record_output
+                    call_type: OutputRecording
+                    input_type:
+                        [0]: Result
+                        [1]: Pointer
+                    output_type: <VOID>
+                    body: <NONE>
+            blocks:
+                Block 0: Block:
+                    Variable(0, Integer) = Store Integer(0)
+                    Variable(0, Integer) = Store Integer(1)
+                    Variable(0, Integer) = Store Integer(2)
+                    Call id(1), args( Qubit(0), Result(0), )
+                    Call id(1), args( Qubit(1), Result(1), )
+                    Variable(1, Integer) = Store Integer(0)
+                    Variable(1, Integer) = Store Integer(1)
+                    Variable(1, Integer) = Store Integer(2)
+                    Call id(2), args( Integer(1), Tag(0), )
+                    Call id(3), args( Integer(2), Tag(1), )
+                    Call id(4), args( Result(0), Tag(2), )
+                    Call id(4), args( Result(1), Tag(3), )
+                    Return
+            config: Config:
+                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset | Delay)
+            num_qubits: 2
+            num_results: 2
+            tags:
+                [0]: "syndrome"
+                [1]: "syndrome[0]"
+                [2]: "syndrome[0].0"
+                [3]: "syndrome[0].1"
+            output_positions:
+                [0]: array at []
+                [1]: tuple at [0]
+                [2]: result at [0, 0]
+                [3]: result at [0, 1]"#]]
+    .assert_eq(&program.to_string());
+}
+
+#[test]
+fn output_recording_labels_tuple_items_with_output_names() {
+    let program = get_rir_program(indoc! {
+        r#"
+        namespace Test {
+            @EntryPoint()
+            @OutputName("syndrome", "flag")
+            operation Main() : (Result[], Bool) {
+                use qs = Qubit[2];
+                let rs = [
+                    QIR.Intrinsic.__quantum__qis__mresetz__body(qs[0]),
+                    QIR.Intrinsic.__quantum__qis__mresetz__body(qs[1])
+                ];
+                (rs, true)
+            }
+        }
+        "#,
+    });
+
+    expect![[r#"
+        Program:
+            entry: 0
+            callables:
+                Callable 0: Callable:
+                    name: main
+                    call_type: Regular
+                    input_type: <VOID>
+                    output_type: <VOID>
+                    body: 0
+                Callable 1: Callable:
+                    name: __quantum__qis__mresetz__body
+                    call_type: Measurement
+                    input_type:
+                        [0]: Qubit
+                        [1]: Result
+                    output_type: <VOID>
+                    body: <NONE>
+                Callable 2: Callable:
+                    name: __quantum__rt__tuple_record_output
+                    call_type: OutputRecording
+                    input_type:
+                        [0]: Integer
+                        [1]: Pointer
+                    output_type: <VOID>
+                    body: <NONE>
+                Callable 3: Callable:
+                    name: __quantum__rt__array_record_output
+                    call_type: OutputRecording
+                    input_type:
+                        [0]: Integer
+                        [1]: Pointer
+                    output_type: <VOID>
+                    body: <NONE>
+                Callable 4: Callable:
+                    name: __quantum__rt__result_record_output
+                    call_type: OutputRecording
+                    input_type:
+                        [0]: Result
+                        [1]: Pointer
+                    output_type: <VOID>
+                    body: <NONE>
+                Callable 5: Callable:
+                    name: __quantum__rt__bool_record_output
+                    call_type: OutputRecording
+                    input_type:
+                        [0]: Boolean
+                        [1]: Pointer
+                    output_type: <VOID>
+                    body: <NONE>
+            blocks:
+                Block 0: Block:
+                    Variable(0, Integer) = Store Integer(0)
+                    Variable(0, Integer) = Store Integer(1)
+                    Variable(0, Integer) = Store Integer(2)
+                    Call id(1), args( Qubit(0), Result(0), )
+                    Call id(1), args( Qubit(1), Result(1), )
+                    Variable(1, Integer) = Store Integer(0)
+                    Variable(1, Integer) = Store Integer(1)
+                    Variable(1, Integer) = Store Integer(2)
+                    Call id(2), args( Integer(2), Pointer, )
+                    Call id(3), args( Integer(2), Tag(0), )
+                    Call id(4), args( Result(0), Tag(1), )
+                    Call id(4), args( Result(1), Tag(2), )
+                    Call id(5), args( Bool(true), Tag(3), )
+                    Return
+            config: Config:
+                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset | Delay)
+            num_qubits: 2
+            num_results: 2
+            tags:
+                [0]: "syndrome"
+                [1]: "syndrome[0]"
+                [2]: "syndrome[1]"
+                [3]: "flag"
+            output_positions:
+                [0]: tuple at []
+                [1]: array at [0]
+                [2]: result at [0, 0]
+                [3]: result at [0, 1]
+                [4]: bool at [1]"#]]
+    .assert_eq(&program.to_string());
+}
+
+#[test]
+fn labeled_output_recording_labels_every_value_after_its_position() {
+    let program = get_rir_program_with_config(
+        indoc! {
+            r#"
+            namespace Test {
+                @EntryPoint()
+                operation Main() : (Int, Result[]) {
+                    use qs = Qubit[2];
+                    let rs = [
+                        QIR.Intrinsic.__quantum__qis__mresetz__body(qs[0]),
+                        QIR.Intrinsic.__quantum__qis__mresetz__body(qs[1])
+                    ];
+                    (3, rs)
+                }
+            }
+            "#,
+        },
+        PartialEvalConfig {
+            output_recording: OutputRecording::Labeled,
+            ..PartialEvalConfig::default()
+        },
+    );
+
+    assert_blocks(
+        &program,
+        &expect![[r#"
+        Blocks:
+        Block 0:Block:
+            Variable(0, Integer) = Store Integer(0)
+            Variable(0, Integer) = Store Integer(1)
+            Variable(0, Integer) = Store Integer(2)
+            Call id(1), args( Qubit(0), Result(0), )
+            Call id(1), args( Qubit(1), Result(1), )
+            Variable(1, Integer) = Store Integer(0)
+            Variable(1, Integer) = Store Integer(1)
+            Variable(1, Integer) = Store Integer(2)
+            Call id(2), args( Integer(2), Tag(0), )
+            Call id(3), args( Integer(3), Tag(1), )
+            Call id(4), args( Integer(2), Tag(2), )
+            Call id(5), args( Result(0), Tag(3), )
+            Call id(5), args( Result(1), Tag(4), )
+            Return"#]],
+    );
+    expect![[r#"
+        [
+            "output",
+            "output.0",
+            "output.1",
+            "output.1[0]",
+            "output.1[1]",
+        ]
+    "#]]
+    .assert_debug_eq(&program.tags);
+}
+
+#[test]
+fn labeled_output_recording_keeps_output_names() {
+    let program = get_rir_program_with_config(
+        indoc! {
+            r#"
+            namespace Test {
+                @EntryPoint()
+                @OutputName("count", "flag")
+                operation Main() : (Int, Bool) {
+                    (3, true)
+                }
+            }
+            "#,
+        },
+        PartialEvalConfig {
+            output_recording: OutputRecording::Labeled,
+            ..PartialEvalConfig::default()
+        },
+    );
+
+    expect![[r#"
+        [
+            "output",
+            "count",
+            "flag",
+        ]
+    "#]]
+    .assert_debug_eq(&program.tags);
+}
+
+#[test]
+fn output_recording_none_records_nothing() {
+    let program = get_rir_program_with_config(
+        indoc! {
+            r#"
+            namespace Test {
+                @EntryPoint()
+                operation Main() : Result[] {
+                    use qs = Qubit[2];
+                    [
+                        QIR.Intrinsic.__quantum__qis__mresetz__body(qs[0]),
+                        QIR.Intrinsic.__quantum__qis__mresetz__body(qs[1])
+                    ]
+                }
+            }
+            "#,
+        },
+        PartialEvalConfig {
+            output_recording: OutputRecording::None,
+            ..PartialEvalConfig::default()
+        },
+    );
+
+    expect![[r#"
+        Program:
+            entry: 0
+            callables:
+                Callable 0: Callable:
+                    name: main
+                    call_type: Regular
+                    input_type: <VOID>
+                    output_type: <VOID>
+                    body: 0
+                Callable 1: Callable:
+                    name: __quantum__qis__mresetz__body
+                    call_type: Measurement
+                    input_type:
+                        [0]: Qubit
+                        [1]: Result
+                    output_type: <VOID>
+                    body: <NONE>
+            blocks:
+                Block 0: Block:
+                    Variable(0, Integer) = Store Integer(0)
+                    Variable(0, Integer) = Store Integer(1)
+                    Variable(0, Integer) = Store Integer(2)
+                    Call id(1), args( Qubit(0), Result(0), )
+                    Call id(1), args( Qubit(1), Result(1), )
+                    Variable(1, Integer) = Store Integer(0)
+                    Variable(1, Integer) = Store Integer(1)
+                    Variable(1, Integer) = Store Integer(2)
+                    Return
+            config: Config:
+                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset | Delay)
+            num_qubits: 2
+            num_results: 2"#]].assert_eq(&program.to_string());
+}
+
+#[test]
+fn output_positions_follow_structure_of_return_value() {
+    let program = get_rir_program(indoc! {
+        r#"
+        namespace Test {
+            @EntryPoint()
+            operation Main() : (Result[], (Bool, Double)) {
+                use qs = Qubit[2];
+                let rs = [
+                    QIR.Intrinsic.__quantum__qis__mresetz__body(qs[0]),
+                    QIR.Intrinsic.__quantum__qis__mresetz__body(qs[1])
+                ];
+                (rs, (true, 1.5))
+            }
+        }
+        "#,
+    });
+
+    let positions = program
+        .output_positions
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    expect![[r#"
+        [
+            "tuple at []",
+            "array at [0]",
+            "result at [0, 0]",
+            "result at [0, 1]",
+            "tuple at [1]",
+            "bool at [1, 0]",
+            "double at [1, 1]",
+        ]
+    "#]]
+    .assert_debug_eq(&positions);
+}
+
+#[test]
+fn no_output_positions_without_output_recording() {
+    let program = get_rir_program_with_config(
+        indoc! {
+            r#"
+            namespace Test {
+                @EntryPoint()
+                operation Main() : (Int, Bool) {
+                    (3, true)
+                }
+            }
+            "#,
+        },
+        PartialEvalConfig {
+            output_recording: OutputRecording::None,
+            ..PartialEvalConfig::default()
+        },
+    );
+
+    assert!(program.output_positions.is_empty());
+}
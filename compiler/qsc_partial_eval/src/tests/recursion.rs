@@ -0,0 +1,148 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#![allow(clippy::needless_raw_string_hashes)]
+
+use super::{assert_error, get_partial_evaluation_error_with_config, get_rir_program};
+use crate::PartialEvalConfig;
+use expect_test::expect;
+use indoc::indoc;
+use qsc_rir::rir::Instruction;
+
+#[test]
+fn recursion_on_dynamic_value_exceeds_recursion_limit() {
+    let error = get_partial_evaluation_error_with_config(
+        indoc! {r#"
+        namespace Test {
+            import Std.Convert.ResultAsBool;
+            operation RepeatUntilZero(q : Qubit) : Unit {
+                H(q);
+                if ResultAsBool(M(q)) {
+                    RepeatUntilZero(q);
+                }
+            }
+            @EntryPoint()
+            operation Main() : Result {
+                use q = Qubit();
+                RepeatUntilZero(q);
+                MResetZ(q)
+            }
+        }
+    "#},
+        PartialEvalConfig {
+            recursion_limit: Some(100),
+            ..PartialEvalConfig::default()
+        },
+    );
+    assert_error(
+        &error,
+        &expect![[
+            r#"RecursionLimit("RepeatUntilZero -> RepeatUntilZero", 100, PackageSpan { package: PackageId(2), span: Span { lo: 163, hi: 181 } })"#
+        ]],
+    );
+}
+
+#[test]
+fn mutual_recursion_error_names_cycle() {
+    let error = get_partial_evaluation_error_with_config(
+        indoc! {r#"
+        namespace Test {
+            import Std.Convert.ResultAsBool;
+            operation Ping(q : Qubit) : Unit {
+                X(q);
+                if ResultAsBool(M(q)) {
+                    Pong(q);
+                }
+            }
+            operation Pong(q : Qubit) : Unit {
+                Y(q);
+                if ResultAsBool(M(q)) {
+                    Ping(q);
+                }
+            }
+            @EntryPoint()
+            operation Main() : Result {
+                use q = Qubit();
+                Ping(q);
+                MResetZ(q)
+            }
+        }
+    "#},
+        PartialEvalConfig {
+            recursion_limit: Some(2),
+            ..PartialEvalConfig::default()
+        },
+    );
+    assert_error(
+        &error,
+        &expect![[
+            r#"RecursionLimit("Ping -> Pong -> Ping", 2, PackageSpan { package: PackageId(2), span: Span { lo: 274, hi: 281 } })"#
+        ]],
+    );
+}
+
+#[test]
+fn recursion_limit_of_one_rejects_any_recursive_call() {
+    let error = get_partial_evaluation_error_with_config(
+        indoc! {r#"
+        namespace Test {
+            import Std.Convert.ResultAsBool;
+            operation Apply(q : Qubit) : Unit {
+                H(q);
+                Recurse(q);
+            }
+            operation Recurse(q : Qubit) : Unit {
+                if ResultAsBool(M(q)) {
+                    Recurse(q);
+                }
+            }
+            @EntryPoint()
+            operation Main() : Result {
+                use q = Qubit();
+                Apply(q);
+                MResetZ(q)
+            }
+        }
+    "#},
+        PartialEvalConfig {
+            recursion_limit: Some(1),
+            ..PartialEvalConfig::default()
+        },
+    );
+    assert_error(
+        &error,
+        &expect![[
+            r#"RecursionLimit("Recurse -> Recurse", 1, PackageSpan { package: PackageId(2), span: Span { lo: 221, hi: 231 } })"#
+        ]],
+    );
+}
+
+#[test]
+fn deep_recursion_bounded_by_static_value_is_not_limited_by_default() {
+    let program = get_rir_program(indoc! {r#"
+        namespace Test {
+            operation ApplyXTimes(q : Qubit, n : Int) : Unit {
+                if n > 0 {
+                    X(q);
+                    ApplyXTimes(q, n - 1);
+                }
+            }
+            @EntryPoint()
+            operation Main() : Result {
+                use q = Qubit();
+                ApplyXTimes(q, 200);
+                MResetZ(q)
+            }
+        }
+    "#});
+    let x_calls = program
+        .blocks
+        .values()
+        .flat_map(|block| &block.0)
+        .filter(|instr| {
+            matches!(instr, Instruction::Call(id, ..)
+                if program.get_callable(*id).name == "__quantum__qis__x__body")
+        })
+        .count();
+    assert_eq!(x_calls, 200);
+}
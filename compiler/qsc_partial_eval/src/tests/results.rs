@@ -1,7 +1,10 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
-use super::{assert_block_instructions, assert_callable, get_rir_program};
+use super::{
+    assert_block_instructions, assert_callable, assert_error, get_partial_evaluation_error,
+    get_rir_program,
+};
 use expect_test::expect;
 use indoc::indoc;
 use qsc_rir::rir::{BlockId, CallableId};
@@ -336,3 +339,22 @@ fn result_ids_are_correct_for_measuring_multiple_qubits() {
     assert_eq!(program.num_qubits, 3);
     assert_eq!(program.num_results, 3);
 }
+
+#[test]
+fn checking_result_for_loss_produces_error() {
+    let error = get_partial_evaluation_error(indoc! {r#"
+        namespace Test {
+            @EntryPoint()
+            operation Main() : Bool {
+                use q = Qubit();
+                IsLossResult(MResetZ(q))
+            }
+        }
+    "#});
+    assert_error(
+        &error,
+        &expect![[
+            r#"UnsupportedLossCheck(PackageSpan { package: PackageId(2), span: Span { lo: 99, hi: 111 } })"#
+        ]],
+    );
+}
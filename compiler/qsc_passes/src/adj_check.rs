@@ -0,0 +1,297 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use miette::Diagnostic;
+use qsc_data_structures::span::Span;
+use qsc_frontend::compile::PackageStore;
+use qsc_hir::{
+    hir::{
+        Attr, Block, CallableDecl, CallableKind, Expr, ExprKind, Functor, ItemId, ItemKind,
+        LocalItemId, Package, PackageId, Res, SpecBody, SpecDecl, SpecGen, Stmt,
+    },
+    ty::{Prim, Ty},
+    visit::{self, Visitor},
+};
+use rustc_hash::FxHashSet;
+use thiserror::Error;
+
+#[derive(Clone, Debug, Diagnostic, Error)]
+pub enum Error {
+    #[error("cannot generate adjoint of a statement that measures or resets a qubit")]
+    #[diagnostic(help("measurements and resets are not reversible, so they cannot be used in an operation with a compiler-generated adjoint specialization; provide an explicit adjoint specialization or move the measurement out of the operation"))]
+    #[diagnostic(code("Qsc.AdjCheck.Measurement"))]
+    Measurement(#[label("this statement measures or resets a qubit")] Span),
+
+    #[error(
+        "cannot generate adjoint of a statement that updates a mutable variable based on a Result"
+    )]
+    #[diagnostic(help("the generated adjoint cannot undo an update that depends on a Result; provide an explicit adjoint specialization or compute the value outside of the operation"))]
+    #[diagnostic(code("Qsc.AdjCheck.MutableConditionalOnResult"))]
+    MutableConditionalOnResult(
+        #[label("this conditional on a Result updates a mutable variable")] Span,
+    ),
+}
+
+/// The QIR intrinsics that measure or reset qubits, which are implemented by the backend rather than marked with an
+/// attribute.
+const QIR_MEASUREMENT_INTRINSICS: [&str; 3] = [
+    "__quantum__qis__m__body",
+    "__quantum__qis__mresetz__body",
+    "__quantum__qis__reset__body",
+];
+
+/// The callables that measure or reset qubits without supporting the adjoint functor. These are the intrinsics with
+/// the `Measurement` or `Reset` attribute, the QIR measurement and reset intrinsics, and the operations that call
+/// any of them, such as `M` and `Reset`, unless they are adjointable themselves.
+#[derive(Default)]
+pub(super) struct Measurements(FxHashSet<ItemId>);
+
+impl Measurements {
+    pub(super) fn new(store: &PackageStore) -> Self {
+        let mut measurements = Self::default();
+        for (package_id, unit) in store {
+            measurements.add_package(Some(package_id), &unit.package);
+        }
+        measurements
+    }
+
+    /// Adds the measurements of a package, whose items are identified relative to `package_id`. Packages must be
+    /// added after the packages they depend on.
+    pub(super) fn add_package(&mut self, package_id: Option<PackageId>, package: &Package) {
+        // Callables can call ones declared after them, so repeat until no more measurements are found.
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for item in package.items.values() {
+                let ItemKind::Callable(decl) = &item.kind else {
+                    continue;
+                };
+                let id = ItemId {
+                    package: package_id,
+                    item: item.id,
+                };
+                if !self.0.contains(&id) && self.is_measurement(package_id, &item.attrs, decl) {
+                    self.0.insert(id);
+                    changed = true;
+                }
+            }
+        }
+    }
+
+    fn is_measurement(
+        &self,
+        package_id: Option<PackageId>,
+        attrs: &[Attr],
+        decl: &CallableDecl,
+    ) -> bool {
+        if decl.kind != CallableKind::Operation {
+            return false;
+        }
+        match &decl.body.body {
+            SpecBody::Gen(SpecGen::Intrinsic) => {
+                attrs.contains(&Attr::Measurement)
+                    || attrs.contains(&Attr::Reset)
+                    || QIR_MEASUREMENT_INTRINSICS.contains(&decl.name.name.as_ref())
+            }
+            SpecBody::Impl(_, block) => {
+                !decl.functors.contains(&Functor::Adj) && {
+                    let mut finder = CallFinder {
+                        measurements: self,
+                        package_id,
+                        found: false,
+                    };
+                    finder.visit_block(block);
+                    finder.found
+                }
+            }
+            SpecBody::Gen(_) => false,
+        }
+    }
+
+    fn contains(&self, package_id: Option<PackageId>, callee: &Expr) -> bool {
+        match &callee.kind {
+            ExprKind::Var(Res::Item(id), _) => self.0.contains(&ItemId {
+                package: id.package.or(package_id),
+                item: id.item,
+            }),
+            _ => false,
+        }
+    }
+}
+
+/// Finds whether a block calls a measurement.
+struct CallFinder<'a> {
+    measurements: &'a Measurements,
+    package_id: Option<PackageId>,
+    found: bool,
+}
+
+impl<'a> Visitor<'a> for CallFinder<'_> {
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        if let ExprKind::Call(callee, _) = &expr.kind {
+            self.found = self.found || self.measurements.contains(self.package_id, callee);
+        }
+        if !self.found {
+            visit::walk_expr(self, expr);
+        }
+    }
+}
+
+/// Checks that the operations whose adjoint is generated by inverting a block only use constructs that can be
+/// inverted, before the specializations are generated. Each statement that measures or resets a qubit, or that
+/// updates a mutable variable in a conditional on a `Result`, is reported at the innermost statement containing it.
+/// Returns the errors along with the callables that failed the check, whose adjoints should not be generated.
+pub(super) fn check_adjointable(
+    package: &Package,
+    measurements: &Measurements,
+) -> (Vec<Error>, FxHashSet<LocalItemId>) {
+    let mut checker = AdjChecker {
+        measurements,
+        stmt: None,
+        reported: None,
+        errors: Vec::new(),
+    };
+    let mut failed = FxHashSet::default();
+    for item in package.items.values() {
+        let ItemKind::Callable(decl) = &item.kind else {
+            continue;
+        };
+        let errors = checker.errors.len();
+        for block in inverted_blocks(decl) {
+            checker.visit_block(block);
+        }
+        if checker.errors.len() > errors {
+            failed.insert(item.id);
+        }
+    }
+    (checker.errors, failed)
+}
+
+/// Gets the blocks of a callable that specialization generation inverts: the body when the adjoint is generated,
+/// and an explicit controlled specialization when the controlled adjoint is generated from it.
+fn inverted_blocks(decl: &CallableDecl) -> Vec<&Block> {
+    if decl.kind != CallableKind::Operation || !decl.functors.contains(&Functor::Adj) {
+        return Vec::new();
+    }
+    let is_generated = |spec: &Option<SpecDecl>, gens: &[SpecGen]| match spec {
+        None => true,
+        Some(spec) => matches!(&spec.body, SpecBody::Gen(gen) if gens.contains(gen)),
+    };
+
+    let mut blocks = Vec::new();
+    let adj_is_inverted = is_generated(&decl.adj, &[SpecGen::Auto, SpecGen::Invert]);
+    if let (true, SpecBody::Impl(_, block)) = (adj_is_inverted, &decl.body.body) {
+        blocks.push(block);
+    }
+    if let Some(SpecDecl {
+        body: SpecBody::Impl(_, block),
+        ..
+    }) = &decl.ctl
+    {
+        // Without an explicit adjoint, the controlled adjoint is generated by inverting the explicit controlled
+        // specialization, as in specialization generation.
+        let ctl_adj_is_inverted = match &decl.ctl_adj {
+            None
+            | Some(SpecDecl {
+                body: SpecBody::Gen(SpecGen::Auto),
+                ..
+            }) => adj_is_inverted,
+            Some(SpecDecl {
+                body: SpecBody::Gen(SpecGen::Invert),
+                ..
+            }) => true,
+            Some(_) => false,
+        };
+        if ctl_adj_is_inverted {
+            blocks.push(block);
+        }
+    }
+    blocks
+}
+
+struct AdjChecker<'a> {
+    measurements: &'a Measurements,
+    /// The span of the innermost statement being visited.
+    stmt: Option<Span>,
+    /// The span of the last statement reported.
+    reported: Option<Span>,
+    errors: Vec<Error>,
+}
+
+impl AdjChecker<'_> {
+    fn report(&mut self, error: fn(Span) -> Error, span: Span) {
+        let span = self.stmt.unwrap_or(span);
+        if self.reported != Some(span) {
+            self.reported = Some(span);
+            self.errors.push(error(span));
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for AdjChecker<'_> {
+    fn visit_stmt(&mut self, stmt: &'a Stmt) {
+        let outer = self.stmt.replace(stmt.span);
+        visit::walk_stmt(self, stmt);
+        self.stmt = outer;
+    }
+
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        match &expr.kind {
+            ExprKind::Call(callee, _) if self.measurements.contains(None, callee) => {
+                self.report(Error::Measurement, expr.span);
+            }
+            ExprKind::If(cond, body, otherwise)
+                if uses_result(cond)
+                    && (assigns(body) || otherwise.as_deref().is_some_and(assigns)) =>
+            {
+                self.report(Error::MutableConditionalOnResult, expr.span);
+            }
+            _ => {}
+        }
+        visit::walk_expr(self, expr);
+    }
+}
+
+fn uses_result(expr: &Expr) -> bool {
+    let mut finder = ExprFinder {
+        pred: |e: &Expr| e.ty == Ty::Prim(Prim::Result),
+        found: false,
+    };
+    finder.visit_expr(expr);
+    finder.found
+}
+
+fn assigns(expr: &Expr) -> bool {
+    let mut finder = ExprFinder {
+        pred: |e: &Expr| {
+            matches!(
+                e.kind,
+                ExprKind::Assign(..)
+                    | ExprKind::AssignOp(..)
+                    | ExprKind::AssignField(..)
+                    | ExprKind::AssignIndex(..)
+            )
+        },
+        found: false,
+    };
+    finder.visit_expr(expr);
+    finder.found
+}
+
+/// Finds whether an expression or any of its subexpressions satisfies a predicate.
+struct ExprFinder<F> {
+    pred: F,
+    found: bool,
+}
+
+impl<'a, F: Fn(&Expr) -> bool> Visitor<'a> for ExprFinder<F> {
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        self.found = self.found || (self.pred)(expr);
+        if !self.found {
+            visit::walk_expr(self, expr);
+        }
+    }
+}
@@ -0,0 +1,317 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use expect_test::{expect, Expect};
+use indoc::indoc;
+use qsc_data_structures::{language_features::LanguageFeatures, target::TargetCapabilityFlags};
+use qsc_frontend::compile::{self, compile, PackageStore, SourceMap};
+
+use crate::{
+    adj_check::{check_adjointable, Measurements},
+    PackageType, PassContext,
+};
+
+fn compile_with_std(file: &str) -> (PackageStore, compile::CompileUnit) {
+    let mut store = PackageStore::new(compile::core());
+    let std = store.insert(compile::std(&store, TargetCapabilityFlags::all()));
+    let sources = SourceMap::new([("test".into(), file.into())], None);
+    let unit = compile(
+        &store,
+        &[(std, None)],
+        sources,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::default(),
+    );
+    assert!(unit.errors.is_empty(), "{:?}", unit.errors);
+    (store, unit)
+}
+
+fn check(file: &str, expect: &Expect) {
+    let (store, unit) = compile_with_std(file);
+    let mut measurements = Measurements::new(&store);
+    measurements.add_package(None, &unit.package);
+    let (errors, _) = check_adjointable(&unit.package, &measurements);
+    expect.assert_debug_eq(&errors);
+}
+
+fn check_passes(file: &str, expect: &Expect) {
+    let (store, mut unit) = compile_with_std(file);
+    let errors = PassContext::with_store(&store).run_default_passes(
+        &mut unit.package,
+        &mut unit.assigner,
+        store.core(),
+        PackageType::Lib,
+    );
+    expect.assert_debug_eq(&errors);
+}
+
+#[test]
+fn measurement_in_generated_adjoint_is_reported_at_statement() {
+    check(
+        indoc! {"
+        namespace Test {
+            operation Foo(q : Qubit) : Unit is Adj {
+                X(q);
+                let r = M(q);
+                if r == One {
+                    X(q);
+                }
+            }
+        }
+        "},
+        &expect![[r#"
+            [
+                Measurement(
+                    Span {
+                        lo: 84,
+                        hi: 97,
+                    },
+                ),
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn reset_in_generated_adjoint_is_reported() {
+    check(
+        indoc! {"
+        namespace Test {
+            operation Foo(q : Qubit) : Unit is Adj + Ctl {
+                H(q);
+                Reset(q);
+            }
+        }
+        "},
+        &expect![[r#"
+            [
+                Measurement(
+                    Span {
+                        lo: 90,
+                        hi: 99,
+                    },
+                ),
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn measurement_intrinsic_in_generated_adjoint_is_reported() {
+    check(
+        indoc! {"
+        namespace Test {
+            @Measurement()
+            operation MeasureX(q : Qubit) : Result {
+                body intrinsic;
+            }
+            operation Foo(q : Qubit) : Unit is Adj {
+                let r = MeasureX(q);
+            }
+        }
+        "},
+        &expect![[r#"
+            [
+                Measurement(
+                    Span {
+                        lo: 164,
+                        hi: 184,
+                    },
+                ),
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn operation_calling_measurement_in_generated_adjoint_is_reported() {
+    check(
+        indoc! {"
+        namespace Test {
+            operation IsOne(q : Qubit) : Bool {
+                M(q) == One
+            }
+            operation Foo(q : Qubit) : Unit is Adj {
+                if IsOne(q) {
+                    X(q);
+                }
+            }
+        }
+        "},
+        &expect![[r#"
+            [
+                Measurement(
+                    Span {
+                        lo: 136,
+                        hi: 177,
+                    },
+                ),
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn operation_returning_result_is_not_measurement() {
+    check(
+        indoc! {"
+        namespace Test {
+            operation Guess(q : Qubit) : Result {
+                Zero
+            }
+            operation Foo(q : Qubit) : Unit is Adj {
+                let r = Guess(q);
+            }
+        }
+        "},
+        &expect![[r#"
+            []
+        "#]],
+    );
+}
+
+#[test]
+fn measurement_with_explicit_adjoint_is_allowed() {
+    check(
+        indoc! {"
+        namespace Test {
+            operation Foo(q : Qubit) : Unit is Adj {
+                body ... {
+                    let r = M(q);
+                }
+                adjoint ... {
+                    Reset(q);
+                }
+            }
+        }
+        "},
+        &expect![[r#"
+            []
+        "#]],
+    );
+}
+
+#[test]
+fn adjointable_operation_that_measures_can_be_called() {
+    check(
+        indoc! {"
+        namespace Test {
+            operation Bar(q : Qubit) : Unit is Adj {
+                body ... {
+                    let r = M(q);
+                }
+                adjoint self;
+            }
+            operation Foo(q : Qubit) : Unit is Adj {
+                Bar(q);
+            }
+        }
+        "},
+        &expect![[r#"
+            []
+        "#]],
+    );
+}
+
+#[test]
+fn measurement_in_explicit_controlled_is_reported_when_controlled_adjoint_is_generated() {
+    check(
+        indoc! {"
+        namespace Test {
+            operation Foo(q : Qubit) : Unit is Adj + Ctl {
+                body ... {
+                    X(q);
+                }
+                adjoint self;
+                controlled (ctls, ...) {
+                    let r = M(q);
+                }
+                controlled adjoint invert;
+            }
+        }
+        "},
+        &expect![[r#"
+            [
+                Measurement(
+                    Span {
+                        lo: 182,
+                        hi: 195,
+                    },
+                ),
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn mutable_conditional_on_result_is_reported() {
+    check(
+        indoc! {"
+        namespace Test {
+            operation Foo(r : Result, q : Qubit) : Unit is Adj {
+                mutable flips = 0;
+                if r == One {
+                    set flips += 1;
+                }
+                for _ in 1..flips {
+                    X(q);
+                }
+            }
+        }
+        "},
+        &expect![[r#"
+            [
+                MutableConditionalOnResult(
+                    Span {
+                        lo: 109,
+                        hi: 160,
+                    },
+                ),
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn conditional_on_result_without_update_is_allowed() {
+    check(
+        indoc! {"
+        namespace Test {
+            operation Foo(r : Result, q : Qubit) : Unit is Adj {
+                if r == One {
+                    X(q);
+                }
+            }
+        }
+        "},
+        &expect![[r#"
+            []
+        "#]],
+    );
+}
+
+#[test]
+fn measurement_is_not_reported_again_by_spec_gen() {
+    check_passes(
+        indoc! {"
+        namespace Test {
+            operation Foo(q : Qubit) : Unit is Adj {
+                X(q);
+                let r = M(q);
+            }
+        }
+        "},
+        &expect![[r#"
+            [
+                AdjCheck(
+                    Measurement(
+                        Span {
+                            lo: 84,
+                            hi: 97,
+                        },
+                    ),
+                ),
+            ]
+        "#]],
+    );
+}
@@ -95,6 +95,29 @@ pub fn check_supported_capabilities(
     checker.check_all()
 }
 
+/// Gets the runtime features used by the package, grouped by the span they would be reported at when checking
+/// against a target that supports none of them.
+#[must_use]
+pub fn get_required_runtime_features(
+    package: &Package,
+    compute_properties: &PackageComputeProperties,
+    store: &qsc_fir::fir::PackageStore,
+) -> Vec<(Span, RuntimeFeatureFlags)> {
+    let mut checker = Checker {
+        package,
+        compute_properties,
+        target_capabilities: TargetCapabilityFlags::empty(),
+        current_callable: None,
+        missing_features_map: FxHashMap::<Span, RuntimeFeatureFlags>::default(),
+        store,
+    };
+
+    checker.visit_package(package, store);
+    let mut features = checker.missing_features_map.drain().collect::<Vec<_>>();
+    features.sort_unstable();
+    features
+}
+
 struct Checker<'a> {
     package: &'a Package,
     compute_properties: &'a PackageComputeProperties,
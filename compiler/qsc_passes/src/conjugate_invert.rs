@@ -239,5 +239,6 @@ impl<'a> Visitor<'a> for ReturnCheck {
         if matches!(&expr.kind, ExprKind::Return(..)) {
             self.errors.push(Error::ReturnForbidden(expr.span));
         }
+        visit::walk_expr(self, expr);
     }
 }
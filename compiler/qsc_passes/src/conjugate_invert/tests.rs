@@ -461,6 +461,38 @@ fn conjugate_return_in_apply_fail() {
     );
 }
 
+#[test]
+fn conjugate_nested_return_in_apply_fail() {
+    check(
+        indoc! {"
+            namespace Test {
+                operation B(i : Int) : Unit is Adj {}
+                operation A(b : Bool) : Int {
+                    within {
+                        B(1);
+                    }
+                    apply {
+                        if b {
+                            return 1;
+                        }
+                    }
+                    0
+                }
+            }
+        "},
+        &expect![[r#"
+            [
+                ReturnForbidden(
+                    Span {
+                        lo: 189,
+                        hi: 197,
+                    },
+                ),
+            ]
+        "#]],
+    );
+}
+
 #[test]
 fn conjugate_mutable_correct_use_succeeds() {
     check(
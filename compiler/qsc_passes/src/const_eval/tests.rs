@@ -0,0 +1,251 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use expect_test::{expect, Expect};
+use indoc::indoc;
+use qsc_data_structures::{language_features::LanguageFeatures, target::TargetCapabilityFlags};
+use qsc_frontend::compile::{self, compile, PackageStore, SourceMap};
+use qsc_hir::{
+    hir::{ItemKind, Package},
+    validate::Validator,
+    visit::Visitor,
+};
+
+use crate::const_eval::{eval_consts, Error};
+
+fn eval(file: &str) -> (Package, Vec<Error>) {
+    let store = PackageStore::new(compile::core());
+    let sources = SourceMap::new([("test".into(), file.into())], None);
+    let mut unit = compile(
+        &store,
+        &[],
+        sources,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::default(),
+    );
+    assert!(unit.errors.is_empty(), "{:?}", unit.errors);
+
+    let errors = eval_consts(&mut unit.package, &mut unit.assigner, store.core());
+    Validator::default().visit_package(&unit.package);
+    (unit.package, errors)
+}
+
+fn check(file: &str, expect: &Expect) {
+    let (_, errors) = eval(file);
+    expect.assert_debug_eq(&errors);
+}
+
+fn check_main(file: &str, expect: &Expect) {
+    let (package, errors) = eval(file);
+    assert!(errors.is_empty(), "{errors:?}");
+    let main = package
+        .items
+        .values()
+        .find(|item| match &item.kind {
+            ItemKind::Callable(decl) => decl.name.name.as_ref() == "Main",
+            _ => false,
+        })
+        .expect("package should have a main callable");
+    expect.assert_eq(&main.to_string());
+}
+
+#[test]
+fn consts_are_folded_into_references() {
+    check_main(
+        indoc! {r#"
+            namespace Test {
+                const Width : Int = 2 * Half;
+                const Half : Int = 2;
+                const Name : String = "reg" + "ister";
+                function Main() : (Int, String, Bool[]) {
+                    (Width, Name, [Width > Half, size = Half])
+                }
+            }
+        "#},
+        &expect![[r#"
+            Item 4 [124-222] (Internal):
+                Parent: 0
+                Callable 27 [124-222] (function):
+                    name: Ident 28 [133-137] "Main"
+                    input: Pat 29 [137-139] [Type Unit]: Unit
+                    output: (Int, String, Bool[])
+                    functors: empty set
+                    body: SpecDecl 30 [124-222]: Impl:
+                        Block 31 [164-222] [Type (Int, String, Bool[])]:
+                            Stmt 32 [174-216]: Expr: Expr 33 [174-216] [Type (Int, String, Bool[])]: Tuple:
+                                Expr 53 [175-180] [Type Int]: Lit: Int(4)
+                                Expr 54 [182-186] [Type String]: String:
+                                    Lit: "register"
+                                Expr 40 [188-215] [Type Bool[]]: ArrayRepeat:
+                                    Expr 41 [189-201] [Type Bool]: BinOp (Gt):
+                                        Expr 55 [189-194] [Type Int]: Lit: Int(4)
+                                        Expr 56 [197-201] [Type Int]: Lit: Int(2)
+                                    Expr 57 [210-214] [Type Int]: Lit: Int(2)
+                    adj: <none>
+                    ctl: <none>
+                    ctl-adj: <none>"#]],
+    );
+}
+
+#[test]
+fn consts_can_use_blocks_and_conditionals() {
+    check(
+        indoc! {"
+            namespace Test {
+                const Bits : Int = {
+                    let (lo, hi) = (3, 5);
+                    if lo < hi { hi - lo } else { 0 }
+                };
+                const Mask : Int = (1 <<< Bits) - 1;
+                const Angles : Double[] = [1.0, 2.0 ^ 0.5, -0.5];
+            }
+        "},
+        &expect![[r#"
+            []
+        "#]],
+    );
+}
+
+#[test]
+fn const_calling_function_is_not_constant() {
+    check(
+        indoc! {"
+            namespace Test {
+                function Two() : Int { 2 }
+                const Four : Int = Two() * 2;
+            }
+        "},
+        &expect![[r#"
+            [
+                NotConstant(
+                    Span {
+                        lo: 71,
+                        hi: 76,
+                    },
+                ),
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn const_depending_on_itself_is_cycle() {
+    check(
+        indoc! {"
+            namespace Test {
+                const A : Int = B + 1;
+                const B : Int = A + 1;
+            }
+        "},
+        &expect![[r#"
+            [
+                Cycle(
+                    "A",
+                    Span {
+                        lo: 64,
+                        hi: 65,
+                    },
+                ),
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn const_division_by_zero_fails() {
+    check(
+        indoc! {"
+            namespace Test {
+                const Null : Int = 0;
+                const Bad : Int = 1 / Null;
+            }
+        "},
+        &expect![[r#"
+            [
+                EvaluationFailed(
+                    "division by zero",
+                    Span {
+                        lo: 65,
+                        hi: 73,
+                    },
+                ),
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn compile_time_assert_that_holds_is_removed() {
+    check_main(
+        indoc! {r#"
+            namespace Test {
+                const Width : Int = 4;
+                function Main() : Unit {
+                    CompileTimeAssert(Width % 2 == 0, "the width must be even");
+                }
+            }
+        "#},
+        &expect![[r#"
+            Item 2 [48-147] (Internal):
+                Parent: 0
+                Callable 7 [48-147] (function):
+                    name: Ident 8 [57-61] "Main"
+                    input: Pat 9 [61-63] [Type Unit]: Unit
+                    output: Unit
+                    functors: empty set
+                    body: SpecDecl 10 [48-147]: Impl:
+                        Block 11 [71-147] [Type Unit]:
+                            Stmt 12 [81-141]: Semi: Expr 13 [81-140] [Type Unit]: Unit
+                    adj: <none>
+                    ctl: <none>
+                    ctl-adj: <none>"#]],
+    );
+}
+
+#[test]
+fn compile_time_assert_that_fails_is_error() {
+    check(
+        indoc! {r#"
+            namespace Test {
+                const Width : Int = 5;
+                function Main() : Unit {
+                    CompileTimeAssert(Width % 2 == 0, "the width must be even");
+                }
+            }
+        "#},
+        &expect![[r#"
+            [
+                AssertFailed(
+                    "the width must be even",
+                    Span {
+                        lo: 81,
+                        hi: 140,
+                    },
+                ),
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn compile_time_assert_on_parameter_is_not_constant() {
+    check(
+        indoc! {r#"
+            namespace Test {
+                function Check(n : Int) : Unit {
+                    CompileTimeAssert(n > 0, "n must be positive");
+                }
+            }
+        "#},
+        &expect![[r#"
+            [
+                NotConstant(
+                    Span {
+                        lo: 80,
+                        hi: 81,
+                    },
+                ),
+            ]
+        "#]],
+    );
+}
@@ -0,0 +1,97 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use miette::Diagnostic;
+use qsc_data_structures::span::Span;
+use qsc_hir::{
+    hir::{Attr, CallableDecl, CallableKind, Item, ItemKind, Package, SpecBody, SpecGen},
+    ty::{Prim, Ty},
+    visit::Visitor,
+};
+use thiserror::Error;
+
+#[derive(Clone, Debug, Diagnostic, Error)]
+pub enum Error {
+    #[error("a callable with the host callable attribute should be a function")]
+    #[diagnostic(code("Qsc.HostCallable.Operation"))]
+    Operation(#[label] Span),
+
+    #[error("a callable with the host callable attribute should be an intrinsic")]
+    #[diagnostic(help(
+        "the implementation is provided by the host, so the body should be `body intrinsic;`"
+    ))]
+    #[diagnostic(code("Qsc.HostCallable.NotIntrinsic"))]
+    NotIntrinsic(#[label] Span),
+
+    #[error("a callable with the host callable attribute cannot take input of type `{0}`")]
+    #[diagnostic(help(
+        "only `Int`, `Double`, `Bool` and `String`, and arrays and tuples of them, can be passed to the host"
+    ))]
+    #[diagnostic(code("Qsc.HostCallable.UnsupportedInput"))]
+    UnsupportedInput(String, #[label] Span),
+
+    #[error("a callable with the host callable attribute cannot have output of type `{0}`")]
+    #[diagnostic(help(
+        "only `Int`, `Double`, `Bool` and `String`, and arrays and tuples of them, can be returned by the host"
+    ))]
+    #[diagnostic(code("Qsc.HostCallable.UnsupportedOutput"))]
+    UnsupportedOutput(String, #[label] Span),
+}
+
+/// For each host callable declaration check that:
+///  1. It is a function.
+///  2. It is an intrinsic.
+///  3. Its input and output only contain types that can be passed to and from the host.
+pub(super) fn validate_host_callable_declarations(package: &Package) -> Vec<Error> {
+    let mut validator = HostCallableValidator { errors: Vec::new() };
+    validator.visit_package(package);
+    validator.errors
+}
+
+fn validate_host_callable_declaration(decl: &CallableDecl, errors: &mut Vec<Error>) {
+    // 1. Check that the declaration is a function.
+    if decl.kind == CallableKind::Operation {
+        errors.push(Error::Operation(decl.name.span));
+    }
+
+    // 2. Check that the declaration is an intrinsic.
+    if !matches!(decl.body.body, SpecBody::Gen(SpecGen::Intrinsic)) {
+        errors.push(Error::NotIntrinsic(decl.name.span));
+    }
+
+    // 3. Check that the input and output can be passed to and from the host.
+    if let Some(ty) = first_unsupported_host_ty(&decl.input.ty) {
+        errors.push(Error::UnsupportedInput(ty.to_string(), decl.input.span));
+    }
+    if let Some(ty) = first_unsupported_host_ty(&decl.output) {
+        errors.push(Error::UnsupportedOutput(ty.to_string(), decl.span));
+    }
+}
+
+/// Finds the first type, recursively, that can't be passed to or from the host.
+fn first_unsupported_host_ty(ty: &Ty) -> Option<&Ty> {
+    match ty {
+        Ty::Prim(Prim::Int | Prim::Double | Prim::Bool | Prim::String) => None,
+        Ty::Array(item) => first_unsupported_host_ty(item),
+        Ty::Tuple(items) => items.iter().find_map(first_unsupported_host_ty),
+        _ => Some(ty),
+    }
+}
+
+/// A helper structure to find and validate host callables in a Package.
+struct HostCallableValidator {
+    errors: Vec<Error>,
+}
+
+impl<'a> Visitor<'a> for HostCallableValidator {
+    fn visit_item(&mut self, item: &'a Item) {
+        if let ItemKind::Callable(callable) = &item.kind {
+            if item.attrs.contains(&Attr::HostCallable) {
+                validate_host_callable_declaration(callable, &mut self.errors);
+            }
+        }
+    }
+}
@@ -0,0 +1,112 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#![allow(clippy::needless_raw_string_hashes)]
+
+use crate::host_callable::validate_host_callable_declarations;
+use expect_test::{expect, Expect};
+use indoc::indoc;
+use qsc_data_structures::{language_features::LanguageFeatures, target::TargetCapabilityFlags};
+use qsc_frontend::compile::{self, compile, PackageStore, SourceMap};
+
+fn check(file: &str, expect: &Expect) {
+    let sources = SourceMap::new([("test".into(), file.into())], None);
+    let unit = compile(
+        &PackageStore::new(compile::core()),
+        &[],
+        sources,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::default(),
+    );
+    assert!(unit.errors.is_empty(), "{:?}", unit.errors);
+
+    let errors = validate_host_callable_declarations(&unit.package);
+    expect.assert_debug_eq(&errors);
+}
+
+#[test]
+fn host_callable_declaration() {
+    check(
+        indoc! {"
+            namespace Test {
+                @HostCallable()
+                function Model(xs : Double[], label : String, (n : Int, flag : Bool)) : (Double, Int[]) {
+                    body intrinsic;
+                }
+            }"},
+        &expect![[r#"
+            []
+        "#]],
+    );
+}
+
+#[test]
+fn host_callable_operation_error() {
+    check(
+        indoc! {"
+            namespace Test {
+                @HostCallable()
+                operation Model(x : Int) : Int { body intrinsic; }
+            }"},
+        &expect![[r#"
+            [
+                Operation(
+                    Span {
+                        lo: 51,
+                        hi: 56,
+                    },
+                ),
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn host_callable_not_intrinsic_error() {
+    check(
+        indoc! {"
+            namespace Test {
+                @HostCallable()
+                function Model(x : Int) : Int { x }
+            }"},
+        &expect![[r#"
+            [
+                NotIntrinsic(
+                    Span {
+                        lo: 50,
+                        hi: 55,
+                    },
+                ),
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn host_callable_unsupported_types_error() {
+    check(
+        indoc! {"
+            namespace Test {
+                @HostCallable()
+                function Model(x : Int, p : Pauli) : Result { body intrinsic; }
+            }"},
+        &expect![[r#"
+            [
+                UnsupportedInput(
+                    "Pauli",
+                    Span {
+                        lo: 55,
+                        hi: 75,
+                    },
+                ),
+                UnsupportedOutput(
+                    "Result",
+                    Span {
+                        lo: 41,
+                        hi: 104,
+                    },
+                ),
+            ]
+        "#]],
+    );
+}
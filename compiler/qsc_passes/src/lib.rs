@@ -1,30 +1,38 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
+mod adj_check;
 mod borrowck;
 mod callable_limits;
 mod capabilitiesck;
 mod common;
 mod conjugate_invert;
+mod const_eval;
 mod entry_point;
+mod host_callable;
 mod id_update;
 mod invert_block;
 mod logic_sep;
 mod loop_unification;
+mod match_lowering;
 mod measurement;
+mod monomorphize;
+mod pure_attribute;
 mod replace_qubit_allocation;
 mod reset;
 mod spec_gen;
 mod test_attribute;
 
 use callable_limits::CallableLimits;
-use capabilitiesck::{check_supported_capabilities, lower_store, run_rca_pass};
+use capabilitiesck::{
+    check_supported_capabilities, get_required_runtime_features, lower_store, run_rca_pass,
+};
 use entry_point::generate_entry_expr;
 use loop_unification::LoopUni;
 use miette::Diagnostic;
-use qsc_data_structures::target::TargetCapabilityFlags;
+use qsc_data_structures::{span::Span, target::TargetCapabilityFlags};
 use qsc_fir::fir;
-use qsc_frontend::compile::CompileUnit;
+use qsc_frontend::compile::{CompileUnit, PackageStore};
 use qsc_hir::{
     assigner::Assigner,
     global::{self, Table},
@@ -34,7 +42,9 @@ use qsc_hir::{
     visit::Visitor,
 };
 use qsc_lowerer::map_hir_package_to_fir;
-use qsc_rca::{PackageComputeProperties, PackageStoreComputeProperties};
+use qsc_rca::{
+    Analyzer, PackageComputeProperties, PackageStoreComputeProperties, RuntimeFeatureFlags,
+};
 use replace_qubit_allocation::ReplaceQubitAllocation;
 use thiserror::Error;
 
@@ -45,18 +55,23 @@ pub(crate) static QIR_RUNTIME_NAMESPACE: &[&str] = &["QIR", "Runtime"];
 #[diagnostic(transparent)]
 #[error(transparent)]
 pub enum Error {
+    AdjCheck(adj_check::Error),
     BorrowCk(borrowck::Error),
     CallableLimits(callable_limits::Error),
     CapabilitiesCk(qsc_rca::errors::Error),
     ConjInvert(conjugate_invert::Error),
+    Const(const_eval::Error),
     EntryPoint(entry_point::Error),
+    HostCallable(host_callable::Error),
+    Match(match_lowering::Error),
     Measurement(measurement::Error),
+    PureAttribute(pure_attribute::Error),
     Reset(reset::Error),
     SpecGen(spec_gen::Error),
     TestAttribute(test_attribute::TestAttributeError),
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq)]
 pub enum PackageType {
     Exe,
     Lib,
@@ -74,6 +89,9 @@ pub fn lower_hir_to_fir(
 
 pub struct PassContext {
     borrow_check: borrowck::Checker,
+    classes: monomorphize::Classes,
+    external_callables: pure_attribute::ExternalCallables,
+    measurements: adj_check::Measurements,
 }
 
 impl Default for PassContext {
@@ -87,6 +105,20 @@ impl PassContext {
     pub fn new() -> Self {
         Self {
             borrow_check: borrowck::Checker::default(),
+            classes: monomorphize::Classes::default(),
+            external_callables: pure_attribute::ExternalCallables::default(),
+            measurements: adj_check::Measurements::default(),
+        }
+    }
+
+    /// Creates a context for passes on a package that can call the callables of the packages in the given store.
+    #[must_use]
+    pub fn with_store(store: &PackageStore) -> Self {
+        Self {
+            borrow_check: borrowck::Checker::default(),
+            classes: monomorphize::Classes::new(store),
+            external_callables: pure_attribute::ExternalCallables::new(store),
+            measurements: adj_check::Measurements::new(store),
         }
     }
 
@@ -98,14 +130,31 @@ impl PassContext {
         core: &Table,
         package_type: PackageType,
     ) -> Vec<Error> {
+        let const_errors = const_eval::eval_consts(package, assigner, core);
+        Validator::default().visit_package(package);
+
+        let match_errors = match_lowering::lower_match_exprs(package, assigner);
+        Validator::default().visit_package(package);
+
+        self.classes.add_package(None, package);
+        monomorphize::dispatch_methods(package, assigner, &self.classes);
+        Validator::default().visit_package(package);
+
         let mut call_limits = CallableLimits::default();
         call_limits.visit_package(package);
         let callable_errors = call_limits.errors;
 
+        let pure_errors =
+            pure_attribute::validate_pure_attributes(package, &self.external_callables);
+
         self.borrow_check.visit_package(package);
         let borrow_errors = &mut self.borrow_check.errors;
 
-        let spec_errors = spec_gen::generate_specs(core, package, assigner);
+        self.measurements.add_package(None, package);
+        let (adj_errors, non_adjointable) =
+            adj_check::check_adjointable(package, &self.measurements);
+
+        let spec_errors = spec_gen::generate_specs(core, package, assigner, &non_adjointable);
         Validator::default().visit_package(package);
 
         let conjugate_errors = conjugate_invert::invert_conjugate_exprs(core, package, assigner);
@@ -113,6 +162,7 @@ impl PassContext {
 
         let measurement_decl_errors = measurement::validate_measurement_declarations(package);
         let reset_decl_errors = reset::validate_reset_declarations(package);
+        let host_callable_decl_errors = host_callable::validate_host_callable_declarations(package);
 
         let entry_point_errors = generate_entry_expr(package, assigner, package_type);
         Validator::default().visit_package(package);
@@ -126,15 +176,24 @@ impl PassContext {
         let test_attribute_errors = test_attribute::validate_test_attributes(package);
         Validator::default().visit_package(package);
 
-        callable_errors
+        const_errors
             .into_iter()
-            .map(Error::CallableLimits)
+            .map(Error::Const)
+            .chain(match_errors.into_iter().map(Error::Match))
+            .chain(callable_errors.into_iter().map(Error::CallableLimits))
+            .chain(pure_errors.into_iter().map(Error::PureAttribute))
             .chain(borrow_errors.drain(..).map(Error::BorrowCk))
+            .chain(adj_errors.into_iter().map(Error::AdjCheck))
             .chain(spec_errors.into_iter().map(Error::SpecGen))
             .chain(conjugate_errors.into_iter().map(Error::ConjInvert))
             .chain(entry_point_errors)
             .chain(measurement_decl_errors.into_iter().map(Error::Measurement))
             .chain(reset_decl_errors.into_iter().map(Error::Reset))
+            .chain(
+                host_callable_decl_errors
+                    .into_iter()
+                    .map(Error::HostCallable),
+            )
             .chain(test_attribute_errors.into_iter().map(Error::TestAttribute))
             .collect()
     }
@@ -146,15 +205,35 @@ impl PassContext {
     ) -> Result<PackageStoreComputeProperties, Vec<Error>> {
         run_rca_pass(fir_store, package_id, capabilities)
     }
+
+    /// Runs runtime capabilities analysis and gets the runtime features used by the package, grouped by the span
+    /// of the code that uses them.
+    #[must_use]
+    pub fn get_required_runtime_features_on_fir(
+        fir_store: &qsc_fir::fir::PackageStore,
+        package_id: qsc_fir::fir::PackageId,
+    ) -> Vec<(Span, RuntimeFeatureFlags)> {
+        let compute_properties = Analyzer::init(fir_store).analyze_all();
+        get_required_runtime_features(
+            fir_store.get(package_id),
+            compute_properties.get(package_id),
+            fir_store,
+        )
+    }
 }
 
 /// Run the default set of passes required for evaluation.
 pub fn run_default_passes(
-    core: &Table,
+    store: &PackageStore,
     unit: &mut CompileUnit,
     package_type: PackageType,
 ) -> Vec<Error> {
-    PassContext::new().run_default_passes(&mut unit.package, &mut unit.assigner, core, package_type)
+    PassContext::with_store(store).run_default_passes(
+        &mut unit.package,
+        &mut unit.assigner,
+        store.core(),
+        package_type,
+    )
 }
 
 pub fn run_core_passes(core: &mut CompileUnit) -> Vec<Error> {
@@ -174,6 +174,7 @@ impl SepCheck {
             ExprKind::If(cond, then_expr, else_expr) => {
                 self.handle_if_expr(prior, cond, then_expr, else_expr.as_deref())
             }
+            ExprKind::Match(..) => panic!("match should be eliminated by passes"),
 
             ExprKind::Array(_)
             | ExprKind::ArrayRepeat(..)
@@ -0,0 +1,295 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use std::{mem::take, rc::Rc};
+
+use miette::Diagnostic;
+use qsc_data_structures::span::Span;
+use qsc_hir::{
+    assigner::Assigner,
+    hir::{
+        BinOp, Block, Expr, ExprKind, Field, FieldPath, Lit, MatchArm, MatchPat, Mutability,
+        Package, Pauli, Result, Stmt, StmtKind,
+    },
+    mut_visit::{walk_expr, MutVisitor},
+    ty::{Prim, Ty},
+};
+use thiserror::Error;
+
+use crate::common::{generated_name, IdentTemplate};
+
+#[derive(Clone, Debug, Diagnostic, Error)]
+pub enum Error {
+    #[error("match expression does not cover {0}")]
+    #[diagnostic(help(
+        "add arms for the missing values, or an arm that binds any value, like `_ => ...`"
+    ))]
+    #[diagnostic(code("Qsc.Match.NonExhaustive"))]
+    NonExhaustive(String, #[label] Span),
+
+    #[error("match arm is unreachable")]
+    #[diagnostic(help("every value matched by this arm is matched by an earlier arm"))]
+    #[diagnostic(code("Qsc.Match.UnreachableArm"))]
+    UnreachableArm(#[label] Span),
+}
+
+/// Checks that match expressions are exhaustive and replaces each of them with a block that binds
+/// the matched value to a local and compares it with the literal arms in an if-elif chain. Enum
+/// values are tuples of the index of their variant and its payload, so variant arms compare the
+/// index and bind the payload. Later passes, like the generation of adjoint specializations, and
+/// the evaluator only ever see the if-elif chain.
+pub(super) fn lower_match_exprs(package: &mut Package, assigner: &mut Assigner) -> Vec<Error> {
+    let mut pass = MatchLowering {
+        assigner,
+        errors: Vec::new(),
+    };
+    pass.visit_package(package);
+    pass.errors
+}
+
+struct MatchLowering<'a> {
+    assigner: &'a mut Assigner,
+    errors: Vec<Error>,
+}
+
+impl MatchLowering<'_> {
+    #[allow(clippy::too_many_lines)]
+    fn lower_match(&mut self, scrutinee: Expr, arms: Vec<MatchArm>, ty: Ty, span: Span) -> Expr {
+        let values = finite_values(&scrutinee.ty);
+        let mut seen = Vec::new();
+        let mut variants = None;
+        let mut seen_variants = Vec::new();
+        let mut exhaustive = false;
+        let mut reachable = Vec::new();
+        for arm in arms {
+            if exhaustive {
+                self.errors.push(Error::UnreachableArm(arm.span));
+                continue;
+            }
+            match &arm.pat {
+                MatchPat::Lit(lit) => {
+                    if seen.contains(&lit.kind) {
+                        self.errors.push(Error::UnreachableArm(arm.span));
+                        continue;
+                    }
+                    seen.push(lit.kind.clone());
+                    exhaustive = values.as_ref().is_some_and(|values| {
+                        values
+                            .iter()
+                            .all(|value| seen.contains(&ExprKind::Lit(value.clone())))
+                    });
+                }
+                MatchPat::Variant(names, index, _) => {
+                    if seen_variants.contains(index) {
+                        self.errors.push(Error::UnreachableArm(arm.span));
+                        continue;
+                    }
+                    seen_variants.push(*index);
+                    exhaustive = seen_variants.len() == names.len();
+                    variants = Some(Rc::clone(names));
+                }
+                MatchPat::Bind(_) => exhaustive = true,
+            }
+            reachable.push(arm);
+        }
+
+        if !exhaustive {
+            let missing = match (&variants, &values) {
+                (Some(names), _) => names
+                    .iter()
+                    .enumerate()
+                    .filter(|(index, _)| !seen_variants.contains(index))
+                    .map(|(_, name)| format!("`{name}`"))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                (None, Some(values)) => values
+                    .iter()
+                    .filter(|value| !seen.contains(&ExprKind::Lit((*value).clone())))
+                    .map(|value| format!("`{}`", lit_name(value)))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                (None, None) => format!("every value of type `{}`", scrutinee.ty.display()),
+            };
+            self.errors.push(Error::NonExhaustive(missing, span));
+        }
+
+        let ident = self.gen_ident(scrutinee.ty.clone(), scrutinee.span);
+        let init = ident.gen_id_init(Mutability::Immutable, scrutinee, self.assigner);
+
+        // The chain is built from the last arm up. When the arms are exhaustive, the last one
+        // doesn't need to be compared with the value and becomes the final else branch.
+        let mut arms = reachable.into_iter().rev();
+        let mut chain = if exhaustive {
+            arms.next()
+                .map(|arm| self.arm_body(arm, ty.clone(), &ident))
+        } else {
+            None
+        };
+        for arm in arms {
+            let (lhs, rhs) = match &arm.pat {
+                MatchPat::Lit(lit) => (ident.gen_local_ref(self.assigner), lit.clone()),
+                MatchPat::Variant(_, index, pat) => (
+                    self.gen_field_ref(&ident, 0, Ty::Prim(Prim::Int)),
+                    Expr {
+                        id: self.assigner.next_node(),
+                        span: pat.span,
+                        ty: Ty::Prim(Prim::Int),
+                        kind: ExprKind::Lit(Lit::Int(
+                            (*index)
+                                .try_into()
+                                .expect("variant index should fit into an integer"),
+                        )),
+                    },
+                ),
+                MatchPat::Bind(_) => unreachable!("only the last reachable arm can bind the value"),
+            };
+            let cond = Expr {
+                id: self.assigner.next_node(),
+                span: rhs.span,
+                ty: Ty::Prim(Prim::Bool),
+                kind: ExprKind::BinOp(BinOp::Eq, Box::new(lhs), Box::new(rhs)),
+            };
+            let span = arm.span;
+            let body = self.arm_body(arm, ty.clone(), &ident);
+            chain = Some(Expr {
+                id: self.assigner.next_node(),
+                span,
+                ty: ty.clone(),
+                kind: ExprKind::If(Box::new(cond), Box::new(body), chain.map(Box::new)),
+            });
+        }
+
+        let mut stmts = vec![init];
+        if let Some(chain) = chain {
+            stmts.push(Stmt {
+                id: self.assigner.next_node(),
+                span: chain.span,
+                kind: StmtKind::Expr(chain),
+            });
+        }
+        Expr {
+            id: self.assigner.next_node(),
+            span,
+            ty: ty.clone(),
+            kind: ExprKind::Block(Block {
+                id: self.assigner.next_node(),
+                span,
+                ty,
+                stmts,
+            }),
+        }
+    }
+
+    /// The branch taken for an arm, which binds the value, or the payload of a variant, to the
+    /// pattern of the arm if it has one.
+    fn arm_body(&mut self, arm: MatchArm, ty: Ty, ident: &IdentTemplate) -> Expr {
+        let binding = match arm.pat {
+            MatchPat::Lit(_) => None,
+            MatchPat::Variant(_, _, pat) => {
+                let payload = self.gen_field_ref(ident, 1, pat.ty.clone());
+                Some((pat, payload))
+            }
+            MatchPat::Bind(pat) => Some((pat, ident.gen_local_ref(self.assigner))),
+        };
+        let stmts = binding
+            .into_iter()
+            .map(|(pat, value)| Stmt {
+                id: self.assigner.next_node(),
+                span: pat.span,
+                kind: StmtKind::Local(Mutability::Immutable, pat, value),
+            })
+            .collect();
+        self.block_expr(arm.span, ty, stmts, arm.body)
+    }
+
+    /// An item of the tuple that an enum value is made of, which is the index of its variant or its
+    /// payload.
+    fn gen_field_ref(&mut self, ident: &IdentTemplate, index: usize, ty: Ty) -> Expr {
+        Expr {
+            id: self.assigner.next_node(),
+            span: ident.span,
+            ty,
+            kind: ExprKind::Field(
+                Box::new(ident.gen_local_ref(self.assigner)),
+                Field::Path(FieldPath {
+                    indices: vec![index],
+                }),
+            ),
+        }
+    }
+
+    fn block_expr(&mut self, span: Span, ty: Ty, mut stmts: Vec<Stmt>, body: Expr) -> Expr {
+        stmts.push(Stmt {
+            id: self.assigner.next_node(),
+            span: body.span,
+            kind: StmtKind::Expr(body),
+        });
+        Expr {
+            id: self.assigner.next_node(),
+            span,
+            ty: ty.clone(),
+            kind: ExprKind::Block(Block {
+                id: self.assigner.next_node(),
+                span,
+                ty,
+                stmts,
+            }),
+        }
+    }
+
+    fn gen_ident(&mut self, ty: Ty, span: Span) -> IdentTemplate {
+        let id = self.assigner.next_node();
+        IdentTemplate {
+            id,
+            span,
+            ty,
+            name: generated_name(&format!("match_{id}")),
+        }
+    }
+}
+
+impl MutVisitor for MatchLowering<'_> {
+    fn visit_expr(&mut self, expr: &mut Expr) {
+        walk_expr(self, expr);
+        match take(&mut expr.kind) {
+            ExprKind::Match(scrutinee, arms) => {
+                *expr = self.lower_match(*scrutinee, arms, take(&mut expr.ty), expr.span);
+            }
+            kind => expr.kind = kind,
+        }
+    }
+}
+
+/// All of the values of a type with few enough values to be matched one by one.
+fn finite_values(ty: &Ty) -> Option<Vec<Lit>> {
+    match ty {
+        Ty::Prim(Prim::Bool) => Some(vec![Lit::Bool(false), Lit::Bool(true)]),
+        Ty::Prim(Prim::Result) => Some(vec![Lit::Result(Result::Zero), Lit::Result(Result::One)]),
+        Ty::Prim(Prim::Pauli) => Some(vec![
+            Lit::Pauli(Pauli::I),
+            Lit::Pauli(Pauli::X),
+            Lit::Pauli(Pauli::Y),
+            Lit::Pauli(Pauli::Z),
+        ]),
+        _ => None,
+    }
+}
+
+fn lit_name(lit: &Lit) -> &'static str {
+    match lit {
+        Lit::Bool(false) => "false",
+        Lit::Bool(true) => "true",
+        Lit::Result(Result::Zero) => "Zero",
+        Lit::Result(Result::One) => "One",
+        Lit::Pauli(Pauli::I) => "PauliI",
+        Lit::Pauli(Pauli::X) => "PauliX",
+        Lit::Pauli(Pauli::Y) => "PauliY",
+        Lit::Pauli(Pauli::Z) => "PauliZ",
+        Lit::BigInt(_) | Lit::Double(_) | Lit::Int(_) => {
+            unreachable!("only literals of types with finite values are named")
+        }
+    }
+}
@@ -0,0 +1,305 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use expect_test::{expect, Expect};
+use indoc::indoc;
+use qsc_data_structures::{language_features::LanguageFeatures, target::TargetCapabilityFlags};
+use qsc_frontend::compile::{self, compile, PackageStore, SourceMap};
+use qsc_hir::{validate::Validator, visit::Visitor};
+
+use crate::match_lowering::lower_match_exprs;
+
+fn check(file: &str, expect: &Expect) {
+    let store = PackageStore::new(compile::core());
+    let sources = SourceMap::new([("test".into(), file.into())], None);
+    let mut unit = compile(
+        &store,
+        &[],
+        sources,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::default(),
+    );
+    assert!(unit.errors.is_empty(), "{:?}", unit.errors);
+
+    let errors = lower_match_exprs(&mut unit.package, &mut unit.assigner);
+    Validator::default().visit_package(&unit.package);
+    expect.assert_debug_eq(&errors);
+}
+
+#[test]
+fn exhaustive_literals_are_accepted() {
+    check(
+        indoc! {"
+            namespace Test {
+                function A(r : Result, b : Bool) : Int {
+                    let x = match r {
+                        Zero => 0,
+                        One => 1,
+                    };
+                    match b {
+                        true => x,
+                        false => -x,
+                    }
+                }
+            }
+        "},
+        &expect![[r#"
+            []
+        "#]],
+    );
+}
+
+#[test]
+fn binding_arm_is_exhaustive() {
+    check(
+        indoc! {"
+            namespace Test {
+                function A(i : Int) : Int {
+                    match i {
+                        0 => 1,
+                        n => n * 2,
+                    }
+                }
+            }
+        "},
+        &expect![[r#"
+            []
+        "#]],
+    );
+}
+
+#[test]
+fn missing_result_is_reported() {
+    check(
+        indoc! {"
+            namespace Test {
+                function A(r : Result) : Int {
+                    match r {
+                        Zero => 0,
+                    }
+                }
+            }
+        "},
+        &expect![[r#"
+            [
+                NonExhaustive(
+                    "`One`",
+                    Span {
+                        lo: 60,
+                        hi: 102,
+                    },
+                ),
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn missing_paulis_are_reported() {
+    check(
+        indoc! {"
+            namespace Test {
+                function A(p : Pauli) : Int {
+                    match p {
+                        PauliX => 1,
+                        PauliZ => 3,
+                    }
+                }
+            }
+        "},
+        &expect![[r#"
+            [
+                NonExhaustive(
+                    "`PauliI`, `PauliY`",
+                    Span {
+                        lo: 59,
+                        hi: 128,
+                    },
+                ),
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn int_without_binding_arm_is_reported() {
+    check(
+        indoc! {"
+            namespace Test {
+                function A(i : Int) : Int {
+                    match i {
+                        0 => 0,
+                    }
+                }
+            }
+        "},
+        &expect![[r#"
+            [
+                NonExhaustive(
+                    "every value of type `Int`",
+                    Span {
+                        lo: 57,
+                        hi: 96,
+                    },
+                ),
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn arm_after_binding_arm_is_unreachable() {
+    check(
+        indoc! {"
+            namespace Test {
+                function A(p : Pauli) : Int {
+                    match p {
+                        PauliX => 1,
+                        _ => 0,
+                        PauliZ => 3,
+                    }
+                }
+            }
+        "},
+        &expect![[r#"
+            [
+                UnreachableArm(
+                    Span {
+                        lo: 126,
+                        hi: 137,
+                    },
+                ),
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn duplicate_literal_is_unreachable() {
+    check(
+        indoc! {"
+            namespace Test {
+                function A(i : Int) : Int {
+                    match i {
+                        1 => 1,
+                        1 => 2,
+                        _ => 0,
+                    }
+                }
+            }
+        "},
+        &expect![[r#"
+            [
+                UnreachableArm(
+                    Span {
+                        lo: 99,
+                        hi: 105,
+                    },
+                ),
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn arm_after_all_values_is_unreachable() {
+    check(
+        indoc! {"
+            namespace Test {
+                function A(b : Bool) : Int {
+                    match b {
+                        true => 1,
+                        false => 0,
+                        true => 2,
+                    }
+                }
+            }
+        "},
+        &expect![[r#"
+            [
+                UnreachableArm(
+                    Span {
+                        lo: 127,
+                        hi: 136,
+                    },
+                ),
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn exhaustive_variants_are_accepted() {
+    check(
+        indoc! {"
+            namespace Test {
+                enum Shape { Circle(Double), Rect(Double, Double), Empty }
+                function Area(s : Shape) : Double {
+                    match s {
+                        Circle(r) => 3.0 * r * r,
+                        Rect(w, h) => w * h,
+                        Empty() => 0.0,
+                    }
+                }
+            }
+        "},
+        &expect![[r#"
+            []
+        "#]],
+    );
+}
+
+#[test]
+fn missing_variants_are_reported() {
+    check(
+        indoc! {"
+            namespace Test {
+                enum Shape { Circle(Double), Rect(Double, Double), Empty }
+                function A(s : Shape) : Double {
+                    match s {
+                        Circle(r) => r,
+                    }
+                }
+            }
+        "},
+        &expect![[r#"
+            [
+                NonExhaustive(
+                    "`Rect`, `Empty`",
+                    Span {
+                        lo: 125,
+                        hi: 172,
+                    },
+                ),
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn duplicate_variant_is_unreachable() {
+    check(
+        indoc! {"
+            namespace Test {
+                enum Shape { Circle(Double), Empty }
+                function A(s : Shape) : Double {
+                    match s {
+                        Circle(r) => r,
+                        Empty() => 0.0,
+                        Circle(x) => x,
+                    }
+                }
+            }
+        "},
+        &expect![[r#"
+            [
+                UnreachableArm(
+                    Span {
+                        lo: 181,
+                        hi: 195,
+                    },
+                ),
+            ]
+        "#]],
+    );
+}
@@ -0,0 +1,369 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use crate::id_update::NodeIdRefresher;
+use qsc_frontend::compile::PackageStore;
+use qsc_hir::{
+    assigner::Assigner,
+    hir::{
+        Block, CallableDecl, Expr, ExprKind, Item, ItemId, ItemKind, LocalItemId, Package,
+        PackageId, Pat, Res, Visibility,
+    },
+    mut_visit::{walk_block, walk_callable_decl, walk_expr, walk_item, walk_pat, MutVisitor},
+    ty::{
+        Arrow, ClassConstraint, ClassConstraints, ClassDef, FunctorSet, GenericArg, Ty,
+        TypeParameter, Udt,
+    },
+};
+use rustc_hash::FxHashMap;
+
+/// The classes with methods and the types that implement them, and the generic callables that call the methods of
+/// their type parameters along with the lambdas lifted out of them.
+#[derive(Default)]
+pub(super) struct Classes {
+    classes: FxHashMap<ItemId, ClassDef>,
+    methods: FxHashMap<ItemId, (ItemId, usize)>,
+    udts: FxHashMap<ItemId, Udt>,
+    callables: FxHashMap<ItemId, CallableDecl>,
+}
+
+impl Classes {
+    pub(super) fn new(store: &PackageStore) -> Self {
+        let mut classes = Self::default();
+        for (package_id, unit) in store {
+            classes.add_package(Some(package_id), &unit.package);
+        }
+        classes
+    }
+
+    /// Adds the classes, types and callables of a package, whose items are identified relative to `package_id`.
+    /// Packages must be added after the packages they depend on.
+    pub(super) fn add_package(&mut self, package_id: Option<PackageId>, package: &Package) {
+        let item_id = |item| ItemId {
+            package: package_id,
+            item,
+        };
+
+        for item in package.items.values() {
+            match &item.kind {
+                ItemKind::Class(_, def) => {
+                    let def = package_id.map_or_else(|| def.clone(), |id| def.with_package(id));
+                    for (index, &(_, method)) in def.methods.iter().enumerate() {
+                        self.methods
+                            .insert(item_id(method), (item_id(item.id), index));
+                    }
+                    self.classes.insert(item_id(item.id), def);
+                }
+                ItemKind::Ty(_, udt) if !udt.instances.is_empty() => {
+                    self.udts.insert(item_id(item.id), udt.clone());
+                }
+                _ => {}
+            }
+        }
+
+        if self.methods.is_empty() {
+            return;
+        }
+
+        let generics: Vec<_> = package
+            .items
+            .values()
+            .filter(|item| match &item.kind {
+                ItemKind::Callable(decl) => decl.generics.iter().any(|param| match param {
+                    TypeParameter::Ty { bounds, .. } => {
+                        let bounds =
+                            package_id.map_or_else(|| bounds.clone(), |id| bounds.with_package(id));
+                        self.requires_methods(&bounds, 0)
+                    }
+                    TypeParameter::Functor(_) => false,
+                }),
+                _ => false,
+            })
+            .map(|item| item.id)
+            .collect();
+
+        // Lambdas are lifted into items whose parent is the callable they were declared in, and specializing a
+        // callable specializes its lambdas too.
+        for item in package.items.values() {
+            let is_needed = generics.contains(&item.id)
+                || item.parent.is_some_and(|parent| generics.contains(&parent));
+            if let (true, ItemKind::Callable(decl)) = (is_needed, &item.kind) {
+                self.callables.insert(item_id(item.id), decl.clone());
+            }
+        }
+    }
+
+    fn requires_methods(&self, bounds: &ClassConstraints, depth: usize) -> bool {
+        const MAX_DEPTH: usize = 100;
+        depth < MAX_DEPTH
+            && bounds.0.iter().any(|bound| match bound {
+                ClassConstraint::NonNativeClass { id, .. } => {
+                    self.classes.get(id).is_some_and(|class| {
+                        !class.methods.is_empty()
+                            || self.requires_methods(&class.constraints, depth + 1)
+                    })
+                }
+                _ => false,
+            })
+    }
+
+    /// The callable that implements a method for a type, if the method is called with a type that is a member of its
+    /// class.
+    fn implementation(&self, method: ItemId, args: &[GenericArg]) -> Option<ItemId> {
+        let &(class, index) = self.methods.get(&method)?;
+        let Some(GenericArg::Ty(Ty::Udt(_, Res::Item(udt), _))) = args.last() else {
+            return None;
+        };
+        let instance = self.udts.get(udt)?.instance(udt.package, class)?;
+        Some(ItemId {
+            package: udt.package,
+            item: *instance.methods.get(index)?,
+        })
+    }
+}
+
+/// Replaces each call to a class method, whose `'Self` argument is a user-defined type, with a call to the callable
+/// that implements the method for that type. Calls to generic callables that call the methods of their type
+/// parameters are replaced with calls to copies of them that are specialized to the type arguments, so that their
+/// method calls are replaced too.
+pub(super) fn dispatch_methods(package: &mut Package, assigner: &mut Assigner, classes: &Classes) {
+    if classes.methods.is_empty() {
+        return;
+    }
+
+    let mut dispatcher = Dispatcher {
+        assigner,
+        classes,
+        parent: None,
+        specializations: Vec::new(),
+        items: Vec::new(),
+    };
+
+    for item in package.items.values_mut() {
+        dispatcher.parent = Some(item.id);
+        dispatcher.visit_item(item);
+    }
+
+    dispatcher.parent = None;
+    for stmt in &mut package.stmts {
+        dispatcher.visit_stmt(stmt);
+    }
+    if let Some(entry) = &mut package.entry {
+        dispatcher.visit_expr(entry);
+    }
+
+    // Specialized callables can call other callables that need to be specialized.
+    while let Some(mut item) = dispatcher.items.pop() {
+        dispatcher.parent = Some(item.id);
+        dispatcher.visit_item(&mut item);
+        package.items.insert(item.id, item);
+    }
+}
+
+struct Dispatcher<'a> {
+    assigner: &'a mut Assigner,
+    classes: &'a Classes,
+    parent: Option<LocalItemId>,
+    specializations: Vec<(ItemId, Vec<GenericArg>, LocalItemId)>,
+    items: Vec<Item>,
+}
+
+impl Dispatcher<'_> {
+    /// The specialization of a callable to the given generic arguments, which is created if it does not exist yet.
+    fn specialize(&mut self, id: ItemId, args: &[GenericArg]) -> LocalItemId {
+        if let Some(&(_, _, item)) = self
+            .specializations
+            .iter()
+            .find(|(other_id, other_args, _)| *other_id == id && other_args == args)
+        {
+            return item;
+        }
+
+        let item = self.assigner.next_item();
+        self.specializations.push((id, args.to_vec(), item));
+
+        let mut decl = self.classes.callables[&id].clone();
+        decl.generics = Vec::new();
+        let mut specializer = Specializer {
+            args,
+            package: id.package,
+            lambdas: Vec::new(),
+        };
+        specializer.visit_callable_decl(&mut decl);
+        NodeIdRefresher::new(self.assigner).visit_callable_decl(&mut decl);
+
+        // Lambdas use the type parameters of the callable they were declared in, so they are specialized with the
+        // same arguments.
+        let lambdas = specializer.lambdas;
+        let specialized_lambdas: Vec<_> = lambdas
+            .iter()
+            .map(|&lambda| {
+                let lambda = ItemId {
+                    package: id.package,
+                    item: lambda,
+                };
+                self.specialize(lambda, args)
+            })
+            .collect();
+        ReplaceLambdas {
+            lambdas: &lambdas,
+            specialized: &specialized_lambdas,
+        }
+        .visit_callable_decl(&mut decl);
+
+        self.items.push(Item {
+            id: item,
+            span: decl.span,
+            parent: self.parent,
+            doc: "".into(),
+            attrs: Vec::new(),
+            visibility: Visibility::Internal,
+            kind: ItemKind::Callable(decl),
+        });
+        item
+    }
+}
+
+impl MutVisitor for Dispatcher<'_> {
+    fn visit_item(&mut self, item: &mut Item) {
+        // The methods of a generic callable's type parameters are only known once it is specialized.
+        if let ItemKind::Callable(decl) = &item.kind {
+            let id = ItemId {
+                package: None,
+                item: item.id,
+            };
+            if !decl.generics.is_empty() && self.classes.callables.contains_key(&id) {
+                return;
+            }
+        }
+        walk_item(self, item);
+    }
+
+    fn visit_expr(&mut self, expr: &mut Expr) {
+        if let ExprKind::Var(Res::Item(id), args) = &expr.kind {
+            if let Some(implementation) = self.classes.implementation(*id, args) {
+                expr.kind = ExprKind::Var(Res::Item(implementation), Vec::new());
+            } else if self
+                .classes
+                .callables
+                .get(id)
+                .is_some_and(|decl| !decl.generics.is_empty())
+                && args.iter().all(is_concrete)
+            {
+                let item = self.specialize(*id, args);
+                expr.kind = ExprKind::Var(
+                    Res::Item(ItemId {
+                        package: None,
+                        item,
+                    }),
+                    Vec::new(),
+                );
+            }
+        }
+        walk_expr(self, expr);
+    }
+}
+
+fn is_concrete(arg: &GenericArg) -> bool {
+    fn is_concrete_ty(ty: &Ty) -> bool {
+        match ty {
+            Ty::Array(item) => is_concrete_ty(item),
+            Ty::Arrow(arrow) => is_concrete_arrow(arrow),
+            Ty::Infer(_) | Ty::Param { .. } | Ty::Err => false,
+            Ty::Prim(_) => true,
+            Ty::Tuple(items) | Ty::Udt(_, _, items) => items.iter().all(is_concrete_ty),
+        }
+    }
+
+    fn is_concrete_arrow(arrow: &Arrow) -> bool {
+        is_concrete_ty(&arrow.input)
+            && is_concrete_ty(&arrow.output)
+            && matches!(arrow.functors, FunctorSet::Value(_))
+    }
+
+    match arg {
+        GenericArg::Ty(ty) => is_concrete_ty(ty),
+        GenericArg::Functor(functors) => matches!(functors, FunctorSet::Value(_)),
+    }
+}
+
+/// Substitutes generic arguments for the type parameters of a copied callable, and identifies the items that it
+/// refers to relative to the package being compiled.
+struct Specializer<'a> {
+    args: &'a [GenericArg],
+    package: Option<PackageId>,
+    lambdas: Vec<LocalItemId>,
+}
+
+impl Specializer<'_> {
+    fn ty(&self, ty: &Ty) -> Ty {
+        match self.package {
+            Some(package) => ty.with_package(package).instantiate(self.args),
+            None => ty.instantiate(self.args),
+        }
+    }
+}
+
+impl MutVisitor for Specializer<'_> {
+    fn visit_callable_decl(&mut self, decl: &mut CallableDecl) {
+        decl.output = self.ty(&decl.output);
+        walk_callable_decl(self, decl);
+    }
+
+    fn visit_block(&mut self, block: &mut Block) {
+        block.ty = self.ty(&block.ty);
+        walk_block(self, block);
+    }
+
+    fn visit_expr(&mut self, expr: &mut Expr) {
+        expr.ty = self.ty(&expr.ty);
+        match &mut expr.kind {
+            ExprKind::Var(res, args) => {
+                if let Some(package) = self.package {
+                    *res = res.with_package(package);
+                }
+                for arg in args {
+                    *arg = match arg {
+                        GenericArg::Ty(ty) => GenericArg::Ty(self.ty(ty)),
+                        GenericArg::Functor(functors) => {
+                            GenericArg::Functor(functors.instantiate(self.args))
+                        }
+                    };
+                }
+            }
+            ExprKind::Struct(res, _, _) => {
+                if let Some(package) = self.package {
+                    *res = res.with_package(package);
+                }
+            }
+            ExprKind::Closure(_, lambda) => self.lambdas.push(*lambda),
+            _ => {}
+        }
+        walk_expr(self, expr);
+    }
+
+    fn visit_pat(&mut self, pat: &mut Pat) {
+        pat.ty = self.ty(&pat.ty);
+        walk_pat(self, pat);
+    }
+}
+
+/// Replaces the lambdas of a copied callable with their specializations.
+struct ReplaceLambdas<'a> {
+    lambdas: &'a [LocalItemId],
+    specialized: &'a [LocalItemId],
+}
+
+impl MutVisitor for ReplaceLambdas<'_> {
+    fn visit_expr(&mut self, expr: &mut Expr) {
+        if let ExprKind::Closure(_, lambda) = &mut expr.kind {
+            if let Some(index) = self.lambdas.iter().position(|other| other == lambda) {
+                *lambda = self.specialized[index];
+            }
+        }
+        walk_expr(self, expr);
+    }
+}
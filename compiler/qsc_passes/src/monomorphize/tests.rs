@@ -0,0 +1,152 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use std::fmt::Write;
+
+use expect_test::{expect, Expect};
+use indoc::indoc;
+use qsc_data_structures::{language_features::LanguageFeatures, target::TargetCapabilityFlags};
+use qsc_frontend::compile::{self, compile, PackageStore, SourceMap};
+use qsc_hir::{
+    hir::{Expr, ExprKind, ItemKind, Package, Res},
+    validate::Validator,
+    visit::{walk_expr, Visitor},
+};
+
+use crate::monomorphize::{dispatch_methods, Classes};
+
+/// The names of the local callables that each expression refers to.
+struct Callees<'a> {
+    package: &'a Package,
+    names: Vec<String>,
+}
+
+impl<'a> Visitor<'a> for Callees<'a> {
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        if let ExprKind::Var(Res::Item(id), _) = &expr.kind {
+            if let (None, Some(item)) = (id.package, self.package.items.get(id.item)) {
+                if let ItemKind::Callable(decl) = &item.kind {
+                    self.names.push(decl.name.name.to_string());
+                }
+            }
+        }
+        walk_expr(self, expr);
+    }
+}
+
+fn check(file: &str, expect: &Expect) {
+    let store = PackageStore::new(compile::core());
+    let sources = SourceMap::new([("test".into(), file.into())], None);
+    let mut unit = compile(
+        &store,
+        &[],
+        sources,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::default(),
+    );
+    assert!(unit.errors.is_empty(), "{:?}", unit.errors);
+
+    let mut classes = Classes::new(&store);
+    classes.add_package(None, &unit.package);
+    dispatch_methods(&mut unit.package, &mut unit.assigner, &classes);
+    Validator::default().visit_package(&unit.package);
+
+    let mut actual = String::new();
+    for item in unit.package.items.values() {
+        if let ItemKind::Callable(decl) = &item.kind {
+            let mut callees = Callees {
+                package: &unit.package,
+                names: Vec::new(),
+            };
+            callees.visit_callable_decl(decl);
+            writeln!(actual, "{}: [{}]", decl.name.name, callees.names.join(", "))
+                .expect("writing to string should succeed");
+        }
+    }
+    expect.assert_eq(&actual);
+}
+
+#[test]
+fn method_call_on_member_calls_implementation() {
+    check(
+        indoc! {"
+            namespace Test {
+                class Sized {
+                    function Size(x : 'Self) : Int;
+                }
+                struct Rect { Width : Int, Height : Int }
+                function SizeRect(r : Rect) : Int { r.Width * r.Height }
+                function Main() : Int { Size(new Rect { Width = 2, Height = 3 }) }
+            }
+        "},
+        &expect![[r#"
+            Size: []
+            SizeRect: []
+            Main: [SizeRect]
+        "#]],
+    );
+}
+
+#[test]
+fn generic_callable_is_specialized_for_each_member() {
+    check(
+        indoc! {"
+            namespace Test {
+                class Sized {
+                    function Size(x : 'Self) : Int;
+                }
+                struct Rect { Width : Int, Height : Int }
+                struct Line { Length : Int }
+                function SizeRect(r : Rect) : Int { r.Width * r.Height }
+                function SizeLine(l : Line) : Int { l.Length }
+                function TotalSize<'T : Sized>(xs : 'T[]) : Int {
+                    mutable total = 0;
+                    for x in xs {
+                        set total += Size(x);
+                    }
+                    total
+                }
+                function Main() : Int {
+                    TotalSize([new Rect { Width = 2, Height = 3 }])
+                        + TotalSize([new Line { Length = 4 }])
+                        + TotalSize([new Rect { Width = 1, Height = 1 }])
+                }
+            }
+        "},
+        &expect![[r#"
+            Size: []
+            SizeRect: []
+            SizeLine: []
+            TotalSize: [Size]
+            Main: [TotalSize, TotalSize, TotalSize]
+            TotalSize: [SizeRect]
+            TotalSize: [SizeLine]
+        "#]],
+    );
+}
+
+#[test]
+fn generic_callable_with_parameterized_class_is_specialized() {
+    check(
+        indoc! {"
+            namespace Test {
+                class Iterable['U] {
+                    function Items(c : 'Self) : 'U[];
+                }
+                struct Bag { Values : Int[] }
+                function ItemsBag(bag : Bag) : Int[] { bag.Values }
+                function First<'T : Iterable['U], 'U>(c : 'T) : 'U {
+                    Items(c)[0]
+                }
+                function Main() : Int { First(new Bag { Values = [1, 2] }) }
+            }
+        "},
+        &expect![[r#"
+            Items: []
+            ItemsBag: []
+            First: [Items]
+            Main: [First]
+            First: [ItemsBag]
+        "#]],
+    );
+}
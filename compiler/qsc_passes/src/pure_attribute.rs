@@ -0,0 +1,141 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use miette::Diagnostic;
+use qsc_data_structures::span::Span;
+use qsc_frontend::compile::PackageStore;
+use qsc_hir::{
+    hir::{Attr, CallableDecl, CallableKind, Expr, ExprKind, ItemId, ItemKind, Package, Res},
+    visit::{walk_callable_decl, walk_expr, Visitor},
+};
+use rustc_hash::FxHashMap;
+use std::rc::Rc;
+use thiserror::Error;
+
+#[derive(Clone, Debug, Diagnostic, Error)]
+pub enum Error {
+    #[error("a callable with the pure attribute should be a function")]
+    #[diagnostic(code("Qsc.Pure.Operation"))]
+    Operation(#[label] Span),
+
+    #[error("a pure function cannot call `{0}`, which does not have the pure attribute")]
+    #[diagnostic(help("add `@Pure()` to `{0}` if it has no side effects"))]
+    #[diagnostic(code("Qsc.Pure.CallsImpure"))]
+    CallsImpure(String, #[label] Span),
+
+    #[error("a pure function cannot call a callable value")]
+    #[diagnostic(help("only callables declared as items can be checked for side effects"))]
+    #[diagnostic(code("Qsc.Pure.CallsCallableValue"))]
+    CallsCallableValue(#[label] Span),
+}
+
+/// For each callable with the pure attribute check that:
+///  1. It is a function, which already can't call operations.
+///  2. It only calls functions that also have the pure attribute, whether they are in the same package or in the
+///     packages it depends on. Intrinsic functions, such as `Message` or host callables, are impure unless they have
+///     the attribute too.
+///  3. It does not call callable values, such as lambdas and callable parameters.
+///
+/// This makes pure functions safe to evaluate classically whenever their arguments are known.
+pub(super) fn validate_pure_attributes(
+    package: &Package,
+    external_callables: &ExternalCallables,
+) -> Vec<Error> {
+    let mut validator = PureValidator {
+        package,
+        external_callables,
+        in_pure_callable: false,
+        errors: Vec::new(),
+    };
+    validator.visit_package(package);
+    validator.errors
+}
+
+/// The names of the callables in the packages of a store, and whether they have the pure attribute, so that calls to
+/// them from the package being compiled can be checked.
+#[derive(Default)]
+pub(super) struct ExternalCallables(FxHashMap<ItemId, (Rc<str>, bool)>);
+
+impl ExternalCallables {
+    pub(super) fn new(store: &PackageStore) -> Self {
+        let callables = store
+            .iter()
+            .flat_map(|(package_id, unit)| {
+                unit.package.items.iter().filter_map(move |(item, entry)| {
+                    let ItemKind::Callable(decl) = &entry.kind else {
+                        return None;
+                    };
+                    let id = ItemId {
+                        package: Some(package_id),
+                        item,
+                    };
+                    Some((id, (decl.name.name.clone(), is_pure(&decl.attrs))))
+                })
+            })
+            .collect();
+        Self(callables)
+    }
+}
+
+fn is_pure(attrs: &[Attr]) -> bool {
+    attrs.contains(&Attr::Pure)
+}
+
+struct PureValidator<'a> {
+    package: &'a Package,
+    external_callables: &'a ExternalCallables,
+    in_pure_callable: bool,
+    errors: Vec<Error>,
+}
+
+impl<'a> Visitor<'a> for PureValidator<'a> {
+    fn visit_callable_decl(&mut self, decl: &'a CallableDecl) {
+        if is_pure(&decl.attrs) {
+            if decl.kind == CallableKind::Operation {
+                self.errors.push(Error::Operation(decl.name.span));
+            } else {
+                self.in_pure_callable = true;
+                walk_callable_decl(self, decl);
+                self.in_pure_callable = false;
+            }
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        // Only the calls in pure callables are checked, not those in the entry expression or in
+        // top-level statements.
+        if let (true, ExprKind::Call(callee, _)) = (self.in_pure_callable, &expr.kind) {
+            if let Some(error) = self.check_callee(callee) {
+                self.errors.push(error);
+            }
+        }
+        walk_expr(self, expr);
+    }
+}
+
+impl PureValidator<'_> {
+    fn check_callee(&self, callee: &Expr) -> Option<Error> {
+        match &callee.kind {
+            ExprKind::Var(
+                Res::Item(ItemId {
+                    package: None,
+                    item,
+                }),
+                _,
+            ) => match &self.package.items.get(*item)?.kind {
+                ItemKind::Callable(decl) if !is_pure(&decl.attrs) => {
+                    Some(Error::CallsImpure(decl.name.name.to_string(), callee.span))
+                }
+                _ => None,
+            },
+            ExprKind::Var(Res::Item(item), _) => match self.external_callables.0.get(item) {
+                Some((name, false)) => Some(Error::CallsImpure(name.to_string(), callee.span)),
+                _ => None,
+            },
+            _ => Some(Error::CallsCallableValue(callee.span)),
+        }
+    }
+}
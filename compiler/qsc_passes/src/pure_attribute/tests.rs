@@ -0,0 +1,201 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use expect_test::{expect, Expect};
+use indoc::indoc;
+use qsc_data_structures::{language_features::LanguageFeatures, target::TargetCapabilityFlags};
+use qsc_frontend::compile::{self, compile, PackageStore, SourceMap};
+
+use crate::pure_attribute::{validate_pure_attributes, ExternalCallables};
+
+fn check(file: &str, expect: &Expect) {
+    check_with_entry(file, None, expect);
+}
+
+fn check_with_entry(file: &str, entry: Option<&str>, expect: &Expect) {
+    let mut store = PackageStore::new(compile::core());
+    let std = store.insert(compile::std(&store, TargetCapabilityFlags::all()));
+    let sources = SourceMap::new([("test".into(), file.into())], entry.map(Into::into));
+    let unit = compile(
+        &store,
+        &[(std, None)],
+        sources,
+        TargetCapabilityFlags::all(),
+        LanguageFeatures::default(),
+    );
+    assert!(unit.errors.is_empty(), "{:?}", unit.errors);
+
+    let errors = validate_pure_attributes(&unit.package, &ExternalCallables::new(&store));
+    expect.assert_debug_eq(&errors);
+}
+
+#[test]
+fn pure_function_can_call_pure_functions() {
+    check(
+        indoc! {"
+        namespace Test {
+            @Pure()
+            function Square(x : Int) : Int {
+                x * x
+            }
+            @Pure()
+            function Abs(x : Int) : Int {
+                x < 0 ? -x | x
+            }
+            @Pure()
+            function SumOfSquares(xs : Int[]) : Int {
+                mutable sum = 0;
+                for x in xs {
+                    set sum += Square(Abs(x));
+                }
+                sum
+            }
+        }
+        "},
+        &expect![[r#"
+            []
+        "#]],
+    );
+}
+
+#[test]
+fn operation_cant_be_pure() {
+    check(
+        indoc! {"
+        namespace Test {
+            @Pure()
+            operation Foo() : Unit {}
+        }
+        "},
+        &expect![[r#"
+            [
+                Operation(
+                    Span {
+                        lo: 43,
+                        hi: 46,
+                    },
+                ),
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn pure_function_cant_call_function_without_attribute() {
+    check(
+        indoc! {"
+        namespace Test {
+            function Square(x : Int) : Int {
+                x * x
+            }
+            @Pure()
+            function Cube(x : Int) : Int {
+                x * Square(x)
+            }
+        }
+        "},
+        &expect![[r#"
+            [
+                CallsImpure(
+                    "Square",
+                    Span {
+                        lo: 133,
+                        hi: 139,
+                    },
+                ),
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn pure_function_cant_call_external_function_without_attribute() {
+    check(
+        indoc! {r#"
+        namespace Test {
+            @Pure()
+            function Double(x : Int) : Int {
+                Message("doubling");
+                2 * x
+            }
+        }
+        "#},
+        &expect![[r#"
+            [
+                CallsImpure(
+                    "Message",
+                    Span {
+                        lo: 74,
+                        hi: 81,
+                    },
+                ),
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn pure_function_cant_call_library_function_without_attribute() {
+    check(
+        indoc! {"
+        namespace Test {
+            @Pure()
+            function Magnitude(x : Int) : Int {
+                Std.Math.AbsI(x)
+            }
+        }
+        "},
+        &expect![[r#"
+            [
+                CallsImpure(
+                    "AbsI",
+                    Span {
+                        lo: 77,
+                        hi: 90,
+                    },
+                ),
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn pure_function_cant_call_callable_value() {
+    check(
+        indoc! {"
+        namespace Test {
+            @Pure()
+            function Apply(f : Int -> Int, x : Int) : Int {
+                f(x)
+            }
+        }
+        "},
+        &expect![[r#"
+            [
+                CallsCallableValue(
+                    Span {
+                        lo: 89,
+                        hi: 90,
+                    },
+                ),
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn entry_expr_can_call_function_without_attribute() {
+    check_with_entry(
+        indoc! {"
+        namespace Test {
+            function Two() : Int {
+                2
+            }
+        }
+        "},
+        Some("Test.Two()"),
+        &expect![[r#"
+            []
+        "#]],
+    );
+}
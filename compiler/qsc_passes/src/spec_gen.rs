@@ -16,12 +16,13 @@ use qsc_hir::{
     assigner::Assigner,
     global::Table,
     hir::{
-        Attr, Block, CallableDecl, CallableKind, Functor, Ident, Item, NodeId, Package, Pat,
-        PatKind, Res, SpecBody, SpecDecl, SpecGen,
+        Attr, Block, CallableDecl, CallableKind, Functor, Ident, Item, LocalItemId, NodeId,
+        Package, Pat, PatKind, Res, SpecBody, SpecDecl, SpecGen,
     },
     mut_visit::{walk_item, MutVisitor},
     ty::{Prim, Ty},
 };
+use rustc_hash::FxHashSet;
 use std::option::Option;
 use thiserror::Error;
 
@@ -69,14 +70,16 @@ pub enum Error {
     SimulatableIntrinsic(#[label] Span),
 }
 
-/// Generates specializations for the given compile unit, updating it in-place.
+/// Generates specializations for the given compile unit, updating it in-place. The adjoint specializations of the
+/// callables in `non_adjointable` are not generated, since they already failed the adjointability check.
 pub(super) fn generate_specs(
     core: &Table,
     package: &mut Package,
     assigner: &mut Assigner,
+    non_adjointable: &FxHashSet<LocalItemId>,
 ) -> Vec<Error> {
     generate_placeholders(package, assigner);
-    generate_spec_impls(core, package, assigner)
+    generate_spec_impls(core, package, assigner, non_adjointable)
 }
 
 fn generate_placeholders(package: &mut Package, assigner: &mut Assigner) {
@@ -144,12 +147,19 @@ fn is_self_adjoint(decl: &CallableDecl) -> bool {
     matches!(&decl.adj, Some(s) if matches!(&s.body, SpecBody::Gen(SpecGen::Slf)))
 }
 
-fn generate_spec_impls(core: &Table, package: &mut Package, assigner: &mut Assigner) -> Vec<Error> {
+fn generate_spec_impls(
+    core: &Table,
+    package: &mut Package,
+    assigner: &mut Assigner,
+    non_adjointable: &FxHashSet<LocalItemId>,
+) -> Vec<Error> {
     let mut pass = SpecImplPass {
         core,
         assigner,
+        non_adjointable,
         errors: Vec::new(),
         is_codegen_intrinsic: false,
+        skip_adj: false,
     };
     pass.visit_package(package);
     pass.errors
@@ -158,8 +168,10 @@ fn generate_spec_impls(core: &Table, package: &mut Package, assigner: &mut Assig
 struct SpecImplPass<'a> {
     core: &'a Table,
     assigner: &'a mut Assigner,
+    non_adjointable: &'a FxHashSet<LocalItemId>,
     errors: Vec<Error>,
     is_codegen_intrinsic: bool,
+    skip_adj: bool,
 }
 
 impl SpecImplPass<'_> {
@@ -217,8 +229,10 @@ impl SpecImplPass<'_> {
 impl MutVisitor for SpecImplPass<'_> {
     fn visit_item(&mut self, item: &mut Item) {
         self.is_codegen_intrinsic = item.attrs.contains(&Attr::SimulatableIntrinsic);
+        self.skip_adj = self.non_adjointable.contains(&item.id);
         walk_item(self, item);
         self.is_codegen_intrinsic = false;
+        self.skip_adj = false;
     }
 
     fn visit_callable_decl(&mut self, decl: &mut CallableDecl) {
@@ -264,6 +278,10 @@ impl MutVisitor for SpecImplPass<'_> {
             }
         };
 
+        if self.skip_adj {
+            return;
+        }
+
         if let Some(adj) = adj.as_mut() {
             match adj.body {
                 SpecBody::Gen(SpecGen::Slf) => {
@@ -8,6 +8,7 @@ use indoc::indoc;
 use qsc_data_structures::{language_features::LanguageFeatures, target::TargetCapabilityFlags};
 use qsc_frontend::compile::{self, compile, PackageStore, SourceMap};
 use qsc_hir::{validate::Validator, visit::Visitor};
+use rustc_hash::FxHashSet;
 
 use crate::spec_gen::generate_specs;
 
@@ -23,7 +24,12 @@ fn check(file: &str, expect: &Expect) {
     );
     assert!(unit.errors.is_empty(), "{:?}", unit.errors);
 
-    let errors = generate_specs(store.core(), &mut unit.package, &mut unit.assigner);
+    let errors = generate_specs(
+        store.core(),
+        &mut unit.package,
+        &mut unit.assigner,
+        &FxHashSet::default(),
+    );
     Validator::default().visit_package(&unit.package);
     if errors.is_empty() {
         expect.assert_eq(&unit.package.to_string());
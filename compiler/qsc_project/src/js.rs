@@ -80,4 +80,10 @@ where
     ) -> miette::Result<std::sync::Arc<str>> {
         self.fetch_github(owner, repo, r#ref, path).await
     }
+
+    async fn exists(&self, path: &std::path::Path) -> bool {
+        // The host lists missing directories as empty, rather than failing to list them.
+        let path = path.to_string_lossy();
+        self.read_file(&path).await.is_ok() || !self.list_directory(&path).await.is_empty()
+    }
 }
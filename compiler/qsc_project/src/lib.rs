@@ -13,13 +13,17 @@ mod error;
 mod fs;
 mod js;
 mod manifest;
+mod memory;
 mod project;
 
 pub use error::StdFsError;
 #[cfg(feature = "fs")]
 pub use fs::StdFs;
 pub use js::{JSFileEntry, JSProjectHost};
-pub use manifest::{Manifest, ManifestDescriptor, PackageRef, PackageType, MANIFEST_FILE_NAME};
+pub use manifest::{
+    GitHubRef, Manifest, ManifestDescriptor, PackageRef, PackageType, MANIFEST_FILE_NAME,
+};
+pub use memory::MemoryFs;
 pub use project::FileSystemAsync;
 pub use project::{
     key_for_package_ref, package_ref_from_key, DependencyCycle, DirEntry, EntryType, Error,
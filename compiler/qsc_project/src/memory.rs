@@ -0,0 +1,150 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+//! This module contains a project implementation that keeps the files in memory,
+//! for embedders such as web playgrounds and test harnesses that supply the
+//! project sources without touching the disk.
+
+use crate::{project::normalize_path, EntryType, FileSystem, FileSystemAsync, JSFileEntry};
+use async_trait::async_trait;
+use miette::Error;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// A file system whose files are kept in memory. Directories exist implicitly,
+/// as long as they contain a file.
+#[derive(Debug, Default, Clone)]
+pub struct MemoryFs {
+    files: BTreeMap<PathBuf, Arc<str>>,
+}
+
+impl MemoryFs {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the file at the given path, replacing its contents if it already exists.
+    pub fn insert_file(&mut self, path: impl AsRef<Path>, contents: impl Into<Arc<str>>) {
+        self.files
+            .insert(normalize_path(path.as_ref()), contents.into());
+    }
+
+    /// Removes the file at the given path, returning its contents if it existed.
+    pub fn remove_file(&mut self, path: impl AsRef<Path>) -> Option<Arc<str>> {
+        self.files.remove(&normalize_path(path.as_ref()))
+    }
+
+    fn entries(&self, dir: &Path) -> BTreeSet<(PathBuf, bool)> {
+        self.files
+            .keys()
+            .filter_map(|file| {
+                let mut components = file.strip_prefix(dir).ok()?.components();
+                let name = components.next()?;
+                let is_dir = components.next().is_some();
+                Some((dir.join(name), is_dir))
+            })
+            .collect()
+    }
+}
+
+impl<P, C> FromIterator<(P, C)> for MemoryFs
+where
+    P: AsRef<Path>,
+    C: Into<Arc<str>>,
+{
+    fn from_iter<I: IntoIterator<Item = (P, C)>>(iter: I) -> Self {
+        let mut fs = Self::new();
+        for (path, contents) in iter {
+            fs.insert_file(path, contents);
+        }
+        fs
+    }
+}
+
+impl FileSystem for MemoryFs {
+    type Entry = JSFileEntry;
+
+    fn read_file(&self, path: &Path) -> miette::Result<(Arc<str>, Arc<str>)> {
+        match self.files.get(&normalize_path(path)) {
+            Some(contents) => Ok((path.to_string_lossy().into(), contents.clone())),
+            None => Err(Error::msg(format!("file `{}` not found", path.display()))),
+        }
+    }
+
+    fn list_directory(&self, path: &Path) -> miette::Result<Vec<JSFileEntry>> {
+        let entries = self.entries(&normalize_path(path));
+        if entries.is_empty() {
+            return Err(Error::msg(format!(
+                "directory `{}` not found",
+                path.display()
+            )));
+        }
+        Ok(entries
+            .into_iter()
+            .map(|(path, is_dir)| JSFileEntry {
+                name: path.to_string_lossy().into(),
+                r#type: if is_dir {
+                    EntryType::Folder
+                } else {
+                    EntryType::File
+                },
+            })
+            .collect())
+    }
+
+    fn resolve_path(&self, base: &Path, path: &Path) -> miette::Result<PathBuf> {
+        Ok(normalize_path(&base.join(path)))
+    }
+
+    fn fetch_github(
+        &self,
+        _owner: &str,
+        _repo: &str,
+        _ref: &str,
+        _path: &str,
+    ) -> miette::Result<Arc<str>> {
+        Err(Error::msg(
+            "github references not supported for this file system",
+        ))
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        let path = normalize_path(path);
+        self.files.contains_key(&path) || !self.entries(&path).is_empty()
+    }
+}
+
+#[async_trait(?Send)]
+impl FileSystemAsync for MemoryFs {
+    type Entry = JSFileEntry;
+
+    async fn read_file(&self, path: &Path) -> miette::Result<(Arc<str>, Arc<str>)> {
+        FileSystem::read_file(self, path)
+    }
+
+    async fn list_directory(&self, path: &Path) -> miette::Result<Vec<JSFileEntry>> {
+        FileSystem::list_directory(self, path)
+    }
+
+    async fn resolve_path(&self, base: &Path, path: &Path) -> miette::Result<PathBuf> {
+        FileSystem::resolve_path(self, base, path)
+    }
+
+    async fn fetch_github(
+        &self,
+        owner: &str,
+        repo: &str,
+        r#ref: &str,
+        path: &str,
+    ) -> miette::Result<Arc<str>> {
+        FileSystem::fetch_github(self, owner, repo, r#ref, path)
+    }
+
+    async fn exists(&self, path: &Path) -> bool {
+        FileSystem::exists(self, path)
+    }
+}
@@ -13,7 +13,7 @@ use qsc_linter::LintConfig;
 use rustc_hash::FxHashMap;
 use std::{
     cell::RefCell,
-    path::{Path, PathBuf},
+    path::{Component, Path, PathBuf},
     sync::Arc,
 };
 use thiserror::Error;
@@ -187,6 +187,11 @@ pub trait FileSystemAsync {
         path: &str,
     ) -> miette::Result<Arc<str>>;
 
+    /// Given a path, returns whether a file or directory exists there.
+    async fn exists(&self, path: &Path) -> bool {
+        self.read_file(path).await.is_ok() || self.list_directory(path).await.is_ok()
+    }
+
     /// Given an initial path, fetch files matching <initial_path>/**/*.qs
     async fn collect_project_sources(&self, initial_path: &Path) -> ProjectResult<Vec<PathBuf>> {
         let listing = self
@@ -794,6 +799,11 @@ pub trait FileSystem {
         path: &str,
     ) -> miette::Result<Arc<str>>;
 
+    /// Given a path, returns whether a file or directory exists there.
+    fn exists(&self, path: &Path) -> bool {
+        self.read_file(path).is_ok() || self.list_directory(path).is_ok()
+    }
+
     fn load_project(
         &self,
         directory: &Path,
@@ -852,4 +862,38 @@ where
     ) -> miette::Result<Arc<str>> {
         self.fs.fetch_github(owner, repo, r#ref, path)
     }
+
+    async fn exists(&self, path: &Path) -> bool {
+        self.fs.exists(path)
+    }
+}
+
+/// Normalizes the path, i.e. removes '.' and redundant separators, and resolves '..'
+/// by removing the preceding segment.
+pub(crate) fn normalize_path(path: &Path) -> PathBuf {
+    // Adapted from https://github.com/rust-lang/cargo/blob/a879a1ca12e3997d9fdd71b70f34f1f3c866e1da/crates/cargo-util/src/paths.rs#L84
+    let mut components = path.components().peekable();
+    let mut normalized = if let Some(c @ Component::Prefix(..)) = components.peek().copied() {
+        components.next();
+        PathBuf::from(c.as_os_str())
+    } else {
+        PathBuf::new()
+    };
+
+    for component in components {
+        match component {
+            Component::Prefix(..) => unreachable!(),
+            Component::RootDir => {
+                normalized.push(component.as_os_str());
+            }
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            Component::Normal(c) => {
+                normalized.push(c);
+            }
+        }
+    }
+    normalized
 }
@@ -8,7 +8,10 @@
 mod harness;
 
 use expect_test::expect;
+use futures::FutureExt;
 use harness::check;
+use qsc_project::{FileSystem, FileSystemAsync, MemoryFs};
+use std::path::Path;
 
 #[test]
 fn basic_manifest() {
@@ -520,3 +523,110 @@ fn circular_dep() {
             }"#]],
     );
 }
+
+fn memory_fs() -> MemoryFs {
+    [
+        (
+            "project/qsharp.json",
+            r#"{ "dependencies": { "Dep": { "path": "../dep" } } }"#,
+        ),
+        ("project/src/Main.qs", "namespace Main {}"),
+        ("project/src/Utils/Helpers.qs", "namespace Helpers {}"),
+        ("dep/qsharp.json", "{}"),
+        ("dep/src/Lib.qs", "namespace Lib {}"),
+    ]
+    .into_iter()
+    .collect()
+}
+
+#[test]
+fn memory_fs_project() {
+    let project = FileSystem::load_project(&memory_fs(), Path::new("project"), None)
+        .expect("project should load");
+    expect![[r#"
+        Project {
+            name: "project",
+            path: "project/qsharp.json",
+            package_graph_sources: PackageGraphSources {
+                root: PackageInfo {
+                    sources: [
+                        (
+                            "project/src/Main.qs",
+                            "namespace Main {}",
+                        ),
+                        (
+                            "project/src/Utils/Helpers.qs",
+                            "namespace Helpers {}",
+                        ),
+                    ],
+                    language_features: LanguageFeatures(
+                        0,
+                    ),
+                    dependencies: {
+                        "Dep": "{\"path\":\"dep\"}",
+                    },
+                    package_type: None,
+                },
+                packages: {
+                    "{\"path\":\"dep\"}": PackageInfo {
+                        sources: [
+                            (
+                                "dep/src/Lib.qs",
+                                "namespace Lib {}",
+                            ),
+                        ],
+                        language_features: LanguageFeatures(
+                            0,
+                        ),
+                        dependencies: {},
+                        package_type: None,
+                    },
+                },
+            },
+            lints: [],
+            errors: [],
+        }"#]]
+    .assert_eq(&format!("{project:#?}"));
+}
+
+#[test]
+fn memory_fs_project_loads_async() {
+    let fs = memory_fs();
+    let project = FileSystemAsync::load_project(&fs, Path::new("project"), None)
+        .now_or_never()
+        .expect("load_project should not await")
+        .expect("project should load");
+    let sync_project =
+        FileSystem::load_project(&fs, Path::new("project"), None).expect("project should load");
+    assert_eq!(format!("{project:?}"), format!("{sync_project:?}"));
+}
+
+#[test]
+fn memory_fs_missing_project_is_error() {
+    let errors = FileSystem::load_project(&memory_fs(), Path::new("other"), None)
+        .expect_err("project should not load");
+    expect![[r#"
+        [
+            FileSystem {
+                about_path: "other/qsharp.json",
+                error: "file `other/qsharp.json` not found",
+            },
+        ]"#]]
+    .assert_eq(&format!("{errors:#?}"));
+}
+
+#[test]
+fn memory_fs_exists() {
+    let mut fs = memory_fs();
+    assert!(FileSystem::exists(&fs, Path::new("project/src/Main.qs")));
+    assert!(FileSystem::exists(&fs, Path::new("project/src/Utils")));
+    assert!(FileSystem::exists(
+        &fs,
+        Path::new("project/src/../qsharp.json")
+    ));
+    assert!(!FileSystem::exists(&fs, Path::new("project/src/Other.qs")));
+    assert!(!FileSystem::exists(&fs, Path::new("project/sr")));
+
+    fs.remove_file("project/src/Utils/Helpers.qs");
+    assert!(!FileSystem::exists(&fs, Path::new("project/src/Utils")));
+}
@@ -1065,6 +1065,7 @@ pub(crate) fn build_operation_with_stmts<S: AsRef<str>>(
         span: whole_span,
         doc: "".into(),
         attrs: attrs.into_boxed_slice(),
+        visibility: None,
         kind: Box::new(qsc_ast::ast::ItemKind::Callable(Box::new(
             qsc_ast::ast::CallableDecl {
                 id: NodeId::default(),
@@ -7,13 +7,12 @@ use crate::{
     ApplicationGeneratorSet, ComputeKind, QuantumProperties, RuntimeFeatureFlags, RuntimeKind,
     ValueKind,
 };
-use qsc_data_structures::index_map::IndexMap;
+use qsc_data_structures::{fx::FxHashMap, index_map::IndexMap};
 use qsc_fir::{
     extensions::{InputParam, InputParamIndex},
     fir::{BlockId, ExprId, LocalVarId, StmtId},
     ty::Ty,
 };
-use rustc_hash::FxHashMap;
 
 /// Auxiliary data structure used to build multiple related application generator sets from individual application
 /// instances for a particular callable specialization.
@@ -2,7 +2,7 @@
 // Licensed under the MIT License.
 
 use indenter::Indented;
-use qsc_data_structures::functors::FunctorApp;
+use qsc_data_structures::{functors::FunctorApp, fx::FxHashMap};
 use qsc_fir::{
     extensions::{InputParam, InputParamIndex},
     fir::{
@@ -12,7 +12,6 @@ use qsc_fir::{
     ty::{FunctorSetValue, Ty},
     visit::{walk_expr, walk_stmt, Visitor},
 };
-use rustc_hash::FxHashMap;
 use std::{
     cmp::max,
     fmt::{Debug, Formatter},
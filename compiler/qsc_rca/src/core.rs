@@ -547,9 +547,15 @@ impl<'a> Analyzer<'a> {
         // If the callee could not be resolved, return a compute kind with certain runtime features.
         let (Some(callee), fixed_args) = maybe_callee else {
             // The value kind of a call expression with an unresolved callee is not known, so to avoid
-            // spurious errors in later analysis where the value is used we assume static.
+            // spurious errors in later analysis where the value is used we assume static, unless the arguments are
+            // dynamic, in which case the callee most likely produces a dynamic value from them.
             // During partial-evaluation, the callable is known the actual return kind will be checked.
-            let value_kind = ValueKind::new_static_from_type(expr_type);
+            let args_expr_compute_kind = *application_instance.get_expr_compute_kind(args_expr_id);
+            let value_kind = if args_expr_compute_kind.is_dynamic() {
+                ValueKind::new_dynamic_from_type(expr_type)
+            } else {
+                ValueKind::new_static_from_type(expr_type)
+            };
             let compute_kind = ComputeKind::Quantum(QuantumProperties {
                 runtime_features: RuntimeFeatureFlags::CallToUnresolvedCallee,
                 value_kind,
@@ -601,6 +607,29 @@ impl<'a> Analyzer<'a> {
         compute_kind
     }
 
+    fn analyze_expr_closure(&self, captures: &[LocalVarId], expr_type: &Ty) -> ComputeKind {
+        // A closure is classical unless it captures locals whose values are only known at runtime, in which case the
+        // closure has to be created during partial evaluation so the captured values can be bound to RIR variables.
+        // Creating the closure does not use any runtime feature by itself and its value is always static since the
+        // callable it refers to is known at compile time.
+        let application_instance = self.get_current_application_instance();
+        let captures_dynamic_value = captures.iter().any(|local_var_id| {
+            application_instance
+                .locals_map
+                .get_local_compute_kind(*local_var_id)
+                .compute_kind
+                .is_dynamic()
+        });
+        if captures_dynamic_value {
+            ComputeKind::Quantum(QuantumProperties {
+                runtime_features: RuntimeFeatureFlags::empty(),
+                value_kind: ValueKind::new_static_from_type(expr_type),
+            })
+        } else {
+            ComputeKind::Classical
+        }
+    }
+
     fn analyze_expr_fail(&mut self, msg_expr_id: ExprId) -> ComputeKind {
         // Visit the message expression to determine its compute kind.
         self.visit_expr(msg_expr_id);
@@ -916,7 +945,7 @@ impl<'a> Analyzer<'a> {
         let mut compute_kind = ComputeKind::Classical;
         for component in components {
             match component {
-                StringComponent::Expr(expr_id) => {
+                StringComponent::Expr(expr_id, _) => {
                     self.visit_expr(*expr_id);
                     let application_instance = self.get_current_application_instance();
                     let component_compute_kind =
@@ -1843,7 +1872,7 @@ impl<'a> Visitor<'a> for Analyzer<'a> {
             ExprKind::Call(callee_expr_id, args_expr_id) => {
                 self.analyze_expr_call(*callee_expr_id, *args_expr_id, &expr.ty)
             }
-            ExprKind::Closure(..) => ComputeKind::Classical,
+            ExprKind::Closure(captures, _) => self.analyze_expr_closure(captures, &expr.ty),
             ExprKind::Fail(msg_expr_id) => self.analyze_expr_fail(*msg_expr_id),
             ExprKind::Field(record_expr_id, _) => {
                 self.analyze_expr_field(*record_expr_id, &expr.ty)
@@ -1924,7 +1953,10 @@ impl<'a> Visitor<'a> for Analyzer<'a> {
             ItemKind::Callable(decl) => {
                 self.visit_callable_decl(decl);
             }
-            ItemKind::Export(_, _) | ItemKind::Namespace(_, _) | ItemKind::Ty(_, _) => {
+            ItemKind::Export(_, _)
+            | ItemKind::Namespace(_, _)
+            | ItemKind::Ty(_, _)
+            | ItemKind::Class(_) => {
                 // Items that are not callables do not have compute properties by themselves so we just record them as
                 // such in the package store compute properties data structure.
                 self.package_store_compute_properties.insert_item(
@@ -4,6 +4,7 @@
 use crate::common::{
     initialize_locals_map, try_resolve_callee, FunctorAppExt, Local, LocalKind, LocalSpecId,
 };
+use qsc_data_structures::fx::{FxHashMap, FxHashSet};
 use qsc_fir::{
     fir::{
         Block, BlockId, CallableDecl, CallableImpl, Expr, ExprId, ExprKind, Item, ItemKind,
@@ -13,7 +14,6 @@ use qsc_fir::{
     ty::FunctorSetValue,
     visit::{walk_expr, Visitor},
 };
-use rustc_hash::{FxHashMap, FxHashSet};
 use std::collections::hash_map::Entry;
 
 pub struct CycleDetector<'a> {
@@ -129,6 +129,7 @@ impl<'a> CycleDetector<'a> {
                 callable_decl,
             ),
             ItemKind::Namespace(_, _) => panic!("calls to namespaces are invalid"),
+            ItemKind::Class(_) => panic!("calls to classes are invalid"),
             ItemKind::Ty(_, _) => {
                 // Ignore "calls" to types.
             }
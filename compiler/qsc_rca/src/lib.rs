@@ -23,6 +23,7 @@ use crate::common::set_indentation;
 use bitflags::bitflags;
 use indenter::indented;
 use qsc_data_structures::{
+    fx::FxHashSet,
     index_map::{IndexMap, Iter},
     target::TargetCapabilityFlags,
 };
@@ -33,7 +34,6 @@ use qsc_fir::{
     },
     ty::Ty,
 };
-use rustc_hash::FxHashSet;
 
 use std::{
     cmp::Ord,
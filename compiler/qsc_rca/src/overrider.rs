@@ -6,6 +6,7 @@ use crate::{
     ApplicationGeneratorSet, ArrayParamApplication, ComputeKind, PackageId, ParamApplication,
     QuantumProperties, RuntimeFeatureFlags, RuntimeKind, ValueKind,
 };
+use qsc_data_structures::fx::FxHashMap;
 use qsc_fir::{
     fir::{
         Block, BlockId, CallableImpl, Expr, ExprId, Global, Item, ItemKind, LocalItemId, Package,
@@ -14,7 +15,6 @@ use qsc_fir::{
     ty::{FunctorSetValue, Ty},
     visit::{walk_block, walk_expr, walk_stmt, Visitor},
 };
-use rustc_hash::FxHashMap;
 
 #[derive(Clone, Debug)]
 struct SpecOverride {
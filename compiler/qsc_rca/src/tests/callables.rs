@@ -70,7 +70,9 @@ fn check_rca_for_closure_function_with_dynamic_captured_value() {
         package_store_compute_properties,
         &expect![[r#"
             ApplicationsGeneratorSet:
-                inherent: Classical
+                inherent: Quantum: QuantumProperties:
+                    runtime_features: RuntimeFeatureFlags(0x0)
+                    value_kind: Element(Static)
                 dynamic_param_applications: <empty>"#]],
     );
 }
@@ -113,7 +115,9 @@ fn check_rca_for_closure_operation_with_dynamic_captured_value() {
         package_store_compute_properties,
         &expect![[r#"
             ApplicationsGeneratorSet:
-                inherent: Classical
+                inherent: Quantum: QuantumProperties:
+                    runtime_features: RuntimeFeatureFlags(0x0)
+                    value_kind: Element(Static)
                 dynamic_param_applications: <empty>"#]],
     );
 }
@@ -63,6 +63,35 @@ pub fn rx_decl() -> Callable {
     }
 }
 
+#[must_use]
+pub fn rz_decl() -> Callable {
+    Callable {
+        name: "__quantum__qis__rz__body".to_string(),
+        input_type: vec![Ty::Double, Ty::Qubit],
+        output_type: None,
+        body: None,
+        call_type: CallableType::Regular,
+    }
+}
+
+/// The prefix of the names of the multi-qubit Pauli rotation intrinsics.
+pub const EXP_PREFIX: &str = "__quantum__qis__exp_";
+
+/// Declares the rotation about the multi-qubit Pauli operator with the given Pauli string, such as `xz`. A call with
+/// angle `theta` applies the same operation as `Exp([PauliX, PauliZ], theta, qubits)`.
+#[must_use]
+pub fn exp_decl(paulis: &str) -> Callable {
+    Callable {
+        name: format!("{EXP_PREFIX}{paulis}__body"),
+        input_type: std::iter::once(Ty::Double)
+            .chain(paulis.chars().map(|_| Ty::Qubit))
+            .collect(),
+        output_type: None,
+        body: None,
+        call_type: CallableType::Regular,
+    }
+}
+
 #[must_use]
 pub fn m_decl() -> Callable {
     Callable {
@@ -74,6 +103,17 @@ pub fn m_decl() -> Callable {
     }
 }
 
+#[must_use]
+pub fn mz_decl() -> Callable {
+    Callable {
+        name: "__quantum__qis__mz__body".to_string(),
+        input_type: vec![Ty::Qubit, Ty::Result],
+        output_type: None,
+        body: None,
+        call_type: CallableType::Measurement,
+    }
+}
+
 #[must_use]
 pub fn mresetz_decl() -> Callable {
     Callable {
@@ -162,6 +202,17 @@ pub fn array_record_decl() -> Callable {
     }
 }
 
+#[must_use]
+pub fn message_record_decl() -> Callable {
+    Callable {
+        name: "__quantum__rt__message_record_output".to_string(),
+        input_type: vec![Ty::Pointer],
+        output_type: None,
+        body: None,
+        call_type: CallableType::OutputRecording,
+    }
+}
+
 #[must_use]
 pub fn tuple_record_decl() -> Callable {
     Callable {
@@ -191,6 +242,74 @@ pub fn new_program() -> Program {
     program
 }
 
+/// Creates a new program like `new_program` that also declares the given callables.
+#[must_use]
+pub fn new_program_with_callables(
+    callables: impl IntoIterator<Item = (CallableId, Callable)>,
+) -> Program {
+    let mut program = new_program();
+    for (callable_id, callable) in callables {
+        program.callables.insert(callable_id, callable);
+    }
+    program
+}
+
+/// Creates a new program that declares the given callables, with blocks numbered in order from block 0 that hold
+/// the given instructions.
+#[must_use]
+pub fn new_program_with_blocks(
+    callables: impl IntoIterator<Item = (CallableId, Callable)>,
+    blocks: impl IntoIterator<Item = Vec<Instruction>>,
+) -> Program {
+    let mut program = Program::new();
+    for (callable_id, callable) in callables {
+        program.callables.insert(callable_id, callable);
+    }
+    for (block_id, instrs) in blocks.into_iter().enumerate() {
+        program
+            .blocks
+            .insert(BlockId::from(block_id), Block(instrs));
+    }
+    program
+}
+
+/// Creates a new program that declares the given callables, with block 0 holding the given instructions.
+#[must_use]
+pub fn new_program_with_block(
+    callables: impl IntoIterator<Item = (CallableId, Callable)>,
+    instrs: Vec<Instruction>,
+) -> Program {
+    new_program_with_blocks(callables, [instrs])
+}
+
+#[must_use]
+pub fn qubit(id: u32) -> Operand {
+    Operand::Literal(Literal::Qubit(id))
+}
+
+#[must_use]
+pub fn result(id: u32) -> Operand {
+    Operand::Literal(Literal::Result(id))
+}
+
+#[must_use]
+pub fn var(id: u32, ty: Ty) -> Variable {
+    Variable {
+        variable_id: VariableId(id),
+        ty,
+    }
+}
+
+#[must_use]
+pub fn bool_var(id: u32) -> Variable {
+    var(id, Ty::Boolean)
+}
+
+#[must_use]
+pub fn int_var(id: u32) -> Variable {
+    var(id, Ty::Integer)
+}
+
 #[must_use]
 pub fn bell_program() -> Program {
     let mut program = Program::default();
@@ -0,0 +1,135 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use std::fmt::Write;
+
+use crate::rir::{BlockId, Instruction, Program, Variable};
+
+/// An edge of the control-flow graph, with the condition under which it is taken, if any.
+struct Edge {
+    from: BlockId,
+    to: BlockId,
+    label: Option<String>,
+}
+
+/// Given a program, return its control-flow graph in the Graphviz DOT format.
+/// Each block is a node labeled with its ID, its name if any and the number of instructions in it, and
+/// the edges of branches are labeled with the condition under which they are taken.
+#[must_use]
+pub fn to_dot(program: &Program) -> String {
+    let mut dot = String::from("digraph program {\n    node [shape=box];\n");
+    for (block_id, block) in program.blocks.iter() {
+        let label = block_label(program, block_id, block.0.len(), "\\n");
+        writeln!(
+            dot,
+            "    {} [label=\"{}\"];",
+            node(block_id),
+            escape_dot(&label)
+        )
+        .expect("writing to string should succeed");
+    }
+    for edge in edges(program) {
+        write!(dot, "    {} -> {}", node(edge.from), node(edge.to))
+            .expect("writing to string should succeed");
+        if let Some(label) = edge.label {
+            write!(dot, " [label=\"{}\"]", escape_dot(&label))
+                .expect("writing to string should succeed");
+        }
+        dot.push_str(";\n");
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+/// Given a program, return its control-flow graph as a Mermaid flowchart.
+/// Nodes and edges are labeled the same way as in [`to_dot`].
+#[must_use]
+pub fn to_mermaid(program: &Program) -> String {
+    let mut mermaid = String::from("flowchart TD\n");
+    for (block_id, block) in program.blocks.iter() {
+        let label = block_label(program, block_id, block.0.len(), "<br/>");
+        writeln!(
+            mermaid,
+            "    {}[\"{}\"]",
+            node(block_id),
+            escape_mermaid(&label)
+        )
+        .expect("writing to string should succeed");
+    }
+    for edge in edges(program) {
+        match edge.label {
+            Some(label) => writeln!(
+                mermaid,
+                "    {} -- \"{}\" --> {}",
+                node(edge.from),
+                escape_mermaid(&label),
+                node(edge.to)
+            ),
+            None => writeln!(mermaid, "    {} --> {}", node(edge.from), node(edge.to)),
+        }
+        .expect("writing to string should succeed");
+    }
+    mermaid
+}
+
+fn node(block_id: BlockId) -> String {
+    format!("block_{}", block_id.0)
+}
+
+fn block_label(program: &Program, block_id: BlockId, len: usize, line_break: &str) -> String {
+    let name = match program.block_names.get(block_id) {
+        Some(name) => format!(": {name}"),
+        None => String::new(),
+    };
+    let plural = if len == 1 { "" } else { "s" };
+    format!(
+        "Block {}{name}{line_break}{len} instruction{plural}",
+        block_id.0
+    )
+}
+
+fn condition(program: &Program, variable: Variable) -> String {
+    match program.variable_names.get(variable.variable_id) {
+        Some(name) => format!("{name} ({variable})"),
+        None => variable.to_string(),
+    }
+}
+
+fn edges(program: &Program) -> Vec<Edge> {
+    let mut edges = Vec::new();
+    for (block_id, block) in program.blocks.iter() {
+        match block.0.last() {
+            Some(Instruction::Branch(cond, true_block, false_block)) => {
+                let cond = condition(program, *cond);
+                edges.push(Edge {
+                    from: block_id,
+                    to: *true_block,
+                    label: Some(cond.clone()),
+                });
+                edges.push(Edge {
+                    from: block_id,
+                    to: *false_block,
+                    label: Some(format!("!{cond}")),
+                });
+            }
+            Some(Instruction::Jump(target)) => edges.push(Edge {
+                from: block_id,
+                to: *target,
+                label: None,
+            }),
+            _ => {}
+        }
+    }
+    edges
+}
+
+fn escape_dot(text: &str) -> String {
+    text.replace('"', "\\\"")
+}
+
+fn escape_mermaid(text: &str) -> String {
+    text.replace('"', "#quot;")
+}
@@ -0,0 +1,121 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#![allow(clippy::needless_raw_string_hashes)]
+
+use expect_test::expect;
+
+use super::{to_dot, to_mermaid};
+use crate::rir::{BlockId, Program, VariableId};
+
+fn program() -> Program {
+    let mut program = "
+        callables:
+            Callable 0: Callable:
+                name: main
+                call_type: Regular
+                input_type: <VOID>
+                output_type: <VOID>
+                body: 0
+            Callable 1: Callable:
+                name: __quantum__qis__mresetz__body
+                call_type: Measurement
+                input_type:
+                    [0]: Qubit
+                    [1]: Result
+                output_type: <VOID>
+                body: <NONE>
+            Callable 2: Callable:
+                name: __quantum__qis__read_result__body
+                call_type: Readout
+                input_type:
+                    [0]: Result
+                output_type: Boolean
+                body: <NONE>
+            Callable 3: Callable:
+                name: __quantum__qis__x__body
+                call_type: Regular
+                input_type:
+                    [0]: Qubit
+                output_type: <VOID>
+                body: <NONE>
+        blocks:
+            Block 0: Block:
+                Call id(1), args( Qubit(0), Result(0), )
+                Variable(0, Boolean) = Call id(2), args( Result(0), )
+                Branch Variable(0, Boolean), 1, 2
+            Block 1: Block:
+                Call id(3), args( Qubit(1), )
+                Jump(2)
+            Block 2: Block:
+                Variable(1, Boolean) = Store Bool(true)
+                Branch Variable(1, Boolean), 3, 3
+            Block 3: Block:
+                Return
+    "
+    .parse::<Program>()
+    .expect("program should parse");
+    program.block_names.insert(BlockId(0), "entry".into());
+    program.block_names.insert(BlockId(1), "if_then".into());
+    program
+        .variable_names
+        .insert(VariableId(0), "is_one".into());
+    program
+}
+
+#[test]
+fn dot_labels_blocks_and_branches() {
+    expect![[r#"
+        digraph program {
+            node [shape=box];
+            block_0 [label="Block 0: entry\n3 instructions"];
+            block_1 [label="Block 1: if_then\n2 instructions"];
+            block_2 [label="Block 2\n2 instructions"];
+            block_3 [label="Block 3\n1 instruction"];
+            block_0 -> block_1 [label="is_one (Variable(0, Boolean))"];
+            block_0 -> block_2 [label="!is_one (Variable(0, Boolean))"];
+            block_1 -> block_2;
+            block_2 -> block_3 [label="Variable(1, Boolean)"];
+            block_2 -> block_3 [label="!Variable(1, Boolean)"];
+        }
+    "#]]
+    .assert_eq(&to_dot(&program()));
+}
+
+#[test]
+fn mermaid_labels_blocks_and_branches() {
+    expect![[r#"
+        flowchart TD
+            block_0["Block 0: entry<br/>3 instructions"]
+            block_1["Block 1: if_then<br/>2 instructions"]
+            block_2["Block 2<br/>2 instructions"]
+            block_3["Block 3<br/>1 instruction"]
+            block_0 -- "is_one (Variable(0, Boolean))" --> block_1
+            block_0 -- "!is_one (Variable(0, Boolean))" --> block_2
+            block_1 --> block_2
+            block_2 -- "Variable(1, Boolean)" --> block_3
+            block_2 -- "!Variable(1, Boolean)" --> block_3
+    "#]]
+    .assert_eq(&to_mermaid(&program()));
+}
+
+#[test]
+fn labels_are_escaped() {
+    let mut program = Program::new();
+    program
+        .blocks
+        .insert(BlockId(0), crate::rir::Block::default());
+    program.block_names.insert(BlockId(0), "say \"hi\"".into());
+    expect![[r#"
+        digraph program {
+            node [shape=box];
+            block_0 [label="Block 0: say \"hi\"\n0 instructions"];
+        }
+    "#]]
+    .assert_eq(&to_dot(&program));
+    expect![[r#"
+        flowchart TD
+            block_0["Block 0: say #quot;hi#quot;<br/>0 instructions"]
+    "#]]
+    .assert_eq(&to_mermaid(&program));
+}
@@ -1,50 +1,117 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
+mod base_fallback;
 mod build_dominator_graph;
+mod constant_fold;
+mod cse;
+mod dead_block_elim;
+mod decompose_gates;
 mod defer_meas;
+mod output_recording_check;
+mod peephole;
 mod reindex_qubits;
 mod remap_block_ids;
+mod renumber_ids;
+mod reuse_qubits;
 mod simplify_control_flow;
 mod ssa_check;
+mod ssa_destruct;
 mod ssa_transform;
+mod terminate_qubits;
 mod type_check;
 mod unreachable_code_check;
 
+pub use base_fallback::{convert_to_base_profile, BaseFallbackError};
 use build_dominator_graph::build_dominator_graph;
+pub use constant_fold::fold_constants;
+pub use cse::eliminate_common_subexpressions;
+pub use dead_block_elim::eliminate_dead_blocks;
+pub use decompose_gates::decompose_to_gate_set;
 use defer_meas::defer_measurements;
+pub use output_recording_check::check_output_recording;
+pub use peephole::apply_peephole_optimizations;
 use qsc_data_structures::target::TargetCapabilityFlags;
 use reindex_qubits::reindex_qubits;
 use remap_block_ids::remap_block_ids;
+pub use renumber_ids::renumber_ids;
+use reuse_qubits::reuse_qubits;
 use simplify_control_flow::simplify_control_flow;
 use ssa_check::check_ssa_form;
-use ssa_transform::transform_to_ssa;
+pub use ssa_destruct::transform_from_ssa;
+pub use ssa_transform::transform_to_ssa;
+pub use terminate_qubits::terminate_qubits;
 pub use type_check::check_types;
 pub use unreachable_code_check::check_unreachable_code;
 
-use crate::{rir::Program, utils::build_predecessors_map};
+use crate::{
+    rir::{Program, QubitTermination},
+    utils::build_predecessors_map,
+};
 
 /// Run the default set of RIR check and transformation passes.
 /// This includes:
+/// - Eliminating dead and empty blocks
 /// - Simplifying control flow
 /// - Checking for unreachable code
 /// - Checking types
 /// - Remapping block IDs
 /// - Transforming the program to SSA form
+/// - Folding constants and removing the branches and definitions they make dead, then simplifying control flow and
+///   remapping block IDs again
 /// - Checking that the program is in SSA form
+/// - Eliminating common subexpressions within each block
+/// - Applying the gate-level peephole optimizations enabled in the program's configuration
+/// - Decomposing calls to gates outside of the gate set in the program's configuration, if any, into gates in the set
 /// - If the target has no reset capability, reindexing qubit IDs and removing resets.
 /// - If the target has no mid-program measurement capability, deferring measurements to the end of the program.
+/// - If the program's configuration asks for it, the target has the reset capability and the program is a single
+///   block, rescheduling instructions and reusing the IDs of reset qubits.
+/// - Measuring or resetting the qubits that are not terminated by the end of the program, if the program's
+///   configuration asks for it.
+/// - Transforming the program out of SSA form, so that no phi nodes reach consumers of the program
+/// - Checking that the output recording calls still match the output positions of the program
 pub fn check_and_transform(program: &mut Program) {
+    check_and_transform_with_observer(program, &mut |_, _| {});
+}
+
+/// Run the default set of RIR check and transformation passes, as in `check_and_transform`, invoking
+/// `observer` with the name of each pass and the state of the program after that pass has run.
+/// This allows callers to inspect or compare intermediate programs without duplicating the pass ordering.
+pub fn check_and_transform_with_observer(
+    program: &mut Program,
+    observer: &mut dyn FnMut(&str, &Program),
+) {
+    eliminate_dead_blocks(program);
+    observer("eliminate_dead_blocks", program);
     simplify_control_flow(program);
+    observer("simplify_control_flow", program);
     check_unreachable_code(program);
     check_types(program);
     remap_block_ids(program);
+    observer("remap_block_ids", program);
     let preds = build_predecessors_map(program);
     transform_to_ssa(program, &preds);
+    observer("transform_to_ssa", program);
+    fold_constants(program);
+    observer("fold_constants", program);
+    simplify_control_flow(program);
+    observer("simplify_control_flow", program);
+    // Folding constants removes the blocks that branches on constant conditions no longer reach, so the remaining
+    // blocks are renumbered again to keep their IDs contiguous and in order.
+    remap_block_ids(program);
+    observer("remap_block_ids", program);
+    let preds = build_predecessors_map(program);
     let doms = build_dominator_graph(program, &preds);
     check_ssa_form(program, &preds, &doms);
+    eliminate_common_subexpressions(program);
+    observer("eliminate_common_subexpressions", program);
     check_unreachable_code(program);
     check_types(program);
+    apply_peephole_optimizations(program);
+    observer("apply_peephole_optimizations", program);
+    decompose_to_gate_set(program);
+    observer("decompose_to_gate_set", program);
 
     // Run the RIR passes that are necessary for targets with no mid-program measurement.
     // This requires that qubits are not reused after measurement or reset, so qubit ids must be reindexed.
@@ -56,8 +123,27 @@ pub fn check_and_transform(program: &mut Program) {
         .contains(TargetCapabilityFlags::QubitReset)
     {
         reindex_qubits(program);
+        observer("reindex_qubits", program);
     }
     if program.config.capabilities == TargetCapabilityFlags::empty() {
         defer_measurements(program);
+        observer("defer_measurements", program);
+    }
+    // Without reset, a qubit can't be returned to a known state once it has been used, so no qubit ID can be reused.
+    if program.config.reuse_qubits
+        && program
+            .config
+            .capabilities
+            .contains(TargetCapabilityFlags::QubitReset)
+    {
+        reuse_qubits(program);
+        observer("reuse_qubits", program);
+    }
+    if program.config.qubit_termination != QubitTermination::None {
+        terminate_qubits(program);
+        observer("terminate_qubits", program);
     }
+    transform_from_ssa(program);
+    observer("transform_from_ssa", program);
+    check_output_recording(program);
 }
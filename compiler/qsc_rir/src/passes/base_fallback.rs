@@ -0,0 +1,428 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use qsc_data_structures::fx::{FxHashMap, FxHashSet};
+use qsc_data_structures::target::TargetCapabilityFlags;
+use thiserror::Error;
+
+use super::decompose_gates::Gate;
+use crate::rir::{
+    Block, BlockId, Callable, CallableId, CallableType, Instruction, Literal, Operand, Program, Ty,
+    Variable, VariableId,
+};
+
+/// The reasons an adaptive program can't be converted into a base profile program.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum BaseFallbackError {
+    #[error("block {} is part of a loop", .0.0)]
+    Loop(BlockId),
+    #[error("instruction `{instruction}` in block {} computes a value at runtime", .block.0)]
+    ClassicalInstruction { block: BlockId, instruction: String },
+    #[error("branch at the end of block {} doesn't depend directly on a measurement result", .0.0)]
+    DynamicBranch(BlockId),
+    #[error("branch at the end of block {} is not a single if or if-else without nested branches", .0.0)]
+    UnstructuredBranch(BlockId),
+    #[error("call to `{callable}` in block {} can't be controlled on a measured qubit", .block.0)]
+    UncontrollableCall { block: BlockId, callable: String },
+    #[error("qubit {qubit} is used after its measurement into result {result}, which the branch at the end of block {} depends on", .block.0)]
+    ReusedMeasuredQubit {
+        block: BlockId,
+        qubit: u32,
+        result: u32,
+    },
+}
+
+/// Converts an adaptive program into a base profile program using the principle of deferred measurement, so that
+/// programs that only branch on measurement results can still run on targets without mid-program measurement.
+/// Each branch on a measurement result must be a single if or if-else whose blocks only apply gates; those gates are
+/// replaced with gates controlled on the measured qubit and the measurement is moved to the end of the program,
+/// leaving the entry callable as a single block. This requires that the measured qubit is not used again other than
+/// as the control, and that the program computes no classical values at runtime.
+///
+/// # Errors
+///
+/// Returns every reason the program can't be converted, in which case the program is left unchanged.
+pub fn convert_to_base_profile(program: &mut Program) -> Result<(), Vec<BaseFallbackError>> {
+    let entry_block = program
+        .get_callable(program.entry)
+        .body
+        .expect("entry callable should have a body");
+    let mut lowering = Lowering::new(program);
+    lowering.lower_blocks(entry_block);
+    if !lowering.errors.is_empty() {
+        return Err(lowering.errors);
+    }
+
+    let Lowering {
+        visited,
+        new_callables,
+        mut instrs,
+        mut deferred,
+        ..
+    } = lowering;
+
+    for (callable_id, callable) in new_callables {
+        program.callables.insert(callable_id, callable);
+    }
+
+    // Measurements that branches were deferred from are moved before the output recordings, which come last.
+    deferred.sort_unstable();
+    let mut moved = Vec::new();
+    for index in deferred.into_iter().rev() {
+        moved.push(instrs.remove(index));
+    }
+    moved.reverse();
+    let recordings_start = instrs
+        .iter()
+        .position(|instr| {
+            matches!(instr, Instruction::Call(callable_id, _, _)
+                if program.get_callable(*callable_id).call_type == CallableType::OutputRecording)
+        })
+        .unwrap_or(instrs.len());
+    instrs.splice(recordings_start..recordings_start, moved);
+    instrs.push(Instruction::Return);
+
+    for block_id in visited {
+        program.blocks.remove(block_id);
+    }
+    program.blocks.insert(entry_block, Block(instrs));
+
+    // Results are no longer read at runtime, so the readout callables are unused.
+    let used_callables = program
+        .blocks
+        .values()
+        .flat_map(|block| &block.0)
+        .filter_map(|instr| match instr {
+            Instruction::Call(callable_id, _, _) => Some(*callable_id),
+            _ => None,
+        })
+        .collect::<FxHashSet<_>>();
+    program.callables.retain(|callable_id, callable| {
+        callable.call_type != CallableType::Readout || used_callables.contains(&callable_id)
+    });
+    program.config.capabilities = TargetCapabilityFlags::empty();
+    Ok(())
+}
+
+struct Lowering<'a> {
+    program: &'a Program,
+    gates: FxHashMap<CallableId, Gate>,
+    gate_ids: FxHashMap<Gate, CallableId>,
+    new_callables: Vec<(CallableId, Callable)>,
+    next_callable_id: CallableId,
+    visited: FxHashSet<BlockId>,
+    /// The instructions of the converted program, in order.
+    instrs: Vec<Instruction>,
+    /// The indices in `instrs` of the measurements that are moved to the end of the program.
+    deferred: Vec<usize>,
+    /// The result each boolean variable was read from, and whether the variable is its negation.
+    conditions: FxHashMap<VariableId, (u32, bool)>,
+    /// The index in `instrs` and the qubit of the measurement into each result.
+    measurements: FxHashMap<u32, (usize, u32)>,
+    /// The result of the latest measurement of each qubit, and whether the qubit was used since.
+    measured_qubits: FxHashMap<u32, (u32, bool)>,
+    /// The qubits that control gates from a branch, with the result and block of that branch.
+    controls: FxHashMap<u32, (u32, BlockId)>,
+    errors: Vec<BaseFallbackError>,
+}
+
+impl<'a> Lowering<'a> {
+    fn new(program: &'a Program) -> Self {
+        let mut gates = FxHashMap::default();
+        let mut gate_ids = FxHashMap::default();
+        for (callable_id, callable) in program.callables.iter() {
+            if callable.call_type != CallableType::Regular || callable.body.is_some() {
+                continue;
+            }
+            if let Some(gate) = Gate::ALL
+                .into_iter()
+                .find(|gate| gate.name() == callable.name)
+            {
+                gates.insert(callable_id, gate);
+                gate_ids.insert(gate, callable_id);
+            }
+        }
+        let next_callable_id = program
+            .callables
+            .iter()
+            .map(|(callable_id, _)| callable_id.successor())
+            .max()
+            .unwrap_or_default();
+        Self {
+            program,
+            gates,
+            gate_ids,
+            new_callables: Vec::new(),
+            next_callable_id,
+            visited: FxHashSet::default(),
+            instrs: Vec::new(),
+            deferred: Vec::new(),
+            conditions: FxHashMap::default(),
+            measurements: FxHashMap::default(),
+            measured_qubits: FxHashMap::default(),
+            controls: FxHashMap::default(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Follows the control flow from the given block until the program returns, lowering each block on the way.
+    fn lower_blocks(&mut self, mut block_id: BlockId) {
+        'blocks: loop {
+            if !self.visited.insert(block_id) {
+                self.errors.push(BaseFallbackError::Loop(block_id));
+                return;
+            }
+            for instr in &self.program.get_block(block_id).0 {
+                match instr {
+                    Instruction::Return => return,
+                    Instruction::Jump(next) => {
+                        block_id = *next;
+                        continue 'blocks;
+                    }
+                    Instruction::Branch(condition, if_true, if_false) => {
+                        match self.lower_branch(block_id, *condition, *if_true, *if_false) {
+                            Some(join) => {
+                                block_id = join;
+                                continue 'blocks;
+                            }
+                            None => return,
+                        }
+                    }
+                    _ => self.lower_instr(block_id, instr),
+                }
+            }
+            return;
+        }
+    }
+
+    fn lower_instr(&mut self, block_id: BlockId, instr: &Instruction) {
+        match instr {
+            Instruction::Call(callable_id, args, output) => {
+                let callable = self.program.get_callable(*callable_id);
+                match (callable.call_type, args.as_slice(), output) {
+                    (
+                        CallableType::Readout,
+                        [Operand::Literal(Literal::Result(result))],
+                        Some(Variable {
+                            variable_id,
+                            ty: Ty::Boolean,
+                        }),
+                    ) => {
+                        self.conditions.insert(*variable_id, (*result, false));
+                        return;
+                    }
+                    (
+                        CallableType::Measurement,
+                        [Operand::Literal(Literal::Qubit(qubit)), Operand::Literal(Literal::Result(result))],
+                        None,
+                    ) => {
+                        self.use_qubits(args);
+                        self.measurements
+                            .insert(*result, (self.instrs.len(), *qubit));
+                        self.measured_qubits.insert(*qubit, (*result, false));
+                        self.instrs.push(instr.clone());
+                        return;
+                    }
+                    (_, _, None) if is_static(args) => {
+                        self.use_qubits(args);
+                        self.instrs.push(instr.clone());
+                        return;
+                    }
+                    _ => {}
+                }
+            }
+            Instruction::LogicalNot(Operand::Variable(value), output) => {
+                if let Some(&(result, negated)) = self.conditions.get(&value.variable_id) {
+                    self.conditions
+                        .insert(output.variable_id, (result, !negated));
+                    return;
+                }
+            }
+            _ => {}
+        }
+        self.errors.push(BaseFallbackError::ClassicalInstruction {
+            block: block_id,
+            instruction: instr.to_string(),
+        });
+    }
+
+    /// Lowers the blocks of a branch into gates controlled on the measured qubit, returning the block where the
+    /// branches join again, or `None` if the branch can't be lowered.
+    fn lower_branch(
+        &mut self,
+        block_id: BlockId,
+        condition: Variable,
+        if_true: BlockId,
+        if_false: BlockId,
+    ) -> Option<BlockId> {
+        let Some(&(result, negated)) = self.conditions.get(&condition.variable_id) else {
+            self.errors.push(BaseFallbackError::DynamicBranch(block_id));
+            return None;
+        };
+        let Some(&(index, qubit)) = self.measurements.get(&result) else {
+            self.errors.push(BaseFallbackError::DynamicBranch(block_id));
+            return None;
+        };
+
+        // Each arm is paired with whether it runs when the measured qubit is zero rather than one.
+        let (arms, join) = match (self.jump_target(if_true), self.jump_target(if_false)) {
+            (Some(join), _) if join == if_false => (vec![(if_true, negated)], if_false),
+            (_, Some(join)) if join == if_true => (vec![(if_false, !negated)], if_true),
+            (Some(true_join), Some(false_join)) if true_join == false_join => {
+                (vec![(if_true, negated), (if_false, !negated)], true_join)
+            }
+            _ => {
+                self.errors
+                    .push(BaseFallbackError::UnstructuredBranch(block_id));
+                return None;
+            }
+        };
+
+        if self.measured_qubits.get(&qubit) != Some(&(result, false)) {
+            self.errors.push(BaseFallbackError::ReusedMeasuredQubit {
+                block: block_id,
+                qubit,
+                result,
+            });
+        }
+
+        let control = Operand::Literal(Literal::Qubit(qubit));
+        for (arm, flipped) in arms {
+            if !self.visited.insert(arm) {
+                self.errors.push(BaseFallbackError::Loop(arm));
+                return None;
+            }
+            if flipped {
+                self.push_gate(Gate::X, vec![control]);
+            }
+            let block = self.program.get_block(arm);
+            for instr in &block.0[..block.0.len() - 1] {
+                self.lower_controlled_instr(arm, instr, qubit, result, block_id);
+            }
+            if flipped {
+                self.push_gate(Gate::X, vec![control]);
+            }
+        }
+
+        if !self.deferred.contains(&index) {
+            self.deferred.push(index);
+        }
+        self.controls.insert(qubit, (result, block_id));
+        Some(join)
+    }
+
+    fn lower_controlled_instr(
+        &mut self,
+        arm: BlockId,
+        instr: &Instruction,
+        control: u32,
+        result: u32,
+        branch: BlockId,
+    ) {
+        let Instruction::Call(callable_id, args, output) = instr else {
+            self.errors.push(BaseFallbackError::ClassicalInstruction {
+                block: arm,
+                instruction: instr.to_string(),
+            });
+            return;
+        };
+        if output.is_some() || !is_static(args) {
+            self.errors.push(BaseFallbackError::ClassicalInstruction {
+                block: arm,
+                instruction: instr.to_string(),
+            });
+            return;
+        }
+        let Some(steps) = self
+            .gates
+            .get(callable_id)
+            .and_then(|gate| controlled(*gate, Operand::Literal(Literal::Qubit(control)), args))
+        else {
+            self.errors.push(BaseFallbackError::UncontrollableCall {
+                block: arm,
+                callable: self.program.get_callable(*callable_id).name.clone(),
+            });
+            return;
+        };
+
+        if args.contains(&Operand::Literal(Literal::Qubit(control))) {
+            self.errors.push(BaseFallbackError::ReusedMeasuredQubit {
+                block: branch,
+                qubit: control,
+                result,
+            });
+        }
+        self.use_qubits(args);
+        for (gate, args) in steps {
+            self.push_gate(gate, args);
+        }
+    }
+
+    /// Records a use of the qubits among the arguments, other than as the control of a lowered branch.
+    fn use_qubits(&mut self, args: &[Operand]) {
+        for arg in args {
+            if let Operand::Literal(Literal::Qubit(qubit)) = arg {
+                if let Some((result, block)) = self.controls.remove(qubit) {
+                    self.errors.push(BaseFallbackError::ReusedMeasuredQubit {
+                        block,
+                        qubit: *qubit,
+                        result,
+                    });
+                }
+                if let Some((_, used)) = self.measured_qubits.get_mut(qubit) {
+                    *used = true;
+                }
+            }
+        }
+    }
+
+    fn push_gate(&mut self, gate: Gate, args: Vec<Operand>) {
+        let callable_id = *self.gate_ids.entry(gate).or_insert_with(|| {
+            let callable_id = self.next_callable_id;
+            self.next_callable_id = callable_id.successor();
+            self.new_callables.push((callable_id, gate.decl()));
+            callable_id
+        });
+        self.instrs.push(Instruction::Call(callable_id, args, None));
+    }
+
+    /// The block that the given block unconditionally jumps to at its end, if any.
+    fn jump_target(&self, block_id: BlockId) -> Option<BlockId> {
+        match self.program.get_block(block_id).0.last() {
+            Some(Instruction::Jump(target)) => Some(*target),
+            _ => None,
+        }
+    }
+}
+
+fn is_static(args: &[Operand]) -> bool {
+    args.iter().all(|arg| matches!(arg, Operand::Literal(_)))
+}
+
+/// The gates that apply the given gate only when the control qubit is one, or `None` if the gate can't be controlled.
+/// Rotations are controlled by applying half of the rotation in each direction around gates that flip its axis.
+fn controlled(gate: Gate, control: Operand, args: &[Operand]) -> Option<Vec<(Gate, Vec<Operand>)>> {
+    match gate {
+        Gate::X => Some(vec![(Gate::Cx, vec![control, args[0]])]),
+        Gate::Y => Some(vec![(Gate::Cy, vec![control, args[0]])]),
+        Gate::Z => Some(vec![(Gate::Cz, vec![control, args[0]])]),
+        Gate::Cx => Some(vec![(Gate::Ccx, vec![control, args[0], args[1]])]),
+        Gate::Rx | Gate::Ry | Gate::Rz => {
+            let Operand::Literal(Literal::Double(angle)) = args[0] else {
+                return None;
+            };
+            let flip = if gate == Gate::Rx { Gate::Cz } else { Gate::Cx };
+            let half = |angle: f64| Operand::Literal(Literal::Double(angle / 2.0));
+            Some(vec![
+                (gate, vec![half(angle), args[1]]),
+                (flip, vec![control, args[1]]),
+                (gate, vec![half(-angle), args[1]]),
+                (flip, vec![control, args[1]]),
+            ])
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,386 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#![allow(clippy::needless_raw_string_hashes)]
+
+use expect_test::{expect, Expect};
+use qsc_data_structures::target::TargetCapabilityFlags;
+
+use crate::{
+    builder::{
+        cx_decl, h_decl, mresetz_decl, new_program_with_blocks, qubit, read_result_decl, result,
+        result_record_decl, rz_decl, teleport_program, x_decl,
+    },
+    rir::{
+        BlockId, Callable, CallableId, CallableType, Instruction, Literal, Operand, Program, Ty,
+        Variable, VariableId,
+    },
+};
+
+use super::convert_to_base_profile;
+
+const X: CallableId = CallableId(0);
+const H: CallableId = CallableId(1);
+const CX: CallableId = CallableId(2);
+const RZ: CallableId = CallableId(3);
+const MRESETZ: CallableId = CallableId(4);
+const READ_RESULT: CallableId = CallableId(5);
+const RECORD: CallableId = CallableId(6);
+const MAIN: CallableId = CallableId(7);
+
+fn boolean(id: u32) -> Variable {
+    Variable {
+        variable_id: VariableId(id),
+        ty: Ty::Boolean,
+    }
+}
+
+fn measure(qubit_id: u32, result_id: u32) -> Instruction {
+    Instruction::Call(MRESETZ, vec![qubit(qubit_id), result(result_id)], None)
+}
+
+fn read(result_id: u32, variable_id: u32) -> Instruction {
+    Instruction::Call(
+        READ_RESULT,
+        vec![result(result_id)],
+        Some(boolean(variable_id)),
+    )
+}
+
+fn record(result_id: u32) -> Instruction {
+    Instruction::Call(
+        RECORD,
+        vec![result(result_id), Operand::Literal(Literal::Pointer)],
+        None,
+    )
+}
+
+fn adaptive_program(blocks: Vec<Vec<Instruction>>) -> Program {
+    let mut program = new_program_with_blocks(
+        [
+            (X, x_decl()),
+            (H, h_decl()),
+            (CX, cx_decl()),
+            (RZ, rz_decl()),
+            (MRESETZ, mresetz_decl()),
+            (READ_RESULT, read_result_decl()),
+            (RECORD, result_record_decl()),
+            (
+                MAIN,
+                Callable {
+                    name: "main".to_string(),
+                    input_type: Vec::new(),
+                    output_type: None,
+                    body: Some(BlockId(0)),
+                    call_type: CallableType::Regular,
+                },
+            ),
+        ],
+        blocks,
+    );
+    program.config.capabilities = TargetCapabilityFlags::Adaptive;
+    program.num_qubits = 3;
+    program.num_results = 3;
+    program.entry = MAIN;
+    program
+}
+
+fn check(mut program: Program, expect: &Expect) {
+    let original = program.to_string();
+    let actual = match convert_to_base_profile(&mut program) {
+        Ok(()) => {
+            assert!(program.config.is_base(), "program should be base profile");
+            let mut actual = String::new();
+            for (callable_id, callable) in program.callables.iter() {
+                actual += &format!("Callable {}: {}\n", callable_id.0, callable.name);
+            }
+            for (block_id, block) in program.blocks.iter() {
+                actual += &format!("Block {}: {block}\n", block_id.0);
+            }
+            actual
+        }
+        Err(errors) => {
+            assert_eq!(program.to_string(), original, "program should be unchanged");
+            errors
+                .iter()
+                .map(|error| error.to_string() + "\n")
+                .collect::<String>()
+        }
+    };
+    expect.assert_eq(&actual);
+}
+
+#[test]
+fn teleport_program_corrections_are_controlled_on_measured_qubits() {
+    check(
+        teleport_program(),
+        &expect![[r#"
+            Callable 0: __quantum__qis__h__body
+            Callable 1: __quantum__qis__z__body
+            Callable 2: __quantum__qis__x__body
+            Callable 3: __quantum__qis__cx__body
+            Callable 4: __quantum__qis__mresetz__body
+            Callable 6: __quantum__rt__result_record_output
+            Callable 7: main
+            Callable 8: __quantum__qis__cz__body
+            Block 0: Block:
+                Call id(2), args( Qubit(0), )
+                Call id(0), args( Qubit(2), )
+                Call id(3), args( Qubit(2), Qubit(1), )
+                Call id(3), args( Qubit(0), Qubit(2), )
+                Call id(0), args( Qubit(0), )
+                Call id(8), args( Qubit(0), Qubit(1), )
+                Call id(3), args( Qubit(2), Qubit(1), )
+                Call id(4), args( Qubit(1), Result(2), )
+                Call id(4), args( Qubit(0), Result(0), )
+                Call id(4), args( Qubit(2), Result(1), )
+                Call id(6), args( Result(2), Pointer, )
+                Return
+        "#]],
+    );
+}
+
+#[test]
+fn else_branch_is_controlled_on_flipped_qubit() {
+    let program = adaptive_program(vec![
+        vec![
+            Instruction::Call(H, vec![qubit(0)], None),
+            measure(0, 0),
+            read(0, 0),
+            Instruction::Branch(boolean(0), BlockId(1), BlockId(2)),
+        ],
+        vec![
+            Instruction::Call(X, vec![qubit(1)], None),
+            Instruction::Jump(BlockId(3)),
+        ],
+        vec![
+            Instruction::Call(
+                RZ,
+                vec![Operand::Literal(Literal::Double(0.5)), qubit(1)],
+                None,
+            ),
+            Instruction::Jump(BlockId(3)),
+        ],
+        vec![measure(1, 1), record(0), record(1), Instruction::Return],
+    ]);
+    check(
+        program,
+        &expect![[r#"
+            Callable 0: __quantum__qis__x__body
+            Callable 1: __quantum__qis__h__body
+            Callable 2: __quantum__qis__cx__body
+            Callable 3: __quantum__qis__rz__body
+            Callable 4: __quantum__qis__mresetz__body
+            Callable 6: __quantum__rt__result_record_output
+            Callable 7: main
+            Block 0: Block:
+                Call id(1), args( Qubit(0), )
+                Call id(2), args( Qubit(0), Qubit(1), )
+                Call id(0), args( Qubit(0), )
+                Call id(3), args( Double(0.25), Qubit(1), )
+                Call id(2), args( Qubit(0), Qubit(1), )
+                Call id(3), args( Double(-0.25), Qubit(1), )
+                Call id(2), args( Qubit(0), Qubit(1), )
+                Call id(0), args( Qubit(0), )
+                Call id(4), args( Qubit(1), Result(1), )
+                Call id(4), args( Qubit(0), Result(0), )
+                Call id(6), args( Result(0), Pointer, )
+                Call id(6), args( Result(1), Pointer, )
+                Return
+        "#]],
+    );
+}
+
+#[test]
+fn negated_condition_is_controlled_on_flipped_qubit() {
+    let program = adaptive_program(vec![
+        vec![
+            measure(0, 0),
+            read(0, 0),
+            Instruction::LogicalNot(Operand::Variable(boolean(0)), boolean(1)),
+            Instruction::Branch(boolean(1), BlockId(1), BlockId(2)),
+        ],
+        vec![
+            Instruction::Call(CX, vec![qubit(1), qubit(2)], None),
+            Instruction::Jump(BlockId(2)),
+        ],
+        vec![record(0), Instruction::Return],
+    ]);
+    check(
+        program,
+        &expect![[r#"
+            Callable 0: __quantum__qis__x__body
+            Callable 1: __quantum__qis__h__body
+            Callable 2: __quantum__qis__cx__body
+            Callable 3: __quantum__qis__rz__body
+            Callable 4: __quantum__qis__mresetz__body
+            Callable 6: __quantum__rt__result_record_output
+            Callable 7: main
+            Callable 8: __quantum__qis__ccx__body
+            Block 0: Block:
+                Call id(0), args( Qubit(0), )
+                Call id(8), args( Qubit(0), Qubit(1), Qubit(2), )
+                Call id(0), args( Qubit(0), )
+                Call id(4), args( Qubit(0), Result(0), )
+                Call id(6), args( Result(0), Pointer, )
+                Return
+        "#]],
+    );
+}
+
+#[test]
+fn qubit_used_between_measurement_and_branch_is_an_error() {
+    let program = adaptive_program(vec![
+        vec![
+            measure(0, 0),
+            Instruction::Call(H, vec![qubit(0)], None),
+            read(0, 0),
+            Instruction::Branch(boolean(0), BlockId(1), BlockId(2)),
+        ],
+        vec![
+            Instruction::Call(X, vec![qubit(1)], None),
+            Instruction::Jump(BlockId(2)),
+        ],
+        vec![Instruction::Return],
+    ]);
+    check(
+        program,
+        &expect![[r#"
+            qubit 0 is used after its measurement into result 0, which the branch at the end of block 0 depends on
+        "#]],
+    );
+}
+
+#[test]
+fn qubit_used_after_branch_is_an_error() {
+    let program = adaptive_program(vec![
+        vec![
+            measure(0, 0),
+            read(0, 0),
+            Instruction::Branch(boolean(0), BlockId(1), BlockId(2)),
+        ],
+        vec![
+            Instruction::Call(X, vec![qubit(1)], None),
+            Instruction::Jump(BlockId(2)),
+        ],
+        vec![
+            Instruction::Call(H, vec![qubit(0)], None),
+            Instruction::Return,
+        ],
+    ]);
+    check(
+        program,
+        &expect![[r#"
+            qubit 0 is used after its measurement into result 0, which the branch at the end of block 0 depends on
+        "#]],
+    );
+}
+
+#[test]
+fn every_unsupported_instruction_is_reported() {
+    let program = adaptive_program(vec![
+        vec![
+            measure(0, 0),
+            read(0, 0),
+            Instruction::Add(
+                Operand::Literal(Literal::Integer(1)),
+                Operand::Literal(Literal::Integer(2)),
+                Variable {
+                    variable_id: VariableId(1),
+                    ty: Ty::Integer,
+                },
+            ),
+            Instruction::Branch(boolean(0), BlockId(1), BlockId(2)),
+        ],
+        vec![
+            Instruction::Call(H, vec![qubit(1)], None),
+            Instruction::Call(
+                RZ,
+                vec![
+                    Operand::Variable(Variable {
+                        variable_id: VariableId(1),
+                        ty: Ty::Double,
+                    }),
+                    qubit(1),
+                ],
+                None,
+            ),
+            measure(1, 1),
+            Instruction::Jump(BlockId(2)),
+        ],
+        vec![Instruction::Return],
+    ]);
+    check(
+        program,
+        &expect![[r#"
+            instruction `Variable(1, Integer) = Add Integer(1), Integer(2)` in block 0 computes a value at runtime
+            call to `__quantum__qis__h__body` in block 1 can't be controlled on a measured qubit
+            instruction `Call id(3), args( Variable(1, Double), Qubit(1), )` in block 1 computes a value at runtime
+            call to `__quantum__qis__mresetz__body` in block 1 can't be controlled on a measured qubit
+        "#]],
+    );
+}
+
+#[test]
+fn nested_branch_is_an_error() {
+    let program = adaptive_program(vec![
+        vec![
+            measure(0, 0),
+            measure(1, 1),
+            read(0, 0),
+            read(1, 1),
+            Instruction::Branch(boolean(0), BlockId(1), BlockId(3)),
+        ],
+        vec![Instruction::Branch(boolean(1), BlockId(2), BlockId(3))],
+        vec![
+            Instruction::Call(X, vec![qubit(2)], None),
+            Instruction::Jump(BlockId(3)),
+        ],
+        vec![Instruction::Return],
+    ]);
+    check(
+        program,
+        &expect![[r#"
+            branch at the end of block 0 is not a single if or if-else without nested branches
+        "#]],
+    );
+}
+
+#[test]
+fn branch_on_computed_value_is_an_error() {
+    let program = adaptive_program(vec![
+        vec![
+            Instruction::Store(Operand::Literal(Literal::Bool(true)), boolean(0)),
+            Instruction::Branch(boolean(0), BlockId(1), BlockId(2)),
+        ],
+        vec![
+            Instruction::Call(X, vec![qubit(0)], None),
+            Instruction::Jump(BlockId(2)),
+        ],
+        vec![Instruction::Return],
+    ]);
+    check(
+        program,
+        &expect![[r#"
+            instruction `Variable(0, Boolean) = Store Bool(true)` in block 0 computes a value at runtime
+            branch at the end of block 0 doesn't depend directly on a measurement result
+        "#]],
+    );
+}
+
+#[test]
+fn loop_is_an_error() {
+    let program = adaptive_program(vec![
+        vec![Instruction::Jump(BlockId(1))],
+        vec![
+            Instruction::Call(X, vec![qubit(0)], None),
+            Instruction::Jump(BlockId(0)),
+        ],
+    ]);
+    check(
+        program,
+        &expect![[r#"
+            block 0 is part of a loop
+        "#]],
+    );
+}
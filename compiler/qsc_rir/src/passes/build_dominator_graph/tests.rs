@@ -203,8 +203,8 @@ fn dominator_graph_branch_and_loop() {
         Block 0 dominated by block 0,
         Block 1 dominated by block 0,
         Block 2 dominated by block 1,
-        Block 3 dominated by block 1,
-        Block 4 dominated by block 2,
+        Block 3 dominated by block 2,
+        Block 4 dominated by block 1,
     "#]]
     .assert_eq(&display_dominator_graph(&doms));
 }
@@ -0,0 +1,364 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use qsc_data_structures::fx::{FxHashMap, FxHashSet};
+
+use crate::{
+    rir::{ConditionCode, FcmpConditionCode, Instruction, Literal, Operand, Program, VariableId},
+    utils::{build_predecessors_map, for_each_operand_mut, get_block_successors},
+};
+
+/// Folds instructions whose operands are all constants and propagates the results, along with copies
+/// introduced by phi nodes whose incoming values all agree, to every use.
+/// The transformation to SSA already replaces stored variables with the values stored into them, so this pass
+/// picks up from there: branches on conditions that fold to a constant become jumps, blocks that can no longer be
+/// reached are removed along with the corresponding phi node arguments, and pure instructions whose results are
+/// never used are deleted, as are declarations of callables that are no longer called.
+/// This requires the program to be in SSA form with block IDs in reverse postorder, so that the only predecessors
+/// not visited before a block are the sources of loop back edges. Phi node arguments coming in through back edges
+/// are updated once every block has been visited.
+pub fn fold_constants(program: &mut Program) {
+    let preds = build_predecessors_map(program);
+    let roots = program
+        .callables
+        .values()
+        .filter_map(|callable| callable.body)
+        .collect::<FxHashSet<_>>();
+
+    let mut values: FxHashMap<VariableId, Operand> = FxHashMap::default();
+    let mut live_edges = FxHashSet::default();
+    let mut reachable = FxHashSet::default();
+    for (block_id, block) in program.blocks.iter_mut() {
+        let is_reachable = roots.contains(&block_id)
+            || preds.get(block_id).is_some_and(|block_preds| {
+                block_preds
+                    .iter()
+                    .any(|pred| live_edges.contains(&(*pred, block_id)))
+            });
+        if !is_reachable {
+            continue;
+        }
+        reachable.insert(block_id);
+
+        block.0.retain_mut(|instr| {
+            if let Instruction::Phi(args, _) = instr {
+                args.retain(|(_, pred)| {
+                    *pred >= block_id || live_edges.contains(&(*pred, block_id))
+                });
+            }
+            for_each_operand_mut(instr, |operand| {
+                if let Operand::Variable(variable) = operand {
+                    if let Some(value) = values.get(&variable.variable_id) {
+                        *operand = *value;
+                    }
+                }
+            });
+            if let Instruction::Branch(cond, true_target, false_target) = instr {
+                match values.get(&cond.variable_id) {
+                    Some(Operand::Literal(Literal::Bool(value))) => {
+                        *instr =
+                            Instruction::Jump(if *value { *true_target } else { *false_target });
+                    }
+                    Some(Operand::Variable(variable)) => *cond = *variable,
+                    _ => {}
+                }
+            }
+
+            match fold(instr) {
+                Some((variable_id, value)) => {
+                    values.insert(variable_id, value);
+                    false
+                }
+                None => true,
+            }
+        });
+
+        for successor in get_block_successors(block) {
+            live_edges.insert((block_id, successor));
+        }
+    }
+
+    // Back edges were not known to be live when their targets were visited, and the values flowing along them may
+    // have been folded since.
+    for (block_id, block) in program.blocks.iter_mut() {
+        for instr in &mut block.0 {
+            if let Instruction::Phi(args, _) = instr {
+                args.retain(|(_, pred)| live_edges.contains(&(*pred, block_id)));
+                for (operand, _) in args {
+                    if let Operand::Variable(variable) = operand {
+                        if let Some(value) = values.get(&variable.variable_id) {
+                            *operand = *value;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let unreachable = program
+        .blocks
+        .iter()
+        .filter_map(|(block_id, _)| (!reachable.contains(&block_id)).then_some(block_id))
+        .collect::<Vec<_>>();
+    for block_id in unreachable {
+        program.blocks.remove(block_id);
+        program.block_names.remove(block_id);
+    }
+
+    remove_unused_definitions(program);
+    remove_uncalled_callables(program);
+}
+
+/// Gets the value of the variable defined by the instruction, if it can be computed without executing the program.
+fn fold(instr: &Instruction) -> Option<(VariableId, Operand)> {
+    let (variable, value) = match instr {
+        Instruction::Phi(args, variable) => {
+            let (first, _) = args.first()?;
+            if args.iter().all(|(operand, _)| operand == first) {
+                return Some((variable.variable_id, *first));
+            }
+            return None;
+        }
+        Instruction::Add(Operand::Literal(lhs), Operand::Literal(rhs), variable) => {
+            (variable, int_op(lhs, rhs, |a, b| Some(a.wrapping_add(b)))?)
+        }
+        Instruction::Sub(Operand::Literal(lhs), Operand::Literal(rhs), variable) => {
+            (variable, int_op(lhs, rhs, |a, b| Some(a.wrapping_sub(b)))?)
+        }
+        Instruction::Mul(Operand::Literal(lhs), Operand::Literal(rhs), variable) => {
+            (variable, int_op(lhs, rhs, |a, b| Some(a.wrapping_mul(b)))?)
+        }
+        // Division by zero and overflowing division are left for the target to handle at runtime.
+        Instruction::Sdiv(Operand::Literal(lhs), Operand::Literal(rhs), variable) => {
+            (variable, int_op(lhs, rhs, i64::checked_div)?)
+        }
+        Instruction::Srem(Operand::Literal(lhs), Operand::Literal(rhs), variable) => {
+            (variable, int_op(lhs, rhs, i64::checked_rem)?)
+        }
+        Instruction::Shl(Operand::Literal(lhs), Operand::Literal(rhs), variable) => (
+            variable,
+            int_op(lhs, rhs, |a, b| a.checked_shl(b.try_into().ok()?))?,
+        ),
+        Instruction::Ashr(Operand::Literal(lhs), Operand::Literal(rhs), variable) => (
+            variable,
+            int_op(lhs, rhs, |a, b| a.checked_shr(b.try_into().ok()?))?,
+        ),
+        Instruction::BitwiseAnd(Operand::Literal(lhs), Operand::Literal(rhs), variable) => {
+            (variable, int_op(lhs, rhs, |a, b| Some(a & b))?)
+        }
+        Instruction::BitwiseOr(Operand::Literal(lhs), Operand::Literal(rhs), variable) => {
+            (variable, int_op(lhs, rhs, |a, b| Some(a | b))?)
+        }
+        Instruction::BitwiseXor(Operand::Literal(lhs), Operand::Literal(rhs), variable) => {
+            (variable, int_op(lhs, rhs, |a, b| Some(a ^ b))?)
+        }
+        Instruction::BitwiseNot(Operand::Literal(Literal::Integer(value)), variable) => {
+            (variable, Literal::Integer(!value))
+        }
+        Instruction::Fadd(Operand::Literal(lhs), Operand::Literal(rhs), variable) => {
+            (variable, double_op(lhs, rhs, |a, b| a + b)?)
+        }
+        Instruction::Fsub(Operand::Literal(lhs), Operand::Literal(rhs), variable) => {
+            (variable, double_op(lhs, rhs, |a, b| a - b)?)
+        }
+        Instruction::Fmul(Operand::Literal(lhs), Operand::Literal(rhs), variable) => {
+            (variable, double_op(lhs, rhs, |a, b| a * b)?)
+        }
+        Instruction::Fdiv(Operand::Literal(lhs), Operand::Literal(rhs), variable) => {
+            (variable, double_op(lhs, rhs, |a, b| a / b)?)
+        }
+        Instruction::LogicalNot(Operand::Literal(Literal::Bool(value)), variable) => {
+            (variable, Literal::Bool(!value))
+        }
+        #[allow(clippy::cast_precision_loss)]
+        Instruction::Sitofp(Operand::Literal(Literal::Integer(value)), variable) => {
+            (variable, Literal::Double(*value as f64))
+        }
+        // Converting a value outside the range of `Int` has no defined result, so it is left as it is.
+        Instruction::Fptosi(Operand::Literal(Literal::Double(value)), variable) => {
+            (variable, Literal::Integer(fptosi(*value)?))
+        }
+        Instruction::LogicalAnd(
+            Operand::Literal(Literal::Bool(lhs)),
+            Operand::Literal(Literal::Bool(rhs)),
+            variable,
+        ) => (variable, Literal::Bool(*lhs && *rhs)),
+        Instruction::LogicalOr(
+            Operand::Literal(Literal::Bool(lhs)),
+            Operand::Literal(Literal::Bool(rhs)),
+            variable,
+        ) => (variable, Literal::Bool(*lhs || *rhs)),
+        Instruction::Icmp(condition, Operand::Literal(lhs), Operand::Literal(rhs), variable) => {
+            (variable, Literal::Bool(icmp(*condition, lhs, rhs)?))
+        }
+        Instruction::Fcmp(
+            condition,
+            Operand::Literal(Literal::Double(lhs)),
+            Operand::Literal(Literal::Double(rhs)),
+            variable,
+        ) => (variable, Literal::Bool(fcmp(*condition, *lhs, *rhs))),
+        _ => return None,
+    };
+    Some((variable.variable_id, Operand::Literal(value)))
+}
+
+fn int_op(lhs: &Literal, rhs: &Literal, op: impl Fn(i64, i64) -> Option<i64>) -> Option<Literal> {
+    match (lhs, rhs) {
+        (Literal::Integer(lhs), Literal::Integer(rhs)) => op(*lhs, *rhs).map(Literal::Integer),
+        _ => None,
+    }
+}
+
+fn double_op(lhs: &Literal, rhs: &Literal, op: impl Fn(f64, f64) -> f64) -> Option<Literal> {
+    match (lhs, rhs) {
+        (Literal::Double(lhs), Literal::Double(rhs)) => Some(Literal::Double(op(*lhs, *rhs))),
+        _ => None,
+    }
+}
+
+/// Truncates a double to an integer, if the result is in the range of `i64`.
+#[allow(clippy::cast_possible_truncation)]
+fn fptosi(value: f64) -> Option<i64> {
+    const TWO_POW_63: f64 = 9_223_372_036_854_775_808.0;
+    (-TWO_POW_63..TWO_POW_63)
+        .contains(&value)
+        .then(|| value as i64)
+}
+
+fn icmp(condition: ConditionCode, lhs: &Literal, rhs: &Literal) -> Option<bool> {
+    match (lhs, rhs) {
+        (Literal::Integer(lhs), Literal::Integer(rhs)) => Some(match condition {
+            ConditionCode::Eq => lhs == rhs,
+            ConditionCode::Ne => lhs != rhs,
+            ConditionCode::Slt => lhs < rhs,
+            ConditionCode::Sle => lhs <= rhs,
+            ConditionCode::Sgt => lhs > rhs,
+            ConditionCode::Sge => lhs >= rhs,
+        }),
+        // Booleans are only compared for equality, since signed comparisons treat `true` as -1.
+        (Literal::Bool(lhs), Literal::Bool(rhs)) => match condition {
+            ConditionCode::Eq => Some(lhs == rhs),
+            ConditionCode::Ne => Some(lhs != rhs),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[allow(clippy::float_cmp)]
+fn fcmp(condition: FcmpConditionCode, lhs: f64, rhs: f64) -> bool {
+    let unordered = lhs.is_nan() || rhs.is_nan();
+    match condition {
+        FcmpConditionCode::False => false,
+        FcmpConditionCode::True => true,
+        FcmpConditionCode::Ordered => !unordered,
+        FcmpConditionCode::Unordered => unordered,
+        FcmpConditionCode::OrderedAndEqual => !unordered && lhs == rhs,
+        FcmpConditionCode::OrderedAndNotEqual => !unordered && lhs != rhs,
+        FcmpConditionCode::OrderedAndGreaterThan => !unordered && lhs > rhs,
+        FcmpConditionCode::OrderedAndGreaterThanOrEqual => !unordered && lhs >= rhs,
+        FcmpConditionCode::OrderedAndLessThan => !unordered && lhs < rhs,
+        FcmpConditionCode::OrderedAndLessThanOrEqual => !unordered && lhs <= rhs,
+        FcmpConditionCode::UnorderedOrEqual => unordered || lhs == rhs,
+        FcmpConditionCode::UnorderedOrNotEqual => unordered || lhs != rhs,
+        FcmpConditionCode::UnorderedOrGreaterThan => unordered || lhs > rhs,
+        FcmpConditionCode::UnorderedOrGreaterThanOrEqual => unordered || lhs >= rhs,
+        FcmpConditionCode::UnorderedOrLessThan => unordered || lhs < rhs,
+        FcmpConditionCode::UnorderedOrLessThanOrEqual => unordered || lhs <= rhs,
+    }
+}
+
+/// Removes instructions without side effects whose results are never used, repeating until no more can be removed
+/// since removing an instruction may leave the instructions that computed its operands unused.
+fn remove_unused_definitions(program: &mut Program) {
+    loop {
+        let mut used = FxHashSet::default();
+        for block in program.blocks.values_mut() {
+            for instr in &mut block.0 {
+                if let Instruction::Branch(cond, _, _) = instr {
+                    used.insert(cond.variable_id);
+                }
+                for_each_operand_mut(instr, |operand| {
+                    if let Operand::Variable(variable) = operand {
+                        used.insert(variable.variable_id);
+                    }
+                });
+            }
+        }
+
+        let mut removed = false;
+        for block in program.blocks.values_mut() {
+            block.0.retain(|instr| {
+                let keep = get_pure_definition(instr).is_none_or(|id| used.contains(&id));
+                removed |= !keep;
+                keep
+            });
+        }
+        if !removed {
+            break;
+        }
+    }
+}
+
+/// Gets the variable defined by an instruction that has no effect other than defining that variable.
+fn get_pure_definition(instr: &Instruction) -> Option<VariableId> {
+    match instr {
+        Instruction::Add(_, _, variable)
+        | Instruction::Sub(_, _, variable)
+        | Instruction::Mul(_, _, variable)
+        | Instruction::Shl(_, _, variable)
+        | Instruction::Ashr(_, _, variable)
+        | Instruction::Fadd(_, _, variable)
+        | Instruction::Fsub(_, _, variable)
+        | Instruction::Fmul(_, _, variable)
+        | Instruction::Fdiv(_, _, variable)
+        | Instruction::Fcmp(_, _, _, variable)
+        | Instruction::Icmp(_, _, _, variable)
+        | Instruction::LogicalNot(_, variable)
+        | Instruction::LogicalAnd(_, _, variable)
+        | Instruction::LogicalOr(_, _, variable)
+        | Instruction::BitwiseNot(_, variable)
+        | Instruction::Sitofp(_, variable)
+        | Instruction::Fptosi(_, variable)
+        | Instruction::BitwiseAnd(_, _, variable)
+        | Instruction::BitwiseOr(_, _, variable)
+        | Instruction::BitwiseXor(_, _, variable)
+        | Instruction::Phi(_, variable) => Some(variable.variable_id),
+        // Division may trap, and calls and stores have effects beyond the variable they define.
+        Instruction::Sdiv(..)
+        | Instruction::Srem(..)
+        | Instruction::Call(..)
+        | Instruction::Store(..)
+        | Instruction::Jump(..)
+        | Instruction::Branch(..)
+        | Instruction::Return => None,
+    }
+}
+
+/// Removes callables without a body that are no longer called from any block, which happens when every call to them
+/// was in a block that has been removed.
+fn remove_uncalled_callables(program: &mut Program) {
+    let mut called = FxHashSet::default();
+    called.insert(program.entry);
+    for block in program.blocks.values() {
+        for instr in &block.0 {
+            if let Instruction::Call(callable_id, _, _) = instr {
+                called.insert(*callable_id);
+            }
+        }
+    }
+
+    let uncalled = program
+        .callables
+        .iter()
+        .filter_map(|(callable_id, callable)| {
+            (callable.body.is_none() && !called.contains(&callable_id)).then_some(callable_id)
+        })
+        .collect::<Vec<_>>();
+    for callable_id in uncalled {
+        program.callables.remove(callable_id);
+    }
+}
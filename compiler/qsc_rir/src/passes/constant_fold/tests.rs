@@ -0,0 +1,398 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#![allow(clippy::too_many_lines, clippy::needless_raw_string_hashes)]
+
+use std::fmt::Write;
+
+use expect_test::expect;
+
+use crate::{
+    builder::{
+        int_record_decl, new_program, new_program_with_callables, read_result_decl, var, x_decl,
+    },
+    passes::check_and_transform,
+    rir::{
+        Block, BlockId, Callable, CallableId, ConditionCode, Instruction, Literal, Operand,
+        Program, Ty,
+    },
+};
+
+use super::fold_constants;
+
+fn int(value: i64) -> Operand {
+    Operand::Literal(Literal::Integer(value))
+}
+
+fn record_int(operand: Operand) -> Instruction {
+    Instruction::Call(
+        CallableId(1),
+        vec![operand, Operand::Literal(Literal::Pointer)],
+        None,
+    )
+}
+
+fn callables() -> [(CallableId, Callable); 3] {
+    [
+        (CallableId(1), int_record_decl()),
+        (CallableId(2), read_result_decl()),
+        (CallableId(3), x_decl()),
+    ]
+}
+
+fn blocks_to_string(program: &Program) -> String {
+    let mut s = String::new();
+    for (block_id, block) in program.blocks.iter() {
+        writeln!(s, "Block {}: {block}", block_id.0).expect("writing to string should succeed");
+    }
+    s
+}
+
+#[test]
+fn arithmetic_on_literals_is_folded_into_uses() {
+    let mut program = new_program_with_callables(callables());
+    program.blocks.insert(
+        BlockId(0),
+        Block(vec![
+            Instruction::Add(int(2), int(3), var(0, Ty::Integer)),
+            Instruction::Mul(
+                Operand::Variable(var(0, Ty::Integer)),
+                int(4),
+                var(1, Ty::Integer),
+            ),
+            Instruction::Shl(
+                Operand::Variable(var(1, Ty::Integer)),
+                int(1),
+                var(2, Ty::Integer),
+            ),
+            record_int(Operand::Variable(var(2, Ty::Integer))),
+            Instruction::Return,
+        ]),
+    );
+
+    fold_constants(&mut program);
+
+    expect![[r#"
+        Block 0: Block:
+            Call id(1), args( Integer(40), Pointer, )
+            Return
+    "#]]
+    .assert_eq(&blocks_to_string(&program));
+}
+
+#[test]
+fn operations_that_would_fail_at_runtime_are_not_folded() {
+    let mut program = new_program_with_callables(callables());
+    program.blocks.insert(
+        BlockId(0),
+        Block(vec![
+            Instruction::Sdiv(int(1), int(0), var(0, Ty::Integer)),
+            Instruction::Srem(int(i64::MIN), int(-1), var(1, Ty::Integer)),
+            Instruction::Shl(int(1), int(64), var(2, Ty::Integer)),
+            record_int(Operand::Variable(var(2, Ty::Integer))),
+            Instruction::Return,
+        ]),
+    );
+
+    fold_constants(&mut program);
+
+    expect![[r#"
+        Block 0: Block:
+            Variable(0, Integer) = Sdiv Integer(1), Integer(0)
+            Variable(1, Integer) = Srem Integer(-9223372036854775808), Integer(-1)
+            Variable(2, Integer) = Shl Integer(1), Integer(64)
+            Call id(1), args( Variable(2, Integer), Pointer, )
+            Return
+    "#]]
+    .assert_eq(&blocks_to_string(&program));
+}
+
+#[test]
+fn conversions_of_literals_are_folded_only_when_in_range() {
+    let mut program = new_program_with_callables(callables());
+    program.blocks.insert(
+        BlockId(0),
+        Block(vec![
+            Instruction::Sitofp(int(3), var(0, Ty::Double)),
+            Instruction::Fmul(
+                Operand::Variable(var(0, Ty::Double)),
+                Operand::Literal(Literal::Double(-0.5)),
+                var(1, Ty::Double),
+            ),
+            Instruction::Fptosi(Operand::Variable(var(1, Ty::Double)), var(2, Ty::Integer)),
+            Instruction::Fptosi(Operand::Literal(Literal::Double(1e19)), var(3, Ty::Integer)),
+            record_int(Operand::Variable(var(2, Ty::Integer))),
+            record_int(Operand::Variable(var(3, Ty::Integer))),
+            Instruction::Return,
+        ]),
+    );
+
+    fold_constants(&mut program);
+
+    expect![[r#"
+        Block 0: Block:
+            Variable(3, Integer) = Fptosi Double(10000000000000000000)
+            Call id(1), args( Integer(-1), Pointer, )
+            Call id(1), args( Variable(3, Integer), Pointer, )
+            Return
+    "#]]
+    .assert_eq(&blocks_to_string(&program));
+}
+
+#[test]
+fn branch_on_folded_condition_removes_untaken_block_and_phi() {
+    let mut program = new_program_with_callables(callables());
+    program.blocks.insert(
+        BlockId(0),
+        Block(vec![
+            Instruction::Icmp(ConditionCode::Slt, int(1), int(2), var(0, Ty::Boolean)),
+            Instruction::Branch(var(0, Ty::Boolean), BlockId(1), BlockId(2)),
+        ]),
+    );
+    program.blocks.insert(
+        BlockId(1),
+        Block(vec![
+            Instruction::Call(
+                CallableId(3),
+                vec![Operand::Literal(Literal::Qubit(0))],
+                None,
+            ),
+            Instruction::Jump(BlockId(3)),
+        ]),
+    );
+    program
+        .blocks
+        .insert(BlockId(2), Block(vec![Instruction::Jump(BlockId(3))]));
+    program.blocks.insert(
+        BlockId(3),
+        Block(vec![
+            Instruction::Phi(
+                vec![(int(1), BlockId(1)), (int(2), BlockId(2))],
+                var(1, Ty::Integer),
+            ),
+            record_int(Operand::Variable(var(1, Ty::Integer))),
+            Instruction::Return,
+        ]),
+    );
+    program.block_names.insert(BlockId(2), "if_false".into());
+
+    fold_constants(&mut program);
+
+    expect![[r#"
+        Block 0: Block:
+            Jump(1)
+        Block 1: Block:
+            Call id(3), args( Qubit(0), )
+            Jump(3)
+        Block 3: Block:
+            Call id(1), args( Integer(1), Pointer, )
+            Return
+    "#]]
+    .assert_eq(&blocks_to_string(&program));
+    assert!(!program.block_names.contains_key(BlockId(2)));
+}
+
+#[test]
+fn phi_is_kept_when_incoming_values_differ() {
+    let mut program = new_program_with_callables(callables());
+    program.blocks.insert(
+        BlockId(0),
+        Block(vec![
+            Instruction::Call(
+                CallableId(2),
+                vec![Operand::Literal(Literal::Result(0))],
+                Some(var(0, Ty::Boolean)),
+            ),
+            Instruction::Branch(var(0, Ty::Boolean), BlockId(1), BlockId(2)),
+        ]),
+    );
+    program
+        .blocks
+        .insert(BlockId(1), Block(vec![Instruction::Jump(BlockId(3))]));
+    program
+        .blocks
+        .insert(BlockId(2), Block(vec![Instruction::Jump(BlockId(3))]));
+    program.blocks.insert(
+        BlockId(3),
+        Block(vec![
+            Instruction::Phi(
+                vec![(int(1), BlockId(1)), (int(2), BlockId(2))],
+                var(1, Ty::Integer),
+            ),
+            Instruction::Phi(
+                vec![(int(5), BlockId(1)), (int(5), BlockId(2))],
+                var(2, Ty::Integer),
+            ),
+            Instruction::Add(
+                Operand::Variable(var(1, Ty::Integer)),
+                Operand::Variable(var(2, Ty::Integer)),
+                var(3, Ty::Integer),
+            ),
+            record_int(Operand::Variable(var(3, Ty::Integer))),
+            Instruction::Return,
+        ]),
+    );
+
+    fold_constants(&mut program);
+
+    expect![[r#"
+        Block 0: Block:
+            Variable(0, Boolean) = Call id(2), args( Result(0), )
+            Branch Variable(0, Boolean), 1, 2
+        Block 1: Block:
+            Jump(3)
+        Block 2: Block:
+            Jump(3)
+        Block 3: Block:
+            Variable(1, Integer) = Phi ( [Integer(1), 1], [Integer(2), 2], )
+            Variable(3, Integer) = Add Variable(1, Integer), Integer(5)
+            Call id(1), args( Variable(3, Integer), Pointer, )
+            Return
+    "#]]
+    .assert_eq(&blocks_to_string(&program));
+}
+
+#[test]
+fn phi_arguments_from_loop_back_edges_are_kept_and_folded() {
+    let mut program = new_program_with_callables(callables());
+    program
+        .blocks
+        .insert(BlockId(0), Block(vec![Instruction::Jump(BlockId(1))]));
+    program.blocks.insert(
+        BlockId(1),
+        Block(vec![
+            Instruction::Phi(
+                vec![
+                    (int(0), BlockId(0)),
+                    (Operand::Variable(var(2, Ty::Integer)), BlockId(2)),
+                ],
+                var(1, Ty::Integer),
+            ),
+            Instruction::Call(
+                CallableId(2),
+                vec![Operand::Literal(Literal::Result(0))],
+                Some(var(0, Ty::Boolean)),
+            ),
+            Instruction::Branch(var(0, Ty::Boolean), BlockId(2), BlockId(3)),
+        ]),
+    );
+    program.blocks.insert(
+        BlockId(2),
+        Block(vec![
+            Instruction::Add(int(2), int(3), var(2, Ty::Integer)),
+            Instruction::Jump(BlockId(1)),
+        ]),
+    );
+    program.blocks.insert(
+        BlockId(3),
+        Block(vec![
+            record_int(Operand::Variable(var(1, Ty::Integer))),
+            Instruction::Return,
+        ]),
+    );
+
+    fold_constants(&mut program);
+
+    expect![[r#"
+        Block 0: Block:
+            Jump(1)
+        Block 1: Block:
+            Variable(1, Integer) = Phi ( [Integer(0), 0], [Integer(5), 2], )
+            Variable(0, Boolean) = Call id(2), args( Result(0), )
+            Branch Variable(0, Boolean), 2, 3
+        Block 2: Block:
+            Jump(1)
+        Block 3: Block:
+            Call id(1), args( Variable(1, Integer), Pointer, )
+            Return
+    "#]]
+    .assert_eq(&blocks_to_string(&program));
+}
+
+#[test]
+fn stored_constants_are_folded_through_branches_in_pipeline() {
+    let mut program = new_program();
+    program.callables.insert(CallableId(1), int_record_decl());
+    program.blocks.insert(
+        BlockId(0),
+        Block(vec![
+            Instruction::Store(int(3), var(0, Ty::Integer)),
+            Instruction::Icmp(
+                ConditionCode::Eq,
+                Operand::Variable(var(0, Ty::Integer)),
+                int(3),
+                var(1, Ty::Boolean),
+            ),
+            Instruction::Branch(var(1, Ty::Boolean), BlockId(1), BlockId(2)),
+        ]),
+    );
+    program.blocks.insert(
+        BlockId(1),
+        Block(vec![
+            Instruction::Add(
+                Operand::Variable(var(0, Ty::Integer)),
+                int(1),
+                var(2, Ty::Integer),
+            ),
+            Instruction::Store(Operand::Variable(var(2, Ty::Integer)), var(0, Ty::Integer)),
+            Instruction::Jump(BlockId(3)),
+        ]),
+    );
+    program.blocks.insert(
+        BlockId(2),
+        Block(vec![
+            Instruction::Store(int(0), var(0, Ty::Integer)),
+            Instruction::Jump(BlockId(3)),
+        ]),
+    );
+    program.blocks.insert(
+        BlockId(3),
+        Block(vec![
+            record_int(Operand::Variable(var(0, Ty::Integer))),
+            Instruction::Return,
+        ]),
+    );
+
+    check_and_transform(&mut program);
+
+    expect![[r#"
+        Block 0: Block:
+            Call id(1), args( Integer(4), Pointer, )
+            Return
+    "#]]
+    .assert_eq(&blocks_to_string(&program));
+}
+
+#[test]
+fn declarations_only_called_from_removed_blocks_are_removed() {
+    let mut program = new_program_with_callables(callables());
+    program.blocks.insert(
+        BlockId(0),
+        Block(vec![
+            Instruction::LogicalNot(Operand::Literal(Literal::Bool(true)), var(0, Ty::Boolean)),
+            Instruction::Branch(var(0, Ty::Boolean), BlockId(1), BlockId(2)),
+        ]),
+    );
+    program.blocks.insert(
+        BlockId(1),
+        Block(vec![
+            Instruction::Call(
+                CallableId(3),
+                vec![Operand::Literal(Literal::Qubit(0))],
+                None,
+            ),
+            Instruction::Jump(BlockId(2)),
+        ]),
+    );
+    program.blocks.insert(
+        BlockId(2),
+        Block(vec![record_int(int(0)), Instruction::Return]),
+    );
+
+    fold_constants(&mut program);
+
+    assert!(program.callables.contains_key(CallableId(0)));
+    assert!(program.callables.contains_key(CallableId(1)));
+    assert!(!program.callables.contains_key(CallableId(2)));
+    assert!(!program.callables.contains_key(CallableId(3)));
+}
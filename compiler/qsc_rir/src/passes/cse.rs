@@ -0,0 +1,210 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use std::mem::{discriminant, Discriminant};
+
+use qsc_data_structures::fx::{FxHashMap, FxHashSet};
+
+use crate::{
+    rir::{
+        CallableId, CallableType, ConditionCode, FcmpConditionCode, Instruction, Literal, Operand,
+        Program, VariableId,
+    },
+    utils::for_each_operand_mut,
+};
+
+/// Removes instructions that recompute a value already computed earlier in the same block.
+/// Pure arithmetic, comparison and logical instructions as well as readout calls with identical operands are
+/// considered redundant, unless a call in between passes the result that is read to another callable, and all uses of the variable they define are replaced with the variable defined by the
+/// first occurrence. This requires the program to be in SSA form, so that the first occurrence dominates every use
+/// of the redundant variable, and expects blocks to be numbered in topological order or, for programs with loops,
+/// in reverse postorder.
+pub fn eliminate_common_subexpressions(program: &mut Program) {
+    let readout_callables: FxHashSet<CallableId> = program
+        .callables
+        .iter()
+        .filter_map(|(id, callable)| (callable.call_type == CallableType::Readout).then_some(id))
+        .collect();
+
+    let mut replacements = FxHashMap::default();
+    for block in program.blocks.values_mut() {
+        let mut available = FxHashMap::default();
+        block.0.retain_mut(|instr| {
+            replace_operands(instr, &replacements);
+            invalidate_readouts(instr, &readout_callables, &mut available);
+            let Some((key, variable_id)) = get_expr_key(instr, &readout_callables) else {
+                return true;
+            };
+            if let Some(existing) = available.get(&key) {
+                replacements.insert(variable_id, *existing);
+                false
+            } else {
+                available.insert(key, variable_id);
+                true
+            }
+        });
+    }
+
+    // Phi nodes in loop headers are visited before the blocks their back edge arguments come from.
+    for block in program.blocks.values_mut() {
+        for instr in &mut block.0 {
+            if let Instruction::Phi(..) = instr {
+                replace_operands(instr, &replacements);
+            }
+        }
+    }
+
+    // Keep the name of an eliminated variable if the variable replacing it does not have one.
+    for (variable_id, replacement) in replacements {
+        if let Some(name) = program.variable_names.get(variable_id).cloned() {
+            program.variable_names.remove(variable_id);
+            if !program.variable_names.contains_key(replacement) {
+                program.variable_names.insert(replacement, name);
+            }
+        }
+    }
+}
+
+/// A hashable representation of the value computed by an instruction, independent of the variable it is stored in.
+#[derive(PartialEq, Eq, Hash)]
+struct ExprKey {
+    kind: Discriminant<Instruction>,
+    callable: Option<CallableId>,
+    condition: Option<ConditionCode>,
+    fcmp_condition: Option<FcmpConditionCode>,
+    operands: Vec<OperandKey>,
+}
+
+#[derive(PartialEq, Eq, Hash)]
+enum OperandKey {
+    Qubit(u32),
+    Result(u32),
+    Bool(bool),
+    Integer(i64),
+    Double(u64),
+    Pointer,
+    Tag(u32),
+    Variable(VariableId),
+}
+
+impl From<&Operand> for OperandKey {
+    fn from(operand: &Operand) -> Self {
+        match operand {
+            Operand::Literal(Literal::Qubit(q)) => OperandKey::Qubit(*q),
+            Operand::Literal(Literal::Result(r)) => OperandKey::Result(*r),
+            Operand::Literal(Literal::Bool(b)) => OperandKey::Bool(*b),
+            Operand::Literal(Literal::Integer(i)) => OperandKey::Integer(*i),
+            Operand::Literal(Literal::Double(d)) => OperandKey::Double(d.to_bits()),
+            Operand::Literal(Literal::Pointer) => OperandKey::Pointer,
+            Operand::Literal(Literal::Tag(t)) => OperandKey::Tag(*t),
+            Operand::Variable(variable) => OperandKey::Variable(variable.variable_id),
+        }
+    }
+}
+
+/// Gets the key for the value computed by the instruction and the variable it is stored in, if the instruction is
+/// free of side effects and can be eliminated when the same value has already been computed.
+fn get_expr_key(
+    instr: &Instruction,
+    readout_callables: &FxHashSet<CallableId>,
+) -> Option<(ExprKey, VariableId)> {
+    let mut key = ExprKey {
+        kind: discriminant(instr),
+        callable: None,
+        condition: None,
+        fcmp_condition: None,
+        operands: Vec::new(),
+    };
+    let variable = match instr {
+        Instruction::Call(callable_id, args, Some(variable))
+            if readout_callables.contains(callable_id) =>
+        {
+            key.callable = Some(*callable_id);
+            key.operands = args.iter().map(Into::into).collect();
+            variable
+        }
+        Instruction::LogicalNot(operand, variable)
+        | Instruction::BitwiseNot(operand, variable)
+        | Instruction::Sitofp(operand, variable)
+        | Instruction::Fptosi(operand, variable) => {
+            key.operands = vec![operand.into()];
+            variable
+        }
+        Instruction::Icmp(condition, lhs, rhs, variable) => {
+            key.condition = Some(*condition);
+            key.operands = vec![lhs.into(), rhs.into()];
+            variable
+        }
+        Instruction::Fcmp(condition, lhs, rhs, variable) => {
+            key.fcmp_condition = Some(*condition);
+            key.operands = vec![lhs.into(), rhs.into()];
+            variable
+        }
+        Instruction::Add(lhs, rhs, variable)
+        | Instruction::Sub(lhs, rhs, variable)
+        | Instruction::Mul(lhs, rhs, variable)
+        | Instruction::Sdiv(lhs, rhs, variable)
+        | Instruction::Srem(lhs, rhs, variable)
+        | Instruction::Shl(lhs, rhs, variable)
+        | Instruction::Ashr(lhs, rhs, variable)
+        | Instruction::Fadd(lhs, rhs, variable)
+        | Instruction::Fsub(lhs, rhs, variable)
+        | Instruction::Fmul(lhs, rhs, variable)
+        | Instruction::Fdiv(lhs, rhs, variable)
+        | Instruction::LogicalAnd(lhs, rhs, variable)
+        | Instruction::LogicalOr(lhs, rhs, variable)
+        | Instruction::BitwiseAnd(lhs, rhs, variable)
+        | Instruction::BitwiseOr(lhs, rhs, variable)
+        | Instruction::BitwiseXor(lhs, rhs, variable) => {
+            key.operands = vec![lhs.into(), rhs.into()];
+            variable
+        }
+        Instruction::Call(..)
+        | Instruction::Store(..)
+        | Instruction::Jump(..)
+        | Instruction::Branch(..)
+        | Instruction::Phi(..)
+        | Instruction::Return => return None,
+    };
+    Some((key, variable.variable_id))
+}
+
+/// Forgets the readouts of any result that the instruction passes to a callable other than a readout, such as a
+/// measurement, since the callable can write a new value to the result.
+fn invalidate_readouts(
+    instr: &Instruction,
+    readout_callables: &FxHashSet<CallableId>,
+    available: &mut FxHashMap<ExprKey, VariableId>,
+) {
+    let Instruction::Call(callable_id, args, _) = instr else {
+        return;
+    };
+    if readout_callables.contains(callable_id) {
+        return;
+    }
+    for arg in args {
+        if let Operand::Literal(Literal::Result(r)) = arg {
+            let result = OperandKey::Result(*r);
+            available.retain(|key, _| key.callable.is_none() || !key.operands.contains(&result));
+        }
+    }
+}
+
+/// Replaces any variable used by the instruction that has been eliminated with the variable that replaces it.
+fn replace_operands(instr: &mut Instruction, replacements: &FxHashMap<VariableId, VariableId>) {
+    if let Instruction::Branch(variable, _, _) = instr {
+        if let Some(replacement) = replacements.get(&variable.variable_id) {
+            variable.variable_id = *replacement;
+        }
+    }
+    for_each_operand_mut(instr, |operand| {
+        if let Operand::Variable(variable) = operand {
+            if let Some(replacement) = replacements.get(&variable.variable_id) {
+                variable.variable_id = *replacement;
+            }
+        }
+    });
+}
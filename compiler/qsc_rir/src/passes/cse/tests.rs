@@ -0,0 +1,317 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#![allow(clippy::too_many_lines, clippy::needless_raw_string_hashes)]
+
+use expect_test::expect;
+
+use crate::{
+    builder::{
+        bool_record_decl, mresetz_decl, new_program_with_callables, qubit, read_result_decl,
+        result, var,
+    },
+    rir::{
+        Block, BlockId, Callable, CallableId, CallableType, ConditionCode, Instruction, Literal,
+        Operand, Ty, VariableId,
+    },
+};
+
+use super::eliminate_common_subexpressions;
+
+fn callables() -> [(CallableId, Callable); 3] {
+    [
+        (CallableId(1), read_result_decl()),
+        (CallableId(2), bool_record_decl()),
+        (
+            CallableId(3),
+            Callable {
+                name: "dynamic_int".to_string(),
+                input_type: Vec::new(),
+                output_type: Some(Ty::Integer),
+                body: None,
+                call_type: CallableType::Regular,
+            },
+        ),
+    ]
+}
+
+#[test]
+fn cse_removes_repeated_read_result_and_comparison_in_block() {
+    let mut program = new_program_with_callables(callables());
+    program.blocks.insert(
+        BlockId(0),
+        Block(vec![
+            Instruction::Call(
+                CallableId(1),
+                vec![Operand::Literal(Literal::Result(0))],
+                Some(var(0, Ty::Boolean)),
+            ),
+            Instruction::Call(
+                CallableId(1),
+                vec![Operand::Literal(Literal::Result(0))],
+                Some(var(1, Ty::Boolean)),
+            ),
+            Instruction::Icmp(
+                ConditionCode::Eq,
+                Operand::Variable(var(0, Ty::Boolean)),
+                Operand::Literal(Literal::Bool(true)),
+                var(2, Ty::Boolean),
+            ),
+            Instruction::Icmp(
+                ConditionCode::Eq,
+                Operand::Variable(var(1, Ty::Boolean)),
+                Operand::Literal(Literal::Bool(true)),
+                var(3, Ty::Boolean),
+            ),
+            Instruction::Branch(var(3, Ty::Boolean), BlockId(1), BlockId(2)),
+        ]),
+    );
+    program.blocks.insert(
+        BlockId(1),
+        Block(vec![
+            Instruction::Call(
+                CallableId(2),
+                vec![
+                    Operand::Variable(var(3, Ty::Boolean)),
+                    Operand::Literal(Literal::Pointer),
+                ],
+                None,
+            ),
+            Instruction::Jump(BlockId(2)),
+        ]),
+    );
+    program
+        .blocks
+        .insert(BlockId(2), Block(vec![Instruction::Return]));
+    program
+        .variable_names
+        .insert(VariableId(3), "is_one".into());
+
+    eliminate_common_subexpressions(&mut program);
+
+    expect![[r#"
+        Program:
+            entry: 0
+            callables:
+                Callable 0: Callable:
+                    name: main
+                    call_type: Regular
+                    input_type: <VOID>
+                    output_type: <VOID>
+                    body: 0
+                Callable 1: Callable:
+                    name: __quantum__qis__read_result__body
+                    call_type: Readout
+                    input_type:
+                        [0]: Result
+                    output_type: Boolean
+                    body: <NONE>
+                Callable 2: Callable:
+                    name: __quantum__rt__bool_record_output
+                    call_type: OutputRecording
+                    input_type:
+                        [0]: Boolean
+                        [1]: Pointer
+                    output_type: <VOID>
+                    body: <NONE>
+                Callable 3: Callable:
+                    name: dynamic_int
+                    call_type: Regular
+                    input_type: <VOID>
+                    output_type: Integer
+                    body: <NONE>
+            blocks:
+                Block 0: Block:
+                    Variable(0, Boolean) = Call id(1), args( Result(0), )
+                    Variable(2, Boolean) = Icmp Eq, Variable(0, Boolean), Bool(true)
+                    Branch Variable(2, Boolean), 1, 2
+                Block 1: Block:
+                    Call id(2), args( Variable(2, Boolean), Pointer, )
+                    Jump(2)
+                Block 2: Block:
+                    Return
+            config: Config:
+                capabilities: Base
+            num_qubits: 0
+            num_results: 0"#]]
+    .assert_eq(&program.to_string());
+    expect![[r#"
+        [
+            (
+                VariableId(
+                    2,
+                ),
+                "is_one",
+            ),
+        ]
+    "#]]
+    .assert_debug_eq(&program.variable_names.iter().collect::<Vec<_>>());
+}
+
+#[test]
+fn cse_removes_chained_arithmetic() {
+    let mut program = new_program_with_callables(callables());
+    program.blocks.insert(
+        BlockId(0),
+        Block(vec![
+            Instruction::Call(CallableId(3), Vec::new(), Some(var(0, Ty::Integer))),
+            Instruction::Add(
+                Operand::Variable(var(0, Ty::Integer)),
+                Operand::Literal(Literal::Integer(1)),
+                var(1, Ty::Integer),
+            ),
+            Instruction::Add(
+                Operand::Variable(var(0, Ty::Integer)),
+                Operand::Literal(Literal::Integer(1)),
+                var(2, Ty::Integer),
+            ),
+            Instruction::Mul(
+                Operand::Variable(var(1, Ty::Integer)),
+                Operand::Literal(Literal::Integer(2)),
+                var(3, Ty::Integer),
+            ),
+            Instruction::Mul(
+                Operand::Variable(var(2, Ty::Integer)),
+                Operand::Literal(Literal::Integer(2)),
+                var(4, Ty::Integer),
+            ),
+            Instruction::Sub(
+                Operand::Variable(var(3, Ty::Integer)),
+                Operand::Variable(var(4, Ty::Integer)),
+                var(5, Ty::Integer),
+            ),
+            Instruction::Return,
+        ]),
+    );
+
+    eliminate_common_subexpressions(&mut program);
+
+    expect![[r#"
+        Program:
+            entry: 0
+            callables:
+                Callable 0: Callable:
+                    name: main
+                    call_type: Regular
+                    input_type: <VOID>
+                    output_type: <VOID>
+                    body: 0
+                Callable 1: Callable:
+                    name: __quantum__qis__read_result__body
+                    call_type: Readout
+                    input_type:
+                        [0]: Result
+                    output_type: Boolean
+                    body: <NONE>
+                Callable 2: Callable:
+                    name: __quantum__rt__bool_record_output
+                    call_type: OutputRecording
+                    input_type:
+                        [0]: Boolean
+                        [1]: Pointer
+                    output_type: <VOID>
+                    body: <NONE>
+                Callable 3: Callable:
+                    name: dynamic_int
+                    call_type: Regular
+                    input_type: <VOID>
+                    output_type: Integer
+                    body: <NONE>
+            blocks:
+                Block 0: Block:
+                    Variable(0, Integer) = Call id(3), args( )
+                    Variable(1, Integer) = Add Variable(0, Integer), Integer(1)
+                    Variable(3, Integer) = Mul Variable(1, Integer), Integer(2)
+                    Variable(5, Integer) = Sub Variable(3, Integer), Variable(3, Integer)
+                    Return
+            config: Config:
+                capabilities: Base
+            num_qubits: 0
+            num_results: 0"#]]
+    .assert_eq(&program.to_string());
+}
+
+#[test]
+fn cse_keeps_calls_with_side_effects_and_different_operands() {
+    let mut program = new_program_with_callables(callables());
+    program.blocks.insert(
+        BlockId(0),
+        Block(vec![
+            Instruction::Call(CallableId(3), Vec::new(), Some(var(0, Ty::Integer))),
+            Instruction::Call(CallableId(3), Vec::new(), Some(var(1, Ty::Integer))),
+            Instruction::Add(
+                Operand::Variable(var(0, Ty::Integer)),
+                Operand::Literal(Literal::Integer(1)),
+                var(2, Ty::Integer),
+            ),
+            Instruction::Add(
+                Operand::Variable(var(1, Ty::Integer)),
+                Operand::Literal(Literal::Integer(1)),
+                var(3, Ty::Integer),
+            ),
+            Instruction::Sub(
+                Operand::Variable(var(0, Ty::Integer)),
+                Operand::Literal(Literal::Integer(1)),
+                var(4, Ty::Integer),
+            ),
+            Instruction::Return,
+        ]),
+    );
+    let original = program.to_string();
+
+    eliminate_common_subexpressions(&mut program);
+
+    assert_eq!(original, program.to_string());
+}
+
+#[test]
+fn cse_does_not_eliminate_across_blocks() {
+    let mut program = new_program_with_callables(callables());
+    program.blocks.insert(
+        BlockId(0),
+        Block(vec![
+            Instruction::Call(
+                CallableId(1),
+                vec![Operand::Literal(Literal::Result(0))],
+                Some(var(0, Ty::Boolean)),
+            ),
+            Instruction::Jump(BlockId(1)),
+        ]),
+    );
+    program.blocks.insert(
+        BlockId(1),
+        Block(vec![
+            Instruction::Call(
+                CallableId(1),
+                vec![Operand::Literal(Literal::Result(0))],
+                Some(var(1, Ty::Boolean)),
+            ),
+            Instruction::Return,
+        ]),
+    );
+    let original = program.to_string();
+
+    eliminate_common_subexpressions(&mut program);
+
+    assert_eq!(original, program.to_string());
+}
+
+#[test]
+fn cse_keeps_read_result_after_measurement_into_same_result() {
+    let mut program = new_program_with_callables(callables());
+    program.callables.insert(CallableId(4), mresetz_decl());
+    program.blocks.insert(
+        BlockId(0),
+        Block(vec![
+            Instruction::Call(CallableId(1), vec![result(0)], Some(var(0, Ty::Boolean))),
+            Instruction::Call(CallableId(4), vec![qubit(0), result(0)], None),
+            Instruction::Call(CallableId(1), vec![result(0)], Some(var(1, Ty::Boolean))),
+            Instruction::Return,
+        ]),
+    );
+    let original = program.to_string();
+
+    eliminate_common_subexpressions(&mut program);
+
+    assert_eq!(original, program.to_string());
+}
@@ -0,0 +1,147 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use qsc_data_structures::fx::{FxHashMap, FxHashSet};
+
+use crate::{
+    rir::{BlockId, Instruction, Program},
+    utils::get_all_block_successors,
+};
+
+/// Removes blocks and instructions that can never execute or that do nothing but pass control along:
+/// - Instructions following the first terminator of a block are dropped.
+/// - Blocks that only jump to another block are bypassed by retargeting their predecessors.
+/// - Branches whose targets are the same block are replaced with a jump to that block.
+/// - Blocks that are not reachable from the body of any callable are removed.
+///
+/// Blocks whose first instruction is a phi node are never bypassed or merged into, since that would change the
+/// predecessors the phi node refers to. Together with `simplify_control_flow`, which merges chains of blocks with a
+/// single predecessor and successor, and `remap_block_ids`, which renumbers the remaining blocks, this cleans up the
+/// blocks left behind by branch generation during partial evaluation.
+pub fn eliminate_dead_blocks(program: &mut Program) {
+    remove_instrs_after_terminator(program);
+    bypass_jump_only_blocks(program);
+    fold_branches_to_same_block(program);
+    remove_unreachable_blocks(program);
+}
+
+fn is_terminator(instr: &Instruction) -> bool {
+    matches!(
+        instr,
+        Instruction::Return | Instruction::Jump(..) | Instruction::Branch(..)
+    )
+}
+
+fn starts_with_phi(program: &Program, block_id: BlockId) -> bool {
+    matches!(
+        program.get_block(block_id).0.first(),
+        Some(Instruction::Phi(..))
+    )
+}
+
+fn remove_instrs_after_terminator(program: &mut Program) {
+    for block in program.blocks.values_mut() {
+        if let Some(idx) = block.0.iter().position(is_terminator) {
+            block.0.truncate(idx + 1);
+        }
+    }
+}
+
+fn bypass_jump_only_blocks(program: &mut Program) {
+    let bodies = program
+        .callables
+        .values()
+        .filter_map(|callable| callable.body)
+        .collect::<FxHashSet<_>>();
+
+    // Map each block that only jumps elsewhere to the block it jumps to. Callable bodies are kept since they are
+    // referenced by their callables rather than by jumps.
+    let mut forwards = FxHashMap::default();
+    for (block_id, block) in program.blocks.iter() {
+        if let [Instruction::Jump(target)] = block.0[..] {
+            if target != block_id
+                && !bodies.contains(&block_id)
+                && !starts_with_phi(program, target)
+            {
+                forwards.insert(block_id, target);
+            }
+        }
+    }
+
+    // Follow chains of forwarding blocks to their final target, stopping if the chain loops back on itself.
+    let resolve = |mut block_id: BlockId| {
+        let mut visited = FxHashSet::default();
+        while let Some(target) = forwards.get(&block_id) {
+            if !visited.insert(block_id) {
+                break;
+            }
+            block_id = *target;
+        }
+        block_id
+    };
+
+    let mut retargeted = Vec::new();
+    for (block_id, block) in program.blocks.iter() {
+        match block.0.last() {
+            Some(Instruction::Jump(target)) => {
+                let new_target = resolve(*target);
+                if new_target != *target {
+                    retargeted.push((block_id, Instruction::Jump(new_target)));
+                }
+            }
+            Some(Instruction::Branch(cond, true_target, false_target)) => {
+                let new_true = resolve(*true_target);
+                let new_false = resolve(*false_target);
+                if new_true != *true_target || new_false != *false_target {
+                    retargeted.push((block_id, Instruction::Branch(*cond, new_true, new_false)));
+                }
+            }
+            _ => {}
+        }
+    }
+    for (block_id, terminator) in retargeted {
+        let block = program.get_block_mut(block_id);
+        block.0.pop();
+        block.0.push(terminator);
+    }
+}
+
+fn fold_branches_to_same_block(program: &mut Program) {
+    let targets_with_phi = program
+        .blocks
+        .iter()
+        .filter_map(|(block_id, _)| starts_with_phi(program, block_id).then_some(block_id))
+        .collect::<FxHashSet<_>>();
+    for block in program.blocks.values_mut() {
+        if let Some(Instruction::Branch(_, true_target, false_target)) = block.0.last() {
+            if true_target == false_target && !targets_with_phi.contains(true_target) {
+                let target = *true_target;
+                block.0.pop();
+                block.0.push(Instruction::Jump(target));
+            }
+        }
+    }
+}
+
+fn remove_unreachable_blocks(program: &mut Program) {
+    let mut live_blocks = FxHashSet::default();
+    for callable in program.callables.values() {
+        if let Some(body) = callable.body {
+            live_blocks.insert(body);
+            live_blocks.extend(get_all_block_successors(body, program));
+        }
+    }
+
+    let dead_blocks = program
+        .blocks
+        .iter()
+        .filter_map(|(block_id, _)| (!live_blocks.contains(&block_id)).then_some(block_id))
+        .collect::<Vec<_>>();
+    for block_id in dead_blocks {
+        program.blocks.remove(block_id);
+        program.block_names.remove(block_id);
+    }
+}
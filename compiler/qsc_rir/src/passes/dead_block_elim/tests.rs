@@ -0,0 +1,285 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#![allow(clippy::too_many_lines, clippy::needless_raw_string_hashes)]
+
+use expect_test::expect;
+use qsc_data_structures::target::TargetCapabilityFlags;
+
+use crate::{
+    builder::{bool_var, int_var, new_program, read_result_decl, x_decl},
+    passes::check_and_transform,
+    rir::{Block, BlockId, CallableId, ConditionCode, Instruction, Literal, Operand, Program},
+};
+
+use super::eliminate_dead_blocks;
+
+fn x_call(qubit: u32) -> Instruction {
+    Instruction::Call(
+        CallableId(1),
+        vec![Operand::Literal(Literal::Qubit(qubit))],
+        None,
+    )
+}
+
+/// Builds a program shaped like an `if` expression whose branches are both empty.
+fn program_with_empty_branches() -> Program {
+    let mut program = new_program();
+    program.callables.insert(CallableId(1), x_decl());
+    program.blocks.insert(
+        BlockId(0),
+        Block(vec![
+            Instruction::Store(Operand::Literal(Literal::Bool(true)), bool_var(0)),
+            Instruction::Branch(bool_var(0), BlockId(1), BlockId(2)),
+        ]),
+    );
+    program
+        .blocks
+        .insert(BlockId(1), Block(vec![Instruction::Jump(BlockId(3))]));
+    program
+        .blocks
+        .insert(BlockId(2), Block(vec![Instruction::Jump(BlockId(3))]));
+    program
+        .blocks
+        .insert(BlockId(3), Block(vec![x_call(0), Instruction::Return]));
+    program.block_names.insert(BlockId(1), "if_true".into());
+    program
+}
+
+#[test]
+fn empty_branches_are_bypassed_and_removed() {
+    let mut program = program_with_empty_branches();
+    eliminate_dead_blocks(&mut program);
+    expect![[r#"
+        Program:
+            entry: 0
+            callables:
+                Callable 0: Callable:
+                    name: main
+                    call_type: Regular
+                    input_type: <VOID>
+                    output_type: <VOID>
+                    body: 0
+                Callable 1: Callable:
+                    name: __quantum__qis__x__body
+                    call_type: Regular
+                    input_type:
+                        [0]: Qubit
+                    output_type: <VOID>
+                    body: <NONE>
+            blocks:
+                Block 0: Block:
+                    Variable(0, Boolean) = Store Bool(true)
+                    Jump(3)
+                Block 3: Block:
+                    Call id(1), args( Qubit(0), )
+                    Return
+            config: Config:
+                capabilities: Base
+            num_qubits: 0
+            num_results: 0"#]]
+    .assert_eq(&program.to_string());
+    assert!(!program.block_names.contains_key(BlockId(1)));
+}
+
+#[test]
+fn empty_branches_collapse_into_single_block_in_pipeline() {
+    let mut program = program_with_empty_branches();
+    program.config.capabilities = TargetCapabilityFlags::all();
+    check_and_transform(&mut program);
+    expect![[r#"
+        Program:
+            entry: 0
+            callables:
+                Callable 0: Callable:
+                    name: main
+                    call_type: Regular
+                    input_type: <VOID>
+                    output_type: <VOID>
+                    body: 0
+                Callable 1: Callable:
+                    name: __quantum__qis__x__body
+                    call_type: Regular
+                    input_type:
+                        [0]: Qubit
+                    output_type: <VOID>
+                    body: <NONE>
+            blocks:
+                Block 0: Block:
+                    Call id(1), args( Qubit(0), )
+                    Return
+            config: Config:
+                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset | Delay)
+            num_qubits: 0
+            num_results: 0"#]]
+    .assert_eq(&program.to_string());
+}
+
+#[test]
+fn blocks_left_after_folding_are_renumbered_in_pipeline() {
+    let mut program = new_program();
+    program.config.capabilities = TargetCapabilityFlags::all();
+    program.callables.insert(CallableId(1), x_decl());
+    program.callables.insert(CallableId(2), read_result_decl());
+    program.blocks.insert(
+        BlockId(0),
+        Block(vec![
+            Instruction::Store(Operand::Literal(Literal::Integer(3)), int_var(0)),
+            Instruction::Icmp(
+                ConditionCode::Eq,
+                Operand::Variable(int_var(0)),
+                Operand::Literal(Literal::Integer(3)),
+                bool_var(1),
+            ),
+            Instruction::Branch(bool_var(1), BlockId(1), BlockId(2)),
+        ]),
+    );
+    program.blocks.insert(
+        BlockId(1),
+        Block(vec![x_call(0), Instruction::Jump(BlockId(3))]),
+    );
+    program.blocks.insert(
+        BlockId(2),
+        Block(vec![x_call(1), Instruction::Jump(BlockId(3))]),
+    );
+    program.blocks.insert(
+        BlockId(3),
+        Block(vec![
+            Instruction::Call(
+                CallableId(2),
+                vec![Operand::Literal(Literal::Result(0))],
+                Some(bool_var(2)),
+            ),
+            Instruction::Branch(bool_var(2), BlockId(4), BlockId(5)),
+        ]),
+    );
+    program.blocks.insert(
+        BlockId(4),
+        Block(vec![x_call(2), Instruction::Jump(BlockId(5))]),
+    );
+    program
+        .blocks
+        .insert(BlockId(5), Block(vec![Instruction::Return]));
+
+    check_and_transform(&mut program);
+
+    // Folding the constant branch removes block 2 and merges blocks 1 and 3 into block 0, and the blocks that
+    // are left are renumbered from 0 without gaps.
+    expect![[r#"
+        Program:
+            entry: 0
+            callables:
+                Callable 0: Callable:
+                    name: main
+                    call_type: Regular
+                    input_type: <VOID>
+                    output_type: <VOID>
+                    body: 0
+                Callable 1: Callable:
+                    name: __quantum__qis__x__body
+                    call_type: Regular
+                    input_type:
+                        [0]: Qubit
+                    output_type: <VOID>
+                    body: <NONE>
+                Callable 2: Callable:
+                    name: __quantum__qis__read_result__body
+                    call_type: Readout
+                    input_type:
+                        [0]: Result
+                    output_type: Boolean
+                    body: <NONE>
+            blocks:
+                Block 0: Block:
+                    Call id(1), args( Qubit(0), )
+                    Variable(2, Boolean) = Call id(2), args( Result(0), )
+                    Branch Variable(2, Boolean), 1, 2
+                Block 1: Block:
+                    Call id(1), args( Qubit(2), )
+                    Jump(2)
+                Block 2: Block:
+                    Return
+            config: Config:
+                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset | Delay)
+            num_qubits: 0
+            num_results: 0"#]].assert_eq(&program.to_string());
+}
+
+#[test]
+fn unreachable_blocks_and_instructions_are_removed() {
+    let mut program = new_program();
+    program.callables.insert(CallableId(1), x_decl());
+    program.blocks.insert(
+        BlockId(0),
+        Block(vec![x_call(0), Instruction::Return, x_call(1)]),
+    );
+    program.blocks.insert(
+        BlockId(1),
+        Block(vec![x_call(2), Instruction::Jump(BlockId(2))]),
+    );
+    program
+        .blocks
+        .insert(BlockId(2), Block(vec![Instruction::Return]));
+    eliminate_dead_blocks(&mut program);
+    expect![[r#"
+        Program:
+            entry: 0
+            callables:
+                Callable 0: Callable:
+                    name: main
+                    call_type: Regular
+                    input_type: <VOID>
+                    output_type: <VOID>
+                    body: 0
+                Callable 1: Callable:
+                    name: __quantum__qis__x__body
+                    call_type: Regular
+                    input_type:
+                        [0]: Qubit
+                    output_type: <VOID>
+                    body: <NONE>
+            blocks:
+                Block 0: Block:
+                    Call id(1), args( Qubit(0), )
+                    Return
+            config: Config:
+                capabilities: Base
+            num_qubits: 0
+            num_results: 0"#]]
+    .assert_eq(&program.to_string());
+}
+
+#[test]
+fn blocks_jumping_to_phi_are_not_bypassed() {
+    let mut program = new_program();
+    program.blocks.insert(
+        BlockId(0),
+        Block(vec![Instruction::Branch(
+            bool_var(0),
+            BlockId(1),
+            BlockId(2),
+        )]),
+    );
+    program
+        .blocks
+        .insert(BlockId(1), Block(vec![Instruction::Jump(BlockId(3))]));
+    program
+        .blocks
+        .insert(BlockId(2), Block(vec![Instruction::Jump(BlockId(3))]));
+    program.blocks.insert(
+        BlockId(3),
+        Block(vec![
+            Instruction::Phi(
+                vec![
+                    (Operand::Literal(Literal::Bool(true)), BlockId(1)),
+                    (Operand::Literal(Literal::Bool(false)), BlockId(2)),
+                ],
+                bool_var(1),
+            ),
+            Instruction::Return,
+        ]),
+    );
+    let program_before = program.to_string();
+    eliminate_dead_blocks(&mut program);
+    assert_eq!(program.to_string(), program_before);
+}
@@ -0,0 +1,426 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+use qsc_data_structures::fx::{FxHashMap, FxHashSet};
+
+use crate::rir::{Callable, CallableType, GateSet, Instruction, Literal, Operand, Program, Ty};
+
+/// The gates that calls can be decomposed from and into.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(super) enum Gate {
+    H,
+    X,
+    Y,
+    Z,
+    S,
+    SAdj,
+    T,
+    TAdj,
+    Sx,
+    Rx,
+    Ry,
+    Rz,
+    Cx,
+    Cy,
+    Cz,
+    Ccx,
+    Swap,
+    Rxx,
+    Ryy,
+    Rzz,
+}
+
+impl Gate {
+    pub(super) const ALL: [Gate; 20] = [
+        Gate::H,
+        Gate::X,
+        Gate::Y,
+        Gate::Z,
+        Gate::S,
+        Gate::SAdj,
+        Gate::T,
+        Gate::TAdj,
+        Gate::Sx,
+        Gate::Rx,
+        Gate::Ry,
+        Gate::Rz,
+        Gate::Cx,
+        Gate::Cy,
+        Gate::Cz,
+        Gate::Ccx,
+        Gate::Swap,
+        Gate::Rxx,
+        Gate::Ryy,
+        Gate::Rzz,
+    ];
+
+    pub(super) fn name(self) -> &'static str {
+        match self {
+            Gate::H => "__quantum__qis__h__body",
+            Gate::X => "__quantum__qis__x__body",
+            Gate::Y => "__quantum__qis__y__body",
+            Gate::Z => "__quantum__qis__z__body",
+            Gate::S => "__quantum__qis__s__body",
+            Gate::SAdj => "__quantum__qis__s__adj",
+            Gate::T => "__quantum__qis__t__body",
+            Gate::TAdj => "__quantum__qis__t__adj",
+            Gate::Sx => "__quantum__qis__sx__body",
+            Gate::Rx => "__quantum__qis__rx__body",
+            Gate::Ry => "__quantum__qis__ry__body",
+            Gate::Rz => "__quantum__qis__rz__body",
+            Gate::Cx => "__quantum__qis__cx__body",
+            Gate::Cy => "__quantum__qis__cy__body",
+            Gate::Cz => "__quantum__qis__cz__body",
+            Gate::Ccx => "__quantum__qis__ccx__body",
+            Gate::Swap => "__quantum__qis__swap__body",
+            Gate::Rxx => "__quantum__qis__rxx__body",
+            Gate::Ryy => "__quantum__qis__ryy__body",
+            Gate::Rzz => "__quantum__qis__rzz__body",
+        }
+    }
+
+    fn flag(self) -> GateSet {
+        match self {
+            Gate::H => GateSet::H,
+            Gate::X => GateSet::X,
+            Gate::Y => GateSet::Y,
+            Gate::Z => GateSet::Z,
+            Gate::S => GateSet::S,
+            Gate::SAdj => GateSet::S_ADJ,
+            Gate::T => GateSet::T,
+            Gate::TAdj => GateSet::T_ADJ,
+            Gate::Sx => GateSet::SX,
+            Gate::Rx => GateSet::RX,
+            Gate::Ry => GateSet::RY,
+            Gate::Rz => GateSet::RZ,
+            Gate::Cx => GateSet::CX,
+            Gate::Cy => GateSet::CY,
+            Gate::Cz => GateSet::CZ,
+            Gate::Ccx => GateSet::CCX,
+            Gate::Swap => GateSet::SWAP,
+            Gate::Rxx => GateSet::RXX,
+            Gate::Ryy => GateSet::RYY,
+            Gate::Rzz => GateSet::RZZ,
+        }
+    }
+
+    fn is_rotation(self) -> bool {
+        matches!(
+            self,
+            Gate::Rx | Gate::Ry | Gate::Rz | Gate::Rxx | Gate::Ryy | Gate::Rzz
+        )
+    }
+
+    fn num_qubits(self) -> usize {
+        match self {
+            Gate::Cx | Gate::Cy | Gate::Cz | Gate::Swap | Gate::Rxx | Gate::Ryy | Gate::Rzz => 2,
+            Gate::Ccx => 3,
+            _ => 1,
+        }
+    }
+
+    pub(super) fn decl(self) -> Callable {
+        let angle = self.is_rotation().then_some(Ty::Double);
+        Callable {
+            name: self.name().to_string(),
+            input_type: angle
+                .into_iter()
+                .chain(std::iter::repeat(Ty::Qubit).take(self.num_qubits()))
+                .collect(),
+            output_type: None,
+            body: None,
+            call_type: CallableType::Regular,
+        }
+    }
+}
+
+/// The angle of a rotation in a decomposition.
+#[derive(Clone, Copy, Debug)]
+enum Angle {
+    /// The gate is not a rotation.
+    None,
+    /// A fixed angle.
+    Fixed(f64),
+    /// The angle of the rotation being decomposed.
+    Param,
+}
+
+/// A gate applied to some of the qubits of the gate being decomposed, given by their index in its arguments.
+#[derive(Clone, Debug)]
+struct Step {
+    gate: Gate,
+    angle: Angle,
+    qubits: Vec<usize>,
+}
+
+fn gate(gate: Gate, qubits: &[usize]) -> Step {
+    Step {
+        gate,
+        angle: Angle::None,
+        qubits: qubits.to_vec(),
+    }
+}
+
+fn rotation(gate: Gate, angle: f64, qubits: &[usize]) -> Step {
+    Step {
+        gate,
+        angle: Angle::Fixed(angle),
+        qubits: qubits.to_vec(),
+    }
+}
+
+fn param_rotation(gate: Gate, qubits: &[usize]) -> Step {
+    Step {
+        gate,
+        angle: Angle::Param,
+        qubits: qubits.to_vec(),
+    }
+}
+
+/// The decompositions of a gate into other gates, each in circuit order and equal to the gate up to a global phase.
+/// When several of them apply, the one with the fewest gates is used, with ties broken by this order.
+#[allow(clippy::too_many_lines)]
+fn rules(target: Gate) -> Vec<Vec<Step>> {
+    use Gate::{Cx, Cz, Rx, Ry, Rz, Rzz, SAdj, Sx, TAdj, H, S, T, X, Z};
+    match target {
+        Gate::X => vec![
+            vec![rotation(Rx, PI, &[0])],
+            vec![gate(Sx, &[0]), gate(Sx, &[0])],
+            vec![gate(H, &[0]), gate(Z, &[0]), gate(H, &[0])],
+        ],
+        Gate::Y => vec![
+            vec![rotation(Ry, PI, &[0])],
+            vec![gate(X, &[0]), gate(Z, &[0])],
+        ],
+        Gate::Z => vec![
+            vec![rotation(Rz, PI, &[0])],
+            vec![gate(S, &[0]), gate(S, &[0])],
+            vec![gate(H, &[0]), gate(X, &[0]), gate(H, &[0])],
+        ],
+        Gate::S => vec![
+            vec![rotation(Rz, FRAC_PI_2, &[0])],
+            vec![gate(T, &[0]), gate(T, &[0])],
+        ],
+        Gate::SAdj => vec![
+            vec![rotation(Rz, -FRAC_PI_2, &[0])],
+            vec![gate(TAdj, &[0]), gate(TAdj, &[0])],
+        ],
+        Gate::T => vec![vec![rotation(Rz, FRAC_PI_4, &[0])]],
+        Gate::TAdj => vec![vec![rotation(Rz, -FRAC_PI_4, &[0])]],
+        Gate::H => vec![
+            vec![
+                rotation(Rz, FRAC_PI_2, &[0]),
+                gate(Sx, &[0]),
+                rotation(Rz, FRAC_PI_2, &[0]),
+            ],
+            vec![
+                rotation(Rz, FRAC_PI_2, &[0]),
+                rotation(Rx, FRAC_PI_2, &[0]),
+                rotation(Rz, FRAC_PI_2, &[0]),
+            ],
+            vec![rotation(Ry, FRAC_PI_2, &[0]), gate(X, &[0])],
+        ],
+        Gate::Sx => vec![
+            vec![rotation(Rx, FRAC_PI_2, &[0])],
+            vec![gate(H, &[0]), gate(S, &[0]), gate(H, &[0])],
+        ],
+        Gate::Rx => vec![vec![gate(H, &[0]), param_rotation(Rz, &[0]), gate(H, &[0])]],
+        Gate::Ry => vec![
+            vec![gate(SAdj, &[0]), param_rotation(Rx, &[0]), gate(S, &[0])],
+            vec![
+                rotation(Rz, -FRAC_PI_2, &[0]),
+                param_rotation(Rx, &[0]),
+                rotation(Rz, FRAC_PI_2, &[0]),
+            ],
+        ],
+        Gate::Rz => vec![vec![gate(H, &[0]), param_rotation(Rx, &[0]), gate(H, &[0])]],
+        Gate::Cx => vec![vec![gate(H, &[1]), gate(Cz, &[0, 1]), gate(H, &[1])]],
+        Gate::Cz => vec![
+            vec![gate(H, &[1]), gate(Cx, &[0, 1]), gate(H, &[1])],
+            vec![
+                rotation(Rzz, -FRAC_PI_2, &[0, 1]),
+                rotation(Rz, FRAC_PI_2, &[0]),
+                rotation(Rz, FRAC_PI_2, &[1]),
+            ],
+        ],
+        Gate::Cy => vec![vec![gate(SAdj, &[1]), gate(Cx, &[0, 1]), gate(S, &[1])]],
+        Gate::Swap => vec![vec![
+            gate(Cx, &[0, 1]),
+            gate(Cx, &[1, 0]),
+            gate(Cx, &[0, 1]),
+        ]],
+        Gate::Rzz => vec![vec![
+            gate(Cx, &[0, 1]),
+            param_rotation(Rz, &[1]),
+            gate(Cx, &[0, 1]),
+        ]],
+        Gate::Rxx => vec![vec![
+            gate(H, &[0]),
+            gate(H, &[1]),
+            param_rotation(Rzz, &[0, 1]),
+            gate(H, &[0]),
+            gate(H, &[1]),
+        ]],
+        Gate::Ryy => vec![vec![
+            rotation(Rx, FRAC_PI_2, &[0]),
+            rotation(Rx, FRAC_PI_2, &[1]),
+            param_rotation(Rzz, &[0, 1]),
+            rotation(Rx, -FRAC_PI_2, &[0]),
+            rotation(Rx, -FRAC_PI_2, &[1]),
+        ]],
+        Gate::Ccx => vec![vec![
+            gate(H, &[2]),
+            gate(Cx, &[1, 2]),
+            gate(TAdj, &[2]),
+            gate(Cx, &[0, 2]),
+            gate(T, &[2]),
+            gate(Cx, &[1, 2]),
+            gate(TAdj, &[2]),
+            gate(Cx, &[0, 2]),
+            gate(T, &[1]),
+            gate(T, &[2]),
+            gate(H, &[2]),
+            gate(Cx, &[0, 1]),
+            gate(T, &[0]),
+            gate(TAdj, &[1]),
+            gate(Cx, &[0, 1]),
+        ]],
+    }
+}
+
+/// Decomposes the given gate into gates of the gate set, using whichever of its rules whose gates can all be
+/// decomposed in turn yields the fewest gates, or returns `None` if there is no such rule. Gates that are already being decomposed are
+/// skipped, so that rules that undo each other don't recurse forever.
+fn decompose(gate: Gate, gate_set: GateSet, visiting: &mut FxHashSet<Gate>) -> Option<Vec<Step>> {
+    if gate_set.contains(gate.flag()) {
+        return Some(vec![Step {
+            gate,
+            angle: if gate.is_rotation() {
+                Angle::Param
+            } else {
+                Angle::None
+            },
+            qubits: (0..gate.num_qubits()).collect(),
+        }]);
+    }
+    if !visiting.insert(gate) {
+        return None;
+    }
+
+    let decomposition = rules(gate)
+        .into_iter()
+        .filter_map(|rule| {
+            let mut steps = Vec::new();
+            for step in rule {
+                for inner in decompose(step.gate, gate_set, visiting)? {
+                    steps.push(Step {
+                        gate: inner.gate,
+                        angle: match inner.angle {
+                            Angle::Param => step.angle,
+                            angle => angle,
+                        },
+                        qubits: inner.qubits.iter().map(|&i| step.qubits[i]).collect(),
+                    });
+                }
+            }
+            Some(steps)
+        })
+        .min_by_key(Vec::len);
+
+    visiting.remove(&gate);
+    decomposition
+}
+
+/// Rewrites the calls to gates outside of the gate set in the program's configuration into calls to gates in the set,
+/// using standard decompositions that are equal up to a global phase, so that the emitted program only uses the
+/// intrinsics the target declares. Gates that are needed by a decomposition and not yet declared are added to the
+/// program's callables, and gates that were fully decomposed are removed from them. Calls to gates that can't be
+/// expressed in the gate set are left as they are.
+pub fn decompose_to_gate_set(program: &mut Program) {
+    let Some(gate_set) = program.config.gate_set else {
+        return;
+    };
+
+    let mut gate_ids = FxHashMap::default();
+    let mut decompositions = FxHashMap::default();
+    for (callable_id, callable) in program.callables.iter() {
+        if callable.call_type != CallableType::Regular || callable.body.is_some() {
+            continue;
+        }
+        let Some(gate) = Gate::ALL
+            .into_iter()
+            .find(|gate| gate.name() == callable.name)
+        else {
+            continue;
+        };
+        gate_ids.insert(gate, callable_id);
+        if !gate_set.contains(gate.flag()) {
+            if let Some(steps) = decompose(gate, gate_set, &mut FxHashSet::default()) {
+                decompositions.insert(callable_id, (gate, steps));
+            }
+        }
+    }
+    if decompositions.is_empty() {
+        return;
+    }
+
+    // Declare the gates the decompositions use that the program doesn't declare yet, in a stable order.
+    let mut next_callable_id = program
+        .callables
+        .iter()
+        .map(|(callable_id, _)| callable_id.successor())
+        .max()
+        .unwrap_or_default();
+    for gate in Gate::ALL {
+        let used = decompositions
+            .values()
+            .any(|(_, steps)| steps.iter().any(|step| step.gate == gate));
+        if used && !gate_ids.contains_key(&gate) {
+            program.callables.insert(next_callable_id, gate.decl());
+            gate_ids.insert(gate, next_callable_id);
+            next_callable_id = next_callable_id.successor();
+        }
+    }
+
+    for block in program.blocks.values_mut() {
+        let instrs = std::mem::take(&mut block.0);
+        for instr in instrs {
+            let Instruction::Call(callable_id, args, None) = &instr else {
+                block.0.push(instr);
+                continue;
+            };
+            let Some((gate, steps)) = decompositions.get(callable_id) else {
+                block.0.push(instr);
+                continue;
+            };
+            let (angle, qubits) = if gate.is_rotation() {
+                (Some(args[0]), &args[1..])
+            } else {
+                (None, &args[..])
+            };
+            for step in steps {
+                let angle = match step.angle {
+                    Angle::None => None,
+                    Angle::Fixed(value) => Some(Operand::Literal(Literal::Double(value))),
+                    Angle::Param => angle,
+                };
+                let args = angle
+                    .into_iter()
+                    .chain(step.qubits.iter().map(|&i| qubits[i]))
+                    .collect();
+                block
+                    .0
+                    .push(Instruction::Call(gate_ids[&step.gate], args, None));
+            }
+        }
+    }
+
+    for &callable_id in decompositions.keys() {
+        program.callables.remove(callable_id);
+    }
+}
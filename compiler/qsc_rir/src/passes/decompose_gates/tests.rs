@@ -0,0 +1,218 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#![allow(clippy::needless_raw_string_hashes)]
+
+use expect_test::{expect, Expect};
+
+use crate::{
+    builder::{cx_decl, h_decl, m_decl, new_program_with_block, qubit, rx_decl, rz_decl, x_decl},
+    rir::{
+        BlockId, Callable, CallableId, GateSet, Instruction, Literal, Operand, Program, Ty,
+        Variable, VariableId,
+    },
+};
+
+use super::decompose_to_gate_set;
+
+const X: CallableId = CallableId(0);
+const H: CallableId = CallableId(1);
+const CX: CallableId = CallableId(2);
+const RZ: CallableId = CallableId(3);
+const RX: CallableId = CallableId(4);
+const M: CallableId = CallableId(5);
+
+fn callables() -> [(CallableId, Callable); 6] {
+    [
+        (X, x_decl()),
+        (H, h_decl()),
+        (CX, cx_decl()),
+        (RZ, rz_decl()),
+        (RX, rx_decl()),
+        (M, m_decl()),
+    ]
+}
+
+fn check(mut program: Program, expect: &Expect) {
+    decompose_to_gate_set(&mut program);
+    let mut actual = String::new();
+    for (callable_id, callable) in program.callables.iter() {
+        actual += &format!("Callable {}: {}\n", callable_id.0, callable.name);
+    }
+    actual += &program.get_block(BlockId(0)).to_string();
+    expect.assert_eq(&actual);
+}
+
+#[test]
+fn no_gate_set_leaves_program_unchanged() {
+    let program = new_program_with_block(
+        callables(),
+        vec![
+            Instruction::Call(H, vec![qubit(0)], None),
+            Instruction::Call(CX, vec![qubit(0), qubit(1)], None),
+            Instruction::Return,
+        ],
+    );
+    check(
+        program,
+        &expect![[r#"
+            Callable 0: __quantum__qis__x__body
+            Callable 1: __quantum__qis__h__body
+            Callable 2: __quantum__qis__cx__body
+            Callable 3: __quantum__qis__rz__body
+            Callable 4: __quantum__qis__rx__body
+            Callable 5: __quantum__qis__m__body
+            Block:
+                Call id(1), args( Qubit(0), )
+                Call id(2), args( Qubit(0), Qubit(1), )
+                Return"#]],
+    );
+}
+
+#[test]
+fn gates_decompose_into_rz_sx_cz() {
+    let mut program = new_program_with_block(
+        callables(),
+        vec![
+            Instruction::Call(H, vec![qubit(0)], None),
+            Instruction::Call(CX, vec![qubit(0), qubit(1)], None),
+            Instruction::Call(X, vec![qubit(1)], None),
+            Instruction::Call(
+                M,
+                vec![qubit(1), Operand::Literal(Literal::Result(0))],
+                None,
+            ),
+            Instruction::Return,
+        ],
+    );
+    program.config.gate_set = Some(GateSet::RZ | GateSet::SX | GateSet::CZ);
+    check(
+        program,
+        &expect![[r#"
+            Callable 3: __quantum__qis__rz__body
+            Callable 5: __quantum__qis__m__body
+            Callable 6: __quantum__qis__sx__body
+            Callable 7: __quantum__qis__cz__body
+            Block:
+                Call id(3), args( Double(1.5707963267948966), Qubit(0), )
+                Call id(6), args( Qubit(0), )
+                Call id(3), args( Double(1.5707963267948966), Qubit(0), )
+                Call id(3), args( Double(1.5707963267948966), Qubit(1), )
+                Call id(6), args( Qubit(1), )
+                Call id(3), args( Double(1.5707963267948966), Qubit(1), )
+                Call id(7), args( Qubit(0), Qubit(1), )
+                Call id(3), args( Double(1.5707963267948966), Qubit(1), )
+                Call id(6), args( Qubit(1), )
+                Call id(3), args( Double(1.5707963267948966), Qubit(1), )
+                Call id(6), args( Qubit(1), )
+                Call id(6), args( Qubit(1), )
+                Call id(5), args( Qubit(1), Result(0), )
+                Return"#]],
+    );
+}
+
+#[test]
+fn rotation_with_dynamic_angle_keeps_its_angle() {
+    let angle = Variable {
+        variable_id: VariableId(0),
+        ty: Ty::Double,
+    };
+    let mut program = new_program_with_block(
+        callables(),
+        vec![
+            Instruction::Call(RZ, vec![Operand::Variable(angle), qubit(0)], None),
+            Instruction::Return,
+        ],
+    );
+    program.config.gate_set = Some(GateSet::RX | GateSet::H | GateSet::CX);
+    check(
+        program,
+        &expect![[r#"
+            Callable 1: __quantum__qis__h__body
+            Callable 2: __quantum__qis__cx__body
+            Callable 4: __quantum__qis__rx__body
+            Callable 5: __quantum__qis__m__body
+            Block:
+                Call id(1), args( Qubit(0), )
+                Call id(4), args( Variable(0, Double), Qubit(0), )
+                Call id(1), args( Qubit(0), )
+                Return"#]],
+    );
+}
+
+#[test]
+fn decomposition_avoids_rules_that_undo_each_other() {
+    // `cx` is decomposed with `cz`, and `cz` with `cx` unless it uses `rzz`, so only the `rzz` rule applies.
+    let mut program = new_program_with_block(
+        callables(),
+        vec![
+            Instruction::Call(CX, vec![qubit(2), qubit(0)], None),
+            Instruction::Return,
+        ],
+    );
+    program.config.gate_set = Some(GateSet::RX | GateSet::RZ | GateSet::RZZ);
+    check(
+        program,
+        &expect![[r#"
+            Callable 3: __quantum__qis__rz__body
+            Callable 4: __quantum__qis__rx__body
+            Callable 5: __quantum__qis__m__body
+            Callable 6: __quantum__qis__rzz__body
+            Block:
+                Call id(3), args( Double(1.5707963267948966), Qubit(0), )
+                Call id(4), args( Double(1.5707963267948966), Qubit(0), )
+                Call id(3), args( Double(1.5707963267948966), Qubit(0), )
+                Call id(6), args( Double(-1.5707963267948966), Qubit(2), Qubit(0), )
+                Call id(3), args( Double(1.5707963267948966), Qubit(2), )
+                Call id(3), args( Double(1.5707963267948966), Qubit(0), )
+                Call id(3), args( Double(1.5707963267948966), Qubit(0), )
+                Call id(4), args( Double(1.5707963267948966), Qubit(0), )
+                Call id(3), args( Double(1.5707963267948966), Qubit(0), )
+                Return"#]],
+    );
+}
+
+#[test]
+fn gate_that_cannot_be_expressed_in_gate_set_is_left_unchanged() {
+    let mut program = new_program_with_block(
+        callables(),
+        vec![
+            Instruction::Call(
+                RZ,
+                vec![Operand::Literal(Literal::Double(0.5)), qubit(0)],
+                None,
+            ),
+            Instruction::Call(H, vec![qubit(0)], None),
+            Instruction::Return,
+        ],
+    );
+    program.config.gate_set = Some(GateSet::H);
+    check(
+        program,
+        &expect![[r#"
+            Callable 0: __quantum__qis__x__body
+            Callable 1: __quantum__qis__h__body
+            Callable 2: __quantum__qis__cx__body
+            Callable 3: __quantum__qis__rz__body
+            Callable 4: __quantum__qis__rx__body
+            Callable 5: __quantum__qis__m__body
+            Block:
+                Call id(3), args( Double(0.5), Qubit(0), )
+                Call id(1), args( Qubit(0), )
+                Return"#]],
+    );
+}
+
+#[test]
+fn gate_set_parses_from_comma_separated_names() {
+    let gate_set = "rz, sx,cz"
+        .parse::<GateSet>()
+        .expect("gate set should parse");
+    assert_eq!(gate_set, GateSet::RZ | GateSet::SX | GateSet::CZ);
+    expect!["sx, rz, cz"].assert_eq(&gate_set.to_string());
+    expect!["unknown gate `u3`"].assert_eq(
+        &"rz, u3"
+            .parse::<GateSet>()
+            .expect_err("gate set should not parse"),
+    );
+}
@@ -5,7 +5,7 @@
 mod tests;
 
 use crate::rir::{CallableType, Instruction, Program};
-use rustc_hash::FxHashSet;
+use qsc_data_structures::fx::FxHashSet;
 
 /// Defers measurements in each block of a program to the end of that block.
 /// Specifically, this function reorders instructions within a block to follow the given order:
@@ -0,0 +1,51 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    rir::{CallableType, Instruction, Program},
+    utils::get_all_block_successors,
+};
+
+/// Checks that the output positions of a program describe the output recording calls that end it. In each block of
+/// the entry point that returns, the calls just before the return must record, in order, a value of the kind of each
+/// output position. Calls that record output earlier in the block, such as messages, are not described by the
+/// positions.
+pub fn check_output_recording(program: &Program) {
+    if program.output_positions.is_empty() {
+        return;
+    }
+    let Some(entry_block) = program.get_callable(program.entry).body else {
+        return;
+    };
+
+    let mut blocks = vec![entry_block];
+    blocks.extend(get_all_block_successors(entry_block, program));
+    for block_id in blocks {
+        let block = program.get_block(block_id);
+        let Some((Instruction::Return, instrs)) = block.0.split_last() else {
+            continue;
+        };
+        assert!(
+            instrs.len() >= program.output_positions.len(),
+            "{block_id:?} returns without recording every output position"
+        );
+        let recorded = &instrs[instrs.len() - program.output_positions.len()..];
+        for (instr, position) in recorded.iter().zip(&program.output_positions) {
+            let Instruction::Call(callable_id, _, _) = instr else {
+                panic!(
+                    "{block_id:?} has {instr} where output {position} is expected to be recorded"
+                );
+            };
+            let callable = program.get_callable(*callable_id);
+            assert!(
+                callable.call_type == CallableType::OutputRecording
+                    && callable.name == position.kind.record_callable_name(),
+                "{block_id:?} calls {} where output {position} is expected to be recorded",
+                callable.name
+            );
+        }
+    }
+}
@@ -0,0 +1,92 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use crate::rir::Program;
+
+use super::check_output_recording;
+
+const CALLABLES: &str = "
+    callables:
+        Callable 0: Callable:
+            name: main
+            call_type: Regular
+            input_type: <VOID>
+            output_type: <VOID>
+            body: 0
+        Callable 1: Callable:
+            name: __quantum__rt__tuple_record_output
+            call_type: OutputRecording
+            input_type:
+                [0]: Integer
+                [1]: Pointer
+            output_type: <VOID>
+            body: <NONE>
+        Callable 2: Callable:
+            name: __quantum__rt__result_record_output
+            call_type: OutputRecording
+            input_type:
+                [0]: Result
+                [1]: Pointer
+            output_type: <VOID>
+            body: <NONE>
+";
+
+fn program(text: &str) -> Program {
+    format!("{CALLABLES}{text}")
+        .parse()
+        .expect("program should parse")
+}
+
+#[test]
+fn matching_output_recording_passes_check() {
+    check_output_recording(&program(
+        "
+        blocks:
+            Block 0: Block:
+                Call id(2), args( Result(0), Pointer, )
+                Call id(1), args( Integer(2), Pointer, )
+                Call id(2), args( Result(0), Pointer, )
+                Call id(2), args( Result(1), Pointer, )
+                Return
+        output_positions:
+            [0]: tuple at []
+            [1]: result at [0]
+            [2]: result at [1]
+        ",
+    ));
+}
+
+#[test]
+#[should_panic(
+    expected = "BlockId(0) calls __quantum__rt__result_record_output where output tuple at [] is expected to be recorded"
+)]
+fn mismatched_output_kind_fails_check() {
+    check_output_recording(&program(
+        "
+        blocks:
+            Block 0: Block:
+                Call id(2), args( Result(0), Pointer, )
+                Call id(1), args( Integer(1), Pointer, )
+                Return
+        output_positions:
+            [0]: tuple at []
+            [1]: result at [0]
+        ",
+    ));
+}
+
+#[test]
+#[should_panic(expected = "BlockId(0) returns without recording every output position")]
+fn missing_output_recording_fails_check() {
+    check_output_recording(&program(
+        "
+        blocks:
+            Block 0: Block:
+                Call id(2), args( Result(0), Pointer, )
+                Return
+        output_positions:
+            [0]: tuple at []
+            [1]: result at [0]
+        ",
+    ));
+}
@@ -0,0 +1,259 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use qsc_data_structures::fx::FxHashMap;
+
+use crate::rir::{
+    CallableId, CallableType, Instruction, Literal, Operand, PeepholeConfig, Program,
+};
+
+/// Gates that undo themselves, so that two adjacent calls on the same qubits have no effect.
+const SELF_INVERSE_GATES: &[&str] = &[
+    "__quantum__qis__h__body",
+    "__quantum__qis__x__body",
+    "__quantum__qis__y__body",
+    "__quantum__qis__z__body",
+    "__quantum__qis__cx__body",
+    "__quantum__qis__cy__body",
+    "__quantum__qis__cz__body",
+    "__quantum__qis__ccx__body",
+    "__quantum__qis__swap__body",
+];
+
+/// Rotations whose angles add up when two calls on the same qubits are applied one after the other.
+const ROTATION_GATES: &[&str] = &[
+    "__quantum__qis__rx__body",
+    "__quantum__qis__ry__body",
+    "__quantum__qis__rz__body",
+    "__quantum__qis__rxx__body",
+    "__quantum__qis__ryy__body",
+    "__quantum__qis__rzz__body",
+];
+
+/// Single-qubit unitary gates, which have no observable effect when their qubit is reset right after them.
+const SINGLE_QUBIT_GATES: &[&str] = &[
+    "__quantum__qis__h__body",
+    "__quantum__qis__x__body",
+    "__quantum__qis__y__body",
+    "__quantum__qis__z__body",
+    "__quantum__qis__s__body",
+    "__quantum__qis__s__adj",
+    "__quantum__qis__t__body",
+    "__quantum__qis__t__adj",
+    "__quantum__qis__rx__body",
+    "__quantum__qis__ry__body",
+    "__quantum__qis__rz__body",
+];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum GateKind {
+    SelfInverse,
+    Rotation,
+    SingleQubit,
+    Reset,
+}
+
+/// Applies the gate-level peephole optimizations enabled in the program's configuration to the calls in each block.
+/// Two calls are adjacent when no instruction between them operates on any of their qubits, so a rewrite can make
+/// the calls around it adjacent and enable further rewrites, as in `h; x; x; h`.
+pub fn apply_peephole_optimizations(program: &mut Program) {
+    let config = program.config.peephole;
+    if config == PeepholeConfig::NONE {
+        return;
+    }
+
+    let mut gate_kinds = FxHashMap::default();
+    for (callable_id, callable) in program.callables.iter() {
+        let name = callable.name.as_str();
+        if callable.call_type == CallableType::Reset {
+            gate_kinds.insert(callable_id, vec![GateKind::Reset]);
+        } else if callable.call_type == CallableType::Regular && callable.body.is_none() {
+            let mut kinds = Vec::new();
+            if SELF_INVERSE_GATES.contains(&name) {
+                kinds.push(GateKind::SelfInverse);
+            }
+            if ROTATION_GATES.contains(&name) {
+                kinds.push(GateKind::Rotation);
+            }
+            if SINGLE_QUBIT_GATES.contains(&name) {
+                kinds.push(GateKind::SingleQubit);
+            }
+            if !kinds.is_empty() {
+                gate_kinds.insert(callable_id, kinds);
+            }
+        }
+    }
+    if gate_kinds.is_empty() {
+        return;
+    }
+
+    for block in program.blocks.values_mut() {
+        let instrs = std::mem::take(&mut block.0);
+        block.0 = optimize_block(instrs, &gate_kinds, config);
+    }
+}
+
+fn optimize_block(
+    instrs: Vec<Instruction>,
+    gate_kinds: &FxHashMap<CallableId, Vec<GateKind>>,
+    config: PeepholeConfig,
+) -> Vec<Instruction> {
+    let is_kind = |callable_id: &CallableId, kind: GateKind| {
+        gate_kinds
+            .get(callable_id)
+            .is_some_and(|kinds| kinds.contains(&kind))
+    };
+
+    let mut optimized: Vec<Option<Instruction>> = Vec::with_capacity(instrs.len());
+    // For each qubit, the indices into `optimized` of the instructions kept so far that operate on it.
+    let mut qubit_instrs: FxHashMap<u32, Vec<usize>> = FxHashMap::default();
+
+    for instr in instrs {
+        let qubits = get_qubits(&instr);
+        if let Instruction::Call(callable_id, args, None) = &instr {
+            // The last kept instruction, if it is the latest one on every qubit this call operates on.
+            let prev_idx = last_on_all(&qubit_instrs, &qubits);
+            let prev_call = prev_idx.and_then(|idx| match &optimized[idx] {
+                Some(Instruction::Call(prev_callable_id, prev_args, None))
+                    if prev_callable_id == callable_id =>
+                {
+                    Some((idx, prev_args))
+                }
+                _ => None,
+            });
+
+            if config.cancel_self_inverse && is_kind(callable_id, GateKind::SelfInverse) {
+                if let Some((idx, prev_args)) = prev_call {
+                    if prev_args == args {
+                        remove(&mut optimized, &mut qubit_instrs, idx, &qubits);
+                        continue;
+                    }
+                }
+            }
+
+            if config.merge_rotations && is_kind(callable_id, GateKind::Rotation) {
+                if let Some((idx, prev_args)) = prev_call {
+                    if let Some(angle) = merged_angle(prev_args, args) {
+                        if angle == 0.0 {
+                            remove(&mut optimized, &mut qubit_instrs, idx, &qubits);
+                        } else {
+                            set_angle(&mut optimized[idx], angle);
+                        }
+                        continue;
+                    }
+                }
+            }
+
+            if config.remove_before_reset && is_kind(callable_id, GateKind::Reset) {
+                if let [qubit] = qubits.as_slice() {
+                    while let Some(idx) = last_on_all(&qubit_instrs, &qubits) {
+                        let removable = matches!(
+                            &optimized[idx],
+                            Some(Instruction::Call(prev_callable_id, prev_args, None))
+                                if is_kind(prev_callable_id, GateKind::SingleQubit)
+                                    && get_qubits_of_args(prev_args) == [*qubit]
+                        );
+                        if !removable {
+                            break;
+                        }
+                        remove(&mut optimized, &mut qubit_instrs, idx, &qubits);
+                    }
+                }
+            }
+        }
+
+        let idx = optimized.len();
+        optimized.push(Some(instr));
+        for qubit in qubits {
+            let instrs = qubit_instrs.entry(qubit).or_default();
+            if instrs.last() != Some(&idx) {
+                instrs.push(idx);
+            }
+        }
+    }
+
+    optimized.into_iter().flatten().collect()
+}
+
+/// Gets the index of the latest kept instruction on the given qubits, if it is the same for all of them.
+fn last_on_all(qubit_instrs: &FxHashMap<u32, Vec<usize>>, qubits: &[u32]) -> Option<usize> {
+    let mut last = None;
+    for qubit in qubits {
+        let idx = *qubit_instrs.get(qubit)?.last()?;
+        if last.is_some_and(|last| last != idx) {
+            return None;
+        }
+        last = Some(idx);
+    }
+    last
+}
+
+fn remove(
+    optimized: &mut [Option<Instruction>],
+    qubit_instrs: &mut FxHashMap<u32, Vec<usize>>,
+    idx: usize,
+    qubits: &[u32],
+) {
+    optimized[idx] = None;
+    for qubit in qubits {
+        if let Some(instrs) = qubit_instrs.get_mut(qubit) {
+            if instrs.last() == Some(&idx) {
+                instrs.pop();
+            }
+        }
+    }
+}
+
+/// Gets the sum of the angles of two rotations on the same qubits, if both angles are literals.
+fn merged_angle(prev_args: &[Operand], args: &[Operand]) -> Option<f64> {
+    if prev_args.len() != args.len() {
+        return None;
+    }
+    let mut angle = None;
+    for (prev_arg, arg) in prev_args.iter().zip(args) {
+        match (prev_arg, arg) {
+            (Operand::Literal(Literal::Double(prev)), Operand::Literal(Literal::Double(next))) => {
+                if angle.is_some() {
+                    return None;
+                }
+                angle = Some(prev + next);
+            }
+            (Operand::Literal(Literal::Qubit(prev)), Operand::Literal(Literal::Qubit(next)))
+                if prev == next => {}
+            _ => return None,
+        }
+    }
+    angle
+}
+
+fn set_angle(instr: &mut Option<Instruction>, angle: f64) {
+    if let Some(Instruction::Call(_, args, _)) = instr {
+        for arg in args {
+            if let Operand::Literal(Literal::Double(value)) = arg {
+                *value = angle;
+            }
+        }
+    }
+}
+
+fn get_qubits(instr: &Instruction) -> Vec<u32> {
+    match instr {
+        Instruction::Call(_, args, _) => get_qubits_of_args(args),
+        _ => Vec::new(),
+    }
+}
+
+fn get_qubits_of_args(args: &[Operand]) -> Vec<u32> {
+    let mut qubits = Vec::new();
+    for arg in args {
+        if let Operand::Literal(Literal::Qubit(qubit)) = arg {
+            if !qubits.contains(qubit) {
+                qubits.push(*qubit);
+            }
+        }
+    }
+    qubits
+}
@@ -0,0 +1,268 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#![allow(clippy::needless_raw_string_hashes)]
+
+use expect_test::expect;
+
+use crate::{
+    builder::{
+        cx_decl, h_decl, m_decl, new_program_with_block, qubit, reset_decl, result, rx_decl,
+        rz_decl, x_decl,
+    },
+    rir::{
+        BlockId, Callable, CallableId, Instruction, Literal, Operand, PeepholeConfig, Program, Ty,
+        Variable, VariableId,
+    },
+};
+
+use super::apply_peephole_optimizations;
+
+const X: CallableId = CallableId(0);
+const H: CallableId = CallableId(1);
+const CX: CallableId = CallableId(2);
+const RZ: CallableId = CallableId(3);
+const RX: CallableId = CallableId(4);
+const RESET: CallableId = CallableId(5);
+const M: CallableId = CallableId(6);
+
+fn angle(value: f64) -> Operand {
+    Operand::Literal(Literal::Double(value))
+}
+
+/// Applies every peephole optimization to the program.
+fn apply_all_optimizations(program: &mut Program) {
+    program.config.peephole = PeepholeConfig::ALL;
+    apply_peephole_optimizations(program);
+}
+
+fn callables() -> [(CallableId, Callable); 7] {
+    [
+        (X, x_decl()),
+        (H, h_decl()),
+        (CX, cx_decl()),
+        (RZ, rz_decl()),
+        (RX, rx_decl()),
+        (RESET, reset_decl()),
+        (M, m_decl()),
+    ]
+}
+
+#[test]
+fn adjacent_self_inverse_gates_cancel() {
+    let mut program = new_program_with_block(
+        callables(),
+        vec![
+            Instruction::Call(H, vec![qubit(0)], None),
+            Instruction::Call(H, vec![qubit(0)], None),
+            Instruction::Call(CX, vec![qubit(0), qubit(1)], None),
+            Instruction::Call(CX, vec![qubit(0), qubit(1)], None),
+            Instruction::Return,
+        ],
+    );
+
+    apply_all_optimizations(&mut program);
+    expect![[r#"
+        Block:
+            Return"#]]
+    .assert_eq(&program.get_block(BlockId(0)).to_string());
+}
+
+#[test]
+fn nested_self_inverse_gates_cancel() {
+    let mut program = new_program_with_block(
+        callables(),
+        vec![
+            Instruction::Call(H, vec![qubit(0)], None),
+            Instruction::Call(X, vec![qubit(0)], None),
+            Instruction::Call(X, vec![qubit(1)], None),
+            Instruction::Call(X, vec![qubit(0)], None),
+            Instruction::Call(H, vec![qubit(0)], None),
+            Instruction::Return,
+        ],
+    );
+
+    apply_all_optimizations(&mut program);
+    expect![[r#"
+        Block:
+            Call id(0), args( Qubit(1), )
+            Return"#]]
+    .assert_eq(&program.get_block(BlockId(0)).to_string());
+}
+
+#[test]
+fn self_inverse_gates_on_different_qubits_do_not_cancel() {
+    let mut program = new_program_with_block(
+        callables(),
+        vec![
+            Instruction::Call(CX, vec![qubit(0), qubit(1)], None),
+            Instruction::Call(CX, vec![qubit(1), qubit(0)], None),
+            Instruction::Call(H, vec![qubit(2)], None),
+            Instruction::Call(CX, vec![qubit(1), qubit(2)], None),
+            Instruction::Call(H, vec![qubit(2)], None),
+            Instruction::Return,
+        ],
+    );
+
+    apply_all_optimizations(&mut program);
+    expect![[r#"
+        Block:
+            Call id(2), args( Qubit(0), Qubit(1), )
+            Call id(2), args( Qubit(1), Qubit(0), )
+            Call id(1), args( Qubit(2), )
+            Call id(2), args( Qubit(1), Qubit(2), )
+            Call id(1), args( Qubit(2), )
+            Return"#]]
+    .assert_eq(&program.get_block(BlockId(0)).to_string());
+}
+
+#[test]
+fn self_inverse_gates_separated_by_measurement_do_not_cancel() {
+    let mut program = new_program_with_block(
+        callables(),
+        vec![
+            Instruction::Call(X, vec![qubit(0)], None),
+            Instruction::Call(M, vec![qubit(0), result(0)], None),
+            Instruction::Call(X, vec![qubit(0)], None),
+            Instruction::Return,
+        ],
+    );
+
+    apply_all_optimizations(&mut program);
+    expect![[r#"
+        Block:
+            Call id(0), args( Qubit(0), )
+            Call id(6), args( Qubit(0), Result(0), )
+            Call id(0), args( Qubit(0), )
+            Return"#]]
+    .assert_eq(&program.get_block(BlockId(0)).to_string());
+}
+
+#[test]
+fn rotations_with_literal_angles_merge() {
+    let mut program = new_program_with_block(
+        callables(),
+        vec![
+            Instruction::Call(RZ, vec![angle(0.5), qubit(0)], None),
+            Instruction::Call(RZ, vec![angle(0.25), qubit(0)], None),
+            Instruction::Call(RX, vec![angle(1.0), qubit(1)], None),
+            Instruction::Call(RX, vec![angle(-1.0), qubit(1)], None),
+            Instruction::Return,
+        ],
+    );
+
+    apply_all_optimizations(&mut program);
+    expect![[r#"
+        Block:
+            Call id(3), args( Double(0.75), Qubit(0), )
+            Return"#]]
+    .assert_eq(&program.get_block(BlockId(0)).to_string());
+}
+
+#[test]
+fn rotations_with_dynamic_angles_or_different_axes_do_not_merge() {
+    let var = Operand::Variable(Variable {
+        variable_id: VariableId(0),
+        ty: Ty::Double,
+    });
+    let mut program = new_program_with_block(
+        callables(),
+        vec![
+            Instruction::Call(RZ, vec![angle(0.5), qubit(0)], None),
+            Instruction::Call(RZ, vec![var, qubit(0)], None),
+            Instruction::Call(RZ, vec![angle(0.5), qubit(1)], None),
+            Instruction::Call(RX, vec![angle(0.5), qubit(1)], None),
+            Instruction::Return,
+        ],
+    );
+
+    apply_all_optimizations(&mut program);
+    expect![[r#"
+        Block:
+            Call id(3), args( Double(0.5), Qubit(0), )
+            Call id(3), args( Variable(0, Double), Qubit(0), )
+            Call id(3), args( Double(0.5), Qubit(1), )
+            Call id(4), args( Double(0.5), Qubit(1), )
+            Return"#]]
+    .assert_eq(&program.get_block(BlockId(0)).to_string());
+}
+
+#[test]
+fn single_qubit_gates_before_reset_removed() {
+    let mut program = new_program_with_block(
+        callables(),
+        vec![
+            Instruction::Call(CX, vec![qubit(0), qubit(1)], None),
+            Instruction::Call(H, vec![qubit(1)], None),
+            Instruction::Call(RZ, vec![angle(0.5), qubit(1)], None),
+            Instruction::Call(X, vec![qubit(0)], None),
+            Instruction::Call(RESET, vec![qubit(1)], None),
+            Instruction::Return,
+        ],
+    );
+
+    apply_all_optimizations(&mut program);
+    expect![[r#"
+        Block:
+            Call id(2), args( Qubit(0), Qubit(1), )
+            Call id(0), args( Qubit(0), )
+            Call id(5), args( Qubit(1), )
+            Return"#]]
+    .assert_eq(&program.get_block(BlockId(0)).to_string());
+}
+
+#[test]
+fn disabled_optimizations_leave_program_unchanged() {
+    let mut program = new_program_with_block(
+        callables(),
+        vec![
+            Instruction::Call(H, vec![qubit(0)], None),
+            Instruction::Call(H, vec![qubit(0)], None),
+            Instruction::Call(RZ, vec![angle(0.5), qubit(0)], None),
+            Instruction::Call(RZ, vec![angle(0.25), qubit(0)], None),
+            Instruction::Call(RESET, vec![qubit(0)], None),
+            Instruction::Return,
+        ],
+    );
+    program.config.peephole = PeepholeConfig::NONE;
+
+    apply_peephole_optimizations(&mut program);
+    expect![[r#"
+        Block:
+            Call id(1), args( Qubit(0), )
+            Call id(1), args( Qubit(0), )
+            Call id(3), args( Double(0.5), Qubit(0), )
+            Call id(3), args( Double(0.25), Qubit(0), )
+            Call id(5), args( Qubit(0), )
+            Return"#]]
+    .assert_eq(&program.get_block(BlockId(0)).to_string());
+}
+
+#[test]
+fn only_enabled_optimizations_applied() {
+    let mut program = new_program_with_block(
+        callables(),
+        vec![
+            Instruction::Call(H, vec![qubit(0)], None),
+            Instruction::Call(H, vec![qubit(0)], None),
+            Instruction::Call(RZ, vec![angle(0.5), qubit(0)], None),
+            Instruction::Call(RZ, vec![angle(0.25), qubit(0)], None),
+            Instruction::Call(RESET, vec![qubit(0)], None),
+            Instruction::Return,
+        ],
+    );
+    program.config.peephole = PeepholeConfig {
+        merge_rotations: true,
+        ..PeepholeConfig::NONE
+    };
+
+    apply_peephole_optimizations(&mut program);
+    expect![[r#"
+        Block:
+            Call id(1), args( Qubit(0), )
+            Call id(1), args( Qubit(0), )
+            Call id(3), args( Double(0.75), Qubit(0), )
+            Call id(5), args( Qubit(0), )
+            Return"#]]
+    .assert_eq(&program.get_block(BlockId(0)).to_string());
+}
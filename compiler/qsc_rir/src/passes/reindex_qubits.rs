@@ -6,8 +6,8 @@ mod tests;
 
 use std::{collections::hash_map::Entry, ops::Sub};
 
+use qsc_data_structures::fx::FxHashMap;
 use qsc_data_structures::index_map::IndexMap;
-use rustc_hash::FxHashMap;
 
 use crate::{
     builder,
@@ -33,7 +33,10 @@ struct BlockQubitMap {
 pub fn reindex_qubits(program: &mut Program) {
     validate_assumptions(program);
 
-    let (used_m, m_id) = match find_callable(program, "__quantum__qis__m__body") {
+    // Targets that only implement `mz` have it in place of `m`.
+    let (used_m, m_id) = match find_callable(program, "__quantum__qis__m__body")
+        .or_else(|| find_callable(program, "__quantum__qis__mz__body"))
+    {
         Some(id) => (true, id),
         None => (false, add_m(program)),
     };
@@ -193,6 +196,7 @@ impl ReindexQubitPass {
 
                     if *call_id == self.m_id {
                         if qubit_used_in_instrs(
+                            program,
                             *ids_used
                                 .first()
                                 .expect("measurement call should have at least one argument"),
@@ -250,13 +254,18 @@ impl ReindexQubitPass {
     }
 }
 
-fn qubit_used_in_instrs<'a>(id: u32, instrs: impl Iterator<Item = &'a Instruction>) -> bool {
+/// Checks whether the qubit is used by the instructions before it is reset, since a reset gives it a new id.
+fn qubit_used_in_instrs<'a>(
+    program: &Program,
+    id: u32,
+    instrs: impl Iterator<Item = &'a Instruction>,
+) -> bool {
     for instr in instrs {
-        if let Instruction::Call(_, args, _) = instr {
+        if let Instruction::Call(call_id, args, _) = instr {
             for arg in args {
                 if let Operand::Literal(Literal::Qubit(qubit_id)) = arg {
                     if *qubit_id == id {
-                        return true;
+                        return program.get_callable(*call_id).call_type != CallableType::Reset;
                     }
                 }
             }
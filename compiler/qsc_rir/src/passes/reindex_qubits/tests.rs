@@ -6,7 +6,9 @@
 use expect_test::expect;
 
 use crate::{
-    builder::{cx_decl, h_decl, m_decl, mresetz_decl, read_result_decl, reset_decl, x_decl},
+    builder::{
+        cx_decl, h_decl, m_decl, mresetz_decl, mz_decl, read_result_decl, reset_decl, x_decl,
+    },
     rir::{
         Block, BlockId, CallableId, CallableType, Instruction, Literal, Operand, Program, Ty,
         Variable, VariableId,
@@ -313,6 +315,65 @@ fn qubit_reindexed_multiple_times_with_mz_inserts_multiple_cx() {
     assert_eq!(program.num_qubits, 4);
 }
 
+#[test]
+fn qubit_reindexed_after_mz_and_reset_on_target_without_m() {
+    const X: CallableId = CallableId(0);
+    const MZ: CallableId = CallableId(1);
+    const RESET: CallableId = CallableId(2);
+    let mut program = Program::new();
+    program.num_qubits = 1;
+    program.callables.insert(X, x_decl());
+    program.callables.insert(MZ, mz_decl());
+    program.callables.insert(RESET, reset_decl());
+    program.blocks.insert(
+        BlockId(0),
+        Block(vec![
+            Instruction::Call(X, vec![Operand::Literal(Literal::Qubit(0))], None),
+            Instruction::Call(
+                MZ,
+                vec![
+                    Operand::Literal(Literal::Qubit(0)),
+                    Operand::Literal(Literal::Result(0)),
+                ],
+                None,
+            ),
+            Instruction::Call(RESET, vec![Operand::Literal(Literal::Qubit(0))], None),
+            Instruction::Call(X, vec![Operand::Literal(Literal::Qubit(0))], None),
+            Instruction::Call(
+                MZ,
+                vec![
+                    Operand::Literal(Literal::Qubit(0)),
+                    Operand::Literal(Literal::Result(1)),
+                ],
+                None,
+            ),
+            Instruction::Return,
+        ]),
+    );
+
+    reindex_qubits(&mut program);
+    expect![[r#"
+        Block:
+            Call id(0), args( Qubit(0), )
+            Call id(1), args( Qubit(0), Result(0), )
+            Call id(0), args( Qubit(1), )
+            Call id(1), args( Qubit(1), Result(1), )
+            Return"#]]
+    .assert_eq(&program.get_block(BlockId(0)).to_string());
+    expect![[r#"
+        0: __quantum__qis__x__body
+        1: __quantum__qis__mz__body"#]]
+    .assert_eq(
+        &program
+            .callables
+            .iter()
+            .map(|(id, callable)| format!("{}: {}", id.0, callable.name))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    );
+    assert_eq!(program.num_qubits, 2);
+}
+
 #[test]
 #[should_panic(expected = "Reindexing qubits across multiple blocks is not supported")]
 fn qubit_reindexed_across_branches() {
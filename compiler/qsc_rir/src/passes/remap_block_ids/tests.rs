@@ -156,6 +156,51 @@ fn remap_block_ids_out_of_order_no_branches() {
     .assert_eq(&program.to_string());
 }
 
+#[test]
+fn remap_block_ids_keeps_block_names() {
+    let mut program = Program::new();
+    program.callables.insert(
+        CallableId(0),
+        Callable {
+            name: "main".to_string(),
+            input_type: Vec::new(),
+            output_type: None,
+            body: Some(BlockId(5)),
+            call_type: CallableType::Regular,
+        },
+    );
+    program
+        .blocks
+        .insert(BlockId(5), Block(vec![Instruction::Jump(BlockId(3))]));
+    program
+        .blocks
+        .insert(BlockId(3), Block(vec![Instruction::Jump(BlockId(7))]));
+    program
+        .blocks
+        .insert(BlockId(7), Block(vec![Instruction::Return]));
+    program.block_names.insert(BlockId(5), "entry".into());
+    program.block_names.insert(BlockId(7), "if_continue".into());
+
+    remap_block_ids(&mut program);
+    expect![[r#"
+        [
+            (
+                BlockId(
+                    0,
+                ),
+                "entry",
+            ),
+            (
+                BlockId(
+                    2,
+                ),
+                "if_continue",
+            ),
+        ]
+    "#]]
+    .assert_debug_eq(&program.block_names.iter().collect::<Vec<_>>());
+}
+
 #[test]
 fn remap_block_ids_out_of_order_with_one_branch() {
     let mut program = Program::new();
@@ -619,3 +664,83 @@ fn remap_block_ids_ensures_acyclic_program_gets_topological_ordering() {
             num_results: 0"#]]
     .assert_eq(&program.to_string());
 }
+
+#[test]
+fn remap_block_ids_loop_with_branching_body_gets_reverse_postorder() {
+    let mut program = Program::new();
+    program.callables.insert(
+        CallableId(0),
+        Callable {
+            name: "main".to_string(),
+            input_type: Vec::new(),
+            output_type: None,
+            body: Some(BlockId(0)),
+            call_type: CallableType::Regular,
+        },
+    );
+    program
+        .blocks
+        .insert(BlockId(0), Block(vec![Instruction::Jump(BlockId(1))]));
+    program.blocks.insert(
+        BlockId(1),
+        Block(vec![Instruction::Branch(
+            Variable {
+                variable_id: VariableId(0),
+                ty: Ty::Boolean,
+            },
+            BlockId(3),
+            BlockId(2),
+        )]),
+    );
+    program
+        .blocks
+        .insert(BlockId(2), Block(vec![Instruction::Return]));
+    program.blocks.insert(
+        BlockId(3),
+        Block(vec![Instruction::Branch(
+            Variable {
+                variable_id: VariableId(1),
+                ty: Ty::Boolean,
+            },
+            BlockId(5),
+            BlockId(4),
+        )]),
+    );
+    program
+        .blocks
+        .insert(BlockId(4), Block(vec![Instruction::Jump(BlockId(1))]));
+    program
+        .blocks
+        .insert(BlockId(5), Block(vec![Instruction::Jump(BlockId(4))]));
+
+    // After
+    remap_block_ids(&mut program);
+    expect![[r#"
+        Program:
+            entry: 0
+            callables:
+                Callable 0: Callable:
+                    name: main
+                    call_type: Regular
+                    input_type: <VOID>
+                    output_type: <VOID>
+                    body: 0
+            blocks:
+                Block 0: Block:
+                    Jump(1)
+                Block 1: Block:
+                    Branch Variable(0, Boolean), 2, 5
+                Block 2: Block:
+                    Branch Variable(1, Boolean), 3, 4
+                Block 3: Block:
+                    Jump(4)
+                Block 4: Block:
+                    Jump(1)
+                Block 5: Block:
+                    Return
+            config: Config:
+                capabilities: Base
+            num_qubits: 0
+            num_results: 0"#]]
+    .assert_eq(&program.to_string());
+}
@@ -0,0 +1,139 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use qsc_data_structures::fx::FxHashMap;
+use qsc_data_structures::index_map::IndexMap;
+
+use crate::rir::{CallableId, Instruction, Operand, Program, Variable, VariableId};
+
+use super::remap_block_ids;
+
+/// Renumbers the blocks, callables and variables of the program in a canonical order that only depends on the
+/// structure of the program and not on the order in which IDs were handed out while it was built.
+/// Blocks are numbered in topological order from the entry block, the entry callable is numbered first followed by
+/// the other callables in the order they are first called, and variables are numbered in the order they first appear
+/// when visiting the blocks in order.
+pub fn renumber_ids(program: &mut Program) {
+    remap_block_ids(program);
+    renumber_callables(program);
+    renumber_variables(program);
+}
+
+fn renumber_callables(program: &mut Program) {
+    let mut callable_id_map = FxHashMap::default();
+    callable_id_map.insert(program.entry, CallableId(0));
+    for (_, block) in program.blocks.iter() {
+        for instr in &block.0 {
+            if let Instruction::Call(callable_id, _, _) = instr {
+                let next_id = callable_id_map.len().into();
+                callable_id_map.entry(*callable_id).or_insert(next_id);
+            }
+        }
+    }
+
+    // Callables that are never called keep their relative order after the ones that are.
+    let callables = program.callables.drain().collect::<Vec<_>>();
+    for (callable_id, _) in &callables {
+        let next_id = callable_id_map.len().into();
+        callable_id_map.entry(*callable_id).or_insert(next_id);
+    }
+    for (callable_id, callable) in callables {
+        program
+            .callables
+            .insert(callable_id_map[&callable_id], callable);
+    }
+    program.entry = callable_id_map[&program.entry];
+
+    for block in program.blocks.values_mut() {
+        for instr in &mut block.0 {
+            if let Instruction::Call(callable_id, _, _) = instr {
+                *callable_id = callable_id_map[callable_id];
+            }
+        }
+    }
+}
+
+fn renumber_variables(program: &mut Program) {
+    let mut variable_id_map = FxHashMap::default();
+    for block in program.blocks.values_mut() {
+        for instr in &mut block.0 {
+            for_each_variable_mut(instr, |variable| {
+                let next_id: VariableId = variable_id_map.len().into();
+                variable.variable_id = *variable_id_map
+                    .entry(variable.variable_id)
+                    .or_insert(next_id);
+            });
+        }
+    }
+
+    let variable_names = program.variable_names.drain().collect::<Vec<_>>();
+    let mut renamed = IndexMap::default();
+    for (variable_id, name) in variable_names {
+        if let Some(new_id) = variable_id_map.get(&variable_id) {
+            renamed.insert(*new_id, name);
+        }
+    }
+    program.variable_names = renamed;
+}
+
+/// Calls `f` on every variable in the instruction, in the order they appear, with operands before the variable
+/// the instruction assigns to.
+fn for_each_variable_mut(instr: &mut Instruction, mut f: impl FnMut(&mut Variable)) {
+    fn visit_operand(operand: &mut Operand, f: &mut impl FnMut(&mut Variable)) {
+        if let Operand::Variable(variable) = operand {
+            f(variable);
+        }
+    }
+
+    match instr {
+        Instruction::Store(operand, variable)
+        | Instruction::LogicalNot(operand, variable)
+        | Instruction::BitwiseNot(operand, variable)
+        | Instruction::Sitofp(operand, variable)
+        | Instruction::Fptosi(operand, variable) => {
+            visit_operand(operand, &mut f);
+            f(variable);
+        }
+        Instruction::Call(_, args, variable) => {
+            for arg in args {
+                visit_operand(arg, &mut f);
+            }
+            if let Some(variable) = variable {
+                f(variable);
+            }
+        }
+        Instruction::Branch(variable, _, _) => f(variable),
+        Instruction::Add(lhs, rhs, variable)
+        | Instruction::Sub(lhs, rhs, variable)
+        | Instruction::Mul(lhs, rhs, variable)
+        | Instruction::Sdiv(lhs, rhs, variable)
+        | Instruction::Srem(lhs, rhs, variable)
+        | Instruction::Shl(lhs, rhs, variable)
+        | Instruction::Ashr(lhs, rhs, variable)
+        | Instruction::Fadd(lhs, rhs, variable)
+        | Instruction::Fsub(lhs, rhs, variable)
+        | Instruction::Fmul(lhs, rhs, variable)
+        | Instruction::Fdiv(lhs, rhs, variable)
+        | Instruction::Fcmp(_, lhs, rhs, variable)
+        | Instruction::Icmp(_, lhs, rhs, variable)
+        | Instruction::LogicalAnd(lhs, rhs, variable)
+        | Instruction::LogicalOr(lhs, rhs, variable)
+        | Instruction::BitwiseAnd(lhs, rhs, variable)
+        | Instruction::BitwiseOr(lhs, rhs, variable)
+        | Instruction::BitwiseXor(lhs, rhs, variable) => {
+            visit_operand(lhs, &mut f);
+            visit_operand(rhs, &mut f);
+            f(variable);
+        }
+        Instruction::Phi(args, variable) => {
+            for (operand, _) in args {
+                visit_operand(operand, &mut f);
+            }
+            f(variable);
+        }
+        Instruction::Jump(_) | Instruction::Return => {}
+    }
+}
@@ -0,0 +1,132 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#![allow(clippy::needless_raw_string_hashes)]
+
+use expect_test::expect;
+
+use crate::{
+    builder::{self, bool_var},
+    rir::{
+        Block, BlockId, Callable, CallableId, CallableType, Instruction, Literal, Operand, Program,
+        VariableId,
+    },
+};
+
+use super::renumber_ids;
+
+/// Builds a program whose IDs were handed out out of order: the entry callable and blocks have high IDs and
+/// variables are numbered in reverse order of appearance.
+fn out_of_order_program() -> Program {
+    let mut program = Program::new();
+    program.entry = CallableId(3);
+    program.callables.insert(
+        CallableId(3),
+        Callable {
+            name: "main".to_string(),
+            input_type: Vec::new(),
+            output_type: None,
+            body: Some(BlockId(4)),
+            call_type: CallableType::Regular,
+        },
+    );
+    program.callables.insert(CallableId(1), builder::x_decl());
+    program
+        .callables
+        .insert(CallableId(0), builder::read_result_decl());
+    program.blocks.insert(
+        BlockId(4),
+        Block(vec![
+            Instruction::Call(
+                CallableId(0),
+                vec![Operand::Literal(Literal::Result(0))],
+                Some(bool_var(7)),
+            ),
+            Instruction::LogicalNot(Operand::Variable(bool_var(7)), bool_var(2)),
+            Instruction::Branch(bool_var(2), BlockId(1), BlockId(2)),
+        ]),
+    );
+    program.blocks.insert(
+        BlockId(1),
+        Block(vec![
+            Instruction::Call(
+                CallableId(1),
+                vec![Operand::Literal(Literal::Qubit(0))],
+                None,
+            ),
+            Instruction::Jump(BlockId(2)),
+        ]),
+    );
+    program
+        .blocks
+        .insert(BlockId(2), Block(vec![Instruction::Return]));
+    program
+        .variable_names
+        .insert(VariableId(2), "flipped".into());
+    program
+}
+
+#[test]
+fn renumber_ids_orders_blocks_callables_and_variables() {
+    let mut program = out_of_order_program();
+    renumber_ids(&mut program);
+    expect![[r#"
+        Program:
+            entry: 0
+            callables:
+                Callable 0: Callable:
+                    name: main
+                    call_type: Regular
+                    input_type: <VOID>
+                    output_type: <VOID>
+                    body: 0
+                Callable 1: Callable:
+                    name: __quantum__qis__read_result__body
+                    call_type: Readout
+                    input_type:
+                        [0]: Result
+                    output_type: Boolean
+                    body: <NONE>
+                Callable 2: Callable:
+                    name: __quantum__qis__x__body
+                    call_type: Regular
+                    input_type:
+                        [0]: Qubit
+                    output_type: <VOID>
+                    body: <NONE>
+            blocks:
+                Block 0: Block:
+                    Variable(0, Boolean) = Call id(1), args( Result(0), )
+                    Variable(1, Boolean) = LogicalNot Variable(0, Boolean)
+                    Branch Variable(1, Boolean), 1, 2
+                Block 1: Block:
+                    Call id(2), args( Qubit(0), )
+                    Jump(2)
+                Block 2: Block:
+                    Return
+            config: Config:
+                capabilities: Base
+            num_qubits: 0
+            num_results: 0"#]]
+    .assert_eq(&program.to_string());
+    expect![[r#"
+        [
+            (
+                VariableId(
+                    1,
+                ),
+                "flipped",
+            ),
+        ]
+    "#]]
+    .assert_debug_eq(&program.variable_names.iter().collect::<Vec<_>>());
+}
+
+#[test]
+fn renumber_ids_is_idempotent() {
+    let mut program = out_of_order_program();
+    renumber_ids(&mut program);
+    let once = program.to_string();
+    renumber_ids(&mut program);
+    assert_eq!(once, program.to_string());
+}
@@ -0,0 +1,296 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use std::collections::BTreeSet;
+
+use qsc_data_structures::fx::{FxHashMap, FxHashSet};
+
+use crate::{
+    rir::{CallableId, CallableType, Instruction, Literal, Operand, Program, VariableId},
+    utils::for_each_operand_mut,
+};
+
+/// Something that instructions must access in program order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Resource {
+    Qubit(u32),
+    Result(u32),
+    Variable(VariableId),
+    /// The effects of regular calls that are not tied to any qubit, which must keep their order relative to each other.
+    Effects,
+}
+
+/// Reduces the number of qubits used by a program by reusing the IDs of qubits once they have been reset.
+/// The instructions are first rescheduled so that operations on the qubits already in use are preferred over
+/// operations that start using another qubit, which keeps the number of qubits in use at any time low. Qubit IDs
+/// are then handed out in the order qubits are first used, and the ID of a qubit becomes available to the next
+/// qubit once it is reset by a reset or `mresetz` call.
+///
+/// Output recording stays at the end of the program, so only the instructions before the first output recording
+/// call are rescheduled.
+///
+/// The pass only applies to programs whose entry point is the only callable with a body and consists of a single
+/// block. Other programs are left unchanged.
+///
+/// Targets without the reset capability, such as base profile targets, are not supported. Reusing a qubit requires
+/// returning it to a known state after it is used, which only a reset can do: a measurement leaves the qubit in an
+/// unknown state, and base profile programs can't apply gates after a measurement to correct it. The qubits of such
+/// programs are instead reindexed, so that each qubit gets a new ID after it is measured or reset.
+pub fn reuse_qubits(program: &mut Program) {
+    if program.blocks.iter().count() != 1
+        || program
+            .callables
+            .iter()
+            .any(|(callable_id, callable)| callable.body.is_some() && callable_id != program.entry)
+    {
+        return;
+    }
+
+    let reset_ids = program
+        .callables
+        .iter()
+        .filter(|(_, callable)| {
+            callable.call_type == CallableType::Reset
+                || callable.name == "__quantum__qis__mresetz__body"
+        })
+        .map(|(callable_id, _)| callable_id)
+        .collect::<FxHashSet<_>>();
+    let output_recording_ids = program
+        .callables
+        .iter()
+        .filter(|(_, callable)| callable.call_type == CallableType::OutputRecording)
+        .map(|(callable_id, _)| callable_id)
+        .collect::<FxHashSet<_>>();
+    let regular_ids = program
+        .callables
+        .iter()
+        .filter(|(_, callable)| callable.call_type == CallableType::Regular)
+        .map(|(callable_id, _)| callable_id)
+        .collect::<FxHashSet<_>>();
+
+    let block = program
+        .blocks
+        .values_mut()
+        .next()
+        .expect("program should have a block");
+    let mut instrs = std::mem::take(&mut block.0);
+    if !reset_ids.is_empty() {
+        // Without resets no qubit ID can be reused, so rescheduling would not reduce the number of qubits.
+        let output_start = instrs
+            .iter()
+            .position(|instr| {
+                matches!(instr, Instruction::Call(callable_id, _, _) if output_recording_ids.contains(callable_id))
+            })
+            .unwrap_or(instrs.len().saturating_sub(1));
+        let output = instrs.split_off(output_start);
+        instrs = schedule(instrs, &reset_ids, &regular_ids);
+        instrs.extend(output);
+    }
+    program.num_qubits = reallocate_qubits(&mut instrs, &reset_ids);
+    block.0 = instrs;
+}
+
+/// Reorders the instructions, keeping the order of any two instructions that access the same resource.
+/// Among the instructions whose dependencies have been scheduled, the earliest one that only operates on qubits
+/// already in use is picked first, and an instruction that starts using a qubit is only picked if there is none.
+fn schedule(
+    instrs: Vec<Instruction>,
+    reset_ids: &FxHashSet<CallableId>,
+    regular_ids: &FxHashSet<CallableId>,
+) -> Vec<Instruction> {
+    let resources = instrs
+        .iter()
+        .map(|instr| get_resources(instr, regular_ids))
+        .collect::<Vec<_>>();
+
+    // Each instruction depends on the previous instruction that accessed each of its resources.
+    let mut dependents = vec![Vec::new(); instrs.len()];
+    let mut dependency_counts = vec![0_usize; instrs.len()];
+    let mut last_access = FxHashMap::default();
+    for (idx, instr_resources) in resources.iter().enumerate() {
+        let mut dependencies = FxHashSet::default();
+        for resource in instr_resources {
+            if let Some(prev_idx) = last_access.insert(*resource, idx) {
+                dependencies.insert(prev_idx);
+            }
+        }
+        dependency_counts[idx] = dependencies.len();
+        for prev_idx in dependencies {
+            dependents[prev_idx].push(idx);
+        }
+    }
+
+    let mut qubits_in_use = FxHashSet::default();
+    let starts_qubit = |idx: usize, qubits_in_use: &FxHashSet<u32>| {
+        resources[idx]
+            .iter()
+            .any(|resource| matches!(resource, Resource::Qubit(q) if !qubits_in_use.contains(q)))
+    };
+    let mut ready = BTreeSet::new();
+    let mut ready_starting_qubits = BTreeSet::new();
+    for (idx, count) in dependency_counts.iter().enumerate() {
+        if *count == 0 {
+            if starts_qubit(idx, &qubits_in_use) {
+                ready_starting_qubits.insert(idx);
+            } else {
+                ready.insert(idx);
+            }
+        }
+    }
+
+    let mut order = Vec::with_capacity(instrs.len());
+    while let Some(idx) = ready
+        .pop_first()
+        .or_else(|| ready_starting_qubits.pop_first())
+    {
+        order.push(idx);
+
+        let mut qubits_changed = false;
+        for resource in &resources[idx] {
+            if let Resource::Qubit(q) = resource {
+                qubits_changed |= if is_reset(&instrs[idx], reset_ids) {
+                    qubits_in_use.remove(q)
+                } else {
+                    qubits_in_use.insert(*q)
+                };
+            }
+        }
+        if qubits_changed {
+            // Which of the ready instructions start using a qubit depends on the qubits in use.
+            let all_ready = ready
+                .iter()
+                .chain(ready_starting_qubits.iter())
+                .copied()
+                .collect::<Vec<_>>();
+            (ready_starting_qubits, ready) = all_ready
+                .into_iter()
+                .partition(|idx| starts_qubit(*idx, &qubits_in_use));
+        }
+
+        for dependent in &dependents[idx] {
+            dependency_counts[*dependent] -= 1;
+            if dependency_counts[*dependent] == 0 {
+                if starts_qubit(*dependent, &qubits_in_use) {
+                    ready_starting_qubits.insert(*dependent);
+                } else {
+                    ready.insert(*dependent);
+                }
+            }
+        }
+    }
+    assert_eq!(
+        order.len(),
+        instrs.len(),
+        "all instructions should be scheduled"
+    );
+
+    let mut instrs = instrs.into_iter().map(Some).collect::<Vec<_>>();
+    order
+        .into_iter()
+        .map(|idx| {
+            instrs[idx]
+                .take()
+                .expect("instruction should be scheduled once")
+        })
+        .collect()
+}
+
+/// Renumbers the qubits in the order they are first used, reusing the ID of a qubit for the next qubit to be used
+/// once it has been reset. Returns the number of qubit IDs used.
+fn reallocate_qubits(instrs: &mut [Instruction], reset_ids: &FxHashSet<CallableId>) -> u32 {
+    let mut qubit_map = FxHashMap::default();
+    let mut free_ids = BTreeSet::new();
+    let mut num_qubits = 0;
+    for instr in instrs.iter_mut() {
+        let is_reset = is_reset(instr, reset_ids);
+        let mut released = Vec::new();
+        for_each_operand_mut(instr, |operand| {
+            if let Operand::Literal(Literal::Qubit(q)) = operand {
+                let new_id = *qubit_map.entry(*q).or_insert_with(|| {
+                    free_ids.pop_first().unwrap_or_else(|| {
+                        num_qubits += 1;
+                        num_qubits - 1
+                    })
+                });
+                if is_reset {
+                    released.push(*q);
+                }
+                *q = new_id;
+            }
+        });
+        for q in released {
+            if let Some(new_id) = qubit_map.remove(&q) {
+                free_ids.insert(new_id);
+            }
+        }
+    }
+    num_qubits
+}
+
+fn is_reset(instr: &Instruction, reset_ids: &FxHashSet<CallableId>) -> bool {
+    matches!(instr, Instruction::Call(callable_id, _, _) if reset_ids.contains(callable_id))
+}
+
+fn get_resources(instr: &Instruction, regular_ids: &FxHashSet<CallableId>) -> Vec<Resource> {
+    let mut resources = Vec::new();
+    // The operands are only read, the mutable access is needed to share the operand visitor.
+    let mut instr_copy = instr.clone();
+    for_each_operand_mut(&mut instr_copy, |operand| match operand {
+        Operand::Literal(Literal::Qubit(q)) => resources.push(Resource::Qubit(*q)),
+        Operand::Literal(Literal::Result(r)) => resources.push(Resource::Result(*r)),
+        Operand::Variable(variable) => resources.push(Resource::Variable(variable.variable_id)),
+        Operand::Literal(_) => {}
+    });
+    match instr {
+        Instruction::Call(callable_id, _, _)
+            if regular_ids.contains(callable_id)
+                && !resources
+                    .iter()
+                    .any(|resource| matches!(resource, Resource::Qubit(_))) =>
+        {
+            resources.push(Resource::Effects);
+        }
+        _ => {}
+    }
+    if let Some(variable_id) = get_assigned_variable(instr) {
+        resources.push(Resource::Variable(variable_id));
+    }
+    resources
+}
+
+fn get_assigned_variable(instr: &Instruction) -> Option<VariableId> {
+    match instr {
+        Instruction::Call(_, _, Some(var))
+        | Instruction::Store(_, var)
+        | Instruction::Add(_, _, var)
+        | Instruction::Sub(_, _, var)
+        | Instruction::Mul(_, _, var)
+        | Instruction::Sdiv(_, _, var)
+        | Instruction::Srem(_, _, var)
+        | Instruction::Shl(_, _, var)
+        | Instruction::Ashr(_, _, var)
+        | Instruction::Fadd(_, _, var)
+        | Instruction::Fsub(_, _, var)
+        | Instruction::Fmul(_, _, var)
+        | Instruction::Fdiv(_, _, var)
+        | Instruction::Fcmp(_, _, _, var)
+        | Instruction::Icmp(_, _, _, var)
+        | Instruction::LogicalNot(_, var)
+        | Instruction::LogicalAnd(_, _, var)
+        | Instruction::LogicalOr(_, _, var)
+        | Instruction::BitwiseNot(_, var)
+        | Instruction::Sitofp(_, var)
+        | Instruction::Fptosi(_, var)
+        | Instruction::BitwiseAnd(_, _, var)
+        | Instruction::BitwiseOr(_, _, var)
+        | Instruction::BitwiseXor(_, _, var)
+        | Instruction::Phi(_, var) => Some(var.variable_id),
+        Instruction::Call(_, _, None)
+        | Instruction::Jump(..)
+        | Instruction::Branch(..)
+        | Instruction::Return => None,
+    }
+}
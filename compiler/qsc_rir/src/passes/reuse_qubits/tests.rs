@@ -0,0 +1,171 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#![allow(clippy::needless_raw_string_hashes)]
+
+use expect_test::expect;
+
+use crate::{
+    builder::{
+        cx_decl, h_decl, mresetz_decl, new_program_with_block, new_program_with_blocks, qubit,
+        reset_decl, result, result_record_decl, x_decl,
+    },
+    rir::{BlockId, Callable, CallableId, Instruction, Literal, Operand},
+};
+
+use super::reuse_qubits;
+
+const X: CallableId = CallableId(0);
+const H: CallableId = CallableId(1);
+const RESET: CallableId = CallableId(2);
+const MRESETZ: CallableId = CallableId(3);
+const RECORD: CallableId = CallableId(4);
+const CX: CallableId = CallableId(5);
+
+fn callables() -> [(CallableId, Callable); 6] {
+    [
+        (X, x_decl()),
+        (H, h_decl()),
+        (RESET, reset_decl()),
+        (MRESETZ, mresetz_decl()),
+        (RECORD, result_record_decl()),
+        (CX, cx_decl()),
+    ]
+}
+
+#[test]
+fn qubit_id_reused_after_reset() {
+    let mut program = new_program_with_block(
+        callables(),
+        vec![
+            Instruction::Call(X, vec![qubit(0)], None),
+            Instruction::Call(RESET, vec![qubit(0)], None),
+            Instruction::Call(X, vec![qubit(1)], None),
+            Instruction::Call(RESET, vec![qubit(1)], None),
+            Instruction::Return,
+        ],
+    );
+
+    reuse_qubits(&mut program);
+    expect![[r#"
+        Block:
+            Call id(0), args( Qubit(0), )
+            Call id(2), args( Qubit(0), )
+            Call id(0), args( Qubit(0), )
+            Call id(2), args( Qubit(0), )
+            Return"#]]
+    .assert_eq(&program.get_block(BlockId(0)).to_string());
+    assert_eq!(program.num_qubits, 1);
+}
+
+#[test]
+fn instructions_scheduled_to_finish_qubits_in_use_before_starting_others() {
+    let mut program = new_program_with_block(
+        callables(),
+        vec![
+            Instruction::Call(H, vec![qubit(0)], None),
+            Instruction::Call(H, vec![qubit(1)], None),
+            Instruction::Call(MRESETZ, vec![qubit(0), result(0)], None),
+            Instruction::Call(MRESETZ, vec![qubit(1), result(1)], None),
+            Instruction::Call(
+                RECORD,
+                vec![result(0), Operand::Literal(Literal::Pointer)],
+                None,
+            ),
+            Instruction::Call(
+                RECORD,
+                vec![result(1), Operand::Literal(Literal::Pointer)],
+                None,
+            ),
+            Instruction::Return,
+        ],
+    );
+
+    reuse_qubits(&mut program);
+    expect![[r#"
+        Block:
+            Call id(1), args( Qubit(0), )
+            Call id(3), args( Qubit(0), Result(0), )
+            Call id(1), args( Qubit(0), )
+            Call id(3), args( Qubit(0), Result(1), )
+            Call id(4), args( Result(0), Pointer, )
+            Call id(4), args( Result(1), Pointer, )
+            Return"#]]
+    .assert_eq(&program.get_block(BlockId(0)).to_string());
+    assert_eq!(program.num_qubits, 1);
+}
+
+#[test]
+fn qubits_in_use_at_the_same_time_get_different_ids() {
+    let mut program = new_program_with_block(
+        callables(),
+        vec![
+            Instruction::Call(H, vec![qubit(0)], None),
+            Instruction::Call(CX, vec![qubit(0), qubit(1)], None),
+            Instruction::Call(H, vec![qubit(2)], None),
+            Instruction::Call(MRESETZ, vec![qubit(0), result(0)], None),
+            Instruction::Call(MRESETZ, vec![qubit(1), result(1)], None),
+            Instruction::Call(MRESETZ, vec![qubit(2), result(2)], None),
+            Instruction::Return,
+        ],
+    );
+
+    reuse_qubits(&mut program);
+    expect![[r#"
+        Block:
+            Call id(1), args( Qubit(0), )
+            Call id(5), args( Qubit(0), Qubit(1), )
+            Call id(3), args( Qubit(0), Result(0), )
+            Call id(3), args( Qubit(1), Result(1), )
+            Call id(1), args( Qubit(0), )
+            Call id(3), args( Qubit(0), Result(2), )
+            Return"#]]
+    .assert_eq(&program.get_block(BlockId(0)).to_string());
+    assert_eq!(program.num_qubits, 2);
+}
+
+#[test]
+fn qubit_ids_compacted_in_order_of_first_use_without_resets() {
+    let mut program = new_program_with_block(
+        [(X, x_decl()), (H, h_decl())],
+        vec![
+            Instruction::Call(X, vec![qubit(5)], None),
+            Instruction::Call(H, vec![qubit(3)], None),
+            Instruction::Call(X, vec![qubit(5)], None),
+            Instruction::Return,
+        ],
+    );
+
+    reuse_qubits(&mut program);
+    expect![[r#"
+        Block:
+            Call id(0), args( Qubit(0), )
+            Call id(1), args( Qubit(1), )
+            Call id(0), args( Qubit(0), )
+            Return"#]]
+    .assert_eq(&program.get_block(BlockId(0)).to_string());
+    assert_eq!(program.num_qubits, 2);
+}
+
+#[test]
+fn program_with_multiple_blocks_unchanged() {
+    let mut program = new_program_with_blocks(
+        callables(),
+        [
+            vec![
+                Instruction::Call(X, vec![qubit(1)], None),
+                Instruction::Jump(BlockId(1)),
+            ],
+            vec![Instruction::Return],
+        ],
+    );
+    program.num_qubits = 2;
+
+    reuse_qubits(&mut program);
+    expect![[r#"
+        Block:
+            Call id(0), args( Qubit(1), )
+            Jump(1)"#]]
+    .assert_eq(&program.get_block(BlockId(0)).to_string());
+    assert_eq!(program.num_qubits, 2);
+}
@@ -4,11 +4,11 @@
 #[cfg(test)]
 mod tests;
 
-use rustc_hash::FxHashMap;
+use qsc_data_structures::fx::FxHashMap;
 
 use crate::{
     rir::{Instruction, Program},
-    utils::build_predecessors_map,
+    utils::{build_predecessors_map, get_block_successors},
 };
 
 /// Simplify control flow of the program.
@@ -24,6 +24,10 @@ pub fn simplify_control_flow(program: &mut Program) {
     for (block_id, preds) in preds_map.iter() {
         if preds.len() == 1
             && program.get_block(preds[0]).0.last() == Some(&Instruction::Jump(block_id))
+            && !matches!(
+                program.get_block(block_id).0.first(),
+                Some(Instruction::Phi(..))
+            )
         {
             merge_map.insert(block_id, preds[0]);
 
@@ -39,6 +43,19 @@ pub fn simplify_control_flow(program: &mut Program) {
             // borrow checker, and in part so the check for last instruction above doesn't need to traverse
             // to the ultimate target block via the merge map.
             let mut instrs = program.get_block_mut(block_id).0.clone();
+
+            // In SSA form, phi nodes in the successors of the current block now need to refer to the target block.
+            for successor in get_block_successors(program.get_block(block_id)) {
+                for instr in &mut program.get_block_mut(successor).0 {
+                    if let Instruction::Phi(args, _) = instr {
+                        for (_, pred) in args.iter_mut() {
+                            if *pred == block_id {
+                                *pred = target_block_id;
+                            }
+                        }
+                    }
+                }
+            }
             let target_block = program.get_block_mut(target_block_id);
 
             // Remove the existing terminator instruction and append the instructions copied from the current block.
@@ -51,5 +68,6 @@ pub fn simplify_control_flow(program: &mut Program) {
     // as the list of blocks to remove from the program.
     for block_id in merge_map.keys() {
         program.blocks.remove(*block_id);
+        program.block_names.remove(*block_id);
     }
 }
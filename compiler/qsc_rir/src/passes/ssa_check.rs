@@ -141,6 +141,8 @@ fn get_variable_uses(program: &Program) -> IndexMap<VariableId, Vec<(BlockId, us
                 | Instruction::LogicalOr(Operand::Variable(var), Operand::Literal(_), _)
                 | Instruction::LogicalOr(Operand::Literal(_), Operand::Variable(var), _)
                 | Instruction::BitwiseNot(Operand::Variable(var), _)
+                | Instruction::Sitofp(Operand::Variable(var), _)
+                | Instruction::Fptosi(Operand::Variable(var), _)
                 | Instruction::BitwiseAnd(Operand::Variable(var), Operand::Literal(_), _)
                 | Instruction::BitwiseAnd(Operand::Literal(_), Operand::Variable(var), _)
                 | Instruction::BitwiseOr(Operand::Variable(var), Operand::Literal(_), _)
@@ -211,6 +213,8 @@ fn get_variable_uses(program: &Program) -> IndexMap<VariableId, Vec<(BlockId, us
                 | Instruction::LogicalAnd(Operand::Literal(_), Operand::Literal(_), _)
                 | Instruction::LogicalOr(Operand::Literal(_), Operand::Literal(_), _)
                 | Instruction::BitwiseNot(Operand::Literal(_), _)
+                | Instruction::Sitofp(Operand::Literal(_), _)
+                | Instruction::Fptosi(Operand::Literal(_), _)
                 | Instruction::BitwiseAnd(Operand::Literal(_), Operand::Literal(_), _)
                 | Instruction::BitwiseOr(Operand::Literal(_), Operand::Literal(_), _)
                 | Instruction::BitwiseXor(Operand::Literal(_), Operand::Literal(_), _) => {
@@ -0,0 +1,124 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use std::collections::BTreeMap;
+
+use crate::{
+    rir::{Block, BlockId, Instruction, Operand, Program, Variable, VariableId},
+    utils::{get_block_successors, get_variable_assignments},
+};
+
+/// Transforms the program out of Single Static Assignment (SSA) form by replacing each phi node with store
+/// instructions on the edges leading into its block. This is the inverse of `transform_to_ssa`, and runs as the
+/// last RIR pass so that no phi nodes reach consumers of the program; QIR emission transforms the program back
+/// into SSA form, since LLVM IR expresses the values of variables that depend on control flow as phi nodes.
+///
+/// Blocks are expected to be numbered in reverse postorder, as `transform_to_ssa` leaves them, so that an edge
+/// into a block with an equal or lower ID is a loop back edge. The stores for an edge are placed at the end of its
+/// source block, just before the terminator, except where the source block of a back edge has other successors.
+/// A phi variable of the loop header can still be live on those other edges, so the back edge is split by a new
+/// block holding the stores. The phi nodes of a block read their arguments in parallel, so where the stores on an
+/// edge overwrite a variable that a later store reads, the value is saved in a new variable first.
+pub fn transform_from_ssa(program: &mut Program) {
+    // New variables are numbered after every variable assigned in the program, including by the phi nodes.
+    let mut next_var_id = get_variable_assignments(program)
+        .iter()
+        .last()
+        .map(|(var_id, _)| var_id.successor())
+        .unwrap_or_default();
+
+    // Collect the values assigned along each edge, keyed by source and target block, removing the phi nodes.
+    let mut edge_copies: BTreeMap<(BlockId, BlockId), Vec<(Variable, Operand)>> = BTreeMap::new();
+    for (block_id, block) in program.blocks.iter_mut() {
+        block.0.retain(|instr| {
+            let Instruction::Phi(args, variable) = instr else {
+                return true;
+            };
+            for (operand, pred) in args {
+                edge_copies
+                    .entry((*pred, block_id))
+                    .or_default()
+                    .push((*variable, *operand));
+            }
+            false
+        });
+    }
+
+    let mut next_block_id = program
+        .blocks
+        .iter()
+        .last()
+        .map(|(block_id, _)| block_id.successor())
+        .unwrap_or_default();
+    for ((pred, block_id), copies) in edge_copies {
+        let stores = sequentialize_copies(copies, &mut next_var_id);
+        if stores.is_empty() {
+            continue;
+        }
+
+        if block_id <= pred && get_block_successors(program.get_block(pred)).len() > 1 {
+            let edge_block_id = next_block_id;
+            next_block_id = next_block_id.successor();
+            let Some(Instruction::Branch(_, if_true, if_false)) =
+                program.get_block_mut(pred).0.last_mut()
+            else {
+                panic!("block with more than one successor should end in a branch");
+            };
+            for target in [if_true, if_false] {
+                if *target == block_id {
+                    *target = edge_block_id;
+                }
+            }
+            let mut edge_block = Block(stores);
+            edge_block.0.push(Instruction::Jump(block_id));
+            program.blocks.insert(edge_block_id, edge_block);
+        } else {
+            let block = program.get_block_mut(pred);
+            let terminator = block.0.pop().expect("block should have a terminator");
+            block.0.extend(stores);
+            block.0.push(terminator);
+        }
+    }
+}
+
+/// Orders the parallel assignments of an edge as a sequence of stores with the same effect. A store is only
+/// emitted once no remaining assignment reads the variable it overwrites; when every remaining assignment
+/// overwrites a variable another one reads, they form cycles, which are broken by saving a variable in a new one.
+fn sequentialize_copies(
+    copies: Vec<(Variable, Operand)>,
+    next_var_id: &mut VariableId,
+) -> Vec<Instruction> {
+    let reads = |operand: &Operand, variable: &Variable| matches!(operand, Operand::Variable(source) if source.variable_id == variable.variable_id);
+
+    let mut pending = copies
+        .into_iter()
+        .filter(|(variable, operand)| !reads(operand, variable))
+        .collect::<Vec<_>>();
+    let mut stores = Vec::new();
+    while !pending.is_empty() {
+        let next = pending
+            .iter()
+            .position(|(variable, _)| !pending.iter().any(|(_, operand)| reads(operand, variable)));
+        if let Some(idx) = next {
+            let (variable, operand) = pending.remove(idx);
+            stores.push(Instruction::Store(operand, variable));
+        } else {
+            let (variable, _) = pending[0];
+            let saved = Variable {
+                variable_id: *next_var_id,
+                ty: variable.ty,
+            };
+            *next_var_id = next_var_id.successor();
+            stores.push(Instruction::Store(Operand::Variable(variable), saved));
+            for (_, operand) in &mut pending {
+                if reads(operand, &variable) {
+                    *operand = Operand::Variable(saved);
+                }
+            }
+        }
+    }
+    stores
+}
@@ -0,0 +1,356 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#![allow(clippy::too_many_lines, clippy::needless_raw_string_hashes)]
+
+use expect_test::expect;
+use qsc_data_structures::target::TargetCapabilityFlags;
+
+use crate::{
+    builder::{bell_program, bool_var, int_record_decl, int_var, new_program},
+    passes::check_and_transform,
+    rir::{Block, BlockId, Callable, CallableId, CallableType, Instruction, Literal, Operand, Ty},
+};
+
+use super::transform_from_ssa;
+
+#[test]
+fn ssa_destruct_leaves_program_without_phi_unchanged() {
+    let mut program = bell_program();
+    let program_string_original = program.to_string();
+    transform_from_ssa(&mut program);
+
+    assert_eq!(program_string_original, program.to_string());
+}
+
+#[test]
+fn ssa_destruct_replaces_phi_with_stores_in_predecessors() {
+    let mut program = new_program();
+    program.callables.insert(
+        CallableId(1),
+        Callable {
+            name: "dynamic_bool".to_string(),
+            input_type: Vec::new(),
+            output_type: Some(Ty::Boolean),
+            body: None,
+            call_type: CallableType::Regular,
+        },
+    );
+    program.blocks.insert(
+        BlockId(0),
+        Block(vec![
+            Instruction::Call(CallableId(1), Vec::new(), Some(bool_var(0))),
+            Instruction::Branch(bool_var(0), BlockId(1), BlockId(2)),
+        ]),
+    );
+    program.blocks.insert(
+        BlockId(1),
+        Block(vec![
+            Instruction::LogicalNot(Operand::Variable(bool_var(0)), bool_var(1)),
+            Instruction::Jump(BlockId(3)),
+        ]),
+    );
+    program.blocks.insert(
+        BlockId(2),
+        Block(vec![
+            Instruction::LogicalNot(Operand::Variable(bool_var(0)), bool_var(2)),
+            Instruction::Jump(BlockId(3)),
+        ]),
+    );
+    program.blocks.insert(
+        BlockId(3),
+        Block(vec![
+            Instruction::Phi(
+                vec![
+                    (Operand::Variable(bool_var(1)), BlockId(1)),
+                    (Operand::Variable(bool_var(2)), BlockId(2)),
+                ],
+                bool_var(3),
+            ),
+            Instruction::LogicalNot(Operand::Variable(bool_var(3)), bool_var(4)),
+            Instruction::Return,
+        ]),
+    );
+
+    transform_from_ssa(&mut program);
+
+    expect![[r#"
+        Program:
+            entry: 0
+            callables:
+                Callable 0: Callable:
+                    name: main
+                    call_type: Regular
+                    input_type: <VOID>
+                    output_type: <VOID>
+                    body: 0
+                Callable 1: Callable:
+                    name: dynamic_bool
+                    call_type: Regular
+                    input_type: <VOID>
+                    output_type: Boolean
+                    body: <NONE>
+            blocks:
+                Block 0: Block:
+                    Variable(0, Boolean) = Call id(1), args( )
+                    Branch Variable(0, Boolean), 1, 2
+                Block 1: Block:
+                    Variable(1, Boolean) = LogicalNot Variable(0, Boolean)
+                    Variable(3, Boolean) = Store Variable(1, Boolean)
+                    Jump(3)
+                Block 2: Block:
+                    Variable(2, Boolean) = LogicalNot Variable(0, Boolean)
+                    Variable(3, Boolean) = Store Variable(2, Boolean)
+                    Jump(3)
+                Block 3: Block:
+                    Variable(4, Boolean) = LogicalNot Variable(3, Boolean)
+                    Return
+            config: Config:
+                capabilities: Base
+            num_qubits: 0
+            num_results: 0"#]]
+    .assert_eq(&program.to_string());
+}
+
+#[test]
+fn ssa_destruct_stores_literals_for_every_phi_of_a_block() {
+    let mut program = new_program();
+    program.callables.insert(
+        CallableId(1),
+        Callable {
+            name: "dynamic_bool".to_string(),
+            input_type: Vec::new(),
+            output_type: Some(Ty::Boolean),
+            body: None,
+            call_type: CallableType::Regular,
+        },
+    );
+    program.blocks.insert(
+        BlockId(0),
+        Block(vec![
+            Instruction::Call(CallableId(1), Vec::new(), Some(bool_var(0))),
+            Instruction::Branch(bool_var(0), BlockId(1), BlockId(2)),
+        ]),
+    );
+    program.blocks.insert(
+        BlockId(1),
+        Block(vec![
+            Instruction::Add(
+                Operand::Literal(Literal::Integer(1)),
+                Operand::Literal(Literal::Integer(2)),
+                int_var(1),
+            ),
+            Instruction::Jump(BlockId(2)),
+        ]),
+    );
+    program.blocks.insert(
+        BlockId(2),
+        Block(vec![
+            Instruction::Phi(
+                vec![
+                    (Operand::Literal(Literal::Integer(0)), BlockId(0)),
+                    (Operand::Variable(int_var(1)), BlockId(1)),
+                ],
+                int_var(2),
+            ),
+            Instruction::Phi(
+                vec![
+                    (Operand::Literal(Literal::Bool(false)), BlockId(0)),
+                    (Operand::Variable(bool_var(0)), BlockId(1)),
+                ],
+                bool_var(3),
+            ),
+            Instruction::Return,
+        ]),
+    );
+
+    transform_from_ssa(&mut program);
+
+    expect![[r#"
+        Program:
+            entry: 0
+            callables:
+                Callable 0: Callable:
+                    name: main
+                    call_type: Regular
+                    input_type: <VOID>
+                    output_type: <VOID>
+                    body: 0
+                Callable 1: Callable:
+                    name: dynamic_bool
+                    call_type: Regular
+                    input_type: <VOID>
+                    output_type: Boolean
+                    body: <NONE>
+            blocks:
+                Block 0: Block:
+                    Variable(0, Boolean) = Call id(1), args( )
+                    Variable(2, Integer) = Store Integer(0)
+                    Variable(3, Boolean) = Store Bool(false)
+                    Branch Variable(0, Boolean), 1, 2
+                Block 1: Block:
+                    Variable(1, Integer) = Add Integer(1), Integer(2)
+                    Variable(2, Integer) = Store Variable(1, Integer)
+                    Variable(3, Boolean) = Store Variable(0, Boolean)
+                    Jump(2)
+                Block 2: Block:
+                    Return
+            config: Config:
+                capabilities: Base
+            num_qubits: 0
+            num_results: 0"#]]
+    .assert_eq(&program.to_string());
+}
+
+#[test]
+fn ssa_destruct_splits_back_edge_and_saves_swapped_variables() {
+    let mut program = new_program();
+    program.callables.insert(
+        CallableId(1),
+        Callable {
+            name: "dynamic_bool".to_string(),
+            input_type: Vec::new(),
+            output_type: Some(Ty::Boolean),
+            body: None,
+            call_type: CallableType::Regular,
+        },
+    );
+    program
+        .blocks
+        .insert(BlockId(0), Block(vec![Instruction::Jump(BlockId(1))]));
+    program.blocks.insert(
+        BlockId(1),
+        Block(vec![
+            Instruction::Phi(
+                vec![
+                    (Operand::Literal(Literal::Integer(0)), BlockId(0)),
+                    (Operand::Variable(int_var(2)), BlockId(1)),
+                ],
+                int_var(1),
+            ),
+            Instruction::Phi(
+                vec![
+                    (Operand::Literal(Literal::Integer(1)), BlockId(0)),
+                    (Operand::Variable(int_var(1)), BlockId(1)),
+                ],
+                int_var(2),
+            ),
+            Instruction::Call(CallableId(1), Vec::new(), Some(bool_var(3))),
+            Instruction::Branch(bool_var(3), BlockId(1), BlockId(2)),
+        ]),
+    );
+    program.blocks.insert(
+        BlockId(2),
+        Block(vec![
+            Instruction::Add(
+                Operand::Variable(int_var(1)),
+                Operand::Variable(int_var(2)),
+                int_var(4),
+            ),
+            Instruction::Return,
+        ]),
+    );
+
+    transform_from_ssa(&mut program);
+
+    expect![[r#"
+        Program:
+            entry: 0
+            callables:
+                Callable 0: Callable:
+                    name: main
+                    call_type: Regular
+                    input_type: <VOID>
+                    output_type: <VOID>
+                    body: 0
+                Callable 1: Callable:
+                    name: dynamic_bool
+                    call_type: Regular
+                    input_type: <VOID>
+                    output_type: Boolean
+                    body: <NONE>
+            blocks:
+                Block 0: Block:
+                    Variable(1, Integer) = Store Integer(0)
+                    Variable(2, Integer) = Store Integer(1)
+                    Jump(1)
+                Block 1: Block:
+                    Variable(3, Boolean) = Call id(1), args( )
+                    Branch Variable(3, Boolean), 3, 2
+                Block 2: Block:
+                    Variable(4, Integer) = Add Variable(1, Integer), Variable(2, Integer)
+                    Return
+                Block 3: Block:
+                    Variable(5, Integer) = Store Variable(1, Integer)
+                    Variable(1, Integer) = Store Variable(2, Integer)
+                    Variable(2, Integer) = Store Variable(5, Integer)
+                    Jump(1)
+            config: Config:
+                capabilities: Base
+            num_qubits: 0
+            num_results: 0"#]]
+    .assert_eq(&program.to_string());
+}
+
+#[test]
+fn no_phi_nodes_remain_after_check_and_transform() {
+    let mut program = new_program();
+    program.callables.insert(
+        CallableId(1),
+        Callable {
+            name: "dynamic_bool".to_string(),
+            input_type: Vec::new(),
+            output_type: Some(Ty::Boolean),
+            body: None,
+            call_type: CallableType::Regular,
+        },
+    );
+    program.callables.insert(CallableId(2), int_record_decl());
+    program.config.capabilities = TargetCapabilityFlags::all();
+    program.blocks.insert(
+        BlockId(0),
+        Block(vec![
+            Instruction::Store(Operand::Literal(Literal::Integer(0)), int_var(1)),
+            Instruction::Call(CallableId(1), Vec::new(), Some(bool_var(0))),
+            Instruction::Branch(bool_var(0), BlockId(1), BlockId(2)),
+        ]),
+    );
+    program.blocks.insert(
+        BlockId(1),
+        Block(vec![
+            Instruction::Store(Operand::Literal(Literal::Integer(1)), int_var(1)),
+            Instruction::Jump(BlockId(2)),
+        ]),
+    );
+    program.blocks.insert(
+        BlockId(2),
+        Block(vec![
+            Instruction::Call(
+                CallableId(2),
+                vec![
+                    Operand::Variable(int_var(1)),
+                    Operand::Literal(Literal::Pointer),
+                ],
+                None,
+            ),
+            Instruction::Return,
+        ]),
+    );
+
+    check_and_transform(&mut program);
+
+    assert!(
+        program
+            .blocks
+            .values()
+            .flat_map(|block| &block.0)
+            .all(|instr| !matches!(instr, Instruction::Phi(..))),
+        "phi nodes should not reach consumers of the program"
+    );
+    expect![[r#"
+        Block:
+            Variable(0, Boolean) = Call id(1), args( )
+            Variable(2, Integer) = Store Integer(0)
+            Branch Variable(0, Boolean), 1, 2"#]]
+    .assert_eq(&program.get_block(BlockId(0)).to_string());
+}
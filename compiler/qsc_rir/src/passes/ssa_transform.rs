@@ -8,15 +8,14 @@ use crate::{
     rir::{Block, BlockId, Instruction, Operand, Program, Variable, VariableId},
     utils::get_variable_assignments,
 };
+use qsc_data_structures::fx::{FxHashMap, FxHashSet};
 use qsc_data_structures::index_map::IndexMap;
-use rustc_hash::FxHashMap;
 
 /// Transforms the program into Single Static Assignment (SSA) form by inserting phi nodes
 /// at the beginning of blocks where necessary, allowing the removal of store instructions.
+/// Blocks are expected to be numbered in reverse postorder, so that the only predecessors of a block with
+/// an equal or higher ID are the sources of loop back edges.
 pub fn transform_to_ssa(program: &mut Program, preds: &IndexMap<BlockId, Vec<BlockId>>) {
-    // Ensure that the graph is acyclic before proceeding. Current approach does not support cycles.
-    ensure_acyclic(preds);
-
     // Get the next available variable ID for use in newly generated phi nodes.
     let mut next_var_id = get_variable_assignments(program)
         .iter()
@@ -24,54 +23,66 @@ pub fn transform_to_ssa(program: &mut Program, preds: &IndexMap<BlockId, Vec<Blo
         .map(|(var_id, _)| var_id.successor())
         .unwrap_or_default();
 
-    // First, remove store instructions and propagate variables through individual blocks.
-    // This produces a per-block map of dynamic variables to their values.
-    // Orphan variables may be left behind where a variable is defined in one block and used in another, which
-    // will be resolved by inserting phi nodes.
-    let mut block_var_map = map_store_to_dominated_ssa(program, preds);
+    // Stored variables are about to be removed, so carry their names over to the variables they are stored from.
+    propagate_variable_names(program);
+
+    // Variables stored within a loop take a different value on each iteration, so they always need a phi node
+    // in the loop header. This has to be determined before the store instructions are removed.
+    let loop_carried_vars = get_loop_carried_variables(program, preds);
 
-    // Insert phi nodes where necessary, mapping any remaining orphaned uses to the new variable
-    // created by the phi node.
-    // This can be done in one pass because the graph is assumed to be acyclic.
+    // Remove store instructions and propagate variables through the blocks in order, producing a per-block map
+    // of dynamic variables to their values. Phi nodes are inserted where predecessors disagree on the value
+    // of a variable. The values coming in through back edges are not known until the loop has been
+    // processed, so those phi arguments are filled in afterwards.
+    let mut block_var_map: IndexMap<BlockId, FxHashMap<VariableId, Operand>> = IndexMap::default();
+    let mut back_edge_phis = Vec::new();
     for (block_id, block) in program.blocks.iter_mut() {
-        let Some(block_preds) = preds.get(block_id) else {
+        let mut var_map = FxHashMap::default();
+        match preds.get(block_id).map(Vec::as_slice) {
             // The block with no predecessors is the entry block and has no phi nodes.
-            continue;
-        };
+            None => {}
 
-        // Use a map to track updates to the variable map for the block. These will be applied after
-        // any phi nodes are inserted and will replace any orphaned variables.
-        let mut var_map_updates = FxHashMap::default();
+            // If the block has only one predecessor, it inherits the variable map from that predecessor
+            // to ensure any phi values that may have been added or inherited in the predecessor
+            // are propagated to this block.
+            Some([pred]) => {
+                block_var_map
+                    .get(*pred)
+                    .expect("block should have variable map")
+                    .clone_into(&mut var_map);
+            }
 
-        let (first_pred, rest_preds) = block_preds
-            .split_first()
-            .expect("block should have at least one predecessor");
+            Some(block_preds) => {
+                let (forward_preds, back_preds): (Vec<BlockId>, Vec<BlockId>) =
+                    block_preds.iter().partition(|pred| **pred < block_id);
+                let carried_vars = loop_carried_vars.get(block_id);
+                let (first_pred, rest_preds) = forward_preds
+                    .split_first()
+                    .expect("block should have at least one forward predecessor");
 
-        // The block is only a candidate for phi nodes if it has multiple predecessors.
-        if rest_preds.is_empty() {
-            // If the block has only one predecessor, track any updates to the variable map from that
-            // predecessor to ensure any phi values that may have been added or inherited in the predecessor
-            // are propagated to this block.
-            let pred_var_map = block_var_map
-                .get(*first_pred)
-                .expect("block should have variable map");
-            pred_var_map.clone_into(&mut var_map_updates);
-        } else {
-            // Check each variable in the first predecessor's variable map, and if any other
-            // predecessor has a different value for the variable, a phi node is needed.
-            let first_pred_map = block_var_map
-                .get(*first_pred)
-                .expect("block should have variable map");
-            'var_loop: for (var_id, operand) in first_pred_map {
-                let mut phi_nodes = FxHashMap::default();
+                // Check each variable in the first predecessor's variable map, and if any other
+                // predecessor has a different value for the variable, a phi node is needed.
+                let first_pred_map = block_var_map
+                    .get(*first_pred)
+                    .expect("block should have variable map");
+                let mut phi_nodes = Vec::new();
+                'var_loop: for (var_id, operand) in first_pred_map {
+                    let is_carried = carried_vars.is_some_and(|vars| vars.contains(var_id));
+                    if !is_carried
+                        && rest_preds.iter().all(|pred| {
+                            block_var_map
+                                .get(*pred)
+                                .expect("block should have variable map")
+                                .get(var_id)
+                                == Some(operand)
+                        })
+                    {
+                        // If all predecessors have the same value for this variable, the value can be propagated.
+                        // Update the block variable map with the common operand.
+                        var_map.insert(*var_id, *operand);
+                        continue;
+                    }
 
-                if rest_preds.iter().any(|pred| {
-                    block_var_map
-                        .get(*pred)
-                        .expect("block should have variable map")
-                        .get(var_id)
-                        != Some(operand)
-                }) {
                     // Some predecessors have different values for this variable, so a phi node is needed.
                     // Start with the first predecessor's value and block id, then add the values from the other predecessors.
                     let mut phi_args = vec![(operand.mapped(first_pred_map), *first_pred)];
@@ -79,88 +90,136 @@ pub fn transform_to_ssa(program: &mut Program, preds: &IndexMap<BlockId, Vec<Blo
                         let pred_var_map = block_var_map
                             .get(*pred)
                             .expect("block should have variable map");
-                        let mut pred_operand = match pred_var_map.get(var_id) {
-                            Some(operand) => *operand,
-                            None => {
-                                // If the variable is not defined in this predecessor, it does not dominate this block.
-                                // Assume it is not used and skip creating a phi node for this variable. If the variable is used,
-                                // the ssa check will detect it and panic later.
-                                continue 'var_loop;
-                            }
+                        let Some(pred_operand) = pred_var_map.get(var_id) else {
+                            // If the variable is not defined in this predecessor, it does not dominate this block.
+                            // Assume it is not used and skip creating a phi node for this variable. If the variable is used,
+                            // the ssa check will detect it and panic later.
+                            continue 'var_loop;
                         };
-                        pred_operand = pred_operand.mapped(pred_var_map);
-                        phi_args.push((pred_operand, *pred));
+                        phi_args.push((pred_operand.mapped(pred_var_map), *pred));
                     }
-                    phi_nodes.insert(*var_id, phi_args);
-                } else {
-                    // If all predecessors have the same value for this variable, the value can be propagated.
-                    // Update the block variable map with the common operand.
-                    var_map_updates.insert(*var_id, *operand);
+                    phi_nodes.push((*var_id, phi_args, operand.get_type()));
                 }
 
                 // For any phi nodes that need to be inserted, create a new variable and insert
                 // the phi node at the beginning of the block. The new variable will be used to replace
                 // the original variable in the block's variable map, which will take care of any orphaned uses.
-                for (variable_id, args) in phi_nodes {
+                for (variable_id, args, ty) in phi_nodes {
                     let new_var = Variable {
                         variable_id: next_var_id,
-                        ty: operand.get_type(),
+                        ty,
                     };
                     let phi_node = Instruction::Phi(args, new_var);
                     block.0.insert(0, phi_node);
-                    var_map_updates.insert(variable_id, Operand::Variable(new_var));
+                    if let Some(name) = program.variable_names.get(variable_id) {
+                        program.variable_names.insert(next_var_id, name.clone());
+                    }
+                    var_map.insert(variable_id, Operand::Variable(new_var));
+                    if !back_preds.is_empty() {
+                        back_edge_phis.push((block_id, new_var, variable_id, back_preds.clone()));
+                    }
                     next_var_id = next_var_id.successor();
                 }
             }
         }
 
-        // Now that the block has finished processing, apply any updates to the block and
-        // merge those updates into the stored variable map to propagate to successors.
-        map_variable_use_in_block(block, &mut var_map_updates);
-        for (var_id, operand) in var_map_updates {
-            let var_map = block_var_map
-                .get_mut(block_id)
+        // Now that the incoming values are known, apply them to the block and record the resulting
+        // variable map to propagate to successors.
+        map_variable_use_in_block(block, &mut var_map);
+        block_var_map.insert(block_id, var_map);
+    }
+
+    // Now that every block has been processed, complete the phi nodes in loop headers with the values
+    // of the variables at the end of each back edge.
+    complete_back_edge_phis(program, &block_var_map, back_edge_phis);
+}
+
+// Adds the values flowing along back edges to the phi nodes created for loop-carried variables.
+fn complete_back_edge_phis(
+    program: &mut Program,
+    block_var_map: &IndexMap<BlockId, FxHashMap<VariableId, Operand>>,
+    back_edge_phis: Vec<(BlockId, Variable, VariableId, Vec<BlockId>)>,
+) {
+    for (block_id, phi_var, var_id, back_preds) in back_edge_phis {
+        let mut back_edge_args = Vec::new();
+        for pred in back_preds {
+            let pred_var_map = block_var_map
+                .get(pred)
                 .expect("block should have variable map");
-            var_map.entry(var_id).or_insert(operand);
+            let operand = pred_var_map
+                .get(&var_id)
+                .expect("variable defined before a loop should be defined throughout the loop");
+            back_edge_args.push((operand.mapped(pred_var_map), pred));
+        }
+        let block = program
+            .blocks
+            .get_mut(block_id)
+            .expect("block should exist");
+        for instr in &mut block.0 {
+            if let Instruction::Phi(args, var) = instr {
+                if *var == phi_var {
+                    args.extend(back_edge_args);
+                    break;
+                }
+            }
         }
     }
 }
 
-// For now, SSA transform assumes the graph is acyclic, so verify that no block has a predecessor with
-// a block id less than itself, which would indicate a cycle.
-fn ensure_acyclic(preds: &IndexMap<BlockId, Vec<BlockId>>) {
-    for (block_id, block_preds) in preds.iter() {
-        assert!(
-            !block_preds.iter().any(|pred| *pred >= block_id),
-            "block {block_id:?} has a cycle in its predecessors"
-        );
+// Gives each variable that is the source of a store into a named variable that name, unless it already has one.
+fn propagate_variable_names(program: &mut Program) {
+    for (_, block) in program.blocks.iter() {
+        for instr in &block.0 {
+            if let Instruction::Store(Operand::Variable(source), target) = instr {
+                if program.variable_names.contains_key(source.variable_id) {
+                    continue;
+                }
+                if let Some(name) = program.variable_names.get(target.variable_id) {
+                    program
+                        .variable_names
+                        .insert(source.variable_id, name.clone());
+                }
+            }
+        }
     }
 }
 
-// Remove store instructions and propagate variables through individual blocks.
-// This produces a per-block map of dynamic variables to their values.
-// Any block with a single predecessor inherits that predecessor's mapped variables, since those
-// are live across the block.
-fn map_store_to_dominated_ssa(
-    program: &mut Program,
+// Finds the variables stored within each loop, keyed by the block ID of the loop header.
+// The blocks of a loop are found by walking backwards from the source of each back edge until the header is reached.
+fn get_loop_carried_variables(
+    program: &Program,
     preds: &IndexMap<BlockId, Vec<BlockId>>,
-) -> IndexMap<BlockId, FxHashMap<VariableId, Operand>> {
-    let mut block_var_map = IndexMap::default();
-    for (block_id, block) in program.blocks.iter_mut() {
-        let mut var_map: FxHashMap<VariableId, Operand> = match preds.get(block_id) {
-            Some(block_preds) if block_preds.len() == 1 => {
-                // Any block with a single predecessor inherits those mapped variables.
-                block_var_map
-                    .get(block_preds[0])
-                    .cloned()
-                    .unwrap_or_default()
+) -> IndexMap<BlockId, FxHashSet<VariableId>> {
+    let mut loop_carried_vars = IndexMap::default();
+    for (header_id, header_preds) in preds.iter() {
+        let mut blocks_to_visit = header_preds
+            .iter()
+            .filter(|pred| **pred >= header_id)
+            .copied()
+            .collect::<Vec<_>>();
+        if blocks_to_visit.is_empty() {
+            continue;
+        }
+        blocks_to_visit.push(header_id);
+
+        let mut visited = FxHashSet::default();
+        let mut carried_vars = FxHashSet::default();
+        while let Some(block_id) = blocks_to_visit.pop() {
+            if !visited.insert(block_id) {
+                continue;
             }
-            _ => FxHashMap::default(),
-        };
-        map_variable_use_in_block(block, &mut var_map);
-        block_var_map.insert(block_id, var_map);
+            for instr in &program.get_block(block_id).0 {
+                if let Instruction::Store(_, var) = instr {
+                    carried_vars.insert(var.variable_id);
+                }
+            }
+            if block_id != header_id {
+                blocks_to_visit.extend(preds.get(block_id).into_iter().flatten());
+            }
+        }
+        loop_carried_vars.insert(header_id, carried_vars);
     }
-    block_var_map
+    loop_carried_vars
 }
 
 // Propagates stored variables through a block, tracking the latest stored value and replacing
@@ -223,7 +282,10 @@ fn map_variable_use_in_block(block: &mut Block, var_map: &mut FxHashMap<Variable
             }
 
             // Single variable instructions, replace operand with new value.
-            Instruction::BitwiseNot(operand, _) | Instruction::LogicalNot(operand, _) => {
+            Instruction::BitwiseNot(operand, _)
+            | Instruction::LogicalNot(operand, _)
+            | Instruction::Sitofp(operand, _)
+            | Instruction::Fptosi(operand, _) => {
                 *operand = operand.mapped(var_map);
             }
 
@@ -8,15 +8,31 @@ use qsc_data_structures::target::TargetCapabilityFlags;
 
 use crate::{
     builder::{bell_program, new_program, teleport_program},
-    passes::check_and_transform,
+    passes::{
+        build_dominator_graph, eliminate_dead_blocks, remap_block_ids, simplify_control_flow,
+        ssa_check::check_ssa_form,
+    },
     rir::{
         Block, BlockId, Callable, CallableId, CallableType, Instruction, Literal, Operand, Program,
         Ty, Variable, VariableId,
     },
+    utils::build_predecessors_map,
 };
+
+use super::transform_to_ssa;
+
+/// Runs the pipeline up to and including the SSA check, leaving out the later optimizations that would remove the
+/// instructions these tests inspect.
 fn transform_program(program: &mut Program) {
     program.config.capabilities = TargetCapabilityFlags::all();
-    check_and_transform(program);
+    eliminate_dead_blocks(program);
+    simplify_control_flow(program);
+    remap_block_ids(program);
+    let preds = build_predecessors_map(program);
+    transform_to_ssa(program, &preds);
+    let preds = build_predecessors_map(program);
+    let doms = build_dominator_graph(program, &preds);
+    check_ssa_form(program, &preds, &doms);
 }
 
 #[test]
@@ -141,7 +157,7 @@ fn ssa_transform_removes_store_in_single_block_program() {
                     Variable(2, Boolean) = LogicalNot Variable(0, Boolean)
                     Return
             config: Config:
-                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset)
+                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset | Delay)
             num_qubits: 0
             num_results: 0"#]]
     .assert_eq(&program.to_string());
@@ -295,7 +311,7 @@ fn ssa_transform_removes_multiple_stores_in_single_block_program() {
                     Variable(4, Boolean) = LogicalNot Variable(3, Boolean)
                     Return
             config: Config:
-                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset)
+                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset | Delay)
             num_qubits: 0
             num_results: 0"#]]
     .assert_eq(&program.to_string());
@@ -464,7 +480,7 @@ fn ssa_transform_store_dominating_usage_propagates_to_successor_blocks() {
                     Variable(4, Boolean) = LogicalNot Variable(0, Boolean)
                     Return
             config: Config:
-                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset)
+                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset | Delay)
             num_qubits: 0
             num_results: 0"#]]
     .assert_eq(&program.to_string());
@@ -595,16 +611,10 @@ fn ssa_transform_store_dominating_usage_propagates_to_successor_blocks_without_i
             blocks:
                 Block 0: Block:
                     Variable(0, Boolean) = Call id(1), args( )
-                    Branch Variable(0, Boolean), 1, 2
-                Block 1: Block:
-                    Jump(3)
-                Block 2: Block:
-                    Jump(3)
-                Block 3: Block:
                     Variable(4, Boolean) = LogicalNot Variable(0, Boolean)
                     Return
             config: Config:
-                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset)
+                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset | Delay)
             num_qubits: 0
             num_results: 0"#]]
     .assert_eq(&program.to_string());
@@ -796,7 +806,7 @@ fn ssa_transform_inserts_phi_for_store_not_dominating_usage() {
                     Variable(4, Boolean) = LogicalNot Variable(5, Boolean)
                     Return
             config: Config:
-                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset)
+                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset | Delay)
             num_qubits: 0
             num_results: 0"#]].assert_eq(&program.to_string());
 }
@@ -953,15 +963,13 @@ fn ssa_transform_inserts_phi_for_store_not_dominating_usage_in_one_branch() {
                     Branch Variable(0, Boolean), 1, 2
                 Block 1: Block:
                     Variable(2, Boolean) = LogicalNot Variable(0, Boolean)
-                    Jump(3)
+                    Jump(2)
                 Block 2: Block:
-                    Jump(3)
-                Block 3: Block:
-                    Variable(5, Boolean) = Phi ( [Variable(2, Boolean), 1], [Variable(0, Boolean), 2], )
+                    Variable(5, Boolean) = Phi ( [Variable(0, Boolean), 0], [Variable(2, Boolean), 1], )
                     Variable(4, Boolean) = LogicalNot Variable(5, Boolean)
                     Return
             config: Config:
-                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset)
+                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset | Delay)
             num_qubits: 0
             num_results: 0"#]].assert_eq(&program.to_string());
 }
@@ -1202,25 +1210,19 @@ fn ssa_transform_inserts_phi_for_node_with_many_predecessors() {
                     Branch Variable(0, Boolean), 1, 2
                 Block 1: Block:
                     Variable(2, Boolean) = LogicalNot Variable(0, Boolean)
-                    Branch Variable(2, Boolean), 3, 4
+                    Jump(4)
                 Block 2: Block:
                     Variable(3, Boolean) = LogicalNot Variable(0, Boolean)
-                    Branch Variable(3, Boolean), 5, 6
+                    Branch Variable(3, Boolean), 4, 3
                 Block 3: Block:
-                    Jump(7)
-                Block 4: Block:
-                    Jump(7)
-                Block 5: Block:
-                    Jump(7)
-                Block 6: Block:
                     Variable(4, Boolean) = LogicalNot Variable(3, Boolean)
-                    Jump(7)
-                Block 7: Block:
-                    Variable(6, Boolean) = Phi ( [Variable(2, Boolean), 3], [Variable(2, Boolean), 4], [Variable(3, Boolean), 5], [Variable(4, Boolean), 6], )
+                    Jump(4)
+                Block 4: Block:
+                    Variable(6, Boolean) = Phi ( [Variable(2, Boolean), 1], [Variable(3, Boolean), 2], [Variable(4, Boolean), 3], )
                     Variable(5, Boolean) = LogicalNot Variable(6, Boolean)
                     Return
             config: Config:
-                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset)
+                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset | Delay)
             num_qubits: 0
             num_results: 0"#]].assert_eq(&program.to_string());
 }
@@ -1435,7 +1437,7 @@ fn ssa_transform_inserts_phi_for_multiple_stored_values() {
                     Variable(6, Boolean) = LogicalNot Variable(8, Boolean)
                     Return
             config: Config:
-                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset)
+                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset | Delay)
             num_qubits: 0
             num_results: 0"#]].assert_eq(&program.to_string());
 }
@@ -1764,7 +1766,7 @@ fn ssa_transform_inserts_phi_nodes_in_successive_blocks_for_chained_branches() {
                     Variable(8, Boolean) = LogicalNot Variable(10, Boolean)
                     Return
             config: Config:
-                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset)
+                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset | Delay)
             num_qubits: 0
             num_results: 0"#]].assert_eq(&program.to_string());
 }
@@ -2051,7 +2053,7 @@ fn ssa_transform_inerts_phi_nodes_for_early_return_graph_pattern() {
                     Variable(4, Boolean) = LogicalNot Variable(9, Boolean)
                     Return
             config: Config:
-                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset)
+                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset | Delay)
             num_qubits: 0
             num_results: 0"#]].assert_eq(&program.to_string());
 }
@@ -2206,18 +2208,10 @@ fn ssa_transform_propagates_updates_from_multiple_predecessors_to_later_single_s
             blocks:
                 Block 0: Block:
                     Variable(0, Boolean) = Call id(1), args( )
-                    Branch Variable(0, Boolean), 1, 2
-                Block 1: Block:
-                    Jump(2)
-                Block 2: Block:
                     Variable(2, Boolean) = Call id(1), args( )
-                    Branch Variable(2, Boolean), 3, 4
-                Block 3: Block:
-                    Jump(4)
-                Block 4: Block:
                     Return
             config: Config:
-                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset)
+                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset | Delay)
             num_qubits: 0
             num_results: 0"#]].assert_eq(&program.to_string());
 }
@@ -2335,7 +2329,7 @@ fn ssa_transform_maps_store_instrs_that_use_values_from_other_store_instrs() {
                     Variable(3, Boolean) = LogicalNot Variable(0, Boolean)
                     Return
             config: Config:
-                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset)
+                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset | Delay)
             num_qubits: 0
             num_results: 0"#]].assert_eq(&program.to_string());
 }
@@ -2489,7 +2483,7 @@ fn ssa_transform_maps_store_with_variable_from_store_in_conditional_to_phi_node(
                     Variable(3, Boolean) = LogicalNot Variable(4, Boolean)
                     Return
             config: Config:
-                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset)
+                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset | Delay)
             num_qubits: 0
             num_results: 0"#]].assert_eq(&program.to_string());
 }
@@ -2642,7 +2636,288 @@ fn ssa_transform_allows_point_in_time_copy_of_dynamic_variable() {
                     Variable(5, Boolean) = LogicalNot Variable(3, Boolean)
                     Return
             config: Config:
-                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset)
+                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset | Delay)
             num_qubits: 0
             num_results: 0"#]].assert_eq(&program.to_string());
 }
+
+#[test]
+fn ssa_transform_inserts_phi_for_variable_stored_in_loop() {
+    let mut program = new_program();
+    program.callables.insert(
+        CallableId(1),
+        Callable {
+            name: "dynamic_bool".to_string(),
+            input_type: Vec::new(),
+            output_type: Some(Ty::Boolean),
+            body: None,
+            call_type: CallableType::Regular,
+        },
+    );
+
+    program.blocks.insert(
+        BlockId(0),
+        Block(vec![
+            Instruction::Store(
+                Operand::Literal(Literal::Integer(0)),
+                Variable {
+                    variable_id: VariableId(0),
+                    ty: Ty::Integer,
+                },
+            ),
+            Instruction::Jump(BlockId(1)),
+        ]),
+    );
+    program.blocks.insert(
+        BlockId(1),
+        Block(vec![
+            Instruction::Call(
+                CallableId(1),
+                Vec::new(),
+                Some(Variable {
+                    variable_id: VariableId(1),
+                    ty: Ty::Boolean,
+                }),
+            ),
+            Instruction::Branch(
+                Variable {
+                    variable_id: VariableId(1),
+                    ty: Ty::Boolean,
+                },
+                BlockId(2),
+                BlockId(3),
+            ),
+        ]),
+    );
+    program.blocks.insert(
+        BlockId(2),
+        Block(vec![
+            Instruction::Add(
+                Operand::Variable(Variable {
+                    variable_id: VariableId(0),
+                    ty: Ty::Integer,
+                }),
+                Operand::Literal(Literal::Integer(1)),
+                Variable {
+                    variable_id: VariableId(2),
+                    ty: Ty::Integer,
+                },
+            ),
+            Instruction::Store(
+                Operand::Variable(Variable {
+                    variable_id: VariableId(2),
+                    ty: Ty::Integer,
+                }),
+                Variable {
+                    variable_id: VariableId(0),
+                    ty: Ty::Integer,
+                },
+            ),
+            Instruction::Jump(BlockId(1)),
+        ]),
+    );
+    program.blocks.insert(
+        BlockId(3),
+        Block(vec![
+            Instruction::Add(
+                Operand::Variable(Variable {
+                    variable_id: VariableId(0),
+                    ty: Ty::Integer,
+                }),
+                Operand::Literal(Literal::Integer(2)),
+                Variable {
+                    variable_id: VariableId(3),
+                    ty: Ty::Integer,
+                },
+            ),
+            Instruction::Return,
+        ]),
+    );
+
+    // After
+    transform_program(&mut program);
+    expect![[r#"
+        Program:
+            entry: 0
+            callables:
+                Callable 0: Callable:
+                    name: main
+                    call_type: Regular
+                    input_type: <VOID>
+                    output_type: <VOID>
+                    body: 0
+                Callable 1: Callable:
+                    name: dynamic_bool
+                    call_type: Regular
+                    input_type: <VOID>
+                    output_type: Boolean
+                    body: <NONE>
+            blocks:
+                Block 0: Block:
+                    Jump(1)
+                Block 1: Block:
+                    Variable(4, Integer) = Phi ( [Integer(0), 0], [Variable(2, Integer), 2], )
+                    Variable(1, Boolean) = Call id(1), args( )
+                    Branch Variable(1, Boolean), 2, 3
+                Block 2: Block:
+                    Variable(2, Integer) = Add Variable(4, Integer), Integer(1)
+                    Jump(1)
+                Block 3: Block:
+                    Variable(3, Integer) = Add Variable(4, Integer), Integer(2)
+                    Return
+            config: Config:
+                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset | Delay)
+            num_qubits: 0
+            num_results: 0"#]]
+    .assert_eq(&program.to_string());
+}
+
+#[test]
+fn ssa_transform_uses_point_in_time_copies_for_values_swapped_in_loop() {
+    let mut program = new_program();
+    program.callables.insert(
+        CallableId(1),
+        Callable {
+            name: "dynamic_bool".to_string(),
+            input_type: Vec::new(),
+            output_type: Some(Ty::Boolean),
+            body: None,
+            call_type: CallableType::Regular,
+        },
+    );
+
+    program.blocks.insert(
+        BlockId(0),
+        Block(vec![
+            Instruction::Store(
+                Operand::Literal(Literal::Integer(0)),
+                Variable {
+                    variable_id: VariableId(0),
+                    ty: Ty::Integer,
+                },
+            ),
+            Instruction::Store(
+                Operand::Literal(Literal::Integer(1)),
+                Variable {
+                    variable_id: VariableId(1),
+                    ty: Ty::Integer,
+                },
+            ),
+            Instruction::Jump(BlockId(1)),
+        ]),
+    );
+    program.blocks.insert(
+        BlockId(1),
+        Block(vec![
+            Instruction::Call(
+                CallableId(1),
+                Vec::new(),
+                Some(Variable {
+                    variable_id: VariableId(2),
+                    ty: Ty::Boolean,
+                }),
+            ),
+            Instruction::Branch(
+                Variable {
+                    variable_id: VariableId(2),
+                    ty: Ty::Boolean,
+                },
+                BlockId(2),
+                BlockId(3),
+            ),
+        ]),
+    );
+    program.blocks.insert(
+        BlockId(2),
+        Block(vec![
+            Instruction::Store(
+                Operand::Variable(Variable {
+                    variable_id: VariableId(0),
+                    ty: Ty::Integer,
+                }),
+                Variable {
+                    variable_id: VariableId(3),
+                    ty: Ty::Integer,
+                },
+            ),
+            Instruction::Store(
+                Operand::Variable(Variable {
+                    variable_id: VariableId(1),
+                    ty: Ty::Integer,
+                }),
+                Variable {
+                    variable_id: VariableId(0),
+                    ty: Ty::Integer,
+                },
+            ),
+            Instruction::Store(
+                Operand::Variable(Variable {
+                    variable_id: VariableId(3),
+                    ty: Ty::Integer,
+                }),
+                Variable {
+                    variable_id: VariableId(1),
+                    ty: Ty::Integer,
+                },
+            ),
+            Instruction::Jump(BlockId(1)),
+        ]),
+    );
+    program.blocks.insert(
+        BlockId(3),
+        Block(vec![
+            Instruction::Add(
+                Operand::Variable(Variable {
+                    variable_id: VariableId(0),
+                    ty: Ty::Integer,
+                }),
+                Operand::Variable(Variable {
+                    variable_id: VariableId(1),
+                    ty: Ty::Integer,
+                }),
+                Variable {
+                    variable_id: VariableId(4),
+                    ty: Ty::Integer,
+                },
+            ),
+            Instruction::Return,
+        ]),
+    );
+
+    // After
+    transform_program(&mut program);
+    expect![[r#"
+        Program:
+            entry: 0
+            callables:
+                Callable 0: Callable:
+                    name: main
+                    call_type: Regular
+                    input_type: <VOID>
+                    output_type: <VOID>
+                    body: 0
+                Callable 1: Callable:
+                    name: dynamic_bool
+                    call_type: Regular
+                    input_type: <VOID>
+                    output_type: Boolean
+                    body: <NONE>
+            blocks:
+                Block 0: Block:
+                    Jump(1)
+                Block 1: Block:
+                    Variable(6, Integer) = Phi ( [Integer(1), 0], [Variable(5, Integer), 2], )
+                    Variable(5, Integer) = Phi ( [Integer(0), 0], [Variable(6, Integer), 2], )
+                    Variable(2, Boolean) = Call id(1), args( )
+                    Branch Variable(2, Boolean), 2, 3
+                Block 2: Block:
+                    Jump(1)
+                Block 3: Block:
+                    Variable(4, Integer) = Add Variable(5, Integer), Variable(6, Integer)
+                    Return
+            config: Config:
+                capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations | BackwardsBranching | HigherLevelConstructs | QubitReset | Delay)
+            num_qubits: 0
+            num_results: 0"#]]
+    .assert_eq(&program.to_string());
+}
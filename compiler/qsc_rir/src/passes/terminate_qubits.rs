@@ -0,0 +1,237 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use std::collections::BTreeSet;
+
+use qsc_data_structures::fx::FxHashMap;
+use qsc_data_structures::{index_map::IndexMap, target::TargetCapabilityFlags};
+
+use crate::{
+    builder,
+    rir::{
+        Block, BlockId, Callable, CallableId, CallableType, InsertedTermination, Instruction,
+        Literal, Operand, Program, QubitTermination,
+    },
+    utils::{build_predecessors_map, get_block_successors},
+};
+
+/// Ensures that every qubit the program uses is measured or reset before the program ends, for targets that
+/// require terminated qubits. A qubit is terminated on a path through the program if the last call that uses it
+/// is a measurement or a reset. At the end of each block that returns, a call that terminates each qubit that is
+/// not terminated on every path to that block is inserted before the output recording calls, according to the
+/// `qubit_termination` option in the program's configuration:
+/// - `Reset` inserts a call to `__quantum__qis__reset__body`. Targets without the reset capability get a
+///   measurement instead, since their resets are removed when qubits are reindexed.
+/// - `Measure` inserts a measurement into a result register that is not otherwise used. The measurement intrinsic
+///   the program already uses is preferred, and `__quantum__qis__mz__body` is used otherwise.
+///
+/// The inserted terminations are recorded in the program, one for each qubit.
+///
+/// The pass only applies to programs whose entry point is the only callable with a body. Other programs are left
+/// unchanged.
+pub fn terminate_qubits(program: &mut Program) {
+    let measure = match program.config.qubit_termination {
+        QubitTermination::None => return,
+        QubitTermination::Reset => !program
+            .config
+            .capabilities
+            .contains(TargetCapabilityFlags::QubitReset),
+        QubitTermination::Measure => true,
+    };
+    if program
+        .callables
+        .iter()
+        .any(|(callable_id, callable)| callable.body.is_some() && callable_id != program.entry)
+    {
+        return;
+    }
+
+    let unterminated = find_unterminated_qubits(program);
+    if unterminated.values().all(BTreeSet::is_empty) {
+        return;
+    }
+
+    let callable_id = if measure {
+        find_callable(program, "__quantum__qis__m__body")
+            .or_else(|| find_callable(program, "__quantum__qis__mz__body"))
+            .unwrap_or_else(|| add_callable(program, builder::mz_decl()))
+    } else {
+        find_callable(program, "__quantum__qis__reset__body")
+            .unwrap_or_else(|| add_callable(program, builder::reset_decl()))
+    };
+
+    // Each qubit is measured into the same result register on every path, so the registers stay unique to it.
+    let output_recording_ids = output_recording_ids(program);
+    let mut results = FxHashMap::default();
+    for (block_id, qubits) in unterminated.iter() {
+        if qubits.is_empty() {
+            continue;
+        }
+        let calls = qubits
+            .iter()
+            .map(|&qubit| {
+                let mut args = vec![Operand::Literal(Literal::Qubit(qubit))];
+                if measure {
+                    let result = *results.entry(qubit).or_insert_with(|| {
+                        let result = program.num_results;
+                        program.num_results += 1;
+                        result
+                    });
+                    args.push(Operand::Literal(Literal::Result(result)));
+                }
+                Instruction::Call(callable_id, args, None)
+            })
+            .collect::<Vec<_>>();
+
+        let block = program.get_block_mut(block_id);
+        let position = output_recording_start(block, &output_recording_ids);
+        block.0.splice(position..position, calls);
+    }
+
+    let qubits = unterminated.values().flatten().collect::<BTreeSet<_>>();
+    program.inserted_terminations = qubits
+        .into_iter()
+        .map(|&qubit| InsertedTermination {
+            qubit,
+            result: results.get(&qubit).copied(),
+        })
+        .collect();
+}
+
+/// Finds the qubits that are not terminated on every path to each block that returns, keyed by the block.
+fn find_unterminated_qubits(program: &Program) -> IndexMap<BlockId, BTreeSet<u32>> {
+    let call_types = program
+        .callables
+        .iter()
+        .map(|(callable_id, callable)| (callable_id, callable.call_type))
+        .collect::<FxHashMap<_, _>>();
+    let used_qubits = program
+        .blocks
+        .values()
+        .flat_map(|block| block.0.iter())
+        .flat_map(call_qubits)
+        .collect::<BTreeSet<_>>();
+    let preds = build_predecessors_map(program);
+    let entry_block = program.get_callable(program.entry).body;
+
+    // The qubits that are terminated at the end of each block on every path to it. The blocks that have not been
+    // visited yet don't constrain their successors, so they start with every qubit.
+    let mut terminated_at_end: IndexMap<BlockId, BTreeSet<u32>> = IndexMap::default();
+    let mut worklist = entry_block.into_iter().collect::<Vec<_>>();
+    while let Some(block_id) = worklist.pop() {
+        let mut terminated = terminated_at_start(block_id, entry_block, &preds, &terminated_at_end);
+        let block = program.get_block(block_id);
+        for instr in &block.0 {
+            if let Instruction::Call(callable_id, ..) = instr {
+                let is_termination = matches!(
+                    call_types.get(callable_id),
+                    Some(CallableType::Measurement | CallableType::Reset)
+                );
+                for qubit in call_qubits(instr) {
+                    if is_termination {
+                        terminated.insert(qubit);
+                    } else {
+                        terminated.remove(&qubit);
+                    }
+                }
+            }
+        }
+        if terminated_at_end.get(block_id) != Some(&terminated) {
+            terminated_at_end.insert(block_id, terminated);
+            worklist.extend(get_block_successors(block));
+        }
+    }
+
+    let mut unterminated = IndexMap::default();
+    for (block_id, terminated) in terminated_at_end.iter() {
+        if matches!(
+            program.get_block(block_id).0.last(),
+            Some(Instruction::Return)
+        ) {
+            unterminated.insert(
+                block_id,
+                used_qubits.difference(terminated).copied().collect(),
+            );
+        }
+    }
+    unterminated
+}
+
+/// The qubits that are terminated on every path to the start of the block, from what is known so far about the
+/// ends of its predecessors.
+fn terminated_at_start(
+    block_id: BlockId,
+    entry_block: Option<BlockId>,
+    preds: &IndexMap<BlockId, Vec<BlockId>>,
+    terminated_at_end: &IndexMap<BlockId, BTreeSet<u32>>,
+) -> BTreeSet<u32> {
+    if Some(block_id) == entry_block {
+        return BTreeSet::new();
+    }
+    let mut visited_preds = preds
+        .get(block_id)
+        .into_iter()
+        .flatten()
+        .filter_map(|pred| terminated_at_end.get(*pred));
+    let first = visited_preds.next().cloned().unwrap_or_default();
+    visited_preds.fold(first, |terminated, pred_terminated| {
+        terminated.intersection(pred_terminated).copied().collect()
+    })
+}
+
+fn call_qubits(instr: &Instruction) -> impl Iterator<Item = u32> + '_ {
+    let args = match instr {
+        Instruction::Call(_, args, _) => args.as_slice(),
+        _ => &[],
+    };
+    args.iter().filter_map(|arg| match arg {
+        Operand::Literal(Literal::Qubit(qubit)) => Some(*qubit),
+        _ => None,
+    })
+}
+
+fn output_recording_ids(program: &Program) -> Vec<CallableId> {
+    program
+        .callables
+        .iter()
+        .filter(|(_, callable)| callable.call_type == CallableType::OutputRecording)
+        .map(|(callable_id, _)| callable_id)
+        .collect()
+}
+
+/// The position of the output recording calls that end the block, before its terminator.
+fn output_recording_start(block: &Block, output_recording_ids: &[CallableId]) -> usize {
+    let end = block.0.len() - 1;
+    let recording = block.0[..end]
+        .iter()
+        .rev()
+        .take_while(|instr| {
+            matches!(instr, Instruction::Call(callable_id, _, _) if output_recording_ids.contains(callable_id))
+        })
+        .count();
+    end - recording
+}
+
+fn find_callable(program: &Program, name: &str) -> Option<CallableId> {
+    program
+        .callables
+        .iter()
+        .find(|(_, callable)| callable.name == name)
+        .map(|(callable_id, _)| callable_id)
+}
+
+fn add_callable(program: &mut Program, callable: Callable) -> CallableId {
+    let callable_id = CallableId(
+        program
+            .callables
+            .iter()
+            .map(|(id, _)| id.0 + 1)
+            .max()
+            .unwrap_or_default(),
+    );
+    program.callables.insert(callable_id, callable);
+    callable_id
+}
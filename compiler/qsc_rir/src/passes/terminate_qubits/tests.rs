@@ -0,0 +1,278 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#![allow(clippy::needless_raw_string_hashes)]
+
+use std::fmt::Write;
+
+use expect_test::{expect, Expect};
+use qsc_data_structures::target::TargetCapabilityFlags;
+
+use crate::rir::{Program, QubitTermination};
+
+use super::terminate_qubits;
+
+const CALLABLES: &str = "
+    callables:
+        Callable 0: Callable:
+            name: main
+            call_type: Regular
+            input_type: <VOID>
+            output_type: <VOID>
+            body: 0
+        Callable 1: Callable:
+            name: __quantum__qis__x__body
+            call_type: Regular
+            input_type:
+                [0]: Qubit
+            output_type: <VOID>
+            body: <NONE>
+        Callable 2: Callable:
+            name: __quantum__qis__mresetz__body
+            call_type: Measurement
+            input_type:
+                [0]: Qubit
+                [1]: Result
+            output_type: <VOID>
+            body: <NONE>
+        Callable 3: Callable:
+            name: __quantum__qis__read_result__body
+            call_type: Readout
+            input_type:
+                [0]: Result
+            output_type: Boolean
+            body: <NONE>
+        Callable 4: Callable:
+            name: __quantum__rt__result_record_output
+            call_type: OutputRecording
+            input_type:
+                [0]: Result
+                [1]: Pointer
+            output_type: <VOID>
+            body: <NONE>
+";
+
+fn check(
+    blocks: &str,
+    qubit_termination: QubitTermination,
+    capabilities: TargetCapabilityFlags,
+    expect: &Expect,
+) {
+    let mut program = format!("{CALLABLES}{blocks}")
+        .parse::<Program>()
+        .expect("program should parse");
+    program.config.capabilities = capabilities;
+    program.config.qubit_termination = qubit_termination;
+    terminate_qubits(&mut program);
+
+    let mut actual = String::new();
+    for (_, callable) in program.callables.iter().skip(5) {
+        writeln!(actual, "added callable: {}", callable.name).expect("writing should succeed");
+    }
+    for (block_id, block) in program.blocks.iter() {
+        writeln!(actual, "Block {}: {block}", block_id.0).expect("writing should succeed");
+    }
+    writeln!(actual, "num_results: {}", program.num_results).expect("writing should succeed");
+    for termination in &program.inserted_terminations {
+        writeln!(actual, "inserted: {termination}").expect("writing should succeed");
+    }
+    expect.assert_eq(&actual);
+}
+
+fn reset_capable() -> TargetCapabilityFlags {
+    TargetCapabilityFlags::Adaptive | TargetCapabilityFlags::QubitReset
+}
+
+#[test]
+fn qubits_not_measured_are_reset_before_output_recording() {
+    check(
+        "
+        blocks:
+            Block 0: Block:
+                Call id(1), args( Qubit(0), )
+                Call id(1), args( Qubit(1), )
+                Call id(2), args( Qubit(1), Result(0), )
+                Call id(4), args( Result(0), Pointer, )
+                Return
+        num_results: 1
+        ",
+        QubitTermination::Reset,
+        reset_capable(),
+        &expect![[r#"
+            added callable: __quantum__qis__reset__body
+            Block 0: Block:
+                Call id(1), args( Qubit(0), )
+                Call id(1), args( Qubit(1), )
+                Call id(2), args( Qubit(1), Result(0), )
+                Call id(5), args( Qubit(0), )
+                Call id(4), args( Result(0), Pointer, )
+                Return
+            num_results: 1
+            inserted: Qubit(0) reset
+        "#]],
+    );
+}
+
+#[test]
+fn qubit_used_after_measurement_is_terminated() {
+    check(
+        "
+        blocks:
+            Block 0: Block:
+                Call id(2), args( Qubit(0), Result(0), )
+                Call id(1), args( Qubit(0), )
+                Return
+        num_results: 1
+        ",
+        QubitTermination::Reset,
+        reset_capable(),
+        &expect![[r#"
+            added callable: __quantum__qis__reset__body
+            Block 0: Block:
+                Call id(2), args( Qubit(0), Result(0), )
+                Call id(1), args( Qubit(0), )
+                Call id(5), args( Qubit(0), )
+                Return
+            num_results: 1
+            inserted: Qubit(0) reset
+        "#]],
+    );
+}
+
+#[test]
+fn terminated_program_is_unchanged() {
+    check(
+        "
+        blocks:
+            Block 0: Block:
+                Call id(1), args( Qubit(0), )
+                Call id(2), args( Qubit(0), Result(0), )
+                Call id(4), args( Result(0), Pointer, )
+                Return
+        num_results: 1
+        ",
+        QubitTermination::Reset,
+        reset_capable(),
+        &expect![[r#"
+            Block 0: Block:
+                Call id(1), args( Qubit(0), )
+                Call id(2), args( Qubit(0), Result(0), )
+                Call id(4), args( Result(0), Pointer, )
+                Return
+            num_results: 1
+        "#]],
+    );
+}
+
+#[test]
+fn qubit_not_terminated_on_one_branch_is_terminated_where_branches_join() {
+    check(
+        "
+        blocks:
+            Block 0: Block:
+                Call id(2), args( Qubit(0), Result(0), )
+                Variable(0, Boolean) = Call id(3), args( Result(0), )
+                Branch Variable(0, Boolean), 1, 2
+            Block 1: Block:
+                Call id(1), args( Qubit(0), )
+                Jump(2)
+            Block 2: Block:
+                Call id(4), args( Result(0), Pointer, )
+                Return
+        num_results: 1
+        ",
+        QubitTermination::Reset,
+        reset_capable(),
+        &expect![[r#"
+            added callable: __quantum__qis__reset__body
+            Block 0: Block:
+                Call id(2), args( Qubit(0), Result(0), )
+                Variable(0, Boolean) = Call id(3), args( Result(0), )
+                Branch Variable(0, Boolean), 1, 2
+            Block 1: Block:
+                Call id(1), args( Qubit(0), )
+                Jump(2)
+            Block 2: Block:
+                Call id(5), args( Qubit(0), )
+                Call id(4), args( Result(0), Pointer, )
+                Return
+            num_results: 1
+            inserted: Qubit(0) reset
+        "#]],
+    );
+}
+
+#[test]
+fn qubits_are_measured_into_new_result_registers() {
+    check(
+        "
+        blocks:
+            Block 0: Block:
+                Call id(1), args( Qubit(0), )
+                Call id(1), args( Qubit(1), )
+                Return
+        ",
+        QubitTermination::Measure,
+        reset_capable(),
+        &expect![[r#"
+            added callable: __quantum__qis__mz__body
+            Block 0: Block:
+                Call id(1), args( Qubit(0), )
+                Call id(1), args( Qubit(1), )
+                Call id(5), args( Qubit(0), Result(0), )
+                Call id(5), args( Qubit(1), Result(1), )
+                Return
+            num_results: 2
+            inserted: Qubit(0) measured into Result(0)
+            inserted: Qubit(1) measured into Result(1)
+        "#]],
+    );
+}
+
+#[test]
+fn qubits_are_measured_instead_of_reset_without_reset_capability() {
+    check(
+        "
+        blocks:
+            Block 0: Block:
+                Call id(1), args( Qubit(0), )
+                Call id(2), args( Qubit(1), Result(0), )
+                Call id(4), args( Result(0), Pointer, )
+                Return
+        num_results: 1
+        ",
+        QubitTermination::Reset,
+        TargetCapabilityFlags::empty(),
+        &expect![[r#"
+            added callable: __quantum__qis__mz__body
+            Block 0: Block:
+                Call id(1), args( Qubit(0), )
+                Call id(2), args( Qubit(1), Result(0), )
+                Call id(5), args( Qubit(0), Result(1), )
+                Call id(4), args( Result(0), Pointer, )
+                Return
+            num_results: 2
+            inserted: Qubit(0) measured into Result(1)
+        "#]],
+    );
+}
+
+#[test]
+fn no_termination_leaves_program_unchanged() {
+    check(
+        "
+        blocks:
+            Block 0: Block:
+                Call id(1), args( Qubit(0), )
+                Return
+        ",
+        QubitTermination::None,
+        reset_capable(),
+        &expect![[r#"
+            Block 0: Block:
+                Call id(1), args( Qubit(0), )
+                Return
+            num_results: 0
+        "#]],
+    );
+}
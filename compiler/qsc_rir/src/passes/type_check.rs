@@ -51,6 +51,16 @@ fn check_instr_types(program: &Program, instr: &Instruction) {
             assert_eq!(opr.get_type(), var.ty);
         }
 
+        Instruction::Sitofp(opr, var) => {
+            assert_eq!(Ty::Integer, opr.get_type());
+            assert_eq!(Ty::Double, var.ty);
+        }
+
+        Instruction::Fptosi(opr, var) => {
+            assert_eq!(Ty::Double, opr.get_type());
+            assert_eq!(Ty::Integer, var.ty);
+        }
+
         Instruction::Phi(args, var) => {
             for (opr, _) in args {
                 assert_eq!(opr.get_type(), var.ty);
@@ -65,6 +65,39 @@ fn unop_instr_mismatching_types_fails_check() {
     );
 }
 
+#[test]
+fn conversion_instr_between_int_and_double_passes_check() {
+    let int_var = Variable {
+        variable_id: VariableId(0),
+        ty: Ty::Integer,
+    };
+    let double_var = Variable {
+        variable_id: VariableId(1),
+        ty: Ty::Double,
+    };
+
+    check_instr_types(
+        &Program::new(),
+        &Instruction::Sitofp(Operand::Variable(int_var), double_var),
+    );
+    check_instr_types(
+        &Program::new(),
+        &Instruction::Fptosi(Operand::Variable(double_var), int_var),
+    );
+}
+
+#[test]
+#[should_panic(expected = "assertion `left == right` failed")]
+fn conversion_instr_to_same_type_fails_check() {
+    let var = Variable {
+        variable_id: VariableId(0),
+        ty: Ty::Integer,
+    };
+    let opr = Operand::Variable(var);
+
+    check_instr_types(&Program::new(), &Instruction::Sitofp(opr, var));
+}
+
 #[test]
 fn phi_instr_matching_types_passes_check() {
     let var = Variable {
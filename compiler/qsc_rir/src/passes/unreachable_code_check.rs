@@ -7,7 +7,7 @@ use crate::{
     rir::{Instruction, Program},
     utils,
 };
-use rustc_hash::FxHashSet;
+use qsc_data_structures::fx::FxHashSet;
 
 #[cfg(test)]
 mod tests;
@@ -1,9 +1,22 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
+mod binary;
+mod parse;
+mod stats;
+
+pub use binary::{DecodeError, FORMAT_VERSION};
+pub use parse::ParseError;
+pub use stats::ProgramStats;
+
+use bitflags::bitflags;
 use indenter::{indented, Indented};
 use qsc_data_structures::{index_map::IndexMap, target::TargetCapabilityFlags};
-use std::fmt::{self, Display, Formatter, Write};
+use std::{
+    fmt::{self, Display, Formatter, Write},
+    rc::Rc,
+    str::FromStr,
+};
 
 /// The root of the RIR.
 #[derive(Default, Clone)]
@@ -14,6 +27,103 @@ pub struct Program {
     pub config: Config,
     pub num_qubits: u32,
     pub num_results: u32,
+    /// Names of the Q# identifiers or callables that variables were derived from, if any.
+    pub variable_names: IndexMap<VariableId, Rc<str>>,
+    /// Names describing the constructs that blocks were generated for, if any.
+    pub block_names: IndexMap<BlockId, Rc<str>>,
+    /// The strings that tag literals refer to, such as the labels of recorded outputs.
+    pub tags: Vec<Rc<str>>,
+    /// The measurements and resets inserted at the end of the program for qubits that were not already measured or
+    /// reset, as configured by the program's `qubit_termination` option.
+    pub inserted_terminations: Vec<InsertedTermination>,
+    /// Where each value recorded by the output recording calls that end the program sits within the return value of
+    /// the entry point, in the order of the calls. Empty if the output is not recorded.
+    pub output_positions: Vec<OutputPosition>,
+}
+
+/// A measurement or reset inserted at the end of a program so that the qubit is terminated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InsertedTermination {
+    pub qubit: u32,
+    /// The result register the qubit is measured into, or `None` if the qubit is reset.
+    pub result: Option<u32>,
+}
+
+impl Display for InsertedTermination {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self.result {
+            Some(result) => write!(f, "Qubit({}) measured into Result({result})", self.qubit),
+            None => write!(f, "Qubit({}) reset", self.qubit),
+        }
+    }
+}
+
+/// The position of a recorded value within the return value of the entry point, which lets the records in the
+/// output of a run be mapped back to the structure of the return value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OutputPosition {
+    pub kind: OutputKind,
+    /// The indices into the tuples and arrays that contain the value, starting from the return value itself, which
+    /// has an empty path.
+    pub path: Vec<u32>,
+}
+
+impl Display for OutputPosition {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let path = self
+            .path
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        write!(f, "{} at [{path}]", self.kind)
+    }
+}
+
+/// The kind of value an output recording call records. Tuples and arrays are recorded with their length, and their
+/// items are recorded after them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputKind {
+    Tuple,
+    Array,
+    Result,
+    Bool,
+    Int,
+    Double,
+}
+
+impl OutputKind {
+    /// The name of the kind, as used in the text of programs and in QIR metadata.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Tuple => "tuple",
+            Self::Array => "array",
+            Self::Result => "result",
+            Self::Bool => "bool",
+            Self::Int => "int",
+            Self::Double => "double",
+        }
+    }
+
+    /// The name of the output recording callable that records values of the kind.
+    #[must_use]
+    pub fn record_callable_name(self) -> &'static str {
+        match self {
+            Self::Tuple => "__quantum__rt__tuple_record_output",
+            Self::Array => "__quantum__rt__array_record_output",
+            Self::Result => "__quantum__rt__result_record_output",
+            Self::Bool => "__quantum__rt__bool_record_output",
+            Self::Int => "__quantum__rt__int_record_output",
+            Self::Double => "__quantum__rt__double_record_output",
+        }
+    }
+}
+
+impl Display for OutputKind {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
 }
 
 impl Display for Program {
@@ -37,6 +147,29 @@ impl Display for Program {
         write!(indent, "\nconfig: {}", self.config)?;
         write!(indent, "\nnum_qubits: {}", self.num_qubits)?;
         write!(indent, "\nnum_results: {}", self.num_results)?;
+        if !self.tags.is_empty() {
+            write!(indent, "\ntags:")?;
+            indent = set_indentation(indent, 2);
+            for (idx, tag) in self.tags.iter().enumerate() {
+                write!(indent, "\n[{idx}]: {tag:?}")?;
+            }
+            indent = set_indentation(indent, 1);
+        }
+        if !self.inserted_terminations.is_empty() {
+            write!(indent, "\ninserted_terminations:")?;
+            indent = set_indentation(indent, 2);
+            for (idx, termination) in self.inserted_terminations.iter().enumerate() {
+                write!(indent, "\n[{idx}]: {termination}")?;
+            }
+            indent = set_indentation(indent, 1);
+        }
+        if !self.output_positions.is_empty() {
+            write!(indent, "\noutput_positions:")?;
+            indent = set_indentation(indent, 2);
+            for (idx, position) in self.output_positions.iter().enumerate() {
+                write!(indent, "\n[{idx}]: {position}")?;
+            }
+        }
         Ok(())
     }
 }
@@ -61,11 +194,175 @@ impl Program {
     pub fn get_block_mut(&mut self, id: BlockId) -> &mut Block {
         self.blocks.get_mut(id).expect("block should be present")
     }
+
+    #[must_use]
+    pub fn get_tag(&self, id: u32) -> &str {
+        self.tags.get(id as usize).expect("tag should be present")
+    }
+
+    /// Adds a tag and returns the literal that refers to it.
+    pub fn add_tag(&mut self, tag: impl Into<Rc<str>>) -> Literal {
+        let id = u32::try_from(self.tags.len()).expect("tag count should fit in u32");
+        self.tags.push(tag.into());
+        Literal::Tag(id)
+    }
 }
 
 #[derive(Default, Clone, Copy)]
 pub struct Config {
     pub capabilities: TargetCapabilityFlags,
+    pub naming: NamingScheme,
+    pub peephole: PeepholeConfig,
+    /// The gates the target supports, which the calls to other gates are decomposed into, or `None` if the
+    /// target supports every gate.
+    pub gate_set: Option<GateSet>,
+    /// How qubits that are not measured or reset by the end of the program are terminated.
+    pub qubit_termination: QubitTermination,
+    /// Whether the IDs of qubits are reused once the qubits are reset, which reduces the number of qubits the
+    /// program requires. This has no effect without the `QubitReset` capability, since only a reset returns a used
+    /// qubit to a known state.
+    pub reuse_qubits: bool,
+    /// Whether the output positions of the program are emitted as metadata in the generated QIR.
+    pub emit_output_positions: bool,
+}
+
+/// How qubits that are not measured or reset by the end of the program are terminated, for targets that require
+/// every qubit to be terminated.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum QubitTermination {
+    /// Qubits are left as they are.
+    #[default]
+    None,
+    /// Qubits are reset, or measured on targets without the reset capability.
+    Reset,
+    /// Qubits are measured into result registers that are not otherwise used.
+    Measure,
+}
+
+/// How variables and blocks are named when the program is emitted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NamingScheme {
+    /// Names are derived from numeric IDs only.
+    #[default]
+    Numeric,
+    /// Names are derived from the Q# identifiers and callables that variables and blocks originate from, when known,
+    /// followed by their numeric ID.
+    Readable,
+}
+
+/// Which gate-level peephole optimizations are applied to the calls in each block before the program is emitted.
+/// By default, none are, so that the emitted program has the gates that the source applies.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PeepholeConfig {
+    /// Whether two adjacent calls to the same self-inverse gate on the same qubits, such as `h` or `cx`, are removed.
+    pub cancel_self_inverse: bool,
+    /// Whether two adjacent rotations about the same axis on the same qubits with literal angles are merged into one.
+    pub merge_rotations: bool,
+    /// Whether single-qubit gates immediately before a reset of their qubit are removed.
+    pub remove_before_reset: bool,
+}
+
+impl PeepholeConfig {
+    /// Applies none of the optimizations.
+    pub const NONE: Self = Self {
+        cancel_self_inverse: false,
+        merge_rotations: false,
+        remove_before_reset: false,
+    };
+
+    /// Applies all of the optimizations.
+    pub const ALL: Self = Self {
+        cancel_self_inverse: true,
+        merge_rotations: true,
+        remove_before_reset: true,
+    };
+}
+
+bitflags! {
+    /// A set of intrinsic gates that a target supports, such as `rz, sx, cz`.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    pub struct GateSet: u32 {
+        const H = 1 << 0;
+        const X = 1 << 1;
+        const Y = 1 << 2;
+        const Z = 1 << 3;
+        const S = 1 << 4;
+        const S_ADJ = 1 << 5;
+        const T = 1 << 6;
+        const T_ADJ = 1 << 7;
+        const SX = 1 << 8;
+        const RX = 1 << 9;
+        const RY = 1 << 10;
+        const RZ = 1 << 11;
+        const CX = 1 << 12;
+        const CY = 1 << 13;
+        const CZ = 1 << 14;
+        const CCX = 1 << 15;
+        const SWAP = 1 << 16;
+        const RXX = 1 << 17;
+        const RYY = 1 << 18;
+        const RZZ = 1 << 19;
+        /// Rotations about multi-qubit Pauli operators, which `Exp` is emitted as instead of being decomposed into
+        /// two-qubit gates.
+        const EXP = 1 << 20;
+    }
+}
+
+impl GateSet {
+    /// The name of each gate, as used in a comma-separated gate set such as `rz, sx, cz`.
+    const NAMES: [(&'static str, Self); 21] = [
+        ("h", Self::H),
+        ("x", Self::X),
+        ("y", Self::Y),
+        ("z", Self::Z),
+        ("s", Self::S),
+        ("s_adj", Self::S_ADJ),
+        ("t", Self::T),
+        ("t_adj", Self::T_ADJ),
+        ("sx", Self::SX),
+        ("rx", Self::RX),
+        ("ry", Self::RY),
+        ("rz", Self::RZ),
+        ("cx", Self::CX),
+        ("cy", Self::CY),
+        ("cz", Self::CZ),
+        ("ccx", Self::CCX),
+        ("swap", Self::SWAP),
+        ("rxx", Self::RXX),
+        ("ryy", Self::RYY),
+        ("rzz", Self::RZZ),
+        ("exp", Self::EXP),
+    ];
+}
+
+impl Display for GateSet {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let names = Self::NAMES
+            .iter()
+            .filter(|(_, gate)| self.contains(*gate))
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>();
+        write!(f, "{}", names.join(", "))
+    }
+}
+
+impl FromStr for GateSet {
+    type Err = String;
+
+    /// Parses a comma-separated list of gate names, such as `rz, sx, cz`.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .try_fold(Self::empty(), |gate_set, name| {
+                Self::NAMES
+                    .iter()
+                    .find(|(gate_name, _)| *gate_name == name)
+                    .map(|(_, gate)| gate_set | *gate)
+                    .ok_or_else(|| format!("unknown gate `{name}`"))
+            })
+    }
 }
 
 impl Display for Config {
@@ -78,6 +375,24 @@ impl Display for Config {
         } else {
             write!(indent, "\ncapabilities: {:?}", self.capabilities)?;
         }
+        if self.naming != NamingScheme::Numeric {
+            write!(indent, "\nnaming: {:?}", self.naming)?;
+        }
+        if self.peephole != PeepholeConfig::default() {
+            write!(indent, "\npeephole: {:?}", self.peephole)?;
+        }
+        if let Some(gate_set) = self.gate_set {
+            write!(indent, "\ngate_set: {gate_set}")?;
+        }
+        if self.qubit_termination != QubitTermination::None {
+            write!(indent, "\nqubit_termination: {:?}", self.qubit_termination)?;
+        }
+        if self.reuse_qubits {
+            write!(indent, "\nreuse_qubits: true")?;
+        }
+        if self.emit_output_positions {
+            write!(indent, "\nemit_output_positions: true")?;
+        }
         Ok(())
     }
 }
@@ -227,7 +542,7 @@ impl Display for CallableType {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum ConditionCode {
     Eq,
     Ne,
@@ -251,7 +566,7 @@ impl Display for ConditionCode {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum FcmpConditionCode {
     False,
     OrderedAndEqual,
@@ -321,6 +636,8 @@ pub enum Instruction {
     BitwiseAnd(Operand, Operand, Variable),
     BitwiseOr(Operand, Operand, Variable),
     BitwiseXor(Operand, Operand, Variable),
+    Sitofp(Operand, Variable),
+    Fptosi(Operand, Variable),
     Phi(Vec<(Operand, BlockId)>, Variable),
     Return,
 }
@@ -481,6 +798,12 @@ impl Display for Instruction {
             Self::Fdiv(lhs, rhs, variable) => {
                 write_binary_instruction(f, "Fdiv", lhs, rhs, *variable)?;
             }
+            Self::Sitofp(value, variable) => {
+                write_unary_instruction(f, "Sitofp", value, *variable)?;
+            }
+            Self::Fptosi(value, variable) => {
+                write_unary_instruction(f, "Fptosi", value, *variable)?;
+            }
             Self::Fcmp(op, lhs, rhs, variable) => {
                 write_fcmp_instruction(f, *op, lhs, rhs, *variable)?;
             }
@@ -607,7 +930,7 @@ impl Operand {
                 Literal::Bool(_) => Ty::Boolean,
                 Literal::Integer(_) => Ty::Integer,
                 Literal::Double(_) => Ty::Double,
-                Literal::Pointer => Ty::Pointer,
+                Literal::Pointer | Literal::Tag(_) => Ty::Pointer,
             },
             Operand::Variable(var) => var.ty,
         }
@@ -622,6 +945,8 @@ pub enum Literal {
     Integer(i64),
     Double(f64),
     Pointer,
+    /// A pointer to the string with the given index in the tags of the program.
+    Tag(u32),
 }
 
 impl Display for Literal {
@@ -633,6 +958,7 @@ impl Display for Literal {
             Self::Integer(i) => write!(f, "Integer({i})")?,
             Self::Double(d) => write!(f, "Double({d})")?,
             Self::Pointer => write!(f, "Pointer")?,
+            Self::Tag(id) => write!(f, "Tag({id})")?,
         };
         Ok(())
     }
@@ -665,6 +991,13 @@ impl PartialEq for Literal {
                 }
             }
             Self::Pointer => matches!(other, Self::Pointer),
+            Self::Tag(self_tag) => {
+                if let Self::Tag(other_tag) = other {
+                    self_tag == other_tag
+                } else {
+                    false
+                }
+            }
             Self::Qubit(self_qubit) => {
                 if let Self::Qubit(other_qubit) = other {
                     self_qubit == other_qubit
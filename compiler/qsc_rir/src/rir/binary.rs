@@ -0,0 +1,725 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use super::{
+    Block, BlockId, Callable, CallableId, CallableType, ConditionCode, Config, FcmpConditionCode,
+    GateSet, InsertedTermination, Instruction, Literal, NamingScheme, Operand, OutputKind,
+    OutputPosition, PeepholeConfig, Program, QubitTermination, Ty, Variable, VariableId,
+};
+use qsc_data_structures::{index_map::IndexMap, target::TargetCapabilityFlags};
+use std::rc::Rc;
+use thiserror::Error;
+
+/// The bytes every encoded program starts with.
+const MAGIC: &[u8; 4] = b"QRIR";
+
+/// The version of the binary format, which changes whenever the encoding of a program changes.
+/// Programs encoded with a different version are rejected rather than misread.
+pub const FORMAT_VERSION: u32 = 7;
+
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum DecodeError {
+    #[error("data is not an encoded RIR program")]
+    NotAProgram,
+    #[error("RIR program is encoded with format version {0}, expected version {FORMAT_VERSION}")]
+    UnsupportedVersion(u32),
+    #[error("encoded RIR program ends unexpectedly at byte {0}")]
+    UnexpectedEnd(usize),
+    #[error("invalid {what} tag {tag} at byte {offset}")]
+    InvalidTag {
+        what: &'static str,
+        tag: u8,
+        offset: usize,
+    },
+    #[error("invalid UTF-8 string at byte {0}")]
+    InvalidString(usize),
+    #[error("unknown capabilities {0:#x}")]
+    InvalidCapabilities(u32),
+    #[error("unknown gates {0:#x}")]
+    InvalidGateSet(u32),
+    #[error("{0} extra bytes after the encoded RIR program")]
+    TrailingBytes(usize),
+}
+
+impl Program {
+    /// Encodes the program, including its configuration, tags and the names of its variables and blocks, in a compact
+    /// binary format that [`Program::from_bytes`] decodes back into the same program. The encoding only depends on
+    /// the program and the format version, so it can be used to cache the result of partial evaluation.
+    #[must_use]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = Writer(Vec::new());
+        writer.0.extend_from_slice(MAGIC);
+        writer.u32(FORMAT_VERSION);
+
+        writer.u32(self.entry.0);
+        writer.u32(self.num_qubits);
+        writer.u32(self.num_results);
+        writer.config(self.config);
+        writer.map(&self.callables, Writer::callable);
+        writer.map(&self.blocks, |writer, block| {
+            writer.len(block.0.len());
+            for instr in &block.0 {
+                writer.instruction(instr);
+            }
+        });
+        writer.map(&self.variable_names, |writer, name| writer.str(name));
+        writer.map(&self.block_names, |writer, name| writer.str(name));
+        writer.len(self.tags.len());
+        for tag in &self.tags {
+            writer.str(tag);
+        }
+        writer.len(self.inserted_terminations.len());
+        for termination in &self.inserted_terminations {
+            writer.u32(termination.qubit);
+            writer.bool(termination.result.is_some());
+            if let Some(result) = termination.result {
+                writer.u32(result);
+            }
+        }
+        writer.len(self.output_positions.len());
+        for position in &self.output_positions {
+            writer.output_position(position);
+        }
+        writer.0
+    }
+
+    /// Decodes a program encoded with [`Program::to_bytes`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bytes are not a program encoded with the current format version.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let mut reader = Reader { bytes, offset: 0 };
+        if reader.take(MAGIC.len()).ok() != Some(MAGIC.as_slice()) {
+            return Err(DecodeError::NotAProgram);
+        }
+        let version = reader.u32()?;
+        if version != FORMAT_VERSION {
+            return Err(DecodeError::UnsupportedVersion(version));
+        }
+
+        let program = Program {
+            entry: CallableId(reader.u32()?),
+            num_qubits: reader.u32()?,
+            num_results: reader.u32()?,
+            config: reader.config()?,
+            callables: reader.map(Reader::callable)?,
+            blocks: reader.map(|reader| {
+                let mut instrs = Vec::new();
+                for _ in 0..reader.len()? {
+                    instrs.push(reader.instruction()?);
+                }
+                Ok(Block(instrs))
+            })?,
+            variable_names: reader.map(Reader::str)?,
+            block_names: reader.map(Reader::str)?,
+            tags: {
+                let mut tags = Vec::new();
+                for _ in 0..reader.len()? {
+                    tags.push(reader.str()?);
+                }
+                tags
+            },
+            inserted_terminations: {
+                let mut terminations = Vec::new();
+                for _ in 0..reader.len()? {
+                    let qubit = reader.u32()?;
+                    let result = if reader.bool()? {
+                        Some(reader.u32()?)
+                    } else {
+                        None
+                    };
+                    terminations.push(InsertedTermination { qubit, result });
+                }
+                terminations
+            },
+            output_positions: {
+                let mut positions = Vec::new();
+                for _ in 0..reader.len()? {
+                    positions.push(reader.output_position()?);
+                }
+                positions
+            },
+        };
+
+        match bytes.len() - reader.offset {
+            0 => Ok(program),
+            extra => Err(DecodeError::TrailingBytes(extra)),
+        }
+    }
+}
+
+struct Writer(Vec<u8>);
+
+impl Writer {
+    fn u8(&mut self, value: u8) {
+        self.0.push(value);
+    }
+
+    fn bool(&mut self, value: bool) {
+        self.u8(value.into());
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.0.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn len(&mut self, len: usize) {
+        self.u32(u32::try_from(len).expect("length should fit in u32"));
+    }
+
+    fn str(&mut self, value: &str) {
+        self.len(value.len());
+        self.0.extend_from_slice(value.as_bytes());
+    }
+
+    fn map<K, V>(&mut self, map: &IndexMap<K, V>, mut value: impl FnMut(&mut Self, &V))
+    where
+        K: Into<usize> + From<usize> + Copy,
+    {
+        self.len(map.iter().count());
+        for (key, item) in map.iter() {
+            self.len(key.into());
+            value(self, item);
+        }
+    }
+
+    fn config(&mut self, config: Config) {
+        self.u32(config.capabilities.bits());
+        self.u8(match config.naming {
+            NamingScheme::Numeric => 0,
+            NamingScheme::Readable => 1,
+        });
+        self.bool(config.peephole.cancel_self_inverse);
+        self.bool(config.peephole.merge_rotations);
+        self.bool(config.peephole.remove_before_reset);
+        self.bool(config.gate_set.is_some());
+        if let Some(gate_set) = config.gate_set {
+            self.u32(gate_set.bits());
+        }
+        self.u8(match config.qubit_termination {
+            QubitTermination::None => 0,
+            QubitTermination::Reset => 1,
+            QubitTermination::Measure => 2,
+        });
+        self.bool(config.reuse_qubits);
+        self.bool(config.emit_output_positions);
+    }
+
+    fn callable(&mut self, callable: &Callable) {
+        self.str(&callable.name);
+        self.len(callable.input_type.len());
+        for ty in &callable.input_type {
+            self.ty(*ty);
+        }
+        match callable.output_type {
+            Some(ty) => {
+                self.u8(1);
+                self.ty(ty);
+            }
+            None => self.u8(0),
+        }
+        match callable.body {
+            Some(block_id) => {
+                self.u8(1);
+                self.u32(block_id.0);
+            }
+            None => self.u8(0),
+        }
+        self.u8(match callable.call_type {
+            CallableType::Measurement => 0,
+            CallableType::Reset => 1,
+            CallableType::Readout => 2,
+            CallableType::OutputRecording => 3,
+            CallableType::Regular => 4,
+        });
+    }
+
+    fn output_position(&mut self, position: &OutputPosition) {
+        self.u8(match position.kind {
+            OutputKind::Tuple => 0,
+            OutputKind::Array => 1,
+            OutputKind::Result => 2,
+            OutputKind::Bool => 3,
+            OutputKind::Int => 4,
+            OutputKind::Double => 5,
+        });
+        self.len(position.path.len());
+        for index in &position.path {
+            self.u32(*index);
+        }
+    }
+
+    fn ty(&mut self, ty: Ty) {
+        self.u8(match ty {
+            Ty::Qubit => 0,
+            Ty::Result => 1,
+            Ty::Boolean => 2,
+            Ty::Integer => 3,
+            Ty::Double => 4,
+            Ty::Pointer => 5,
+        });
+    }
+
+    fn variable(&mut self, variable: Variable) {
+        self.u32(variable.variable_id.0);
+        self.ty(variable.ty);
+    }
+
+    fn operand(&mut self, operand: &Operand) {
+        match operand {
+            Operand::Literal(literal) => match literal {
+                Literal::Qubit(id) => {
+                    self.u8(0);
+                    self.u32(*id);
+                }
+                Literal::Result(id) => {
+                    self.u8(1);
+                    self.u32(*id);
+                }
+                Literal::Bool(value) => {
+                    self.u8(2);
+                    self.bool(*value);
+                }
+                Literal::Integer(value) => {
+                    self.u8(3);
+                    self.0.extend_from_slice(&value.to_le_bytes());
+                }
+                Literal::Double(value) => {
+                    self.u8(4);
+                    self.0.extend_from_slice(&value.to_bits().to_le_bytes());
+                }
+                Literal::Pointer => self.u8(5),
+                Literal::Tag(id) => {
+                    self.u8(7);
+                    self.u32(*id);
+                }
+            },
+            Operand::Variable(variable) => {
+                self.u8(6);
+                self.variable(*variable);
+            }
+        }
+    }
+
+    fn binary(&mut self, tag: u8, lhs: &Operand, rhs: &Operand, variable: Variable) {
+        self.u8(tag);
+        self.operand(lhs);
+        self.operand(rhs);
+        self.variable(variable);
+    }
+
+    fn instruction(&mut self, instr: &Instruction) {
+        match instr {
+            Instruction::Store(operand, variable) => {
+                self.u8(0);
+                self.operand(operand);
+                self.variable(*variable);
+            }
+            Instruction::Call(callable_id, args, variable) => {
+                self.u8(1);
+                self.u32(callable_id.0);
+                self.len(args.len());
+                for arg in args {
+                    self.operand(arg);
+                }
+                match variable {
+                    Some(variable) => {
+                        self.u8(1);
+                        self.variable(*variable);
+                    }
+                    None => self.u8(0),
+                }
+            }
+            Instruction::Jump(block_id) => {
+                self.u8(2);
+                self.u32(block_id.0);
+            }
+            Instruction::Branch(variable, true_block, false_block) => {
+                self.u8(3);
+                self.variable(*variable);
+                self.u32(true_block.0);
+                self.u32(false_block.0);
+            }
+            Instruction::Add(lhs, rhs, variable) => self.binary(4, lhs, rhs, *variable),
+            Instruction::Sub(lhs, rhs, variable) => self.binary(5, lhs, rhs, *variable),
+            Instruction::Mul(lhs, rhs, variable) => self.binary(6, lhs, rhs, *variable),
+            Instruction::Sdiv(lhs, rhs, variable) => self.binary(7, lhs, rhs, *variable),
+            Instruction::Srem(lhs, rhs, variable) => self.binary(8, lhs, rhs, *variable),
+            Instruction::Shl(lhs, rhs, variable) => self.binary(9, lhs, rhs, *variable),
+            Instruction::Ashr(lhs, rhs, variable) => self.binary(10, lhs, rhs, *variable),
+            Instruction::Fadd(lhs, rhs, variable) => self.binary(11, lhs, rhs, *variable),
+            Instruction::Fsub(lhs, rhs, variable) => self.binary(12, lhs, rhs, *variable),
+            Instruction::Fmul(lhs, rhs, variable) => self.binary(13, lhs, rhs, *variable),
+            Instruction::Fdiv(lhs, rhs, variable) => self.binary(14, lhs, rhs, *variable),
+            Instruction::Fcmp(condition_code, lhs, rhs, variable) => {
+                self.binary(15, lhs, rhs, *variable);
+                self.u8(fcmp_condition_code_tag(*condition_code));
+            }
+            Instruction::Icmp(condition_code, lhs, rhs, variable) => {
+                self.binary(16, lhs, rhs, *variable);
+                self.u8(match condition_code {
+                    ConditionCode::Eq => 0,
+                    ConditionCode::Ne => 1,
+                    ConditionCode::Slt => 2,
+                    ConditionCode::Sle => 3,
+                    ConditionCode::Sgt => 4,
+                    ConditionCode::Sge => 5,
+                });
+            }
+            Instruction::LogicalNot(operand, variable) => {
+                self.u8(17);
+                self.operand(operand);
+                self.variable(*variable);
+            }
+            Instruction::LogicalAnd(lhs, rhs, variable) => self.binary(18, lhs, rhs, *variable),
+            Instruction::LogicalOr(lhs, rhs, variable) => self.binary(19, lhs, rhs, *variable),
+            Instruction::BitwiseNot(operand, variable) => {
+                self.u8(20);
+                self.operand(operand);
+                self.variable(*variable);
+            }
+            Instruction::BitwiseAnd(lhs, rhs, variable) => self.binary(21, lhs, rhs, *variable),
+            Instruction::BitwiseOr(lhs, rhs, variable) => self.binary(22, lhs, rhs, *variable),
+            Instruction::BitwiseXor(lhs, rhs, variable) => self.binary(23, lhs, rhs, *variable),
+            Instruction::Phi(args, variable) => {
+                self.u8(24);
+                self.len(args.len());
+                for (operand, block_id) in args {
+                    self.operand(operand);
+                    self.u32(block_id.0);
+                }
+                self.variable(*variable);
+            }
+            Instruction::Return => self.u8(25),
+            Instruction::Sitofp(operand, variable) => {
+                self.u8(26);
+                self.operand(operand);
+                self.variable(*variable);
+            }
+            Instruction::Fptosi(operand, variable) => {
+                self.u8(27);
+                self.operand(operand);
+                self.variable(*variable);
+            }
+        }
+    }
+}
+
+/// The condition codes, in the order of their tags.
+const FCMP_CONDITION_CODES: [FcmpConditionCode; 16] = [
+    FcmpConditionCode::False,
+    FcmpConditionCode::OrderedAndEqual,
+    FcmpConditionCode::OrderedAndGreaterThan,
+    FcmpConditionCode::OrderedAndGreaterThanOrEqual,
+    FcmpConditionCode::OrderedAndLessThan,
+    FcmpConditionCode::OrderedAndLessThanOrEqual,
+    FcmpConditionCode::OrderedAndNotEqual,
+    FcmpConditionCode::Ordered,
+    FcmpConditionCode::UnorderedOrEqual,
+    FcmpConditionCode::UnorderedOrGreaterThan,
+    FcmpConditionCode::UnorderedOrGreaterThanOrEqual,
+    FcmpConditionCode::UnorderedOrLessThan,
+    FcmpConditionCode::UnorderedOrLessThanOrEqual,
+    FcmpConditionCode::UnorderedOrNotEqual,
+    FcmpConditionCode::Unordered,
+    FcmpConditionCode::True,
+];
+
+fn fcmp_condition_code_tag(condition_code: FcmpConditionCode) -> u8 {
+    let index = FCMP_CONDITION_CODES
+        .iter()
+        .position(|code| *code == condition_code)
+        .expect("condition code should have a tag");
+    u8::try_from(index).expect("tag should fit in u8")
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], DecodeError> {
+        let bytes = self
+            .offset
+            .checked_add(len)
+            .and_then(|end| self.bytes.get(self.offset..end))
+            .ok_or(DecodeError::UnexpectedEnd(self.bytes.len()))?;
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], DecodeError> {
+        Ok(self
+            .take(N)?
+            .try_into()
+            .expect("slice should have length N"))
+    }
+
+    fn u8(&mut self) -> Result<u8, DecodeError> {
+        Ok(self.take(1)?[0])
+    }
+
+    /// Reads a tag, mapping it to a value with the given function, which returns `None` for unknown tags.
+    fn tag<T>(
+        &mut self,
+        what: &'static str,
+        value: impl FnOnce(u8) -> Option<T>,
+    ) -> Result<T, DecodeError> {
+        let offset = self.offset;
+        let tag = self.u8()?;
+        value(tag).ok_or(DecodeError::InvalidTag { what, tag, offset })
+    }
+
+    fn bool(&mut self) -> Result<bool, DecodeError> {
+        self.tag("boolean", |tag| match tag {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        })
+    }
+
+    fn u32(&mut self) -> Result<u32, DecodeError> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    fn len(&mut self) -> Result<usize, DecodeError> {
+        Ok(self.u32()? as usize)
+    }
+
+    fn str(&mut self) -> Result<Rc<str>, DecodeError> {
+        let offset = self.offset;
+        let len = self.len()?;
+        std::str::from_utf8(self.take(len)?)
+            .map(Into::into)
+            .map_err(|_| DecodeError::InvalidString(offset))
+    }
+
+    fn map<K, V>(
+        &mut self,
+        mut value: impl FnMut(&mut Self) -> Result<V, DecodeError>,
+    ) -> Result<IndexMap<K, V>, DecodeError>
+    where
+        K: Into<usize> + From<usize>,
+    {
+        let mut map = IndexMap::new();
+        for _ in 0..self.len()? {
+            let key = K::from(self.len()?);
+            map.insert(key, value(self)?);
+        }
+        Ok(map)
+    }
+
+    fn config(&mut self) -> Result<Config, DecodeError> {
+        let bits = self.u32()?;
+        let capabilities =
+            TargetCapabilityFlags::from_bits(bits).ok_or(DecodeError::InvalidCapabilities(bits))?;
+        let naming = self.tag("naming scheme", |tag| match tag {
+            0 => Some(NamingScheme::Numeric),
+            1 => Some(NamingScheme::Readable),
+            _ => None,
+        })?;
+        let peephole = PeepholeConfig {
+            cancel_self_inverse: self.bool()?,
+            merge_rotations: self.bool()?,
+            remove_before_reset: self.bool()?,
+        };
+        let gate_set = if self.bool()? {
+            let bits = self.u32()?;
+            Some(GateSet::from_bits(bits).ok_or(DecodeError::InvalidGateSet(bits))?)
+        } else {
+            None
+        };
+        let qubit_termination = self.tag("qubit termination", |tag| match tag {
+            0 => Some(QubitTermination::None),
+            1 => Some(QubitTermination::Reset),
+            2 => Some(QubitTermination::Measure),
+            _ => None,
+        })?;
+        Ok(Config {
+            capabilities,
+            naming,
+            peephole,
+            gate_set,
+            qubit_termination,
+            reuse_qubits: self.bool()?,
+            emit_output_positions: self.bool()?,
+        })
+    }
+
+    fn callable(&mut self) -> Result<Callable, DecodeError> {
+        let name = self.str()?.to_string();
+        let mut input_type = Vec::new();
+        for _ in 0..self.len()? {
+            input_type.push(self.ty()?);
+        }
+        let output_type = if self.bool()? { Some(self.ty()?) } else { None };
+        let body = if self.bool()? {
+            Some(BlockId(self.u32()?))
+        } else {
+            None
+        };
+        let call_type = self.tag("callable type", |tag| match tag {
+            0 => Some(CallableType::Measurement),
+            1 => Some(CallableType::Reset),
+            2 => Some(CallableType::Readout),
+            3 => Some(CallableType::OutputRecording),
+            4 => Some(CallableType::Regular),
+            _ => None,
+        })?;
+        Ok(Callable {
+            name,
+            input_type,
+            output_type,
+            body,
+            call_type,
+        })
+    }
+
+    fn output_position(&mut self) -> Result<OutputPosition, DecodeError> {
+        let kind = self.tag("output kind", |tag| match tag {
+            0 => Some(OutputKind::Tuple),
+            1 => Some(OutputKind::Array),
+            2 => Some(OutputKind::Result),
+            3 => Some(OutputKind::Bool),
+            4 => Some(OutputKind::Int),
+            5 => Some(OutputKind::Double),
+            _ => None,
+        })?;
+        let mut path = Vec::new();
+        for _ in 0..self.len()? {
+            path.push(self.u32()?);
+        }
+        Ok(OutputPosition { kind, path })
+    }
+
+    fn ty(&mut self) -> Result<Ty, DecodeError> {
+        self.tag("type", |tag| match tag {
+            0 => Some(Ty::Qubit),
+            1 => Some(Ty::Result),
+            2 => Some(Ty::Boolean),
+            3 => Some(Ty::Integer),
+            4 => Some(Ty::Double),
+            5 => Some(Ty::Pointer),
+            _ => None,
+        })
+    }
+
+    fn variable(&mut self) -> Result<Variable, DecodeError> {
+        Ok(Variable {
+            variable_id: VariableId(self.u32()?),
+            ty: self.ty()?,
+        })
+    }
+
+    fn operand(&mut self) -> Result<Operand, DecodeError> {
+        let offset = self.offset;
+        Ok(match self.u8()? {
+            0 => Operand::Literal(Literal::Qubit(self.u32()?)),
+            1 => Operand::Literal(Literal::Result(self.u32()?)),
+            2 => Operand::Literal(Literal::Bool(self.bool()?)),
+            3 => Operand::Literal(Literal::Integer(i64::from_le_bytes(self.array()?))),
+            4 => Operand::Literal(Literal::Double(f64::from_bits(u64::from_le_bytes(
+                self.array()?,
+            )))),
+            5 => Operand::Literal(Literal::Pointer),
+            6 => Operand::Variable(self.variable()?),
+            7 => Operand::Literal(Literal::Tag(self.u32()?)),
+            tag => {
+                return Err(DecodeError::InvalidTag {
+                    what: "operand",
+                    tag,
+                    offset,
+                })
+            }
+        })
+    }
+
+    fn binary(
+        &mut self,
+        instr: fn(Operand, Operand, Variable) -> Instruction,
+    ) -> Result<Instruction, DecodeError> {
+        Ok(instr(self.operand()?, self.operand()?, self.variable()?))
+    }
+
+    fn instruction(&mut self) -> Result<Instruction, DecodeError> {
+        let offset = self.offset;
+        Ok(match self.u8()? {
+            0 => Instruction::Store(self.operand()?, self.variable()?),
+            1 => {
+                let callable_id = CallableId(self.u32()?);
+                let mut args = Vec::new();
+                for _ in 0..self.len()? {
+                    args.push(self.operand()?);
+                }
+                let variable = if self.bool()? {
+                    Some(self.variable()?)
+                } else {
+                    None
+                };
+                Instruction::Call(callable_id, args, variable)
+            }
+            2 => Instruction::Jump(BlockId(self.u32()?)),
+            3 => Instruction::Branch(self.variable()?, BlockId(self.u32()?), BlockId(self.u32()?)),
+            4 => self.binary(Instruction::Add)?,
+            5 => self.binary(Instruction::Sub)?,
+            6 => self.binary(Instruction::Mul)?,
+            7 => self.binary(Instruction::Sdiv)?,
+            8 => self.binary(Instruction::Srem)?,
+            9 => self.binary(Instruction::Shl)?,
+            10 => self.binary(Instruction::Ashr)?,
+            11 => self.binary(Instruction::Fadd)?,
+            12 => self.binary(Instruction::Fsub)?,
+            13 => self.binary(Instruction::Fmul)?,
+            14 => self.binary(Instruction::Fdiv)?,
+            15 => {
+                let (lhs, rhs, variable) = (self.operand()?, self.operand()?, self.variable()?);
+                let condition_code = self.tag("condition code", |tag| {
+                    FCMP_CONDITION_CODES.get(usize::from(tag)).copied()
+                })?;
+                Instruction::Fcmp(condition_code, lhs, rhs, variable)
+            }
+            16 => {
+                let (lhs, rhs, variable) = (self.operand()?, self.operand()?, self.variable()?);
+                let condition_code = self.tag("condition code", |tag| match tag {
+                    0 => Some(ConditionCode::Eq),
+                    1 => Some(ConditionCode::Ne),
+                    2 => Some(ConditionCode::Slt),
+                    3 => Some(ConditionCode::Sle),
+                    4 => Some(ConditionCode::Sgt),
+                    5 => Some(ConditionCode::Sge),
+                    _ => None,
+                })?;
+                Instruction::Icmp(condition_code, lhs, rhs, variable)
+            }
+            17 => Instruction::LogicalNot(self.operand()?, self.variable()?),
+            18 => self.binary(Instruction::LogicalAnd)?,
+            19 => self.binary(Instruction::LogicalOr)?,
+            20 => Instruction::BitwiseNot(self.operand()?, self.variable()?),
+            21 => self.binary(Instruction::BitwiseAnd)?,
+            22 => self.binary(Instruction::BitwiseOr)?,
+            23 => self.binary(Instruction::BitwiseXor)?,
+            24 => {
+                let mut args = Vec::new();
+                for _ in 0..self.len()? {
+                    args.push((self.operand()?, BlockId(self.u32()?)));
+                }
+                Instruction::Phi(args, self.variable()?)
+            }
+            25 => Instruction::Return,
+            26 => Instruction::Sitofp(self.operand()?, self.variable()?),
+            27 => Instruction::Fptosi(self.operand()?, self.variable()?),
+            tag => {
+                return Err(DecodeError::InvalidTag {
+                    what: "instruction",
+                    tag,
+                    offset,
+                })
+            }
+        })
+    }
+}
@@ -0,0 +1,273 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use expect_test::expect;
+
+use super::FORMAT_VERSION;
+use crate::rir::{BlockId, DecodeError, Program, VariableId};
+
+/// A program using every kind of instruction, operand and callable.
+const PROGRAM: &str = "
+    Program:
+        entry: 0
+        callables:
+            Callable 0: Callable:
+                name: main
+                call_type: Regular
+                input_type: <VOID>
+                output_type: Integer
+                body: 0
+            Callable 1: Callable:
+                name: __quantum__qis__mresetz__body
+                call_type: Measurement
+                input_type:
+                    [0]: Qubit
+                    [1]: Result
+                output_type: <VOID>
+                body: <NONE>
+            Callable 2: Callable:
+                name: __quantum__qis__read_result__body
+                call_type: Readout
+                input_type:
+                    [0]: Result
+                output_type: Boolean
+                body: <NONE>
+            Callable 3: Callable:
+                name: __quantum__qis__reset__body
+                call_type: Reset
+                input_type:
+                    [0]: Qubit
+                output_type: <VOID>
+                body: <NONE>
+            Callable 4: Callable:
+                name: __quantum__rt__int_record_output
+                call_type: OutputRecording
+                input_type:
+                    [0]: Integer
+                    [1]: Pointer
+                output_type: <VOID>
+                body: <NONE>
+            Callable 5: Callable:
+                name: __quantum__qis__rx__body
+                call_type: Regular
+                input_type:
+                    [0]: Double
+                    [1]: Qubit
+                output_type: <VOID>
+                body: <NONE>
+        blocks:
+            Block 0: Block:
+                Call id(1), args( Qubit(0), Result(0), )
+                Variable(0, Boolean) = Call id(2), args( Result(0), )
+                Variable(1, Boolean) = LogicalNot Variable(0, Boolean)
+                Variable(2, Boolean) = LogicalAnd Variable(0, Boolean), Bool(true)
+                Variable(3, Boolean) = LogicalOr Variable(2, Boolean), Bool(false)
+                Branch Variable(3, Boolean), 1, 2
+            Block 1: Block:
+                Variable(4, Integer) = Store Integer(-9223372036854775808)
+                Variable(5, Integer) = Add Variable(4, Integer), Integer(1)
+                Variable(6, Integer) = Sub Variable(5, Integer), Integer(2)
+                Variable(7, Integer) = Mul Variable(6, Integer), Integer(3)
+                Variable(8, Integer) = Sdiv Variable(7, Integer), Integer(4)
+                Variable(9, Integer) = Srem Variable(8, Integer), Integer(5)
+                Variable(10, Integer) = Shl Variable(9, Integer), Integer(6)
+                Variable(11, Integer) = Ashr Variable(10, Integer), Integer(7)
+                Variable(12, Integer) = BitwiseAnd Variable(11, Integer), Integer(8)
+                Variable(13, Integer) = BitwiseOr Variable(12, Integer), Integer(9)
+                Variable(14, Integer) = BitwiseXor Variable(13, Integer), Integer(10)
+                Variable(15, Integer) = BitwiseNot Variable(14, Integer)
+                Variable(16, Boolean) = Icmp Sle, Variable(15, Integer), Integer(0)
+                Jump(2)
+            Block 2: Block:
+                Variable(17, Double) = Fadd Double(0.1), Double(-0)
+                Variable(18, Double) = Fsub Variable(17, Double), Double(inf)
+                Variable(19, Double) = Fmul Variable(18, Double), Double(1e-300)
+                Variable(20, Double) = Fdiv Variable(19, Double), Double(3.141592653589793)
+                Variable(21, Boolean) = Fcmp Uno, Variable(20, Double), Double(NaN)
+                Variable(23, Integer) = Fptosi Variable(20, Double)
+                Variable(24, Double) = Sitofp Variable(23, Integer)
+                Call id(5), args( Variable(20, Double), Qubit(1), )
+                Call id(3), args( Qubit(1), )
+                Variable(22, Integer) = Phi ( [Integer(1), 0], [Variable(15, Integer), 1], )
+                Call id(4), args( Variable(22, Integer), Tag(0), )
+                Return
+            Block 3: Block: <EMPTY>
+        config: Config:
+            capabilities: TargetCapabilityFlags(Adaptive | IntegerComputations | FloatingPointComputations)
+            naming: Readable
+            peephole: PeepholeConfig { cancel_self_inverse: true, merge_rotations: false, remove_before_reset: true }
+            gate_set: sx, rz, cz
+            qubit_termination: Measure
+            reuse_qubits: true
+            emit_output_positions: true
+        num_qubits: 2
+        num_results: 1
+        tags:
+            [0]: \"value\"
+        inserted_terminations:
+            [0]: Qubit(0) reset
+            [1]: Qubit(1) measured into Result(1)
+        output_positions:
+            [0]: int at []
+";
+
+fn program() -> Program {
+    let mut program = PROGRAM.parse::<Program>().expect("program should parse");
+    program
+        .variable_names
+        .insert(VariableId(0), "is_one".into());
+    program
+        .variable_names
+        .insert(VariableId(22), "value".into());
+    program.block_names.insert(BlockId(1), "if_then".into());
+    program
+}
+
+#[test]
+fn program_round_trips() {
+    let program = program();
+    let decoded = Program::from_bytes(&program.to_bytes()).expect("program should decode");
+    assert_eq!(decoded.to_string(), program.to_string());
+    assert_eq!(decoded.entry, program.entry);
+    assert_eq!(
+        decoded.variable_names.iter().collect::<Vec<_>>(),
+        program.variable_names.iter().collect::<Vec<_>>()
+    );
+    assert_eq!(
+        decoded.block_names.iter().collect::<Vec<_>>(),
+        program.block_names.iter().collect::<Vec<_>>()
+    );
+    assert_eq!(decoded.tags, program.tags);
+    assert_eq!(decoded.inserted_terminations, program.inserted_terminations);
+    assert_eq!(decoded.output_positions, program.output_positions);
+    assert_eq!(decoded.to_bytes(), program.to_bytes());
+}
+
+#[test]
+fn empty_program_encoding_is_stable() {
+    let bytes = Program::new().to_bytes();
+    expect![[r#"
+        [
+            81,
+            82,
+            73,
+            82,
+            7,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+        ]
+    "#]]
+    .assert_debug_eq(&bytes);
+}
+
+#[test]
+fn data_without_magic_is_error() {
+    assert_eq!(
+        Program::from_bytes(b"QIR program").err(),
+        Some(DecodeError::NotAProgram)
+    );
+    assert_eq!(
+        Program::from_bytes(b"").err(),
+        Some(DecodeError::NotAProgram)
+    );
+}
+
+#[test]
+fn other_format_version_is_error() {
+    let mut bytes = Program::new().to_bytes();
+    bytes[4..8].copy_from_slice(&(FORMAT_VERSION + 1).to_le_bytes());
+    expect!["RIR program is encoded with format version 8, expected version 7"].assert_eq(
+        &Program::from_bytes(&bytes)
+            .err()
+            .expect("program should not decode")
+            .to_string(),
+    );
+}
+
+#[test]
+fn truncated_program_is_error() {
+    let bytes = program().to_bytes();
+    for len in 4..bytes.len() {
+        assert_eq!(
+            Program::from_bytes(&bytes[..len]).err(),
+            Some(DecodeError::UnexpectedEnd(len)),
+            "program truncated to {len} bytes should not decode"
+        );
+    }
+}
+
+#[test]
+fn invalid_tag_is_error() {
+    let mut bytes = Program::new().to_bytes();
+    // The naming scheme follows the magic, the version, the entry, the counts and the capabilities.
+    bytes[24] = 7;
+    expect!["invalid naming scheme tag 7 at byte 24"].assert_eq(
+        &Program::from_bytes(&bytes)
+            .err()
+            .expect("program should not decode")
+            .to_string(),
+    );
+}
+
+#[test]
+fn trailing_bytes_are_error() {
+    let mut bytes = Program::new().to_bytes();
+    bytes.extend_from_slice(&[0, 0]);
+    assert_eq!(
+        Program::from_bytes(&bytes).err(),
+        Some(DecodeError::TrailingBytes(2))
+    );
+}
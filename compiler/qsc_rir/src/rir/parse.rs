@@ -0,0 +1,651 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use super::{
+    Block, BlockId, Callable, CallableId, CallableType, ConditionCode, FcmpConditionCode,
+    InsertedTermination, Instruction, Literal, NamingScheme, Operand, OutputKind, OutputPosition,
+    PeepholeConfig, Program, QubitTermination, Ty, Variable, VariableId,
+};
+use qsc_data_structures::target::TargetCapabilityFlags;
+use std::{rc::Rc, str::FromStr};
+use thiserror::Error;
+
+/// An error in the text of a RIR program.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+#[error("invalid RIR on line {line}: {message}")]
+pub struct ParseError {
+    /// The line of the text the error is on, starting at 1.
+    pub line: usize,
+    pub message: String,
+}
+
+/// The section of a program that the lines being parsed belong to.
+enum Section {
+    Program,
+    Callable(CallableId),
+    InputType(CallableId),
+    Block(BlockId),
+    Config,
+    Tags,
+    InsertedTerminations,
+    OutputPositions,
+}
+
+/// Parses a program from the text produced by its `Display` implementation, so that programs can be written by
+/// hand and dumped programs can be read back. Indentation and empty lines are ignored, and the entry, config and
+/// counts can be left out to use their defaults. The names of variables and blocks are not part of the text, so
+/// parsed programs have none.
+impl FromStr for Program {
+    type Err = ParseError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut program = Program::new();
+        let mut section = Section::Program;
+        for (idx, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            parse_line(&mut program, &mut section, line).map_err(|message| ParseError {
+                line: idx + 1,
+                message,
+            })?;
+        }
+        Ok(program)
+    }
+}
+
+#[allow(clippy::too_many_lines)]
+fn parse_line(program: &mut Program, section: &mut Section, line: &str) -> Result<(), String> {
+    if let Some(rest) = line.strip_prefix("Callable ") {
+        let id = CallableId(parse_value(rest.strip_suffix(": Callable:").ok_or_else(
+            || format!("expected `Callable <id>: Callable:`, found `{line}`"),
+        )?)?);
+        if program.callables.contains_key(id) {
+            return Err(format!("callable {} is declared more than once", id.0));
+        }
+        program.callables.insert(
+            id,
+            Callable {
+                name: String::new(),
+                input_type: Vec::new(),
+                output_type: None,
+                body: None,
+                call_type: CallableType::Regular,
+            },
+        );
+        *section = Section::Callable(id);
+        return Ok(());
+    }
+    if let Some(rest) = line.strip_prefix("Block ") {
+        if let Some((id, contents)) = rest.split_once(": Block:") {
+            let id = BlockId(parse_value(id)?);
+            if program.blocks.contains_key(id) {
+                return Err(format!("block {} is declared more than once", id.0));
+            }
+            match contents.trim() {
+                "" | "<EMPTY>" => {}
+                _ => {
+                    return Err(format!(
+                        "unexpected `{}` after block header",
+                        contents.trim()
+                    ))
+                }
+            }
+            program.blocks.insert(id, Block::default());
+            *section = Section::Block(id);
+            return Ok(());
+        }
+    }
+
+    match section {
+        Section::Block(id) => {
+            if !is_program_field(line) {
+                let instruction = Cursor::new(line).instruction()?;
+                program.get_block_mut(*id).0.push(instruction);
+                return Ok(());
+            }
+        }
+        Section::InputType(id) => {
+            if let Some(rest) = line.strip_prefix('[') {
+                let (index, ty) = rest
+                    .split_once("]: ")
+                    .ok_or_else(|| format!("expected `[<index>]: <type>`, found `{line}`"))?;
+                let callable = program
+                    .callables
+                    .get_mut(*id)
+                    .expect("callable should be present");
+                if parse_value::<usize>(index)? != callable.input_type.len() {
+                    return Err(format!("expected input type {}", callable.input_type.len()));
+                }
+                callable.input_type.push(parse_ty(ty)?);
+                return Ok(());
+            }
+            *section = Section::Callable(*id);
+        }
+        Section::Tags => {
+            if let Some(rest) = line.strip_prefix('[') {
+                let (index, tag) = rest
+                    .split_once("]: ")
+                    .ok_or_else(|| format!("expected `[<index>]: <string>`, found `{line}`"))?;
+                if parse_value::<usize>(index)? != program.tags.len() {
+                    return Err(format!("expected tag {}", program.tags.len()));
+                }
+                program.tags.push(parse_string(tag)?);
+                return Ok(());
+            }
+        }
+        Section::InsertedTerminations => {
+            if let Some(rest) = line.strip_prefix('[') {
+                let (index, termination) = rest.split_once("]: ").ok_or_else(|| {
+                    format!("expected `[<index>]: <termination>`, found `{line}`")
+                })?;
+                if parse_value::<usize>(index)? != program.inserted_terminations.len() {
+                    return Err(format!(
+                        "expected inserted termination {}",
+                        program.inserted_terminations.len()
+                    ));
+                }
+                program
+                    .inserted_terminations
+                    .push(parse_inserted_termination(termination)?);
+                return Ok(());
+            }
+        }
+        Section::OutputPositions => {
+            if let Some(rest) = line.strip_prefix('[') {
+                let (index, position) = rest
+                    .split_once("]: ")
+                    .ok_or_else(|| format!("expected `[<index>]: <position>`, found `{line}`"))?;
+                if parse_value::<usize>(index)? != program.output_positions.len() {
+                    return Err(format!(
+                        "expected output position {}",
+                        program.output_positions.len()
+                    ));
+                }
+                program
+                    .output_positions
+                    .push(parse_output_position(position)?);
+                return Ok(());
+            }
+        }
+        Section::Program | Section::Callable(_) | Section::Config => {}
+    }
+
+    let (key, value) = match line.split_once(':') {
+        Some((key, value)) => (key, value.trim()),
+        None => return Err(format!("unexpected `{line}`")),
+    };
+    match (&*section, key) {
+        (_, "Program" | "callables" | "blocks") if value.is_empty() => {
+            *section = Section::Program;
+        }
+        (_, "entry") => program.entry = CallableId(parse_value(value)?),
+        (_, "config") if value == "Config:" => *section = Section::Config,
+        (_, "num_qubits") => program.num_qubits = parse_value(value)?,
+        (_, "num_results") => program.num_results = parse_value(value)?,
+        (_, "tags") if value.is_empty() => *section = Section::Tags,
+        (_, "inserted_terminations") if value.is_empty() => {
+            *section = Section::InsertedTerminations;
+        }
+        (_, "output_positions") if value.is_empty() => *section = Section::OutputPositions,
+        (Section::Callable(id), _) => {
+            let id = *id;
+            let callable = program
+                .callables
+                .get_mut(id)
+                .expect("callable should be present");
+            match key {
+                "name" => callable.name = value.to_string(),
+                "call_type" => callable.call_type = parse_callable_type(value)?,
+                "input_type" => match value {
+                    "<VOID>" => {}
+                    "" => *section = Section::InputType(id),
+                    _ => return Err(format!("expected input types on the lines after `{line}`")),
+                },
+                "output_type" => {
+                    callable.output_type = match value {
+                        "<VOID>" => None,
+                        _ => Some(parse_ty(value)?),
+                    };
+                }
+                "body" => {
+                    callable.body = match value {
+                        "<NONE>" => None,
+                        _ => Some(BlockId(parse_value(value)?)),
+                    };
+                }
+                _ => return Err(format!("unknown callable field `{key}`")),
+            }
+        }
+        (Section::Config, "capabilities") => {
+            program.config.capabilities = parse_capabilities(value)?;
+        }
+        (Section::Config, "naming") => {
+            program.config.naming = match value {
+                "Numeric" => NamingScheme::Numeric,
+                "Readable" => NamingScheme::Readable,
+                _ => return Err(format!("unknown naming scheme `{value}`")),
+            };
+        }
+        (Section::Config, "peephole") => program.config.peephole = parse_peephole(value)?,
+        (Section::Config, "gate_set") => program.config.gate_set = Some(value.parse()?),
+        (Section::Config, "qubit_termination") => {
+            program.config.qubit_termination = match value {
+                "None" => QubitTermination::None,
+                "Reset" => QubitTermination::Reset,
+                "Measure" => QubitTermination::Measure,
+                _ => return Err(format!("unknown qubit termination `{value}`")),
+            };
+        }
+        (Section::Config, "reuse_qubits") => {
+            program.config.reuse_qubits = parse_value(value)?;
+        }
+        (Section::Config, "emit_output_positions") => {
+            program.config.emit_output_positions = parse_value(value)?;
+        }
+        _ => return Err(format!("unexpected `{line}`")),
+    }
+    Ok(())
+}
+
+/// Parses a string written with its `Debug` implementation, which quotes it and escapes special characters.
+fn parse_string(text: &str) -> Result<Rc<str>, String> {
+    let inner = text
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+        .ok_or_else(|| format!("expected a quoted string, found `{text}`"))?;
+    let mut value = String::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+        value.push(match chars.next() {
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some('0') => '\0',
+            Some(c @ ('\\' | '"' | '\'')) => c,
+            Some('u') => {
+                let code = chars
+                    .as_str()
+                    .strip_prefix('{')
+                    .and_then(|rest| rest.split_once('}'))
+                    .map(|(code, _)| code)
+                    .ok_or_else(|| format!("invalid escape in `{text}`"))?;
+                chars.nth(code.len() + 1);
+                u32::from_str_radix(code, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| format!("invalid escape in `{text}`"))?
+            }
+            _ => return Err(format!("invalid escape in `{text}`")),
+        });
+    }
+    Ok(value.into())
+}
+
+/// Parses an inserted termination written with its `Display` implementation, such as `Qubit(0) reset` or
+/// `Qubit(1) measured into Result(2)`.
+fn parse_inserted_termination(text: &str) -> Result<InsertedTermination, String> {
+    let invalid = || format!("invalid inserted termination `{text}`");
+    let (qubit, rest) = text
+        .strip_prefix("Qubit(")
+        .and_then(|rest| rest.split_once(") "))
+        .ok_or_else(invalid)?;
+    let result = match rest {
+        "reset" => None,
+        _ => Some(parse_value(
+            rest.strip_prefix("measured into Result(")
+                .and_then(|rest| rest.strip_suffix(')'))
+                .ok_or_else(invalid)?,
+        )?),
+    };
+    Ok(InsertedTermination {
+        qubit: parse_value(qubit)?,
+        result,
+    })
+}
+
+/// Parses an output position written with its `Display` implementation, such as `tuple at []` or
+/// `result at [0, 1]`.
+fn parse_output_position(text: &str) -> Result<OutputPosition, String> {
+    let invalid = || format!("invalid output position `{text}`");
+    let (kind, path) = text
+        .split_once(" at [")
+        .and_then(|(kind, rest)| Some((kind, rest.strip_suffix(']')?)))
+        .ok_or_else(invalid)?;
+    let kind = match kind {
+        "tuple" => OutputKind::Tuple,
+        "array" => OutputKind::Array,
+        "result" => OutputKind::Result,
+        "bool" => OutputKind::Bool,
+        "int" => OutputKind::Int,
+        "double" => OutputKind::Double,
+        _ => return Err(format!("unknown output kind `{kind}`")),
+    };
+    let path = if path.is_empty() {
+        Vec::new()
+    } else {
+        path.split(',').map(parse_value).collect::<Result<_, _>>()?
+    };
+    Ok(OutputPosition { kind, path })
+}
+
+fn parse_value<T: FromStr>(text: &str) -> Result<T, String> {
+    text.trim()
+        .parse()
+        .map_err(|_| format!("invalid value `{}`", text.trim()))
+}
+
+/// Whether the line holds one of the fields of the program itself, which end the section before it.
+fn is_program_field(line: &str) -> bool {
+    [
+        "callables:",
+        "blocks:",
+        "config:",
+        "entry:",
+        "num_qubits:",
+        "num_results:",
+        "tags:",
+        "inserted_terminations:",
+        "output_positions:",
+    ]
+    .iter()
+    .any(|field| line.starts_with(field))
+}
+
+fn parse_ty(text: &str) -> Result<Ty, String> {
+    match text.trim() {
+        "Qubit" => Ok(Ty::Qubit),
+        "Result" => Ok(Ty::Result),
+        "Boolean" => Ok(Ty::Boolean),
+        "Integer" => Ok(Ty::Integer),
+        "Double" => Ok(Ty::Double),
+        "Pointer" => Ok(Ty::Pointer),
+        text => Err(format!("unknown type `{text}`")),
+    }
+}
+
+fn parse_callable_type(text: &str) -> Result<CallableType, String> {
+    match text {
+        "Measurement" => Ok(CallableType::Measurement),
+        "Reset" => Ok(CallableType::Reset),
+        "Readout" => Ok(CallableType::Readout),
+        "OutputRecording" => Ok(CallableType::OutputRecording),
+        "Regular" => Ok(CallableType::Regular),
+        _ => Err(format!("unknown callable type `{text}`")),
+    }
+}
+
+/// Parses capabilities written either as `Base` or in the debug format of the flags, as in
+/// `TargetCapabilityFlags(Adaptive | IntegerComputations)`.
+fn parse_capabilities(text: &str) -> Result<TargetCapabilityFlags, String> {
+    let names = match text
+        .strip_prefix("TargetCapabilityFlags(")
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        Some(names) => names,
+        None => text,
+    };
+    names.split('|').try_fold(
+        TargetCapabilityFlags::empty(),
+        |capabilities, name| match name.trim().parse::<TargetCapabilityFlags>() {
+            Ok(capability) => Ok(capabilities | capability),
+            Err(()) => Err(format!("unknown capability `{}`", name.trim())),
+        },
+    )
+}
+
+/// Parses a peephole configuration in its debug format, such as
+/// `PeepholeConfig { cancel_self_inverse: true, merge_rotations: false, remove_before_reset: true }`.
+fn parse_peephole(text: &str) -> Result<PeepholeConfig, String> {
+    let fields = text
+        .strip_prefix("PeepholeConfig {")
+        .and_then(|rest| rest.strip_suffix('}'))
+        .ok_or_else(|| format!("expected `PeepholeConfig {{ ... }}`, found `{text}`"))?;
+    let mut config = PeepholeConfig::default();
+    for field in fields.split(',').map(str::trim).filter(|f| !f.is_empty()) {
+        let (name, value) = field
+            .split_once(':')
+            .ok_or_else(|| format!("expected `<name>: <value>`, found `{field}`"))?;
+        let value = match value.trim() {
+            "true" => true,
+            "false" => false,
+            value => return Err(format!("expected `true` or `false`, found `{value}`")),
+        };
+        match name.trim() {
+            "cancel_self_inverse" => config.cancel_self_inverse = value,
+            "merge_rotations" => config.merge_rotations = value,
+            "remove_before_reset" => config.remove_before_reset = value,
+            name => return Err(format!("unknown peephole optimization `{name}`")),
+        }
+    }
+    Ok(config)
+}
+
+/// A position in a line of text holding an instruction.
+struct Cursor<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(text: &'a str) -> Self {
+        Self { rest: text }
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        match self.rest.trim_start().strip_prefix(token) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), String> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(format!(
+                "expected `{token}`, found `{}`",
+                self.rest.trim_start()
+            ))
+        }
+    }
+
+    /// Takes the text up to the next delimiter.
+    fn word(&mut self) -> &'a str {
+        let rest = self.rest.trim_start();
+        let end = rest
+            .find(['(', ')', ',', ' ', '[', ']'])
+            .unwrap_or(rest.len());
+        self.rest = &rest[end..];
+        &rest[..end]
+    }
+
+    fn value<T: FromStr>(&mut self) -> Result<T, String> {
+        parse_value(self.word())
+    }
+
+    fn end(&self) -> Result<(), String> {
+        if self.rest.trim().is_empty() {
+            Ok(())
+        } else {
+            Err(format!("unexpected `{}`", self.rest.trim()))
+        }
+    }
+
+    fn variable(&mut self) -> Result<Variable, String> {
+        self.expect("Variable(")?;
+        let variable_id = VariableId(self.value()?);
+        self.expect(",")?;
+        let ty = parse_ty(self.word())?;
+        self.expect(")")?;
+        Ok(Variable { variable_id, ty })
+    }
+
+    fn operand(&mut self) -> Result<Operand, String> {
+        if self.rest.trim_start().starts_with("Variable(") {
+            return Ok(Operand::Variable(self.variable()?));
+        }
+        let kind = self.word();
+        if kind == "Pointer" {
+            return Ok(Operand::Literal(Literal::Pointer));
+        }
+        self.expect("(")?;
+        let literal = match kind {
+            "Tag" => Literal::Tag(self.value()?),
+            "Qubit" => Literal::Qubit(self.value()?),
+            "Result" => Literal::Result(self.value()?),
+            "Bool" => Literal::Bool(self.value()?),
+            "Integer" => Literal::Integer(self.value()?),
+            "Double" => Literal::Double(self.value()?),
+            _ => return Err(format!("expected an operand, found `{kind}`")),
+        };
+        self.expect(")")?;
+        Ok(Operand::Literal(literal))
+    }
+
+    fn binary_operands(&mut self) -> Result<(Operand, Operand), String> {
+        let lhs = self.operand()?;
+        self.expect(",")?;
+        let rhs = self.operand()?;
+        Ok((lhs, rhs))
+    }
+
+    fn call(&mut self, variable: Option<Variable>) -> Result<Instruction, String> {
+        self.expect("id(")?;
+        let callable_id = CallableId(self.value()?);
+        self.expect(")")?;
+        self.expect(",")?;
+        self.expect("args(")?;
+        let mut args = Vec::new();
+        while !self.eat(")") {
+            args.push(self.operand()?);
+            self.expect(",")?;
+        }
+        Ok(Instruction::Call(callable_id, args, variable))
+    }
+
+    fn instruction(&mut self) -> Result<Instruction, String> {
+        let instruction = if self.eat("Return") {
+            Instruction::Return
+        } else if self.eat("Jump(") {
+            let block_id = BlockId(self.value()?);
+            self.expect(")")?;
+            Instruction::Jump(block_id)
+        } else if self.eat("Branch") {
+            let condition = self.variable()?;
+            self.expect(",")?;
+            let if_true = BlockId(self.value()?);
+            self.expect(",")?;
+            let if_false = BlockId(self.value()?);
+            Instruction::Branch(condition, if_true, if_false)
+        } else if self.eat("Call") {
+            self.call(None)?
+        } else {
+            let variable = self.variable()?;
+            self.expect("=")?;
+            self.assignment(variable)?
+        };
+        self.end()?;
+        Ok(instruction)
+    }
+
+    fn assignment(&mut self, variable: Variable) -> Result<Instruction, String> {
+        let binary: fn(Operand, Operand, Variable) -> Instruction = match self.word() {
+            "Call" => return self.call(Some(variable)),
+            "Store" => return Ok(Instruction::Store(self.operand()?, variable)),
+            "LogicalNot" => return Ok(Instruction::LogicalNot(self.operand()?, variable)),
+            "BitwiseNot" => return Ok(Instruction::BitwiseNot(self.operand()?, variable)),
+            "Sitofp" => return Ok(Instruction::Sitofp(self.operand()?, variable)),
+            "Fptosi" => return Ok(Instruction::Fptosi(self.operand()?, variable)),
+            "Icmp" => {
+                let condition = parse_condition_code(self.word())?;
+                self.expect(",")?;
+                let (lhs, rhs) = self.binary_operands()?;
+                return Ok(Instruction::Icmp(condition, lhs, rhs, variable));
+            }
+            "Fcmp" => {
+                let condition = parse_fcmp_condition_code(self.word())?;
+                self.expect(",")?;
+                let (lhs, rhs) = self.binary_operands()?;
+                return Ok(Instruction::Fcmp(condition, lhs, rhs, variable));
+            }
+            "Phi" => {
+                self.expect("(")?;
+                let mut args = Vec::new();
+                while !self.eat(")") {
+                    self.expect("[")?;
+                    let value = self.operand()?;
+                    self.expect(",")?;
+                    let block_id = BlockId(self.value()?);
+                    self.expect("]")?;
+                    self.expect(",")?;
+                    args.push((value, block_id));
+                }
+                return Ok(Instruction::Phi(args, variable));
+            }
+            "Add" => Instruction::Add,
+            "Sub" => Instruction::Sub,
+            "Mul" => Instruction::Mul,
+            "Sdiv" => Instruction::Sdiv,
+            "Srem" => Instruction::Srem,
+            "Shl" => Instruction::Shl,
+            "Ashr" => Instruction::Ashr,
+            "Fadd" => Instruction::Fadd,
+            "Fsub" => Instruction::Fsub,
+            "Fmul" => Instruction::Fmul,
+            "Fdiv" => Instruction::Fdiv,
+            "LogicalAnd" => Instruction::LogicalAnd,
+            "LogicalOr" => Instruction::LogicalOr,
+            "BitwiseAnd" => Instruction::BitwiseAnd,
+            "BitwiseOr" => Instruction::BitwiseOr,
+            "BitwiseXor" => Instruction::BitwiseXor,
+            word => return Err(format!("unknown instruction `{word}`")),
+        };
+        let (lhs, rhs) = self.binary_operands()?;
+        Ok(binary(lhs, rhs, variable))
+    }
+}
+
+fn parse_condition_code(text: &str) -> Result<ConditionCode, String> {
+    match text {
+        "Eq" => Ok(ConditionCode::Eq),
+        "Ne" => Ok(ConditionCode::Ne),
+        "Slt" => Ok(ConditionCode::Slt),
+        "Sle" => Ok(ConditionCode::Sle),
+        "Sgt" => Ok(ConditionCode::Sgt),
+        "Sge" => Ok(ConditionCode::Sge),
+        _ => Err(format!("unknown condition code `{text}`")),
+    }
+}
+
+fn parse_fcmp_condition_code(text: &str) -> Result<FcmpConditionCode, String> {
+    match text {
+        "False" => Ok(FcmpConditionCode::False),
+        "Oeq" => Ok(FcmpConditionCode::OrderedAndEqual),
+        "Ogt" => Ok(FcmpConditionCode::OrderedAndGreaterThan),
+        "Oge" => Ok(FcmpConditionCode::OrderedAndGreaterThanOrEqual),
+        "Olt" => Ok(FcmpConditionCode::OrderedAndLessThan),
+        "Ole" => Ok(FcmpConditionCode::OrderedAndLessThanOrEqual),
+        "One" => Ok(FcmpConditionCode::OrderedAndNotEqual),
+        "Ord" => Ok(FcmpConditionCode::Ordered),
+        "Ueq" => Ok(FcmpConditionCode::UnorderedOrEqual),
+        "Ugt" => Ok(FcmpConditionCode::UnorderedOrGreaterThan),
+        "Uge" => Ok(FcmpConditionCode::UnorderedOrGreaterThanOrEqual),
+        "Ult" => Ok(FcmpConditionCode::UnorderedOrLessThan),
+        "Ule" => Ok(FcmpConditionCode::UnorderedOrLessThanOrEqual),
+        "Une" => Ok(FcmpConditionCode::UnorderedOrNotEqual),
+        "Uno" => Ok(FcmpConditionCode::Unordered),
+        "True" => Ok(FcmpConditionCode::True),
+        _ => Err(format!("unknown condition code `{text}`")),
+    }
+}
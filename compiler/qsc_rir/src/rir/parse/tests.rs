@@ -0,0 +1,415 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#![allow(clippy::too_many_lines, clippy::needless_raw_string_hashes)]
+
+use expect_test::expect;
+use qsc_data_structures::target::TargetCapabilityFlags;
+
+use crate::{
+    builder::{bool_record_decl, h_decl, m_decl, read_result_decl, rx_decl},
+    rir::{
+        Block, BlockId, Callable, CallableId, CallableType, ConditionCode, FcmpConditionCode,
+        GateSet, Instruction, Literal, NamingScheme, Operand, PeepholeConfig, Program, Ty,
+        Variable, VariableId,
+    },
+};
+
+fn variable(id: u32, ty: Ty) -> Variable {
+    Variable {
+        variable_id: VariableId(id),
+        ty,
+    }
+}
+
+fn check_round_trip(program: &Program) {
+    let text = program.to_string();
+    let parsed = text
+        .parse::<Program>()
+        .unwrap_or_else(|error| panic!("program should parse: {error}\n{text}"));
+    assert_eq!(parsed.to_string(), text);
+}
+
+#[test]
+fn program_with_every_instruction_round_trips() {
+    let mut program = Program::new();
+    program.callables.insert(
+        CallableId(0),
+        Callable {
+            name: "main".to_string(),
+            input_type: Vec::new(),
+            output_type: None,
+            body: Some(BlockId(0)),
+            call_type: CallableType::Regular,
+        },
+    );
+    program.callables.insert(CallableId(1), h_decl());
+    program.callables.insert(CallableId(2), m_decl());
+    program.callables.insert(CallableId(3), read_result_decl());
+    program.callables.insert(CallableId(4), rx_decl());
+    program.callables.insert(CallableId(5), bool_record_decl());
+
+    let int = |id| variable(id, Ty::Integer);
+    let double = |id| variable(id, Ty::Double);
+    let boolean = |id| variable(id, Ty::Boolean);
+    let lit_int = |value| Operand::Literal(Literal::Integer(value));
+    let lit_double = |value| Operand::Literal(Literal::Double(value));
+    program.blocks.insert(
+        BlockId(0),
+        Block(vec![
+            Instruction::Call(
+                CallableId(1),
+                vec![Operand::Literal(Literal::Qubit(0))],
+                None,
+            ),
+            Instruction::Call(
+                CallableId(2),
+                vec![
+                    Operand::Literal(Literal::Qubit(0)),
+                    Operand::Literal(Literal::Result(0)),
+                ],
+                None,
+            ),
+            Instruction::Call(
+                CallableId(3),
+                vec![Operand::Literal(Literal::Result(0))],
+                Some(boolean(0)),
+            ),
+            Instruction::Store(lit_int(-3), int(1)),
+            Instruction::Add(Operand::Variable(int(1)), lit_int(2), int(2)),
+            Instruction::Sub(Operand::Variable(int(2)), lit_int(2), int(3)),
+            Instruction::Mul(Operand::Variable(int(3)), lit_int(2), int(4)),
+            Instruction::Sdiv(Operand::Variable(int(4)), lit_int(2), int(5)),
+            Instruction::Srem(Operand::Variable(int(5)), lit_int(2), int(6)),
+            Instruction::Shl(Operand::Variable(int(6)), lit_int(2), int(7)),
+            Instruction::Ashr(Operand::Variable(int(7)), lit_int(2), int(8)),
+            Instruction::BitwiseAnd(Operand::Variable(int(8)), lit_int(1), int(9)),
+            Instruction::BitwiseOr(Operand::Variable(int(9)), lit_int(1), int(10)),
+            Instruction::BitwiseXor(Operand::Variable(int(10)), lit_int(1), int(11)),
+            Instruction::BitwiseNot(Operand::Variable(int(11)), int(12)),
+            Instruction::Icmp(
+                ConditionCode::Sge,
+                Operand::Variable(int(12)),
+                lit_int(0),
+                boolean(13),
+            ),
+            Instruction::LogicalNot(Operand::Variable(boolean(13)), boolean(14)),
+            Instruction::LogicalAnd(
+                Operand::Variable(boolean(0)),
+                Operand::Literal(Literal::Bool(true)),
+                boolean(15),
+            ),
+            Instruction::LogicalOr(
+                Operand::Variable(boolean(15)),
+                Operand::Variable(boolean(14)),
+                boolean(16),
+            ),
+            Instruction::Branch(boolean(16), BlockId(1), BlockId(2)),
+        ]),
+    );
+    program.blocks.insert(
+        BlockId(1),
+        Block(vec![
+            Instruction::Fadd(lit_double(0.1), lit_double(-2.5e-7), double(17)),
+            Instruction::Fsub(Operand::Variable(double(17)), lit_double(1.0), double(18)),
+            Instruction::Fmul(Operand::Variable(double(18)), lit_double(3.0), double(19)),
+            Instruction::Fdiv(
+                Operand::Variable(double(19)),
+                lit_double(std::f64::consts::PI),
+                double(20),
+            ),
+            Instruction::Call(
+                CallableId(4),
+                vec![
+                    Operand::Variable(double(20)),
+                    Operand::Literal(Literal::Qubit(0)),
+                ],
+                None,
+            ),
+            Instruction::Jump(BlockId(2)),
+        ]),
+    );
+    program.blocks.insert(
+        BlockId(2),
+        Block(vec![
+            Instruction::Phi(
+                vec![
+                    (Operand::Literal(Literal::Bool(false)), BlockId(0)),
+                    (Operand::Variable(boolean(0)), BlockId(1)),
+                ],
+                boolean(21),
+            ),
+            Instruction::Fcmp(
+                FcmpConditionCode::UnorderedOrLessThanOrEqual,
+                lit_double(f64::NAN),
+                lit_double(f64::INFINITY),
+                boolean(22),
+            ),
+            Instruction::Call(
+                CallableId(5),
+                vec![
+                    Operand::Variable(boolean(21)),
+                    Operand::Literal(Literal::Pointer),
+                ],
+                None,
+            ),
+            Instruction::Return,
+        ]),
+    );
+    program.blocks.insert(BlockId(3), Block::default());
+    program.config.capabilities =
+        TargetCapabilityFlags::Adaptive | TargetCapabilityFlags::FloatingPointComputations;
+    program.config.naming = NamingScheme::Readable;
+    program.config.peephole = PeepholeConfig {
+        merge_rotations: false,
+        ..PeepholeConfig::ALL
+    };
+    program.config.gate_set = Some(GateSet::RZ | GateSet::SX | GateSet::CZ);
+    program.num_qubits = 1;
+    program.num_results = 1;
+
+    check_round_trip(&program);
+}
+
+#[test]
+fn program_with_tags_round_trips() {
+    let mut program = Program::new();
+    program.callables.insert(
+        CallableId(0),
+        Callable {
+            name: "main".to_string(),
+            input_type: Vec::new(),
+            output_type: None,
+            body: Some(BlockId(0)),
+            call_type: CallableType::Regular,
+        },
+    );
+    program.callables.insert(CallableId(1), bool_record_decl());
+    let plain = program.add_tag("result");
+    let escaped = program.add_tag("say \"hi\"\n\t\\ \u{7f} é");
+    program.blocks.insert(
+        BlockId(0),
+        Block(vec![
+            Instruction::Call(
+                CallableId(1),
+                vec![
+                    Operand::Literal(Literal::Bool(true)),
+                    Operand::Literal(plain),
+                ],
+                None,
+            ),
+            Instruction::Call(
+                CallableId(1),
+                vec![
+                    Operand::Literal(Literal::Bool(false)),
+                    Operand::Literal(escaped),
+                ],
+                None,
+            ),
+            Instruction::Return,
+        ]),
+    );
+
+    check_round_trip(&program);
+    let parsed = program
+        .to_string()
+        .parse::<Program>()
+        .expect("program should parse");
+    assert_eq!(parsed.tags, program.tags);
+}
+
+#[test]
+fn default_program_round_trips() {
+    check_round_trip(&Program::new());
+}
+
+#[test]
+fn program_can_be_written_by_hand() {
+    let program = "
+        callables:
+          Callable 0: Callable:
+            name: main
+            call_type: Regular
+            input_type: <VOID>
+            output_type: <VOID>
+            body: 0
+          Callable 1: Callable:
+            name: __quantum__qis__h__body
+            call_type: Regular
+            input_type:
+              [0]: Qubit
+            output_type: <VOID>
+            body: <NONE>
+
+        blocks:
+          Block 0: Block:
+            Call id(1), args( Qubit(0), )
+            Call id(1), args(Qubit(1),)
+            Return
+        num_qubits: 2
+    "
+    .parse::<Program>()
+    .expect("program should parse");
+
+    expect![[r#"
+        Program:
+            entry: 0
+            callables:
+                Callable 0: Callable:
+                    name: main
+                    call_type: Regular
+                    input_type: <VOID>
+                    output_type: <VOID>
+                    body: 0
+                Callable 1: Callable:
+                    name: __quantum__qis__h__body
+                    call_type: Regular
+                    input_type:
+                        [0]: Qubit
+                    output_type: <VOID>
+                    body: <NONE>
+            blocks:
+                Block 0: Block:
+                    Call id(1), args( Qubit(0), )
+                    Call id(1), args( Qubit(1), )
+                    Return
+            config: Config:
+                capabilities: Base
+            num_qubits: 2
+            num_results: 0"#]]
+    .assert_eq(&program.to_string());
+}
+
+#[test]
+fn capabilities_can_be_written_by_name() {
+    let program = "
+        config: Config:
+            capabilities: Adaptive | QubitReset
+    "
+    .parse::<Program>()
+    .expect("program should parse");
+    assert_eq!(
+        program.config.capabilities,
+        TargetCapabilityFlags::Adaptive | TargetCapabilityFlags::QubitReset
+    );
+}
+
+#[test]
+fn unknown_instruction_is_error() {
+    let error = "
+        blocks:
+            Block 0: Block:
+                Return
+                Variable(0, Integer) = Pow Integer(2), Integer(3)
+    "
+    .parse::<Program>()
+    .err()
+    .expect("program should not parse");
+    expect!["invalid RIR on line 5: unknown instruction `Pow`"].assert_eq(&error.to_string());
+}
+
+#[test]
+fn invalid_operand_is_error() {
+    let error = "
+        blocks:
+            Block 0: Block:
+                Call id(1), args( Qubit(a), )
+    "
+    .parse::<Program>()
+    .err()
+    .expect("program should not parse");
+    expect!["invalid RIR on line 4: invalid value `a`"].assert_eq(&error.to_string());
+}
+
+#[test]
+fn trailing_text_after_instruction_is_error() {
+    let error = "
+        blocks:
+            Block 0: Block:
+                Jump(1) Return
+    "
+    .parse::<Program>()
+    .err()
+    .expect("program should not parse");
+    expect!["invalid RIR on line 4: unexpected `Return`"].assert_eq(&error.to_string());
+}
+
+#[test]
+fn duplicate_block_is_error() {
+    let error = "
+        blocks:
+            Block 0: Block: <EMPTY>
+            Block 0: Block: <EMPTY>
+    "
+    .parse::<Program>()
+    .err()
+    .expect("program should not parse");
+    expect!["invalid RIR on line 4: block 0 is declared more than once"]
+        .assert_eq(&error.to_string());
+}
+
+#[test]
+fn invalid_inserted_termination_is_error() {
+    let error = "
+        inserted_terminations:
+            [0]: Qubit(0) measured
+    "
+    .parse::<Program>()
+    .err()
+    .expect("program should not parse");
+    expect!["invalid RIR on line 3: invalid inserted termination `Qubit(0) measured`"]
+        .assert_eq(&error.to_string());
+}
+
+#[test]
+fn output_positions_round_trip() {
+    let text = "
+        output_positions:
+            [0]: tuple at []
+            [1]: array at [0]
+            [2]: result at [0, 1]
+            [3]: double at [1]
+    ";
+    let program = text.parse::<Program>().expect("program should parse");
+    check_round_trip(&program);
+    expect![[r#"
+        [
+            OutputPosition {
+                kind: Tuple,
+                path: [],
+            },
+            OutputPosition {
+                kind: Array,
+                path: [
+                    0,
+                ],
+            },
+            OutputPosition {
+                kind: Result,
+                path: [
+                    0,
+                    1,
+                ],
+            },
+            OutputPosition {
+                kind: Double,
+                path: [
+                    1,
+                ],
+            },
+        ]
+    "#]]
+    .assert_debug_eq(&program.output_positions);
+}
+
+#[test]
+fn invalid_output_position_is_error() {
+    let error = "
+        output_positions:
+            [0]: qubit at [0]
+    "
+    .parse::<Program>()
+    .err()
+    .expect("program should not parse");
+    expect!["invalid RIR on line 3: unknown output kind `qubit`"].assert_eq(&error.to_string());
+}
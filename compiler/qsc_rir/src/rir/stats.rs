@@ -0,0 +1,240 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use super::{BlockId, CallableId, CallableType, Instruction, Literal, Operand, Program, Ty};
+use crate::utils::{build_predecessors_map, get_block_successors};
+use qsc_data_structures::fx::FxHashMap;
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display, Formatter},
+};
+
+const QIS_PREFIX: &str = "__quantum__qis__";
+
+/// Statistics about the instructions of a program. Counts are static: every instruction is counted once,
+/// regardless of how many times it runs or whether the branch it is on is taken.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProgramStats {
+    /// The number of calls to each gate, including resets, by gate name. Gate names are the names of the
+    /// callables without the QIS prefix and the `__body` suffix, for example `h` or `cx`.
+    pub gate_counts: BTreeMap<String, usize>,
+    /// The number of calls to gates that act on exactly two qubits.
+    pub two_qubit_gate_count: usize,
+    /// The number of measurements.
+    pub measurement_count: usize,
+    /// The number of classical instructions, which are the instructions other than quantum operations,
+    /// output recording and control flow.
+    pub classical_instruction_count: usize,
+    /// The estimated depth of the circuit, which is the largest number of quantum operations that act on
+    /// any qubit one after the other when each operation is scheduled as early as possible. Of the
+    /// branches of a conditional, the deepest is assumed to be taken, and loops are assumed to run once.
+    pub depth: usize,
+    /// The largest number of branches that run one after the other on any path through the program,
+    /// where loops are assumed to run once. Branch conditions are computed at runtime from measurement
+    /// results, so each of these branches waits for classical feedback before the program can go on.
+    pub feedback_depth: usize,
+}
+
+impl Display for ProgramStats {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "gates:")?;
+        if self.gate_counts.is_empty() {
+            write!(f, " <NONE>")?;
+        }
+        for (gate, count) in &self.gate_counts {
+            write!(f, "\n    {gate}: {count}")?;
+        }
+        write!(f, "\ntwo-qubit gates: {}", self.two_qubit_gate_count)?;
+        write!(f, "\nmeasurements: {}", self.measurement_count)?;
+        write!(
+            f,
+            "\nclassical instructions: {}",
+            self.classical_instruction_count
+        )?;
+        write!(f, "\ndepth: {}", self.depth)?;
+        write!(f, "\nfeedback depth: {}", self.feedback_depth)?;
+        Ok(())
+    }
+}
+
+/// The kind of an instruction, for the purpose of computing statistics.
+enum InstructionKind<'a> {
+    Gate { name: &'a str, qubits: Vec<u32> },
+    Measurement { qubits: Vec<u32> },
+    Classical,
+    Other,
+}
+
+impl Program {
+    /// Computes statistics about the instructions of the program.
+    #[must_use]
+    pub fn stats(&self) -> ProgramStats {
+        let mut stats = ProgramStats::default();
+        for (_, block) in self.blocks.iter() {
+            for instr in &block.0 {
+                match self.instruction_kind(instr) {
+                    InstructionKind::Gate { name, qubits } => {
+                        *stats.gate_counts.entry(name.to_string()).or_default() += 1;
+                        if qubits.len() == 2 {
+                            stats.two_qubit_gate_count += 1;
+                        }
+                    }
+                    InstructionKind::Measurement { .. } => stats.measurement_count += 1,
+                    InstructionKind::Classical => stats.classical_instruction_count += 1,
+                    InstructionKind::Other => {}
+                }
+            }
+        }
+        stats.depth = self.depth();
+        stats.feedback_depth = self.feedback_depth();
+        stats
+    }
+
+    fn instruction_kind<'a>(&'a self, instr: &Instruction) -> InstructionKind<'a> {
+        match instr {
+            Instruction::Call(callable_id, args, _) => self.call_kind(*callable_id, args),
+            Instruction::Jump(..) | Instruction::Branch(..) | Instruction::Return => {
+                InstructionKind::Other
+            }
+            _ => InstructionKind::Classical,
+        }
+    }
+
+    fn call_kind<'a>(&'a self, callable_id: CallableId, args: &[Operand]) -> InstructionKind<'a> {
+        let callable = self.get_callable(callable_id);
+        let qubits = args
+            .iter()
+            .filter_map(|arg| match arg {
+                Operand::Literal(Literal::Qubit(q)) => Some(*q),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        match callable.call_type {
+            CallableType::Measurement => InstructionKind::Measurement { qubits },
+            CallableType::Reset => InstructionKind::Gate {
+                name: gate_name(&callable.name),
+                qubits,
+            },
+            CallableType::Regular if callable.input_type.contains(&Ty::Qubit) => {
+                InstructionKind::Gate {
+                    name: gate_name(&callable.name),
+                    qubits,
+                }
+            }
+            CallableType::Regular | CallableType::Readout => InstructionKind::Classical,
+            CallableType::OutputRecording => InstructionKind::Other,
+        }
+    }
+
+    /// Estimates the depth of the circuit by scheduling the quantum operations of each block as early as
+    /// possible after those of its predecessors.
+    fn depth(&self) -> usize {
+        let Some(entry_block) = self
+            .callables
+            .get(self.entry)
+            .and_then(|callable| callable.body)
+        else {
+            return 0;
+        };
+        let preds = build_predecessors_map(self);
+        let mut qubit_depths: FxHashMap<BlockId, FxHashMap<u32, usize>> = FxHashMap::default();
+        let mut depth = 0;
+        for block_id in self.reverse_postorder(entry_block) {
+            // The predecessors through back edges are not processed yet, so they are ignored.
+            let mut depths: FxHashMap<u32, usize> = FxHashMap::default();
+            for pred in preds.get(block_id).into_iter().flatten() {
+                for (qubit, pred_depth) in qubit_depths.get(pred).into_iter().flatten() {
+                    let entry = depths.entry(*qubit).or_default();
+                    *entry = (*entry).max(*pred_depth);
+                }
+            }
+            for instr in &self.get_block(block_id).0 {
+                let (InstructionKind::Gate { qubits, .. }
+                | InstructionKind::Measurement { qubits }) = self.instruction_kind(instr)
+                else {
+                    continue;
+                };
+                let layer = qubits
+                    .iter()
+                    .map(|qubit| depths.get(qubit).copied().unwrap_or_default())
+                    .max()
+                    .unwrap_or_default()
+                    + 1;
+                for qubit in qubits {
+                    depths.insert(qubit, layer);
+                }
+                depth = depth.max(layer);
+            }
+            qubit_depths.insert(block_id, depths);
+        }
+        depth
+    }
+
+    /// Counts the largest number of branches on any path through the program by visiting the blocks
+    /// after their predecessors, so that loops are counted once.
+    fn feedback_depth(&self) -> usize {
+        let Some(entry_block) = self
+            .callables
+            .get(self.entry)
+            .and_then(|callable| callable.body)
+        else {
+            return 0;
+        };
+        let preds = build_predecessors_map(self);
+        // The number of branches on the deepest path up to and including the end of each block.
+        let mut branch_counts: FxHashMap<BlockId, usize> = FxHashMap::default();
+        let mut feedback_depth = 0;
+        for block_id in self.reverse_postorder(entry_block) {
+            // The predecessors through back edges are not processed yet, so they are ignored.
+            let count = preds
+                .get(block_id)
+                .into_iter()
+                .flatten()
+                .filter_map(|pred| branch_counts.get(pred).copied())
+                .max()
+                .unwrap_or_default()
+                + usize::from(matches!(
+                    self.get_block(block_id).0.last(),
+                    Some(Instruction::Branch(..))
+                ));
+            branch_counts.insert(block_id, count);
+            feedback_depth = feedback_depth.max(count);
+        }
+        feedback_depth
+    }
+
+    /// Gets the blocks reachable from the given block in reverse postorder, so that each block comes
+    /// after its predecessors other than those reaching it through a back edge.
+    fn reverse_postorder(&self, entry_block: BlockId) -> Vec<BlockId> {
+        let mut order = Vec::new();
+        let mut visited = vec![entry_block];
+        let mut stack = vec![(
+            entry_block,
+            get_block_successors(self.get_block(entry_block)),
+        )];
+        while let Some((block_id, successors)) = stack.last_mut() {
+            if let Some(successor) = successors.pop() {
+                if !visited.contains(&successor) {
+                    visited.push(successor);
+                    let successors = get_block_successors(self.get_block(successor));
+                    stack.push((successor, successors));
+                }
+            } else {
+                order.push(*block_id);
+                stack.pop();
+            }
+        }
+        order.reverse();
+        order
+    }
+}
+
+fn gate_name(callable_name: &str) -> &str {
+    let name = callable_name
+        .strip_prefix(QIS_PREFIX)
+        .unwrap_or(callable_name);
+    name.strip_suffix("__body").unwrap_or(name)
+}
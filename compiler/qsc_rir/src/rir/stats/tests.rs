@@ -0,0 +1,207 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use expect_test::expect;
+
+use crate::rir::Program;
+
+const CALLABLES: &str = "
+    callables:
+        Callable 0: Callable:
+            name: main
+            call_type: Regular
+            input_type: <VOID>
+            output_type: <VOID>
+            body: 0
+        Callable 1: Callable:
+            name: __quantum__qis__h__body
+            call_type: Regular
+            input_type:
+                [0]: Qubit
+            output_type: <VOID>
+            body: <NONE>
+        Callable 2: Callable:
+            name: __quantum__qis__cx__body
+            call_type: Regular
+            input_type:
+                [0]: Qubit
+                [1]: Qubit
+            output_type: <VOID>
+            body: <NONE>
+        Callable 3: Callable:
+            name: __quantum__qis__mresetz__body
+            call_type: Measurement
+            input_type:
+                [0]: Qubit
+                [1]: Result
+            output_type: <VOID>
+            body: <NONE>
+        Callable 4: Callable:
+            name: __quantum__qis__read_result__body
+            call_type: Readout
+            input_type:
+                [0]: Result
+            output_type: Boolean
+            body: <NONE>
+        Callable 5: Callable:
+            name: __quantum__qis__reset__body
+            call_type: Reset
+            input_type:
+                [0]: Qubit
+            output_type: <VOID>
+            body: <NONE>
+        Callable 6: Callable:
+            name: __quantum__rt__result_record_output
+            call_type: OutputRecording
+            input_type:
+                [0]: Result
+                [1]: Pointer
+            output_type: <VOID>
+            body: <NONE>
+";
+
+fn program(blocks: &str) -> Program {
+    format!("{CALLABLES}\n    blocks:{blocks}")
+        .parse()
+        .expect("program should parse")
+}
+
+#[test]
+fn straight_line_program_stats() {
+    let program = program(
+        "
+        Block 0: Block:
+            Call id(1), args( Qubit(0), )
+            Call id(2), args( Qubit(0), Qubit(1), )
+            Call id(1), args( Qubit(2), )
+            Call id(2), args( Qubit(1), Qubit(2), )
+            Call id(3), args( Qubit(0), Result(0), )
+            Call id(6), args( Result(0), Pointer, )
+            Return
+    ",
+    );
+    expect![[r#"
+        gates:
+            cx: 2
+            h: 2
+        two-qubit gates: 2
+        measurements: 1
+        classical instructions: 0
+        depth: 3
+        feedback depth: 0"#]]
+    .assert_eq(&program.stats().to_string());
+}
+
+#[test]
+fn branching_program_stats_use_deepest_branch() {
+    let program = program(
+        "
+        Block 0: Block:
+            Call id(1), args( Qubit(0), )
+            Call id(3), args( Qubit(0), Result(0), )
+            Variable(0, Boolean) = Call id(4), args( Result(0), )
+            Variable(1, Boolean) = LogicalNot Variable(0, Boolean)
+            Branch Variable(1, Boolean), 1, 2
+        Block 1: Block:
+            Call id(1), args( Qubit(1), )
+            Call id(1), args( Qubit(1), )
+            Call id(1), args( Qubit(1), )
+            Jump(2)
+        Block 2: Block:
+            Call id(2), args( Qubit(0), Qubit(1), )
+            Call id(5), args( Qubit(0), )
+            Return
+    ",
+    );
+    expect![[r#"
+        gates:
+            cx: 1
+            h: 4
+            reset: 1
+        two-qubit gates: 1
+        measurements: 1
+        classical instructions: 2
+        depth: 5
+        feedback depth: 1"#]]
+    .assert_eq(&program.stats().to_string());
+}
+
+#[test]
+fn loop_is_counted_once_for_depth() {
+    let program = program(
+        "
+        Block 0: Block:
+            Jump(1)
+        Block 1: Block:
+            Call id(1), args( Qubit(0), )
+            Call id(3), args( Qubit(0), Result(0), )
+            Variable(0, Boolean) = Call id(4), args( Result(0), )
+            Branch Variable(0, Boolean), 1, 2
+        Block 2: Block:
+            Return
+    ",
+    );
+    let stats = program.stats();
+    assert_eq!(stats.depth, 2);
+    assert_eq!(stats.measurement_count, 1);
+    assert_eq!(stats.classical_instruction_count, 1);
+    assert_eq!(stats.feedback_depth, 1);
+}
+
+#[test]
+fn sequential_branches_add_to_feedback_depth() {
+    let program = program(
+        "
+        Block 0: Block:
+            Call id(3), args( Qubit(0), Result(0), )
+            Variable(0, Boolean) = Call id(4), args( Result(0), )
+            Branch Variable(0, Boolean), 1, 2
+        Block 1: Block:
+            Call id(1), args( Qubit(1), )
+            Jump(2)
+        Block 2: Block:
+            Call id(3), args( Qubit(1), Result(1), )
+            Variable(1, Boolean) = Call id(4), args( Result(1), )
+            Branch Variable(1, Boolean), 3, 4
+        Block 3: Block:
+            Call id(1), args( Qubit(2), )
+            Jump(4)
+        Block 4: Block:
+            Return
+    ",
+    );
+    assert_eq!(program.stats().feedback_depth, 2);
+}
+
+#[test]
+fn parallel_branches_count_once_for_feedback_depth() {
+    let program = program(
+        "
+        Block 0: Block:
+            Call id(3), args( Qubit(0), Result(0), )
+            Variable(0, Boolean) = Call id(4), args( Result(0), )
+            Branch Variable(0, Boolean), 1, 2
+        Block 1: Block:
+            Call id(1), args( Qubit(1), )
+            Jump(3)
+        Block 2: Block:
+            Call id(1), args( Qubit(2), )
+            Jump(3)
+        Block 3: Block:
+            Return
+    ",
+    );
+    assert_eq!(program.stats().feedback_depth, 1);
+}
+
+#[test]
+fn empty_program_stats() {
+    expect![[r#"
+        gates: <NONE>
+        two-qubit gates: 0
+        measurements: 0
+        classical instructions: 0
+        depth: 0
+        feedback depth: 0"#]]
+    .assert_eq(&Program::new().stats().to_string());
+}
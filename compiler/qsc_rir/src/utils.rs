@@ -1,9 +1,9 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
-use crate::rir::{Block, BlockId, Instruction, Program, VariableId};
+use crate::rir::{Block, BlockId, Instruction, Operand, Program, VariableId};
+use qsc_data_structures::fx::FxHashSet;
 use qsc_data_structures::index_map::IndexMap;
-use rustc_hash::FxHashSet;
 
 /// Given a block, return the block IDs of its successors.
 #[must_use]
@@ -95,6 +95,8 @@ pub fn get_variable_assignments(program: &Program) -> IndexMap<VariableId, (Bloc
                 | Instruction::LogicalAnd(_, _, var)
                 | Instruction::LogicalOr(_, _, var)
                 | Instruction::BitwiseNot(_, var)
+                | Instruction::Sitofp(_, var)
+                | Instruction::Fptosi(_, var)
                 | Instruction::BitwiseAnd(_, _, var)
                 | Instruction::BitwiseOr(_, _, var)
                 | Instruction::BitwiseXor(_, _, var)
@@ -125,3 +127,75 @@ pub fn get_variable_assignments(program: &Program) -> IndexMap<VariableId, (Bloc
     );
     assignments
 }
+
+/// Calls `f` on each operand read by the instruction.
+/// Branch conditions are not included, since they are always variables rather than operands.
+pub fn for_each_operand(instr: &Instruction, mut f: impl FnMut(&Operand)) {
+    match instr {
+        Instruction::Store(operand, _)
+        | Instruction::LogicalNot(operand, _)
+        | Instruction::BitwiseNot(operand, _)
+        | Instruction::Sitofp(operand, _)
+        | Instruction::Fptosi(operand, _) => f(operand),
+        Instruction::Call(_, args, _) => args.iter().for_each(f),
+        Instruction::Add(lhs, rhs, _)
+        | Instruction::Sub(lhs, rhs, _)
+        | Instruction::Mul(lhs, rhs, _)
+        | Instruction::Sdiv(lhs, rhs, _)
+        | Instruction::Srem(lhs, rhs, _)
+        | Instruction::Shl(lhs, rhs, _)
+        | Instruction::Ashr(lhs, rhs, _)
+        | Instruction::Fadd(lhs, rhs, _)
+        | Instruction::Fsub(lhs, rhs, _)
+        | Instruction::Fmul(lhs, rhs, _)
+        | Instruction::Fdiv(lhs, rhs, _)
+        | Instruction::Fcmp(_, lhs, rhs, _)
+        | Instruction::Icmp(_, lhs, rhs, _)
+        | Instruction::LogicalAnd(lhs, rhs, _)
+        | Instruction::LogicalOr(lhs, rhs, _)
+        | Instruction::BitwiseAnd(lhs, rhs, _)
+        | Instruction::BitwiseOr(lhs, rhs, _)
+        | Instruction::BitwiseXor(lhs, rhs, _) => {
+            f(lhs);
+            f(rhs);
+        }
+        Instruction::Phi(args, _) => args.iter().for_each(|(operand, _)| f(operand)),
+        Instruction::Jump(_) | Instruction::Branch(..) | Instruction::Return => {}
+    }
+}
+
+/// Calls `f` on each operand read by the instruction, allowing it to be modified.
+/// Branch conditions are not included, since they are always variables rather than operands.
+pub fn for_each_operand_mut(instr: &mut Instruction, mut f: impl FnMut(&mut Operand)) {
+    match instr {
+        Instruction::Store(operand, _)
+        | Instruction::LogicalNot(operand, _)
+        | Instruction::BitwiseNot(operand, _)
+        | Instruction::Sitofp(operand, _)
+        | Instruction::Fptosi(operand, _) => f(operand),
+        Instruction::Call(_, args, _) => args.iter_mut().for_each(f),
+        Instruction::Add(lhs, rhs, _)
+        | Instruction::Sub(lhs, rhs, _)
+        | Instruction::Mul(lhs, rhs, _)
+        | Instruction::Sdiv(lhs, rhs, _)
+        | Instruction::Srem(lhs, rhs, _)
+        | Instruction::Shl(lhs, rhs, _)
+        | Instruction::Ashr(lhs, rhs, _)
+        | Instruction::Fadd(lhs, rhs, _)
+        | Instruction::Fsub(lhs, rhs, _)
+        | Instruction::Fmul(lhs, rhs, _)
+        | Instruction::Fdiv(lhs, rhs, _)
+        | Instruction::Fcmp(_, lhs, rhs, _)
+        | Instruction::Icmp(_, lhs, rhs, _)
+        | Instruction::LogicalAnd(lhs, rhs, _)
+        | Instruction::LogicalOr(lhs, rhs, _)
+        | Instruction::BitwiseAnd(lhs, rhs, _)
+        | Instruction::BitwiseOr(lhs, rhs, _)
+        | Instruction::BitwiseXor(lhs, rhs, _) => {
+            f(lhs);
+            f(rhs);
+        }
+        Instruction::Phi(args, _) => args.iter_mut().for_each(|(operand, _)| f(operand)),
+        Instruction::Jump(_) | Instruction::Branch(..) | Instruction::Return => {}
+    }
+}
@@ -18,6 +18,7 @@ fn compile(data: &[u8]) {
                 (store, std)
             };
         }
+        let _tokens = qsc::parse::highlight::tokenize(fuzzed_code);
         let sources = SourceMap::new([("fuzzed_code".into(), fuzzed_code.into())], None);
         STORE_STD.with(|(store, std)| {
             let mut _unit = qsc::compile::compile(
@@ -46,12 +46,12 @@ function registerQSharpLanguage(
     },
     {
       token: "keyword",
-      regex: String.raw`(namespace|open|import|export|as|operation|function|body|adjoint|newtype|struct|new|controlled|internal)\b`,
+      regex: String.raw`(namespace|open|import|export|as|operation|function|body|adjoint|newtype|struct|enum|const|class|new|controlled|internal)\b`,
       beginWord: true,
     },
     {
       token: "keyword",
-      regex: String.raw`(if|elif|else|repeat|until|fixup|for|in|return|fail|within|apply)\b`,
+      regex: String.raw`(if|elif|else|match|repeat|until|fixup|for|in|return|fail|within|apply)\b`,
       beginWord: true,
     },
     {
@@ -0,0 +1,117 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    compilation::Compilation,
+    protocol::{RequiredCapabilities, RuntimeFeature},
+    qsc_utils::into_range,
+};
+use qsc::{
+    compile::ErrorKind,
+    hir::{ItemKind, Package},
+    line_column::{Encoding, Range},
+    target::Profile,
+    PassContext, RuntimeFeatureFlags, Span,
+};
+
+/// Gets the runtime capabilities required by the code in the given range, along with the most restrictive target
+/// profile that accepts it. An empty range is expanded to the callable that contains it, or to the whole source if
+/// it is not inside a callable.
+///
+/// Returns `None` if the compilation has errors that prevent capabilities analysis from running.
+pub(crate) fn get_required_capabilities(
+    compilation: &Compilation,
+    source_name: &str,
+    range: Range,
+    position_encoding: Encoding,
+) -> Option<RequiredCapabilities> {
+    if compilation
+        .compile_errors
+        .iter()
+        .any(|error| !matches!(error.error(), ErrorKind::Pass(_) | ErrorKind::Lint(_)))
+    {
+        return None;
+    }
+
+    let unit = compilation.user_unit();
+    let selection = compilation.source_range_to_package_span(source_name, range, position_encoding);
+    let scope = if selection.lo == selection.hi {
+        enclosing_callable_span(&unit.package, selection.lo)
+            .unwrap_or_else(|| compilation.package_span_of_source(source_name))
+    } else {
+        selection
+    };
+
+    let (fir_store, fir_package_id) =
+        qsc::lower_hir_to_fir(&compilation.package_store, compilation.user_package_id);
+    let features = PassContext::get_required_runtime_features_on_fir(&fir_store, fir_package_id)
+        .into_iter()
+        .filter(|(span, _)| scope.lo <= span.lo && span.hi <= scope.hi)
+        .collect::<Vec<_>>();
+
+    let all_features = features
+        .iter()
+        .fold(RuntimeFeatureFlags::empty(), |all, (_, features)| {
+            all | *features
+        });
+    let capabilities = all_features
+        .target_capabilities()
+        .iter_names()
+        .map(|(name, _)| name.to_string())
+        .collect::<Vec<_>>();
+    let minimal_profile = Profile::minimal_for(all_features.target_capabilities());
+    let summary = if capabilities.is_empty() {
+        format!(
+            "No runtime capabilities required (minimal profile: {})",
+            minimal_profile.to_str()
+        )
+    } else {
+        format!(
+            "Requires {} (minimal profile: {})",
+            capabilities.join(", "),
+            minimal_profile.to_str()
+        )
+    };
+
+    Some(RequiredCapabilities {
+        range: into_range(position_encoding, scope, &unit.sources),
+        capabilities,
+        minimal_profile,
+        features: features
+            .into_iter()
+            .flat_map(|(span, features)| {
+                features.iter_names().map(move |(name, _)| RuntimeFeature {
+                    range: into_range(position_encoding, span, &unit.sources),
+                    description: describe_feature(name),
+                })
+            })
+            .collect(),
+        summary,
+    })
+}
+
+/// Finds the innermost callable whose declaration contains the offset.
+fn enclosing_callable_span(package: &Package, offset: u32) -> Option<Span> {
+    package
+        .items
+        .values()
+        .filter(|item| matches!(item.kind, ItemKind::Callable(_)) && item.span.touches(offset))
+        .map(|item| item.span)
+        .min_by_key(|span| span.hi - span.lo)
+}
+
+/// Turns the name of a runtime feature flag, such as `UseOfDynamicBool`, into a phrase such as
+/// "use of dynamic bool".
+fn describe_feature(name: &str) -> String {
+    let mut description = String::new();
+    for c in name.chars() {
+        if c.is_uppercase() && !description.is_empty() {
+            description.push(' ');
+        }
+        description.push(c.to_ascii_lowercase());
+    }
+    description
+}
@@ -0,0 +1,274 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::get_required_capabilities;
+use crate::{
+    test_utils::{compile_with_fake_stdlib_and_markers_no_cursor, compile_with_markers},
+    Encoding,
+};
+use expect_test::{expect, Expect};
+use qsc::line_column::Range;
+
+fn check_cursor(source_with_markers: &str, expect: &Expect) {
+    let (compilation, cursor_position, _) = compile_with_markers(source_with_markers, false);
+    let actual = get_required_capabilities(
+        &compilation,
+        "<source>",
+        Range {
+            start: cursor_position,
+            end: cursor_position,
+        },
+        Encoding::Utf8,
+    );
+    expect.assert_debug_eq(&actual);
+}
+
+fn check_selection(source_with_markers: &str, expect: &Expect) {
+    let (compilation, target_ranges) =
+        compile_with_fake_stdlib_and_markers_no_cursor(source_with_markers, false);
+    let [selection] = target_ranges[..] else {
+        panic!("expected a single selection");
+    };
+    let actual = get_required_capabilities(&compilation, "<source>", selection, Encoding::Utf8);
+    expect.assert_debug_eq(&actual);
+}
+
+#[test]
+fn static_callable_requires_no_capabilities() {
+    check_cursor(
+        r#"
+        namespace Test {
+            operation Foo() : Unit {
+                use q = Qubit();
+                ↘H(q);
+            }
+            operation Bar() : Unit {
+                use q = Qubit();
+                if M(q) == One {
+                    X(q);
+                }
+            }
+        }"#,
+        &expect![[r#"
+            Some(
+                RequiredCapabilities {
+                    range: Range {
+                        start: Position {
+                            line: 2,
+                            column: 12,
+                        },
+                        end: Position {
+                            line: 5,
+                            column: 13,
+                        },
+                    },
+                    capabilities: [],
+                    minimal_profile: Base,
+                    features: [],
+                    summary: "No runtime capabilities required (minimal profile: Base)",
+                },
+            )
+        "#]],
+    );
+}
+
+#[test]
+fn callable_containing_cursor_is_analyzed() {
+    check_cursor(
+        r#"
+        namespace Test {
+            operation Foo() : Unit {
+                use q = Qubit();
+                H(q);
+            }
+            operation Bar() : Unit {
+                use q = Qubit();
+                if M(q) == ↘One {
+                    X(q);
+                }
+            }
+        }"#,
+        &expect![[r#"
+            Some(
+                RequiredCapabilities {
+                    range: Range {
+                        start: Position {
+                            line: 6,
+                            column: 12,
+                        },
+                        end: Position {
+                            line: 11,
+                            column: 13,
+                        },
+                    },
+                    capabilities: [
+                        "Adaptive",
+                    ],
+                    minimal_profile: AdaptiveRI,
+                    features: [
+                        RuntimeFeature {
+                            range: Range {
+                                start: Position {
+                                    line: 8,
+                                    column: 19,
+                                },
+                                end: Position {
+                                    line: 8,
+                                    column: 30,
+                                },
+                            },
+                            description: "use of dynamic bool",
+                        },
+                    ],
+                    summary: "Requires Adaptive (minimal profile: Adaptive_RI)",
+                },
+            )
+        "#]],
+    );
+}
+
+#[test]
+fn selection_limits_analysis_to_selected_code() {
+    check_selection(
+        r#"
+        namespace Test {
+            operation Foo() : Unit {
+                use q = Qubit();
+                ◉let r = M(q);
+                let b = r == One;◉
+                mutable i = 0;
+                if b {
+                    set i += 1;
+                }
+            }
+        }"#,
+        &expect![[r#"
+            Some(
+                RequiredCapabilities {
+                    range: Range {
+                        start: Position {
+                            line: 4,
+                            column: 16,
+                        },
+                        end: Position {
+                            line: 5,
+                            column: 33,
+                        },
+                    },
+                    capabilities: [
+                        "Adaptive",
+                    ],
+                    minimal_profile: AdaptiveRI,
+                    features: [
+                        RuntimeFeature {
+                            range: Range {
+                                start: Position {
+                                    line: 5,
+                                    column: 24,
+                                },
+                                end: Position {
+                                    line: 5,
+                                    column: 32,
+                                },
+                            },
+                            description: "use of dynamic bool",
+                        },
+                    ],
+                    summary: "Requires Adaptive (minimal profile: Adaptive_RI)",
+                },
+            )
+        "#]],
+    );
+}
+
+#[test]
+fn dynamic_double_requires_floating_point_profile() {
+    check_cursor(
+        r#"
+        namespace Test {
+            operation Foo() : Unit {
+                use q = Qubit();
+                ↘let d = M(q) == One ? 1.0 | 2.0;
+                Rx(d, q);
+            }
+        }"#,
+        &expect![[r#"
+            Some(
+                RequiredCapabilities {
+                    range: Range {
+                        start: Position {
+                            line: 2,
+                            column: 12,
+                        },
+                        end: Position {
+                            line: 6,
+                            column: 13,
+                        },
+                    },
+                    capabilities: [
+                        "Adaptive",
+                        "FloatingPointComputations",
+                    ],
+                    minimal_profile: AdaptiveRIF,
+                    features: [
+                        RuntimeFeature {
+                            range: Range {
+                                start: Position {
+                                    line: 4,
+                                    column: 24,
+                                },
+                                end: Position {
+                                    line: 4,
+                                    column: 35,
+                                },
+                            },
+                            description: "use of dynamic bool",
+                        },
+                        RuntimeFeature {
+                            range: Range {
+                                start: Position {
+                                    line: 5,
+                                    column: 16,
+                                },
+                                end: Position {
+                                    line: 5,
+                                    column: 24,
+                                },
+                            },
+                            description: "use of dynamic bool",
+                        },
+                        RuntimeFeature {
+                            range: Range {
+                                start: Position {
+                                    line: 5,
+                                    column: 16,
+                                },
+                                end: Position {
+                                    line: 5,
+                                    column: 24,
+                                },
+                            },
+                            description: "use of dynamic double",
+                        },
+                    ],
+                    summary: "Requires Adaptive, FloatingPointComputations (minimal profile: Adaptive_RIF)",
+                },
+            )
+        "#]],
+    );
+}
+
+#[test]
+fn compilation_errors_prevent_analysis() {
+    check_cursor(
+        r#"
+        namespace Test {
+            operation Foo() : Unit {
+                ↘let x : Int = "not an int";
+            }
+        }"#,
+        &expect![[r#"
+            None
+        "#]],
+    );
+}
@@ -1,6 +1,14 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
+#[cfg(test)]
+mod tests;
+
+mod accumulate;
+mod conjugate;
+mod generate_test;
+mod inline;
+
 use miette::Diagnostic;
 use qsc::{
     compile::ErrorKind,
@@ -22,7 +30,32 @@ pub(crate) fn get_code_actions(
 ) -> Vec<CodeAction> {
     // Compute quick_fixes and other code_actions, and then merge them together
     let span = compilation.source_range_to_package_span(source_name, range, position_encoding);
-    quick_fixes(compilation, source_name, span, position_encoding)
+    let mut code_actions = quick_fixes(compilation, source_name, span, position_encoding);
+    code_actions.extend(inline::inline_call(
+        compilation,
+        source_name,
+        span,
+        position_encoding,
+    ));
+    code_actions.extend(accumulate::rewrite_accumulation(
+        compilation,
+        source_name,
+        span,
+        position_encoding,
+    ));
+    code_actions.extend(conjugate::wrap_in_conjugate(
+        compilation,
+        source_name,
+        span,
+        position_encoding,
+    ));
+    code_actions.extend(generate_test::generate_test(
+        compilation,
+        source_name,
+        span,
+        position_encoding,
+    ));
+    code_actions
 }
 
 fn quick_fixes(
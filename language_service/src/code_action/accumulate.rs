@@ -0,0 +1,549 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use qsc::{
+    ast::{
+        self,
+        visit::{walk_block, walk_expr, walk_pat, walk_path, walk_stmt, Visitor},
+        BinOp, Block, CallableKind, Expr, ExprKind, Mutability, NodeId, PatKind, PathKind, Stmt,
+        StmtKind,
+    },
+    display::Lookup,
+    hir::{self, ty::Ty},
+    line_column::Encoding,
+    resolve::Res,
+    SourceMap, Span,
+};
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use super::inline::{line_indent, text};
+use crate::{
+    compilation::Compilation,
+    protocol::{CodeAction, CodeActionKind, TextEdit, WorkspaceEdit},
+    qsc_utils::into_range,
+};
+
+/// Offers to rewrite a loop that accumulates into a mutable variable as a call to `Std.Arrays.Fold`, `Mapped` or
+/// `ForEach`, or a `let` binding of such a call with a lambda argument as a loop.
+///
+/// A loop is rewritten when it iterates over an array, immediately follows the declaration of the mutable
+/// variable, and its body is a single `set` of the variable. Appending a one-item array to a variable that starts out
+/// empty becomes `Mapped`, or `ForEach` when the item calls operations. Any other update becomes `Fold`, as long as it
+/// doesn't call operations. The new value can't refer to other mutable variables, which lambdas can't capture.
+pub(super) fn rewrite_accumulation(
+    compilation: &Compilation,
+    source_name: &str,
+    span: Span,
+    encoding: Encoding,
+) -> Option<CodeAction> {
+    let unit = compilation.user_unit();
+    let mut finder = BlockFinder {
+        compilation,
+        offset: span.lo,
+        blocks: Vec::new(),
+        mutables: FxHashSet::default(),
+        assignments: FxHashMap::default(),
+    };
+    finder.visit_package(&unit.ast.package);
+
+    let rewriter = Rewriter {
+        compilation,
+        source_map: &unit.sources,
+        mutables: &finder.mutables,
+        assignments: &finder.assignments,
+    };
+    let (title, edit_span, new_text) = finder.blocks.iter().rev().find_map(|block| {
+        let index = block
+            .stmts
+            .iter()
+            .position(|stmt| stmt.span.lo <= span.lo && span.lo <= stmt.span.hi)?;
+        let loop_start = match &*block.stmts[index].kind {
+            StmtKind::Local(Mutability::Mutable, ..) => Some(index),
+            _ => index.checked_sub(1),
+        };
+        loop_start
+            .and_then(|start| Some((&*block.stmts[start], &**block.stmts.get(start + 1)?)))
+            .and_then(|(decl, stmt)| rewriter.loop_to_call(decl, stmt))
+            .or_else(|| rewriter.call_to_loop(&block.stmts[index]))
+    })?;
+
+    Some(CodeAction {
+        title,
+        edit: Some(WorkspaceEdit {
+            changes: vec![(
+                source_name.to_string(),
+                vec![TextEdit {
+                    new_text,
+                    range: into_range(encoding, edit_span, &unit.sources),
+                }],
+            )],
+        }),
+        kind: Some(CodeActionKind::RefactorRewrite),
+        is_preferred: None,
+    })
+}
+
+/// Finds the blocks containing the offset, from the outermost to the innermost, along with the mutable variables
+/// declared in the package and the number of times each variable is assigned to.
+struct BlockFinder<'a> {
+    compilation: &'a Compilation,
+    offset: u32,
+    blocks: Vec<&'a Block>,
+    mutables: FxHashSet<NodeId>,
+    assignments: FxHashMap<NodeId, usize>,
+}
+
+impl<'a> Visitor<'a> for BlockFinder<'a> {
+    fn visit_block(&mut self, block: &'a Block) {
+        if block.span.lo <= self.offset && self.offset <= block.span.hi {
+            self.blocks.push(block);
+        }
+        walk_block(self, block);
+    }
+
+    fn visit_stmt(&mut self, stmt: &'a Stmt) {
+        if let StmtKind::Local(Mutability::Mutable, pat, _) = &*stmt.kind {
+            self.mutables.extend(bound_ids(pat));
+        }
+        walk_stmt(self, stmt);
+    }
+
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        if let ExprKind::Assign(lhs, _)
+        | ExprKind::AssignOp(_, lhs, _)
+        | ExprKind::AssignUpdate(lhs, ..) = &*expr.kind
+        {
+            if let Some(id) = local(self.compilation, lhs) {
+                *self.assignments.entry(id).or_default() += 1;
+            }
+        }
+        walk_expr(self, expr);
+    }
+}
+
+/// Gets the IDs of the names bound by the pattern.
+pub(super) fn bound_ids(pat: &ast::Pat) -> Vec<NodeId> {
+    struct BindingFinder(Vec<NodeId>);
+    impl Visitor<'_> for BindingFinder {
+        fn visit_pat(&mut self, pat: &ast::Pat) {
+            if let PatKind::Bind(ident, _) = &*pat.kind {
+                self.0.push(ident.id);
+            }
+            walk_pat(self, pat);
+        }
+    }
+
+    let mut finder = BindingFinder(Vec::new());
+    finder.visit_pat(pat);
+    finder.0
+}
+
+/// Gets the name and ID of the single name bound by the pattern, which may have a type annotation.
+fn single_binding(pat: &ast::Pat) -> Option<(&str, NodeId)> {
+    match &*pat.kind {
+        PatKind::Bind(ident, _) => Some((&ident.name, ident.id)),
+        PatKind::Paren(inner) => single_binding(inner),
+        _ => None,
+    }
+}
+
+/// Gets the ID of the local variable the expression refers to, if it is a plain name.
+pub(super) fn local(compilation: &Compilation, expr: &Expr) -> Option<NodeId> {
+    match &*expr.kind {
+        ExprKind::Path(PathKind::Ok(path)) if path.segments.is_none() => {
+            match compilation.get_res(path.id) {
+                Some(Res::Local(id)) => Some(*id),
+                _ => None,
+            }
+        }
+        ExprKind::Paren(inner) => local(compilation, inner),
+        _ => None,
+    }
+}
+
+/// Whether `x op= y` is a valid update for the operator, which excludes comparisons.
+fn is_assignable(op: BinOp) -> bool {
+    !matches!(
+        op,
+        BinOp::Eq | BinOp::Neq | BinOp::Gt | BinOp::Gte | BinOp::Lt | BinOp::Lte
+    )
+}
+
+/// Whether the expression binds as tightly as an operand of any binary operator, so it needs no parentheses.
+fn is_atomic(expr: &Expr) -> bool {
+    matches!(
+        &*expr.kind,
+        ExprKind::Array(_)
+            | ExprKind::ArrayRepeat(..)
+            | ExprKind::Call(..)
+            | ExprKind::Field(..)
+            | ExprKind::Index(..)
+            | ExprKind::Interpolate(_)
+            | ExprKind::Lit(_)
+            | ExprKind::Paren(_)
+            | ExprKind::Path(_)
+            | ExprKind::Struct(..)
+            | ExprKind::Tuple(_)
+    )
+}
+
+/// What the expressions in a lambda body refer to.
+#[derive(Default)]
+struct References {
+    /// The spans of the references to each local variable.
+    locals: FxHashMap<NodeId, Vec<Span>>,
+    /// The names of the variables referred to.
+    names: FxHashSet<String>,
+    calls_operation: bool,
+    assigns: bool,
+}
+
+impl References {
+    fn of<'a>(compilation: &Compilation, exprs: impl IntoIterator<Item = &'a Expr>) -> Self {
+        struct Finder<'a> {
+            compilation: &'a Compilation,
+            references: References,
+        }
+        impl<'a> Visitor<'a> for Finder<'_> {
+            fn visit_expr(&mut self, expr: &'a Expr) {
+                match &*expr.kind {
+                    ExprKind::Call(callee, _) => {
+                        if matches!(
+                            self.compilation.get_ty(callee.id),
+                            Some(Ty::Arrow(arrow)) if arrow.kind == hir::CallableKind::Operation
+                        ) {
+                            self.references.calls_operation = true;
+                        }
+                    }
+                    ExprKind::Assign(..)
+                    | ExprKind::AssignOp(..)
+                    | ExprKind::AssignUpdate(..)
+                    | ExprKind::Return(_) => self.references.assigns = true,
+                    _ => {}
+                }
+                walk_expr(self, expr);
+            }
+
+            fn visit_path(&mut self, path: &'a ast::Path) {
+                // A reference to a field of a local is a path whose first segment is the local.
+                let ident = match &path.segments {
+                    None => Some((path.id, path.name.span, &path.name.name)),
+                    Some(segments) => segments
+                        .first()
+                        .map(|first| (first.id, first.span, &first.name)),
+                };
+                if let Some((id, span, name)) = ident {
+                    if let Some(Res::Local(local)) = self.compilation.get_res(id) {
+                        self.references.locals.entry(*local).or_default().push(span);
+                        self.references.names.insert(name.to_string());
+                    }
+                }
+                walk_path(self, path);
+            }
+        }
+
+        let mut finder = Finder {
+            compilation,
+            references: References::default(),
+        };
+        for expr in exprs {
+            finder.visit_expr(expr);
+        }
+        finder.references
+    }
+
+    /// Whether any of the expressions refer to a mutable variable other than `allowed`.
+    fn captures_mutable(&self, mutables: &FxHashSet<NodeId>, allowed: Option<NodeId>) -> bool {
+        self.locals
+            .keys()
+            .any(|id| mutables.contains(id) && Some(*id) != allowed)
+    }
+}
+
+struct Rewriter<'a> {
+    compilation: &'a Compilation,
+    source_map: &'a SourceMap,
+    mutables: &'a FxHashSet<NodeId>,
+    assignments: &'a FxHashMap<NodeId, usize>,
+}
+
+impl Rewriter<'_> {
+    /// Rewrites the declaration of a mutable variable followed by a loop accumulating into it as a `let` binding of a
+    /// call, returning the title of the code action, the span to replace and the new text.
+    #[allow(clippy::too_many_lines)]
+    fn loop_to_call(&self, decl: &Stmt, stmt: &Stmt) -> Option<(String, Span, String)> {
+        let StmtKind::Local(Mutability::Mutable, pat, init) = &*decl.kind else {
+            return None;
+        };
+        let (name, id) = single_binding(pat)?;
+        let (StmtKind::Expr(expr) | StmtKind::Semi(expr)) = &*stmt.kind else {
+            return None;
+        };
+        let ExprKind::For(item, iterable, body) = &*expr.kind else {
+            return None;
+        };
+        if !matches!(self.compilation.get_ty(iterable.id), Some(Ty::Array(_))) {
+            return None;
+        }
+        let [update] = &*body.stmts else {
+            return None;
+        };
+        let (StmtKind::Expr(update) | StmtKind::Semi(update)) = &*update.kind else {
+            return None;
+        };
+        if References::of(self.compilation, [&**iterable])
+            .locals
+            .contains_key(&id)
+        {
+            return None;
+        }
+
+        // The variable only stays mutable if it is assigned to somewhere other than in the loop.
+        let keyword = if self.assignments.get(&id).copied().unwrap_or_default() > 1 {
+            "mutable"
+        } else {
+            "let"
+        };
+        let item = self.text(item.span);
+        let iterable = self.text(iterable.span);
+        let (title, call) = match &*update.kind {
+            ExprKind::AssignOp(BinOp::Add, lhs, rhs)
+                if local(self.compilation, lhs) == Some(id)
+                    && matches!(&*init.kind, ExprKind::Array(items) if items.is_empty())
+                    && matches!(&*rhs.kind, ExprKind::Array(items) if items.len() == 1) =>
+            {
+                let ExprKind::Array(items) = &*rhs.kind else {
+                    unreachable!("update should append an array");
+                };
+                let references = References::of(self.compilation, [&*items[0]]);
+                if references.assigns || references.captures_mutable(self.mutables, None) {
+                    return None;
+                }
+                let (callee, arrow) = if references.calls_operation {
+                    ("ForEach", "=>")
+                } else {
+                    ("Mapped", "->")
+                };
+                (
+                    callee,
+                    format!(
+                        "Std.Arrays.{callee}({item} {arrow} {}, {iterable})",
+                        self.text(items[0].span)
+                    ),
+                )
+            }
+            ExprKind::Assign(lhs, rhs) | ExprKind::AssignOp(_, lhs, rhs)
+                if local(self.compilation, lhs) == Some(id) =>
+            {
+                let references = References::of(self.compilation, [&**rhs]);
+                if references.assigns
+                    || references.calls_operation
+                    || references.captures_mutable(self.mutables, Some(id))
+                {
+                    return None;
+                }
+                let value = if let ExprKind::AssignOp(..) = &*update.kind {
+                    // The operator is the text between the operands, without the trailing `=`.
+                    let op = self.text(Span {
+                        lo: lhs.span.hi,
+                        hi: rhs.span.lo,
+                    });
+                    let op = op.trim();
+                    let op = op.strip_suffix('=').unwrap_or(op);
+                    if is_atomic(rhs) {
+                        format!("{name} {op} {}", self.text(rhs.span))
+                    } else {
+                        format!("{name} {op} ({})", self.text(rhs.span))
+                    }
+                } else {
+                    self.text(rhs.span)
+                };
+                (
+                    "Fold",
+                    format!(
+                        "Std.Arrays.Fold(({name}, {item}) -> {value}, {}, {iterable})",
+                        self.text(init.span)
+                    ),
+                )
+            }
+            ExprKind::AssignUpdate(lhs, index, value)
+                if local(self.compilation, lhs) == Some(id) =>
+            {
+                let references = References::of(self.compilation, [&**index, &**value]);
+                if references.assigns
+                    || references.calls_operation
+                    || references.captures_mutable(self.mutables, Some(id))
+                {
+                    return None;
+                }
+                (
+                    "Fold",
+                    format!(
+                        "Std.Arrays.Fold(({name}, {item}) -> {name} w/ {} <- {}, {}, {iterable})",
+                        self.text(index.span),
+                        self.text(value.span),
+                        self.text(init.span)
+                    ),
+                )
+            }
+            _ => return None,
+        };
+
+        Some((
+            format!("Convert loop to `{title}`"),
+            Span {
+                lo: decl.span.lo,
+                hi: stmt.span.hi,
+            },
+            format!("{keyword} {} = {call};", self.text(pat.span)),
+        ))
+    }
+
+    /// Rewrites a `let` binding of a call to `Fold`, `Mapped` or `ForEach` with a lambda argument as the declaration
+    /// of a mutable variable followed by a loop accumulating into it.
+    fn call_to_loop(&self, stmt: &Stmt) -> Option<(String, Span, String)> {
+        let StmtKind::Local(_, pat, init) = &*stmt.kind else {
+            return None;
+        };
+        let (name, _) = single_binding(pat)?;
+        let ExprKind::Call(callee, args) = &*init.kind else {
+            return None;
+        };
+        let callee = self.array_callable(callee)?;
+        let ExprKind::Tuple(args) = &*args.kind else {
+            return None;
+        };
+
+        let (init, item, iterable, update) = match (callee, &**args) {
+            ("Fold", [folder, state, array]) => {
+                let ExprKind::Lambda(CallableKind::Function, input, body) = &*folder.kind else {
+                    return None;
+                };
+                let PatKind::Tuple(params) = &*input.kind else {
+                    return None;
+                };
+                let [acc, item] = &**params else {
+                    return None;
+                };
+                let (_, acc_id) = single_binding(acc)?;
+                let references = References::of(self.compilation, [&**body]);
+                if self.shadows(name, &references, acc_id) {
+                    return None;
+                }
+                let update = match &*body.kind {
+                    ExprKind::BinOp(op, lhs, rhs)
+                        if is_assignable(*op) && local(self.compilation, lhs) == Some(acc_id) =>
+                    {
+                        let op = self.text(Span {
+                            lo: lhs.span.hi,
+                            hi: rhs.span.lo,
+                        });
+                        format!(
+                            "set {name} {}= {};",
+                            op.trim(),
+                            self.renamed(rhs.span, &references, acc_id, name)
+                        )
+                    }
+                    _ => format!(
+                        "set {name} = {};",
+                        self.renamed(body.span, &references, acc_id, name)
+                    ),
+                };
+                (self.text(state.span), &**item, array, update)
+            }
+            ("Mapped" | "ForEach", [mapper, array]) => {
+                let ExprKind::Lambda(_, item, body) = &*mapper.kind else {
+                    return None;
+                };
+                let references = References::of(self.compilation, [&**body]);
+                if references.names.contains(name) {
+                    return None;
+                }
+                let update = format!("set {name} += [{}];", self.text(body.span));
+                ("[]".to_string(), &**item, array, update)
+            }
+            _ => return None,
+        };
+        if References::of(self.compilation, [&**iterable])
+            .names
+            .contains(name)
+        {
+            return None;
+        }
+
+        let source = self
+            .source_map
+            .find_by_offset(stmt.span.lo)
+            .expect("source should exist for offset");
+        let indent = line_indent(&source.contents, stmt.span.lo - source.offset);
+        Some((
+            format!("Convert `{callee}` to loop"),
+            stmt.span,
+            format!(
+                "mutable {} = {init};\n{indent}for {} in {} {{\n{indent}    {update}\n{indent}}}",
+                self.text(pat.span),
+                self.text(item.span),
+                self.text(iterable.span),
+            ),
+        ))
+    }
+
+    /// Gets the name of the `Std.Arrays` callable that can be rewritten as a loop that the callee refers to.
+    fn array_callable(&self, callee: &Expr) -> Option<&'static str> {
+        let ExprKind::Path(PathKind::Ok(path)) = &*callee.kind else {
+            return None;
+        };
+        let Some(Res::Item(item_id, _)) = self.compilation.get_res(path.id) else {
+            return None;
+        };
+        let (item, package, _) = self
+            .compilation
+            .resolve_item_relative_to_user_package(item_id);
+        let hir::ItemKind::Callable(decl) = &item.kind else {
+            return None;
+        };
+        let namespace = package.items.get(item.parent?)?;
+        let hir::ItemKind::Namespace(namespace, _) = &namespace.kind else {
+            return None;
+        };
+        if !namespace.str_iter().into_iter().eq(["Std", "Arrays"]) {
+            return None;
+        }
+        ["Fold", "Mapped", "ForEach"]
+            .into_iter()
+            .find(|name| **name == *decl.name.name)
+    }
+
+    /// Whether the lambda body refers to a variable with the given name other than the accumulator parameter, which
+    /// would be shadowed by the mutable variable.
+    fn shadows(&self, name: &str, references: &References, acc_id: NodeId) -> bool {
+        references.names.contains(name)
+            && references.locals.iter().any(|(id, spans)| {
+                *id != acc_id && spans.iter().any(|span| self.text(*span) == name)
+            })
+    }
+
+    /// Gets the text of the span with the references to the accumulator parameter replaced by the variable name.
+    fn renamed(&self, span: Span, references: &References, acc_id: NodeId, name: &str) -> String {
+        let mut spans = references
+            .locals
+            .get(&acc_id)
+            .into_iter()
+            .flatten()
+            .filter(|reference| span.lo <= reference.lo && reference.hi <= span.hi)
+            .collect::<Vec<_>>();
+        spans.sort_by_key(|reference| std::cmp::Reverse(reference.lo));
+
+        let mut text = self.text(span);
+        for reference in spans {
+            let lo = (reference.lo - span.lo) as usize;
+            let hi = (reference.hi - span.lo) as usize;
+            text.replace_range(lo..hi, name);
+        }
+        text
+    }
+
+    fn text(&self, span: Span) -> String {
+        text(self.source_map, span)
+    }
+}
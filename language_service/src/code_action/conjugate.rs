@@ -0,0 +1,321 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use qsc::{
+    ast::{
+        self,
+        visit::{walk_block, walk_expr, walk_path, Visitor},
+        Block, Expr, ExprKind, Mutability, NodeId, Stmt, StmtKind,
+    },
+    display::Lookup,
+    hir::{
+        self,
+        ty::{Arrow, FunctorSet, Ty},
+    },
+    line_column::Encoding,
+    resolve::Res,
+    Span,
+};
+use rustc_hash::FxHashSet;
+
+use super::{
+    accumulate::{bound_ids, local},
+    inline::{line_indent, text},
+};
+use crate::{
+    compilation::Compilation,
+    protocol::{CodeAction, CodeActionKind, TextEdit, WorkspaceEdit},
+    qsc_utils::into_range,
+};
+
+/// Offers to wrap the selected statements in the `within` block of a conjugation, with the rest of the statements in
+/// the enclosing block as the `apply` block, so that the effect of the selected statements is undone automatically.
+///
+/// The selected statements have to call at least one operation, and must be invertible: every operation they call
+/// must support `Adjoint` and be called as a statement of its own, and they can't assign to variables, loop with
+/// `while` or `repeat`, allocate qubits or return. The statements that follow can't return, assign to variables the
+/// selected statements use, or refer to names the selected statements bind.
+#[allow(clippy::too_many_lines)]
+pub(super) fn wrap_in_conjugate(
+    compilation: &Compilation,
+    source_name: &str,
+    span: Span,
+    encoding: Encoding,
+) -> Option<CodeAction> {
+    if span.lo == span.hi {
+        return None;
+    }
+
+    let unit = compilation.user_unit();
+    let mut finder = BlockFinder { span, block: None };
+    finder.visit_package(&unit.ast.package);
+    let block = finder.block?;
+
+    let first = block.stmts.iter().position(|stmt| span.lo < stmt.span.hi)?;
+    let last = block
+        .stmts
+        .iter()
+        .rposition(|stmt| stmt.span.lo < span.hi)?;
+    if first > last || last + 1 == block.stmts.len() {
+        return None;
+    }
+    let (within, apply) = block.stmts[first..].split_at(last + 1 - first);
+
+    let mut check = InvertibilityCheck {
+        compilation,
+        invertible: true,
+        operation_calls: 0,
+    };
+    check.check_stmts(within);
+    let returns_unit = match &*within[within.len() - 1].kind {
+        StmtKind::Expr(expr) => is_unit(compilation, expr),
+        _ => true,
+    };
+    if !check.invertible || check.operation_calls == 0 || !returns_unit {
+        return None;
+    }
+
+    let mut used = References {
+        compilation,
+        locals: FxHashSet::default(),
+    };
+    for stmt in within {
+        used.visit_stmt(stmt);
+    }
+    let bound = within
+        .iter()
+        .filter_map(|stmt| match &*stmt.kind {
+            StmtKind::Local(_, pat, _) => Some(bound_ids(pat)),
+            _ => None,
+        })
+        .flatten()
+        .collect::<FxHashSet<_>>();
+    let mut apply_check = ApplyCheck {
+        compilation,
+        within_locals: &used.locals,
+        within_bindings: &bound,
+        allowed: true,
+    };
+    for stmt in apply {
+        apply_check.visit_stmt(stmt);
+    }
+    if !apply_check.allowed {
+        return None;
+    }
+
+    let source = unit
+        .sources
+        .find_by_offset(within[0].span.lo)
+        .expect("source should exist for offset");
+    let indent = line_indent(&source.contents, within[0].span.lo - source.offset);
+    let section = |stmts: &[Box<Stmt>]| {
+        let text = text(
+            &unit.sources,
+            Span {
+                lo: stmts[0].span.lo,
+                hi: stmts[stmts.len() - 1].span.hi,
+            },
+        );
+        text.lines()
+            .enumerate()
+            .map(|(i, line)| match (i, line.trim().is_empty()) {
+                (0, _) => line.to_string(),
+                (_, true) => String::new(),
+                (_, false) => format!("    {line}"),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    let new_text = format!(
+        "within {{\n{indent}    {}\n{indent}}} apply {{\n{indent}    {}\n{indent}}}",
+        section(within),
+        section(apply)
+    );
+    let edit_span = Span {
+        lo: within[0].span.lo,
+        hi: apply[apply.len() - 1].span.hi,
+    };
+
+    Some(CodeAction {
+        title: "Wrap in `within`/`apply`".to_string(),
+        edit: Some(WorkspaceEdit {
+            changes: vec![(
+                source_name.to_string(),
+                vec![TextEdit {
+                    new_text,
+                    range: into_range(encoding, edit_span, &unit.sources),
+                }],
+            )],
+        }),
+        kind: Some(CodeActionKind::RefactorRewrite),
+        is_preferred: None,
+    })
+}
+
+fn is_unit(compilation: &Compilation, expr: &Expr) -> bool {
+    matches!(compilation.get_ty(expr.id), Some(Ty::Tuple(items)) if items.is_empty())
+}
+
+/// Gets the arrow type of the callee if it is an operation.
+fn operation_callee<'a>(compilation: &'a Compilation, callee: &Expr) -> Option<&'a Arrow> {
+    match compilation.get_ty(callee.id) {
+        Some(Ty::Arrow(arrow)) if matches!(arrow.kind, hir::CallableKind::Operation) => {
+            Some(&**arrow)
+        }
+        _ => None,
+    }
+}
+
+/// Finds the innermost block whose braces enclose the selection.
+struct BlockFinder<'a> {
+    span: Span,
+    block: Option<&'a Block>,
+}
+
+impl<'a> Visitor<'a> for BlockFinder<'a> {
+    fn visit_block(&mut self, block: &'a Block) {
+        if block.span.lo < self.span.lo && self.span.hi < block.span.hi {
+            self.block = Some(block);
+        }
+        walk_block(self, block);
+    }
+}
+
+/// Checks that statements can appear in a `within` block, counting the operations they call.
+struct InvertibilityCheck<'a> {
+    compilation: &'a Compilation,
+    invertible: bool,
+    operation_calls: usize,
+}
+
+impl InvertibilityCheck<'_> {
+    fn check_stmts(&mut self, stmts: &[Box<Stmt>]) {
+        for stmt in stmts {
+            match &*stmt.kind {
+                StmtKind::Empty => {}
+                StmtKind::Local(Mutability::Immutable, _, init) => self.visit_expr(init),
+                StmtKind::Expr(expr) | StmtKind::Semi(expr) => self.check_stmt_expr(expr),
+                StmtKind::Local(Mutability::Mutable, ..)
+                | StmtKind::Item(_)
+                | StmtKind::Qubit(..)
+                | StmtKind::Err => self.invertible = false,
+            }
+        }
+    }
+
+    /// Checks the expression of a statement, which is the only place an operation can be called.
+    fn check_stmt_expr(&mut self, expr: &Expr) {
+        match &*expr.kind {
+            ExprKind::Call(callee, args) => match operation_callee(self.compilation, callee) {
+                Some(arrow) => {
+                    self.operation_calls += 1;
+                    let adjointable = match &arrow.functors {
+                        FunctorSet::Value(functors) | FunctorSet::Param(_, functors) => {
+                            functors.contains(&hir::Functor::Adj)
+                        }
+                        FunctorSet::Infer(_) => false,
+                    };
+                    self.invertible &= adjointable;
+                    self.visit_expr(callee);
+                    self.visit_expr(args);
+                }
+                None => self.visit_expr(expr),
+            },
+            ExprKind::Block(block) => self.check_stmts(&block.stmts),
+            ExprKind::Conjugate(within, apply) => {
+                self.check_stmts(&within.stmts);
+                self.check_stmts(&apply.stmts);
+            }
+            ExprKind::For(_, iterable, body) => {
+                self.visit_expr(iterable);
+                self.check_stmts(&body.stmts);
+            }
+            ExprKind::If(cond, body, otherwise) => {
+                self.visit_expr(cond);
+                self.check_stmts(&body.stmts);
+                if let Some(otherwise) = otherwise {
+                    self.check_stmt_expr(otherwise);
+                }
+            }
+            ExprKind::Paren(inner) => self.check_stmt_expr(inner),
+            _ => self.visit_expr(expr),
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for InvertibilityCheck<'_> {
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        match &*expr.kind {
+            ExprKind::Assign(..)
+            | ExprKind::AssignOp(..)
+            | ExprKind::AssignUpdate(..)
+            | ExprKind::Repeat(..)
+            | ExprKind::While(..)
+            | ExprKind::Return(_) => self.invertible = false,
+            ExprKind::Call(callee, _) if operation_callee(self.compilation, callee).is_some() => {
+                self.invertible = false;
+            }
+            _ => walk_expr(self, expr),
+        }
+    }
+}
+
+/// Collects the local variables that are referred to.
+struct References<'a> {
+    compilation: &'a Compilation,
+    locals: FxHashSet<NodeId>,
+}
+
+impl<'a> Visitor<'a> for References<'_> {
+    fn visit_path(&mut self, path: &'a ast::Path) {
+        // A reference to a field of a local is a path whose first segment is the local.
+        let id = match &path.segments {
+            None => Some(path.id),
+            Some(segments) => segments.first().map(|first| first.id),
+        };
+        if let Some(Res::Local(local)) = id.and_then(|id| self.compilation.get_res(id)) {
+            self.locals.insert(*local);
+        }
+        walk_path(self, path);
+    }
+}
+
+/// Checks that statements can appear in an `apply` block after the `within` block.
+struct ApplyCheck<'a> {
+    compilation: &'a Compilation,
+    within_locals: &'a FxHashSet<NodeId>,
+    within_bindings: &'a FxHashSet<NodeId>,
+    allowed: bool,
+}
+
+impl<'a> Visitor<'a> for ApplyCheck<'_> {
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        match &*expr.kind {
+            ExprKind::Return(_) => self.allowed = false,
+            ExprKind::Assign(lhs, _)
+            | ExprKind::AssignOp(_, lhs, _)
+            | ExprKind::AssignUpdate(lhs, ..) => {
+                if local(self.compilation, lhs).is_some_and(|id| self.within_locals.contains(&id)) {
+                    self.allowed = false;
+                }
+            }
+            _ => {}
+        }
+        walk_expr(self, expr);
+    }
+
+    fn visit_path(&mut self, path: &'a ast::Path) {
+        let mut references = References {
+            compilation: self.compilation,
+            locals: FxHashSet::default(),
+        };
+        references.visit_path(path);
+        if references
+            .locals
+            .iter()
+            .any(|id| self.within_bindings.contains(id))
+        {
+            self.allowed = false;
+        }
+    }
+}
@@ -0,0 +1,324 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use qsc::{
+    ast::{ItemKind, Namespace, TopLevelNode},
+    display::Lookup,
+    hir::{
+        self,
+        ty::{Prim, Ty},
+        Attr, CallableDecl, CallableKind, PatKind,
+    },
+    line_column::{Encoding, Position, Range},
+    resolve::Res,
+    Span,
+};
+
+use super::inline::line_indent;
+use crate::{
+    compilation::{Compilation, CompilationKind},
+    protocol::{CodeAction, CodeActionKind, TextEdit, WorkspaceEdit},
+    qsc_utils::into_range,
+};
+
+/// The name of the file that tests are generated in, next to the file of the callable under test.
+const TESTS_FILE_NAME: &str = "Tests.qs";
+
+/// Offers to generate a test for the callable whose name is under the cursor, either right after the callable or in
+/// the `Tests.qs` file next to it.
+///
+/// The test allocates a qubit or an array of qubits for each qubit parameter, binds an example value for each other
+/// parameter, calls the callable and then checks its result and that the qubits were returned to |0⟩ with `Fact`.
+/// The test is only offered for callables without type parameters whose parameters all have simple example values.
+///
+/// The tests file is offered in projects with more than one file, where every file in the directory is compiled
+/// together. It is created if it doesn't exist yet.
+#[allow(clippy::too_many_lines)]
+pub(super) fn generate_test(
+    compilation: &Compilation,
+    source_name: &str,
+    span: Span,
+    encoding: Encoding,
+) -> Vec<CodeAction> {
+    let unit = compilation.user_unit();
+    let Some((namespace, item, name)) = unit.ast.package.nodes.iter().find_map(|node| {
+        let TopLevelNode::Namespace(namespace) = node else {
+            return None;
+        };
+        namespace.items.iter().find_map(|item| match &*item.kind {
+            ItemKind::Callable(decl)
+                if decl.name.span.lo <= span.lo && span.lo <= decl.name.span.hi =>
+            {
+                Some((namespace, item, &decl.name))
+            }
+            _ => None,
+        })
+    }) else {
+        return Vec::new();
+    };
+    let Some(Res::Item(item_id, _)) = compilation.get_res(name.id) else {
+        return Vec::new();
+    };
+    let (hir_item, ..) = compilation.resolve_item_relative_to_user_package(item_id);
+    let hir::ItemKind::Callable(decl) = &hir_item.kind else {
+        return Vec::new();
+    };
+    let is_test = hir_item.attrs.contains(&Attr::Test);
+    let Some(params) = params(&decl.input).filter(|_| decl.generics.is_empty() && !is_test) else {
+        return Vec::new();
+    };
+
+    let source = unit
+        .sources
+        .find_by_offset(item.span.lo)
+        .expect("source should exist for offset");
+    let indent = line_indent(&source.contents, item.span.lo - source.offset);
+    let test = test_callable(decl, &decl.name.name, &params);
+    let mut actions = vec![code_action(
+        format!("Generate test for `{}`", decl.name.name),
+        source_name,
+        into_range(
+            encoding,
+            Span {
+                lo: item.span.hi,
+                hi: item.span.hi,
+            },
+            &unit.sources,
+        ),
+        format!("\n\n{}", indented(&test, indent)),
+    )];
+
+    let Some(dir_len) = source_name.rfind('/') else {
+        return actions;
+    };
+    let tests_name = format!("{}{TESTS_FILE_NAME}", &source_name[..=dir_len]);
+    if tests_name == source_name {
+        return actions;
+    }
+    // The tests file has its own namespace, so it calls the callable by its qualified name.
+    let callee = format!("{}.{}", qualified_name(namespace), decl.name.name);
+    let test = test_callable(decl, &callee, &params);
+    let title = format!(
+        "Generate test for `{}` in `{TESTS_FILE_NAME}`",
+        decl.name.name
+    );
+    let start = Position { line: 0, column: 0 };
+    if let Some(tests_source) = unit.sources.find_by_name(&tests_name) {
+        // Add the test after the last item in the tests file, or at its start if it has none.
+        let last_item = unit
+            .ast
+            .package
+            .nodes
+            .iter()
+            .rev()
+            .find_map(|node| match node {
+                TopLevelNode::Namespace(namespace)
+                    if unit
+                        .sources
+                        .find_by_offset(namespace.span.lo)
+                        .is_some_and(|source| source.name == tests_source.name) =>
+                {
+                    namespace.items.last()
+                }
+                _ => None,
+            });
+        actions.push(match last_item {
+            Some(item) => {
+                let indent =
+                    line_indent(&tests_source.contents, item.span.lo - tests_source.offset);
+                code_action(
+                    title,
+                    &tests_name,
+                    into_range(
+                        encoding,
+                        Span {
+                            lo: item.span.hi,
+                            hi: item.span.hi,
+                        },
+                        &unit.sources,
+                    ),
+                    format!("\n\n{}", indented(&test, indent)),
+                )
+            }
+            None => code_action(
+                title,
+                &tests_name,
+                Range { start, end: start },
+                format!("{test}\n"),
+            ),
+        });
+    } else if matches!(compilation.kind, CompilationKind::OpenProject { .. })
+        && unit.sources.iter().count() > 1
+    {
+        actions.push(code_action(
+            title,
+            &tests_name,
+            Range { start, end: start },
+            format!("{test}\n"),
+        ));
+    }
+    actions
+}
+
+fn code_action(title: String, source_name: &str, range: Range, new_text: String) -> CodeAction {
+    CodeAction {
+        title,
+        edit: Some(WorkspaceEdit {
+            changes: vec![(source_name.to_string(), vec![TextEdit { new_text, range }])],
+        }),
+        kind: Some(CodeActionKind::Refactor),
+        is_preferred: None,
+    }
+}
+
+/// A parameter of the callable under test, and how the test provides an argument for it.
+enum Param<'a> {
+    Qubit(&'a str),
+    Qubits(&'a str),
+    Value(&'a str, String),
+}
+
+/// Gets the parameters of the callable, or `None` if any of them has no simple example value.
+fn params(input: &hir::Pat) -> Option<Vec<Param>> {
+    fn collect<'a>(pat: &'a hir::Pat, params: &mut Vec<Param<'a>>) -> Option<()> {
+        match &pat.kind {
+            PatKind::Tuple(items) => items.iter().try_for_each(|item| collect(item, params)),
+            PatKind::Bind(ident) => {
+                let name = ident.name.as_ref();
+                params.push(match &pat.ty {
+                    Ty::Prim(Prim::Qubit) => Param::Qubit(name),
+                    Ty::Array(item) if **item == Ty::Prim(Prim::Qubit) => Param::Qubits(name),
+                    ty => Param::Value(name, example_value(ty)?),
+                });
+                Some(())
+            }
+            PatKind::Discard | PatKind::Err => None,
+        }
+    }
+
+    let mut params = Vec::new();
+    collect(input, &mut params)?;
+    Some(params)
+}
+
+/// An example value of the type, written as Q# source, if the type has a simple one.
+fn example_value(ty: &Ty) -> Option<String> {
+    match ty {
+        Ty::Prim(prim) => Some(
+            match prim {
+                Prim::BigInt => "0L",
+                Prim::Bool => "false",
+                Prim::Double => "0.0",
+                Prim::Int => "0",
+                Prim::Pauli => "PauliZ",
+                Prim::Range => "0..1",
+                Prim::Result => "Zero",
+                Prim::String => "\"\"",
+                Prim::Qubit | Prim::RangeTo | Prim::RangeFrom | Prim::RangeFull => return None,
+            }
+            .to_string(),
+        ),
+        // A single item lets the type of the array be inferred.
+        Ty::Array(item) => Some(format!("[{}]", example_value(item)?)),
+        Ty::Tuple(items) if items.len() == 1 => Some(format!("({},)", example_value(&items[0])?)),
+        Ty::Tuple(items) => {
+            let items = items
+                .iter()
+                .map(example_value)
+                .collect::<Option<Vec<_>>>()?;
+            Some(format!("({})", items.join(", ")))
+        }
+        _ => None,
+    }
+}
+
+/// Writes a test callable that calls the callee with the given parameters.
+fn test_callable(decl: &CallableDecl, callee: &str, params: &[Param]) -> String {
+    let mut lines = Vec::new();
+    let mut args = Vec::new();
+    let mut qubits = Vec::new();
+    let mut qubit_arrays = Vec::new();
+    for param in params {
+        match param {
+            Param::Qubit(name) => {
+                lines.push(format!("use {name} = Qubit();"));
+                qubits.push(*name);
+                args.push(*name);
+            }
+            Param::Qubits(name) => {
+                lines.push(format!("use {name} = Qubit[2];"));
+                qubit_arrays.push(*name);
+                args.push(*name);
+            }
+            Param::Value(name, value) => {
+                lines.push(format!("let {name} = {value};"));
+                args.push(*name);
+            }
+        }
+    }
+
+    let call = format!("{callee}({})", args.join(", "));
+    // Results that can be compared are checked against an example value, to be replaced with the expected one.
+    let expected = match &decl.output {
+        Ty::Prim(Prim::Range) => None,
+        ty @ Ty::Prim(_) => example_value(ty),
+        _ => None,
+    };
+    match (&decl.output, expected) {
+        (Ty::Tuple(items), _) if items.is_empty() => lines.push(format!("{call};")),
+        (_, Some(expected)) => {
+            lines.push(format!("let result = {call};"));
+            lines.push(format!(
+                "Std.Diagnostics.Fact(result == {expected}, \"`{}` should return the expected result\");",
+                decl.name.name
+            ));
+        }
+        _ => lines.push(format!("let result = {call};")),
+    }
+
+    if !qubits.is_empty() || !qubit_arrays.is_empty() {
+        let mut all_qubits = Vec::new();
+        if !qubits.is_empty() {
+            all_qubits.push(format!("[{}]", qubits.join(", ")));
+        }
+        all_qubits.extend(qubit_arrays.iter().map(ToString::to_string));
+        lines.push(format!(
+            "Std.Diagnostics.Fact(Std.Diagnostics.CheckAllZero({}), \"qubits should be returned to the |0⟩ state\");",
+            all_qubits.join(" + ")
+        ));
+    }
+
+    // Tests that allocate qubits or call operations have to be operations themselves.
+    let kind =
+        if decl.kind == CallableKind::Function && qubits.is_empty() && qubit_arrays.is_empty() {
+            "function"
+        } else {
+            "operation"
+        };
+    format!(
+        "@Test()\n{kind} Test{}() : Unit {{\n{}\n}}",
+        decl.name.name,
+        lines
+            .iter()
+            .map(|line| format!("    {line}"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    )
+}
+
+/// Indents every line, since the text is inserted after a blank line.
+fn indented(text: &str, indent: &str) -> String {
+    text.lines()
+        .map(|line| format!("{indent}{line}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn qualified_name(namespace: &Namespace) -> String {
+    namespace
+        .name
+        .iter()
+        .map(|ident| ident.name.as_ref())
+        .collect::<Vec<_>>()
+        .join(".")
+}
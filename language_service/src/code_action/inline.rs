@@ -0,0 +1,452 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use std::rc::Rc;
+
+use qsc::{
+    ast::{
+        self,
+        visit::{walk_expr, walk_item, walk_namespace, walk_pat, walk_path, Visitor},
+        CallableBody, CallableDecl, Expr, ExprKind, Functor, Ident, ItemKind, NodeId, PatKind,
+        PathKind, StmtKind, UnOp,
+    },
+    display::Lookup,
+    hir::{self, ty::Ty},
+    line_column::Encoding,
+    resolve::Res,
+    SourceMap, Span,
+};
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::{
+    compilation::Compilation,
+    protocol::{CodeAction, CodeActionKind, TextEdit, WorkspaceEdit},
+    qsc_utils::into_range,
+};
+
+/// Offers to replace the innermost call at the start of the range with a block containing the body of the called
+/// callable, when it is declared with a body block in the same namespace as the call.
+///
+/// Parameters are substituted with the arguments that are plain names or literals, and bound to the other arguments
+/// at the start of the block. Calls through the `Adjoint` functor or a single `Controlled` functor are only inlined
+/// when the body consists of operation calls, which are then reversed or controlled one by one.
+pub(super) fn inline_call(
+    compilation: &Compilation,
+    source_name: &str,
+    span: Span,
+    encoding: Encoding,
+) -> Option<CodeAction> {
+    let unit = compilation.user_unit();
+    let mut finder = CallFinder {
+        compilation,
+        offset: span.lo,
+        namespace: None,
+        call: None,
+        callables: FxHashMap::default(),
+    };
+    finder.visit_package(&unit.ast.package);
+    let (call, call_namespace) = finder.call?;
+    let ExprKind::Call(callee, args) = &*call.kind else {
+        unreachable!("only calls should be found");
+    };
+
+    let (callee, adjoint, controlled) = strip_functors(callee);
+    let ExprKind::Path(PathKind::Ok(path)) = &*callee.kind else {
+        return None;
+    };
+    let Some(Res::Item(item_id, _)) = compilation.get_res(path.id) else {
+        return None;
+    };
+    let (decl, namespace) = finder.callables.get(item_id)?;
+    if item_id.package.is_some() || call_namespace != *namespace {
+        return None;
+    }
+    let CallableBody::Block(body) = &*decl.body else {
+        return None;
+    };
+    if contains_return(body) {
+        return None;
+    }
+
+    let source_map = &unit.sources;
+    let (controls, args) = match controlled {
+        0 => (None, &**args),
+        1 => match &*args.kind {
+            ExprKind::Tuple(items) if items.len() == 2 && is_simple(&items[0]) => {
+                (Some(text(source_map, items[0].span)), &*items[1])
+            }
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    let mut inliner = Inliner {
+        compilation,
+        source_map,
+        substitutions: FxHashMap::default(),
+        edits: Vec::new(),
+    };
+    let bindings = inliner.bind_params(decl, args, &bound_names(body));
+    inliner.visit_block(body);
+
+    let stmts = if adjoint || controls.is_some() {
+        inliner.functor_stmts(body, adjoint, controls.as_deref())?
+    } else {
+        inliner.stmts(decl, body)
+    };
+
+    let call_source = source_map
+        .find_by_offset(call.span.lo)
+        .expect("source should exist for offset");
+    let indent = line_indent(&call_source.contents, call.span.lo - call_source.offset);
+    let lines = bindings
+        .into_iter()
+        .chain(stmts)
+        .flat_map(|stmt| {
+            stmt.lines()
+                .map(|line| format!("\n{indent}    {line}"))
+                .collect::<Vec<_>>()
+        })
+        .collect::<String>();
+    let new_text = if lines.is_empty() {
+        "{}".to_string()
+    } else {
+        format!("{{{lines}\n{indent}}}")
+    };
+
+    Some(CodeAction {
+        title: format!("Inline call to `{}`", decl.name.name),
+        edit: Some(WorkspaceEdit {
+            changes: vec![(
+                source_name.to_string(),
+                vec![TextEdit {
+                    new_text,
+                    range: into_range(encoding, call.span, source_map),
+                }],
+            )],
+        }),
+        kind: Some(CodeActionKind::RefactorInline),
+        is_preferred: None,
+    })
+}
+
+/// Finds the innermost call containing the offset, and the callables declared in the package, along with the names
+/// of the namespaces they are in.
+struct CallFinder<'a> {
+    compilation: &'a Compilation,
+    offset: u32,
+    namespace: Option<Rc<str>>,
+    call: Option<(&'a Expr, Option<Rc<str>>)>,
+    callables: FxHashMap<hir::ItemId, (&'a CallableDecl, Option<Rc<str>>)>,
+}
+
+impl<'a> Visitor<'a> for CallFinder<'a> {
+    fn visit_namespace(&mut self, namespace: &'a ast::Namespace) {
+        self.namespace = Some(
+            namespace
+                .name
+                .iter()
+                .map(|ident| ident.name.as_ref())
+                .collect::<Vec<_>>()
+                .join(".")
+                .into(),
+        );
+        walk_namespace(self, namespace);
+        self.namespace = None;
+    }
+
+    fn visit_item(&mut self, item: &'a ast::Item) {
+        if let ItemKind::Callable(decl) = &*item.kind {
+            if let Some(Res::Item(item_id, _)) = self.compilation.get_res(decl.name.id) {
+                self.callables
+                    .insert(*item_id, (decl, self.namespace.clone()));
+            }
+        }
+        walk_item(self, item);
+    }
+
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        if matches!(&*expr.kind, ExprKind::Call(..))
+            && expr.span.lo <= self.offset
+            && self.offset <= expr.span.hi
+        {
+            self.call = Some((expr, self.namespace.clone()));
+        }
+        walk_expr(self, expr);
+    }
+}
+
+/// Removes the functors applied to a callee, returning whether the adjoint is applied and how many times the
+/// controlled functor is applied.
+fn strip_functors(mut callee: &Expr) -> (&Expr, bool, usize) {
+    let mut adjoint = false;
+    let mut controlled = 0;
+    loop {
+        callee = match &*callee.kind {
+            ExprKind::UnOp(UnOp::Functor(Functor::Adj), inner) => {
+                adjoint = !adjoint;
+                inner
+            }
+            ExprKind::UnOp(UnOp::Functor(Functor::Ctl), inner) => {
+                controlled += 1;
+                inner
+            }
+            ExprKind::Paren(inner) => inner,
+            _ => return (callee, adjoint, controlled),
+        };
+    }
+}
+
+/// Whether the expression can be repeated where the parameter it is passed for is used, because it is a literal or
+/// a name without side effects.
+fn is_simple(expr: &Expr) -> bool {
+    match &*expr.kind {
+        ExprKind::Lit(_) => true,
+        ExprKind::Path(PathKind::Ok(path)) => path.segments.is_none(),
+        ExprKind::Paren(inner) => is_simple(inner),
+        _ => false,
+    }
+}
+
+fn simple_name(expr: &Expr) -> Option<&str> {
+    match &*expr.kind {
+        ExprKind::Path(PathKind::Ok(path)) => Some(&path.name.name),
+        ExprKind::Paren(inner) => simple_name(inner),
+        _ => None,
+    }
+}
+
+fn contains_return(block: &ast::Block) -> bool {
+    struct ReturnFinder(bool);
+    impl Visitor<'_> for ReturnFinder {
+        fn visit_expr(&mut self, expr: &Expr) {
+            if matches!(&*expr.kind, ExprKind::Return(_)) {
+                self.0 = true;
+            }
+            walk_expr(self, expr);
+        }
+    }
+
+    let mut finder = ReturnFinder(false);
+    finder.visit_block(block);
+    finder.0
+}
+
+/// Gets the names bound anywhere in the block.
+fn bound_names(block: &ast::Block) -> FxHashSet<Rc<str>> {
+    struct BindingFinder(FxHashSet<Rc<str>>);
+    impl Visitor<'_> for BindingFinder {
+        fn visit_pat(&mut self, pat: &ast::Pat) {
+            if let PatKind::Bind(ident, _) = &*pat.kind {
+                self.0.insert(ident.name.clone());
+            }
+            walk_pat(self, pat);
+        }
+    }
+
+    let mut finder = BindingFinder(FxHashSet::default());
+    finder.visit_block(block);
+    finder.0
+}
+
+pub(super) fn line_indent(contents: &str, offset: u32) -> &str {
+    let line_start = contents[..offset as usize].rfind('\n').map_or(0, |i| i + 1);
+    let line = &contents[line_start..];
+    &line[..line.len() - line.trim_start().len()]
+}
+
+pub(super) fn text(source_map: &SourceMap, span: Span) -> String {
+    let source = source_map
+        .find_by_offset(span.lo)
+        .expect("source should exist for offset");
+    source.contents[(span.lo - source.offset) as usize..(span.hi - source.offset) as usize]
+        .to_string()
+}
+
+struct Inliner<'a> {
+    compilation: &'a Compilation,
+    source_map: &'a SourceMap,
+    /// The text replacing each reference to a substituted parameter, by the node ID of the parameter.
+    substitutions: FxHashMap<NodeId, String>,
+    /// The references to substituted parameters, with the text replacing them.
+    edits: Vec<(Span, String)>,
+}
+
+impl Inliner<'_> {
+    /// Records the parameters of the callable that can be substituted with their argument, and returns the
+    /// statements binding the others.
+    fn bind_params(
+        &mut self,
+        decl: &CallableDecl,
+        args: &Expr,
+        bound_names: &FxHashSet<Rc<str>>,
+    ) -> Vec<String> {
+        let params = match &*decl.input.kind {
+            PatKind::Tuple(items) => items.iter().map(AsRef::as_ref).collect(),
+            PatKind::Paren(inner) => vec![&**inner],
+            _ => vec![&*decl.input],
+        };
+        let args = match &*args.kind {
+            ExprKind::Tuple(items) if items.len() == params.len() => {
+                items.iter().map(AsRef::as_ref).collect()
+            }
+            ExprKind::Paren(inner) if params.len() == 1 => vec![&**inner],
+            _ => {
+                return vec![format!(
+                    "let {} = {};",
+                    text(self.source_map, decl.input.span),
+                    text(self.source_map, args.span)
+                )];
+            }
+        };
+
+        let param_names = params
+            .iter()
+            .filter_map(|param| match &*param.kind {
+                PatKind::Bind(ident, _) => Some(ident),
+                _ => None,
+            })
+            .collect::<Vec<&Box<Ident>>>();
+        let mut bound_params = Vec::new();
+        let mut bound_args = Vec::new();
+        for (param, arg) in params.into_iter().zip(args) {
+            match &*param.kind {
+                PatKind::Bind(ident, _) if is_simple(arg) => {
+                    let captured = simple_name(arg).is_some_and(|name| {
+                        bound_names.contains(name)
+                            || param_names
+                                .iter()
+                                .any(|other| other.id != ident.id && &*other.name == name)
+                    });
+                    if !captured {
+                        self.substitutions
+                            .insert(ident.id, text(self.source_map, arg.span));
+                        continue;
+                    }
+                }
+                PatKind::Discard(_) if is_simple(arg) => continue,
+                _ => {}
+            }
+            bound_params.push(text(self.source_map, param.span));
+            bound_args.push(text(self.source_map, arg.span));
+        }
+
+        match bound_params.len() {
+            0 => Vec::new(),
+            1 => vec![format!("let {} = {};", bound_params[0], bound_args[0])],
+            _ => vec![format!(
+                "let ({}) = ({});",
+                bound_params.join(", "),
+                bound_args.join(", ")
+            )],
+        }
+    }
+
+    /// Gets the statements of the body with the functors applied, if it consists only of operation calls.
+    fn functor_stmts(
+        &self,
+        body: &ast::Block,
+        adjoint: bool,
+        controls: Option<&str>,
+    ) -> Option<Vec<String>> {
+        let mut stmts = Vec::new();
+        for stmt in &body.stmts {
+            let (StmtKind::Semi(expr) | StmtKind::Expr(expr)) = &*stmt.kind else {
+                return None;
+            };
+            let ExprKind::Call(callee, args) = &*expr.kind else {
+                return None;
+            };
+            if !self.is_operation(callee) {
+                return None;
+            }
+            let callee = if adjoint {
+                match &*callee.kind {
+                    ExprKind::UnOp(UnOp::Functor(Functor::Adj), inner) => self.text(inner.span),
+                    _ => format!("Adjoint {}", self.text(callee.span)),
+                }
+            } else {
+                self.text(callee.span)
+            };
+            stmts.push(match controls {
+                Some(controls) => {
+                    format!("Controlled {callee}({controls}, {});", self.text(args.span))
+                }
+                None => format!("{callee}{};", self.text(args.span)),
+            });
+        }
+        if adjoint {
+            stmts.reverse();
+        }
+        Some(stmts)
+    }
+
+    /// Gets the statements of the body, with the indentation of their lines relative to the first one.
+    fn stmts(&self, decl: &CallableDecl, body: &ast::Block) -> Vec<String> {
+        let source = self
+            .source_map
+            .find_by_offset(decl.span.lo)
+            .expect("source should exist for offset");
+        body.stmts
+            .iter()
+            .map(|stmt| {
+                let indent = line_indent(&source.contents, stmt.span.lo - source.offset);
+                self.text(stmt.span)
+                    .lines()
+                    .enumerate()
+                    .map(|(i, line)| {
+                        if i == 0 {
+                            line
+                        } else {
+                            line.strip_prefix(indent).unwrap_or(line)
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+            .collect()
+    }
+
+    fn is_operation(&self, callee: &Expr) -> bool {
+        matches!(
+            self.compilation.get_ty(callee.id),
+            Some(Ty::Arrow(arrow)) if arrow.kind == hir::CallableKind::Operation
+        )
+    }
+
+    /// Gets the text of the span with the references to substituted parameters in it replaced.
+    fn text(&self, span: Span) -> String {
+        let mut edits = self
+            .edits
+            .iter()
+            .filter(|(edit_span, _)| span.lo <= edit_span.lo && edit_span.hi <= span.hi)
+            .collect::<Vec<_>>();
+        edits.sort_by_key(|(edit_span, _)| std::cmp::Reverse(edit_span.lo));
+
+        let mut text = text(self.source_map, span);
+        for (edit_span, new_text) in edits {
+            let lo = (edit_span.lo - span.lo) as usize;
+            let hi = (edit_span.hi - span.lo) as usize;
+            text.replace_range(lo..hi, new_text);
+        }
+        text
+    }
+}
+
+impl<'a> Visitor<'a> for Inliner<'_> {
+    fn visit_path(&mut self, path: &'a ast::Path) {
+        // A reference to a field of a local is a path whose first segment is the local.
+        let ident = match &path.segments {
+            None => Some((path.id, path.name.span)),
+            Some(segments) => segments.first().map(|first| (first.id, first.span)),
+        };
+        if let Some((id, ident_span)) = ident {
+            if let Some(Res::Local(local)) = self.compilation.get_res(id) {
+                if let Some(new_text) = self.substitutions.get(local) {
+                    self.edits.push((ident_span, new_text.clone()));
+                }
+            }
+        }
+        walk_path(self, path);
+    }
+}
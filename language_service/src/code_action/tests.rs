@@ -0,0 +1,708 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#![allow(clippy::needless_raw_string_hashes)]
+
+use super::get_code_actions;
+use crate::{
+    protocol::{CodeActionKind, TextEdit},
+    test_utils::{
+        compile_project_with_markers, compile_with_fake_stdlib_and_markers_no_cursor,
+        compile_with_markers,
+    },
+    Encoding,
+};
+use expect_test::{expect, Expect};
+use qsc::line_column::{Position, Range};
+
+fn offset(source: &str, position: Position) -> usize {
+    let line_start: usize = source
+        .split_inclusive('\n')
+        .take(position.line as usize)
+        .map(str::len)
+        .sum();
+    line_start + position.column as usize
+}
+
+/// Applies the first code action of the given kind offered at the cursor position, indicated by a `↘` marker in the
+/// source text, or for the selection between a pair of `◉` markers, returning the title of the action followed by the
+/// edited source text.
+fn apply_action(source_with_markers: &str, is_kind: fn(&CodeActionKind) -> bool) -> Option<String> {
+    let (compilation, range) = if source_with_markers.contains('↘') {
+        let (compilation, cursor_position, _) = compile_with_markers(source_with_markers, false);
+        let range = Range {
+            start: cursor_position,
+            end: cursor_position,
+        };
+        (compilation, range)
+    } else {
+        let (compilation, ranges) =
+            compile_with_fake_stdlib_and_markers_no_cursor(source_with_markers, false);
+        (compilation, ranges[0])
+    };
+    let actions = get_code_actions(&compilation, "<source>", range, Encoding::Utf8);
+    let action = actions
+        .iter()
+        .find(|action| action.kind.as_ref().is_some_and(is_kind))?;
+    let edits = &action
+        .edit
+        .as_ref()
+        .expect("code action should have an edit")
+        .changes[0]
+        .1;
+    let [TextEdit { new_text, range }] = edits.as_slice() else {
+        panic!("expected a single edit, found {edits:?}");
+    };
+
+    let mut source = source_with_markers.replace(['↘', '◉'], "");
+    let start = offset(&source, range.start);
+    let end = offset(&source, range.end);
+    source.replace_range(start..end, new_text);
+    Some(format!("{}\n{source}", action.title))
+}
+
+fn is_inline(kind: &CodeActionKind) -> bool {
+    matches!(kind, CodeActionKind::RefactorInline)
+}
+
+fn is_rewrite(kind: &CodeActionKind) -> bool {
+    matches!(kind, CodeActionKind::RefactorRewrite)
+}
+
+fn is_refactor(kind: &CodeActionKind) -> bool {
+    matches!(kind, CodeActionKind::Refactor)
+}
+
+fn check_inline(source_with_markers: &str, expect: &Expect) {
+    let actual =
+        apply_action(source_with_markers, is_inline).expect("inline code action should be offered");
+    expect.assert_eq(&actual);
+}
+
+fn assert_no_inline(source_with_markers: &str) {
+    assert!(
+        apply_action(source_with_markers, is_inline).is_none(),
+        "inline code action should not be offered"
+    );
+}
+
+fn check_rewrite(source_with_markers: &str, expect: &Expect) {
+    let actual = apply_action(source_with_markers, is_rewrite)
+        .expect("rewrite code action should be offered");
+    expect.assert_eq(&actual);
+}
+
+fn assert_no_rewrite(source_with_markers: &str) {
+    assert!(
+        apply_action(source_with_markers, is_rewrite).is_none(),
+        "rewrite code action should not be offered"
+    );
+}
+
+#[test]
+fn inline_substitutes_simple_arguments() {
+    check_inline(
+        r#"
+        namespace Test {
+            operation Entangle(control : Qubit, target : Qubit) : Unit {
+                H(control);
+                CNOT(control, target);
+            }
+            operation Main() : Unit {
+                use (a, b) = (Qubit(), Qubit());
+                Ent↘angle(a, b);
+            }
+        }
+    "#,
+        &expect![[r#"
+            Inline call to `Entangle`
+
+                    namespace Test {
+                        operation Entangle(control : Qubit, target : Qubit) : Unit {
+                            H(control);
+                            CNOT(control, target);
+                        }
+                        operation Main() : Unit {
+                            use (a, b) = (Qubit(), Qubit());
+                            {
+                                H(a);
+                                CNOT(a, b);
+                            };
+                        }
+                    }
+                "#]],
+    );
+}
+
+#[test]
+fn inline_binds_complex_arguments() {
+    check_inline(
+        r#"
+        namespace Test {
+            function Scale(x : Double, factor : Double) : Double {
+                let scaled = x * factor;
+                scaled
+            }
+            function Main() : Double {
+                let y = 2.0;
+                Sca↘le(y + 1.0, 3.0)
+            }
+        }
+    "#,
+        &expect![[r#"
+            Inline call to `Scale`
+
+                    namespace Test {
+                        function Scale(x : Double, factor : Double) : Double {
+                            let scaled = x * factor;
+                            scaled
+                        }
+                        function Main() : Double {
+                            let y = 2.0;
+                            {
+                                let x : Double = y + 1.0;
+                                let scaled = x * 3.0;
+                                scaled
+                            }
+                        }
+                    }
+                "#]],
+    );
+}
+
+#[test]
+fn inline_binds_arguments_captured_by_body() {
+    check_inline(
+        r#"
+        namespace Test {
+            operation Prepare(q : Qubit) : Unit {
+                use target = Qubit();
+                CNOT(q, target);
+                Reset(target);
+            }
+            operation Main() : Unit {
+                use target = Qubit();
+                Prepare↘(target);
+            }
+        }
+    "#,
+        &expect![[r#"
+            Inline call to `Prepare`
+
+                    namespace Test {
+                        operation Prepare(q : Qubit) : Unit {
+                            use target = Qubit();
+                            CNOT(q, target);
+                            Reset(target);
+                        }
+                        operation Main() : Unit {
+                            use target = Qubit();
+                            {
+                                let q : Qubit = target;
+                                use target = Qubit();
+                                CNOT(q, target);
+                                Reset(target);
+                            };
+                        }
+                    }
+                "#]],
+    );
+}
+
+#[test]
+fn inline_adjoint_reverses_calls() {
+    check_inline(
+        r#"
+        namespace Test {
+            operation Prepare(q : Qubit) : Unit is Adj {
+                H(q);
+                Adjoint S(q);
+                T(q);
+            }
+            operation Main() : Unit {
+                use q = Qubit();
+                Adjoint Prep↘are(q);
+            }
+        }
+    "#,
+        &expect![[r#"
+            Inline call to `Prepare`
+
+                    namespace Test {
+                        operation Prepare(q : Qubit) : Unit is Adj {
+                            H(q);
+                            Adjoint S(q);
+                            T(q);
+                        }
+                        operation Main() : Unit {
+                            use q = Qubit();
+                            {
+                                Adjoint T(q);
+                                S(q);
+                                Adjoint H(q);
+                            };
+                        }
+                    }
+                "#]],
+    );
+}
+
+#[test]
+fn inline_controlled_controls_each_call() {
+    check_inline(
+        r#"
+        namespace Test {
+            operation Flip(q : Qubit, angle : Double) : Unit is Ctl {
+                X(q);
+                Rz(angle, q);
+            }
+            operation Main() : Unit {
+                use (ctls, q) = (Qubit[2], Qubit());
+                Controlled Fl↘ip(ctls, (q, 0.5));
+            }
+        }
+    "#,
+        &expect![[r#"
+            Inline call to `Flip`
+
+                    namespace Test {
+                        operation Flip(q : Qubit, angle : Double) : Unit is Ctl {
+                            X(q);
+                            Rz(angle, q);
+                        }
+                        operation Main() : Unit {
+                            use (ctls, q) = (Qubit[2], Qubit());
+                            {
+                                Controlled X(ctls, (q));
+                                Controlled Rz(ctls, (0.5, q));
+                            };
+                        }
+                    }
+                "#]],
+    );
+}
+
+#[test]
+fn inline_not_offered_for_adjoint_of_classical_statements() {
+    assert_no_inline(
+        r#"
+        namespace Test {
+            operation Rotate(q : Qubit) : Unit is Adj {
+                let angle = 0.5;
+                Rx(angle, q);
+            }
+            operation Main() : Unit {
+                use q = Qubit();
+                Adjoint Rot↘ate(q);
+            }
+        }
+    "#,
+    );
+}
+
+#[test]
+fn inline_not_offered_for_body_with_return() {
+    assert_no_inline(
+        r#"
+        namespace Test {
+            function Sign(x : Int) : Int {
+                if x < 0 {
+                    return -1;
+                }
+                1
+            }
+            function Main() : Int {
+                Si↘gn(3)
+            }
+        }
+    "#,
+    );
+}
+
+#[test]
+fn inline_not_offered_for_library_callable() {
+    assert_no_inline(
+        r#"
+        namespace Test {
+            operation Main() : Unit {
+                use q = Qubit();
+                H↘(q);
+            }
+        }
+    "#,
+    );
+}
+
+#[test]
+fn inline_not_offered_across_namespaces() {
+    assert_no_inline(
+        r#"
+        namespace Other {
+            operation Prepare(q : Qubit) : Unit {
+                H(q);
+            }
+        }
+        namespace Test {
+            open Other;
+            operation Main() : Unit {
+                use q = Qubit();
+                Prep↘are(q);
+            }
+        }
+    "#,
+    );
+}
+
+#[test]
+fn rewrite_loop_to_fold() {
+    check_rewrite(
+        r#"
+        namespace Test {
+            function Main() : Int {
+                let xs = [1, 2, 3];
+                mutable ↘sum = 0;
+                for x in xs {
+                    set sum += x;
+                }
+                sum
+            }
+        }
+    "#,
+        &expect![[r#"
+            Convert loop to `Fold`
+
+                    namespace Test {
+                        function Main() : Int {
+                            let xs = [1, 2, 3];
+                            let sum = Std.Arrays.Fold((sum, x) -> sum + x, 0, xs);
+                            sum
+                        }
+                    }
+                "#]],
+    );
+}
+
+#[test]
+fn rewrite_loop_to_mapped() {
+    check_rewrite(
+        r#"
+        namespace Test {
+            function Main() : Int[] {
+                let xs = [1, 2, 3];
+                mutable squares = [];
+                f↘or x in xs {
+                    set squares += [x * x];
+                }
+                squares
+            }
+        }
+    "#,
+        &expect![[r#"
+            Convert loop to `Mapped`
+
+                    namespace Test {
+                        function Main() : Int[] {
+                            let xs = [1, 2, 3];
+                            let squares = Std.Arrays.Mapped(x -> x * x, xs);
+                            squares
+                        }
+                    }
+                "#]],
+    );
+}
+
+#[test]
+fn rewrite_fold_to_loop() {
+    check_rewrite(
+        r#"
+        namespace Test {
+            function Main() : Int {
+                let xs = [1, 2, 3];
+                let ↘total = Std.Arrays.Fold((acc, x) -> acc + 2 * x, 0, xs);
+                total
+            }
+        }
+    "#,
+        &expect![[r#"
+            Convert `Fold` to loop
+
+                    namespace Test {
+                        function Main() : Int {
+                            let xs = [1, 2, 3];
+                            mutable total = 0;
+                            for x in xs {
+                                set total += 2 * x;
+                            }
+                            total
+                        }
+                    }
+                "#]],
+    );
+}
+
+#[test]
+fn rewrite_mapped_to_loop() {
+    check_rewrite(
+        r#"
+        namespace Test {
+            function Main() : Int[] {
+                let xs = [1, 2, 3];
+                let ↘doubled = Std.Arrays.Mapped(x -> 2 * x, xs);
+                doubled
+            }
+        }
+    "#,
+        &expect![[r#"
+            Convert `Mapped` to loop
+
+                    namespace Test {
+                        function Main() : Int[] {
+                            let xs = [1, 2, 3];
+                            mutable doubled = [];
+                            for x in xs {
+                                set doubled += [2 * x];
+                            }
+                            doubled
+                        }
+                    }
+                "#]],
+    );
+}
+
+#[test]
+fn rewrite_not_offered_when_loop_reads_other_mutable() {
+    assert_no_rewrite(
+        r#"
+        namespace Test {
+            function Main() : Int {
+                let xs = [1, 2, 3];
+                mutable scale = 2;
+                mutable ↘sum = 0;
+                for x in xs {
+                    set sum += scale * x;
+                }
+                sum
+            }
+        }
+    "#,
+    );
+}
+
+#[test]
+fn wrap_in_conjugate_uses_following_statements_as_apply() {
+    check_rewrite(
+        r#"
+        namespace Test {
+            operation Main() : Unit {
+                use (a, b) = (Qubit(), Qubit());
+                ◉H(a);
+                CNOT(a, b);◉
+                Rz(0.5, b);
+                Message("done");
+            }
+        }
+    "#,
+        &expect![[r#"
+            Wrap in `within`/`apply`
+
+                    namespace Test {
+                        operation Main() : Unit {
+                            use (a, b) = (Qubit(), Qubit());
+                            within {
+                                H(a);
+                                CNOT(a, b);
+                            } apply {
+                                Rz(0.5, b);
+                                Message("done");
+                            }
+                        }
+                    }
+                "#]],
+    );
+}
+
+#[test]
+fn wrap_in_conjugate_reindents_nested_blocks() {
+    check_rewrite(
+        r#"
+        namespace Test {
+            operation Main() : Unit {
+                use qs = Qubit[3];
+                ◉for q in qs {
+                    H(q);
+                }◉
+                Controlled Z([qs[0], qs[1]], qs[2]);
+            }
+        }
+    "#,
+        &expect![[r#"
+            Wrap in `within`/`apply`
+
+                    namespace Test {
+                        operation Main() : Unit {
+                            use qs = Qubit[3];
+                            within {
+                                for q in qs {
+                                    H(q);
+                                }
+                            } apply {
+                                Controlled Z([qs[0], qs[1]], qs[2]);
+                            }
+                        }
+                    }
+                "#]],
+    );
+}
+
+#[test]
+fn wrap_in_conjugate_not_offered_for_measurement() {
+    assert_no_rewrite(
+        r#"
+        namespace Test {
+            operation Main() : Unit {
+                use q = Qubit();
+                ◉H(q);
+                M(q);◉
+                X(q);
+            }
+        }
+    "#,
+    );
+}
+
+#[test]
+fn wrap_in_conjugate_not_offered_when_binding_used_after_selection() {
+    assert_no_rewrite(
+        r#"
+        namespace Test {
+            operation Main() : Unit {
+                use q = Qubit();
+                ◉let angle = 0.5;
+                Rx(angle, q);◉
+                Rz(angle, q);
+            }
+        }
+    "#,
+    );
+}
+
+#[test]
+fn generate_test_allocates_qubits_and_checks_they_are_reset() {
+    let actual = apply_action(
+        r#"
+        namespace Test {
+            operation ↘Prepare(angle : Double, q : Qubit, register : Qubit[]) : Unit {
+                Ry(angle, q);
+            }
+        }
+    "#,
+        is_refactor,
+    )
+    .expect("test generation should be offered");
+    expect![[r#"
+        Generate test for `Prepare`
+
+                namespace Test {
+                    operation Prepare(angle : Double, q : Qubit, register : Qubit[]) : Unit {
+                        Ry(angle, q);
+                    }
+
+                    @Test()
+                    operation TestPrepare() : Unit {
+                        let angle = 0.0;
+                        use q = Qubit();
+                        use register = Qubit[2];
+                        Prepare(angle, q, register);
+                        Std.Diagnostics.Fact(Std.Diagnostics.CheckAllZero([q] + register), "qubits should be returned to the |0⟩ state");
+                    }
+                }
+            "#]]
+    .assert_eq(&actual);
+}
+
+#[test]
+fn generate_test_checks_function_result() {
+    let actual = apply_action(
+        r#"
+        namespace Test {
+            function ↘Square(x : Int) : Int { x * x }
+        }
+    "#,
+        is_refactor,
+    )
+    .expect("test generation should be offered");
+    expect![[r#"
+        Generate test for `Square`
+
+                namespace Test {
+                    function Square(x : Int) : Int { x * x }
+
+                    @Test()
+                    function TestSquare() : Unit {
+                        let x = 0;
+                        let result = Square(x);
+                        Std.Diagnostics.Fact(result == 0, "`Square` should return the expected result");
+                    }
+                }
+            "#]]
+    .assert_eq(&actual);
+}
+
+#[test]
+fn generate_test_not_offered_for_generic_callable() {
+    assert!(apply_action(
+        r#"
+        namespace Test {
+            function ↘Identity<'T>(x : 'T) : 'T { x }
+        }
+    "#,
+        is_refactor,
+    )
+    .is_none());
+}
+
+#[test]
+fn generate_test_in_new_tests_file_of_project() {
+    let (compilation, cursor_uri, cursor_position, _) = compile_project_with_markers(
+        &[
+            (
+                "project/src/Main.qs",
+                "operation ↘Flip(q : Qubit) : Unit { X(q); }",
+            ),
+            ("project/src/Other.qs", "function Helper() : Unit {}"),
+        ],
+        false,
+    );
+    let actions = get_code_actions(
+        &compilation,
+        &cursor_uri,
+        Range {
+            start: cursor_position,
+            end: cursor_position,
+        },
+        Encoding::Utf8,
+    );
+    let action = actions
+        .iter()
+        .find(|action| action.title == "Generate test for `Flip` in `Tests.qs`")
+        .expect("test generation in the tests file should be offered");
+    let (uri, edits) = &action
+        .edit
+        .as_ref()
+        .expect("code action should have an edit")
+        .changes[0];
+    assert_eq!(uri, "project/src/Tests.qs");
+    let [TextEdit { new_text, range }] = edits.as_slice() else {
+        panic!("expected a single edit, found {edits:?}");
+    };
+    assert_eq!(range.start, Position { line: 0, column: 0 });
+    expect![[r#"
+        @Test()
+        operation TestFlip() : Unit {
+            use q = Qubit();
+            Main.Flip(q);
+            Std.Diagnostics.Fact(Std.Diagnostics.CheckAllZero([q]), "qubits should be returned to the |0⟩ state");
+        }
+    "#]]
+    .assert_eq(new_text);
+}
@@ -6,12 +6,13 @@ mod tests;
 
 use crate::{
     compilation::{Compilation, CompilationKind},
+    entry_point::entry_point_callable,
     protocol::{CodeLens, CodeLensCommand, OperationInfo},
     qsc_utils::into_range,
 };
 use qsc::{
     circuit::qubit_param_info,
-    hir::{Expr, ExprKind, ItemId, ItemKind, LocalItemId, Package, Res},
+    hir::{Expr, ExprKind, ItemId, ItemKind, LocalItemId, Package, PatKind, Res},
     line_column::Encoding,
 };
 
@@ -30,6 +31,7 @@ pub(crate) fn get_code_lenses(
     let source_span = compilation.package_span_of_source(source_name);
 
     let entry_item_id = entry_callable(package);
+    let parameterized_entry_item_id = parameterized_entry_callable(package);
 
     // Get callables in the current source file.
     let callables = package.items.iter().filter_map(|(item_id, item)| {
@@ -44,7 +46,14 @@ pub(crate) fn get_code_lenses(
                     let range = into_range(position_encoding, decl.span, &user_unit.sources);
                     let name = decl.name.name.clone();
 
-                    return Some((item, range, namespace, name, Some(item_id) == entry_item_id));
+                    return Some((
+                        item,
+                        range,
+                        namespace,
+                        name,
+                        Some(item_id) == entry_item_id,
+                        Some(item_id) == parameterized_entry_item_id,
+                    ));
                 }
             }
         }
@@ -52,43 +61,66 @@ pub(crate) fn get_code_lenses(
     });
 
     callables
-        .flat_map(|(item, range, namespace, name, is_entry_point)| {
-            if is_entry_point {
-                vec![
-                    CodeLens {
-                        range,
-                        command: CodeLensCommand::Run,
-                    },
-                    CodeLens {
-                        range,
-                        command: CodeLensCommand::Histogram,
-                    },
-                    CodeLens {
-                        range,
-                        command: CodeLensCommand::Estimate,
-                    },
-                    CodeLens {
-                        range,
-                        command: CodeLensCommand::Debug,
-                    },
-                    CodeLens {
-                        range,
-                        command: CodeLensCommand::Circuit(None),
-                    },
-                ]
-            } else {
-                if let Some((_, total_num_qubits)) = qubit_param_info(item) {
-                    return vec![CodeLens {
-                        range,
-                        command: CodeLensCommand::Circuit(Some(OperationInfo {
-                            operation: format!("{namespace}.{name}"),
-                            total_num_qubits,
-                        })),
-                    }];
+        .flat_map(
+            |(item, range, namespace, name, is_entry_point, is_parameterized_entry_point)| {
+                if is_entry_point {
+                    vec![
+                        CodeLens {
+                            range,
+                            command: CodeLensCommand::Run,
+                        },
+                        CodeLens {
+                            range,
+                            command: CodeLensCommand::Histogram,
+                        },
+                        CodeLens {
+                            range,
+                            command: CodeLensCommand::Estimate,
+                        },
+                        CodeLens {
+                            range,
+                            command: CodeLensCommand::Debug,
+                        },
+                        CodeLens {
+                            range,
+                            command: CodeLensCommand::Circuit(None),
+                        },
+                    ]
+                } else if is_parameterized_entry_point {
+                    // The arguments are prompted for by the editor, which then runs the program
+                    // with an entry expression. Circuits can't be generated this way yet.
+                    vec![
+                        CodeLens {
+                            range,
+                            command: CodeLensCommand::Run,
+                        },
+                        CodeLens {
+                            range,
+                            command: CodeLensCommand::Histogram,
+                        },
+                        CodeLens {
+                            range,
+                            command: CodeLensCommand::Estimate,
+                        },
+                        CodeLens {
+                            range,
+                            command: CodeLensCommand::Debug,
+                        },
+                    ]
+                } else {
+                    if let Some((_, total_num_qubits)) = qubit_param_info(item) {
+                        return vec![CodeLens {
+                            range,
+                            command: CodeLensCommand::Circuit(Some(OperationInfo {
+                                operation: format!("{namespace}.{name}"),
+                                total_num_qubits,
+                            })),
+                        }];
+                    }
+                    vec![]
                 }
-                vec![]
-            }
-        })
+            },
+        )
         .collect()
 }
 
@@ -121,3 +153,18 @@ fn entry_callable(package: &Package) -> Option<LocalItemId> {
     }
     None
 }
+
+/// Finds the entry point callable when it takes parameters, in which case no
+/// entry expression could be generated for it.
+fn parameterized_entry_callable(package: &Package) -> Option<LocalItemId> {
+    if package.entry.is_some() {
+        return None;
+    }
+    let item_id = entry_point_callable(package)?;
+    match &package.items.get(item_id)?.kind {
+        ItemKind::Callable(decl) if !matches!(&decl.input.kind, PatKind::Tuple(items) if items.is_empty()) => {
+            Some(item_id)
+        }
+        _ => None,
+    }
+}
@@ -121,6 +121,31 @@ fn main_function() {
     );
 }
 
+#[test]
+fn entrypoint_with_params() {
+    check(
+        r#"
+        namespace Test {
+            @EntryPoint()
+            ◉operation Test(n : Int) : Unit{
+            }◉
+        }"#,
+        &expect![[r#"
+            [
+                (
+                    0,
+                    [
+                        Run,
+                        Histogram,
+                        Estimate,
+                        Debug,
+                    ],
+                ),
+            ]
+        "#]],
+    );
+}
+
 #[test]
 fn no_entrypoint_code_lens_in_notebook() {
     let compilation = compile_notebook_with_fake_stdlib(
@@ -3,13 +3,15 @@
 
 use log::trace;
 use qsc::{
-    ast, compile,
+    ast,
+    codegen::qir::OperationSummary,
+    compile,
     display::Lookup,
     error::WithSource,
     hir::{self, PackageId},
     incremental::Compiler,
     line_column::{Encoding, Position, Range},
-    packages::{prepare_package_store, BuildableProgram},
+    packages::{prepare_package_store_with_cache, BuildableProgram},
     project, resolve,
     target::Profile,
     CompileUnit, LanguageFeatures, PackageStore, PackageType, PassContext, SourceMap, Span,
@@ -17,7 +19,7 @@ use qsc::{
 use qsc_linter::{LintConfig, LintLevel};
 use qsc_project::{PackageGraphSources, Project};
 use rustc_hash::FxHashMap;
-use std::sync::Arc;
+use std::{cell::OnceCell, sync::Arc};
 use std::{iter::once, mem::take};
 
 /// The alias that a project gives a dependency in its qsharp.json.
@@ -38,6 +40,9 @@ pub(crate) struct Compilation {
     pub kind: CompilationKind,
     pub dependencies: FxHashMap<PackageId, Option<PackageAlias>>,
     pub test_cases: Vec<(String, Span)>,
+    /// The summaries of the operations in the user package, computed on first use since they require
+    /// partially evaluating every operation.
+    pub operation_summaries: OnceCell<Vec<(hir::LocalItemId, OperationSummary)>>,
 }
 
 #[derive(Debug)]
@@ -58,6 +63,7 @@ pub(crate) enum CompilationKind {
 
 impl Compilation {
     /// Creates a new `Compilation` by compiling sources.
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         package_type: PackageType,
         target_profile: Profile,
@@ -66,9 +72,14 @@ impl Compilation {
         package_graph_sources: PackageGraphSources,
         project_errors: Vec<project::Error>,
         friendly_name: &Arc<str>,
+        parse_cache: &mut compile::ParseCache,
+        compile_cache: &mut compile::CompileCache,
     ) -> Self {
-        let mut buildable_program =
-            prepare_package_store(target_profile.into(), package_graph_sources.clone());
+        let mut buildable_program = prepare_package_store_with_cache(
+            target_profile.into(),
+            package_graph_sources.clone(),
+            compile_cache,
+        );
 
         let mut compile_errors = take(&mut buildable_program.dependency_errors);
 
@@ -80,13 +91,14 @@ impl Compilation {
         } = buildable_program;
         let user_code = SourceMap::new(user_code.sources, None);
 
-        let (unit, mut this_errors) = compile::compile(
+        let (unit, mut this_errors) = compile::compile_with_parse_cache(
             &package_store,
             &user_code_dependencies,
             user_code,
             package_type,
             target_profile.into(),
             language_features,
+            parse_cache,
         );
 
         compile_errors.append(&mut this_errors);
@@ -119,6 +131,7 @@ impl Compilation {
             project_errors,
             dependencies: user_code_dependencies.into_iter().collect(),
             test_cases,
+            operation_summaries: OnceCell::new(),
         }
     }
 
@@ -129,6 +142,7 @@ impl Compilation {
         language_features: LanguageFeatures,
         lints_config: &[LintConfig],
         project: Option<Project>,
+        compile_cache: &mut compile::CompileCache,
     ) -> Self
     where
         I: Iterator<Item = (Arc<str>, Arc<str>)>,
@@ -138,8 +152,11 @@ impl Compilation {
         let (sources, dependencies, store, mut errors) = match &project {
             Some(p) if p.errors.is_empty() => {
                 trace!("using buildable program from project");
-                let buildable_program =
-                    prepare_package_store(target_profile.into(), p.package_graph_sources.clone());
+                let buildable_program = prepare_package_store_with_cache(
+                    target_profile.into(),
+                    p.package_graph_sources.clone(),
+                    compile_cache,
+                );
 
                 (
                     SourceMap::new(buildable_program.user_code.sources, None),
@@ -152,8 +169,10 @@ impl Compilation {
                 // If no project is specified, or if the project has errors, compile stdlib only.
                 // Any project errors will be handled below.
                 trace!("compiling stdlib only");
-                let (std_id, store) =
-                    qsc::compile::package_store_with_stdlib(target_profile.into());
+                let (std_id, store) = qsc::compile::package_store_with_stdlib_cached(
+                    target_profile.into(),
+                    compile_cache,
+                );
                 (
                     SourceMap::default(),
                     vec![(std_id, None)],
@@ -178,8 +197,10 @@ impl Compilation {
                 // Because there were errors in the user code project, we need to create a new compiler with no sources
                 // to do a best effort compilation of the cells.
                 trace!("falling back stdlib only only after user code project errors");
-                let (std_id, store) =
-                    qsc::compile::package_store_with_stdlib(target_profile.into());
+                let (std_id, store) = qsc::compile::package_store_with_stdlib_cached(
+                    target_profile.into(),
+                    compile_cache,
+                );
 
                 Compiler::new(
                     SourceMap::default(),
@@ -236,6 +257,7 @@ impl Compilation {
             kind: CompilationKind::Notebook { project },
             test_cases,
             dependencies,
+            operation_summaries: OnceCell::new(),
         }
     }
 
@@ -331,6 +353,8 @@ impl Compilation {
         target_profile: Profile,
         language_features: LanguageFeatures,
         lints_config: &[LintConfig],
+        parse_cache: &mut compile::ParseCache,
+        compile_cache: &mut compile::CompileCache,
     ) {
         let sources = self
             .user_unit()
@@ -351,6 +375,8 @@ impl Compilation {
                 package_graph_sources.clone(),
                 Vec::new(), // project errors will stay the same
                 friendly_name,
+                parse_cache,
+                compile_cache,
             ),
             CompilationKind::Notebook { ref project } => Self::new_notebook(
                 sources.into_iter(),
@@ -358,12 +384,14 @@ impl Compilation {
                 language_features,
                 lints_config,
                 project.clone(),
+                compile_cache,
             ),
         };
 
         self.package_store = new.package_store;
         self.user_package_id = new.user_package_id;
         self.compile_errors = new.compile_errors;
+        self.operation_summaries = OnceCell::new();
     }
 }
 
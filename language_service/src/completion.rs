@@ -167,6 +167,10 @@ fn collect_hardcoded_words(expected: WordKinds) -> Vec<Completion> {
                     Completion::new("Measurement".to_string(), CompletionItemKind::Interface),
                     Completion::new("Reset".to_string(), CompletionItemKind::Interface),
                     Completion::new("Test".to_string(), CompletionItemKind::Interface),
+                    Completion::new("Pure".to_string(), CompletionItemKind::Interface),
+                    Completion::new("HostCallable".to_string(), CompletionItemKind::Interface),
+                    Completion::new("Quantity".to_string(), CompletionItemKind::Interface),
+                    Completion::new("OutputName".to_string(), CompletionItemKind::Interface),
                 ]);
             }
             HardcodedIdentKind::Size => {
@@ -239,7 +243,7 @@ fn collect_names(
                     | Eq
                     | Exp { .. }
                     | Iterable { .. }
-                    | NonNativeClass(_)
+                    | NonNativeClass { .. }
                     | Integral
                     | Mod
                     | Sub
@@ -33,7 +33,7 @@ impl<'a> Fields<'a> {
 
         let mut completions = vec![];
         let ty = self.compilation.get_ty(id);
-        if let Some(Ty::Udt(_, Res::Item(item_id))) = ty {
+        if let Some(Ty::Udt(_, Res::Item(item_id), _)) = ty {
             let (item, _, _) = self
                 .compilation
                 .resolve_item_relative_to_user_package(item_id);
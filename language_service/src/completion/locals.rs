@@ -82,8 +82,9 @@ impl Locals<'_> {
                             CompletionItemKind::Interface,
                         )
                     }
-                    // We don't want completions for items exported from the local scope
-                    ItemKind::Export(_, _) => return None,
+                    // We don't want completions for items exported from the local scope,
+                    // and classes are not types or terms
+                    ItemKind::Export(_, _) | ItemKind::Class(..) => return None,
                 };
                 (kind, detail)
             }
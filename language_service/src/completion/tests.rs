@@ -1575,6 +1575,39 @@ fn local_vars() {
     );
 }
 
+#[test]
+fn local_vars_in_interpolated_string() {
+    check(
+        r#"
+    namespace Test {
+        operation Foo() : Unit {
+            let bar = 3;
+            let s = $"bar is {b↘}";
+            let foo = 3;
+        }
+    }"#,
+        &["foo", "bar"],
+        &expect![[r#"
+            [
+                None,
+                Some(
+                    CompletionItem {
+                        label: "bar",
+                        kind: Variable,
+                        sort_text: Some(
+                            "0100bar",
+                        ),
+                        detail: Some(
+                            "bar : Int",
+                        ),
+                        additional_text_edits: None,
+                    },
+                ),
+            ]
+        "#]],
+    );
+}
+
 #[test]
 fn local_items() {
     check(
@@ -3805,6 +3838,37 @@ fn field_access_expr_chained() {
     );
 }
 
+#[test]
+fn field_access_in_interpolated_string() {
+    check(
+        r#"namespace Test {
+            struct Bar { fieldBar : Int }
+            function Main() : Unit {
+                let bar = new Bar { fieldBar = 3 };
+                let s = $"{bar.↘} and {bar}";
+            }
+        }"#,
+        &["fieldBar"],
+        &expect![[r#"
+            [
+                Some(
+                    CompletionItem {
+                        label: "fieldBar",
+                        kind: Field,
+                        sort_text: Some(
+                            "0100fieldBar",
+                        ),
+                        detail: Some(
+                            "Int",
+                        ),
+                        additional_text_edits: None,
+                    },
+                ),
+            ]
+        "#]],
+    );
+}
+
 #[test]
 fn field_assignment_rhs() {
     check(
@@ -4106,6 +4170,29 @@ fn no_completion_inside_attr() {
     );
 }
 
+#[test]
+fn no_completions_in_interpolated_string_text() {
+    check_no_completions(
+        r#"namespace Test {
+            operation Foo() : Unit {
+                let bar = 3;
+                let s = $"bar is {bar}, fo↘o";
+            }
+        }"#,
+    );
+}
+
+#[test]
+fn no_completions_in_string() {
+    check_no_completions(
+        r#"namespace Test {
+            operation Foo() : Unit {
+                let s = "fo↘o";
+            }
+        }"#,
+    );
+}
+
 #[test]
 fn in_comment() {
     check_no_completions(
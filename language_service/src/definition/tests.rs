@@ -2,6 +2,7 @@
 // Licensed under the MIT License.
 
 use expect_test::{expect, Expect};
+use qsc::library::LIBRARY_VERSION;
 use qsc::location::Location;
 
 use super::get_definition;
@@ -45,7 +46,10 @@ fn check(source_with_markers: &str, expect: &Expect) {
     let (compilation, cursor_position, _) = compile_with_markers(source_with_markers, true);
     let actual_definition =
         get_definition(&compilation, "<source>", cursor_position, Encoding::Utf8);
-    expect.assert_debug_eq(&actual_definition);
+    // Library locations include the library version, which changes with every release.
+    let actual =
+        format!("{actual_definition:#?}\n").replace(&format!("v{LIBRARY_VERSION}/"), "v<version>/");
+    expect.assert_eq(&actual);
 }
 
 #[test]
@@ -459,6 +463,20 @@ fn lambda_closure_ref() {
     );
 }
 
+#[test]
+fn interpolated_string_ref() {
+    assert_definition(
+        r#"
+    namespace Test {
+        operation Foo() : Unit {
+            let ◉a◉ = "Hello";
+            let b = $"{a} {↘a}";
+        }
+    }
+    "#,
+    );
+}
+
 #[test]
 fn std_call() {
     check(
@@ -473,7 +491,7 @@ fn std_call() {
         &expect![[r#"
             Some(
                 Location {
-                    source: "qsharp-library-source:<std>",
+                    source: "qsharp-library-source:v<version>/<std>",
                     range: Range {
                         start: Position {
                             line: 2,
@@ -581,7 +599,7 @@ fn std_udt() {
         &expect![[r#"
             Some(
                 Location {
-                    source: "qsharp-library-source:<std>",
+                    source: "qsharp-library-source:v<version>/<std>",
                     range: Range {
                         start: Position {
                             line: 5,
@@ -613,7 +631,7 @@ fn std_udt_udt_field() {
         &expect![[r#"
             Some(
                 Location {
-                    source: "qsharp-library-source:<std>",
+                    source: "qsharp-library-source:v<version>/<std>",
                     range: Range {
                         start: Position {
                             line: 5,
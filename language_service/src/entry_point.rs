@@ -0,0 +1,138 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    compilation::{Compilation, CompilationKind},
+    protocol::{EntryPoint, EntryPointParam},
+    qsc_utils::into_range,
+};
+use qsc::{
+    hir::{
+        ty::{Prim, Ty},
+        Attr, ItemKind, LocalItemId, Package, Pat, PatKind,
+    },
+    line_column::Encoding,
+};
+
+/// Gets the entry point of the program and the parameters it takes, so that the editor
+/// can prompt for argument values and run the program with an entry expression that
+/// calls the entry point with them.
+///
+/// Returns `None` for notebooks, and when the program does not have exactly one entry point.
+pub(crate) fn get_entry_point(
+    compilation: &Compilation,
+    position_encoding: Encoding,
+) -> Option<EntryPoint> {
+    if matches!(compilation.kind, CompilationKind::Notebook { .. }) {
+        return None;
+    }
+
+    let unit = compilation.user_unit();
+    let item = unit
+        .package
+        .items
+        .get(entry_point_callable(&unit.package)?)?;
+    let ItemKind::Callable(decl) = &item.kind else {
+        return None;
+    };
+    let Some(ItemKind::Namespace(ns, _)) = item
+        .parent
+        .and_then(|parent_id| unit.package.items.get(parent_id))
+        .map(|parent| &parent.kind)
+    else {
+        return None;
+    };
+
+    let params = match &decl.input.kind {
+        PatKind::Tuple(items) => items.iter().map(param).collect(),
+        _ => vec![param(&decl.input)],
+    };
+
+    Some(EntryPoint {
+        range: into_range(position_encoding, decl.span, &unit.sources),
+        callable: format!("{}.{}", ns.name(), decl.name.name),
+        params,
+    })
+}
+
+/// Finds the callable that the entry expression is generated from when none is provided:
+/// the only callable with the `@EntryPoint()` attribute or, if there is none, the only
+/// callable named `Main`. Unlike the entry expression generation, this also finds
+/// entry points that take parameters.
+pub(crate) fn entry_point_callable(package: &Package) -> Option<LocalItemId> {
+    let callables = package
+        .items
+        .iter()
+        .filter_map(|(item_id, item)| match &item.kind {
+            ItemKind::Callable(decl) => Some((item_id, item, decl)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let mut candidates = callables
+        .iter()
+        .filter(|(_, item, _)| item.attrs.contains(&Attr::EntryPoint))
+        .map(|(item_id, _, _)| *item_id)
+        .collect::<Vec<_>>();
+    if candidates.is_empty() {
+        candidates = callables
+            .iter()
+            .filter(|(_, _, decl)| decl.name.name.as_ref() == "Main")
+            .map(|(item_id, _, _)| *item_id)
+            .collect();
+    }
+
+    match candidates.as_slice() {
+        [item_id] => Some(*item_id),
+        _ => None,
+    }
+}
+
+fn param(pat: &Pat) -> EntryPointParam {
+    EntryPointParam {
+        name: param_name(pat),
+        ty: pat.ty.display(),
+        default_value: default_value(&pat.ty),
+    }
+}
+
+fn param_name(pat: &Pat) -> String {
+    match &pat.kind {
+        PatKind::Bind(ident) => ident.name.to_string(),
+        PatKind::Tuple(items) => format!(
+            "({})",
+            items.iter().map(param_name).collect::<Vec<_>>().join(", ")
+        ),
+        PatKind::Discard | PatKind::Err => "_".to_string(),
+    }
+}
+
+/// Gets the zero value of a type that can be passed to an entry point, written as a Q# expression.
+fn default_value(ty: &Ty) -> Option<String> {
+    match ty {
+        Ty::Prim(prim) => match prim {
+            Prim::BigInt => Some("0L".to_string()),
+            Prim::Bool => Some("false".to_string()),
+            Prim::Double => Some("0.0".to_string()),
+            Prim::Int => Some("0".to_string()),
+            Prim::Pauli => Some("PauliI".to_string()),
+            Prim::Result => Some("Zero".to_string()),
+            Prim::String => Some("\"\"".to_string()),
+            Prim::Qubit | Prim::Range | Prim::RangeTo | Prim::RangeFrom | Prim::RangeFull => None,
+        },
+        Ty::Array(_) => Some("[]".to_string()),
+        Ty::Tuple(items) => {
+            let items = items
+                .iter()
+                .map(default_value)
+                .collect::<Option<Vec<_>>>()?;
+            Some(match items.as_slice() {
+                [item] => format!("({item},)"),
+                _ => format!("({})", items.join(", ")),
+            })
+        }
+        Ty::Arrow(_) | Ty::Infer(_) | Ty::Param { .. } | Ty::Udt(..) | Ty::Err => None,
+    }
+}
@@ -0,0 +1,172 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::get_entry_point;
+use crate::{
+    test_utils::{
+        compile_notebook_with_fake_stdlib, compile_with_fake_stdlib_and_markers_no_cursor,
+    },
+    Encoding,
+};
+use expect_test::{expect, Expect};
+
+fn check(source_with_markers: &str, expect: &Expect) {
+    let (compilation, expected_ranges) =
+        compile_with_fake_stdlib_and_markers_no_cursor(source_with_markers, true);
+    let actual = get_entry_point(&compilation, Encoding::Utf8);
+    assert_eq!(
+        actual.as_ref().map(|entry_point| vec![entry_point.range]),
+        (!expected_ranges.is_empty()).then_some(expected_ranges),
+        "unexpected entry point range"
+    );
+    expect.assert_debug_eq(&actual.map(|entry_point| (entry_point.callable, entry_point.params)));
+}
+
+#[test]
+fn parameterless_entry_point() {
+    check(
+        r#"
+        namespace Test {
+            @EntryPoint()
+            ◉operation Foo() : Unit {}◉
+        }"#,
+        &expect![[r#"
+            Some(
+                (
+                    "Test.Foo",
+                    [],
+                ),
+            )
+        "#]],
+    );
+}
+
+#[test]
+fn entry_point_with_params() {
+    check(
+        r#"
+        namespace Test {
+            @EntryPoint()
+            ◉operation Foo(n : Int, angle : Double, bases : Pauli[], label : String) : Result[] { [] }◉
+        }"#,
+        &expect![[r#"
+            Some(
+                (
+                    "Test.Foo",
+                    [
+                        EntryPointParam {
+                            name: "n",
+                            ty: "Int",
+                            default_value: Some(
+                                "0",
+                            ),
+                        },
+                        EntryPointParam {
+                            name: "angle",
+                            ty: "Double",
+                            default_value: Some(
+                                "0.0",
+                            ),
+                        },
+                        EntryPointParam {
+                            name: "bases",
+                            ty: "Pauli[]",
+                            default_value: Some(
+                                "[]",
+                            ),
+                        },
+                        EntryPointParam {
+                            name: "label",
+                            ty: "String",
+                            default_value: Some(
+                                "\"\"",
+                            ),
+                        },
+                    ],
+                ),
+            )
+        "#]],
+    );
+}
+
+#[test]
+fn main_with_tuple_param() {
+    check(
+        r#"
+        namespace Test {
+            ◉operation Main(pair : (Bool, BigInt), _ : Result) : Unit {}◉
+        }"#,
+        &expect![[r#"
+            Some(
+                (
+                    "Test.Main",
+                    [
+                        EntryPointParam {
+                            name: "pair",
+                            ty: "(Bool, BigInt)",
+                            default_value: Some(
+                                "(false, 0L)",
+                            ),
+                        },
+                        EntryPointParam {
+                            name: "_",
+                            ty: "Result",
+                            default_value: Some(
+                                "Zero",
+                            ),
+                        },
+                    ],
+                ),
+            )
+        "#]],
+    );
+}
+
+#[test]
+fn param_without_default_value() {
+    check(
+        r#"
+        namespace Test {
+            @EntryPoint()
+            ◉operation Foo(r : Range) : Unit {}◉
+        }"#,
+        &expect![[r#"
+            Some(
+                (
+                    "Test.Foo",
+                    [
+                        EntryPointParam {
+                            name: "r",
+                            ty: "Range",
+                            default_value: None,
+                        },
+                    ],
+                ),
+            )
+        "#]],
+    );
+}
+
+#[test]
+fn two_entry_points() {
+    check(
+        r#"
+        namespace Test {
+            @EntryPoint()
+            operation Foo(n : Int) : Unit {}
+            @EntryPoint()
+            operation Bar(n : Int) : Unit {}
+        }"#,
+        &expect![[r#"
+            None
+        "#]],
+    );
+}
+
+#[test]
+fn no_entry_point_in_notebook() {
+    let compilation = compile_notebook_with_fake_stdlib(
+        [("cell1", "operation Main(n : Int) : Unit {}")].into_iter(),
+    );
+    assert_eq!(get_entry_point(&compilation, Encoding::Utf8), None);
+}
@@ -0,0 +1,86 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    compilation::{Compilation, CompilationKind},
+    protocol::{Export, ExportKind},
+    qsc_utils::into_location,
+};
+use qsc::{
+    hir::{global, ItemId, ItemKind, PackageId, Visibility},
+    line_column::Encoding,
+    Span,
+};
+
+/// Gets the effective exports of the package: the callables and types that its dependents can refer to,
+/// including the items it re-exports from its own dependencies, sorted by name. The exports of a namespace
+/// are gathered from all of the files the namespace is declared in.
+///
+/// Returns nothing for notebooks, since they cannot be used as dependencies.
+pub(crate) fn get_exports(compilation: &Compilation, position_encoding: Encoding) -> Vec<Export> {
+    if matches!(compilation.kind, CompilationKind::Notebook { .. }) {
+        return Vec::new();
+    }
+
+    let package_id = compilation.user_package_id;
+    let unit = compilation.user_unit();
+    let mut exports = global::iter_package(Some(package_id), &unit.package)
+        .filter(|global| global.visibility == Visibility::Public)
+        .filter_map(|global| {
+            let id = match global.kind {
+                global::Kind::Term(term) => term.id,
+                global::Kind::Ty(ty) => ty.id,
+                global::Kind::Export(id) => id,
+                global::Kind::Namespace => return None,
+            };
+            let (package, kind, span) = declaration(compilation, id, package_id)?;
+
+            // Dependents refer to the items of the `Main` namespace without a namespace.
+            let name = if global.namespace.len() == 1 && &*global.namespace[0] == "Main" {
+                global.name.to_string()
+            } else {
+                format!("{}.{}", global.namespace.join("."), global.name)
+            };
+
+            Some(Export {
+                name,
+                kind,
+                location: into_location(position_encoding, compilation, span, package),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    // Types are also found as the terms that construct them.
+    exports.sort_by(|a, b| a.name.cmp(&b.name));
+    exports.dedup_by(|a, b| a.name == b.name);
+    exports
+}
+
+/// Finds the package, kind and name span of the callable or type that an item refers to, following
+/// re-exports back to the declaration.
+fn declaration(
+    compilation: &Compilation,
+    mut id: ItemId,
+    mut package: PackageId,
+) -> Option<(PackageId, ExportKind, Span)> {
+    loop {
+        package = id.package.unwrap_or(package);
+        let item = compilation
+            .package_store
+            .get(package)?
+            .package
+            .items
+            .get(id.item)?;
+        match &item.kind {
+            ItemKind::Callable(decl) => {
+                return Some((package, ExportKind::Callable, decl.name.span));
+            }
+            ItemKind::Ty(name, _) => return Some((package, ExportKind::Type, name.span)),
+            ItemKind::Export(_, exported) => id = *exported,
+            ItemKind::Class(..) | ItemKind::Namespace(..) => return None,
+        }
+    }
+}
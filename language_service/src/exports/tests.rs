@@ -0,0 +1,155 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::get_exports;
+use crate::{
+    test_utils::{compile_notebook_with_fake_stdlib, compile_project_with_markers_no_cursor},
+    Encoding,
+};
+use expect_test::{expect, Expect};
+
+/// Checks the names and kinds of the exports, and that the exports declared in the sources are found at the
+/// marked names.
+fn check(sources_with_markers: &[(&str, &str)], expect: &Expect) {
+    let (compilation, mut expected_locations) =
+        compile_project_with_markers_no_cursor(sources_with_markers, true);
+    let exports = get_exports(&compilation, Encoding::Utf8);
+
+    let key = |location: &qsc::location::Location| {
+        (
+            location.source.clone(),
+            location.range.start.line,
+            location.range.start.column,
+        )
+    };
+    let mut actual_locations = exports
+        .iter()
+        .map(|export| export.location.clone())
+        .filter(|location| {
+            sources_with_markers
+                .iter()
+                .any(|(name, _)| *name == &*location.source)
+        })
+        .collect::<Vec<_>>();
+    // Several exports can refer to the same declaration.
+    actual_locations.sort_by_key(key);
+    actual_locations.dedup();
+    expected_locations.sort_by_key(key);
+    assert_eq!(actual_locations, expected_locations);
+
+    expect.assert_debug_eq(
+        &exports
+            .iter()
+            .map(|export| (export.name.as_str(), export.kind))
+            .collect::<Vec<_>>(),
+    );
+}
+
+#[test]
+fn only_exported_items_are_listed() {
+    check(
+        &[(
+            "A.qs",
+            r#"
+            namespace Test {
+                operation ◉Foo◉() : Unit {}
+                operation Bar() : Unit {}
+                newtype ◉Pair◉ = (Int, Int);
+                export Foo, Pair;
+            }"#,
+        )],
+        &expect![[r#"
+            [
+                (
+                    "Test.Foo",
+                    Callable,
+                ),
+                (
+                    "Test.Pair",
+                    Type,
+                ),
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn exports_of_namespace_split_across_files() {
+    check(
+        &[
+            (
+                "A.qs",
+                r#"
+                namespace Test {
+                    operation ◉Foo◉() : Unit {}
+                    struct ◉Point◉ { X : Int, Y : Int }
+                }"#,
+            ),
+            (
+                "B.qs",
+                r#"
+                namespace Test {
+                    export Foo, Point;
+                }"#,
+            ),
+        ],
+        &expect![[r#"
+            [
+                (
+                    "Test.Foo",
+                    Callable,
+                ),
+                (
+                    "Test.Point",
+                    Type,
+                ),
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn reexports_are_listed_under_their_export_name() {
+    check(
+        &[(
+            "A.qs",
+            r#"
+            namespace Test {
+                operation ◉Foo◉() : Unit {}
+                export Foo as Renamed;
+                export FakeStdLib.Library.OperationInLibrary;
+            }
+            namespace Other {
+                export Test.Foo;
+            }"#,
+        )],
+        &expect![[r#"
+            [
+                (
+                    "Other.Foo",
+                    Callable,
+                ),
+                (
+                    "Test.OperationInLibrary",
+                    Callable,
+                ),
+                (
+                    "Test.Renamed",
+                    Callable,
+                ),
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn notebook_has_no_exports() {
+    let compilation = compile_notebook_with_fake_stdlib(
+        [(
+            "cell1",
+            "namespace Test { operation Foo() : Unit {} export Foo; }",
+        )]
+        .into_iter(),
+    );
+    assert!(get_exports(&compilation, Encoding::Utf8).is_empty());
+}
@@ -73,7 +73,7 @@ fn attr_with_arg() {
 
             Provides pre-processing information about when an item should be included in compilation.
 
-            Valid arguments are `Base`, `Adaptive`, `IntegerComputations`, `FloatingPointComputations`, `BackwardsBranching`, `HigherLevelConstructs`, `QubitReset`, and `Unrestricted`.
+            Valid arguments are `Base`, `Adaptive`, `IntegerComputations`, `FloatingPointComputations`, `BackwardsBranching`, `HigherLevelConstructs`, `QubitReset`, `Delay`, and `Unrestricted`.
 
             The `not` operator is also supported to negate the attribute, e.g. `not Adaptive`."#]],
     );
@@ -529,6 +529,66 @@ fn identifier_nested_ref() {
     );
 }
 
+#[test]
+fn identifier_interpolated_string_ref() {
+    check(
+        indoc! {r#"
+        namespace Test {
+            operation Foo() : Unit {
+                let x = 3;
+                let s = $"x is {◉↘x◉}";
+            }
+        }
+    "#},
+        &expect![[r#"
+            local
+            ```qsharp
+            x : Int
+            ```
+        "#]],
+    );
+}
+
+#[test]
+fn identifier_interpolated_string_with_incomplete_hole_ref() {
+    check(
+        indoc! {r#"
+        namespace Test {
+            operation Foo() : Unit {
+                let x = 3;
+                let s = $"{◉↘x◉} and {x.}";
+            }
+        }
+    "#},
+        &expect![[r#"
+            local
+            ```qsharp
+            x : Int
+            ```
+        "#]],
+    );
+}
+
+#[test]
+fn callable_interpolated_string_ref() {
+    check(
+        indoc! {r#"
+        namespace Test {
+            function Bar(x : Int) : Int { x }
+            operation Foo() : Unit {
+                let s = $"{◉B↘ar◉(1) + 1} and {Bar(2)}";
+            }
+        }
+    "#},
+        &expect![[r#"
+            callable of `Test`
+            ```qsharp
+            function Bar(x : Int) : Int
+            ```
+        "#]],
+    );
+}
+
 #[test]
 fn lambda() {
     check(
@@ -1053,6 +1113,26 @@ fn struct_field_path_with_expr_ref() {
     );
 }
 
+#[test]
+fn struct_field_interpolated_string_ref() {
+    check(
+        indoc! {r#"
+        namespace Test {
+            struct A { b : Int }
+            operation Foo(a : A) : Unit {
+                let s = $"b is {a.◉↘b◉}";
+            }
+        }
+    "#},
+        &expect![[r#"
+            field of `A`
+            ```qsharp
+            b : Int
+            ```
+        "#]],
+    );
+}
+
 #[test]
 fn primitive_type() {
     check_none(indoc! {r#"
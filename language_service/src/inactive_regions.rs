@@ -0,0 +1,25 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use crate::{compilation::Compilation, qsc_utils::into_range};
+use qsc::line_column::{Encoding, Range};
+
+/// Gets the ranges of the items in the given source that are excluded from the compilation by their `@Config`
+/// attributes under the current target profile. Editors can render these as inactive code.
+pub(crate) fn get_inactive_regions(
+    compilation: &Compilation,
+    source_name: &str,
+    position_encoding: Encoding,
+) -> Vec<Range> {
+    let unit = compilation.user_unit();
+    let source_span = compilation.package_span_of_source(source_name);
+
+    unit.excluded_spans
+        .iter()
+        .filter(|span| source_span.lo <= span.lo && span.hi <= source_span.hi)
+        .map(|span| into_range(position_encoding, *span, &unit.sources))
+        .collect()
+}
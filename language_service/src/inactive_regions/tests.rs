@@ -0,0 +1,80 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::get_inactive_regions;
+use crate::{
+    test_utils::{compile_notebook_with_markers, compile_with_fake_stdlib_and_markers_no_cursor},
+    Encoding,
+};
+
+fn check(source_with_markers: &str) {
+    let (compilation, expected) =
+        compile_with_fake_stdlib_and_markers_no_cursor(source_with_markers, true);
+    let actual = get_inactive_regions(&compilation, "<source>", Encoding::Utf8);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn no_config_attributes() {
+    check(
+        r#"
+        namespace Test {
+            operation Foo() : Unit {}
+            function Bar() : Int { 0 }
+        }"#,
+    );
+}
+
+#[test]
+fn base_items_are_inactive_when_unrestricted() {
+    check(
+        r#"
+        namespace Test {
+            ◉@Config(Base)
+            operation Foo() : Unit {}◉
+            @Config(Adaptive)
+            operation Foo() : Unit {}
+            ◉@Config(not Adaptive)
+            newtype Bar = Int;◉
+        }"#,
+    );
+}
+
+#[test]
+fn regions_in_multiple_namespaces() {
+    check(
+        r#"
+        namespace A {
+            ◉@Config(Base)
+            function Foo() : Unit {}◉
+        }
+        namespace B {
+            function Foo() : Unit {}
+            ◉@Config(Base)
+            function Bar() : Unit {}◉
+        }"#,
+    );
+}
+
+#[test]
+fn notebook_regions_are_per_cell() {
+    let (compilation, _, _, expected) = compile_notebook_with_markers(&[
+        (
+            "cell1",
+            "◉@Config(Base)
+            operation Foo() : Unit {}◉",
+        ),
+        ("cell2", "@Config(Base)\noperation Bar() : Unit {}\n↘"),
+    ]);
+    let actual = get_inactive_regions(&compilation, "cell1", Encoding::Utf8);
+    let expected = expected
+        .into_iter()
+        .filter(|location| location.source.as_ref() == "cell1")
+        .map(|location| location.range)
+        .collect::<Vec<_>>();
+    assert_eq!(actual, expected);
+    assert_eq!(
+        get_inactive_regions(&compilation, "cell2", Encoding::Utf8).len(),
+        1
+    );
+}
@@ -1,18 +1,25 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
+pub mod capabilities;
 pub mod code_action;
 pub mod code_lens;
 mod compilation;
 pub mod completion;
 pub mod definition;
+pub mod entry_point;
+mod exports;
 pub mod format;
 pub mod hover;
+pub mod inactive_regions;
+mod memory_usage;
 mod name_locator;
+pub mod operation_stats;
 pub mod protocol;
 mod qsc_utils;
 pub mod references;
 pub mod rename;
+pub mod semantic_tokens;
 pub mod signature_help;
 mod state;
 #[cfg(test)]
@@ -25,12 +32,14 @@ use futures::channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
 use futures_util::StreamExt;
 use log::{trace, warn};
 use protocol::{
-    CodeAction, CodeLens, CompletionList, DiagnosticUpdate, Hover, NotebookMetadata, SignatureHelp,
+    CodeAction, CodeLens, CompletionList, DiagnosticUpdate, EntryPoint, Export, Hover,
+    NotebookMetadata, OperationStats, RequiredCapabilities, SemanticToken, SignatureHelp,
     TestCallables, TextEdit, WorkspaceConfigurationUpdate,
 };
 use qsc::{
     line_column::{Encoding, Position, Range},
     location::Location,
+    memory::MemoryUsage,
 };
 use qsc_project::JSProjectHost;
 use state::{CompilationState, CompilationStateUpdater};
@@ -178,6 +187,14 @@ impl LanguageService {
         });
     }
 
+    /// Frees the packages, parses and dependency sources that no open document refers to anymore.
+    /// This happens automatically as documents are closed and projects change, so embedders only
+    /// need to call it to reclaim memory on demand.
+    pub fn collect(&mut self) {
+        trace!("collect");
+        self.send_update(Update::Collect);
+    }
+
     #[must_use]
     pub fn get_code_actions(&self, uri: &str, range: Range) -> Vec<CodeAction> {
         self.document_op(
@@ -284,6 +301,117 @@ impl LanguageService {
         )
     }
 
+    /// Gets the runtime capabilities required by the selected code, or by the callable containing the cursor
+    /// when the selection is empty.
+    ///
+    /// LSP: custom request, not part of the protocol.
+    #[must_use]
+    pub fn get_required_capabilities(
+        &self,
+        uri: &str,
+        range: Range,
+    ) -> Option<RequiredCapabilities> {
+        self.document_op(
+            capabilities::get_required_capabilities,
+            "get_required_capabilities",
+            uri,
+            range,
+        )
+    }
+
+    /// Gets the ranges of the items in the document that are excluded from the compilation
+    /// by their `@Config` attributes under the current target profile.
+    ///
+    /// LSP: custom request, not part of the protocol.
+    #[must_use]
+    pub fn get_inactive_regions(&self, uri: &str) -> Vec<Range> {
+        self.document_op(
+            |compilation, uri, (), position_encoding| {
+                inactive_regions::get_inactive_regions(compilation, uri, position_encoding)
+            },
+            "get_inactive_regions",
+            uri,
+            (),
+        )
+    }
+
+    /// Gets the comments, keywords, literals and operators to highlight in the document, found from
+    /// its tokens alone so that they are highlighted even while the document doesn't parse.
+    ///
+    /// LSP: textDocument/semanticTokens/full
+    #[must_use]
+    pub fn get_semantic_tokens(&self, uri: &str) -> Vec<SemanticToken> {
+        self.document_op(
+            |compilation, uri, (), position_encoding| {
+                semantic_tokens::get_semantic_tokens(compilation, uri, position_encoding)
+            },
+            "get_semantic_tokens",
+            uri,
+            (),
+        )
+    }
+
+    /// Gets the entry point of the program containing the document, with the parameters
+    /// the editor should prompt for before running it.
+    ///
+    /// LSP: custom request, not part of the protocol.
+    #[must_use]
+    pub fn get_entry_point(&self, uri: &str) -> Option<EntryPoint> {
+        self.document_op(
+            |compilation, _, (), position_encoding| {
+                entry_point::get_entry_point(compilation, position_encoding)
+            },
+            "get_entry_point",
+            uri,
+            (),
+        )
+    }
+
+    /// Gets the effective exports of the package containing the document: the callables and types that
+    /// its dependents can refer to, including the items it re-exports.
+    ///
+    /// LSP: custom request, not part of the protocol.
+    #[must_use]
+    pub fn get_exports(&self, uri: &str) -> Vec<Export> {
+        self.document_op(
+            |compilation, _, (), position_encoding| {
+                exports::get_exports(compilation, position_encoding)
+            },
+            "get_exports",
+            uri,
+            (),
+        )
+    }
+
+    /// Gets the estimated instruction count and required capabilities of each operation declared in the document.
+    ///
+    /// LSP: custom request, not part of the protocol.
+    #[must_use]
+    pub fn get_operation_stats(&self, uri: &str) -> Vec<OperationStats> {
+        self.document_op(
+            |compilation, uri, (), position_encoding| {
+                operation_stats::get_operation_stats(compilation, uri, position_encoding)
+            },
+            "get_operation_stats",
+            uri,
+            (),
+        )
+    }
+
+    /// Gets an estimate of the memory held by each package in the compilation containing the document,
+    /// to help find what is taking up space in the language service on large projects.
+    ///
+    /// LSP: custom request, not part of the protocol.
+    #[must_use]
+    pub fn get_memory_usage(&self, uri: &str) -> MemoryUsage {
+        self.document_op(
+            |compilation, _, (), _| memory_usage::get_memory_usage(compilation),
+            "get_memory_usage",
+            uri,
+            (),
+        )
+    }
+
     /// Executes an operation that takes a document uri, using the current compilation for that document.
     /// All "read" operations should go through this method. This method will borrow the current
     /// compilation state to perform the request.
@@ -417,7 +545,8 @@ fn push_update(pending_updates: &mut Vec<Update>, update: Update) {
         }
         Update::Configuration { .. }
         | Update::CloseDocument { .. }
-        | Update::CloseNotebookDocument { .. } => (), // These events aren't noisy enough to bother deduping.
+        | Update::CloseNotebookDocument { .. }
+        | Update::Collect => (), // These events aren't noisy enough to bother deduping.
     }
     pending_updates.push(update);
 }
@@ -451,6 +580,9 @@ async fn apply_update(updater: &mut CompilationStateUpdater<'_>, update: Update)
         Update::Configuration { changed } => {
             updater.update_configuration(changed);
         }
+        Update::Collect => {
+            updater.collect();
+        }
     }
 }
 
@@ -474,4 +606,5 @@ enum Update {
     CloseNotebookDocument {
         notebook_uri: String,
     },
+    Collect,
 }
@@ -0,0 +1,35 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use crate::compilation::{Compilation, CompilationKind};
+use qsc::memory::{unit_memory_usage, MemoryUsage};
+
+/// Estimates the memory held by each package in the compilation. The language service only keeps
+/// the sources, AST and HIR of each package, so the FIR, capabilities analysis and simulator
+/// usage are always zero.
+pub(crate) fn get_memory_usage(compilation: &Compilation) -> MemoryUsage {
+    let packages = compilation
+        .package_store
+        .iter()
+        .map(|(id, unit)| {
+            let mut usage = unit_memory_usage(id, unit);
+            if id == compilation.user_package_id {
+                usage.name = match &compilation.kind {
+                    CompilationKind::OpenProject { friendly_name, .. } => friendly_name.to_string(),
+                    CompilationKind::Notebook { .. } => "notebook".to_string(),
+                };
+            } else if let Some(Some(alias)) = compilation.dependencies.get(&id) {
+                usage.name = alias.to_string();
+            }
+            usage
+        })
+        .collect();
+
+    MemoryUsage {
+        packages,
+        simulator: 0,
+    }
+}
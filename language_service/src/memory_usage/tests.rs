@@ -0,0 +1,49 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::get_memory_usage;
+use crate::test_utils::{compile_notebook_with_fake_stdlib, compile_with_dependency_with_markers};
+
+#[test]
+fn project_packages_are_named_by_alias() {
+    let (compilation, _, _, _) = compile_with_dependency_with_markers(
+        &[("<source>", "namespace Test { operation Main() : Unit {↘} }")],
+        "MyDep",
+        &[("dep.qs", "namespace Dep { operation Foo() : Unit {} }")],
+    );
+    let usage = get_memory_usage(&compilation);
+
+    let names = usage
+        .packages
+        .iter()
+        .map(|package| package.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names[0], "core");
+    assert!(names.contains(&"MyDep"), "{names:?} should contain MyDep");
+    assert_eq!(names.last(), Some(&"test project"));
+    for package in &usage.packages {
+        assert!(package.ast > 0, "{} should have an AST", package.name);
+        assert!(package.hir > 0, "{} should have a HIR", package.name);
+        assert_eq!(package.fir, 0);
+        assert_eq!(package.rca, 0);
+    }
+    assert_eq!(usage.simulator, 0);
+}
+
+#[test]
+fn notebook_package_is_named_notebook() {
+    let compilation = compile_notebook_with_fake_stdlib(
+        [("cell1", "operation Foo() : Unit {}"), ("cell2", "Foo()")].into_iter(),
+    );
+    let usage = get_memory_usage(&compilation);
+
+    let notebook = usage
+        .packages
+        .iter()
+        .find(|package| package.name == "notebook")
+        .expect("notebook package should be reported");
+    assert_eq!(
+        notebook.sources,
+        "cell1".len() + "operation Foo() : Unit {}".len() + "cell2".len() + "Foo()".len()
+    );
+}
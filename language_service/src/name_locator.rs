@@ -167,6 +167,19 @@ impl<'inner, 'package, T: Handler<'package>> Locator<'inner, 'package, T> {
         }
         finder.ident
     }
+
+    fn is_namespace(&self, path: &ast::Path) -> bool {
+        match self.compilation.get_res(path.id) {
+            Some(resolve::Res::Item(item_id, _)) => matches!(
+                self.compilation
+                    .resolve_item_relative_to_user_package(item_id)
+                    .0
+                    .kind,
+                hir::ItemKind::Namespace(..)
+            ),
+            _ => false,
+        }
+    }
 }
 
 impl<'package, T: Handler<'package>> Visitor<'package> for Locator<'_, 'package, T> {
@@ -267,6 +280,18 @@ impl<'package, T: Handler<'package>> Visitor<'package> for Locator<'_, 'package,
                         self.context.current_item_name = context_curr_item_name;
                     }
                 }
+                ast::ItemKind::Enum(decl) => self.visit_enum_decl(decl),
+                ast::ItemKind::Const(decl) => self.visit_const_decl(decl),
+                ast::ItemKind::ImportOrExport(decl) => {
+                    // Imported and exported namespaces are not references to items.
+                    for item in decl.items() {
+                        if let ast::PathKind::Ok(path) = &item.path {
+                            if !item.is_glob && !self.is_namespace(path) {
+                                self.visit_path(path);
+                            }
+                        }
+                    }
+                }
                 _ => {}
             }
             self.context.current_item_doc = context;
@@ -362,7 +387,7 @@ impl<'package, T: Handler<'package>> Visitor<'package> for Locator<'_, 'package,
                 ast::ExprKind::Field(udt, FieldAccess::Ok(field_ref))
                     if field_ref.span.touches(self.offset) =>
                 {
-                    if let Some(hir::ty::Ty::Udt(_, res)) = &self.compilation.get_ty(udt.id) {
+                    if let Some(hir::ty::Ty::Udt(_, res, _)) = &self.compilation.get_ty(udt.id) {
                         if let Some((item_id, field_def)) = self.get_field_def(res, field_ref) {
                             self.inner.at_field_ref(field_ref, &item_id, field_def);
                         }
@@ -384,7 +409,7 @@ impl<'package, T: Handler<'package>> Visitor<'package> for Locator<'_, 'package,
                     for field in fields {
                         if field.span.touches(self.offset) {
                             if field.field.span.touches(self.offset) {
-                                if let Some(hir::ty::Ty::Udt(_, res)) =
+                                if let Some(hir::ty::Ty::Udt(_, res, _)) =
                                     &self.compilation.get_ty(expr.id)
                                 {
                                     if let Some((item_id, field_def)) =
@@ -432,7 +457,7 @@ impl<'package, T: Handler<'package>> Visitor<'package> for Locator<'_, 'package,
                         let mut last_id = first.id;
                         for part in rest {
                             if part.span.touches(self.offset) {
-                                if let Some(hir::ty::Ty::Udt(_, res)) =
+                                if let Some(hir::ty::Ty::Udt(_, res, _)) =
                                     &self.compilation.get_ty(last_id)
                                 {
                                     if let Some((item_id, field_def)) =
@@ -461,6 +486,8 @@ impl<'package, T: Handler<'package>> Visitor<'package> for Locator<'_, 'package,
                                 self.inner
                                     .at_new_type_ref(path, &resolved_item_id, type_name, udt);
                             }
+                            // A class is not a type, so there is nothing to locate for it.
+                            hir::ItemKind::Class(..) => {}
                             hir::ItemKind::Namespace(_, _) => {
                                 panic!(
                                     "Reference node should not refer to a namespace: {}",
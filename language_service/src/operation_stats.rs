@@ -0,0 +1,116 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use crate::{compilation::Compilation, protocol::OperationStats, qsc_utils::into_range};
+use qsc::{
+    codegen::qir::{summarize_operations, OperationSummary},
+    compile::ErrorKind,
+    hir::{CallableKind, ItemKind, LocalItemId, Package, SpecBody, SpecGen},
+    line_column::Encoding,
+    target::Profile,
+};
+
+/// Gets the estimated instruction count and required capabilities of each operation declared in the given source,
+/// for display above the operation. The estimates for the whole package are computed the first time they are
+/// requested for a compilation and reused until it changes.
+///
+/// Returns no stats if the compilation has errors that prevent capabilities analysis from running.
+pub(crate) fn get_operation_stats(
+    compilation: &Compilation,
+    source_name: &str,
+    position_encoding: Encoding,
+) -> Vec<OperationStats> {
+    if compilation
+        .compile_errors
+        .iter()
+        .any(|error| !matches!(error.error(), ErrorKind::Pass(_) | ErrorKind::Lint(_)))
+    {
+        return Vec::new();
+    }
+
+    let unit = compilation.user_unit();
+    let source_span = compilation.package_span_of_source(source_name);
+    let summaries = compilation.operation_summaries.get_or_init(|| {
+        let operations = operations(&unit.package).collect::<Vec<_>>();
+        let summaries = summarize_operations(
+            &compilation.package_store,
+            compilation.user_package_id,
+            &operations,
+        );
+        operations.into_iter().zip(summaries).collect()
+    });
+
+    summaries
+        .iter()
+        .filter_map(|(item_id, summary)| {
+            let item = unit.package.items.get(*item_id)?;
+            let ItemKind::Callable(decl) = &item.kind else {
+                return None;
+            };
+            if !source_span.contains(item.span.lo) {
+                return None;
+            }
+
+            let target_capabilities = summary.runtime_features.target_capabilities();
+            let minimal_profile = Profile::minimal_for(target_capabilities);
+            Some(OperationStats {
+                range: into_range(position_encoding, decl.span, &unit.sources),
+                capabilities: target_capabilities
+                    .iter_names()
+                    .map(|(name, _)| name.to_string())
+                    .collect(),
+                minimal_profile,
+                instruction_count: summary.instruction_count,
+                label: label(minimal_profile, summary),
+            })
+        })
+        .collect()
+}
+
+/// Gets the operations declared directly in a namespace that are implemented in Q#.
+fn operations(package: &Package) -> impl Iterator<Item = LocalItemId> + '_ {
+    package.items.iter().filter_map(|(item_id, item)| {
+        let ItemKind::Callable(decl) = &item.kind else {
+            return None;
+        };
+        let in_namespace = matches!(
+            item.parent
+                .and_then(|parent_id| package.items.get(parent_id))
+                .map(|parent| &parent.kind),
+            Some(ItemKind::Namespace(..))
+        );
+        let is_intrinsic = matches!(decl.body.body, SpecBody::Gen(SpecGen::Intrinsic));
+        (decl.kind == CallableKind::Operation && in_namespace && !is_intrinsic).then_some(item_id)
+    })
+}
+
+/// Formats the badge shown above an operation, such as "Adaptive_RI · ~1.2k instructions".
+fn label(minimal_profile: Profile, summary: &OperationSummary) -> String {
+    match summary.instruction_count {
+        Some(count) => format!(
+            "{} · {}",
+            minimal_profile.to_str(),
+            format_instruction_count(count)
+        ),
+        None => minimal_profile.to_str().to_string(),
+    }
+}
+
+/// Formats an instruction count, rounding counts of a thousand or more to the nearest hundred.
+fn format_instruction_count(count: usize) -> String {
+    match count {
+        1 => "1 instruction".to_string(),
+        0..1000 => format!("{count} instructions"),
+        _ => {
+            let hundreds = (count + 50) / 100;
+            if hundreds % 10 == 0 {
+                format!("~{}k instructions", hundreds / 10)
+            } else {
+                format!("~{}.{}k instructions", hundreds / 10, hundreds % 10)
+            }
+        }
+    }
+}
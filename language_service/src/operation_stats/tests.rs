@@ -0,0 +1,122 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{format_instruction_count, get_operation_stats};
+use crate::{test_utils::compile_with_markers, Encoding};
+use expect_test::{expect, Expect};
+
+fn check(source: &str, expect: &Expect) {
+    let (compilation, _, _) = compile_with_markers(source, false);
+    let actual = get_operation_stats(&compilation, "<source>", Encoding::Utf8)
+        .into_iter()
+        .map(|stats| (stats.range.start.line, stats.capabilities, stats.label))
+        .collect::<Vec<_>>();
+    expect.assert_debug_eq(&actual);
+}
+
+#[test]
+fn operations_report_minimal_profile_and_instruction_count() {
+    check(
+        r#"
+        namespace Test {
+            operation Static() : Result {
+                use q = Qubit();
+                H(q);
+                M(q)
+            }
+            operation Dynamic() : Unit {
+                use q = Qubit();
+                if M(q) == One {
+                    X(q);
+                }
+            }
+            operation TakesQubit(q : Qubit) : Unit {
+                H(q);
+            }
+            function NotAnOperation() : Unit {}
+        }↘"#,
+        &expect![[r#"
+            [
+                (
+                    2,
+                    [],
+                    "Base · 4 instructions",
+                ),
+                (
+                    7,
+                    [
+                        "Adaptive",
+                    ],
+                    "Adaptive_RI · 7 instructions",
+                ),
+                (
+                    13,
+                    [],
+                    "Base",
+                ),
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn compilation_with_errors_has_no_stats() {
+    check(
+        r#"
+        namespace Test {
+            operation Foo() : Unit {
+                let x : Int = 1.0;
+            }
+        }↘"#,
+        &expect![[r#"
+            []
+        "#]],
+    );
+}
+
+#[test]
+fn stats_are_reused_for_the_same_compilation() {
+    let (compilation, _, _) = compile_with_markers(
+        r#"
+        namespace Test {
+            operation Foo() : Unit {}
+        }↘"#,
+        false,
+    );
+    let first = get_operation_stats(&compilation, "<source>", Encoding::Utf8);
+    let summaries = compilation
+        .operation_summaries
+        .get()
+        .expect("summaries should be computed on first use")
+        .as_ptr();
+    let second = get_operation_stats(&compilation, "<source>", Encoding::Utf8);
+    assert_eq!(first, second);
+    assert_eq!(
+        summaries,
+        compilation
+            .operation_summaries
+            .get()
+            .expect("summaries should still be cached")
+            .as_ptr()
+    );
+}
+
+#[test]
+fn instruction_counts_are_rounded_to_hundreds_above_a_thousand() {
+    let formatted = [0, 1, 999, 1000, 1249, 1250, 15_020]
+        .into_iter()
+        .map(format_instruction_count)
+        .collect::<Vec<_>>();
+    expect![[r#"
+        [
+            "0 instructions",
+            "1 instruction",
+            "999 instructions",
+            "~1k instructions",
+            "~1.2k instructions",
+            "~1.3k instructions",
+            "~15k instructions",
+        ]
+    "#]]
+    .assert_debug_eq(&formatted);
+}
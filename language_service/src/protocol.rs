@@ -210,3 +210,99 @@ pub struct OperationInfo {
     pub operation: String,
     pub total_num_qubits: u32,
 }
+
+/// The runtime capabilities required by a region of code.
+#[derive(Debug, PartialEq)]
+pub struct RequiredCapabilities {
+    /// The region of code the capabilities were computed for.
+    pub range: Range,
+    /// The names of the target capabilities the code requires.
+    pub capabilities: Vec<String>,
+    /// The most restrictive target profile that accepts the code.
+    pub minimal_profile: Profile,
+    /// The runtime features that make the capabilities necessary, in source order.
+    pub features: Vec<RuntimeFeature>,
+    /// A human-readable summary of the capabilities and the minimal profile.
+    pub summary: String,
+}
+
+/// The estimated size and required capabilities of an operation.
+#[derive(Debug, PartialEq)]
+pub struct OperationStats {
+    /// The declaration of the operation.
+    pub range: Range,
+    /// The names of the target capabilities the operation requires.
+    pub capabilities: Vec<String>,
+    /// The most restrictive target profile that accepts the operation.
+    pub minimal_profile: Profile,
+    /// The number of instructions generated for the operation, if it takes no input and code generation for the
+    /// minimal profile succeeds.
+    pub instruction_count: Option<usize>,
+    /// A short human-readable summary of the minimal profile and the instruction count.
+    pub label: String,
+}
+
+/// The entry point of a program and the parameters it must be called with.
+#[derive(Debug, PartialEq)]
+pub struct EntryPoint {
+    /// The declaration of the entry point callable.
+    pub range: Range,
+    /// The fully qualified name of the entry point callable.
+    pub callable: String,
+    /// The parameters of the entry point callable, in declaration order.
+    pub params: Vec<EntryPointParam>,
+}
+
+/// A parameter of an entry point callable.
+#[derive(Debug, PartialEq)]
+pub struct EntryPointParam {
+    /// The name of the parameter, or `_` if it is discarded.
+    pub name: String,
+    /// The type of the parameter as it is written in Q#.
+    pub ty: String,
+    /// A Q# expression that can be used as the initial value of the argument, if the type has one.
+    pub default_value: Option<String>,
+}
+
+/// An item that the dependents of a package can refer to.
+#[derive(Debug, PartialEq)]
+pub struct Export {
+    /// The name that dependents refer to the item by, qualified by its namespace in the package.
+    pub name: String,
+    /// Whether the item is a callable or a type.
+    pub kind: ExportKind,
+    /// The declaration of the item, which is in a dependency when the item is re-exported from it.
+    pub location: Location,
+}
+
+/// The kind of an exported item.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportKind {
+    Callable,
+    Type,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct RuntimeFeature {
+    pub range: Range,
+    pub description: String,
+}
+
+/// A token to highlight, found from the tokens of the source alone, so that highlighting works even
+/// when the source doesn't parse.
+#[derive(Debug, PartialEq)]
+pub struct SemanticToken {
+    pub range: Range,
+    pub kind: SemanticTokenKind,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SemanticTokenKind {
+    Comment,
+    DocComment,
+    Keyword,
+    TypeParameter,
+    Number,
+    String,
+    Operator,
+}
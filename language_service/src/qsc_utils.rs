@@ -2,6 +2,7 @@
 // Licensed under the MIT License.
 
 use crate::compilation::Compilation;
+use qsc::library::versioned_library_uri;
 use qsc::line_column::{Encoding, Range};
 use qsc::location::Location;
 use qsc::{hir::PackageId, SourceMap, Span};
@@ -24,16 +25,22 @@ pub(crate) fn into_range(encoding: Encoding, span: Span, source_map: &SourceMap)
     Range::from_span(encoding, &lo_source.contents, &(span - lo_source.offset))
 }
 
+/// Converts a span to a location. Locations in library sources use URIs that include the library
+/// version, so that the documents the editor opens for them always match the compiled sources.
 pub(crate) fn into_location(
     position_encoding: Encoding,
     compilation: &Compilation,
     span: Span,
     package_id: PackageId,
 ) -> Location {
-    Location::from(
+    let mut location = Location::from(
         span,
         package_id,
         &compilation.package_store,
         position_encoding,
-    )
+    );
+    if let Some(uri) = versioned_library_uri(&location.source) {
+        location.source = uri.into();
+    }
+    location
 }
@@ -199,6 +199,16 @@ impl<'a> ReferenceFinder<'a> {
     }
 
     pub fn for_item(&self, item_id: &hir::ItemId) -> Vec<Location> {
+        self.item_locations(item_id, false)
+    }
+
+    /// Gets the locations of the item like [`Self::for_item`], leaving out the references that use an alias
+    /// introduced by an import or export instead of the declared name of the item.
+    pub fn for_item_name(&self, item_id: &hir::ItemId) -> Vec<Location> {
+        self.item_locations(item_id, true)
+    }
+
+    fn item_locations(&self, item_id: &hir::ItemId, declared_name_only: bool) -> Vec<Location> {
         let mut locations = vec![];
 
         let (def, _, resolved_item_id) = self
@@ -208,7 +218,9 @@ impl<'a> ReferenceFinder<'a> {
             let def_span = match &def.kind {
                 hir::ItemKind::Callable(decl) => decl.name.span,
                 hir::ItemKind::Namespace(name, _) => name.span(),
-                hir::ItemKind::Ty(name, _) | hir::ItemKind::Export(name, _) => name.span,
+                hir::ItemKind::Ty(name, _)
+                | hir::ItemKind::Export(name, _)
+                | hir::ItemKind::Class(name, _) => name.span,
             };
             locations.push(
                 self.location(
@@ -222,6 +234,7 @@ impl<'a> ReferenceFinder<'a> {
 
         let mut find_refs = FindItemRefs {
             item_id: &resolved_item_id,
+            name: declared_name_only.then(|| item_name(def)).flatten(),
             compilation: self.compilation,
             locations: vec![],
         };
@@ -334,8 +347,21 @@ impl<'a> ReferenceFinder<'a> {
     }
 }
 
+/// Gets the declared name of the item, if it has a single name.
+pub(crate) fn item_name(item: &hir::Item) -> Option<Rc<str>> {
+    match &item.kind {
+        hir::ItemKind::Callable(decl) => Some(decl.name.name.clone()),
+        hir::ItemKind::Ty(name, _)
+        | hir::ItemKind::Export(name, _)
+        | hir::ItemKind::Class(name, _) => Some(name.name.clone()),
+        hir::ItemKind::Namespace(..) => None,
+    }
+}
+
 struct FindItemRefs<'a> {
     item_id: &'a hir::ItemId,
+    /// The name that references must use to be included, if any.
+    name: Option<Rc<str>>,
     compilation: &'a Compilation,
     locations: Vec<Span>,
 }
@@ -344,7 +370,7 @@ impl Visitor<'_> for FindItemRefs<'_> {
     fn visit_path(&mut self, path: &ast::Path) {
         let res = self.compilation.get_res(path.id);
         if let Some(resolve::Res::Item(item_id, _)) = res {
-            if self.eq(item_id) {
+            if self.eq(item_id) && self.has_name(&path.name) {
                 self.locations.push(path.name.span);
             }
         }
@@ -354,7 +380,7 @@ impl Visitor<'_> for FindItemRefs<'_> {
         if let ast::TyKind::Path(PathKind::Ok(ty_path)) = &*ty.kind {
             let res = self.compilation.get_res(ty_path.id);
             if let Some(resolve::Res::Item(item_id, _)) = res {
-                if self.eq(item_id) {
+                if self.eq(item_id) && self.has_name(&ty_path.name) {
                     self.locations.push(ty_path.name.span);
                 }
             }
@@ -370,6 +396,10 @@ impl FindItemRefs<'_> {
             && item_id.package.unwrap_or(self.compilation.user_package_id)
                 == self.item_id.package.expect("package id should be resolved")
     }
+
+    fn has_name(&self, ident: &ast::Ident) -> bool {
+        self.name.as_ref().is_none_or(|name| *name == ident.name)
+    }
 }
 
 struct FindFieldRefs<'a> {
@@ -391,7 +421,7 @@ impl Visitor<'_> for FindFieldRefs<'_> {
             // Loop through the parts of the path to find references
             for part in rest {
                 if part.name == self.field_name {
-                    if let Some(Ty::Udt(_, Res::Item(id))) = self.compilation.get_ty(prev_id) {
+                    if let Some(Ty::Udt(_, Res::Item(id), _)) = self.compilation.get_ty(prev_id) {
                         if self.eq(id) {
                             self.locations.push(part.span);
                         }
@@ -407,7 +437,9 @@ impl Visitor<'_> for FindFieldRefs<'_> {
             ast::ExprKind::Field(qualifier, ast::FieldAccess::Ok(field_name)) => {
                 self.visit_expr(qualifier);
                 if field_name.name == self.field_name {
-                    if let Some(Ty::Udt(_, Res::Item(id))) = self.compilation.get_ty(qualifier.id) {
+                    if let Some(Ty::Udt(_, Res::Item(id), _)) =
+                        self.compilation.get_ty(qualifier.id)
+                    {
                         if self.eq(id) {
                             self.locations.push(field_name.span);
                         }
@@ -421,7 +453,8 @@ impl Visitor<'_> for FindFieldRefs<'_> {
                 }
                 for field in fields {
                     if field.field.name == self.field_name {
-                        if let Some(Ty::Udt(_, Res::Item(id))) = self.compilation.get_ty(expr.id) {
+                        if let Some(Ty::Udt(_, Res::Item(id), _)) = self.compilation.get_ty(expr.id)
+                        {
                             if self.eq(id) {
                                 self.locations.push(field.field.span);
                             }
@@ -7,7 +7,7 @@ mod tests;
 use crate::compilation::Compilation;
 use crate::name_locator::{Handler, Locator, LocatorContext};
 use crate::qsc_utils::into_range;
-use crate::references::ReferenceFinder;
+use crate::references::{item_name, ReferenceFinder};
 use qsc::ast::visit::Visitor;
 use qsc::display::Lookup;
 use qsc::line_column::{Encoding, Position, Range};
@@ -89,10 +89,20 @@ impl<'a> Rename<'a> {
         let package_id = item_id.package.expect("package id should be resolved");
         // Only rename items that are part of the user package
         if package_id == self.compilation.user_package_id {
+            // A reference through an alias from an import or export names the alias rather than the item,
+            // so renaming the item from it would rename the item after the alias.
+            let (item, _, _) = self
+                .compilation
+                .resolve_item_relative_to_user_package(item_id);
+            if item_name(item).is_some_and(|name| name != ast_name.name) {
+                return;
+            }
             if self.is_prepare {
                 self.prepare = Some((ast_name.span, ast_name.name.to_string()));
             } else {
-                self.locations = self.reference_finder.for_item(item_id);
+                // References through aliases keep working after the rename, as the aliases are not renamed,
+                // and the paths in the import and export declarations introducing them are.
+                self.locations = self.reference_finder.for_item_name(item_id);
             }
         }
     }
@@ -132,6 +132,20 @@ fn local_ref() {
     );
 }
 
+#[test]
+fn local_interpolated_string_ref() {
+    check(
+        r#"
+        namespace Test {
+            operation Foo(x : Int) : Unit {
+                let ◉temp◉ = x;
+                let s = $"{◉temp◉} and {◉t↘emp◉ + 1}";
+            }
+        }
+    "#,
+    );
+}
+
 #[test]
 fn udt_def() {
     check(
@@ -664,3 +678,82 @@ fn notebook_rename_across_cells() {
         "#]],
     );
 }
+
+#[test]
+fn callable_in_export_list() {
+    check(
+        r#"
+        namespace Test {
+            operation ◉Fo↘o◉() : Unit {}
+            export ◉Foo◉, Test.◉Foo◉;
+        }
+        namespace Other {
+            operation Main() : Unit {
+                Test.◉Foo◉();
+            }
+        }
+    "#,
+    );
+}
+
+#[test]
+fn callable_ref_in_export_list() {
+    check(
+        r#"
+        namespace Test {
+            operation ◉Foo◉() : Unit {}
+            export Test.◉F↘oo◉;
+        }
+    "#,
+    );
+}
+
+#[test]
+fn callable_with_aliases_keeps_alias_refs() {
+    check(
+        r#"
+        namespace Test {
+            operation ◉Fo↘o◉() : Unit {}
+            export ◉Foo◉ as Bar;
+        }
+        namespace Other {
+            import Test.◉Foo◉ as Baz;
+            operation Main() : Unit {
+                ◉Foo◉();
+                Baz();
+                Test.Bar();
+            }
+            open Test;
+        }
+    "#,
+    );
+}
+
+#[test]
+fn no_rename_through_import_alias() {
+    assert_no_rename(
+        r#"
+        namespace Test {
+            operation Foo() : Unit {}
+        }
+        namespace Other {
+            import Test.Foo as Baz;
+            operation Main() : Unit {
+                Ba↘z();
+            }
+        }
+    "#,
+    );
+}
+
+#[test]
+fn no_rename_imported_namespace() {
+    assert_no_rename(
+        r#"
+        namespace Test {
+            import Std.Arr↘ays;
+            operation Main() : Unit {}
+        }
+    "#,
+    );
+}
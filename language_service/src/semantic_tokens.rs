@@ -0,0 +1,54 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    compilation::Compilation,
+    protocol::{SemanticToken, SemanticTokenKind},
+};
+use qsc::{
+    line_column::{Encoding, Range},
+    parse::highlight::{tokenize, HighlightKind},
+};
+
+/// Gets the tokens to highlight in the given source. Identifiers, punctuation and unrecognized
+/// text are left to the editor's own highlighting.
+pub(crate) fn get_semantic_tokens(
+    compilation: &Compilation,
+    source_name: &str,
+    position_encoding: Encoding,
+) -> Vec<SemanticToken> {
+    let contents = &compilation
+        .user_unit()
+        .sources
+        .find_by_name(source_name)
+        .expect("source should exist in the user source map")
+        .contents;
+
+    let (spans, kinds): (Vec<_>, Vec<_>) = tokenize(contents)
+        .into_iter()
+        .filter_map(|token| {
+            let kind = match token.kind {
+                HighlightKind::Comment => SemanticTokenKind::Comment,
+                HighlightKind::DocComment => SemanticTokenKind::DocComment,
+                HighlightKind::Keyword => SemanticTokenKind::Keyword,
+                HighlightKind::TypeParam => SemanticTokenKind::TypeParameter,
+                HighlightKind::Number => SemanticTokenKind::Number,
+                HighlightKind::String => SemanticTokenKind::String,
+                HighlightKind::Operator => SemanticTokenKind::Operator,
+                HighlightKind::Ident | HighlightKind::Punctuation | HighlightKind::Error => {
+                    return None
+                }
+            };
+            Some((token.span, kind))
+        })
+        .unzip();
+
+    Range::from_sorted_spans(position_encoding, contents, &spans)
+        .into_iter()
+        .zip(kinds)
+        .map(|(range, kind)| SemanticToken { range, kind })
+        .collect()
+}
@@ -0,0 +1,71 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::get_semantic_tokens;
+use crate::{test_utils::compile_with_fake_stdlib_and_markers_no_cursor, Encoding};
+use expect_test::{expect, Expect};
+use std::fmt::Write;
+
+fn check(source: &str, expect: &Expect) {
+    let (compilation, _) = compile_with_fake_stdlib_and_markers_no_cursor(source, true);
+    let mut actual = String::new();
+    for token in get_semantic_tokens(&compilation, "<source>", Encoding::Utf16) {
+        let _ = writeln!(
+            actual,
+            "{:?} {}:{}-{}:{}",
+            token.kind,
+            token.range.start.line,
+            token.range.start.column,
+            token.range.end.line,
+            token.range.end.column
+        );
+    }
+    expect.assert_eq(&actual);
+}
+
+#[test]
+fn tokens_of_callable() {
+    check(
+        r#"namespace Test {
+    /// Says hello.
+    operation Foo<'T>(x : 'T) : Int {
+        let s = "𝑓"; // Comment.
+        1 + 2
+    }
+}"#,
+        &expect![[r#"
+            Keyword 0:0-0:9
+            DocComment 1:4-1:19
+            Keyword 2:4-2:13
+            Operator 2:17-2:18
+            TypeParameter 2:18-2:20
+            Operator 2:20-2:21
+            TypeParameter 2:26-2:28
+            Keyword 3:8-3:11
+            Operator 3:14-3:15
+            String 3:16-3:20
+            Comment 3:22-3:33
+            Number 4:8-4:9
+            Operator 4:10-4:11
+            Number 4:12-4:13
+        "#]],
+    );
+}
+
+#[test]
+fn tokens_of_source_that_does_not_parse() {
+    check(
+        r#"namespace Test {
+    operation Foo() : Unit {
+        let x = 0x + $"unterminated {x"#,
+        &expect![[r#"
+            Keyword 0:0-0:9
+            Keyword 1:4-1:13
+            Keyword 2:8-2:11
+            Operator 2:14-2:15
+            Number 2:16-2:18
+            Operator 2:19-2:20
+            String 2:21-2:37
+        "#]],
+    );
+}
@@ -6,7 +6,7 @@ mod tests;
 
 use crate::protocol::TestCallable;
 
-use super::compilation::Compilation;
+use super::compilation::{Compilation, CompilationKind};
 use super::protocol::{
     DiagnosticUpdate, ErrorKind, NotebookMetadata, TestCallables, WorkspaceConfigurationUpdate,
 };
@@ -108,6 +108,13 @@ pub(super) struct CompilationStateUpdater<'a> {
     /// Callback which will receive test callables whenever a (re-)compilation occurs.
     test_callable_receiver: Box<dyn Fn(TestCallables) + 'a>,
     cache: RefCell<PackageCache>,
+    /// The most recent parse of each user source, so that an edit only reparses
+    /// the top-level items it touched.
+    parse_cache: RefCell<compile::ParseCache>,
+    /// The most recently compiled standard library and dependencies, so that a change to
+    /// the user code or to a notebook cell doesn't recompile the packages it depends on. The
+    /// open package itself is always resolved and type-checked again in full.
+    compile_cache: RefCell<compile::CompileCache>,
     /// Functions to interact with the host filesystem for project system operations.
     project_host: Box<dyn JSProjectHost>,
     /// Encoding for converting between line/column and byte offsets.
@@ -129,6 +136,8 @@ impl<'a> CompilationStateUpdater<'a> {
             diagnostics_receiver: Box::new(diagnostics_receiver),
             test_callable_receiver: Box::new(test_callable_receiver),
             cache: RefCell::default(),
+            parse_cache: RefCell::default(),
+            compile_cache: RefCell::default(),
             project_host: Box::new(project_host),
             position_encoding,
         }
@@ -184,6 +193,7 @@ impl<'a> CompilationStateUpdater<'a> {
         }
 
         self.insert_buffer_aware_compilation(project);
+        self.collect();
 
         self.publish_diagnostics_and_test_callables();
     }
@@ -265,6 +275,8 @@ impl<'a> CompilationStateUpdater<'a> {
                 loaded_project.package_graph_sources,
                 loaded_project.errors,
                 &loaded_project.name,
+                &mut self.parse_cache.borrow_mut(),
+                &mut self.compile_cache.borrow_mut(),
             );
 
             state
@@ -286,6 +298,7 @@ impl<'a> CompilationStateUpdater<'a> {
                 self.insert_buffer_aware_compilation(project);
             }
         }
+        self.collect();
 
         self.publish_diagnostics_and_test_callables();
     }
@@ -377,6 +390,7 @@ impl<'a> CompilationStateUpdater<'a> {
                 configuration.language_features,
                 &configuration.lints_config,
                 project,
+                &mut self.compile_cache.borrow_mut(),
             );
 
             state.compilations.insert(
@@ -384,6 +398,7 @@ impl<'a> CompilationStateUpdater<'a> {
                 (compilation, notebook_configuration),
             );
         });
+        self.collect();
         self.publish_diagnostics_and_test_callables();
     }
 
@@ -401,10 +416,57 @@ impl<'a> CompilationStateUpdater<'a> {
             // Then remove the notebook itself from the compilations map
             state.compilations.remove(notebook_uri);
         });
+        self.collect();
 
         self.publish_diagnostics_and_test_callables();
     }
 
+    /// Frees what no open compilation can reach anymore: packages in each compilation's
+    /// package store that the user package doesn't depend on, the cached parses of sources
+    /// that are no longer part of a compilation, and the cached dependency packages that no
+    /// project refers to since their documents were closed or their manifests changed.
+    pub(super) fn collect(&mut self) {
+        self.with_state_mut(|state| {
+            let mut sources = FxHashSet::default();
+            let mut packages = FxHashSet::default();
+            for (compilation, _) in state.compilations.values_mut() {
+                let removed = compilation
+                    .package_store
+                    .collect([compilation.user_package_id]);
+                if !removed.is_empty() {
+                    trace!("collected packages {removed:?}");
+                }
+                // Sources are parsed, and so cached, under their names relative to the project root.
+                sources.extend(
+                    compilation
+                        .user_unit()
+                        .sources
+                        .relative_sources()
+                        .map(|source| source.name),
+                );
+                let package_graph_sources = match &compilation.kind {
+                    CompilationKind::OpenProject {
+                        package_graph_sources,
+                        ..
+                    } => Some(package_graph_sources),
+                    CompilationKind::Notebook { project } => project
+                        .as_ref()
+                        .map(|project| &project.package_graph_sources),
+                };
+                if let Some(package_graph_sources) = package_graph_sources {
+                    packages.extend(package_graph_sources.packages.keys().cloned());
+                }
+            }
+
+            self.parse_cache
+                .borrow_mut()
+                .retain(|source_name| sources.contains(source_name));
+            self.cache
+                .borrow_mut()
+                .retain(|key, _| packages.contains(key));
+        });
+    }
+
     // It gets really messy knowing when to clear diagnostics
     // when the document changes ownership between compilations, etc.
     // So let's do it the simplest way possible. Republish all the diagnostics every time.
@@ -512,6 +574,8 @@ impl<'a> CompilationStateUpdater<'a> {
                     configuration.target_profile,
                     configuration.language_features,
                     &lints_config,
+                    &mut self.parse_cache.borrow_mut(),
+                    &mut self.compile_cache.borrow_mut(),
                 );
             }
         });
@@ -1,7 +1,7 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
-use std::sync::Arc;
+use std::{cell::OnceCell, sync::Arc};
 
 use crate::compilation::{Compilation, CompilationKind};
 use qsc::{
@@ -238,6 +238,7 @@ fn compile_project_with_markers_cursor_optional(
             project_errors: Vec::new(),
             dependencies: dependencies.into_iter().collect(),
             test_cases,
+            operation_summaries: OnceCell::new(),
         },
         cursor_location,
         target_spans,
@@ -298,6 +299,7 @@ where
         project_errors: Vec::new(),
         dependencies: [(source_package_id, None)].into_iter().collect(),
         test_cases: Default::default(),
+        operation_summaries: OnceCell::new(),
     }
 }
 
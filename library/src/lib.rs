@@ -6,6 +6,10 @@ mod tests;
 
 pub const QSHARP_LIBRARY_URI_SCHEME: &str = "qsharp-library-source";
 
+/// The version of the library. It is included in the URIs of library sources that are given to
+/// the editor, so that documents opened from them always show the sources of this version.
+pub const LIBRARY_VERSION: &str = "0.0.0";
+
 // The core prefix on the name is needed to disambiguate from the std
 // files of the same name. This comes in during debugging when we need
 // to load a core/std file from the library.
@@ -89,8 +93,61 @@ pub const STD_LIB: &[(&str, &str)] = &[
         "qsharp-library-source:Std/TableLookup.qs",
         include_str!("../std/src/Std/TableLookup.qs"),
     ),
+    (
+        "qsharp-library-source:Std/Tasks.qs",
+        include_str!("../std/src/Std/Tasks.qs"),
+    ),
+    (
+        "qsharp-library-source:Std/Units.qs",
+        include_str!("../std/src/Std/Units.qs"),
+    ),
     (
         "qsharp-library-source:Std/legacy_api.qs",
         include_str!("../std/src/legacy_api.qs"),
     ),
 ];
+
+/// Gets the URI of a library source that includes the library version, for example
+/// `qsharp-library-source:v1.0.0/Std/Arrays.qs` for `qsharp-library-source:Std/Arrays.qs`.
+///
+/// Returns `None` if the source is not from the library.
+#[must_use]
+pub fn versioned_library_uri(source_name: &str) -> Option<String> {
+    let path = library_path(source_name)?;
+    if version_prefix(path).is_some() {
+        return Some(source_name.to_string());
+    }
+    Some(format!(
+        "{QSHARP_LIBRARY_URI_SCHEME}:v{LIBRARY_VERSION}/{path}"
+    ))
+}
+
+/// Gets the name of the library source at the URI, which may include the library version.
+///
+/// Returns `None` if the URI is not a library source URI, or if it includes a different version of
+/// the library, whose sources may not match the sources of this version.
+#[must_use]
+pub fn library_source_name(uri: &str) -> Option<String> {
+    let path = library_path(uri)?;
+    match version_prefix(path) {
+        Some((version, path)) if version == LIBRARY_VERSION => {
+            Some(format!("{QSHARP_LIBRARY_URI_SCHEME}:{path}"))
+        }
+        Some(_) => None,
+        None => Some(uri.to_string()),
+    }
+}
+
+fn library_path(uri: &str) -> Option<&str> {
+    uri.strip_prefix(QSHARP_LIBRARY_URI_SCHEME)?
+        .strip_prefix(':')
+}
+
+/// Splits a path starting with a version, such as `v1.0.0/Std/Arrays.qs`, into the version and the
+/// rest of the path.
+fn version_prefix(path: &str) -> Option<(&str, &str)> {
+    let (version, path) = path.strip_prefix('v')?.split_once('/')?;
+    version
+        .starts_with(|c: char| c.is_ascii_digit())
+        .then_some((version, path))
+}
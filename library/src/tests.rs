@@ -13,6 +13,9 @@ mod math;
 mod measurement;
 mod state_preparation;
 mod table_lookup;
+mod tasks;
+mod units;
+mod uri;
 
 use indoc::indoc;
 use qsc::{
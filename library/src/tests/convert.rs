@@ -1,7 +1,8 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 #![allow(clippy::too_many_lines)]
-use super::test_expression;
+use super::{test_expression, test_expression_fails};
+use expect_test::expect;
 use qsc::interpret::Value;
 
 // Tests for Microsoft.Quantum.Convert namespace
@@ -277,3 +278,100 @@ fn test_complex_polar_as_complex() {
         &Value::Tuple(vec![Value::Double(2.0), Value::Double(1.0)].into()),
     );
 }
+
+#[test]
+fn check_try_int_as_double() {
+    test_expression(
+        "Std.Convert.TryIntAsDouble(1 <<< 53)",
+        &Value::Tuple(vec![Value::Bool(true), Value::Double(9_007_199_254_740_992.0)].into()),
+    );
+}
+
+#[test]
+fn check_try_int_as_double_inexact() {
+    test_expression(
+        "Std.Convert.TryIntAsDouble((1 <<< 53) + 1)",
+        &Value::Tuple(vec![Value::Bool(false), Value::Double(0.0)].into()),
+    );
+}
+
+#[test]
+fn check_try_int_as_double_max_int() {
+    test_expression(
+        "Std.Convert.TryIntAsDouble(0x7FFFFFFFFFFFFFFF)",
+        &Value::Tuple(vec![Value::Bool(false), Value::Double(0.0)].into()),
+    );
+}
+
+#[test]
+fn check_try_double_as_int() {
+    test_expression(
+        "Std.Convert.TryDoubleAsInt(-42.0)",
+        &Value::Tuple(vec![Value::Bool(true), Value::Int(-42)].into()),
+    );
+}
+
+#[test]
+fn check_try_double_as_int_fractional() {
+    test_expression(
+        "Std.Convert.TryDoubleAsInt(4.5)",
+        &Value::Tuple(vec![Value::Bool(false), Value::Int(0)].into()),
+    );
+}
+
+#[test]
+fn check_try_double_as_int_out_of_range() {
+    test_expression(
+        "Std.Convert.TryDoubleAsInt(1e19)",
+        &Value::Tuple(vec![Value::Bool(false), Value::Int(0)].into()),
+    );
+}
+
+#[test]
+fn check_try_double_as_int_nan() {
+    test_expression(
+        "Std.Convert.TryDoubleAsInt(Std.Math.Sqrt(-1.0))",
+        &Value::Tuple(vec![Value::Bool(false), Value::Int(0)].into()),
+    );
+}
+
+#[test]
+fn check_try_big_int_as_int() {
+    test_expression(
+        "Std.Convert.TryBigIntAsInt(-9223372036854775808L)",
+        &Value::Tuple(vec![Value::Bool(true), Value::Int(i64::MIN)].into()),
+    );
+}
+
+#[test]
+fn check_try_big_int_as_int_out_of_range() {
+    test_expression(
+        "Std.Convert.TryBigIntAsInt(9223372036854775808L)",
+        &Value::Tuple(vec![Value::Bool(false), Value::Int(0)].into()),
+    );
+}
+
+#[test]
+fn check_double_as_int() {
+    test_expression("Std.Convert.DoubleAsInt(3.0)", &Value::Int(3));
+}
+
+#[test]
+fn check_double_as_int_fails_on_fractional_input() {
+    let err = test_expression_fails("Std.Convert.DoubleAsInt(3.5)");
+    expect!["program failed: 3.5 cannot be converted to an Int without losing information."]
+        .assert_eq(&err);
+}
+
+#[test]
+fn check_big_int_as_int_fails_when_out_of_range() {
+    let err = test_expression_fails("Std.Convert.BigIntAsInt(1L <<< 64)");
+    expect!["program failed: 18446744073709551616 is out of the range of Int."].assert_eq(&err);
+}
+
+#[test]
+fn check_int_as_double_exact_fails_when_inexact() {
+    let err = test_expression_fails("Std.Convert.IntAsDoubleExact((1 <<< 53) + 1)");
+    expect!["program failed: 9007199254740993 cannot be represented exactly as a Double."]
+        .assert_eq(&err);
+}
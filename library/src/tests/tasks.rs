@@ -0,0 +1,45 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::{test_expression, test_expression_fails};
+use expect_test::expect;
+use indoc::indoc;
+use qsc::interpret::Value;
+
+// Tests for Std.Tasks namespace
+
+#[test]
+fn check_run_all_joins_results_in_order() {
+    test_expression(
+        "Std.Tasks.RunAll([() -> 1, () -> 2 * 3, () -> 4 + 5])",
+        &Value::Array(vec![Value::Int(1), Value::Int(6), Value::Int(9)].into()),
+    );
+}
+
+#[test]
+fn check_run_all_reports_first_failure() {
+    let err = test_expression_fails(indoc! {r#"
+        Std.Tasks.RunAll([
+            () -> 1,
+            () -> fail "first",
+            () -> fail "second"
+        ])
+    "#});
+    expect!["program failed: first"].assert_eq(&err);
+}
+
+#[test]
+fn check_run_both() {
+    test_expression(
+        r#"Std.Tasks.RunBoth(() -> 3 * 7, () -> "oracle")"#,
+        &Value::Tuple(vec![Value::Int(21), Value::String("oracle".into())].into()),
+    );
+}
+
+#[test]
+fn check_parallel_mapped() {
+    test_expression(
+        "Std.Tasks.MappedAsTasks(x -> x * x, [1, 2, 3])",
+        &Value::Array(vec![Value::Int(1), Value::Int(4), Value::Int(9)].into()),
+    );
+}
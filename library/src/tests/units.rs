@@ -0,0 +1,166 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::test_expression;
+use indoc::indoc;
+use qsc::interpret::Value;
+
+// Tests for Std.Units namespace
+
+#[test]
+fn check_angles_are_added_as_doubles() {
+    test_expression(
+        indoc! {"{
+            import Std.Units.*;
+            Radians(1.0) + Radians(0.5)
+        }"},
+        &Value::Double(1.5),
+    );
+}
+
+#[test]
+fn check_angles_are_compared() {
+    test_expression(
+        indoc! {"{
+            import Std.Units.*;
+            Radians(1.0) < Radians(0.5) or Radians(1.0) == Radians(1.0)
+        }"},
+        &Value::Bool(true),
+    );
+}
+
+#[test]
+fn check_degrees_as_radians() {
+    test_expression(
+        "Std.Units.DegreesAsRadians(180.0)!",
+        &Value::Double(std::f64::consts::PI),
+    );
+}
+
+#[test]
+fn check_radians_as_degrees() {
+    test_expression(
+        "Std.Units.RadiansAsDegrees(Std.Units.Radians(Std.Math.PI()))",
+        &Value::Double(180.0),
+    );
+}
+
+#[test]
+fn check_scaled_duration() {
+    test_expression(
+        indoc! {"{
+            import Std.Units.*;
+            ScaledDuration(3.0, NanosecondsAsMicroseconds(500.0))!
+        }"},
+        &Value::Double(1.5),
+    );
+}
+
+#[test]
+fn check_rotate_x() {
+    test_expression(
+        indoc! {"{
+            import Std.Units.*;
+            use q = Qubit();
+            RotateX(DegreesAsRadians(180.0), q);
+            MResetZ(q)
+        }"},
+        &Value::RESULT_ONE,
+    );
+}
+
+#[test]
+fn check_adjoint_rotate_z() {
+    test_expression(
+        indoc! {"{
+            import Std.Units.*;
+            use q = Qubit();
+            H(q);
+            RotateZ(Radians(Std.Math.PI() / 2.0), q);
+            Adjoint RotateZ(Radians(Std.Math.PI() / 2.0), q);
+            H(q);
+            MResetZ(q)
+        }"},
+        &Value::RESULT_ZERO,
+    );
+}
+
+#[test]
+fn check_double_as_angle() {
+    test_expression(
+        indoc! {"{
+            import Std.Units.*;
+            import Std.Math.*;
+            DoubleAsAngle(PI() / 2.0, 2, Round)!
+        }"},
+        &Value::Int(1 << 62),
+    );
+}
+
+#[test]
+fn check_double_as_angle_applies_rounding() {
+    test_expression(
+        indoc! {"{
+            import Std.Units.*;
+            import Std.Math.*;
+            (DoubleAsAngle(0.6 * PI(), 3, Floor)!, DoubleAsAngle(0.6 * PI(), 3, Ceiling)!)
+        }"},
+        &Value::Tuple(vec![Value::Int(2 << 61), Value::Int(3 << 61)].into()),
+    );
+}
+
+#[test]
+fn check_double_as_angle_just_below_half_a_turn_with_64_bits() {
+    test_expression(
+        indoc! {"{
+            import Std.Units.*;
+            import Std.Math.*;
+            (
+                DoubleAsAngle((0.5 - 2.0^-54.0) * 2.0 * PI(), 64, Ceiling)!,
+                DoubleAsAngle(-PI(), 64, Round)!
+            )
+        }"},
+        &Value::Tuple(vec![Value::Int(i64::MAX - 1023), Value::Int(i64::MIN)].into()),
+    );
+}
+
+#[test]
+fn check_double_as_angle_of_many_turns() {
+    test_expression(
+        indoc! {"{
+            import Std.Units.*;
+            import Std.Math.*;
+            (
+                DoubleAsAngle(1e20, 64, Round)!,
+                DoubleAsAngle(5.5 * PI(), 2, Round)!,
+                DoubleAsAngle(-1.5 * PI(), 2, Round)!
+            )
+        }"},
+        &Value::Tuple(vec![Value::Int(0), Value::Int(-1 << 62), Value::Int(1 << 62)].into()),
+    );
+}
+
+#[test]
+fn check_angles_wrap_around_at_a_full_turn() {
+    test_expression(
+        indoc! {"{
+            import Std.Units.*;
+            import Std.Math.*;
+            let half = DoubleAsAngle(PI(), 8, Round);
+            (half!, (half + half)!, MultipliedAngle(4, DoubleAsAngle(PI() / 2.0, 2, Round))!)
+        }"},
+        &Value::Tuple(vec![Value::Int(i64::MIN), Value::Int(0), Value::Int(0)].into()),
+    );
+}
+
+#[test]
+fn check_angle_as_double() {
+    test_expression(
+        indoc! {"{
+            import Std.Units.*;
+            import Std.Math.*;
+            AngleAsDouble(DoubleAsAngle(-PI() / 4.0, 16, Round))
+        }"},
+        &Value::Double(-std::f64::consts::FRAC_PI_4),
+    );
+}
@@ -0,0 +1,51 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use crate::{library_source_name, versioned_library_uri, LIBRARY_VERSION};
+
+#[test]
+fn library_uri_includes_version() {
+    assert_eq!(
+        versioned_library_uri("qsharp-library-source:Std/Arrays.qs"),
+        Some(format!(
+            "qsharp-library-source:v{LIBRARY_VERSION}/Std/Arrays.qs"
+        ))
+    );
+}
+
+#[test]
+fn versioned_library_uri_round_trips() {
+    let uri = versioned_library_uri("qsharp-library-source:Std/Arrays.qs")
+        .expect("library source should have a versioned URI");
+    assert_eq!(
+        versioned_library_uri(&uri).as_deref(),
+        Some(uri.as_str()),
+        "versioned URI should not be versioned again"
+    );
+    assert_eq!(
+        library_source_name(&uri).as_deref(),
+        Some("qsharp-library-source:Std/Arrays.qs")
+    );
+}
+
+#[test]
+fn unversioned_library_uri_is_source_name() {
+    assert_eq!(
+        library_source_name("qsharp-library-source:core/qir.qs").as_deref(),
+        Some("qsharp-library-source:core/qir.qs")
+    );
+}
+
+#[test]
+fn library_uri_with_other_version_has_no_source() {
+    assert_eq!(
+        library_source_name("qsharp-library-source:v0.1.2-other/Std/Arrays.qs"),
+        None
+    );
+}
+
+#[test]
+fn other_uri_is_not_library_source() {
+    assert_eq!(versioned_library_uri("file:///project/src/Main.qs"), None);
+    assert_eq!(library_source_name("file:///project/src/Main.qs"), None);
+}
@@ -5,7 +5,12 @@
 // the "./main.js" module is the entry point.
 
 import * as wasm from "../lib/web/qsc_wasm.js";
-import initWasm, { IProjectHost, TargetProfile } from "../lib/web/qsc_wasm.js";
+import initWasm, {
+  IProjectHost,
+  IQuota,
+  Simulator,
+  TargetProfile,
+} from "../lib/web/qsc_wasm.js";
 import {
   Compiler,
   ICompiler,
@@ -151,19 +156,29 @@ export function getLanguageServiceWorker(
   return createProxy(worker, wasmModule, languageServiceProtocol);
 }
 
-export { StepResultId } from "../lib/web/qsc_wasm.js";
+export { LineIndex, StepResultId } from "../lib/web/qsc_wasm.js";
 export type {
+  IAttributionNode,
+  IBranchDecision,
   IBreakpointSpan,
   ICodeAction,
   ICodeLens,
   IDocFile,
+  IEntryPoint,
+  IEntryPointParam,
+  IExport,
   ILocation,
+  IMemoryUsage,
   IOperationInfo,
+  IOperationStats,
+  IPackageMemoryUsage,
   IPosition,
   IProjectConfig,
   IProjectHost,
   IQSharpError,
   IRange,
+  IRequiredCapabilities,
+  ISemanticToken,
   IStackFrame,
   IStructStepResult,
   IWorkspaceEdit,
@@ -172,7 +187,11 @@ export type {
   VSDiagnostic,
 } from "../lib/web/qsc_wasm.js";
 export { type Dump, type ShotResult } from "./compiler/common.js";
-export { type CompilerState, type ProgramConfig } from "./compiler/compiler.js";
+export {
+  runShots,
+  type CompilerState,
+  type ProgramConfig,
+} from "./compiler/compiler.js";
 export { QscEventTarget } from "./compiler/events.js";
 export type {
   LanguageServiceDiagnosticEvent,
@@ -180,7 +199,13 @@ export type {
   LanguageServiceTestCallablesEvent,
 } from "./language-service/language-service.js";
 export { default as samples } from "./samples.generated.js";
-export { log, type LogLevel, type TargetProfile };
+export {
+  log,
+  type IQuota,
+  type LogLevel,
+  type Simulator,
+  type TargetProfile,
+};
 export type {
   ICompiler,
   ICompilerWorker,
@@ -18,6 +18,8 @@ interface DumpMsg {
   state: Dump;
   stateLatex: string | null;
   qubitCount: number;
+  // The ids of the qubits in the dump, in the order of the bits of each basis state.
+  qubits: number[];
 }
 
 interface MatrixMsg {
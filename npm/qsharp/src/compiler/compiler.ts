@@ -3,10 +3,14 @@
 
 import { type Circuit as CircuitData } from "@microsoft/quantum-viz.js/lib/circuit.js";
 import {
+  IAttributionNode,
   IDocFile,
   IOperationInfo,
   IPackageGraphSources,
   IProgramConfig as wasmIProgramConfig,
+  IQuota,
+  type ShotStream,
+  Simulator,
   TargetProfile,
   type VSDiagnostic,
 } from "../../lib/web/qsc_wasm.js";
@@ -16,10 +20,11 @@ import {
   ServiceProtocol,
   ServiceState,
 } from "../workers/common.js";
-import { eventStringToMsg } from "./common.js";
+import { ShotResult, eventStringToMsg } from "./common.js";
 import {
   IQscEventTarget,
   QscEventData,
+  QscEventTarget,
   QscEvents,
   makeEvent,
 } from "./events.js";
@@ -62,9 +67,48 @@ export interface ICompiler {
     eventHandler: IQscEventTarget,
   ): Promise<void>;
 
+  /**
+   * Prepares to run the program for the given number of shots, which then run
+   * one at a time with `runNextShot`. Replaces any shots prepared before.
+   * Each shot is bounded by the quota, if given, and runs on the given
+   * simulator, or the sparse simulator if none is given.
+   * Use `runShots` to consume the shots as an async iterator.
+   */
+  startShots(
+    program: ProgramConfig,
+    expr: string,
+    shots: number,
+    pauliNoise?: number[],
+    quota?: IQuota,
+    simulator?: Simulator,
+  ): Promise<void>;
+
+  /**
+   * Runs the next prepared shot, reporting its events like `run` does.
+   * Resolves to false once all prepared shots have run.
+   */
+  runNextShot(eventHandler: IQscEventTarget): Promise<boolean>;
+
+  /** Discards the prepared shots that have not run yet. */
+  stopShots(): Promise<void>;
+
   getQir(program: ProgramConfig): Promise<string>;
 
-  getEstimates(program: ProgramConfig, params: string): Promise<string>;
+  /** Generates LLVM bitcode, the binary form of QIR, for the program. */
+  getQirBitcode(program: ProgramConfig): Promise<Uint8Array>;
+
+  /**
+   * Checks the whole program against its target profile, including the checks
+   * that run during code generation, without generating any output. Rejects
+   * with all of the diagnostics at once if there are any.
+   */
+  checkForTarget(program: ProgramConfig): Promise<void>;
+
+  getEstimates(
+    program: ProgramConfig,
+    expr: string,
+    params: string,
+  ): Promise<string>;
 
   getCircuit(
     program: ProgramConfig,
@@ -72,6 +116,17 @@ export interface ICompiler {
     operation?: IOperationInfo,
   ): Promise<CircuitData>;
 
+  /**
+   * Explains how the program comes to produce a result shown in the histogram,
+   * as a tree of the measurement outcomes that its probability comes from.
+   * Only supported for small programs.
+   */
+  explainResult(
+    program: ProgramConfig,
+    expr: string,
+    result: string,
+  ): Promise<IAttributionNode>;
+
   getDocumentation(additionalProgram?: ProgramConfig): Promise<IDocFile[]>;
 
   checkExerciseSolution(
@@ -107,6 +162,7 @@ export type CompilerState = ServiceState;
 
 export class Compiler implements ICompiler {
   private wasm: Wasm;
+  private shotStream: ShotStream | undefined;
 
   constructor(wasm: Wasm) {
     log.info("Constructing a Compiler instance");
@@ -197,16 +253,62 @@ export class Compiler implements ICompiler {
       (msg: string) => onCompilerEvent(msg, eventHandler!),
       shots!,
       pauliNoise,
+      null,
     );
   }
 
+  async startShots(
+    program: ProgramConfig,
+    expr: string,
+    shots: number,
+    pauliNoise?: number[],
+    quota?: IQuota,
+    simulator?: Simulator,
+  ): Promise<void> {
+    this.shotStream?.free();
+    this.shotStream = undefined;
+    this.shotStream = new this.wasm.ShotStream(
+      toWasmProgramConfig(program, "unrestricted"),
+      expr,
+      shots,
+      pauliNoise ?? null,
+      quota ?? null,
+      simulator ?? null,
+    );
+  }
+
+  async runNextShot(eventHandler: IQscEventTarget): Promise<boolean> {
+    if (!this.shotStream) return false;
+    return this.shotStream.next_shot((msg: string) =>
+      onCompilerEvent(msg, eventHandler),
+    );
+  }
+
+  async stopShots(): Promise<void> {
+    this.shotStream?.free();
+    this.shotStream = undefined;
+  }
+
   async getQir(program: ProgramConfig): Promise<string> {
     return this.wasm.get_qir(toWasmProgramConfig(program, "base"));
   }
 
-  async getEstimates(program: ProgramConfig, params: string): Promise<string> {
+  async getQirBitcode(program: ProgramConfig): Promise<Uint8Array> {
+    return this.wasm.get_qir_bitcode(toWasmProgramConfig(program, "base"));
+  }
+
+  async checkForTarget(program: ProgramConfig): Promise<void> {
+    return this.wasm.check_for_target(toWasmProgramConfig(program, "base"));
+  }
+
+  async getEstimates(
+    program: ProgramConfig,
+    expr: string,
+    params: string,
+  ): Promise<string> {
     return this.wasm.get_estimates(
       toWasmProgramConfig(program, "unrestricted"),
+      expr,
       params,
     );
   }
@@ -223,6 +325,18 @@ export class Compiler implements ICompiler {
     );
   }
 
+  async explainResult(
+    program: ProgramConfig,
+    expr: string,
+    result: string,
+  ): Promise<IAttributionNode> {
+    return this.wasm.explain_result(
+      toWasmProgramConfig(program, "unrestricted"),
+      expr,
+      result,
+    );
+  }
+
   // Returns all autogenerated documentation files for the standard library
   // and loaded project (if requested). This include file names and metadata,
   // including specially formatted table of content file.
@@ -250,6 +364,43 @@ export class Compiler implements ICompiler {
   }
 }
 
+/**
+ * Runs the shots of a program one at a time and yields each shot as soon as it
+ * completes, so that results can be shown as they arrive. Breaking out of the
+ * loop stops the remaining shots. Works with the compiler in a worker too,
+ * since each shot is a separate request. A shot that exceeds the quota, if
+ * given, fails with a `Qsc.Eval.StepQuotaExceeded` or `Qsc.Eval.Timeout` error.
+ * Each shot runs on the given simulator, or the sparse simulator if none is
+ * given.
+ */
+export async function* runShots(
+  compiler: ICompiler,
+  program: ProgramConfig,
+  expr: string,
+  shots: number,
+  pauliNoise?: number[],
+  quota?: IQuota,
+  simulator?: Simulator,
+): AsyncGenerator<ShotResult> {
+  await compiler.startShots(
+    program,
+    expr,
+    shots,
+    pauliNoise,
+    quota,
+    simulator,
+  );
+  try {
+    for (;;) {
+      const shotEvents = new QscEventTarget(true);
+      if (!(await compiler.runNextShot(shotEvents))) return;
+      yield shotEvents.getResults()[0];
+    }
+  } finally {
+    await compiler.stopShots();
+  }
+}
+
 /**
  * Fills in the defaults, to convert from the backwards-compatible ProgramConfig,
  * to the IProgramConfig type that the wasm layer expects
@@ -298,6 +449,7 @@ export function onCompilerEvent(msg: string, eventTarget: IQscEventTarget) {
         state: qscMsg.state,
         stateLatex: qscMsg.stateLatex,
         qubitCount: qscMsg.qubitCount,
+        qubits: qscMsg.qubits,
       });
       break;
     case "Result":
@@ -326,11 +478,17 @@ export const compilerProtocol: ServiceProtocol<ICompiler, QscEventData> = {
     getHir: "request",
     getRir: "request",
     getQir: "request",
+    getQirBitcode: "request",
+    checkForTarget: "request",
     getEstimates: "request",
     getCircuit: "request",
+    explainResult: "request",
     getDocumentation: "request",
     run: "requestWithProgress",
     runWithPauliNoise: "requestWithProgress",
+    startShots: "request",
+    runNextShot: "requestWithProgress",
+    stopShots: "request",
     checkExerciseSolution: "requestWithProgress",
   },
   eventNames: ["DumpMachine", "Matrix", "Message", "Result"],
@@ -5,6 +5,7 @@ import { type Circuit as CircuitData } from "@microsoft/quantum-viz.js/lib/circu
 import type {
   DebugService,
   IBreakpointSpan,
+  IMemoryUsage,
   IQuantumState,
   IStackFrame,
   IStructStepResult,
@@ -35,6 +36,7 @@ export interface IDebugService {
   getLocalVariables(): Promise<Array<IVariable>>;
   captureQuantumState(): Promise<Array<IQuantumState>>;
   getCircuit(): Promise<CircuitData>;
+  getMemoryUsage(): Promise<IMemoryUsage>;
   getStackFrames(): Promise<IStackFrame[]>;
   evalContinue(
     bps: number[],
@@ -95,6 +97,10 @@ export class QSharpDebugService implements IDebugService {
     return this.debugService.get_circuit();
   }
 
+  async getMemoryUsage(): Promise<IMemoryUsage> {
+    return this.debugService.get_memory_usage();
+  }
+
   async getStackFrames(): Promise<IStackFrame[]> {
     return this.debugService.get_stack_frames().frames;
   }
@@ -159,6 +165,7 @@ export function onCompilerEvent(msg: string, eventTarget: IQscEventTarget) {
         state: qscMsg.state,
         stateLatex: qscMsg.stateLatex,
         qubitCount: qscMsg.qubitCount,
+        qubits: qscMsg.qubits,
       });
       break;
     case "Result":
@@ -190,6 +197,7 @@ export const debugServiceProtocol: ServiceProtocol<
     getLocalVariables: "request",
     captureQuantumState: "request",
     getCircuit: "request",
+    getMemoryUsage: "request",
     getStackFrames: "request",
     evalContinue: "requestWithProgress",
     evalNext: "requestWithProgress",
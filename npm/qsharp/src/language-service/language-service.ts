@@ -5,11 +5,17 @@ import type {
   ICodeAction,
   ICodeLens,
   ICompletionList,
+  IEntryPoint,
+  IExport,
   IHover,
   ILocation,
+  IMemoryUsage,
   INotebookMetadata,
+  IOperationStats,
   IPosition,
   IRange,
+  IRequiredCapabilities,
+  ISemanticToken,
   ISignatureHelp,
   ITextEdit,
   IWorkspaceConfiguration,
@@ -64,6 +70,7 @@ export interface ILanguageService {
   ): Promise<void>;
   closeDocument(uri: string): Promise<void>;
   closeNotebookDocument(notebookUri: string): Promise<void>;
+  collect(): Promise<void>;
   getCodeActions(documentUri: string, range: IRange): Promise<ICodeAction[]>;
   getCompletions(
     documentUri: string,
@@ -97,6 +104,16 @@ export interface ILanguageService {
     position: IPosition,
   ): Promise<ITextEdit | undefined>;
   getCodeLenses(documentUri: string): Promise<ICodeLens[]>;
+  getRequiredCapabilities(
+    documentUri: string,
+    range: IRange,
+  ): Promise<IRequiredCapabilities | undefined>;
+  getInactiveRegions(documentUri: string): Promise<IRange[]>;
+  getSemanticTokens(documentUri: string): Promise<ISemanticToken[]>;
+  getOperationStats(documentUri: string): Promise<IOperationStats[]>;
+  getEntryPoint(documentUri: string): Promise<IEntryPoint | undefined>;
+  getExports(documentUri: string): Promise<IExport[]>;
+  getMemoryUsage(documentUri: string): Promise<IMemoryUsage>;
 
   dispose(): Promise<void>;
 
@@ -172,6 +189,10 @@ export class QSharpLanguageService implements ILanguageService {
     this.languageService.close_notebook_document(documentUri);
   }
 
+  async collect(): Promise<void> {
+    this.languageService.collect();
+  }
+
   async getCodeActions(
     documentUri: string,
     range: IRange,
@@ -249,6 +270,37 @@ export class QSharpLanguageService implements ILanguageService {
     return this.languageService.get_code_lenses(documentUri);
   }
 
+  async getRequiredCapabilities(
+    documentUri: string,
+    range: IRange,
+  ): Promise<IRequiredCapabilities | undefined> {
+    return this.languageService.get_required_capabilities(documentUri, range);
+  }
+
+  async getInactiveRegions(documentUri: string): Promise<IRange[]> {
+    return this.languageService.get_inactive_regions(documentUri);
+  }
+
+  async getSemanticTokens(documentUri: string): Promise<ISemanticToken[]> {
+    return this.languageService.get_semantic_tokens(documentUri);
+  }
+
+  async getOperationStats(documentUri: string): Promise<IOperationStats[]> {
+    return this.languageService.get_operation_stats(documentUri);
+  }
+
+  async getEntryPoint(documentUri: string): Promise<IEntryPoint | undefined> {
+    return this.languageService.get_entry_point(documentUri);
+  }
+
+  async getExports(documentUri: string): Promise<IExport[]> {
+    return this.languageService.get_exports(documentUri);
+  }
+
+  async getMemoryUsage(documentUri: string): Promise<IMemoryUsage> {
+    return this.languageService.get_memory_usage(documentUri);
+  }
+
   async dispose() {
     this.languageService.stop_background_work();
     await this.backgroundWork;
@@ -319,6 +371,7 @@ export const languageServiceProtocol: ServiceProtocol<
     updateNotebookDocument: "request",
     closeDocument: "request",
     closeNotebookDocument: "request",
+    collect: "request",
     getCodeActions: "request",
     getCompletions: "request",
     getFormatChanges: "request",
@@ -329,6 +382,13 @@ export const languageServiceProtocol: ServiceProtocol<
     getRename: "request",
     prepareRename: "request",
     getCodeLenses: "request",
+    getRequiredCapabilities: "request",
+    getInactiveRegions: "request",
+    getSemanticTokens: "request",
+    getOperationStats: "request",
+    getEntryPoint: "request",
+    getExports: "request",
+    getMemoryUsage: "request",
     dispose: "request",
     addEventListener: "addEventListener",
     removeEventListener: "removeEventListener",
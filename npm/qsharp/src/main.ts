@@ -30,6 +30,7 @@ import type { ProjectLoader } from "../lib/web/qsc_wasm.js";
 import { IProjectHost } from "./browser.js";
 
 export { qsharpLibraryUriScheme };
+export { runShots } from "./compiler/compiler.js";
 
 // Only load the Wasm module when first needed, as it may only be used in a Worker,
 // and not in the main thread.
@@ -9,10 +9,28 @@ export type ReData = {
   logicalQubit: any;
   tfactory: any;
   errorBudget: any;
+  floorplan?: Floorplan;
   logicalCounts: any;
   frontierEntries: FrontierEntry[];
 };
 
+// A sketch of the placement of logical qubit patches and T-factories on a grid
+// whose cells are the size of one logical qubit patch.
+export type Floorplan = {
+  width: number;
+  height: number;
+  regions: FloorplanRegion[];
+};
+
+export type FloorplanRegion = {
+  kind: "data" | "routing" | "tfactory";
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+  patches: number;
+};
+
 export type SingleEstimateResult = {
   status: string;
   jobParams: any;
@@ -21,6 +39,7 @@ export type SingleEstimateResult = {
   logicalQubit: any;
   tfactory: any;
   errorBudget: any;
+  floorplan?: Floorplan;
   logicalCounts: any;
 };
 
@@ -30,6 +49,7 @@ export type FrontierEntry = {
   errorBudget: any;
   physicalCounts: any;
   physicalCountsFormatted: any;
+  floorplan: Floorplan;
 };
 
 export function CreateSingleEstimateResult(
@@ -59,6 +79,7 @@ export function CreateSingleEstimateResult(
       logicalQubit: entry.logicalQubit,
       tfactory: entry.tfactory,
       errorBudget: entry.errorBudget,
+      floorplan: entry.floorplan,
       logicalCounts: input.logicalCounts,
     };
   }
@@ -22,12 +22,12 @@ if (window.require && window.CodeMirror && window.Jupyter) {
       },
       {
         token: "keyword",
-        regex: String.raw`(namespace|open|as|operation|function|body|adjoint|newtype|controlled|internal)\b`,
+        regex: String.raw`(namespace|open|as|operation|function|body|adjoint|newtype|enum|const|class|controlled|internal)\b`,
         beginWord: true,
       },
       {
         token: "keyword",
-        regex: String.raw`(if|elif|else|repeat|until|fixup|for|in|return|fail|within|apply)\b`,
+        regex: String.raw`(if|elif|else|match|repeat|until|fixup|for|in|return|fail|within|apply)\b`,
         beginWord: true,
       },
       {
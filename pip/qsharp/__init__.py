@@ -6,15 +6,21 @@ from ._qsharp import (
     init,
     eval,
     run,
+    run_sweep,
     compile,
     circuit,
+    program_stats,
     estimate,
+    logical_counts,
+    register_host_function,
     set_quantum_seed,
     set_classical_seed,
+    set_quota,
     dump_machine,
     dump_circuit,
     StateDump,
     ShotResult,
+    QubitReuseWarning,
     PauliNoise,
     DepolarizingNoise,
     BitFlipNoise,
@@ -40,19 +46,25 @@ __all__ = [
     "init",
     "eval",
     "run",
+    "run_sweep",
+    "register_host_function",
     "set_quantum_seed",
     "set_classical_seed",
+    "set_quota",
     "dump_machine",
     "dump_circuit",
     "compile",
     "circuit",
+    "program_stats",
     "estimate",
+    "logical_counts",
     "Result",
     "Pauli",
     "QSharpError",
     "TargetProfile",
     "StateDump",
     "ShotResult",
+    "QubitReuseWarning",
     "PauliNoise",
     "DepolarizingNoise",
     "BitFlipNoise",
@@ -164,6 +164,7 @@ class Interpreter:
         noise: Optional[Tuple[float, float, float]],
         callable: Optional[GlobalCallable],
         args: Optional[Any],
+        qubit_loss: Optional[float] = None,
     ) -> Any:
         """
         Runs the given Q# expression with an independent instance of the simulator.
@@ -174,6 +175,8 @@ class Interpreter:
             to use in simulation as a parametric Pauli noise.
         :param callable: The callable to run, if no entry expression is provided.
         :param args: The arguments to pass to the callable, if any.
+        :param qubit_loss: The probability that a measured qubit is lost, in which case the
+            measurement returns `Result.Loss`.
 
         :returns values: A result or runtime errors.
 
@@ -181,6 +184,60 @@ class Interpreter:
         """
         ...
 
+    def run_shots(
+        self,
+        entry_expr: str,
+        shots: int,
+        threads: Optional[int] = None,
+        noise: Optional[Tuple[float, float, float]] = None,
+        qubit_loss: Optional[float] = None,
+    ) -> List[Tuple[str, Any, Optional[str]]]:
+        """
+        Runs the given Q# expression for the given number of shots on several threads. Each
+        thread builds its own interpreter from the code evaluated so far, without the
+        registered host functions, and simulates its shots with the seeds, quota and simulator
+        of this interpreter. Shot `i` uses the quantum seed plus `i`.
+
+        :param entry_expr: The entry expression.
+        :param shots: The number of shots to run.
+        :param threads: The number of threads to use. Defaults to the available parallelism.
+        :param noise: A tuple with probabilities of Pauli-X, Pauli-Y, and Pauli-Z errors
+            to use in simulation as a parametric Pauli noise.
+        :param qubit_loss: The probability that a measured qubit is lost, in which case the
+            measurement returns `Result.Loss`.
+
+        :returns values: The printed output of each shot, in order, with either its result
+            or the runtime errors of the shot.
+
+        :raises QSharpError: If there is an error compiling the code on a thread.
+        """
+        ...
+
+    def run_sweep(
+        self,
+        callable: GlobalCallable,
+        args: List[Any],
+        shots: int,
+        output_fn: Optional[Callable[[Output], None]],
+        noise: Optional[Tuple[float, float, float]],
+    ) -> List[List[Any]]:
+        """
+        Runs the callable for the given number of shots with each of the given arguments,
+        reusing the compiled callable for every run.
+
+        :param callable: The callable to run.
+        :param args: The arguments of each parameter set, converted into the appropriate Q# values.
+        :param shots: The number of shots to run for each parameter set.
+        :param output_fn: A callback function that will be called with each output.
+        :param noise: A tuple with probabilities of Pauli-X, Pauli-Y, and Pauli-Z errors
+            to use in simulation as a parametric Pauli noise.
+
+        :returns values: The results of the shots of each parameter set, in the order of `args`.
+
+        :raises QSharpError: If there is an error interpreting the input or running a shot.
+        """
+        ...
+
     def invoke(
         self,
         callable: GlobalCallable,
@@ -214,6 +271,30 @@ class Interpreter:
         """
         ...
 
+    def qir_bitcode(self, entry_expr: str) -> bytes:
+        """
+        Generates QIR from the given entry expression as LLVM bitcode.
+
+        :param entry_expr: The entry expression.
+
+        :returns bitcode: The QIR as LLVM bitcode.
+        """
+        ...
+
+    def program_stats(self, entry_expr: str) -> Dict[str, Any]:
+        """
+        Computes instruction count and depth statistics for the program generated
+        from the given entry expression.
+
+        :param entry_expr: The entry expression.
+
+        :returns stats: A dictionary with the keys `gate_counts`, `two_qubit_gate_count`,
+            `measurement_count`, `classical_instruction_count`, `depth` and `feedback_depth`.
+
+        :raises QSharpError: If there is an error generating the program.
+        """
+        ...
+
     def circuit(
         self,
         entry_expr: Optional[str],
@@ -258,6 +339,79 @@ class Interpreter:
         """
         ...
 
+    def estimate_paths(
+        self,
+        params: str,
+        paths: int,
+        seed: int,
+        entry_expr: Optional[str],
+        callable: Optional[GlobalCallable],
+        args: Optional[Any],
+    ) -> str:
+        """
+        Estimates resources for Q# source code along the classical control paths
+        of seeded simulations of it.
+
+        :param params: The parameters to configure estimation.
+        :param paths: The number of paths to simulate.
+        :param seed: The seed of the first path. Each later path adds one to it.
+        :param entry_expr: The entry expression to estimate.
+        :param callable: The callable to estimate resources for, if no entry expression is provided.
+        :param args: The arguments to pass to the callable, if any.
+
+        :returns resources: The estimated resources of the expected counts, with
+            the counts of each path.
+        """
+        ...
+
+    def logical_counts(
+        self,
+        entry_expr: Optional[str],
+        callable: Optional[GlobalCallable],
+        args: Optional[Any],
+    ) -> str:
+        """
+        Computes the logical counts of Q# source code, which later estimates of the
+        same program reuse instead of running the program again.
+
+        :param entry_expr: The entry expression to count.
+        :param callable: The callable to count, if no entry expression is provided.
+        :param args: The arguments to pass to the callable, if any.
+
+        :returns counts: The logical counts as JSON.
+        """
+        ...
+
+    def register_host_function(self, name: str, function: Callable) -> None:
+        """
+        Registers a Python function that implements the Q# function with the
+        `@HostCallable()` attribute and the given name.
+
+        :param name: The name of the Q# function.
+        :param function: The Python function that implements it. It is called
+            with the arguments of the Q# function, and its return value is
+            converted to the output type of the Q# function.
+        """
+        ...
+
+    def set_reuse_analysis(self, enabled: bool) -> None:
+        """
+        Enables or disables the analysis of how subsequent runs reuse their qubits,
+        discarding the issues found so far.
+
+        :param enabled: Whether to analyze qubit reuse.
+        """
+        ...
+
+    def reuse_warnings(self) -> List[Tuple[str, int, int, int]]:
+        """
+        The issues in how qubits were reused, aggregated over the runs since the
+        analysis was enabled.
+
+        :returns warnings: A list of `(issue, qubit, occurrences, shots)` tuples.
+        """
+        ...
+
     def set_quantum_seed(self, seed: Optional[int]) -> None:
         """
         Sets the seed for the quantum random number generator.
@@ -276,6 +430,36 @@ class Interpreter:
         """
         ...
 
+    def set_quota(
+        self,
+        max_steps: Optional[int] = None,
+        timeout: Optional[float] = None,
+        max_call_depth: Optional[int] = None,
+    ) -> None:
+        """
+        Sets the limits on each subsequent run, which stops with an error once it
+        exceeds one. The limits that are not given are removed.
+
+        :param max_steps: The maximum number of evaluation steps a run can take.
+        :param timeout: The maximum time, in seconds, a run can take.
+        :param max_call_depth: The maximum number of nested calls a run can make.
+
+        :raises ValueError: If the timeout is negative or not finite.
+        """
+        ...
+
+    def set_simulator(self, simulator: str) -> None:
+        """
+        Selects the simulator that runs use.
+
+        :param simulator: "sparse" for the sparse state-vector simulator, "gpu" for the dense
+            state-vector simulator on the GPU, or "auto" for the stabilizer simulator, which switches
+            to the sparse simulator at the first non-Clifford operation.
+
+        :raises QSharpError: If the GPU simulator isn't part of this build or no GPU device is available.
+        """
+        ...
+
     def dump_machine(self) -> StateDumpData:
         """
         Returns the sparse state vector of the simulator as a StateDump object.
@@ -300,6 +484,7 @@ class Result(Enum):
 
     Zero: int
     One: int
+    Loss: int
 
 class Pauli(Enum):
     """
@@ -335,11 +520,22 @@ class StateDumpData:
     """
     qubit_count: int
 
+    """
+    The ids of the qubits in the dump, in the order of the bits of each basis state.
+    """
+    qubits: List[int]
+
     """
     Get the amplitudes of the state vector as a dictionary from state integer to
     complex amplitudes.
     """
     def get_dict(self) -> dict: ...
+
+    """
+    Get the amplitudes of the state vector as a dictionary from basis state label,
+    such as "|01⟩", to complex amplitudes.
+    """
+    def get_labeled_dict(self) -> Dict[str, complex]: ...
     def __repr__(self) -> str: ...
     def __str__(self) -> str: ...
     def _repr_markdown_(self) -> str: ...
@@ -22,11 +22,12 @@ from typing import (
     List,
     overload,
 )
-from .estimator._estimator import EstimatorResult, EstimatorParams
+from .estimator._estimator import EstimatorResult, EstimatorParams, LogicalCounts
 import json
 import os
 import sys
 import types
+import warnings
 from time import monotonic
 
 _interpreter = None
@@ -149,6 +150,7 @@ def init(
     target_name: Optional[str] = None,
     project_root: Optional[str] = None,
     language_features: Optional[List[str]] = None,
+    simulator: Optional[str] = None,
 ) -> Config:
     """
     Initializes the Q# interpreter.
@@ -162,6 +164,12 @@ def init(
 
     :param project_root: An optional path to a root directory with a Q# project to include.
         It must contain a qsharp.json project manifest.
+
+    :param simulator: An optional simulator for runs of the program: "sparse", the default, or "gpu"
+        for the dense state-vector simulator on the GPU, which is faster for large, highly entangled
+        states. The GPU simulator is only available in builds with GPU support. "auto" starts with the
+        stabilizer simulator, which scales to thousands of qubits for programs made only of Clifford
+        operations, and switches to the sparse simulator at the first non-Clifford operation.
     """
     from ._fs import read_file, list_directory, exists, join, resolve
     from ._http import fetch_github
@@ -224,6 +232,8 @@ def init(
         fetch_github,
         _make_callable,
     )
+    if simulator is not None:
+        _interpreter.set_simulator(simulator)
 
     _config = Config(target_profile, language_features, manifest_contents, project_root)
     # Return the configuration information to provide a hint to the
@@ -254,6 +264,11 @@ class StateDump:
     """
     qubit_count: int
 
+    """
+    The ids of the qubits in the dump, in the order of the bits of each basis state.
+    """
+    qubits: List[int]
+
     __inner: dict
     __data: StateDumpData
 
@@ -261,6 +276,7 @@ class StateDump:
         self.__data = data
         self.__inner = data.get_dict()
         self.qubit_count = data.qubit_count
+        self.qubits = data.qubits
 
     def __getitem__(self, index: int) -> complex:
         return self.__inner.__getitem__(index)
@@ -319,6 +335,13 @@ class StateDump:
         """
         return [self.__inner.get(i, complex(0)) for i in range(2**self.qubit_count)]
 
+    def as_labeled_dict(self) -> Dict[str, complex]:
+        """
+        Returns the state dump as a dictionary from basis state labels, such as "|01⟩",
+        to complex amplitudes. Only basis states with a nonzero amplitude are included.
+        """
+        return self.__data.get_labeled_dict()
+
 
 class ShotResult(TypedDict):
     """
@@ -447,6 +470,23 @@ def _make_callable(callable: GlobalCallable, namespace: List[str], callable_name
     module.__setattr__(callable_name, _callable)
 
 
+class QubitReuseWarning(UserWarning):
+    """
+    A warning about how a program reused a qubit, found by `run` with `analyze_reuse`.
+    Qubits that are used again after a measurement without being reset, or released in
+    a non-zero state, usually point to a logical bug.
+    """
+
+    def __init__(self, issue: str, qubit: int, occurrences: int, shots: int):
+        super().__init__(
+            f"{issue}: Qubit{qubit} ({occurrences} times in {shots} shots)"
+        )
+        self.issue = issue
+        self.qubit = qubit
+        self.occurrences = occurrences
+        self.shots = shots
+
+
 def run(
     entry_expr: Union[str, Callable],
     shots: int,
@@ -462,6 +502,9 @@ def run(
             DepolarizingNoise,
         ]
     ] = None,
+    qubit_loss: Optional[float] = None,
+    analyze_reuse: bool = False,
+    threads: Optional[int] = None,
 ) -> List[Any]:
     """
     Runs the given Q# expression for the given number of shots.
@@ -474,6 +517,16 @@ def run(
     :param on_result: A callback function that will be called with each result.
     :param save_events: If true, the output of each shot will be saved. If false, they will be printed.
     :param noise: The noise to use in simulation.
+    :param qubit_loss: The probability that a measured qubit is lost, in which case the
+        measurement returns `Result.Loss`.
+    :param analyze_reuse: If true, the shots are analyzed for qubits that are used again
+        after a measurement without being reset, or released in a non-zero state. Each
+        issue found is reported once, aggregated across shots, as a `QubitReuseWarning`.
+    :param threads: If given, the shots run in parallel on this many threads, each of which
+        compiles its own copy of the code evaluated so far. Shot `i` uses the quantum seed plus
+        `i`, so the results don't depend on the number of threads, but differ from those of
+        running the shots one after another. Only supported for entry expressions, without
+        saved events, reuse analysis, or host functions.
 
     :returns values: A list of results or runtime errors. If `save_events` is true,
     a List of ShotResults is returned.
@@ -519,24 +572,67 @@ def run(
         callable = entry_expr.__global_callable
         entry_expr = None
 
-    for shot in range(shots):
-        results.append(
-            {"result": None, "events": [], "messages": [], "matrices": [], "dumps": []}
-        )
-        run_results = get_interpreter().run(
-            entry_expr,
-            on_save_events if save_events else print_output,
-            noise,
-            callable,
-            args,
-        )
-        results[-1]["result"] = run_results
-        if on_result:
-            on_result(results[-1])
-        # For every shot after the first, treat the entry expression as None to trigger
-        # a rerun of the last executed expression without paying the cost for any additional
-        # compilation.
-        entry_expr = None
+    if threads is not None:
+        if callable is not None:
+            raise QSharpError(
+                "Running shots on several threads requires an entry expression."
+            )
+        if save_events or analyze_reuse:
+            raise QSharpError(
+                "Running shots on several threads does not support saved events or reuse analysis."
+            )
+        for output, value, error in get_interpreter().run_shots(
+            entry_expr, shots, threads, noise, qubit_loss
+        ):
+            if output:
+                print(output, end="", flush=True)
+            if error is not None:
+                raise QSharpError(error)
+            results.append(
+                {
+                    "result": value,
+                    "events": [],
+                    "messages": [],
+                    "matrices": [],
+                    "dumps": [],
+                }
+            )
+            if on_result:
+                on_result(results[-1])
+    else:
+        if analyze_reuse:
+            get_interpreter().set_reuse_analysis(True)
+        try:
+            for shot in range(shots):
+                results.append(
+                    {
+                        "result": None,
+                        "events": [],
+                        "messages": [],
+                        "matrices": [],
+                        "dumps": [],
+                    }
+                )
+                run_results = get_interpreter().run(
+                    entry_expr,
+                    on_save_events if save_events else print_output,
+                    noise,
+                    callable,
+                    args,
+                    qubit_loss,
+                )
+                results[-1]["result"] = run_results
+                if on_result:
+                    on_result(results[-1])
+                # For every shot after the first, treat the entry expression as None to trigger
+                # a rerun of the last executed expression without paying the cost for any additional
+                # compilation.
+                entry_expr = None
+        finally:
+            if analyze_reuse:
+                for warning in get_interpreter().reuse_warnings():
+                    warnings.warn(QubitReuseWarning(*warning), stacklevel=2)
+                get_interpreter().set_reuse_analysis(False)
 
     durationMs = (monotonic() - start_time) * 1000
     telemetry_events.on_run_end(durationMs, shots)
@@ -547,6 +643,64 @@ def run(
         return [shot["result"] for shot in results]
 
 
+def run_sweep(
+    callable: Callable,
+    params: List[Any],
+    shots: int = 1,
+    noise: Optional[
+        Union[
+            Tuple[float, float, float],
+            PauliNoise,
+            BitFlipNoise,
+            PhaseFlipNoise,
+            DepolarizingNoise,
+        ]
+    ] = None,
+) -> List[Tuple[Any, List[Any]]]:
+    """
+    Runs a parameter sweep of the given Q# callable: the callable is run for the given
+    number of shots with each of the given arguments. The callable is compiled once and
+    reused for every run, which makes sweeps much faster than calling `run` for each of
+    the arguments.
+
+    :param callable: The callable to run, which must be a Q# global callable.
+    :param params: The arguments to run the callable with, one entry per parameter set.
+        Use a tuple for callables that take more than one argument, and None for callables
+        that take no arguments.
+    :param shots: The number of shots to run for each parameter set.
+    :param noise: The noise to use in simulation.
+
+    :returns results: A list with one `(params, results)` pair per parameter set, in the
+        order of `params`, where `results` is the list of the results of its shots.
+
+    :raises QSharpError: If there is an error interpreting the input or running a shot.
+    """
+    ipython_helper()
+
+    if shots < 1:
+        raise QSharpError("The number of shots must be greater than 0.")
+    if not (
+        isinstance(callable, Callable) and hasattr(callable, "__global_callable")
+    ):
+        raise QSharpError("A parameter sweep requires a Q# global callable.")
+
+    telemetry_events.on_run(shots * len(params))
+    start_time = monotonic()
+
+    results = get_interpreter().run_sweep(
+        callable.__global_callable,
+        list(params),
+        shots,
+        lambda output: print(output, flush=True),
+        noise,
+    )
+
+    durationMs = (monotonic() - start_time) * 1000
+    telemetry_events.on_run_end(durationMs, shots * len(params))
+
+    return list(zip(params, results))
+
+
 # Class that wraps generated QIR, which can be used by
 # azure-quantum as input data.
 #
@@ -612,6 +766,27 @@ def compile(entry_expr: Union[str, Callable], *args) -> QirInputData:
     return res
 
 
+def program_stats(entry_expr: str) -> Dict[str, Any]:
+    """
+    Computes instruction count and depth statistics for the program that would be
+    compiled from the given entry expression. The counts are static: every instruction
+    is counted once, regardless of whether the branch it is on is taken.
+
+    :param entry_expr: The Q# expression that will be used as the entrypoint
+        for the program.
+
+    :returns stats: A dictionary with the per-gate counts (`gate_counts`), the number of
+        two-qubit gates (`two_qubit_gate_count`), measurements (`measurement_count`) and
+        classical instructions (`classical_instruction_count`), the estimated
+        circuit depth (`depth`), and the largest number of branches on measurement
+        results that run one after the other (`feedback_depth`).
+
+    :raises QSharpError: If there is an error generating the program.
+    """
+    ipython_helper()
+    return get_interpreter().program_stats(entry_expr)
+
+
 def circuit(
     entry_expr: Optional[Union[str, Callable]] = None,
     *args,
@@ -649,6 +824,8 @@ def estimate(
     entry_expr: Union[str, Callable],
     params: Optional[Union[Dict[str, Any], List, EstimatorParams]] = None,
     *args,
+    simulated_paths: Optional[int] = None,
+    seed: int = 0,
 ) -> EstimatorResult:
     """
     Estimates resources for Q# source code.
@@ -657,8 +834,17 @@ def estimate(
     :param entry_expr: The entry expression. Alternatively, a callable can be provided,
         which must be a Q# global callable.
     :param params: The parameters to configure physical estimation.
+    :param simulated_paths: If given, the program is simulated this many times, and
+        branches on measurement results follow the simulated results instead of random
+        ones. The estimates are of the expected counts over these paths, and the counts
+        of each path are available as `path_logical_counts` on the result.
+    :param seed: The seed of the first simulated path. Each later path adds one to it.
 
     :returns `EstimatorResult`: The estimated resources.
+
+    The logical counts of the program are cached, so estimating the same program again
+    with other parameters only repeats the physical estimation. The cache is cleared
+    whenever more Q# code is evaluated. Estimates along simulated paths aren't cached.
     """
 
     ipython_helper()
@@ -686,11 +872,24 @@ def estimate(
             args = args[0]
         elif len(args) == 0:
             args = None
-        res_str = get_interpreter().estimate(
-            param_str, callable=entry_expr.__global_callable, args=args
-        )
-    else:
+        if simulated_paths is None:
+            res_str = get_interpreter().estimate(
+                param_str, callable=entry_expr.__global_callable, args=args
+            )
+        else:
+            res_str = get_interpreter().estimate_paths(
+                param_str,
+                simulated_paths,
+                seed,
+                callable=entry_expr.__global_callable,
+                args=args,
+            )
+    elif simulated_paths is None:
         res_str = get_interpreter().estimate(param_str, entry_expr=entry_expr)
+    else:
+        res_str = get_interpreter().estimate_paths(
+            param_str, simulated_paths, seed, entry_expr=entry_expr
+        )
     res = json.loads(res_str)
 
     try:
@@ -703,6 +902,53 @@ def estimate(
     return EstimatorResult(res)
 
 
+def logical_counts(entry_expr: Union[str, Callable], *args) -> LogicalCounts:
+    """
+    Computes the logical counts of Q# source code, which are independent of the
+    physical parameters of an estimate. The counts can be estimated with many
+    different parameters without running the program again.
+    Either an entry expression or a callable with arguments must be provided.
+
+    :param entry_expr: The entry expression. Alternatively, a callable can be provided,
+        which must be a Q# global callable.
+
+    :returns `LogicalCounts`: The logical counts of the program.
+    """
+
+    ipython_helper()
+
+    if isinstance(entry_expr, Callable) and hasattr(entry_expr, "__global_callable"):
+        if len(args) == 1:
+            args = args[0]
+        elif len(args) == 0:
+            args = None
+        res_str = get_interpreter().logical_counts(
+            callable=entry_expr.__global_callable, args=args
+        )
+    else:
+        res_str = get_interpreter().logical_counts(entry_expr=entry_expr)
+    return LogicalCounts(json.loads(res_str))
+
+
+def register_host_function(name: str, function: Callable) -> None:
+    """
+    Registers a Python function that implements a Q# function declared with
+    the `@HostCallable()` attribute, such as
+    `@HostCallable() function Model(x : Double) : Double { body intrinsic; }`.
+
+    The Python function is called with the arguments of the Q# function
+    whenever the Q# function is called. Arguments and return values can be
+    int, float, bool and str values, and lists and tuples of them.
+
+    Registered functions are cleared when the interpreter is initialized with
+    `qsharp.init`.
+
+    :param name: The name of the Q# function.
+    :param function: The Python function that implements it.
+    """
+    get_interpreter().register_host_function(name, function)
+
+
 def set_quantum_seed(seed: Optional[int]) -> None:
     """
     Sets the seed for the random number generator used for quantum measurements.
@@ -726,6 +972,27 @@ def set_classical_seed(seed: Optional[int]) -> None:
     get_interpreter().set_classical_seed(seed)
 
 
+def set_quota(
+    max_steps: Optional[int] = None,
+    timeout: Optional[float] = None,
+    max_call_depth: Optional[int] = None,
+) -> None:
+    """
+    Sets the limits on each subsequent evaluation or run of Q# code, which stops
+    with a QSharpError once it exceeds one, so that untrusted programs can't run
+    forever. The limits that are not given are removed. Initializing the
+    interpreter with `qsharp.init` removes all limits.
+
+    :param max_steps: The maximum number of evaluation steps each evaluation or
+        shot can take.
+    :param timeout: The maximum time, in seconds, each evaluation or shot can take.
+    :param max_call_depth: The maximum number of nested calls each evaluation or
+        shot can make, so that unbounded recursion raises an error instead of
+        running out of memory.
+    """
+    get_interpreter().set_quota(max_steps, timeout, max_call_depth)
+
+
 def dump_machine() -> StateDump:
     """
     Returns the sparse state vector of the simulator as a StateDump object.
@@ -12,6 +12,8 @@ from ._estimator import (
     EstimatorQecScheme,
     ProtocolSpecificDistillationUnitSpecification,
     DistillationUnitSpecification,
+    FactoryRoundSpecification,
+    FactorySpecification,
     ErrorBudgetPartition,
     EstimatorConstraints,
     EstimatorInputParamsItem,
@@ -29,6 +31,8 @@ __all__ = [
     "EstimatorQecScheme",
     "ProtocolSpecificDistillationUnitSpecification",
     "DistillationUnitSpecification",
+    "FactoryRoundSpecification",
+    "FactorySpecification",
     "ErrorBudgetPartition",
     "EstimatorConstraints",
     "EstimatorInputParamsItem",
@@ -352,6 +352,56 @@ class DistillationUnitSpecification(AutoValidatingParams):
         return specification_dict
 
 
+@dataclass
+class FactoryRoundSpecification(AutoValidatingParams):
+    display_name: Optional[str] = None
+    num_units: Optional[int] = None
+    num_input_ts: Optional[int] = None
+    num_output_ts: Optional[int] = None
+    code_distance: Optional[int] = None
+    num_unit_qubits: Optional[int] = None
+    duration_in_qubit_cycle_time: Optional[int] = None
+    output_error_rate: Optional[float] = validating_field(_check_error_rate)
+    failure_probability: Optional[float] = None
+
+    def post_validation(self, result):
+        if self.display_name is None:
+            raise LookupError("display_name must be set")
+
+        if self.num_units is None:
+            raise LookupError("num_units must be set")
+
+        if self.num_input_ts is None:
+            raise LookupError("num_input_ts must be set")
+
+        if self.num_output_ts is None:
+            raise LookupError("num_output_ts must be set")
+
+        if self.num_unit_qubits is None:
+            raise LookupError("num_unit_qubits must be set")
+
+        if self.duration_in_qubit_cycle_time is None:
+            raise LookupError("duration_in_qubit_cycle_time must be set")
+
+        if self.output_error_rate is None:
+            raise LookupError("output_error_rate must be set")
+
+
+@dataclass
+class FactorySpecification(AutoValidatingParams):
+    rounds: List[FactoryRoundSpecification] = field(default_factory=list)
+
+    def post_validation(self, result):
+        if len(self.rounds) == 0:
+            raise LookupError("rounds must not be empty")
+
+    def as_dict(self, validate=True) -> Dict[str, Any]:
+        result = {"rounds": [round.as_dict(validate) for round in self.rounds]}
+        if validate:
+            self.post_validation(result)
+        return result
+
+
 @dataclass
 class ErrorBudgetPartition(AutoValidatingParams):
     logical: float = 0.001 / 3
@@ -383,6 +433,13 @@ class EstimatorInputParamsItem:
     Input params for microsoft.estimator target
 
     :ivar error_budget Total error budget for execution of the algorithm
+    :ivar feedback_latency Time the classical control system takes to act on a
+        measurement result in adaptive programs, such as "1 us"
+    :ivar factory_specifications Factory designs with fixed rounds that are
+        considered by the estimation, alone unless distillation unit
+        specifications are given too
+    :ivar factory_sharing Whether program phases that run one after the other
+        share their factories (default: True)
     """
 
     def __init__(self):
@@ -396,6 +453,9 @@ class EstimatorInputParamsItem:
         self.constraints: EstimatorConstraints = EstimatorConstraints()
         self.error_budget: Optional[Union[float, ErrorBudgetPartition]] = None
         self.estimate_type: Optional[str] = None
+        self.feedback_latency: Optional[str] = None
+        self.factory_specifications = []  # type: List[FactorySpecification]
+        self.factory_sharing: Optional[bool] = None
 
     def as_dict(self, validate=True, additional_params=None) -> Dict[str, Any]:
         result = {}
@@ -473,6 +533,20 @@ class EstimatorInputParamsItem:
                 )
             result["estimateType"] = self.estimate_type
 
+        if self.feedback_latency is not None:
+            if validate:
+                check_time("feedback_latency", self.feedback_latency)
+            result["feedbackLatency"] = self.feedback_latency
+
+        if len(self.factory_specifications) != 0:
+            result["factorySpecifications"] = [
+                specification.as_dict(validate)
+                for specification in self.factory_specifications
+            ]
+
+        if self.factory_sharing is not None:
+            result["factorySharing"] = self.factory_sharing
+
         return result
 
 
@@ -624,6 +698,17 @@ class EstimatorResult(dict):
         else:
             return LogicalCounts(self.data(0)["logicalCounts"])
 
+    @property
+    def path_logical_counts(self) -> Optional[List[LogicalCounts]]:
+        """
+        Returns the logical counts of each simulated path, if the resources
+        were estimated along simulated paths, and None otherwise.
+        """
+        data = self.data() if self._is_simple else self.data(0)
+        if "pathLogicalCounts" not in data:
+            return None
+        return [LogicalCounts(counts) for counts in data["pathLogicalCounts"]]
+
     def _repr_html_(self):
         """
         HTML table representation of the result.
@@ -1029,6 +1114,12 @@ class LogicalCounts(dict):
 
     The class represents logical counts that can be used as input to physical estimation of resources
     in the Microsoft Resource Estimator.
+
+    Counts can be created directly, without any Q# source, such as from a published
+    table of algorithm resources, and then estimated under different hardware
+    assumptions with `estimate`. The keys are `numQubits`, `tCount`, `rotationCount`,
+    `rotationDepth`, `cczCount`, `ccixCount` and `measurementCount`. The optional
+    `logicalDepth` replaces the logical depth that is otherwise computed from the counts.
     """
 
     def __init__(self, data: Dict):
@@ -1040,6 +1131,12 @@ class LogicalCounts(dict):
         self._data["cczCount"] = data.get("cczCount", 0)
         self._data["ccixCount"] = data.get("ccixCount", 0)
         self._data["measurementCount"] = data.get("measurementCount", 0)
+        if "feedbackDepth" in data:
+            self._data["feedbackDepth"] = data["feedbackDepth"]
+        if "logicalDepth" in data:
+            self._data["logicalDepth"] = data["logicalDepth"]
+        if "phases" in data:
+            self._data["phases"] = data["phases"]
         super().__init__(self._data)
 
     @property
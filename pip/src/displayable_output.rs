@@ -12,8 +12,10 @@ use qsc::{
 };
 use std::fmt::Write;
 
+/// A state dump: the amplitudes, the qubit count and the ids of the qubits, in the order of the bits of each
+/// basis state.
 #[derive(Clone)]
-pub struct DisplayableState(pub Vec<(BigUint, Complex64)>, pub usize);
+pub struct DisplayableState(pub Vec<(BigUint, Complex64)>, pub usize, pub Vec<usize>);
 pub struct DisplayableMatrix(pub Vec<Vec<Complex64>>);
 
 impl DisplayableState {
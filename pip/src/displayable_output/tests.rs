@@ -8,7 +8,11 @@ use crate::displayable_output::DisplayableState;
 
 #[test]
 fn display_neg_zero() {
-    let s = DisplayableState(vec![(BigUint::default(), Complex::new(-0.0, -0.0))], 1);
+    let s = DisplayableState(
+        vec![(BigUint::default(), Complex::new(-0.0, -0.0))],
+        1,
+        vec![0],
+    );
     // -0 should be displayed as 0.0000 without a minus sign
     assert_eq!("STATE:\n|0⟩: 0.0000+0.0000𝑖", s.to_plain());
 }
@@ -18,6 +22,7 @@ fn display_rounds_to_neg_zero() {
     let s = DisplayableState(
         vec![(BigUint::default(), Complex::new(-0.00001, -0.00001))],
         1,
+        vec![0],
     );
     // -0.00001 should be displayed as 0.0000 without a minus sign
     assert_eq!("STATE:\n|0⟩: 0.0000+0.0000𝑖", s.to_plain());
@@ -33,6 +38,7 @@ fn display_preserves_order() {
             (BigUint::from(3_u64), Complex::new(1.0, 1.0)),
         ],
         2,
+        vec![0, 1],
     );
     assert_eq!(
         "STATE:\n|00⟩: 0.0000+0.0000𝑖\n|01⟩: 0.0000+1.0000𝑖\n|10⟩: 1.0000+0.0000𝑖\n|11⟩: 1.0000+1.0000𝑖",
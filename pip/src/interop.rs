@@ -86,7 +86,11 @@ pub fn run_qasm3(
     fetch_github: Option<PyObject>,
     kwargs: Option<Bound<'_, PyDict>>,
 ) -> PyResult<PyObject> {
-    let mut receiver = OptionalCallbackReceiver { callback, py };
+    let mut receiver = OptionalCallbackReceiver {
+        callback,
+        py,
+        host_functions: None,
+    };
 
     let kwargs = kwargs.unwrap_or_else(|| PyDict::new(py));
 
@@ -17,25 +17,34 @@ use pyo3::{
     create_exception,
     exceptions::{PyException, PyValueError},
     prelude::*,
-    types::{PyDict, PyList, PyString, PyTuple, PyType},
+    types::{PyBool, PyBytes, PyDict, PyFloat, PyInt, PyList, PyString, PyTuple, PyType},
     IntoPyObjectExt,
 };
 use qsc::{
     fir::{self},
+    format_state_id,
     hir::ty::{Prim, Ty},
     interpret::{
         self,
-        output::{Error, Receiver},
-        CircuitEntryPoint, PauliNoise, Value,
+        output::{Error, GenericReceiver, Receiver, StateSnapshot},
+        CircuitEntryPoint, PauliNoise, SimulatorKind, Value,
     },
     packages::BuildableProgram,
     project::{FileSystem, PackageCache, PackageGraphSources},
+    shots::ShotConfig,
     target::Profile,
-    LanguageFeatures, PackageType, SourceMap,
+    LanguageFeatures, NoiseModel, PackageType, Quota, SourceMap, TargetCapabilityFlags,
 };
 
-use resource_estimator::{self as re, estimate_call, estimate_expr};
-use std::{cell::RefCell, fmt::Write, path::PathBuf, rc::Rc, str::FromStr};
+use resource_estimator::{self as re, LogicalCountsCache};
+use rustc_hash::FxHashMap;
+use std::{
+    cell::RefCell, fmt::Write, io, num::NonZeroUsize, path::PathBuf, rc::Rc, str::FromStr, thread,
+    time::Duration,
+};
+
+/// The printed output of a shot run in parallel, with either its result or its formatted errors.
+type ShotOutcome = (String, Option<PyObject>, Option<String>);
 
 /// If the classes are not Send, the Python interpreter
 /// will not be able to use them in a separate thread.
@@ -231,6 +240,43 @@ pub(crate) struct Interpreter {
     pub(crate) interpreter: interpret::Interpreter,
     /// The Python function to call to create a new function wrapping a callable invocation.
     pub(crate) make_callable: Option<PyObject>,
+    /// The Python functions that implement functions with the `@HostCallable()` attribute, by name.
+    pub(crate) host_functions: FxHashMap<String, PyObject>,
+    /// The logical counts of the programs estimated so far, which are reused when the same program
+    /// is estimated with other parameters. Cleared whenever new code is compiled.
+    pub(crate) logical_counts_cache: LogicalCountsCache,
+    /// The sources of the program, from which an interpreter is built on each thread when shots
+    /// run in parallel. `None` once code that can't be evaluated again, like OpenQASM, is added.
+    pub(crate) program: Option<ProgramSources>,
+}
+
+/// The sources a program was built from: the sources of its project and the fragments
+/// evaluated since, in order.
+pub(crate) struct ProgramSources {
+    target: TargetCapabilityFlags,
+    graph: PackageGraphSources,
+    fragments: Vec<String>,
+}
+
+impl ProgramSources {
+    /// Builds a new interpreter of the program. The fragments are compiled again, and the ones that
+    /// bind names are evaluated again with their output discarded.
+    fn build(&self) -> std::result::Result<interpret::Interpreter, Vec<interpret::Error>> {
+        let program = BuildableProgram::new(self.target, self.graph.clone());
+        let mut interpreter = interpret::Interpreter::new(
+            SourceMap::new(program.user_code.sources, None),
+            PackageType::Lib,
+            self.target,
+            program.user_code.language_features,
+            program.store,
+            &program.user_code_dependencies,
+        )?;
+        let mut sink = io::sink();
+        for fragment in &self.fragments {
+            interpreter.replay_fragments(&mut GenericReceiver::new(&mut sink), fragment)?;
+        }
+        Ok(interpreter)
+    }
 }
 
 thread_local! { static PACKAGE_CACHE: Rc<RefCell<PackageCache>> = Rc::default(); }
@@ -260,7 +306,7 @@ impl Interpreter {
 
         let package_cache = PACKAGE_CACHE.with(Clone::clone);
 
-        let buildable_program = if let Some(project_root) = project_root {
+        let graph = if let Some(project_root) = project_root {
             if let (Some(read_file), Some(list_directory), Some(resolve_path), Some(fetch_github)) =
                 (read_file, list_directory, resolve_path, fetch_github)
             {
@@ -273,18 +319,18 @@ impl Interpreter {
                     return Err(project.errors.into_py_err());
                 }
 
-                BuildableProgram::new(target, project.package_graph_sources)
+                project.package_graph_sources
             } else {
                 panic!("file system hooks should have been passed in with a manifest descriptor")
             }
         } else {
-            let graph = PackageGraphSources::with_no_dependencies(
+            PackageGraphSources::with_no_dependencies(
                 Vec::default(),
                 LanguageFeatures::from_iter(language_features),
                 None,
-            );
-            BuildableProgram::new(target, graph)
+            )
         };
+        let buildable_program = BuildableProgram::new(target, graph.clone());
 
         match interpret::Interpreter::new(
             SourceMap::new(buildable_program.user_code.sources, None),
@@ -305,6 +351,13 @@ impl Interpreter {
                 Ok(Self {
                     interpreter,
                     make_callable,
+                    host_functions: FxHashMap::default(),
+                    logical_counts_cache: LogicalCountsCache::default(),
+                    program: Some(ProgramSources {
+                        target,
+                        graph,
+                        fragments: Vec::new(),
+                    }),
                 })
             }
             Err(errors) => Err(QSharpError::new_err(format_errors(errors))),
@@ -326,9 +379,17 @@ impl Interpreter {
         input: &str,
         callback: Option<PyObject>,
     ) -> PyResult<PyObject> {
-        let mut receiver = OptionalCallbackReceiver { callback, py };
+        let mut receiver = OptionalCallbackReceiver {
+            callback,
+            py,
+            host_functions: Some(&self.host_functions),
+        };
+        self.logical_counts_cache.clear();
         match self.interpreter.eval_fragments(&mut receiver, input) {
             Ok(value) => {
+                if let Some(program) = &mut self.program {
+                    program.fragments.push(input.to_string());
+                }
                 if let Some(make_callable) = &self.make_callable {
                     // Get any global callables from the evaluated input and add them to the environment. This will grab
                     // every callable that was defined in the input and by previous calls that added to the open package.
@@ -345,6 +406,37 @@ impl Interpreter {
         }
     }
 
+    /// Registers a Python function that implements the Q# function with the `@HostCallable()` attribute and
+    /// the given name. The Python function is called with the arguments of the Q# function, and its return value
+    /// is converted to the output type of the Q# function.
+    fn register_host_function(&mut self, name: String, function: PyObject) {
+        self.host_functions.insert(name, function);
+    }
+
+    /// Enables or disables the analysis of how subsequent runs reuse their qubits, discarding the
+    /// issues found so far.
+    fn set_reuse_analysis(&mut self, enabled: bool) {
+        self.interpreter.set_reuse_analysis(enabled);
+    }
+
+    /// The issues in how qubits were reused, aggregated over the runs since the analysis was
+    /// enabled, as `(issue, qubit, occurrences, shots)` tuples.
+    fn reuse_warnings(&self) -> Vec<(String, usize, usize, usize)> {
+        self.interpreter
+            .reuse_warnings()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|warning| {
+                (
+                    warning.issue.to_string(),
+                    warning.qubit,
+                    warning.occurrences,
+                    warning.shots,
+                )
+            })
+            .collect()
+    }
+
     /// Sets the quantum seed for the interpreter.
     #[pyo3(signature=(seed=None))]
     fn set_quantum_seed(&mut self, seed: Option<u64>) {
@@ -357,12 +449,60 @@ impl Interpreter {
         self.interpreter.set_classical_seed(seed);
     }
 
+    /// Sets the limits on each subsequent run, which stops with an error once it exceeds one.
+    /// The limits that are not given are removed.
+    #[pyo3(signature=(max_steps=None, timeout=None, max_call_depth=None))]
+    fn set_quota(
+        &mut self,
+        max_steps: Option<u64>,
+        timeout: Option<f64>,
+        max_call_depth: Option<usize>,
+    ) -> PyResult<()> {
+        let timeout = timeout
+            .map(|timeout| {
+                Duration::try_from_secs_f64(timeout).map_err(|_| {
+                    PyValueError::new_err(format!(
+                        "timeout must be a non-negative number of seconds, not {timeout}"
+                    ))
+                })
+            })
+            .transpose()?;
+        self.interpreter.set_quota(Quota {
+            max_steps,
+            timeout,
+            max_call_depth,
+            ..Quota::default()
+        });
+        Ok(())
+    }
+
+    /// Selects the simulator that runs use, either "sparse", "gpu" or "auto".
+    fn set_simulator(&mut self, simulator: &str) -> PyResult<()> {
+        let kind = match simulator {
+            "sparse" => SimulatorKind::Sparse,
+            "gpu" => SimulatorKind::Gpu,
+            "auto" => SimulatorKind::Auto,
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "{simulator} is not a valid simulator"
+                )))
+            }
+        };
+        self.interpreter
+            .set_simulator(kind)
+            .map_err(QSharpError::new_err)
+    }
+
     /// Dumps the quantum state of the interpreter.
     /// Returns a tuple of (amplitudes, num_qubits), where amplitudes is a dictionary from integer indices to
     /// pairs of real and imaginary amplitudes.
     fn dump_machine(&mut self) -> StateDumpData {
         let (state, qubit_count) = self.interpreter.get_quantum_state();
-        StateDumpData(DisplayableState(state, qubit_count))
+        StateDumpData(DisplayableState(
+            state,
+            qubit_count,
+            (0..qubit_count).collect(),
+        ))
     }
 
     /// Dumps the current circuit state of the interpreter.
@@ -373,7 +513,8 @@ impl Interpreter {
         Circuit(self.interpreter.get_circuit()).into_py_any(py)
     }
 
-    #[pyo3(signature=(entry_expr=None, callback=None, noise=None, callable=None, args=None))]
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature=(entry_expr=None, callback=None, noise=None, callable=None, args=None, qubit_loss=None))]
     fn run(
         &mut self,
         py: Python,
@@ -382,17 +523,18 @@ impl Interpreter {
         noise: Option<(f64, f64, f64)>,
         callable: Option<GlobalCallable>,
         args: Option<PyObject>,
+        qubit_loss: Option<f64>,
     ) -> PyResult<PyObject> {
-        let mut receiver = OptionalCallbackReceiver { callback, py };
-
-        let noise = match noise {
-            None => None,
-            Some((px, py, pz)) => match PauliNoise::from_probabilities(px, py, pz) {
-                Ok(noise_struct) => Some(noise_struct),
-                Err(error_message) => return Err(PyException::new_err(error_message)),
-            },
+        let mut receiver = OptionalCallbackReceiver {
+            callback,
+            py,
+            host_functions: Some(&self.host_functions),
         };
 
+        let noise = pauli_noise(noise)?;
+        self.interpreter
+            .set_noise_model(qubit_loss_noise_model(qubit_loss)?);
+
         let result = match callable {
             Some(callable) => {
                 let (input_ty, output_ty) = self
@@ -412,6 +554,111 @@ impl Interpreter {
         }
     }
 
+    /// Runs the entry expression for the given number of shots on several threads. Each thread
+    /// builds its own interpreter from the sources evaluated so far, without the host functions
+    /// registered from Python, and simulates its shots with the noise, seeds, quota, memory budget
+    /// and simulator of this interpreter. Shot `i` is simulated with the quantum seed plus `i`, so
+    /// the results don't depend on the number of threads, but differ from those of running the
+    /// shots one after another. Returns the printed output of each shot, in order, with either its
+    /// result or its runtime errors.
+    #[pyo3(signature=(entry_expr, shots, threads=None, noise=None, qubit_loss=None))]
+    fn run_shots(
+        &self,
+        py: Python,
+        entry_expr: &str,
+        shots: usize,
+        threads: Option<usize>,
+        noise: Option<(f64, f64, f64)>,
+        qubit_loss: Option<f64>,
+    ) -> PyResult<Vec<ShotOutcome>> {
+        let program = self.program.as_ref().ok_or(QSharpError::new_err(
+            "shots can't run in parallel after OpenQASM code has been imported",
+        ))?;
+        let threads = threads
+            .and_then(NonZeroUsize::new)
+            .or_else(|| thread::available_parallelism().ok())
+            .unwrap_or(NonZeroUsize::MIN);
+        let mut simulation = self.interpreter.simulation_config();
+        simulation.noise_model = qubit_loss_noise_model(qubit_loss)?;
+        let config = ShotConfig {
+            entry_expr: Some(entry_expr.to_string()),
+            noise: pauli_noise(noise)?,
+            simulation,
+        };
+
+        let shots = py
+            .allow_threads(|| {
+                qsc::shots::run_shots(
+                    shots,
+                    threads,
+                    &config,
+                    || program.build(),
+                    |value| Python::with_gil(|py| ValueWrapper(value).into_py_any(py)),
+                )
+            })
+            .map_err(|errors| QSharpError::new_err(format_errors(errors)))?;
+
+        shots
+            .into_iter()
+            .map(|shot| match shot.result {
+                Ok(value) => Ok((shot.output, Some(value?), None)),
+                Err(errors) => Ok((shot.output, None, Some(format_errors(errors)))),
+            })
+            .collect()
+    }
+
+    /// Runs a parameter sweep of the callable: each of the argument values is run for the given
+    /// number of shots, reusing the compiled callable. Returns the results of the shots of each
+    /// argument value, in the order the argument values were given.
+    #[pyo3(signature=(callable, args, shots, callback=None, noise=None))]
+    #[allow(clippy::needless_pass_by_value)]
+    fn run_sweep(
+        &mut self,
+        py: Python,
+        callable: GlobalCallable,
+        args: Vec<Option<PyObject>>,
+        shots: usize,
+        callback: Option<PyObject>,
+        noise: Option<(f64, f64, f64)>,
+    ) -> PyResult<Vec<Vec<PyObject>>> {
+        let mut receiver = OptionalCallbackReceiver {
+            callback,
+            py,
+            host_functions: Some(&self.host_functions),
+        };
+
+        let noise = match noise {
+            None => None,
+            Some((px, py, pz)) => match PauliNoise::from_probabilities(px, py, pz) {
+                Ok(noise_struct) => Some(noise_struct),
+                Err(error_message) => return Err(PyException::new_err(error_message)),
+            },
+        };
+        let (input_ty, output_ty) = self
+            .interpreter
+            .global_tys(&callable.0)
+            .ok_or(QSharpError::new_err("callable not found"))?;
+        let args = args
+            .into_iter()
+            .map(|args| args_to_values(py, args, &input_ty, &output_ty))
+            .collect::<PyResult<Vec<_>>>()?;
+
+        self.interpreter
+            .run_sweep(&mut receiver, &callable.0, args, shots, noise)
+            .into_iter()
+            .map(|point| {
+                point
+                    .results
+                    .into_iter()
+                    .map(|result| match result {
+                        Ok(value) => Ok(ValueWrapper(value).into_pyobject(py)?.unbind()),
+                        Err(errors) => Err(QSharpError::new_err(format_errors(errors))),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
     #[pyo3(signature=(callable, args=None, callback=None))]
     fn invoke(
         &mut self,
@@ -420,7 +667,11 @@ impl Interpreter {
         args: Option<PyObject>,
         callback: Option<PyObject>,
     ) -> PyResult<PyObject> {
-        let mut receiver = OptionalCallbackReceiver { callback, py };
+        let mut receiver = OptionalCallbackReceiver {
+            callback,
+            py,
+            host_functions: Some(&self.host_functions),
+        };
         let (input_ty, output_ty) = self
             .interpreter
             .global_tys(&callable.0)
@@ -464,6 +715,53 @@ impl Interpreter {
         }
     }
 
+    /// Generates QIR from the given entry expression as LLVM bitcode.
+    ///
+    /// :param entry_expr: The entry expression.
+    ///
+    /// :returns bitcode: The QIR as LLVM bitcode.
+    fn qir_bitcode<'py>(
+        &mut self,
+        py: Python<'py>,
+        entry_expr: &str,
+    ) -> PyResult<Bound<'py, PyBytes>> {
+        match self.interpreter.qirgen_bitcode(entry_expr) {
+            Ok(bitcode) => Ok(PyBytes::new(py, &bitcode)),
+            Err(errors) => Err(QSharpError::new_err(format_errors(errors))),
+        }
+    }
+
+    /// Computes instruction count and depth statistics for the program generated
+    /// from the given entry expression.
+    ///
+    /// :param entry_expr: The entry expression.
+    ///
+    /// :returns stats: A dictionary with the keys `gate_counts`, `two_qubit_gate_count`,
+    /// `measurement_count`, `classical_instruction_count`, `depth` and `feedback_depth`.
+    ///
+    /// :raises QSharpError: If there is an error generating the program.
+    fn program_stats<'a>(
+        &mut self,
+        py: Python<'a>,
+        entry_expr: &str,
+    ) -> PyResult<Bound<'a, PyDict>> {
+        let stats = self
+            .interpreter
+            .program_stats(entry_expr)
+            .map_err(|errors| QSharpError::new_err(format_errors(errors)))?;
+        let dict = PyDict::new(py);
+        dict.set_item("gate_counts", stats.gate_counts)?;
+        dict.set_item("two_qubit_gate_count", stats.two_qubit_gate_count)?;
+        dict.set_item("measurement_count", stats.measurement_count)?;
+        dict.set_item(
+            "classical_instruction_count",
+            stats.classical_instruction_count,
+        )?;
+        dict.set_item("depth", stats.depth)?;
+        dict.set_item("feedback_depth", stats.feedback_depth)?;
+        Ok(dict)
+    }
+
     /// Synthesizes a circuit for a Q# program. Either an entry
     /// expression or an operation must be provided.
     ///
@@ -521,7 +819,8 @@ impl Interpreter {
         args: Option<PyObject>,
     ) -> PyResult<String> {
         let results = if let Some(entry_expr) = entry_expr {
-            estimate_expr(&mut self.interpreter, entry_expr, job_params)
+            self.logical_counts_cache
+                .estimate_expr(&mut self.interpreter, entry_expr, job_params)
         } else {
             let callable = callable.ok_or_else(|| {
                 QSharpError::new_err("either entry_expr or callable must be specified")
@@ -531,32 +830,81 @@ impl Interpreter {
                 .global_tys(&callable.0)
                 .ok_or(QSharpError::new_err("callable not found"))?;
             let args = args_to_values(py, args, &input_ty, &output_ty)?;
-            estimate_call(&mut self.interpreter, callable.0, args, job_params)
+            self.logical_counts_cache.estimate_call(
+                &mut self.interpreter,
+                callable.0,
+                args,
+                job_params,
+            )
         };
-        match results {
-            Ok(estimate) => Ok(estimate),
-            Err(errors) if matches!(errors[0], re::Error::Interpreter(_)) => {
-                Err(QSharpError::new_err(format_errors(
-                    errors
-                        .into_iter()
-                        .map(|e| match e {
-                            re::Error::Interpreter(e) => e,
-                            re::Error::Estimation(_) => unreachable!(),
-                        })
-                        .collect::<Vec<_>>(),
-                )))
-            }
-            Err(errors) => Err(QSharpError::new_err(
-                errors
-                    .into_iter()
-                    .map(|e| match e {
-                        re::Error::Estimation(e) => e.to_string(),
-                        re::Error::Interpreter(_) => unreachable!(),
-                    })
-                    .collect::<Vec<_>>()
-                    .join("\n"),
-            )),
-        }
+        results.map_err(estimation_errors_into_py)
+    }
+
+    /// Estimates resources along the classical control paths of `paths` seeded simulations of the
+    /// program, instead of assuming random measurement results. The results contain the counts of
+    /// each path, and the estimates are of the expected counts over the paths.
+    #[pyo3(signature=(job_params, paths, seed, entry_expr=None, callable=None, args=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn estimate_paths(
+        &mut self,
+        py: Python,
+        job_params: &str,
+        paths: usize,
+        seed: u64,
+        entry_expr: Option<&str>,
+        callable: Option<GlobalCallable>,
+        args: Option<PyObject>,
+    ) -> PyResult<String> {
+        let results = if let Some(entry_expr) = entry_expr {
+            re::estimate_paths_expr(&mut self.interpreter, entry_expr, job_params, paths, seed)
+        } else {
+            let callable = callable.ok_or_else(|| {
+                QSharpError::new_err("either entry_expr or callable must be specified")
+            })?;
+            let (input_ty, output_ty) = self
+                .interpreter
+                .global_tys(&callable.0)
+                .ok_or(QSharpError::new_err("callable not found"))?;
+            let args = args_to_values(py, args, &input_ty, &output_ty)?;
+            re::estimate_paths_call(
+                &mut self.interpreter,
+                &callable.0,
+                &args,
+                job_params,
+                paths,
+                seed,
+            )
+        };
+        results.map_err(estimation_errors_into_py)
+    }
+
+    /// Computes the logical counts of the program, which are reused by later estimates of the
+    /// same program. Returns them as JSON.
+    #[pyo3(signature=(entry_expr=None, callable=None, args=None))]
+    fn logical_counts(
+        &mut self,
+        py: Python,
+        entry_expr: Option<&str>,
+        callable: Option<GlobalCallable>,
+        args: Option<PyObject>,
+    ) -> PyResult<String> {
+        let counts = if let Some(entry_expr) = entry_expr {
+            self.logical_counts_cache
+                .logical_counts_expr(&mut self.interpreter, entry_expr)
+        } else {
+            let callable = callable.ok_or_else(|| {
+                QSharpError::new_err("either entry_expr or callable must be specified")
+            })?;
+            let (input_ty, output_ty) = self
+                .interpreter
+                .global_tys(&callable.0)
+                .ok_or(QSharpError::new_err("callable not found"))?;
+            let args = args_to_values(py, args, &input_ty, &output_ty)?;
+            self.logical_counts_cache
+                .logical_counts_call(&mut self.interpreter, callable.0, args)
+        };
+        let counts = counts.map_err(estimation_errors_into_py)?;
+        Ok(serde_json::to_string(&counts).expect("serializing to json string should succeed"))
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -574,7 +922,11 @@ impl Interpreter {
         fetch_github: Option<PyObject>,
         kwargs: Option<Bound<'_, PyDict>>,
     ) -> PyResult<PyObject> {
-        let mut receiver = OptionalCallbackReceiver { callback, py };
+        let mut receiver = OptionalCallbackReceiver {
+            callback,
+            py,
+            host_functions: Some(&self.host_functions),
+        };
 
         let kwargs = kwargs.unwrap_or_else(|| PyDict::new(py));
 
@@ -603,6 +955,10 @@ impl Interpreter {
             false,
         )?;
 
+        self.logical_counts_cache.clear();
+        // The OpenQASM source is compiled to an AST that can't be evaluated again from the
+        // sources, so shots can no longer run in parallel.
+        self.program = None;
         let value = self
             .interpreter
             .eval_ast_fragments(&mut receiver, source, package)
@@ -627,6 +983,31 @@ impl Interpreter {
     }
 }
 
+fn estimation_errors_into_py(errors: Vec<re::Error>) -> PyErr {
+    if matches!(errors[0], re::Error::Interpreter(_)) {
+        QSharpError::new_err(format_errors(
+            errors
+                .into_iter()
+                .map(|e| match e {
+                    re::Error::Interpreter(e) => e,
+                    re::Error::Estimation(_) => unreachable!(),
+                })
+                .collect::<Vec<_>>(),
+        ))
+    } else {
+        QSharpError::new_err(
+            errors
+                .into_iter()
+                .map(|e| match e {
+                    re::Error::Estimation(e) => e.to_string(),
+                    re::Error::Interpreter(_) => unreachable!(),
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+        )
+    }
+}
+
 fn args_to_values(
     py: Python,
     args: Option<PyObject>,
@@ -698,9 +1079,11 @@ fn convert_obj_with_ty(py: Python, obj: &PyObject, ty: &Ty) -> PyResult<Value> {
             Prim::Double => Ok(Value::Double(obj.extract::<f64>(py)?)),
             Prim::Int => Ok(Value::Int(obj.extract::<i64>(py)?)),
             Prim::String => Ok(Value::String(obj.extract::<String>(py)?.into())),
-            Prim::Result => Ok(Value::Result(qsc::interpret::Result::Val(
-                obj.extract::<Result>(py)? == Result::One,
-            ))),
+            Prim::Result => Ok(Value::Result(match obj.extract::<Result>(py)? {
+                Result::Zero => qsc::interpret::Result::Val(false),
+                Result::One => qsc::interpret::Result::Val(true),
+                Result::Loss => qsc::interpret::Result::Loss,
+            })),
             Prim::Pauli => Ok(Value::Pauli(match obj.extract::<Pauli>(py)? {
                 Pauli::I => fir::Pauli::I,
                 Pauli::X => fir::Pauli::X,
@@ -765,6 +1148,28 @@ create_exception!(
     "An error returned from the OpenQASM parser."
 );
 
+/// The Pauli noise with the given probabilities of X, Y and Z errors, if any.
+fn pauli_noise(noise: Option<(f64, f64, f64)>) -> PyResult<Option<PauliNoise>> {
+    noise
+        .map(|(px, py, pz)| PauliNoise::from_probabilities(px, py, pz))
+        .transpose()
+        .map_err(PyException::new_err)
+}
+
+/// The noise model of a run that loses each measured qubit with the given probability.
+fn qubit_loss_noise_model(qubit_loss: Option<f64>) -> PyResult<NoiseModel> {
+    let loss = qubit_loss.unwrap_or_default();
+    if !(0.0..=1.0).contains(&loss) {
+        return Err(PyException::new_err(
+            "qubit loss probability must be between 0 and 1",
+        ));
+    }
+    Ok(NoiseModel {
+        loss,
+        ..NoiseModel::default()
+    })
+}
+
 pub(crate) fn format_errors(errors: Vec<interpret::Error>) -> String {
     errors
         .into_iter()
@@ -861,11 +1266,27 @@ impl StateDumpData {
         dict.into_pyobject(py)
     }
 
+    /// Gets the amplitudes keyed by the label of their basis state, such as `|01⟩`.
+    fn get_labeled_dict<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyDict>> {
+        let dict = self
+            .0
+             .0
+            .iter()
+            .map(|(id, amplitude)| (format_state_id(id, self.0 .1), *amplitude))
+            .collect::<rustc_hash::FxHashMap<_, _>>();
+        dict.into_pyobject(py)
+    }
+
     #[getter]
     fn get_qubit_count(&self) -> usize {
         self.0 .1
     }
 
+    #[getter]
+    fn get_qubits(&self) -> Vec<usize> {
+        self.0 .2.clone()
+    }
+
     fn __len__(&self) -> usize {
         self.0 .0.len()
     }
@@ -898,6 +1319,7 @@ impl StateDumpData {
 pub(crate) enum Result {
     Zero,
     One,
+    Loss,
 }
 
 #[pymethods]
@@ -907,6 +1329,7 @@ impl Result {
         match self {
             Result::Zero => "Zero".to_owned(),
             Result::One => "One".to_owned(),
+            Result::Loss => "Loss".to_owned(),
         }
     }
 
@@ -920,6 +1343,7 @@ impl Result {
         match self {
             Result::Zero => 0,
             Result::One => 1,
+            Result::Loss => 2,
         }
     }
 }
@@ -951,6 +1375,7 @@ impl<'py> IntoPyObject<'py> for ValueWrapper {
             Value::Double(val) => val.into_bound_py_any(py),
             Value::Bool(val) => val.into_bound_py_any(py),
             Value::String(val) => val.into_bound_py_any(py),
+            Value::Result(interpret::Result::Loss) => Result::Loss.into_bound_py_any(py),
             Value::Result(val) => if val.unwrap_bool() {
                 Result::One
             } else {
@@ -983,6 +1408,7 @@ impl<'py> IntoPyObject<'py> for ValueWrapper {
 pub(crate) struct OptionalCallbackReceiver<'a> {
     pub(crate) callback: Option<PyObject>,
     pub(crate) py: Python<'a>,
+    pub(crate) host_functions: Option<&'a FxHashMap<String, PyObject>>,
 }
 
 impl Receiver for OptionalCallbackReceiver<'_> {
@@ -991,8 +1417,20 @@ impl Receiver for OptionalCallbackReceiver<'_> {
         state: Vec<(BigUint, Complex64)>,
         qubit_count: usize,
     ) -> core::result::Result<(), Error> {
+        self.state_snapshot(StateSnapshot {
+            qubits: (0..qubit_count).collect(),
+            amplitudes: state,
+        })
+    }
+
+    fn state_snapshot(&mut self, snapshot: StateSnapshot) -> core::result::Result<(), Error> {
         if let Some(callback) = &self.callback {
-            let out = DisplayableOutput::State(DisplayableState(state, qubit_count));
+            let qubit_count = snapshot.qubit_count();
+            let out = DisplayableOutput::State(DisplayableState(
+                snapshot.amplitudes,
+                qubit_count,
+                snapshot.qubits,
+            ));
             callback
                 .call1(
                     self.py,
@@ -1040,6 +1478,56 @@ impl Receiver for OptionalCallbackReceiver<'_> {
         }
         Ok(())
     }
+
+    fn host_call(&mut self, name: &str, arg: Value) -> Option<core::result::Result<Value, String>> {
+        let function = self.host_functions?.get(name)?;
+        // The items of a tuple argument are passed as separate arguments, and Unit as no arguments.
+        let args = match arg {
+            Value::Tuple(items) => {
+                PyTuple::new(self.py, items.iter().map(|v| ValueWrapper(v.clone())))
+            }
+            arg => PyTuple::new(self.py, [ValueWrapper(arg)]),
+        };
+        Some(
+            args.and_then(|args| function.call1(self.py, args))
+                .and_then(|result| host_value_from_obj(result.bind(self.py)))
+                .map_err(|e| e.to_string()),
+        )
+    }
+}
+
+/// Converts the value returned by a host function into a Q# value, based on its Python type.
+/// The evaluator then checks that the value has the output type of the Q# function.
+fn host_value_from_obj(obj: &Bound<PyAny>) -> PyResult<Value> {
+    if obj.is_none() {
+        Ok(Value::unit())
+    } else if let Ok(val) = obj.downcast::<PyBool>() {
+        // Check for `bool` before `int`, since `bool` is a subclass of `int` in Python.
+        Ok(Value::Bool(val.is_true()))
+    } else if obj.is_instance_of::<PyInt>() {
+        Ok(Value::Int(obj.extract::<i64>()?))
+    } else if obj.is_instance_of::<PyFloat>() {
+        Ok(Value::Double(obj.extract::<f64>()?))
+    } else if let Ok(val) = obj.downcast::<PyString>() {
+        Ok(Value::String(val.extract::<String>()?.into()))
+    } else if let Ok(val) = obj.downcast::<PyTuple>() {
+        let items = val
+            .iter()
+            .map(|item| host_value_from_obj(&item))
+            .collect::<PyResult<Vec<_>>>()?;
+        Ok(Value::Tuple(items.into()))
+    } else if let Ok(val) = obj.downcast::<PyList>() {
+        let items = val
+            .iter()
+            .map(|item| host_value_from_obj(&item))
+            .collect::<PyResult<Vec<_>>>()?;
+        Ok(Value::Array(items.into()))
+    } else {
+        Err(PyValueError::new_err(format!(
+            "cannot convert value of type `{}` to a Q# value",
+            obj.get_type().name()?
+        )))
+    }
 }
 
 #[pyclass]
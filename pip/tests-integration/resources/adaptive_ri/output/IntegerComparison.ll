@@ -11,101 +11,101 @@ block_1:
   call void @__quantum__qis__x__body(%Qubit* inttoptr (i64 0 to %Qubit*))
   br label %block_2
 block_2:
-  %var_34 = phi i64 [0, %block_0], [1, %block_1]
+  %var_44 = phi i64 [0, %block_0], [1, %block_1]
   call void @__quantum__qis__x__body(%Qubit* inttoptr (i64 0 to %Qubit*))
   call void @__quantum__qis__m__body(%Qubit* inttoptr (i64 0 to %Qubit*), %Result* inttoptr (i64 1 to %Result*))
   %var_4 = call i1 @__quantum__qis__read_result__body(%Result* inttoptr (i64 1 to %Result*))
   br i1 %var_4, label %block_3, label %block_4
 block_3:
   call void @__quantum__qis__x__body(%Qubit* inttoptr (i64 0 to %Qubit*))
-  %var_6 = add i64 %var_34, 1
+  %var_6 = add i64 %var_44, 1
   br label %block_4
 block_4:
-  %var_35 = phi i64 [%var_34, %block_2], [%var_6, %block_3]
+  %var_45 = phi i64 [%var_44, %block_2], [%var_6, %block_3]
   call void @__quantum__qis__x__body(%Qubit* inttoptr (i64 0 to %Qubit*))
   call void @__quantum__qis__m__body(%Qubit* inttoptr (i64 0 to %Qubit*), %Result* inttoptr (i64 2 to %Result*))
   %var_7 = call i1 @__quantum__qis__read_result__body(%Result* inttoptr (i64 2 to %Result*))
   br i1 %var_7, label %block_5, label %block_6
 block_5:
   call void @__quantum__qis__x__body(%Qubit* inttoptr (i64 0 to %Qubit*))
-  %var_9 = add i64 %var_35, 1
+  %var_9 = add i64 %var_45, 1
   br label %block_6
 block_6:
-  %var_36 = phi i64 [%var_35, %block_4], [%var_9, %block_5]
+  %var_46 = phi i64 [%var_45, %block_4], [%var_9, %block_5]
   call void @__quantum__qis__x__body(%Qubit* inttoptr (i64 0 to %Qubit*))
   call void @__quantum__qis__m__body(%Qubit* inttoptr (i64 0 to %Qubit*), %Result* inttoptr (i64 3 to %Result*))
   %var_10 = call i1 @__quantum__qis__read_result__body(%Result* inttoptr (i64 3 to %Result*))
   br i1 %var_10, label %block_7, label %block_8
 block_7:
   call void @__quantum__qis__x__body(%Qubit* inttoptr (i64 0 to %Qubit*))
-  %var_12 = add i64 %var_36, 1
+  %var_12 = add i64 %var_46, 1
   br label %block_8
 block_8:
-  %var_37 = phi i64 [%var_36, %block_6], [%var_12, %block_7]
+  %var_47 = phi i64 [%var_46, %block_6], [%var_12, %block_7]
   call void @__quantum__qis__x__body(%Qubit* inttoptr (i64 0 to %Qubit*))
   call void @__quantum__qis__m__body(%Qubit* inttoptr (i64 0 to %Qubit*), %Result* inttoptr (i64 4 to %Result*))
   %var_13 = call i1 @__quantum__qis__read_result__body(%Result* inttoptr (i64 4 to %Result*))
   br i1 %var_13, label %block_9, label %block_10
 block_9:
   call void @__quantum__qis__x__body(%Qubit* inttoptr (i64 0 to %Qubit*))
-  %var_15 = add i64 %var_37, 1
+  %var_15 = add i64 %var_47, 1
   br label %block_10
 block_10:
-  %var_38 = phi i64 [%var_37, %block_8], [%var_15, %block_9]
+  %var_48 = phi i64 [%var_47, %block_8], [%var_15, %block_9]
   call void @__quantum__qis__x__body(%Qubit* inttoptr (i64 0 to %Qubit*))
   call void @__quantum__qis__m__body(%Qubit* inttoptr (i64 0 to %Qubit*), %Result* inttoptr (i64 5 to %Result*))
   %var_16 = call i1 @__quantum__qis__read_result__body(%Result* inttoptr (i64 5 to %Result*))
   br i1 %var_16, label %block_11, label %block_12
 block_11:
   call void @__quantum__qis__x__body(%Qubit* inttoptr (i64 0 to %Qubit*))
-  %var_18 = add i64 %var_38, 1
+  %var_18 = add i64 %var_48, 1
   br label %block_12
 block_12:
-  %var_39 = phi i64 [%var_38, %block_10], [%var_18, %block_11]
+  %var_49 = phi i64 [%var_48, %block_10], [%var_18, %block_11]
   call void @__quantum__qis__x__body(%Qubit* inttoptr (i64 0 to %Qubit*))
   call void @__quantum__qis__m__body(%Qubit* inttoptr (i64 0 to %Qubit*), %Result* inttoptr (i64 6 to %Result*))
   %var_19 = call i1 @__quantum__qis__read_result__body(%Result* inttoptr (i64 6 to %Result*))
   br i1 %var_19, label %block_13, label %block_14
 block_13:
   call void @__quantum__qis__x__body(%Qubit* inttoptr (i64 0 to %Qubit*))
-  %var_21 = add i64 %var_39, 1
+  %var_21 = add i64 %var_49, 1
   br label %block_14
 block_14:
-  %var_40 = phi i64 [%var_39, %block_12], [%var_21, %block_13]
+  %var_50 = phi i64 [%var_49, %block_12], [%var_21, %block_13]
   call void @__quantum__qis__x__body(%Qubit* inttoptr (i64 0 to %Qubit*))
   call void @__quantum__qis__m__body(%Qubit* inttoptr (i64 0 to %Qubit*), %Result* inttoptr (i64 7 to %Result*))
   %var_22 = call i1 @__quantum__qis__read_result__body(%Result* inttoptr (i64 7 to %Result*))
   br i1 %var_22, label %block_15, label %block_16
 block_15:
   call void @__quantum__qis__x__body(%Qubit* inttoptr (i64 0 to %Qubit*))
-  %var_24 = add i64 %var_40, 1
+  %var_24 = add i64 %var_50, 1
   br label %block_16
 block_16:
-  %var_41 = phi i64 [%var_40, %block_14], [%var_24, %block_15]
+  %var_51 = phi i64 [%var_50, %block_14], [%var_24, %block_15]
   call void @__quantum__qis__x__body(%Qubit* inttoptr (i64 0 to %Qubit*))
   call void @__quantum__qis__m__body(%Qubit* inttoptr (i64 0 to %Qubit*), %Result* inttoptr (i64 8 to %Result*))
   %var_25 = call i1 @__quantum__qis__read_result__body(%Result* inttoptr (i64 8 to %Result*))
   br i1 %var_25, label %block_17, label %block_18
 block_17:
   call void @__quantum__qis__x__body(%Qubit* inttoptr (i64 0 to %Qubit*))
-  %var_27 = add i64 %var_41, 1
+  %var_27 = add i64 %var_51, 1
   br label %block_18
 block_18:
-  %var_42 = phi i64 [%var_41, %block_16], [%var_27, %block_17]
+  %var_52 = phi i64 [%var_51, %block_16], [%var_27, %block_17]
   call void @__quantum__qis__x__body(%Qubit* inttoptr (i64 0 to %Qubit*))
   call void @__quantum__qis__m__body(%Qubit* inttoptr (i64 0 to %Qubit*), %Result* inttoptr (i64 9 to %Result*))
   %var_28 = call i1 @__quantum__qis__read_result__body(%Result* inttoptr (i64 9 to %Result*))
   br i1 %var_28, label %block_19, label %block_20
 block_19:
   call void @__quantum__qis__x__body(%Qubit* inttoptr (i64 0 to %Qubit*))
-  %var_30 = add i64 %var_42, 1
+  %var_30 = add i64 %var_52, 1
   br label %block_20
 block_20:
-  %var_43 = phi i64 [%var_42, %block_18], [%var_30, %block_19]
+  %var_53 = phi i64 [%var_52, %block_18], [%var_30, %block_19]
   call void @__quantum__qis__reset__body(%Qubit* inttoptr (i64 0 to %Qubit*))
-  %var_31 = icmp sgt i64 %var_43, 5
-  %var_32 = icmp slt i64 %var_43, 5
-  %var_33 = icmp eq i64 %var_43, 10
+  %var_31 = icmp sgt i64 %var_53, 5
+  %var_32 = icmp slt i64 %var_53, 5
+  %var_33 = icmp eq i64 %var_53, 10
   call void @__quantum__rt__tuple_record_output(i64 3, i8* null)
   call void @__quantum__rt__bool_record_output(i1 %var_31, i8* null)
   call void @__quantum__rt__bool_record_output(i1 %var_32, i8* null)
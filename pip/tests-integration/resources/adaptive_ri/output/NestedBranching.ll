@@ -28,19 +28,19 @@ block_4:
   %var_24 = icmp eq i1 %var_23, false
   br label %block_6
 block_5:
-  %var_81 = phi i1 [false, %block_1], [%var_9, %block_3]
-  br i1 %var_81, label %block_7, label %block_8
+  %var_90 = phi i1 [false, %block_1], [%var_9, %block_3]
+  br i1 %var_90, label %block_7, label %block_8
 block_6:
-  %var_82 = phi i1 [false, %block_2], [%var_24, %block_4]
-  br i1 %var_82, label %block_9, label %block_10
+  %var_91 = phi i1 [false, %block_2], [%var_24, %block_4]
+  br i1 %var_91, label %block_9, label %block_10
 block_7:
-  br label %block_31
+  br label %block_27
 block_8:
   %var_10 = call i1 @__quantum__qis__read_result__body(%Result* inttoptr (i64 1 to %Result*))
   %var_11 = icmp eq i1 %var_10, false
   br i1 %var_11, label %block_11, label %block_13
 block_9:
-  br label %block_32
+  br label %block_27
 block_10:
   %var_25 = call i1 @__quantum__qis__read_result__body(%Result* inttoptr (i64 1 to %Result*))
   %var_26 = icmp eq i1 %var_25, false
@@ -52,18 +52,18 @@ block_12:
   %var_28 = call i1 @__quantum__qis__read_result__body(%Result* inttoptr (i64 2 to %Result*))
   br label %block_14
 block_13:
-  %var_83 = phi i1 [false, %block_8], [%var_13, %block_11]
-  br i1 %var_83, label %block_15, label %block_16
+  %var_92 = phi i1 [false, %block_8], [%var_13, %block_11]
+  br i1 %var_92, label %block_15, label %block_16
 block_14:
-  %var_84 = phi i1 [false, %block_10], [%var_28, %block_12]
-  br i1 %var_84, label %block_17, label %block_18
+  %var_93 = phi i1 [false, %block_10], [%var_28, %block_12]
+  br i1 %var_93, label %block_17, label %block_18
 block_15:
-  br label %block_29
+  br label %block_27
 block_16:
   %var_15 = call i1 @__quantum__qis__read_result__body(%Result* inttoptr (i64 1 to %Result*))
   br i1 %var_15, label %block_19, label %block_21
 block_17:
-  br label %block_30
+  br label %block_27
 block_18:
   %var_30 = call i1 @__quantum__qis__read_result__body(%Result* inttoptr (i64 1 to %Result*))
   br i1 %var_30, label %block_20, label %block_22
@@ -76,39 +76,21 @@ block_20:
   %var_34 = icmp eq i1 %var_33, false
   br label %block_22
 block_21:
-  %var_85 = phi i1 [false, %block_16], [%var_19, %block_19]
-  br i1 %var_85, label %block_23, label %block_24
+  %var_94 = phi i1 [false, %block_16], [%var_19, %block_19]
+  br i1 %var_94, label %block_23, label %block_24
 block_22:
-  %var_86 = phi i1 [false, %block_18], [%var_34, %block_20]
-  br i1 %var_86, label %block_25, label %block_26
+  %var_95 = phi i1 [false, %block_18], [%var_34, %block_20]
+  br i1 %var_95, label %block_25, label %block_26
 block_23:
   br label %block_27
 block_24:
   br label %block_27
 block_25:
-  br label %block_28
+  br label %block_27
 block_26:
-  br label %block_28
+  br label %block_27
 block_27:
-  %var_87 = phi i64 [2, %block_23], [3, %block_24]
-  br label %block_29
-block_28:
-  %var_88 = phi i64 [6, %block_25], [7, %block_26]
-  br label %block_30
-block_29:
-  %var_89 = phi i64 [1, %block_15], [%var_87, %block_27]
-  br label %block_31
-block_30:
-  %var_90 = phi i64 [5, %block_17], [%var_88, %block_28]
-  br label %block_32
-block_31:
-  %var_91 = phi i64 [0, %block_7], [%var_89, %block_29]
-  br label %block_33
-block_32:
-  %var_92 = phi i64 [4, %block_9], [%var_90, %block_30]
-  br label %block_33
-block_33:
-  %var_93 = phi i64 [%var_91, %block_31], [%var_92, %block_32]
+  %var_96 = phi i64 [0, %block_7], [4, %block_9], [1, %block_15], [5, %block_17], [2, %block_23], [3, %block_24], [6, %block_25], [7, %block_26]
   call void @__quantum__qis__reset__body(%Qubit* inttoptr (i64 0 to %Qubit*))
   call void @__quantum__qis__reset__body(%Qubit* inttoptr (i64 1 to %Qubit*))
   call void @__quantum__qis__reset__body(%Qubit* inttoptr (i64 2 to %Qubit*))
@@ -122,160 +104,124 @@ block_33:
   call void @__quantum__qis__m__body(%Qubit* inttoptr (i64 6 to %Qubit*), %Result* inttoptr (i64 6 to %Result*))
   %var_40 = call i1 @__quantum__qis__read_result__body(%Result* inttoptr (i64 3 to %Result*))
   %var_41 = icmp eq i1 %var_40, false
-  br i1 %var_41, label %block_34, label %block_35
-block_34:
+  br i1 %var_41, label %block_28, label %block_29
+block_28:
   %var_42 = call i1 @__quantum__qis__read_result__body(%Result* inttoptr (i64 4 to %Result*))
   %var_43 = icmp eq i1 %var_42, false
-  br i1 %var_43, label %block_36, label %block_37
-block_35:
+  br i1 %var_43, label %block_30, label %block_31
+block_29:
   %var_48 = call i1 @__quantum__qis__read_result__body(%Result* inttoptr (i64 3 to %Result*))
   %var_49 = icmp eq i1 %var_48, false
-  br i1 %var_49, label %block_38, label %block_43
-block_36:
+  br i1 %var_49, label %block_32, label %block_36
+block_30:
   %var_44 = call i1 @__quantum__qis__read_result__body(%Result* inttoptr (i64 5 to %Result*))
   %var_45 = icmp eq i1 %var_44, false
-  br i1 %var_45, label %block_39, label %block_40
-block_37:
+  br i1 %var_45, label %block_58, label %block_33
+block_31:
   %var_46 = call i1 @__quantum__qis__read_result__body(%Result* inttoptr (i64 5 to %Result*))
   %var_47 = icmp eq i1 %var_46, false
-  br i1 %var_47, label %block_41, label %block_42
-block_38:
+  br i1 %var_47, label %block_34, label %block_35
+block_32:
   %var_51 = call i1 @__quantum__qis__read_result__body(%Result* inttoptr (i64 4 to %Result*))
-  br label %block_43
-block_39:
-  br label %block_44
-block_40:
+  br label %block_36
+block_33:
   call void @__quantum__qis__x__body(%Qubit* inttoptr (i64 7 to %Qubit*))
   call void @__quantum__qis__x__body(%Qubit* inttoptr (i64 7 to %Qubit*))
-  br label %block_44
-block_41:
+  br label %block_58
+block_34:
   call void @__quantum__qis__y__body(%Qubit* inttoptr (i64 7 to %Qubit*))
   call void @__quantum__qis__y__body(%Qubit* inttoptr (i64 7 to %Qubit*))
-  br label %block_45
-block_42:
+  br label %block_58
+block_35:
   call void @__quantum__qis__z__body(%Qubit* inttoptr (i64 7 to %Qubit*))
   call void @__quantum__qis__z__body(%Qubit* inttoptr (i64 7 to %Qubit*))
-  br label %block_45
-block_43:
-  %var_94 = phi i1 [false, %block_35], [%var_51, %block_38]
-  br i1 %var_94, label %block_46, label %block_47
-block_44:
-  br label %block_48
-block_45:
-  br label %block_48
-block_46:
+  br label %block_58
+block_36:
+  %var_97 = phi i1 [false, %block_29], [%var_51, %block_32]
+  br i1 %var_97, label %block_37, label %block_38
+block_37:
   %var_53 = call i1 @__quantum__qis__read_result__body(%Result* inttoptr (i64 4 to %Result*))
   %var_54 = icmp eq i1 %var_53, false
-  br i1 %var_54, label %block_49, label %block_50
-block_47:
+  br i1 %var_54, label %block_39, label %block_40
+block_38:
   %var_59 = call i1 @__quantum__qis__read_result__body(%Result* inttoptr (i64 3 to %Result*))
-  br i1 %var_59, label %block_51, label %block_56
-block_48:
-  br label %block_82
-block_49:
+  br i1 %var_59, label %block_41, label %block_45
+block_39:
   %var_55 = call i1 @__quantum__qis__read_result__body(%Result* inttoptr (i64 5 to %Result*))
   %var_56 = icmp eq i1 %var_55, false
-  br i1 %var_56, label %block_52, label %block_53
-block_50:
+  br i1 %var_56, label %block_58, label %block_42
+block_40:
   %var_57 = call i1 @__quantum__qis__read_result__body(%Result* inttoptr (i64 5 to %Result*))
   %var_58 = icmp eq i1 %var_57, false
-  br i1 %var_58, label %block_54, label %block_55
-block_51:
+  br i1 %var_58, label %block_43, label %block_44
+block_41:
   %var_62 = call i1 @__quantum__qis__read_result__body(%Result* inttoptr (i64 4 to %Result*))
   %var_63 = icmp eq i1 %var_62, false
-  br label %block_56
-block_52:
-  br label %block_57
-block_53:
+  br label %block_45
+block_42:
   call void @__quantum__qis__x__body(%Qubit* inttoptr (i64 7 to %Qubit*))
   call void @__quantum__qis__x__body(%Qubit* inttoptr (i64 7 to %Qubit*))
-  br label %block_57
-block_54:
+  br label %block_58
+block_43:
   call void @__quantum__qis__y__body(%Qubit* inttoptr (i64 7 to %Qubit*))
   call void @__quantum__qis__y__body(%Qubit* inttoptr (i64 7 to %Qubit*))
   br label %block_58
-block_55:
+block_44:
   call void @__quantum__qis__z__body(%Qubit* inttoptr (i64 7 to %Qubit*))
   call void @__quantum__qis__z__body(%Qubit* inttoptr (i64 7 to %Qubit*))
   br label %block_58
-block_56:
-  %var_95 = phi i1 [false, %block_47], [%var_63, %block_51]
-  br i1 %var_95, label %block_59, label %block_60
-block_57:
-  br label %block_61
-block_58:
-  br label %block_61
-block_59:
+block_45:
+  %var_98 = phi i1 [false, %block_38], [%var_63, %block_41]
+  br i1 %var_98, label %block_46, label %block_47
+block_46:
   %var_64 = call i1 @__quantum__qis__read_result__body(%Result* inttoptr (i64 4 to %Result*))
   %var_65 = icmp eq i1 %var_64, false
-  br i1 %var_65, label %block_62, label %block_63
-block_60:
+  br i1 %var_65, label %block_48, label %block_49
+block_47:
   %var_70 = call i1 @__quantum__qis__read_result__body(%Result* inttoptr (i64 4 to %Result*))
   %var_71 = icmp eq i1 %var_70, false
-  br i1 %var_71, label %block_64, label %block_65
-block_61:
-  br label %block_81
-block_62:
+  br i1 %var_71, label %block_50, label %block_51
+block_48:
   %var_66 = call i1 @__quantum__qis__read_result__body(%Result* inttoptr (i64 5 to %Result*))
   %var_67 = icmp eq i1 %var_66, false
-  br i1 %var_67, label %block_66, label %block_67
-block_63:
+  br i1 %var_67, label %block_58, label %block_52
+block_49:
   %var_68 = call i1 @__quantum__qis__read_result__body(%Result* inttoptr (i64 5 to %Result*))
   %var_69 = icmp eq i1 %var_68, false
-  br i1 %var_69, label %block_68, label %block_69
-block_64:
+  br i1 %var_69, label %block_53, label %block_54
+block_50:
   %var_72 = call i1 @__quantum__qis__read_result__body(%Result* inttoptr (i64 5 to %Result*))
   %var_73 = icmp eq i1 %var_72, false
-  br i1 %var_73, label %block_70, label %block_71
-block_65:
+  br i1 %var_73, label %block_58, label %block_55
+block_51:
   %var_74 = call i1 @__quantum__qis__read_result__body(%Result* inttoptr (i64 5 to %Result*))
   %var_75 = icmp eq i1 %var_74, false
-  br i1 %var_75, label %block_72, label %block_73
-block_66:
-  br label %block_74
-block_67:
+  br i1 %var_75, label %block_56, label %block_57
+block_52:
   call void @__quantum__qis__x__body(%Qubit* inttoptr (i64 7 to %Qubit*))
   call void @__quantum__qis__x__body(%Qubit* inttoptr (i64 7 to %Qubit*))
-  br label %block_74
-block_68:
+  br label %block_58
+block_53:
   call void @__quantum__qis__y__body(%Qubit* inttoptr (i64 7 to %Qubit*))
   call void @__quantum__qis__y__body(%Qubit* inttoptr (i64 7 to %Qubit*))
-  br label %block_75
-block_69:
+  br label %block_58
+block_54:
   call void @__quantum__qis__z__body(%Qubit* inttoptr (i64 7 to %Qubit*))
   call void @__quantum__qis__z__body(%Qubit* inttoptr (i64 7 to %Qubit*))
-  br label %block_75
-block_70:
-  br label %block_76
-block_71:
+  br label %block_58
+block_55:
   call void @__quantum__qis__x__body(%Qubit* inttoptr (i64 7 to %Qubit*))
   call void @__quantum__qis__x__body(%Qubit* inttoptr (i64 7 to %Qubit*))
-  br label %block_76
-block_72:
+  br label %block_58
+block_56:
   call void @__quantum__qis__y__body(%Qubit* inttoptr (i64 7 to %Qubit*))
   call void @__quantum__qis__y__body(%Qubit* inttoptr (i64 7 to %Qubit*))
-  br label %block_77
-block_73:
+  br label %block_58
+block_57:
   call void @__quantum__qis__z__body(%Qubit* inttoptr (i64 7 to %Qubit*))
   call void @__quantum__qis__z__body(%Qubit* inttoptr (i64 7 to %Qubit*))
-  br label %block_77
-block_74:
-  br label %block_78
-block_75:
-  br label %block_78
-block_76:
-  br label %block_79
-block_77:
-  br label %block_79
-block_78:
-  br label %block_80
-block_79:
-  br label %block_80
-block_80:
-  br label %block_81
-block_81:
-  br label %block_82
-block_82:
+  br label %block_58
+block_58:
   call void @__quantum__qis__reset__body(%Qubit* inttoptr (i64 3 to %Qubit*))
   call void @__quantum__qis__reset__body(%Qubit* inttoptr (i64 4 to %Qubit*))
   call void @__quantum__qis__reset__body(%Qubit* inttoptr (i64 5 to %Qubit*))
@@ -288,7 +234,7 @@ block_82:
   call void @__quantum__rt__result_record_output(%Result* inttoptr (i64 0 to %Result*), i8* null)
   call void @__quantum__rt__result_record_output(%Result* inttoptr (i64 1 to %Result*), i8* null)
   call void @__quantum__rt__result_record_output(%Result* inttoptr (i64 2 to %Result*), i8* null)
-  call void @__quantum__rt__int_record_output(i64 %var_93, i8* null)
+  call void @__quantum__rt__int_record_output(i64 %var_96, i8* null)
   call void @__quantum__rt__tuple_record_output(i64 2, i8* null)
   call void @__quantum__rt__array_record_output(i64 4, i8* null)
   call void @__quantum__rt__result_record_output(%Result* inttoptr (i64 3 to %Result*), i8* null)
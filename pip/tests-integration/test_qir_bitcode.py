@@ -0,0 +1,84 @@
+# Copyright (c) Microsoft Corporation.
+# Licensed under the MIT License.
+
+import pytest
+
+from qsharp import TargetProfile
+from utils import (
+    compile_qsharp,
+    compile_qsharp_bitcode,
+    get_input_files,
+    read_file,
+)
+
+try:
+    from pyqir import Context, Module
+
+    PYQIR_AVAILABLE = True
+except ImportError:
+    PYQIR_AVAILABLE = False
+
+SKIP_REASON = "PyQIR is not available"
+
+TARGET_PROFILES = [TargetProfile.Adaptive_RI, TargetProfile.Adaptive_RIF]
+
+
+def assert_modules_equal(bitcode_module: Module, ir_module: Module) -> None:
+    assert [f.name for f in bitcode_module.functions] == [
+        f.name for f in ir_module.functions
+    ]
+    for bitcode_function, ir_function in zip(
+        bitcode_module.functions, ir_module.functions
+    ):
+        assert str(bitcode_function) == str(ir_function)
+    assert str(bitcode_module.get_flag("qir_major_version")) == str(
+        ir_module.get_flag("qir_major_version")
+    )
+    assert str(bitcode_module.get_flag("qir_minor_version")) == str(
+        ir_module.get_flag("qir_minor_version")
+    )
+
+
+@pytest.mark.skipif(not PYQIR_AVAILABLE, reason=SKIP_REASON)
+def test_base_bitcode_matches_textual_qir() -> None:
+    source = """
+    namespace Test {
+        @EntryPoint()
+        operation Main() : (Result, Result) {
+            use (q1, q2) = (Qubit(), Qubit());
+            H(q1);
+            CNOT(q1, q2);
+            Rx(1.5, q2);
+            (MResetZ(q1), MResetZ(q2))
+        }
+    }
+    """
+    qir = compile_qsharp(source, TargetProfile.Base)
+    bitcode = compile_qsharp_bitcode(source, TargetProfile.Base)
+    context = Context()
+    bitcode_module = Module.from_bitcode(context, bitcode)
+    ir_module = Module.from_ir(context, qir)
+    assert bitcode_module.verify() is None
+    assert_modules_equal(bitcode_module, ir_module)
+
+
+@pytest.mark.parametrize(
+    "target_profile, file_path",
+    [
+        (target_profile, file_path)
+        for target_profile in TARGET_PROFILES
+        for file_path in get_input_files(target_profile)
+    ],
+)
+@pytest.mark.skipif(not PYQIR_AVAILABLE, reason=SKIP_REASON)
+def test_bitcode_matches_textual_qir(
+    target_profile: TargetProfile, file_path: str
+) -> None:
+    source = read_file(file_path, target_profile)
+    qir = compile_qsharp(source, target_profile)
+    bitcode = compile_qsharp_bitcode(source, target_profile)
+    context = Context()
+    bitcode_module = Module.from_bitcode(context, bitcode)
+    ir_module = Module.from_ir(context, qir)
+    assert bitcode_module.verify() is None
+    assert_modules_equal(bitcode_module, ir_module)
@@ -144,6 +144,17 @@ def compile_qsharp(
     return qir
 
 
+def compile_qsharp_bitcode(
+    source: str,
+    target_profile: TargetProfile = TargetProfile.Adaptive_RI,
+    target_name: Optional[str] = None,
+) -> bytes:
+    interpreter = get_interpreter(target_profile, target_name)
+    interpreter.interpret(source)
+    bitcode = interpreter.qir_bitcode("Test.Main()")
+    return bitcode
+
+
 def get_input_files(target_profile: TargetProfile) -> List[str]:
     resources_dir = get_input_dir(target_profile)
     input_files = [
@@ -392,13 +392,12 @@ def test_adaptive_ri_qir_can_be_generated() -> None:
 
         ; module flags
 
-        !llvm.module.flags = !{!0, !1, !2, !3, !4}
+        !llvm.module.flags = !{!0, !1, !2, !3}
 
         !0 = !{i32 1, !"qir_major_version", i32 1}
         !1 = !{i32 7, !"qir_minor_version", i32 0}
         !2 = !{i32 1, !"dynamic_qubit_management", i1 false}
         !3 = !{i32 1, !"dynamic_result_management", i1 false}
-        !4 = !{i32 1, !"int_computations", !"i64"}
         """
     )
 
@@ -66,6 +66,19 @@ def test_captured_matrix() -> None:
     )
 
 
+def test_captured_dump_register_has_qubit_ids() -> None:
+    qsharp.init(target_profile=qsharp.TargetProfile.Unrestricted)
+    result = qsharp.eval(
+        "{ use qs = Qubit[3]; X(qs[2]); Std.Diagnostics.DumpRegister([qs[2], qs[0]]); ResetAll(qs); }",
+        save_events=True,
+    )
+    assert len(result["dumps"]) == 1
+    state_dump = result["dumps"][0]
+    assert state_dump.qubit_count == 2
+    assert state_dump.qubits == [2, 0]
+    assert state_dump.as_labeled_dict() == {"|10⟩": complex(1.0, 0.0)}
+
+
 def test_quantum_seed() -> None:
     qsharp.init(target_profile=qsharp.TargetProfile.Unrestricted)
     qsharp.set_quantum_seed(42)
@@ -105,6 +118,102 @@ def test_classical_seed() -> None:
     assert value1 != value3
 
 
+def test_eval_exceeding_step_quota_raises() -> None:
+    qsharp.init(target_profile=qsharp.TargetProfile.Unrestricted)
+    qsharp.set_quota(max_steps=1000)
+    with pytest.raises(qsharp.QSharpError) as excinfo:
+        qsharp.eval("{ mutable i = 0; while true { set i += 1; } }")
+    assert "program exceeded the limit of 1000 execution steps" in str(excinfo.value)
+    qsharp.set_quota()
+    assert (
+        qsharp.eval("{ mutable sum = 0; for i in 0..2000 { set sum += i; } sum }")
+        == 2001000
+    )
+
+
+def test_run_exceeding_timeout_raises() -> None:
+    qsharp.init(target_profile=qsharp.TargetProfile.Unrestricted)
+    qsharp.set_quota(timeout=0)
+    with pytest.raises(qsharp.QSharpError, match="program timed out"):
+        qsharp.run("{ while true {} }", 1)
+
+
+def test_run_on_threads_exceeding_step_quota_raises() -> None:
+    qsharp.init(target_profile=qsharp.TargetProfile.Unrestricted)
+    qsharp.set_quota(max_steps=1000)
+    with pytest.raises(qsharp.QSharpError, match="1000 execution steps"):
+        qsharp.run("{ mutable i = 0; while true { set i += 1; } }", 2, threads=2)
+
+
+def test_eval_exceeding_call_depth_raises() -> None:
+    qsharp.init(target_profile=qsharp.TargetProfile.Unrestricted)
+    qsharp.eval(
+        "function Down(n : Int) : Int { if n == 0 { 0 } else { 1 + Down(n - 1) } }"
+    )
+    qsharp.set_quota(max_call_depth=100)
+    with pytest.raises(qsharp.QSharpError) as excinfo:
+        qsharp.eval("Down(1000)")
+    assert "program exceeded the limit of 100 nested calls" in str(excinfo.value)
+    assert qsharp.eval("Down(50)") == 50
+
+
+def test_set_quota_with_negative_timeout_raises() -> None:
+    qsharp.init(target_profile=qsharp.TargetProfile.Unrestricted)
+    with pytest.raises(ValueError):
+        qsharp.set_quota(timeout=-1.0)
+
+
+def test_host_callable_function_calls_registered_python_function() -> None:
+    qsharp.init(target_profile=qsharp.TargetProfile.Unrestricted)
+    qsharp.eval(
+        """
+        @HostCallable()
+        function Model(xs : Double[], scale : Int) : (Double, Int[]) { body intrinsic; }
+        """
+    )
+    calls = []
+
+    def model(xs, scale):
+        calls.append((xs, scale))
+        scaled = [x * scale for x in xs]
+        return (sum(scaled), [int(x) for x in scaled])
+
+    qsharp.register_host_function("Model", model)
+    assert qsharp.eval("Model([1.5, 2.5], 2)") == (8.0, [3, 5])
+    assert calls == [([1.5, 2.5], 2)]
+
+
+def test_host_callable_function_without_registered_python_function_fails() -> None:
+    qsharp.init(target_profile=qsharp.TargetProfile.Unrestricted)
+    qsharp.eval(
+        """
+        @HostCallable()
+        function Model(x : Int) : Int { body intrinsic; }
+        """
+    )
+    with pytest.raises(qsharp.QSharpError) as excinfo:
+        qsharp.eval("Model(1)")
+    assert "host function `Model` is not registered" in str(excinfo.value)
+
+
+def test_host_callable_function_reports_python_exception() -> None:
+    qsharp.init(target_profile=qsharp.TargetProfile.Unrestricted)
+    qsharp.eval(
+        """
+        @HostCallable()
+        function Model(x : Int) : Int { body intrinsic; }
+        """
+    )
+
+    def model(x):
+        raise ValueError("model diverged")
+
+    qsharp.register_host_function("Model", model)
+    with pytest.raises(qsharp.QSharpError) as excinfo:
+        qsharp.eval("Model(1)")
+    assert "model diverged" in str(excinfo.value)
+
+
 def test_dump_machine() -> None:
     qsharp.init(target_profile=qsharp.TargetProfile.Unrestricted)
     qsharp.eval(
@@ -116,8 +225,10 @@ def test_dump_machine() -> None:
     )
     state_dump = qsharp.dump_machine()
     assert state_dump.qubit_count == 2
+    assert state_dump.qubits == [0, 1]
     assert len(state_dump) == 1
     assert state_dump[2] == complex(1.0, 0.0)
+    assert state_dump.as_labeled_dict() == {"|10⟩": complex(1.0, 0.0)}
     assert state_dump.as_dense_state() == [0, 0, 1, 0]
     qsharp.eval("X(q2);")
     state_dump = qsharp.dump_machine()
@@ -306,6 +417,18 @@ def test_run_with_noise_produces_noisy_results() -> None:
     assert result[0] > 5
 
 
+def test_run_with_qubit_loss_produces_loss_results() -> None:
+    qsharp.init()
+    result = qsharp.run(
+        "{ use q = Qubit(); X(q); let r = MResetZ(q); (r, IsLossResult(r)) }",
+        shots=2,
+        qubit_loss=1.0,
+    )
+    assert result == [(qsharp.Result.Loss, True), (qsharp.Result.Loss, True)]
+    result = qsharp.run("{ use q = Qubit(); X(q); MResetZ(q) }", shots=2)
+    assert result == [qsharp.Result.One, qsharp.Result.One]
+
+
 def test_compile_qir_input_data() -> None:
     qsharp.init(target_profile=qsharp.TargetProfile.Base)
     qsharp.eval("operation Program() : Result { use q = Qubit(); return M(q) }")
@@ -480,6 +603,77 @@ def test_run_with_result_callback_from_callable_with_args(capsys) -> None:
     assert called
 
 
+def test_run_on_threads_matches_across_thread_counts(capsys) -> None:
+    qsharp.init()
+    qsharp.eval(
+        'operation Foo() : Result[] { use qs = Qubit[3]; ApplyToEach(H, qs); Message("shot"); MResetEachZ(qs) }'
+    )
+    qsharp.set_quantum_seed(42)
+    serial = qsharp.run("Foo()", 8, threads=1)
+    parallel = qsharp.run("Foo()", 8, threads=3)
+    assert serial == parallel
+    assert len(serial) == 8
+    stdout = capsys.readouterr().out
+    assert stdout == "shot\n" * 16
+
+
+def test_run_on_threads_with_noise_matches_across_thread_counts() -> None:
+    qsharp.init()
+    qsharp.eval("operation Foo() : Result[] { use qs = Qubit[4]; MResetEachZ(qs) }")
+    qsharp.set_quantum_seed(7)
+    serial = qsharp.run("Foo()", 10, threads=1, noise=qsharp.BitFlipNoise(0.3))
+    parallel = qsharp.run("Foo()", 10, threads=4, noise=qsharp.BitFlipNoise(0.3))
+    assert serial == parallel
+    # Without noise, every qubit would be measured as `Zero`.
+    assert any(qsharp.Result.One in shot for shot in serial)
+
+
+def test_run_on_threads_reports_runtime_errors() -> None:
+    qsharp.init()
+    qsharp.eval('operation Foo() : Unit { fail "boom"; }')
+    with pytest.raises(qsharp.QSharpError, match="boom"):
+        qsharp.run("Foo()", 2, threads=2)
+
+
+def test_run_on_threads_requires_entry_expression() -> None:
+    qsharp.init()
+    qsharp.eval("operation Foo() : Result { Zero }")
+    with pytest.raises(qsharp.QSharpError, match="requires an entry expression"):
+        qsharp.run(qsharp.code.Foo, 2, threads=2)
+
+
+def test_run_sweep_keys_results_by_params() -> None:
+    qsharp.init()
+    qsharp.eval(
+        "operation Foo(nResults : Int, result : Result) : Result[] { Repeated(result, nResults) }"
+    )
+    params = [(1, qsharp.Result.Zero), (2, qsharp.Result.One)]
+    results = qsharp.run_sweep(qsharp.code.Foo, params, 2)
+    assert results == [
+        ((1, qsharp.Result.Zero), [[qsharp.Result.Zero], [qsharp.Result.Zero]]),
+        (
+            (2, qsharp.Result.One),
+            [[qsharp.Result.One, qsharp.Result.One], [qsharp.Result.One, qsharp.Result.One]],
+        ),
+    ]
+
+
+def test_run_with_reuse_analysis_warns_once_across_shots() -> None:
+    qsharp.init()
+    qsharp.eval(
+        "operation Foo() : Result { use q = Qubit(); X(q); let r = M(q); H(q); H(q); X(q); r }"
+    )
+    with pytest.warns(qsharp.QubitReuseWarning) as record:
+        results = qsharp.run("Foo()", 3, analyze_reuse=True)
+    assert results == [qsharp.Result.One] * 3
+    assert len(record) == 1
+    warning = record[0].message
+    assert warning.issue == "qubit used after measurement without reset"
+    assert warning.qubit == 0
+    assert warning.occurrences == 3
+    assert warning.shots == 3
+
+
 def test_run_with_invalid_shots_produces_error() -> None:
     qsharp.init()
     qsharp.eval('operation Foo() : Result { Message("Hello, world!"); Zero }')
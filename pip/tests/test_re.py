@@ -30,6 +30,46 @@ def test_qsharp_estimation() -> None:
     )
 
 
+def test_logical_counts_are_reused_across_estimates() -> None:
+    qsharp.init(target_profile=qsharp.TargetProfile.Unrestricted)
+    qsharp.eval(
+        "operation Program(n : Int) : Unit { use qs = Qubit[n]; for q in qs { T(q); } }"
+    )
+    counts = qsharp.logical_counts(qsharp.code.Program, 5)
+    assert counts == LogicalCounts({"numQubits": 5, "tCount": 5})
+
+    loose = qsharp.estimate(qsharp.code.Program, {"errorBudget": 0.01}, 5)
+    tight = qsharp.estimate(qsharp.code.Program, {"errorBudget": 0.0001}, 5)
+    assert loose.logical_counts == counts
+    assert tight.logical_counts == counts
+    assert (
+        tight["physicalCounts"]["physicalQubits"]
+        > loose["physicalCounts"]["physicalQubits"]
+    )
+
+    # Redefining the program must not reuse the counts of the old definition.
+    qsharp.eval(
+        "operation Program(n : Int) : Unit { use qs = Qubit[n]; for q in qs { T(q); T(q); } }"
+    )
+    assert qsharp.logical_counts(qsharp.code.Program, 5) == LogicalCounts(
+        {"numQubits": 5, "tCount": 10}
+    )
+
+
+def test_estimation_along_simulated_paths() -> None:
+    qsharp.init(target_profile=qsharp.TargetProfile.Unrestricted)
+    qsharp.eval(
+        "operation Branch() : Unit { use q = Qubit(); X(q); if MResetZ(q) == One { T(q); T(q); } }"
+    )
+    res = qsharp.estimate("Branch()", simulated_paths=3, seed=7)
+    assert res.logical_counts["tCount"] == 2
+    paths = res.path_logical_counts
+    assert len(paths) == 3
+    assert all(path["tCount"] == 2 for path in paths)
+
+    assert qsharp.estimate("Branch()").path_logical_counts is None
+
+
 def test_qsharp_estimation_from_precalculated_counts() -> None:
     qsharp.init(target_profile=qsharp.TargetProfile.Unrestricted)
     res = qsharp.estimate(
@@ -301,6 +341,17 @@ def test_estimation_from_logical_counts() -> None:
     assert res.logical_counts == logical_counts
 
 
+def test_estimation_from_logical_counts_with_logical_depth() -> None:
+    logical_counts = LogicalCounts(
+        {"numQubits": 100, "tCount": 1000000, "logicalDepth": 2000000}
+    )
+    res = logical_counts.estimate()
+
+    assert res["status"] == "success"
+    assert res.logical_counts == logical_counts
+    assert res["physicalCounts"]["breakdown"]["algorithmicLogicalDepth"] == 2000000
+
+
 def test_estimation_from_logical_counts_with_single_params() -> None:
     logical_counts = LogicalCounts(
         {
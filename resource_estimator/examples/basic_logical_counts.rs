@@ -43,6 +43,9 @@ fn main() {
         ccz_count: 100,
         ccix_count: 0,
         measurement_count: 10,
+        feedback_depth: 0,
+        logical_depth: None,
+        phases: vec![],
     });
 
     // 5) An error budget; in this example we are using a uniform error budget
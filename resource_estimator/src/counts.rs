@@ -6,7 +6,7 @@ mod tests;
 
 use num_bigint::BigUint;
 use num_complex::Complex;
-use qsc::{interpret::Value, Backend};
+use qsc::{interpret::Value, Backend, SparseSim};
 use rand::{rngs::StdRng, Rng, SeedableRng};
 use rustc_hash::FxHashMap;
 use std::{array, cell::RefCell, f64::consts::PI, fmt::Debug, iter::Sum};
@@ -78,6 +78,24 @@ impl LogicalCounter {
             ccz_count: self.ccz_count as _,
             ccix_count: 0,
             measurement_count: self.m_count as _,
+            feedback_depth: 0,
+            logical_depth: None,
+            phases: vec![],
+        }
+    }
+
+    /// Allocates the qubit with the given id, which was chosen by a simulator
+    /// that runs alongside the counter, so that both use the same ids.
+    fn qubit_allocate_id(&mut self, q: usize) {
+        if let Some(pos) = self.free_list.iter().position(|&free| free == q) {
+            self.free_list.remove(pos);
+        }
+        while self.next_free <= q {
+            if self.next_free < q {
+                self.free_list.push(self.next_free);
+            }
+            self.next_free += 1;
+            self.max_layer.push(self.allocation_barrier);
         }
     }
 
@@ -570,6 +588,235 @@ impl Backend for LogicalCounter {
     }
 }
 
+/// Resource counter that resolves measurements with a simulation
+///
+/// The program runs on a sparse simulator alongside the counter, so that
+/// classical control flow follows the outcomes of the simulated measurements,
+/// instead of random outcomes.  This counts the resources of the path that one
+/// run of an adaptive program, such as iterative phase estimation, actually
+/// takes.  The simulator chooses the qubit ids, and the counter uses the same
+/// ones.  Estimate caching is turned off, since skipping the cached code would
+/// also skip its simulation.
+pub struct SimulatedCounter {
+    sim: SparseSim,
+    counter: LogicalCounter,
+}
+
+impl SimulatedCounter {
+    /// Creates a counter whose simulation uses the given seed.  The seed takes
+    /// precedence over the quantum seed of the interpreter.
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        let mut sim = SparseSim::new();
+        sim.set_seed(Some(seed));
+        Self {
+            sim,
+            counter: LogicalCounter::default(),
+        }
+    }
+
+    #[must_use]
+    pub fn logical_resources(&self) -> LogicalResourceCounts {
+        self.counter.logical_resources()
+    }
+}
+
+impl Backend for SimulatedCounter {
+    type ResultType = bool;
+
+    fn ccx(&mut self, ctl0: usize, ctl1: usize, q: usize) {
+        self.counter.ccx(ctl0, ctl1, q);
+        self.sim.ccx(ctl0, ctl1, q);
+    }
+
+    fn cx(&mut self, ctl: usize, q: usize) {
+        self.counter.cx(ctl, q);
+        self.sim.cx(ctl, q);
+    }
+
+    fn cy(&mut self, ctl: usize, q: usize) {
+        self.counter.cy(ctl, q);
+        self.sim.cy(ctl, q);
+    }
+
+    fn cz(&mut self, ctl: usize, q: usize) {
+        self.counter.cz(ctl, q);
+        self.sim.cz(ctl, q);
+    }
+
+    fn h(&mut self, q: usize) {
+        self.counter.h(q);
+        self.sim.h(q);
+    }
+
+    fn m(&mut self, q: usize) -> Self::ResultType {
+        self.counter.m(q);
+        self.sim.m(q)
+    }
+
+    fn mresetz(&mut self, q: usize) -> Self::ResultType {
+        self.counter.mresetz(q);
+        self.sim.mresetz(q)
+    }
+
+    fn reset(&mut self, q: usize) {
+        self.counter.reset(q);
+        self.sim.reset(q);
+    }
+
+    fn rx(&mut self, theta: f64, q: usize) {
+        self.counter.rx(theta, q);
+        self.sim.rx(theta, q);
+    }
+
+    fn rxx(&mut self, theta: f64, q0: usize, q1: usize) {
+        self.counter.rxx(theta, q0, q1);
+        self.sim.rxx(theta, q0, q1);
+    }
+
+    fn ry(&mut self, theta: f64, q: usize) {
+        self.counter.ry(theta, q);
+        self.sim.ry(theta, q);
+    }
+
+    fn ryy(&mut self, theta: f64, q0: usize, q1: usize) {
+        self.counter.ryy(theta, q0, q1);
+        self.sim.ryy(theta, q0, q1);
+    }
+
+    fn rz(&mut self, theta: f64, q: usize) {
+        self.counter.rz(theta, q);
+        self.sim.rz(theta, q);
+    }
+
+    fn rzz(&mut self, theta: f64, q0: usize, q1: usize) {
+        self.counter.rzz(theta, q0, q1);
+        self.sim.rzz(theta, q0, q1);
+    }
+
+    fn sadj(&mut self, q: usize) {
+        self.counter.sadj(q);
+        self.sim.sadj(q);
+    }
+
+    fn s(&mut self, q: usize) {
+        self.counter.s(q);
+        self.sim.s(q);
+    }
+
+    fn swap(&mut self, q0: usize, q1: usize) {
+        self.counter.swap(q0, q1);
+        self.sim.swap(q0, q1);
+    }
+
+    fn tadj(&mut self, q: usize) {
+        self.counter.tadj(q);
+        self.sim.tadj(q);
+    }
+
+    fn t(&mut self, q: usize) {
+        self.counter.t(q);
+        self.sim.t(q);
+    }
+
+    fn x(&mut self, q: usize) {
+        self.counter.x(q);
+        self.sim.x(q);
+    }
+
+    fn y(&mut self, q: usize) {
+        self.counter.y(q);
+        self.sim.y(q);
+    }
+
+    fn z(&mut self, q: usize) {
+        self.counter.z(q);
+        self.sim.z(q);
+    }
+
+    fn qubit_allocate(&mut self) -> usize {
+        let q = self.sim.qubit_allocate();
+        self.counter.qubit_allocate_id(q);
+        q
+    }
+
+    fn qubit_release(&mut self, q: usize) -> bool {
+        self.counter.qubit_release(q);
+        self.sim.qubit_release(q)
+    }
+
+    fn qubit_swap_id(&mut self, q0: usize, q1: usize) {
+        self.counter.qubit_swap_id(q0, q1);
+        self.sim.qubit_swap_id(q0, q1);
+    }
+
+    fn capture_quantum_state(&mut self) -> (Vec<(BigUint, Complex<f64>)>, usize) {
+        self.sim.capture_quantum_state()
+    }
+
+    fn set_quantum_state(
+        &mut self,
+        state: &[(BigUint, Complex<f64>)],
+        qubits: &[usize],
+    ) -> Result<(), String> {
+        self.sim.set_quantum_state(state, qubits)
+    }
+
+    fn qubit_is_zero(&mut self, q: usize) -> bool {
+        self.sim.qubit_is_zero(q)
+    }
+
+    fn custom_intrinsic(&mut self, name: &str, arg: Value) -> Option<Result<Value, String>> {
+        match name {
+            "BeginEstimateCaching" => Some(Ok(Value::Bool(true))),
+            "EndEstimateCaching" => Some(Ok(Value::unit())),
+            "BeginRepeatEstimatesInternal"
+            | "EndRepeatEstimatesInternal"
+            | "AccountForEstimatesInternal" => self.counter.custom_intrinsic(name, arg),
+            _ => self.sim.custom_intrinsic(name, arg),
+        }
+    }
+
+    fn set_seed(&mut self, _seed: Option<u64>) {
+        // The seed of the path, given when the counter was created, is kept.
+    }
+}
+
+/// Logical counts of a program along the classical control paths taken by
+/// seeded simulations of it
+#[derive(Debug)]
+pub struct PathCounts {
+    /// The counts of each simulated path
+    pub paths: Vec<LogicalResourceCounts>,
+}
+
+impl PathCounts {
+    /// The mean of the counts of the paths, rounded up
+    #[must_use]
+    pub fn expected(&self) -> LogicalResourceCounts {
+        let num_paths = self.paths.len().max(1) as u64;
+        let mean = |count: fn(&LogicalResourceCounts) -> u64| {
+            self.paths
+                .iter()
+                .map(count)
+                .sum::<u64>()
+                .div_ceil(num_paths)
+        };
+        LogicalResourceCounts {
+            num_qubits: mean(|counts| counts.num_qubits),
+            t_count: mean(|counts| counts.t_count),
+            rotation_count: mean(|counts| counts.rotation_count),
+            rotation_depth: mean(|counts| counts.rotation_depth),
+            ccz_count: mean(|counts| counts.ccz_count),
+            ccix_count: mean(|counts| counts.ccix_count),
+            measurement_count: mean(|counts| counts.measurement_count),
+            feedback_depth: mean(|counts| counts.feedback_depth),
+            logical_depth: None,
+            phases: vec![],
+        }
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
 pub struct LayerInfo {
     t: usize,
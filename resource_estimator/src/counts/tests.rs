@@ -71,6 +71,9 @@ fn gates_are_counted() {
                 ccz_count: 2,
                 ccix_count: 0,
                 measurement_count: 10,
+                feedback_depth: 0,
+                logical_depth: None,
+                phases: [],
             }
         "]],
     );
@@ -118,6 +121,9 @@ fn estimate_caching_works() {
                 ccz_count: 0,
                 ccix_count: 0,
                 measurement_count: 0,
+                feedback_depth: 0,
+                logical_depth: None,
+                phases: [],
             }
         "]],
     );
@@ -165,6 +171,9 @@ fn estimate_repeat_works() {
                 ccz_count: 0,
                 ccix_count: 0,
                 measurement_count: 0,
+                feedback_depth: 0,
+                logical_depth: None,
+                phases: [],
             }
         "#]],
     );
@@ -204,6 +213,9 @@ fn account_for_estimates_works() {
                 ccz_count: 5,
                 ccix_count: 0,
                 measurement_count: 6,
+                feedback_depth: 0,
+                logical_depth: None,
+                phases: [],
             }
         "]],
     );
@@ -232,6 +244,9 @@ fn pauli_i_rotation_for_global_phase_is_noop() {
                 ccz_count: 0,
                 ccix_count: 0,
                 measurement_count: 0,
+                feedback_depth: 0,
+                logical_depth: None,
+                phases: [],
             }
         "#]],
     );
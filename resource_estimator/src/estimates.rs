@@ -20,6 +20,6 @@ pub use physical_estimation::{
 };
 mod layout;
 mod logical_qubit;
-pub use layout::{Overhead, RealizedOverhead};
+pub use layout::{Overhead, Phase, RealizedOverhead};
 pub use logical_qubit::LogicalPatch;
 pub mod optimization;
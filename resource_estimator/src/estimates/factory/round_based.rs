@@ -121,6 +121,12 @@ impl<P: Clone> DistillationRound<P> {
     pub fn num_units(&self) -> u64 {
         self.num_units
     }
+
+    /// Sets the number of units directly, for rounds of factories whose design
+    /// is given instead of searched
+    pub fn set_num_units(&mut self, num_units: u64) {
+        self.num_units = num_units;
+    }
 }
 
 #[derive(Debug, Clone)]
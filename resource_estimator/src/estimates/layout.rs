@@ -24,12 +24,39 @@ pub trait Overhead {
     /// supported by available factory builders in the physical estimation.
     fn num_magic_states(&self, budget: &ErrorBudget, index: usize) -> u64;
 
+    /// The number of classical feedback steps that run one after the other
+    ///
+    /// At each step, the algorithm waits for the result of a measurement
+    /// before it can go on, which adds to the runtime but not to the logical
+    /// depth.
+    fn feedback_depth(&self) -> u64 {
+        0
+    }
+
+    /// The logical depth and the number of magic states of each phase, for
+    /// algorithms whose phases run one after the other
+    ///
+    /// The phases together make up the algorithm.  Since phases do not need
+    /// magic states at the same time, they can share their factories.  An
+    /// empty vector means that the algorithm is a single phase.
+    #[allow(unused_variables)]
+    fn phases(&self, budget: &ErrorBudget, index: usize) -> Vec<Phase> {
+        vec![]
+    }
+
     /// When implemented, prunes the error budget with respect to the provided
     /// strategy
     #[allow(unused_variables)]
     fn prune_error_budget(&self, budget: &mut ErrorBudget, strategy: ErrorBudgetStrategy) {}
 }
 
+/// The logical overhead of one phase of an algorithm
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Phase {
+    pub logical_depth: u64,
+    pub num_magic_states: u64,
+}
+
 /// This is the realized logical overhead after applying an error budget.  This
 /// structure has two purposes: 1) it is used to store the realized logical
 /// overhead, once the error budget partition is decided into the resource
@@ -27,6 +27,10 @@ pub struct PhysicalResourceEstimation<E: ErrorCorrection, Builder, L> {
     max_duration: Option<u64>,
     max_physical_qubits: Option<u64>,
     error_budget_strategy: ErrorBudgetStrategy,
+    // classical feedback latency in nanoseconds
+    feedback_latency: u64,
+    // whether phases of the algorithm share their factories
+    factory_sharing: bool,
 }
 
 impl<
@@ -51,6 +55,8 @@ impl<
             max_duration: None,
             max_physical_qubits: None,
             error_budget_strategy: ErrorBudgetStrategy::default(),
+            feedback_latency: 0,
+            factory_sharing: true,
         }
     }
 
@@ -75,6 +81,27 @@ impl<
         self.max_physical_qubits = Some(max_physical_qubits);
     }
 
+    /// Sets the time in nanoseconds that the classical control system takes
+    /// to act on a measurement result, after the measurement itself.
+    pub fn set_feedback_latency(&mut self, feedback_latency: u64) {
+        self.feedback_latency = feedback_latency;
+    }
+
+    /// The time in nanoseconds that the algorithm waits for classical
+    /// feedback: each feedback step waits one logical cycle for the
+    /// measurement and then the feedback latency.
+    pub fn feedback_runtime(&self, logical_patch: &LogicalPatch<E>) -> u64 {
+        self.layout_overhead.feedback_depth()
+            * (logical_patch.logical_cycle_time() + self.feedback_latency)
+    }
+
+    /// Sets whether the phases of the algorithm share their factories.  When
+    /// shared, the number of factories is the one of the most demanding phase,
+    /// otherwise each phase has its own factories.
+    pub fn set_factory_sharing(&mut self, factory_sharing: bool) {
+        self.factory_sharing = factory_sharing;
+    }
+
     pub fn error_budget_strategy(&self) -> ErrorBudgetStrategy {
         self.error_budget_strategy
     }
@@ -171,6 +198,7 @@ impl<
                 LogicalPatch::new(&self.ftp, min_code_parameter, self.qubit.clone())?;
 
             if num_cycles_required_by_layout_overhead * logical_patch.logical_cycle_time()
+                + self.feedback_runtime(&logical_patch)
                 <= max_duration_in_nanoseconds
             {
                 return Ok(PhysicalResourceEstimationResult::without_factories(
@@ -201,7 +229,9 @@ impl<
             let logical_patch =
                 LogicalPatch::new(&self.ftp, code_parameter.clone(), self.qubit.clone())?;
 
-            let max_num_cycles_allowed_by_duration = ((max_duration_in_nanoseconds as f64)
+            let max_duration_for_cycles =
+                max_duration_in_nanoseconds.saturating_sub(self.feedback_runtime(&logical_patch));
+            let max_num_cycles_allowed_by_duration = ((max_duration_for_cycles as f64)
                 / logical_patch.logical_cycle_time() as f64)
                 .floor() as u64;
             if max_num_cycles_allowed_by_duration < num_cycles_required_by_layout_overhead {
@@ -598,21 +628,63 @@ impl<
         factory: &Builder::Factory,
         error_budget: &ErrorBudget,
         num_cycles: u64,
+    ) -> u64 {
+        let phases = self.layout_overhead.phases(error_budget, magic_state_index);
+        if phases.is_empty() {
+            return Self::num_factories_for_magic_states(
+                logical_patch,
+                factory,
+                self.layout_overhead
+                    .num_magic_states(error_budget, magic_state_index),
+                num_cycles,
+            );
+        }
+
+        // each phase runs for a share of the cycles that corresponds to its
+        // logical depth
+        let total_depth = phases
+            .iter()
+            .map(|phase| phase.logical_depth)
+            .sum::<u64>()
+            .max(1);
+        let num_factories_per_phase = phases.iter().map(|phase| {
+            let phase_cycles = ((num_cycles as f64) * (phase.logical_depth as f64)
+                / (total_depth as f64))
+                .ceil() as u64;
+            Self::num_factories_for_magic_states(
+                logical_patch,
+                factory,
+                phase.num_magic_states,
+                phase_cycles,
+            )
+        });
+
+        if self.factory_sharing {
+            num_factories_per_phase.max().unwrap_or(0)
+        } else {
+            num_factories_per_phase.sum()
+        }
+    }
+
+    fn num_factories_for_magic_states(
+        logical_patch: &LogicalPatch<E>,
+        factory: &Builder::Factory,
+        num_magic_states: u64,
+        num_cycles: u64,
     ) -> u64 {
         // first, try with the exact calculation; if that does not work, use
         // floating-point arithmetic, which may cause numeric imprecision
         if let Some(total_duration) = num_cycles.checked_mul(logical_patch.logical_cycle_time()) {
-            // number of magic states that one factory can compute in num_cycles
+            // number of magic states that one factory can compute in
+            // num_cycles; a phase that is shorter than one factory run still
+            // receives the states of one run, which the factory produces
+            // ahead of time
             let num_states_per_run =
-                (total_duration / factory.duration()) * factory.num_output_states();
-            self.layout_overhead
-                .num_magic_states(error_budget, magic_state_index)
-                .div_ceil(num_states_per_run)
+                (total_duration / factory.duration()).max(1) * factory.num_output_states();
+            num_magic_states.div_ceil(num_states_per_run)
         } else {
             let magic_states_per_cycles =
-                self.layout_overhead
-                    .num_magic_states(error_budget, magic_state_index) as f64
-                    / (factory.num_output_states() * num_cycles) as f64;
+                num_magic_states as f64 / (factory.num_output_states() * num_cycles) as f64;
 
             let factory_duration_fraction =
                 factory.duration() as f64 / logical_patch.logical_cycle_time() as f64;
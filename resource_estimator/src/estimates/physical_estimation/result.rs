@@ -22,6 +22,8 @@ pub struct PhysicalResourceEstimationResult<E: ErrorCorrection, F> {
     physical_qubits_for_algorithm: u64,
     physical_qubits: u64,
     runtime: u64,
+    #[serde(skip_serializing_if = "is_zero")]
+    feedback_runtime: u64,
     rqops: u64,
     layout_overhead: RealizedOverhead,
     error_budget: ErrorBudget,
@@ -54,7 +56,8 @@ impl<E: ErrorCorrection<Parameter = impl Clone>, F: Factory<Parameter = E::Param
 
         let physical_qubits = physical_qubits_for_algorithm + physical_qubits_for_factories;
 
-        let runtime = (logical_patch.logical_cycle_time()) * num_cycles;
+        let feedback_runtime = estimation.feedback_runtime(&logical_patch);
+        let runtime = (logical_patch.logical_cycle_time()) * num_cycles + feedback_runtime;
 
         let rqops = (estimation.layout_overhead().logical_qubits() as f64
             * logical_patch.logical_cycles_per_second())
@@ -69,6 +72,7 @@ impl<E: ErrorCorrection<Parameter = impl Clone>, F: Factory<Parameter = E::Param
             physical_qubits_for_algorithm,
             physical_qubits,
             runtime,
+            feedback_runtime,
             rqops,
             layout_overhead: RealizedOverhead::from_overhead(
                 estimation.layout_overhead(),
@@ -141,6 +145,12 @@ impl<E: ErrorCorrection<Parameter = impl Clone>, F: Factory<Parameter = E::Param
         self.runtime
     }
 
+    /// The part of the runtime that the algorithm waits for classical
+    /// feedback
+    pub fn feedback_runtime(&self) -> u64 {
+        self.feedback_runtime
+    }
+
     pub fn rqops(&self) -> u64 {
         self.rqops
     }
@@ -223,3 +233,8 @@ impl<F: Factory> FactoryPart<F> {
         self.factory
     }
 }
+
+#[allow(clippy::trivially_copy_pass_by_ref)] // forced by serde interface
+fn is_zero(value: &u64) -> bool {
+    *value == 0
+}
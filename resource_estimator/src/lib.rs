@@ -21,12 +21,18 @@ pub mod estimates;
 /// customizaable gate-based and Majorana qubits, planar codes, and T-factories.
 pub mod system;
 
-pub use system::estimate_physical_resources_from_json;
+#[cfg(test)]
+mod tests;
 
-use counts::LogicalCounter;
+pub use system::{
+    estimate_physical_resources, estimate_physical_resources_from_json, LogicalResourceCounts,
+};
+
+use counts::{LogicalCounter, PathCounts, SimulatedCounter};
 use miette::Diagnostic;
 use qsc::interpret::{self, GenericReceiver, Interpreter, Value};
-use system::estimate_physical_resources;
+use rustc_hash::{FxHashMap, FxHasher};
+use std::hash::{Hash, Hasher};
 use thiserror::Error;
 
 #[derive(Debug, Diagnostic, Error)]
@@ -53,14 +59,43 @@ pub fn estimate_expr(
     expr: &str,
     params: &str,
 ) -> Result<String, Vec<Error>> {
+    let logical_resources = logical_counts_expr(interpreter, expr)?;
+    estimate_physical_resources(logical_resources, params).map_err(|e| vec![Error::Estimation(e)])
+}
+
+/// Computes the logical counts of the given entry expression, which is the part of an estimate
+/// that runs the program and doesn't depend on the physical parameters.
+pub fn logical_counts_expr(
+    interpreter: &mut Interpreter,
+    expr: &str,
+) -> Result<LogicalResourceCounts, Vec<Error>> {
     let mut counter = LogicalCounter::default();
     let mut stdout = std::io::sink();
     let mut out = GenericReceiver::new(&mut stdout);
     interpreter
         .run_with_sim(&mut counter, &mut out, Some(expr))
         .map_err(|e| e.into_iter().map(Error::Interpreter).collect::<Vec<_>>())?;
-    estimate_physical_resources(counter.logical_resources(), params)
-        .map_err(|e| vec![Error::Estimation(e)])
+    let mut logical_resources = counter.logical_resources();
+    logical_resources.feedback_depth = feedback_depth(interpreter, expr)?;
+    Ok(logical_resources)
+}
+
+/// Finds the number of branches on measurement results that run one after the other in the program
+/// generated for the target, so that the estimates account for the time spent waiting for classical
+/// feedback. Programs for unrestricted targets aren't generated, so their feedback isn't known.
+fn feedback_depth(interpreter: &mut Interpreter, expr: &str) -> Result<u64, Vec<Error>> {
+    match interpreter.program_stats(expr) {
+        Ok(stats) => Ok(stats.feedback_depth as u64),
+        Err(errors)
+            if matches!(
+                errors.as_slice(),
+                [interpret::Error::UnsupportedRuntimeCapabilities]
+            ) =>
+        {
+            Ok(0)
+        }
+        Err(errors) => Err(errors.into_iter().map(Error::Interpreter).collect()),
+    }
 }
 
 pub fn estimate_call(
@@ -69,12 +104,210 @@ pub fn estimate_call(
     args: Value,
     params: &str,
 ) -> Result<String, Vec<Error>> {
+    let logical_resources = logical_counts_call(interpreter, callable, args)?;
+    estimate_physical_resources(logical_resources, params).map_err(|e| vec![Error::Estimation(e)])
+}
+
+/// Computes the logical counts of invoking the given callable with the given arguments.
+pub fn logical_counts_call(
+    interpreter: &mut Interpreter,
+    callable: Value,
+    args: Value,
+) -> Result<LogicalResourceCounts, Vec<Error>> {
     let mut counter = LogicalCounter::default();
     let mut stdout = std::io::sink();
     let mut out = GenericReceiver::new(&mut stdout);
     interpreter
         .invoke_with_sim(&mut counter, &mut out, callable, args)
         .map_err(|e| e.into_iter().map(Error::Interpreter).collect::<Vec<_>>())?;
-    estimate_physical_resources(counter.logical_resources(), params)
-        .map_err(|e| vec![Error::Estimation(e)])
+    Ok(counter.logical_resources())
+}
+
+/// Counts the logical resources of the given entry expression along the classical control paths
+/// of `paths` seeded simulations, instead of assuming random measurement results. Path `i` is
+/// simulated with the seed `seed + i`, so that the counts are reproducible.
+pub fn path_counts_expr(
+    interpreter: &mut Interpreter,
+    expr: &str,
+    paths: usize,
+    seed: u64,
+) -> Result<PathCounts, Vec<Error>> {
+    let feedback_depth = feedback_depth(interpreter, expr)?;
+    let paths = (0..paths as u64)
+        .map(|path| {
+            let mut counter = SimulatedCounter::new(seed.wrapping_add(path));
+            let mut stdout = std::io::sink();
+            let mut out = GenericReceiver::new(&mut stdout);
+            interpreter
+                .run_with_sim(&mut counter, &mut out, Some(expr))
+                .map_err(|e| e.into_iter().map(Error::Interpreter).collect::<Vec<_>>())?;
+            let mut logical_resources = counter.logical_resources();
+            logical_resources.feedback_depth = feedback_depth;
+            Ok(logical_resources)
+        })
+        .collect::<Result<_, Vec<Error>>>()?;
+    Ok(PathCounts { paths })
+}
+
+/// Counts the logical resources of invoking the given callable with the given arguments along the
+/// classical control paths of `paths` seeded simulations, like [`path_counts_expr`].
+pub fn path_counts_call(
+    interpreter: &mut Interpreter,
+    callable: &Value,
+    args: &Value,
+    paths: usize,
+    seed: u64,
+) -> Result<PathCounts, Vec<Error>> {
+    let paths = (0..paths as u64)
+        .map(|path| {
+            let mut counter = SimulatedCounter::new(seed.wrapping_add(path));
+            let mut stdout = std::io::sink();
+            let mut out = GenericReceiver::new(&mut stdout);
+            interpreter
+                .invoke_with_sim(&mut counter, &mut out, callable.clone(), args.clone())
+                .map_err(|e| e.into_iter().map(Error::Interpreter).collect::<Vec<_>>())?;
+            Ok(counter.logical_resources())
+        })
+        .collect::<Result<_, Vec<Error>>>()?;
+    Ok(PathCounts { paths })
+}
+
+/// Estimates the expected logical counts over the simulated paths of the given entry expression,
+/// and adds the counts of each path to every result as `pathLogicalCounts`.
+pub fn estimate_paths_expr(
+    interpreter: &mut Interpreter,
+    expr: &str,
+    params: &str,
+    paths: usize,
+    seed: u64,
+) -> Result<String, Vec<Error>> {
+    let path_counts = path_counts_expr(interpreter, expr, paths, seed)?;
+    estimate_path_counts(&path_counts, params)
+}
+
+/// Estimates the expected logical counts over the simulated paths of invoking the given callable,
+/// like [`estimate_paths_expr`].
+pub fn estimate_paths_call(
+    interpreter: &mut Interpreter,
+    callable: &Value,
+    args: &Value,
+    params: &str,
+    paths: usize,
+    seed: u64,
+) -> Result<String, Vec<Error>> {
+    let path_counts = path_counts_call(interpreter, callable, args, paths, seed)?;
+    estimate_path_counts(&path_counts, params)
+}
+
+fn estimate_path_counts(path_counts: &PathCounts, params: &str) -> Result<String, Vec<Error>> {
+    let estimates = estimate_physical_resources(path_counts.expected(), params)
+        .map_err(|e| vec![Error::Estimation(e)])?;
+    let mut estimates: Vec<serde_json::Value> =
+        serde_json::from_str(&estimates).expect("estimates should be valid JSON");
+    let path_logical_counts =
+        serde_json::to_value(&path_counts.paths).expect("serializing counts should succeed");
+    for estimate in &mut estimates {
+        if let serde_json::Value::Object(estimate) = estimate {
+            estimate.insert("pathLogicalCounts".into(), path_logical_counts.clone());
+        }
+    }
+    Ok(serde_json::to_string(&estimates).expect("serializing estimates should succeed"))
+}
+
+/// The logical counts of the programs estimated so far, so that estimating a program again with
+/// other physical parameters, such as in a sweep over error budgets or qubit models, skips running
+/// the program and only repeats the physical estimation.
+///
+/// Programs are keyed by a hash of their entry expression, or of the callable and its arguments.
+/// The counts only stay valid while the compilation they were computed from is unchanged, so the
+/// cache must be cleared whenever new code is compiled into the interpreter.
+#[derive(Default)]
+pub struct LogicalCountsCache {
+    counts: FxHashMap<u64, LogicalResourceCounts>,
+}
+
+impl LogicalCountsCache {
+    /// Estimates the given entry expression, computing its logical counts only if they aren't
+    /// cached yet.
+    pub fn estimate_expr(
+        &mut self,
+        interpreter: &mut Interpreter,
+        expr: &str,
+        params: &str,
+    ) -> Result<String, Vec<Error>> {
+        let logical_resources = self.logical_counts_expr(interpreter, expr)?;
+        estimate_physical_resources(logical_resources, params)
+            .map_err(|e| vec![Error::Estimation(e)])
+    }
+
+    /// Estimates invoking the given callable with the given arguments, computing its logical
+    /// counts only if they aren't cached yet.
+    pub fn estimate_call(
+        &mut self,
+        interpreter: &mut Interpreter,
+        callable: Value,
+        args: Value,
+        params: &str,
+    ) -> Result<String, Vec<Error>> {
+        let logical_resources = self.logical_counts_call(interpreter, callable, args)?;
+        estimate_physical_resources(logical_resources, params)
+            .map_err(|e| vec![Error::Estimation(e)])
+    }
+
+    /// The logical counts of the given entry expression, from the cache if possible.
+    pub fn logical_counts_expr(
+        &mut self,
+        interpreter: &mut Interpreter,
+        expr: &str,
+    ) -> Result<LogicalResourceCounts, Vec<Error>> {
+        let key = program_hash(&("expr", expr));
+        if let Some(counts) = self.counts.get(&key) {
+            return Ok(counts.clone());
+        }
+        let counts = logical_counts_expr(interpreter, expr)?;
+        self.counts.insert(key, counts.clone());
+        Ok(counts)
+    }
+
+    /// The logical counts of invoking the given callable with the given arguments, from the cache
+    /// if possible. Closures are never cached, since they can't be told apart by their display
+    /// form.
+    pub fn logical_counts_call(
+        &mut self,
+        interpreter: &mut Interpreter,
+        callable: Value,
+        args: Value,
+    ) -> Result<LogicalResourceCounts, Vec<Error>> {
+        if matches!(callable, Value::Closure(..)) {
+            return logical_counts_call(interpreter, callable, args);
+        }
+        let key = program_hash(&("call", callable.to_string(), args.to_string()));
+        if let Some(counts) = self.counts.get(&key) {
+            return Ok(counts.clone());
+        }
+        let counts = logical_counts_call(interpreter, callable, args)?;
+        self.counts.insert(key, counts.clone());
+        Ok(counts)
+    }
+
+    /// Forgets all cached logical counts.
+    pub fn clear(&mut self) {
+        self.counts.clear();
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+}
+
+fn program_hash(program: &impl Hash) -> u64 {
+    let mut hasher = FxHasher::default();
+    program.hash(&mut hasher);
+    hasher.finish()
 }
@@ -28,7 +28,10 @@ pub use self::modeling::{
     ProtocolEvaluator, ProtocolSpecification, TFactory,
 };
 pub use self::optimization::TFactoryBuilder;
-pub use self::{data::LogicalResourceCounts, error::Error};
+pub use self::{
+    data::{LogicalResourceCounts, PhaseCounts},
+    error::Error,
+};
 use data::{EstimateType, JobParams};
 pub use data::{LayoutReportData, PartitioningOverhead};
 use serde::Serialize;
@@ -80,9 +83,18 @@ fn estimate_single<L: Overhead + LayoutReportData + PartitioningOverhead + Seria
     let qubit = job_params.qubit_params().clone();
 
     let ftp = load_protocol_from_specification(job_params.qec_scheme_mut(), &qubit)?;
-    let distillation_unit_templates = job_params
-        .distillation_unit_specifications()
-        .as_templates()?;
+    let factory_designs = job_params.factory_specifications().as_designs()?;
+    // factory designs without distillation unit specifications replace the
+    // search over the default distillation units
+    let distillation_unit_templates = if !factory_designs.is_empty()
+        && job_params.distillation_unit_specifications().is_empty()
+    {
+        vec![]
+    } else {
+        job_params
+            .distillation_unit_specifications()
+            .as_templates()?
+    };
     // create error budget partitioning
     let partitioning = job_params
         .error_budget()
@@ -90,15 +102,13 @@ fn estimate_single<L: Overhead + LayoutReportData + PartitioningOverhead + Seria
 
     // The clone on the logical resources is on an Rc and therefore inexpensive,
     // the value is later used in creating the result object
-    let mut estimation = PhysicalResourceEstimation::new(
-        ftp,
-        qubit,
-        TFactoryBuilder::new(
-            distillation_unit_templates,
-            job_params.constraints().max_distillation_rounds,
-        ),
-        logical_resources.clone(),
+    let mut factory_builder = TFactoryBuilder::new(
+        distillation_unit_templates,
+        job_params.constraints().max_distillation_rounds,
     );
+    factory_builder.set_designs(factory_designs);
+    let mut estimation =
+        PhysicalResourceEstimation::new(ftp, qubit, factory_builder, logical_resources.clone());
     if let Some(logical_depth_factor) = job_params.constraints().logical_depth_factor {
         estimation.set_logical_depth_factor(logical_depth_factor);
     }
@@ -111,6 +121,10 @@ fn estimate_single<L: Overhead + LayoutReportData + PartitioningOverhead + Seria
     if let Some(max_physical_qubits) = job_params.constraints().max_physical_qubits {
         estimation.set_max_physical_qubits(max_physical_qubits);
     }
+    if let Some(feedback_latency) = job_params.feedback_latency() {
+        estimation.set_feedback_latency(feedback_latency);
+    }
+    estimation.set_factory_sharing(job_params.factory_sharing());
 
     match job_params.estimate_type() {
         EstimateType::Frontier => {
@@ -2,6 +2,7 @@
 // Licensed under the MIT License.
 
 mod constraints;
+mod floorplan;
 mod job_params;
 mod logical_counts;
 mod physical_counts;
@@ -10,8 +11,11 @@ mod result;
 mod tfactory;
 
 pub use constraints::Constraints;
+pub use floorplan::Floorplan;
+#[cfg(test)]
+pub use floorplan::FloorplanRegionKind;
 pub use job_params::{EstimateType, JobParams, PartitioningOverhead};
-pub use logical_counts::{LayoutReportData, LogicalResourceCounts};
+pub use logical_counts::{LayoutReportData, LogicalResourceCounts, PhaseCounts};
 pub use physical_counts::{PhysicalResourceCounts, PhysicalResourceCountsBreakdown};
 pub use report::{FormattedPhysicalResourceCounts, Report};
 pub use result::{Failure, Success};
@@ -0,0 +1,159 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use serde::Serialize;
+
+/// A sketch of how the logical qubit patches and T-factories can be placed on
+/// a 2D grid, whose cells are the size of one logical qubit patch.
+///
+/// Data patches are placed in rows that alternate with routing lanes, so that
+/// every data patch borders a lane, and the remaining routing patches are
+/// placed in a column to the right.  The T-factories are placed in a block to
+/// the right of the algorithm.
+#[derive(Debug, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct Floorplan {
+    /// The width of the grid in patches
+    width: u64,
+    /// The height of the grid in patches
+    height: u64,
+    /// The rectangular regions of the grid
+    regions: Vec<FloorplanRegion>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct FloorplanRegion {
+    kind: FloorplanRegionKind,
+    /// The column of the top left cell
+    x: u64,
+    /// The row of the top left cell
+    y: u64,
+    width: u64,
+    height: u64,
+    /// The number of patches in the region, which can be less than its area
+    /// if the last row or column is not full
+    patches: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub enum FloorplanRegionKind {
+    Data,
+    Routing,
+    #[serde(rename = "tfactory")]
+    TFactory,
+}
+
+impl Floorplan {
+    /// Lays out the given number of data and routing patches, and the given
+    /// number of T-factories that each take up the area of
+    /// `patches_per_factory` patches.
+    pub fn new(
+        data_patches: u64,
+        routing_patches: u64,
+        num_factories: u64,
+        patches_per_factory: u64,
+    ) -> Self {
+        let mut regions = vec![];
+
+        let columns = ((data_patches as f64).sqrt().ceil() as u64).max(1);
+        let data_rows = data_patches.div_ceil(columns);
+
+        let mut remaining_data = data_patches;
+        let mut remaining_routing = routing_patches;
+        for row in 0..data_rows {
+            let patches = remaining_data.min(columns);
+            remaining_data -= patches;
+            regions.push(FloorplanRegion::row(
+                FloorplanRegionKind::Data,
+                2 * row,
+                patches,
+            ));
+
+            let patches = remaining_routing.min(columns);
+            remaining_routing -= patches;
+            if patches > 0 {
+                regions.push(FloorplanRegion::row(
+                    FloorplanRegionKind::Routing,
+                    2 * row + 1,
+                    patches,
+                ));
+            }
+        }
+
+        let mut width = if data_rows == 0 { 0 } else { columns };
+        let mut height = 2 * data_rows;
+        if remaining_routing > 0 {
+            let column_height = height.max(1);
+            let column_width = remaining_routing.div_ceil(column_height);
+            regions.push(FloorplanRegion {
+                kind: FloorplanRegionKind::Routing,
+                x: width,
+                y: 0,
+                width: column_width,
+                height: column_height.min(remaining_routing),
+                patches: remaining_routing,
+            });
+            width += column_width;
+            height = column_height;
+        }
+
+        if num_factories > 0 && patches_per_factory > 0 {
+            // Each factory takes up a square, and the squares are stacked in
+            // columns as high as the algorithm.
+            let side = (patches_per_factory as f64).sqrt().ceil() as u64;
+            let factories_per_column = (height / side).max(1);
+            let factory_columns = num_factories.div_ceil(factories_per_column);
+            let block_height = num_factories.min(factories_per_column) * side;
+            regions.push(FloorplanRegion {
+                kind: FloorplanRegionKind::TFactory,
+                x: width,
+                y: 0,
+                width: factory_columns * side,
+                height: block_height,
+                patches: num_factories * patches_per_factory,
+            });
+            width += factory_columns * side;
+            height = height.max(block_height);
+        }
+
+        Self {
+            width,
+            height,
+            regions,
+        }
+    }
+
+    #[cfg(test)]
+    pub fn width(&self) -> u64 {
+        self.width
+    }
+
+    #[cfg(test)]
+    pub fn height(&self) -> u64 {
+        self.height
+    }
+
+    #[cfg(test)]
+    pub fn patches(&self, kind: FloorplanRegionKind) -> u64 {
+        self.regions
+            .iter()
+            .filter(|region| region.kind == kind)
+            .map(|region| region.patches)
+            .sum()
+    }
+}
+
+impl FloorplanRegion {
+    fn row(kind: FloorplanRegionKind, y: u64, patches: u64) -> Self {
+        Self {
+            kind,
+            x: 0,
+            y,
+            width: patches,
+            height: 1,
+            patches,
+        }
+    }
+}
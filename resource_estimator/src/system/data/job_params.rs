@@ -8,10 +8,14 @@ use serde::{Deserialize, Serialize};
 use super::super::{
     error::InvalidInput::{self, InvalidErrorBudget},
     modeling::{PhysicalQubit, ProtocolSpecification},
+    serialization::time,
 };
 use crate::estimates::ErrorBudget;
 
-use super::{tfactory::TFactoryDistillationUnitSpecifications, Constraints};
+use super::{
+    tfactory::{TFactoryDistillationUnitSpecifications, TFactorySpecifications},
+    Constraints,
+};
 
 #[derive(Default, Deserialize, Serialize)]
 #[serde(rename_all(serialize = "camelCase", deserialize = "camelCase"))]
@@ -34,8 +38,17 @@ pub struct JobParams {
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     distillation_unit_specifications: TFactoryDistillationUnitSpecifications,
 
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    factory_specifications: TFactorySpecifications,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    factory_sharing: Option<bool>,
+
     #[serde(default)]
     estimate_type: EstimateType,
+
+    #[serde(default, with = "time", skip_serializing_if = "Option::is_none")]
+    feedback_latency: Option<u64>,
 }
 
 impl JobParams {
@@ -75,11 +88,33 @@ impl JobParams {
         &self.distillation_unit_specifications
     }
 
+    #[must_use]
+    #[inline]
+    pub fn factory_specifications(&self) -> &TFactorySpecifications {
+        &self.factory_specifications
+    }
+
+    /// Whether the phases of a program, which run one after the other, share
+    /// their factories, which is the default.
+    #[must_use]
+    #[inline]
+    pub fn factory_sharing(&self) -> bool {
+        self.factory_sharing.unwrap_or(true)
+    }
+
     #[must_use]
     #[inline]
     pub fn estimate_type(&self) -> &EstimateType {
         &self.estimate_type
     }
+
+    /// The time in nanoseconds that the classical control system takes to act
+    /// on a measurement result, in addition to the time of the measurement.
+    #[must_use]
+    #[inline]
+    pub fn feedback_latency(&self) -> Option<u64> {
+        self.feedback_latency
+    }
 }
 
 #[derive(Serialize, Deserialize, Default, Eq, PartialEq)]
@@ -2,7 +2,7 @@
 // Licensed under the MIT License.
 
 use crate::{
-    estimates::{ErrorBudget, ErrorBudgetStrategy, Overhead},
+    estimates::{ErrorBudget, ErrorBudgetStrategy, Overhead, Phase},
     system::constants::{
         NUM_MEASUREMENTS_PER_R, NUM_MEASUREMENTS_PER_TOF, NUM_TS_PER_ROTATION_A_COEFFICIENT,
         NUM_TS_PER_ROTATION_B_COEFFICIENT,
@@ -24,7 +24,7 @@ pub trait LayoutReportData {
 }
 
 /// Resource counts output from `qir_estimate_counts` program
-#[derive(Default, Debug, Deserialize, Serialize)]
+#[derive(Default, Debug, Clone, Deserialize, Serialize)]
 #[serde(
     rename_all(deserialize = "camelCase", serialize = "camelCase"),
     deny_unknown_fields
@@ -43,6 +43,68 @@ pub struct LogicalResourceCounts {
     pub ccix_count: u64,
     #[serde(default)]
     pub measurement_count: u64,
+    /// The number of branches on measurement results that run one after the
+    /// other, which is only known for programs compiled for adaptive targets
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub feedback_depth: u64,
+    /// The logical depth of the program, such as one taken from a published
+    /// table of resources, which replaces the depth that is otherwise
+    /// computed from the counts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logical_depth: Option<u64>,
+    /// The counts of the phases of the program, which run one after the other
+    /// and together make up the counts above
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub phases: Vec<PhaseCounts>,
+}
+
+/// Resource counts of one phase of a program
+#[derive(Default, Debug, Clone, Deserialize, Serialize)]
+#[serde(
+    rename_all(deserialize = "camelCase", serialize = "camelCase"),
+    deny_unknown_fields
+)]
+pub struct PhaseCounts {
+    #[serde(default)]
+    pub t_count: u64,
+    #[serde(default)]
+    pub rotation_count: u64,
+    #[serde(default)]
+    pub rotation_depth: u64,
+    #[serde(default)]
+    pub ccz_count: u64,
+    #[serde(default)]
+    pub ccix_count: u64,
+    #[serde(default)]
+    pub measurement_count: u64,
+}
+
+impl PhaseCounts {
+    fn logical_depth(&self, num_ts_per_rotation: u64) -> u64 {
+        (self.measurement_count + self.rotation_count + self.t_count) * NUM_MEASUREMENTS_PER_R
+            + (self.ccz_count + self.ccix_count) * NUM_MEASUREMENTS_PER_TOF
+            + num_ts_per_rotation * self.rotation_depth * NUM_MEASUREMENTS_PER_R
+    }
+
+    fn num_magic_states(&self, num_ts_per_rotation: u64) -> u64 {
+        4 * (self.ccz_count + self.ccix_count)
+            + self.t_count
+            + num_ts_per_rotation * self.rotation_count
+    }
+}
+
+impl LogicalResourceCounts {
+    /// The counts of the whole program as a single phase
+    fn as_phase(&self) -> PhaseCounts {
+        PhaseCounts {
+            t_count: self.t_count,
+            rotation_count: self.rotation_count,
+            rotation_depth: self.rotation_depth,
+            ccz_count: self.ccz_count,
+            ccix_count: self.ccix_count,
+            measurement_count: self.measurement_count,
+        }
+    }
 }
 
 /// Models the logical resources after layout
@@ -60,22 +122,38 @@ impl Overhead for LogicalResourceCounts {
     }
 
     fn logical_depth(&self, budget: &ErrorBudget) -> u64 {
-        (self.measurement_count + self.rotation_count + self.t_count) * NUM_MEASUREMENTS_PER_R
-            + (self.ccz_count + self.ccix_count) * NUM_MEASUREMENTS_PER_TOF
-            + self
-                .num_ts_per_rotation(budget.rotations())
-                .unwrap_or_default()
-                * self.rotation_depth
-                * NUM_MEASUREMENTS_PER_R
+        self.logical_depth.unwrap_or_else(|| {
+            self.as_phase().logical_depth(
+                self.num_ts_per_rotation(budget.rotations())
+                    .unwrap_or_default(),
+            )
+        })
+    }
+
+    fn feedback_depth(&self) -> u64 {
+        self.feedback_depth
     }
 
     fn num_magic_states(&self, budget: &ErrorBudget, _index: usize) -> u64 {
-        4 * (self.ccz_count + self.ccix_count)
-            + self.t_count
-            + self
-                .num_ts_per_rotation(budget.rotations())
-                .unwrap_or_default()
-                * self.rotation_count
+        self.as_phase().num_magic_states(
+            self.num_ts_per_rotation(budget.rotations())
+                .unwrap_or_default(),
+        )
+    }
+
+    // the number of T states per rotation depends on the rotations of the
+    // whole program, which share the rotation synthesis error budget
+    fn phases(&self, budget: &ErrorBudget, _index: usize) -> Vec<Phase> {
+        let num_ts_per_rotation = self
+            .num_ts_per_rotation(budget.rotations())
+            .unwrap_or_default();
+        self.phases
+            .iter()
+            .map(|phase| Phase {
+                logical_depth: phase.logical_depth(num_ts_per_rotation),
+                num_magic_states: phase.num_magic_states(num_ts_per_rotation),
+            })
+            .collect()
     }
 
     fn prune_error_budget(&self, budget: &mut ErrorBudget, strategy: ErrorBudgetStrategy) {
@@ -147,3 +225,8 @@ impl LayoutReportData for LogicalResourceCounts {
         }
     }
 }
+
+#[allow(clippy::trivially_copy_pass_by_ref)] // forced by serde interface
+fn is_zero(value: &u64) -> bool {
+    *value == 0
+}
@@ -38,6 +38,13 @@ pub struct PhysicalResourceCountsBreakdown {
     pub(crate) physical_qubits_for_tfactories: u64,
     /// The number of physical qubits for algorithm layout
     pub(crate) physical_qubits_for_algorithm: u64,
+    /// The number of physical qubits for the patches of the algorithm's data
+    /// qubits, which are part of the physical qubits for algorithm layout
+    pub(crate) physical_qubits_for_data: u64,
+    /// The number of physical qubits for the routing and ancilla patches
+    /// between the data qubits, which are the remaining physical qubits for
+    /// algorithm layout
+    pub(crate) physical_qubits_for_routing: u64,
     /// The required logical error rate
     pub(crate) required_logical_qubit_error_rate: f64,
     /// The required logical T-state error rate
@@ -46,4 +53,8 @@ pub struct PhysicalResourceCountsBreakdown {
     pub(crate) num_ts_per_rotation: Option<u64>,
     /// The Clifford error rate based on the qubit parameters
     pub(crate) clifford_error_rate: f64,
+    /// The part of the runtime in nanoseconds that the algorithm waits for
+    /// classical feedback, if it has any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) feedback_runtime: Option<u64>,
 }
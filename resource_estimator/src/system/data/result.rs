@@ -4,12 +4,14 @@
 use std::ops::Deref;
 use std::rc::Rc;
 
-use crate::estimates::{ErrorBudget, FactoryPart, LogicalPatch, PhysicalResourceEstimationResult};
+use crate::estimates::{
+    ErrorBudget, Factory, FactoryPart, LogicalPatch, PhysicalResourceEstimationResult,
+};
 use crate::system::modeling::{Protocol, TFactory};
 
 use super::LayoutReportData;
 use super::{
-    super::Error, FormattedPhysicalResourceCounts, JobParams, PhysicalResourceCounts,
+    super::Error, Floorplan, FormattedPhysicalResourceCounts, JobParams, PhysicalResourceCounts,
     PhysicalResourceCountsBreakdown, Report,
 };
 use miette::Diagnostic;
@@ -30,6 +32,8 @@ pub struct Success<L: LayoutReportData + Serialize> {
     tfactory: Option<TFactory>,
     #[serde(skip_serializing_if = "Option::is_none")]
     error_budget: Option<ErrorBudget>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    floorplan: Option<Floorplan>,
     logical_counts: Rc<L>,
     report_data: Report,
     #[serde(skip_serializing_if = "Vec::is_empty")]
@@ -43,6 +47,7 @@ impl<L: LayoutReportData + Serialize> Success<L> {
         result: PhysicalResourceEstimationResult<Protocol, TFactory>,
     ) -> Self {
         let counts = create_physical_resource_counts(&result, layout_report_data.as_ref());
+        let floorplan = create_floorplan(&result, layout_report_data.as_ref());
 
         let formatted_counts: FormattedPhysicalResourceCounts =
             FormattedPhysicalResourceCounts::new(&result, &job_params, layout_report_data.as_ref());
@@ -65,6 +70,7 @@ impl<L: LayoutReportData + Serialize> Success<L> {
             logical_qubit: Some(LogicalQubit(logical_qubit)),
             tfactory,
             error_budget: Some(error_budget),
+            floorplan: Some(floorplan),
             logical_counts: layout_report_data,
             report_data,
             frontier_entries: Vec::new(),
@@ -105,6 +111,7 @@ impl<L: LayoutReportData + Serialize> Success<L> {
             logical_qubit: None,
             tfactory: None,
             error_budget: None,
+            floorplan: None,
             logical_counts: layout_report_data,
             report_data: report_data.expect("error should have report"), // Here we assume that at least a single solution was found.
             frontier_entries,
@@ -120,6 +127,7 @@ pub struct FrontierEntry {
     pub error_budget: ErrorBudget,
     pub physical_counts: PhysicalResourceCounts,
     pub physical_counts_formatted: FormattedPhysicalResourceCounts,
+    pub floorplan: Floorplan,
 }
 
 fn create_frontier_entry(
@@ -129,6 +137,7 @@ fn create_frontier_entry(
     create_report: bool,
 ) -> (FrontierEntry, Option<Report>) {
     let physical_counts = create_physical_resource_counts(&result, layout_report_data);
+    let floorplan = create_floorplan(&result, layout_report_data);
 
     let physical_counts_formatted: FormattedPhysicalResourceCounts =
         FormattedPhysicalResourceCounts::new(&result, job_params, layout_report_data);
@@ -154,6 +163,7 @@ fn create_frontier_entry(
             error_budget,
             physical_counts,
             physical_counts_formatted,
+            floorplan,
         },
         report_data,
     )
@@ -182,6 +192,9 @@ fn create_physical_resource_counts_breakdown(
 
     let part = result.factory_parts()[0].as_ref();
 
+    let physical_qubits_for_data =
+        num_data_patches(result, layout_report_data) * result.logical_patch().physical_qubits();
+
     PhysicalResourceCountsBreakdown {
         algorithmic_logical_qubits: result.layout_overhead().logical_qubits(),
         algorithmic_logical_depth: result.layout_overhead().logical_depth(),
@@ -192,6 +205,10 @@ fn create_physical_resource_counts_breakdown(
         num_tfactory_runs: part.map_or(0, FactoryPart::runs),
         physical_qubits_for_tfactories: result.physical_qubits_for_factories(),
         physical_qubits_for_algorithm: result.physical_qubits_for_algorithm(),
+        physical_qubits_for_data,
+        physical_qubits_for_routing: result
+            .physical_qubits_for_algorithm()
+            .saturating_sub(physical_qubits_for_data),
         required_logical_qubit_error_rate: result.required_logical_error_rate(),
         required_logical_tstate_error_rate: part.map(FactoryPart::required_output_error_rate),
         num_ts_per_rotation,
@@ -199,9 +216,45 @@ fn create_physical_resource_counts_breakdown(
             .logical_patch()
             .physical_qubit()
             .clifford_error_rate(),
+        feedback_runtime: Some(result.feedback_runtime()).filter(|&runtime| runtime > 0),
     }
 }
 
+/// The number of logical qubit patches that hold the data qubits of the
+/// algorithm, as opposed to routing.
+fn num_data_patches(
+    result: &PhysicalResourceEstimationResult<Protocol, TFactory>,
+    layout_report_data: &impl LayoutReportData,
+) -> u64 {
+    layout_report_data
+        .num_qubits()
+        .div_ceil(result.logical_patch().logical_qubits())
+}
+
+fn create_floorplan(
+    result: &PhysicalResourceEstimationResult<Protocol, TFactory>,
+    layout_report_data: &impl LayoutReportData,
+) -> Floorplan {
+    let logical_patch = result.logical_patch();
+    let data_patches = num_data_patches(result, layout_report_data);
+    let algorithm_patches = result
+        .layout_overhead()
+        .logical_qubits()
+        .div_ceil(logical_patch.logical_qubits());
+    let part = result.factory_parts()[0].as_ref();
+
+    Floorplan::new(
+        data_patches,
+        algorithm_patches.saturating_sub(data_patches),
+        part.map_or(0, FactoryPart::copies),
+        part.map_or(0, |part| {
+            part.factory()
+                .physical_qubits()
+                .div_ceil(logical_patch.physical_qubits())
+        }),
+    )
+}
+
 pub struct Failure {
     error: Error,
     batch_index: Option<usize>,
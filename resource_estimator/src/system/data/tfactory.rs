@@ -8,8 +8,8 @@ use super::super::{
     compiled_expression::CompiledExpression,
     error::IO::{self, CannotParseJSON},
     modeling::{
-        TFactoryDistillationUnitResources, TFactoryDistillationUnitTemplate,
-        TFactoryDistillationUnitType, TFactoryFormula,
+        TFactoryDesign, TFactoryDesignRound, TFactoryDistillationUnitResources,
+        TFactoryDistillationUnitTemplate, TFactoryDistillationUnitType, TFactoryFormula,
     },
     Error, Result,
 };
@@ -194,3 +194,153 @@ impl TFactoryProtocolSpecificDistillationUnitSpecification {
         })
     }
 }
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct TFactorySpecifications(Vec<TFactorySpecification>);
+
+impl TFactorySpecifications {
+    pub fn as_designs(&self) -> Result<Vec<TFactoryDesign>> {
+        self.iter().map(TFactoryDesign::try_from).collect()
+    }
+}
+
+impl Deref for TFactorySpecifications {
+    type Target = Vec<TFactorySpecification>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct TFactorySpecification {
+    /// The distillation rounds of the factory, from the first to the last.
+    pub(crate) rounds: Vec<TFactoryRoundSpecification>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct TFactoryRoundSpecification {
+    /// The distillation unit output name.
+    pub(crate) display_name: String,
+    /// The number of distillation units in the round.
+    pub(crate) num_units: u64,
+    /// The number of input t states accepted by one distillation unit.
+    pub(crate) num_input_ts: u64,
+    /// The number of output t states generated by one distillation unit.
+    pub(crate) num_output_ts: u64,
+    /// The code distance of the qubits in the units; physical qubits if not
+    /// specified.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) code_distance: Option<u64>,
+    /// The number of unit qubits utilized in distillation.
+    pub(crate) num_unit_qubits: u64,
+    /// The duration of distillation measured in qubit cycles.
+    pub(crate) duration_in_qubit_cycle_time: u64,
+    /// The error rate of the t states that the round outputs.
+    pub(crate) output_error_rate: f64,
+    /// The probability that a distillation unit fails.
+    #[serde(default)]
+    pub(crate) failure_probability: f64,
+}
+
+impl TryFrom<&TFactorySpecification> for TFactoryDesign {
+    type Error = Error;
+
+    fn try_from(specification: &TFactorySpecification) -> Result<Self> {
+        if specification.rounds.is_empty() {
+            return Err(CannotParseJSON(serde::de::Error::custom(
+                "A factory specification must have at least one round.",
+            ))
+            .into());
+        }
+
+        for (previous, round) in specification
+            .rounds
+            .iter()
+            .zip(specification.rounds.iter().skip(1))
+        {
+            if previous.num_units * previous.num_output_ts < round.num_units * round.num_input_ts {
+                return Err(CannotParseJSON(serde::de::Error::custom(format!(
+                    "The round {} requires more input t states than the previous round outputs.",
+                    round.display_name
+                )))
+                .into());
+            }
+        }
+
+        let rounds = specification
+            .rounds
+            .iter()
+            .map(TFactoryDesignRound::try_from)
+            .collect::<Result<_>>()?;
+
+        Ok(Self { rounds })
+    }
+}
+
+impl TryFrom<&TFactoryRoundSpecification> for TFactoryDesignRound {
+    type Error = Error;
+
+    fn try_from(specification: &TFactoryRoundSpecification) -> Result<Self> {
+        if specification.num_units == 0 {
+            return Err(CannotParseJSON(serde::de::Error::custom(
+                "The number of units must be greater than 0.",
+            ))
+            .into());
+        }
+
+        if specification.num_input_ts == 0 {
+            return Err(CannotParseJSON(serde::de::Error::custom(
+                "The number of input t states must be greater than 0.",
+            ))
+            .into());
+        }
+
+        if specification.num_output_ts == 0 {
+            return Err(CannotParseJSON(serde::de::Error::custom(
+                "The number of output t states must be greater than 0.",
+            ))
+            .into());
+        }
+
+        if specification.code_distance == Some(0) {
+            return Err(CannotParseJSON(serde::de::Error::custom(
+                "The code distance must be greater than 0.",
+            ))
+            .into());
+        }
+
+        if !(specification.output_error_rate > 0.0 && specification.output_error_rate < 1.0) {
+            return Err(CannotParseJSON(serde::de::Error::custom(
+                "The output error rate must be between 0 and 1.",
+            ))
+            .into());
+        }
+
+        if !(0.0..1.0).contains(&specification.failure_probability) {
+            return Err(CannotParseJSON(serde::de::Error::custom(
+                "The failure probability must be at least 0 and less than 1.",
+            ))
+            .into());
+        }
+
+        let resources = TFactoryProtocolSpecificDistillationUnitSpecification {
+            num_unit_qubits: specification.num_unit_qubits,
+            duration_in_qubit_cycle_time: specification.duration_in_qubit_cycle_time,
+        }
+        .as_resources()?;
+
+        Ok(Self {
+            name: specification.display_name.clone(),
+            num_units: specification.num_units,
+            num_input_ts: specification.num_input_ts,
+            num_output_ts: specification.num_output_ts,
+            code_distance: specification.code_distance.unwrap_or(1),
+            resources,
+            output_error_rate: specification.output_error_rate,
+            failure_probability: specification.failure_probability,
+        })
+    }
+}
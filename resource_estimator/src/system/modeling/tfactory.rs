@@ -5,7 +5,7 @@
 mod tests;
 
 use core::fmt;
-use std::{collections::BTreeMap, fmt::Display, vec};
+use std::{collections::BTreeMap, fmt::Display, rc::Rc, vec};
 
 use serde::{ser::SerializeMap, Serialize};
 
@@ -487,6 +487,127 @@ pub fn default_t_factory(logical_qubit: &LogicalPatch<Protocol>) -> TFactory {
     )
 }
 
+/// One round of a factory design, with a fixed number of units
+pub struct TFactoryDesignRound {
+    /// The distillation unit output name.
+    pub(crate) name: String,
+    /// The number of distillation units in the round.
+    pub(crate) num_units: u64,
+    /// The number of input t states accepted by one distillation unit.
+    pub(crate) num_input_ts: u64,
+    /// The number of output t states generated by one distillation unit.
+    pub(crate) num_output_ts: u64,
+    /// The code distance of the qubits in the units, 1 for physical qubits.
+    pub(crate) code_distance: u64,
+    /// The qubits and duration of one distillation unit.
+    pub(crate) resources: TFactoryDistillationUnitResources,
+    /// The error rate of the t states that the round outputs.
+    pub(crate) output_error_rate: f64,
+    /// The probability that a distillation unit fails.
+    pub(crate) failure_probability: f64,
+}
+
+/// A factory whose rounds are given by the user instead of being found by the
+/// exhaustive search
+pub struct TFactoryDesign {
+    pub(crate) rounds: Vec<TFactoryDesignRound>,
+}
+
+impl TFactoryDesign {
+    /// Builds the factory for a QEC scheme and a physical qubit, or returns
+    /// `None` if the scheme does not support the code distance of a round.
+    pub fn build(&self, ftp: &Protocol, qubit: &Rc<PhysicalQubit>) -> Option<TFactory> {
+        let length = self.rounds.len();
+        // the failure probability requirement is only relevant for rounds in
+        // which units can fail
+        let failure_probability_requirement = 0.01;
+
+        let mut rounds = Vec::with_capacity(length);
+        let mut input_t_error_rate_before_each_round = Vec::with_capacity(length + 1);
+        input_t_error_rate_before_each_round.push(qubit.t_gate_error_rate());
+        let mut failure_probability_after_each_round = Vec::with_capacity(length + 1);
+
+        for (position, round) in self.rounds.iter().enumerate() {
+            let logical_qubit;
+            let tfactory_qubit = if round.code_distance == 1 {
+                TFactoryQubit::Physical(qubit)
+            } else {
+                logical_qubit = LogicalPatch::new(ftp, round.code_distance, qubit.clone()).ok()?;
+                TFactoryQubit::Logical(&logical_qubit)
+            };
+
+            let unit = TFactoryDesignUnit {
+                round,
+                physical_qubits: round.resources.num_unit_qubits * tfactory_qubit.physical_qubits(),
+                duration: round.resources.duration_in_qubit_cycle_time
+                    * tfactory_qubit.cycle_time(),
+            };
+
+            let round_requirement = if round.failure_probability == 0.0 {
+                0.0
+            } else {
+                failure_probability_requirement / (length as f64)
+            };
+            let mut distillation_round = DistillationRound::new(&unit, round_requirement, position);
+            distillation_round.set_num_units(round.num_units);
+
+            rounds.push(distillation_round);
+            input_t_error_rate_before_each_round.push(round.output_error_rate);
+            failure_probability_after_each_round.push(round.failure_probability);
+        }
+        failure_probability_after_each_round.push(1.0);
+
+        Some(TFactory::new(
+            length,
+            failure_probability_requirement,
+            rounds,
+            input_t_error_rate_before_each_round,
+            failure_probability_after_each_round,
+        ))
+    }
+}
+
+/// The distillation unit of a round in a factory design, for a given qubit
+struct TFactoryDesignUnit<'a> {
+    round: &'a TFactoryDesignRound,
+    physical_qubits: u64,
+    duration: u64,
+}
+
+impl DistillationUnit<u64> for TFactoryDesignUnit<'_> {
+    fn num_output_states(&self) -> u64 {
+        self.round.num_output_ts
+    }
+
+    fn num_input_states(&self) -> u64 {
+        self.round.num_input_ts
+    }
+
+    fn duration(&self, _position: usize) -> u64 {
+        self.duration
+    }
+
+    fn physical_qubits(&self, _position: usize) -> u64 {
+        self.physical_qubits
+    }
+
+    fn name(&self) -> &str {
+        &self.round.name
+    }
+
+    fn code_parameter(&self) -> Option<&u64> {
+        Some(&self.round.code_distance)
+    }
+
+    fn output_error_rate(&self, _input_error_rate: f64) -> f64 {
+        self.round.output_error_rate
+    }
+
+    fn failure_probability(&self, _input_error_rate: f64) -> f64 {
+        self.round.failure_probability
+    }
+}
+
 impl Serialize for TFactory {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -7,7 +7,8 @@ use std::rc::Rc;
 
 use crate::system::constants::MAX_DISTILLATION_ROUNDS;
 use crate::system::modeling::{
-    PhysicalQubit, Protocol, TFactory, TFactoryDistillationUnit, TFactoryDistillationUnitTemplate,
+    PhysicalQubit, Protocol, TFactory, TFactoryDesign, TFactoryDistillationUnit,
+    TFactoryDistillationUnitTemplate,
 };
 use crate::{
     estimates::{
@@ -346,6 +347,7 @@ fn process_for_specifications_combination<P>(
 pub struct TFactoryBuilder {
     distillation_unit_templates: Vec<TFactoryDistillationUnitTemplate>,
     max_distillation_rounds: u64,
+    designs: Vec<TFactoryDesign>,
 }
 
 impl TFactoryBuilder {
@@ -357,8 +359,17 @@ impl TFactoryBuilder {
         Self {
             distillation_unit_templates,
             max_distillation_rounds,
+            designs: vec![],
         }
     }
+
+    /// Sets factory designs that are considered in addition to the factories
+    /// found by the search over the distillation unit templates
+    ///
+    /// Without distillation unit templates, only the designs are considered.
+    pub fn set_designs(&mut self, designs: Vec<TFactoryDesign>) {
+        self.designs = designs;
+    }
 }
 
 impl Default for TFactoryBuilder {
@@ -370,6 +381,7 @@ impl Default for TFactoryBuilder {
         Self {
             distillation_unit_templates,
             max_distillation_rounds,
+            designs: vec![],
         }
     }
 }
@@ -385,14 +397,38 @@ impl FactoryBuilder<Protocol> for TFactoryBuilder {
         output_t_error_rate: f64,
         max_code_distance: &u64,
     ) -> Option<Vec<Cow<Self::Factory>>> {
-        Some(find_nondominated_tfactories(
-            ftp,
-            qubit,
-            &self.distillation_unit_templates,
-            output_t_error_rate,
-            *max_code_distance,
-            self.max_distillation_rounds,
-        ))
+        let mut factories = if self.distillation_unit_templates.is_empty() {
+            vec![]
+        } else {
+            find_nondominated_tfactories(
+                ftp,
+                qubit,
+                &self.distillation_unit_templates,
+                output_t_error_rate,
+                *max_code_distance,
+                self.max_distillation_rounds,
+            )
+        };
+
+        // designs are subject to the same requirements as the factories found
+        // by the search
+        factories.extend(
+            self.designs
+                .iter()
+                .filter_map(|design| design.build(ftp, qubit))
+                .filter(|factory| {
+                    factory.output_error_rate() <= output_t_error_rate
+                        && factory.num_output_states() > 0
+                        && factory
+                            .code_parameter_per_round()
+                            .into_iter()
+                            .flatten()
+                            .all(|distance| distance <= max_code_distance)
+                })
+                .map(Cow::Owned),
+        );
+
+        Some(factories)
     }
 }
 
@@ -17,7 +17,10 @@ use super::{
 };
 
 use crate::system::{
-    data::{ErrorBudgetSpecification, JobParams, LogicalResourceCounts},
+    data::{
+        ErrorBudgetSpecification, Floorplan, FloorplanRegionKind, JobParams, LogicalResourceCounts,
+        PhaseCounts,
+    },
     error::IO,
     modeling::GateBasedPhysicalQubit,
     modeling::{PhysicalQubit, Protocol, TFactory},
@@ -37,6 +40,9 @@ fn estimate_single() {
         ccz_count: 0,
         ccix_count: 0,
         measurement_count: 0,
+        feedback_depth: 0,
+        logical_depth: None,
+        phases: vec![],
     };
 
     let params: &str = "[{}]";
@@ -63,6 +69,9 @@ fn estimate_frontier() {
         ccz_count: 0,
         ccix_count: 0,
         measurement_count: 0,
+        feedback_depth: 0,
+        logical_depth: None,
+        phases: vec![],
     };
 
     let params: &str = r#"[{
@@ -93,6 +102,9 @@ fn physical_estimates_crash() {
             ccz_count: 8,
             ccix_count: 0,
             measurement_count: 5,
+            feedback_depth: 0,
+            logical_depth: None,
+            phases: vec![],
         },
         r#"[{"qubitParams": {"name": "qubit_maj_ns_e6"},
             "qecScheme": {"name": "floquet_code"},
@@ -1006,6 +1018,9 @@ fn test_report() {
         ccz_count: 0,
         ccix_count: 0,
         measurement_count: 0,
+        feedback_depth: 0,
+        logical_depth: None,
+        phases: vec![],
     };
 
     let params: &str = "[{}]";
@@ -1023,6 +1038,182 @@ fn test_report() {
     );
 }
 
+#[test]
+fn feedback_adds_to_runtime() {
+    let estimate = |feedback_depth: u64| {
+        let logical_resources = LogicalResourceCounts {
+            num_qubits: 10,
+            t_count: 100,
+            rotation_count: 0,
+            rotation_depth: 0,
+            ccz_count: 0,
+            ccix_count: 0,
+            measurement_count: 20,
+            feedback_depth,
+            logical_depth: None,
+            phases: vec![],
+        };
+        let result =
+            estimate_physical_resources(logical_resources, r#"[{"feedbackLatency": "2 us"}]"#)
+                .expect("estimation should succeed");
+        let json_value: Vec<Value> = serde_json::from_str(&result).expect("Failed to parse JSON");
+        json_value[0].clone()
+    };
+
+    let branch_free = estimate(0);
+    let branch_heavy = estimate(20);
+
+    let runtime = |value: &Value| value["physicalCounts"]["runtime"].as_u64();
+    let cycle_time = branch_heavy["logicalQubit"]["logicalCycleTime"]
+        .as_u64()
+        .expect("logical cycle time should be a number");
+    let feedback_runtime = 20 * (cycle_time + 2_000);
+    assert_eq!(
+        branch_heavy["physicalCounts"]["breakdown"]["feedbackRuntime"].as_u64(),
+        Some(feedback_runtime)
+    );
+    assert!(branch_free["physicalCounts"]["breakdown"]
+        .get("feedbackRuntime")
+        .is_none());
+    assert_eq!(
+        runtime(&branch_heavy),
+        runtime(&branch_free).map(|runtime| runtime + feedback_runtime)
+    );
+}
+
+#[test]
+fn given_logical_depth_replaces_computed_depth() {
+    let logical_resources = LogicalResourceCounts {
+        num_qubits: 10,
+        t_count: 100,
+        measurement_count: 20,
+        logical_depth: Some(5_000),
+        ..LogicalResourceCounts::default()
+    };
+    let result =
+        estimate_physical_resources(logical_resources, "[{}]").expect("estimation should succeed");
+    let json_value: Vec<Value> = serde_json::from_str(&result).expect("Failed to parse JSON");
+    assert_eq!(
+        json_value[0]["physicalCounts"]["breakdown"]["algorithmicLogicalDepth"].as_u64(),
+        Some(5_000)
+    );
+    assert_eq!(
+        json_value[0]["logicalCounts"]["logicalDepth"].as_u64(),
+        Some(5_000)
+    );
+}
+
+#[test]
+fn floorplan_places_all_patches() {
+    let floorplan = Floorplan::new(7, 12, 5, 10);
+    assert_eq!(floorplan.patches(FloorplanRegionKind::Data), 7);
+    assert_eq!(floorplan.patches(FloorplanRegionKind::Routing), 12);
+    assert_eq!(floorplan.patches(FloorplanRegionKind::TFactory), 50);
+    // 3 columns of data and routing rows, 3 more routing patches in a column
+    // of height 6, and factories of side 4 stacked in columns of 1
+    assert_eq!(floorplan.width(), 3 + 1 + 5 * 4);
+    assert_eq!(floorplan.height(), 6);
+}
+
+#[test]
+fn floorplan_without_factories() {
+    let floorplan = Floorplan::new(4, 6, 0, 0);
+    assert_eq!(floorplan.patches(FloorplanRegionKind::Data), 4);
+    assert_eq!(floorplan.patches(FloorplanRegionKind::Routing), 6);
+    assert_eq!(floorplan.patches(FloorplanRegionKind::TFactory), 0);
+    assert_eq!(floorplan.width(), 3);
+    assert_eq!(floorplan.height(), 4);
+}
+
+#[test]
+fn factory_design_replaces_search() {
+    let logical_resources = LogicalResourceCounts {
+        num_qubits: 100,
+        t_count: 10_000,
+        rotation_count: 0,
+        rotation_depth: 0,
+        ccz_count: 0,
+        ccix_count: 0,
+        measurement_count: 1_000,
+        feedback_depth: 0,
+        logical_depth: None,
+        phases: vec![],
+    };
+    let params = r#"[{"factorySpecifications": [{"rounds": [
+        {"displayName": "physical 15-to-1", "numUnits": 15, "numInputTs": 15, "numOutputTs": 1,
+         "numUnitQubits": 31, "durationInQubitCycleTime": 24, "outputErrorRate": 3.5e-8},
+        {"displayName": "logical 15-to-1", "numUnits": 1, "numInputTs": 15, "numOutputTs": 1,
+         "codeDistance": 7, "numUnitQubits": 31, "durationInQubitCycleTime": 11,
+         "outputErrorRate": 1e-14}
+    ]}]}]"#;
+
+    let result = estimate_physical_resources(logical_resources, params).expect("result is err");
+    let json_value: Vec<Value> = serde_json::from_str(&result).expect("Failed to parse JSON");
+    let tfactory = &json_value[0]["tfactory"];
+    assert_eq!(
+        tfactory["unitNamePerRound"],
+        json!(["physical 15-to-1", "logical 15-to-1"])
+    );
+    assert_eq!(tfactory["numUnitsPerRound"], json!([15, 1]));
+    assert_eq!(tfactory["codeDistancePerRound"], json!([1, 7]));
+    assert_eq!(tfactory["numTstates"], json!(1));
+}
+
+#[test]
+fn factory_design_needs_enough_input_states() {
+    let logical_resources = LogicalResourceCounts {
+        num_qubits: 100,
+        t_count: 10_000,
+        ..LogicalResourceCounts::default()
+    };
+    let params = r#"[{"factorySpecifications": [{"rounds": [
+        {"displayName": "first", "numUnits": 15, "numInputTs": 15, "numOutputTs": 1,
+         "numUnitQubits": 31, "durationInQubitCycleTime": 24, "outputErrorRate": 3.5e-8},
+        {"displayName": "second", "numUnits": 2, "numInputTs": 15, "numOutputTs": 1,
+         "codeDistance": 7, "numUnitQubits": 31, "durationInQubitCycleTime": 11,
+         "outputErrorRate": 1e-14}
+    ]}]}]"#;
+
+    let result = estimate_physical_resources(logical_resources, params).expect("result is err");
+    let json_value: Vec<Value> = serde_json::from_str(&result).expect("Failed to parse JSON");
+    assert_eq!(
+        json_value[0]["code"],
+        json!("Qsc.Estimates.IOError.CannotParseJSON")
+    );
+    assert!(json_value[0]["message"]
+        .as_str()
+        .is_some_and(|message| message.contains("second requires more input t states")));
+}
+
+#[test]
+fn phases_share_factories() {
+    let estimate = |factory_sharing: bool| {
+        let phase = PhaseCounts {
+            t_count: 50_000,
+            measurement_count: 5_000,
+            ..PhaseCounts::default()
+        };
+        let logical_resources = LogicalResourceCounts {
+            num_qubits: 100,
+            t_count: 100_000,
+            measurement_count: 10_000,
+            phases: vec![phase.clone(), phase],
+            ..LogicalResourceCounts::default()
+        };
+        let result = estimate_physical_resources(
+            logical_resources,
+            &format!(r#"[{{"factorySharing": {factory_sharing}}}]"#),
+        )
+        .expect("estimation should succeed");
+        let json_value: Vec<Value> = serde_json::from_str(&result).expect("Failed to parse JSON");
+        json_value[0]["physicalCounts"]["breakdown"]["numTfactories"]
+            .as_u64()
+            .expect("number of factories should be a number")
+    };
+
+    assert!(estimate(false) > estimate(true));
+}
+
 fn create_factory_builder() -> TFactoryBuilder {
     TFactoryBuilder::new(
         TFactoryDistillationUnitTemplate::default_distillation_unit_templates(),
@@ -0,0 +1,161 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use indoc::indoc;
+use qsc::{
+    interpret::Interpreter, target::Profile, LanguageFeatures, PackageType, SourceMap,
+    TargetCapabilityFlags,
+};
+
+use super::{
+    counts::PathCounts, estimate_expr, estimate_paths_expr, path_counts_expr, LogicalCountsCache,
+};
+
+fn interpreter() -> Interpreter {
+    let source = indoc! {"
+        namespace Test {
+            operation ApplyTs(n : Int) : Unit {
+                use qs = Qubit[n];
+                for q in qs {
+                    T(q);
+                }
+            }
+
+            operation Branch() : Unit {
+                use q = Qubit();
+                X(q);
+                if MResetZ(q) == One {
+                    T(q);
+                    T(q);
+                }
+            }
+
+            operation CoinFlip() : Unit {
+                use q = Qubit();
+                H(q);
+                if MResetZ(q) == One {
+                    T(q);
+                }
+            }
+        }
+    "};
+    let source_map = SourceMap::new([("test".into(), source.into())], None);
+    let (std_id, store) = qsc::compile::package_store_with_stdlib(TargetCapabilityFlags::all());
+    Interpreter::new(
+        source_map,
+        PackageType::Lib,
+        Profile::Unrestricted.into(),
+        LanguageFeatures::default(),
+        store,
+        &[(std_id, None)],
+    )
+    .expect("compilation should succeed")
+}
+
+#[test]
+fn cached_estimates_match_uncached_estimates() {
+    let params = r#"[{"errorBudget": 0.01}, {"errorBudget": 0.0001}]"#;
+    let mut interpreter = interpreter();
+    let mut cache = LogicalCountsCache::default();
+
+    let uncached = estimate_expr(&mut interpreter, "Test.ApplyTs(5)", params)
+        .expect("estimate should succeed");
+    let first = cache
+        .estimate_expr(&mut interpreter, "Test.ApplyTs(5)", params)
+        .expect("estimate should succeed");
+    let second = cache
+        .estimate_expr(&mut interpreter, "Test.ApplyTs(5)", params)
+        .expect("estimate should succeed");
+
+    assert_eq!(first, uncached);
+    assert_eq!(second, uncached);
+    assert_eq!(cache.len(), 1);
+}
+
+#[test]
+fn programs_are_cached_separately() {
+    let mut interpreter = interpreter();
+    let mut cache = LogicalCountsCache::default();
+
+    let small = cache
+        .logical_counts_expr(&mut interpreter, "Test.ApplyTs(2)")
+        .expect("counts should be computed");
+    let large = cache
+        .logical_counts_expr(&mut interpreter, "Test.ApplyTs(4)")
+        .expect("counts should be computed");
+
+    assert_eq!(small.t_count, 2);
+    assert_eq!(large.t_count, 4);
+    assert_eq!(cache.len(), 2);
+
+    cache.clear();
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn simulated_paths_follow_measurement_results() {
+    let mut interpreter = interpreter();
+
+    let path_counts = path_counts_expr(&mut interpreter, "Test.Branch()", 3, 0)
+        .expect("counts should be computed");
+
+    assert_eq!(path_counts.paths.len(), 3);
+    for counts in &path_counts.paths {
+        assert_eq!(counts.t_count, 2);
+        assert_eq!(counts.measurement_count, 1);
+    }
+    assert_eq!(path_counts.expected().t_count, 2);
+}
+
+#[test]
+fn expected_counts_are_the_rounded_up_mean_of_the_paths() {
+    let mut interpreter = interpreter();
+
+    let path_counts = path_counts_expr(&mut interpreter, "Test.CoinFlip()", 20, 0)
+        .expect("counts should be computed");
+    let taken = path_counts
+        .paths
+        .iter()
+        .filter(|counts| counts.t_count == 1)
+        .count();
+
+    assert!(taken > 0 && taken < 20, "both branches should be taken");
+    assert_eq!(path_counts.expected().t_count, 1);
+    assert_eq!(path_counts.expected().measurement_count, 1);
+}
+
+#[test]
+fn simulated_paths_are_reproducible_with_a_seed() {
+    let mut interpreter = interpreter();
+
+    let first = path_counts_expr(&mut interpreter, "Test.CoinFlip()", 10, 42)
+        .expect("counts should be computed");
+    let second = path_counts_expr(&mut interpreter, "Test.CoinFlip()", 10, 42)
+        .expect("counts should be computed");
+
+    let t_counts = |path_counts: &PathCounts| {
+        path_counts
+            .paths
+            .iter()
+            .map(|counts| counts.t_count)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(t_counts(&first), t_counts(&second));
+}
+
+#[test]
+fn path_estimates_include_counts_of_each_path() {
+    let mut interpreter = interpreter();
+
+    let estimates = estimate_paths_expr(&mut interpreter, "Test.Branch()", "", 2, 0)
+        .expect("estimate should succeed");
+    let estimates: serde_json::Value =
+        serde_json::from_str(&estimates).expect("estimates should be valid JSON");
+
+    assert_eq!(estimates[0]["logicalCounts"]["tCount"], 2);
+    let paths = estimates[0]["pathLogicalCounts"]
+        .as_array()
+        .expect("path counts should be an array");
+    assert_eq!(paths.len(), 2);
+    assert_eq!(paths[0]["tCount"], 2);
+}
@@ -82,6 +82,12 @@ update_file(
     r'"version": "{}",'.format(npm_version),
 )
 
+update_file(
+    os.path.join(root_dir, "library/src/lib.rs"),
+    r'pub const LIBRARY_VERSION: &str = "0.0.0";',
+    r'pub const LIBRARY_VERSION: &str = "{}";'.format(npm_version),
+)
+
 update_file(
     os.path.join(root_dir, "vscode/package.json"),
     r'"version": "0.0.0",',
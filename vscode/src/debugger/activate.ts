@@ -3,10 +3,16 @@
 
 /* eslint-disable @typescript-eslint/no-unused-vars */
 
-import { IDebugServiceWorker, getDebugServiceWorker, log } from "qsharp-lang";
+import {
+  IDebugServiceWorker,
+  IEntryPoint,
+  getDebugServiceWorker,
+  log,
+} from "qsharp-lang";
 import * as vscode from "vscode";
 import { qsharpExtensionId } from "../common";
 import { clearCommandDiagnostics } from "../diagnostics";
+import { promptForEntryExpression } from "../entryPoint";
 import {
   getActiveQSharpDocumentUri,
   getProgramForDocument,
@@ -45,17 +51,23 @@ function registerCommands(context: vscode.ExtensionContext) {
   context.subscriptions.push(
     vscode.commands.registerCommand(
       `${qsharpExtensionId}.runEditorContents`,
-      (resource: vscode.Uri) =>
+      (resource: vscode.Uri, entryPoint?: IEntryPoint) =>
         startDebugging(
           resource,
           { name: "Run Q# File", stopOnEntry: false },
           { noDebug: true },
+          entryPoint,
         ),
     ),
     vscode.commands.registerCommand(
       `${qsharpExtensionId}.debugEditorContents`,
-      (resource: vscode.Uri) =>
-        startDebugging(resource, { name: "Debug Q# File", stopOnEntry: true }),
+      (resource: vscode.Uri, entryPoint?: IEntryPoint) =>
+        startDebugging(
+          resource,
+          { name: "Debug Q# File", stopOnEntry: true },
+          undefined,
+          entryPoint,
+        ),
     ),
     vscode.commands.registerCommand(
       `${qsharpExtensionId}.runEditorContentsWithCircuit`,
@@ -72,10 +84,11 @@ function registerCommands(context: vscode.ExtensionContext) {
     ),
   );
 
-  function startDebugging(
+  async function startDebugging(
     resource: vscode.Uri,
     config: { name: string; [key: string]: any },
     options?: vscode.DebugSessionOptions,
+    entryPoint?: IEntryPoint,
   ) {
     clearCommandDiagnostics();
 
@@ -84,6 +97,14 @@ function registerCommands(context: vscode.ExtensionContext) {
       return;
     }
 
+    const entry = await promptForEntryExpression(entryPoint);
+    if (entry === undefined) {
+      return;
+    }
+    if (entry) {
+      config.entry = entry;
+    }
+
     const targetResource = resource || getActiveQSharpDocumentUri();
 
     if (targetResource) {
@@ -0,0 +1,43 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+import { IEntryPoint } from "qsharp-lang";
+import { window } from "vscode";
+
+/**
+ * Prompts for a value for each parameter of the entry point, and builds an
+ * entry expression that calls the entry point with those values.
+ *
+ * @returns The entry expression, the empty string if no arguments are needed,
+ * or `undefined` if the user cancelled.
+ */
+export async function promptForEntryExpression(
+  entryPoint?: IEntryPoint,
+): Promise<string | undefined> {
+  if (!entryPoint || entryPoint.params.length === 0) {
+    return "";
+  }
+
+  const args: string[] = [];
+  for (const param of entryPoint.params) {
+    const arg = await window.showInputBox({
+      title: `Arguments for ${entryPoint.callable}`,
+      prompt: `${param.name} : ${param.ty}`,
+      value: param.defaultValue,
+      ignoreFocusOut: true,
+      validateInput: (input: string) => {
+        if (input.trim() === "") {
+          return `Enter a Q# expression of type ${param.ty}`;
+        }
+      },
+    });
+
+    // abort if the user hits <Esc> during argument entry
+    if (arg === undefined) {
+      return undefined;
+    }
+    args.push(arg.trim());
+  }
+
+  return `${entryPoint.callable}(${args.join(", ")})`;
+}
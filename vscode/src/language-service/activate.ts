@@ -26,6 +26,7 @@ import {
   QsharpDocumentType,
   sendTelemetryEvent,
 } from "../telemetry.js";
+import { startRequiredCapabilitiesStatusBarItem } from "./capabilities.js";
 import { createCodeActionsProvider } from "./codeActions.js";
 import { createCodeLensProvider } from "./codeLens.js";
 import { createCompletionItemProvider } from "./completion.js";
@@ -33,9 +34,15 @@ import { createDefinitionProvider } from "./definition.js";
 import { startLanguageServiceDiagnostics } from "./diagnostics.js";
 import { createFormattingProvider } from "./format.js";
 import { createHoverProvider } from "./hover.js";
+import { startInactiveRegionDecorations } from "./inactiveRegions.js";
 import { registerQSharpNotebookCellUpdateHandlers } from "./notebook.js";
+import { createOperationStatsCodeLensProvider } from "./operationStats.js";
 import { createReferenceProvider } from "./references.js";
 import { createRenameProvider } from "./rename.js";
+import {
+  createSemanticTokensProvider,
+  semanticTokensLegend,
+} from "./semanticTokens.js";
 import { createSignatureHelpProvider } from "./signature.js";
 import { startTestDiscovery } from "./testExplorer.js";
 
@@ -56,6 +63,14 @@ export async function activateLanguageService(
   // test explorer
   subscriptions.push(...startTestDiscovery(languageService, context));
 
+  // required capabilities of the current selection
+  subscriptions.push(
+    ...startRequiredCapabilitiesStatusBarItem(languageService),
+  );
+
+  // dim items excluded by @Config
+  subscriptions.push(...startInactiveRegionDecorations(languageService));
+
   // synchronize document contents
   subscriptions.push(...registerDocumentUpdateHandlers(languageService));
 
@@ -128,6 +143,15 @@ export async function activateLanguageService(
     ),
   );
 
+  // semantic highlighting
+  subscriptions.push(
+    vscode.languages.registerDocumentSemanticTokensProvider(
+      qsharpLanguageId,
+      createSemanticTokensProvider(languageService),
+      semanticTokensLegend,
+    ),
+  );
+
   // rename symbol
   subscriptions.push(
     vscode.languages.registerRenameProvider(
@@ -144,6 +168,14 @@ export async function activateLanguageService(
     ),
   );
 
+  // operation size and capability badges
+  subscriptions.push(
+    vscode.languages.registerCodeLensProvider(
+      qsharpLanguageId,
+      createOperationStatsCodeLensProvider(languageService),
+    ),
+  );
+
   subscriptions.push(
     vscode.languages.registerCodeActionsProvider(
       qsharpLanguageId,
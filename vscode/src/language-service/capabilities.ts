@@ -0,0 +1,85 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+import { ILanguageService, log } from "qsharp-lang";
+import * as vscode from "vscode";
+import { isQsharpDocument } from "../common.js";
+import { getTargetFriendlyName } from "../config.js";
+
+/**
+ * Shows the runtime capabilities required by the current selection, or by the
+ * callable containing the cursor, in a status bar item.
+ */
+export function startRequiredCapabilitiesStatusBarItem(
+  languageService: ILanguageService,
+): vscode.Disposable[] {
+  const disposables = [];
+
+  const statusBarItem = vscode.window.createStatusBarItem(
+    vscode.StatusBarAlignment.Right,
+    199,
+  );
+  disposables.push(statusBarItem);
+
+  // Capabilities analysis runs over the whole program, so wait for the
+  // cursor to settle before requesting it.
+  let timeout: ReturnType<typeof setTimeout> | undefined;
+  function scheduleRefresh(editor: vscode.TextEditor | undefined) {
+    clearTimeout(timeout);
+    if (!editor || !isQsharpDocument(editor.document)) {
+      statusBarItem.hide();
+      return;
+    }
+    timeout = setTimeout(() => refresh(editor), 500);
+  }
+
+  async function refresh(editor: vscode.TextEditor) {
+    const selection = editor.selection;
+    try {
+      const required = await languageService.getRequiredCapabilities(
+        editor.document.uri.toString(),
+        {
+          start: {
+            line: selection.start.line,
+            character: selection.start.character,
+          },
+          end: { line: selection.end.line, character: selection.end.character },
+        },
+      );
+      if (!required) {
+        statusBarItem.hide();
+        return;
+      }
+      statusBarItem.text = `$(circuit-board) ${getTargetFriendlyName(required.minimalProfile)}`;
+      const features = required.features
+        .map(
+          (feature) =>
+            `- line ${feature.range.start.line + 1}: ${feature.description}`,
+        )
+        .join("\n");
+      statusBarItem.tooltip = new vscode.MarkdownString(
+        `## Q# required capabilities\n\n${required.summary}\n\n${features}`,
+      );
+      statusBarItem.show();
+    } catch (e) {
+      log.error("Error getting required capabilities", e);
+      statusBarItem.hide();
+    }
+  }
+
+  disposables.push(
+    vscode.window.onDidChangeTextEditorSelection((event) =>
+      scheduleRefresh(event.textEditor),
+    ),
+  );
+  disposables.push(
+    vscode.window.onDidChangeActiveTextEditor((editor) =>
+      scheduleRefresh(editor),
+    ),
+  );
+  disposables.push({ dispose: () => clearTimeout(timeout) });
+
+  scheduleRefresh(vscode.window.activeTextEditor);
+
+  return disposables;
+}
@@ -21,22 +21,53 @@ class QSharpCodeActionProvider implements vscode.CodeActionProvider {
     );
 
     // Convert language-service type to vscode type
-    return iCodeActions.map(toCodeAction);
+    return Promise.all(
+      iCodeActions.map((iCodeAction) => toCodeAction(iCodeAction, document)),
+    );
   }
 }
 
-function toCodeAction(iCodeAction: ICodeAction): vscode.CodeAction {
+async function toCodeAction(
+  iCodeAction: ICodeAction,
+  document: vscode.TextDocument,
+): Promise<vscode.CodeAction> {
   const codeAction = new vscode.CodeAction(
     iCodeAction.title,
     toCodeActionKind(iCodeAction.kind),
   );
   if (iCodeAction.edit) {
-    codeAction.edit = toVsCodeWorkspaceEdit(iCodeAction.edit);
+    // Some code actions, such as generating a test in a tests file, edit a file
+    // that doesn't exist yet, so the file is created before it is edited.
+    const edit = new vscode.WorkspaceEdit();
+    for (const [source] of iCodeAction.edit.changes) {
+      const uri = vscode.Uri.parse(source, true);
+      if (
+        uri.toString() !== document.uri.toString() &&
+        !(await fileExists(uri))
+      ) {
+        edit.createFile(uri, { ignoreIfExists: true });
+      }
+    }
+    for (const [uri, edits] of toVsCodeWorkspaceEdit(
+      iCodeAction.edit,
+    ).entries()) {
+      edit.set(uri, edits);
+    }
+    codeAction.edit = edit;
   }
   codeAction.isPreferred = iCodeAction.isPreferred;
   return codeAction;
 }
 
+async function fileExists(uri: vscode.Uri): Promise<boolean> {
+  try {
+    await vscode.workspace.fs.stat(uri);
+    return true;
+  } catch {
+    return false;
+  }
+}
+
 function toCodeActionKind(
   codeActionKind?: string,
 ): vscode.CodeActionKind | undefined {
@@ -3,6 +3,7 @@
 
 import {
   ICodeLens,
+  IEntryPoint,
   ILanguageService,
   qsharpLibraryUriScheme,
 } from "qsharp-lang";
@@ -31,15 +32,31 @@ class QSharpCodeLensProvider implements vscode.CodeLensProvider {
       document.uri.toString(),
     );
 
-    return codeLenses.map((cl) => mapCodeLens(cl));
+    // Entry points that take parameters are run with the argument values
+    // the user is prompted for, so pass their signature along to the command.
+    let entryPoint = undefined;
+    if (codeLenses.some((cl) => cl.command !== "circuit")) {
+      entryPoint = await this.languageService.getEntryPoint(
+        document.uri.toString(),
+      );
+    }
+
+    return codeLenses.map((cl) => mapCodeLens(cl, document.uri, entryPoint));
   }
 }
 
-function mapCodeLens(cl: ICodeLens): vscode.CodeLens {
+function mapCodeLens(
+  cl: ICodeLens,
+  uri: vscode.Uri,
+  entryPoint?: IEntryPoint,
+): vscode.CodeLens {
   let command;
   let title;
   let tooltip;
   let args = undefined;
+  if (cl.command !== "circuit" && entryPoint && entryPoint.params.length > 0) {
+    args = [uri, entryPoint];
+  }
   switch (cl.command) {
     case "histogram":
       title = "Histogram";
@@ -0,0 +1,73 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+import { ILanguageService, log } from "qsharp-lang";
+import * as vscode from "vscode";
+import { isQsharpDocument } from "../common.js";
+
+/**
+ * Dims the items that are excluded from the compilation by their `@Config`
+ * attributes under the current target profile.
+ */
+export function startInactiveRegionDecorations(
+  languageService: ILanguageService,
+): vscode.Disposable[] {
+  const disposables = [];
+
+  const decorationType = vscode.window.createTextEditorDecorationType({
+    opacity: "0.5",
+  });
+  disposables.push(decorationType);
+
+  async function refresh(editor: vscode.TextEditor) {
+    if (!isQsharpDocument(editor.document)) {
+      return;
+    }
+    try {
+      const regions = await languageService.getInactiveRegions(
+        editor.document.uri.toString(),
+      );
+      editor.setDecorations(
+        decorationType,
+        regions.map(
+          (region) =>
+            new vscode.Range(
+              region.start.line,
+              region.start.character,
+              region.end.line,
+              region.end.character,
+            ),
+        ),
+      );
+    } catch (e) {
+      log.error("Error getting inactive regions", e);
+    }
+  }
+
+  // The excluded items change whenever the document is recompiled, which
+  // includes target profile changes, and every compilation publishes
+  // diagnostics for its documents.
+  async function onDiagnostics(evt: { detail: { uri: string } }) {
+    for (const editor of vscode.window.visibleTextEditors) {
+      if (editor.document.uri.toString() === evt.detail.uri) {
+        await refresh(editor);
+      }
+    }
+  }
+
+  languageService.addEventListener("diagnostics", onDiagnostics);
+  disposables.push({
+    dispose: () => {
+      languageService.removeEventListener("diagnostics", onDiagnostics);
+    },
+  });
+  disposables.push(
+    vscode.window.onDidChangeActiveTextEditor((editor) => {
+      if (editor) {
+        refresh(editor);
+      }
+    }),
+  );
+
+  return disposables;
+}
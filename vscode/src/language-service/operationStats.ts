@@ -0,0 +1,51 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+import {
+  ILanguageService,
+  IOperationStats,
+  qsharpLibraryUriScheme,
+} from "qsharp-lang";
+import * as vscode from "vscode";
+import { toVsCodeRange } from "../common";
+
+/**
+ * Shows the minimal target profile and estimated instruction count of each
+ * operation as a code lens above its declaration.
+ */
+export function createOperationStatsCodeLensProvider(
+  languageService: ILanguageService,
+) {
+  return new QSharpOperationStatsCodeLensProvider(languageService);
+}
+
+class QSharpOperationStatsCodeLensProvider implements vscode.CodeLensProvider {
+  constructor(public languageService: ILanguageService) {}
+
+  async provideCodeLenses(
+    document: vscode.TextDocument,
+  ): Promise<vscode.CodeLens[]> {
+    if (document.uri.scheme === qsharpLibraryUriScheme) {
+      return [];
+    }
+
+    const stats = await this.languageService.getOperationStats(
+      document.uri.toString(),
+    );
+
+    return stats.map((s) => mapOperationStats(s));
+  }
+}
+
+function mapOperationStats(stats: IOperationStats): vscode.CodeLens {
+  const capabilities =
+    stats.capabilities.length > 0
+      ? `Requires ${stats.capabilities.join(", ")}`
+      : "No runtime capabilities required";
+  // An empty command makes the code lens a label that can't be clicked.
+  return new vscode.CodeLens(toVsCodeRange(stats.range), {
+    title: stats.label,
+    command: "",
+    tooltip: capabilities,
+  });
+}
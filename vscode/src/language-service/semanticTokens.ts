@@ -0,0 +1,55 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+import { ILanguageService } from "qsharp-lang";
+import * as vscode from "vscode";
+import { toVsCodeRange } from "../common";
+
+// Doc comments are reported as comments with the `documentation` modifier,
+// which themes already style.
+export const semanticTokensLegend = new vscode.SemanticTokensLegend(
+  ["comment", "keyword", "typeParameter", "number", "string", "operator"],
+  ["documentation"],
+);
+
+export function createSemanticTokensProvider(
+  languageService: ILanguageService,
+) {
+  return new QSharpSemanticTokensProvider(languageService);
+}
+
+class QSharpSemanticTokensProvider
+  implements vscode.DocumentSemanticTokensProvider
+{
+  constructor(public languageService: ILanguageService) {}
+
+  async provideDocumentSemanticTokens(document: vscode.TextDocument) {
+    const tokens = await this.languageService.getSemanticTokens(
+      document.uri.toString(),
+    );
+    const builder = new vscode.SemanticTokensBuilder(semanticTokensLegend);
+    for (const token of tokens) {
+      const [tokenType, tokenModifiers] =
+        token.kind === "docComment"
+          ? ["comment", ["documentation"]]
+          : [token.kind, []];
+      const range = toVsCodeRange(token.range);
+      // The builder only accepts single-line ranges, and unterminated or
+      // multi-line strings can span several lines.
+      for (let line = range.start.line; line <= range.end.line; line++) {
+        const start =
+          line === range.start.line ? range.start : new vscode.Position(line, 0);
+        const end =
+          line === range.end.line ? range.end : document.lineAt(line).range.end;
+        if (start.isBefore(end)) {
+          builder.push(
+            new vscode.Range(start, end),
+            tokenType,
+            tokenModifiers,
+          );
+        }
+      }
+    }
+    return builder.build();
+  }
+}
@@ -172,4 +172,60 @@ export function initCodegen(context: vscode.ExtensionContext) {
       }
     }),
   );
+
+  context.subscriptions.push(
+    vscode.commands.registerCommand(
+      `${qsharpExtensionId}.checkForTarget`,
+      checkActiveProgramForTarget,
+    ),
+  );
+}
+
+/**
+ * Checks the whole active program against the target profile, including the
+ * checks that run during code generation, and reports all of the diagnostics
+ * in the problems view without generating QIR.
+ */
+async function checkActiveProgramForTarget() {
+  const program = await getActiveProgram();
+  if (!program.success) {
+    vscode.window.showErrorMessage(program.errorMsg);
+    return;
+  }
+  const config = program.programConfig;
+  const targetName = `the ${config.profile} profile`;
+
+  // Use a temporary worker, as with QIR generation, since partial evaluation
+  // may loop or panic.
+  const worker = getCompilerWorker(compilerWorkerScriptPath);
+  try {
+    await vscode.window.withProgress(
+      {
+        location: vscode.ProgressLocation.Notification,
+        cancellable: true,
+        title: `Q#: Checking program for ${targetName}`,
+      },
+      async (progress, token) => {
+        token.onCancellationRequested(() => {
+          worker.terminate();
+        });
+        await invokeAndReportCommandDiagnostics(() =>
+          worker.checkForTarget(config),
+        );
+        progress.report({ increment: 100 });
+      },
+    );
+    vscode.window.showInformationMessage(
+      `No problems found for ${targetName}.`,
+    );
+  } catch (e: any) {
+    log.error("Target check failed. ", e);
+    vscode.window.showErrorMessage(
+      e.toString() === "terminated"
+        ? "The target check was cancelled."
+        : `Problems were found for ${targetName}. See the problems view for details.`,
+    );
+  } finally {
+    worker.terminate();
+  }
 }
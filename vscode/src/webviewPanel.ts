@@ -2,6 +2,7 @@
 // Licensed under the MIT License.
 
 import {
+  IEntryPoint,
   IOperationInfo,
   QscEventTarget,
   VSDiagnostic,
@@ -21,6 +22,7 @@ import {
 import { showCircuitCommand } from "./circuit";
 import { clearCommandDiagnostics } from "./diagnostics";
 import { showDocumentationCommand } from "./documentation";
+import { promptForEntryExpression } from "./entryPoint";
 import { getActiveProgram } from "./programConfig";
 import { EventType, sendTelemetryEvent } from "./telemetry";
 import { getRandomGuid } from "./utils";
@@ -44,229 +46,238 @@ export function registerWebViewCommands(context: ExtensionContext) {
   ).toString();
 
   context.subscriptions.push(
-    commands.registerCommand(`${qsharpExtensionId}.showRe`, async () => {
-      clearCommandDiagnostics();
-      const associationId = getRandomGuid();
-      sendTelemetryEvent(
-        EventType.TriggerResourceEstimation,
-        { associationId },
-        {},
-      );
-      const program = await getActiveProgram();
-      if (!program.success) {
-        throw new Error(program.errorMsg);
-      }
+    commands.registerCommand(
+      `${qsharpExtensionId}.showRe`,
+      async (_resource?: Uri, entryPoint?: IEntryPoint) => {
+        clearCommandDiagnostics();
+        const associationId = getRandomGuid();
+        sendTelemetryEvent(
+          EventType.TriggerResourceEstimation,
+          { associationId },
+          {},
+        );
+        const program = await getActiveProgram();
+        if (!program.success) {
+          throw new Error(program.errorMsg);
+        }
 
-      const qubitType = await window.showQuickPick(
-        [
-          {
-            label: "qubit_gate_ns_e3",
-            detail: "Superconducting/spin qubit with 1e-3 error rate",
-            picked: true,
-            params: {
-              qubitParams: { name: "qubit_gate_ns_e3" },
-              qecScheme: { name: "surface_code" },
+        const expr = await promptForEntryExpression(entryPoint);
+        if (expr === undefined) {
+          return;
+        }
+
+        const qubitType = await window.showQuickPick(
+          [
+            {
+              label: "qubit_gate_ns_e3",
+              detail: "Superconducting/spin qubit with 1e-3 error rate",
+              picked: true,
+              params: {
+                qubitParams: { name: "qubit_gate_ns_e3" },
+                qecScheme: { name: "surface_code" },
+              },
             },
-          },
-          {
-            label: "qubit_gate_ns_e4",
-            detail: "Superconducting/spin qubit with 1e-4 error rate",
-            params: {
-              qubitParams: { name: "qubit_gate_ns_e4" },
-              qecScheme: { name: "surface_code" },
+            {
+              label: "qubit_gate_ns_e4",
+              detail: "Superconducting/spin qubit with 1e-4 error rate",
+              params: {
+                qubitParams: { name: "qubit_gate_ns_e4" },
+                qecScheme: { name: "surface_code" },
+              },
             },
-          },
-          {
-            label: "qubit_gate_us_e3",
-            detail: "Trapped ion qubit with 1e-3 error rate",
-            params: {
-              qubitParams: { name: "qubit_gate_us_e3" },
-              qecScheme: { name: "surface_code" },
+            {
+              label: "qubit_gate_us_e3",
+              detail: "Trapped ion qubit with 1e-3 error rate",
+              params: {
+                qubitParams: { name: "qubit_gate_us_e3" },
+                qecScheme: { name: "surface_code" },
+              },
             },
-          },
-          {
-            label: "qubit_gate_us_e4",
-            detail: "Trapped ion qubit with 1e-4 error rate",
-            params: {
-              qubitParams: { name: "qubit_gate_us_e4" },
-              qecScheme: { name: "surface_code" },
+            {
+              label: "qubit_gate_us_e4",
+              detail: "Trapped ion qubit with 1e-4 error rate",
+              params: {
+                qubitParams: { name: "qubit_gate_us_e4" },
+                qecScheme: { name: "surface_code" },
+              },
             },
-          },
-          {
-            label: "qubit_maj_ns_e4 + surface_code",
-            detail: "Majorana qubit with 1e-4 error rate (surface code QEC)",
-            params: {
-              qubitParams: { name: "qubit_maj_ns_e4" },
-              qecScheme: { name: "surface_code" },
+            {
+              label: "qubit_maj_ns_e4 + surface_code",
+              detail: "Majorana qubit with 1e-4 error rate (surface code QEC)",
+              params: {
+                qubitParams: { name: "qubit_maj_ns_e4" },
+                qecScheme: { name: "surface_code" },
+              },
             },
-          },
-          {
-            label: "qubit_maj_ns_e6 + surface_code",
-            detail: "Majorana qubit with 1e-6 error rate (surface code QEC)",
-            params: {
-              qubitParams: { name: "qubit_maj_ns_e6" },
-              qecScheme: { name: "surface_code" },
+            {
+              label: "qubit_maj_ns_e6 + surface_code",
+              detail: "Majorana qubit with 1e-6 error rate (surface code QEC)",
+              params: {
+                qubitParams: { name: "qubit_maj_ns_e6" },
+                qecScheme: { name: "surface_code" },
+              },
             },
-          },
-          {
-            label: "qubit_maj_ns_e4 + floquet_code",
-            detail: "Majorana qubit with 1e-4 error rate (floquet code QEC)",
-            params: {
-              qubitParams: { name: "qubit_maj_ns_e4" },
-              qecScheme: { name: "floquet_code" },
+            {
+              label: "qubit_maj_ns_e4 + floquet_code",
+              detail: "Majorana qubit with 1e-4 error rate (floquet code QEC)",
+              params: {
+                qubitParams: { name: "qubit_maj_ns_e4" },
+                qecScheme: { name: "floquet_code" },
+              },
             },
-          },
-          {
-            label: "qubit_maj_ns_e6 + floquet_code",
-            detail: "Majorana qubit with 1e-6 error rate (floquet code QEC)",
-            params: {
-              qubitParams: { name: "qubit_maj_ns_e6" },
-              qecScheme: { name: "floquet_code" },
+            {
+              label: "qubit_maj_ns_e6 + floquet_code",
+              detail: "Majorana qubit with 1e-6 error rate (floquet code QEC)",
+              params: {
+                qubitParams: { name: "qubit_maj_ns_e6" },
+                qecScheme: { name: "floquet_code" },
+              },
             },
+          ],
+          {
+            canPickMany: true,
+            title: "Qubit types",
+            placeHolder: "Superconducting/spin qubit with 1e-3 error rate",
+            matchOnDetail: true,
           },
-        ],
-        {
-          canPickMany: true,
-          title: "Qubit types",
-          placeHolder: "Superconducting/spin qubit with 1e-3 error rate",
-          matchOnDetail: true,
-        },
-      );
-
-      if (!qubitType) {
-        return;
-      }
+        );
 
-      // Prompt for error budget (default to 0.001)
-      const validateErrorBudget = (input: string) => {
-        const result = parseFloat(input);
-        if (isNaN(result) || result <= 0.0 || result >= 1.0) {
-          return "Error budgets must be between 0 and 1";
+        if (!qubitType) {
+          return;
         }
-      };
 
-      const errorBudget = await window.showInputBox({
-        value: "0.001",
-        prompt: "Error budget",
-        validateInput: validateErrorBudget,
-      });
-
-      // abort if the user hits <Esc> during shots entry
-      if (errorBudget === undefined) {
-        return;
-      }
+        // Prompt for error budget (default to 0.001)
+        const validateErrorBudget = (input: string) => {
+          const result = parseFloat(input);
+          if (isNaN(result) || result <= 0.0 || result >= 1.0) {
+            return "Error budgets must be between 0 and 1";
+          }
+        };
 
-      let runName = await window.showInputBox({
-        title: "Friendly name for run",
-        value: `${program.programConfig.projectName}`,
-      });
-      if (!runName) {
-        return;
-      }
+        const errorBudget = await window.showInputBox({
+          value: "0.001",
+          prompt: "Error budget",
+          validateInput: validateErrorBudget,
+        });
 
-      const params = qubitType.map((item) => ({
-        ...item.params,
-        errorBudget: parseFloat(errorBudget),
-        estimateType: "frontier",
-      }));
-
-      log.info("RE params", params);
-
-      sendMessageToPanel({ panelType: "estimates" }, true, {
-        calculating: true,
-      });
-
-      const estimatePanel = getOrCreatePanel("estimates");
-      // Ensure the name is unique
-      if (estimatePanel.state[runName] !== undefined) {
-        let idx = 2;
-        for (;;) {
-          const newName = `${runName}-${idx}`;
-          if (estimatePanel.state[newName] === undefined) {
-            runName = newName;
-            break;
-          }
-          idx++;
+        // abort if the user hits <Esc> during shots entry
+        if (errorBudget === undefined) {
+          return;
         }
-      }
-      estimatePanel.state[runName] = true;
 
-      // Start the worker, run the code, and send the results to the webview
-      log.debug("Starting resource estimates worker.");
-      let timedOut = false;
+        let runName = await window.showInputBox({
+          title: "Friendly name for run",
+          value: `${program.programConfig.projectName}`,
+        });
+        if (!runName) {
+          return;
+        }
 
-      const worker = getCompilerWorker(compilerWorkerScriptPath);
-      const compilerTimeout = setTimeout(() => {
-        log.info("Compiler timeout. Terminating worker.");
-        timedOut = true;
-        worker.terminate();
-      }, compilerRunTimeoutMs);
+        const params = qubitType.map((item) => ({
+          ...item.params,
+          errorBudget: parseFloat(errorBudget),
+          estimateType: "frontier",
+        }));
 
-      try {
-        const start = performance.now();
-        sendTelemetryEvent(
-          EventType.ResourceEstimationStart,
-          { associationId },
-          {},
-        );
-        const estimatesStr = await worker.getEstimates(
-          program.programConfig,
-          JSON.stringify(params),
-        );
-        sendTelemetryEvent(
-          EventType.ResourceEstimationEnd,
-          { associationId },
-          { timeToCompleteMs: performance.now() - start },
-        );
-        log.debug("Estimates result", estimatesStr);
+        log.info("RE params", params);
 
-        // Should be an array of one ReData object returned
-        const estimates = JSON.parse(estimatesStr);
+        sendMessageToPanel({ panelType: "estimates" }, true, {
+          calculating: true,
+        });
 
-        for (const item of estimates) {
-          // if item doesn't have a status property, it's an error
-          if (!("status" in item) || item.status !== "success") {
-            log.error("Estimates error code: ", item.code);
-            log.error("Estimates error message: ", item.message);
-            throw item.message;
+        const estimatePanel = getOrCreatePanel("estimates");
+        // Ensure the name is unique
+        if (estimatePanel.state[runName] !== undefined) {
+          let idx = 2;
+          for (;;) {
+            const newName = `${runName}-${idx}`;
+            if (estimatePanel.state[newName] === undefined) {
+              runName = newName;
+              break;
+            }
+            idx++;
           }
         }
+        estimatePanel.state[runName] = true;
 
-        (estimates as Array<any>).forEach(
-          (item) => (item.jobParams.sharedRunName = runName),
-        );
-
-        clearTimeout(compilerTimeout);
+        // Start the worker, run the code, and send the results to the webview
+        log.debug("Starting resource estimates worker.");
+        let timedOut = false;
 
-        const message = {
-          calculating: false,
-          estimates,
-        };
-        sendMessageToPanel({ panelType: "estimates" }, true, message);
-      } catch (e: any) {
-        // Stop the 'calculating' animation
-        const message = {
-          calculating: false,
-          estimates: [],
-        };
-        sendMessageToPanel({ panelType: "estimates" }, false, message);
+        const worker = getCompilerWorker(compilerWorkerScriptPath);
+        const compilerTimeout = setTimeout(() => {
+          log.info("Compiler timeout. Terminating worker.");
+          timedOut = true;
+          worker.terminate();
+        }, compilerRunTimeoutMs);
+
+        try {
+          const start = performance.now();
+          sendTelemetryEvent(
+            EventType.ResourceEstimationStart,
+            { associationId },
+            {},
+          );
+          const estimatesStr = await worker.getEstimates(
+            program.programConfig,
+            expr,
+            JSON.stringify(params),
+          );
+          sendTelemetryEvent(
+            EventType.ResourceEstimationEnd,
+            { associationId },
+            { timeToCompleteMs: performance.now() - start },
+          );
+          log.debug("Estimates result", estimatesStr);
+
+          // Should be an array of one ReData object returned
+          const estimates = JSON.parse(estimatesStr);
+
+          for (const item of estimates) {
+            // if item doesn't have a status property, it's an error
+            if (!("status" in item) || item.status !== "success") {
+              log.error("Estimates error code: ", item.code);
+              log.error("Estimates error message: ", item.message);
+              throw item.message;
+            }
+          }
 
-        if (timedOut) {
-          // Show a VS Code popup that a timeout occurred
-          window.showErrorMessage(
-            "The resource estimation timed out. Please try again.",
+          (estimates as Array<any>).forEach(
+            (item) => (item.jobParams.sharedRunName = runName),
           );
-        } else {
-          log.error("getEstimates error: ", e.toString());
-          throw new Error("Estimating failed with error: " + e.toString());
-        }
-      } finally {
-        if (!timedOut) {
-          log.debug("Terminating resource estimates worker.");
-          worker.terminate();
+
+          clearTimeout(compilerTimeout);
+
+          const message = {
+            calculating: false,
+            estimates,
+          };
+          sendMessageToPanel({ panelType: "estimates" }, true, message);
+        } catch (e: any) {
+          // Stop the 'calculating' animation
+          const message = {
+            calculating: false,
+            estimates: [],
+          };
+          sendMessageToPanel({ panelType: "estimates" }, false, message);
+
+          if (timedOut) {
+            // Show a VS Code popup that a timeout occurred
+            window.showErrorMessage(
+              "The resource estimation timed out. Please try again.",
+            );
+          } else {
+            log.error("getEstimates error: ", e.toString());
+            throw new Error("Estimating failed with error: " + e.toString());
+          }
+        } finally {
+          if (!timedOut) {
+            log.debug("Terminating resource estimates worker.");
+            worker.terminate();
+          }
         }
-      }
-    }),
+      },
+    ),
   );
 
   context.subscriptions.push(
@@ -277,103 +288,115 @@ export function registerWebViewCommands(context: ExtensionContext) {
   );
 
   context.subscriptions.push(
-    commands.registerCommand(`${qsharpExtensionId}.showHistogram`, async () => {
-      clearCommandDiagnostics();
-
-      const associationId = getRandomGuid();
-      sendTelemetryEvent(EventType.TriggerHistogram, { associationId }, {});
-      function resultToLabel(result: string | VSDiagnostic): string {
-        if (typeof result !== "string") return "ERROR";
-        return result;
-      }
-
-      const program = await getActiveProgram();
-      if (!program.success) {
-        throw new Error(program.errorMsg);
-      }
-
-      const panelId = program.programConfig.projectName;
-
-      // Start the worker, run the code, and send the results to the webview
-      const worker = getCompilerWorker(compilerWorkerScriptPath);
-      const compilerTimeout = setTimeout(() => {
-        worker.terminate();
-      }, compilerRunTimeoutMs);
-
-      try {
-        const validateShotsInput = (input: string) => {
-          const result = parseFloat(input);
-          if (isNaN(result) || Math.floor(result) !== result || result <= 0) {
-            return "Number of shots must be a positive integer";
-          }
-        };
+    commands.registerCommand(
+      `${qsharpExtensionId}.showHistogram`,
+      async (_resource?: Uri, entryPoint?: IEntryPoint) => {
+        clearCommandDiagnostics();
+
+        const associationId = getRandomGuid();
+        sendTelemetryEvent(EventType.TriggerHistogram, { associationId }, {});
+        function resultToLabel(result: string | VSDiagnostic): string {
+          if (typeof result !== "string") return "ERROR";
+          return result;
+        }
 
-        const numberOfShots =
-          (await window.showInputBox({
-            value: "100",
-            prompt: "Number of shots",
-            validateInput: validateShotsInput,
-          })) || "100";
+        const program = await getActiveProgram();
+        if (!program.success) {
+          throw new Error(program.errorMsg);
+        }
 
-        // abort if the user hits <Esc> during shots entry
-        if (numberOfShots === undefined) {
+        const expr = await promptForEntryExpression(entryPoint);
+        if (expr === undefined) {
           return;
         }
 
-        sendMessageToPanel(
-          { panelType: "histogram", id: panelId },
-          true,
-          undefined,
-        );
+        const panelId = program.programConfig.projectName;
 
-        const evtTarget = new QscEventTarget(true);
-        evtTarget.addEventListener("uiResultsRefresh", () => {
-          const results = evtTarget.getResults();
-          const resultCount = evtTarget.resultCount();
-          const buckets = new Map();
-          for (let i = 0; i < resultCount; ++i) {
-            const key = results[i].result;
-            const strKey = resultToLabel(key);
-            const newValue = (buckets.get(strKey) || 0) + 1;
-            buckets.set(strKey, newValue);
-          }
-          const message = {
-            buckets: Array.from(buckets.entries()),
-            shotCount: resultCount,
+        // Start the worker, run the code, and send the results to the webview
+        const worker = getCompilerWorker(compilerWorkerScriptPath);
+        const compilerTimeout = setTimeout(() => {
+          worker.terminate();
+        }, compilerRunTimeoutMs);
+
+        try {
+          const validateShotsInput = (input: string) => {
+            const result = parseFloat(input);
+            if (isNaN(result) || Math.floor(result) !== result || result <= 0) {
+              return "Number of shots must be a positive integer";
+            }
           };
+
+          const numberOfShots =
+            (await window.showInputBox({
+              value: "100",
+              prompt: "Number of shots",
+              validateInput: validateShotsInput,
+            })) || "100";
+
+          // abort if the user hits <Esc> during shots entry
+          if (numberOfShots === undefined) {
+            return;
+          }
+
           sendMessageToPanel(
             { panelType: "histogram", id: panelId },
-            false,
-            message,
+            true,
+            undefined,
           );
-        });
-        const start = performance.now();
-        sendTelemetryEvent(EventType.HistogramStart, { associationId }, {});
 
-        const noise = getPauliNoiseModel();
-        if (noise[0] != 0 || noise[1] != 0 || noise[2] != 0) {
-          sendTelemetryEvent(EventType.NoisySimulation, { associationId }, {});
+          const evtTarget = new QscEventTarget(true);
+          evtTarget.addEventListener("uiResultsRefresh", () => {
+            const results = evtTarget.getResults();
+            const resultCount = evtTarget.resultCount();
+            const buckets = new Map();
+            for (let i = 0; i < resultCount; ++i) {
+              const key = results[i].result;
+              const strKey = resultToLabel(key);
+              const newValue = (buckets.get(strKey) || 0) + 1;
+              buckets.set(strKey, newValue);
+            }
+            const message = {
+              buckets: Array.from(buckets.entries()),
+              shotCount: resultCount,
+            };
+            sendMessageToPanel(
+              { panelType: "histogram", id: panelId },
+              false,
+              message,
+            );
+          });
+          const start = performance.now();
+          sendTelemetryEvent(EventType.HistogramStart, { associationId }, {});
+
+          const noise = getPauliNoiseModel();
+          if (noise[0] != 0 || noise[1] != 0 || noise[2] != 0) {
+            sendTelemetryEvent(
+              EventType.NoisySimulation,
+              { associationId },
+              {},
+            );
+          }
+          await worker.runWithPauliNoise(
+            program.programConfig,
+            expr,
+            parseInt(numberOfShots),
+            noise,
+            evtTarget,
+          );
+          sendTelemetryEvent(
+            EventType.HistogramEnd,
+            { associationId },
+            { timeToCompleteMs: performance.now() - start },
+          );
+          clearTimeout(compilerTimeout);
+        } catch (e: any) {
+          log.error("Histogram error. ", e.toString());
+          throw new Error("Run failed. " + e.toString());
+        } finally {
+          worker.terminate();
         }
-        await worker.runWithPauliNoise(
-          program.programConfig,
-          "",
-          parseInt(numberOfShots),
-          noise,
-          evtTarget,
-        );
-        sendTelemetryEvent(
-          EventType.HistogramEnd,
-          { associationId },
-          { timeToCompleteMs: performance.now() - start },
-        );
-        clearTimeout(compilerTimeout);
-      } catch (e: any) {
-        log.error("Histogram error. ", e.toString());
-        throw new Error("Run failed. " + e.toString());
-      } finally {
-        worker.terminate();
-      }
-    }),
+      },
+    ),
   );
 
   context.subscriptions.push(
@@ -2,8 +2,9 @@
 // Licensed under the MIT License.
 
 use crate::line_column::{Location, Range};
+use crate::memory_usage::{IMemoryUsage, MemoryUsage};
 use crate::project_system::{into_qsc_args, ProgramConfig};
-use crate::{serializable_type, CallbackReceiver};
+use crate::{default_quota, serializable_type, CallbackReceiver};
 use qsc::fir::StmtId;
 use qsc::fmt_complex;
 use qsc::interpret::{Debugger, Error, StepAction, StepResult};
@@ -54,6 +55,10 @@ impl DebugService {
         QuantumStateList { entries }.into()
     }
 
+    pub fn get_memory_usage(&mut self) -> IMemoryUsage {
+        MemoryUsage::from(self.debugger_mut().memory_usage()).into()
+    }
+
     pub fn get_circuit(&self) -> Result<JsValue, String> {
         let circuit = self.debugger().circuit();
         serde_wasm_bindgen::to_value(&circuit).map_err(|e| e.to_string())
@@ -136,7 +141,10 @@ impl DebugService {
     where
         F: Fn(&str),
     {
-        let mut out = CallbackReceiver { event_cb };
+        let mut out = CallbackReceiver {
+            event_cb,
+            host_functions: None,
+        };
         let result = self.debugger_mut().eval_step(&mut out, bps, step);
         let mut success = true;
 
@@ -217,14 +225,16 @@ pub fn init_debugger(
         into_qsc_args(program, entry)
             .map_err(|e| e.into_iter().map(Into::into).collect::<Vec<_>>())?;
 
-    Debugger::new(
+    let mut debugger = Debugger::new(
         source_map,
         capabilities,
         Encoding::Utf16,
         language_features,
         package_store,
         &user_code_dependencies[..],
-    )
+    )?;
+    debugger.set_quota(default_quota());
+    Ok(debugger)
 }
 
 fn render_errors(errors: Vec<Error>) -> String {
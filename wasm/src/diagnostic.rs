@@ -271,7 +271,10 @@ fn interpret_error_labels(err: &interpret::Error) -> Vec<Label> {
         interpret::Error::NoEntryPoint
         | interpret::Error::UnsupportedRuntimeCapabilities
         | interpret::Error::Circuit(_)
+        | interpret::Error::Codegen(_)
         | interpret::Error::NotAnOperation
-        | interpret::Error::NotACallable => Vec::new(),
+        | interpret::Error::NotACallable
+        | interpret::Error::TooLargeToExplain
+        | interpret::Error::SimulatorUnavailable(_) => Vec::new(),
     }
 }
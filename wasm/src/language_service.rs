@@ -4,6 +4,7 @@
 use crate::{
     diagnostic::VSDiagnostic,
     line_column::{ILocation, IPosition, IRange, Location, Position, Range},
+    memory_usage::{IMemoryUsage, MemoryUsage},
     project_system::ProjectHost,
     serializable_type,
     test_discovery::TestDescriptor,
@@ -174,6 +175,10 @@ impl LanguageService {
         self.0.close_notebook_document(notebook_uri);
     }
 
+    pub fn collect(&mut self) {
+        self.0.collect();
+    }
+
     pub fn get_code_actions(&self, uri: &str, range: IRange) -> Vec<ICodeAction> {
         let range: Range = range.into();
         let code_actions = self.0.get_code_actions(uri, range.into());
@@ -359,6 +364,123 @@ impl LanguageService {
             })
             .collect()
     }
+
+    pub fn get_required_capabilities(
+        &self,
+        uri: &str,
+        range: IRange,
+    ) -> Option<IRequiredCapabilities> {
+        let range: Range = range.into();
+        let required = self.0.get_required_capabilities(uri, range.into());
+        required.map(|required| {
+            RequiredCapabilities {
+                range: required.range.into(),
+                capabilities: required.capabilities,
+                minimalProfile: required.minimal_profile.to_str().to_lowercase(),
+                features: required
+                    .features
+                    .into_iter()
+                    .map(|feature| RuntimeFeature {
+                        range: feature.range.into(),
+                        description: feature.description,
+                    })
+                    .collect(),
+                summary: required.summary,
+            }
+            .into()
+        })
+    }
+
+    pub fn get_inactive_regions(&self, uri: &str) -> Vec<IRange> {
+        let regions = self.0.get_inactive_regions(uri);
+        regions
+            .into_iter()
+            .map(|range| Range::from(range).into())
+            .collect()
+    }
+
+    pub fn get_semantic_tokens(&self, uri: &str) -> Vec<ISemanticToken> {
+        let tokens = self.0.get_semantic_tokens(uri);
+        tokens
+            .into_iter()
+            .map(|token| {
+                SemanticToken {
+                    range: token.range.into(),
+                    kind: match token.kind {
+                        qsls::protocol::SemanticTokenKind::Comment => "comment",
+                        qsls::protocol::SemanticTokenKind::DocComment => "docComment",
+                        qsls::protocol::SemanticTokenKind::Keyword => "keyword",
+                        qsls::protocol::SemanticTokenKind::TypeParameter => "typeParameter",
+                        qsls::protocol::SemanticTokenKind::Number => "number",
+                        qsls::protocol::SemanticTokenKind::String => "string",
+                        qsls::protocol::SemanticTokenKind::Operator => "operator",
+                    }
+                    .to_string(),
+                }
+                .into()
+            })
+            .collect()
+    }
+
+    pub fn get_entry_point(&self, uri: &str) -> Option<IEntryPoint> {
+        let entry_point = self.0.get_entry_point(uri);
+        entry_point.map(|entry_point| {
+            EntryPoint {
+                range: entry_point.range.into(),
+                callable: entry_point.callable,
+                params: entry_point
+                    .params
+                    .into_iter()
+                    .map(|param| EntryPointParam {
+                        name: param.name,
+                        ty: param.ty,
+                        defaultValue: param.default_value,
+                    })
+                    .collect(),
+            }
+            .into()
+        })
+    }
+
+    pub fn get_exports(&self, uri: &str) -> Vec<IExport> {
+        let exports = self.0.get_exports(uri);
+        exports
+            .into_iter()
+            .map(|export| {
+                Export {
+                    name: export.name,
+                    kind: match export.kind {
+                        qsls::protocol::ExportKind::Callable => "callable",
+                        qsls::protocol::ExportKind::Type => "type",
+                    }
+                    .to_string(),
+                    location: export.location.into(),
+                }
+                .into()
+            })
+            .collect()
+    }
+
+    pub fn get_operation_stats(&self, uri: &str) -> Vec<IOperationStats> {
+        let stats = self.0.get_operation_stats(uri);
+        stats
+            .into_iter()
+            .map(|stats| {
+                OperationStats {
+                    range: stats.range.into(),
+                    capabilities: stats.capabilities,
+                    minimalProfile: stats.minimal_profile.to_str().to_lowercase(),
+                    instructionCount: stats.instruction_count,
+                    label: stats.label,
+                }
+                .into()
+            })
+            .collect()
+    }
+
+    pub fn get_memory_usage(&self, uri: &str) -> IMemoryUsage {
+        MemoryUsage::from(self.0.get_memory_usage(uri)).into()
+    }
 }
 
 serializable_type! {
@@ -610,7 +732,7 @@ serializable_type! {
     },
     r#"export interface INotebookMetadata {
         targetProfile?: "base" | "adaptive_ri" | "adaptive_rif" | "unrestricted";
-        languageFeatures?: "v2-preview-syntax"[];
+        languageFeatures?: ("v2-preview-syntax" | "strict-unicode-identifiers")[];
         manifest?: string;
         projectRoot?: string;
     }"#,
@@ -630,3 +752,110 @@ extern "C" {
     #[wasm_bindgen(typescript_type = "(callables: ITestDescriptor[]) => void")]
     pub type TestCallableCallback;
 }
+
+serializable_type! {
+    RequiredCapabilities,
+    {
+        range: Range,
+        capabilities: Vec<String>,
+        minimalProfile: String,
+        features: Vec<RuntimeFeature>,
+        summary: String,
+    },
+    r#"export interface IRequiredCapabilities {
+        range: IRange;
+        capabilities: string[];
+        minimalProfile: TargetProfile;
+        features: IRuntimeFeature[];
+        summary: string;
+    }"#,
+    IRequiredCapabilities
+}
+
+serializable_type! {
+    RuntimeFeature,
+    {
+        range: Range,
+        description: String,
+    },
+    r#"export interface IRuntimeFeature {
+        range: IRange;
+        description: string;
+    }"#
+}
+
+serializable_type! {
+    OperationStats,
+    {
+        range: Range,
+        capabilities: Vec<String>,
+        minimalProfile: String,
+        instructionCount: Option<usize>,
+        label: String,
+    },
+    r#"export interface IOperationStats {
+        range: IRange;
+        capabilities: string[];
+        minimalProfile: TargetProfile;
+        instructionCount?: number;
+        label: string;
+    }"#,
+    IOperationStats
+}
+
+serializable_type! {
+    SemanticToken,
+    {
+        range: Range,
+        kind: String,
+    },
+    r#"export interface ISemanticToken {
+        range: IRange;
+        kind: "comment" | "docComment" | "keyword" | "typeParameter" | "number" | "string" | "operator";
+    }"#,
+    ISemanticToken
+}
+
+serializable_type! {
+    Export,
+    {
+        name: String,
+        kind: String,
+        location: Location,
+    },
+    r#"export interface IExport {
+        name: string;
+        kind: "callable" | "type";
+        location: ILocation;
+    }"#,
+    IExport
+}
+
+serializable_type! {
+    EntryPoint,
+    {
+        range: Range,
+        callable: String,
+        params: Vec<EntryPointParam>,
+    },
+    r#"export interface IEntryPoint {
+        range: IRange;
+        callable: string;
+        params: IEntryPointParam[];
+    }"#,
+    IEntryPoint
+}
+
+serializable_type! {
+    EntryPointParam,
+    {
+        name: String,
+        ty: String,
+        defaultValue: Option<String>,
+    },
+    r#"export interface IEntryPointParam {
+        name: string;
+        ty: string;
+        defaultValue?: string;
+    }"#
+}
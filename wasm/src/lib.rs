@@ -9,24 +9,24 @@ use katas::check_solution;
 use language_service::IOperationInfo;
 use num_bigint::BigUint;
 use num_complex::Complex64;
-use project_system::{into_qsc_args, ProgramConfig};
+use project_system::{into_qsc_args, package_type, ProgramConfig};
 use qsc::{
     compile::{self, Dependencies},
     format_state_id, get_matrix_latex, get_state_latex,
     hir::PackageId,
     interpret::{
         self,
-        output::{self, Receiver},
-        CircuitEntryPoint,
+        output::{self, Receiver, StateSnapshot},
+        CircuitEntryPoint, SimulatorKind, Value,
     },
     target::Profile,
-    LanguageFeatures, PackageStore, PackageType, PauliNoise, SourceContents, SourceMap, SourceName,
-    SparseSim, TargetCapabilityFlags,
+    LanguageFeatures, PackageStore, PackageType, PauliNoise, Quota, SourceContents, SourceMap,
+    SourceName, TargetCapabilityFlags,
 };
 use resource_estimator::{self as re, estimate_entry};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use std::{fmt::Write, str::FromStr};
+use std::{fmt::Write, str::FromStr, time::Duration};
 use wasm_bindgen::prelude::*;
 
 mod debug_service;
@@ -34,6 +34,7 @@ mod diagnostic;
 mod language_service;
 mod line_column;
 mod logging;
+mod memory_usage;
 mod project_system;
 mod serializable_type;
 mod test_discovery;
@@ -82,12 +83,28 @@ pub(crate) fn get_qir_(
 }
 
 #[wasm_bindgen]
-pub fn get_estimates(program: ProgramConfig, params: &str) -> Result<String, String> {
-    let (source_map, capabilities, language_features, store, deps) = into_qsc_args(program, None)
-        .map_err(|mut e| {
-        // Wrap in `interpret::Error` to match the error type from `Interpreter::new` below
-        qsc::interpret::Error::from(e.pop().expect("expected at least one error")).to_string()
-    })?;
+pub fn get_qir_bitcode(program: ProgramConfig) -> Result<Vec<u8>, String> {
+    let (source_map, capabilities, language_features, store, deps) =
+        into_qsc_args(program, None).map_err(compile_errors_into_qsharp_errors_json)?;
+
+    qsc::codegen::qir::get_qir_bitcode(
+        source_map,
+        language_features,
+        capabilities,
+        store,
+        &deps[..],
+        qsc::codegen::qir::QirOptions::default(),
+    )
+    .map_err(interpret_errors_into_qsharp_errors_json)
+}
+
+#[wasm_bindgen]
+pub fn get_estimates(program: ProgramConfig, expr: &str, params: &str) -> Result<String, String> {
+    let (source_map, capabilities, language_features, store, deps) =
+        into_qsc_args(program, Some(expr.into())).map_err(|mut e| {
+            // Wrap in `interpret::Error` to match the error type from `Interpreter::new` below
+            qsc::interpret::Error::from(e.pop().expect("expected at least one error")).to_string()
+        })?;
 
     let mut interpreter = interpret::Interpreter::new(
         source_map,
@@ -98,6 +115,7 @@ pub fn get_estimates(program: ProgramConfig, params: &str) -> Result<String, Str
         &deps[..],
     )
     .map_err(|e| e[0].to_string())?;
+    interpreter.set_quota(default_quota());
 
     estimate_entry(&mut interpreter, params).map_err(|e| match &e[0] {
         re::Error::Interpreter(interpret::Error::Eval(e)) => e.to_string(),
@@ -136,6 +154,7 @@ pub fn get_circuit(
         &deps[..],
     )
     .map_err(interpret_errors_into_qsharp_errors_json)?;
+    interpreter.set_quota(default_quota());
 
     let circuit = interpreter
         .circuit(entry_point, simulate)
@@ -144,6 +163,83 @@ pub fn get_circuit(
     serde_wasm_bindgen::to_value(&circuit).map_err(|e| e.to_string())
 }
 
+serializable_type! {
+    AttributionNode,
+    {
+        pub measurement: Option<BranchDecision>,
+        pub probability: f64,
+        pub contribution: f64,
+        pub result: Option<String>,
+        pub children: Vec<AttributionNode>,
+    },
+    r#"export interface IAttributionNode {
+        measurement?: IBranchDecision;
+        probability: number;
+        contribution: number;
+        result?: string;
+        children: IAttributionNode[];
+    }"#
+}
+
+serializable_type! {
+    BranchDecision,
+    {
+        pub kind: String,
+        pub qubit: usize,
+        pub result: String,
+        pub probability: f64,
+    },
+    r#"export interface IBranchDecision {
+        kind: "M" | "MResetZ" | "Reset";
+        qubit: number;
+        result: "Zero" | "One";
+        probability: number;
+    }"#
+}
+
+impl From<interpret::AttributionNode> for AttributionNode {
+    fn from(node: interpret::AttributionNode) -> Self {
+        AttributionNode {
+            measurement: node.measurement.map(|measurement| BranchDecision {
+                kind: measurement.kind.to_string(),
+                qubit: measurement.qubit,
+                result: if measurement.result { "One" } else { "Zero" }.to_string(),
+                probability: measurement.probability,
+            }),
+            probability: node.probability,
+            contribution: node.contribution,
+            result: node.result,
+            children: node.children.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Explains how the program comes to produce the given result, as displayed in the histogram,
+/// by returning the tree of measurement outcomes that the probability of the result comes from.
+#[wasm_bindgen]
+pub fn explain_result(program: ProgramConfig, expr: &str, result: &str) -> Result<JsValue, String> {
+    let (source_map, capabilities, language_features, store, deps) =
+        into_qsc_args(program, Some(expr.into()))
+            .map_err(compile_errors_into_qsharp_errors_json)?;
+
+    let mut interpreter = interpret::Interpreter::new(
+        source_map,
+        PackageType::Exe,
+        capabilities,
+        language_features,
+        store,
+        &deps[..],
+    )
+    .map_err(interpret_errors_into_qsharp_errors_json)?;
+    interpreter.set_quota(default_quota());
+
+    let tree = interpreter
+        .explain_result(None, result)
+        .map_err(interpret_errors_into_qsharp_errors_json)?;
+
+    serde_wasm_bindgen::to_value(&AttributionNode::from(tree)).map_err(|e| e.to_string())
+}
+
 #[allow(clippy::needless_pass_by_value)]
 fn interpret_errors_into_qsharp_errors_json(errs: Vec<qsc::interpret::Error>) -> String {
     serde_json::to_string(&interpret_errors_into_qsharp_errors(&errs))
@@ -157,13 +253,15 @@ fn compile_errors_into_qsharp_errors_json(errs: Vec<qsc::compile::Error>) -> Str
 #[wasm_bindgen]
 #[must_use]
 pub fn get_library_source_content(name: &str) -> Option<String> {
+    // Library sources are given to the editor with URIs that include the library version.
+    let name = qsc::library::library_source_name(name)?;
     STORE_CORE_STD.with(|(store, std)| {
         for id in [PackageId::CORE, *std] {
             if let Some(source) = store
                 .get(id)
                 .expect("package should be in store")
                 .sources
-                .find_by_name(name)
+                .find_by_name(&name)
             {
                 return Some(source.contents.to_string());
             }
@@ -236,11 +334,36 @@ pub fn get_rir(program: ProgramConfig) -> Result<Vec<String>, String> {
     .map_err(interpret_errors_into_qsharp_errors_json)
 }
 
+/// Checks the whole program against its target profile, including the checks that run during code generation,
+/// without generating any output. All of the diagnostics are returned at once as the error.
+#[wasm_bindgen]
+pub fn check_for_target(program: ProgramConfig) -> Result<(), String> {
+    let package_type = package_type(&program);
+    let (source_map, capabilities, language_features, store, deps) =
+        into_qsc_args(program, None).map_err(compile_errors_into_qsharp_errors_json)?;
+
+    let errors = qsc::codegen::qir::check_for_target(
+        source_map,
+        language_features,
+        capabilities,
+        package_type,
+        store,
+        &deps[..],
+    );
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(interpret_errors_into_qsharp_errors_json(errors))
+    }
+}
+
 struct CallbackReceiver<F>
 where
     F: FnMut(&str),
 {
     event_cb: F,
+    /// The JavaScript functions that implement functions with the `@HostCallable()` attribute, by name.
+    host_functions: Option<js_sys::Object>,
 }
 
 impl<F> Receiver for CallbackReceiver<F>
@@ -252,6 +375,15 @@ where
         state: Vec<(BigUint, Complex64)>,
         qubit_count: usize,
     ) -> Result<(), output::Error> {
+        self.state_snapshot(StateSnapshot {
+            qubits: (0..qubit_count).collect(),
+            amplitudes: state,
+        })
+    }
+
+    fn state_snapshot(&mut self, snapshot: StateSnapshot) -> Result<(), output::Error> {
+        let state = snapshot.amplitudes;
+        let qubit_count = snapshot.qubits.len();
         let mut dump_json = String::new();
         write!(dump_json, r#"{{"type": "DumpMachine","state": {{"#)
             .expect("writing to string should succeed");
@@ -279,9 +411,11 @@ where
 
         let json_latex = serde_json::to_string(&get_state_latex(&state, qubit_count))
             .expect("serialization should succeed");
+        let json_qubits =
+            serde_json::to_string(&snapshot.qubits).expect("serialization should succeed");
         write!(
             dump_json,
-            r#" "stateLatex": {json_latex}, "qubitCount": {qubit_count} }} "#
+            r#" "stateLatex": {json_latex}, "qubitCount": {qubit_count}, "qubits": {json_qubits} }} "#
         )
         .expect("writing to string should succeed");
         (self.event_cb)(&dump_json);
@@ -332,8 +466,81 @@ where
         (self.event_cb)(&msg_json.to_string());
         Ok(())
     }
+
+    fn host_call(&mut self, name: &str, arg: Value) -> Option<Result<Value, String>> {
+        let function =
+            js_sys::Reflect::get(self.host_functions.as_ref()?, &JsValue::from_str(name))
+                .ok()?
+                .dyn_into::<js_sys::Function>()
+                .ok()?;
+        // The items of a tuple argument are passed as separate arguments, and Unit as no arguments.
+        let args = match arg {
+            Value::Tuple(items) => items.iter().map(value_to_js).collect::<js_sys::Array>(),
+            arg => std::iter::once(value_to_js(&arg)).collect(),
+        };
+        Some(
+            function
+                .apply(&JsValue::null(), &args)
+                .map_err(|e| match e.dyn_into::<js_sys::Error>() {
+                    Ok(error) => String::from(error.message()),
+                    Err(e) => format!("{e:?}"),
+                })
+                .and_then(|result| value_from_js(&result)),
+        )
+    }
+}
+
+/// Converts a Q# value passed to a host function into a JavaScript value. Tuples are passed as arrays, since
+/// JavaScript doesn't have tuples.
+fn value_to_js(value: &Value) -> JsValue {
+    match value {
+        #[allow(clippy::cast_precision_loss)]
+        Value::Int(val) => JsValue::from_f64(*val as f64),
+        Value::Double(val) => JsValue::from_f64(*val),
+        Value::Bool(val) => JsValue::from_bool(*val),
+        Value::String(val) => JsValue::from_str(val),
+        Value::Tuple(items) if items.is_empty() => JsValue::undefined(),
+        Value::Tuple(items) => items
+            .iter()
+            .map(value_to_js)
+            .collect::<js_sys::Array>()
+            .into(),
+        Value::Array(items) => items
+            .iter()
+            .map(value_to_js)
+            .collect::<js_sys::Array>()
+            .into(),
+        _ => JsValue::from_str(&value.to_string()),
+    }
 }
 
+/// Converts the value returned by a host function into a Q# value, based on its JavaScript type.
+/// The evaluator then checks that the value has the output type of the Q# function, converting numbers to `Int`
+/// and arrays to tuples where the output type needs it.
+fn value_from_js(value: &JsValue) -> Result<Value, String> {
+    if value.is_undefined() || value.is_null() {
+        Ok(Value::unit())
+    } else if let Some(val) = value.as_bool() {
+        Ok(Value::Bool(val))
+    } else if let Some(val) = value.as_f64() {
+        Ok(Value::Double(val))
+    } else if let Some(val) = value.as_string() {
+        Ok(Value::String(val.into()))
+    } else if value.is_array() {
+        let items = js_sys::Array::from(value)
+            .iter()
+            .map(|item| value_from_js(&item))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Value::Array(items.into()))
+    } else {
+        Err(format!("cannot convert value `{value:?}` to a Q# value"))
+    }
+}
+
+/// The memory, in bytes, that the quantum state of a run can use. The wasm runtime aborts when it
+/// runs out of memory, so runs whose state grows larger fail with an error instead.
+const SIMULATION_MEMORY_BUDGET: usize = 1 << 30;
+
 #[allow(clippy::too_many_arguments)]
 fn run_internal_with_features<F>(
     sources: SourceMap,
@@ -344,6 +551,8 @@ fn run_internal_with_features<F>(
     store: PackageStore,
     dependencies: &Dependencies,
     pauliNoise: &PauliNoise,
+    quota: Quota,
+    host_functions: Option<js_sys::Object>,
 ) -> Result<(), Box<interpret::Error>>
 where
     F: FnMut(&str),
@@ -354,7 +563,10 @@ where
         .next()
         .expect("There must be a source to process")
         .to_string();
-    let mut out = CallbackReceiver { event_cb };
+    let mut out = CallbackReceiver {
+        event_cb,
+        host_functions,
+    };
     let mut interpreter = match interpret::Interpreter::new(
         sources,
         PackageType::Exe,
@@ -375,27 +587,132 @@ where
             return Err(Box::new(e));
         }
     };
+    interpreter.set_quota(quota);
+    interpreter.set_simulation_memory_budget(Some(SIMULATION_MEMORY_BUDGET));
 
     for _ in 0..shots {
-        let result =
-            interpreter.eval_entry_with_sim(&mut SparseSim::new_with_noise(pauliNoise), &mut out);
-        let mut success = true;
-        let msg: serde_json::Value = match result {
-            Ok(value) => serde_json::Value::String(value.to_string()),
-            Err(errors) => {
-                // TODO: handle multiple errors
-                // https://github.com/microsoft/qsharp/issues/149
-                success = false;
-                VSDiagnostic::from_interpret_error(&source_name, &errors[0]).json()
-            }
-        };
-
-        let msg_string = json!({"type": "Result", "success": success, "result": msg}).to_string();
-        (out.event_cb)(&msg_string);
+        run_shot(&mut interpreter, &mut out, &source_name, pauliNoise);
     }
     Ok(())
 }
 
+/// Runs one shot of the entry point on a new simulator of the kind selected for the interpreter and
+/// reports its result as a `Result` event.
+fn run_shot<F>(
+    interpreter: &mut interpret::Interpreter,
+    out: &mut CallbackReceiver<F>,
+    source_name: &str,
+    pauliNoise: &PauliNoise,
+) where
+    F: FnMut(&str),
+{
+    let result = interpreter.run_seeded_shot(out, true, Some(*pauliNoise), None);
+    let mut success = true;
+    let msg: serde_json::Value = match result {
+        Ok(value) => serde_json::Value::String(value.to_string()),
+        Err(errors) => {
+            // TODO: handle multiple errors
+            // https://github.com/microsoft/qsharp/issues/149
+            success = false;
+            VSDiagnostic::from_interpret_error(source_name, &errors[0]).json()
+        }
+    };
+
+    let msg_string = json!({"type": "Result", "success": success, "result": msg}).to_string();
+    (out.event_cb)(&msg_string);
+}
+
+/// Runs the shots of a program one at a time, so that JavaScript can consume them as an async
+/// iterator, showing each result as it arrives and stopping early by no longer asking for shots.
+#[wasm_bindgen]
+pub struct ShotStream {
+    interpreter: interpret::Interpreter,
+    source_name: String,
+    noise: PauliNoise,
+    remaining: u32,
+}
+
+#[wasm_bindgen]
+impl ShotStream {
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        program: ProgramConfig,
+        expr: &str,
+        shots: u32,
+        pauliNoise: &JsValue,
+        quota: &JsValue,
+        simulator: &JsValue,
+    ) -> Result<ShotStream, JsValue> {
+        let (source_map, capabilities, language_features, store, deps) =
+            into_qsc_args(program, Some(expr.into())).map_err(|mut e| {
+                JsError::from(qsc::interpret::Error::from(
+                    e.pop().expect("expected at least one error"),
+                ))
+            })?;
+        let noise = pauli_noise_from_js(pauliNoise)?;
+        let quota = quota_from_js(quota)?;
+        let simulator = simulator_from_js(simulator)?;
+        let source_name = source_map
+            .iter()
+            .map(|x| x.name.to_string())
+            .next()
+            .expect("There must be a source to process");
+        let mut interpreter = interpret::Interpreter::new(
+            source_map,
+            PackageType::Exe,
+            capabilities,
+            language_features,
+            store,
+            &deps[..],
+        )
+        .map_err(|mut errors| {
+            // TODO: handle multiple errors
+            // https://github.com/microsoft/qsharp/issues/149
+            JsError::from(errors.swap_remove(0))
+        })?;
+        interpreter.set_quota(quota);
+        interpreter.set_simulation_memory_budget(Some(SIMULATION_MEMORY_BUDGET));
+        interpreter
+            .set_simulator(simulator)
+            .map_err(|error| JsError::new(&error))?;
+
+        Ok(ShotStream {
+            interpreter,
+            source_name,
+            noise,
+            remaining: shots,
+        })
+    }
+
+    /// The number of shots that have not run yet.
+    #[wasm_bindgen(getter)]
+    pub fn remaining(&self) -> u32 {
+        self.remaining
+    }
+
+    /// Runs the next shot, reporting its output and result to `event_cb` like `run` does.
+    /// Returns `false` without running anything once all shots have run.
+    pub fn next_shot(&mut self, event_cb: &js_sys::Function) -> bool {
+        if self.remaining == 0 {
+            return false;
+        }
+        self.remaining -= 1;
+        let mut out = CallbackReceiver {
+            event_cb: |msg: &str| {
+                let _ = event_cb.call1(&JsValue::null(), &JsValue::from(msg));
+            },
+            host_functions: None,
+        };
+        run_shot(
+            &mut self.interpreter,
+            &mut out,
+            &self.source_name,
+            &self.noise,
+        );
+        true
+    }
+}
+
 #[wasm_bindgen]
 pub fn run(
     program: ProgramConfig,
@@ -403,9 +720,18 @@ pub fn run(
     event_cb: &js_sys::Function,
     shots: u32,
 ) -> Result<bool, JsValue> {
-    runWithPauliNoise(program, expr, event_cb, shots, &JsValue::null())
+    runWithPauliNoise(
+        program,
+        expr,
+        event_cb,
+        shots,
+        &JsValue::null(),
+        &JsValue::null(),
+    )
 }
 
+/// Runs the program with the Pauli noise given as an array of the X, Y and Z probabilities, if any. Each shot is
+/// bounded by `quota`, if given, which is an `IQuota` object.
 #[wasm_bindgen]
 pub fn runWithPauliNoise(
     program: ProgramConfig,
@@ -413,6 +739,40 @@ pub fn runWithPauliNoise(
     event_cb: &js_sys::Function,
     shots: u32,
     pauliNoise: &JsValue,
+    quota: &JsValue,
+) -> Result<bool, JsValue> {
+    run_with_options(program, expr, event_cb, shots, pauliNoise, quota, None)
+}
+
+/// Runs the program, calling the functions in `hostFunctions` for functions with the `@HostCallable()` attribute.
+/// `hostFunctions` is an object whose properties are the functions, named like the Q# functions they implement.
+#[wasm_bindgen]
+pub fn runWithHostFunctions(
+    program: ProgramConfig,
+    expr: &str,
+    event_cb: &js_sys::Function,
+    shots: u32,
+    hostFunctions: js_sys::Object,
+) -> Result<bool, JsValue> {
+    run_with_options(
+        program,
+        expr,
+        event_cb,
+        shots,
+        &JsValue::null(),
+        &JsValue::null(),
+        Some(hostFunctions),
+    )
+}
+
+fn run_with_options(
+    program: ProgramConfig,
+    expr: &str,
+    event_cb: &js_sys::Function,
+    shots: u32,
+    pauliNoise: &JsValue,
+    quota: &JsValue,
+    host_functions: Option<js_sys::Object>,
 ) -> Result<bool, JsValue> {
     let (source_map, capabilities, language_features, store, deps) =
         into_qsc_args(program, Some(expr.into())).map_err(|mut e| {
@@ -432,6 +792,29 @@ pub fn runWithPauliNoise(
         let _ = event_cb.call1(&JsValue::null(), &JsValue::from(msg));
     };
 
+    let noise = pauli_noise_from_js(pauliNoise)?;
+    let quota = quota_from_js(quota)?;
+
+    match run_internal_with_features(
+        source_map,
+        event_cb,
+        shots,
+        language_features,
+        capabilities,
+        store,
+        &deps[..],
+        &noise,
+        quota,
+        host_functions,
+    ) {
+        Ok(()) => Ok(true),
+        Err(e) => Err(JsError::from(e).into()),
+    }
+}
+
+/// Reads Pauli noise from an array of the X, Y and Z probabilities, or no noise if the value is
+/// not an array.
+fn pauli_noise_from_js(pauliNoise: &JsValue) -> Result<PauliNoise, JsValue> {
     // See if the pauliNoise JsValue is an array
     let noise = if pauliNoise.is_array() {
         let pauliArray = js_sys::Array::from(pauliNoise);
@@ -456,22 +839,73 @@ pub fn runWithPauliNoise(
     } else {
         PauliNoise::default()
     };
+    Ok(noise)
+}
 
-    match run_internal_with_features(
-        source_map,
-        event_cb,
-        shots,
-        language_features,
-        capabilities,
-        store,
-        &deps[..],
-        &noise,
-    ) {
-        Ok(()) => Ok(true),
-        Err(e) => Err(JsError::from(e).into()),
+/// Reads the simulator of a run from a `Simulator` string, or the sparse simulator if the value is
+/// not a string.
+fn simulator_from_js(simulator: &JsValue) -> Result<SimulatorKind, JsValue> {
+    match simulator.as_string().as_deref() {
+        None | Some("sparse") => Ok(SimulatorKind::Sparse),
+        Some("auto") => Ok(SimulatorKind::Auto),
+        Some(simulator) => {
+            Err(JsError::new(&format!("{simulator} is not a valid simulator")).into())
+        }
     }
 }
 
+/// The most nested calls a program run in wasm can make unless its quota says otherwise. Deep recursion
+/// otherwise grows the call stack until the wasm runtime runs out of memory and aborts.
+const DEFAULT_MAX_CALL_DEPTH: usize = 10_000;
+
+/// The quota of the programs run in wasm that are not given one.
+fn default_quota() -> Quota {
+    Quota {
+        max_call_depth: Some(DEFAULT_MAX_CALL_DEPTH),
+        ..Quota::default()
+    }
+}
+
+/// Reads a quota from an `IQuota` object, or the default quota if the value is not an object.
+fn quota_from_js(quota: &JsValue) -> Result<Quota, JsValue> {
+    let mut result = default_quota();
+    if !quota.is_object() {
+        return Ok(result);
+    }
+    let limit = |name: &str| -> Result<Option<f64>, JsValue> {
+        let value = js_sys::Reflect::get(quota, &JsValue::from_str(name))?;
+        if value.is_undefined() || value.is_null() {
+            return Ok(None);
+        }
+        match value.as_f64() {
+            Some(value) if value.is_finite() && value >= 0.0 => Ok(Some(value)),
+            _ => Err(JsError::new(&format!("quota `{name}` must be a non-negative number")).into()),
+        }
+    };
+    if let Some(max_steps) = limit("maxSteps")? {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let max_steps = max_steps as u64;
+        result.max_steps = Some(max_steps);
+    }
+    if let Some(timeout_ms) = limit("timeoutMs")? {
+        let timeout = Duration::try_from_secs_f64(timeout_ms / 1000.0)
+            .map_err(|_| JsError::new("quota `timeoutMs` is too large"))?;
+        result.timeout = Some(timeout);
+        result.clock = Some(js_clock);
+    }
+    if let Some(max_call_depth) = limit("maxCallDepth")? {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let max_call_depth = max_call_depth as usize;
+        result.max_call_depth = Some(max_call_depth);
+    }
+    Ok(result)
+}
+
+/// The time from `Date.now`, which timeouts are measured with since the wasm runtime has no system clock.
+fn js_clock() -> Duration {
+    Duration::from_secs_f64(js_sys::Date::now() / 1000.0)
+}
+
 fn check_exercise_solution_internal(
     solution_code: &str,
     exercise_sources: Vec<(SourceName, SourceContents)>,
@@ -482,7 +916,10 @@ fn check_exercise_solution_internal(
     for exercise_source in exercise_sources {
         sources.push(exercise_source);
     }
-    let mut out = CallbackReceiver { event_cb };
+    let mut out = CallbackReceiver {
+        event_cb,
+        host_functions: None,
+    };
     let result = check_solution(sources, &mut out);
     let mut runtime_success = true;
     let (exercise_success, msg) = match result {
@@ -577,7 +1014,29 @@ const TARGET_PROFILE: &'static str = r#"
 export type TargetProfile = "base" | "adaptive_ri" | "adaptive_rif" | "unrestricted";
 "#;
 
+#[wasm_bindgen(typescript_custom_section)]
+const QUOTA: &'static str = r#"
+/** Limits on each shot of a run, which stops with an error when one is exceeded. */
+export interface IQuota {
+    /** The maximum number of evaluation steps. */
+    maxSteps?: number;
+    /** The maximum time, in milliseconds. */
+    timeoutMs?: number;
+    /** The maximum number of nested calls, which is 10000 if not given. */
+    maxCallDepth?: number;
+}
+"#;
+
+#[wasm_bindgen(typescript_custom_section)]
+const SIMULATOR: &'static str = r#"
+/**
+ * The simulator of each shot of a run: "sparse", the default, or "auto", which starts with the
+ * stabilizer simulator and switches to the sparse simulator at the first non-Clifford operation.
+ */
+export type Simulator = "sparse" | "auto";
+"#;
+
 #[wasm_bindgen(typescript_custom_section)]
 const LANGUAGE_FEATURES: &'static str = r#"
-export type LanguageFeatures = "v2-preview-syntax";
+export type LanguageFeatures = "v2-preview-syntax" | "strict-unicode-identifiers";
 "#;
@@ -89,3 +89,38 @@ impl From<qsc::location::Location> for Location {
         }
     }
 }
+
+/// Converts between the utf-8 byte offsets used by the compiler, the utf-16 code unit offsets
+/// used by JavaScript strings, and the line and character positions used by editors.
+#[wasm_bindgen]
+pub struct LineIndex(line_column::LineIndex);
+
+#[wasm_bindgen]
+impl LineIndex {
+    #[wasm_bindgen(constructor)]
+    pub fn new(contents: &str) -> Self {
+        Self(line_column::LineIndex::new(contents))
+    }
+
+    /// The position, in utf-16 characters, of a utf-8 byte offset.
+    pub fn position(&self, utf8_offset: u32) -> IPosition {
+        Position::from(self.0.position(line_column::Encoding::Utf16, utf8_offset)).into()
+    }
+
+    /// The utf-8 byte offset of a position in utf-16 characters.
+    pub fn utf8_offset(&self, position: IPosition) -> u32 {
+        let position: Position = position.into();
+        self.0
+            .utf8_byte_offset(line_column::Encoding::Utf16, position.into())
+    }
+
+    /// The utf-16 code unit offset of a utf-8 byte offset.
+    pub fn utf16_offset(&self, utf8_offset: u32) -> u32 {
+        self.0.utf16_offset(utf8_offset)
+    }
+
+    /// The utf-8 byte offset of a utf-16 code unit offset.
+    pub fn utf8_offset_from_utf16(&self, utf16_offset: u32) -> u32 {
+        self.0.utf8_byte_offset_from_utf16(utf16_offset)
+    }
+}
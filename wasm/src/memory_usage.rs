@@ -0,0 +1,66 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use crate::serializable_type;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+serializable_type! {
+    PackageMemoryUsage,
+    {
+        pub id: usize,
+        pub name: String,
+        pub sources: usize,
+        pub ast: usize,
+        pub hir: usize,
+        pub fir: usize,
+        pub rca: usize,
+    },
+    r#"export interface IPackageMemoryUsage {
+        id: number;
+        name: string;
+        sources: number;
+        ast: number;
+        hir: number;
+        fir: number;
+        rca: number;
+    }"#
+}
+
+serializable_type! {
+    MemoryUsage,
+    {
+        pub packages: Vec<PackageMemoryUsage>,
+        pub simulator: usize,
+        pub total: usize,
+    },
+    r#"export interface IMemoryUsage {
+        packages: IPackageMemoryUsage[];
+        simulator: number;
+        total: number;
+    }"#,
+    IMemoryUsage
+}
+
+impl From<qsc::memory::MemoryUsage> for MemoryUsage {
+    fn from(usage: qsc::memory::MemoryUsage) -> Self {
+        let total = usage.total();
+        MemoryUsage {
+            packages: usage
+                .packages
+                .into_iter()
+                .map(|package| PackageMemoryUsage {
+                    id: package.id,
+                    name: package.name,
+                    sources: package.sources,
+                    ast: package.ast,
+                    hir: package.hir,
+                    fir: package.fir,
+                    rca: package.rca,
+                })
+                .collect(),
+            simulator: usage.simulator,
+            total,
+        }
+    }
+}
@@ -364,6 +364,16 @@ impl From<PackageInfo> for qsc_project::PackageInfo {
     }
 }
 
+/// Gets the package type declared in the manifest of the root package, treating packages
+/// that do not declare one as libraries.
+pub(crate) fn package_type(program: &ProgramConfig) -> qsc::PackageType {
+    let pkg_graph: PackageGraphSources = program.packageGraphSources().into();
+    match pkg_graph.root.package_type.as_deref() {
+        Some("exe") => qsc::PackageType::Exe,
+        _ => qsc::PackageType::Lib,
+    }
+}
+
 /// This returns the common parameters that the compiler/interpreter uses
 #[allow(clippy::type_complexity)]
 #[allow(clippy::needless_pass_by_value)]
@@ -4,14 +4,27 @@
 use expect_test::expect;
 use indoc::indoc;
 use qsc::{
-    interpret, LanguageFeatures, PackageStore, PauliNoise, SourceMap, TargetCapabilityFlags,
+    interpret, LanguageFeatures, PackageStore, PauliNoise, Quota, SourceMap, TargetCapabilityFlags,
 };
+use std::time::Duration;
 
 use crate::get_qir_;
 
 use super::run_internal_with_features;
 
 fn run_internal<F>(sources: SourceMap, event_cb: F, shots: u32) -> Result<(), Box<interpret::Error>>
+where
+    F: FnMut(&str),
+{
+    run_internal_with_quota(sources, event_cb, shots, crate::default_quota())
+}
+
+fn run_internal_with_quota<F>(
+    sources: SourceMap,
+    event_cb: F,
+    shots: u32,
+    quota: Quota,
+) -> Result<(), Box<interpret::Error>>
 where
     F: FnMut(&str),
 {
@@ -25,6 +38,8 @@ where
         store,
         &[(std_id, None)],
         &PauliNoise::default(),
+        quota,
+        None,
     )
 }
 
@@ -88,6 +103,125 @@ fn test_run_two_shots() {
     assert_eq!(count.get(), 2);
 }
 
+#[test]
+fn test_run_exceeding_step_quota_fails_each_shot() {
+    let mut output = Vec::new();
+    run_internal_with_quota(
+        SourceMap::new(
+            [("test.qs".into(), "".into())],
+            Some("{ mutable i = 0; while true { set i += 1; } }".into()),
+        ),
+        |msg| output.push(msg.to_string()),
+        2,
+        Quota {
+            max_steps: Some(1000),
+            ..Quota::default()
+        },
+    )
+    .expect("code should compile and run");
+    assert_eq!(output.len(), 2);
+    for msg in output {
+        assert!(
+            msg.contains(r#""code":"Qsc.Eval.StepQuotaExceeded""#),
+            "{msg}"
+        );
+        assert!(msg.contains(r#""success":false"#), "{msg}");
+    }
+}
+
+#[test]
+fn test_run_exceeding_timeout_fails() {
+    let mut output = Vec::new();
+    run_internal_with_quota(
+        SourceMap::new(
+            [("test.qs".into(), "".into())],
+            Some("{ while true {} }".into()),
+        ),
+        |msg| output.push(msg.to_string()),
+        1,
+        Quota {
+            timeout: Some(Duration::ZERO),
+            ..Quota::default()
+        },
+    )
+    .expect("code should compile and run");
+    assert_eq!(output.len(), 1);
+    assert!(
+        output[0].contains(r#""code":"Qsc.Eval.Timeout""#),
+        "{}",
+        output[0]
+    );
+}
+
+#[test]
+fn test_run_within_quota_succeeds() {
+    let mut output = Vec::new();
+    run_internal_with_quota(
+        SourceMap::new(
+            [("test.qs".into(), "".into())],
+            Some("{ mutable sum = 0; for i in 1..10 { set sum += i; } sum }".into()),
+        ),
+        |msg| output.push(msg.to_string()),
+        1,
+        Quota {
+            max_steps: Some(1000),
+            timeout: Some(Duration::from_secs(10)),
+            ..Quota::default()
+        },
+    )
+    .expect("code should compile and run");
+    expect![[r#"{"result":"55","success":true,"type":"Result"}"#]].assert_eq(&output.join("\n"));
+}
+
+#[test]
+fn test_run_deep_recursion_exceeds_default_call_depth() {
+    let code = "
+        namespace Test {
+            function Down(n : Int) : Int { if n == 0 { 0 } else { 1 + Down(n - 1) } }
+        }
+    ";
+    let mut output = Vec::new();
+    run_internal(
+        SourceMap::new(
+            [("test.qs".into(), code.into())],
+            Some("Test.Down(20000)".into()),
+        ),
+        |msg| output.push(msg.to_string()),
+        1,
+    )
+    .expect("code should compile and run");
+    assert_eq!(output.len(), 1);
+    assert!(
+        output[0].contains(r#""code":"Qsc.Eval.RecursionLimitExceeded""#),
+        "{}",
+        output[0]
+    );
+}
+
+#[test]
+fn test_run_deep_recursion_within_quota_succeeds() {
+    let code = "
+        namespace Test {
+            function Down(n : Int) : Int { if n == 0 { 0 } else { 1 + Down(n - 1) } }
+        }
+    ";
+    let mut output = Vec::new();
+    run_internal_with_quota(
+        SourceMap::new(
+            [("test.qs".into(), code.into())],
+            Some("Test.Down(20000)".into()),
+        ),
+        |msg| output.push(msg.to_string()),
+        1,
+        Quota {
+            max_call_depth: Some(30_000),
+            ..Quota::default()
+        },
+    )
+    .expect("code should compile and run");
+    expect![[r#"{"result":"20000","success":true,"type":"Result"}"#]].assert_eq(&output.join("\n"));
+}
+
 #[test]
 fn fail_ry() {
     let code = "namespace Sample {